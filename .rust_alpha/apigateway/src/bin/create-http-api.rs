@@ -0,0 +1,178 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0.
+ */
+
+use std::error::Error;
+
+use apigateway_code_examples::urls::execute_api_arn;
+use apigatewayv2::model::{IntegrationType, ProtocolType};
+use apigatewayv2::Region;
+
+use aws_types::region::ProvideRegion;
+
+use common::profile::ProfileCredentialsProvider;
+use structopt::StructOpt;
+use tracing_subscriber::fmt::format::FmtSpan;
+use tracing_subscriber::fmt::SubscriberBuilder;
+
+#[derive(Debug, StructOpt)]
+struct Opt {
+    /// The region. Overrides environment variable AWS_DEFAULT_REGION.
+    #[structopt(short, long)]
+    default_region: Option<String>,
+
+    /// The name of the API
+    #[structopt(short, long)]
+    name: String,
+
+    /// The name or ARN of the Lambda function that handles the requests
+    #[structopt(short, long)]
+    function: String,
+
+    /// The route that sends requests to the function, such as "GET /hello". Defaults to every request
+    #[structopt(short, long, default_value = "$default")]
+    route_key: String,
+
+    /// The AWS profile to use from the shared credentials file
+    #[structopt(long)]
+    profile: Option<String>,
+
+    /// Whether to display additional information
+    #[structopt(short, long)]
+    verbose: bool,
+}
+
+/// Creates an HTTP API that sends requests to a Lambda function, with a Lambda proxy integration.
+/// The function gets the whole request, in the version 2.0 payload format, and its response is
+/// returned to the caller as it is.
+/// The example also gives API Gateway permission to invoke the function, from any route of any stage of the API.
+/// The API has no stage yet, so it can't be called until you create one with **create-stage**.
+/// # Arguments
+///
+/// * `-n NAME` - The name of the API.
+/// * `-f FUNCTION` - The name or ARN of the Lambda function.
+/// * `[-r ROUTE-KEY]` - The route that sends requests to the function, such as **"GET /hello"**.
+///   If not supplied, defaults to **$default**, which matches every request.
+/// * `[-d DEFAULT-REGION]` - The region in which the client is created.
+///   If not supplied, uses the value of the **AWS_DEFAULT_REGION** environment variable.
+///   If the environment variable is not set, defaults to **us-west-2**.
+/// * `[--profile PROFILE]` - The AWS profile to use from the shared credentials file.
+/// * `[-v]` - Whether to display additional information.
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn Error>> {
+    let Opt {
+        default_region,
+        function,
+        name,
+        profile,
+        route_key,
+        verbose,
+    } = Opt::from_args();
+
+    let region = default_region
+        .as_ref()
+        .map(|region| Region::new(region.clone()))
+        .or_else(|| aws_types::region::default_provider().region())
+        .unwrap_or_else(|| Region::new("us-west-2"));
+
+    if verbose {
+        println!(
+            "API Gateway V2 client version: {}",
+            apigatewayv2::PKG_VERSION
+        );
+        println!("Lambda client version:         {}", lambda::PKG_VERSION);
+        println!("Region:                        {:?}", &region);
+        println!("API name:                      {}", &name);
+        println!("Function:                      {}", &function);
+        println!("Route:                         {}", &route_key);
+        println!();
+
+        SubscriberBuilder::default()
+            .with_env_filter("info")
+            .with_span_events(FmtSpan::CLOSE)
+            .init();
+    }
+
+    let mut apigateway_config = apigatewayv2::Config::builder().region(region.clone());
+    let mut lambda_config = lambda::Config::builder().region(region.clone());
+    if let Some(profile) = &profile {
+        apigateway_config =
+            apigateway_config.credentials_provider(ProfileCredentialsProvider::new(profile));
+        lambda_config =
+            lambda_config.credentials_provider(ProfileCredentialsProvider::new(profile));
+    }
+    let apigateway_client = apigatewayv2::Client::from_conf(apigateway_config.build());
+    let lambda_client = lambda::Client::from_conf(lambda_config.build());
+
+    // The integration needs the function's ARN, even if it was given by name.
+    let function_arn = lambda_client
+        .get_function_configuration()
+        .function_name(&function)
+        .send()
+        .await?
+        .function_arn
+        .ok_or("AWS Lambda did not return the function ARN")?;
+
+    let api = apigateway_client
+        .create_api()
+        .name(&name)
+        .protocol_type(ProtocolType::Http)
+        .send()
+        .await?;
+    let api_id = api.api_id.ok_or("API Gateway did not return the API ID")?;
+
+    println!("Created HTTP API {} ({})", &name, &api_id);
+
+    let integration_id = apigateway_client
+        .create_integration()
+        .api_id(&api_id)
+        .integration_type(IntegrationType::AwsProxy)
+        .integration_uri(&function_arn)
+        .payload_format_version("2.0")
+        .send()
+        .await?
+        .integration_id
+        .ok_or("API Gateway did not return the integration ID")?;
+
+    println!(
+        "Created a Lambda proxy integration ({}) with {}",
+        &integration_id, &function_arn
+    );
+
+    apigateway_client
+        .create_route()
+        .api_id(&api_id)
+        .route_key(&route_key)
+        .target(format!("integrations/{}", &integration_id))
+        .send()
+        .await?;
+
+    println!("Created route {}", &route_key);
+
+    // Without this permission, API Gateway gets an error from Lambda, and the caller gets a 500 error.
+    let source_arn = execute_api_arn(&function_arn, region.as_ref(), &api_id)
+        .ok_or_else(|| format!("{} is not a Lambda function ARN", &function_arn))?;
+
+    lambda_client
+        .add_permission()
+        .function_name(&function_arn)
+        .statement_id(format!("apigateway-{}", &api_id))
+        .action("lambda:InvokeFunction")
+        .principal("apigateway.amazonaws.com")
+        .source_arn(&source_arn)
+        .send()
+        .await?;
+
+    println!(
+        "Allowed API Gateway to invoke the function from {}",
+        &source_arn
+    );
+    println!();
+    println!(
+        "Run create-stage -a {} to deploy the API and get its invoke URL",
+        &api_id
+    );
+
+    Ok(())
+}
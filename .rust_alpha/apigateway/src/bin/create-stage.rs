@@ -0,0 +1,114 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0.
+ */
+
+use std::error::Error;
+
+use apigateway_code_examples::urls::invoke_url;
+use apigatewayv2::{Client, Config, Region};
+
+use aws_types::region::ProvideRegion;
+
+use common::profile::ProfileCredentialsProvider;
+use structopt::StructOpt;
+use tracing_subscriber::fmt::format::FmtSpan;
+use tracing_subscriber::fmt::SubscriberBuilder;
+
+#[derive(Debug, StructOpt)]
+struct Opt {
+    /// The region. Overrides environment variable AWS_DEFAULT_REGION.
+    #[structopt(short, long)]
+    default_region: Option<String>,
+
+    /// The ID of the HTTP API
+    #[structopt(short, long)]
+    api_id: String,
+
+    /// The name of the stage, such as prod. Defaults to $default, which is served at the root of the API's URL
+    #[structopt(short, long, default_value = "$default")]
+    stage: String,
+
+    /// The AWS profile to use from the shared credentials file
+    #[structopt(long)]
+    profile: Option<String>,
+
+    /// Whether to display additional information
+    #[structopt(short, long)]
+    verbose: bool,
+}
+
+/// Creates a stage for an HTTP API, and displays the URL that the stage is called at.
+/// The stage deploys the API automatically, now and whenever the API changes,
+/// so it can be called as soon as it's created.
+/// # Arguments
+///
+/// * `-a API-ID` - The ID of the HTTP API.
+/// * `[-s STAGE]` - The name of the stage, such as **prod**.
+///   If not supplied, defaults to **$default**, which is served at the root of the API's URL.
+/// * `[-d DEFAULT-REGION]` - The region in which the client is created.
+///   If not supplied, uses the value of the **AWS_DEFAULT_REGION** environment variable.
+///   If the environment variable is not set, defaults to **us-west-2**.
+/// * `[--profile PROFILE]` - The AWS profile to use from the shared credentials file.
+/// * `[-v]` - Whether to display additional information.
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn Error>> {
+    let Opt {
+        api_id,
+        default_region,
+        profile,
+        stage,
+        verbose,
+    } = Opt::from_args();
+
+    let region = default_region
+        .as_ref()
+        .map(|region| Region::new(region.clone()))
+        .or_else(|| aws_types::region::default_provider().region())
+        .unwrap_or_else(|| Region::new("us-west-2"));
+
+    if verbose {
+        println!(
+            "API Gateway V2 client version: {}",
+            apigatewayv2::PKG_VERSION
+        );
+        println!("Region:                        {:?}", &region);
+        println!("API ID:                        {}", &api_id);
+        println!("Stage:                         {}", &stage);
+        println!();
+
+        SubscriberBuilder::default()
+            .with_env_filter("info")
+            .with_span_events(FmtSpan::CLOSE)
+            .init();
+    }
+
+    let mut config = Config::builder().region(region);
+    if let Some(profile) = profile {
+        config = config.credentials_provider(ProfileCredentialsProvider::new(profile));
+    }
+    let client = Client::from_conf(config.build());
+
+    let stage = client
+        .create_stage()
+        .api_id(&api_id)
+        .stage_name(&stage)
+        .auto_deploy(true)
+        .send()
+        .await?
+        .stage_name
+        .unwrap_or(stage);
+
+    let api_endpoint = client
+        .get_api()
+        .api_id(&api_id)
+        .send()
+        .await?
+        .api_endpoint
+        .ok_or("API Gateway did not return the API's endpoint")?;
+
+    println!("Created stage {}", &stage);
+    println!("Invoke URL: {}", invoke_url(&api_endpoint, &stage));
+
+    Ok(())
+}
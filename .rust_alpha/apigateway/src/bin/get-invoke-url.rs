@@ -0,0 +1,144 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0.
+ */
+
+use std::error::Error;
+
+use apigateway_code_examples::urls::invoke_url;
+use apigatewayv2::{Client, Config, Region};
+
+use aws_types::region::ProvideRegion;
+
+use common::profile::ProfileCredentialsProvider;
+use structopt::StructOpt;
+use tracing_subscriber::fmt::format::FmtSpan;
+use tracing_subscriber::fmt::SubscriberBuilder;
+
+#[derive(Debug, StructOpt)]
+struct Opt {
+    /// The region. Overrides environment variable AWS_DEFAULT_REGION.
+    #[structopt(short, long)]
+    default_region: Option<String>,
+
+    /// The ID of the HTTP API
+    #[structopt(short, long)]
+    api_id: String,
+
+    /// The name of the stage. Defaults to every stage
+    #[structopt(short, long)]
+    stage: Option<String>,
+
+    /// The AWS profile to use from the shared credentials file
+    #[structopt(long)]
+    profile: Option<String>,
+
+    /// Whether to display additional information
+    #[structopt(short, long)]
+    verbose: bool,
+}
+
+/// Displays the URLs that the stages of an HTTP API are called at.
+/// # Arguments
+///
+/// * `-a API-ID` - The ID of the HTTP API.
+/// * `[-s STAGE]` - The name of the stage.
+///   If not supplied, displays the URL of every stage.
+/// * `[-d DEFAULT-REGION]` - The region in which the client is created.
+///   If not supplied, uses the value of the **AWS_DEFAULT_REGION** environment variable.
+///   If the environment variable is not set, defaults to **us-west-2**.
+/// * `[--profile PROFILE]` - The AWS profile to use from the shared credentials file.
+/// * `[-v]` - Whether to display additional information.
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn Error>> {
+    let Opt {
+        api_id,
+        default_region,
+        profile,
+        stage,
+        verbose,
+    } = Opt::from_args();
+
+    let region = default_region
+        .as_ref()
+        .map(|region| Region::new(region.clone()))
+        .or_else(|| aws_types::region::default_provider().region())
+        .unwrap_or_else(|| Region::new("us-west-2"));
+
+    if verbose {
+        println!(
+            "API Gateway V2 client version: {}",
+            apigatewayv2::PKG_VERSION
+        );
+        println!("Region:                        {:?}", &region);
+        println!("API ID:                        {}", &api_id);
+        println!("Stage:                         {:?}", &stage);
+        println!();
+
+        SubscriberBuilder::default()
+            .with_env_filter("info")
+            .with_span_events(FmtSpan::CLOSE)
+            .init();
+    }
+
+    let mut config = Config::builder().region(region);
+    if let Some(profile) = profile {
+        config = config.credentials_provider(ProfileCredentialsProvider::new(profile));
+    }
+    let client = Client::from_conf(config.build());
+
+    let api = client.get_api().api_id(&api_id).send().await?;
+    let api_endpoint = api
+        .api_endpoint
+        .ok_or("API Gateway did not return the API's endpoint")?;
+
+    println!(
+        "{} ({})",
+        api.name.as_deref().unwrap_or_default(),
+        api.protocol_type
+            .as_ref()
+            .map(|protocol| protocol.as_str())
+            .unwrap_or_default()
+    );
+
+    let mut stages = Vec::new();
+    let mut next_token = None;
+
+    loop {
+        let resp = client
+            .get_stages()
+            .api_id(&api_id)
+            .set_next_token(next_token)
+            .send()
+            .await?;
+
+        stages.extend(
+            resp.items
+                .unwrap_or_default()
+                .into_iter()
+                .filter_map(|stage| stage.stage_name),
+        );
+
+        next_token = resp.next_token;
+        if next_token.is_none() {
+            break;
+        }
+    }
+
+    if let Some(stage) = &stage {
+        if !stages.contains(stage) {
+            return Err(format!("API {} has no stage {}", &api_id, stage).into());
+        }
+        stages = vec![stage.clone()];
+    }
+
+    if stages.is_empty() {
+        println!("The API has no stages, so it can't be called. Run create-stage to add one");
+    }
+
+    for stage in &stages {
+        println!("  {:<12}  {}", stage, invoke_url(&api_endpoint, stage));
+    }
+
+    Ok(())
+}
@@ -0,0 +1,139 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0.
+ */
+
+use std::error::Error;
+
+use apigateway::{Client, Config, Region};
+use apigateway_code_examples::urls::{invoke_url, rest_api_endpoint};
+
+use aws_types::region::ProvideRegion;
+
+use common::profile::ProfileCredentialsProvider;
+use smithy_types::instant::Format;
+use structopt::StructOpt;
+use tracing_subscriber::fmt::format::FmtSpan;
+use tracing_subscriber::fmt::SubscriberBuilder;
+
+#[derive(Debug, StructOpt)]
+struct Opt {
+    /// The region. Overrides environment variable AWS_DEFAULT_REGION.
+    #[structopt(short, long)]
+    default_region: Option<String>,
+
+    /// Whether to list each API's stages, with their invoke URLs
+    #[structopt(short, long)]
+    stages: bool,
+
+    /// The AWS profile to use from the shared credentials file
+    #[structopt(long)]
+    profile: Option<String>,
+
+    /// Whether to display additional information
+    #[structopt(short, long)]
+    verbose: bool,
+}
+
+/// Lists the REST APIs in the region, following every page of results:
+/// the ID, name, endpoint type, creation date, and description of each.
+/// Lists only REST APIs; HTTP and WebSocket APIs are listed by the API Gateway V2 API.
+/// # Arguments
+///
+/// * `[-s]` - Whether to list each API's stages, with their invoke URLs.
+/// * `[-d DEFAULT-REGION]` - The region in which the client is created.
+///   If not supplied, uses the value of the **AWS_DEFAULT_REGION** environment variable.
+///   If the environment variable is not set, defaults to **us-west-2**.
+/// * `[--profile PROFILE]` - The AWS profile to use from the shared credentials file.
+/// * `[-v]` - Whether to display additional information.
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn Error>> {
+    let Opt {
+        default_region,
+        profile,
+        stages,
+        verbose,
+    } = Opt::from_args();
+
+    let region = default_region
+        .as_ref()
+        .map(|region| Region::new(region.clone()))
+        .or_else(|| aws_types::region::default_provider().region())
+        .unwrap_or_else(|| Region::new("us-west-2"));
+
+    if verbose {
+        println!("API Gateway client version: {}", apigateway::PKG_VERSION);
+        println!("Region:                     {:?}", &region);
+        println!();
+
+        SubscriberBuilder::default()
+            .with_env_filter("info")
+            .with_span_events(FmtSpan::CLOSE)
+            .init();
+    }
+
+    let mut config = Config::builder().region(region.clone());
+    if let Some(profile) = profile {
+        config = config.credentials_provider(ProfileCredentialsProvider::new(profile));
+    }
+    let client = Client::from_conf(config.build());
+
+    let mut count = 0;
+    let mut position = None;
+
+    loop {
+        let resp = client.get_rest_apis().set_position(position).send().await?;
+
+        for api in resp.items.unwrap_or_default() {
+            let id = api.id.as_deref().unwrap_or_default();
+            let endpoint_types: Vec<&str> = api
+                .endpoint_configuration
+                .as_ref()
+                .and_then(|configuration| configuration.types.as_deref())
+                .unwrap_or_default()
+                .iter()
+                .map(|endpoint_type| endpoint_type.as_str())
+                .collect();
+
+            println!("{}  {}", id, api.name.as_deref().unwrap_or_default());
+            println!("  Endpoint type: {}", endpoint_types.join(", "));
+            println!(
+                "  Created:       {}",
+                api.created_date
+                    .as_ref()
+                    .map(|created| created.fmt(Format::DateTime))
+                    .unwrap_or_default()
+            );
+            if let Some(description) = api.description.as_deref() {
+                println!("  Description:   {}", description);
+            }
+
+            // A private API is reached through a VPC endpoint, so its public URL doesn't answer.
+            if stages {
+                let resp = client.get_stages().rest_api_id(id).send().await?;
+                let endpoint = rest_api_endpoint(id, region.as_ref());
+
+                for stage in resp.item.unwrap_or_default() {
+                    let stage_name = stage.stage_name.as_deref().unwrap_or_default();
+                    println!(
+                        "  Stage {:<12}  {}",
+                        stage_name,
+                        invoke_url(&endpoint, stage_name)
+                    );
+                }
+            }
+
+            println!();
+            count += 1;
+        }
+
+        position = resp.position;
+        if position.is_none() {
+            break;
+        }
+    }
+
+    println!("Found {} REST APIs", count);
+
+    Ok(())
+}
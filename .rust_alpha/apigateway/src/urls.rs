@@ -0,0 +1,50 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0.
+ */
+
+//! Builds the URLs that clients call an API at, and the ARNs that grant API Gateway access to a function.
+//!
+//! An API is called at its invoke URL, which is the API's endpoint followed by the stage name.
+//! An HTTP API's __$default__ stage is served at the root of the endpoint, with no stage name:
+//!
+//! ```ignore
+//! // https://a1b2c3d4e5.execute-api.us-west-2.amazonaws.com/prod/
+//! let url = invoke_url(&api.api_endpoint, "prod");
+//! ```
+
+/// The name of the stage that an HTTP API serves at the root of its endpoint.
+pub const DEFAULT_STAGE: &str = "$default";
+
+/// Returns the invoke URL of a stage, given the API's endpoint, such as
+/// __https://a1b2c3d4e5.execute-api.us-west-2.amazonaws.com__.
+pub fn invoke_url(api_endpoint: &str, stage: &str) -> String {
+    let api_endpoint = api_endpoint.trim_end_matches('/');
+
+    if stage == DEFAULT_STAGE {
+        format!("{}/", api_endpoint)
+    } else {
+        format!("{}/{}/", api_endpoint, stage)
+    }
+}
+
+/// Returns the endpoint of a REST API, which, unlike an HTTP API, the API doesn't return.
+pub fn rest_api_endpoint(api_id: &str, region: &str) -> String {
+    format!("https://{}.execute-api.{}.amazonaws.com", api_id, region)
+}
+
+/// Returns the ARN that matches every route of every stage of an API,
+/// for the permission that lets API Gateway invoke a Lambda function.
+/// The partition and account are taken from the function's ARN,
+/// such as __arn:aws:lambda:us-west-2:123456789012:function:my-function__.
+pub fn execute_api_arn(function_arn: &str, region: &str, api_id: &str) -> Option<String> {
+    let parts: Vec<&str> = function_arn.split(':').collect();
+
+    match parts.as_slice() {
+        ["arn", partition, "lambda", _, account, "function", ..] => Some(format!(
+            "arn:{}:execute-api:{}:{}:{}/*",
+            partition, region, account, api_id
+        )),
+        _ => None,
+    }
+}
@@ -5,10 +5,16 @@
 
 use aws_types::region::ProvideRegion;
 
+use cloudformation::model::Capability;
 use cloudformation::{Client, Config, Region};
+use cloudformation_code_examples::events::{format_event, is_success, wait_for_stack};
+use cloudformation_code_examples::parameters::{parameters, parse_parameter};
 
+use std::error::Error;
 use std::fs;
+use std::time::Duration;
 
+use common::profile::ProfileCredentialsProvider;
 use structopt::StructOpt;
 
 #[derive(Debug, StructOpt)]
@@ -25,28 +31,61 @@ struct Opt {
     #[structopt(short, long)]
     template_file: String,
 
+    /// A parameter of the template, as KEY=VALUE. Repeat to set several
+    #[structopt(short, long, parse(try_from_str = parse_parameter))]
+    parameter: Vec<(String, String)>,
+
+    /// A capability the template needs, such as CAPABILITY_IAM. Repeat to allow several
+    #[structopt(short, long)]
+    capability: Vec<String>,
+
+    /// Whether to display the stack's events until it's created
+    #[structopt(short, long)]
+    wait: bool,
+
+    /// How long to wait, in seconds
+    #[structopt(long, default_value = "1800")]
+    timeout: u64,
+
+    /// The AWS profile to use from the shared credentials file
+    #[structopt(long)]
+    profile: Option<String>,
+
     /// Whether to display additional runtime information.
     #[structopt(short, long)]
     verbose: bool,
 }
 
-/// Creates a CloudFormation stack in the region.
+/// Creates a CloudFormation stack in the region, and optionally displays its events
+/// as its resources are created, until the stack is **CREATE_COMPLETE** or has failed.
 /// # Arguments
 ///
 /// * `-s STACK-NAME` - The name of the stack.
 /// * `-t TEMPLATE-NAME` - The name of the file containing the stack template.
+/// * `[-p KEY=VALUE]` - A parameter of the template. Repeat to set several.
+///   Parameters that aren't set use their default values in the template.
+/// * `[-c CAPABILITY]` - A capability that the template needs, such as **CAPABILITY_IAM**
+///   for a template that creates IAM resources. Repeat to allow several.
+/// * `[-w]` - Whether to display the stack's events until it's created.
+/// * `[--timeout TIMEOUT]` - How long to wait, in seconds. Defaults to 1800.
 /// * `[-d DEFAULT-REGION]` - The region in which the client is created.
 ///    If not supplied, uses the value of the **AWS_DEFAULT_REGION** environment variable.
 ///    If the environment variable is not set, defaults to **us-west-2**.
+/// * `[--profile PROFILE]` - The AWS profile to use from the shared credentials file.
 /// * `[-v]` - Whether to display additional information.
 #[tokio::main]
-async fn main() -> Result<(), cloudformation::Error> {
+async fn main() -> Result<(), Box<dyn Error>> {
     tracing_subscriber::fmt::init();
 
     let Opt {
         default_region,
+        profile,
         stack_name,
         template_file,
+        parameter,
+        capability,
+        wait,
+        timeout,
         verbose,
     } = Opt::from_args();
 
@@ -64,27 +103,64 @@ async fn main() -> Result<(), cloudformation::Error> {
         println!("Region:                   {:?}", &region);
         println!("Stack:                    {}", &stack_name);
         println!("Template:                 {}", &template_file);
+        println!("Parameters:               {:?}", &parameter);
+        println!("Capabilities:             {:?}", &capability);
+        println!("Wait:                     {}", wait);
         println!();
     }
 
     // Get content of template file as a string.
-    let contents =
-        fs::read_to_string(template_file).expect("Something went wrong reading the file");
+    let contents = fs::read_to_string(template_file)?;
 
-    let conf = Config::builder().region(region).build();
-    let client = Client::from_conf(conf);
+    let mut config = Config::builder().region(region);
+    if let Some(profile) = profile {
+        config = config.credentials_provider(ProfileCredentialsProvider::new(profile));
+    }
+    let client = Client::from_conf(config.build());
 
-    client
+    let resp = client
         .create_stack()
-        .stack_name(stack_name)
+        .stack_name(&stack_name)
         .template_body(contents)
+        .set_parameters(Some(parameters(&parameter, &[])))
+        .set_capabilities(Some(
+            capability
+                .iter()
+                .map(|capability| Capability::from(capability.as_str()))
+                .collect(),
+        ))
         .send()
         .await?;
 
-    println!("Stack created.");
-    println!("Use describe-stacks with your stack name to see the status of your stack.");
-    println!("You cannot use/deploy the stack until the status is 'CreateComplete'.");
+    let stack_id = resp.stack_id.unwrap_or_default();
+    println!("Creating stack {}", stack_id);
+
+    if !wait {
+        println!(
+            "Use describe-stack-events with your stack name to see the progress of your stack."
+        );
+        println!("You cannot use the stack until its status is CREATE_COMPLETE.");
+        println!();
+
+        return Ok(());
+    }
+
+    println!();
+    let status = wait_for_stack(&client, &stack_id, Duration::from_secs(timeout), |event| {
+        println!("{}", format_event(event))
+    })
+    .await?;
+
     println!();
+    println!("Stack status: {}", status);
+
+    if !is_success(&status) {
+        return Err(format!(
+            "stack {} wasn't created; see the events for the reason",
+            stack_name
+        )
+        .into());
+    }
 
     Ok(())
 }
@@ -6,7 +6,12 @@
 use aws_types::region::ProvideRegion;
 
 use cloudformation::{Client, Config, Region};
+use cloudformation_code_examples::events::{format_event, is_success, wait_for_stack};
 
+use std::error::Error;
+use std::time::Duration;
+
+use common::profile::ProfileCredentialsProvider;
 use structopt::StructOpt;
 
 #[derive(Debug, StructOpt)]
@@ -19,26 +24,45 @@ struct Opt {
     #[structopt(short, long)]
     stack_name: String,
 
+    /// Whether to display the stack's events until it's deleted
+    #[structopt(short, long)]
+    wait: bool,
+
+    /// How long to wait, in seconds
+    #[structopt(long, default_value = "1800")]
+    timeout: u64,
+
+    /// The AWS profile to use from the shared credentials file
+    #[structopt(long)]
+    profile: Option<String>,
+
     /// Whether to display additional runtime information.
     #[structopt(short, long)]
     verbose: bool,
 }
 
-/// Deletes a CloudFormation stack in the region.
+/// Deletes a CloudFormation stack in the region, and optionally displays its events
+/// as its resources are deleted, until the stack is **DELETE_COMPLETE**.
 /// # Arguments
 ///
 /// * `-s STACK-NAME` - The name of the stack.
+/// * `[-w]` - Whether to display the stack's events until it's deleted.
+/// * `[--timeout TIMEOUT]` - How long to wait, in seconds. Defaults to 1800.
 /// * `[-d DEFAULT-REGION]` - The region in which the client is created.
 ///    If not supplied, uses the value of the **AWS_DEFAULT_REGION** environment variable.
 ///    If the environment variable is not set, defaults to **us-west-2**.
+/// * `[--profile PROFILE]` - The AWS profile to use from the shared credentials file.
 /// * `[-v]` - Whether to display additional information.
 #[tokio::main]
-async fn main() -> Result<(), cloudformation::Error> {
+async fn main() -> Result<(), Box<dyn Error>> {
     tracing_subscriber::fmt::init();
 
     let Opt {
         default_region,
+        profile,
         stack_name,
+        wait,
+        timeout,
         verbose,
     } = Opt::from_args();
 
@@ -55,16 +79,52 @@ async fn main() -> Result<(), cloudformation::Error> {
         );
         println!("Region:                   {:?}", &region);
         println!("Stack:                    {}", &stack_name);
+        println!("Wait:                     {}", wait);
         println!();
     }
 
-    let conf = Config::builder().region(region).build();
-    let client = Client::from_conf(conf);
+    let mut config = Config::builder().region(region);
+    if let Some(profile) = profile {
+        config = config.credentials_provider(ProfileCredentialsProvider::new(profile));
+    }
+    let client = Client::from_conf(config.build());
+
+    // Once it's deleted, a stack can only be described by its ID.
+    let stack_id = client
+        .describe_stacks()
+        .stack_name(&stack_name)
+        .send()
+        .await?
+        .stacks
+        .unwrap_or_default()
+        .pop()
+        .and_then(|stack| stack.stack_id)
+        .ok_or_else(|| format!("Could not find stack {}", stack_name))?;
 
-    client.delete_stack().stack_name(stack_name).send().await?;
+    client.delete_stack().stack_name(&stack_id).send().await?;
 
-    println!("Stack deleted");
+    println!("Deleting stack {}", stack_id);
     println!();
 
+    if !wait {
+        return Ok(());
+    }
+
+    let status = wait_for_stack(&client, &stack_id, Duration::from_secs(timeout), |event| {
+        println!("{}", format_event(event))
+    })
+    .await?;
+
+    println!();
+    println!("Stack status: {}", status);
+
+    if !is_success(&status) {
+        return Err(format!(
+            "stack {} wasn't deleted; see the events for the reason",
+            stack_name
+        )
+        .into());
+    }
+
     Ok(())
 }
@@ -0,0 +1,99 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0.
+ */
+
+use aws_types::region::ProvideRegion;
+
+use cloudformation::{Client, Config, Region};
+use cloudformation_code_examples::events::{format_event, wait_for_stack};
+
+use std::error::Error;
+use std::time::Duration;
+
+use common::profile::ProfileCredentialsProvider;
+use structopt::StructOpt;
+
+#[derive(Debug, StructOpt)]
+struct Opt {
+    /// The region. Overrides environment variable AWS_DEFAULT_REGION.
+    #[structopt(short, long)]
+    default_region: Option<String>,
+
+    /// The name or ID of the stack.
+    #[structopt(short, long)]
+    stack_name: String,
+
+    /// How long to wait for the stack, in seconds
+    #[structopt(long, default_value = "1800")]
+    timeout: u64,
+
+    /// The AWS profile to use from the shared credentials file
+    #[structopt(long)]
+    profile: Option<String>,
+
+    /// Whether to display additional runtime information.
+    #[structopt(short, long)]
+    verbose: bool,
+}
+
+/// Displays the events of a CloudFormation stack's most recent operation, such as its creation,
+/// and, while the operation is in progress, displays each new event as it happens,
+/// until the stack is, for example, **CREATE_COMPLETE** or **ROLLBACK_COMPLETE**.
+/// # Arguments
+///
+/// * `-s STACK-NAME` - The name of the stack, or its ID to see the events of a deleted stack.
+/// * `[--timeout TIMEOUT]` - How long to wait for the stack, in seconds. Defaults to 1800.
+/// * `[-d DEFAULT-REGION]` - The region in which the client is created.
+///    If not supplied, uses the value of the **AWS_DEFAULT_REGION** environment variable.
+///    If the environment variable is not set, defaults to **us-west-2**.
+/// * `[--profile PROFILE]` - The AWS profile to use from the shared credentials file.
+/// * `[-v]` - Whether to display additional information.
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn Error>> {
+    tracing_subscriber::fmt::init();
+
+    let Opt {
+        default_region,
+        profile,
+        stack_name,
+        timeout,
+        verbose,
+    } = Opt::from_args();
+
+    let region = default_region
+        .as_ref()
+        .map(|region| Region::new(region.clone()))
+        .or_else(|| aws_types::region::default_provider().region())
+        .unwrap_or_else(|| Region::new("us-west-2"));
+
+    if verbose {
+        println!(
+            "CloudFormation client version: {}",
+            cloudformation::PKG_VERSION
+        );
+        println!("Region:                   {:?}", &region);
+        println!("Stack:                    {}", &stack_name);
+        println!("Timeout:                  {}", timeout);
+        println!();
+    }
+
+    let mut config = Config::builder().region(region);
+    if let Some(profile) = profile {
+        config = config.credentials_provider(ProfileCredentialsProvider::new(profile));
+    }
+    let client = Client::from_conf(config.build());
+
+    let status = wait_for_stack(
+        &client,
+        &stack_name,
+        Duration::from_secs(timeout),
+        |event| println!("{}", format_event(event)),
+    )
+    .await?;
+
+    println!();
+    println!("Stack status: {}", status);
+
+    Ok(())
+}
@@ -7,6 +7,9 @@ use aws_types::region::ProvideRegion;
 
 use cloudformation::{Client, Config, Region};
 
+use std::error::Error;
+
+use common::profile::ProfileCredentialsProvider;
 use structopt::StructOpt;
 
 #[derive(Debug, StructOpt)]
@@ -19,6 +22,10 @@ struct Opt {
     #[structopt(short, long)]
     stack_name: String,
 
+    /// The AWS profile to use from the shared credentials file
+    #[structopt(long)]
+    profile: Option<String>,
+
     /// Whether to display additional runtime information.
     #[structopt(short, long)]
     verbose: bool,
@@ -31,15 +38,16 @@ struct Opt {
 /// * `[-d DEFAULT-REGION]` - The region in which the client is created.
 ///    If not supplied, uses the value of the **AWS_DEFAULT_REGION** environment variable.
 ///    If the environment variable is not set, defaults to **us-west-2**.
+/// * `[--profile PROFILE]` - The AWS profile to use from the shared credentials file.
 /// * `[-v]` - Whether to display additional information.
 #[tokio::main]
-async fn main() -> Result<(), cloudformation::Error> {
+async fn main() -> Result<(), Box<dyn Error>> {
     tracing_subscriber::fmt::init();
 
     let Opt {
         default_region,
+        profile,
         stack_name,
-
         verbose,
     } = Opt::from_args();
 
@@ -59,24 +67,30 @@ async fn main() -> Result<(), cloudformation::Error> {
         println!();
     }
 
-    let conf = Config::builder().region(region).build();
-    let client = Client::from_conf(conf);
+    let mut config = Config::builder().region(region);
+    if let Some(profile) = profile {
+        config = config.credentials_provider(ProfileCredentialsProvider::new(profile));
+    }
+    let client = Client::from_conf(config.build());
 
-    // Panic if stack_name does not exist
-    let status = client
+    // Returns an error if stack_name does not exist
+    let stack = client
         .describe_stacks()
-        .stack_name(stack_name)
+        .stack_name(&stack_name)
         .send()
-        .await
-        .expect("Could not find your stack")
+        .await?
         .stacks
-        .unwrap()
+        .unwrap_or_default()
         .pop()
-        .unwrap()
+        .ok_or_else(|| format!("Could not find stack {}", stack_name))?;
+
+    let status = stack
         .stack_status
-        .unwrap();
+        .as_ref()
+        .map(|status| status.as_str())
+        .unwrap_or("unknown");
 
-    println!("Stack status: {:?}", status);
+    println!("Stack status: {}", status);
     println!();
 
     Ok(())
@@ -7,6 +7,7 @@ use aws_types::region::ProvideRegion;
 
 use cloudformation::{Client, Config, Region};
 
+use common::profile::ProfileCredentialsProvider;
 use structopt::StructOpt;
 
 #[derive(Debug, StructOpt)]
@@ -15,6 +16,10 @@ struct Opt {
     #[structopt(short, long)]
     default_region: Option<String>,
 
+    /// The AWS profile to use from the shared credentials file
+    #[structopt(long)]
+    profile: Option<String>,
+
     /// Whether to display additional runtime information
     #[structopt(short, long)]
     verbose: bool,
@@ -26,6 +31,7 @@ struct Opt {
 /// * `[-d DEFAULT-REGION]` - The region in which the client is created.
 ///    If not supplied, uses the value of the **AWS_DEFAULT_REGION** environment variable.
 ///    If the environment variable is not set, defaults to **us-west-2**.
+/// * `[--profile PROFILE]` - The AWS profile to use from the shared credentials file.
 /// * `[-v]` - Whether to display additional information.
 #[tokio::main]
 async fn main() -> Result<(), cloudformation::Error> {
@@ -33,6 +39,7 @@ async fn main() -> Result<(), cloudformation::Error> {
 
     let Opt {
         default_region,
+        profile,
         verbose,
     } = Opt::from_args();
 
@@ -50,14 +57,23 @@ async fn main() -> Result<(), cloudformation::Error> {
         println!("Region:                   {:?}", &region);
     }
 
-    let conf = Config::builder().region(region).build();
-    let client = Client::from_conf(conf);
+    let mut config = Config::builder().region(region);
+    if let Some(profile) = profile {
+        config = config.credentials_provider(ProfileCredentialsProvider::new(profile));
+    }
+    let client = Client::from_conf(config.build());
 
     let stacks = client.list_stacks().send().await?;
 
     for s in stacks.stack_summaries.unwrap_or_default() {
         println!("{}", s.stack_name.as_deref().unwrap_or_default());
-        println!("  Status: {:?}", s.stack_status.unwrap());
+        println!(
+            "  Status: {}",
+            s.stack_status
+                .as_ref()
+                .map(|status| status.as_str())
+                .unwrap_or("unknown")
+        );
         println!();
     }
 
@@ -0,0 +1,202 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0.
+ */
+
+use aws_types::region::ProvideRegion;
+
+use cloudformation::model::Capability;
+use cloudformation::{Client, Config, Region, SdkError};
+use cloudformation_code_examples::events::{format_event, is_success, wait_for_stack};
+use cloudformation_code_examples::parameters::{parameters, parse_parameter};
+
+use std::error::Error;
+use std::fs;
+use std::time::Duration;
+
+use common::profile::ProfileCredentialsProvider;
+use structopt::StructOpt;
+
+/// What UpdateStack says when the template and parameters are the ones the stack already has.
+const NO_UPDATES: &str = "No updates are to be performed";
+
+#[derive(Debug, StructOpt)]
+struct Opt {
+    /// The region. Overrides environment variable AWS_DEFAULT_REGION.
+    #[structopt(short, long)]
+    default_region: Option<String>,
+
+    /// The name of the stack.
+    #[structopt(short, long)]
+    stack_name: String,
+
+    /// The name of the file containing the new stack template. Defaults to the stack's current template
+    #[structopt(short, long)]
+    template_file: Option<String>,
+
+    /// A parameter to change, as KEY=VALUE. Repeat to change several
+    #[structopt(short, long, parse(try_from_str = parse_parameter))]
+    parameter: Vec<(String, String)>,
+
+    /// A capability the template needs, such as CAPABILITY_IAM. Repeat to allow several
+    #[structopt(short, long)]
+    capability: Vec<String>,
+
+    /// Whether to display the stack's events until it's updated
+    #[structopt(short, long)]
+    wait: bool,
+
+    /// How long to wait, in seconds
+    #[structopt(long, default_value = "1800")]
+    timeout: u64,
+
+    /// The AWS profile to use from the shared credentials file
+    #[structopt(long)]
+    profile: Option<String>,
+
+    /// Whether to display additional runtime information.
+    #[structopt(short, long)]
+    verbose: bool,
+}
+
+/// Updates a CloudFormation stack in the region with a new template, new parameter values, or both,
+/// and optionally displays its events until the stack is **UPDATE_COMPLETE** or has rolled back.
+/// # Arguments
+///
+/// * `-s STACK-NAME` - The name of the stack.
+/// * `[-t TEMPLATE-NAME]` - The name of the file containing the new stack template.
+///   If not supplied, the stack keeps its current template.
+/// * `[-p KEY=VALUE]` - A parameter to change. Repeat to change several.
+///   The stack's other parameters keep their current values.
+/// * `[-c CAPABILITY]` - A capability that the template needs, such as **CAPABILITY_IAM**.
+///   Repeat to allow several.
+/// * `[-w]` - Whether to display the stack's events until it's updated.
+/// * `[--timeout TIMEOUT]` - How long to wait, in seconds. Defaults to 1800.
+/// * `[-d DEFAULT-REGION]` - The region in which the client is created.
+///    If not supplied, uses the value of the **AWS_DEFAULT_REGION** environment variable.
+///    If the environment variable is not set, defaults to **us-west-2**.
+/// * `[--profile PROFILE]` - The AWS profile to use from the shared credentials file.
+/// * `[-v]` - Whether to display additional information.
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn Error>> {
+    tracing_subscriber::fmt::init();
+
+    let Opt {
+        default_region,
+        profile,
+        stack_name,
+        template_file,
+        parameter,
+        capability,
+        wait,
+        timeout,
+        verbose,
+    } = Opt::from_args();
+
+    let region = default_region
+        .as_ref()
+        .map(|region| Region::new(region.clone()))
+        .or_else(|| aws_types::region::default_provider().region())
+        .unwrap_or_else(|| Region::new("us-west-2"));
+
+    if verbose {
+        println!(
+            "CloudFormation client version: {}",
+            cloudformation::PKG_VERSION
+        );
+        println!("Region:                   {:?}", &region);
+        println!("Stack:                    {}", &stack_name);
+        println!("Template:                 {:?}", &template_file);
+        println!("Parameters:               {:?}", &parameter);
+        println!("Capabilities:             {:?}", &capability);
+        println!("Wait:                     {}", wait);
+        println!();
+    }
+
+    let contents = match &template_file {
+        Some(template_file) => Some(fs::read_to_string(template_file)?),
+        None => None,
+    };
+
+    let mut config = Config::builder().region(region);
+    if let Some(profile) = profile {
+        config = config.credentials_provider(ProfileCredentialsProvider::new(profile));
+    }
+    let client = Client::from_conf(config.build());
+
+    // Without a value, a parameter would revert to its default in the template,
+    // so the ones that aren't changed are given their previous values.
+    let stack = client
+        .describe_stacks()
+        .stack_name(&stack_name)
+        .send()
+        .await?
+        .stacks
+        .unwrap_or_default()
+        .pop()
+        .ok_or_else(|| format!("Could not find stack {}", stack_name))?;
+
+    let previous: Vec<String> = stack
+        .parameters
+        .unwrap_or_default()
+        .into_iter()
+        .filter_map(|parameter| parameter.parameter_key)
+        .collect();
+
+    let resp = client
+        .update_stack()
+        .stack_name(&stack_name)
+        .set_template_body(contents)
+        .use_previous_template(template_file.is_none())
+        .set_parameters(Some(parameters(&parameter, &previous)))
+        .set_capabilities(Some(
+            capability
+                .iter()
+                .map(|capability| Capability::from(capability.as_str()))
+                .collect(),
+        ))
+        .send()
+        .await;
+
+    let stack_id = match resp {
+        Ok(resp) => resp.stack_id.unwrap_or_default(),
+        Err(SdkError::ServiceError { err, .. })
+            if err.code() == Some("ValidationError")
+                && err.message().unwrap_or_default().contains(NO_UPDATES) =>
+        {
+            println!("Stack {} is already up to date.", stack_name);
+            return Ok(());
+        }
+        Err(e) => return Err(e.into()),
+    };
+
+    println!("Updating stack {}", stack_id);
+
+    if !wait {
+        println!(
+            "Use describe-stack-events with your stack name to see the progress of your stack."
+        );
+        println!();
+
+        return Ok(());
+    }
+
+    println!();
+    let status = wait_for_stack(&client, &stack_id, Duration::from_secs(timeout), |event| {
+        println!("{}", format_event(event))
+    })
+    .await?;
+
+    println!();
+    println!("Stack status: {}", status);
+
+    if !is_success(&status) {
+        return Err(format!(
+            "stack {} wasn't updated; see the events for the reason",
+            stack_name
+        )
+        .into());
+    }
+
+    Ok(())
+}
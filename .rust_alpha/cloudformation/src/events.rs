@@ -0,0 +1,237 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0.
+ */
+
+//! Streams the events of a stack while waiting for it to finish creating, updating, or deleting.
+//!
+//! `CreateStack`, `UpdateStack`, and `DeleteStack` return as soon as the operation starts.
+//! While it runs, each resource in the stack records events, such as __CREATE_IN_PROGRESS__
+//! and __CREATE_COMPLETE__, and a failure's event says why in its reason. `wait_for_stack`
+//! polls `DescribeStackEvents`, calls `on_event` with each new event, oldest first,
+//! and returns the stack's status once it's no longer in progress:
+//!
+//! ```ignore
+//! let status = wait_for_stack(&client, &stack_id, Duration::from_secs(1800), |event| {
+//!     println!("{}", format_event(event));
+//! })
+//! .await?;
+//! ```
+//!
+//! The events start with the one that began the stack's most recent operation, so that
+//! the events of earlier operations aren't repeated. A deleted stack can only be described
+//! by its ID, not its name, so wait with the ID to see a deletion finish.
+
+use std::collections::HashSet;
+use std::error::Error;
+use std::fmt;
+use std::time::{Duration, Instant};
+
+use cloudformation::model::StackEvent;
+use cloudformation::Client;
+use smithy_types::instant::Format;
+use tokio::time::sleep;
+
+/// How long to wait between polls.
+const POLL_DELAY: Duration = Duration::from_secs(5);
+
+/// The reason on the stack's own event when someone starts an operation on it.
+const USER_INITIATED: &str = "User Initiated";
+
+/// Why waiting for a stack failed.
+#[derive(Debug)]
+pub enum WaitError {
+    /// DescribeStacks or DescribeStackEvents failed.
+    Sdk(cloudformation::Error),
+    /// The stack doesn't exist.
+    NotFound(String),
+    /// The stack was still in progress at the timeout. Holds its status.
+    Timeout(String, String),
+}
+
+impl fmt::Display for WaitError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            WaitError::Sdk(e) => write!(f, "{}", e),
+            WaitError::NotFound(stack) => write!(f, "stack {} doesn't exist", stack),
+            WaitError::Timeout(stack, status) => {
+                write!(f, "stack {} was still {}", stack, status)
+            }
+        }
+    }
+}
+
+impl Error for WaitError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            WaitError::Sdk(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+impl From<cloudformation::Error> for WaitError {
+    fn from(e: cloudformation::Error) -> Self {
+        WaitError::Sdk(e)
+    }
+}
+
+/// Returns whether a stack or resource status, such as __UPDATE_ROLLBACK_IN_PROGRESS__,
+/// means an operation is still running.
+pub fn is_in_progress(status: &str) -> bool {
+    status.ends_with("_IN_PROGRESS")
+}
+
+/// Returns whether a stack status means that the operation succeeded, such as __CREATE_COMPLETE__.
+/// A completed rollback, such as __ROLLBACK_COMPLETE__, means that it failed.
+pub fn is_success(status: &str) -> bool {
+    status.ends_with("_COMPLETE") && !status.contains("ROLLBACK")
+}
+
+/// Formats an event on one line: when, its status, the resource's type and logical ID,
+/// and the reason, if any.
+pub fn format_event(event: &StackEvent) -> String {
+    let mut line = format!(
+        "{}  {:<28}  {:<36}  {}",
+        event
+            .timestamp
+            .as_ref()
+            .map(|time| time.fmt(Format::DateTime))
+            .unwrap_or_default(),
+        event
+            .resource_status
+            .as_ref()
+            .map(|status| status.as_str())
+            .unwrap_or_default(),
+        event.resource_type.as_deref().unwrap_or_default(),
+        event.logical_resource_id.as_deref().unwrap_or_default()
+    );
+
+    if let Some(reason) = event.resource_status_reason.as_deref() {
+        line.push_str("  ");
+        line.push_str(reason);
+    }
+
+    line
+}
+
+/// Returns whether the event is the stack's own, rather than one of its resources'.
+fn is_stack_event(event: &StackEvent) -> bool {
+    event.resource_type.as_deref() == Some("AWS::CloudFormation::Stack")
+        && event.physical_resource_id == event.stack_id
+}
+
+/// The new events of a stack, since the last time they were read.
+pub struct EventStream<'a> {
+    client: &'a Client,
+    stack: String,
+    seen: HashSet<String>,
+}
+
+impl<'a> EventStream<'a> {
+    /// Streams the events of the stack, by name or ID, starting with its most recent operation.
+    pub fn new(client: &'a Client, stack: &str) -> Self {
+        EventStream {
+            client,
+            stack: stack.to_string(),
+            seen: HashSet::new(),
+        }
+    }
+
+    /// Returns the events since the last call, oldest first.
+    pub async fn next_events(&mut self) -> Result<Vec<StackEvent>, cloudformation::Error> {
+        // DescribeStackEvents returns the newest events first, so reading stops at the first
+        // event that's been seen, or, the first time, at the start of the latest operation.
+        let first = self.seen.is_empty();
+        let mut events = Vec::new();
+        let mut next_token = None;
+
+        'pages: loop {
+            let resp = self
+                .client
+                .describe_stack_events()
+                .stack_name(&self.stack)
+                .set_next_token(next_token)
+                .send()
+                .await?;
+
+            for event in resp.stack_events.unwrap_or_default() {
+                let id = event.event_id.clone().unwrap_or_default();
+                if self.seen.contains(&id) {
+                    break 'pages;
+                }
+
+                let started = first
+                    && is_stack_event(&event)
+                    && event.resource_status_reason.as_deref() == Some(USER_INITIATED);
+
+                self.seen.insert(id);
+                events.push(event);
+
+                if started {
+                    break 'pages;
+                }
+            }
+
+            next_token = resp.next_token;
+            if next_token.is_none() {
+                break;
+            }
+        }
+
+        events.reverse();
+        Ok(events)
+    }
+}
+
+/// Returns the status of the stack, by name or ID.
+pub async fn stack_status(client: &Client, stack: &str) -> Result<String, WaitError> {
+    client
+        .describe_stacks()
+        .stack_name(stack)
+        .send()
+        .await
+        .map_err(cloudformation::Error::from)?
+        .stacks
+        .unwrap_or_default()
+        .pop()
+        .and_then(|stack| stack.stack_status)
+        .map(|status| status.as_str().to_string())
+        .ok_or_else(|| WaitError::NotFound(stack.to_string()))
+}
+
+/// Calls `on_event` with each event of the stack's current operation, as it happens,
+/// until the stack is no longer in progress, and returns its status.
+pub async fn wait_for_stack<F>(
+    client: &Client,
+    stack: &str,
+    timeout: Duration,
+    mut on_event: F,
+) -> Result<String, WaitError>
+where
+    F: FnMut(&StackEvent),
+{
+    let deadline = Instant::now() + timeout;
+    let mut events = EventStream::new(client, stack);
+
+    loop {
+        // The status is read before the events, so that the events that
+        // finished the operation have all been seen once it's done.
+        let status = stack_status(client, stack).await?;
+
+        for event in events.next_events().await? {
+            on_event(&event);
+        }
+
+        if !is_in_progress(&status) {
+            return Ok(status);
+        }
+
+        let now = Instant::now();
+        if now >= deadline {
+            return Err(WaitError::Timeout(stack.to_string(), status));
+        }
+
+        sleep(POLL_DELAY.min(deadline - now)).await;
+    }
+}
@@ -0,0 +1,50 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0.
+ */
+
+//! Reads stack parameters from the command line.
+//!
+//! A parameter is written as `KEY=VALUE`, such as `InstanceType=t3.micro`, so that structopt can parse it:
+//!
+//! ```ignore
+//! #[structopt(short, long, parse(try_from_str = parse_parameter))]
+//! parameter: Vec<(String, String)>,
+//! ```
+//!
+//! When a stack is updated, any parameter it already has that isn't given again
+//! keeps its previous value, rather than reverting to the template's default.
+
+use cloudformation::model::Parameter;
+
+/// Splits `KEY=VALUE` at the first `=`, so the value can itself contain one,
+/// as a comma-delimited list or a JSON string might.
+pub fn parse_parameter(s: &str) -> Result<(String, String), String> {
+    match s.split_once('=') {
+        Some((key, value)) if !key.is_empty() => Ok((key.to_string(), value.to_string())),
+        _ => Err(format!("{} is not KEY=VALUE", s)),
+    }
+}
+
+/// Builds the parameters for CreateStack or UpdateStack. Each of the `previous` keys,
+/// the parameters the stack already has, that isn't in `values` keeps its previous value.
+pub fn parameters(values: &[(String, String)], previous: &[String]) -> Vec<Parameter> {
+    let given = values.iter().map(|(key, value)| {
+        Parameter::builder()
+            .parameter_key(key)
+            .parameter_value(value)
+            .build()
+    });
+
+    let kept = previous
+        .iter()
+        .filter(|key| !values.iter().any(|(given, _)| given == *key))
+        .map(|key| {
+            Parameter::builder()
+                .parameter_key(key)
+                .use_previous_value(true)
+                .build()
+        });
+
+    given.chain(kept).collect()
+}
@@ -0,0 +1,189 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0.
+ */
+
+use std::error::Error;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use cloudwatch::model::{Datapoint, Dimension, Statistic};
+use cloudwatch::{Client, Config, Region};
+
+use aws_types::region::ProvideRegion;
+
+use cloudwatch_code_examples::dimensions::parse_dimension;
+use common::profile::ProfileCredentialsProvider;
+use smithy_types::instant::Format;
+use smithy_types::Instant;
+use structopt::StructOpt;
+use tracing_subscriber::fmt::format::FmtSpan;
+use tracing_subscriber::fmt::SubscriberBuilder;
+
+#[derive(Debug, StructOpt)]
+struct Opt {
+    /// The region. Overrides environment variable AWS_DEFAULT_REGION.
+    #[structopt(short, long)]
+    default_region: Option<String>,
+
+    /// The namespace of the metric, such as AWS/EC2
+    #[structopt(short, long)]
+    namespace: String,
+
+    /// The name of the metric
+    #[structopt(short, long)]
+    metric: String,
+
+    /// A dimension of the metric, as NAME=VALUE. Can be repeated
+    #[structopt(long, parse(try_from_str = parse_dimension))]
+    dimension: Vec<Dimension>,
+
+    /// The statistic: Average, Sum, Minimum, Maximum, or SampleCount
+    #[structopt(short, long, default_value = "Average")]
+    statistic: String,
+
+    /// The length of each period, in seconds
+    #[structopt(short, long, default_value = "300")]
+    period: i32,
+
+    /// How far back to look, in minutes
+    #[structopt(long, default_value = "60")]
+    minutes: i64,
+
+    /// The AWS profile to use from the shared credentials file
+    #[structopt(long)]
+    profile: Option<String>,
+
+    /// Whether to display additional information
+    #[structopt(short, long)]
+    verbose: bool,
+}
+
+/// Returns the value of the statistic from a datapoint.
+fn value(datapoint: &Datapoint, statistic: &Statistic) -> Option<f64> {
+    match statistic {
+        Statistic::Average => datapoint.average,
+        Statistic::Sum => datapoint.sum,
+        Statistic::Minimum => datapoint.minimum,
+        Statistic::Maximum => datapoint.maximum,
+        Statistic::SampleCount => datapoint.sample_count,
+        _ => None,
+    }
+}
+
+/// Displays a statistic of an Amazon CloudWatch metric for each period in a time range, oldest first.
+/// # Arguments
+///
+/// * `-n NAMESPACE` - The namespace of the metric, such as **AWS/EC2** or a custom namespace.
+/// * `-m METRIC` - The name of the metric, such as **CPUUtilization**.
+/// * `[--dimension NAME=VALUE]...` - The dimensions of the metric, such as **InstanceId=i-1234567890abcdef0**.
+///   You must supply all of the dimensions that the metric was published with.
+/// * `[-s STATISTIC]` - The statistic: **Average** (the default), **Sum**, **Minimum**, **Maximum**, or **SampleCount**.
+/// * `[-p PERIOD]` - The length of each period, in seconds. Defaults to 300.
+/// * `[--minutes MINUTES]` - How far back to look, in minutes. Defaults to 60.
+/// * `[-d DEFAULT-REGION]` - The region in which the client is created.
+///   If not supplied, uses the value of the **AWS_DEFAULT_REGION** environment variable.
+///   If the environment variable is not set, defaults to **us-west-2**.
+/// * `[--profile PROFILE]` - The AWS profile to use from the shared credentials file.
+/// * `[-v]` - Whether to display additional information.
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn Error>> {
+    let Opt {
+        default_region,
+        dimension,
+        metric,
+        minutes,
+        namespace,
+        period,
+        profile,
+        statistic,
+        verbose,
+    } = Opt::from_args();
+
+    let region = default_region
+        .as_ref()
+        .map(|region| Region::new(region.clone()))
+        .or_else(|| aws_types::region::default_provider().region())
+        .unwrap_or_else(|| Region::new("us-west-2"));
+
+    let statistic = Statistic::from(statistic.as_str());
+    if let Statistic::Unknown(statistic) = &statistic {
+        return Err(format!("{} is not a CloudWatch statistic", statistic).into());
+    }
+
+    if verbose {
+        println!("CloudWatch client version: {}", cloudwatch::PKG_VERSION);
+        println!("Region:                    {:?}", &region);
+        println!("Namespace:                 {}", &namespace);
+        println!("Metric:                    {}", &metric);
+        println!("Dimensions:                {:?}", &dimension);
+        println!("Statistic:                 {}", statistic.as_str());
+        println!("Period:                    {}", period);
+        println!("Minutes:                   {}", minutes);
+
+        SubscriberBuilder::default()
+            .with_env_filter("info")
+            .with_span_events(FmtSpan::CLOSE)
+            .init();
+    }
+
+    let mut config = Config::builder().region(region);
+    if let Some(profile) = profile {
+        config = config.credentials_provider(ProfileCredentialsProvider::new(profile));
+    }
+    let client = Client::from_conf(config.build());
+
+    let now = SystemTime::now().duration_since(UNIX_EPOCH)?.as_secs() as i64;
+
+    let resp = client
+        .get_metric_statistics()
+        .namespace(&namespace)
+        .metric_name(&metric)
+        .set_dimensions(Some(dimension))
+        .start_time(Instant::from_epoch_seconds(now - minutes * 60))
+        .end_time(Instant::from_epoch_seconds(now))
+        .period(period)
+        .statistics(statistic.clone())
+        .send()
+        .await?;
+
+    // The datapoints aren't in any particular order.
+    let mut datapoints = resp.datapoints.unwrap_or_default();
+    datapoints.sort_by_key(|datapoint| {
+        datapoint
+            .timestamp
+            .as_ref()
+            .map(|timestamp| timestamp.epoch_seconds())
+    });
+
+    println!(
+        "{} of {}/{} in the last {} minutes:",
+        statistic.as_str(),
+        namespace,
+        metric,
+        minutes
+    );
+
+    // A metric with no values in the range, or with different dimensions, has no datapoints.
+    if datapoints.is_empty() {
+        println!("  No datapoints. Check the dimensions, which must match the metric's exactly.");
+    }
+
+    for datapoint in &datapoints {
+        println!(
+            "  {}  {} {}",
+            datapoint
+                .timestamp
+                .as_ref()
+                .map(|timestamp| timestamp.fmt(Format::DateTime))
+                .unwrap_or_default(),
+            value(datapoint, &statistic).unwrap_or_default(),
+            datapoint
+                .unit
+                .as_ref()
+                .map(|unit| unit.as_str())
+                .unwrap_or_default()
+        );
+    }
+
+    Ok(())
+}
@@ -0,0 +1,147 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0.
+ */
+
+use std::error::Error;
+
+use cloudwatch::model::DimensionFilter;
+use cloudwatch::{Client, Config, Region};
+
+use aws_types::region::ProvideRegion;
+
+use cloudwatch_code_examples::dimensions::parse_dimension_filter;
+use common::profile::ProfileCredentialsProvider;
+use structopt::StructOpt;
+use tracing_subscriber::fmt::format::FmtSpan;
+use tracing_subscriber::fmt::SubscriberBuilder;
+
+#[derive(Debug, StructOpt)]
+struct Opt {
+    /// The region. Overrides environment variable AWS_DEFAULT_REGION.
+    #[structopt(short, long)]
+    default_region: Option<String>,
+
+    /// Only list the metrics in this namespace, such as AWS/EC2
+    #[structopt(short, long)]
+    namespace: Option<String>,
+
+    /// Only list the metrics with this name
+    #[structopt(short, long)]
+    metric: Option<String>,
+
+    /// Only list the metrics with this dimension, as NAME or NAME=VALUE. Can be repeated
+    #[structopt(long, parse(from_str = parse_dimension_filter))]
+    dimension: Vec<DimensionFilter>,
+
+    /// The AWS profile to use from the shared credentials file
+    #[structopt(long)]
+    profile: Option<String>,
+
+    /// Whether to display additional information
+    #[structopt(short, long)]
+    verbose: bool,
+}
+
+/// Lists your Amazon CloudWatch metrics, and their dimensions.
+/// # Arguments
+///
+/// * `[-n NAMESPACE]` - Only list the metrics in this namespace, such as **AWS/EC2**.
+/// * `[-m METRIC]` - Only list the metrics with this name, such as **CPUUtilization**.
+/// * `[--dimension NAME[=VALUE]]...` - Only list the metrics with this dimension,
+///   and if a value is supplied, with this value of the dimension.
+/// * `[-d DEFAULT-REGION]` - The region in which the client is created.
+///   If not supplied, uses the value of the **AWS_DEFAULT_REGION** environment variable.
+///   If the environment variable is not set, defaults to **us-west-2**.
+/// * `[--profile PROFILE]` - The AWS profile to use from the shared credentials file.
+/// * `[-v]` - Whether to display additional information.
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn Error>> {
+    let Opt {
+        default_region,
+        dimension,
+        metric,
+        namespace,
+        profile,
+        verbose,
+    } = Opt::from_args();
+
+    let region = default_region
+        .as_ref()
+        .map(|region| Region::new(region.clone()))
+        .or_else(|| aws_types::region::default_provider().region())
+        .unwrap_or_else(|| Region::new("us-west-2"));
+
+    if verbose {
+        println!("CloudWatch client version: {}", cloudwatch::PKG_VERSION);
+        println!("Region:                    {:?}", &region);
+        println!("Namespace:                 {:?}", &namespace);
+        println!("Metric:                    {:?}", &metric);
+        println!("Dimensions:                {:?}", &dimension);
+
+        SubscriberBuilder::default()
+            .with_env_filter("info")
+            .with_span_events(FmtSpan::CLOSE)
+            .init();
+    }
+
+    let mut config = Config::builder().region(region);
+    if let Some(profile) = profile {
+        config = config.credentials_provider(ProfileCredentialsProvider::new(profile));
+    }
+    let client = Client::from_conf(config.build());
+
+    let dimensions = if dimension.is_empty() {
+        None
+    } else {
+        Some(dimension)
+    };
+
+    // ListMetrics returns up to 500 metrics at a time, so follow NextToken to get them all.
+    let mut found = 0;
+    let mut next_token = None;
+    loop {
+        let resp = client
+            .list_metrics()
+            .set_namespace(namespace.clone())
+            .set_metric_name(metric.clone())
+            .set_dimensions(dimensions.clone())
+            .set_next_token(next_token)
+            .send()
+            .await?;
+
+        for metric in resp.metrics.unwrap_or_default() {
+            found += 1;
+
+            let dimensions: Vec<String> = metric
+                .dimensions
+                .unwrap_or_default()
+                .into_iter()
+                .map(|dimension| {
+                    format!(
+                        "{}={}",
+                        dimension.name.unwrap_or_default(),
+                        dimension.value.unwrap_or_default()
+                    )
+                })
+                .collect();
+
+            println!(
+                "{}/{} {}",
+                metric.namespace.as_deref().unwrap_or_default(),
+                metric.metric_name.as_deref().unwrap_or_default(),
+                dimensions.join(" ")
+            );
+        }
+
+        next_token = resp.next_token;
+        if next_token.is_none() {
+            break;
+        }
+    }
+
+    println!();
+    println!("Found {} metrics", found);
+
+    Ok(())
+}
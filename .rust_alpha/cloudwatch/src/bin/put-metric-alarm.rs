@@ -0,0 +1,183 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0.
+ */
+
+use std::error::Error;
+
+use cloudwatch::model::{ComparisonOperator, Dimension, Statistic};
+use cloudwatch::{Client, Config, Region};
+
+use aws_types::region::ProvideRegion;
+
+use cloudwatch_code_examples::dimensions::parse_dimension;
+use common::profile::ProfileCredentialsProvider;
+use structopt::StructOpt;
+use tracing_subscriber::fmt::format::FmtSpan;
+use tracing_subscriber::fmt::SubscriberBuilder;
+
+#[derive(Debug, StructOpt)]
+struct Opt {
+    /// The region. Overrides environment variable AWS_DEFAULT_REGION.
+    #[structopt(short, long)]
+    default_region: Option<String>,
+
+    /// The name of the alarm
+    #[structopt(short, long)]
+    alarm: String,
+
+    /// The namespace of the metric, such as AWS/EC2
+    #[structopt(short, long)]
+    namespace: String,
+
+    /// The name of the metric
+    #[structopt(short, long)]
+    metric: String,
+
+    /// A dimension of the metric, as NAME=VALUE. Can be repeated
+    #[structopt(long, parse(try_from_str = parse_dimension))]
+    dimension: Vec<Dimension>,
+
+    /// The statistic: Average, Sum, Minimum, Maximum, or SampleCount
+    #[structopt(short, long, default_value = "Average")]
+    statistic: String,
+
+    /// The value to compare the statistic with
+    #[structopt(short, long)]
+    threshold: f64,
+
+    /// How to compare the statistic with the threshold
+    #[structopt(short, long, default_value = "GreaterThanThreshold")]
+    comparison: String,
+
+    /// The length of each period, in seconds
+    #[structopt(short, long, default_value = "300")]
+    period: i32,
+
+    /// How many consecutive periods must breach the threshold to set off the alarm
+    #[structopt(short, long, default_value = "1")]
+    evaluation_periods: i32,
+
+    /// The ARN of an Amazon SNS topic to notify when the alarm changes state
+    #[structopt(long)]
+    topic_arn: Option<String>,
+
+    /// The AWS profile to use from the shared credentials file
+    #[structopt(long)]
+    profile: Option<String>,
+
+    /// Whether to display additional information
+    #[structopt(short, long)]
+    verbose: bool,
+}
+
+/// Creates or updates an Amazon CloudWatch alarm on a metric.
+/// The alarm goes off when the statistic breaches the threshold for the number of evaluation periods.
+/// # Arguments
+///
+/// * `-a ALARM` - The name of the alarm. If an alarm with this name exists, it's replaced.
+/// * `-n NAMESPACE` - The namespace of the metric, such as **AWS/EC2**.
+/// * `-m METRIC` - The name of the metric, such as **CPUUtilization**.
+/// * `[--dimension NAME=VALUE]...` - The dimensions of the metric, such as **InstanceId=i-1234567890abcdef0**.
+/// * `-t THRESHOLD` - The value to compare the statistic with.
+/// * `[-s STATISTIC]` - The statistic: **Average** (the default), **Sum**, **Minimum**, **Maximum**, or **SampleCount**.
+/// * `[-c COMPARISON]` - How to compare the statistic with the threshold:
+///   **GreaterThanThreshold** (the default), **GreaterThanOrEqualToThreshold**, **LessThanThreshold**,
+///   or **LessThanOrEqualToThreshold**.
+/// * `[-p PERIOD]` - The length of each period, in seconds. Defaults to 300.
+/// * `[-e EVALUATION-PERIODS]` - How many consecutive periods must breach the threshold. Defaults to 1.
+/// * `[--topic-arn TOPIC-ARN]` - The ARN of an Amazon SNS topic to notify when the alarm goes off,
+///   and when it returns to OK.
+/// * `[-d DEFAULT-REGION]` - The region in which the client is created.
+///   If not supplied, uses the value of the **AWS_DEFAULT_REGION** environment variable.
+///   If the environment variable is not set, defaults to **us-west-2**.
+/// * `[--profile PROFILE]` - The AWS profile to use from the shared credentials file.
+/// * `[-v]` - Whether to display additional information.
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn Error>> {
+    let Opt {
+        alarm,
+        comparison,
+        default_region,
+        dimension,
+        evaluation_periods,
+        metric,
+        namespace,
+        period,
+        profile,
+        statistic,
+        threshold,
+        topic_arn,
+        verbose,
+    } = Opt::from_args();
+
+    let region = default_region
+        .as_ref()
+        .map(|region| Region::new(region.clone()))
+        .or_else(|| aws_types::region::default_provider().region())
+        .unwrap_or_else(|| Region::new("us-west-2"));
+
+    let statistic = Statistic::from(statistic.as_str());
+    if let Statistic::Unknown(statistic) = &statistic {
+        return Err(format!("{} is not a CloudWatch statistic", statistic).into());
+    }
+
+    let comparison = ComparisonOperator::from(comparison.as_str());
+    if let ComparisonOperator::Unknown(comparison) = &comparison {
+        return Err(format!("{} is not a comparison operator", comparison).into());
+    }
+
+    if verbose {
+        println!("CloudWatch client version: {}", cloudwatch::PKG_VERSION);
+        println!("Region:                    {:?}", &region);
+        println!("Alarm:                     {}", &alarm);
+        println!("Namespace:                 {}", &namespace);
+        println!("Metric:                    {}", &metric);
+        println!("Dimensions:                {:?}", &dimension);
+        println!("Topic ARN:                 {:?}", &topic_arn);
+
+        SubscriberBuilder::default()
+            .with_env_filter("info")
+            .with_span_events(FmtSpan::CLOSE)
+            .init();
+    }
+
+    let mut config = Config::builder().region(region);
+    if let Some(profile) = profile {
+        config = config.credentials_provider(ProfileCredentialsProvider::new(profile));
+    }
+    let client = Client::from_conf(config.build());
+
+    // The same topic is notified when the alarm goes off and when it recovers.
+    let actions = topic_arn.map(|arn| vec![arn]);
+
+    client
+        .put_metric_alarm()
+        .alarm_name(&alarm)
+        .namespace(&namespace)
+        .metric_name(&metric)
+        .set_dimensions(Some(dimension))
+        .statistic(statistic.clone())
+        .threshold(threshold)
+        .comparison_operator(comparison.clone())
+        .period(period)
+        .evaluation_periods(evaluation_periods)
+        .set_alarm_actions(actions.clone())
+        .set_ok_actions(actions)
+        .send()
+        .await?;
+
+    println!(
+        "Created alarm {}: {} of {}/{} {} {} for {} period(s) of {} seconds",
+        alarm,
+        statistic.as_str(),
+        namespace,
+        metric,
+        comparison.as_str(),
+        threshold,
+        evaluation_periods,
+        period
+    );
+
+    Ok(())
+}
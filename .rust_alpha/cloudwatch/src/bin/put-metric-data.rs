@@ -0,0 +1,147 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0.
+ */
+
+use std::error::Error;
+
+use cloudwatch::model::{Dimension, MetricDatum, StandardUnit};
+use cloudwatch::{Client, Config, Region};
+
+use aws_types::region::ProvideRegion;
+
+use cloudwatch_code_examples::dimensions::parse_dimension;
+use common::profile::ProfileCredentialsProvider;
+use structopt::StructOpt;
+use tracing_subscriber::fmt::format::FmtSpan;
+use tracing_subscriber::fmt::SubscriberBuilder;
+
+#[derive(Debug, StructOpt)]
+struct Opt {
+    /// The region. Overrides environment variable AWS_DEFAULT_REGION.
+    #[structopt(short, long)]
+    default_region: Option<String>,
+
+    /// The namespace of the metric, such as MyApp
+    #[structopt(short, long)]
+    namespace: String,
+
+    /// The name of the metric
+    #[structopt(short, long)]
+    metric: String,
+
+    /// The value to publish
+    #[structopt(long)]
+    value: f64,
+
+    /// A dimension of the metric, as NAME=VALUE. Can be repeated
+    #[structopt(long, parse(try_from_str = parse_dimension))]
+    dimension: Vec<Dimension>,
+
+    /// The unit of the value, such as Count or Milliseconds
+    #[structopt(short, long, default_value = "None")]
+    unit: String,
+
+    /// Whether to store the value with one-second resolution, rather than one-minute
+    #[structopt(long)]
+    high_resolution: bool,
+
+    /// The AWS profile to use from the shared credentials file
+    #[structopt(long)]
+    profile: Option<String>,
+
+    /// Whether to display additional information
+    #[structopt(short, long)]
+    verbose: bool,
+}
+
+/// Publishes a value of a custom Amazon CloudWatch metric.
+/// CloudWatch creates the metric the first time a value is published.
+/// # Arguments
+///
+/// * `-n NAMESPACE` - The namespace of the metric, such as **MyApp**.
+///   Namespaces that start with **AWS/** are reserved for AWS services.
+/// * `-m METRIC` - The name of the metric.
+/// * `--value VALUE` - The value to publish.
+/// * `[--dimension NAME=VALUE]...` - The dimensions of the metric, such as **Environment=Test**.
+///   Each combination of dimensions is a separate metric.
+/// * `[-u UNIT]` - The unit of the value, such as **Count** or **Milliseconds**. Defaults to **None**.
+/// * `[--high-resolution]` - Whether to store the value with one-second resolution.
+/// * `[-d DEFAULT-REGION]` - The region in which the client is created.
+///   If not supplied, uses the value of the **AWS_DEFAULT_REGION** environment variable.
+///   If the environment variable is not set, defaults to **us-west-2**.
+/// * `[--profile PROFILE]` - The AWS profile to use from the shared credentials file.
+/// * `[-v]` - Whether to display additional information.
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn Error>> {
+    let Opt {
+        default_region,
+        dimension,
+        high_resolution,
+        metric,
+        namespace,
+        profile,
+        unit,
+        value,
+        verbose,
+    } = Opt::from_args();
+
+    let region = default_region
+        .as_ref()
+        .map(|region| Region::new(region.clone()))
+        .or_else(|| aws_types::region::default_provider().region())
+        .unwrap_or_else(|| Region::new("us-west-2"));
+
+    if namespace.starts_with("AWS/") {
+        return Err("namespaces that start with AWS/ are reserved for AWS services".into());
+    }
+
+    let unit = StandardUnit::from(unit.as_str());
+    if let StandardUnit::Unknown(unit) = &unit {
+        return Err(format!("{} is not a CloudWatch unit", unit).into());
+    }
+
+    if verbose {
+        println!("CloudWatch client version: {}", cloudwatch::PKG_VERSION);
+        println!("Region:                    {:?}", &region);
+        println!("Namespace:                 {}", &namespace);
+        println!("Metric:                    {}", &metric);
+        println!("Dimensions:                {:?}", &dimension);
+
+        SubscriberBuilder::default()
+            .with_env_filter("info")
+            .with_span_events(FmtSpan::CLOSE)
+            .init();
+    }
+
+    let mut config = Config::builder().region(region);
+    if let Some(profile) = profile {
+        config = config.credentials_provider(ProfileCredentialsProvider::new(profile));
+    }
+    let client = Client::from_conf(config.build());
+
+    let datum = MetricDatum::builder()
+        .metric_name(&metric)
+        .set_dimensions(Some(dimension))
+        .value(value)
+        .unit(unit.clone())
+        .storage_resolution(if high_resolution { 1 } else { 60 })
+        .build();
+
+    client
+        .put_metric_data()
+        .namespace(&namespace)
+        .metric_data(datum)
+        .send()
+        .await?;
+
+    println!(
+        "Published {} {} to {}/{}",
+        value,
+        unit.as_str(),
+        namespace,
+        metric
+    );
+
+    Ok(())
+}
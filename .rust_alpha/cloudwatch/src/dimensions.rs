@@ -0,0 +1,26 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0.
+ */
+
+//! Parses the `--dimension` arguments that the CloudWatch examples accept.
+
+use cloudwatch::model::{Dimension, DimensionFilter};
+
+/// Parses a NAME=VALUE dimension argument.
+pub fn parse_dimension(s: &str) -> Result<Dimension, String> {
+    match s.split_once('=') {
+        Some((name, value)) if !name.is_empty() && !value.is_empty() => {
+            Ok(Dimension::builder().name(name).value(value).build())
+        }
+        _ => Err(format!("dimension must be NAME=VALUE, got {}", s)),
+    }
+}
+
+/// Parses a NAME or NAME=VALUE dimension filter argument.
+pub fn parse_dimension_filter(s: &str) -> DimensionFilter {
+    match s.split_once('=') {
+        Some((name, value)) => DimensionFilter::builder().name(name).value(value).build(),
+        None => DimensionFilter::builder().name(s).build(),
+    }
+}
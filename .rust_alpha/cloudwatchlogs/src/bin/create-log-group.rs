@@ -0,0 +1,124 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0.
+ */
+
+use std::error::Error;
+
+use cloudwatchlogs::{Client, Config, Region};
+
+use aws_types::region::ProvideRegion;
+
+use common::profile::ProfileCredentialsProvider;
+use structopt::StructOpt;
+use tracing_subscriber::fmt::format::FmtSpan;
+use tracing_subscriber::fmt::SubscriberBuilder;
+
+/// The numbers of days that CloudWatch Logs can keep log events for.
+const RETENTION_DAYS: &[i32] = &[
+    1, 3, 5, 7, 14, 30, 60, 90, 120, 150, 180, 365, 400, 545, 731, 1827, 3653,
+];
+
+#[derive(Debug, StructOpt)]
+struct Opt {
+    /// The region. Overrides environment variable AWS_DEFAULT_REGION.
+    #[structopt(short, long)]
+    default_region: Option<String>,
+
+    /// The name of the log group
+    #[structopt(short, long)]
+    group: String,
+
+    /// How many days to keep the log events for. Defaults to forever
+    #[structopt(long)]
+    retention_days: Option<i32>,
+
+    /// The AWS profile to use from the shared credentials file
+    #[structopt(long)]
+    profile: Option<String>,
+
+    /// Whether to display additional information
+    #[structopt(short, long)]
+    verbose: bool,
+}
+
+/// Creates an Amazon CloudWatch Logs log group, and optionally sets how long it keeps log events.
+/// # Arguments
+///
+/// * `-g GROUP` - The name of the log group, such as **/myapp/dev**.
+/// * `[--retention-days DAYS]` - How many days to keep the log events for, such as **30**.
+///   If not supplied, the log events are kept forever.
+/// * `[-d DEFAULT-REGION]` - The region in which the client is created.
+///   If not supplied, uses the value of the **AWS_DEFAULT_REGION** environment variable.
+///   If the environment variable is not set, defaults to **us-west-2**.
+/// * `[--profile PROFILE]` - The AWS profile to use from the shared credentials file.
+/// * `[-v]` - Whether to display additional information.
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn Error>> {
+    let Opt {
+        default_region,
+        group,
+        profile,
+        retention_days,
+        verbose,
+    } = Opt::from_args();
+
+    let region = default_region
+        .as_ref()
+        .map(|region| Region::new(region.clone()))
+        .or_else(|| aws_types::region::default_provider().region())
+        .unwrap_or_else(|| Region::new("us-west-2"));
+
+    if let Some(days) = retention_days {
+        if !RETENTION_DAYS.contains(&days) {
+            return Err(format!(
+                "the retention period must be one of {:?} days, got {}",
+                RETENTION_DAYS, days
+            )
+            .into());
+        }
+    }
+
+    if verbose {
+        println!(
+            "CloudWatch Logs client version: {}",
+            cloudwatchlogs::PKG_VERSION
+        );
+        println!("Region:                         {:?}", &region);
+        println!("Log group:                      {}", &group);
+        println!("Retention (days):               {:?}", retention_days);
+
+        SubscriberBuilder::default()
+            .with_env_filter("info")
+            .with_span_events(FmtSpan::CLOSE)
+            .init();
+    }
+
+    let mut config = Config::builder().region(region);
+    if let Some(profile) = profile {
+        config = config.credentials_provider(ProfileCredentialsProvider::new(profile));
+    }
+    let client = Client::from_conf(config.build());
+
+    client
+        .create_log_group()
+        .log_group_name(&group)
+        .send()
+        .await?;
+
+    println!("Created log group {}", group);
+
+    // A new log group keeps its log events forever, which can get expensive.
+    if let Some(days) = retention_days {
+        client
+            .put_retention_policy()
+            .log_group_name(&group)
+            .retention_in_days(days)
+            .send()
+            .await?;
+
+        println!("Log events expire after {} days", days);
+    }
+
+    Ok(())
+}
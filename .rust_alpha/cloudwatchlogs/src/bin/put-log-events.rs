@@ -0,0 +1,268 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0.
+ */
+
+use std::error::Error;
+use std::io::{self, BufRead};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use cloudwatchlogs::error::{CreateLogStreamErrorKind, PutLogEventsErrorKind};
+use cloudwatchlogs::model::InputLogEvent;
+use cloudwatchlogs::{Client, Config, Region, SdkError};
+
+use aws_types::region::ProvideRegion;
+
+use common::profile::ProfileCredentialsProvider;
+use structopt::StructOpt;
+use tracing_subscriber::fmt::format::FmtSpan;
+use tracing_subscriber::fmt::SubscriberBuilder;
+
+/// The most events that PutLogEvents accepts in one request.
+const MAX_BATCH_EVENTS: usize = 10_000;
+
+/// The largest batch that PutLogEvents accepts, in bytes.
+const MAX_BATCH_BYTES: usize = 1_048_576;
+
+/// How many bytes CloudWatch Logs counts for each event, in addition to its message.
+const EVENT_OVERHEAD: usize = 26;
+
+/// How many times to retry a batch whose sequence token is out of date.
+const MAX_ATTEMPTS: usize = 5;
+
+#[derive(Debug, StructOpt)]
+struct Opt {
+    /// The region. Overrides environment variable AWS_DEFAULT_REGION.
+    #[structopt(short, long)]
+    default_region: Option<String>,
+
+    /// The name of the log group
+    #[structopt(short, long)]
+    group: String,
+
+    /// The name of the log stream. It's created if it doesn't exist
+    #[structopt(short, long)]
+    stream: String,
+
+    /// The messages to send. If none are supplied, sends each line of standard input
+    messages: Vec<String>,
+
+    /// The AWS profile to use from the shared credentials file
+    #[structopt(long)]
+    profile: Option<String>,
+
+    /// Whether to display additional information
+    #[structopt(short, long)]
+    verbose: bool,
+}
+
+/// Creates the log stream, unless it already exists.
+async fn ensure_stream(client: &Client, group: &str, stream: &str) -> Result<(), Box<dyn Error>> {
+    match client
+        .create_log_stream()
+        .log_group_name(group)
+        .log_stream_name(stream)
+        .send()
+        .await
+    {
+        Ok(_) => {
+            println!("Created log stream {}", stream);
+            Ok(())
+        }
+        Err(SdkError::ServiceError { err, .. })
+            if matches!(
+                err.kind,
+                CreateLogStreamErrorKind::ResourceAlreadyExistsError(_)
+            ) =>
+        {
+            Ok(())
+        }
+        Err(e) => Err(e.into()),
+    }
+}
+
+/// Returns the sequence token for the next batch of the stream, or `None` for an empty stream.
+async fn sequence_token(
+    client: &Client,
+    group: &str,
+    stream: &str,
+) -> Result<Option<String>, cloudwatchlogs::Error> {
+    let streams = client
+        .describe_log_streams()
+        .log_group_name(group)
+        .log_stream_name_prefix(stream)
+        .send()
+        .await?
+        .log_streams
+        .unwrap_or_default();
+
+    // The prefix can match other streams too, so pick out the one with the exact name.
+    Ok(streams
+        .into_iter()
+        .find(|candidate| candidate.log_stream_name.as_deref() == Some(stream))
+        .and_then(|stream| stream.upload_sequence_token))
+}
+
+/// Sends a batch of events, and returns the sequence token for the next batch.
+///
+/// Each batch must include the token that the previous batch returned. If another writer
+/// has sent a batch to the stream in the meantime, the token is out of date, and CloudWatch Logs
+/// rejects the batch with the token it expected, so the batch is sent again with that one.
+async fn put_batch(
+    client: &Client,
+    group: &str,
+    stream: &str,
+    events: Vec<InputLogEvent>,
+    mut token: Option<String>,
+) -> Result<Option<String>, Box<dyn Error>> {
+    for _ in 0..MAX_ATTEMPTS {
+        match client
+            .put_log_events()
+            .log_group_name(group)
+            .log_stream_name(stream)
+            .set_log_events(Some(events.clone()))
+            .set_sequence_token(token.clone())
+            .send()
+            .await
+        {
+            Ok(resp) => {
+                if let Some(rejected) = resp.rejected_log_events_info {
+                    eprintln!("Some events were rejected: {:?}", rejected);
+                }
+                return Ok(resp.next_sequence_token);
+            }
+            Err(SdkError::ServiceError { err, .. }) => match &err.kind {
+                PutLogEventsErrorKind::InvalidSequenceTokenError(e) => {
+                    token = e.expected_sequence_token.clone();
+                }
+                // The batch was sent before, for example by a retry whose response was lost.
+                PutLogEventsErrorKind::DataAlreadyAcceptedError(e) => {
+                    return Ok(e.expected_sequence_token.clone());
+                }
+                _ => return Err(err.into()),
+            },
+            Err(e) => return Err(e.into()),
+        }
+    }
+
+    Err(format!(
+        "the sequence token was still out of date after {} attempts",
+        MAX_ATTEMPTS
+    )
+    .into())
+}
+
+/// Splits the messages into batches that PutLogEvents accepts.
+/// All of the events get the current time, which keeps them in the order they were given.
+fn batches(messages: Vec<String>) -> Vec<Vec<InputLogEvent>> {
+    let timestamp = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_millis() as i64;
+
+    let mut batches = Vec::new();
+    let mut batch = Vec::new();
+    let mut bytes = 0;
+
+    for message in messages {
+        let size = message.len() + EVENT_OVERHEAD;
+
+        if !batch.is_empty() && (batch.len() == MAX_BATCH_EVENTS || bytes + size > MAX_BATCH_BYTES)
+        {
+            batches.push(batch);
+            batch = Vec::new();
+            bytes = 0;
+        }
+
+        bytes += size;
+        batch.push(
+            InputLogEvent::builder()
+                .timestamp(timestamp)
+                .message(message)
+                .build(),
+        );
+    }
+
+    if !batch.is_empty() {
+        batches.push(batch);
+    }
+
+    batches
+}
+
+/// Sends messages to an Amazon CloudWatch Logs log stream, creating the stream if it doesn't exist.
+/// # Arguments
+///
+/// * `-g GROUP` - The name of the log group. The log group must exist.
+/// * `-s STREAM` - The name of the log stream.
+/// * `[MESSAGES]...` - The messages to send.
+///   If not supplied, sends each line of standard input as a message.
+/// * `[-d DEFAULT-REGION]` - The region in which the client is created.
+///   If not supplied, uses the value of the **AWS_DEFAULT_REGION** environment variable.
+///   If the environment variable is not set, defaults to **us-west-2**.
+/// * `[--profile PROFILE]` - The AWS profile to use from the shared credentials file.
+/// * `[-v]` - Whether to display additional information.
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn Error>> {
+    let Opt {
+        default_region,
+        group,
+        messages,
+        profile,
+        stream,
+        verbose,
+    } = Opt::from_args();
+
+    let region = default_region
+        .as_ref()
+        .map(|region| Region::new(region.clone()))
+        .or_else(|| aws_types::region::default_provider().region())
+        .unwrap_or_else(|| Region::new("us-west-2"));
+
+    let messages = if messages.is_empty() {
+        io::stdin().lock().lines().collect::<Result<Vec<_>, _>>()?
+    } else {
+        messages
+    };
+
+    // CloudWatch Logs rejects empty messages.
+    let messages: Vec<String> = messages
+        .into_iter()
+        .filter(|message| !message.is_empty())
+        .collect();
+
+    if verbose {
+        println!(
+            "CloudWatch Logs client version: {}",
+            cloudwatchlogs::PKG_VERSION
+        );
+        println!("Region:                         {:?}", &region);
+        println!("Log group:                      {}", &group);
+        println!("Log stream:                     {}", &stream);
+        println!("Messages:                       {}", messages.len());
+
+        SubscriberBuilder::default()
+            .with_env_filter("info")
+            .with_span_events(FmtSpan::CLOSE)
+            .init();
+    }
+
+    let mut config = Config::builder().region(region);
+    if let Some(profile) = profile {
+        config = config.credentials_provider(ProfileCredentialsProvider::new(profile));
+    }
+    let client = Client::from_conf(config.build());
+
+    ensure_stream(&client, &group, &stream).await?;
+
+    let count = messages.len();
+    let mut token = sequence_token(&client, &group, &stream).await?;
+
+    for batch in batches(messages) {
+        token = put_batch(&client, &group, &stream, batch, token).await?;
+    }
+
+    println!("Sent {} events to {}/{}", count, group, stream);
+
+    Ok(())
+}
@@ -0,0 +1,228 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0.
+ */
+
+use std::collections::HashSet;
+use std::error::Error;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use cloudwatchlogs::model::FilteredLogEvent;
+use cloudwatchlogs::{Client, Config, Region};
+
+use aws_types::region::ProvideRegion;
+
+use common::profile::ProfileCredentialsProvider;
+use common::shutdown::Shutdown;
+use smithy_types::instant::Format;
+use smithy_types::Instant;
+use structopt::StructOpt;
+use tokio::time::sleep;
+use tracing_subscriber::fmt::format::FmtSpan;
+use tracing_subscriber::fmt::SubscriberBuilder;
+
+#[derive(Debug, StructOpt)]
+struct Opt {
+    /// The region. Overrides environment variable AWS_DEFAULT_REGION.
+    #[structopt(short, long)]
+    default_region: Option<String>,
+
+    /// The name of the log group
+    #[structopt(short, long)]
+    group: String,
+
+    /// Only show the events in the log streams whose names start with this prefix
+    #[structopt(short, long)]
+    stream_prefix: Option<String>,
+
+    /// Only show the events that match this filter pattern, such as ERROR
+    #[structopt(short, long)]
+    pattern: Option<String>,
+
+    /// How far back to start, in minutes
+    #[structopt(long, default_value = "10")]
+    since: u64,
+
+    /// Whether to keep showing new events as they arrive, until you press Ctrl-C
+    #[structopt(short, long)]
+    follow: bool,
+
+    /// How often to check for new events when following, in seconds
+    #[structopt(short, long, default_value = "5")]
+    interval: u64,
+
+    /// The AWS profile to use from the shared credentials file
+    #[structopt(long)]
+    profile: Option<String>,
+
+    /// Whether to display additional information
+    #[structopt(short, long)]
+    verbose: bool,
+}
+
+/// Remembers the newest events shown, so that each event is shown once.
+///
+/// Each poll starts at the timestamp of the newest event shown so far, rather than just after it,
+/// because more events with the same timestamp can arrive later. The events at that timestamp
+/// that were already shown are skipped.
+struct Cursor {
+    timestamp: i64,
+    shown: HashSet<String>,
+}
+
+impl Cursor {
+    /// Returns whether the event is new, and if it is, remembers it.
+    fn is_new(&mut self, event: &FilteredLogEvent) -> bool {
+        let timestamp = event.timestamp.unwrap_or_default();
+        let id = event.event_id.clone().unwrap_or_default();
+
+        if timestamp < self.timestamp || self.shown.contains(&id) {
+            return false;
+        }
+
+        if timestamp > self.timestamp {
+            self.timestamp = timestamp;
+            self.shown.clear();
+        }
+        self.shown.insert(id);
+
+        true
+    }
+}
+
+/// Prints an event as TIME STREAM MESSAGE.
+fn print_event(event: &FilteredLogEvent) {
+    let time = event
+        .timestamp
+        .map(|ms| Instant::from_f64(ms as f64 / 1000.0).fmt(Format::DateTime))
+        .unwrap_or_default();
+
+    println!(
+        "{} {} {}",
+        time,
+        event.log_stream_name.as_deref().unwrap_or_default(),
+        event.message.as_deref().unwrap_or_default().trim_end()
+    );
+}
+
+/// Shows the events since the cursor, a page at a time.
+async fn poll(
+    client: &Client,
+    opt: &Opt,
+    cursor: &mut Cursor,
+) -> Result<(), cloudwatchlogs::Error> {
+    let mut next_token = None;
+
+    loop {
+        let resp = client
+            .filter_log_events()
+            .log_group_name(&opt.group)
+            .set_log_stream_name_prefix(opt.stream_prefix.clone())
+            .set_filter_pattern(opt.pattern.clone())
+            .start_time(cursor.timestamp)
+            .set_next_token(next_token)
+            .send()
+            .await?;
+
+        for event in resp.events.unwrap_or_default() {
+            if cursor.is_new(&event) {
+                print_event(&event);
+            }
+        }
+
+        next_token = resp.next_token;
+        if next_token.is_none() {
+            return Ok(());
+        }
+    }
+}
+
+/// Displays the recent events in an Amazon CloudWatch Logs log group, oldest first,
+/// and with `-f`, keeps displaying new events as they arrive, like **aws logs tail --follow**.
+///
+/// Events can take a few seconds to become searchable, so an event that arrives
+/// with a timestamp older than the newest event shown might not be shown.
+/// # Arguments
+///
+/// * `-g GROUP` - The name of the log group.
+/// * `[-s STREAM-PREFIX]` - Only show the events in the log streams whose names start with this prefix.
+/// * `[-p PATTERN]` - Only show the events that match this filter pattern, such as **ERROR**.
+///   For the syntax, see **Filter and pattern syntax** in the CloudWatch Logs User Guide.
+/// * `[--since MINUTES]` - How far back to start, in minutes. Defaults to 10.
+/// * `[-f]` - Whether to keep displaying new events until you press Ctrl-C.
+/// * `[-i INTERVAL]` - How often to check for new events, in seconds. Defaults to 5.
+/// * `[-d DEFAULT-REGION]` - The region in which the client is created.
+///   If not supplied, uses the value of the **AWS_DEFAULT_REGION** environment variable.
+///   If the environment variable is not set, defaults to **us-west-2**.
+/// * `[--profile PROFILE]` - The AWS profile to use from the shared credentials file.
+/// * `[-v]` - Whether to display additional information.
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn Error>> {
+    let opt = Opt::from_args();
+
+    let region = opt
+        .default_region
+        .as_ref()
+        .map(|region| Region::new(region.clone()))
+        .or_else(|| aws_types::region::default_provider().region())
+        .unwrap_or_else(|| Region::new("us-west-2"));
+
+    if opt.follow && opt.interval == 0 {
+        return Err("the interval must be at least one second".into());
+    }
+
+    if opt.verbose {
+        println!(
+            "CloudWatch Logs client version: {}",
+            cloudwatchlogs::PKG_VERSION
+        );
+        println!("Region:                         {:?}", &region);
+        println!("Log group:                      {}", &opt.group);
+        println!("Stream prefix:                  {:?}", &opt.stream_prefix);
+        println!("Pattern:                        {:?}", &opt.pattern);
+        println!("Since (minutes):                {}", opt.since);
+        println!("Follow:                         {}", opt.follow);
+
+        SubscriberBuilder::default()
+            .with_env_filter("info")
+            .with_span_events(FmtSpan::CLOSE)
+            .init();
+    }
+
+    let mut config = Config::builder().region(region);
+    if let Some(profile) = &opt.profile {
+        config = config.credentials_provider(ProfileCredentialsProvider::new(profile));
+    }
+    let client = Client::from_conf(config.build());
+
+    let now = SystemTime::now().duration_since(UNIX_EPOCH)?;
+    let mut cursor = Cursor {
+        timestamp: now
+            .saturating_sub(Duration::from_secs(opt.since * 60))
+            .as_millis() as i64,
+        shown: HashSet::new(),
+    };
+
+    if !opt.follow {
+        poll(&client, &opt, &mut cursor).await?;
+        return Ok(());
+    }
+
+    // Ctrl-C is how following normally stops, so it ends the loop rather than failing.
+    let shutdown = Shutdown::listen();
+    let interval = Duration::from_secs(opt.interval);
+
+    loop {
+        tokio::select! {
+            result = poll(&client, &opt, &mut cursor) => result?,
+            _ = shutdown.requested() => break,
+        }
+
+        tokio::select! {
+            _ = sleep(interval) => {}
+            _ = shutdown.requested() => break,
+        }
+    }
+
+    Ok(())
+}
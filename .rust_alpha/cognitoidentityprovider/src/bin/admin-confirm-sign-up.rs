@@ -0,0 +1,97 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0.
+ */
+
+use cognitoidentityprovider::{Client, Config, Error, Region};
+
+use aws_types::region::ProvideRegion;
+
+use common::profile::ProfileCredentialsProvider;
+use structopt::StructOpt;
+use tracing_subscriber::fmt::format::FmtSpan;
+use tracing_subscriber::fmt::SubscriberBuilder;
+
+#[derive(Debug, StructOpt)]
+struct Opt {
+    /// The region. Overrides environment variable AWS_DEFAULT_REGION.
+    #[structopt(short, long)]
+    default_region: Option<String>,
+
+    /// The ID of the user pool
+    #[structopt(short, long)]
+    pool_id: String,
+
+    /// The username
+    #[structopt(short, long)]
+    username: String,
+
+    /// The AWS profile to use from the shared credentials file
+    #[structopt(long)]
+    profile: Option<String>,
+
+    /// Whether to display additional information
+    #[structopt(short, long)]
+    verbose: bool,
+}
+
+/// Confirms a user who signed up, as an administrator, without the code that Amazon Cognito sent them,
+/// so they can sign in. This needs AWS credentials, unlike **sign-up**.
+/// # Arguments
+///
+/// * `-p POOL-ID` - The ID of the user pool.
+/// * `-u USERNAME` - The username.
+/// * `[-d DEFAULT-REGION]` - The region in which the client is created.
+///    If not supplied, uses the value of the **AWS_DEFAULT_REGION** environment variable.
+///    If the environment variable is not set, defaults to **us-west-2**.
+/// * `[--profile PROFILE]` - The AWS profile to use from the shared credentials file.
+/// * `[-v]` - Whether to display additional information.
+#[tokio::main]
+async fn main() -> Result<(), Error> {
+    let Opt {
+        default_region,
+        pool_id,
+        profile,
+        username,
+        verbose,
+    } = Opt::from_args();
+
+    let region = default_region
+        .as_ref()
+        .map(|region| Region::new(region.clone()))
+        .or_else(|| aws_types::region::default_provider().region())
+        .unwrap_or_else(|| Region::new("us-west-2"));
+
+    if verbose {
+        println!(
+            "Cognito client version: {}",
+            cognitoidentityprovider::PKG_VERSION
+        );
+        println!("Region:                 {:?}", &region);
+        println!("User pool ID:           {}", &pool_id);
+        println!("Username:               {}", &username);
+        println!();
+
+        SubscriberBuilder::default()
+            .with_env_filter("info")
+            .with_span_events(FmtSpan::CLOSE)
+            .init();
+    }
+
+    let mut config = Config::builder().region(region);
+    if let Some(profile) = profile {
+        config = config.credentials_provider(ProfileCredentialsProvider::new(profile));
+    }
+    let client = Client::from_conf(config.build());
+
+    client
+        .admin_confirm_sign_up()
+        .user_pool_id(&pool_id)
+        .username(&username)
+        .send()
+        .await?;
+
+    println!("Confirmed {}, who can now sign in", username);
+
+    Ok(())
+}
@@ -0,0 +1,111 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0.
+ */
+
+use std::error::Error;
+
+use cognitoidentityprovider::model::ExplicitAuthFlowsType;
+use cognitoidentityprovider::{Client, Config, Region};
+
+use aws_types::region::ProvideRegion;
+
+use common::profile::ProfileCredentialsProvider;
+use structopt::StructOpt;
+use tracing_subscriber::fmt::format::FmtSpan;
+use tracing_subscriber::fmt::SubscriberBuilder;
+
+#[derive(Debug, StructOpt)]
+struct Opt {
+    /// The region. Overrides environment variable AWS_DEFAULT_REGION.
+    #[structopt(short, long)]
+    default_region: Option<String>,
+
+    /// The ID of the user pool
+    #[structopt(short, long)]
+    pool_id: String,
+
+    /// The name of the app client
+    #[structopt(short, long)]
+    name: String,
+
+    /// The AWS profile to use from the shared credentials file
+    #[structopt(long)]
+    profile: Option<String>,
+
+    /// Whether to display additional information
+    #[structopt(short, long)]
+    verbose: bool,
+}
+
+/// Creates an app client for a user pool, which lets an application sign users up and in,
+/// and displays its ID, which **sign-up** and **initiate-auth** use.
+/// The client can sign users in with their username and password, and refresh their tokens.
+/// It has no client secret, as an application that runs on a user's device can't keep one.
+/// # Arguments
+///
+/// * `-p POOL-ID` - The ID of the user pool, which **create-user-pool** displays.
+/// * `-n NAME` - The name of the app client.
+/// * `[-d DEFAULT-REGION]` - The region in which the client is created.
+///    If not supplied, uses the value of the **AWS_DEFAULT_REGION** environment variable.
+///    If the environment variable is not set, defaults to **us-west-2**.
+/// * `[--profile PROFILE]` - The AWS profile to use from the shared credentials file.
+/// * `[-v]` - Whether to display additional information.
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn Error>> {
+    let Opt {
+        default_region,
+        name,
+        pool_id,
+        profile,
+        verbose,
+    } = Opt::from_args();
+
+    let region = default_region
+        .as_ref()
+        .map(|region| Region::new(region.clone()))
+        .or_else(|| aws_types::region::default_provider().region())
+        .unwrap_or_else(|| Region::new("us-west-2"));
+
+    if verbose {
+        println!(
+            "Cognito client version: {}",
+            cognitoidentityprovider::PKG_VERSION
+        );
+        println!("Region:                 {:?}", &region);
+        println!("User pool ID:           {}", &pool_id);
+        println!("Name:                   {}", &name);
+        println!();
+
+        SubscriberBuilder::default()
+            .with_env_filter("info")
+            .with_span_events(FmtSpan::CLOSE)
+            .init();
+    }
+
+    let mut config = Config::builder().region(region);
+    if let Some(profile) = profile {
+        config = config.credentials_provider(ProfileCredentialsProvider::new(profile));
+    }
+    let client = Client::from_conf(config.build());
+
+    let resp = client
+        .create_user_pool_client()
+        .user_pool_id(&pool_id)
+        .client_name(&name)
+        .generate_secret(false)
+        .explicit_auth_flows(ExplicitAuthFlowsType::AllowUserPasswordAuth)
+        .explicit_auth_flows(ExplicitAuthFlowsType::AllowRefreshTokenAuth)
+        .send()
+        .await?;
+
+    let app_client = resp.user_pool_client.ok_or("no app client returned")?;
+
+    println!(
+        "Created app client {}: {}",
+        name,
+        app_client.client_id.as_deref().unwrap_or_default()
+    );
+
+    Ok(())
+}
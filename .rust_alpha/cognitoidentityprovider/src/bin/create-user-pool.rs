@@ -0,0 +1,146 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0.
+ */
+
+use std::error::Error;
+
+use cognitoidentityprovider::model::{
+    PasswordPolicyType, UserPoolPolicyType, UsernameAttributeType, VerifiedAttributeType,
+};
+use cognitoidentityprovider::{Client, Config, Region};
+
+use aws_types::region::ProvideRegion;
+
+use common::profile::ProfileCredentialsProvider;
+use structopt::StructOpt;
+use tracing_subscriber::fmt::format::FmtSpan;
+use tracing_subscriber::fmt::SubscriberBuilder;
+
+#[derive(Debug, StructOpt)]
+struct Opt {
+    /// The region. Overrides environment variable AWS_DEFAULT_REGION.
+    #[structopt(short, long)]
+    default_region: Option<String>,
+
+    /// The name of the user pool
+    #[structopt(short, long)]
+    name: String,
+
+    /// The fewest characters a password can have
+    #[structopt(short, long, default_value = "8")]
+    min_password_length: i32,
+
+    /// Whether users sign up and sign in with their email address as their username
+    #[structopt(short, long)]
+    email_username: bool,
+
+    /// The AWS profile to use from the shared credentials file
+    #[structopt(long)]
+    profile: Option<String>,
+
+    /// Whether to display additional information
+    #[structopt(short, long)]
+    verbose: bool,
+}
+
+/// Creates an Amazon Cognito user pool, a directory of users who can sign up and sign in,
+/// and displays its ID, which the other examples use.
+/// Passwords must have uppercase and lowercase letters and numbers.
+/// # Arguments
+///
+/// * `-n NAME` - The name of the user pool.
+/// * `[-m MIN-PASSWORD-LENGTH]` - The fewest characters a password can have, from 6 to 99.
+///   If not supplied, defaults to **8**.
+/// * `[-e]` - Whether users sign up and sign in with their email address as their username.
+///   Their email address is then verified when they sign up.
+/// * `[-d DEFAULT-REGION]` - The region in which the client is created.
+///    If not supplied, uses the value of the **AWS_DEFAULT_REGION** environment variable.
+///    If the environment variable is not set, defaults to **us-west-2**.
+/// * `[--profile PROFILE]` - The AWS profile to use from the shared credentials file.
+/// * `[-v]` - Whether to display additional information.
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn Error>> {
+    let Opt {
+        default_region,
+        email_username,
+        min_password_length,
+        name,
+        profile,
+        verbose,
+    } = Opt::from_args();
+
+    let region = default_region
+        .as_ref()
+        .map(|region| Region::new(region.clone()))
+        .or_else(|| aws_types::region::default_provider().region())
+        .unwrap_or_else(|| Region::new("us-west-2"));
+
+    if !(6..=99).contains(&min_password_length) {
+        return Err(format!(
+            "the minimum password length must be from 6 to 99, got {}",
+            min_password_length
+        )
+        .into());
+    }
+
+    if verbose {
+        println!(
+            "Cognito client version: {}",
+            cognitoidentityprovider::PKG_VERSION
+        );
+        println!("Region:                 {:?}", &region);
+        println!("Name:                   {}", &name);
+        println!("Min password length:    {}", min_password_length);
+        println!("Email as username:      {}", email_username);
+        println!();
+
+        SubscriberBuilder::default()
+            .with_env_filter("info")
+            .with_span_events(FmtSpan::CLOSE)
+            .init();
+    }
+
+    let mut config = Config::builder().region(region);
+    if let Some(profile) = profile {
+        config = config.credentials_provider(ProfileCredentialsProvider::new(profile));
+    }
+    let client = Client::from_conf(config.build());
+
+    let policies = UserPoolPolicyType::builder()
+        .password_policy(
+            PasswordPolicyType::builder()
+                .minimum_length(min_password_length)
+                .require_uppercase(true)
+                .require_lowercase(true)
+                .require_numbers(true)
+                .require_symbols(false)
+                .build(),
+        )
+        .build();
+
+    let mut request = client
+        .create_user_pool()
+        .pool_name(&name)
+        .policies(policies);
+    if email_username {
+        request = request
+            .username_attributes(UsernameAttributeType::Email)
+            .auto_verified_attributes(VerifiedAttributeType::Email);
+    }
+
+    let resp = request.send().await?;
+    let pool = resp.user_pool.ok_or("no user pool returned")?;
+
+    println!(
+        "Created user pool {}: {}",
+        name,
+        pool.id.as_deref().unwrap_or_default()
+    );
+
+    if verbose {
+        println!("ARN: {}", pool.arn.as_deref().unwrap_or_default());
+    }
+
+    Ok(())
+}
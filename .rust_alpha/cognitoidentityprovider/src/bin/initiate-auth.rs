@@ -0,0 +1,200 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0.
+ */
+
+use std::error::Error;
+
+use cognitoidentityprovider::error::InitiateAuthErrorKind;
+use cognitoidentityprovider::model::AuthFlowType;
+use cognitoidentityprovider::{Client, Config, Region, SdkError};
+use cognitoidentityprovider_code_examples::tokens::{claims, expires_in, pretty};
+
+use aws_types::region::ProvideRegion;
+
+use common::profile::ProfileCredentialsProvider;
+use structopt::StructOpt;
+use tracing_subscriber::fmt::format::FmtSpan;
+use tracing_subscriber::fmt::SubscriberBuilder;
+
+#[derive(Debug, StructOpt)]
+struct Opt {
+    /// The region. Overrides environment variable AWS_DEFAULT_REGION.
+    #[structopt(short, long)]
+    default_region: Option<String>,
+
+    /// The ID of the app client
+    #[structopt(short, long)]
+    client_id: String,
+
+    /// The username
+    #[structopt(short, long)]
+    username: String,
+
+    /// The password
+    #[structopt(short, long)]
+    password: String,
+
+    /// Whether to display the claims in the ID and access tokens
+    #[structopt(long)]
+    claims: bool,
+
+    /// The AWS profile to use from the shared credentials file
+    #[structopt(long)]
+    profile: Option<String>,
+
+    /// Whether to display additional information
+    #[structopt(short, long)]
+    verbose: bool,
+}
+
+/// Displays a token, and, if asked, the claims in it.
+fn show_token(name: &str, token: &str, show_claims: bool) -> Result<(), String> {
+    println!("{}:", name);
+    println!("{}", token);
+
+    if show_claims {
+        let claims = claims(token)?;
+
+        if let Some(expires_in) = expires_in(&claims) {
+            println!("Expires in {} minutes", expires_in.as_secs() / 60);
+        }
+        println!("Claims:");
+        println!("{}", pretty(&claims, 2));
+    }
+
+    println!();
+    Ok(())
+}
+
+/// Signs a user in to a user pool with their username and password (the USER_PASSWORD_AUTH flow),
+/// and displays the JSON Web Tokens (JWTs) that Amazon Cognito returns: the ID token, which says who the user is,
+/// the access token, which an API checks, and the refresh token, which gets new ID and access tokens
+/// when they expire.
+/// # Arguments
+///
+/// * `-c CLIENT-ID` - The ID of the app client, which must allow the USER_PASSWORD_AUTH flow,
+///   as the client that **create-user-pool-client** creates does.
+/// * `-u USERNAME` - The username.
+/// * `-p PASSWORD` - The password.
+/// * `[--claims]` - Whether to display the claims in the ID and access tokens, such as the user's **sub**.
+///   The claims aren't verified; an application must check the token's signature before it trusts them.
+/// * `[-d DEFAULT-REGION]` - The region in which the client is created.
+///    If not supplied, uses the value of the **AWS_DEFAULT_REGION** environment variable.
+///    If the environment variable is not set, defaults to **us-west-2**.
+/// * `[--profile PROFILE]` - The AWS profile to use from the shared credentials file.
+/// * `[-v]` - Whether to display additional information.
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn Error>> {
+    let Opt {
+        claims,
+        client_id,
+        default_region,
+        password,
+        profile,
+        username,
+        verbose,
+    } = Opt::from_args();
+
+    let region = default_region
+        .as_ref()
+        .map(|region| Region::new(region.clone()))
+        .or_else(|| aws_types::region::default_provider().region())
+        .unwrap_or_else(|| Region::new("us-west-2"));
+
+    if verbose {
+        println!(
+            "Cognito client version: {}",
+            cognitoidentityprovider::PKG_VERSION
+        );
+        println!("Region:                 {:?}", &region);
+        println!("App client ID:          {}", &client_id);
+        println!("Username:               {}", &username);
+        println!();
+
+        SubscriberBuilder::default()
+            .with_env_filter("info")
+            .with_span_events(FmtSpan::CLOSE)
+            .init();
+    }
+
+    let mut config = Config::builder().region(region);
+    if let Some(profile) = profile {
+        config = config.credentials_provider(ProfileCredentialsProvider::new(profile));
+    }
+    let client = Client::from_conf(config.build());
+
+    let result = client
+        .initiate_auth()
+        .client_id(&client_id)
+        .auth_flow(AuthFlowType::UserPasswordAuth)
+        .auth_parameters("USERNAME", &username)
+        .auth_parameters("PASSWORD", password)
+        .send()
+        .await;
+
+    let resp = match result {
+        Ok(resp) => resp,
+        Err(SdkError::ServiceError { err, .. })
+            if matches!(err.kind, InitiateAuthErrorKind::NotAuthorizedException(_)) =>
+        {
+            return Err(format!(
+                "couldn't sign in {}: {}",
+                username,
+                err.message().unwrap_or("incorrect username or password")
+            )
+            .into());
+        }
+        Err(SdkError::ServiceError { err, .. })
+            if matches!(
+                err.kind,
+                InitiateAuthErrorKind::UserNotConfirmedException(_)
+            ) =>
+        {
+            return Err(format!(
+                "{} isn't confirmed yet; confirm them with admin-confirm-sign-up",
+                username
+            )
+            .into());
+        }
+        Err(e) => return Err(e.into()),
+    };
+
+    // A user pool can ask for more before it signs the user in, such as a new password
+    // for a user an administrator created, or a code for multi-factor authentication.
+    if let Some(challenge) = resp.challenge_name {
+        println!(
+            "Amazon Cognito needs a response to the {} challenge before it signs {} in",
+            challenge.as_str(),
+            username
+        );
+        return Ok(());
+    }
+
+    let result = resp
+        .authentication_result
+        .ok_or("no authentication result returned")?;
+
+    println!(
+        "Signed in {}. The ID and access tokens expire in {} seconds",
+        username, result.expires_in
+    );
+    println!();
+
+    show_token(
+        "ID token",
+        result.id_token.as_deref().unwrap_or_default(),
+        claims,
+    )?;
+    show_token(
+        "Access token",
+        result.access_token.as_deref().unwrap_or_default(),
+        claims,
+    )?;
+
+    // The refresh token is encrypted, so it has no claims to show.
+    println!("Refresh token:");
+    println!("{}", result.refresh_token.as_deref().unwrap_or_default());
+
+    Ok(())
+}
@@ -0,0 +1,158 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0.
+ */
+
+use std::error::Error;
+
+use cognitoidentityprovider::error::SignUpErrorKind;
+use cognitoidentityprovider::model::AttributeType;
+use cognitoidentityprovider::{Client, Config, Region, SdkError};
+
+use aws_types::region::ProvideRegion;
+
+use common::profile::ProfileCredentialsProvider;
+use structopt::StructOpt;
+use tracing_subscriber::fmt::format::FmtSpan;
+use tracing_subscriber::fmt::SubscriberBuilder;
+
+#[derive(Debug, StructOpt)]
+struct Opt {
+    /// The region. Overrides environment variable AWS_DEFAULT_REGION.
+    #[structopt(short, long)]
+    default_region: Option<String>,
+
+    /// The ID of the app client
+    #[structopt(short, long)]
+    client_id: String,
+
+    /// The username
+    #[structopt(short, long)]
+    username: String,
+
+    /// The password
+    #[structopt(short, long)]
+    password: String,
+
+    /// The user's email address
+    #[structopt(short, long)]
+    email: Option<String>,
+
+    /// The AWS profile to use from the shared credentials file
+    #[structopt(long)]
+    profile: Option<String>,
+
+    /// Whether to display additional information
+    #[structopt(short, long)]
+    verbose: bool,
+}
+
+/// Signs a user up to a user pool through an app client, as an application would.
+/// The user can't sign in until they're confirmed, either with the code that Amazon Cognito sends them,
+/// or by an administrator with **admin-confirm-sign-up**.
+/// # Arguments
+///
+/// * `-c CLIENT-ID` - The ID of the app client, which **create-user-pool-client** displays.
+/// * `-u USERNAME` - The username. For a user pool that uses email addresses as usernames, the email address.
+/// * `-p PASSWORD` - The password, which must meet the user pool's password policy.
+/// * `[-e EMAIL]` - The user's email address.
+/// * `[-d DEFAULT-REGION]` - The region in which the client is created.
+///    If not supplied, uses the value of the **AWS_DEFAULT_REGION** environment variable.
+///    If the environment variable is not set, defaults to **us-west-2**.
+/// * `[--profile PROFILE]` - The AWS profile to use from the shared credentials file.
+/// * `[-v]` - Whether to display additional information.
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn Error>> {
+    let Opt {
+        client_id,
+        default_region,
+        email,
+        password,
+        profile,
+        username,
+        verbose,
+    } = Opt::from_args();
+
+    let region = default_region
+        .as_ref()
+        .map(|region| Region::new(region.clone()))
+        .or_else(|| aws_types::region::default_provider().region())
+        .unwrap_or_else(|| Region::new("us-west-2"));
+
+    if verbose {
+        println!(
+            "Cognito client version: {}",
+            cognitoidentityprovider::PKG_VERSION
+        );
+        println!("Region:                 {:?}", &region);
+        println!("App client ID:          {}", &client_id);
+        println!("Username:               {}", &username);
+        println!("Email:                  {:?}", &email);
+        println!();
+
+        SubscriberBuilder::default()
+            .with_env_filter("info")
+            .with_span_events(FmtSpan::CLOSE)
+            .init();
+    }
+
+    let mut config = Config::builder().region(region);
+    if let Some(profile) = profile {
+        config = config.credentials_provider(ProfileCredentialsProvider::new(profile));
+    }
+    let client = Client::from_conf(config.build());
+
+    let mut request = client
+        .sign_up()
+        .client_id(&client_id)
+        .username(&username)
+        .password(password);
+    if let Some(email) = email {
+        request =
+            request.user_attributes(AttributeType::builder().name("email").value(email).build());
+    }
+
+    let resp = match request.send().await {
+        Ok(resp) => resp,
+        Err(SdkError::ServiceError { err, .. })
+            if matches!(err.kind, SignUpErrorKind::UsernameExistsException(_)) =>
+        {
+            return Err(format!("the user {} already exists", username).into());
+        }
+        Err(SdkError::ServiceError { err, .. })
+            if matches!(err.kind, SignUpErrorKind::InvalidPasswordException(_)) =>
+        {
+            return Err(format!(
+                "the password doesn't meet the user pool's policy: {}",
+                err.message().unwrap_or_default()
+            )
+            .into());
+        }
+        Err(e) => return Err(e.into()),
+    };
+
+    println!(
+        "Signed up {}, whose sub is {}",
+        username,
+        resp.user_sub.as_deref().unwrap_or_default()
+    );
+
+    if resp.user_confirmed {
+        println!("The user is confirmed, and can sign in");
+    } else {
+        match resp.code_delivery_details {
+            Some(details) => println!(
+                "Sent a confirmation code by {} to {}",
+                details
+                    .delivery_medium
+                    .as_ref()
+                    .map(|medium| medium.as_str())
+                    .unwrap_or_default(),
+                details.destination.as_deref().unwrap_or_default()
+            ),
+            None => println!("The user must be confirmed before they can sign in"),
+        }
+    }
+
+    Ok(())
+}
@@ -0,0 +1,63 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0.
+ */
+
+//! Reads the claims in the JSON Web Tokens (JWTs) that a user pool returns when a user signs in.
+//!
+//! A JWT is three base64url parts separated by dots: a header, the claims, and a signature.
+//! The ID token's claims say who the user is, such as their **sub** and **email**; the access token's
+//! claims say what they can do, such as their **scope** and **cognito:groups**. Both have an **exp** claim,
+//! when the token expires, in seconds since the Unix epoch:
+//!
+//! ```ignore
+//! let claims = claims(&id_token)?;
+//! println!("{} expires in {:?}", claims["sub"], expires_in(&claims));
+//! ```
+//!
+//! The claims are decoded here only to display them. An application must verify the signature,
+//! with the keys that the user pool publishes, before it trusts them.
+
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use serde_json::Value;
+
+/// Decodes the claims of a JWT, without verifying its signature.
+pub fn claims(token: &str) -> Result<Value, String> {
+    let parts: Vec<&str> = token.split('.').collect();
+    if parts.len() != 3 {
+        return Err(format!(
+            "a JWT has three parts separated by dots, but this one has {}",
+            parts.len()
+        ));
+    }
+
+    let json = base64::decode_config(parts[1], base64::URL_SAFE_NO_PAD)
+        .map_err(|e| format!("the claims of the JWT aren't base64url: {}", e))?;
+
+    serde_json::from_slice(&json).map_err(|e| format!("the claims of the JWT aren't JSON: {}", e))
+}
+
+/// Returns how long the token has left before it expires, or `None` if it has no **exp** claim.
+/// An expired token has no time left.
+pub fn expires_in(claims: &Value) -> Option<Duration> {
+    let expires = UNIX_EPOCH + Duration::from_secs(claims["exp"].as_u64()?);
+
+    Some(
+        expires
+            .duration_since(SystemTime::now())
+            .unwrap_or_default(),
+    )
+}
+
+/// Formats the claims over several indented lines, sorted by name.
+pub fn pretty(claims: &Value, indent: usize) -> String {
+    let padding = " ".repeat(indent);
+
+    serde_json::to_string_pretty(claims)
+        .unwrap_or_else(|_| claims.to_string())
+        .lines()
+        .map(|line| format!("{}{}", padding, line))
+        .collect::<Vec<_>>()
+        .join("\n")
+}
@@ -0,0 +1,35 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0.
+ */
+
+//! Sending requests to a local endpoint instead of AWS.
+//!
+//! When the `AWS_ENDPOINT_URL` environment variable is set, the examples that
+//! support it send their requests there, such as `http://localhost:8000` for
+//! DynamoDB Local or `http://localhost:4566` for LocalStack:
+//!
+//! ```ignore
+//! if let Some(uri) = endpoint::from_env()? {
+//!     config = config.endpoint_resolver(Endpoint::immutable(uri));
+//! }
+//! ```
+//!
+//! The integration tests use this to run the examples without an AWS account.
+
+use std::env;
+
+use http::uri::{InvalidUri, Uri};
+
+/// The environment variable that holds the endpoint URL.
+pub const ENDPOINT_URL: &str = "AWS_ENDPOINT_URL";
+
+/// Returns the endpoint in `AWS_ENDPOINT_URL`, or `None` if the variable isn't set.
+/// An invalid URL is an error, rather than being ignored,
+/// so that requests meant for a local endpoint are never sent to AWS.
+pub fn from_env() -> Result<Option<Uri>, InvalidUri> {
+    match env::var(ENDPOINT_URL) {
+        Ok(url) if !url.is_empty() => url.parse().map(Some),
+        _ => Ok(None),
+    }
+}
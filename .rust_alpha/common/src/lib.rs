@@ -0,0 +1,17 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0.
+ */
+
+//! Helpers shared by the AWS SDK for Rust code examples.
+//!
+//! The examples depend on this crate through the workspace:
+//!
+//! ```toml
+//! common = { workspace = true }
+//! ```
+
+pub mod endpoint;
+pub mod output;
+pub mod profile;
+pub mod shutdown;
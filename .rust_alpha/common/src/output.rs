@@ -0,0 +1,114 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0.
+ */
+
+//! Printing example results as plain text or JSON.
+//!
+//! Each example describes its result with a struct that implements both
+//! `serde::Serialize` and `std::fmt::Display`, and adds an `--output` option:
+//!
+//! ```ignore
+//! /// The format of the output, text or json
+//! #[structopt(long, default_value = "text")]
+//! output: OutputFormat,
+//! ```
+//!
+//! Calling `output.print(&result)` then writes the `Display` text, or the
+//! result as pretty-printed JSON that can be piped into tools such as __jq__.
+//! Examples that print results as they arrive call `output.print_line(&result)`
+//! for each one instead, which writes each result as JSON on its own line.
+
+use std::error::Error;
+use std::fmt;
+use std::str::FromStr;
+
+use serde::Serialize;
+
+/// The format an example uses to print its results.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum OutputFormat {
+    /// Human-readable text.
+    Text,
+    /// Pretty-printed JSON.
+    Json,
+}
+
+impl OutputFormat {
+    /// Whether the results are printed as JSON.
+    pub fn is_json(self) -> bool {
+        self == OutputFormat::Json
+    }
+
+    /// Prints `result` to stdout in this format.
+    pub fn print<T>(self, result: &T)
+    where
+        T: Serialize + fmt::Display,
+    {
+        match self {
+            OutputFormat::Text => println!("{}", result),
+            OutputFormat::Json => match serde_json::to_string_pretty(result) {
+                Ok(json) => println!("{}", json),
+                Err(e) => eprintln!("Got an error serializing the output: {}", e),
+            },
+        }
+    }
+
+    /// Prints `result` to stdout in this format, as JSON on a single line,
+    /// so that a stream of results can be read one line at a time.
+    pub fn print_line<T>(self, result: &T)
+    where
+        T: Serialize + fmt::Display,
+    {
+        match self {
+            OutputFormat::Text => println!("{}", result),
+            OutputFormat::Json => match serde_json::to_string(result) {
+                Ok(json) => println!("{}", json),
+                Err(e) => eprintln!("Got an error serializing the output: {}", e),
+            },
+        }
+    }
+}
+
+impl Default for OutputFormat {
+    fn default() -> Self {
+        OutputFormat::Text
+    }
+}
+
+impl fmt::Display for OutputFormat {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            OutputFormat::Text => write!(f, "text"),
+            OutputFormat::Json => write!(f, "json"),
+        }
+    }
+}
+
+impl FromStr for OutputFormat {
+    type Err = ParseOutputFormatError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "text" => Ok(OutputFormat::Text),
+            "json" => Ok(OutputFormat::Json),
+            _ => Err(ParseOutputFormatError(s.to_string())),
+        }
+    }
+}
+
+/// The error returned when an `--output` value is neither __text__ nor __json__.
+#[derive(Debug)]
+pub struct ParseOutputFormatError(String);
+
+impl fmt::Display for ParseOutputFormatError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} is not a valid output format; use text or json",
+            self.0
+        )
+    }
+}
+
+impl Error for ParseOutputFormatError {}
@@ -0,0 +1,162 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0.
+ */
+
+//! Credentials from a named profile in the shared credentials file.
+//!
+//! The SDK's default credentials provider only reads environment variables.
+//! Examples that add a `--profile` option use this provider instead when a
+//! profile is named:
+//!
+//! ```ignore
+//! /// The AWS profile to use from the shared credentials file
+//! #[structopt(long)]
+//! profile: Option<String>,
+//! ```
+//!
+//! ```ignore
+//! let mut config = Config::builder().region(region);
+//! if let Some(profile) = profile {
+//!     config = config.credentials_provider(ProfileCredentialsProvider::new(profile));
+//! }
+//! let client = Client::from_conf(config.build());
+//! ```
+//!
+//! The credentials file is __~/.aws/credentials__, unless the
+//! __AWS_SHARED_CREDENTIALS_FILE__ environment variable names another file.
+
+use std::env;
+use std::fmt;
+use std::fs;
+use std::path::PathBuf;
+
+use aws_auth::{Credentials, CredentialsError, ProvideCredentials};
+
+/// Provides the credentials of a named profile in the shared credentials file.
+#[derive(Clone, Debug)]
+pub struct ProfileCredentialsProvider {
+    profile: String,
+}
+
+/// Why the credentials of a profile could not be loaded.
+#[derive(Debug)]
+pub enum ProfileError {
+    /// The home directory, and so the credentials file, could not be found.
+    NoHomeDirectory,
+    /// The credentials file could not be read.
+    Io(PathBuf, std::io::Error),
+    /// The credentials file has no section for the profile.
+    NoSuchProfile(PathBuf, String),
+    /// The profile is missing a required key, such as __aws_access_key_id__.
+    MissingKey(String, &'static str),
+}
+
+impl fmt::Display for ProfileError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ProfileError::NoHomeDirectory => {
+                write!(
+                    f,
+                    "could not find the home directory to locate the credentials file"
+                )
+            }
+            ProfileError::Io(path, e) => write!(f, "could not read {}: {}", path.display(), e),
+            ProfileError::NoSuchProfile(path, profile) => {
+                write!(f, "{} has no profile named {}", path.display(), profile)
+            }
+            ProfileError::MissingKey(profile, key) => {
+                write!(f, "profile {} has no {}", profile, key)
+            }
+        }
+    }
+}
+
+impl std::error::Error for ProfileError {}
+
+impl ProfileCredentialsProvider {
+    /// Creates a provider for the named profile.
+    /// The credentials file isn't read until the first request is signed.
+    pub fn new(profile: impl Into<String>) -> Self {
+        ProfileCredentialsProvider {
+            profile: profile.into(),
+        }
+    }
+
+    /// Reads the profile's credentials from the credentials file.
+    pub fn load(&self) -> Result<Credentials, ProfileError> {
+        let path = credentials_file()?;
+        let text = fs::read_to_string(&path).map_err(|e| ProfileError::Io(path.clone(), e))?;
+
+        let properties = parse_profile(&text, &self.profile)
+            .ok_or_else(|| ProfileError::NoSuchProfile(path, self.profile.clone()))?;
+
+        let get = |key: &'static str| {
+            properties
+                .iter()
+                .find(|(name, _)| name == key)
+                .map(|(_, value)| value.clone())
+        };
+
+        let access_key_id = get("aws_access_key_id")
+            .ok_or_else(|| ProfileError::MissingKey(self.profile.clone(), "aws_access_key_id"))?;
+        let secret_access_key = get("aws_secret_access_key").ok_or_else(|| {
+            ProfileError::MissingKey(self.profile.clone(), "aws_secret_access_key")
+        })?;
+
+        Ok(Credentials::new(
+            access_key_id,
+            secret_access_key,
+            get("aws_session_token"),
+            None,
+            "Profile",
+        ))
+    }
+}
+
+impl ProvideCredentials for ProfileCredentialsProvider {
+    fn provide_credentials(&self) -> Result<Credentials, CredentialsError> {
+        self.load()
+            .map_err(|e| CredentialsError::Unhandled(Box::new(e)))
+    }
+}
+
+/// Returns the path of the shared credentials file.
+fn credentials_file() -> Result<PathBuf, ProfileError> {
+    if let Some(path) = env::var_os("AWS_SHARED_CREDENTIALS_FILE") {
+        return Ok(PathBuf::from(path));
+    }
+
+    env::var_os("HOME")
+        .or_else(|| env::var_os("USERPROFILE"))
+        .map(|home| PathBuf::from(home).join(".aws").join("credentials"))
+        .ok_or(ProfileError::NoHomeDirectory)
+}
+
+/// Returns the `key = value` properties in the profile's section of an INI file,
+/// or `None` if the file has no section for the profile.
+fn parse_profile(text: &str, profile: &str) -> Option<Vec<(String, String)>> {
+    let mut properties = None;
+    let mut in_profile = false;
+
+    for line in text.lines().map(str::trim) {
+        if line.is_empty() || line.starts_with('#') || line.starts_with(';') {
+            continue;
+        }
+
+        if line.starts_with('[') && line.ends_with(']') {
+            in_profile = line[1..line.len() - 1].trim() == profile;
+            if in_profile {
+                properties.get_or_insert_with(Vec::new);
+            }
+        } else if in_profile {
+            if let (Some(properties), Some((key, value))) =
+                (properties.as_mut(), line.split_once('='))
+            {
+                properties.push((key.trim().to_string(), value.trim().to_string()));
+            }
+        }
+    }
+
+    properties
+}
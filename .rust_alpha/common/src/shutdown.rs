@@ -0,0 +1,115 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0.
+ */
+
+//! Stopping long-running examples cleanly when the user presses Ctrl-C.
+//!
+//! Without a handler, Ctrl-C kills the process on the spot, so a scenario
+//! never gets to delete what it created, and a consumer loses the batch it
+//! was working on. An example that runs until interrupted calls
+//! `Shutdown::listen()` once, before it starts the work, and then either:
+//!
+//! - wraps the work in `shutdown.run(...)`, which stops the work at its next
+//!   `.await` and returns `Interrupted`, so that the cleanup after it still runs:
+//!
+//!   ```ignore
+//!   let shutdown = Shutdown::listen();
+//!   let result = shutdown.run(run(&clients, &opt, &mut resources)).await;
+//!   tear_down(&clients, &resources).await;
+//!   ```
+//!
+//! - or checks `shutdown.is_requested()` between batches, so that the batch in
+//!   progress is finished before the loop ends:
+//!
+//!   ```ignore
+//!   while !shutdown.is_requested() {
+//!       let batch = receive().await?;
+//!       process(batch).await?;
+//!   }
+//!   ```
+//!
+//! Once the handler is listening, further Ctrl-Cs don't kill the process,
+//! so the cleanup isn't cut short.
+
+use std::error::Error;
+use std::fmt;
+use std::future::{self, Future};
+
+use tokio::signal;
+use tokio::sync::watch;
+
+/// The error that `Shutdown::run` returns when the user presses Ctrl-C.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Interrupted;
+
+impl fmt::Display for Interrupted {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "interrupted by Ctrl-C")
+    }
+}
+
+impl Error for Interrupted {}
+
+/// A handle that reports whether the user has pressed Ctrl-C.
+/// Clones share the same state, so a handle can be passed to each task that needs it.
+#[derive(Clone, Debug)]
+pub struct Shutdown {
+    receiver: watch::Receiver<bool>,
+}
+
+impl Shutdown {
+    /// Starts listening for Ctrl-C in the background.
+    /// Must be called from within the Tokio runtime, such as in a `#[tokio::main]` function.
+    pub fn listen() -> Self {
+        let (sender, receiver) = watch::channel(false);
+
+        tokio::spawn(async move {
+            match signal::ctrl_c().await {
+                Ok(()) => {
+                    eprintln!();
+                    eprintln!("Stopping, and cleaning up. This can take a minute");
+                    let _ = sender.send(true);
+                }
+                Err(e) => {
+                    eprintln!("Got an error listening for Ctrl-C: {}", e);
+                    // Keep the sender, so that waiting for Ctrl-C waits forever rather than
+                    // looking like a shutdown request.
+                    future::pending::<()>().await;
+                }
+            }
+        });
+
+        Shutdown { receiver }
+    }
+
+    /// Whether the user has pressed Ctrl-C.
+    pub fn is_requested(&self) -> bool {
+        *self.receiver.borrow()
+    }
+
+    /// Waits until the user presses Ctrl-C.
+    pub async fn requested(&self) {
+        let mut receiver = self.receiver.clone();
+
+        while !*receiver.borrow() {
+            if receiver.changed().await.is_err() {
+                future::pending::<()>().await;
+            }
+        }
+    }
+
+    /// Runs the future until it finishes, or until the user presses Ctrl-C.
+    /// If the user presses Ctrl-C first, the future is dropped at its current `.await`,
+    /// and the result is `Interrupted`.
+    pub async fn run<F, T, E>(&self, work: F) -> Result<T, E>
+    where
+        F: Future<Output = Result<T, E>>,
+        E: From<Interrupted>,
+    {
+        tokio::select! {
+            result = work => result,
+            _ = self.requested() => Err(Interrupted.into()),
+        }
+    }
+}
@@ -0,0 +1,145 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0.
+ */
+
+use std::error::Error;
+use std::fmt;
+
+use comprehend::{Client, Config, Region};
+use comprehend_code_examples::text::read_text;
+
+use aws_types::region::ProvideRegion;
+
+use common::output::OutputFormat;
+use common::profile::ProfileCredentialsProvider;
+use serde::Serialize;
+use structopt::StructOpt;
+use tracing_subscriber::fmt::format::FmtSpan;
+use tracing_subscriber::fmt::SubscriberBuilder;
+
+#[derive(Debug, StructOpt)]
+struct Opt {
+    /// The default region
+    #[structopt(short, long)]
+    default_region: Option<String>,
+
+    /// The file holding the text, or - to read it from stdin
+    #[structopt(short, long)]
+    file: Option<String>,
+
+    /// The format of the output, text or json
+    #[structopt(long, default_value = "text")]
+    output: OutputFormat,
+
+    /// The AWS profile to use from the shared credentials file
+    #[structopt(long)]
+    profile: Option<String>,
+
+    /// Whether to display additional information
+    #[structopt(short, long)]
+    verbose: bool,
+}
+
+/// A language that the text might be in.
+#[derive(Debug, Serialize)]
+struct Language {
+    /// The RFC 5646 code of the language, such as en or zh-TW.
+    code: String,
+    score: f32,
+}
+
+/// The languages that the text might be in, most likely first.
+#[derive(Debug, Serialize)]
+struct Languages {
+    languages: Vec<Language>,
+}
+
+impl fmt::Display for Languages {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.languages.first() {
+            Some(language) => writeln!(f, "Dominant language: {}\n", language.code)?,
+            None => writeln!(f, "No language detected\n")?,
+        }
+
+        write!(f, "Score  Language")?;
+        for language in &self.languages {
+            write!(f, "\n{:.2}   {}", language.score, language.code)?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Detects which language text is in with Amazon Comprehend, and displays each language
+/// it might be in, with its score, most likely first. Text in more than one language
+/// has more than one language with a high score.
+/// # Arguments
+///
+/// * `[-f FILE]` - The file holding the text, up to 5,000 bytes.
+///   If not supplied, or **-**, the text is read from stdin.
+/// * `[-d DEFAULT-REGION]` - The region in which the client is created.
+///   If not supplied, uses the value of the **AWS_DEFAULT_REGION** environment variable.
+///   If the environment variable is not set, defaults to **us-west-2**.
+/// * `[--output FORMAT]` - How to display the result, **text** (the default) or **json**.
+/// * `[--profile PROFILE]` - The AWS profile to use from the shared credentials file.
+/// * `[-v]` - Whether to display additional information.
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn Error>> {
+    let Opt {
+        default_region,
+        file,
+        output,
+        profile,
+        verbose,
+    } = Opt::from_args();
+
+    let region = default_region
+        .as_ref()
+        .map(|region| Region::new(region.clone()))
+        .or_else(|| aws_types::region::default_provider().region())
+        .unwrap_or_else(|| Region::new("us-west-2"));
+
+    let text = read_text(file.as_deref())?;
+
+    if verbose {
+        println!("Comprehend client version: {}", comprehend::PKG_VERSION);
+        println!("Region:                    {:?}", &region);
+        println!("File:                      {:?}", &file);
+        println!("Text:                      {} bytes", text.len());
+        println!();
+
+        SubscriberBuilder::default()
+            .with_env_filter("info")
+            .with_span_events(FmtSpan::CLOSE)
+            .init();
+    }
+
+    let mut config = Config::builder().region(region);
+    if let Some(profile) = profile {
+        config = config.credentials_provider(ProfileCredentialsProvider::new(profile));
+    }
+    let client = Client::from_conf(config.build());
+
+    let resp = client.detect_dominant_language().text(text).send().await?;
+
+    let mut languages: Vec<Language> = resp
+        .languages
+        .unwrap_or_default()
+        .into_iter()
+        .map(|language| Language {
+            code: language.language_code.unwrap_or_default(),
+            score: language.score.unwrap_or_default(),
+        })
+        .collect();
+
+    languages.sort_by(|a, b| {
+        b.score
+            .partial_cmp(&a.score)
+            .unwrap_or(std::cmp::Ordering::Equal)
+    });
+
+    output.print(&Languages { languages });
+
+    Ok(())
+}
@@ -0,0 +1,169 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0.
+ */
+
+use std::error::Error;
+use std::fmt;
+
+use comprehend::model::LanguageCode;
+use comprehend::{Client, Config, Region};
+use comprehend_code_examples::text::read_text;
+
+use aws_types::region::ProvideRegion;
+
+use common::output::OutputFormat;
+use common::profile::ProfileCredentialsProvider;
+use serde::Serialize;
+use structopt::StructOpt;
+use tracing_subscriber::fmt::format::FmtSpan;
+use tracing_subscriber::fmt::SubscriberBuilder;
+
+#[derive(Debug, StructOpt)]
+struct Opt {
+    /// The default region
+    #[structopt(short, long)]
+    default_region: Option<String>,
+
+    /// The file holding the text, or - to read it from stdin
+    #[structopt(short, long)]
+    file: Option<String>,
+
+    /// The language of the text, such as en
+    #[structopt(short, long, default_value = "en")]
+    language: String,
+
+    /// Only display the entities with at least this score, from 0 through 1
+    #[structopt(short, long, default_value = "0")]
+    min_score: f32,
+
+    /// The format of the output, text or json
+    #[structopt(long, default_value = "text")]
+    output: OutputFormat,
+
+    /// The AWS profile to use from the shared credentials file
+    #[structopt(long)]
+    profile: Option<String>,
+
+    /// Whether to display additional information
+    #[structopt(short, long)]
+    verbose: bool,
+}
+
+/// A person, place, date, or other entity found in the text.
+#[derive(Debug, Serialize)]
+struct Entity {
+    text: String,
+    #[serde(rename = "type")]
+    kind: String,
+    score: f32,
+    /// Where the entity starts and ends in the text, in characters.
+    begin_offset: i32,
+    end_offset: i32,
+}
+
+/// The entities found in the text, in the order they appear.
+#[derive(Debug, Serialize)]
+struct Entities {
+    entities: Vec<Entity>,
+}
+
+impl fmt::Display for Entities {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "Score  Type              Entity")?;
+
+        for entity in &self.entities {
+            writeln!(
+                f,
+                "{:.2}   {:<17} {} ({}-{})",
+                entity.score, entity.kind, entity.text, entity.begin_offset, entity.end_offset
+            )?;
+        }
+
+        write!(f, "\nFound {} entities.", self.entities.len())
+    }
+}
+
+/// Finds the entities in text, such as people, places, organizations, dates, and quantities,
+/// with Amazon Comprehend, and displays each with its type, score, and where it is in the text.
+/// # Arguments
+///
+/// * `[-f FILE]` - The file holding the text, up to 5,000 bytes.
+///   If not supplied, or **-**, the text is read from stdin.
+/// * `[-l LANGUAGE]` - The language of the text. Defaults to **en**.
+/// * `[-m MIN-SCORE]` - Only display the entities with at least this score, from 0 through 1.
+/// * `[-d DEFAULT-REGION]` - The region in which the client is created.
+///   If not supplied, uses the value of the **AWS_DEFAULT_REGION** environment variable.
+///   If the environment variable is not set, defaults to **us-west-2**.
+/// * `[--output FORMAT]` - How to display the result, **text** (the default) or **json**.
+/// * `[--profile PROFILE]` - The AWS profile to use from the shared credentials file.
+/// * `[-v]` - Whether to display additional information.
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn Error>> {
+    let Opt {
+        default_region,
+        file,
+        language,
+        min_score,
+        output,
+        profile,
+        verbose,
+    } = Opt::from_args();
+
+    let region = default_region
+        .as_ref()
+        .map(|region| Region::new(region.clone()))
+        .or_else(|| aws_types::region::default_provider().region())
+        .unwrap_or_else(|| Region::new("us-west-2"));
+
+    let text = read_text(file.as_deref())?;
+
+    if verbose {
+        println!("Comprehend client version: {}", comprehend::PKG_VERSION);
+        println!("Region:                    {:?}", &region);
+        println!("File:                      {:?}", &file);
+        println!("Language:                  {}", &language);
+        println!("Minimum score:             {}", min_score);
+        println!("Text:                      {} bytes", text.len());
+        println!();
+
+        SubscriberBuilder::default()
+            .with_env_filter("info")
+            .with_span_events(FmtSpan::CLOSE)
+            .init();
+    }
+
+    let mut config = Config::builder().region(region);
+    if let Some(profile) = profile {
+        config = config.credentials_provider(ProfileCredentialsProvider::new(profile));
+    }
+    let client = Client::from_conf(config.build());
+
+    let resp = client
+        .detect_entities()
+        .text(text)
+        .language_code(LanguageCode::from(language.as_str()))
+        .send()
+        .await?;
+
+    let entities = resp
+        .entities
+        .unwrap_or_default()
+        .into_iter()
+        .map(|entity| Entity {
+            text: entity.text.unwrap_or_default(),
+            kind: entity
+                .r#type
+                .map(|kind| kind.as_str().to_string())
+                .unwrap_or_default(),
+            score: entity.score.unwrap_or_default(),
+            begin_offset: entity.begin_offset.unwrap_or_default(),
+            end_offset: entity.end_offset.unwrap_or_default(),
+        })
+        .filter(|entity| entity.score >= min_score)
+        .collect();
+
+    output.print(&Entities { entities });
+
+    Ok(())
+}
@@ -0,0 +1,163 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0.
+ */
+
+use std::error::Error;
+use std::fmt;
+
+use comprehend::model::LanguageCode;
+use comprehend::{Client, Config, Region};
+use comprehend_code_examples::text::read_text;
+
+use aws_types::region::ProvideRegion;
+
+use common::output::OutputFormat;
+use common::profile::ProfileCredentialsProvider;
+use serde::Serialize;
+use structopt::StructOpt;
+use tracing_subscriber::fmt::format::FmtSpan;
+use tracing_subscriber::fmt::SubscriberBuilder;
+
+#[derive(Debug, StructOpt)]
+struct Opt {
+    /// The default region
+    #[structopt(short, long)]
+    default_region: Option<String>,
+
+    /// The file holding the text, or - to read it from stdin
+    #[structopt(short, long)]
+    file: Option<String>,
+
+    /// The language of the text, such as en
+    #[structopt(short, long, default_value = "en")]
+    language: String,
+
+    /// Only display the key phrases with at least this score, from 0 through 1
+    #[structopt(short, long, default_value = "0")]
+    min_score: f32,
+
+    /// The format of the output, text or json
+    #[structopt(long, default_value = "text")]
+    output: OutputFormat,
+
+    /// The AWS profile to use from the shared credentials file
+    #[structopt(long)]
+    profile: Option<String>,
+
+    /// Whether to display additional information
+    #[structopt(short, long)]
+    verbose: bool,
+}
+
+/// A noun phrase that says what the text is about.
+#[derive(Debug, Serialize)]
+struct KeyPhrase {
+    text: String,
+    score: f32,
+    /// Where the phrase starts and ends in the text, in characters.
+    begin_offset: i32,
+    end_offset: i32,
+}
+
+/// The key phrases found in the text, in the order they appear.
+#[derive(Debug, Serialize)]
+struct KeyPhrases {
+    key_phrases: Vec<KeyPhrase>,
+}
+
+impl fmt::Display for KeyPhrases {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "Score  Key phrase")?;
+
+        for phrase in &self.key_phrases {
+            writeln!(
+                f,
+                "{:.2}   {} ({}-{})",
+                phrase.score, phrase.text, phrase.begin_offset, phrase.end_offset
+            )?;
+        }
+
+        write!(f, "\nFound {} key phrases.", self.key_phrases.len())
+    }
+}
+
+/// Finds the key phrases in text, the noun phrases that say what it's about, with Amazon Comprehend,
+/// and displays each with its score and where it is in the text.
+/// # Arguments
+///
+/// * `[-f FILE]` - The file holding the text, up to 5,000 bytes.
+///   If not supplied, or **-**, the text is read from stdin.
+/// * `[-l LANGUAGE]` - The language of the text. Defaults to **en**.
+/// * `[-m MIN-SCORE]` - Only display the key phrases with at least this score, from 0 through 1.
+/// * `[-d DEFAULT-REGION]` - The region in which the client is created.
+///   If not supplied, uses the value of the **AWS_DEFAULT_REGION** environment variable.
+///   If the environment variable is not set, defaults to **us-west-2**.
+/// * `[--output FORMAT]` - How to display the result, **text** (the default) or **json**.
+/// * `[--profile PROFILE]` - The AWS profile to use from the shared credentials file.
+/// * `[-v]` - Whether to display additional information.
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn Error>> {
+    let Opt {
+        default_region,
+        file,
+        language,
+        min_score,
+        output,
+        profile,
+        verbose,
+    } = Opt::from_args();
+
+    let region = default_region
+        .as_ref()
+        .map(|region| Region::new(region.clone()))
+        .or_else(|| aws_types::region::default_provider().region())
+        .unwrap_or_else(|| Region::new("us-west-2"));
+
+    let text = read_text(file.as_deref())?;
+
+    if verbose {
+        println!("Comprehend client version: {}", comprehend::PKG_VERSION);
+        println!("Region:                    {:?}", &region);
+        println!("File:                      {:?}", &file);
+        println!("Language:                  {}", &language);
+        println!("Minimum score:             {}", min_score);
+        println!("Text:                      {} bytes", text.len());
+        println!();
+
+        SubscriberBuilder::default()
+            .with_env_filter("info")
+            .with_span_events(FmtSpan::CLOSE)
+            .init();
+    }
+
+    let mut config = Config::builder().region(region);
+    if let Some(profile) = profile {
+        config = config.credentials_provider(ProfileCredentialsProvider::new(profile));
+    }
+    let client = Client::from_conf(config.build());
+
+    let resp = client
+        .detect_key_phrases()
+        .text(text)
+        .language_code(LanguageCode::from(language.as_str()))
+        .send()
+        .await?;
+
+    let key_phrases = resp
+        .key_phrases
+        .unwrap_or_default()
+        .into_iter()
+        .map(|phrase| KeyPhrase {
+            text: phrase.text.unwrap_or_default(),
+            score: phrase.score.unwrap_or_default(),
+            begin_offset: phrase.begin_offset.unwrap_or_default(),
+            end_offset: phrase.end_offset.unwrap_or_default(),
+        })
+        .filter(|phrase| phrase.score >= min_score)
+        .collect();
+
+    output.print(&KeyPhrases { key_phrases });
+
+    Ok(())
+}
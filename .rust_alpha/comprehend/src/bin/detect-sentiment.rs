@@ -0,0 +1,144 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0.
+ */
+
+use std::error::Error;
+use std::fmt;
+
+use comprehend::model::{LanguageCode, SentimentScore};
+use comprehend::{Client, Config, Region};
+use comprehend_code_examples::text::read_text;
+
+use aws_types::region::ProvideRegion;
+
+use common::output::OutputFormat;
+use common::profile::ProfileCredentialsProvider;
+use serde::Serialize;
+use structopt::StructOpt;
+use tracing_subscriber::fmt::format::FmtSpan;
+use tracing_subscriber::fmt::SubscriberBuilder;
+
+#[derive(Debug, StructOpt)]
+struct Opt {
+    /// The default region
+    #[structopt(short, long)]
+    default_region: Option<String>,
+
+    /// The file holding the text, or - to read it from stdin
+    #[structopt(short, long)]
+    file: Option<String>,
+
+    /// The language of the text, such as en
+    #[structopt(short, long, default_value = "en")]
+    language: String,
+
+    /// The format of the output, text or json
+    #[structopt(long, default_value = "text")]
+    output: OutputFormat,
+
+    /// The AWS profile to use from the shared credentials file
+    #[structopt(long)]
+    profile: Option<String>,
+
+    /// Whether to display additional information
+    #[structopt(short, long)]
+    verbose: bool,
+}
+
+/// The overall sentiment of the text, and how confident Amazon Comprehend is in each sentiment.
+#[derive(Debug, Serialize)]
+struct Sentiment {
+    sentiment: String,
+    positive: f32,
+    negative: f32,
+    neutral: f32,
+    mixed: f32,
+}
+
+impl fmt::Display for Sentiment {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "Sentiment: {}", self.sentiment)?;
+        writeln!(f, "  Positive: {:.2}", self.positive)?;
+        writeln!(f, "  Negative: {:.2}", self.negative)?;
+        writeln!(f, "  Neutral:  {:.2}", self.neutral)?;
+        write!(f, "  Mixed:    {:.2}", self.mixed)
+    }
+}
+
+/// Detects whether text is positive, negative, neutral, or mixed, with Amazon Comprehend,
+/// and displays the sentiment and the score of each.
+/// # Arguments
+///
+/// * `[-f FILE]` - The file holding the text, up to 5,000 bytes.
+///   If not supplied, or **-**, the text is read from stdin.
+/// * `[-l LANGUAGE]` - The language of the text. Defaults to **en**.
+/// * `[-d DEFAULT-REGION]` - The region in which the client is created.
+///   If not supplied, uses the value of the **AWS_DEFAULT_REGION** environment variable.
+///   If the environment variable is not set, defaults to **us-west-2**.
+/// * `[--output FORMAT]` - How to display the result, **text** (the default) or **json**.
+/// * `[--profile PROFILE]` - The AWS profile to use from the shared credentials file.
+/// * `[-v]` - Whether to display additional information.
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn Error>> {
+    let Opt {
+        default_region,
+        file,
+        language,
+        output,
+        profile,
+        verbose,
+    } = Opt::from_args();
+
+    let region = default_region
+        .as_ref()
+        .map(|region| Region::new(region.clone()))
+        .or_else(|| aws_types::region::default_provider().region())
+        .unwrap_or_else(|| Region::new("us-west-2"));
+
+    let text = read_text(file.as_deref())?;
+
+    if verbose {
+        println!("Comprehend client version: {}", comprehend::PKG_VERSION);
+        println!("Region:                    {:?}", &region);
+        println!("File:                      {:?}", &file);
+        println!("Language:                  {}", &language);
+        println!("Text:                      {} bytes", text.len());
+        println!();
+
+        SubscriberBuilder::default()
+            .with_env_filter("info")
+            .with_span_events(FmtSpan::CLOSE)
+            .init();
+    }
+
+    let mut config = Config::builder().region(region);
+    if let Some(profile) = profile {
+        config = config.credentials_provider(ProfileCredentialsProvider::new(profile));
+    }
+    let client = Client::from_conf(config.build());
+
+    let resp = client
+        .detect_sentiment()
+        .text(text)
+        .language_code(LanguageCode::from(language.as_str()))
+        .send()
+        .await?;
+
+    let score = resp
+        .sentiment_score
+        .unwrap_or_else(|| SentimentScore::builder().build());
+
+    output.print(&Sentiment {
+        sentiment: resp
+            .sentiment
+            .map(|sentiment| sentiment.as_str().to_string())
+            .unwrap_or_default(),
+        positive: score.positive.unwrap_or_default(),
+        negative: score.negative.unwrap_or_default(),
+        neutral: score.neutral.unwrap_or_default(),
+        mixed: score.mixed.unwrap_or_default(),
+    });
+
+    Ok(())
+}
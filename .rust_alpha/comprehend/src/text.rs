@@ -0,0 +1,89 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0.
+ */
+
+//! Reads the text to analyze, and checks that Amazon Comprehend accepts it.
+//!
+//! Each example reads the text from a file, or from stdin when no file is given,
+//! so that the output of another command can be piped into it:
+//!
+//! ```ignore
+//! let text = read_text(file.as_deref())?;
+//! ```
+//!
+//! The detect operations take at most 5,000 bytes of UTF-8 text. A longer text is rejected
+//! before it's sent, rather than being reported as a bare validation error.
+
+use std::error::Error;
+use std::fmt;
+use std::fs;
+use std::io::{self, Read};
+
+/// The most bytes of UTF-8 text that a detect operation accepts.
+pub const MAX_BYTES: usize = 5_000;
+
+/// Why the text can't be analyzed.
+#[derive(Debug)]
+pub enum TextError {
+    /// The file or stdin couldn't be read.
+    Io(io::Error),
+    /// There's no text, only whitespace.
+    Empty,
+    /// The text is longer than `MAX_BYTES`. Holds its length in bytes.
+    TooLong(usize),
+}
+
+impl fmt::Display for TextError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TextError::Io(e) => write!(f, "{}", e),
+            TextError::Empty => write!(f, "there's no text to analyze"),
+            TextError::TooLong(len) => write!(
+                f,
+                "the text is {} bytes; Amazon Comprehend analyzes at most {} bytes at a time",
+                len, MAX_BYTES
+            ),
+        }
+    }
+}
+
+impl Error for TextError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            TextError::Io(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+impl From<io::Error> for TextError {
+    fn from(e: io::Error) -> Self {
+        TextError::Io(e)
+    }
+}
+
+/// Reads the text from the file, or from stdin if there's no file or it's `-`,
+/// and checks that it isn't empty or too long.
+pub fn read_text(file: Option<&str>) -> Result<String, TextError> {
+    let text = match file {
+        Some(file) if file != "-" => fs::read_to_string(file)?,
+        _ => {
+            let mut text = String::new();
+            io::stdin().read_to_string(&mut text)?;
+            text
+        }
+    };
+
+    let text = text.trim();
+
+    if text.is_empty() {
+        return Err(TextError::Empty);
+    }
+
+    if text.len() > MAX_BYTES {
+        return Err(TextError::TooLong(text.len()));
+    }
+
+    Ok(text.to_string())
+}
@@ -0,0 +1,121 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0.
+ */
+
+//! Writes items in batches with `BatchWriteItem`.
+//!
+//! `BatchWriteItem` accepts up to 25 items in one call. Rather than failing when the table's
+//! write capacity is exceeded, it writes what it can and returns the rest as unprocessed items,
+//! which are lost unless they're sent again. Examples that load many items split them into
+//! batches and let `write_batch` resend the unprocessed items:
+//!
+//! ```ignore
+//! for chunk in items.chunks(BATCH_SIZE) {
+//!     retries += write_batch(&client, table, chunk.to_vec()).await?;
+//! }
+//! ```
+
+use std::collections::HashMap;
+use std::error::Error;
+use std::fmt;
+use std::time::Duration;
+
+use dynamodb::model::{AttributeValue, PutRequest, WriteRequest};
+use dynamodb::Client;
+use tokio::time::sleep;
+
+/// The most items that BatchWriteItem accepts in one call.
+pub const BATCH_SIZE: usize = 25;
+
+/// How many times to resend the items that DynamoDB didn't process.
+const MAX_RETRIES: u32 = 6;
+
+/// How long to wait before the first retry. The delay doubles after each retry.
+const INITIAL_DELAY: Duration = Duration::from_millis(100);
+
+/// The longest delay between two retries.
+const MAX_DELAY: Duration = Duration::from_secs(5);
+
+/// Why writing a batch failed.
+#[derive(Debug)]
+pub enum BatchError {
+    /// BatchWriteItem failed.
+    Sdk(dynamodb::Error),
+    /// Some items were still unprocessed after the last retry. Holds how many.
+    Unprocessed(usize),
+}
+
+impl fmt::Display for BatchError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            BatchError::Sdk(e) => write!(f, "{}", e),
+            BatchError::Unprocessed(count) => write!(
+                f,
+                "{} items were still unprocessed after {} retries",
+                count, MAX_RETRIES
+            ),
+        }
+    }
+}
+
+impl Error for BatchError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            BatchError::Sdk(e) => Some(e),
+            BatchError::Unprocessed(_) => None,
+        }
+    }
+}
+
+impl From<dynamodb::Error> for BatchError {
+    fn from(e: dynamodb::Error) -> Self {
+        BatchError::Sdk(e)
+    }
+}
+
+/// Writes a batch of up to `BATCH_SIZE` items, resending any that DynamoDB didn't process,
+/// with exponential backoff between the attempts.
+/// Returns how many times the unprocessed items were resent.
+pub async fn write_batch(
+    client: &Client,
+    table: &str,
+    items: Vec<HashMap<String, AttributeValue>>,
+) -> Result<u32, BatchError> {
+    let mut requests: Vec<WriteRequest> = items
+        .into_iter()
+        .map(|item| {
+            WriteRequest::builder()
+                .put_request(PutRequest::builder().set_item(Some(item)).build())
+                .build()
+        })
+        .collect();
+
+    let mut delay = INITIAL_DELAY;
+
+    for attempt in 0..=MAX_RETRIES {
+        if attempt > 0 {
+            sleep(delay).await;
+            delay = (delay * 2).min(MAX_DELAY);
+        }
+
+        let mut request_items = HashMap::new();
+        request_items.insert(table.to_string(), requests);
+
+        requests = client
+            .batch_write_item()
+            .set_request_items(Some(request_items))
+            .send()
+            .await
+            .map_err(dynamodb::Error::from)?
+            .unprocessed_items
+            .and_then(|mut unprocessed| unprocessed.remove(table))
+            .unwrap_or_default();
+
+        if requests.is_empty() {
+            return Ok(attempt);
+        }
+    }
+
+    Err(BatchError::Unprocessed(requests.len()))
+}
@@ -3,13 +3,18 @@
  * SPDX-License-Identifier: Apache-2.0.
  */
 
-use std::process;
+use std::error::Error;
+use std::fmt;
 
-use dynamodb::model::AttributeValue;
-use dynamodb::{Client, Config, Region};
+use dynamodb::{Client, Config, Endpoint, Region};
+use dynamodb_code_examples::items::to_item;
 
 use aws_types::region::{EnvironmentProvider, ProvideRegion};
 
+use common::endpoint;
+use common::output::OutputFormat;
+use common::profile::ProfileCredentialsProvider;
+use serde::Serialize;
 use structopt::StructOpt;
 use tracing_subscriber::fmt::format::FmtSpan;
 use tracing_subscriber::fmt::SubscriberBuilder;
@@ -44,11 +49,48 @@ struct Opt {
     #[structopt(short, long)]
     region: Option<String>,
 
+    /// The format of the output, text or json
+    #[structopt(long, default_value = "text")]
+    output: OutputFormat,
+
+    /// The AWS profile to use from the shared credentials file
+    #[structopt(long)]
+    profile: Option<String>,
+
     /// Activate verbose mode
     #[structopt(short, long)]
     verbose: bool,
 }
 
+/// A user, as it's stored in the table. Each field is an attribute of the item.
+#[derive(Debug, Serialize)]
+struct User {
+    username: String,
+    account_type: String,
+    age: String,
+    first_name: String,
+    last_name: String,
+}
+
+/// The user that was added to the table.
+#[derive(Debug, Serialize)]
+struct AddedItem {
+    table: String,
+    #[serde(flatten)]
+    user: User,
+}
+
+impl fmt::Display for AddedItem {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let user = &self.user;
+        write!(
+            f,
+            "Added user {}, {} {}, age {} as {} user",
+            user.username, user.first_name, user.last_name, user.age, user.account_type
+        )
+    }
+}
+
 /// Adds an item to an Amazon DynamoDB table.
 /// The table schema must use one of username, p_type, age, first, or last as the primary key.
 /// # Arguments
@@ -62,25 +104,29 @@ struct Opt {
 /// * `[-r REGION]` - The region in which the table is created.
 ///   If not supplied, uses the value of the **AWS_DEFAULT_REGION** environment variable.
 ///   If the environment variable is not set, defaults to **us-west-2**.
+/// * `[--output FORMAT]` - How to display the result, **text** (the default) or **json**.
+/// * `[--profile PROFILE]` - The AWS profile to use from the shared credentials file.
 /// * `[-v]` - Whether to display additional information.
 #[tokio::main]
-async fn main() {
+async fn main() -> Result<(), Box<dyn Error>> {
     let Opt {
+        profile,
         table,
         username,
         p_type,
         age,
         first,
         last,
+        output,
         region,
         verbose,
     } = Opt::from_args();
 
     if p_type != "standard_user" && p_type != "admin" {
-        println!("\n{} is not a valid permission type", p_type);
-        println!("You must specify a permission type value of 'admin' or 'standard_user':");
-        println!("-p PERMISSION-TYPE\n");
-        process::exit(1);
+        eprintln!("\n{} is not a valid permission type", p_type);
+        eprintln!("You must specify a permission type value of 'admin' or 'standard_user':");
+        eprintln!("-p PERMISSION-TYPE\n");
+        return Err(format!("invalid permission type {}", p_type).into());
     }
 
     let region = EnvironmentProvider::new()
@@ -104,36 +150,38 @@ async fn main() {
             .init();
     }
 
-    let config = Config::builder().region(region).build();
+    let mut config = Config::builder().region(region);
+    if let Some(profile) = profile {
+        config = config.credentials_provider(ProfileCredentialsProvider::new(profile));
+    }
+
+    // Sends the requests to DynamoDB Local or LocalStack if AWS_ENDPOINT_URL is set.
+    if let Some(uri) = endpoint::from_env()? {
+        config = config.endpoint_resolver(Endpoint::immutable(uri));
+    }
 
-    let client = Client::from_conf(config);
+    let client = Client::from_conf(config.build());
 
-    let user_av = AttributeValue::S(String::from(&username));
-    let type_av = AttributeValue::S(String::from(&p_type));
-    let age_av = AttributeValue::S(String::from(&age));
-    let first_av = AttributeValue::S(String::from(&first));
-    let last_av = AttributeValue::S(String::from(&last));
+    let user = User {
+        username,
+        account_type: p_type,
+        age,
+        first_name: first,
+        last_name: last,
+    };
 
     let request = client
         .put_item()
-        .table_name(table)
-        .item("username", user_av)
-        .item("account_type", type_av)
-        .item("age", age_av)
-        .item("first_name", first_av)
-        .item("last_name", last_av);
+        .table_name(&table)
+        .set_item(Some(to_item(&user)?));
 
-    println!("Executing request [{:?}] to add item...", request);
+    if verbose {
+        println!("Executing request [{:?}] to add item...", request);
+    }
 
-    match request.send().await {
-        Ok(_) => println!(
-            "Added user {}, {} {}, age {} as {} user",
-            username, first, last, age, p_type
-        ),
-        Err(e) => {
-            println!("Got an error adding item:");
-            println!("{}", e);
-            process::exit(1);
-        }
-    };
+    request.send().await?;
+
+    output.print(&AddedItem { table, user });
+
+    Ok(())
 }
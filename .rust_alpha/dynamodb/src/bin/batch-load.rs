@@ -0,0 +1,252 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0.
+ */
+
+use std::collections::HashMap;
+use std::error::Error;
+use std::fmt;
+use std::fs;
+use std::path::Path;
+use std::str::FromStr;
+
+use dynamodb::model::AttributeValue;
+use dynamodb::{Client, Config, Endpoint, Region};
+use dynamodb_code_examples::batch::{write_batch, BATCH_SIZE};
+use dynamodb_code_examples::items::to_attribute;
+
+use aws_types::region::{EnvironmentProvider, ProvideRegion};
+
+use common::endpoint;
+use common::output::OutputFormat;
+use common::profile::ProfileCredentialsProvider;
+use serde::Serialize;
+use serde_json::Value;
+use structopt::StructOpt;
+use tracing_subscriber::fmt::format::FmtSpan;
+use tracing_subscriber::fmt::SubscriberBuilder;
+
+/// The format of the file to load.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Format {
+    Json,
+    Csv,
+}
+
+impl FromStr for Format {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "json" => Ok(Format::Json),
+            "csv" => Ok(Format::Csv),
+            _ => Err(format!("the format must be json or csv, got {}", s)),
+        }
+    }
+}
+
+#[derive(Debug, StructOpt)]
+struct Opt {
+    /// The region
+    #[structopt(short, long)]
+    region: Option<String>,
+
+    /// The name of the table
+    #[structopt(short, long)]
+    table: String,
+
+    /// The file to load, a JSON array of objects or a CSV file with a header row
+    #[structopt(short, long)]
+    file: String,
+
+    /// The format of the file, json or csv. Defaults to the file's extension
+    #[structopt(long)]
+    format: Option<Format>,
+
+    /// The format of the output, text or json
+    #[structopt(long, default_value = "text")]
+    output: OutputFormat,
+
+    /// The AWS profile to use from the shared credentials file
+    #[structopt(long)]
+    profile: Option<String>,
+
+    /// Whether to display additional information
+    #[structopt(short, long)]
+    verbose: bool,
+}
+
+/// The items that were loaded.
+#[derive(Debug, Serialize)]
+struct LoadedItems {
+    table: String,
+    file: String,
+    items: usize,
+    batches: usize,
+    retries: u32,
+}
+
+impl fmt::Display for LoadedItems {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "Loaded {} items from {} into table {} in {} batches",
+            self.items, self.file, self.table, self.batches
+        )?;
+
+        if self.retries > 0 {
+            write!(f, ", resending unprocessed items {} times", self.retries)?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Converts a CSV field into an attribute value.
+/// Fields that look like numbers are stored as numbers; everything else is stored as a string.
+fn field_to_attribute(field: &str) -> AttributeValue {
+    if field.parse::<f64>().map_or(false, |n| n.is_finite()) {
+        AttributeValue::N(field.to_string())
+    } else {
+        AttributeValue::S(field.to_string())
+    }
+}
+
+/// Reads the items from a JSON array of objects.
+fn read_json(data: &str) -> Result<Vec<HashMap<String, AttributeValue>>, Box<dyn Error>> {
+    let rows = match serde_json::from_str(data)? {
+        Value::Array(rows) => rows,
+        _ => return Err("the JSON file must contain an array of objects".into()),
+    };
+
+    rows.into_iter()
+        .enumerate()
+        .map(|(i, row)| match to_attribute(row) {
+            AttributeValue::M(item) => Ok(item),
+            other => Err(format!("element {} is not an object: {:?}", i, other).into()),
+        })
+        .collect()
+}
+
+/// Reads the items from a CSV file, whose first row holds the attribute names.
+/// Empty fields are left out, because DynamoDB doesn't store empty key attributes.
+fn read_csv(data: &str) -> Result<Vec<HashMap<String, AttributeValue>>, Box<dyn Error>> {
+    let mut reader = csv::Reader::from_reader(data.as_bytes());
+    let headers: Vec<String> = reader.headers()?.iter().map(String::from).collect();
+
+    let mut items = Vec::new();
+
+    for record in reader.records() {
+        let record = record?;
+
+        items.push(
+            headers
+                .iter()
+                .zip(record.iter())
+                .filter(|(_, field)| !field.is_empty())
+                .map(|(name, field)| (name.clone(), field_to_attribute(field)))
+                .collect(),
+        );
+    }
+
+    Ok(items)
+}
+
+/// Loads the items in a JSON or CSV file into an Amazon DynamoDB table with BatchWriteItem.
+/// Each row becomes an item, and must include the table's key attributes.
+/// # Arguments
+///
+/// * `-t TABLE` - The name of the table.
+/// * `-f FILE` - The file to load.
+///   A JSON file must contain an array of objects. A CSV file must have a header row with the attribute names.
+/// * `[--format FORMAT]` - The format of the file, **json** or **csv**.
+///   If not supplied, uses the file's extension.
+/// * `[-r REGION]` - The region in which the table is located.
+///   If not supplied, uses the value of the **AWS_DEFAULT_REGION** environment variable.
+///   If the environment variable is not set, defaults to **us-west-2**.
+/// * `[--output FORMAT]` - How to display the result, **text** (the default) or **json**.
+/// * `[--profile PROFILE]` - The AWS profile to use from the shared credentials file.
+/// * `[-v]` - Whether to display additional information.
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn Error>> {
+    let Opt {
+        file,
+        format,
+        output,
+        profile,
+        region,
+        table,
+        verbose,
+    } = Opt::from_args();
+
+    let region = EnvironmentProvider::new()
+        .region()
+        .or_else(|| region.as_ref().map(|region| Region::new(region.clone())))
+        .unwrap_or_else(|| Region::new("us-west-2"));
+
+    let format = match format {
+        Some(format) => format,
+        None => Path::new(&file)
+            .extension()
+            .and_then(|ext| ext.to_str())
+            .ok_or("the file has no extension, so supply --format")?
+            .parse()?,
+    };
+
+    let data = fs::read_to_string(&file)?;
+    let items = match format {
+        Format::Json => read_json(&data)?,
+        Format::Csv => read_csv(&data)?,
+    };
+
+    if verbose {
+        println!("DynamoDB client version: {}", dynamodb::PKG_VERSION);
+        println!("Region:                  {:?}", &region);
+        println!("Table:                   {}", &table);
+        println!("File:                    {}", &file);
+        println!("Format:                  {:?}", format);
+        println!("Items:                   {}", items.len());
+
+        SubscriberBuilder::default()
+            .with_env_filter("info")
+            .with_span_events(FmtSpan::CLOSE)
+            .init();
+    }
+
+    let mut config = Config::builder().region(region);
+    if let Some(profile) = profile {
+        config = config.credentials_provider(ProfileCredentialsProvider::new(profile));
+    }
+
+    // Sends the requests to DynamoDB Local or LocalStack if AWS_ENDPOINT_URL is set.
+    if let Some(uri) = endpoint::from_env()? {
+        config = config.endpoint_resolver(Endpoint::immutable(uri));
+    }
+
+    let client = Client::from_conf(config.build());
+
+    let count = items.len();
+    let mut batches = 0;
+    let mut retries = 0;
+    let mut items = items.into_iter().peekable();
+
+    while items.peek().is_some() {
+        let batch: Vec<_> = items.by_ref().take(BATCH_SIZE).collect();
+        retries += write_batch(&client, &table, batch).await?;
+        batches += 1;
+
+        if verbose {
+            println!("Wrote batch {}", batches);
+        }
+    }
+
+    output.print(&LoadedItems {
+        table,
+        file,
+        items: count,
+        batches,
+        retries,
+    });
+
+    Ok(())
+}
@@ -0,0 +1,311 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0.
+ */
+
+use std::collections::HashMap;
+use std::error::Error;
+use std::fmt;
+use std::time::{Duration, Instant};
+
+use dynamodb::model::{AttributeValue, ReturnConsumedCapacity, ScalarAttributeType};
+use dynamodb::{Client, Config, Endpoint, Region};
+
+use aws_types::region::{EnvironmentProvider, ProvideRegion};
+
+use common::endpoint;
+use common::output::OutputFormat;
+use common::profile::ProfileCredentialsProvider;
+use serde::Serialize;
+use structopt::StructOpt;
+use tracing_subscriber::fmt::format::FmtSpan;
+use tracing_subscriber::fmt::SubscriberBuilder;
+
+/// The value of each key attribute of the item that the example writes, if it's a string.
+const KEY_VALUE: &str = "rust-consistency-example";
+
+/// The attribute that holds the number of the write, so that a read can tell whether it saw the latest one.
+const VERSION_ATTRIBUTE: &str = "consistency_version";
+
+#[derive(Debug, StructOpt)]
+struct Opt {
+    /// The table name
+    #[structopt(short, long)]
+    table: String,
+
+    /// How many times to write the item and read it back
+    #[structopt(short, long, default_value = "20")]
+    iterations: u32,
+
+    /// The region
+    #[structopt(short, long)]
+    region: Option<String>,
+
+    /// The format of the output, text or json
+    #[structopt(long, default_value = "text")]
+    output: OutputFormat,
+
+    /// The AWS profile to use from the shared credentials file
+    #[structopt(long)]
+    profile: Option<String>,
+
+    /// Activate verbose mode
+    #[structopt(short, long)]
+    verbose: bool,
+}
+
+/// What the reads of one kind saw.
+#[derive(Debug, Default, Serialize)]
+struct Reads {
+    /// How many reads returned the item as it was written.
+    current: u32,
+    /// How many reads returned an earlier version of the item, or no item.
+    stale: u32,
+    average_millis: f64,
+    /// The read capacity units that each read consumed.
+    capacity_units: f64,
+    #[serde(skip)]
+    total: Duration,
+}
+
+impl Reads {
+    /// Records a read, and whether it saw the version that was just written.
+    fn record(&mut self, current: bool, elapsed: Duration, capacity_units: Option<f64>) {
+        if current {
+            self.current += 1;
+        } else {
+            self.stale += 1;
+        }
+
+        self.total += elapsed;
+        self.average_millis =
+            self.total.as_secs_f64() * 1000.0 / f64::from(self.current + self.stale);
+        self.capacity_units = capacity_units.unwrap_or_default();
+    }
+}
+
+/// What the reads after each write saw.
+#[derive(Debug, Serialize)]
+struct Consistency {
+    table: String,
+    iterations: u32,
+    eventually_consistent: Reads,
+    strongly_consistent: Reads,
+}
+
+impl fmt::Display for Consistency {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "Wrote an item to table {} {} times, and read it back right after each write",
+            self.table, self.iterations
+        )?;
+
+        for (label, reads) in &[
+            ("Eventually consistent", &self.eventually_consistent),
+            ("Strongly consistent", &self.strongly_consistent),
+        ] {
+            write!(
+                f,
+                "\n{} reads: {} current, {} stale, {:.1} ms on average, {} read capacity units each",
+                label, reads.current, reads.stale, reads.average_millis, reads.capacity_units
+            )?;
+        }
+
+        if self.eventually_consistent.stale == 0 {
+            write!(
+                f,
+                "\nNo eventually consistent read was stale this time. \
+                 They usually aren't, but a program can't count on that"
+            )?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Returns the key of the item that the example writes, which has a value for each key attribute of the table.
+/// String attributes get a fixed value, and number attributes get 0.
+async fn demo_key(
+    client: &Client,
+    table: &str,
+) -> Result<HashMap<String, AttributeValue>, Box<dyn Error>> {
+    let description = client
+        .describe_table()
+        .table_name(table)
+        .send()
+        .await?
+        .table
+        .ok_or("DynamoDB did not return the table description")?;
+
+    let definitions = description.attribute_definitions.unwrap_or_default();
+    let mut key = HashMap::new();
+
+    for element in description.key_schema.unwrap_or_default() {
+        let name = element.attribute_name.unwrap_or_default();
+        let attribute_type = definitions
+            .iter()
+            .find(|definition| definition.attribute_name.as_deref() == Some(name.as_str()))
+            .and_then(|definition| definition.attribute_type.clone());
+
+        let value = match attribute_type {
+            Some(ScalarAttributeType::S) => AttributeValue::S(KEY_VALUE.to_string()),
+            Some(ScalarAttributeType::N) => AttributeValue::N("0".to_string()),
+            _ => {
+                return Err(format!(
+                    "key attribute {} of table {} must be a string or a number",
+                    name, table
+                )
+                .into())
+            }
+        };
+
+        key.insert(name, value);
+    }
+
+    Ok(key)
+}
+
+/// Reads the item, and returns whether it has the version that was just written,
+/// and how many read capacity units the read consumed.
+async fn read(
+    client: &Client,
+    table: &str,
+    key: &HashMap<String, AttributeValue>,
+    version: u32,
+    consistent: bool,
+) -> Result<(bool, Option<f64>), dynamodb::Error> {
+    let resp = client
+        .get_item()
+        .table_name(table)
+        .set_key(Some(key.clone()))
+        .consistent_read(consistent)
+        .return_consumed_capacity(ReturnConsumedCapacity::Total)
+        .send()
+        .await?;
+
+    let current = resp
+        .item
+        .as_ref()
+        .and_then(|item| item.get(VERSION_ATTRIBUTE))
+        .map_or(false, |value| {
+            *value == AttributeValue::N(version.to_string())
+        });
+
+    Ok((
+        current,
+        resp.consumed_capacity
+            .and_then(|capacity| capacity.capacity_units),
+    ))
+}
+
+/// Shows the difference between eventually consistent and strongly consistent reads.
+/// Writes an item to an Amazon DynamoDB table, then reads it back straight away both ways, and repeats.
+/// An eventually consistent read can return the item as it was before the write, but costs half as much.
+/// A strongly consistent read always returns the latest write.
+/// The item is deleted at the end. Every key attribute of the table must be a string or a number.
+/// # Arguments
+///
+/// * `-t TABLE` - The name of the table.
+/// * `[-i ITERATIONS]` - How many times to write the item and read it back.
+///   If not supplied, defaults to **20**.
+/// * `[-r REGION]` - The region in which the table is created.
+///   If not supplied, uses the value of the **AWS_DEFAULT_REGION** environment variable.
+///   If the environment variable is not set, defaults to **us-west-2**.
+/// * `[--output FORMAT]` - How to display the result, **text** (the default) or **json**.
+/// * `[--profile PROFILE]` - The AWS profile to use from the shared credentials file.
+/// * `[-v]` - Whether to display additional information.
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn Error>> {
+    let Opt {
+        iterations,
+        output,
+        profile,
+        region,
+        table,
+        verbose,
+    } = Opt::from_args();
+
+    let region = EnvironmentProvider::new()
+        .region()
+        .or_else(|| region.as_ref().map(|region| Region::new(region.clone())))
+        .unwrap_or_else(|| Region::new("us-west-2"));
+
+    if verbose {
+        println!("DynamoDB client version: {}\n", dynamodb::PKG_VERSION);
+        println!("Region:     {:?}", &region);
+        println!("Table:      {}", &table);
+        println!("Iterations: {}\n", iterations);
+
+        SubscriberBuilder::default()
+            .with_env_filter("info")
+            .with_span_events(FmtSpan::CLOSE)
+            .init();
+    }
+
+    let mut config = Config::builder().region(region);
+    if let Some(profile) = profile {
+        config = config.credentials_provider(ProfileCredentialsProvider::new(profile));
+    }
+
+    // Sends the requests to DynamoDB Local or LocalStack if AWS_ENDPOINT_URL is set.
+    if let Some(uri) = endpoint::from_env()? {
+        config = config.endpoint_resolver(Endpoint::immutable(uri));
+    }
+
+    let client = Client::from_conf(config.build());
+
+    let key = demo_key(&client, &table).await?;
+
+    let mut eventually_consistent = Reads::default();
+    let mut strongly_consistent = Reads::default();
+
+    for version in 1..=iterations {
+        let mut item = key.clone();
+        item.insert(
+            VERSION_ATTRIBUTE.to_string(),
+            AttributeValue::N(version.to_string()),
+        );
+
+        client
+            .put_item()
+            .table_name(&table)
+            .set_item(Some(item))
+            .send()
+            .await?;
+
+        // The eventually consistent read goes first, while the write is most likely still being copied.
+        let start = Instant::now();
+        let (current, units) = read(&client, &table, &key, version, false).await?;
+        eventually_consistent.record(current, start.elapsed(), units);
+
+        let start = Instant::now();
+        let (strong, strong_units) = read(&client, &table, &key, version, true).await?;
+        strongly_consistent.record(strong, start.elapsed(), strong_units);
+
+        if verbose {
+            println!(
+                "Write {}: eventually consistent read was {}, strongly consistent read was {}",
+                version,
+                if current { "current" } else { "stale" },
+                if strong { "current" } else { "stale" }
+            );
+        }
+    }
+
+    client
+        .delete_item()
+        .table_name(&table)
+        .set_key(Some(key))
+        .send()
+        .await?;
+
+    output.print(&Consistency {
+        table,
+        iterations,
+        eventually_consistent,
+        strongly_consistent,
+    });
+
+    Ok(())
+}
@@ -0,0 +1,315 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0.
+ */
+
+use std::error::Error;
+use std::fmt;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use dynamodb::model::TableDescription;
+use dynamodb::{Client, Config, Endpoint, Region};
+use dynamodb_code_examples::batch::{write_batch, BatchError, BATCH_SIZE};
+
+use aws_types::region::{EnvironmentProvider, ProvideRegion};
+
+use common::endpoint;
+use common::output::OutputFormat;
+use common::profile::ProfileCredentialsProvider;
+use common::shutdown::Shutdown;
+use indicatif::{ProgressBar, ProgressStyle};
+use serde::Serialize;
+use structopt::StructOpt;
+use tokio::time::sleep;
+use tracing_subscriber::fmt::format::FmtSpan;
+use tracing_subscriber::fmt::SubscriberBuilder;
+
+#[derive(Debug, StructOpt)]
+struct Opt {
+    /// The region
+    #[structopt(short, long)]
+    region: Option<String>,
+
+    /// The name of the table to copy
+    #[structopt(short, long)]
+    source: String,
+
+    /// The name of the table to copy the items into, which must have the same key schema
+    #[structopt(short, long)]
+    target: String,
+
+    /// The region of the target table. Defaults to the region of the source table
+    #[structopt(long)]
+    target_region: Option<String>,
+
+    /// How many segments to scan the source table in, in parallel
+    #[structopt(long, default_value = "4")]
+    segments: i32,
+
+    /// The most items to write each second, across all segments. Defaults to no limit
+    #[structopt(long)]
+    rate: Option<f64>,
+
+    /// The format of the output, text or json
+    #[structopt(long, default_value = "text")]
+    output: OutputFormat,
+
+    /// The AWS profile to use from the shared credentials file
+    #[structopt(long)]
+    profile: Option<String>,
+
+    /// Whether to display additional information
+    #[structopt(short, long)]
+    verbose: bool,
+}
+
+/// The items that were copied.
+#[derive(Debug, Serialize)]
+struct CopiedTable {
+    source: String,
+    target: String,
+    target_region: String,
+    items: usize,
+    segments: i32,
+    retries: u32,
+    seconds: f64,
+    interrupted: bool,
+}
+
+impl fmt::Display for CopiedTable {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "Copied {} items from {} to {} in {} in {:.1} seconds, using {} segments",
+            self.items, self.source, self.target, self.target_region, self.seconds, self.segments
+        )?;
+
+        if self.retries > 0 {
+            write!(f, "\nResent unprocessed items {} times", self.retries)?;
+        }
+
+        if self.interrupted {
+            write!(f, "\nStopped by Ctrl-C before every item was copied")?;
+        }
+
+        Ok(())
+    }
+}
+
+/// What one segment's scan needs, shared by all of the segments.
+struct CopyJob {
+    source_client: Client,
+    target_client: Client,
+    source: String,
+    target: String,
+    segments: i32,
+    /// The most items that each segment writes per second.
+    rate: Option<f64>,
+    progress: ProgressBar,
+    shutdown: Shutdown,
+}
+
+/// Scans one segment of the source table, a page at a time, and writes each page to the target table.
+/// Returns how many items were copied, and how many times unprocessed items were resent.
+async fn copy_segment(copy: Arc<CopyJob>, segment: i32) -> Result<(usize, u32), BatchError> {
+    let start = Instant::now();
+    let mut copied = 0;
+    let mut retries = 0;
+    let mut start_key = None;
+
+    loop {
+        let resp = copy
+            .source_client
+            .scan()
+            .table_name(&copy.source)
+            .segment(segment)
+            .total_segments(copy.segments)
+            .set_exclusive_start_key(start_key)
+            .send()
+            .await
+            .map_err(dynamodb::Error::from)?;
+
+        for chunk in resp.items.unwrap_or_default().chunks(BATCH_SIZE) {
+            retries += write_batch(&copy.target_client, &copy.target, chunk.to_vec()).await?;
+            copied += chunk.len();
+            copy.progress.inc(chunk.len() as u64);
+
+            // Throttles by sleeping until the segment's average rate is back under its share of the limit.
+            if let Some(rate) = copy.rate {
+                let due = Duration::from_secs_f64(copied as f64 / rate);
+                let elapsed = start.elapsed();
+
+                if due > elapsed {
+                    sleep(due - elapsed).await;
+                }
+            }
+        }
+
+        start_key = resp.last_evaluated_key;
+
+        // Ctrl-C stops the segment after the page in progress, so no page is half copied.
+        if start_key.is_none() || copy.shutdown.is_requested() {
+            return Ok((copied, retries));
+        }
+    }
+}
+
+/// Gets the description of a table, which must exist.
+async fn describe(client: &Client, table: &str) -> Result<TableDescription, Box<dyn Error>> {
+    Ok(client
+        .describe_table()
+        .table_name(table)
+        .send()
+        .await?
+        .table
+        .ok_or_else(|| format!("DynamoDB did not describe table {}", table))?)
+}
+
+/// Creates a client for the region, sending the requests to a local endpoint if one is set.
+fn client(region: Region, profile: &Option<String>) -> Result<Client, Box<dyn Error>> {
+    let mut config = Config::builder().region(region);
+    if let Some(profile) = profile {
+        config = config.credentials_provider(ProfileCredentialsProvider::new(profile));
+    }
+
+    // Sends the requests to DynamoDB Local or LocalStack if AWS_ENDPOINT_URL is set.
+    if let Some(uri) = endpoint::from_env()? {
+        config = config.endpoint_resolver(Endpoint::immutable(uri));
+    }
+
+    Ok(Client::from_conf(config.build()))
+}
+
+/// Copies the items in one Amazon DynamoDB table into another, which can be in another region.
+/// Scans the source table in parallel segments, and writes the items to the target table with BatchWriteItem.
+/// The target table must already exist, with the same key schema as the source table.
+/// Items that already exist in the target table with the same key are overwritten.
+/// # Arguments
+///
+/// * `-s SOURCE` - The name of the table to copy.
+/// * `-t TARGET` - The name of the table to copy the items into.
+/// * `[--target-region TARGET-REGION]` - The region of the target table.
+///   If not supplied, uses the region of the source table.
+/// * `[--segments SEGMENTS]` - How many segments to scan the source table in, in parallel.
+///   If not supplied, defaults to **4**.
+/// * `[--rate RATE]` - The most items to write each second.
+///   If not supplied, writes as fast as the target table accepts.
+/// * `[-r REGION]` - The region in which the source table is located.
+///   If not supplied, uses the value of the **AWS_DEFAULT_REGION** environment variable.
+///   If the environment variable is not set, defaults to **us-west-2**.
+/// * `[--output FORMAT]` - How to display the result, **text** (the default) or **json**.
+/// * `[--profile PROFILE]` - The AWS profile to use from the shared credentials file.
+/// * `[-v]` - Whether to display additional information.
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn Error>> {
+    let Opt {
+        output,
+        profile,
+        rate,
+        region,
+        segments,
+        source,
+        target,
+        target_region,
+        verbose,
+    } = Opt::from_args();
+
+    let region = EnvironmentProvider::new()
+        .region()
+        .or_else(|| region.as_ref().map(|region| Region::new(region.clone())))
+        .unwrap_or_else(|| Region::new("us-west-2"));
+    let target_region = target_region
+        .map(Region::new)
+        .unwrap_or_else(|| region.clone());
+
+    if !(1..=1_000_000).contains(&segments) {
+        return Err(format!(
+            "the number of segments must be from 1 to 1,000,000, got {}",
+            segments
+        )
+        .into());
+    }
+
+    if let Some(rate) = rate {
+        if rate <= 0.0 {
+            return Err(format!("the rate must be more than 0, got {}", rate).into());
+        }
+    }
+
+    if verbose {
+        println!("DynamoDB client version: {}", dynamodb::PKG_VERSION);
+        println!("Region:                  {:?}", &region);
+        println!("Source table:            {}", &source);
+        println!("Target region:           {:?}", &target_region);
+        println!("Target table:            {}", &target);
+        println!("Segments:                {}", segments);
+        println!("Rate (items/second):     {:?}", rate);
+
+        SubscriberBuilder::default()
+            .with_env_filter("info")
+            .with_span_events(FmtSpan::CLOSE)
+            .init();
+    }
+
+    let source_client = client(region, &profile)?;
+    let target_client = client(target_region.clone(), &profile)?;
+
+    let source_table = describe(&source_client, &source).await?;
+    let target_table = describe(&target_client, &target).await?;
+
+    if source_table.key_schema != target_table.key_schema {
+        return Err(format!(
+            "tables {} and {} have different key schemas, {:?} and {:?}",
+            &source, &target, source_table.key_schema, target_table.key_schema
+        )
+        .into());
+    }
+
+    // DynamoDB updates the item count about every six hours, so the total is only an estimate.
+    let progress = ProgressBar::new(source_table.item_count.max(0) as u64);
+    progress.set_style(
+        ProgressStyle::default_bar().template("{bar:40} {pos}/~{len} items ({per_sec}, {eta})"),
+    );
+
+    let copy = Arc::new(CopyJob {
+        source_client,
+        target_client,
+        source: source.clone(),
+        target: target.clone(),
+        segments,
+        rate: rate.map(|rate| rate / segments as f64),
+        progress: progress.clone(),
+        shutdown: Shutdown::listen(),
+    });
+
+    let start = Instant::now();
+
+    let handles: Vec<_> = (0..segments)
+        .map(|segment| tokio::spawn(copy_segment(copy.clone(), segment)))
+        .collect();
+
+    let mut items = 0;
+    let mut retries = 0;
+
+    for handle in handles {
+        let (copied, resent) = handle.await??;
+        items += copied;
+        retries += resent;
+    }
+
+    progress.finish_and_clear();
+
+    output.print(&CopiedTable {
+        source,
+        target,
+        target_region: target_region.as_ref().to_string(),
+        items,
+        segments,
+        retries,
+        seconds: start.elapsed().as_secs_f64(),
+        interrupted: copy.shutdown.is_requested(),
+    });
+
+    Ok(())
+}
@@ -0,0 +1,229 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0.
+ */
+
+use std::error::Error;
+use std::fmt;
+use std::fs;
+
+use dynamodb::{Client, Config, Endpoint, Region};
+use dynamodb_code_examples::schema::{apply, changes, describe, Change, Schema};
+
+use aws_types::region::{EnvironmentProvider, ProvideRegion};
+
+use common::endpoint;
+use common::output::OutputFormat;
+use common::profile::ProfileCredentialsProvider;
+use serde::Serialize;
+use structopt::StructOpt;
+use tracing_subscriber::fmt::format::FmtSpan;
+use tracing_subscriber::fmt::SubscriberBuilder;
+
+#[derive(Debug, StructOpt)]
+struct Opt {
+    /// The region
+    #[structopt(short, long)]
+    region: Option<String>,
+
+    /// The schema file, in JSON
+    #[structopt(short, long)]
+    file: String,
+
+    /// Only create or update this table from the schema. Repeat to pick several
+    #[structopt(short, long)]
+    table: Vec<String>,
+
+    /// Whether to display the changes, rather than make them
+    #[structopt(long)]
+    dry_run: bool,
+
+    /// The format of the output, text or json
+    #[structopt(long, default_value = "text")]
+    output: OutputFormat,
+
+    /// The AWS profile to use from the shared credentials file
+    #[structopt(long)]
+    profile: Option<String>,
+
+    /// Activate verbose mode
+    #[structopt(short, long)]
+    verbose: bool,
+}
+
+/// The changes to one table.
+#[derive(Debug, Serialize)]
+struct TablePlan {
+    table: String,
+    changes: Vec<Change>,
+}
+
+/// The changes that make the tables match the schema, and whether they were made.
+#[derive(Debug, Serialize)]
+struct Plan {
+    tables: Vec<TablePlan>,
+    applied: bool,
+}
+
+impl fmt::Display for Plan {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for table in &self.tables {
+            if table.changes.is_empty() {
+                writeln!(f, "{}: matches the schema", table.table)?;
+                continue;
+            }
+
+            writeln!(f, "{}:", table.table)?;
+            for change in &table.changes {
+                writeln!(f, "  {}", change)?;
+            }
+        }
+
+        let count: usize = self.tables.iter().map(|table| table.changes.len()).sum();
+        if count == 0 {
+            write!(f, "\nNothing to change")
+        } else if self.applied {
+            write!(f, "\nMade {} changes", count)
+        } else {
+            write!(f, "\nWould make {} changes", count)
+        }
+    }
+}
+
+/// Creates or updates DynamoDB tables to match a schema file, which describes each table's keys,
+/// capacity, global secondary indexes, time to live (TTL) attribute, and stream.
+/// Displays the changes first, and then makes them, one at a time, waiting for each to finish.
+/// A difference that DynamoDB can't change in place, such as a table's keys, is displayed with **!**,
+/// and stops the example before it changes anything.
+/// # Arguments
+///
+/// * `-f FILE` - The schema file.
+/// * `[-t TABLE]` - Only create or update this table from the schema. Repeat to pick several.
+///   If not supplied, uses every table in the schema.
+/// * `[--dry-run]` - Whether to display the changes, rather than make them.
+/// * `[-r REGION]` - The region in which the client is created.
+///    If not supplied, uses the value of the **AWS_DEFAULT_REGION** environment variable.
+///    If the environment variable is not set, defaults to **us-west-2**.
+/// * `[--output FORMAT]` - How to display the result, **text** (the default) or **json**.
+/// * `[--profile PROFILE]` - The AWS profile to use from the shared credentials file.
+/// * `[-v]` - Whether to display additional information.
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn Error>> {
+    let Opt {
+        dry_run,
+        file,
+        output,
+        profile,
+        region,
+        table,
+        verbose,
+    } = Opt::from_args();
+
+    let region = EnvironmentProvider::new()
+        .region()
+        .or_else(|| region.as_ref().map(|region| Region::new(region.clone())))
+        .unwrap_or_else(|| Region::new("us-west-2"));
+
+    let schema = Schema::from_json(&fs::read_to_string(&file)?)?;
+
+    for name in &table {
+        if !schema.tables.iter().any(|schema| &schema.name == name) {
+            return Err(format!("table {} isn't in {}", name, file).into());
+        }
+    }
+
+    let tables: Vec<_> = schema
+        .tables
+        .iter()
+        .filter(|schema| table.is_empty() || table.contains(&schema.name))
+        .collect();
+
+    if verbose {
+        println!("DynamoDB client version: {}", dynamodb::PKG_VERSION);
+        println!("Region:                  {:?}", &region);
+        println!("Schema:                  {}", &file);
+        println!(
+            "Tables:                  {:?}",
+            tables.iter().map(|table| &table.name).collect::<Vec<_>>()
+        );
+        println!("Dry run:                 {}", dry_run);
+        println!();
+
+        SubscriberBuilder::default()
+            .with_env_filter("info")
+            .with_span_events(FmtSpan::CLOSE)
+            .init();
+    }
+
+    let mut config = Config::builder().region(region);
+    if let Some(profile) = profile {
+        config = config.credentials_provider(ProfileCredentialsProvider::new(profile));
+    }
+
+    // Sends the requests to DynamoDB Local or LocalStack if AWS_ENDPOINT_URL is set.
+    if let Some(uri) = endpoint::from_env()? {
+        config = config.endpoint_resolver(Endpoint::immutable(uri));
+    }
+    let client = Client::from_conf(config.build());
+
+    let mut plan = Plan {
+        tables: Vec::new(),
+        applied: false,
+    };
+
+    for schema in &tables {
+        let current = describe(&client, &schema.name).await?;
+
+        plan.tables.push(TablePlan {
+            table: schema.name.clone(),
+            changes: changes(schema, current.as_ref()),
+        });
+    }
+
+    let conflicts = plan
+        .tables
+        .iter()
+        .flat_map(|table| &table.changes)
+        .any(|change| matches!(change, Change::Conflict(_)));
+
+    if dry_run || conflicts {
+        output.print(&plan);
+
+        if conflicts {
+            return Err(
+                "the tables can't be changed to match the schema, so nothing was changed; \
+                        resolve the differences marked with !"
+                    .into(),
+            );
+        }
+
+        return Ok(());
+    }
+
+    // The changes are displayed before they're made, since creating an index can take a while.
+    if !output.is_json() {
+        println!("{}\n", plan);
+    }
+
+    for (schema, table_plan) in tables.iter().zip(&plan.tables) {
+        if table_plan.changes.is_empty() {
+            continue;
+        }
+
+        if verbose {
+            println!("Changing table {}", schema.name);
+        }
+
+        apply(&client, schema, &table_plan.changes).await?;
+    }
+
+    plan.applied = true;
+
+    if output.is_json() {
+        output.print(&plan);
+    } else {
+        println!("Done");
+    }
+
+    Ok(())
+}
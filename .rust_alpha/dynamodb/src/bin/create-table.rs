@@ -3,19 +3,29 @@
  * SPDX-License-Identifier: Apache-2.0.
  */
 
-use std::process;
+use std::error::Error;
+use std::fmt;
+use std::time::Duration;
 
 use dynamodb::model::{
     AttributeDefinition, KeySchemaElement, KeyType, ProvisionedThroughput, ScalarAttributeType,
 };
-use dynamodb::{Client, Config, Region};
+use dynamodb::{Client, Config, Endpoint, Region};
+use dynamodb_code_examples::waiters::wait_for_table_active;
 
 use aws_types::region::{EnvironmentProvider, ProvideRegion};
 
+use common::endpoint;
+use common::output::OutputFormat;
+use common::profile::ProfileCredentialsProvider;
+use serde::Serialize;
 use structopt::StructOpt;
 use tracing_subscriber::fmt::format::FmtSpan;
 use tracing_subscriber::fmt::SubscriberBuilder;
 
+/// How long to wait for the new table to become active.
+const ACTIVE_TIMEOUT: Duration = Duration::from_secs(120);
+
 #[derive(Debug, StructOpt)]
 struct Opt {
     /// The region
@@ -30,12 +40,33 @@ struct Opt {
     #[structopt(short, long)]
     key: String,
 
+    /// The format of the output, text or json
+    #[structopt(long, default_value = "text")]
+    output: OutputFormat,
+
+    /// The AWS profile to use from the shared credentials file
+    #[structopt(long)]
+    profile: Option<String>,
+
     /// Activate verbose mode
     #[structopt(short, long)]
     verbose: bool,
 }
 
-/// Creates a DynamoDB table.
+/// The table that was created.
+#[derive(Debug, Serialize)]
+struct CreatedTable {
+    table: String,
+    key: String,
+}
+
+impl fmt::Display for CreatedTable {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Added table {} with key {}", self.table, self.key)
+    }
+}
+
+/// Creates a DynamoDB table, and waits for it to become active.
 /// # Arguments
 ///
 /// * `-k KEY` - The primary key for the table.
@@ -43,13 +74,17 @@ struct Opt {
 /// * `[-d DEFAULT-REGION]` - The region in which the client is created.
 ///    If not supplied, uses the value of the **AWS_DEFAULT_REGION** environment variable.
 ///    If the environment variable is not set, defaults to **us-west-2**.
+/// * `[--output FORMAT]` - How to display the result, **text** (the default) or **json**.
+/// * `[--profile PROFILE]` - The AWS profile to use from the shared credentials file.
 /// * `[-v]` - Whether to display additional information.
 #[tokio::main]
-async fn main() {
+async fn main() -> Result<(), Box<dyn Error>> {
     let Opt {
+        profile,
         table,
         key,
         region,
+        output,
         verbose,
     } = Opt::from_args();
 
@@ -70,8 +105,16 @@ async fn main() {
             .init();
     }
 
-    let config = Config::builder().region(region).build();
-    let client = Client::from_conf(config);
+    let mut config = Config::builder().region(region);
+    if let Some(profile) = profile {
+        config = config.credentials_provider(ProfileCredentialsProvider::new(profile));
+    }
+
+    // Sends the requests to DynamoDB Local or LocalStack if AWS_ENDPOINT_URL is set.
+    if let Some(uri) = endpoint::from_env()? {
+        config = config.endpoint_resolver(Endpoint::immutable(uri));
+    }
+    let client = Client::from_conf(config.build());
 
     let ad = AttributeDefinition::builder()
         .attribute_name(String::from(&key))
@@ -88,20 +131,23 @@ async fn main() {
         .write_capacity_units(5)
         .build();
 
-    match client
+    client
         .create_table()
         .table_name(String::from(&table))
         .key_schema(ks)
         .attribute_definitions(ad)
         .provisioned_throughput(pt)
         .send()
-        .await
-    {
-        Ok(_) => println!("Added table {} with key {}", table, key),
-        Err(e) => {
-            println!("Got an error creating table:");
-            println!("{}", e);
-            process::exit(1);
-        }
-    };
+        .await?;
+
+    // The table can't be used until it's active.
+    if verbose {
+        println!("Waiting for table {} to become active", &table);
+    }
+
+    wait_for_table_active(&client, &table, ACTIVE_TIMEOUT).await?;
+
+    output.print(&CreatedTable { table, key });
+
+    Ok(())
 }
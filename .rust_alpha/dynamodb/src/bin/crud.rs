@@ -5,34 +5,25 @@
 
 use rand::distributions::Alphanumeric;
 use rand::{thread_rng, Rng};
-use std::collections::HashMap;
+use std::error::Error;
+use std::fmt;
 use std::io::{stdin, Read};
+use std::iter;
 use std::time::Duration;
-use std::{iter, process};
-
-use aws_http::AwsErrorRetryPolicy;
-use aws_hyper::{SdkError, SdkSuccess};
-
-use dynamodb::error::DescribeTableError;
-
-use dynamodb::input::DescribeTableInput;
 
 use dynamodb::model::{
     AttributeDefinition, AttributeValue, KeySchemaElement, KeyType, ProvisionedThroughput,
-    ScalarAttributeType, Select, TableStatus,
+    ScalarAttributeType, Select,
 };
-
-use dynamodb::operation::DescribeTable;
-use dynamodb::output::DescribeTableOutput;
-
 use dynamodb::{Client, Config, Region};
+use dynamodb_code_examples::expressions::{Condition, Expression, Update};
+use dynamodb_code_examples::waiters::wait_for_table_active;
 
 use aws_types::region::{EnvironmentProvider, ProvideRegion};
 
-use smithy_http::operation::Operation;
-use smithy_http::retry::ClassifyResponse;
-use smithy_types::retry::RetryKind;
-
+use common::output::OutputFormat;
+use common::profile::ProfileCredentialsProvider;
+use serde::Serialize;
 use structopt::StructOpt;
 use tracing_subscriber::fmt::format::FmtSpan;
 use tracing_subscriber::fmt::SubscriberBuilder;
@@ -47,6 +38,14 @@ struct Opt {
     #[structopt(short, long)]
     region: Option<String>,
 
+    /// The format of the output, text or json
+    #[structopt(long, default_value = "text")]
+    output: OutputFormat,
+
+    /// The AWS profile to use from the shared credentials file
+    #[structopt(long)]
+    profile: Option<String>,
+
     /// Activate verbose mode    
     #[structopt(short, long)]
     verbose: bool,
@@ -63,7 +62,11 @@ fn random_string(n: usize) -> String {
 }
 
 /// Create a new table. It's remotely possible the random table name exists.
-async fn create_table(client: &dynamodb::Client, table: &str, key: &str) {
+async fn create_table(
+    client: &dynamodb::Client,
+    table: &str,
+    key: &str,
+) -> Result<(), dynamodb::Error> {
     let ad = AttributeDefinition::builder()
         .attribute_name(key)
         .attribute_type(ScalarAttributeType::S)
@@ -79,22 +82,16 @@ async fn create_table(client: &dynamodb::Client, table: &str, key: &str) {
         .write_capacity_units(5)
         .build();
 
-    match client
+    client
         .create_table()
         .table_name(table)
         .key_schema(ks)
         .attribute_definitions(ad)
         .provisioned_throughput(pt)
         .send()
-        .await
-    {
-        Ok(_) => println!(),
-        Err(e) => {
-            println!("Got an error creating the table:");
-            println!("{}", e);
-            process::exit(1);
-        }
-    }
+        .await?;
+
+    Ok(())
 }
 
 /// For add_item and scan_item
@@ -110,14 +107,14 @@ struct Item {
 }
 
 /// Add an item to the table.
-async fn add_item(client: &dynamodb::Client, item: Item) {
+async fn add_item(client: &dynamodb::Client, item: Item) -> Result<(), dynamodb::Error> {
     let user_av = AttributeValue::S(item.value);
     let type_av = AttributeValue::S(item.utype);
     let age_av = AttributeValue::S(item.age);
     let first_av = AttributeValue::S(item.first_name);
     let last_av = AttributeValue::S(item.last_name);
 
-    match client
+    client
         .put_item()
         .table_name(item.table)
         .item(item.key, user_av)
@@ -126,19 +123,35 @@ async fn add_item(client: &dynamodb::Client, item: Item) {
         .item("first_name", first_av)
         .item("last_name", last_av)
         .send()
-        .await
-    {
-        Ok(_) => println!(),
-        Err(e) => {
-            println!("Got an error adding item to table:");
-            println!("{}", e);
-            process::exit(1);
-        }
-    }
+        .await?;
+
+    Ok(())
 }
 
-/// Query the table for an item matching the input values.
-async fn query(client: &dynamodb::Client, item: Item) {
+/// Change the age of an existing item.
+/// The condition makes the update fail if the item doesn't exist, rather than create it.
+async fn update_age(client: &dynamodb::Client, item: &Item) -> Result<(), dynamodb::Error> {
+    let expression = Expression::builder()
+        .condition(Condition::exists(&item.key))
+        .update(Update::new().set("age", AttributeValue::S(item.age.clone())))
+        .build();
+
+    client
+        .update_item()
+        .table_name(&item.table)
+        .key(&item.key, AttributeValue::S(item.value.clone()))
+        .set_condition_expression(expression.condition)
+        .set_update_expression(expression.update)
+        .set_expression_attribute_names(expression.names)
+        .set_expression_attribute_values(expression.values)
+        .send()
+        .await?;
+
+    Ok(())
+}
+
+/// Query the table for an item matching the input values, and return whether it matched.
+async fn query(client: &dynamodb::Client, item: Item) -> Result<bool, dynamodb::Error> {
     let value = &item.value;
     let key = &item.key;
     let user_av = AttributeValue::S(value.to_string());
@@ -148,165 +161,134 @@ async fn query(client: &dynamodb::Client, item: Item) {
     let first_av = AttributeValue::S(item.first_name);
     let last_av = AttributeValue::S(item.last_name);
     let mut found_match = true;
-    let mut cond = HashMap::new();
-    cond.insert("#key".to_string(), key.to_string());
-    let mut expr = HashMap::new();
-    expr.insert(":value".to_string(), user_av);
-    match client
+    let expression = Expression::builder()
+        .key_condition(Condition::eq(key, user_av))
+        .build();
+    let resp = client
         .query()
         .table_name(item.table)
-        .set_key_condition_expression(Some("#key = :value".to_string()))
-        .set_expression_attribute_names(Some(cond))
-        .set_expression_attribute_values(Some(expr))
+        .set_key_condition_expression(expression.key_condition)
+        .set_expression_attribute_names(expression.names)
+        .set_expression_attribute_values(expression.values)
         .select(Select::AllAttributes)
         .send()
-        .await
-    {
-        Ok(resp) => {
-            let items = resp.items.unwrap_or_default();
-            for item in items {
-                // Do key values match?
-                match item.get(&String::from(key)) {
-                    None => found_match = false,
-                    Some(v) => {
-                        if v != &userav {
-                            found_match = false;
-                        }
-                    }
+        .await?;
+
+    let items = resp.items.unwrap_or_default();
+    for item in items {
+        // Do key values match?
+        match item.get(&String::from(key)) {
+            None => found_match = false,
+            Some(v) => {
+                if v != &userav {
+                    found_match = false;
                 }
+            }
+        }
 
-                // Do age values match?
-                match item.get(&String::from("age")) {
-                    None => found_match = false,
-                    Some(v) => {
-                        if v != &age_av {
-                            found_match = false;
-                        }
-                    }
+        // Do age values match?
+        match item.get(&String::from("age")) {
+            None => found_match = false,
+            Some(v) => {
+                if v != &age_av {
+                    found_match = false;
                 }
+            }
+        }
 
-                // Do first name values match?
-                match item.get(&String::from("first_name")) {
-                    None => found_match = false,
-                    Some(v) => {
-                        if v != &first_av {
-                            found_match = false;
-                        }
-                    }
+        // Do first name values match?
+        match item.get(&String::from("first_name")) {
+            None => found_match = false,
+            Some(v) => {
+                if v != &first_av {
+                    found_match = false;
                 }
+            }
+        }
 
-                // Do last name values match?
-                match item.get(&String::from("last_name")) {
-                    None => found_match = false,
-                    Some(v) => {
-                        if v != &last_av {
-                            found_match = false;
-                        }
-                    }
+        // Do last name values match?
+        match item.get(&String::from("last_name")) {
+            None => found_match = false,
+            Some(v) => {
+                if v != &last_av {
+                    found_match = false;
                 }
+            }
+        }
 
-                // Do account type values match?
-                match item.get(&String::from("account_type")) {
-                    None => found_match = false,
-                    Some(v) => {
-                        if v != &type_av {
-                            found_match = false;
-                        }
-                    }
+        // Do account type values match?
+        match item.get(&String::from("account_type")) {
+            None => found_match = false,
+            Some(v) => {
+                if v != &type_av {
+                    found_match = false;
                 }
             }
         }
-        Err(e) => {
-            println!("Got an error querying the table:");
-            println!("{}", e);
-            process::exit(1);
-        }
-    };
-
-    if !found_match {
-        println!("Did not find matching entry in table");
-    } else {
-        println!("Found a match!");
     }
+
+    Ok(found_match)
 }
 
 /// Delete an item from the table.
-async fn delete_item(client: &dynamodb::Client, table: &str, key: &str, value: &str) {
+async fn delete_item(
+    client: &dynamodb::Client,
+    table: &str,
+    key: &str,
+    value: &str,
+) -> Result<(), dynamodb::Error> {
     let user_av = AttributeValue::S(String::from(value));
-    match client
+    client
         .delete_item()
         .table_name(table)
         .key(key, user_av)
         .send()
-        .await
-    {
-        Ok(_) => println!(),
-        Err(e) => {
-            println!("Got an error trying to delete item:");
-            println!("{}", e);
-            process::exit(1);
-        }
-    }
+        .await?;
+
+    Ok(())
 }
 
 /// Delete the table.
-async fn delete_table(client: &dynamodb::Client, table: &str) {
-    match client.delete_table().table_name(table).send().await {
-        Ok(_) => println!(),
-        Err(e) => {
-            println!("Got an error deleting table:");
-            println!("{}", e);
-            process::exit(1);
-        }
-    }
+async fn delete_table(client: &dynamodb::Client, table: &str) -> Result<(), dynamodb::Error> {
+    client.delete_table().table_name(table).send().await?;
+
+    Ok(())
 }
 
-/// Hand-written waiter to retry every second until the table is out of `Creating` state
-#[derive(Clone)]
-struct WaitForReadyTable<R> {
-    inner: R,
+/// What the operations did.
+#[derive(Debug, Serialize)]
+struct Crud {
+    table: String,
+    key: String,
+    value: String,
+    /// Whether the item that was read back after the update matched it.
+    matched: bool,
 }
 
-impl<R> ClassifyResponse<SdkSuccess<DescribeTableOutput>, SdkError<DescribeTableError>>
-    for WaitForReadyTable<R>
-where
-    R: ClassifyResponse<SdkSuccess<DescribeTableOutput>, SdkError<DescribeTableError>>,
-{
-    fn classify(
-        &self,
-        response: Result<&SdkSuccess<DescribeTableOutput>, &SdkError<DescribeTableError>>,
-    ) -> RetryKind {
-        match self.inner.classify(response) {
-            RetryKind::NotRetryable => (),
-            other => return other,
-        };
-        match response {
-            Ok(SdkSuccess { parsed, .. }) => {
-                if parsed
-                    .table
-                    .as_ref()
-                    .unwrap()
-                    .table_status
-                    .as_ref()
-                    .unwrap()
-                    == &TableStatus::Creating
-                {
-                    RetryKind::Explicit(Duration::from_secs(1))
-                } else {
-                    RetryKind::NotRetryable
-                }
+impl fmt::Display for Crud {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "Created table {}, added, updated, and deleted the item with {} {}, and deleted the table\n{}",
+            self.table,
+            self.key,
+            self.value,
+            if self.matched {
+                "The item read back after the update matched"
+            } else {
+                "The item read back after the update did not match"
             }
-            _ => RetryKind::NotRetryable,
-        }
+        )
     }
 }
 
 /// Wait for the user to press Enter.
-fn pause() {
-    println!();
-    println!("Press Enter to continue");
-    println!();
-    stdin().read_exact(&mut [0]).unwrap();
+/// The prompt goes to stderr so it stays out of the JSON output.
+fn pause() -> std::io::Result<()> {
+    eprintln!();
+    eprintln!("Press Enter to continue");
+    eprintln!();
+    stdin().read_exact(&mut [0])
 }
 
 /// Performs CRUD (create, read, update, delete) operations on a DynamoDB table and table item.
@@ -319,11 +301,15 @@ fn pause() {
 /// * `[-d DEFAULT-REGION]` - The region in which the client is created.
 ///    If not supplied, uses the value of the **AWS_DEFAULT_REGION** environment variable.
 ///    If the environment variable is not set, defaults to **us-west-2**.
+/// * `[--output FORMAT]` - How to display the result, **text** (the default) or **json**.
+/// * `[--profile PROFILE]` - The AWS profile to use from the shared credentials file.
 /// * `[-v]` - Whether to display additional information.
 #[tokio::main]
-async fn main() {
+async fn main() -> Result<(), Box<dyn Error>> {
     let Opt {
         interactive,
+        output,
+        profile,
         region,
         verbose,
     } = Opt::from_args();
@@ -362,31 +348,34 @@ async fn main() {
 
     let r = region.clone();
 
-    let conf = Config::builder().region(&r).build();
-    let client = Client::from_conf(conf);
+    let mut config = Config::builder().region(&r);
+    if let Some(profile) = profile {
+        config = config.credentials_provider(ProfileCredentialsProvider::new(profile));
+    }
+    let client = Client::from_conf(config.build());
 
-    /* Create table */
-    println!();
-    println!("Creating table {} in {:?}", table, r);
-    create_table(&client, &table, &key).await;
+    // Reports each step as it happens, except in JSON, which is only the result.
+    let progress = |message: String| {
+        if !output.is_json() {
+            println!("{}", message);
+        }
+    };
 
-    println!("Waiting for table to be ready");
+    /* Create table */
+    progress(format!("Creating table {} in {:?}", table, r));
+    create_table(&client, &table, &key).await?;
 
-    let raw_client = aws_hyper::Client::https();
+    progress("Waiting for table to be ready".to_string());
 
-    raw_client
-        .call(wait_for_ready_table(&table, client.conf()))
-        .await
-        .expect("table should become ready");
+    wait_for_table_active(&client, &table, Duration::from_secs(60)).await?;
 
-    println!("Table is now ready to use");
+    progress("Table is now ready to use".to_string());
 
     if interactive {
-        pause();
+        pause()?;
     }
 
-    println!();
-    println!("Adding item to table");
+    progress("Adding item to table".to_string());
 
     let mut item = Item {
         table: table.clone(),
@@ -398,60 +387,50 @@ async fn main() {
         utype: utype.to_string(),
     };
 
-    add_item(&client, item.clone()).await;
+    add_item(&client, item.clone()).await?;
 
     if interactive {
-        pause();
+        pause()?;
     }
 
     item.age = "44".to_string();
 
     /* Update the item */
-    println!("Modifying table item");
+    progress("Modifying table item".to_string());
 
-    add_item(&client, item.clone()).await;
+    update_age(&client, &item).await?;
 
     if interactive {
-        pause();
+        pause()?;
     }
 
     /* Get item and compare it with the one we added */
-    println!("Comparing table item to original value");
+    progress("Comparing table item to original value".to_string());
 
-    query(&client, item).await;
+    let matched = query(&client, item).await?;
 
     if interactive {
-        pause();
+        pause()?;
     }
 
     /* Delete item */
-    println!();
-    println!("Deleting item");
-    delete_item(&client, &table, &key, &value).await;
+    progress("Deleting item".to_string());
+    delete_item(&client, &table, &key, &value).await?;
 
     if interactive {
-        pause();
+        pause()?;
     }
 
     /* Delete table */
-    println!("Deleting table");
-    delete_table(&client, &table).await;
-}
+    progress("Deleting table".to_string());
+    delete_table(&client, &table).await?;
 
-/// Construct a `DescribeTable` request with a policy to retry every second until the table
-/// is ready
-fn wait_for_ready_table(
-    table_name: &str,
-    conf: &Config,
-) -> Operation<DescribeTable, WaitForReadyTable<AwsErrorRetryPolicy>> {
-    let operation = DescribeTableInput::builder()
-        .table_name(table_name)
-        .build()
-        .expect("valid input")
-        .make_operation(&conf)
-        .expect("valid operation");
-    let waiting_policy = WaitForReadyTable {
-        inner: operation.retry_policy().clone(),
-    };
-    operation.with_retry_policy(waiting_policy)
+    output.print(&Crud {
+        table,
+        key,
+        value,
+        matched,
+    });
+
+    Ok(())
 }
@@ -3,13 +3,18 @@
  * SPDX-License-Identifier: Apache-2.0.
  */
 
-use std::process;
+use std::error::Error;
+use std::fmt;
 
 use dynamodb::model::AttributeValue;
-use dynamodb::{Client, Config, Region};
+use dynamodb::{Client, Config, Endpoint, Region};
 
 use aws_types::region::{EnvironmentProvider, ProvideRegion};
 
+use common::endpoint;
+use common::output::OutputFormat;
+use common::profile::ProfileCredentialsProvider;
+use serde::Serialize;
 use structopt::StructOpt;
 use tracing_subscriber::fmt::format::FmtSpan;
 use tracing_subscriber::fmt::SubscriberBuilder;
@@ -32,11 +37,37 @@ struct Opt {
     #[structopt(short, long)]
     value: String,
 
+    /// The format of the output, text or json
+    #[structopt(long, default_value = "text")]
+    output: OutputFormat,
+
+    /// The AWS profile to use from the shared credentials file
+    #[structopt(long)]
+    profile: Option<String>,
+
     /// Whether to display additional information
     #[structopt(short, long)]
     info: bool,
 }
 
+/// The item that was deleted.
+#[derive(Debug, Serialize)]
+struct DeletedItem {
+    table: String,
+    key: String,
+    value: String,
+}
+
+impl fmt::Display for DeletedItem {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "Deleted item with {} {} from table {}",
+            self.key, self.value, self.table
+        )
+    }
+}
+
 /// Deletes an item from an Amazon DynamoDB table.
 /// The table schema must use the key as the primary key.
 /// # Arguments
@@ -47,12 +78,16 @@ struct Opt {
 /// * `[-r REGION]` - The region in which the table is created.
 ///   If not supplied, uses the value of the **AWS_DEFAULT_REGION** environment variable.
 ///   If the environment variable is not set, defaults to **us-west-2**.
+/// * `[--output FORMAT]` - How to display the result, **text** (the default) or **json**.
+/// * `[--profile PROFILE]` - The AWS profile to use from the shared credentials file.
 /// * `[-i]` - Whether to display additional information.
 #[tokio::main]
-async fn main() {
+async fn main() -> Result<(), Box<dyn Error>> {
     let Opt {
         info,
         key,
+        output,
+        profile,
         region,
         table,
         value,
@@ -75,22 +110,26 @@ async fn main() {
             .init();
     }
 
-    let config = Config::builder().region(region).build();
+    let mut config = Config::builder().region(region);
+    if let Some(profile) = profile {
+        config = config.credentials_provider(ProfileCredentialsProvider::new(profile));
+    }
 
-    let client = Client::from_conf(config);
+    // Sends the requests to DynamoDB Local or LocalStack if AWS_ENDPOINT_URL is set.
+    if let Some(uri) = endpoint::from_env()? {
+        config = config.endpoint_resolver(Endpoint::immutable(uri));
+    }
 
-    match client
+    let client = Client::from_conf(config.build());
+
+    client
         .delete_item()
-        .table_name(table)
-        .key(key, AttributeValue::S(value))
+        .table_name(&table)
+        .key(&key, AttributeValue::S(value.clone()))
         .send()
-        .await
-    {
-        Ok(_) => println!("Deleted item from table"),
-        Err(e) => {
-            println!("Got an error deleting item from table:");
-            println!("{}", e);
-            process::exit(1);
-        }
-    };
+        .await?;
+
+    output.print(&DeletedItem { table, key, value });
+
+    Ok(())
 }
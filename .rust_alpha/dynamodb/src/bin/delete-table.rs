@@ -3,12 +3,17 @@
  * SPDX-License-Identifier: Apache-2.0.
  */
 
-use std::process;
+use std::error::Error;
+use std::fmt;
 
-use dynamodb::{Client, Config, Region};
+use dynamodb::{Client, Config, Endpoint, Region};
 
 use aws_types::region::{EnvironmentProvider, ProvideRegion};
 
+use common::endpoint;
+use common::output::OutputFormat;
+use common::profile::ProfileCredentialsProvider;
+use serde::Serialize;
 use structopt::StructOpt;
 use tracing_subscriber::fmt::format::FmtSpan;
 use tracing_subscriber::fmt::SubscriberBuilder;
@@ -23,11 +28,31 @@ struct Opt {
     #[structopt(short, long)]
     table: String,
 
+    /// The format of the output, text or json
+    #[structopt(long, default_value = "text")]
+    output: OutputFormat,
+
+    /// The AWS profile to use from the shared credentials file
+    #[structopt(long)]
+    profile: Option<String>,
+
     /// Activate verbose mode
     #[structopt(short, long)]
     verbose: bool,
 }
 
+/// The table that was deleted.
+#[derive(Debug, Serialize)]
+struct DeletedTable {
+    table: String,
+}
+
+impl fmt::Display for DeletedTable {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Deleted table {}", self.table)
+    }
+}
+
 /// Deletes a DynamoDB table.
 /// # Arguments
 ///
@@ -35,12 +60,16 @@ struct Opt {
 /// * `[-d DEFAULT-REGION]` - The region in which the client is created.
 ///    If not supplied, uses the value of the **AWS_DEFAULT_REGION** environment variable.
 ///    If the environment variable is not set, defaults to **us-west-2**.
+/// * `[--output FORMAT]` - How to display the result, **text** (the default) or **json**.
+/// * `[--profile PROFILE]` - The AWS profile to use from the shared credentials file.
 /// * `[-v]` - Whether to display additional information.
 #[tokio::main]
-async fn main() {
+async fn main() -> Result<(), Box<dyn Error>> {
     let Opt {
+        profile,
         table,
         region,
+        output,
         verbose,
     } = Opt::from_args();
 
@@ -60,16 +89,21 @@ async fn main() {
             .init();
     }
 
-    let config = Config::builder().region(region).build();
+    let mut config = Config::builder().region(region);
+    if let Some(profile) = profile {
+        config = config.credentials_provider(ProfileCredentialsProvider::new(profile));
+    }
+
+    // Sends the requests to DynamoDB Local or LocalStack if AWS_ENDPOINT_URL is set.
+    if let Some(uri) = endpoint::from_env()? {
+        config = config.endpoint_resolver(Endpoint::immutable(uri));
+    }
+
+    let client = Client::from_conf(config.build());
+
+    client.delete_table().table_name(&table).send().await?;
 
-    let client = Client::from_conf(config);
+    output.print(&DeletedTable { table });
 
-    match client.delete_table().table_name(table).send().await {
-        Ok(_) => println!("Deleted table"),
-        Err(e) => {
-            println!("Got an error deleting the table:");
-            println!("{}", e);
-            process::exit(1);
-        }
-    };
+    Ok(())
 }
@@ -3,17 +3,101 @@
  * SPDX-License-Identifier: Apache-2.0.
  */
 
+use std::error::Error;
+use std::fmt;
+use std::time::Duration;
+
 use dynamodb::model::{
     AttributeDefinition, KeySchemaElement, KeyType, ProvisionedThroughput, ScalarAttributeType,
 };
+use dynamodb::{Client, Config, Endpoint, Region};
+use dynamodb_code_examples::waiters::wait_for_table_active;
+
+use aws_types::region::{EnvironmentProvider, ProvideRegion};
+
+use common::endpoint;
+use common::output::OutputFormat;
+use common::profile::ProfileCredentialsProvider;
+use serde::Serialize;
+use structopt::StructOpt;
+
+#[derive(Debug, StructOpt)]
+struct Opt {
+    /// The region
+    #[structopt(short, long)]
+    region: Option<String>,
+
+    /// The format of the output, text or json
+    #[structopt(long, default_value = "text")]
+    output: OutputFormat,
+
+    /// The AWS profile to use from the shared credentials file
+    #[structopt(long)]
+    profile: Option<String>,
+}
+
+/// The tables that existed and the table that was created.
+#[derive(Debug, Serialize)]
+struct HelloWorld {
+    tables: Vec<String>,
+    new_table: String,
+    new_table_arn: String,
+}
+
+impl fmt::Display for HelloWorld {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "Current DynamoDB tables:")?;
+
+        for name in &self.tables {
+            writeln!(f, "  {}", name)?;
+        }
 
-/// Lists your DynamoDB tables and creates the table __test_table__.
+        write!(
+            f,
+            "Created {} ({}), which is now active",
+            self.new_table, self.new_table_arn
+        )
+    }
+}
+
+/// Lists your DynamoDB tables, creates the table __test-table__, and waits for it to become active.
+/// # Arguments
+///
+/// * `[-r REGION]` - The region in which the table is created.
+///   If not supplied, uses the value of the **AWS_DEFAULT_REGION** environment variable.
+///   If the environment variable is not set, defaults to **us-west-2**.
+/// * `[--output FORMAT]` - How to display the result, **text** (the default) or **json**.
+/// * `[--profile PROFILE]` - The AWS profile to use from the shared credentials file.
 #[tokio::main]
-async fn main() -> Result<(), dynamodb::Error> {
-    let client = dynamodb::Client::from_env();
-    let tables = client.list_tables().send().await?;
+async fn main() -> Result<(), Box<dyn Error>> {
+    let Opt {
+        output,
+        profile,
+        region,
+    } = Opt::from_args();
+
+    let region = EnvironmentProvider::new()
+        .region()
+        .or_else(|| region.as_ref().map(|region| Region::new(region.clone())))
+        .unwrap_or_else(|| Region::new("us-west-2"));
+
+    let mut config = Config::builder().region(region);
+    if let Some(profile) = profile {
+        config = config.credentials_provider(ProfileCredentialsProvider::new(profile));
+    }
 
-    println!("Current DynamoDB tables: {:?}", tables);
+    // Sends the requests to DynamoDB Local or LocalStack if AWS_ENDPOINT_URL is set.
+    if let Some(uri) = endpoint::from_env()? {
+        config = config.endpoint_resolver(Endpoint::immutable(uri));
+    }
+
+    let client = Client::from_conf(config.build());
+    let tables = client
+        .list_tables()
+        .send()
+        .await?
+        .table_names
+        .unwrap_or_default();
 
     let new_table = client
         .create_table()
@@ -38,9 +122,19 @@ async fn main() -> Result<(), dynamodb::Error> {
         )
         .send()
         .await?;
-    println!(
-        "new table: {:#?}",
-        &new_table.table_description.unwrap().table_arn.unwrap()
-    );
+    let new_table_arn = new_table
+        .table_description
+        .and_then(|table| table.table_arn)
+        .unwrap_or_default();
+
+    // Items can't be added to the table until it's active.
+    wait_for_table_active(&client, "test-table", Duration::from_secs(60)).await?;
+
+    output.print(&HelloWorld {
+        tables,
+        new_table: "test-table".to_string(),
+        new_table_arn,
+    });
+
     Ok(())
 }
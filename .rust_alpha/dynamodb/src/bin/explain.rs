@@ -0,0 +1,508 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0.
+ */
+
+use std::error::Error;
+use std::fmt;
+
+use dynamodb::model::{
+    KeySchemaElement, KeyType, ReturnConsumedCapacity, ScalarAttributeType, Select,
+};
+use dynamodb::{Client, Config, Endpoint, Region};
+use dynamodb_code_examples::expressions::{Condition, Expression};
+use dynamodb_code_examples::plans::{
+    describe, equality_attributes, parse_condition, plan, queryable_indexes, retype, Keys,
+    Operation, Plan,
+};
+
+use aws_types::region::{EnvironmentProvider, ProvideRegion};
+
+use common::endpoint;
+use common::output::OutputFormat;
+use common::profile::ProfileCredentialsProvider;
+use serde::Serialize;
+use structopt::StructOpt;
+use tracing_subscriber::fmt::format::FmtSpan;
+use tracing_subscriber::fmt::SubscriberBuilder;
+
+/// How many bytes one read capacity unit reads with a strongly consistent read.
+/// An eventually consistent read reads twice as much.
+const READ_UNIT_BYTES: f64 = 4096.0;
+
+/// The share of the items read that a filter must discard before the example suggests a better key.
+const WASTEFUL_FILTER: f64 = 0.5;
+
+#[derive(Debug, StructOpt)]
+struct Opt {
+    /// The region
+    #[structopt(short, long)]
+    region: Option<String>,
+
+    /// The name of the table
+    #[structopt(short, long)]
+    table: String,
+
+    /// The index to read, instead of the table
+    #[structopt(short, long)]
+    index: Option<String>,
+
+    /// A condition on the items, such as "year = 2013". Can be repeated
+    #[structopt(short = "w", long = "where", parse(try_from_str = parse_condition))]
+    conditions: Vec<Condition>,
+
+    /// Whether to use strongly consistent reads
+    #[structopt(short, long)]
+    consistent: bool,
+
+    /// The most pages to read in the sample run
+    #[structopt(short, long, default_value = "5")]
+    pages: usize,
+
+    /// The format of the output, text or json
+    #[structopt(long, default_value = "text")]
+    output: OutputFormat,
+
+    /// The AWS profile to use from the shared credentials file
+    #[structopt(long)]
+    profile: Option<String>,
+
+    /// Whether to display additional information
+    #[structopt(short, long)]
+    verbose: bool,
+}
+
+/// What the sample run read.
+#[derive(Debug, Default, Serialize)]
+struct Sample {
+    pages: usize,
+    /// How many items were read, before the filter.
+    items_read: i64,
+    /// How many items matched the filter.
+    items_returned: i64,
+    capacity_units: f64,
+    /// Whether every matching item was read, rather than stopping after the most pages.
+    complete: bool,
+}
+
+/// How an access pattern reads a table, what it cost, and how it could cost less.
+#[derive(Debug, Serialize)]
+struct Explanation {
+    table: String,
+    index: Option<String>,
+    operation: Operation,
+    key_condition: Option<String>,
+    filter: Option<String>,
+    consistent: bool,
+    sample: Sample,
+    /// For a Scan that the sample didn't finish, about how many capacity units reading everything takes.
+    estimated_capacity_units: Option<f64>,
+    suggestions: Vec<String>,
+}
+
+impl fmt::Display for Explanation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.index {
+            Some(index) => write!(
+                f,
+                "{} of index {} of table {}",
+                self.operation, index, self.table
+            )?,
+            None => write!(f, "{} of table {}", self.operation, self.table)?,
+        }
+
+        write!(
+            f,
+            "\n  Key condition:  {}",
+            self.key_condition.as_deref().unwrap_or("none")
+        )?;
+        write!(
+            f,
+            "\n  Filter:         {}",
+            self.filter.as_deref().unwrap_or("none")
+        )?;
+        write!(
+            f,
+            "\n  Reads:          {}",
+            if self.consistent {
+                "strongly consistent"
+            } else {
+                "eventually consistent"
+            }
+        )?;
+
+        write!(
+            f,
+            "\nSample run: {} pages{}",
+            self.sample.pages,
+            if self.sample.complete {
+                ", every matching item"
+            } else {
+                ", stopped before the end"
+            }
+        )?;
+        write!(f, "\n  Items read:     {}", self.sample.items_read)?;
+        write!(f, "\n  Items returned: {}", self.sample.items_returned)?;
+        write!(f, "\n  Capacity units: {:.1}", self.sample.capacity_units)?;
+        if let Some(estimate) = self.estimated_capacity_units {
+            write!(
+                f,
+                "\n  Whole scan:     about {:.1} capacity units",
+                estimate
+            )?;
+        }
+
+        if !self.suggestions.is_empty() {
+            write!(f, "\nSuggestions:")?;
+            for suggestion in &self.suggestions {
+                write!(f, "\n  - {}", suggestion)?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Returns the partition and sort keys in a key schema.
+fn keys(schema: Option<Vec<KeySchemaElement>>) -> Option<Keys> {
+    let schema = schema.unwrap_or_default();
+    let name = |key_type: KeyType| {
+        schema
+            .iter()
+            .find(|element| element.key_type.as_ref() == Some(&key_type))
+            .and_then(|element| element.attribute_name.clone())
+    };
+
+    Some(Keys {
+        partition: name(KeyType::Hash)?,
+        sort: name(KeyType::Range),
+    })
+}
+
+/// Runs the plan, counting the items rather than returning them, for up to `max_pages` pages.
+async fn sample(
+    client: &Client,
+    table: &str,
+    plan: &Plan,
+    expression: &Expression,
+    consistent: bool,
+    max_pages: usize,
+) -> Result<Sample, dynamodb::Error> {
+    let mut sample = Sample::default();
+    let mut start_key = None;
+
+    while sample.pages < max_pages {
+        let (count, scanned_count, capacity, last_key) = match plan.operation {
+            Operation::Query => {
+                let resp = client
+                    .query()
+                    .table_name(table)
+                    .set_index_name(plan.index.clone())
+                    .set_key_condition_expression(expression.key_condition.clone())
+                    .set_filter_expression(expression.filter.clone())
+                    .set_expression_attribute_names(expression.names.clone())
+                    .set_expression_attribute_values(expression.values.clone())
+                    .consistent_read(consistent)
+                    .select(Select::Count)
+                    .return_consumed_capacity(ReturnConsumedCapacity::Total)
+                    .set_exclusive_start_key(start_key)
+                    .send()
+                    .await?;
+
+                (
+                    resp.count,
+                    resp.scanned_count,
+                    resp.consumed_capacity,
+                    resp.last_evaluated_key,
+                )
+            }
+            Operation::Scan => {
+                let resp = client
+                    .scan()
+                    .table_name(table)
+                    .set_index_name(plan.index.clone())
+                    .set_filter_expression(expression.filter.clone())
+                    .set_expression_attribute_names(expression.names.clone())
+                    .set_expression_attribute_values(expression.values.clone())
+                    .consistent_read(consistent)
+                    .select(Select::Count)
+                    .return_consumed_capacity(ReturnConsumedCapacity::Total)
+                    .set_exclusive_start_key(start_key)
+                    .send()
+                    .await?;
+
+                (
+                    resp.count,
+                    resp.scanned_count,
+                    resp.consumed_capacity,
+                    resp.last_evaluated_key,
+                )
+            }
+        };
+
+        sample.pages += 1;
+        sample.items_returned += i64::from(count);
+        sample.items_read += i64::from(scanned_count);
+        sample.capacity_units += capacity
+            .and_then(|capacity| capacity.capacity_units)
+            .unwrap_or_default();
+
+        // An empty last key means there are no more pages.
+        start_key = last_key.filter(|key| !key.is_empty());
+        if start_key.is_none() {
+            sample.complete = true;
+            break;
+        }
+    }
+
+    Ok(sample)
+}
+
+/// Explains how DynamoDB reads the items that match some conditions, and what it costs.
+/// Works out whether the conditions can be read with a Query, which reads only the items with one
+/// partition key value, or need a Scan, which reads every item. Then runs the Query or Scan
+/// for a few pages, counting the items rather than returning them, and reports how many items it read,
+/// how many the filter kept, and how many read capacity units it consumed.
+/// Finally, suggests how to read the items more cheaply, such as querying an index,
+/// or a key that avoids a filter that discards most of the items it reads.
+/// # Arguments
+///
+/// * `-t TABLE` - The name of the table.
+/// * `[-i INDEX]` - The index to read, instead of the table.
+/// * `[-w CONDITION]...` - A condition on the items, written as NAME OPERATOR [VALUE], such as **"year = 2013"**.
+///   The operators are =, <>, <, <=, >, >=, between, begins_with, contains, exists, and not_exists.
+///   Values that look like numbers are numbers, unless the attribute is a string key, or they're quoted.
+///   If supplied more than once, the items must match all of the conditions.
+/// * `[-c]` - Whether to use strongly consistent reads, which cost twice as much.
+/// * `[-p PAGES]` - The most pages to read in the sample run, each up to 1 MB.
+///   If not supplied, defaults to **5**.
+/// * `[-r REGION]` - The region in which the client is created.
+///    If not supplied, uses the value of the **AWS_DEFAULT_REGION** environment variable.
+///    If the environment variable is not set, defaults to **us-west-2**.
+/// * `[--output FORMAT]` - How to display the result, **text** (the default) or **json**.
+/// * `[--profile PROFILE]` - The AWS profile to use from the shared credentials file.
+/// * `[-v]` - Whether to display additional information.
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn Error>> {
+    let Opt {
+        conditions,
+        consistent,
+        index,
+        output,
+        pages,
+        profile,
+        region,
+        table,
+        verbose,
+    } = Opt::from_args();
+
+    let region = EnvironmentProvider::new()
+        .region()
+        .or_else(|| region.as_ref().map(|region| Region::new(region.clone())))
+        .unwrap_or_else(|| Region::new("us-west-2"));
+
+    if pages == 0 {
+        return Err("the sample run must read at least 1 page".into());
+    }
+
+    if verbose {
+        println!("DynamoDB client version: {}", dynamodb::PKG_VERSION);
+        println!("Region:                  {:?}", &region);
+        println!("Table:                   {}", &table);
+        println!("Index:                   {:?}", &index);
+        println!("Conditions:              {:?}", &conditions);
+        println!("Consistent:              {}", consistent);
+        println!("Pages:                   {}", pages);
+
+        SubscriberBuilder::default()
+            .with_env_filter("info")
+            .with_span_events(FmtSpan::CLOSE)
+            .init();
+    }
+
+    let mut config = Config::builder().region(region);
+    if let Some(profile) = profile {
+        config = config.credentials_provider(ProfileCredentialsProvider::new(profile));
+    }
+
+    // Sends the requests to DynamoDB Local or LocalStack if AWS_ENDPOINT_URL is set.
+    if let Some(uri) = endpoint::from_env()? {
+        config = config.endpoint_resolver(Endpoint::immutable(uri));
+    }
+
+    let client = Client::from_conf(config.build());
+
+    let description = client
+        .describe_table()
+        .table_name(&table)
+        .send()
+        .await?
+        .table
+        .ok_or("DynamoDB did not return the table description")?;
+
+    let table_keys =
+        keys(description.key_schema.clone()).ok_or("the table has no partition key")?;
+
+    let global_indexes: Vec<(String, Keys)> = description
+        .global_secondary_indexes
+        .clone()
+        .unwrap_or_default()
+        .into_iter()
+        .filter_map(|index| Some((index.index_name?, keys(index.key_schema)?)))
+        .collect();
+    let local_indexes: Vec<(String, Keys)> = description
+        .local_secondary_indexes
+        .clone()
+        .unwrap_or_default()
+        .into_iter()
+        .filter_map(|index| Some((index.index_name?, keys(index.key_schema)?)))
+        .collect();
+
+    let target_keys = match &index {
+        Some(index) => {
+            if consistent && global_indexes.iter().any(|(name, _)| name == index) {
+                return Err(format!(
+                    "{} is a global secondary index, which only supports eventually consistent reads",
+                    index
+                )
+                .into());
+            }
+
+            global_indexes
+                .iter()
+                .chain(&local_indexes)
+                .find(|(name, _)| name == index)
+                .map(|(_, keys)| keys.clone())
+                .ok_or_else(|| format!("table {} has no index named {}", &table, index))?
+        }
+        None => table_keys.clone(),
+    };
+
+    // A key attribute that's a string is compared as a string, even if the value looks like a number.
+    let strings: Vec<String> = description
+        .attribute_definitions
+        .clone()
+        .unwrap_or_default()
+        .into_iter()
+        .filter(|definition| definition.attribute_type == Some(ScalarAttributeType::S))
+        .filter_map(|definition| definition.attribute_name)
+        .collect();
+    let conditions: Vec<Condition> = conditions
+        .into_iter()
+        .map(|condition| retype(condition, &strings))
+        .collect();
+
+    let plan = plan(conditions.clone(), &target_keys, index.clone())?;
+
+    let mut expression = Expression::builder();
+    if let Some(key_condition) = plan.key_condition.clone() {
+        expression = expression.key_condition(key_condition);
+    }
+    if let Some(filter) = plan.filter.clone() {
+        expression = expression.filter(filter);
+    }
+    let expression = expression.build();
+
+    let sample = sample(&client, &table, &plan, &expression, consistent, pages).await?;
+
+    let mut suggestions = Vec::new();
+    let mut estimated_capacity_units = None;
+
+    if plan.operation == Operation::Scan {
+        suggestions.push(format!(
+            "A Scan reads every item in the {}, about {} items, however few match. \
+             Add a condition on the partition key, {}, with =, to make it a Query.",
+            if plan.index.is_some() {
+                "index"
+            } else {
+                "table"
+            },
+            description.item_count,
+            &target_keys.partition
+        ));
+
+        let indexes: Vec<(String, Keys)> = global_indexes
+            .iter()
+            .chain(&local_indexes)
+            .filter(|(name, _)| Some(name) != plan.index.as_ref())
+            .cloned()
+            .collect();
+        let mut candidates: Vec<String> = queryable_indexes(&conditions, &indexes)
+            .into_iter()
+            .map(|name| format!("index {} (-i {})", name, name))
+            .collect();
+        if plan.index.is_some()
+            && equality_attributes(&conditions).contains(&table_keys.partition.as_str())
+        {
+            candidates.push("the table, without -i".to_string());
+        }
+
+        if !candidates.is_empty() {
+            suggestions.push(format!(
+                "These conditions can be a Query of {}.",
+                candidates.join(", or ")
+            ));
+        } else if let Some(attribute) = equality_attributes(&conditions).first() {
+            suggestions.push(format!(
+                "If you often read items by {}, a global secondary index with {} as its partition key \
+                 would turn this Scan into a Query.",
+                attribute, attribute
+            ));
+        }
+
+        if !sample.complete && plan.index.is_none() {
+            // The table size is updated about every six hours, so this is only an estimate.
+            let units = description.table_size_bytes as f64 / READ_UNIT_BYTES;
+            estimated_capacity_units = Some(if consistent { units } else { units / 2.0 });
+        }
+    }
+
+    if plan.filter.is_some() && sample.items_read > 0 {
+        let discarded = 1.0 - sample.items_returned as f64 / sample.items_read as f64;
+
+        if discarded >= WASTEFUL_FILTER {
+            let advice = match (plan.operation, &target_keys.sort) {
+                (Operation::Query, Some(_)) => {
+                    "A local secondary index, with the filtered attribute as its sort key, \
+                     would let the Query read only the items it keeps."
+                }
+                (Operation::Query, None) => {
+                    "A sort key on the filtered attribute would let the Query read only the items it keeps."
+                }
+                (Operation::Scan, _) => "A Query with a key condition reads far fewer items.",
+            };
+
+            suggestions.push(format!(
+                "The filter discards {:.0}% of the items read, which are paid for all the same. {}",
+                discarded * 100.0,
+                advice
+            ));
+        }
+    }
+
+    if consistent {
+        suggestions.push(
+            "Strongly consistent reads cost twice as much as eventually consistent ones. \
+             Leave out -c if reading a write from a moment ago doesn't matter."
+                .to_string(),
+        );
+    }
+
+    if suggestions.is_empty() {
+        suggestions.push("This access pattern reads only the items it returns.".to_string());
+    }
+
+    output.print(&Explanation {
+        table,
+        index: plan.index,
+        operation: plan.operation,
+        key_condition: plan.key_condition.as_ref().map(describe),
+        filter: plan.filter.as_ref().map(describe),
+        consistent,
+        sample,
+        estimated_capacity_units,
+        suggestions,
+    });
+
+    Ok(())
+}
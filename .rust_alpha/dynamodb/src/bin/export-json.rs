@@ -0,0 +1,234 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0.
+ */
+
+use std::error::Error;
+use std::fmt;
+use std::fs::File;
+use std::io::{self, BufWriter, Write};
+use std::time::Instant;
+
+use dynamodb::{Client, Config, Endpoint, Region};
+use dynamodb_code_examples::items::item_to_json;
+
+use aws_types::region::{EnvironmentProvider, ProvideRegion};
+
+use common::endpoint;
+use common::output::OutputFormat;
+use common::profile::ProfileCredentialsProvider;
+use serde::Serialize;
+use serde_json::Value;
+use structopt::StructOpt;
+use tracing_subscriber::fmt::format::FmtSpan;
+use tracing_subscriber::fmt::SubscriberBuilder;
+
+#[derive(Debug, StructOpt)]
+struct Opt {
+    /// The region
+    #[structopt(short, long)]
+    region: Option<String>,
+
+    /// The name of the table
+    #[structopt(short, long)]
+    table: String,
+
+    /// The file to write the items to. Defaults to standard output
+    #[structopt(short, long)]
+    file: Option<String>,
+
+    /// How many segments to scan the table in, in parallel
+    #[structopt(long, default_value = "1")]
+    segments: i32,
+
+    /// Whether to indent the JSON
+    #[structopt(long)]
+    pretty: bool,
+
+    /// The format of the output, text or json
+    #[structopt(long, default_value = "text")]
+    output: OutputFormat,
+
+    /// The AWS profile to use from the shared credentials file
+    #[structopt(long)]
+    profile: Option<String>,
+
+    /// Whether to display additional information
+    #[structopt(short, long)]
+    verbose: bool,
+}
+
+/// The items that were exported.
+#[derive(Debug, Serialize)]
+struct ExportedTable {
+    table: String,
+    file: String,
+    items: usize,
+    segments: i32,
+    seconds: f64,
+}
+
+impl fmt::Display for ExportedTable {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "Exported {} items from table {} to {} in {:.1} seconds, using {} segments",
+            self.items, self.table, self.file, self.seconds, self.segments
+        )
+    }
+}
+
+/// Scans one segment of the table, a page at a time, and converts the items to JSON objects.
+async fn scan_segment(
+    client: Client,
+    table: String,
+    segment: i32,
+    segments: i32,
+) -> Result<Vec<Value>, dynamodb::Error> {
+    let mut items = Vec::new();
+    let mut start_key = None;
+
+    loop {
+        let resp = client
+            .scan()
+            .table_name(&table)
+            .segment(segment)
+            .total_segments(segments)
+            .set_exclusive_start_key(start_key)
+            .send()
+            .await?;
+
+        items.extend(resp.items.unwrap_or_default().into_iter().map(item_to_json));
+
+        start_key = resp.last_evaluated_key;
+        if start_key.is_none() {
+            return Ok(items);
+        }
+    }
+}
+
+/// Exports the items in an Amazon DynamoDB table as a JSON array of plain objects,
+/// which the __import-json__ example loads back into a table.
+/// Each attribute value becomes the closest JSON type: numbers become numbers, maps become objects,
+/// and lists and sets become arrays. Binary values become base64 strings.
+/// The items are written in the order that the scan returns them, which isn't sorted.
+/// # Arguments
+///
+/// * `-t TABLE` - The name of the table.
+/// * `[-f FILE]` - The file to write the items to.
+///   If not supplied, writes the items to standard output, and doesn't display a summary.
+/// * `[--segments SEGMENTS]` - How many segments to scan the table in, in parallel.
+///   If not supplied, defaults to **1**.
+/// * `[--pretty]` - Whether to indent the JSON.
+/// * `[-r REGION]` - The region in which the table is located.
+///   If not supplied, uses the value of the **AWS_DEFAULT_REGION** environment variable.
+///   If the environment variable is not set, defaults to **us-west-2**.
+/// * `[--output FORMAT]` - How to display the result, **text** (the default) or **json**.
+/// * `[--profile PROFILE]` - The AWS profile to use from the shared credentials file.
+/// * `[-v]` - Whether to display additional information, to standard error.
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn Error>> {
+    let Opt {
+        file,
+        output,
+        pretty,
+        profile,
+        region,
+        segments,
+        table,
+        verbose,
+    } = Opt::from_args();
+
+    let region = EnvironmentProvider::new()
+        .region()
+        .or_else(|| region.as_ref().map(|region| Region::new(region.clone())))
+        .unwrap_or_else(|| Region::new("us-west-2"));
+
+    if !(1..=1_000_000).contains(&segments) {
+        return Err(format!(
+            "the number of segments must be from 1 to 1,000,000, got {}",
+            segments
+        )
+        .into());
+    }
+
+    // The items can go to standard output, so the additional information goes to standard error.
+    if verbose {
+        eprintln!("DynamoDB client version: {}", dynamodb::PKG_VERSION);
+        eprintln!("Region:                  {:?}", &region);
+        eprintln!("Table:                   {}", &table);
+        eprintln!("File:                    {:?}", &file);
+        eprintln!("Segments:                {}", segments);
+
+        SubscriberBuilder::default()
+            .with_env_filter("info")
+            .with_span_events(FmtSpan::CLOSE)
+            .with_writer(io::stderr)
+            .init();
+    }
+
+    let mut config = Config::builder().region(region);
+    if let Some(profile) = profile {
+        config = config.credentials_provider(ProfileCredentialsProvider::new(profile));
+    }
+
+    // Sends the requests to DynamoDB Local or LocalStack if AWS_ENDPOINT_URL is set.
+    if let Some(uri) = endpoint::from_env()? {
+        config = config.endpoint_resolver(Endpoint::immutable(uri));
+    }
+
+    let client = Client::from_conf(config.build());
+
+    let start = Instant::now();
+
+    let handles: Vec<_> = (0..segments)
+        .map(|segment| {
+            tokio::spawn(scan_segment(
+                client.clone(),
+                table.clone(),
+                segment,
+                segments,
+            ))
+        })
+        .collect();
+
+    let mut items = Vec::new();
+    for (segment, handle) in handles.into_iter().enumerate() {
+        let scanned = handle.await??;
+
+        if verbose {
+            eprintln!("Scanned {} items in segment {}", scanned.len(), segment);
+        }
+
+        items.extend(scanned);
+    }
+
+    let count = items.len();
+    let items = Value::Array(items);
+
+    let writer: Box<dyn Write> = match &file {
+        Some(file) => Box::new(File::create(file)?),
+        None => Box::new(io::stdout()),
+    };
+    let mut writer = BufWriter::new(writer);
+
+    if pretty {
+        serde_json::to_writer_pretty(&mut writer, &items)?;
+    } else {
+        serde_json::to_writer(&mut writer, &items)?;
+    }
+    writeln!(writer)?;
+    writer.flush()?;
+
+    if let Some(file) = file {
+        output.print(&ExportedTable {
+            table,
+            file,
+            items: count,
+            segments,
+            seconds: start.elapsed().as_secs_f64(),
+        });
+    }
+
+    Ok(())
+}
@@ -0,0 +1,168 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0.
+ */
+
+use std::error::Error;
+use std::fmt;
+use std::fs;
+use std::io::{self, Read};
+
+use dynamodb::{Client, Config, Endpoint, Region};
+use dynamodb_code_examples::batch::{write_batch, BATCH_SIZE};
+use dynamodb_code_examples::items::json_to_items;
+
+use aws_types::region::{EnvironmentProvider, ProvideRegion};
+
+use common::endpoint;
+use common::output::OutputFormat;
+use common::profile::ProfileCredentialsProvider;
+use serde::Serialize;
+use structopt::StructOpt;
+use tracing_subscriber::fmt::format::FmtSpan;
+use tracing_subscriber::fmt::SubscriberBuilder;
+
+#[derive(Debug, StructOpt)]
+struct Opt {
+    /// The region
+    #[structopt(short, long)]
+    region: Option<String>,
+
+    /// The name of the table
+    #[structopt(short, long)]
+    table: String,
+
+    /// The file to read the items from, a JSON array of objects. Defaults to standard input
+    #[structopt(short, long)]
+    file: Option<String>,
+
+    /// The format of the output, text or json
+    #[structopt(long, default_value = "text")]
+    output: OutputFormat,
+
+    /// The AWS profile to use from the shared credentials file
+    #[structopt(long)]
+    profile: Option<String>,
+
+    /// Whether to display additional information
+    #[structopt(short, long)]
+    verbose: bool,
+}
+
+/// The items that were imported.
+#[derive(Debug, Serialize)]
+struct ImportedItems {
+    table: String,
+    file: String,
+    items: usize,
+    batches: usize,
+    retries: u32,
+}
+
+impl fmt::Display for ImportedItems {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "Imported {} items from {} into table {} in {} batches",
+            self.items, self.file, self.table, self.batches
+        )?;
+
+        if self.retries > 0 {
+            write!(f, ", resending unprocessed items {} times", self.retries)?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Imports the items that the __export-json__ example exported into an Amazon DynamoDB table,
+/// converting each JSON value back into an attribute value, and writing the items with BatchWriteItem.
+/// Numbers become __N__, strings __S__, booleans __BOOL__, nulls __NULL__, arrays __L__, and objects __M__.
+/// JSON doesn't say which strings were binary values, or which arrays were sets,
+/// so those come back as strings and lists.
+/// Each item must include the table's key attributes. Items with the same key are overwritten.
+/// # Arguments
+///
+/// * `-t TABLE` - The name of the table.
+/// * `[-f FILE]` - The file to read the items from, a JSON array of objects.
+///   If not supplied, reads the items from standard input.
+/// * `[-r REGION]` - The region in which the table is located.
+///   If not supplied, uses the value of the **AWS_DEFAULT_REGION** environment variable.
+///   If the environment variable is not set, defaults to **us-west-2**.
+/// * `[--output FORMAT]` - How to display the result, **text** (the default) or **json**.
+/// * `[--profile PROFILE]` - The AWS profile to use from the shared credentials file.
+/// * `[-v]` - Whether to display additional information.
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn Error>> {
+    let Opt {
+        file,
+        output,
+        profile,
+        region,
+        table,
+        verbose,
+    } = Opt::from_args();
+
+    let region = EnvironmentProvider::new()
+        .region()
+        .or_else(|| region.as_ref().map(|region| Region::new(region.clone())))
+        .unwrap_or_else(|| Region::new("us-west-2"));
+
+    let data = match &file {
+        Some(file) => fs::read_to_string(file)?,
+        None => {
+            let mut data = String::new();
+            io::stdin().read_to_string(&mut data)?;
+            data
+        }
+    };
+    let items = json_to_items(serde_json::from_str(&data)?)?;
+
+    if verbose {
+        println!("DynamoDB client version: {}", dynamodb::PKG_VERSION);
+        println!("Region:                  {:?}", &region);
+        println!("Table:                   {}", &table);
+        println!("File:                    {:?}", &file);
+        println!("Items:                   {}", items.len());
+
+        SubscriberBuilder::default()
+            .with_env_filter("info")
+            .with_span_events(FmtSpan::CLOSE)
+            .init();
+    }
+
+    let mut config = Config::builder().region(region);
+    if let Some(profile) = profile {
+        config = config.credentials_provider(ProfileCredentialsProvider::new(profile));
+    }
+
+    // Sends the requests to DynamoDB Local or LocalStack if AWS_ENDPOINT_URL is set.
+    if let Some(uri) = endpoint::from_env()? {
+        config = config.endpoint_resolver(Endpoint::immutable(uri));
+    }
+
+    let client = Client::from_conf(config.build());
+
+    let count = items.len();
+    let mut batches = 0;
+    let mut retries = 0;
+
+    for chunk in items.chunks(BATCH_SIZE) {
+        retries += write_batch(&client, &table, chunk.to_vec()).await?;
+        batches += 1;
+
+        if verbose {
+            println!("Wrote batch {}", batches);
+        }
+    }
+
+    output.print(&ImportedItems {
+        table,
+        file: file.unwrap_or_else(|| "standard input".to_string()),
+        items: count,
+        batches,
+        retries,
+    });
+
+    Ok(())
+}
@@ -3,12 +3,21 @@
  * SPDX-License-Identifier: Apache-2.0.
  */
 
-use std::process;
+use std::error::Error;
+use std::fmt;
 
-use dynamodb::{Client, Config, Region};
+use dynamodb::model::AttributeValue;
+use dynamodb::{Client, Config, Endpoint, Region};
+use dynamodb_code_examples::expressions::{Condition, Expression};
+use dynamodb_code_examples::items::item_to_json;
 
 use aws_types::region::{EnvironmentProvider, ProvideRegion};
 
+use common::endpoint;
+use common::output::OutputFormat;
+use common::profile::ProfileCredentialsProvider;
+use serde::Serialize;
+use serde_json::Value;
 use structopt::StructOpt;
 use tracing_subscriber::fmt::format::FmtSpan;
 use tracing_subscriber::fmt::SubscriberBuilder;
@@ -22,22 +31,68 @@ struct Opt {
     #[structopt(short, long)]
     table: String,
 
+    /// Only list items whose attribute has this string value, as NAME=VALUE. Can be repeated
+    #[structopt(short, long, parse(try_from_str = parse_filter))]
+    filter: Vec<(String, String)>,
+
+    /// The format of the output, text or json
+    #[structopt(long, default_value = "text")]
+    output: OutputFormat,
+
+    /// The AWS profile to use from the shared credentials file
+    #[structopt(long)]
+    profile: Option<String>,
+
     #[structopt(short, long)]
     verbose: bool,
 }
 
+/// The items in a table, as plain JSON objects.
+#[derive(Debug, Serialize)]
+struct ItemList {
+    table: String,
+    items: Vec<Value>,
+}
+
+impl fmt::Display for ItemList {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Items in table {}:", self.table)?;
+
+        for item in &self.items {
+            write!(f, "\n   {}", item)?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Splits a NAME=VALUE filter argument.
+fn parse_filter(s: &str) -> Result<(String, String), String> {
+    match s.split_once('=') {
+        Some((name, value)) if !name.is_empty() => Ok((name.to_string(), value.to_string())),
+        _ => Err(format!("filter must be NAME=VALUE, got {}", s)),
+    }
+}
+
 /// Lists the items in a DynamoDB table.
 /// # Arguments
 ///
 /// * `-t TABLE` - The name of the table.
+/// * `[-f NAME=VALUE]...` - Only list the items whose attribute NAME is the string VALUE.
+///   If supplied more than once, lists the items that match all of the filters.
 /// * `[-d DEFAULT-REGION]` - The region in which the client is created.
 ///    If not supplied, uses the value of the **AWS_DEFAULT_REGION** environment variable.
 ///    If the environment variable is not set, defaults to **us-west-2**.
+/// * `[--output FORMAT]` - How to display the result, **text** (the default) or **json**.
+/// * `[--profile PROFILE]` - The AWS profile to use from the shared credentials file.
 /// * `[-v]` - Whether to display additional information.
 #[tokio::main]
-async fn main() {
+async fn main() -> Result<(), Box<dyn Error>> {
     let Opt {
+        filter,
+        profile,
         table,
+        output,
         region,
         verbose,
     } = Opt::from_args();
@@ -51,6 +106,7 @@ async fn main() {
         println!("DynamoDB client version: {}\n", dynamodb::PKG_VERSION);
         println!("Region: {:?}", &region);
         println!("Table:  {}", table);
+        println!("Filter: {:?}", filter);
 
         SubscriberBuilder::default()
             .with_env_filter("info")
@@ -58,26 +114,50 @@ async fn main() {
             .init();
     }
 
-    let config = Config::builder().region(region).build();
+    let mut config = Config::builder().region(region);
+    if let Some(profile) = profile {
+        config = config.credentials_provider(ProfileCredentialsProvider::new(profile));
+    }
 
-    let client = Client::from_conf(config);
+    // Sends the requests to DynamoDB Local or LocalStack if AWS_ENDPOINT_URL is set.
+    if let Some(uri) = endpoint::from_env()? {
+        config = config.endpoint_resolver(Endpoint::immutable(uri));
+    }
 
-    let t = &table;
+    let client = Client::from_conf(config.build());
 
-    match client.scan().table_name(t).send().await {
-        Ok(resp) => {
-            println!("Items in table {}:", table);
+    let t = &table;
 
-            let items = resp.items.unwrap_or_default();
+    // The filter is applied after the items are read, so a filtered scan reads, and is charged for,
+    // the whole table.
+    let filter = filter
+        .into_iter()
+        .map(|(name, value)| Condition::eq(name, AttributeValue::S(value)))
+        .reduce(Condition::and);
 
-            for item in items {
-                println!("   {:?}", item);
-            }
-        }
-        Err(e) => {
-            println!("Got an error listing items:");
-            println!("{}", e);
-            process::exit(1);
-        }
-    };
+    let mut expression = Expression::builder();
+    if let Some(filter) = filter {
+        expression = expression.filter(filter);
+    }
+    let expression = expression.build();
+
+    let resp = client
+        .scan()
+        .table_name(t)
+        .set_filter_expression(expression.filter)
+        .set_expression_attribute_names(expression.names)
+        .set_expression_attribute_values(expression.values)
+        .send()
+        .await?;
+
+    let items = resp
+        .items
+        .unwrap_or_default()
+        .into_iter()
+        .map(item_to_json)
+        .collect();
+
+    output.print(&ItemList { table, items });
+
+    Ok(())
 }
@@ -3,12 +3,17 @@
  * SPDX-License-Identifier: Apache-2.0.
  */
 
-use std::process;
+use std::error::Error;
+use std::fmt;
 
-use dynamodb::{Client, Config, Region};
+use dynamodb::{Client, Config, Endpoint, Region};
 
 use aws_types::region::{EnvironmentProvider, ProvideRegion};
 
+use common::endpoint;
+use common::output::OutputFormat;
+use common::profile::ProfileCredentialsProvider;
+use serde::Serialize;
 use structopt::StructOpt;
 use tracing_subscriber::fmt::format::FmtSpan;
 use tracing_subscriber::fmt::SubscriberBuilder;
@@ -19,20 +24,53 @@ struct Opt {
     #[structopt(short, long)]
     region: Option<String>,
 
+    /// The format of the output, text or json
+    #[structopt(long, default_value = "text")]
+    output: OutputFormat,
+
+    /// The AWS profile to use from the shared credentials file
+    #[structopt(long)]
+    profile: Option<String>,
+
     #[structopt(short, long)]
     verbose: bool,
 }
 
+/// The names of your tables.
+#[derive(Debug, Serialize)]
+struct TableList {
+    tables: Vec<String>,
+}
+
+impl fmt::Display for TableList {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "Tables:")?;
+
+        for name in &self.tables {
+            writeln!(f, "  {}", name)?;
+        }
+
+        write!(f, "Found {} tables", self.tables.len())
+    }
+}
+
 /// Lists your DynamoDB tables.
 /// # Arguments
 ///
 /// * `[-d DEFAULT-REGION]` - The region in which the client is created.
 ///    If not supplied, uses the value of the **AWS_DEFAULT_REGION** environment variable.
 ///    If the environment variable is not set, defaults to **us-west-2**.
+/// * `[--output FORMAT]` - How to display the result, **text** (the default) or **json**.
+/// * `[--profile PROFILE]` - The AWS profile to use from the shared credentials file.
 /// * `[-v]` - Whether to display additional information.
 #[tokio::main]
-async fn main() {
-    let Opt { region, verbose } = Opt::from_args();
+async fn main() -> Result<(), Box<dyn Error>> {
+    let Opt {
+        output,
+        profile,
+        region,
+        verbose,
+    } = Opt::from_args();
 
     let region = EnvironmentProvider::new()
         .region()
@@ -49,26 +87,23 @@ async fn main() {
             .init();
     }
 
-    let config = Config::builder().region(region).build();
+    let mut config = Config::builder().region(region);
+    if let Some(profile) = profile {
+        config = config.credentials_provider(ProfileCredentialsProvider::new(profile));
+    }
 
-    let client = Client::from_conf(config);
+    // Sends the requests to DynamoDB Local or LocalStack if AWS_ENDPOINT_URL is set.
+    if let Some(uri) = endpoint::from_env()? {
+        config = config.endpoint_resolver(Endpoint::immutable(uri));
+    }
 
-    match client.list_tables().send().await {
-        Ok(resp) => {
-            println!("Tables:");
+    let client = Client::from_conf(config.build());
 
-            let names = resp.table_names.unwrap_or_default();
+    let resp = client.list_tables().send().await?;
 
-            for name in &names {
-                println!("  {}", name);
-            }
+    let tables = resp.table_names.unwrap_or_default();
 
-            println!("Found {} tables", names.len());
-        }
-        Err(e) => {
-            println!("Got an error listing tables:");
-            println!("{}", e);
-            process::exit(1);
-        }
-    };
+    output.print(&TableList { tables });
+
+    Ok(())
 }
@@ -0,0 +1,576 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0.
+ */
+
+use std::collections::HashMap;
+use std::error::Error;
+use std::fmt;
+use std::fs;
+use std::path::Path;
+
+use dynamodb::error::UpdateItemErrorKind;
+use dynamodb::model::AttributeValue;
+use dynamodb::{Blob, Client, Config, Endpoint, Region, SdkError};
+use dynamodb_code_examples::expressions::{Condition, Expression, Update};
+use dynamodb_code_examples::items::to_attribute;
+
+use aws_types::region::{EnvironmentProvider, ProvideRegion};
+
+use common::endpoint;
+use common::output::OutputFormat;
+use common::profile::ProfileCredentialsProvider;
+use common::shutdown::Shutdown;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Map, Value};
+use structopt::StructOpt;
+use tracing_subscriber::fmt::format::FmtSpan;
+use tracing_subscriber::fmt::SubscriberBuilder;
+
+/// How many times to read an item again and retry, when someone else changes it between the read and the write.
+const MAX_CONFLICT_RETRIES: u32 = 3;
+
+#[derive(Debug, StructOpt)]
+struct Opt {
+    /// The region
+    #[structopt(short, long)]
+    region: Option<String>,
+
+    /// The name of the table
+    #[structopt(short, long)]
+    table: String,
+
+    /// Renames an attribute, as OLD=NEW. Repeat to rename several
+    #[structopt(long, parse(try_from_str = parse_rename))]
+    rename: Vec<(String, String)>,
+
+    /// Adds an attribute to the items that don't have it, as NAME=JSON, such as status="active". Repeat to add several
+    #[structopt(long, parse(try_from_str = parse_backfill))]
+    backfill: Vec<(String, Value)>,
+
+    /// Removes an attribute. Repeat to remove several
+    #[structopt(long)]
+    remove: Vec<String>,
+
+    /// The file that records how far the migration got, so that it can carry on from there
+    #[structopt(short, long)]
+    checkpoint: String,
+
+    /// How many items to read in each page of the scan
+    #[structopt(long, default_value = "100")]
+    page_size: i32,
+
+    /// Whether to display the changes, rather than make them
+    #[structopt(long)]
+    dry_run: bool,
+
+    /// The format of the output, text or json
+    #[structopt(long, default_value = "text")]
+    output: OutputFormat,
+
+    /// The AWS profile to use from the shared credentials file
+    #[structopt(long)]
+    profile: Option<String>,
+
+    /// Whether to display additional information
+    #[structopt(short, long)]
+    verbose: bool,
+}
+
+/// Reads OLD=NEW.
+fn parse_rename(s: &str) -> Result<(String, String), String> {
+    match s.split_once('=') {
+        Some((old, new)) if !old.is_empty() && !new.is_empty() && old != new => {
+            Ok((old.to_string(), new.to_string()))
+        }
+        _ => Err(format!("{} is not OLD=NEW", s)),
+    }
+}
+
+/// Reads NAME=JSON. A value that isn't valid JSON is taken as a string, so status=active works without quotes.
+fn parse_backfill(s: &str) -> Result<(String, Value), String> {
+    match s.split_once('=') {
+        Some((name, value)) if !name.is_empty() => {
+            let value = serde_json::from_str(value).unwrap_or_else(|_| json!(value));
+            Ok((name.to_string(), value))
+        }
+        _ => Err(format!("{} is not NAME=VALUE", s)),
+    }
+}
+
+/// The changes to make to every item.
+struct Migration {
+    /// The names of the table's key attributes, which identify the item to update.
+    key_names: Vec<String>,
+    rename: Vec<(String, String)>,
+    backfill: Vec<(String, AttributeValue)>,
+    remove: Vec<String>,
+}
+
+impl Migration {
+    /// Returns the update that migrates the item, and the condition that the item must still meet
+    /// for the update to be safe: every attribute that the update reads or replaces still has the value
+    /// that was read, and every attribute that it adds is still missing. Returns `None` if the item
+    /// has already been migrated, so running the migration again changes nothing.
+    fn plan(&self, item: &HashMap<String, AttributeValue>) -> Option<(Update, Condition)> {
+        let mut update = Update::new();
+        let mut conditions = Vec::new();
+
+        for (old, new) in &self.rename {
+            if let (Some(value), false) = (item.get(old), item.contains_key(new)) {
+                update = update.set(new, value.clone()).remove(old);
+                conditions.push(Condition::eq(old, value.clone()));
+                conditions.push(Condition::not_exists(new));
+            }
+        }
+
+        for (name, value) in &self.backfill {
+            if !item.contains_key(name) {
+                update = update.set(name, value.clone());
+                conditions.push(Condition::not_exists(name));
+            }
+        }
+
+        for name in &self.remove {
+            if let Some(value) = item.get(name) {
+                update = update.remove(name);
+                conditions.push(Condition::eq(name, value.clone()));
+            }
+        }
+
+        let mut conditions = conditions.into_iter();
+        let first = conditions.next()?;
+
+        Some((update, conditions.fold(first, Condition::and)))
+    }
+}
+
+/// How far the migration has got. It's written after each page of the scan, so that a migration that stops,
+/// for whatever reason, can carry on from the last page it finished.
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct Checkpoint {
+    table: String,
+    /// Where the next page of the scan starts, in the typed form that DynamoDB uses, such as {"id": {"S": "a"}}.
+    /// `None` before the first page, and after the last one.
+    last_evaluated_key: Option<Map<String, Value>>,
+    complete: bool,
+    #[serde(flatten)]
+    counts: Counts,
+}
+
+/// What the migration has done to the items so far.
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+struct Counts {
+    scanned: u64,
+    migrated: u64,
+    unchanged: u64,
+    /// How many items changed between being read and being written, and were read again.
+    conflicts: u64,
+    /// How many items were deleted before they could be migrated.
+    vanished: u64,
+}
+
+/// The result of the migration.
+#[derive(Debug, Serialize)]
+struct Migrated {
+    table: String,
+    dry_run: bool,
+    complete: bool,
+    #[serde(flatten)]
+    counts: Counts,
+}
+
+impl fmt::Display for Migrated {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} {} of {} items in table {}; {} needed no change, {} were changed by someone else first, and {} were deleted",
+            if self.dry_run { "Would migrate" } else { "Migrated" },
+            self.counts.migrated,
+            self.counts.scanned,
+            self.table,
+            self.counts.unchanged,
+            self.counts.conflicts,
+            self.counts.vanished
+        )?;
+
+        if !self.complete {
+            write!(
+                f,
+                "\nStopped before the end of the table; run again to carry on"
+            )?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Converts a key into the typed JSON form, which keeps the difference between strings, numbers, and binary values.
+fn key_to_json(key: &HashMap<String, AttributeValue>) -> Result<Map<String, Value>, String> {
+    key.iter()
+        .map(|(name, value)| {
+            let typed = match value {
+                AttributeValue::S(s) => json!({ "S": s }),
+                AttributeValue::N(n) => json!({ "N": n }),
+                AttributeValue::B(b) => json!({ "B": base64::encode(b.as_ref()) }),
+                _ => return Err(format!("key attribute {} isn't S, N, or B", name)),
+            };
+            Ok((name.clone(), typed))
+        })
+        .collect()
+}
+
+/// Converts a key in the typed JSON form back into attribute values.
+fn key_from_json(key: &Map<String, Value>) -> Result<HashMap<String, AttributeValue>, String> {
+    key.iter()
+        .map(|(name, typed)| {
+            let value = match (typed.get("S"), typed.get("N"), typed.get("B")) {
+                (Some(Value::String(s)), _, _) => AttributeValue::S(s.clone()),
+                (_, Some(Value::String(n)), _) => AttributeValue::N(n.clone()),
+                (_, _, Some(Value::String(b))) => AttributeValue::B(Blob::new(
+                    base64::decode(b).map_err(|e| format!("key attribute {}: {}", name, e))?,
+                )),
+                _ => return Err(format!("key attribute {} isn't S, N, or B", name)),
+            };
+            Ok((name.clone(), value))
+        })
+        .collect()
+}
+
+/// Writes the checkpoint to a temporary file, and then renames it over the old one,
+/// so that a crash while writing leaves the old checkpoint rather than half of the new one.
+fn save_checkpoint(file: &str, checkpoint: &Checkpoint) -> Result<(), Box<dyn Error>> {
+    let temp = format!("{}.tmp", file);
+    fs::write(&temp, serde_json::to_string_pretty(checkpoint)?)?;
+    fs::rename(&temp, file)?;
+
+    Ok(())
+}
+
+/// Returns the names of the table's key attributes, which the migration can't change.
+async fn key_names(client: &Client, table: &str) -> Result<Vec<String>, Box<dyn Error>> {
+    let description = client
+        .describe_table()
+        .table_name(table)
+        .send()
+        .await?
+        .table
+        .ok_or("DynamoDB did not return the table description")?;
+
+    Ok(description
+        .key_schema
+        .unwrap_or_default()
+        .into_iter()
+        .filter_map(|element| element.attribute_name)
+        .collect())
+}
+
+/// Migrates one item, reading it again and retrying if someone else changes it first,
+/// and adds what happened to the counts. If `show_plan` is set, prints the update for the item.
+async fn migrate_item(
+    client: &Client,
+    table: &str,
+    migration: &Migration,
+    mut item: HashMap<String, AttributeValue>,
+    dry_run: bool,
+    show_plan: bool,
+    counts: &mut Counts,
+) -> Result<(), Box<dyn Error>> {
+    let key: HashMap<String, AttributeValue> = migration
+        .key_names
+        .iter()
+        .filter_map(|name| item.get(name).map(|value| (name.clone(), value.clone())))
+        .collect();
+
+    for attempt in 0..=MAX_CONFLICT_RETRIES {
+        let (update, condition) = match migration.plan(&item) {
+            Some(plan) => plan,
+            None => {
+                counts.unchanged += 1;
+                return Ok(());
+            }
+        };
+
+        let expression = Expression::builder()
+            .update(update)
+            .condition(condition)
+            .build();
+
+        if show_plan {
+            println!(
+                "{:?}: {}",
+                &key,
+                expression.update.as_deref().unwrap_or_default()
+            );
+        }
+
+        if dry_run {
+            counts.migrated += 1;
+            return Ok(());
+        }
+
+        match client
+            .update_item()
+            .table_name(table)
+            .set_key(Some(key.clone()))
+            .set_update_expression(expression.update)
+            .set_condition_expression(expression.condition)
+            .set_expression_attribute_names(expression.names)
+            .set_expression_attribute_values(expression.values)
+            .send()
+            .await
+        {
+            Ok(_) => {
+                counts.migrated += 1;
+                return Ok(());
+            }
+            Err(SdkError::ServiceError { err, .. })
+                if matches!(
+                    err.kind,
+                    UpdateItemErrorKind::ConditionalCheckFailedException(_)
+                ) && attempt < MAX_CONFLICT_RETRIES =>
+            {
+                counts.conflicts += 1;
+
+                // Someone else changed the item since it was read. Read it again, consistently,
+                // so the migration is planned from what's in the table now.
+                match client
+                    .get_item()
+                    .table_name(table)
+                    .set_key(Some(key.clone()))
+                    .consistent_read(true)
+                    .send()
+                    .await?
+                    .item
+                {
+                    Some(current) => item = current,
+                    None => {
+                        counts.vanished += 1;
+                        return Ok(());
+                    }
+                }
+            }
+            Err(e) => return Err(e.into()),
+        }
+    }
+
+    Err(format!(
+        "item {:?} kept changing, so it couldn't be migrated in {} attempts",
+        key,
+        MAX_CONFLICT_RETRIES + 1
+    )
+    .into())
+}
+
+/// Migrates the items in an Amazon DynamoDB table: renames attributes, adds attributes to the items that
+/// don't have them, and removes attributes. Each item is updated with a condition that every attribute the
+/// update touches is as it was read, so a change that someone else makes in the meantime isn't overwritten;
+/// instead, the item is read again and the migration retried. An item that's already migrated isn't changed,
+/// so the migration can be run again safely.
+/// After each page of the scan, the example records where the next page starts in a checkpoint file.
+/// If it stops, whether from an error or Ctrl-C, running it again with the same checkpoint carries on from there.
+/// # Arguments
+///
+/// * `-t TABLE` - The name of the table.
+/// * `[--rename OLD=NEW]` - Renames an attribute. Repeat to rename several.
+/// * `[--backfill NAME=JSON]` - Adds an attribute to the items that don't have it. Repeat to add several.
+/// * `[--remove NAME]` - Removes an attribute. Repeat to remove several.
+/// * `-c CHECKPOINT` - The file that records how far the migration got.
+/// * `[--page-size PAGE-SIZE]` - How many items to read in each page of the scan.
+///   If not supplied, defaults to **100**.
+/// * `[--dry-run]` - Whether to display the changes, rather than make them. Doesn't write the checkpoint.
+/// * `[-r REGION]` - The region in which the client is created.
+///    If not supplied, uses the value of the **AWS_DEFAULT_REGION** environment variable.
+///    If the environment variable is not set, defaults to **us-west-2**.
+/// * `[--output FORMAT]` - How to display the result, **text** (the default) or **json**.
+/// * `[--profile PROFILE]` - The AWS profile to use from the shared credentials file.
+/// * `[-v]` - Whether to display additional information.
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn Error>> {
+    let Opt {
+        backfill,
+        checkpoint: checkpoint_file,
+        dry_run,
+        output,
+        page_size,
+        profile,
+        region,
+        remove,
+        rename,
+        table,
+        verbose,
+    } = Opt::from_args();
+
+    let region = EnvironmentProvider::new()
+        .region()
+        .or_else(|| region.as_ref().map(|region| Region::new(region.clone())))
+        .unwrap_or_else(|| Region::new("us-west-2"));
+
+    if rename.is_empty() && backfill.is_empty() && remove.is_empty() {
+        return Err("nothing to do; use --rename, --backfill, or --remove".into());
+    }
+
+    if !(1..=1000).contains(&page_size) {
+        return Err(format!("the page size must be from 1 to 1000, got {}", page_size).into());
+    }
+
+    if verbose {
+        println!("DynamoDB client version: {}", dynamodb::PKG_VERSION);
+        println!("Region:                  {:?}", &region);
+        println!("Table:                   {}", &table);
+        println!("Rename:                  {:?}", &rename);
+        println!("Backfill:                {:?}", &backfill);
+        println!("Remove:                  {:?}", &remove);
+        println!("Checkpoint:              {}", &checkpoint_file);
+        println!("Page size:               {}", page_size);
+        println!("Dry run:                 {}", dry_run);
+
+        SubscriberBuilder::default()
+            .with_env_filter("info")
+            .with_span_events(FmtSpan::CLOSE)
+            .init();
+    }
+
+    let mut checkpoint = if Path::new(&checkpoint_file).exists() {
+        let checkpoint: Checkpoint =
+            serde_json::from_str(&fs::read_to_string(&checkpoint_file)?)
+                .map_err(|e| format!("couldn't read checkpoint {}: {}", &checkpoint_file, e))?;
+
+        if checkpoint.table != table {
+            return Err(format!(
+                "checkpoint {} is for table {}, not {}",
+                &checkpoint_file, checkpoint.table, &table
+            )
+            .into());
+        }
+
+        checkpoint
+    } else {
+        Checkpoint {
+            table: table.clone(),
+            ..Default::default()
+        }
+    };
+
+    if checkpoint.complete {
+        if !output.is_json() {
+            println!(
+                "The migration of table {} is already complete. Delete {} to run it again",
+                &table, &checkpoint_file
+            );
+        }
+
+        output.print(&Migrated {
+            table,
+            dry_run,
+            complete: true,
+            counts: checkpoint.counts,
+        });
+        return Ok(());
+    }
+
+    let mut config = Config::builder().region(region);
+    if let Some(profile) = profile {
+        config = config.credentials_provider(ProfileCredentialsProvider::new(profile));
+    }
+
+    // Sends the requests to LocalStack or DynamoDB Local, if AWS_ENDPOINT_URL is set.
+    if let Some(uri) = endpoint::from_env()? {
+        config = config.endpoint_resolver(Endpoint::immutable(uri));
+    }
+
+    let client = Client::from_conf(config.build());
+
+    let key_names = key_names(&client, &table).await?;
+
+    for name in rename
+        .iter()
+        .flat_map(|(old, new)| vec![old, new])
+        .chain(backfill.iter().map(|(name, _)| name))
+        .chain(remove.iter())
+    {
+        if key_names.contains(name) {
+            return Err(format!("{} is a key attribute, so it can't be changed", name).into());
+        }
+    }
+
+    let migration = Migration {
+        key_names,
+        rename,
+        backfill: backfill
+            .into_iter()
+            .map(|(name, value)| (name, to_attribute(value)))
+            .collect(),
+        remove,
+    };
+
+    // A dry run starts from the checkpoint, but doesn't move it on.
+    let mut counts = if dry_run {
+        Counts::default()
+    } else {
+        checkpoint.counts.clone()
+    };
+    let mut start_key = match &checkpoint.last_evaluated_key {
+        Some(key) => {
+            if !output.is_json() {
+                println!("Carrying on from checkpoint {}", &checkpoint_file);
+            }
+            Some(key_from_json(key)?)
+        }
+        None => None,
+    };
+
+    // The updates are listed in a dry run, or in verbose mode, unless they'd break the JSON output.
+    let show_plan = (verbose || dry_run) && !output.is_json();
+
+    // Ctrl-C stops the migration between pages, after the checkpoint is written.
+    let shutdown = Shutdown::listen();
+    let mut complete = false;
+
+    while !shutdown.is_requested() {
+        let resp = client
+            .scan()
+            .table_name(&table)
+            .limit(page_size)
+            .set_exclusive_start_key(start_key)
+            .send()
+            .await?;
+
+        for item in resp.items.unwrap_or_default() {
+            counts.scanned += 1;
+            migrate_item(
+                &client,
+                &table,
+                &migration,
+                item,
+                dry_run,
+                show_plan,
+                &mut counts,
+            )
+            .await?;
+        }
+
+        start_key = resp.last_evaluated_key;
+        complete = start_key.is_none();
+
+        if !dry_run {
+            checkpoint.last_evaluated_key = start_key.as_ref().map(key_to_json).transpose()?;
+            checkpoint.complete = complete;
+            checkpoint.counts = counts.clone();
+            save_checkpoint(&checkpoint_file, &checkpoint)?;
+        }
+
+        if complete {
+            break;
+        }
+    }
+
+    output.print(&Migrated {
+        table,
+        dry_run,
+        complete,
+        counts,
+    });
+
+    Ok(())
+}
@@ -3,25 +3,63 @@
  * SPDX-License-Identifier: Apache-2.0.
  */
 
-use aws_http::AwsErrorRetryPolicy;
-use aws_hyper::{SdkError, SdkSuccess};
 use dynamodb::client::fluent_builders::Query;
-use dynamodb::error::DescribeTableError;
-use dynamodb::input::DescribeTableInput;
 use dynamodb::model::{
     AttributeDefinition, AttributeValue, KeySchemaElement, KeyType, ProvisionedThroughput,
-    ScalarAttributeType, TableStatus,
+    ScalarAttributeType,
 };
-use dynamodb::operation::DescribeTable;
-use dynamodb::output::DescribeTableOutput;
-use dynamodb::{Config, Region};
+use dynamodb::{Client, Config, Endpoint, Region};
+use dynamodb_code_examples::expressions::{Condition, Expression};
+use dynamodb_code_examples::items::to_item;
+use dynamodb_code_examples::waiters::wait_for_table_active;
+use serde::Serialize;
 use serde_json::Value;
-use smithy_http::operation::Operation;
-use smithy_http::retry::ClassifyResponse;
-use smithy_types::retry::RetryKind;
-use std::collections::HashMap;
+use std::error::Error;
+use std::fmt;
 use std::time::Duration;
 
+use aws_types::region::{EnvironmentProvider, ProvideRegion};
+
+use common::endpoint;
+use common::output::OutputFormat;
+use common::profile::ProfileCredentialsProvider;
+use structopt::StructOpt;
+
+#[derive(Debug, StructOpt)]
+struct Opt {
+    /// The region
+    #[structopt(short, long)]
+    region: Option<String>,
+
+    /// The format of the output, text or json
+    #[structopt(long, default_value = "text")]
+    output: OutputFormat,
+
+    /// The AWS profile to use from the shared credentials file
+    #[structopt(long)]
+    profile: Option<String>,
+}
+
+/// The movies found for a year.
+#[derive(Debug, Serialize)]
+struct Movies {
+    table: String,
+    year: u16,
+    titles: Vec<String>,
+}
+
+impl fmt::Display for Movies {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "Movies from {} in {}:", self.year, self.table)?;
+
+        for title in &self.titles {
+            writeln!(f, "  {}", title)?;
+        }
+
+        write!(f, "Found {} movies", self.titles.len())
+    }
+}
+
 /// A partial reimplementation of https://docs.amazonaws.cn/en_us/amazondynamodb/latest/developerguide/GettingStarted.Ruby.html
 /// in Rust
 ///
@@ -29,69 +67,78 @@ use std::time::Duration;
 /// - Wait for table to be ready
 /// - Add a couple of rows
 /// - Query for those rows
+///
+/// # Arguments
+///
+/// * `[-r REGION]` - The region in which the table is created.
+///   If not supplied, uses the value of the **AWS_DEFAULT_REGION** environment variable.
+///   If the environment variable is not set, defaults to **us-west-2**.
+/// * `[--output FORMAT]` - How to display the result, **text** (the default) or **json**.
+/// * `[--profile PROFILE]` - The AWS profile to use from the shared credentials file.
 #[tokio::main]
-async fn main() {
+async fn main() -> Result<(), Box<dyn Error>> {
+    let Opt {
+        output,
+        profile,
+        region,
+    } = Opt::from_args();
+
+    let region = EnvironmentProvider::new()
+        .region()
+        .or_else(|| region.as_ref().map(|region| Region::new(region.clone())))
+        .unwrap_or_else(|| Region::new("us-west-2"));
+
+    let mut config = Config::builder().region(region);
+    if let Some(profile) = profile {
+        config = config.credentials_provider(ProfileCredentialsProvider::new(profile));
+    }
+
+    // Sends the requests to DynamoDB Local or LocalStack if AWS_ENDPOINT_URL is set.
+    if let Some(uri) = endpoint::from_env()? {
+        config = config.endpoint_resolver(Endpoint::immutable(uri));
+    }
+
     let table_name = "dynamo-movies-example";
-    let conf = dynamodb::Config::builder()
-        .region(Region::new("us-east-1"))
-        .build();
-    let client = dynamodb::Client::from_conf(conf);
-    let raw_client = aws_hyper::Client::https();
+    let client = Client::from_conf(config.build());
 
     let table_exists = client
         .list_tables()
         .send()
-        .await
-        .expect("should succeed")
+        .await?
         .table_names
-        .as_ref()
-        .unwrap()
+        .unwrap_or_default()
         .contains(&table_name.to_string());
 
     if !table_exists {
-        create_table(&client, table_name)
-            .send()
-            .await
-            .expect("failed to create table");
+        create_table(&client, table_name).send().await?;
     }
 
-    raw_client
-        .call(wait_for_ready_table(table_name, client.conf()))
-        .await
-        .expect("table should become ready");
+    wait_for_table_active(&client, table_name, Duration::from_secs(60)).await?;
 
     // data.json contains 2 movies from 2013
-    let data = match serde_json::from_str(include_str!("data.json")).expect("should be valid JSON")
-    {
+    let data = match serde_json::from_str(include_str!("data.json"))? {
         Value::Array(inner) => inner,
-        data => panic!("data must be an array, got: {:?}", data),
+        data => return Err(format!("data must be an array, got: {:?}", data).into()),
     };
     for value in data {
         client
             .put_item()
             .table_name(table_name)
-            .set_item(Some(parse_item(value)))
+            .set_item(Some(to_item(&value)?))
             .send()
-            .await
-            .expect("failed to insert item");
+            .await?;
     }
-    let films_2222 = movies_in_year(&client, table_name, 2222)
-        .send()
-        .await
-        .expect("query should succeed");
+    let films_2222 = movies_in_year(&client, table_name, 2222).send().await?;
     // this isn't back to the future, there are no movies from 2022
     assert_eq!(films_2222.count, 0);
 
-    let films_2013 = movies_in_year(&client, table_name, 2013)
-        .send()
-        .await
-        .expect("query should succeed");
+    let films_2013 = movies_in_year(&client, table_name, 2013).send().await?;
     assert_eq!(films_2013.count, 2);
     let titles: Vec<AttributeValue> = films_2013
         .items
-        .unwrap()
+        .unwrap_or_default()
         .into_iter()
-        .map(|mut row| row.remove("title").expect("row should have title"))
+        .filter_map(|mut row| row.remove("title"))
         .collect();
     assert_eq!(
         titles,
@@ -100,10 +147,24 @@ async fn main() {
             AttributeValue::S("Turn It Down, Or Else!".to_string())
         ]
     );
+
+    output.print(&Movies {
+        table: table_name.to_string(),
+        year: 2013,
+        titles: titles
+            .into_iter()
+            .filter_map(|title| match title {
+                AttributeValue::S(title) => Some(title),
+                _ => None,
+            })
+            .collect(),
+    });
+
+    Ok(())
 }
 
 fn create_table(
-    client: &dynamodb::Client,
+    client: &Client,
     table_name: &str,
 ) -> dynamodb::client::fluent_builders::CreateTable {
     client
@@ -141,89 +202,16 @@ fn create_table(
         )
 }
 
-fn parse_item(value: Value) -> HashMap<String, AttributeValue> {
-    match value_to_item(value) {
-        AttributeValue::M(map) => map,
-        other => panic!("can only insert top level values, got {:?}", other),
-    }
-}
-
-fn value_to_item(value: Value) -> AttributeValue {
-    match value {
-        Value::Null => AttributeValue::Null(true),
-        Value::Bool(b) => AttributeValue::Bool(b),
-        Value::Number(n) => AttributeValue::N(n.to_string()),
-        Value::String(s) => AttributeValue::S(s),
-        Value::Array(a) => AttributeValue::L(a.into_iter().map(value_to_item).collect()),
-        Value::Object(o) => {
-            AttributeValue::M(o.into_iter().map(|(k, v)| (k, value_to_item(v))).collect())
-        }
-    }
-}
+fn movies_in_year(client: &Client, table_name: &str, year: u16) -> Query {
+    // year is a reserved word, so it can only be used in an expression through a placeholder.
+    let expression = Expression::builder()
+        .key_condition(Condition::eq("year", AttributeValue::N(year.to_string())))
+        .build();
 
-fn movies_in_year(client: &dynamodb::Client, table_name: &str, year: u16) -> Query {
     client
         .query()
         .table_name(table_name)
-        .key_condition_expression("#yr = :yyyy")
-        .expression_attribute_names("#yr", "year")
-        .expression_attribute_values(":yyyy", AttributeValue::N(year.to_string()))
-}
-
-/// Hand-written waiter to retry every second until the table is out of `Creating` state
-#[derive(Clone)]
-struct WaitForReadyTable<R> {
-    inner: R,
-}
-
-impl<R> ClassifyResponse<SdkSuccess<DescribeTableOutput>, SdkError<DescribeTableError>>
-    for WaitForReadyTable<R>
-where
-    R: ClassifyResponse<SdkSuccess<DescribeTableOutput>, SdkError<DescribeTableError>>,
-{
-    fn classify(
-        &self,
-        response: Result<&SdkSuccess<DescribeTableOutput>, &SdkError<DescribeTableError>>,
-    ) -> RetryKind {
-        match self.inner.classify(response) {
-            RetryKind::NotRetryable => (),
-            other => return other,
-        };
-        match response {
-            Ok(SdkSuccess { parsed, .. }) => {
-                if parsed
-                    .table
-                    .as_ref()
-                    .unwrap()
-                    .table_status
-                    .as_ref()
-                    .unwrap()
-                    == &TableStatus::Creating
-                {
-                    RetryKind::Explicit(Duration::from_secs(1))
-                } else {
-                    RetryKind::NotRetryable
-                }
-            }
-            _ => RetryKind::NotRetryable,
-        }
-    }
-}
-
-/// Construct a `DescribeTable` request with a policy to retry every second until the table
-/// is ready
-fn wait_for_ready_table(
-    table_name: &str,
-    conf: &Config,
-) -> Operation<DescribeTable, WaitForReadyTable<AwsErrorRetryPolicy>> {
-    let operation = DescribeTableInput::builder()
-        .table_name(table_name)
-        .build()
-        .expect("valid input")
-        .make_operation(&conf)
-        .expect("valid operation");
-    let waiting_policy = WaitForReadyTable {
-        inner: operation.retry_policy().clone(),
-    };
-    operation.with_retry_policy(waiting_policy)
+        .set_key_condition_expression(expression.key_condition)
+        .set_expression_attribute_names(expression.names)
+        .set_expression_attribute_values(expression.values)
 }
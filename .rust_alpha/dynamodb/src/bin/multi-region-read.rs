@@ -0,0 +1,317 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0.
+ */
+
+use std::collections::HashMap;
+use std::error::Error;
+use std::fmt;
+use std::time::Duration;
+
+use dynamodb::model::AttributeValue;
+use dynamodb::{Client, Config, Endpoint, Region};
+use dynamodb_code_examples::failover::{replica_regions, Failure, Policy, Router};
+use dynamodb_code_examples::items::item_to_json;
+
+use aws_types::region::{EnvironmentProvider, ProvideRegion};
+
+use common::endpoint;
+use common::output::OutputFormat;
+use common::profile::ProfileCredentialsProvider;
+use http::Uri;
+use serde::Serialize;
+use serde_json::Value;
+use structopt::StructOpt;
+use tokio::time::sleep;
+use tracing_subscriber::fmt::format::FmtSpan;
+use tracing_subscriber::fmt::SubscriberBuilder;
+
+/// Where the clients of the regions given with --unavailable send their requests.
+/// Nothing listens on port 9 (discard) of the local host, so the connection is refused.
+const UNAVAILABLE_ENDPOINT: &str = "http://127.0.0.1:9";
+
+#[derive(Debug, StructOpt)]
+struct Opt {
+    /// The regions to read from. If only one is given, the table's other replicas are found from it
+    #[structopt(short, long = "region")]
+    regions: Vec<String>,
+
+    /// The table name
+    #[structopt(short, long)]
+    table: String,
+
+    /// The key for the item in the table
+    #[structopt(short, long)]
+    key: String,
+
+    /// The value of the item's key
+    #[structopt(short, long)]
+    value: String,
+
+    /// How many times to read the item
+    #[structopt(long, default_value = "5")]
+    reads: u32,
+
+    /// How many milliseconds a call can take before the next region is tried
+    #[structopt(long, default_value = "500")]
+    threshold: u64,
+
+    /// How many seconds to skip a region after a call to it failed or was too slow
+    #[structopt(long, default_value = "30")]
+    cooldown: u64,
+
+    /// A region whose requests fail, to show the failover. Can be given more than once
+    #[structopt(long)]
+    unavailable: Vec<String>,
+
+    /// The format of the output, text or json
+    #[structopt(long, default_value = "text")]
+    output: OutputFormat,
+
+    /// The AWS profile to use from the shared credentials file
+    #[structopt(long)]
+    profile: Option<String>,
+
+    /// Whether to display additional information
+    #[structopt(short, long)]
+    info: bool,
+}
+
+/// How near a region was when the regions were ranked.
+#[derive(Debug, Serialize)]
+struct Ranked {
+    region: String,
+    /// How long DescribeTable took, or `None` if it failed or was too slow.
+    latency_millis: Option<u128>,
+}
+
+/// One read, the region that answered it, and the regions that were given up on first.
+#[derive(Debug, Serialize)]
+struct Served {
+    region: String,
+    millis: u128,
+    item: Option<Value>,
+    failures: Vec<Failure>,
+}
+
+/// Where each read of the item was served from.
+#[derive(Debug, Serialize)]
+struct MultiRegionRead {
+    table: String,
+    key: String,
+    value: String,
+    regions: Vec<Ranked>,
+    reads: Vec<Served>,
+}
+
+impl fmt::Display for MultiRegionRead {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Regions of table {}, nearest first:", self.table)?;
+        for ranked in &self.regions {
+            match ranked.latency_millis {
+                Some(millis) => write!(f, "\n  {} ({} ms)", ranked.region, millis)?,
+                None => write!(f, "\n  {} (unavailable)", ranked.region)?,
+            }
+        }
+
+        for (i, read) in self.reads.iter().enumerate() {
+            write!(
+                f,
+                "\nRead {}: {} {} {} from {} in {} ms",
+                i + 1,
+                if read.item.is_some() {
+                    "found item with"
+                } else {
+                    "no item with"
+                },
+                self.key,
+                self.value,
+                read.region,
+                read.millis
+            )?;
+            for failure in &read.failures {
+                write!(
+                    f,
+                    "\n  Failed over from {}: {}",
+                    failure.region, failure.reason
+                )?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Returns a client for the region, whose requests fail if `unavailable` is set.
+fn regional_client(
+    region: &str,
+    profile: Option<&str>,
+    local: Option<&Uri>,
+    unavailable: bool,
+) -> Client {
+    let mut config = Config::builder().region(Region::new(region.to_string()));
+    if let Some(profile) = profile {
+        config = config.credentials_provider(ProfileCredentialsProvider::new(profile));
+    }
+
+    if unavailable {
+        config =
+            config.endpoint_resolver(Endpoint::immutable(Uri::from_static(UNAVAILABLE_ENDPOINT)));
+    } else if let Some(uri) = local {
+        config = config.endpoint_resolver(Endpoint::immutable(uri.clone()));
+    }
+
+    Client::from_conf(config.build())
+}
+
+/// Reads an item from an Amazon DynamoDB global table, from the nearest replica,
+/// and fails over to the next nearest when a read fails or takes longer than the threshold.
+/// A client is created for each region, and the regions are ranked by how long a DescribeTable call takes.
+/// A region that fails, or is too slow, is skipped for the cooldown.
+/// The table schema must use the key as the primary key, and it must be a string.
+/// # Arguments
+///
+/// * `-t TABLE` - The name of the table.
+/// * `-k KEY` - The table's primary key.
+/// * `-v VALUE` - The value of the item's primary key.
+/// * `[-r REGION]...` - The regions to read from.
+///   If only one is supplied, the table's other replicas are found from it.
+///   If none is supplied, uses the value of the **AWS_DEFAULT_REGION** environment variable.
+///   If the environment variable is not set, defaults to **us-west-2**.
+/// * `[--reads READS]` - How many times to read the item. Defaults to **5**.
+/// * `[--threshold MILLISECONDS]` - How long a call can take before the next region is tried.
+///   Defaults to **500**.
+/// * `[--cooldown SECONDS]` - How long to skip a region after a call to it failed or was too slow.
+///   Defaults to **30**.
+/// * `[--unavailable REGION]...` - A region whose requests fail, to show the failover.
+/// * `[--output FORMAT]` - How to display the result, **text** (the default) or **json**.
+/// * `[--profile PROFILE]` - The AWS profile to use from the shared credentials file.
+/// * `[-i]` - Whether to display additional information.
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn Error>> {
+    let Opt {
+        cooldown,
+        info,
+        key,
+        output,
+        profile,
+        reads,
+        mut regions,
+        table,
+        threshold,
+        unavailable,
+        value,
+    } = Opt::from_args();
+
+    if regions.is_empty() {
+        let region = EnvironmentProvider::new()
+            .region()
+            .unwrap_or_else(|| Region::new("us-west-2"));
+        regions.push(region.as_ref().to_string());
+    }
+
+    let policy = Policy {
+        threshold: Duration::from_millis(threshold),
+        cooldown: Duration::from_secs(cooldown),
+    };
+
+    if info {
+        println!("DynamoDB client version: {}\n", dynamodb::PKG_VERSION);
+        println!("Regions:     {}", regions.join(", "));
+        println!("Table:       {}", &table);
+        println!("Key:         {}", &key);
+        println!("Value:       {}", &value);
+        println!("Reads:       {}", reads);
+        println!("Threshold:   {} ms", threshold);
+        println!("Cooldown:    {} s", cooldown);
+        println!("Unavailable: {}\n", unavailable.join(", "));
+
+        SubscriberBuilder::default()
+            .with_env_filter("info")
+            .with_span_events(FmtSpan::CLOSE)
+            .init();
+    }
+
+    // Sends the requests to DynamoDB Local or LocalStack if AWS_ENDPOINT_URL is set.
+    let local = endpoint::from_env()?;
+
+    let client_for = |region: &str| {
+        regional_client(
+            region,
+            profile.as_deref(),
+            local.as_ref(),
+            unavailable.iter().any(|unavailable| unavailable == region),
+        )
+    };
+
+    // A global table's replicas are listed in every region, so one region is enough to find the rest.
+    if regions.len() == 1 {
+        let first = client_for(&regions[0]);
+        for region in replica_regions(&first, &table).await? {
+            if !regions.contains(&region) {
+                regions.push(region);
+            }
+        }
+
+        if regions.len() == 1 && info {
+            println!(
+                "Table {} has no replicas in other regions, so there's nowhere to fail over to\n",
+                &table
+            );
+        }
+    }
+
+    let clients = regions
+        .iter()
+        .map(|region| (region.clone(), client_for(region)))
+        .collect();
+    let mut router = Router::new(clients, policy);
+    router.rank(&table).await;
+
+    let ranked = router
+        .replicas()
+        .iter()
+        .map(|replica| Ranked {
+            region: replica.region.clone(),
+            latency_millis: replica.latency.map(|latency| latency.as_millis()),
+        })
+        .collect();
+
+    let mut item_key = HashMap::new();
+    item_key.insert(key.clone(), AttributeValue::S(value.clone()));
+
+    let mut served = Vec::new();
+    for i in 0..reads {
+        // A pause between reads, so the reads are spread over time as they would be in an application.
+        if i > 0 {
+            sleep(Duration::from_secs(1)).await;
+        }
+
+        let read = router.get_item(&table, item_key.clone()).await?;
+        if info {
+            println!(
+                "Read {} served by {} after {} failures",
+                i + 1,
+                read.region,
+                read.failures.len()
+            );
+        }
+
+        served.push(Served {
+            region: read.region,
+            millis: read.elapsed.as_millis(),
+            item: read.item.map(item_to_json),
+            failures: read.failures,
+        });
+    }
+
+    output.print(&MultiRegionRead {
+        table,
+        key,
+        value,
+        regions: ranked,
+        reads: served,
+    });
+
+    Ok(())
+}
@@ -0,0 +1,380 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0.
+ */
+
+use std::collections::HashMap;
+use std::error::Error;
+use std::fmt;
+use std::time::Duration;
+
+use dynamodb::model::{
+    AttributeDefinition, AttributeValue, KeySchemaElement, KeyType, ProvisionedThroughput,
+    ReturnValue, ScalarAttributeType,
+};
+use dynamodb::{Client, Config, Endpoint, Region};
+use dynamodb_code_examples::expressions::{Condition, Expression, Update};
+use dynamodb_code_examples::items::{from_item, to_item};
+use dynamodb_code_examples::waiters::wait_for_table_active;
+
+use aws_types::region::{EnvironmentProvider, ProvideRegion};
+
+use common::endpoint;
+use common::output::OutputFormat;
+use common::profile::ProfileCredentialsProvider;
+use common::shutdown::Shutdown;
+use serde::{Deserialize, Serialize};
+use structopt::StructOpt;
+use tracing_subscriber::fmt::format::FmtSpan;
+use tracing_subscriber::fmt::SubscriberBuilder;
+
+/// The movies that the scenario adds to the table, as year, title, and rating.
+const MOVIES: &[(u16, &str, f64)] = &[
+    (2013, "Rush", 8.1),
+    (2013, "Turn It Down, Or Else!", 4.5),
+    (2013, "The Secret Life of Walter Mitty", 7.3),
+    (2014, "Interstellar", 8.6),
+];
+
+#[derive(Debug, StructOpt)]
+struct Opt {
+    /// The region
+    #[structopt(short, long)]
+    region: Option<String>,
+
+    /// The name of the table to create
+    #[structopt(short, long, default_value = "rust-scenario-movies")]
+    table: String,
+
+    /// Whether to keep the table
+    #[structopt(short, long)]
+    keep: bool,
+
+    /// The format of the output, text or json
+    #[structopt(long, default_value = "text")]
+    output: OutputFormat,
+
+    /// The AWS profile to use from the shared credentials file
+    #[structopt(long)]
+    profile: Option<String>,
+
+    /// Whether to display additional information
+    #[structopt(short, long)]
+    verbose: bool,
+}
+
+/// The resources that the scenario has created, so that they can be deleted even if a later step fails.
+#[derive(Default)]
+struct Resources {
+    table: bool,
+}
+
+/// Returns the key of a movie.
+fn key(year: u16, title: &str) -> HashMap<String, AttributeValue> {
+    let mut key = HashMap::new();
+    key.insert("year".to_string(), AttributeValue::N(year.to_string()));
+    key.insert("title".to_string(), AttributeValue::S(title.to_string()));
+    key
+}
+
+/// A movie, as it's stored in the table.
+/// `to_item` and `from_item` convert it to and from an item, so the scenario doesn't handle attribute values.
+#[derive(Debug, Serialize, Deserialize)]
+struct Movie {
+    year: u16,
+    title: String,
+    rating: f64,
+}
+
+impl fmt::Display for Movie {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} ({}), rated {}", self.title, self.year, self.rating)
+    }
+}
+
+/// What the scenario found.
+#[derive(Debug, Serialize)]
+struct Summary {
+    table: String,
+    updated: Movie,
+    /// The movies that the query returned.
+    highly_rated: Vec<Movie>,
+    /// The movies that the scan returned, after one was deleted.
+    remaining: Vec<Movie>,
+    kept: bool,
+}
+
+impl fmt::Display for Summary {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "Updated {}; the query found {} movies and the scan found {} in {}",
+            self.updated,
+            self.highly_rated.len(),
+            self.remaining.len(),
+            self.table
+        )?;
+
+        if self.kept {
+            write!(f, "; kept the table")
+        } else {
+            write!(f, "; deleted the table")
+        }
+    }
+}
+
+/// Displays a step of the scenario, unless the output is JSON, which is only the summary.
+fn step(opt: &Opt, message: impl fmt::Display) {
+    if !opt.output.is_json() {
+        println!("{}", message);
+    }
+}
+
+/// Creates a table whose partition key is the year and whose sort key is the title.
+async fn create_table(client: &Client, table: &str) -> Result<(), dynamodb::Error> {
+    client
+        .create_table()
+        .table_name(table)
+        .key_schema(
+            KeySchemaElement::builder()
+                .attribute_name("year")
+                .key_type(KeyType::Hash)
+                .build(),
+        )
+        .key_schema(
+            KeySchemaElement::builder()
+                .attribute_name("title")
+                .key_type(KeyType::Range)
+                .build(),
+        )
+        .attribute_definitions(
+            AttributeDefinition::builder()
+                .attribute_name("year")
+                .attribute_type(ScalarAttributeType::N)
+                .build(),
+        )
+        .attribute_definitions(
+            AttributeDefinition::builder()
+                .attribute_name("title")
+                .attribute_type(ScalarAttributeType::S)
+                .build(),
+        )
+        .provisioned_throughput(
+            ProvisionedThroughput::builder()
+                .read_capacity_units(5)
+                .write_capacity_units(5)
+                .build(),
+        )
+        .send()
+        .await?;
+
+    Ok(())
+}
+
+/// Runs the steps of the scenario, stopping at the first error,
+/// and returns the updated movie, the movies the query found, and the movies the scan found.
+async fn run(
+    client: &Client,
+    opt: &Opt,
+    resources: &mut Resources,
+) -> Result<(Movie, Vec<Movie>, Vec<Movie>), Box<dyn Error>> {
+    step(opt, format!("1. Creating table {}", &opt.table));
+    create_table(client, &opt.table).await?;
+    resources.table = true;
+
+    step(opt, "  Waiting for the table to become active");
+    wait_for_table_active(client, &opt.table, Duration::from_secs(120)).await?;
+
+    step(opt, format!("2. Adding {} movies", MOVIES.len()));
+    for (year, title, rating) in MOVIES {
+        let movie = Movie {
+            year: *year,
+            title: title.to_string(),
+            rating: *rating,
+        };
+
+        client
+            .put_item()
+            .table_name(&opt.table)
+            .set_item(Some(to_item(&movie)?))
+            .send()
+            .await?;
+    }
+
+    let (year, title, _) = MOVIES[0];
+
+    step(opt, format!("3. Getting {} ({})", title, year));
+    let movie: Movie = from_item(
+        client
+            .get_item()
+            .table_name(&opt.table)
+            .set_key(Some(key(year, title)))
+            .send()
+            .await?
+            .item
+            .ok_or("the movie wasn't found")?,
+    )?;
+    step(opt, format!("  {}", movie));
+
+    // The condition makes the update fail if the movie doesn't exist, rather than create it.
+    step(opt, format!("4. Changing the rating of {} to 8.3", title));
+    let expression = Expression::builder()
+        .condition(Condition::exists("title"))
+        .update(Update::new().set("rating", AttributeValue::N("8.3".to_string())))
+        .build();
+    let updated: Movie = from_item(
+        client
+            .update_item()
+            .table_name(&opt.table)
+            .set_key(Some(key(year, title)))
+            .set_condition_expression(expression.condition)
+            .set_update_expression(expression.update)
+            .set_expression_attribute_names(expression.names)
+            .set_expression_attribute_values(expression.values)
+            .return_values(ReturnValue::AllNew)
+            .send()
+            .await?
+            .attributes
+            .unwrap_or_default(),
+    )?;
+    step(opt, format!("  {}", updated));
+
+    step(
+        opt,
+        format!("5. Querying the movies from {} rated 7 or higher", year),
+    );
+    let expression = Expression::builder()
+        .key_condition(Condition::eq("year", AttributeValue::N(year.to_string())))
+        .filter(Condition::ge("rating", AttributeValue::N("7".to_string())))
+        .build();
+    let highly_rated = client
+        .query()
+        .table_name(&opt.table)
+        .set_key_condition_expression(expression.key_condition)
+        .set_filter_expression(expression.filter)
+        .set_expression_attribute_names(expression.names)
+        .set_expression_attribute_values(expression.values)
+        .send()
+        .await?
+        .items
+        .unwrap_or_default()
+        .into_iter()
+        .map(from_item::<Movie>)
+        .collect::<Result<Vec<_>, _>>()?;
+    for movie in &highly_rated {
+        step(opt, format!("  {}", movie));
+    }
+
+    step(opt, format!("6. Deleting {} ({})", title, year));
+    client
+        .delete_item()
+        .table_name(&opt.table)
+        .set_key(Some(key(year, title)))
+        .send()
+        .await?;
+
+    step(opt, "7. Scanning the table");
+    let remaining = client
+        .scan()
+        .table_name(&opt.table)
+        .send()
+        .await?
+        .items
+        .unwrap_or_default()
+        .into_iter()
+        .map(from_item::<Movie>)
+        .collect::<Result<Vec<_>, _>>()?;
+    for movie in &remaining {
+        step(opt, format!("  {}", movie));
+    }
+
+    Ok((updated, highly_rated, remaining))
+}
+
+/// Deletes the table, and with it, the movies.
+async fn tear_down(client: &Client, opt: &Opt, resources: &Resources) {
+    if resources.table {
+        if let Err(e) = client.delete_table().table_name(&opt.table).send().await {
+            eprintln!("Got an error deleting table {}: {}", &opt.table, e);
+        }
+    }
+}
+
+/// Gets started with Amazon DynamoDB by running a complete workflow in one program.
+/// The scenario creates a table of movies, adds some movies, gets one, updates its rating,
+/// queries the movies from a year, deletes a movie, and scans the table.
+/// Finally, it deletes the table, even if a step fails or you press Ctrl-C.
+/// # Arguments
+///
+/// * `[-t TABLE]` - The name of the table to create.
+///   If not supplied, defaults to **rust-scenario-movies**.
+/// * `[-k]` - Whether to keep the table.
+/// * `[--output FORMAT]` - How to display the result, **text** (the default) or **json**,
+///   which only displays a summary once the scenario is finished.
+/// * `[-r REGION]` - The region in which the table is created.
+///   If the **AWS_DEFAULT_REGION** environment variable is set, it's used instead.
+///   If neither is set, defaults to **us-west-2**.
+/// * `[--profile PROFILE]` - The AWS profile to use from the shared credentials file.
+/// * `[-v]` - Whether to display additional information.
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn Error>> {
+    let opt = Opt::from_args();
+
+    let region = EnvironmentProvider::new()
+        .region()
+        .or_else(|| {
+            opt.region
+                .as_ref()
+                .map(|region| Region::new(region.clone()))
+        })
+        .unwrap_or_else(|| Region::new("us-west-2"));
+
+    if opt.verbose {
+        println!("DynamoDB client version: {}", dynamodb::PKG_VERSION);
+        println!("Region:                  {:?}", &region);
+        println!("Table:                   {}", &opt.table);
+        println!();
+
+        SubscriberBuilder::default()
+            .with_env_filter("info")
+            .with_span_events(FmtSpan::CLOSE)
+            .init();
+    }
+
+    let mut config = Config::builder().region(region);
+    if let Some(profile) = &opt.profile {
+        config = config.credentials_provider(ProfileCredentialsProvider::new(profile));
+    }
+
+    // Sends the requests to LocalStack, or another local endpoint, if AWS_ENDPOINT_URL is set.
+    if let Some(uri) = endpoint::from_env()? {
+        config = config.endpoint_resolver(Endpoint::immutable(uri));
+    }
+
+    let client = Client::from_conf(config.build());
+
+    let mut resources = Resources::default();
+
+    // Ctrl-C stops the scenario wherever it is, and still cleans up what it created.
+    let shutdown = Shutdown::listen();
+    let result = shutdown.run(run(&client, &opt, &mut resources)).await;
+
+    if opt.keep {
+        step(&opt, format!("Keeping table {}", &opt.table));
+    } else {
+        step(&opt, "8. Deleting the table");
+        tear_down(&client, &opt, &resources).await;
+    }
+
+    let (updated, highly_rated, remaining) = result?;
+
+    opt.output.print(&Summary {
+        table: opt.table.clone(),
+        updated,
+        highly_rated,
+        remaining,
+        kept: opt.keep,
+    });
+
+    Ok(())
+}
@@ -0,0 +1,306 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0.
+ */
+
+use std::error::Error;
+use std::fmt;
+use std::str::FromStr;
+use std::time::Duration;
+
+use dynamodb::{Client, Config, Endpoint, Region};
+use dynamodb_code_examples::sessions::{now, Session, SessionStore, TTL_ATTRIBUTE};
+
+use aws_types::region::{EnvironmentProvider, ProvideRegion};
+
+use common::endpoint;
+use common::output::OutputFormat;
+use common::profile::ProfileCredentialsProvider;
+use serde::Serialize;
+use structopt::StructOpt;
+use tracing_subscriber::fmt::format::FmtSpan;
+use tracing_subscriber::fmt::SubscriberBuilder;
+
+/// What to do with the session store.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+enum Action {
+    Setup,
+    Create,
+    Get,
+    Touch,
+    Delete,
+}
+
+impl FromStr for Action {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "setup" => Ok(Action::Setup),
+            "create" => Ok(Action::Create),
+            "get" => Ok(Action::Get),
+            "touch" => Ok(Action::Touch),
+            "delete" => Ok(Action::Delete),
+            _ => Err(format!(
+                "the action must be setup, create, get, touch, or delete, got {}",
+                s
+            )),
+        }
+    }
+}
+
+#[derive(Debug, StructOpt)]
+struct Opt {
+    /// What to do: setup, create, get, touch, or delete
+    action: Action,
+
+    /// The region
+    #[structopt(short, long)]
+    region: Option<String>,
+
+    /// The name of the table
+    #[structopt(short, long, default_value = "sessions")]
+    table: String,
+
+    /// The ID of the session, for get, touch, and delete
+    #[structopt(short, long)]
+    session_id: Option<String>,
+
+    /// The user that the session is for, for create
+    #[structopt(short, long)]
+    user: Option<String>,
+
+    /// Data to keep in the session, as KEY=VALUE, for create. Repeat to add several
+    #[structopt(long, parse(try_from_str = parse_data))]
+    data: Vec<(String, String)>,
+
+    /// How many seconds a session lasts without being used
+    #[structopt(long, default_value = "1800")]
+    idle_timeout: u64,
+
+    /// How many seconds a session lasts, however often it's used
+    #[structopt(long, default_value = "43200")]
+    max_lifetime: u64,
+
+    /// The format of the output, text or json
+    #[structopt(long, default_value = "text")]
+    output: OutputFormat,
+
+    /// The AWS profile to use from the shared credentials file
+    #[structopt(long)]
+    profile: Option<String>,
+
+    /// Whether to display additional information
+    #[structopt(short, long)]
+    verbose: bool,
+}
+
+/// Splits a KEY=VALUE data argument.
+fn parse_data(s: &str) -> Result<(String, String), String> {
+    match s.split_once('=') {
+        Some((key, value)) if !key.is_empty() => Ok((key.to_string(), value.to_string())),
+        _ => Err(format!("data must be KEY=VALUE, got {}", s)),
+    }
+}
+
+/// What the action did.
+#[derive(Debug, Serialize)]
+struct Outcome {
+    action: Action,
+    table: String,
+    session_id: Option<String>,
+    /// Whether there was a session that hadn't expired.
+    found: bool,
+    session: Option<Session>,
+}
+
+impl fmt::Display for Outcome {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let id = self.session_id.as_deref().unwrap_or_default();
+
+        match (self.action, &self.session) {
+            (Action::Setup, _) => {
+                return write!(
+                    f,
+                    "Created table {}, with TTL on the {} attribute",
+                    self.table, TTL_ATTRIBUTE
+                )
+            }
+            (Action::Delete, _) if self.found => return write!(f, "Deleted session {}", id),
+            (Action::Delete, _) => {
+                return write!(f, "Session {} had already expired or didn't exist", id)
+            }
+            (_, None) => return write!(f, "Session {} has expired or doesn't exist", id),
+            (Action::Create, Some(_)) => write!(f, "Created session {}", id)?,
+            (_, Some(_)) => write!(f, "Session {}", id)?,
+        }
+
+        if let Some(session) = &self.session {
+            let now = now();
+
+            write!(f, "\n  User:       {}", session.user)?;
+            for (key, value) in &session.data {
+                write!(f, "\n  Data:       {}={}", key, value)?;
+            }
+            write!(
+                f,
+                "\n  Created:    {} seconds ago",
+                now - session.created_at
+            )?;
+            write!(
+                f,
+                "\n  Last used:  {} seconds ago",
+                now - session.last_used_at
+            )?;
+            write!(
+                f,
+                "\n  Expires in: {} seconds, unless it's used",
+                session.expires_at.min(session.max_expires_at) - now
+            )?;
+            write!(
+                f,
+                "\n  Ends in:    {} seconds, however often it's used",
+                session.max_expires_at - now
+            )?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Keeps web sessions in a DynamoDB table, which expire after a period without use.
+/// Each session's **expires_at** attribute is when it expires, and the table's time to live (TTL)
+/// setting deletes the session some time after that.
+/// Touching a session moves **expires_at** forward, with an update that's conditional on the session
+/// not having expired yet, so that an expired session stays expired before TTL deletes it.
+/// A session also has a maximum lifetime, after which it expires however often it's used.
+/// # Arguments
+///
+/// * `ACTION` - What to do:
+///   **setup** creates the table and turns on TTL;
+///   **create** creates a session for a user, and displays its ID;
+///   **get** displays a session;
+///   **touch** marks a session as used, so that it expires later;
+///   **delete** deletes a session, as when the user signs out.
+/// * `[-t TABLE]` - The name of the table.
+///   If not supplied, defaults to **sessions**.
+/// * `[-s SESSION-ID]` - The ID of the session, for **get**, **touch**, and **delete**.
+/// * `[-u USER]` - The user that the session is for, for **create**.
+/// * `[--data KEY=VALUE]...` - Data to keep in the session, for **create**.
+/// * `[--idle-timeout SECONDS]` - How long a session lasts without being used.
+///   If not supplied, defaults to **1800** (30 minutes).
+/// * `[--max-lifetime SECONDS]` - How long a session lasts, however often it's used.
+///   If not supplied, defaults to **43200** (12 hours).
+/// * `[-r REGION]` - The region in which the client is created.
+///    If not supplied, uses the value of the **AWS_DEFAULT_REGION** environment variable.
+///    If the environment variable is not set, defaults to **us-west-2**.
+/// * `[--output FORMAT]` - How to display the result, **text** (the default) or **json**.
+/// * `[--profile PROFILE]` - The AWS profile to use from the shared credentials file.
+/// * `[-v]` - Whether to display additional information.
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn Error>> {
+    let Opt {
+        action,
+        data,
+        idle_timeout,
+        max_lifetime,
+        output,
+        profile,
+        region,
+        session_id,
+        table,
+        user,
+        verbose,
+    } = Opt::from_args();
+
+    let region = EnvironmentProvider::new()
+        .region()
+        .or_else(|| region.as_ref().map(|region| Region::new(region.clone())))
+        .unwrap_or_else(|| Region::new("us-west-2"));
+
+    if idle_timeout == 0 || max_lifetime == 0 {
+        return Err("the idle timeout and the maximum lifetime must be at least 1 second".into());
+    }
+
+    match action {
+        Action::Create if user.is_none() => return Err("create needs -u USER".into()),
+        Action::Get | Action::Touch | Action::Delete if session_id.is_none() => {
+            return Err("get, touch, and delete need -s SESSION-ID".into())
+        }
+        _ => {}
+    }
+
+    if verbose {
+        println!("DynamoDB client version: {}", dynamodb::PKG_VERSION);
+        println!("Region:                  {:?}", &region);
+        println!("Action:                  {:?}", action);
+        println!("Table:                   {}", &table);
+        println!("Session:                 {:?}", &session_id);
+        println!("User:                    {:?}", &user);
+        println!("Data:                    {:?}", &data);
+        println!("Idle timeout:            {}s", idle_timeout);
+        println!("Maximum lifetime:        {}s", max_lifetime);
+
+        SubscriberBuilder::default()
+            .with_env_filter("info")
+            .with_span_events(FmtSpan::CLOSE)
+            .init();
+    }
+
+    let mut config = Config::builder().region(region);
+    if let Some(profile) = profile {
+        config = config.credentials_provider(ProfileCredentialsProvider::new(profile));
+    }
+
+    // Sends the requests to LocalStack or DynamoDB Local, if AWS_ENDPOINT_URL is set.
+    if let Some(uri) = endpoint::from_env()? {
+        config = config.endpoint_resolver(Endpoint::immutable(uri));
+    }
+
+    let client = Client::from_conf(config.build());
+
+    let store = SessionStore::new(
+        &client,
+        &table,
+        Duration::from_secs(idle_timeout),
+        Duration::from_secs(max_lifetime),
+    );
+
+    let id = session_id.clone().unwrap_or_default();
+
+    let (found, session) = match action {
+        Action::Setup => {
+            store.create_table().await?;
+            (true, None)
+        }
+        Action::Create => {
+            let session = store
+                .create(&user.unwrap_or_default(), data.into_iter().collect())
+                .await?;
+            (true, Some(session))
+        }
+        Action::Get => {
+            let session = store.get(&id).await?;
+            (session.is_some(), session)
+        }
+        Action::Touch => {
+            let session = store.touch(&id).await?;
+            (session.is_some(), session)
+        }
+        Action::Delete => (store.delete(&id).await?, None),
+    };
+
+    output.print(&Outcome {
+        action,
+        table,
+        session_id: session
+            .as_ref()
+            .map(|session| session.id.clone())
+            .or(session_id),
+        found,
+        session,
+    });
+
+    Ok(())
+}
@@ -0,0 +1,414 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0.
+ */
+
+//! Builds DynamoDB condition, filter, key condition, and update expressions.
+//!
+//! An expression refers to attribute names and values through placeholders, such as `#n0` and `:v0`,
+//! which go in separate maps. Placeholders are needed for any name that's a reserved word, such as
+//! __year__ or __name__, and for every value. Writing the expression and the two maps by hand is
+//! easy to get wrong, so this module writes all three from conditions and updates:
+//!
+//! ```ignore
+//! let expression = Expression::builder()
+//!     .key_condition(Condition::eq("year", AttributeValue::N("2013".to_string())))
+//!     .filter(Condition::begins_with("title", AttributeValue::S("R".to_string())))
+//!     .build();
+//!
+//! client
+//!     .query()
+//!     .table_name(table)
+//!     .set_key_condition_expression(expression.key_condition)
+//!     .set_filter_expression(expression.filter)
+//!     .set_expression_attribute_names(expression.names)
+//!     .set_expression_attribute_values(expression.values)
+//!     .send()
+//!     .await?;
+//! ```
+//!
+//! Names are used as they are, so a name that contains a dot refers to a top-level attribute
+//! with a dot in its name, not to a nested attribute.
+
+use std::collections::HashMap;
+
+use dynamodb::model::AttributeValue;
+
+/// How a `Condition::Compare` compares an attribute with a value.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Comparator {
+    Eq,
+    Ne,
+    Lt,
+    Le,
+    Gt,
+    Ge,
+}
+
+impl Comparator {
+    fn as_str(self) -> &'static str {
+        match self {
+            Comparator::Eq => "=",
+            Comparator::Ne => "<>",
+            Comparator::Lt => "<",
+            Comparator::Le => "<=",
+            Comparator::Gt => ">",
+            Comparator::Ge => ">=",
+        }
+    }
+}
+
+/// A condition on the attributes of an item, for a key condition, filter, or condition expression.
+/// Key conditions support only `eq` on the partition key, and `eq`, the other comparisons,
+/// `between`, or `begins_with` on the sort key, combined with `and`.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Condition {
+    Compare(String, Comparator, AttributeValue),
+    Between(String, AttributeValue, AttributeValue),
+    BeginsWith(String, AttributeValue),
+    Contains(String, AttributeValue),
+    Exists(String),
+    NotExists(String),
+    And(Box<Condition>, Box<Condition>),
+    Or(Box<Condition>, Box<Condition>),
+    Not(Box<Condition>),
+}
+
+impl Condition {
+    /// The attribute equals the value.
+    pub fn eq(name: impl Into<String>, value: AttributeValue) -> Self {
+        Condition::Compare(name.into(), Comparator::Eq, value)
+    }
+
+    /// The attribute doesn't equal the value.
+    pub fn ne(name: impl Into<String>, value: AttributeValue) -> Self {
+        Condition::Compare(name.into(), Comparator::Ne, value)
+    }
+
+    /// The attribute is less than the value.
+    pub fn lt(name: impl Into<String>, value: AttributeValue) -> Self {
+        Condition::Compare(name.into(), Comparator::Lt, value)
+    }
+
+    /// The attribute is less than or equal to the value.
+    pub fn le(name: impl Into<String>, value: AttributeValue) -> Self {
+        Condition::Compare(name.into(), Comparator::Le, value)
+    }
+
+    /// The attribute is greater than the value.
+    pub fn gt(name: impl Into<String>, value: AttributeValue) -> Self {
+        Condition::Compare(name.into(), Comparator::Gt, value)
+    }
+
+    /// The attribute is greater than or equal to the value.
+    pub fn ge(name: impl Into<String>, value: AttributeValue) -> Self {
+        Condition::Compare(name.into(), Comparator::Ge, value)
+    }
+
+    /// The attribute is between the two values, inclusive.
+    pub fn between(name: impl Into<String>, low: AttributeValue, high: AttributeValue) -> Self {
+        Condition::Between(name.into(), low, high)
+    }
+
+    /// The attribute, a string or binary, starts with the value.
+    pub fn begins_with(name: impl Into<String>, value: AttributeValue) -> Self {
+        Condition::BeginsWith(name.into(), value)
+    }
+
+    /// The attribute, a string, set, or list, contains the value.
+    pub fn contains(name: impl Into<String>, value: AttributeValue) -> Self {
+        Condition::Contains(name.into(), value)
+    }
+
+    /// The item has the attribute.
+    pub fn exists(name: impl Into<String>) -> Self {
+        Condition::Exists(name.into())
+    }
+
+    /// The item doesn't have the attribute.
+    pub fn not_exists(name: impl Into<String>) -> Self {
+        Condition::NotExists(name.into())
+    }
+
+    /// Both this condition and the other are true.
+    pub fn and(self, other: Condition) -> Self {
+        Condition::And(Box::new(self), Box::new(other))
+    }
+
+    /// This condition, the other, or both are true.
+    pub fn or(self, other: Condition) -> Self {
+        Condition::Or(Box::new(self), Box::new(other))
+    }
+
+    /// This condition is false.
+    pub fn negate(self) -> Self {
+        Condition::Not(Box::new(self))
+    }
+
+    fn render(self, placeholders: &mut Placeholders) -> String {
+        match self {
+            Condition::Compare(name, comparator, value) => format!(
+                "{} {} {}",
+                placeholders.name(name),
+                comparator.as_str(),
+                placeholders.value(value)
+            ),
+            Condition::Between(name, low, high) => format!(
+                "{} BETWEEN {} AND {}",
+                placeholders.name(name),
+                placeholders.value(low),
+                placeholders.value(high)
+            ),
+            Condition::BeginsWith(name, value) => format!(
+                "begins_with({}, {})",
+                placeholders.name(name),
+                placeholders.value(value)
+            ),
+            Condition::Contains(name, value) => format!(
+                "contains({}, {})",
+                placeholders.name(name),
+                placeholders.value(value)
+            ),
+            Condition::Exists(name) => format!("attribute_exists({})", placeholders.name(name)),
+            Condition::NotExists(name) => {
+                format!("attribute_not_exists({})", placeholders.name(name))
+            }
+            // NOT binds tighter than AND, which binds tighter than OR, so only the operands that
+            // would otherwise change meaning get parentheses. Key conditions don't allow them.
+            Condition::And(left, right) => format!(
+                "{} AND {}",
+                left.render_operand(placeholders, Condition::is_or),
+                right.render_operand(placeholders, Condition::is_or)
+            ),
+            Condition::Or(left, right) => format!(
+                "{} OR {}",
+                left.render(placeholders),
+                right.render(placeholders)
+            ),
+            Condition::Not(condition) => format!(
+                "NOT {}",
+                condition.render_operand(placeholders, Condition::is_compound)
+            ),
+        }
+    }
+
+    /// Renders the condition as an operand of AND or NOT, in parentheses if `needs_parentheses` says so.
+    fn render_operand(
+        self,
+        placeholders: &mut Placeholders,
+        needs_parentheses: fn(&Condition) -> bool,
+    ) -> String {
+        if needs_parentheses(&self) {
+            format!("({})", self.render(placeholders))
+        } else {
+            self.render(placeholders)
+        }
+    }
+
+    fn is_or(&self) -> bool {
+        matches!(self, Condition::Or(..))
+    }
+
+    fn is_compound(&self) -> bool {
+        matches!(self, Condition::And(..) | Condition::Or(..))
+    }
+}
+
+/// The changes that an update expression makes to an item.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Update {
+    set: Vec<(String, AttributeValue)>,
+    set_if_not_exists: Vec<(String, AttributeValue)>,
+    add: Vec<(String, AttributeValue)>,
+    remove: Vec<String>,
+}
+
+impl Update {
+    /// An update that doesn't change anything yet.
+    pub fn new() -> Self {
+        Update::default()
+    }
+
+    /// Sets the attribute to the value.
+    pub fn set(mut self, name: impl Into<String>, value: AttributeValue) -> Self {
+        self.set.push((name.into(), value));
+        self
+    }
+
+    /// Sets the attribute to the value, unless the item already has the attribute.
+    pub fn set_if_not_exists(mut self, name: impl Into<String>, value: AttributeValue) -> Self {
+        self.set_if_not_exists.push((name.into(), value));
+        self
+    }
+
+    /// Adds the value to a number attribute, or adds the elements of the value to a set attribute.
+    /// If the item doesn't have the attribute, it's set to the value.
+    pub fn add(mut self, name: impl Into<String>, value: AttributeValue) -> Self {
+        self.add.push((name.into(), value));
+        self
+    }
+
+    /// Removes the attribute from the item.
+    pub fn remove(mut self, name: impl Into<String>) -> Self {
+        self.remove.push(name.into());
+        self
+    }
+
+    fn render(self, placeholders: &mut Placeholders) -> String {
+        let mut set: Vec<String> = self
+            .set
+            .into_iter()
+            .map(|(name, value)| {
+                format!(
+                    "{} = {}",
+                    placeholders.name(name),
+                    placeholders.value(value)
+                )
+            })
+            .collect();
+
+        set.extend(self.set_if_not_exists.into_iter().map(|(name, value)| {
+            let name = placeholders.name(name);
+            format!(
+                "{} = if_not_exists({}, {})",
+                name,
+                name,
+                placeholders.value(value)
+            )
+        }));
+
+        let add: Vec<String> = self
+            .add
+            .into_iter()
+            .map(|(name, value)| {
+                format!("{} {}", placeholders.name(name), placeholders.value(value))
+            })
+            .collect();
+
+        let remove: Vec<String> = self
+            .remove
+            .into_iter()
+            .map(|name| placeholders.name(name))
+            .collect();
+
+        let mut clauses = Vec::new();
+        for (action, parts) in &[("SET", set), ("ADD", add), ("REMOVE", remove)] {
+            if !parts.is_empty() {
+                clauses.push(format!("{} {}", action, parts.join(", ")));
+            }
+        }
+
+        clauses.join(" ")
+    }
+}
+
+/// The expressions for a request, and the names and values that their placeholders stand for.
+/// Each field is `None` if it isn't needed, so they can be passed straight to the `set_` methods
+/// of a request builder, such as `set_filter_expression`.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Expression {
+    pub key_condition: Option<String>,
+    pub condition: Option<String>,
+    pub filter: Option<String>,
+    pub update: Option<String>,
+    pub names: Option<HashMap<String, String>>,
+    pub values: Option<HashMap<String, AttributeValue>>,
+}
+
+impl Expression {
+    /// Returns a builder for the expressions of one request.
+    pub fn builder() -> ExpressionBuilder {
+        ExpressionBuilder::default()
+    }
+}
+
+/// Collects the expressions of one request, so that they share one set of placeholders.
+#[derive(Clone, Debug, Default)]
+pub struct ExpressionBuilder {
+    key_condition: Option<Condition>,
+    condition: Option<Condition>,
+    filter: Option<Condition>,
+    update: Option<Update>,
+}
+
+impl ExpressionBuilder {
+    /// The key condition of a Query.
+    pub fn key_condition(mut self, condition: Condition) -> Self {
+        self.key_condition = Some(condition);
+        self
+    }
+
+    /// The condition that a PutItem, UpdateItem, or DeleteItem request must meet to succeed.
+    pub fn condition(mut self, condition: Condition) -> Self {
+        self.condition = Some(condition);
+        self
+    }
+
+    /// The filter of a Query or Scan, which is applied to the items after they are read.
+    pub fn filter(mut self, condition: Condition) -> Self {
+        self.filter = Some(condition);
+        self
+    }
+
+    /// The changes that an UpdateItem request makes.
+    pub fn update(mut self, update: Update) -> Self {
+        self.update = Some(update);
+        self
+    }
+
+    /// Writes the expressions, replacing every name and value with a placeholder.
+    pub fn build(self) -> Expression {
+        let mut placeholders = Placeholders::default();
+
+        let key_condition = self
+            .key_condition
+            .map(|condition| condition.render(&mut placeholders));
+        let condition = self
+            .condition
+            .map(|condition| condition.render(&mut placeholders));
+        let filter = self
+            .filter
+            .map(|condition| condition.render(&mut placeholders));
+        let update = self.update.map(|update| update.render(&mut placeholders));
+
+        // DynamoDB rejects empty maps, so leave them out if nothing uses them.
+        let names: HashMap<String, String> = placeholders
+            .names
+            .into_iter()
+            .map(|(name, placeholder)| (placeholder, name))
+            .collect();
+
+        Expression {
+            key_condition,
+            condition,
+            filter,
+            update,
+            names: Some(names).filter(|names| !names.is_empty()),
+            values: Some(placeholders.values).filter(|values| !values.is_empty()),
+        }
+    }
+}
+
+/// Hands out placeholders. Each name gets one placeholder however often it's used;
+/// each value gets its own.
+#[derive(Default)]
+struct Placeholders {
+    names: HashMap<String, String>,
+    values: HashMap<String, AttributeValue>,
+}
+
+impl Placeholders {
+    fn name(&mut self, name: String) -> String {
+        let next = self.names.len();
+        self.names
+            .entry(name)
+            .or_insert_with(|| format!("#n{}", next))
+            .clone()
+    }
+
+    fn value(&mut self, value: AttributeValue) -> String {
+        let placeholder = format!(":v{}", self.values.len());
+        self.values.insert(placeholder.clone(), value);
+        placeholder
+    }
+}
@@ -0,0 +1,231 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0.
+ */
+
+//! Reads a global table from the nearest replica, and fails over to another region.
+//!
+//! A global table has a replica in each of several regions, and a client talks to one region.
+//! A `Router` holds a client for each replica's region, ranked nearest first by how long
+//! a DescribeTable call takes, and sends each read to the nearest replica that's available.
+//! A read that fails, or that takes longer than the policy's latency threshold, is abandoned
+//! and sent to the next replica, and the replica is skipped for the policy's cooldown,
+//! so that later reads don't wait on it too:
+//!
+//! ```ignore
+//! let mut router = Router::new(clients, Policy::default());
+//! router.rank(&table).await;
+//! let read = router.get_item(&table, key).await?;
+//! println!("Read from {}", read.region);
+//! ```
+//!
+//! Replicas are updated asynchronously, usually within a second, so a replica can return
+//! an earlier version of an item than the region it was written in.
+
+use std::collections::HashMap;
+use std::error::Error;
+use std::fmt;
+use std::time::{Duration, Instant};
+
+use dynamodb::model::AttributeValue;
+use dynamodb::Client;
+use serde::Serialize;
+use tokio::time::timeout;
+
+/// When to give up on a replica.
+#[derive(Clone, Copy, Debug)]
+pub struct Policy {
+    /// How long a call can take before it's abandoned and the next replica is tried.
+    pub threshold: Duration,
+    /// How long to skip a replica after a call to it failed or was too slow.
+    pub cooldown: Duration,
+}
+
+impl Default for Policy {
+    fn default() -> Self {
+        Policy {
+            threshold: Duration::from_millis(500),
+            cooldown: Duration::from_secs(30),
+        }
+    }
+}
+
+/// A client for one replica's region.
+#[derive(Debug)]
+pub struct Replica {
+    pub region: String,
+    /// How long DescribeTable took when the replicas were ranked, or `None` if it failed or was too slow.
+    pub latency: Option<Duration>,
+    client: Client,
+    skip_until: Option<Instant>,
+}
+
+impl Replica {
+    /// Whether the replica is being skipped, because a recent call to it failed or was too slow.
+    pub fn is_skipped(&self, now: Instant) -> bool {
+        self.skip_until.map_or(false, |until| now < until)
+    }
+}
+
+/// A replica that was tried and given up on.
+#[derive(Clone, Debug, Serialize)]
+pub struct Failure {
+    pub region: String,
+    pub reason: String,
+}
+
+/// The result of a read, and the replicas that were given up on before it.
+#[derive(Debug)]
+pub struct Read {
+    /// The region of the replica that answered.
+    pub region: String,
+    /// The item, or `None` if the replica doesn't have it.
+    pub item: Option<HashMap<String, AttributeValue>>,
+    /// How long the read took, including the replicas that were given up on.
+    pub elapsed: Duration,
+    pub failures: Vec<Failure>,
+}
+
+/// Every replica failed, or was too slow. Holds why, nearest first.
+#[derive(Debug)]
+pub struct FailoverError(pub Vec<Failure>);
+
+impl fmt::Display for FailoverError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.0.is_empty() {
+            return write!(f, "there are no replicas to read from");
+        }
+
+        write!(f, "every replica failed")?;
+        for failure in &self.0 {
+            write!(f, "; {}: {}", failure.region, failure.reason)?;
+        }
+
+        Ok(())
+    }
+}
+
+impl Error for FailoverError {}
+
+/// Returns the regions of the table's replicas, as DescribeTable reports them in the client's region.
+/// The list is empty if the table isn't a global table.
+pub async fn replica_regions(client: &Client, table: &str) -> Result<Vec<String>, dynamodb::Error> {
+    let description = client.describe_table().table_name(table).send().await?;
+
+    Ok(description
+        .table
+        .and_then(|table| table.replicas)
+        .unwrap_or_default()
+        .into_iter()
+        .filter_map(|replica| replica.region_name)
+        .collect())
+}
+
+/// Sends reads to the nearest available replica, and fails over to the others in order.
+#[derive(Debug)]
+pub struct Router {
+    replicas: Vec<Replica>,
+    policy: Policy,
+}
+
+impl Router {
+    /// Creates a router from a client for each region, in the order to try them until they're ranked.
+    pub fn new(clients: Vec<(String, Client)>, policy: Policy) -> Self {
+        Router {
+            replicas: clients
+                .into_iter()
+                .map(|(region, client)| Replica {
+                    region,
+                    latency: None,
+                    client,
+                    skip_until: None,
+                })
+                .collect(),
+            policy,
+        }
+    }
+
+    /// The replicas, in the order they're tried.
+    pub fn replicas(&self) -> &[Replica] {
+        &self.replicas
+    }
+
+    /// Times a DescribeTable call in each region, and orders the replicas nearest first.
+    /// A region where the call fails, or takes longer than the threshold, goes last,
+    /// and is skipped for the cooldown.
+    pub async fn rank(&mut self, table: &str) {
+        let policy = self.policy;
+
+        for replica in &mut self.replicas {
+            let start = Instant::now();
+            let call = replica.client.describe_table().table_name(table).send();
+
+            replica.latency = match timeout(policy.threshold, call).await {
+                Ok(Ok(_)) => Some(start.elapsed()),
+                _ => {
+                    replica.skip_until = Some(Instant::now() + policy.cooldown);
+                    None
+                }
+            };
+        }
+
+        // The sort is stable, so regions that are equally near, or unreachable, keep their order.
+        self.replicas
+            .sort_by_key(|replica| replica.latency.unwrap_or(Duration::MAX));
+    }
+
+    /// Gets an item from the nearest replica that isn't being skipped. If the call fails,
+    /// or takes longer than the threshold, the replica is skipped for the cooldown and the next one is tried.
+    /// When every replica is being skipped, they're all tried anyway, rather than failing without trying.
+    pub async fn get_item(
+        &mut self,
+        table: &str,
+        key: HashMap<String, AttributeValue>,
+    ) -> Result<Read, FailoverError> {
+        let policy = self.policy;
+        let start = Instant::now();
+
+        let mut order: Vec<usize> = (0..self.replicas.len())
+            .filter(|&i| !self.replicas[i].is_skipped(start))
+            .collect();
+        if order.is_empty() {
+            order = (0..self.replicas.len()).collect();
+        }
+
+        let mut failures = Vec::new();
+
+        for i in order {
+            let replica = &mut self.replicas[i];
+            let call = replica
+                .client
+                .get_item()
+                .table_name(table)
+                .set_key(Some(key.clone()))
+                .send();
+
+            // Any error fails over, even a client error, since the table might not be replicated
+            // to that region yet, or might have been removed from it.
+            let reason = match timeout(policy.threshold, call).await {
+                Ok(Ok(resp)) => {
+                    replica.skip_until = None;
+                    return Ok(Read {
+                        region: replica.region.clone(),
+                        item: resp.item,
+                        elapsed: start.elapsed(),
+                        failures,
+                    });
+                }
+                Ok(Err(e)) => dynamodb::Error::from(e).to_string(),
+                Err(_) => format!("no response within {} ms", policy.threshold.as_millis()),
+            };
+
+            replica.skip_until = Some(Instant::now() + policy.cooldown);
+            failures.push(Failure {
+                region: replica.region.clone(),
+                reason,
+            });
+        }
+
+        Err(FailoverError(failures))
+    }
+}
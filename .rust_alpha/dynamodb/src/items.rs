@@ -0,0 +1,149 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0.
+ */
+
+//! Converts between Rust types and DynamoDB items.
+//!
+//! An item is a `HashMap<String, AttributeValue>`, which is tedious to build and to read.
+//! Any type that implements `serde::Serialize` can be turned into an item, and any type that
+//! implements `serde::Deserialize` can be read back out of one:
+//!
+//! ```ignore
+//! #[derive(Serialize, Deserialize)]
+//! struct Movie { year: u16, title: String, rating: f64 }
+//!
+//! client.put_item().table_name(table).set_item(Some(to_item(&movie)?)).send().await?;
+//! let movie: Movie = from_item(resp.item.ok_or("not found")?)?;
+//! ```
+//!
+//! The conversion goes through `serde_json::Value`, so it follows JSON's rules:
+//! numbers are stored as __N__, strings as __S__, `bool` as __BOOL__, `None` as __NULL__,
+//! sequences as __L__, and structs and maps as __M__.
+//! Reading an item, sets are read as lists, and binary values are read as base64 strings.
+
+use std::collections::HashMap;
+use std::error::Error;
+use std::fmt;
+
+use dynamodb::model::AttributeValue;
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use serde_json::{Map, Value};
+
+/// Why a value couldn't be converted to or from an item.
+#[derive(Debug)]
+pub enum ItemError {
+    /// serde couldn't convert the value.
+    Json(serde_json::Error),
+    /// The value isn't a struct or a map, so it can't be an item.
+    NotAnObject,
+    /// The value isn't an array, so it can't be a list of items.
+    NotAnArray,
+}
+
+impl fmt::Display for ItemError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ItemError::Json(e) => write!(f, "{}", e),
+            ItemError::NotAnObject => write!(f, "an item must be a struct or a map"),
+            ItemError::NotAnArray => write!(f, "a list of items must be an array"),
+        }
+    }
+}
+
+impl Error for ItemError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            ItemError::Json(e) => Some(e),
+            ItemError::NotAnObject | ItemError::NotAnArray => None,
+        }
+    }
+}
+
+impl From<serde_json::Error> for ItemError {
+    fn from(e: serde_json::Error) -> Self {
+        ItemError::Json(e)
+    }
+}
+
+/// Converts a value, such as a struct, into an item.
+pub fn to_item<T>(value: &T) -> Result<HashMap<String, AttributeValue>, ItemError>
+where
+    T: Serialize + ?Sized,
+{
+    match to_attribute(serde_json::to_value(value)?) {
+        AttributeValue::M(item) => Ok(item),
+        _ => Err(ItemError::NotAnObject),
+    }
+}
+
+/// Converts an item into a value, such as a struct.
+pub fn from_item<T>(item: HashMap<String, AttributeValue>) -> Result<T, ItemError>
+where
+    T: DeserializeOwned,
+{
+    Ok(serde_json::from_value(item_to_json(item))?)
+}
+
+/// Converts a JSON array of objects into items, such as the file that __export-json__ writes.
+pub fn json_to_items(value: Value) -> Result<Vec<HashMap<String, AttributeValue>>, ItemError> {
+    match value {
+        Value::Array(rows) => rows
+            .into_iter()
+            .map(|row| match to_attribute(row) {
+                AttributeValue::M(item) => Ok(item),
+                _ => Err(ItemError::NotAnObject),
+            })
+            .collect(),
+        _ => Err(ItemError::NotAnArray),
+    }
+}
+
+/// Converts a JSON value into an attribute value.
+pub fn to_attribute(value: Value) -> AttributeValue {
+    match value {
+        Value::Null => AttributeValue::Null(true),
+        Value::Bool(b) => AttributeValue::Bool(b),
+        Value::Number(n) => AttributeValue::N(n.to_string()),
+        Value::String(s) => AttributeValue::S(s),
+        Value::Array(a) => AttributeValue::L(a.into_iter().map(to_attribute).collect()),
+        Value::Object(o) => {
+            AttributeValue::M(o.into_iter().map(|(k, v)| (k, to_attribute(v))).collect())
+        }
+    }
+}
+
+/// Converts an item into a JSON object.
+pub fn item_to_json(item: HashMap<String, AttributeValue>) -> Value {
+    Value::Object(
+        item.into_iter()
+            .map(|(k, v)| (k, attribute_to_json(v)))
+            .collect::<Map<String, Value>>(),
+    )
+}
+
+/// Converts an attribute value into the closest JSON type.
+/// Numbers that don't fit a JSON number, such as very large integers, are kept as strings.
+pub fn attribute_to_json(value: AttributeValue) -> Value {
+    match value {
+        AttributeValue::S(s) => Value::String(s),
+        AttributeValue::N(n) => serde_json::from_str(&n).unwrap_or(Value::String(n)),
+        AttributeValue::B(b) => Value::String(base64::encode(b.as_ref())),
+        AttributeValue::Ss(ss) => Value::from(ss),
+        AttributeValue::Ns(ns) => Value::Array(
+            ns.into_iter()
+                .map(|n| attribute_to_json(AttributeValue::N(n)))
+                .collect(),
+        ),
+        AttributeValue::Bs(bs) => Value::Array(
+            bs.into_iter()
+                .map(|b| Value::String(base64::encode(b.as_ref())))
+                .collect(),
+        ),
+        AttributeValue::M(m) => item_to_json(m),
+        AttributeValue::L(l) => Value::Array(l.into_iter().map(attribute_to_json).collect()),
+        AttributeValue::Null(_) => Value::Null,
+        AttributeValue::Bool(b) => Value::Bool(b),
+    }
+}
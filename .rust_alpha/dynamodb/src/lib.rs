@@ -0,0 +1,15 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0.
+ */
+
+//! Helpers shared by the DynamoDB code examples.
+
+pub mod batch;
+pub mod expressions;
+pub mod failover;
+pub mod items;
+pub mod plans;
+pub mod schema;
+pub mod sessions;
+pub mod waiters;
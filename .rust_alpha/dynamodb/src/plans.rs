@@ -0,0 +1,328 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0.
+ */
+
+//! Works out how DynamoDB reads the items that match some conditions: with a Query or a Scan.
+//!
+//! A Query reads only the items with one partition key value, so it needs an equality condition on
+//! the partition key of the table or index. It can also narrow the items by one condition on the sort key.
+//! Every other condition becomes a filter, which is applied after the items are read,
+//! so the items it discards are still paid for:
+//!
+//! ```ignore
+//! let keys = Keys { partition: "year".to_string(), sort: Some("title".to_string()) };
+//! let conditions = vec![parse_condition("year = 2013")?, parse_condition("rating > 7")?];
+//! let plan = plan(conditions, &keys, None)?;
+//! // plan.operation is Operation::Query, and the rating condition is plan.filter.
+//! ```
+
+use std::fmt;
+
+use dynamodb::model::AttributeValue;
+use serde::Serialize;
+
+use crate::expressions::{Comparator, Condition};
+
+/// The key attributes of a table or an index.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Keys {
+    pub partition: String,
+    pub sort: Option<String>,
+}
+
+/// The operation that reads the items.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+pub enum Operation {
+    Query,
+    Scan,
+}
+
+impl fmt::Display for Operation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Operation::Query => write!(f, "Query"),
+            Operation::Scan => write!(f, "Scan"),
+        }
+    }
+}
+
+/// How the items that match the conditions are read.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Plan {
+    pub operation: Operation,
+    /// The index that's read, or `None` for the table.
+    pub index: Option<String>,
+    /// The conditions that pick the items to read, for a Query.
+    pub key_condition: Option<Condition>,
+    /// The conditions that are applied after the items are read.
+    pub filter: Option<Condition>,
+    /// How many of the conditions are in the filter.
+    pub filter_conditions: usize,
+}
+
+/// Returns the attribute that a simple condition is on, or `None` for AND, OR, and NOT.
+fn attribute(condition: &Condition) -> Option<&str> {
+    match condition {
+        Condition::Compare(name, ..)
+        | Condition::Between(name, ..)
+        | Condition::BeginsWith(name, ..)
+        | Condition::Contains(name, ..)
+        | Condition::Exists(name)
+        | Condition::NotExists(name) => Some(name),
+        Condition::And(..) | Condition::Or(..) | Condition::Not(..) => None,
+    }
+}
+
+/// Returns whether the condition can be the sort key part of a key condition.
+/// Key conditions don't support <>, contains, or the attribute_exists functions.
+fn is_sort_key_condition(condition: &Condition, sort: &str) -> bool {
+    match condition {
+        Condition::Compare(name, comparator, _) => name == sort && *comparator != Comparator::Ne,
+        Condition::Between(name, ..) | Condition::BeginsWith(name, ..) => name == sort,
+        _ => false,
+    }
+}
+
+/// Works out whether the conditions can be read with a Query of the table or index that has these keys,
+/// and splits them into the key condition and the filter. Without an equality condition on the
+/// partition key, the items are read with a Scan, and every condition is in the filter.
+/// Fails if a Query would need a filter on a key attribute, which DynamoDB doesn't allow,
+/// such as a second condition on the sort key.
+pub fn plan(
+    conditions: Vec<Condition>,
+    keys: &Keys,
+    index: Option<String>,
+) -> Result<Plan, String> {
+    let partition = conditions.iter().position(|condition| {
+        matches!(condition, Condition::Compare(name, Comparator::Eq, _) if *name == keys.partition)
+    });
+
+    let mut conditions = conditions;
+
+    let partition = match partition {
+        Some(position) => conditions.remove(position),
+        None => {
+            let filter_conditions = conditions.len();
+
+            return Ok(Plan {
+                operation: Operation::Scan,
+                index,
+                key_condition: None,
+                filter: conditions.into_iter().reduce(Condition::and),
+                filter_conditions,
+            });
+        }
+    };
+
+    let sort = keys.sort.as_deref().and_then(|sort| {
+        conditions
+            .iter()
+            .position(|condition| is_sort_key_condition(condition, sort))
+    });
+
+    let key_condition = match sort {
+        Some(position) => partition.and(conditions.remove(position)),
+        None => partition,
+    };
+
+    for condition in &conditions {
+        if let Some(name) = attribute(condition) {
+            if name == keys.partition || keys.sort.as_deref() == Some(name) {
+                return Err(format!(
+                    "a Query can't filter on the key attribute {}; \
+                     use one condition on it that a key condition supports",
+                    name
+                ));
+            }
+        }
+    }
+
+    Ok(Plan {
+        operation: Operation::Query,
+        index,
+        key_condition: Some(key_condition),
+        filter_conditions: conditions.len(),
+        filter: conditions.into_iter().reduce(Condition::and),
+    })
+}
+
+/// Returns the indexes whose partition key has an equality condition, so they could be queried
+/// instead of scanning the table.
+pub fn queryable_indexes<'a>(
+    conditions: &[Condition],
+    indexes: &'a [(String, Keys)],
+) -> Vec<&'a str> {
+    indexes
+        .iter()
+        .filter(|(_, keys)| {
+            conditions.iter().any(|condition| {
+                matches!(condition, Condition::Compare(name, Comparator::Eq, _) if *name == keys.partition)
+            })
+        })
+        .map(|(name, _)| name.as_str())
+        .collect()
+}
+
+/// Returns the attributes with an equality condition, which a new global secondary index
+/// could use as its partition key.
+pub fn equality_attributes(conditions: &[Condition]) -> Vec<&str> {
+    conditions
+        .iter()
+        .filter_map(|condition| match condition {
+            Condition::Compare(name, Comparator::Eq, _) => Some(name.as_str()),
+            _ => None,
+        })
+        .collect()
+}
+
+/// Writes a value as it's written in a condition: a number as it is, and a string in quotes.
+fn describe_value(value: &AttributeValue) -> String {
+    match value {
+        AttributeValue::N(number) => number.clone(),
+        AttributeValue::S(string) => format!("{:?}", string),
+        value => format!("{:?}", value),
+    }
+}
+
+/// Writes a condition with its attribute names and values, rather than the placeholders
+/// that an expression has, so it can be read.
+pub fn describe(condition: &Condition) -> String {
+    match condition {
+        Condition::Compare(name, comparator, value) => {
+            let operator = match comparator {
+                Comparator::Eq => "=",
+                Comparator::Ne => "<>",
+                Comparator::Lt => "<",
+                Comparator::Le => "<=",
+                Comparator::Gt => ">",
+                Comparator::Ge => ">=",
+            };
+            format!("{} {} {}", name, operator, describe_value(value))
+        }
+        Condition::Between(name, low, high) => format!(
+            "{} BETWEEN {} AND {}",
+            name,
+            describe_value(low),
+            describe_value(high)
+        ),
+        Condition::BeginsWith(name, value) => {
+            format!("begins_with({}, {})", name, describe_value(value))
+        }
+        Condition::Contains(name, value) => {
+            format!("contains({}, {})", name, describe_value(value))
+        }
+        Condition::Exists(name) => format!("attribute_exists({})", name),
+        Condition::NotExists(name) => format!("attribute_not_exists({})", name),
+        Condition::And(left, right) => format!("{} AND {}", describe(left), describe(right)),
+        Condition::Or(left, right) => format!("({} OR {})", describe(left), describe(right)),
+        Condition::Not(condition) => format!("NOT ({})", describe(condition)),
+    }
+}
+
+/// Reads a value: a number if it looks like one, otherwise a string.
+/// Quotes make a value a string, such as "2013".
+pub fn parse_value(text: &str) -> AttributeValue {
+    let quoted = text.len() >= 2
+        && ((text.starts_with('"') && text.ends_with('"'))
+            || (text.starts_with('\'') && text.ends_with('\'')));
+
+    if quoted {
+        AttributeValue::S(text[1..text.len() - 1].to_string())
+    } else if text.parse::<f64>().is_ok() {
+        AttributeValue::N(text.to_string())
+    } else {
+        AttributeValue::S(text.to_string())
+    }
+}
+
+/// Makes the number values of the string attributes strings, such as a key attribute
+/// that's defined as a string but compared with __2013__.
+pub fn retype(condition: Condition, strings: &[String]) -> Condition {
+    let value = |name: &str, value: AttributeValue| match value {
+        AttributeValue::N(number) if strings.iter().any(|string| string == name) => {
+            AttributeValue::S(number)
+        }
+        value => value,
+    };
+
+    match condition {
+        Condition::Compare(name, comparator, v) => {
+            let v = value(&name, v);
+            Condition::Compare(name, comparator, v)
+        }
+        Condition::Between(name, low, high) => {
+            let (low, high) = (value(&name, low), value(&name, high));
+            Condition::Between(name, low, high)
+        }
+        Condition::BeginsWith(name, v) => {
+            let v = value(&name, v);
+            Condition::BeginsWith(name, v)
+        }
+        Condition::Contains(name, v) => {
+            let v = value(&name, v);
+            Condition::Contains(name, v)
+        }
+        Condition::And(left, right) => retype(*left, strings).and(retype(*right, strings)),
+        Condition::Or(left, right) => retype(*left, strings).or(retype(*right, strings)),
+        Condition::Not(condition) => retype(*condition, strings).negate(),
+        condition => condition,
+    }
+}
+
+/// Reads a condition written as NAME OPERATOR [VALUE], such as __year = 2013__,
+/// __title begins_with R__, __rating between 5 8__, or __info exists__.
+/// The operators are =, <>, <, <=, >, >=, between, begins_with, contains, exists, and not_exists.
+pub fn parse_condition(text: &str) -> Result<Condition, String> {
+    let parts: Vec<&str> = text.split_whitespace().collect();
+    let invalid = || {
+        format!(
+            "a condition must be NAME OPERATOR [VALUE], such as \"year = 2013\", got {}",
+            text
+        )
+    };
+
+    let (name, operator, values) = match parts.as_slice() {
+        [name, operator, values @ ..] => (name.to_string(), operator.to_ascii_lowercase(), values),
+        _ => return Err(invalid()),
+    };
+
+    let comparator = match operator.as_str() {
+        "=" | "==" => Some(Comparator::Eq),
+        "<>" | "!=" => Some(Comparator::Ne),
+        "<" => Some(Comparator::Lt),
+        "<=" => Some(Comparator::Le),
+        ">" => Some(Comparator::Gt),
+        ">=" => Some(Comparator::Ge),
+        _ => None,
+    };
+
+    // A value can contain spaces, such as a title, so the rest of the words are one value.
+    let value = || {
+        if values.is_empty() {
+            Err(invalid())
+        } else {
+            Ok(parse_value(&values.join(" ")))
+        }
+    };
+
+    match (comparator, operator.as_str()) {
+        (Some(comparator), _) => Ok(Condition::Compare(name, comparator, value()?)),
+        (None, "between") => match values {
+            [low, high] | [low, _, high] => Ok(Condition::between(
+                name,
+                parse_value(low),
+                parse_value(high),
+            )),
+            _ => Err(format!(
+                "between needs two values, such as \"rating between 5 8\", got {}",
+                text
+            )),
+        },
+        (None, "begins_with") => Ok(Condition::begins_with(name, value()?)),
+        (None, "contains") => Ok(Condition::contains(name, value()?)),
+        (None, "exists") if values.is_empty() => Ok(Condition::exists(name)),
+        (None, "not_exists") if values.is_empty() => Ok(Condition::not_exists(name)),
+        _ => Err(invalid()),
+    }
+}
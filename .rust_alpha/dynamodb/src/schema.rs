@@ -0,0 +1,811 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0.
+ */
+
+//! Creates and updates tables to match a schema file, so that the tables the examples use
+//! can be set up the same way every time.
+//!
+//! A schema is a JSON file that lists tables, each with its keys, and optionally its provisioned
+//! capacity (on-demand if not given), global secondary indexes, time to live (TTL) attribute,
+//! and stream:
+//!
+//! ```json
+//! {
+//!   "tables": [
+//!     {
+//!       "name": "sessions",
+//!       "partition_key": { "name": "id", "type": "S" },
+//!       "ttl": "expires_at",
+//!       "indexes": [
+//!         {
+//!           "name": "user-index",
+//!           "partition_key": { "name": "user", "type": "S" },
+//!           "projection": "KEYS_ONLY"
+//!         }
+//!       ]
+//!     }
+//!   ]
+//! }
+//! ```
+//!
+//! `describe` reads a table's current settings into the same form, `changes` compares the two,
+//! and `apply` makes the changes, one at a time, since DynamoDB updates a table one index at a time:
+//!
+//! ```ignore
+//! let current = describe(&client, &table.name).await?;
+//! let changes = changes(&table, current.as_ref());
+//! apply(&client, &table, &changes).await?;
+//! ```
+//!
+//! A table's keys, and an index's keys and projection, can't be changed once it exists,
+//! so a difference in them is a `Change::Conflict`, which `apply` refuses to work around.
+
+use std::collections::BTreeMap;
+use std::error::Error;
+use std::fmt;
+use std::time::Duration;
+
+use dynamodb::error::{DescribeTableError, DescribeTableErrorKind};
+use dynamodb::model::{
+    AttributeDefinition, BillingMode, CreateGlobalSecondaryIndexAction,
+    DeleteGlobalSecondaryIndexAction, GlobalSecondaryIndex, GlobalSecondaryIndexUpdate,
+    KeySchemaElement, KeyType, Projection, ProjectionType, ProvisionedThroughput,
+    ScalarAttributeType, StreamSpecification, StreamViewType, TableDescription,
+    TimeToLiveSpecification, TimeToLiveStatus, UpdateGlobalSecondaryIndexAction,
+};
+use dynamodb::{Client, SdkError};
+use serde::{Deserialize, Serialize};
+
+use crate::waiters::{wait_for_indexes_active, WaitError};
+
+/// How long to wait for a table or index to become active after each change.
+/// Creating an index on a table that has items takes as long as reading them all.
+const ACTIVE_TIMEOUT: Duration = Duration::from_secs(1800);
+
+/// The projection of an index that doesn't say.
+const DEFAULT_PROJECTION: &str = "ALL";
+
+/// The tables in a schema file.
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Schema {
+    pub tables: Vec<TableSchema>,
+}
+
+/// A key attribute, and its type: __S__ for a string, __N__ for a number, or __B__ for binary.
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct Key {
+    pub name: String,
+    #[serde(rename = "type")]
+    pub kind: String,
+}
+
+/// The provisioned read and write capacity units of a table and its indexes.
+#[derive(Clone, Copy, Debug, Deserialize, PartialEq, Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct Capacity {
+    pub read: i64,
+    pub write: i64,
+}
+
+/// A global secondary index.
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct IndexSchema {
+    pub name: String,
+    pub partition_key: Key,
+    #[serde(default)]
+    pub sort_key: Option<Key>,
+    /// __ALL__ (the default), __KEYS_ONLY__, or __INCLUDE__ with `non_key_attributes`.
+    #[serde(default = "default_projection")]
+    pub projection: String,
+    #[serde(default)]
+    pub non_key_attributes: Vec<String>,
+}
+
+fn default_projection() -> String {
+    DEFAULT_PROJECTION.to_string()
+}
+
+/// A table, as a schema file describes it, or as `describe` finds it.
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct TableSchema {
+    pub name: String,
+    pub partition_key: Key,
+    #[serde(default)]
+    pub sort_key: Option<Key>,
+    /// The provisioned capacity, or `None` for on-demand capacity.
+    #[serde(default)]
+    pub capacity: Option<Capacity>,
+    #[serde(default)]
+    pub indexes: Vec<IndexSchema>,
+    /// The attribute that holds when each item expires, if TTL is on.
+    #[serde(default)]
+    pub ttl: Option<String>,
+    /// What the stream records about each change, such as __NEW_AND_OLD_IMAGES__, if there's a stream.
+    #[serde(default)]
+    pub stream: Option<String>,
+}
+
+/// Why a schema can't be used.
+#[derive(Debug)]
+pub struct SchemaError(pub String);
+
+impl fmt::Display for SchemaError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "invalid schema: {}", self.0)
+    }
+}
+
+impl Error for SchemaError {}
+
+impl Schema {
+    /// Reads a schema from JSON, and checks it.
+    pub fn from_json(json: &str) -> Result<Schema, SchemaError> {
+        let schema: Schema = serde_json::from_str(json).map_err(|e| SchemaError(e.to_string()))?;
+
+        let mut names = Vec::new();
+        for table in &schema.tables {
+            if names.contains(&&table.name) {
+                return Err(SchemaError(format!("table {} is listed twice", table.name)));
+            }
+            names.push(&table.name);
+
+            table.check()?;
+        }
+
+        Ok(schema)
+    }
+}
+
+impl TableSchema {
+    /// Checks the types, and that each key attribute has one type everywhere it's used.
+    fn check(&self) -> Result<(), SchemaError> {
+        let error = |reason: String| Err(SchemaError(format!("table {}: {}", self.name, reason)));
+
+        if let Some(capacity) = self.capacity {
+            if capacity.read < 1 || capacity.write < 1 {
+                return error("capacity must be at least 1 read and 1 write unit".to_string());
+            }
+        }
+
+        if let Some(stream) = &self.stream {
+            if !["NEW_IMAGE", "OLD_IMAGE", "NEW_AND_OLD_IMAGES", "KEYS_ONLY"]
+                .contains(&stream.as_str())
+            {
+                return error(format!("{} isn't a stream view type", stream));
+            }
+        }
+
+        let mut index_names = Vec::new();
+        for index in &self.indexes {
+            if index_names.contains(&&index.name) {
+                return error(format!("index {} is listed twice", index.name));
+            }
+            index_names.push(&index.name);
+
+            match index.projection.as_str() {
+                "ALL" | "KEYS_ONLY" if index.non_key_attributes.is_empty() => {}
+                "INCLUDE" if !index.non_key_attributes.is_empty() => {}
+                "ALL" | "KEYS_ONLY" | "INCLUDE" => return error(format!(
+                    "index {} lists non_key_attributes if, and only if, its projection is INCLUDE",
+                    index.name
+                )),
+                projection => {
+                    return error(format!(
+                        "index {} has projection {}, not ALL, KEYS_ONLY, or INCLUDE",
+                        index.name, projection
+                    ))
+                }
+            }
+        }
+
+        let mut types: BTreeMap<&str, &str> = BTreeMap::new();
+        for key in self.keys() {
+            if !["S", "N", "B"].contains(&key.kind.as_str()) {
+                return error(format!(
+                    "key {} has type {}, not S, N, or B",
+                    key.name, key.kind
+                ));
+            }
+
+            match types.insert(&key.name, &key.kind) {
+                Some(kind) if kind != key.kind => {
+                    return error(format!(
+                        "key {} is both type {} and type {}",
+                        key.name, kind, key.kind
+                    ))
+                }
+                _ => {}
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Returns every key attribute of the table and its indexes.
+    fn keys(&self) -> impl Iterator<Item = &Key> {
+        let table = std::iter::once(&self.partition_key).chain(self.sort_key.as_ref());
+        let indexes = self
+            .indexes
+            .iter()
+            .flat_map(|index| std::iter::once(&index.partition_key).chain(index.sort_key.as_ref()));
+
+        table.chain(indexes)
+    }
+
+    /// Returns the attribute definitions of the key attributes, each once,
+    /// which CreateTable needs for the table and all of its indexes.
+    fn attribute_definitions(&self) -> Vec<AttributeDefinition> {
+        definitions(self.keys())
+    }
+
+    /// Returns the attribute definitions that UpdateTable needs to create the index:
+    /// the table's keys and the index's keys. DynamoDB rejects a definition that no key uses,
+    /// so the keys of indexes that haven't been created yet are left out.
+    fn index_attribute_definitions(&self, index: &IndexSchema) -> Vec<AttributeDefinition> {
+        let table = std::iter::once(&self.partition_key).chain(self.sort_key.as_ref());
+        let index = std::iter::once(&index.partition_key).chain(index.sort_key.as_ref());
+
+        definitions(table.chain(index))
+    }
+}
+
+/// Returns an attribute definition for each of the keys, once per attribute, by name.
+fn definitions<'a>(keys: impl Iterator<Item = &'a Key>) -> Vec<AttributeDefinition> {
+    let types: BTreeMap<&str, &str> = keys
+        .map(|key| (key.name.as_str(), key.kind.as_str()))
+        .collect();
+
+    types
+        .into_iter()
+        .map(|(name, kind)| {
+            AttributeDefinition::builder()
+                .attribute_name(name)
+                .attribute_type(ScalarAttributeType::from(kind))
+                .build()
+        })
+        .collect()
+}
+
+/// A change that makes a table match its schema.
+#[derive(Clone, Debug, PartialEq, Serialize)]
+#[serde(tag = "change", content = "detail", rename_all = "kebab-case")]
+pub enum Change {
+    /// Create the table, with its indexes and stream.
+    CreateTable,
+    /// Switch to provisioned capacity, or change it, or, with `None`, switch to on-demand capacity.
+    SetCapacity(Option<Capacity>),
+    CreateIndex(IndexSchema),
+    DeleteIndex(String),
+    EnableStream(String),
+    DisableStream,
+    EnableTtl(String),
+    DisableTtl(String),
+    /// A difference that can't be changed in place, and why.
+    Conflict(String),
+}
+
+impl fmt::Display for Change {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Change::CreateTable => write!(f, "+ create the table"),
+            Change::SetCapacity(Some(capacity)) => write!(
+                f,
+                "~ set provisioned capacity to {} read and {} write units",
+                capacity.read, capacity.write
+            ),
+            Change::SetCapacity(None) => write!(f, "~ switch to on-demand capacity"),
+            Change::CreateIndex(index) => write!(f, "+ create index {}", index.name),
+            Change::DeleteIndex(name) => write!(f, "- delete index {}", name),
+            Change::EnableStream(view) => write!(f, "+ enable a stream of {}", view),
+            Change::DisableStream => write!(f, "- disable the stream"),
+            Change::EnableTtl(attribute) => write!(f, "+ enable TTL on {}", attribute),
+            Change::DisableTtl(attribute) => write!(f, "- disable TTL on {}", attribute),
+            Change::Conflict(reason) => write!(f, "! {}", reason),
+        }
+    }
+}
+
+/// Formats a table's or index's keys, such as `year (N), title (S)`.
+fn format_keys(partition_key: &Key, sort_key: Option<&Key>) -> String {
+    std::iter::once(partition_key)
+        .chain(sort_key)
+        .map(|key| format!("{} ({})", key.name, key.kind))
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+/// Returns whether two indexes have the same keys and projection,
+/// whatever order their non-key attributes are listed in.
+fn same_index(a: &IndexSchema, b: &IndexSchema) -> bool {
+    let sorted = |index: &IndexSchema| {
+        let mut attributes = index.non_key_attributes.clone();
+        attributes.sort();
+        attributes
+    };
+
+    a.partition_key == b.partition_key
+        && a.sort_key == b.sort_key
+        && a.projection == b.projection
+        && sorted(a) == sorted(b)
+}
+
+/// Returns the changes that make the current table, or `None` if there's no table, match the schema.
+pub fn changes(schema: &TableSchema, current: Option<&TableSchema>) -> Vec<Change> {
+    let current = match current {
+        Some(current) => current,
+        None => {
+            let mut changes = vec![Change::CreateTable];
+            // TTL can only be turned on once the table exists.
+            if let Some(attribute) = &schema.ttl {
+                changes.push(Change::EnableTtl(attribute.clone()));
+            }
+            return changes;
+        }
+    };
+
+    let mut changes = Vec::new();
+
+    if schema.partition_key != current.partition_key || schema.sort_key != current.sort_key {
+        changes.push(Change::Conflict(format!(
+            "the table's keys are {}, not {}; a table's keys can't be changed",
+            format_keys(&current.partition_key, current.sort_key.as_ref()),
+            format_keys(&schema.partition_key, schema.sort_key.as_ref())
+        )));
+    }
+
+    for index in &current.indexes {
+        match schema
+            .indexes
+            .iter()
+            .find(|wanted| wanted.name == index.name)
+        {
+            None => changes.push(Change::DeleteIndex(index.name.clone())),
+            Some(wanted) if !same_index(wanted, index) => changes.push(Change::Conflict(format!(
+                "index {} has different keys or projection; an index can't be changed, \
+                 so rename it in the schema, or delete it first",
+                index.name
+            ))),
+            Some(_) => {}
+        }
+    }
+
+    // The capacity is set after the extra indexes are deleted, and before the new ones are
+    // created, since switching to provisioned capacity sets the capacity of every index.
+    if schema.capacity != current.capacity {
+        changes.push(Change::SetCapacity(schema.capacity));
+    }
+
+    for index in &schema.indexes {
+        if !current.indexes.iter().any(|found| found.name == index.name) {
+            changes.push(Change::CreateIndex(index.clone()));
+        }
+    }
+
+    if schema.stream != current.stream {
+        // A stream's view type can't be changed, but the stream can be replaced with a new one.
+        if current.stream.is_some() {
+            changes.push(Change::DisableStream);
+        }
+        if let Some(view) = &schema.stream {
+            changes.push(Change::EnableStream(view.clone()));
+        }
+    }
+
+    match (&current.ttl, &schema.ttl) {
+        (Some(found), Some(wanted)) if found != wanted => changes.push(Change::Conflict(format!(
+            "TTL is on {}, not {}; disable it, and an hour later, enable it on {}, \
+             since DynamoDB allows one TTL change an hour",
+            found, wanted, wanted
+        ))),
+        (Some(found), None) => changes.push(Change::DisableTtl(found.clone())),
+        (None, Some(wanted)) => changes.push(Change::EnableTtl(wanted.clone())),
+        _ => {}
+    }
+
+    changes
+}
+
+/// Reads a key attribute's name and type from a table's description.
+fn key(
+    key_schema: &[KeySchemaElement],
+    definitions: &[AttributeDefinition],
+    key_type: KeyType,
+) -> Option<Key> {
+    let name = key_schema
+        .iter()
+        .find(|element| element.key_type.as_ref() == Some(&key_type))?
+        .attribute_name
+        .clone()?;
+
+    let kind = definitions
+        .iter()
+        .find(|definition| definition.attribute_name.as_deref() == Some(&name))
+        .and_then(|definition| definition.attribute_type.as_ref())
+        .map(|kind| kind.as_str().to_string())
+        .unwrap_or_default();
+
+    Some(Key { name, kind })
+}
+
+/// Reads the table's current settings, or returns `None` if there's no such table.
+pub async fn describe(
+    client: &Client,
+    table: &str,
+) -> Result<Option<TableSchema>, dynamodb::Error> {
+    let description: TableDescription = match client.describe_table().table_name(table).send().await
+    {
+        Ok(resp) => match resp.table {
+            Some(description) => description,
+            None => return Ok(None),
+        },
+        Err(SdkError::ServiceError {
+            err:
+                DescribeTableError {
+                    kind: DescribeTableErrorKind::ResourceNotFoundException(_),
+                    ..
+                },
+            ..
+        }) => return Ok(None),
+        Err(e) => return Err(e.into()),
+    };
+
+    let definitions = description.attribute_definitions.unwrap_or_default();
+    let key_schema = description.key_schema.unwrap_or_default();
+
+    let partition_key = match key(&key_schema, &definitions, KeyType::Hash) {
+        Some(key) => key,
+        None => return Ok(None),
+    };
+    let sort_key = key(&key_schema, &definitions, KeyType::Range);
+
+    // A table that has never been switched to on-demand capacity has no billing mode summary.
+    let on_demand = description
+        .billing_mode_summary
+        .and_then(|summary| summary.billing_mode)
+        == Some(BillingMode::PayPerRequest);
+    let capacity = if on_demand {
+        None
+    } else {
+        description
+            .provisioned_throughput
+            .map(|throughput| Capacity {
+                read: throughput.read_capacity_units.unwrap_or_default(),
+                write: throughput.write_capacity_units.unwrap_or_default(),
+            })
+    };
+
+    let indexes = description
+        .global_secondary_indexes
+        .unwrap_or_default()
+        .into_iter()
+        .filter_map(|index| {
+            let key_schema = index.key_schema.unwrap_or_default();
+            let projection = index.projection;
+
+            Some(IndexSchema {
+                name: index.index_name?,
+                partition_key: key(&key_schema, &definitions, KeyType::Hash)?,
+                sort_key: key(&key_schema, &definitions, KeyType::Range),
+                projection: projection
+                    .as_ref()
+                    .and_then(|projection| projection.projection_type.as_ref())
+                    .map(|projection| projection.as_str().to_string())
+                    .unwrap_or_else(default_projection),
+                non_key_attributes: projection
+                    .and_then(|projection| projection.non_key_attributes)
+                    .unwrap_or_default(),
+            })
+        })
+        .collect();
+
+    let stream = description
+        .stream_specification
+        .filter(|stream| stream.stream_enabled == Some(true))
+        .and_then(|stream| stream.stream_view_type)
+        .map(|view| view.as_str().to_string());
+
+    // TTL that's being turned on counts as on, so that it isn't turned on twice.
+    let ttl = client
+        .describe_time_to_live()
+        .table_name(table)
+        .send()
+        .await?
+        .time_to_live_description
+        .filter(|ttl| {
+            matches!(
+                ttl.time_to_live_status,
+                Some(TimeToLiveStatus::Enabled) | Some(TimeToLiveStatus::Enabling)
+            )
+        })
+        .and_then(|ttl| ttl.attribute_name);
+
+    Ok(Some(TableSchema {
+        name: table.to_string(),
+        partition_key,
+        sort_key,
+        capacity,
+        indexes,
+        ttl,
+        stream,
+    }))
+}
+
+/// Why a change couldn't be made.
+#[derive(Debug)]
+pub enum ApplyError {
+    /// A DynamoDB call failed.
+    Sdk(dynamodb::Error),
+    /// The table or an index didn't become active.
+    Wait(WaitError),
+    /// The changes include a conflict, so none were made.
+    Conflict(String),
+}
+
+impl fmt::Display for ApplyError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ApplyError::Sdk(e) => write!(f, "{}", e),
+            ApplyError::Wait(e) => write!(f, "{}", e),
+            ApplyError::Conflict(table) => write!(
+                f,
+                "table {} can't be changed to match the schema, so it wasn't changed",
+                table
+            ),
+        }
+    }
+}
+
+impl Error for ApplyError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            ApplyError::Sdk(e) => Some(e),
+            ApplyError::Wait(e) => Some(e),
+            ApplyError::Conflict(_) => None,
+        }
+    }
+}
+
+impl From<dynamodb::Error> for ApplyError {
+    fn from(e: dynamodb::Error) -> Self {
+        ApplyError::Sdk(e)
+    }
+}
+
+impl From<WaitError> for ApplyError {
+    fn from(e: WaitError) -> Self {
+        ApplyError::Wait(e)
+    }
+}
+
+/// Returns the key schema for a partition key and an optional sort key.
+fn key_schema(partition_key: &Key, sort_key: Option<&Key>) -> Vec<KeySchemaElement> {
+    std::iter::once((partition_key, KeyType::Hash))
+        .chain(sort_key.map(|key| (key, KeyType::Range)))
+        .map(|(key, key_type)| {
+            KeySchemaElement::builder()
+                .attribute_name(&key.name)
+                .key_type(key_type)
+                .build()
+        })
+        .collect()
+}
+
+fn projection(index: &IndexSchema) -> Projection {
+    let non_key_attributes = if index.non_key_attributes.is_empty() {
+        None
+    } else {
+        Some(index.non_key_attributes.clone())
+    };
+
+    Projection::builder()
+        .projection_type(ProjectionType::from(index.projection.as_str()))
+        .set_non_key_attributes(non_key_attributes)
+        .build()
+}
+
+fn throughput(capacity: Capacity) -> ProvisionedThroughput {
+    ProvisionedThroughput::builder()
+        .read_capacity_units(capacity.read)
+        .write_capacity_units(capacity.write)
+        .build()
+}
+
+fn billing_mode(capacity: Option<Capacity>) -> BillingMode {
+    match capacity {
+        Some(_) => BillingMode::Provisioned,
+        None => BillingMode::PayPerRequest,
+    }
+}
+
+fn stream_specification(view: Option<&str>) -> StreamSpecification {
+    StreamSpecification::builder()
+        .stream_enabled(view.is_some())
+        .set_stream_view_type(view.map(StreamViewType::from))
+        .build()
+}
+
+/// Makes the changes, in order, waiting for the table and its indexes to be active after each one.
+/// Makes none of them if any is a conflict.
+pub async fn apply(
+    client: &Client,
+    schema: &TableSchema,
+    changes: &[Change],
+) -> Result<(), ApplyError> {
+    if changes
+        .iter()
+        .any(|change| matches!(change, Change::Conflict(_)))
+    {
+        return Err(ApplyError::Conflict(schema.name.clone()));
+    }
+
+    let table = schema.name.as_str();
+
+    for change in changes {
+        match change {
+            Change::CreateTable => {
+                let indexes: Vec<GlobalSecondaryIndex> = schema
+                    .indexes
+                    .iter()
+                    .map(|index| {
+                        GlobalSecondaryIndex::builder()
+                            .index_name(&index.name)
+                            .set_key_schema(Some(key_schema(
+                                &index.partition_key,
+                                index.sort_key.as_ref(),
+                            )))
+                            .projection(projection(index))
+                            .set_provisioned_throughput(schema.capacity.map(throughput))
+                            .build()
+                    })
+                    .collect();
+
+                client
+                    .create_table()
+                    .table_name(table)
+                    .set_key_schema(Some(key_schema(
+                        &schema.partition_key,
+                        schema.sort_key.as_ref(),
+                    )))
+                    .set_attribute_definitions(Some(schema.attribute_definitions()))
+                    .billing_mode(billing_mode(schema.capacity))
+                    .set_provisioned_throughput(schema.capacity.map(throughput))
+                    .set_global_secondary_indexes(if indexes.is_empty() {
+                        None
+                    } else {
+                        Some(indexes)
+                    })
+                    .set_stream_specification(
+                        schema
+                            .stream
+                            .as_deref()
+                            .map(|view| stream_specification(Some(view))),
+                    )
+                    .send()
+                    .await
+                    .map_err(dynamodb::Error::from)?;
+            }
+            Change::SetCapacity(capacity) => {
+                // Switching to provisioned capacity sets the capacity of every index too,
+                // except the ones that are created afterwards.
+                let index_updates: Option<Vec<GlobalSecondaryIndexUpdate>> =
+                    capacity.map(|capacity| {
+                        schema
+                            .indexes
+                            .iter()
+                            .filter(|index| {
+                                !changes.iter().any(|change| {
+                                    matches!(change, Change::CreateIndex(new) if new.name == index.name)
+                                })
+                            })
+                            .map(|index| {
+                                GlobalSecondaryIndexUpdate::builder()
+                                    .update(
+                                        UpdateGlobalSecondaryIndexAction::builder()
+                                            .index_name(&index.name)
+                                            .provisioned_throughput(throughput(capacity))
+                                            .build(),
+                                    )
+                                    .build()
+                            })
+                            .collect()
+                    });
+
+                client
+                    .update_table()
+                    .table_name(table)
+                    .billing_mode(billing_mode(*capacity))
+                    .set_provisioned_throughput(capacity.map(throughput))
+                    .set_global_secondary_index_updates(
+                        index_updates.filter(|updates| !updates.is_empty()),
+                    )
+                    .send()
+                    .await
+                    .map_err(dynamodb::Error::from)?;
+            }
+            Change::CreateIndex(index) => {
+                client
+                    .update_table()
+                    .table_name(table)
+                    .set_attribute_definitions(Some(schema.index_attribute_definitions(index)))
+                    .global_secondary_index_updates(
+                        GlobalSecondaryIndexUpdate::builder()
+                            .create(
+                                CreateGlobalSecondaryIndexAction::builder()
+                                    .index_name(&index.name)
+                                    .set_key_schema(Some(key_schema(
+                                        &index.partition_key,
+                                        index.sort_key.as_ref(),
+                                    )))
+                                    .projection(projection(index))
+                                    .set_provisioned_throughput(schema.capacity.map(throughput))
+                                    .build(),
+                            )
+                            .build(),
+                    )
+                    .send()
+                    .await
+                    .map_err(dynamodb::Error::from)?;
+            }
+            Change::DeleteIndex(name) => {
+                client
+                    .update_table()
+                    .table_name(table)
+                    .global_secondary_index_updates(
+                        GlobalSecondaryIndexUpdate::builder()
+                            .delete(
+                                DeleteGlobalSecondaryIndexAction::builder()
+                                    .index_name(name)
+                                    .build(),
+                            )
+                            .build(),
+                    )
+                    .send()
+                    .await
+                    .map_err(dynamodb::Error::from)?;
+            }
+            Change::EnableStream(view) => {
+                client
+                    .update_table()
+                    .table_name(table)
+                    .stream_specification(stream_specification(Some(view)))
+                    .send()
+                    .await
+                    .map_err(dynamodb::Error::from)?;
+            }
+            Change::DisableStream => {
+                client
+                    .update_table()
+                    .table_name(table)
+                    .stream_specification(stream_specification(None))
+                    .send()
+                    .await
+                    .map_err(dynamodb::Error::from)?;
+            }
+            Change::EnableTtl(attribute) | Change::DisableTtl(attribute) => {
+                client
+                    .update_time_to_live()
+                    .table_name(table)
+                    .time_to_live_specification(
+                        TimeToLiveSpecification::builder()
+                            .attribute_name(attribute)
+                            .enabled(matches!(change, Change::EnableTtl(_)))
+                            .build(),
+                    )
+                    .send()
+                    .await
+                    .map_err(dynamodb::Error::from)?;
+            }
+            Change::Conflict(_) => unreachable!("conflicts are rejected before any change"),
+        }
+
+        // The next change fails while the table, or an index, is still being created or updated.
+        wait_for_indexes_active(client, table, ACTIVE_TIMEOUT).await?;
+    }
+
+    Ok(())
+}
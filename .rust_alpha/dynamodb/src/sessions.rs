@@ -0,0 +1,357 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0.
+ */
+
+//! A store for web sessions, which expire after a period without use.
+//!
+//! Each session is an item whose __expires_at__ attribute is the time, in seconds since the epoch,
+//! when it expires. The table's time to live (TTL) setting is on that attribute, so DynamoDB deletes
+//! expired sessions for free. TTL deletes items in the background, typically within 48 hours of
+//! expiry rather than at the second, so the store also checks __expires_at__ itself, and treats an
+//! expired session that hasn't been deleted yet as gone.
+//!
+//! Using a session moves its expiry forward (a sliding expiration). The update is conditional, so
+//! that touching a session that has expired, or that was deleted by signing out, can't bring it
+//! back. A session also has a maximum lifetime, after which it expires however often it's used:
+//!
+//! ```ignore
+//! let store = SessionStore::new(&client, "sessions", idle_timeout, max_lifetime);
+//! let session = store.create("carlos", data).await?;
+//! // On each request:
+//! match store.touch(&session.id).await? {
+//!     Some(session) => { /* signed in as session.user */ }
+//!     None => { /* expired or signed out */ }
+//! }
+//! ```
+
+use std::collections::HashMap;
+use std::error::Error;
+use std::fmt;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use dynamodb::error::{PutItemErrorKind, UpdateItemErrorKind};
+use dynamodb::model::{
+    AttributeDefinition, AttributeValue, BillingMode, KeySchemaElement, KeyType, ReturnValue,
+    ScalarAttributeType, TimeToLiveSpecification,
+};
+use dynamodb::{Client, SdkError};
+use serde::{Deserialize, Serialize};
+
+use crate::expressions::{Condition, Expression, Update};
+use crate::items::{from_item, to_item, ItemError};
+use crate::waiters::{wait_for_table_active, WaitError};
+
+/// The partition key of the table.
+pub const ID_ATTRIBUTE: &str = "id";
+
+/// The attribute that the table's TTL setting is on.
+pub const TTL_ATTRIBUTE: &str = "expires_at";
+
+/// How many times to try another ID, if a new session's random ID is already taken.
+const MAX_ID_ATTEMPTS: u32 = 3;
+
+/// A session, as it's stored in the table. Times are seconds since the epoch.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct Session {
+    pub id: String,
+    pub user: String,
+    #[serde(default)]
+    pub data: HashMap<String, String>,
+    pub created_at: i64,
+    pub last_used_at: i64,
+    /// When the session expires if it isn't used again. TTL deletes the item after this.
+    pub expires_at: i64,
+    /// When the session expires even if it's being used.
+    pub max_expires_at: i64,
+}
+
+impl Session {
+    /// Whether the session has expired at `now`, whether or not TTL has deleted it yet.
+    pub fn is_expired(&self, now: i64) -> bool {
+        self.expires_at <= now || self.max_expires_at <= now
+    }
+}
+
+/// Why a session operation failed.
+#[derive(Debug)]
+pub enum SessionError {
+    /// DynamoDB returned an error.
+    Sdk(dynamodb::Error),
+    /// An item in the table isn't a session.
+    Item(ItemError),
+    /// The new table didn't become active.
+    Wait(WaitError),
+    /// Every random ID that was tried is already taken, which is all but impossible.
+    IdTaken,
+}
+
+impl fmt::Display for SessionError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SessionError::Sdk(e) => write!(f, "{}", e),
+            SessionError::Item(e) => write!(f, "the item isn't a session: {}", e),
+            SessionError::Wait(e) => write!(f, "{}", e),
+            SessionError::IdTaken => write!(f, "couldn't find an unused session ID"),
+        }
+    }
+}
+
+impl Error for SessionError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            SessionError::Sdk(e) => Some(e),
+            SessionError::Item(e) => Some(e),
+            SessionError::Wait(e) => Some(e),
+            SessionError::IdTaken => None,
+        }
+    }
+}
+
+impl From<dynamodb::Error> for SessionError {
+    fn from(e: dynamodb::Error) -> Self {
+        SessionError::Sdk(e)
+    }
+}
+
+impl From<ItemError> for SessionError {
+    fn from(e: ItemError) -> Self {
+        SessionError::Item(e)
+    }
+}
+
+impl From<WaitError> for SessionError {
+    fn from(e: WaitError) -> Self {
+        SessionError::Wait(e)
+    }
+}
+
+/// Returns the current time, in seconds since the epoch.
+pub fn now() -> i64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|since| since.as_secs() as i64)
+        .unwrap_or_default()
+}
+
+/// Returns a new session ID: 128 random bits, in hex, so that IDs can't be guessed.
+fn new_id() -> String {
+    format!("{:032x}", rand::random::<u128>())
+}
+
+/// Returns the number as a DynamoDB number.
+fn number(n: i64) -> AttributeValue {
+    AttributeValue::N(n.to_string())
+}
+
+/// Stores sessions in a DynamoDB table.
+pub struct SessionStore<'a> {
+    client: &'a Client,
+    table: String,
+    idle_timeout: Duration,
+    max_lifetime: Duration,
+}
+
+impl<'a> SessionStore<'a> {
+    /// Returns a store whose sessions expire after `idle_timeout` without being used,
+    /// or `max_lifetime` after they're created.
+    pub fn new(
+        client: &'a Client,
+        table: impl Into<String>,
+        idle_timeout: Duration,
+        max_lifetime: Duration,
+    ) -> Self {
+        SessionStore {
+            client,
+            table: table.into(),
+            idle_timeout,
+            max_lifetime,
+        }
+    }
+
+    /// Creates the table, with on-demand capacity, waits for it to be active,
+    /// and turns on TTL for the __expires_at__ attribute.
+    pub async fn create_table(&self) -> Result<(), SessionError> {
+        self.client
+            .create_table()
+            .table_name(&self.table)
+            .key_schema(
+                KeySchemaElement::builder()
+                    .attribute_name(ID_ATTRIBUTE)
+                    .key_type(KeyType::Hash)
+                    .build(),
+            )
+            .attribute_definitions(
+                AttributeDefinition::builder()
+                    .attribute_name(ID_ATTRIBUTE)
+                    .attribute_type(ScalarAttributeType::S)
+                    .build(),
+            )
+            .billing_mode(BillingMode::PayPerRequest)
+            .send()
+            .await
+            .map_err(dynamodb::Error::from)?;
+
+        wait_for_table_active(self.client, &self.table, Duration::from_secs(60)).await?;
+
+        // TTL can only be turned on once the table is active.
+        self.client
+            .update_time_to_live()
+            .table_name(&self.table)
+            .time_to_live_specification(
+                TimeToLiveSpecification::builder()
+                    .attribute_name(TTL_ATTRIBUTE)
+                    .enabled(true)
+                    .build(),
+            )
+            .send()
+            .await
+            .map_err(dynamodb::Error::from)?;
+
+        Ok(())
+    }
+
+    /// Creates a session for the user.
+    /// The put is conditional on the ID being unused, so a new session never replaces another.
+    pub async fn create(
+        &self,
+        user: &str,
+        data: HashMap<String, String>,
+    ) -> Result<Session, SessionError> {
+        let now = now();
+        let mut session = Session {
+            id: new_id(),
+            user: user.to_string(),
+            data,
+            created_at: now,
+            last_used_at: now,
+            expires_at: now + self.idle_timeout.as_secs() as i64,
+            max_expires_at: now + self.max_lifetime.as_secs() as i64,
+        };
+        session.expires_at = session.expires_at.min(session.max_expires_at);
+
+        let expression = Expression::builder()
+            .condition(Condition::not_exists(ID_ATTRIBUTE))
+            .build();
+
+        for _ in 0..MAX_ID_ATTEMPTS {
+            match self
+                .client
+                .put_item()
+                .table_name(&self.table)
+                .set_item(Some(to_item(&session)?))
+                .set_condition_expression(expression.condition.clone())
+                .set_expression_attribute_names(expression.names.clone())
+                .send()
+                .await
+            {
+                Ok(_) => return Ok(session),
+                Err(SdkError::ServiceError { err, .. })
+                    if matches!(
+                        err.kind,
+                        PutItemErrorKind::ConditionalCheckFailedException(_)
+                    ) =>
+                {
+                    session.id = new_id();
+                }
+                Err(e) => return Err(dynamodb::Error::from(e).into()),
+            }
+        }
+
+        Err(SessionError::IdTaken)
+    }
+
+    /// Returns the session, or `None` if there's no such session or it has expired.
+    /// Doesn't move the session's expiry; use `touch` for that.
+    pub async fn get(&self, id: &str) -> Result<Option<Session>, SessionError> {
+        let item = self
+            .client
+            .get_item()
+            .table_name(&self.table)
+            .key(ID_ATTRIBUTE, AttributeValue::S(id.to_string()))
+            .consistent_read(true)
+            .send()
+            .await
+            .map_err(dynamodb::Error::from)?
+            .item;
+
+        let session: Session = match item {
+            Some(item) => from_item(item)?,
+            None => return Ok(None),
+        };
+
+        if session.is_expired(now()) {
+            return Ok(None);
+        }
+
+        Ok(Some(session))
+    }
+
+    /// Marks the session as used, moving its expiry `idle_timeout` from now, and returns it.
+    /// Returns `None` if there's no such session or it has expired; the condition on the update
+    /// makes sure that an expired session stays expired, even before TTL deletes it.
+    pub async fn touch(&self, id: &str) -> Result<Option<Session>, SessionError> {
+        let now = now();
+
+        // The new expiry can be past the maximum lifetime; `is_expired` checks both, so the maximum still holds.
+        let expression = Expression::builder()
+            .condition(
+                Condition::exists(ID_ATTRIBUTE)
+                    .and(Condition::gt(TTL_ATTRIBUTE, number(now)))
+                    .and(Condition::gt("max_expires_at", number(now))),
+            )
+            .update(Update::new().set("last_used_at", number(now)).set(
+                TTL_ATTRIBUTE,
+                number(now + self.idle_timeout.as_secs() as i64),
+            ))
+            .build();
+
+        match self
+            .client
+            .update_item()
+            .table_name(&self.table)
+            .key(ID_ATTRIBUTE, AttributeValue::S(id.to_string()))
+            .set_condition_expression(expression.condition)
+            .set_update_expression(expression.update)
+            .set_expression_attribute_names(expression.names)
+            .set_expression_attribute_values(expression.values)
+            .return_values(ReturnValue::AllNew)
+            .send()
+            .await
+        {
+            Ok(resp) => match resp.attributes {
+                Some(item) => Ok(Some(from_item(item)?)),
+                None => Ok(None),
+            },
+            Err(SdkError::ServiceError { err, .. })
+                if matches!(
+                    err.kind,
+                    UpdateItemErrorKind::ConditionalCheckFailedException(_)
+                ) =>
+            {
+                Ok(None)
+            }
+            Err(e) => Err(dynamodb::Error::from(e).into()),
+        }
+    }
+
+    /// Deletes the session, as when the user signs out.
+    /// Returns whether there was a session that hadn't expired.
+    pub async fn delete(&self, id: &str) -> Result<bool, SessionError> {
+        let old = self
+            .client
+            .delete_item()
+            .table_name(&self.table)
+            .key(ID_ATTRIBUTE, AttributeValue::S(id.to_string()))
+            .return_values(ReturnValue::AllOld)
+            .send()
+            .await
+            .map_err(dynamodb::Error::from)?
+            .attributes;
+
+        match old {
+            Some(item) => Ok(!from_item::<Session>(item)?.is_expired(now())),
+            None => Ok(false),
+        }
+    }
+}
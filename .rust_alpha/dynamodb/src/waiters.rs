@@ -0,0 +1,152 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0.
+ */
+
+//! Waits for a DynamoDB table to reach a state.
+//!
+//! `CreateTable` returns as soon as DynamoDB accepts the request, while the table is still
+//! __CREATING__. Reading or writing items fails until the table is __ACTIVE__, so examples
+//! that create a table wait for it first:
+//!
+//! ```ignore
+//! client.create_table().table_name(table) /* ... */ .send().await?;
+//! wait_for_table_active(&client, table, Duration::from_secs(60)).await?;
+//! ```
+//!
+//! UpdateTable leaves the table __UPDATING__, and a new global secondary index __CREATING__
+//! while it's filled from the table's items, which can take much longer than the table.
+//! A table takes one such change at a time, so `wait_for_indexes_active` waits for both.
+
+use std::error::Error;
+use std::fmt;
+use std::time::{Duration, Instant};
+
+use dynamodb::model::{IndexStatus, TableStatus};
+use dynamodb::Client;
+use tokio::time::sleep;
+
+/// How long to wait before the first retry. The delay doubles after each retry.
+const INITIAL_DELAY: Duration = Duration::from_millis(500);
+
+/// The longest delay between two retries.
+const MAX_DELAY: Duration = Duration::from_secs(10);
+
+/// Why waiting for a table failed.
+#[derive(Debug)]
+pub enum WaitError {
+    /// DescribeTable failed.
+    Sdk(dynamodb::Error),
+    /// The table didn't become active before the timeout. Holds the last status seen.
+    Timeout(String, Option<TableStatus>),
+}
+
+impl fmt::Display for WaitError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            WaitError::Sdk(e) => write!(f, "{}", e),
+            WaitError::Timeout(table, status) => write!(
+                f,
+                "table {} did not become active in time, last status: {}",
+                table,
+                status
+                    .as_ref()
+                    .map(|status| status.as_str())
+                    .unwrap_or("unknown")
+            ),
+        }
+    }
+}
+
+impl Error for WaitError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            WaitError::Sdk(e) => Some(e),
+            WaitError::Timeout(..) => None,
+        }
+    }
+}
+
+impl From<dynamodb::Error> for WaitError {
+    fn from(e: dynamodb::Error) -> Self {
+        WaitError::Sdk(e)
+    }
+}
+
+/// Polls DescribeTable until the table is __ACTIVE__, backing off exponentially between calls.
+/// Returns `WaitError::Timeout` if the table isn't active within `timeout`.
+pub async fn wait_for_table_active(
+    client: &Client,
+    table: &str,
+    timeout: Duration,
+) -> Result<(), WaitError> {
+    let deadline = Instant::now() + timeout;
+    let mut delay = INITIAL_DELAY;
+
+    loop {
+        let status = client
+            .describe_table()
+            .table_name(table)
+            .send()
+            .await
+            .map_err(dynamodb::Error::from)?
+            .table
+            .and_then(|table| table.table_status);
+
+        if status == Some(TableStatus::Active) {
+            return Ok(());
+        }
+
+        let now = Instant::now();
+        if now >= deadline {
+            return Err(WaitError::Timeout(table.to_string(), status));
+        }
+
+        // Don't sleep past the deadline; check one last time when it's reached.
+        sleep(delay.min(deadline - now)).await;
+        delay = (delay * 2).min(MAX_DELAY);
+    }
+}
+
+/// Polls DescribeTable until the table and all its global secondary indexes are __ACTIVE__,
+/// and any index that's being deleted is gone, backing off exponentially between calls.
+/// Returns `WaitError::Timeout` if they aren't within `timeout`.
+pub async fn wait_for_indexes_active(
+    client: &Client,
+    table: &str,
+    timeout: Duration,
+) -> Result<(), WaitError> {
+    let deadline = Instant::now() + timeout;
+    let mut delay = INITIAL_DELAY;
+
+    loop {
+        let description = client
+            .describe_table()
+            .table_name(table)
+            .send()
+            .await
+            .map_err(dynamodb::Error::from)?
+            .table;
+
+        let status = description
+            .as_ref()
+            .and_then(|table| table.table_status.clone());
+        let indexes_active = description
+            .and_then(|table| table.global_secondary_indexes)
+            .unwrap_or_default()
+            .iter()
+            .all(|index| index.index_status == Some(IndexStatus::Active));
+
+        if status == Some(TableStatus::Active) && indexes_active {
+            return Ok(());
+        }
+
+        let now = Instant::now();
+        if now >= deadline {
+            return Err(WaitError::Timeout(table.to_string(), status));
+        }
+
+        sleep(delay.min(deadline - now)).await;
+        delay = (delay * 2).min(MAX_DELAY);
+    }
+}
@@ -0,0 +1,288 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0.
+ */
+
+//! Runs the DynamoDB examples against LocalStack or DynamoDB Local,
+//! and checks that a table can be created, filled, read, emptied, and deleted.
+//!
+//! Start LocalStack with the docker-compose.yml file in the parent directory, and then run:
+//!
+//! ```text
+//! cargo test -p dynamodb-code-examples --features integration
+//! ```
+//!
+//! To use another endpoint, such as DynamoDB Local on port 8000, set `INTEGRATION_ENDPOINT_URL`.
+
+#![cfg(feature = "integration")]
+
+use std::env;
+use std::process::Command;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use serde_json::Value;
+
+/// The endpoint of LocalStack, which the tests use unless INTEGRATION_ENDPOINT_URL is set.
+const DEFAULT_ENDPOINT: &str = "http://localhost:4566";
+
+/// Returns a command that runs an example against the local endpoint, with dummy credentials,
+/// so that the tests can't reach a real AWS account.
+fn example(path: &str) -> Command {
+    let endpoint =
+        env::var("INTEGRATION_ENDPOINT_URL").unwrap_or_else(|_| DEFAULT_ENDPOINT.to_string());
+
+    let mut command = Command::new(path);
+    command
+        .env("AWS_ENDPOINT_URL", endpoint)
+        .env("AWS_DEFAULT_REGION", "us-west-2")
+        .env("AWS_ACCESS_KEY_ID", "test")
+        .env("AWS_SECRET_ACCESS_KEY", "test")
+        .env_remove("AWS_SESSION_TOKEN");
+
+    command
+}
+
+/// Runs an example with `--output json`, checks that it succeeds, and returns what it printed.
+fn run(path: &str, args: &[&str]) -> Value {
+    let output = example(path)
+        .args(args)
+        .args(&["--output", "json"])
+        .output()
+        .unwrap_or_else(|e| panic!("couldn't run {}: {}", path, e));
+
+    assert!(
+        output.status.success(),
+        "{} {:?} failed: {}",
+        path,
+        args,
+        String::from_utf8_lossy(&output.stderr)
+    );
+
+    serde_json::from_slice(&output.stdout).unwrap_or_else(|e| {
+        panic!(
+            "{} {:?} didn't print JSON ({}): {}",
+            path,
+            args,
+            e,
+            String::from_utf8_lossy(&output.stdout)
+        )
+    })
+}
+
+/// A table that's deleted when the test ends, even if the test fails.
+struct Table(String);
+
+impl Table {
+    fn new() -> Self {
+        let nanos = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|since| since.as_nanos())
+            .unwrap_or_default();
+
+        Table(format!("integration-{}", nanos))
+    }
+}
+
+impl Drop for Table {
+    fn drop(&mut self) {
+        let _ = example(env!("CARGO_BIN_EXE_delete-table"))
+            .args(&["-t", &self.0])
+            .output();
+    }
+}
+
+/// Returns the names of the tables.
+fn table_names() -> Vec<String> {
+    run(env!("CARGO_BIN_EXE_list-tables"), &[])["tables"]
+        .as_array()
+        .cloned()
+        .unwrap_or_default()
+        .into_iter()
+        .filter_map(|name| name.as_str().map(str::to_string))
+        .collect()
+}
+
+/// Returns the items in the table.
+fn items(table: &str) -> Vec<Value> {
+    run(env!("CARGO_BIN_EXE_list-items"), &["-t", table])["items"]
+        .as_array()
+        .cloned()
+        .unwrap_or_default()
+}
+
+#[test]
+fn create_put_get_delete() {
+    let table = Table::new();
+    let name = table.0.as_str();
+
+    let created = run(
+        env!("CARGO_BIN_EXE_create-table"),
+        &["-t", name, "-k", "username"],
+    );
+    assert_eq!(created["table"], name);
+    assert!(table_names().iter().any(|table| table == name));
+
+    run(
+        env!("CARGO_BIN_EXE_add-item"),
+        &[
+            "-t", name, "-u", "jdoe", "-p", "admin", "-a", "33", "-f", "Jane", "-l", "Doe",
+        ],
+    );
+
+    let found = items(name);
+    assert_eq!(found.len(), 1);
+    assert_eq!(found[0]["username"], "jdoe");
+    assert_eq!(found[0]["account_type"], "admin");
+    assert_eq!(found[0]["first_name"], "Jane");
+
+    run(
+        env!("CARGO_BIN_EXE_delete-item"),
+        &["-t", name, "-k", "username", "-v", "jdoe"],
+    );
+    assert!(items(name).is_empty());
+
+    run(env!("CARGO_BIN_EXE_delete-table"), &["-t", name]);
+    assert!(!table_names().iter().any(|table| table == name));
+}
+
+#[test]
+fn export_and_import_json() {
+    let table = Table::new();
+    let name = table.0.as_str();
+    let file = env::temp_dir().join(format!("{}.json", name));
+    let file = file.to_str().expect("the temporary directory isn't UTF-8");
+
+    run(
+        env!("CARGO_BIN_EXE_create-table"),
+        &["-t", name, "-k", "username"],
+    );
+    run(
+        env!("CARGO_BIN_EXE_add-item"),
+        &[
+            "-t", name, "-u", "jdoe", "-p", "admin", "-a", "33", "-f", "Jane", "-l", "Doe",
+        ],
+    );
+
+    let exported = run(env!("CARGO_BIN_EXE_export-json"), &["-t", name, "-f", file]);
+    assert_eq!(exported["items"], 1);
+
+    run(
+        env!("CARGO_BIN_EXE_delete-item"),
+        &["-t", name, "-k", "username", "-v", "jdoe"],
+    );
+    assert!(items(name).is_empty());
+
+    let imported = run(env!("CARGO_BIN_EXE_import-json"), &["-t", name, "-f", file]);
+    assert_eq!(imported["items"], 1);
+
+    let found = items(name);
+    assert_eq!(found.len(), 1);
+    assert_eq!(found[0]["username"], "jdoe");
+    assert_eq!(found[0]["first_name"], "Jane");
+
+    let _ = std::fs::remove_file(file);
+}
+
+#[test]
+fn migrate_items() {
+    let table = Table::new();
+    let name = table.0.as_str();
+    let checkpoint = env::temp_dir().join(format!("{}-checkpoint.json", name));
+    let checkpoint = checkpoint
+        .to_str()
+        .expect("the temporary directory isn't UTF-8");
+    let migrate = |checkpoint: &str| {
+        run(
+            env!("CARGO_BIN_EXE_migrate-items"),
+            &[
+                "-t",
+                name,
+                "-c",
+                checkpoint,
+                "--rename",
+                "first_name=given_name",
+                "--backfill",
+                "status=active",
+            ],
+        )
+    };
+
+    run(
+        env!("CARGO_BIN_EXE_create-table"),
+        &["-t", name, "-k", "username"],
+    );
+    run(
+        env!("CARGO_BIN_EXE_add-item"),
+        &[
+            "-t", name, "-u", "jdoe", "-p", "admin", "-a", "33", "-f", "Jane", "-l", "Doe",
+        ],
+    );
+
+    let migrated = migrate(checkpoint);
+    assert_eq!(migrated["migrated"], 1);
+    assert_eq!(migrated["complete"], true);
+
+    let found = items(name);
+    assert_eq!(found[0]["given_name"], "Jane");
+    assert_eq!(found[0]["status"], "active");
+    assert!(found[0].get("first_name").is_none());
+
+    // Once the checkpoint says the migration is complete, it isn't run again.
+    assert_eq!(migrate(checkpoint)["scanned"], 1);
+
+    // Without the checkpoint, the whole table is scanned again, but nothing needs to change.
+    let _ = std::fs::remove_file(checkpoint);
+    let again = migrate(checkpoint);
+    assert_eq!(again["migrated"], 0);
+    assert_eq!(again["unchanged"], 1);
+
+    let _ = std::fs::remove_file(checkpoint);
+}
+
+#[test]
+fn session_store() {
+    let table = Table::new();
+    let name = table.0.as_str();
+    let sessions = |args: &[&str]| {
+        let mut all = vec!["-t", name];
+        all.extend_from_slice(args);
+        run(env!("CARGO_BIN_EXE_session-store"), &all)
+    };
+
+    sessions(&["setup"]);
+
+    let created = sessions(&["create", "-u", "jdoe", "--data", "theme=dark"]);
+    let id = created["session_id"]
+        .as_str()
+        .expect("create didn't return a session ID")
+        .to_string();
+    assert_eq!(created["session"]["user"], "jdoe");
+    assert_eq!(created["session"]["data"]["theme"], "dark");
+
+    let got = sessions(&["get", "-s", &id]);
+    assert_eq!(got["found"], true);
+    assert_eq!(got["session"]["user"], "jdoe");
+
+    let touched = sessions(&["touch", "-s", &id]);
+    assert_eq!(touched["found"], true);
+    assert!(touched["session"]["expires_at"].as_i64() >= created["session"]["expires_at"].as_i64());
+
+    assert_eq!(sessions(&["delete", "-s", &id])["found"], true);
+
+    // Once the session is deleted, touching it can't bring it back.
+    assert_eq!(sessions(&["get", "-s", &id])["found"], false);
+    assert_eq!(sessions(&["touch", "-s", &id])["found"], false);
+    assert_eq!(sessions(&["delete", "-s", &id])["found"], false);
+}
+
+#[test]
+fn add_item_rejects_an_invalid_permission_type() {
+    let output = example(env!("CARGO_BIN_EXE_add-item"))
+        .args(&[
+            "-t", "unused", "-u", "jdoe", "-p", "owner", "-a", "33", "-f", "Jane", "-l", "Doe",
+        ])
+        .output()
+        .expect("couldn't run add-item");
+
+    assert!(!output.status.success());
+}
@@ -0,0 +1,191 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0.
+ */
+
+use std::error::Error;
+use std::net::Ipv4Addr;
+
+use ec2::model::{IpPermission, IpRange};
+use ec2::{Client, Config, Region};
+
+use aws_types::region::ProvideRegion;
+
+use common::profile::ProfileCredentialsProvider;
+use structopt::StructOpt;
+use tracing_subscriber::fmt::format::FmtSpan;
+use tracing_subscriber::fmt::SubscriberBuilder;
+
+#[derive(Debug, StructOpt)]
+struct Opt {
+    /// The default region
+    #[structopt(short, long)]
+    default_region: Option<String>,
+
+    /// The ID of the security group
+    #[structopt(short, long)]
+    group_id: String,
+
+    /// The IPv4 address range to allow, in CIDR notation, such as 203.0.113.0/24. Repeat to allow several
+    #[structopt(short, long, required = true, parse(try_from_str = parse_cidr))]
+    cidr: Vec<String>,
+
+    /// The port, such as 22, or range of ports, such as 8000-8080, to allow
+    #[structopt(short, long, parse(try_from_str = parse_ports))]
+    port: (i32, i32),
+
+    /// The protocol, tcp or udp
+    #[structopt(long, default_value = "tcp")]
+    protocol: String,
+
+    /// A description of the rule, such as SSH from the office
+    #[structopt(long)]
+    description: Option<String>,
+
+    /// The AWS profile to use from the shared credentials file
+    #[structopt(long)]
+    profile: Option<String>,
+
+    /// Whether to display additional information
+    #[structopt(short, long)]
+    verbose: bool,
+}
+
+/// Checks that an address range is an IPv4 address and a prefix length from 0 to 32,
+/// so that a mistake is reported before Amazon EC2 is called.
+fn parse_cidr(s: &str) -> Result<String, String> {
+    let (address, prefix) = s
+        .split_once('/')
+        .ok_or_else(|| format!("{} is not in CIDR notation, such as 203.0.113.0/24", s))?;
+
+    address
+        .parse::<Ipv4Addr>()
+        .map_err(|_| format!("{} is not an IPv4 address", address))?;
+
+    match prefix.parse::<u8>() {
+        Ok(prefix) if prefix <= 32 => Ok(s.to_string()),
+        _ => Err(format!("{} is not a prefix length from 0 to 32", prefix)),
+    }
+}
+
+/// Reads a port, such as 22, or a range of ports, such as 8000-8080.
+fn parse_ports(s: &str) -> Result<(i32, i32), String> {
+    let parse = |port: &str| match port.parse::<u16>() {
+        Ok(port) => Ok(i32::from(port)),
+        Err(_) => Err(format!("{} is not a port from 0 to 65535", port)),
+    };
+
+    let (from, to) = match s.split_once('-') {
+        Some((from, to)) => (parse(from)?, parse(to)?),
+        None => (parse(s)?, parse(s)?),
+    };
+
+    if from > to {
+        return Err(format!("{} starts after it ends", s));
+    }
+
+    Ok((from, to))
+}
+
+/// Adds an inbound rule to an Amazon EC2 security group,
+/// allowing traffic on a port or range of ports from one or more IPv4 address ranges.
+/// Adding a rule that the group already has fails with **InvalidPermission.Duplicate**.
+/// # Arguments
+///
+/// * `-g GROUP-ID` - The ID of the security group.
+/// * `-c CIDR` - The IPv4 address range to allow, such as **203.0.113.0/24**. Repeat to allow several.
+/// * `-p PORT` - The port, such as **22**, or range of ports, such as **8000-8080**, to allow.
+/// * `[--protocol PROTOCOL]` - The protocol, **tcp** or **udp**.
+///   If not supplied, defaults to **tcp**.
+/// * `[--description DESCRIPTION]` - A description of the rule.
+/// * `[-d DEFAULT-REGION]` - The AWS Region in which the client is created.
+///   If not supplied, uses the value of the **AWS_DEFAULT_REGION** environment variable.
+///   If the environment variable is not set, defaults to **us-west-2**.
+/// * `[--profile PROFILE]` - The AWS profile to use from the shared credentials file.
+/// * `[-v]` - Whether to display additional information.
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn Error>> {
+    let Opt {
+        cidr,
+        default_region,
+        description,
+        group_id,
+        port,
+        profile,
+        protocol,
+        verbose,
+    } = Opt::from_args();
+
+    let region = default_region
+        .as_ref()
+        .map(|region| Region::new(region.clone()))
+        .or_else(|| aws_types::region::default_provider().region())
+        .unwrap_or_else(|| Region::new("us-west-2"));
+
+    if protocol != "tcp" && protocol != "udp" {
+        return Err(format!("the protocol must be tcp or udp, got {}", protocol).into());
+    }
+
+    if verbose {
+        println!("EC2 client version: {}", ec2::PKG_VERSION);
+        println!("Region:             {:?}", &region);
+        println!("Security group ID:  {}", &group_id);
+        println!("CIDRs:              {:?}", &cidr);
+        println!("Ports:              {}-{}", port.0, port.1);
+        println!("Protocol:           {}", &protocol);
+        println!("Description:        {:?}", &description);
+        println!();
+
+        SubscriberBuilder::default()
+            .with_env_filter("info")
+            .with_span_events(FmtSpan::CLOSE)
+            .init();
+    }
+
+    let mut config = Config::builder().region(&region);
+    if let Some(profile) = profile {
+        config = config.credentials_provider(ProfileCredentialsProvider::new(profile));
+    }
+
+    let client = Client::from_conf(config.build());
+
+    let ip_ranges = cidr
+        .iter()
+        .map(|cidr| {
+            IpRange::builder()
+                .cidr_ip(cidr)
+                .set_description(description.clone())
+                .build()
+        })
+        .collect();
+
+    client
+        .authorize_security_group_ingress()
+        .group_id(&group_id)
+        .ip_permissions(
+            IpPermission::builder()
+                .ip_protocol(&protocol)
+                .from_port(port.0)
+                .to_port(port.1)
+                .set_ip_ranges(Some(ip_ranges))
+                .build(),
+        )
+        .send()
+        .await?;
+
+    let ports = if port.0 == port.1 {
+        port.0.to_string()
+    } else {
+        format!("{}-{}", port.0, port.1)
+    };
+
+    println!(
+        "Security group {} now allows {} port {} from {}",
+        &group_id,
+        &protocol,
+        ports,
+        cidr.join(", ")
+    );
+
+    Ok(())
+}
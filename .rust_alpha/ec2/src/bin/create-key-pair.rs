@@ -0,0 +1,141 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0.
+ */
+
+use std::error::Error;
+use std::fs::OpenOptions;
+use std::io::Write;
+
+use ec2::{Client, Config, Region};
+
+use aws_types::region::ProvideRegion;
+
+use common::profile::ProfileCredentialsProvider;
+use structopt::StructOpt;
+use tracing_subscriber::fmt::format::FmtSpan;
+use tracing_subscriber::fmt::SubscriberBuilder;
+
+#[derive(Debug, StructOpt)]
+struct Opt {
+    /// The default region
+    #[structopt(short, long)]
+    default_region: Option<String>,
+
+    /// The name of the key pair
+    #[structopt(short, long)]
+    name: String,
+
+    /// The file in which to save the private key. Defaults to NAME.pem
+    #[structopt(short, long)]
+    file: Option<String>,
+
+    /// The AWS profile to use from the shared credentials file
+    #[structopt(long)]
+    profile: Option<String>,
+
+    /// Whether to display additional information
+    #[structopt(short, long)]
+    verbose: bool,
+}
+
+/// Creates the file for the private key, readable and writable only by the current user, as SSH requires.
+/// Fails if the file exists, rather than overwriting another key.
+fn create_key_file(file: &str) -> std::io::Result<std::fs::File> {
+    let mut options = OpenOptions::new();
+    options.write(true).create_new(true);
+
+    // Setting the mode when the file is created means the key is never readable by anyone else.
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::OpenOptionsExt;
+        options.mode(0o600);
+    }
+
+    options.open(file)
+}
+
+/// Creates an Amazon EC2 key pair, and saves its private key in a PEM file that only you can read.
+/// Amazon EC2 doesn't keep the private key, so the file is the only copy.
+/// The file is created before the key pair, so that a key pair is never created without somewhere to save it.
+/// # Arguments
+///
+/// * `-n NAME` - The name of the key pair.
+/// * `[-f FILE]` - The file in which to save the private key.
+///   If not supplied, defaults to **NAME.pem**.
+/// * `[-d DEFAULT-REGION]` - The AWS Region in which the client is created.
+///   If not supplied, uses the value of the **AWS_DEFAULT_REGION** environment variable.
+///   If the environment variable is not set, defaults to **us-west-2**.
+/// * `[--profile PROFILE]` - The AWS profile to use from the shared credentials file.
+/// * `[-v]` - Whether to display additional information.
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn Error>> {
+    let Opt {
+        default_region,
+        file,
+        name,
+        profile,
+        verbose,
+    } = Opt::from_args();
+
+    let region = default_region
+        .as_ref()
+        .map(|region| Region::new(region.clone()))
+        .or_else(|| aws_types::region::default_provider().region())
+        .unwrap_or_else(|| Region::new("us-west-2"));
+
+    let file = file.unwrap_or_else(|| format!("{}.pem", &name));
+
+    if verbose {
+        println!("EC2 client version: {}", ec2::PKG_VERSION);
+        println!("Region:             {:?}", &region);
+        println!("Key name:           {}", &name);
+        println!("File:               {}", &file);
+        println!();
+
+        SubscriberBuilder::default()
+            .with_env_filter("info")
+            .with_span_events(FmtSpan::CLOSE)
+            .init();
+    }
+
+    let mut config = Config::builder().region(&region);
+    if let Some(profile) = profile {
+        config = config.credentials_provider(ProfileCredentialsProvider::new(profile));
+    }
+
+    let client = Client::from_conf(config.build());
+
+    let mut key_file =
+        create_key_file(&file).map_err(|e| format!("couldn't create {}: {}", &file, e))?;
+
+    let resp = match client.create_key_pair().key_name(&name).send().await {
+        Ok(resp) => resp,
+        Err(e) => {
+            // Don't leave an empty key file behind.
+            drop(key_file);
+            let _ = std::fs::remove_file(&file);
+            return Err(e.into());
+        }
+    };
+
+    let key_material = resp
+        .key_material
+        .ok_or("Amazon EC2 did not return the private key")?;
+
+    key_file.write_all(key_material.as_bytes())?;
+    key_file.sync_all()?;
+
+    println!(
+        "Created key pair {} ({})",
+        &name,
+        resp.key_pair_id.as_deref().unwrap_or_default()
+    );
+    println!(
+        "  Fingerprint: {}",
+        resp.key_fingerprint.as_deref().unwrap_or_default()
+    );
+    println!("  Saved the private key in {}", &file);
+
+    Ok(())
+}
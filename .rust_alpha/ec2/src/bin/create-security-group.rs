@@ -0,0 +1,134 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0.
+ */
+
+use std::error::Error;
+
+use ec2::model::Filter;
+use ec2::{Client, Config, Region};
+
+use aws_types::region::ProvideRegion;
+
+use common::profile::ProfileCredentialsProvider;
+use structopt::StructOpt;
+use tracing_subscriber::fmt::format::FmtSpan;
+use tracing_subscriber::fmt::SubscriberBuilder;
+
+#[derive(Debug, StructOpt)]
+struct Opt {
+    /// The default region
+    #[structopt(short, long)]
+    default_region: Option<String>,
+
+    /// The name of the security group
+    #[structopt(short, long)]
+    name: String,
+
+    /// The description of the security group
+    #[structopt(long)]
+    description: String,
+
+    /// The ID of the VPC to create the security group in. Defaults to the default VPC
+    #[structopt(long)]
+    vpc_id: Option<String>,
+
+    /// The AWS profile to use from the shared credentials file
+    #[structopt(long)]
+    profile: Option<String>,
+
+    /// Whether to display additional information
+    #[structopt(short, long)]
+    verbose: bool,
+}
+
+/// Returns the ID of the default VPC.
+async fn default_vpc(client: &Client) -> Result<String, Box<dyn Error>> {
+    let vpc_id = client
+        .describe_vpcs()
+        .filters(Filter::builder().name("isDefault").values("true").build())
+        .send()
+        .await?
+        .vpcs
+        .unwrap_or_default()
+        .into_iter()
+        .find_map(|vpc| vpc.vpc_id)
+        .ok_or("the region has no default VPC; use --vpc-id")?;
+
+    Ok(vpc_id)
+}
+
+/// Creates an Amazon EC2 security group, and displays its ID.
+/// A new security group allows all outbound traffic and no inbound traffic;
+/// use `authorize-ingress` to let traffic in.
+/// # Arguments
+///
+/// * `-n NAME` - The name of the security group.
+/// * `--description DESCRIPTION` - The description of the security group.
+/// * `[--vpc-id VPC-ID]` - The ID of the VPC to create the security group in.
+///   If not supplied, uses the default VPC.
+/// * `[-d DEFAULT-REGION]` - The AWS Region in which the client is created.
+///   If not supplied, uses the value of the **AWS_DEFAULT_REGION** environment variable.
+///   If the environment variable is not set, defaults to **us-west-2**.
+/// * `[--profile PROFILE]` - The AWS profile to use from the shared credentials file.
+/// * `[-v]` - Whether to display additional information.
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn Error>> {
+    let Opt {
+        default_region,
+        description,
+        name,
+        profile,
+        verbose,
+        vpc_id,
+    } = Opt::from_args();
+
+    let region = default_region
+        .as_ref()
+        .map(|region| Region::new(region.clone()))
+        .or_else(|| aws_types::region::default_provider().region())
+        .unwrap_or_else(|| Region::new("us-west-2"));
+
+    if verbose {
+        println!("EC2 client version: {}", ec2::PKG_VERSION);
+        println!("Region:             {:?}", &region);
+        println!("Name:               {}", &name);
+        println!("Description:        {}", &description);
+        println!("VPC ID:             {:?}", &vpc_id);
+        println!();
+
+        SubscriberBuilder::default()
+            .with_env_filter("info")
+            .with_span_events(FmtSpan::CLOSE)
+            .init();
+    }
+
+    let mut config = Config::builder().region(&region);
+    if let Some(profile) = profile {
+        config = config.credentials_provider(ProfileCredentialsProvider::new(profile));
+    }
+
+    let client = Client::from_conf(config.build());
+
+    let vpc_id = match vpc_id {
+        Some(vpc_id) => vpc_id,
+        None => default_vpc(&client).await?,
+    };
+
+    let group_id = client
+        .create_security_group()
+        .group_name(&name)
+        .description(&description)
+        .vpc_id(&vpc_id)
+        .send()
+        .await?
+        .group_id
+        .ok_or("Amazon EC2 did not return the security group ID")?;
+
+    println!(
+        "Created security group {} ({}) in {}",
+        &name, &group_id, &vpc_id
+    );
+
+    Ok(())
+}
@@ -3,11 +3,18 @@
  * SPDX-License-Identifier: Apache-2.0.
  */
 
-use ec2::{Client, Config, Error, Region};
+use std::error::Error;
+
+use ec2::model::{Filter, InstanceStateName};
+use ec2::{Client, Config, Region};
+use ec2_code_examples::tags::{parse_tag, tag_value};
 
 use aws_types::region::ProvideRegion;
 
+use common::profile::ProfileCredentialsProvider;
 use structopt::StructOpt;
+use tracing_subscriber::fmt::format::FmtSpan;
+use tracing_subscriber::fmt::SubscriberBuilder;
 
 #[derive(Debug, StructOpt)]
 struct Opt {
@@ -15,77 +22,188 @@ struct Opt {
     #[structopt(short, long)]
     default_region: Option<String>,
 
-    /// To get info about one instance
+    /// The ID of an instance to describe. Repeat to describe several
+    #[structopt(short, long)]
+    instance_id: Vec<String>,
+
+    /// Only show instances in this state, such as running. Repeat to allow several
     #[structopt(short, long)]
-    instance_id: Option<String>,
+    state: Vec<String>,
+
+    /// Only show instances with this tag, as KEY=VALUE. Repeat to require several
+    #[structopt(short, long, parse(try_from_str = parse_tag))]
+    tag: Vec<(String, String)>,
+
+    /// Only show instances of this type, such as t3.micro. Repeat to allow several
+    #[structopt(long)]
+    instance_type: Vec<String>,
+
+    /// The AWS profile to use from the shared credentials file
+    #[structopt(long)]
+    profile: Option<String>,
 
     /// Whether to display additional information
     #[structopt(short, long)]
     verbose: bool,
 }
 
-/// Lists the state of one or all of your Amazon EC2 instances.
+/// Builds the filters for DescribeInstances.
+/// The values of one filter are ORed, and different filters are ANDed,
+/// so each tag gets a filter of its own, and an instance must have all of them.
+fn filters(opt: &Opt) -> Result<Vec<Filter>, Box<dyn Error>> {
+    let mut filters = Vec::new();
+
+    if !opt.state.is_empty() {
+        for state in &opt.state {
+            if let InstanceStateName::Unknown(_) = InstanceStateName::from(state.as_str()) {
+                return Err(format!(
+                    "{} is not a state; use pending, running, shutting-down, terminated, stopping, or stopped",
+                    state
+                )
+                .into());
+            }
+        }
+
+        filters.push(
+            Filter::builder()
+                .name("instance-state-name")
+                .set_values(Some(opt.state.clone()))
+                .build(),
+        );
+    }
+
+    if !opt.instance_type.is_empty() {
+        filters.push(
+            Filter::builder()
+                .name("instance-type")
+                .set_values(Some(opt.instance_type.clone()))
+                .build(),
+        );
+    }
+
+    for (key, value) in &opt.tag {
+        filters.push(
+            Filter::builder()
+                .name(format!("tag:{}", key))
+                .values(value)
+                .build(),
+        );
+    }
+
+    Ok(filters)
+}
+
+/// Lists your Amazon EC2 instances, with their name, type, state, and addresses, following every page of results.
+/// Lists all of them, or just the ones with the IDs, and filters them by state, tag, and instance type.
 /// # Arguments
 ///
-/// * `[-i INSTANCE-ID]` - The ID of an instance.
+/// * `[-i INSTANCE-ID]` - The ID of an instance. Repeat to describe several.
+/// * `[-s STATE]` - Only show instances in the state, such as **running**. Repeat to allow several.
+/// * `[-t KEY=VALUE]` - Only show instances with the tag. Repeat to require several.
+/// * `[--instance-type INSTANCE-TYPE]` - Only show instances of the type, such as **t3.micro**. Repeat to allow several.
 /// * `[-d DEFAULT-REGION]` - The AWS Region in which the client is created.
 ///   If not supplied, uses the value of the **AWS_DEFAULT_REGION** environment variable.
 ///   If the environment variable is not set, defaults to **us-west-2**.
+/// * `[--profile PROFILE]` - The AWS profile to use from the shared credentials file.
 /// * `[-v]` - Whether to display additional information.
 #[tokio::main]
-async fn main() -> Result<(), Error> {
-    tracing_subscriber::fmt::init();
-    let Opt {
-        default_region,
-        instance_id,
-        verbose,
-    } = Opt::from_args();
-
-    let region = default_region
+async fn main() -> Result<(), Box<dyn Error>> {
+    let opt = Opt::from_args();
+
+    let region = opt
+        .default_region
         .as_ref()
         .map(|region| Region::new(region.clone()))
         .or_else(|| aws_types::region::default_provider().region())
         .unwrap_or_else(|| Region::new("us-west-2"));
 
-    let only_one = instance_id.as_deref().unwrap_or_default() != "";
+    let filters = filters(&opt)?;
 
-    if verbose {
+    if opt.verbose {
         println!("EC2 client version: {}", ec2::PKG_VERSION);
         println!("Region:             {:?}", &region);
+        println!("Instance IDs:       {:?}", &opt.instance_id);
+        println!("States:             {:?}", &opt.state);
+        println!("Tags:               {:?}", &opt.tag);
+        println!("Instance types:     {:?}", &opt.instance_type);
+        println!();
+
+        SubscriberBuilder::default()
+            .with_env_filter("info")
+            .with_span_events(FmtSpan::CLOSE)
+            .init();
+    }
 
-        if only_one {
-            println!("Instance ID:        {:?}", instance_id);
-        }
+    let mut config = Config::builder().region(&region);
+    if let Some(profile) = &opt.profile {
+        config = config.credentials_provider(ProfileCredentialsProvider::new(profile));
     }
 
-    let config = Config::builder().region(&region).build();
+    let client = Client::from_conf(config.build());
 
-    let client = Client::from_conf(config);
+    let mut count = 0;
+    let mut next_token = None;
 
-    if only_one {
-        let resp = client.describe_instances().send().await?;
-        for reservation in resp.reservations.unwrap_or_default() {
-            println!("Instances:");
+    println!("Instances:");
 
-            for instance in reservation.instances.unwrap_or_default() {
-                println!("  {}", instance.instance_id.unwrap());
-                println!("  State: {:?}", instance.state.unwrap().name.unwrap());
-                println!();
-            }
-        }
-    } else {
+    loop {
+        // Without instance IDs, DescribeInstances returns all of the instances that match the filters.
         let resp = client
             .describe_instances()
-            .instance_ids(instance_id.unwrap())
+            .set_instance_ids(Some(opt.instance_id.clone()).filter(|ids| !ids.is_empty()))
+            .set_filters(Some(filters.clone()).filter(|filters| !filters.is_empty()))
+            .set_next_token(next_token)
             .send()
             .await?;
+
         for reservation in resp.reservations.unwrap_or_default() {
             for instance in reservation.instances.unwrap_or_default() {
-                println!("State: {:?}", instance.state.unwrap().name.unwrap());
+                let state = instance
+                    .state
+                    .as_ref()
+                    .and_then(|state| state.name.as_ref())
+                    .map(|name| name.as_str())
+                    .unwrap_or_default();
+
+                println!("  {}", instance.instance_id.as_deref().unwrap_or_default());
+                println!(
+                    "  Name:       {}",
+                    tag_value(instance.tags.as_deref(), "Name").unwrap_or("-")
+                );
+                println!(
+                    "  Type:       {}",
+                    instance
+                        .instance_type
+                        .as_ref()
+                        .map(|instance_type| instance_type.as_str())
+                        .unwrap_or_default()
+                );
+                println!("  State:      {}", state);
+                println!(
+                    "  Image:      {}",
+                    instance.image_id.as_deref().unwrap_or_default()
+                );
+                println!(
+                    "  Private IP: {}",
+                    instance.private_ip_address.as_deref().unwrap_or("-")
+                );
+                println!(
+                    "  Public IP:  {}",
+                    instance.public_ip_address.as_deref().unwrap_or("-")
+                );
                 println!();
+
+                count += 1;
             }
         }
+
+        next_token = resp.next_token;
+        if next_token.is_none() {
+            break;
+        }
     }
 
+    println!("Found {} instances", count);
+
     Ok(())
 }
@@ -0,0 +1,180 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0.
+ */
+
+use std::error::Error;
+
+use ec2::model::{Filter, IpPermission};
+use ec2::{Client, Config, Region};
+
+use aws_types::region::ProvideRegion;
+
+use common::profile::ProfileCredentialsProvider;
+use structopt::StructOpt;
+use tracing_subscriber::fmt::format::FmtSpan;
+use tracing_subscriber::fmt::SubscriberBuilder;
+
+#[derive(Debug, StructOpt)]
+struct Opt {
+    /// The default region
+    #[structopt(short, long)]
+    default_region: Option<String>,
+
+    /// The ID of a security group to describe. Repeat to describe several
+    #[structopt(short, long)]
+    group_id: Vec<String>,
+
+    /// Only show the security groups in this VPC
+    #[structopt(long)]
+    vpc_id: Option<String>,
+
+    /// The AWS profile to use from the shared credentials file
+    #[structopt(long)]
+    profile: Option<String>,
+
+    /// Whether to display additional information
+    #[structopt(short, long)]
+    verbose: bool,
+}
+
+/// Describes a rule as its protocol, ports, and sources, such as `tcp 22 from 203.0.113.0/24`.
+fn describe_rule(permission: &IpPermission) -> String {
+    let protocol = match permission.ip_protocol.as_deref() {
+        Some("-1") | None => "all traffic",
+        Some(protocol) => protocol,
+    };
+
+    let ports = match (permission.from_port, permission.to_port) {
+        (Some(from), Some(to)) if from == to && from >= 0 => format!(" {}", from),
+        (Some(from), Some(to)) if from >= 0 => format!(" {}-{}", from, to),
+        _ => String::new(),
+    };
+
+    let sources: Vec<&str> = permission
+        .ip_ranges
+        .as_deref()
+        .unwrap_or_default()
+        .iter()
+        .filter_map(|range| range.cidr_ip.as_deref())
+        .chain(
+            permission
+                .ipv6_ranges
+                .as_deref()
+                .unwrap_or_default()
+                .iter()
+                .filter_map(|range| range.cidr_ipv6.as_deref()),
+        )
+        .chain(
+            permission
+                .user_id_group_pairs
+                .as_deref()
+                .unwrap_or_default()
+                .iter()
+                .filter_map(|pair| pair.group_id.as_deref()),
+        )
+        .collect();
+
+    format!("{}{} from {}", protocol, ports, sources.join(", "))
+}
+
+/// Lists your Amazon EC2 security groups, with their inbound and outbound rules, following every page of results.
+/// Lists all of them, or just the ones with the IDs or in the VPC.
+/// # Arguments
+///
+/// * `[-g GROUP-ID]` - The ID of a security group. Repeat to describe several.
+/// * `[--vpc-id VPC-ID]` - Only show the security groups in the VPC.
+/// * `[-d DEFAULT-REGION]` - The AWS Region in which the client is created.
+///   If not supplied, uses the value of the **AWS_DEFAULT_REGION** environment variable.
+///   If the environment variable is not set, defaults to **us-west-2**.
+/// * `[--profile PROFILE]` - The AWS profile to use from the shared credentials file.
+/// * `[-v]` - Whether to display additional information.
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn Error>> {
+    let Opt {
+        default_region,
+        group_id,
+        profile,
+        verbose,
+        vpc_id,
+    } = Opt::from_args();
+
+    let region = default_region
+        .as_ref()
+        .map(|region| Region::new(region.clone()))
+        .or_else(|| aws_types::region::default_provider().region())
+        .unwrap_or_else(|| Region::new("us-west-2"));
+
+    if verbose {
+        println!("EC2 client version: {}", ec2::PKG_VERSION);
+        println!("Region:             {:?}", &region);
+        println!("Security group IDs: {:?}", &group_id);
+        println!("VPC ID:             {:?}", &vpc_id);
+        println!();
+
+        SubscriberBuilder::default()
+            .with_env_filter("info")
+            .with_span_events(FmtSpan::CLOSE)
+            .init();
+    }
+
+    let mut config = Config::builder().region(&region);
+    if let Some(profile) = profile {
+        config = config.credentials_provider(ProfileCredentialsProvider::new(profile));
+    }
+
+    let client = Client::from_conf(config.build());
+
+    let filters =
+        vpc_id.map(|vpc_id| vec![Filter::builder().name("vpc-id").values(vpc_id).build()]);
+
+    let mut count = 0;
+    let mut next_token = None;
+
+    println!("Security groups:");
+
+    loop {
+        let resp = client
+            .describe_security_groups()
+            .set_group_ids(Some(group_id.clone()).filter(|ids| !ids.is_empty()))
+            .set_filters(filters.clone())
+            .set_next_token(next_token)
+            .send()
+            .await?;
+
+        for group in resp.security_groups.unwrap_or_default() {
+            println!(
+                "  {} ({})",
+                group.group_name.as_deref().unwrap_or_default(),
+                group.group_id.as_deref().unwrap_or_default()
+            );
+            println!(
+                "  Description: {}",
+                group.description.as_deref().unwrap_or_default()
+            );
+            println!("  VPC:         {}", group.vpc_id.as_deref().unwrap_or("-"));
+
+            println!("  Inbound:");
+            for permission in group.ip_permissions.as_deref().unwrap_or_default() {
+                println!("    {}", describe_rule(permission));
+            }
+
+            println!("  Outbound:");
+            for permission in group.ip_permissions_egress.as_deref().unwrap_or_default() {
+                println!("    {}", describe_rule(permission));
+            }
+
+            println!();
+            count += 1;
+        }
+
+        next_token = resp.next_token;
+        if next_token.is_none() {
+            break;
+        }
+    }
+
+    println!("Found {} security groups", count);
+
+    Ok(())
+}
@@ -7,6 +7,7 @@ use ec2::{Client, Config, Error, Region};
 
 use aws_types::region::ProvideRegion;
 
+use common::profile::ProfileCredentialsProvider;
 use structopt::StructOpt;
 
 #[derive(Debug, StructOpt)]
@@ -15,6 +16,10 @@ struct Opt {
     #[structopt(short, long)]
     default_region: Option<String>,
 
+    /// The AWS profile to use from the shared credentials file
+    #[structopt(long)]
+    profile: Option<String>,
+
     /// Whether to display additional information
     #[structopt(short, long)]
     verbose: bool,
@@ -26,12 +31,14 @@ struct Opt {
 /// * `[-d DEFAULT-REGION]` - The AWS Region in which the client is created.
 ///   If not supplied, uses the value of the **AWS_DEFAULT_REGION** environment variable.
 ///   If the environment variable is not set, defaults to **us-west-2**.
+/// * `[--profile PROFILE]` - The AWS profile to use from the shared credentials file.
 /// * `[-v]` - Whether to display additional information.
 #[tokio::main]
 async fn main() -> Result<(), Error> {
     tracing_subscriber::fmt::init();
     let Opt {
         default_region,
+        profile,
         verbose,
     } = Opt::from_args();
 
@@ -46,14 +53,17 @@ async fn main() -> Result<(), Error> {
         println!("Region:             {:?}", &region);
     }
 
-    let config = Config::builder().region(&region).build();
+    let mut config = Config::builder().region(&region);
+    if let Some(profile) = profile {
+        config = config.credentials_provider(ProfileCredentialsProvider::new(profile));
+    }
 
-    let client = Client::from_conf(config);
+    let client = Client::from_conf(config.build());
     let rsp = client.describe_regions().send().await?;
 
     println!("Regions:");
     for region in rsp.regions.unwrap_or_default() {
-        println!("  {}", region.region_name.unwrap());
+        println!("  {}", region.region_name.as_deref().unwrap_or_default());
     }
 
     println!();
@@ -7,6 +7,7 @@ use ec2::{Client, Config, Error, Region};
 
 use aws_types::region::ProvideRegion;
 
+use common::profile::ProfileCredentialsProvider;
 use structopt::StructOpt;
 
 #[derive(Debug, StructOpt)]
@@ -19,6 +20,10 @@ struct Opt {
     #[structopt(short, long)]
     instance_id: String,
 
+    /// The AWS profile to use from the shared credentials file
+    #[structopt(long)]
+    profile: Option<String>,
+
     /// Whether to display additional information
     #[structopt(short, long)]
     verbose: bool,
@@ -31,6 +36,7 @@ struct Opt {
 /// * `[-d DEFAULT-REGION]` - The AWS Region in which the client is created.
 ///   If not supplied, uses the value of the **AWS_DEFAULT_REGION** environment variable.
 ///   If the environment variable is not set, defaults to **us-west-2**.
+/// * `[--profile PROFILE]` - The AWS profile to use from the shared credentials file.
 /// * `[-v]` - Whether to display additional information.
 #[tokio::main]
 async fn main() -> Result<(), Error> {
@@ -38,6 +44,7 @@ async fn main() -> Result<(), Error> {
     let Opt {
         default_region,
         instance_id,
+        profile,
         verbose,
     } = Opt::from_args();
 
@@ -57,9 +64,12 @@ async fn main() -> Result<(), Error> {
     //let insts: Option<Vec<String>> = Some(instances.iter().map(|i| i.to_string()).collect());
     //    let insts: Option<Vec<String>> = Some(instances.iter().map(ToString::to_string).collect());
 
-    let config = Config::builder().region(&region).build();
+    let mut config = Config::builder().region(&region);
+    if let Some(profile) = profile {
+        config = config.credentials_provider(ProfileCredentialsProvider::new(profile));
+    }
 
-    let client = Client::from_conf(config);
+    let client = Client::from_conf(config.build());
     client
         .monitor_instances()
         .instance_ids(instance_id)
@@ -3,12 +3,21 @@
  * SPDX-License-Identifier: Apache-2.0.
  */
 
-use ec2::{Client, Config, Error, Region};
+use std::error::Error;
+use std::time::Duration;
+
+use ec2::model::SummaryStatus;
+use ec2::{Client, Config, Region};
+use ec2_code_examples::waiters::wait_for_status_ok;
 
 use aws_types::region::ProvideRegion;
 
+use common::profile::ProfileCredentialsProvider;
 use structopt::StructOpt;
 
+/// How long to wait for the instance to pass its status checks again.
+const STATUS_TIMEOUT: Duration = Duration::from_secs(600);
+
 #[derive(Debug, StructOpt)]
 struct Opt {
     /// The default region
@@ -19,25 +28,32 @@ struct Opt {
     #[structopt(short, long)]
     instance_id: String,
 
+    /// The AWS profile to use from the shared credentials file
+    #[structopt(long)]
+    profile: Option<String>,
+
     /// Whether to display additional information
     #[structopt(short, long)]
     verbose: bool,
 }
 
-/// Reboots an Amazon EC2 instance.
+/// Reboots an Amazon EC2 instance, and waits until it passes its status checks.
+/// The instance stays **running** while it reboots, so its status checks are the only sign that it's back.
 /// # Arguments
 ///
-/// * `-i INSTANCE-ID` - The ID of the instances to reboot.
+/// * `-i INSTANCE-ID` - The ID of the instance to reboot.
 /// * `[-d DEFAULT-REGION]` - The AWS Region in which the client is created.
 ///   If not supplied, uses the value of the **AWS_DEFAULT_REGION** environment variable.
 ///   If the environment variable is not set, defaults to **us-west-2**.
+/// * `[--profile PROFILE]` - The AWS profile to use from the shared credentials file.
 /// * `[-v]` - Whether to display additional information.
 #[tokio::main]
-async fn main() -> Result<(), Error> {
+async fn main() -> Result<(), Box<dyn Error>> {
     tracing_subscriber::fmt::init();
     let Opt {
         default_region,
         instance_id,
+        profile,
         verbose,
     } = Opt::from_args();
 
@@ -53,16 +69,30 @@ async fn main() -> Result<(), Error> {
         println!("Instance ID:        {:?}", &instance_id);
     }
 
-    let config = Config::builder().region(&region).build();
+    let mut config = Config::builder().region(&region);
+    if let Some(profile) = profile {
+        config = config.credentials_provider(ProfileCredentialsProvider::new(profile));
+    }
 
-    let client = Client::from_conf(config);
+    let client = Client::from_conf(config.build());
     client
         .reboot_instances()
-        .instance_ids(instance_id)
+        .instance_ids(&instance_id)
         .send()
         .await?;
-    println!("Rebooted instance");
-    println!();
+
+    // RebootInstances returns at once, and the checks can take a minute to notice the reboot,
+    // so passing checks straight away mean the instance was healthy, not that it's back.
+    wait_for_status_ok(&client, &instance_id, STATUS_TIMEOUT, |system, instance| {
+        println!(
+            "System status: {}, instance status: {}",
+            system.map(SummaryStatus::as_str).unwrap_or("-"),
+            instance.map(SummaryStatus::as_str).unwrap_or("-")
+        )
+    })
+    .await?;
+
+    println!("Rebooted instance {}", &instance_id);
 
     Ok(())
 }
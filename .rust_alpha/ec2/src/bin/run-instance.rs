@@ -0,0 +1,168 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0.
+ */
+
+use std::error::Error;
+use std::time::Duration;
+
+use ec2::model::{InstanceStateName, InstanceType, ResourceType, Tag, TagSpecification};
+use ec2::{Client, Config, Region};
+use ec2_code_examples::tags::parse_tag;
+use ec2_code_examples::waiters::{state_name, wait_for_state};
+
+use aws_types::region::ProvideRegion;
+
+use common::profile::ProfileCredentialsProvider;
+use structopt::StructOpt;
+use tracing_subscriber::fmt::format::FmtSpan;
+use tracing_subscriber::fmt::SubscriberBuilder;
+
+/// How long to wait for the new instance to be running.
+const RUNNING_TIMEOUT: Duration = Duration::from_secs(300);
+
+#[derive(Debug, StructOpt)]
+struct Opt {
+    /// The default region
+    #[structopt(short, long)]
+    default_region: Option<String>,
+
+    /// The ID of the AMI to launch, such as ami-0123456789abcdef0
+    #[structopt(short, long)]
+    ami_id: String,
+
+    /// The instance type
+    #[structopt(short, long, default_value = "t3.micro")]
+    instance_type: String,
+
+    /// The name of the key pair to connect with
+    #[structopt(short, long)]
+    key_name: Option<String>,
+
+    /// A tag for the instance and its volumes, as KEY=VALUE. Repeat to add several
+    #[structopt(short, long, parse(try_from_str = parse_tag))]
+    tag: Vec<(String, String)>,
+
+    /// The AWS profile to use from the shared credentials file
+    #[structopt(long)]
+    profile: Option<String>,
+
+    /// Whether to display additional information
+    #[structopt(short, long)]
+    verbose: bool,
+}
+
+/// Launches an Amazon EC2 instance, and displays its state until it's running.
+/// The instance is launched in the default VPC with the default security group, unless the AMI says otherwise.
+/// # Arguments
+///
+/// * `-a AMI-ID` - The ID of the AMI to launch.
+/// * `[-i INSTANCE-TYPE]` - The instance type.
+///   If not supplied, defaults to **t3.micro**.
+/// * `[-k KEY-NAME]` - The name of the key pair to connect with.
+///   If not supplied, the instance has no key pair, so you can't connect to it with SSH.
+/// * `[-t KEY=VALUE]` - A tag for the instance and its volumes, such as **Name=web-1**. Repeat to add several.
+/// * `[-d DEFAULT-REGION]` - The AWS Region in which the client is created.
+///   If not supplied, uses the value of the **AWS_DEFAULT_REGION** environment variable.
+///   If the environment variable is not set, defaults to **us-west-2**.
+/// * `[--profile PROFILE]` - The AWS profile to use from the shared credentials file.
+/// * `[-v]` - Whether to display additional information.
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn Error>> {
+    let Opt {
+        ami_id,
+        default_region,
+        instance_type,
+        key_name,
+        profile,
+        tag,
+        verbose,
+    } = Opt::from_args();
+
+    let region = default_region
+        .as_ref()
+        .map(|region| Region::new(region.clone()))
+        .or_else(|| aws_types::region::default_provider().region())
+        .unwrap_or_else(|| Region::new("us-west-2"));
+
+    let instance_type = match InstanceType::from(instance_type.as_str()) {
+        InstanceType::Unknown(_) => {
+            return Err(format!("{} is not an instance type", instance_type).into())
+        }
+        instance_type => instance_type,
+    };
+
+    if verbose {
+        println!("EC2 client version: {}", ec2::PKG_VERSION);
+        println!("Region:             {:?}", &region);
+        println!("AMI ID:             {}", &ami_id);
+        println!("Instance type:      {}", instance_type.as_str());
+        println!("Key name:           {:?}", &key_name);
+        println!("Tags:               {:?}", &tag);
+        println!();
+
+        SubscriberBuilder::default()
+            .with_env_filter("info")
+            .with_span_events(FmtSpan::CLOSE)
+            .init();
+    }
+
+    let mut config = Config::builder().region(&region);
+    if let Some(profile) = profile {
+        config = config.credentials_provider(ProfileCredentialsProvider::new(profile));
+    }
+
+    let client = Client::from_conf(config.build());
+
+    // Tagging at launch, rather than with CreateTags afterwards, means the instance is never untagged.
+    let tags: Vec<Tag> = tag
+        .into_iter()
+        .map(|(key, value)| Tag::builder().key(key).value(value).build())
+        .collect();
+    let tag_specifications = if tags.is_empty() {
+        None
+    } else {
+        Some(
+            [ResourceType::Instance, ResourceType::Volume]
+                .iter()
+                .map(|resource_type| {
+                    TagSpecification::builder()
+                        .resource_type(resource_type.clone())
+                        .set_tags(Some(tags.clone()))
+                        .build()
+                })
+                .collect(),
+        )
+    };
+
+    let instance_id = client
+        .run_instances()
+        .image_id(&ami_id)
+        .instance_type(instance_type)
+        .set_key_name(key_name)
+        .set_tag_specifications(tag_specifications)
+        .min_count(1)
+        .max_count(1)
+        .send()
+        .await?
+        .instances
+        .unwrap_or_default()
+        .into_iter()
+        .find_map(|instance| instance.instance_id)
+        .ok_or("Amazon EC2 did not return the instance ID")?;
+
+    println!("Launched instance {}", &instance_id);
+
+    wait_for_state(
+        &client,
+        &instance_id,
+        InstanceStateName::Running,
+        RUNNING_TIMEOUT,
+        |state| println!("Instance state: {}", state_name(state)),
+    )
+    .await?;
+
+    println!("Instance {} is running", &instance_id);
+
+    Ok(())
+}
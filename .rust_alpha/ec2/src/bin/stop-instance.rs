@@ -3,12 +3,21 @@
  * SPDX-License-Identifier: Apache-2.0.
  */
 
-use ec2::{Client, Config, Error, Region};
+use std::error::Error;
+use std::time::Duration;
+
+use ec2::model::InstanceStateName;
+use ec2::{Client, Config, Region};
+use ec2_code_examples::waiters::{state_name, wait_for_state};
 
 use aws_types::region::ProvideRegion;
 
+use common::profile::ProfileCredentialsProvider;
 use structopt::StructOpt;
 
+/// How long to wait for the instance to be stopped.
+const STOPPED_TIMEOUT: Duration = Duration::from_secs(600);
+
 #[derive(Debug, StructOpt)]
 struct Opt {
     /// The default region
@@ -19,25 +28,31 @@ struct Opt {
     #[structopt(short, long)]
     instance_id: String,
 
+    /// The AWS profile to use from the shared credentials file
+    #[structopt(long)]
+    profile: Option<String>,
+
     /// Whether to display additional information
     #[structopt(short, long)]
     verbose: bool,
 }
 
-/// Stops an Amazon EC2 instance.
+/// Stops an Amazon EC2 instance, and displays its state until it's stopped.
 /// # Arguments
 ///
-/// * `-i INSTANCE-ID` - The ID of the instances to stop.
+/// * `-i INSTANCE-ID` - The ID of the instance to stop.
 /// * `[-d DEFAULT-REGION]` - The AWS Region in which the client is created.
 ///   If not supplied, uses the value of the **AWS_DEFAULT_REGION** environment variable.
 ///   If the environment variable is not set, defaults to **us-west-2**.
+/// * `[--profile PROFILE]` - The AWS profile to use from the shared credentials file.
 /// * `[-v]` - Whether to display additional information.
 #[tokio::main]
-async fn main() -> Result<(), Error> {
+async fn main() -> Result<(), Box<dyn Error>> {
     tracing_subscriber::fmt::init();
     let Opt {
         default_region,
         instance_id,
+        profile,
         verbose,
     } = Opt::from_args();
 
@@ -53,16 +68,28 @@ async fn main() -> Result<(), Error> {
         println!("Instance ID:        {:?}", &instance_id);
     }
 
-    let config = Config::builder().region(&region).build();
+    let mut config = Config::builder().region(&region);
+    if let Some(profile) = profile {
+        config = config.credentials_provider(ProfileCredentialsProvider::new(profile));
+    }
 
-    let client = Client::from_conf(config);
+    let client = Client::from_conf(config.build());
     client
         .stop_instances()
-        .instance_ids(instance_id)
+        .instance_ids(&instance_id)
         .send()
         .await?;
-    println!("Stopped instance");
-    println!();
+
+    wait_for_state(
+        &client,
+        &instance_id,
+        InstanceStateName::Stopped,
+        STOPPED_TIMEOUT,
+        |state| println!("Instance state: {}", state_name(state)),
+    )
+    .await?;
+
+    println!("Stopped instance {}", &instance_id);
 
     Ok(())
 }
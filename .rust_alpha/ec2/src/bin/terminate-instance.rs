@@ -0,0 +1,96 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0.
+ */
+
+use std::error::Error;
+use std::time::Duration;
+
+use ec2::model::InstanceStateName;
+use ec2::{Client, Config, Region};
+use ec2_code_examples::waiters::{state_name, wait_for_state};
+
+use aws_types::region::ProvideRegion;
+
+use common::profile::ProfileCredentialsProvider;
+use structopt::StructOpt;
+
+/// How long to wait for the instance to be terminated.
+const TERMINATED_TIMEOUT: Duration = Duration::from_secs(600);
+
+#[derive(Debug, StructOpt)]
+struct Opt {
+    /// The default region
+    #[structopt(short, long)]
+    default_region: Option<String>,
+
+    /// The ID of the instance to terminate
+    #[structopt(short, long)]
+    instance_id: String,
+
+    /// The AWS profile to use from the shared credentials file
+    #[structopt(long)]
+    profile: Option<String>,
+
+    /// Whether to display additional information
+    #[structopt(short, long)]
+    verbose: bool,
+}
+
+/// Terminates an Amazon EC2 instance, and displays its state until it's terminated.
+/// A terminated instance can't be started again, and its root volume is deleted unless it was set to be kept.
+/// # Arguments
+///
+/// * `-i INSTANCE-ID` - The ID of the instance to terminate.
+/// * `[-d DEFAULT-REGION]` - The AWS Region in which the client is created.
+///   If not supplied, uses the value of the **AWS_DEFAULT_REGION** environment variable.
+///   If the environment variable is not set, defaults to **us-west-2**.
+/// * `[--profile PROFILE]` - The AWS profile to use from the shared credentials file.
+/// * `[-v]` - Whether to display additional information.
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn Error>> {
+    tracing_subscriber::fmt::init();
+    let Opt {
+        default_region,
+        instance_id,
+        profile,
+        verbose,
+    } = Opt::from_args();
+
+    let region = default_region
+        .as_ref()
+        .map(|region| Region::new(region.clone()))
+        .or_else(|| aws_types::region::default_provider().region())
+        .unwrap_or_else(|| Region::new("us-west-2"));
+
+    if verbose {
+        println!("EC2 client version: {}", ec2::PKG_VERSION);
+        println!("Region:             {:?}", &region);
+        println!("Instance ID:        {:?}", &instance_id);
+    }
+
+    let mut config = Config::builder().region(&region);
+    if let Some(profile) = profile {
+        config = config.credentials_provider(ProfileCredentialsProvider::new(profile));
+    }
+
+    let client = Client::from_conf(config.build());
+    client
+        .terminate_instances()
+        .instance_ids(&instance_id)
+        .send()
+        .await?;
+
+    wait_for_state(
+        &client,
+        &instance_id,
+        InstanceStateName::Terminated,
+        TERMINATED_TIMEOUT,
+        |state| println!("Instance state: {}", state_name(state)),
+    )
+    .await?;
+
+    println!("Terminated instance {}", &instance_id);
+
+    Ok(())
+}
@@ -0,0 +1,32 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0.
+ */
+
+//! Reads tags from the command line, and finds them on instances.
+//!
+//! A tag is written as `KEY=VALUE`, such as `Name=web-1`, so that structopt can parse it:
+//!
+//! ```ignore
+//! #[structopt(long, parse(try_from_str = parse_tag))]
+//! tag: Vec<(String, String)>,
+//! ```
+
+use ec2::model::Tag;
+
+/// Splits `KEY=VALUE` at the first `=`, so the value can itself contain one.
+/// The key can't be empty, but the value can, as Amazon EC2 allows.
+pub fn parse_tag(s: &str) -> Result<(String, String), String> {
+    match s.split_once('=') {
+        Some((key, value)) if !key.is_empty() => Ok((key.to_string(), value.to_string())),
+        _ => Err(format!("{} is not KEY=VALUE", s)),
+    }
+}
+
+/// Returns the value of the tag with the key, such as the instance's __Name__.
+pub fn tag_value<'a>(tags: Option<&'a [Tag]>, key: &str) -> Option<&'a str> {
+    tags.unwrap_or_default()
+        .iter()
+        .find(|tag| tag.key.as_deref() == Some(key))
+        .and_then(|tag| tag.value.as_deref())
+}
@@ -0,0 +1,243 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0.
+ */
+
+//! Waits for an Amazon EC2 instance to reach a state.
+//!
+//! `RunInstances`, `StartInstances`, `StopInstances`, and `TerminateInstances` return while the instance
+//! is still __pending__, __stopping__, or __shutting-down__. The waiters poll `DescribeInstances`,
+//! and call `on_state` each time the state changes, so that an example can show the transitions:
+//!
+//! ```ignore
+//! wait_for_state(&client, &id, InstanceStateName::Running, Duration::from_secs(300), |state| {
+//!     println!("Instance state: {}", state_name(state));
+//! })
+//! .await?;
+//! ```
+
+use std::error::Error;
+use std::fmt;
+use std::time::{Duration, Instant};
+
+use ec2::model::{InstanceStateName, SummaryStatus};
+use ec2::{Client, SdkError};
+use tokio::time::sleep;
+
+/// The error code that DescribeInstances returns for an instance it doesn't know,
+/// including one that was launched a moment ago, until the launch is visible everywhere.
+const INSTANCE_NOT_FOUND: &str = "InvalidInstanceID.NotFound";
+
+/// How long to wait before the first retry. The delay doubles after each retry.
+const INITIAL_DELAY: Duration = Duration::from_secs(2);
+
+/// The longest delay between two retries.
+const MAX_DELAY: Duration = Duration::from_secs(15);
+
+/// Why waiting for an instance failed.
+#[derive(Debug)]
+pub enum WaitError {
+    /// DescribeInstances or DescribeInstanceStatus failed.
+    Sdk(ec2::Error),
+    /// The instance reached a state it can't leave for the one it was waited for,
+    /// such as __terminated__ while waiting for __running__.
+    Unreachable(String, InstanceStateName),
+    /// The instance didn't reach the state before the timeout. Holds the last state seen.
+    Timeout(String, Option<InstanceStateName>),
+}
+
+impl fmt::Display for WaitError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            WaitError::Sdk(e) => write!(f, "{}", e),
+            WaitError::Unreachable(instance_id, state) => write!(
+                f,
+                "instance {} is {}, so it can't reach the state",
+                instance_id,
+                state.as_str()
+            ),
+            WaitError::Timeout(instance_id, state) => write!(
+                f,
+                "instance {} did not change in time, last state: {}",
+                instance_id,
+                state_name(state.as_ref())
+            ),
+        }
+    }
+}
+
+impl Error for WaitError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            WaitError::Sdk(e) => Some(e),
+            WaitError::Unreachable(..) | WaitError::Timeout(..) => None,
+        }
+    }
+}
+
+impl From<ec2::Error> for WaitError {
+    fn from(e: ec2::Error) -> Self {
+        WaitError::Sdk(e)
+    }
+}
+
+/// Returns the name of a state, such as __running__, or __not found__ if the instance isn't visible.
+pub fn state_name(state: Option<&InstanceStateName>) -> &str {
+    state.map(|state| state.as_str()).unwrap_or("not found")
+}
+
+/// Returns the instance's state, or `None` if Amazon EC2 doesn't know the instance (yet, or any more).
+pub async fn instance_state(
+    client: &Client,
+    instance_id: &str,
+) -> Result<Option<InstanceStateName>, ec2::Error> {
+    match client
+        .describe_instances()
+        .instance_ids(instance_id)
+        .send()
+        .await
+    {
+        Ok(resp) => Ok(resp
+            .reservations
+            .unwrap_or_default()
+            .into_iter()
+            .flat_map(|reservation| reservation.instances.unwrap_or_default())
+            .find_map(|instance| instance.state.and_then(|state| state.name))),
+        Err(SdkError::ServiceError { err, .. }) if err.code() == Some(INSTANCE_NOT_FOUND) => {
+            Ok(None)
+        }
+        Err(e) => Err(e.into()),
+    }
+}
+
+/// Returns whether an instance in the state can never reach the target without another request.
+/// A terminated instance stays terminated, and one that's shutting down only becomes terminated.
+fn is_unreachable(state: &InstanceStateName, target: &InstanceStateName) -> bool {
+    match state {
+        InstanceStateName::ShuttingDown => *target != InstanceStateName::Terminated,
+        InstanceStateName::Terminated => *target != InstanceStateName::Terminated,
+        _ => false,
+    }
+}
+
+/// Polls the instance's state until it's `target`, backing off exponentially between calls.
+/// Calls `on_state` with the first state it sees, and again each time the state changes.
+/// An instance that isn't found counts as terminated, since terminated instances eventually disappear,
+/// and otherwise is waited for, since a new instance takes a moment to appear.
+pub async fn wait_for_state<F>(
+    client: &Client,
+    instance_id: &str,
+    target: InstanceStateName,
+    timeout: Duration,
+    mut on_state: F,
+) -> Result<(), WaitError>
+where
+    F: FnMut(Option<&InstanceStateName>),
+{
+    let deadline = Instant::now() + timeout;
+    let mut delay = INITIAL_DELAY;
+    let mut last = None;
+    let mut first = true;
+
+    loop {
+        let state = instance_state(client, instance_id).await?;
+
+        if first || state != last {
+            on_state(state.as_ref());
+            first = false;
+        }
+
+        match &state {
+            Some(state) if *state == target => return Ok(()),
+            None if target == InstanceStateName::Terminated => return Ok(()),
+            Some(state) if is_unreachable(state, &target) => {
+                return Err(WaitError::Unreachable(
+                    instance_id.to_string(),
+                    state.clone(),
+                ))
+            }
+            _ => {}
+        }
+
+        last = state;
+
+        let now = Instant::now();
+        if now >= deadline {
+            return Err(WaitError::Timeout(instance_id.to_string(), last));
+        }
+
+        // Don't sleep past the deadline; check one last time when it's reached.
+        sleep(delay.min(deadline - now)).await;
+        delay = (delay * 2).min(MAX_DELAY);
+    }
+}
+
+/// Waits until the instance is running and has passed both its system and instance status checks.
+/// A reboot doesn't change the instance's state, so this is how to tell that the instance is back.
+/// Calls `on_status` with the system and instance status each time either changes.
+pub async fn wait_for_status_ok<F>(
+    client: &Client,
+    instance_id: &str,
+    timeout: Duration,
+    mut on_status: F,
+) -> Result<(), WaitError>
+where
+    F: FnMut(Option<&SummaryStatus>, Option<&SummaryStatus>),
+{
+    let deadline = Instant::now() + timeout;
+    let mut delay = INITIAL_DELAY;
+    let mut last = None;
+    let mut last_state = None;
+
+    loop {
+        let status = client
+            .describe_instance_status()
+            .instance_ids(instance_id)
+            .include_all_instances(true)
+            .send()
+            .await
+            .map_err(ec2::Error::from)?
+            .instance_statuses
+            .unwrap_or_default()
+            .into_iter()
+            .next();
+
+        if let Some(status) = status {
+            let state = status.instance_state.and_then(|state| state.name);
+            let system = status.system_status.and_then(|summary| summary.status);
+            let instance = status.instance_status.and_then(|summary| summary.status);
+
+            if let Some(state) = &state {
+                if is_unreachable(state, &InstanceStateName::Running) {
+                    return Err(WaitError::Unreachable(
+                        instance_id.to_string(),
+                        state.clone(),
+                    ));
+                }
+            }
+
+            let current = Some((system.clone(), instance.clone()));
+            if current != last {
+                on_status(system.as_ref(), instance.as_ref());
+                last = current;
+            }
+
+            if state == Some(InstanceStateName::Running)
+                && system == Some(SummaryStatus::Ok)
+                && instance == Some(SummaryStatus::Ok)
+            {
+                return Ok(());
+            }
+
+            last_state = state;
+        }
+
+        let now = Instant::now();
+        if now >= deadline {
+            return Err(WaitError::Timeout(instance_id.to_string(), last_state));
+        }
+
+        sleep(delay.min(deadline - now)).await;
+        delay = (delay * 2).min(MAX_DELAY);
+    }
+}
@@ -0,0 +1,194 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0.
+ */
+
+use std::error::Error;
+
+use ecr::model::{ImageIdentifier, ListImagesFilter, TagStatus};
+use ecr::{Client, Config, Region};
+
+use aws_types::region::ProvideRegion;
+
+use common::profile::ProfileCredentialsProvider;
+use structopt::StructOpt;
+use tracing_subscriber::fmt::format::FmtSpan;
+use tracing_subscriber::fmt::SubscriberBuilder;
+
+/// The most images that BatchDeleteImage deletes in one call.
+const MAX_BATCH_IMAGES: usize = 100;
+
+#[derive(Debug, StructOpt)]
+struct Opt {
+    /// The region. Overrides environment variable AWS_DEFAULT_REGION.
+    #[structopt(short, long)]
+    default_region: Option<String>,
+
+    /// The name of the repository
+    #[structopt(short, long)]
+    name: String,
+
+    /// The tag of an image to delete. Repeat to delete several
+    #[structopt(short, long)]
+    tag: Vec<String>,
+
+    /// The digest of an image to delete, such as sha256:0123... Repeat to delete several
+    #[structopt(long)]
+    digest: Vec<String>,
+
+    /// Whether to delete every image without tags
+    #[structopt(long, required_unless_one = &["tag", "digest"])]
+    untagged: bool,
+
+    /// The AWS profile to use from the shared credentials file
+    #[structopt(long)]
+    profile: Option<String>,
+
+    /// Whether to display additional information
+    #[structopt(short, long)]
+    verbose: bool,
+}
+
+/// Lists the digests of the images in the repository without tags, following every page of results.
+async fn untagged_images(client: &Client, name: &str) -> Result<Vec<ImageIdentifier>, ecr::Error> {
+    let mut images = Vec::new();
+    let mut next_token = None;
+
+    loop {
+        let resp = client
+            .list_images()
+            .repository_name(name)
+            .filter(
+                ListImagesFilter::builder()
+                    .tag_status(TagStatus::Untagged)
+                    .build(),
+            )
+            .set_next_token(next_token)
+            .send()
+            .await?;
+
+        images.extend(resp.image_ids.unwrap_or_default());
+
+        next_token = resp.next_token;
+        if next_token.is_none() {
+            return Ok(images);
+        }
+    }
+}
+
+/// Deletes images from an Amazon ECR repository, by tag, by digest, or all of the untagged ones,
+/// in batches of up to 100.
+/// Deleting by tag removes the tag, and deletes the image once it has no tags left,
+/// so an image with other tags is kept. Deleting by digest deletes the image and all of its tags.
+/// An image that couldn't be deleted, such as one that doesn't exist, is reported, and the rest are still deleted.
+/// # Arguments
+///
+/// * `-n NAME` - The name of the repository.
+/// * `[-t TAG]` - The tag of an image to delete. Repeat to delete several.
+/// * `[--digest DIGEST]` - The digest of an image to delete. Repeat to delete several.
+/// * `[--untagged]` - Delete every image without tags.
+///   You must supply at least one of **-t**, **--digest**, or **--untagged**.
+/// * `[-d DEFAULT-REGION]` - The region in which the client is created.
+///   If not supplied, uses the value of the **AWS_DEFAULT_REGION** environment variable.
+///   If the environment variable is not set, defaults to **us-west-2**.
+/// * `[--profile PROFILE]` - The AWS profile to use from the shared credentials file.
+/// * `[-v]` - Whether to display additional information.
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn Error>> {
+    let Opt {
+        default_region,
+        digest,
+        name,
+        profile,
+        tag,
+        untagged,
+        verbose,
+    } = Opt::from_args();
+
+    let region = default_region
+        .as_ref()
+        .map(|region| Region::new(region.clone()))
+        .or_else(|| aws_types::region::default_provider().region())
+        .unwrap_or_else(|| Region::new("us-west-2"));
+
+    if verbose {
+        println!("ECR client version: {}", ecr::PKG_VERSION);
+        println!("Region:             {:?}", &region);
+        println!("Repository:         {}", &name);
+        println!("Tags:               {:?}", &tag);
+        println!("Digests:            {:?}", &digest);
+        println!("Untagged:           {}", untagged);
+        println!();
+
+        SubscriberBuilder::default()
+            .with_env_filter("info")
+            .with_span_events(FmtSpan::CLOSE)
+            .init();
+    }
+
+    let mut config = Config::builder().region(region);
+    if let Some(profile) = profile {
+        config = config.credentials_provider(ProfileCredentialsProvider::new(profile));
+    }
+    let client = Client::from_conf(config.build());
+
+    let mut images: Vec<ImageIdentifier> = tag
+        .into_iter()
+        .map(|tag| ImageIdentifier::builder().image_tag(tag).build())
+        .chain(
+            digest
+                .into_iter()
+                .map(|digest| ImageIdentifier::builder().image_digest(digest).build()),
+        )
+        .collect();
+
+    if untagged {
+        images.extend(untagged_images(&client, &name).await?);
+    }
+
+    if images.is_empty() {
+        println!("There are no images to delete");
+        return Ok(());
+    }
+
+    let mut deleted = 0;
+    let mut failed = 0;
+
+    for chunk in images.chunks(MAX_BATCH_IMAGES) {
+        let resp = client
+            .batch_delete_image()
+            .repository_name(&name)
+            .set_image_ids(Some(chunk.to_vec()))
+            .send()
+            .await?;
+
+        for image in resp.image_ids.unwrap_or_default() {
+            println!(
+                "Deleted {}",
+                image.image_tag.or(image.image_digest).unwrap_or_default()
+            );
+            deleted += 1;
+        }
+
+        // BatchDeleteImage succeeds even when some images weren't deleted, and says why in the failures.
+        for failure in resp.failures.unwrap_or_default() {
+            let image = failure
+                .image_id
+                .unwrap_or_else(|| ImageIdentifier::builder().build());
+            println!(
+                "Couldn't delete {}: {}",
+                image.image_tag.or(image.image_digest).unwrap_or_default(),
+                failure.failure_reason.as_deref().unwrap_or_default()
+            );
+            failed += 1;
+        }
+    }
+
+    println!("Deleted {} images; {} failed", deleted, failed);
+
+    if failed > 0 {
+        return Err(format!("{} images couldn't be deleted", failed).into());
+    }
+
+    Ok(())
+}
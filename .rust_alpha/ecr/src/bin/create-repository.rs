@@ -0,0 +1,118 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0.
+ */
+
+use std::error::Error;
+
+use ecr::model::{ImageScanningConfiguration, ImageTagMutability};
+use ecr::{Client, Config, Region};
+
+use aws_types::region::ProvideRegion;
+
+use common::profile::ProfileCredentialsProvider;
+use structopt::StructOpt;
+use tracing_subscriber::fmt::format::FmtSpan;
+use tracing_subscriber::fmt::SubscriberBuilder;
+
+#[derive(Debug, StructOpt)]
+struct Opt {
+    /// The region. Overrides environment variable AWS_DEFAULT_REGION.
+    #[structopt(short, long)]
+    default_region: Option<String>,
+
+    /// The name of the repository, such as team/web
+    #[structopt(short, long)]
+    name: String,
+
+    /// Whether to scan each image for vulnerabilities when it's pushed
+    #[structopt(long)]
+    scan_on_push: bool,
+
+    /// Whether to stop a tag from being moved to another image once it's pushed
+    #[structopt(long)]
+    immutable_tags: bool,
+
+    /// The AWS profile to use from the shared credentials file
+    #[structopt(long)]
+    profile: Option<String>,
+
+    /// Whether to display additional information
+    #[structopt(short, long)]
+    verbose: bool,
+}
+
+/// Creates an Amazon ECR private repository, and displays its URI, which is what you tag images with to push them.
+/// # Arguments
+///
+/// * `-n NAME` - The name of the repository, such as **team/web**.
+/// * `[--scan-on-push]` - Scan each image for vulnerabilities when it's pushed.
+/// * `[--immutable-tags]` - Stop a tag from being moved to another image once it's pushed.
+/// * `[-d DEFAULT-REGION]` - The region in which the client is created.
+///   If not supplied, uses the value of the **AWS_DEFAULT_REGION** environment variable.
+///   If the environment variable is not set, defaults to **us-west-2**.
+/// * `[--profile PROFILE]` - The AWS profile to use from the shared credentials file.
+/// * `[-v]` - Whether to display additional information.
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn Error>> {
+    let Opt {
+        default_region,
+        immutable_tags,
+        name,
+        profile,
+        scan_on_push,
+        verbose,
+    } = Opt::from_args();
+
+    let region = default_region
+        .as_ref()
+        .map(|region| Region::new(region.clone()))
+        .or_else(|| aws_types::region::default_provider().region())
+        .unwrap_or_else(|| Region::new("us-west-2"));
+
+    if verbose {
+        println!("ECR client version: {}", ecr::PKG_VERSION);
+        println!("Region:             {:?}", &region);
+        println!("Repository:         {}", &name);
+        println!("Scan on push:       {}", scan_on_push);
+        println!("Immutable tags:     {}", immutable_tags);
+        println!();
+
+        SubscriberBuilder::default()
+            .with_env_filter("info")
+            .with_span_events(FmtSpan::CLOSE)
+            .init();
+    }
+
+    let mut config = Config::builder().region(region);
+    if let Some(profile) = profile {
+        config = config.credentials_provider(ProfileCredentialsProvider::new(profile));
+    }
+    let client = Client::from_conf(config.build());
+
+    let repository = client
+        .create_repository()
+        .repository_name(&name)
+        .image_scanning_configuration(
+            ImageScanningConfiguration::builder()
+                .scan_on_push(scan_on_push)
+                .build(),
+        )
+        .image_tag_mutability(if immutable_tags {
+            ImageTagMutability::Immutable
+        } else {
+            ImageTagMutability::Mutable
+        })
+        .send()
+        .await?
+        .repository
+        .ok_or("Amazon ECR did not return the repository")?;
+
+    println!("Created repository {}", &name);
+    println!(
+        "URI: {}",
+        repository.repository_uri.as_deref().unwrap_or_default()
+    );
+
+    Ok(())
+}
@@ -0,0 +1,121 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0.
+ */
+
+use std::error::Error;
+
+use ecr::{Client, Config, Region};
+
+use aws_types::region::ProvideRegion;
+
+use common::profile::ProfileCredentialsProvider;
+use smithy_types::instant::Format;
+use structopt::StructOpt;
+use tracing_subscriber::fmt::format::FmtSpan;
+use tracing_subscriber::fmt::SubscriberBuilder;
+
+/// The user name that every Amazon ECR authorization token is for.
+const USERNAME: &str = "AWS";
+
+#[derive(Debug, StructOpt)]
+struct Opt {
+    /// The region. Overrides environment variable AWS_DEFAULT_REGION.
+    #[structopt(short, long)]
+    default_region: Option<String>,
+
+    /// The AWS profile to use from the shared credentials file
+    #[structopt(long)]
+    profile: Option<String>,
+
+    /// Whether to display additional information
+    #[structopt(short, long)]
+    verbose: bool,
+}
+
+/// Gets a password for logging in to your Amazon ECR registry with Docker, and displays it on its own,
+/// so that it can be piped to `docker login`:
+///
+/// ```text
+/// cargo run --bin get-login-password | docker login --username AWS --password-stdin REGISTRY
+/// ```
+///
+/// The authorization token that Amazon ECR returns is **AWS:PASSWORD**, encoded in base64.
+/// The password is good for 12 hours. The registry and the command to log in are displayed
+/// on stderr, so they don't end up in the pipe.
+/// # Arguments
+///
+/// * `[-d DEFAULT-REGION]` - The region in which the client is created.
+///   If not supplied, uses the value of the **AWS_DEFAULT_REGION** environment variable.
+///   If the environment variable is not set, defaults to **us-west-2**.
+/// * `[--profile PROFILE]` - The AWS profile to use from the shared credentials file.
+/// * `[-v]` - Whether to display additional information.
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn Error>> {
+    let Opt {
+        default_region,
+        profile,
+        verbose,
+    } = Opt::from_args();
+
+    let region = default_region
+        .as_ref()
+        .map(|region| Region::new(region.clone()))
+        .or_else(|| aws_types::region::default_provider().region())
+        .unwrap_or_else(|| Region::new("us-west-2"));
+
+    // Everything but the password goes to stderr, so that the password can be piped to docker login.
+    if verbose {
+        eprintln!("ECR client version: {}", ecr::PKG_VERSION);
+        eprintln!("Region:             {:?}", &region);
+        eprintln!();
+
+        SubscriberBuilder::default()
+            .with_env_filter("info")
+            .with_span_events(FmtSpan::CLOSE)
+            .with_writer(std::io::stderr)
+            .init();
+    }
+
+    let mut config = Config::builder().region(region);
+    if let Some(profile) = profile {
+        config = config.credentials_provider(ProfileCredentialsProvider::new(profile));
+    }
+    let client = Client::from_conf(config.build());
+
+    let authorization = client
+        .get_authorization_token()
+        .send()
+        .await?
+        .authorization_data
+        .unwrap_or_default()
+        .into_iter()
+        .next()
+        .ok_or("Amazon ECR did not return an authorization token")?;
+
+    let token = authorization
+        .authorization_token
+        .ok_or("Amazon ECR did not return an authorization token")?;
+    let decoded = String::from_utf8(base64::decode(&token)?)?;
+    let password = match decoded.split_once(':') {
+        Some((USERNAME, password)) => password,
+        _ => return Err("the authorization token is not for the AWS user".into()),
+    };
+
+    let registry = authorization.proxy_endpoint.unwrap_or_default();
+
+    eprintln!(
+        "Log in with: docker login --username {} --password-stdin {}",
+        USERNAME, &registry
+    );
+    if let Some(expires_at) = authorization.expires_at {
+        eprintln!(
+            "The password expires at {}",
+            expires_at.fmt(Format::DateTime)
+        );
+    }
+
+    println!("{}", password);
+
+    Ok(())
+}
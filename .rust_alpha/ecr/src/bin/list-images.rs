@@ -0,0 +1,157 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0.
+ */
+
+use std::error::Error;
+
+use ecr::model::{DescribeImagesFilter, TagStatus};
+use ecr::{Client, Config, Region};
+
+use aws_types::region::ProvideRegion;
+
+use common::profile::ProfileCredentialsProvider;
+use smithy_types::instant::Format;
+use structopt::StructOpt;
+use tracing_subscriber::fmt::format::FmtSpan;
+use tracing_subscriber::fmt::SubscriberBuilder;
+
+#[derive(Debug, StructOpt)]
+struct Opt {
+    /// The region. Overrides environment variable AWS_DEFAULT_REGION.
+    #[structopt(short, long)]
+    default_region: Option<String>,
+
+    /// The name of the repository
+    #[structopt(short, long)]
+    name: String,
+
+    /// Whether to only list the images without tags
+    #[structopt(long, conflicts_with = "tagged")]
+    untagged: bool,
+
+    /// Whether to only list the images with tags
+    #[structopt(long)]
+    tagged: bool,
+
+    /// The AWS profile to use from the shared credentials file
+    #[structopt(long)]
+    profile: Option<String>,
+
+    /// Whether to display additional information
+    #[structopt(short, long)]
+    verbose: bool,
+}
+
+/// Lists the images in an Amazon ECR repository, with their tags, size, and when they were pushed,
+/// following every page of results.
+/// An image that lost its tags when they were pushed to a newer image is untagged, and only has its digest.
+/// # Arguments
+///
+/// * `-n NAME` - The name of the repository.
+/// * `[--untagged]` - Only list the images without tags.
+/// * `[--tagged]` - Only list the images with tags.
+/// * `[-d DEFAULT-REGION]` - The region in which the client is created.
+///   If not supplied, uses the value of the **AWS_DEFAULT_REGION** environment variable.
+///   If the environment variable is not set, defaults to **us-west-2**.
+/// * `[--profile PROFILE]` - The AWS profile to use from the shared credentials file.
+/// * `[-v]` - Whether to display additional information.
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn Error>> {
+    let Opt {
+        default_region,
+        name,
+        profile,
+        tagged,
+        untagged,
+        verbose,
+    } = Opt::from_args();
+
+    let region = default_region
+        .as_ref()
+        .map(|region| Region::new(region.clone()))
+        .or_else(|| aws_types::region::default_provider().region())
+        .unwrap_or_else(|| Region::new("us-west-2"));
+
+    if verbose {
+        println!("ECR client version: {}", ecr::PKG_VERSION);
+        println!("Region:             {:?}", &region);
+        println!("Repository:         {}", &name);
+        println!("Tagged only:        {}", tagged);
+        println!("Untagged only:      {}", untagged);
+        println!();
+
+        SubscriberBuilder::default()
+            .with_env_filter("info")
+            .with_span_events(FmtSpan::CLOSE)
+            .init();
+    }
+
+    let mut config = Config::builder().region(region);
+    if let Some(profile) = profile {
+        config = config.credentials_provider(ProfileCredentialsProvider::new(profile));
+    }
+    let client = Client::from_conf(config.build());
+
+    let filter = match (tagged, untagged) {
+        (true, _) => Some(TagStatus::Tagged),
+        (_, true) => Some(TagStatus::Untagged),
+        _ => None,
+    }
+    .map(|tag_status| {
+        DescribeImagesFilter::builder()
+            .tag_status(tag_status)
+            .build()
+    });
+
+    let mut count = 0;
+    let mut bytes = 0;
+    let mut next_token = None;
+
+    println!("Images in {}:", &name);
+
+    loop {
+        let resp = client
+            .describe_images()
+            .repository_name(&name)
+            .set_filter(filter.clone())
+            .set_next_token(next_token)
+            .send()
+            .await?;
+
+        for image in resp.image_details.unwrap_or_default() {
+            let tags = image.image_tags.unwrap_or_default();
+
+            println!("  {}", image.image_digest.as_deref().unwrap_or_default());
+            println!(
+                "  Tags:   {}",
+                if tags.is_empty() {
+                    "(untagged)".to_string()
+                } else {
+                    tags.join(", ")
+                }
+            );
+            println!("  Size:   {} bytes", image.image_size_in_bytes);
+            println!(
+                "  Pushed: {}",
+                image
+                    .image_pushed_at
+                    .map(|pushed_at| pushed_at.fmt(Format::DateTime))
+                    .unwrap_or_default()
+            );
+            println!();
+
+            count += 1;
+            bytes += image.image_size_in_bytes;
+        }
+
+        next_token = resp.next_token;
+        if next_token.is_none() {
+            break;
+        }
+    }
+
+    println!("Found {} images, {} bytes in all", count, bytes);
+
+    Ok(())
+}
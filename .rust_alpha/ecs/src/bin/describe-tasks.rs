@@ -0,0 +1,197 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0.
+ */
+
+use std::error::Error;
+
+use ecs::model::DesiredStatus;
+use ecs::{Client, Config, Region};
+
+use aws_types::region::ProvideRegion;
+
+use common::profile::ProfileCredentialsProvider;
+use structopt::StructOpt;
+use tracing_subscriber::fmt::format::FmtSpan;
+use tracing_subscriber::fmt::SubscriberBuilder;
+
+/// The most tasks that DescribeTasks accepts in one call.
+const MAX_DESCRIBE_TASKS: usize = 100;
+
+#[derive(Debug, StructOpt)]
+struct Opt {
+    /// The region. Overrides environment variable AWS_DEFAULT_REGION.
+    #[structopt(short, long)]
+    default_region: Option<String>,
+
+    /// The name or ARN of the cluster. Defaults to the default cluster
+    #[structopt(short, long)]
+    cluster: Option<String>,
+
+    /// The ID or ARN of a task to describe. Repeat to describe several. Defaults to the cluster's tasks
+    #[structopt(short, long)]
+    task: Vec<String>,
+
+    /// Whether to describe the cluster's stopped tasks, rather than its running ones, when no task is given
+    #[structopt(long)]
+    stopped: bool,
+
+    /// The AWS profile to use from the shared credentials file
+    #[structopt(long)]
+    profile: Option<String>,
+
+    /// Whether to display additional information
+    #[structopt(short, long)]
+    verbose: bool,
+}
+
+/// Lists the IDs of the cluster's running or stopped tasks, following every page of results.
+async fn list_tasks(
+    client: &Client,
+    cluster: &Option<String>,
+    desired_status: DesiredStatus,
+) -> Result<Vec<String>, ecs::Error> {
+    let mut tasks = Vec::new();
+    let mut next_token = None;
+
+    loop {
+        let resp = client
+            .list_tasks()
+            .set_cluster(cluster.clone())
+            .desired_status(desired_status.clone())
+            .set_next_token(next_token)
+            .send()
+            .await?;
+
+        tasks.extend(resp.task_arns.unwrap_or_default());
+
+        next_token = resp.next_token;
+        if next_token.is_none() {
+            return Ok(tasks);
+        }
+    }
+}
+
+/// Describes Amazon ECS tasks: their status, task definition, and containers,
+/// and, for a stopped task, why it stopped and each container's exit code.
+/// Describes the tasks with the IDs, or else all of the cluster's running tasks, or its recently stopped ones.
+/// Amazon ECS keeps stopped tasks for about an hour.
+/// # Arguments
+///
+/// * `[-c CLUSTER]` - The name or ARN of the cluster.
+///   If not supplied, uses the **default** cluster.
+/// * `[-t TASK]` - The ID or ARN of a task. Repeat to describe several.
+///   If not supplied, describes the cluster's tasks.
+/// * `[--stopped]` - Describe the cluster's stopped tasks rather than its running ones.
+/// * `[-d DEFAULT-REGION]` - The region in which the client is created.
+///   If not supplied, uses the value of the **AWS_DEFAULT_REGION** environment variable.
+///   If the environment variable is not set, defaults to **us-west-2**.
+/// * `[--profile PROFILE]` - The AWS profile to use from the shared credentials file.
+/// * `[-v]` - Whether to display additional information.
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn Error>> {
+    let Opt {
+        cluster,
+        default_region,
+        profile,
+        stopped,
+        task,
+        verbose,
+    } = Opt::from_args();
+
+    let region = default_region
+        .as_ref()
+        .map(|region| Region::new(region.clone()))
+        .or_else(|| aws_types::region::default_provider().region())
+        .unwrap_or_else(|| Region::new("us-west-2"));
+
+    if verbose {
+        println!("ECS client version: {}", ecs::PKG_VERSION);
+        println!("Region:             {:?}", &region);
+        println!("Cluster:            {:?}", &cluster);
+        println!("Tasks:              {:?}", &task);
+        println!("Stopped:            {}", stopped);
+        println!();
+
+        SubscriberBuilder::default()
+            .with_env_filter("info")
+            .with_span_events(FmtSpan::CLOSE)
+            .init();
+    }
+
+    let mut config = Config::builder().region(region);
+    if let Some(profile) = profile {
+        config = config.credentials_provider(ProfileCredentialsProvider::new(profile));
+    }
+    let client = Client::from_conf(config.build());
+
+    let tasks = if task.is_empty() {
+        let desired_status = if stopped {
+            DesiredStatus::Stopped
+        } else {
+            DesiredStatus::Running
+        };
+        list_tasks(&client, &cluster, desired_status).await?
+    } else {
+        task
+    };
+
+    let mut count = 0;
+
+    for chunk in tasks.chunks(MAX_DESCRIBE_TASKS) {
+        let resp = client
+            .describe_tasks()
+            .set_cluster(cluster.clone())
+            .set_tasks(Some(chunk.to_vec()))
+            .send()
+            .await?;
+
+        for task in resp.tasks.unwrap_or_default() {
+            println!("{}", task.task_arn.as_deref().unwrap_or_default());
+            println!(
+                "  Task definition: {}",
+                task.task_definition_arn.as_deref().unwrap_or_default()
+            );
+            println!(
+                "  Status:          {} (desired {})",
+                task.last_status.as_deref().unwrap_or_default(),
+                task.desired_status.as_deref().unwrap_or_default()
+            );
+
+            if let Some(reason) = &task.stopped_reason {
+                println!("  Stopped reason:  {}", reason);
+            }
+
+            for container in task.containers.unwrap_or_default() {
+                print!(
+                    "  Container {}: {}",
+                    container.name.as_deref().unwrap_or_default(),
+                    container.last_status.as_deref().unwrap_or_default()
+                );
+                if let Some(exit_code) = container.exit_code {
+                    print!(", exit code {}", exit_code);
+                }
+                if let Some(reason) = &container.reason {
+                    print!(", {}", reason);
+                }
+                println!();
+            }
+
+            println!();
+            count += 1;
+        }
+
+        // A task that doesn't exist, or that was stopped too long ago, is reported as a failure.
+        for failure in resp.failures.unwrap_or_default() {
+            println!(
+                "{}: {}",
+                failure.arn.as_deref().unwrap_or_default(),
+                failure.reason.as_deref().unwrap_or_default()
+            );
+        }
+    }
+
+    println!("Found {} tasks", count);
+
+    Ok(())
+}
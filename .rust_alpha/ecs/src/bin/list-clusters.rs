@@ -0,0 +1,129 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0.
+ */
+
+use std::error::Error;
+
+use ecs::{Client, Config, Region};
+
+use aws_types::region::ProvideRegion;
+
+use common::profile::ProfileCredentialsProvider;
+use structopt::StructOpt;
+use tracing_subscriber::fmt::format::FmtSpan;
+use tracing_subscriber::fmt::SubscriberBuilder;
+
+/// The most clusters that DescribeClusters accepts in one call.
+const MAX_DESCRIBE_CLUSTERS: usize = 100;
+
+#[derive(Debug, StructOpt)]
+struct Opt {
+    /// The region. Overrides environment variable AWS_DEFAULT_REGION.
+    #[structopt(short, long)]
+    default_region: Option<String>,
+
+    /// The AWS profile to use from the shared credentials file
+    #[structopt(long)]
+    profile: Option<String>,
+
+    /// Whether to display additional information
+    #[structopt(short, long)]
+    verbose: bool,
+}
+
+/// Lists your Amazon ECS clusters, with their status and how many services and tasks they run,
+/// following every page of results.
+/// # Arguments
+///
+/// * `[-d DEFAULT-REGION]` - The region in which the client is created.
+///   If not supplied, uses the value of the **AWS_DEFAULT_REGION** environment variable.
+///   If the environment variable is not set, defaults to **us-west-2**.
+/// * `[--profile PROFILE]` - The AWS profile to use from the shared credentials file.
+/// * `[-v]` - Whether to display additional information.
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn Error>> {
+    let Opt {
+        default_region,
+        profile,
+        verbose,
+    } = Opt::from_args();
+
+    let region = default_region
+        .as_ref()
+        .map(|region| Region::new(region.clone()))
+        .or_else(|| aws_types::region::default_provider().region())
+        .unwrap_or_else(|| Region::new("us-west-2"));
+
+    if verbose {
+        println!("ECS client version: {}", ecs::PKG_VERSION);
+        println!("Region:             {:?}", &region);
+        println!();
+
+        SubscriberBuilder::default()
+            .with_env_filter("info")
+            .with_span_events(FmtSpan::CLOSE)
+            .init();
+    }
+
+    let mut config = Config::builder().region(region);
+    if let Some(profile) = profile {
+        config = config.credentials_provider(ProfileCredentialsProvider::new(profile));
+    }
+    let client = Client::from_conf(config.build());
+
+    // ListClusters only returns ARNs, so they're described afterwards, in groups as big as DescribeClusters takes.
+    let mut arns = Vec::new();
+    let mut next_token = None;
+
+    loop {
+        let resp = client
+            .list_clusters()
+            .set_next_token(next_token)
+            .send()
+            .await?;
+
+        arns.extend(resp.cluster_arns.unwrap_or_default());
+
+        next_token = resp.next_token;
+        if next_token.is_none() {
+            break;
+        }
+    }
+
+    println!("Clusters:");
+
+    for chunk in arns.chunks(MAX_DESCRIBE_CLUSTERS) {
+        let resp = client
+            .describe_clusters()
+            .set_clusters(Some(chunk.to_vec()))
+            .send()
+            .await?;
+
+        for cluster in resp.clusters.unwrap_or_default() {
+            println!("  {}", cluster.cluster_name.as_deref().unwrap_or_default());
+            println!(
+                "  ARN:           {}",
+                cluster.cluster_arn.as_deref().unwrap_or_default()
+            );
+            println!(
+                "  Status:        {}",
+                cluster.status.as_deref().unwrap_or_default()
+            );
+            println!("  Services:      {}", cluster.active_services_count);
+            println!(
+                "  Tasks:         {} running, {} pending",
+                cluster.running_tasks_count, cluster.pending_tasks_count
+            );
+            println!(
+                "  Instances:     {}",
+                cluster.registered_container_instances_count
+            );
+            println!();
+        }
+    }
+
+    println!("Found {} clusters", arns.len());
+
+    Ok(())
+}
@@ -0,0 +1,160 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0.
+ */
+
+use std::error::Error;
+use std::fs;
+
+use ecs::model::{Compatibility, NetworkMode};
+use ecs::{Client, Config, Region};
+use ecs_code_examples::containers::{names, parse};
+
+use aws_types::region::ProvideRegion;
+
+use common::profile::ProfileCredentialsProvider;
+use structopt::StructOpt;
+use tracing_subscriber::fmt::format::FmtSpan;
+use tracing_subscriber::fmt::SubscriberBuilder;
+
+#[derive(Debug, StructOpt)]
+struct Opt {
+    /// The region. Overrides environment variable AWS_DEFAULT_REGION.
+    #[structopt(short, long)]
+    default_region: Option<String>,
+
+    /// The family of the task definition. Each registration adds a revision to the family
+    #[structopt(short, long)]
+    family: String,
+
+    /// The JSON file with the container definition, or an array of them
+    #[structopt(short, long)]
+    containers: String,
+
+    /// The CPU units for the task, such as 256 for a quarter of a vCPU
+    #[structopt(long, default_value = "256")]
+    cpu: String,
+
+    /// The memory for the task, in MiB
+    #[structopt(long, default_value = "512")]
+    memory: String,
+
+    /// The ARN of the role that Amazon ECS uses to pull the images and send the logs
+    #[structopt(long)]
+    execution_role_arn: Option<String>,
+
+    /// The ARN of the role that the containers use to call AWS services
+    #[structopt(long)]
+    task_role_arn: Option<String>,
+
+    /// The AWS profile to use from the shared credentials file
+    #[structopt(long)]
+    profile: Option<String>,
+
+    /// Whether to display additional information
+    #[structopt(short, long)]
+    verbose: bool,
+}
+
+/// Registers an Amazon ECS task definition for the Fargate launch type, from a JSON file of container definitions.
+/// Fargate tasks use the awsvpc network mode, and the CPU and memory are set for the whole task,
+/// as one of the combinations that Fargate supports, such as 256 CPU units with 512 MiB.
+/// # Arguments
+///
+/// * `-f FAMILY` - The family of the task definition.
+/// * `-c CONTAINERS` - The JSON file with the container definition, or an array of them,
+///   with the same fields that the AWS CLI uses, such as **name**, **image**, and **portMappings**.
+/// * `[--cpu CPU]` - The CPU units for the task.
+///   If not supplied, defaults to **256**.
+/// * `[--memory MEMORY]` - The memory for the task, in MiB.
+///   If not supplied, defaults to **512**.
+/// * `[--execution-role-arn ARN]` - The ARN of the role that Amazon ECS uses to pull private images and send logs
+///   to CloudWatch Logs, usually **ecsTaskExecutionRole**.
+/// * `[--task-role-arn ARN]` - The ARN of the role that the containers use to call AWS services.
+/// * `[-d DEFAULT-REGION]` - The region in which the client is created.
+///   If not supplied, uses the value of the **AWS_DEFAULT_REGION** environment variable.
+///   If the environment variable is not set, defaults to **us-west-2**.
+/// * `[--profile PROFILE]` - The AWS profile to use from the shared credentials file.
+/// * `[-v]` - Whether to display additional information.
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn Error>> {
+    let Opt {
+        containers,
+        cpu,
+        default_region,
+        execution_role_arn,
+        family,
+        memory,
+        profile,
+        task_role_arn,
+        verbose,
+    } = Opt::from_args();
+
+    let region = default_region
+        .as_ref()
+        .map(|region| Region::new(region.clone()))
+        .or_else(|| aws_types::region::default_provider().region())
+        .unwrap_or_else(|| Region::new("us-west-2"));
+
+    let json = fs::read_to_string(&containers)
+        .map_err(|e| format!("couldn't read {}: {}", &containers, e))?;
+    let definitions = parse(&json)
+        .map_err(|e| format!("{} is not a container definition: {}", &containers, e))?;
+
+    if definitions.is_empty() {
+        return Err(format!("{} has no container definitions", &containers).into());
+    }
+
+    if verbose {
+        println!("ECS client version: {}", ecs::PKG_VERSION);
+        println!("Region:             {:?}", &region);
+        println!("Family:             {}", &family);
+        println!("Containers:         {:?}", names(&definitions));
+        println!("CPU:                {}", &cpu);
+        println!("Memory:             {}", &memory);
+        println!("Execution role:     {:?}", &execution_role_arn);
+        println!("Task role:          {:?}", &task_role_arn);
+        println!();
+
+        SubscriberBuilder::default()
+            .with_env_filter("info")
+            .with_span_events(FmtSpan::CLOSE)
+            .init();
+    }
+
+    let mut config = Config::builder().region(region);
+    if let Some(profile) = profile {
+        config = config.credentials_provider(ProfileCredentialsProvider::new(profile));
+    }
+    let client = Client::from_conf(config.build());
+
+    let task_definition = client
+        .register_task_definition()
+        .family(&family)
+        .set_container_definitions(Some(definitions))
+        .requires_compatibilities(Compatibility::Fargate)
+        .network_mode(NetworkMode::Awsvpc)
+        .cpu(&cpu)
+        .memory(&memory)
+        .set_execution_role_arn(execution_role_arn)
+        .set_task_role_arn(task_role_arn)
+        .send()
+        .await?
+        .task_definition
+        .ok_or("Amazon ECS did not return the task definition")?;
+
+    println!(
+        "Registered task definition {}:{}",
+        task_definition.family.as_deref().unwrap_or_default(),
+        task_definition.revision
+    );
+    println!(
+        "ARN: {}",
+        task_definition
+            .task_definition_arn
+            .as_deref()
+            .unwrap_or_default()
+    );
+
+    Ok(())
+}
@@ -0,0 +1,177 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0.
+ */
+
+use std::error::Error;
+
+use ecs::model::{AssignPublicIp, AwsVpcConfiguration, LaunchType, NetworkConfiguration};
+use ecs::{Client, Config, Region};
+
+use aws_types::region::ProvideRegion;
+
+use common::profile::ProfileCredentialsProvider;
+use structopt::StructOpt;
+use tracing_subscriber::fmt::format::FmtSpan;
+use tracing_subscriber::fmt::SubscriberBuilder;
+
+/// The most tasks that RunTask starts in one call.
+const MAX_COUNT: i32 = 10;
+
+#[derive(Debug, StructOpt)]
+struct Opt {
+    /// The region. Overrides environment variable AWS_DEFAULT_REGION.
+    #[structopt(short, long)]
+    default_region: Option<String>,
+
+    /// The name or ARN of the cluster. Defaults to the default cluster
+    #[structopt(short, long)]
+    cluster: Option<String>,
+
+    /// The task definition to run, as FAMILY, FAMILY:REVISION, or an ARN
+    #[structopt(short, long)]
+    task_definition: String,
+
+    /// The ID of a subnet for the task's network interface. Repeat to allow several
+    #[structopt(short, long, required = true)]
+    subnet: Vec<String>,
+
+    /// The ID of a security group for the task's network interface. Repeat to add several
+    #[structopt(short = "g", long)]
+    security_group: Vec<String>,
+
+    /// Whether to give the task a public IP address
+    #[structopt(long)]
+    public_ip: bool,
+
+    /// How many copies of the task to run
+    #[structopt(long, default_value = "1")]
+    count: i32,
+
+    /// The AWS profile to use from the shared credentials file
+    #[structopt(long)]
+    profile: Option<String>,
+
+    /// Whether to display additional information
+    #[structopt(short, long)]
+    verbose: bool,
+}
+
+/// Runs an Amazon ECS task on Fargate, and displays the ARNs of the tasks that were started.
+/// Fargate tasks use the awsvpc network mode, so each task gets its own network interface in one of the subnets.
+/// A task in a private subnet needs a NAT gateway or VPC endpoints to pull its images;
+/// a task in a public subnet needs a public IP address instead.
+/// Use **describe-tasks** to follow the tasks as they start.
+/// # Arguments
+///
+/// * `[-c CLUSTER]` - The name or ARN of the cluster.
+///   If not supplied, uses the **default** cluster.
+/// * `-t TASK-DEFINITION` - The task definition to run, as **FAMILY**, **FAMILY:REVISION**, or an ARN.
+///   A family alone runs its latest active revision.
+/// * `-s SUBNET` - The ID of a subnet for the task. Repeat to allow several.
+/// * `[-g SECURITY-GROUP]` - The ID of a security group for the task. Repeat to add several.
+///   If not supplied, uses the VPC's default security group.
+/// * `[--public-ip]` - Give the task a public IP address.
+/// * `[--count COUNT]` - How many copies of the task to run, from 1 to 10.
+///   If not supplied, defaults to **1**.
+/// * `[-d DEFAULT-REGION]` - The region in which the client is created.
+///   If not supplied, uses the value of the **AWS_DEFAULT_REGION** environment variable.
+///   If the environment variable is not set, defaults to **us-west-2**.
+/// * `[--profile PROFILE]` - The AWS profile to use from the shared credentials file.
+/// * `[-v]` - Whether to display additional information.
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn Error>> {
+    let Opt {
+        cluster,
+        count,
+        default_region,
+        profile,
+        public_ip,
+        security_group,
+        subnet,
+        task_definition,
+        verbose,
+    } = Opt::from_args();
+
+    let region = default_region
+        .as_ref()
+        .map(|region| Region::new(region.clone()))
+        .or_else(|| aws_types::region::default_provider().region())
+        .unwrap_or_else(|| Region::new("us-west-2"));
+
+    if !(1..=MAX_COUNT).contains(&count) {
+        return Err(format!("the count must be from 1 to {}, got {}", MAX_COUNT, count).into());
+    }
+
+    if verbose {
+        println!("ECS client version: {}", ecs::PKG_VERSION);
+        println!("Region:             {:?}", &region);
+        println!("Cluster:            {:?}", &cluster);
+        println!("Task definition:    {}", &task_definition);
+        println!("Subnets:            {:?}", &subnet);
+        println!("Security groups:    {:?}", &security_group);
+        println!("Public IP:          {}", public_ip);
+        println!("Count:              {}", count);
+        println!();
+
+        SubscriberBuilder::default()
+            .with_env_filter("info")
+            .with_span_events(FmtSpan::CLOSE)
+            .init();
+    }
+
+    let mut config = Config::builder().region(region);
+    if let Some(profile) = profile {
+        config = config.credentials_provider(ProfileCredentialsProvider::new(profile));
+    }
+    let client = Client::from_conf(config.build());
+
+    let network_configuration = NetworkConfiguration::builder()
+        .awsvpc_configuration(
+            AwsVpcConfiguration::builder()
+                .set_subnets(Some(subnet))
+                .set_security_groups(Some(security_group).filter(|groups| !groups.is_empty()))
+                .assign_public_ip(if public_ip {
+                    AssignPublicIp::Enabled
+                } else {
+                    AssignPublicIp::Disabled
+                })
+                .build(),
+        )
+        .build();
+
+    let resp = client
+        .run_task()
+        .set_cluster(cluster)
+        .task_definition(&task_definition)
+        .launch_type(LaunchType::Fargate)
+        .network_configuration(network_configuration)
+        .count(count)
+        .send()
+        .await?;
+
+    // RunTask succeeds even when some of the tasks couldn't be started, and says why in the failures.
+    let tasks = resp.tasks.unwrap_or_default();
+    let failures = resp.failures.unwrap_or_default();
+
+    for task in &tasks {
+        println!(
+            "Started task {}",
+            task.task_arn.as_deref().unwrap_or_default()
+        );
+    }
+
+    for failure in &failures {
+        println!(
+            "Couldn't start a task: {} {}",
+            failure.reason.as_deref().unwrap_or_default(),
+            failure.detail.as_deref().unwrap_or_default()
+        );
+    }
+
+    if tasks.is_empty() {
+        return Err("no tasks were started".into());
+    }
+
+    Ok(())
+}
@@ -0,0 +1,166 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0.
+ */
+
+//! Reads container definitions from JSON, in the same shape that the AWS CLI and the console use.
+//!
+//! The SDK's models can't be deserialized, so the fields that the examples support are mirrored here,
+//! with their JSON names, and converted to the SDK's `ContainerDefinition`. A field that isn't supported
+//! is an error rather than being ignored, so a definition never runs with less than it says.
+//! The file holds one container definition, or an array of them:
+//!
+//! ```json
+//! [
+//!   {
+//!     "name": "web",
+//!     "image": "public.ecr.aws/nginx/nginx:latest",
+//!     "essential": true,
+//!     "portMappings": [{ "containerPort": 80 }],
+//!     "environment": [{ "name": "STAGE", "value": "dev" }]
+//!   }
+//! ]
+//! ```
+
+use std::collections::HashMap;
+
+use ecs::model::{
+    ContainerDefinition, KeyValuePair, LogConfiguration, LogDriver, PortMapping, TransportProtocol,
+};
+use serde::Deserialize;
+use serde_json::Value;
+
+/// A container definition, as JSON.
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+struct Container {
+    name: String,
+    image: String,
+    cpu: Option<i32>,
+    memory: Option<i32>,
+    memory_reservation: Option<i32>,
+    essential: Option<bool>,
+    #[serde(default)]
+    entry_point: Vec<String>,
+    #[serde(default)]
+    command: Vec<String>,
+    working_directory: Option<String>,
+    #[serde(default)]
+    environment: Vec<Variable>,
+    #[serde(default)]
+    port_mappings: Vec<Port>,
+    log_configuration: Option<Log>,
+}
+
+/// An environment variable.
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct Variable {
+    name: String,
+    value: String,
+}
+
+/// A port mapping. With the awsvpc network mode that Fargate uses, the host port, if set,
+/// must be the same as the container port.
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+struct Port {
+    container_port: i32,
+    host_port: Option<i32>,
+    protocol: Option<Protocol>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "lowercase")]
+enum Protocol {
+    Tcp,
+    Udp,
+}
+
+/// Where the container's output goes, such as the awslogs driver and its log group.
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+struct Log {
+    log_driver: String,
+    #[serde(default)]
+    options: HashMap<String, String>,
+}
+
+impl From<Container> for ContainerDefinition {
+    fn from(container: Container) -> Self {
+        let non_empty = |values: Vec<String>| Some(values).filter(|values| !values.is_empty());
+
+        let environment = container
+            .environment
+            .into_iter()
+            .map(|variable| {
+                KeyValuePair::builder()
+                    .name(variable.name)
+                    .value(variable.value)
+                    .build()
+            })
+            .collect::<Vec<_>>();
+
+        let port_mappings = container
+            .port_mappings
+            .into_iter()
+            .map(|port| {
+                PortMapping::builder()
+                    .container_port(port.container_port)
+                    .set_host_port(port.host_port)
+                    .set_protocol(port.protocol.map(|protocol| match protocol {
+                        Protocol::Tcp => TransportProtocol::Tcp,
+                        Protocol::Udp => TransportProtocol::Udp,
+                    }))
+                    .build()
+            })
+            .collect::<Vec<_>>();
+
+        let log_configuration = container.log_configuration.map(|log| {
+            LogConfiguration::builder()
+                .log_driver(LogDriver::from(log.log_driver.as_str()))
+                .set_options(Some(log.options).filter(|options| !options.is_empty()))
+                .build()
+        });
+
+        ContainerDefinition::builder()
+            .name(container.name)
+            .image(container.image)
+            .set_cpu(container.cpu)
+            .set_memory(container.memory)
+            .set_memory_reservation(container.memory_reservation)
+            .set_essential(container.essential)
+            .set_entry_point(non_empty(container.entry_point))
+            .set_command(non_empty(container.command))
+            .set_working_directory(container.working_directory)
+            .set_environment(Some(environment).filter(|environment| !environment.is_empty()))
+            .set_port_mappings(Some(port_mappings).filter(|mappings| !mappings.is_empty()))
+            .set_log_configuration(log_configuration)
+            .build()
+    }
+}
+
+/// Parses one container definition, or an array of them, and returns them as the SDK's model.
+pub fn parse(json: &str) -> Result<Vec<ContainerDefinition>, serde_json::Error> {
+    // Deserializing the two shapes separately, rather than trying both, keeps serde's error messages,
+    // which say which field is wrong.
+    let value: Value = serde_json::from_str(json)?;
+    let containers: Vec<Container> = if value.is_array() {
+        serde_json::from_value(value)?
+    } else {
+        vec![serde_json::from_value(value)?]
+    };
+
+    Ok(containers
+        .into_iter()
+        .map(ContainerDefinition::from)
+        .collect())
+}
+
+/// Returns the names of the containers, for display.
+pub fn names(containers: &[ContainerDefinition]) -> Vec<&str> {
+    containers
+        .iter()
+        .map(|container| container.name.as_deref().unwrap_or_default())
+        .collect()
+}
@@ -0,0 +1,162 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0.
+ */
+
+use std::error::Error;
+use std::time::Duration;
+
+use eks::model::VpcConfigRequest;
+use eks::{Client, Config, Region};
+use eks_code_examples::waiters::{status_name, wait_for_cluster_active};
+
+use aws_types::region::ProvideRegion;
+
+use common::profile::ProfileCredentialsProvider;
+use structopt::StructOpt;
+use tracing_subscriber::fmt::format::FmtSpan;
+use tracing_subscriber::fmt::SubscriberBuilder;
+
+/// How long to wait for the new cluster to be active. Most take 10 to 15 minutes.
+const ACTIVE_TIMEOUT: Duration = Duration::from_secs(30 * 60);
+
+#[derive(Debug, StructOpt)]
+struct Opt {
+    /// The region. Overrides environment variable AWS_DEFAULT_REGION.
+    #[structopt(short, long)]
+    default_region: Option<String>,
+
+    /// The name of the cluster
+    #[structopt(short, long)]
+    name: String,
+
+    /// The ARN of the IAM role that Amazon EKS uses to manage the cluster's resources
+    #[structopt(long)]
+    role_arn: String,
+
+    /// The ID of a subnet for the cluster, in at least two Availability Zones. Repeat to add several
+    #[structopt(short, long, required = true)]
+    subnet: Vec<String>,
+
+    /// The ID of a security group for the cluster's network interfaces. Repeat to add several
+    #[structopt(short = "g", long)]
+    security_group: Vec<String>,
+
+    /// The Kubernetes version, such as 1.21. Defaults to the latest version that Amazon EKS supports
+    #[structopt(long)]
+    kubernetes_version: Option<String>,
+
+    /// Whether to return as soon as the cluster is being created, rather than waiting until it's active
+    #[structopt(long)]
+    no_wait: bool,
+
+    /// The AWS profile to use from the shared credentials file
+    #[structopt(long)]
+    profile: Option<String>,
+
+    /// Whether to display additional information
+    #[structopt(short, long)]
+    verbose: bool,
+}
+
+/// Creates an Amazon EKS cluster, and displays its status until it's active.
+/// The cluster's API server is reachable from the internet, and from within the VPC.
+/// The cluster has no nodes; add a managed node group or a Fargate profile to run pods.
+/// # Arguments
+///
+/// * `-n NAME` - The name of the cluster.
+/// * `--role-arn ROLE-ARN` - The ARN of the IAM role that Amazon EKS uses to manage the cluster,
+///   with the **AmazonEKSClusterPolicy** managed policy attached.
+/// * `-s SUBNET` - The ID of a subnet for the cluster. Repeat to add several,
+///   in at least two Availability Zones.
+/// * `[-g SECURITY-GROUP]` - The ID of a security group for the cluster. Repeat to add several.
+///   Amazon EKS creates a cluster security group either way.
+/// * `[--kubernetes-version VERSION]` - The Kubernetes version.
+///   If not supplied, uses the latest version that Amazon EKS supports.
+/// * `[--no-wait]` - Return as soon as the cluster is being created.
+/// * `[-d DEFAULT-REGION]` - The region in which the client is created.
+///   If not supplied, uses the value of the **AWS_DEFAULT_REGION** environment variable.
+///   If the environment variable is not set, defaults to **us-west-2**.
+/// * `[--profile PROFILE]` - The AWS profile to use from the shared credentials file.
+/// * `[-v]` - Whether to display additional information.
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn Error>> {
+    let Opt {
+        default_region,
+        kubernetes_version,
+        name,
+        no_wait,
+        profile,
+        role_arn,
+        security_group,
+        subnet,
+        verbose,
+    } = Opt::from_args();
+
+    let region = default_region
+        .as_ref()
+        .map(|region| Region::new(region.clone()))
+        .or_else(|| aws_types::region::default_provider().region())
+        .unwrap_or_else(|| Region::new("us-west-2"));
+
+    if subnet.len() < 2 {
+        return Err("a cluster needs subnets in at least two Availability Zones".into());
+    }
+
+    if verbose {
+        println!("EKS client version: {}", eks::PKG_VERSION);
+        println!("Region:             {:?}", &region);
+        println!("Cluster:            {}", &name);
+        println!("Role ARN:           {}", &role_arn);
+        println!("Subnets:            {:?}", &subnet);
+        println!("Security groups:    {:?}", &security_group);
+        println!("Kubernetes version: {:?}", &kubernetes_version);
+        println!();
+
+        SubscriberBuilder::default()
+            .with_env_filter("info")
+            .with_span_events(FmtSpan::CLOSE)
+            .init();
+    }
+
+    let mut config = Config::builder().region(region);
+    if let Some(profile) = profile {
+        config = config.credentials_provider(ProfileCredentialsProvider::new(profile));
+    }
+    let client = Client::from_conf(config.build());
+
+    client
+        .create_cluster()
+        .name(&name)
+        .role_arn(&role_arn)
+        .resources_vpc_config(
+            VpcConfigRequest::builder()
+                .set_subnet_ids(Some(subnet))
+                .set_security_group_ids(Some(security_group).filter(|groups| !groups.is_empty()))
+                .endpoint_public_access(true)
+                .endpoint_private_access(true)
+                .build(),
+        )
+        .set_version(kubernetes_version)
+        .send()
+        .await?;
+
+    println!("Creating cluster {}", &name);
+
+    if no_wait {
+        return Ok(());
+    }
+
+    wait_for_cluster_active(&client, &name, ACTIVE_TIMEOUT, |status| {
+        println!("Cluster status: {}", status_name(status))
+    })
+    .await?;
+
+    println!("Cluster {} is active", &name);
+    println!(
+        "Run describe-cluster -n {} -k kubeconfig.yaml to connect kubectl to it",
+        &name
+    );
+
+    Ok(())
+}
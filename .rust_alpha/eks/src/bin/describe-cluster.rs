@@ -0,0 +1,172 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0.
+ */
+
+use std::error::Error;
+use std::fs;
+
+use eks::{Client, Config, Region};
+use eks_code_examples::kubeconfig::{kubeconfig, ClusterAccess};
+
+use aws_types::region::ProvideRegion;
+
+use common::profile::ProfileCredentialsProvider;
+use smithy_types::instant::Format;
+use structopt::StructOpt;
+use tracing_subscriber::fmt::format::FmtSpan;
+use tracing_subscriber::fmt::SubscriberBuilder;
+
+#[derive(Debug, StructOpt)]
+struct Opt {
+    /// The region. Overrides environment variable AWS_DEFAULT_REGION.
+    #[structopt(short, long)]
+    default_region: Option<String>,
+
+    /// The name of the cluster
+    #[structopt(short, long)]
+    name: String,
+
+    /// Write a kubeconfig for the cluster to this file, for kubectl --kubeconfig
+    #[structopt(short, long)]
+    kubeconfig: Option<String>,
+
+    /// The AWS profile to use from the shared credentials file
+    #[structopt(long)]
+    profile: Option<String>,
+
+    /// Whether to display additional information
+    #[structopt(short, long)]
+    verbose: bool,
+}
+
+/// Describes an Amazon EKS cluster: its status, Kubernetes version, API server endpoint,
+/// and the certificate authority that signed the endpoint's certificate.
+/// Optionally writes a kubeconfig for the cluster, which gets tokens with `aws eks get-token`,
+/// so the AWS CLI must be installed to use it.
+/// # Arguments
+///
+/// * `-n NAME` - The name of the cluster.
+/// * `[-k KUBECONFIG]` - The file to write a kubeconfig for the cluster to.
+///   If it's **-**, the kubeconfig is displayed instead.
+/// * `[-d DEFAULT-REGION]` - The region in which the client is created.
+///   If not supplied, uses the value of the **AWS_DEFAULT_REGION** environment variable.
+///   If the environment variable is not set, defaults to **us-west-2**.
+/// * `[--profile PROFILE]` - The AWS profile to use from the shared credentials file.
+///   The kubeconfig uses the same profile.
+/// * `[-v]` - Whether to display additional information.
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn Error>> {
+    let Opt {
+        default_region,
+        kubeconfig: kubeconfig_file,
+        name,
+        profile,
+        verbose,
+    } = Opt::from_args();
+
+    let region = default_region
+        .as_ref()
+        .map(|region| Region::new(region.clone()))
+        .or_else(|| aws_types::region::default_provider().region())
+        .unwrap_or_else(|| Region::new("us-west-2"));
+
+    if verbose {
+        println!("EKS client version: {}", eks::PKG_VERSION);
+        println!("Region:             {:?}", &region);
+        println!("Cluster:            {}", &name);
+        println!("Kubeconfig:         {:?}", &kubeconfig_file);
+        println!();
+
+        SubscriberBuilder::default()
+            .with_env_filter("info")
+            .with_span_events(FmtSpan::CLOSE)
+            .init();
+    }
+
+    let mut config = Config::builder().region(region.clone());
+    if let Some(profile) = &profile {
+        config = config.credentials_provider(ProfileCredentialsProvider::new(profile));
+    }
+    let client = Client::from_conf(config.build());
+
+    let cluster = client
+        .describe_cluster()
+        .name(&name)
+        .send()
+        .await?
+        .cluster
+        .ok_or("Amazon EKS did not return the cluster")?;
+
+    let arn = cluster.arn.as_deref().unwrap_or_default();
+    let endpoint = cluster.endpoint.as_deref();
+    let certificate_authority = cluster
+        .certificate_authority
+        .as_ref()
+        .and_then(|certificate| certificate.data.as_deref());
+
+    // A cluster that's still being created has no endpoint or certificate authority yet.
+    println!("Cluster:     {}", &name);
+    println!("ARN:         {}", arn);
+    println!(
+        "Status:      {}",
+        cluster
+            .status
+            .as_ref()
+            .map(|status| status.as_str())
+            .unwrap_or_default()
+    );
+    println!(
+        "Version:     {}",
+        cluster.version.as_deref().unwrap_or_default()
+    );
+    println!(
+        "Created:     {}",
+        cluster
+            .created_at
+            .as_ref()
+            .map(|created_at| created_at.fmt(Format::DateTime))
+            .unwrap_or_default()
+    );
+    println!("Endpoint:    {}", endpoint.unwrap_or("not available yet"));
+    println!(
+        "Certificate authority data: {}",
+        certificate_authority.unwrap_or("not available yet")
+    );
+
+    let kubeconfig_file = match kubeconfig_file {
+        Some(file) => file,
+        None => return Ok(()),
+    };
+
+    let (endpoint, certificate_authority) = match (endpoint, certificate_authority) {
+        (Some(endpoint), Some(certificate_authority)) => (endpoint, certificate_authority),
+        _ => {
+            return Err(
+                format!("cluster {} is not active yet, so it has no endpoint", &name).into(),
+            )
+        }
+    };
+
+    let yaml = kubeconfig(&ClusterAccess {
+        name: &name,
+        arn,
+        endpoint,
+        certificate_authority,
+        region: region.as_ref(),
+        profile: profile.as_deref(),
+    });
+
+    if kubeconfig_file == "-" {
+        println!();
+        print!("{}", yaml);
+    } else {
+        fs::write(&kubeconfig_file, yaml)
+            .map_err(|e| format!("couldn't write {}: {}", &kubeconfig_file, e))?;
+        println!();
+        println!("Wrote a kubeconfig to {}. Try:", &kubeconfig_file);
+        println!("  kubectl --kubeconfig {} get nodes", &kubeconfig_file);
+    }
+
+    Ok(())
+}
@@ -0,0 +1,96 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0.
+ */
+
+//! Writes a kubeconfig file that lets `kubectl` reach an Amazon EKS cluster.
+//!
+//! `kubectl` needs the cluster's API server endpoint, the certificate authority that signed the
+//! server's certificate, and a way to get a token. EKS tokens are made from your AWS credentials,
+//! so the kubeconfig runs `aws eks get-token` whenever `kubectl` needs one, as the AWS CLI's
+//! `aws eks update-kubeconfig` does. The cluster, user, and context are all named after the
+//! cluster's ARN, which is unique across accounts and regions:
+//!
+//! ```ignore
+//! let yaml = kubeconfig(&ClusterAccess {
+//!     name: "demo",
+//!     arn: "arn:aws:eks:us-west-2:123456789012:cluster/demo",
+//!     endpoint: "https://0123456789ABCDEF.gr7.us-west-2.eks.amazonaws.com",
+//!     certificate_authority: "LS0tLS1CRUdJTi...",
+//!     region: "us-west-2",
+//!     profile: None,
+//! });
+//! ```
+
+/// What a kubeconfig needs to know about a cluster.
+#[derive(Debug)]
+pub struct ClusterAccess<'a> {
+    pub name: &'a str,
+    pub arn: &'a str,
+    /// The URL of the cluster's Kubernetes API server.
+    pub endpoint: &'a str,
+    /// The certificate authority data, in base64, as DescribeCluster returns it.
+    pub certificate_authority: &'a str,
+    pub region: &'a str,
+    /// The AWS profile for `aws eks get-token` to use, or `None` for the default credentials.
+    pub profile: Option<&'a str>,
+}
+
+/// Quotes a string for YAML, so that names with colons or other special characters stay strings.
+fn quote(value: &str) -> String {
+    format!("\"{}\"", value.replace('\\', "\\\\").replace('"', "\\\""))
+}
+
+/// Returns a kubeconfig, in YAML, with the cluster, a user that gets tokens from the AWS CLI,
+/// and a context that joins them, which is also the current context.
+/// Save it to a file, and point `kubectl` at it with `--kubeconfig` or the KUBECONFIG environment variable.
+pub fn kubeconfig(access: &ClusterAccess) -> String {
+    let args: String = [
+        "--region",
+        access.region,
+        "eks",
+        "get-token",
+        "--cluster-name",
+        access.name,
+    ]
+    .iter()
+    .map(|arg| format!("      - {}\n", quote(arg)))
+    .collect();
+
+    let env = match access.profile {
+        Some(profile) => format!(
+            "      env:\n      - name: AWS_PROFILE\n        value: {}\n",
+            quote(profile)
+        ),
+        None => String::new(),
+    };
+
+    format!(
+        "apiVersion: v1
+kind: Config
+clusters:
+- cluster:
+    certificate-authority-data: {certificate_authority}
+    server: {endpoint}
+  name: {arn}
+contexts:
+- context:
+    cluster: {arn}
+    user: {arn}
+  name: {arn}
+current-context: {arn}
+users:
+- name: {arn}
+  user:
+    exec:
+      apiVersion: client.authentication.k8s.io/v1beta1
+      command: aws
+      args:
+{args}{env}",
+        certificate_authority = quote(access.certificate_authority),
+        endpoint = quote(access.endpoint),
+        arn = quote(access.arn),
+        args = args,
+        env = env,
+    )
+}
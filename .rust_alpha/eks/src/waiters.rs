@@ -0,0 +1,148 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0.
+ */
+
+//! Waits for an Amazon EKS cluster to reach a status.
+//!
+//! `CreateCluster` returns while the cluster is still __CREATING__, which usually takes 10 to 15 minutes.
+//! The waiter polls `DescribeCluster`, and calls `on_status` each time the status changes,
+//! so that an example can show the transitions:
+//!
+//! ```ignore
+//! wait_for_cluster_active(&client, &name, Duration::from_secs(1800), |status| {
+//!     println!("Cluster status: {}", status_name(status));
+//! })
+//! .await?;
+//! ```
+
+use std::error::Error;
+use std::fmt;
+use std::time::{Duration, Instant};
+
+use eks::error::DescribeClusterErrorKind;
+use eks::model::ClusterStatus;
+use eks::{Client, SdkError};
+use tokio::time::sleep;
+
+/// How long to wait before the first retry. The delay doubles after each retry.
+const INITIAL_DELAY: Duration = Duration::from_secs(10);
+
+/// The longest delay between two retries.
+const MAX_DELAY: Duration = Duration::from_secs(30);
+
+/// Why waiting for a cluster failed.
+#[derive(Debug)]
+pub enum WaitError {
+    /// DescribeCluster failed.
+    Sdk(eks::Error),
+    /// The cluster has a status it can't leave for the one it was waited for, such as __FAILED__.
+    Unreachable(String, ClusterStatus),
+    /// The cluster didn't reach the status before the timeout. Holds the last status seen.
+    Timeout(String, Option<ClusterStatus>),
+}
+
+impl fmt::Display for WaitError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            WaitError::Sdk(e) => write!(f, "{}", e),
+            WaitError::Unreachable(name, status) => write!(
+                f,
+                "cluster {} is {}, so it can't become active",
+                name,
+                status.as_str()
+            ),
+            WaitError::Timeout(name, status) => write!(
+                f,
+                "cluster {} did not change in time, last status: {}",
+                name,
+                status_name(status.as_ref())
+            ),
+        }
+    }
+}
+
+impl Error for WaitError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            WaitError::Sdk(e) => Some(e),
+            WaitError::Unreachable(..) | WaitError::Timeout(..) => None,
+        }
+    }
+}
+
+impl From<eks::Error> for WaitError {
+    fn from(e: eks::Error) -> Self {
+        WaitError::Sdk(e)
+    }
+}
+
+/// Returns the name of a status, such as __ACTIVE__, or __NOT FOUND__ if the cluster doesn't exist.
+pub fn status_name(status: Option<&ClusterStatus>) -> &str {
+    status.map(|status| status.as_str()).unwrap_or("NOT FOUND")
+}
+
+/// Returns the cluster's status, or `None` if the cluster doesn't exist.
+pub async fn cluster_status(
+    client: &Client,
+    name: &str,
+) -> Result<Option<ClusterStatus>, eks::Error> {
+    match client.describe_cluster().name(name).send().await {
+        Ok(resp) => Ok(resp.cluster.and_then(|cluster| cluster.status)),
+        Err(SdkError::ServiceError { err, .. })
+            if matches!(
+                err.kind,
+                DescribeClusterErrorKind::ResourceNotFoundException(_)
+            ) =>
+        {
+            Ok(None)
+        }
+        Err(e) => Err(e.into()),
+    }
+}
+
+/// Polls the cluster's status until it's __ACTIVE__, backing off exponentially between calls.
+/// Calls `on_status` with the first status it sees, and again each time the status changes.
+/// A cluster that's __FAILED__ or __DELETING__ never becomes active, so waiting stops.
+pub async fn wait_for_cluster_active<F>(
+    client: &Client,
+    name: &str,
+    timeout: Duration,
+    mut on_status: F,
+) -> Result<(), WaitError>
+where
+    F: FnMut(Option<&ClusterStatus>),
+{
+    let deadline = Instant::now() + timeout;
+    let mut delay = INITIAL_DELAY;
+    let mut last = None;
+    let mut first = true;
+
+    loop {
+        let status = cluster_status(client, name).await?;
+
+        if first || status != last {
+            on_status(status.as_ref());
+            first = false;
+        }
+
+        match &status {
+            Some(ClusterStatus::Active) => return Ok(()),
+            Some(status @ ClusterStatus::Failed) | Some(status @ ClusterStatus::Deleting) => {
+                return Err(WaitError::Unreachable(name.to_string(), status.clone()))
+            }
+            _ => {}
+        }
+
+        last = status;
+
+        let now = Instant::now();
+        if now >= deadline {
+            return Err(WaitError::Timeout(name.to_string(), last));
+        }
+
+        // Don't sleep past the deadline; check one last time when it's reached.
+        sleep(delay.min(deadline - now)).await;
+        delay = (delay * 2).min(MAX_DELAY);
+    }
+}
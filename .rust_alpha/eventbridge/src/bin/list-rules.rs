@@ -0,0 +1,130 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0.
+ */
+
+use std::error::Error;
+
+use eventbridge::{Client, Config, Region};
+
+use aws_types::region::ProvideRegion;
+
+use common::profile::ProfileCredentialsProvider;
+use structopt::StructOpt;
+use tracing_subscriber::fmt::format::FmtSpan;
+use tracing_subscriber::fmt::SubscriberBuilder;
+
+#[derive(Debug, StructOpt)]
+struct Opt {
+    /// The region. Overrides environment variable AWS_DEFAULT_REGION.
+    #[structopt(short, long)]
+    default_region: Option<String>,
+
+    /// Only list the rules whose names start with this prefix
+    #[structopt(short, long)]
+    prefix: Option<String>,
+
+    /// The event bus. Defaults to the default event bus
+    #[structopt(short, long)]
+    event_bus: Option<String>,
+
+    /// The AWS profile to use from the shared credentials file
+    #[structopt(long)]
+    profile: Option<String>,
+
+    /// Whether to display additional information
+    #[structopt(short, long)]
+    verbose: bool,
+}
+
+/// Lists the rules on an event bus, with whether each is enabled,
+/// and its schedule or event pattern.
+/// # Arguments
+///
+/// * `[-p PREFIX]` - Only lists the rules whose names start with this prefix.
+/// * `[-e EVENT-BUS]` - The event bus.
+///   If not supplied, uses the default event bus.
+/// * `[-d DEFAULT-REGION]` - The region in which the client is created.
+///    If not supplied, uses the value of the **AWS_DEFAULT_REGION** environment variable.
+///    If the environment variable is not set, defaults to **us-west-2**.
+/// * `[--profile PROFILE]` - The AWS profile to use from the shared credentials file.
+/// * `[-v]` - Whether to display additional information.
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn Error>> {
+    let Opt {
+        default_region,
+        event_bus,
+        prefix,
+        profile,
+        verbose,
+    } = Opt::from_args();
+
+    let region = default_region
+        .as_ref()
+        .map(|region| Region::new(region.clone()))
+        .or_else(|| aws_types::region::default_provider().region())
+        .unwrap_or_else(|| Region::new("us-west-2"));
+
+    if verbose {
+        println!("EventBridge client version: {}", eventbridge::PKG_VERSION);
+        println!("Region:                     {:?}", &region);
+        println!("Prefix:                     {:?}", &prefix);
+        println!("Event bus:                  {:?}", &event_bus);
+        println!();
+
+        SubscriberBuilder::default()
+            .with_env_filter("info")
+            .with_span_events(FmtSpan::CLOSE)
+            .init();
+    }
+
+    let mut config = Config::builder().region(region);
+    if let Some(profile) = profile {
+        config = config.credentials_provider(ProfileCredentialsProvider::new(profile));
+    }
+    let client = Client::from_conf(config.build());
+
+    let mut rules = Vec::new();
+    let mut token = None;
+
+    loop {
+        let resp = client
+            .list_rules()
+            .set_name_prefix(prefix.clone())
+            .set_event_bus_name(event_bus.clone())
+            .set_next_token(token)
+            .send()
+            .await?;
+
+        rules.extend(resp.rules.unwrap_or_default());
+
+        token = resp.next_token;
+        if token.is_none() {
+            break;
+        }
+    }
+
+    for rule in &rules {
+        println!(
+            "{:<32} {:<9} {}",
+            rule.name.as_deref().unwrap_or_default(),
+            rule.state
+                .as_ref()
+                .map(|state| state.as_str())
+                .unwrap_or_default(),
+            rule.schedule_expression
+                .as_deref()
+                .or_else(|| rule.event_pattern.as_deref())
+                .unwrap_or_default()
+        );
+
+        if let Some(description) = &rule.description {
+            println!("{:<32} {}", "", description);
+        }
+    }
+
+    println!();
+    println!("Found {} rules", rules.len());
+
+    Ok(())
+}
@@ -0,0 +1,143 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0.
+ */
+
+use std::error::Error;
+use std::fs;
+
+use eventbridge::model::PutEventsRequestEntry;
+use eventbridge::{Client, Config, Region};
+use eventbridge_code_examples::events::{parse_events, MAX_ENTRIES};
+
+use aws_types::region::ProvideRegion;
+
+use common::profile::ProfileCredentialsProvider;
+use structopt::StructOpt;
+use tracing_subscriber::fmt::format::FmtSpan;
+use tracing_subscriber::fmt::SubscriberBuilder;
+
+#[derive(Debug, StructOpt)]
+struct Opt {
+    /// The region. Overrides environment variable AWS_DEFAULT_REGION.
+    #[structopt(short, long)]
+    default_region: Option<String>,
+
+    /// The file holding the events, as JSON
+    #[structopt(short, long)]
+    file: String,
+
+    /// The event bus. Defaults to the default event bus
+    #[structopt(short, long)]
+    event_bus: Option<String>,
+
+    /// The AWS profile to use from the shared credentials file
+    #[structopt(long)]
+    profile: Option<String>,
+
+    /// Whether to display additional information
+    #[structopt(short, long)]
+    verbose: bool,
+}
+
+/// Sends the custom events in a JSON file to an event bus, in batches of up to 10,
+/// and displays the ID of each event, or why it wasn't sent.
+/// The events are checked before any are sent.
+/// # Arguments
+///
+/// * `-f FILE` - The file holding one event, or an array of them, each with a **Source**,
+///   a **DetailType**, a **Detail** object, and optional **Resources** and **EventBusName**.
+/// * `[-e EVENT-BUS]` - The event bus for the events without an **EventBusName**.
+///   If not supplied, uses the default event bus.
+/// * `[-d DEFAULT-REGION]` - The region in which the client is created.
+///    If not supplied, uses the value of the **AWS_DEFAULT_REGION** environment variable.
+///    If the environment variable is not set, defaults to **us-west-2**.
+/// * `[--profile PROFILE]` - The AWS profile to use from the shared credentials file.
+/// * `[-v]` - Whether to display additional information.
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn Error>> {
+    let Opt {
+        default_region,
+        event_bus,
+        file,
+        profile,
+        verbose,
+    } = Opt::from_args();
+
+    let region = default_region
+        .as_ref()
+        .map(|region| Region::new(region.clone()))
+        .or_else(|| aws_types::region::default_provider().region())
+        .unwrap_or_else(|| Region::new("us-west-2"));
+
+    let text = fs::read_to_string(&file).map_err(|e| format!("couldn't read {}: {}", file, e))?;
+    let events = parse_events(&text)?;
+
+    if verbose {
+        println!("EventBridge client version: {}", eventbridge::PKG_VERSION);
+        println!("Region:                     {:?}", &region);
+        println!("File:                       {}", &file);
+        println!("Events:                     {}", events.len());
+        println!("Event bus:                  {:?}", &event_bus);
+        println!();
+
+        SubscriberBuilder::default()
+            .with_env_filter("info")
+            .with_span_events(FmtSpan::CLOSE)
+            .init();
+    }
+
+    let mut config = Config::builder().region(region);
+    if let Some(profile) = profile {
+        config = config.credentials_provider(ProfileCredentialsProvider::new(profile));
+    }
+    let client = Client::from_conf(config.build());
+
+    let mut failed = 0;
+
+    for batch in events.chunks(MAX_ENTRIES) {
+        let mut entries = Vec::with_capacity(batch.len());
+        for event in batch {
+            entries.push(
+                PutEventsRequestEntry::builder()
+                    .source(&event.source)
+                    .detail_type(&event.detail_type)
+                    .detail(event.detail_json()?)
+                    .set_resources(Some(event.resources.clone()))
+                    .set_event_bus_name(event.event_bus_name.clone().or_else(|| event_bus.clone()))
+                    .build(),
+            );
+        }
+
+        let resp = client
+            .put_events()
+            .set_entries(Some(entries))
+            .send()
+            .await?;
+
+        // The results are in the same order as the entries.
+        for (event, result) in batch.iter().zip(resp.entries.unwrap_or_default()) {
+            match result.event_id {
+                Some(event_id) => println!("{:<24} {}", event.detail_type, event_id),
+                None => {
+                    failed += 1;
+                    eprintln!(
+                        "{:<24} not sent: {} {}",
+                        event.detail_type,
+                        result.error_code.as_deref().unwrap_or_default(),
+                        result.error_message.as_deref().unwrap_or_default()
+                    );
+                }
+            }
+        }
+    }
+
+    println!();
+    println!("Sent {} of {} events", events.len() - failed, events.len());
+
+    if failed > 0 {
+        return Err(format!("{} events weren't sent", failed).into());
+    }
+
+    Ok(())
+}
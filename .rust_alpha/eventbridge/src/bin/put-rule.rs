@@ -0,0 +1,165 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0.
+ */
+
+use std::error::Error;
+use std::fs;
+
+use eventbridge::model::RuleState;
+use eventbridge::{Client, Config, Region};
+use eventbridge_code_examples::rules::{check_pattern, check_schedule};
+
+use aws_types::region::ProvideRegion;
+
+use common::profile::ProfileCredentialsProvider;
+use structopt::StructOpt;
+use tracing_subscriber::fmt::format::FmtSpan;
+use tracing_subscriber::fmt::SubscriberBuilder;
+
+#[derive(Debug, StructOpt)]
+struct Opt {
+    /// The region. Overrides environment variable AWS_DEFAULT_REGION.
+    #[structopt(short, long)]
+    default_region: Option<String>,
+
+    /// The name of the rule
+    #[structopt(short, long)]
+    name: String,
+
+    /// The schedule, such as "rate(5 minutes)"
+    #[structopt(short, long, conflicts_with_all = &["pattern", "pattern-file"])]
+    schedule: Option<String>,
+
+    /// The event pattern, as JSON
+    #[structopt(short, long, conflicts_with = "pattern-file")]
+    pattern: Option<String>,
+
+    /// A file holding the event pattern, as JSON
+    #[structopt(long)]
+    pattern_file: Option<String>,
+
+    /// The event bus. Defaults to the default event bus
+    #[structopt(short, long)]
+    event_bus: Option<String>,
+
+    /// A description of the rule
+    #[structopt(long)]
+    description: Option<String>,
+
+    /// Whether to create the rule disabled
+    #[structopt(long)]
+    disabled: bool,
+
+    /// The AWS profile to use from the shared credentials file
+    #[structopt(long)]
+    profile: Option<String>,
+
+    /// Whether to display additional information
+    #[structopt(short, long)]
+    verbose: bool,
+}
+
+/// Creates or updates a rule that runs on a schedule, or matches events with a pattern,
+/// and displays its ARN, which **put-targets** uses.
+/// The schedule or pattern is checked before it's sent.
+/// # Arguments
+///
+/// * `-n NAME` - The name of the rule.
+/// * `[-s SCHEDULE]` - The schedule, such as **rate(5 minutes)** or **cron(0 12 * * ? *)**.
+/// * `[-p PATTERN]` - The event pattern, such as **{"source":["com.example.orders"]}**.
+/// * `[--pattern-file PATTERN-FILE]` - A file holding the event pattern.
+///   One of `-s`, `-p`, or `--pattern-file` must be supplied.
+/// * `[-e EVENT-BUS]` - The event bus that the rule matches events on.
+///   If not supplied, uses the default event bus. Only rules on the default event bus can have a schedule.
+/// * `[--description DESCRIPTION]` - A description of the rule.
+/// * `[--disabled]` - Creates the rule disabled, so it doesn't send events to its targets yet.
+/// * `[-d DEFAULT-REGION]` - The region in which the client is created.
+///    If not supplied, uses the value of the **AWS_DEFAULT_REGION** environment variable.
+///    If the environment variable is not set, defaults to **us-west-2**.
+/// * `[--profile PROFILE]` - The AWS profile to use from the shared credentials file.
+/// * `[-v]` - Whether to display additional information.
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn Error>> {
+    let Opt {
+        default_region,
+        description,
+        disabled,
+        event_bus,
+        name,
+        pattern,
+        pattern_file,
+        profile,
+        schedule,
+        verbose,
+    } = Opt::from_args();
+
+    let region = default_region
+        .as_ref()
+        .map(|region| Region::new(region.clone()))
+        .or_else(|| aws_types::region::default_provider().region())
+        .unwrap_or_else(|| Region::new("us-west-2"));
+
+    let pattern = match (pattern, &pattern_file) {
+        (Some(pattern), _) => Some(pattern),
+        (None, Some(file)) => {
+            Some(fs::read_to_string(file).map_err(|e| format!("couldn't read {}: {}", file, e))?)
+        }
+        (None, None) => None,
+    };
+
+    match (&schedule, &pattern) {
+        (Some(schedule), None) => check_schedule(schedule)?,
+        (None, Some(pattern)) => check_pattern(pattern)?,
+        _ => {
+            return Err("supply a schedule (-s) or an event pattern (-p or --pattern-file)".into())
+        }
+    }
+
+    let state = if disabled {
+        RuleState::Disabled
+    } else {
+        RuleState::Enabled
+    };
+
+    if verbose {
+        println!("EventBridge client version: {}", eventbridge::PKG_VERSION);
+        println!("Region:                     {:?}", &region);
+        println!("Rule:                       {}", &name);
+        println!("Schedule:                   {:?}", &schedule);
+        println!("Pattern:                    {:?}", &pattern);
+        println!("Event bus:                  {:?}", &event_bus);
+        println!("State:                      {:?}", &state);
+        println!();
+
+        SubscriberBuilder::default()
+            .with_env_filter("info")
+            .with_span_events(FmtSpan::CLOSE)
+            .init();
+    }
+
+    let mut config = Config::builder().region(region);
+    if let Some(profile) = profile {
+        config = config.credentials_provider(ProfileCredentialsProvider::new(profile));
+    }
+    let client = Client::from_conf(config.build());
+
+    let resp = client
+        .put_rule()
+        .name(&name)
+        .set_schedule_expression(schedule)
+        .set_event_pattern(pattern)
+        .set_event_bus_name(event_bus)
+        .set_description(description)
+        .state(state)
+        .send()
+        .await?;
+
+    println!(
+        "Put rule {}: {}",
+        name,
+        resp.rule_arn.as_deref().unwrap_or_default()
+    );
+
+    Ok(())
+}
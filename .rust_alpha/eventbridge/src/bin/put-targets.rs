@@ -0,0 +1,169 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0.
+ */
+
+use std::error::Error;
+
+use eventbridge::model::Target;
+use eventbridge::{Client, Config, Region};
+use eventbridge_code_examples::rules::TargetKind;
+
+use aws_types::region::ProvideRegion;
+
+use common::profile::ProfileCredentialsProvider;
+use structopt::StructOpt;
+use tracing_subscriber::fmt::format::FmtSpan;
+use tracing_subscriber::fmt::SubscriberBuilder;
+
+#[derive(Debug, StructOpt)]
+struct Opt {
+    /// The region. Overrides environment variable AWS_DEFAULT_REGION.
+    #[structopt(short, long)]
+    default_region: Option<String>,
+
+    /// The name of the rule
+    #[structopt(short, long)]
+    rule: String,
+
+    /// The ARN of the Lambda function or SQS queue
+    #[structopt(short, long)]
+    target_arn: String,
+
+    /// The ID of the target. Defaults to the function or queue name
+    #[structopt(short, long)]
+    id: Option<String>,
+
+    /// JSON to send to the target instead of the event
+    #[structopt(long)]
+    input: Option<String>,
+
+    /// The event bus of the rule. Defaults to the default event bus
+    #[structopt(short, long)]
+    event_bus: Option<String>,
+
+    /// The AWS profile to use from the shared credentials file
+    #[structopt(long)]
+    profile: Option<String>,
+
+    /// Whether to display additional information
+    #[structopt(short, long)]
+    verbose: bool,
+}
+
+/// Adds a Lambda function or an SQS queue as a target of a rule, or updates the target with the same ID,
+/// so the events that the rule matches are sent to it.
+/// Then it displays the permission that EventBridge needs to send events to the target.
+/// # Arguments
+///
+/// * `-r RULE` - The name of the rule.
+/// * `-t TARGET-ARN` - The ARN of the Lambda function or SQS queue.
+/// * `[-i ID]` - The ID of the target, which is unique for the rule.
+///   If not supplied, uses the name of the function or queue.
+/// * `[--input INPUT]` - JSON to send to the target instead of the event, such as **{"source":"schedule"}**.
+/// * `[-e EVENT-BUS]` - The event bus of the rule.
+///   If not supplied, uses the default event bus.
+/// * `[-d DEFAULT-REGION]` - The region in which the client is created.
+///    If not supplied, uses the value of the **AWS_DEFAULT_REGION** environment variable.
+///    If the environment variable is not set, defaults to **us-west-2**.
+/// * `[--profile PROFILE]` - The AWS profile to use from the shared credentials file.
+/// * `[-v]` - Whether to display additional information.
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn Error>> {
+    let Opt {
+        default_region,
+        event_bus,
+        id,
+        input,
+        profile,
+        rule,
+        target_arn,
+        verbose,
+    } = Opt::from_args();
+
+    let region = default_region
+        .as_ref()
+        .map(|region| Region::new(region.clone()))
+        .or_else(|| aws_types::region::default_provider().region())
+        .unwrap_or_else(|| Region::new("us-west-2"));
+
+    let kind = TargetKind::from_arn(&target_arn)?;
+
+    if let Some(input) = &input {
+        serde_json::from_str::<serde_json::Value>(input)
+            .map_err(|e| format!("the input isn't valid JSON: {}", e))?;
+    }
+
+    // The name of a function is the seventh field of its ARN, and the name of a queue the sixth.
+    let id = id.unwrap_or_else(|| {
+        let fields: Vec<&str> = target_arn.split(':').collect();
+        match kind {
+            TargetKind::Lambda => fields[6].to_string(),
+            TargetKind::Sqs => fields[5].to_string(),
+        }
+    });
+
+    if verbose {
+        println!("EventBridge client version: {}", eventbridge::PKG_VERSION);
+        println!("Region:                     {:?}", &region);
+        println!("Rule:                       {}", &rule);
+        println!("Target:                     {} ({:?})", &target_arn, kind);
+        println!("ID:                         {}", &id);
+        println!("Input:                      {:?}", &input);
+        println!("Event bus:                  {:?}", &event_bus);
+        println!();
+
+        SubscriberBuilder::default()
+            .with_env_filter("info")
+            .with_span_events(FmtSpan::CLOSE)
+            .init();
+    }
+
+    let mut config = Config::builder().region(region);
+    if let Some(profile) = profile {
+        config = config.credentials_provider(ProfileCredentialsProvider::new(profile));
+    }
+    let client = Client::from_conf(config.build());
+
+    // Get the rule's ARN, which the target's permission names, and check that the rule exists.
+    let described = client
+        .describe_rule()
+        .name(&rule)
+        .set_event_bus_name(event_bus.clone())
+        .send()
+        .await?;
+    let rule_arn = described.arn.unwrap_or_default();
+
+    let target = Target::builder()
+        .id(&id)
+        .arn(&target_arn)
+        .set_input(input)
+        .build();
+
+    let resp = client
+        .put_targets()
+        .rule(&rule)
+        .set_event_bus_name(event_bus)
+        .targets(target)
+        .send()
+        .await?;
+
+    if resp.failed_entry_count > 0 {
+        for entry in resp.failed_entries.unwrap_or_default() {
+            eprintln!(
+                "Couldn't add target {}: {} {}",
+                entry.target_id.as_deref().unwrap_or_default(),
+                entry.error_code.as_deref().unwrap_or_default(),
+                entry.error_message.as_deref().unwrap_or_default()
+            );
+        }
+
+        return Err("the target wasn't added".into());
+    }
+
+    println!("Added target {} to rule {}", id, rule);
+    println!();
+    println!("{}", kind.permission(&rule_arn));
+
+    Ok(())
+}
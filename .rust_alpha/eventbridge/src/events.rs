@@ -0,0 +1,82 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0.
+ */
+
+//! Reads the custom events that `put-events` sends from a JSON file.
+//!
+//! The file holds one event, or an array of them, with the same fields as a PutEvents entry.
+//! The detail can be written as a JSON object, rather than as a string of JSON:
+//!
+//! ```json
+//! [
+//!     {
+//!         "Source": "com.example.orders",
+//!         "DetailType": "OrderPlaced",
+//!         "Detail": { "orderId": "order-1", "total": 42.5 }
+//!     }
+//! ]
+//! ```
+
+use serde::Deserialize;
+use serde_json::Value;
+
+/// The most entries that one PutEvents request can have.
+pub const MAX_ENTRIES: usize = 10;
+
+/// A custom event, as it's written in the file.
+#[derive(Clone, Debug, Deserialize, PartialEq)]
+#[serde(rename_all = "PascalCase")]
+pub struct Event {
+    pub source: String,
+    pub detail_type: String,
+    /// A JSON object, or a string holding one.
+    pub detail: Value,
+    #[serde(default)]
+    pub resources: Vec<String>,
+    /// The event bus to send the event to, instead of the one that `put-events` is given.
+    pub event_bus_name: Option<String>,
+}
+
+impl Event {
+    /// Returns the detail as the string of JSON that EventBridge takes.
+    /// Fails if the detail isn't a JSON object, which EventBridge requires.
+    pub fn detail_json(&self) -> Result<String, String> {
+        let detail = match &self.detail {
+            Value::String(text) => serde_json::from_str(text).map_err(|e| {
+                format!(
+                    "the detail of the {} event isn't valid JSON: {}",
+                    self.detail_type, e
+                )
+            })?,
+            detail => detail.clone(),
+        };
+
+        if !detail.is_object() {
+            return Err(format!(
+                "the detail of the {} event must be a JSON object",
+                self.detail_type
+            ));
+        }
+
+        Ok(detail.to_string())
+    }
+}
+
+/// Reads the events from the text of a file holding one event, or an array of them.
+pub fn parse_events(text: &str) -> Result<Vec<Event>, String> {
+    let value: Value =
+        serde_json::from_str(text).map_err(|e| format!("the file isn't valid JSON: {}", e))?;
+
+    let events: Vec<Event> = match value {
+        Value::Array(_) => serde_json::from_value(value),
+        value => serde_json::from_value(value).map(|event| vec![event]),
+    }
+    .map_err(|e| format!("the file doesn't hold events: {}", e))?;
+
+    for event in &events {
+        event.detail_json()?;
+    }
+
+    Ok(events)
+}
@@ -0,0 +1,151 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0.
+ */
+
+//! Checks the schedules, event patterns, and targets of rules before they're sent to EventBridge.
+//!
+//! EventBridge only reports a mistake in a schedule or pattern once the request reaches the service,
+//! and a pattern whose values aren't arrays is rejected with a message that doesn't say which value,
+//! so the examples check them first:
+//!
+//! ```ignore
+//! check_schedule("rate(5 minutes)")?;
+//! check_pattern(r#"{"source": ["my.app"], "detail": {"status": ["FAILED"]}}"#)?;
+//! ```
+
+use serde_json::Value;
+
+/// Checks that a schedule is a rate expression, such as __rate(5 minutes)__,
+/// or a cron expression with six fields, such as __cron(0 12 * * ? *)__.
+pub fn check_schedule(schedule: &str) -> Result<(), String> {
+    if let Some(rate) = schedule
+        .strip_prefix("rate(")
+        .and_then(|rest| rest.strip_suffix(')'))
+    {
+        let (value, unit) = match rate.split_whitespace().collect::<Vec<_>>().as_slice() {
+            [value, unit] => (value.parse::<u32>().ok(), *unit),
+            _ => (None, ""),
+        };
+
+        // A rate of 1 takes the singular unit, and any other rate the plural.
+        return match (value, unit) {
+            (Some(0), _) | (None, _) => Err(format!(
+                "a rate must be a positive whole number and a unit, such as rate(5 minutes), got {}",
+                schedule
+            )),
+            (Some(1), "minute") | (Some(1), "hour") | (Some(1), "day") => Ok(()),
+            (Some(value), "minutes") | (Some(value), "hours") | (Some(value), "days")
+                if value > 1 =>
+            {
+                Ok(())
+            }
+            _ => Err(format!(
+                "the unit of a rate must be minute, hour, or day for 1, \
+                 and minutes, hours, or days otherwise, got {}",
+                schedule
+            )),
+        };
+    }
+
+    if let Some(cron) = schedule
+        .strip_prefix("cron(")
+        .and_then(|rest| rest.strip_suffix(')'))
+    {
+        let fields = cron.split_whitespace().count();
+
+        return if fields == 6 {
+            Ok(())
+        } else {
+            Err(format!(
+                "a cron expression has six fields (minutes hours day-of-month month day-of-week year), \
+                 got {} in {}",
+                fields, schedule
+            ))
+        };
+    }
+
+    Err(format!(
+        "a schedule must be rate(...) or cron(...), got {}",
+        schedule
+    ))
+}
+
+/// Checks that an event pattern is a JSON object, whose fields are either objects,
+/// to match the fields of a nested object in the event, or arrays of the values to match.
+pub fn check_pattern(pattern: &str) -> Result<(), String> {
+    let pattern: Value = serde_json::from_str(pattern)
+        .map_err(|e| format!("the event pattern isn't valid JSON: {}", e))?;
+
+    match &pattern {
+        Value::Object(fields) if !fields.is_empty() => check_fields(&pattern, ""),
+        _ => Err("the event pattern must be a JSON object with at least one field".to_string()),
+    }
+}
+
+fn check_fields(value: &Value, path: &str) -> Result<(), String> {
+    let fields = match value {
+        Value::Object(fields) => fields,
+        _ => return Ok(()),
+    };
+
+    for (name, value) in fields {
+        let path = if path.is_empty() {
+            name.to_string()
+        } else {
+            format!("{}.{}", path, name)
+        };
+
+        match value {
+            Value::Object(_) => check_fields(value, &path)?,
+            Value::Array(values) if !values.is_empty() => {}
+            _ => {
+                return Err(format!(
+                    "the {} field of the event pattern must be an array of the values to match, \
+                     such as [\"{}\"], or an object",
+                    path,
+                    value.as_str().unwrap_or("value")
+                ))
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// The services that the examples send a rule's events to.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TargetKind {
+    Lambda,
+    Sqs,
+}
+
+impl TargetKind {
+    /// Works out which service a target is from its ARN. Fails for any other service.
+    pub fn from_arn(arn: &str) -> Result<TargetKind, String> {
+        match arn.split(':').collect::<Vec<_>>().as_slice() {
+            ["arn", _, "lambda", _, _, "function", ..] => Ok(TargetKind::Lambda),
+            ["arn", _, "sqs", _, _, _] => Ok(TargetKind::Sqs),
+            _ => Err(format!(
+                "the target must be the ARN of a Lambda function or an SQS queue, got {}",
+                arn
+            )),
+        }
+    }
+
+    /// Describes the permission that EventBridge needs to send the rule's events to the target.
+    pub fn permission(&self, rule_arn: &str) -> String {
+        match self {
+            TargetKind::Lambda => format!(
+                "The function's resource-based policy must let events.amazonaws.com call \
+                 lambda:InvokeFunction, with the source ARN {}.",
+                rule_arn
+            ),
+            TargetKind::Sqs => format!(
+                "The queue's access policy must let events.amazonaws.com call sqs:SendMessage, \
+                 with the condition aws:SourceArn equal to {}.",
+                rule_arn
+            ),
+        }
+    }
+}
@@ -0,0 +1,93 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0.
+ */
+
+use iam::{Client, Config, Error, Region};
+
+use aws_types::region::ProvideRegion;
+
+use common::profile::ProfileCredentialsProvider;
+use structopt::StructOpt;
+use tracing_subscriber::fmt::format::FmtSpan;
+use tracing_subscriber::fmt::SubscriberBuilder;
+
+#[derive(Debug, StructOpt)]
+struct Opt {
+    /// The region. Overrides environment variable AWS_DEFAULT_REGION.
+    #[structopt(short, long)]
+    default_region: Option<String>,
+
+    /// The name of the role
+    #[structopt(short, long)]
+    role_name: String,
+
+    /// The ARN of the managed policy, such as arn:aws:iam::aws:policy/ReadOnlyAccess
+    #[structopt(short, long)]
+    policy_arn: String,
+
+    /// The AWS profile to use from the shared credentials file
+    #[structopt(long)]
+    profile: Option<String>,
+
+    /// Whether to display additional runtime information
+    #[structopt(short, long)]
+    verbose: bool,
+}
+
+/// Attaches a managed policy to an IAM role.
+/// # Arguments
+///
+/// * `-r ROLE-NAME` - The name of the role.
+/// * `-p POLICY-ARN` - The ARN of the managed policy.
+/// * `[-d DEFAULT-REGION]` - The region in which the client is created.
+///    IAM is a global service, but the client still needs a region.
+///    If not supplied, uses the value of the **AWS_DEFAULT_REGION** environment variable.
+///    If the environment variable is not set, defaults to **us-west-2**.
+/// * `[--profile PROFILE]` - The AWS profile to use from the shared credentials file.
+/// * `[-v]` - Whether to display additional information.
+#[tokio::main]
+async fn main() -> Result<(), Error> {
+    let Opt {
+        default_region,
+        policy_arn,
+        profile,
+        role_name,
+        verbose,
+    } = Opt::from_args();
+
+    let region = default_region
+        .as_ref()
+        .map(|region| Region::new(region.clone()))
+        .or_else(|| aws_types::region::default_provider().region())
+        .unwrap_or_else(|| Region::new("us-west-2"));
+
+    if verbose {
+        println!("IAM client version: {}", iam::PKG_VERSION);
+        println!("Region:             {:?}", &region);
+        println!("Role name:          {}", &role_name);
+        println!("Policy ARN:         {}", &policy_arn);
+
+        SubscriberBuilder::default()
+            .with_env_filter("info")
+            .with_span_events(FmtSpan::CLOSE)
+            .init();
+    }
+
+    let mut config = Config::builder().region(region);
+    if let Some(profile) = profile {
+        config = config.credentials_provider(ProfileCredentialsProvider::new(profile));
+    }
+    let client = Client::from_conf(config.build());
+
+    client
+        .attach_role_policy()
+        .role_name(&role_name)
+        .policy_arn(&policy_arn)
+        .send()
+        .await?;
+
+    println!("Attached policy {} to role {}", policy_arn, role_name);
+
+    Ok(())
+}
@@ -0,0 +1,98 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0.
+ */
+
+use std::error::Error;
+
+use iam::{Client, Config, Region};
+
+use aws_types::region::ProvideRegion;
+
+use common::profile::ProfileCredentialsProvider;
+use structopt::StructOpt;
+use tracing_subscriber::fmt::format::FmtSpan;
+use tracing_subscriber::fmt::SubscriberBuilder;
+
+#[derive(Debug, StructOpt)]
+struct Opt {
+    /// The region. Overrides environment variable AWS_DEFAULT_REGION.
+    #[structopt(short, long)]
+    default_region: Option<String>,
+
+    /// The name of the user
+    #[structopt(short, long)]
+    user_name: String,
+
+    /// The AWS profile to use from the shared credentials file
+    #[structopt(long)]
+    profile: Option<String>,
+
+    /// Whether to display additional runtime information
+    #[structopt(short, long)]
+    verbose: bool,
+}
+
+/// Creates an access key for an IAM user, and displays the access key ID and secret access key.
+/// # Arguments
+///
+/// * `-u USER-NAME` - The name of the user.
+/// * `[-d DEFAULT-REGION]` - The region in which the client is created.
+///    IAM is a global service, but the client still needs a region.
+///    If not supplied, uses the value of the **AWS_DEFAULT_REGION** environment variable.
+///    If the environment variable is not set, defaults to **us-west-2**.
+/// * `[--profile PROFILE]` - The AWS profile to use from the shared credentials file.
+/// * `[-v]` - Whether to display additional information.
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn Error>> {
+    let Opt {
+        default_region,
+        profile,
+        user_name,
+        verbose,
+    } = Opt::from_args();
+
+    let region = default_region
+        .as_ref()
+        .map(|region| Region::new(region.clone()))
+        .or_else(|| aws_types::region::default_provider().region())
+        .unwrap_or_else(|| Region::new("us-west-2"));
+
+    if verbose {
+        println!("IAM client version: {}", iam::PKG_VERSION);
+        println!("Region:             {:?}", &region);
+        println!("User name:          {}", &user_name);
+
+        SubscriberBuilder::default()
+            .with_env_filter("info")
+            .with_span_events(FmtSpan::CLOSE)
+            .init();
+    }
+
+    let mut config = Config::builder().region(region);
+    if let Some(profile) = profile {
+        config = config.credentials_provider(ProfileCredentialsProvider::new(profile));
+    }
+    let client = Client::from_conf(config.build());
+
+    let key = client
+        .create_access_key()
+        .user_name(&user_name)
+        .send()
+        .await?
+        .access_key
+        .ok_or("IAM did not return the access key")?;
+
+    println!(
+        "Access key ID:     {}",
+        key.access_key_id.unwrap_or_default()
+    );
+    println!(
+        "Secret access key: {}",
+        key.secret_access_key.unwrap_or_default()
+    );
+    println!();
+    println!("This is the only time the secret access key is available. Store it securely.");
+
+    Ok(())
+}
@@ -0,0 +1,124 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0.
+ */
+
+use std::error::Error;
+use std::fs;
+
+use iam::{Client, Config, Region};
+
+use aws_types::region::ProvideRegion;
+
+use common::profile::ProfileCredentialsProvider;
+use serde_json::Value;
+use structopt::StructOpt;
+use tracing_subscriber::fmt::format::FmtSpan;
+use tracing_subscriber::fmt::SubscriberBuilder;
+
+#[derive(Debug, StructOpt)]
+struct Opt {
+    /// The region. Overrides environment variable AWS_DEFAULT_REGION.
+    #[structopt(short, long)]
+    default_region: Option<String>,
+
+    /// The name of the role
+    #[structopt(short, long)]
+    role_name: String,
+
+    /// The file containing the trust policy, which says who can assume the role
+    #[structopt(short, long)]
+    trust_policy: String,
+
+    /// The description of the role
+    #[structopt(long)]
+    description: Option<String>,
+
+    /// The AWS profile to use from the shared credentials file
+    #[structopt(long)]
+    profile: Option<String>,
+
+    /// Whether to display additional runtime information
+    #[structopt(short, long)]
+    verbose: bool,
+}
+
+/// Reads a policy document and checks that it is a JSON object with a Statement,
+/// so that mistakes are reported with their line and column instead of as a malformed policy.
+fn read_policy(file: &str) -> Result<String, Box<dyn Error>> {
+    let text = fs::read_to_string(file)?;
+
+    let policy: Value =
+        serde_json::from_str(&text).map_err(|e| format!("{} is not valid JSON: {}", file, e))?;
+
+    if policy.get("Statement").is_none() {
+        return Err(format!("{} does not contain a Statement", file).into());
+    }
+
+    // Send the document without the file's whitespace.
+    Ok(policy.to_string())
+}
+
+/// Creates an IAM role with a trust policy read from a file.
+/// # Arguments
+///
+/// * `-r ROLE-NAME` - The name of the role.
+/// * `-t TRUST-POLICY` - The file containing the trust policy.
+/// * `[--description DESCRIPTION]` - The description of the role.
+/// * `[-d DEFAULT-REGION]` - The region in which the client is created.
+///    IAM is a global service, but the client still needs a region.
+///    If not supplied, uses the value of the **AWS_DEFAULT_REGION** environment variable.
+///    If the environment variable is not set, defaults to **us-west-2**.
+/// * `[--profile PROFILE]` - The AWS profile to use from the shared credentials file.
+/// * `[-v]` - Whether to display additional information.
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn Error>> {
+    let Opt {
+        default_region,
+        description,
+        profile,
+        role_name,
+        trust_policy,
+        verbose,
+    } = Opt::from_args();
+
+    let region = default_region
+        .as_ref()
+        .map(|region| Region::new(region.clone()))
+        .or_else(|| aws_types::region::default_provider().region())
+        .unwrap_or_else(|| Region::new("us-west-2"));
+
+    if verbose {
+        println!("IAM client version: {}", iam::PKG_VERSION);
+        println!("Region:             {:?}", &region);
+        println!("Role name:          {}", &role_name);
+        println!("Trust policy file:  {}", &trust_policy);
+
+        SubscriberBuilder::default()
+            .with_env_filter("info")
+            .with_span_events(FmtSpan::CLOSE)
+            .init();
+    }
+
+    let policy = read_policy(&trust_policy)?;
+
+    let mut config = Config::builder().region(region);
+    if let Some(profile) = profile {
+        config = config.credentials_provider(ProfileCredentialsProvider::new(profile));
+    }
+    let client = Client::from_conf(config.build());
+
+    let resp = client
+        .create_role()
+        .role_name(&role_name)
+        .assume_role_policy_document(policy)
+        .set_description(description)
+        .send()
+        .await?;
+
+    let arn = resp.role.and_then(|role| role.arn).unwrap_or_default();
+
+    println!("Created role {} with ARN {}", role_name, arn);
+
+    Ok(())
+}
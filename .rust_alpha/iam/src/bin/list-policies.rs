@@ -0,0 +1,154 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0.
+ */
+
+use std::error::Error;
+
+use iam::model::PolicyScopeType;
+use iam::{Client, Config, Region};
+
+use aws_types::region::ProvideRegion;
+
+use common::profile::ProfileCredentialsProvider;
+use percent_encoding::percent_decode_str;
+use serde_json::Value;
+use structopt::StructOpt;
+use tracing_subscriber::fmt::format::FmtSpan;
+use tracing_subscriber::fmt::SubscriberBuilder;
+
+#[derive(Debug, StructOpt)]
+struct Opt {
+    /// The region. Overrides environment variable AWS_DEFAULT_REGION.
+    #[structopt(short, long)]
+    default_region: Option<String>,
+
+    /// Which policies to list: Local (your policies), AWS (AWS managed policies), or All
+    #[structopt(short, long, default_value = "Local")]
+    scope: String,
+
+    /// Whether to list only the policies that are attached to a user, group, or role
+    #[structopt(short, long)]
+    attached: bool,
+
+    /// Whether to display the policy document of each policy
+    #[structopt(long)]
+    documents: bool,
+
+    /// The AWS profile to use from the shared credentials file
+    #[structopt(long)]
+    profile: Option<String>,
+
+    /// Whether to display additional runtime information
+    #[structopt(short, long)]
+    verbose: bool,
+}
+
+/// Returns the default version of a policy as pretty-printed JSON.
+/// IAM returns policy documents URL-encoded, so they must be decoded before they can be parsed.
+async fn policy_document(
+    client: &Client,
+    arn: &str,
+    version_id: &str,
+) -> Result<String, Box<dyn Error>> {
+    let encoded = client
+        .get_policy_version()
+        .policy_arn(arn)
+        .version_id(version_id)
+        .send()
+        .await?
+        .policy_version
+        .and_then(|version| version.document)
+        .ok_or("IAM did not return the policy document")?;
+
+    let decoded = percent_decode_str(&encoded).decode_utf8()?;
+    let document: Value = serde_json::from_str(&decoded)?;
+
+    Ok(serde_json::to_string_pretty(&document)?)
+}
+
+/// Lists your IAM managed policies, and optionally displays their policy documents.
+/// # Arguments
+///
+/// * `[-s SCOPE]` - Which policies to list: **Local** (the default), **AWS**, or **All**.
+/// * `[-a]` - Whether to list only attached policies.
+/// * `[--documents]` - Whether to display the policy document of each policy.
+/// * `[-d DEFAULT-REGION]` - The region in which the client is created.
+///    IAM is a global service, but the client still needs a region.
+///    If not supplied, uses the value of the **AWS_DEFAULT_REGION** environment variable.
+///    If the environment variable is not set, defaults to **us-west-2**.
+/// * `[--profile PROFILE]` - The AWS profile to use from the shared credentials file.
+/// * `[-v]` - Whether to display additional information.
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn Error>> {
+    let Opt {
+        attached,
+        default_region,
+        documents,
+        profile,
+        scope,
+        verbose,
+    } = Opt::from_args();
+
+    let region = default_region
+        .as_ref()
+        .map(|region| Region::new(region.clone()))
+        .or_else(|| aws_types::region::default_provider().region())
+        .unwrap_or_else(|| Region::new("us-west-2"));
+
+    if verbose {
+        println!("IAM client version: {}", iam::PKG_VERSION);
+        println!("Region:             {:?}", &region);
+        println!("Scope:              {}", &scope);
+        println!("Only attached:      {}", attached);
+
+        SubscriberBuilder::default()
+            .with_env_filter("info")
+            .with_span_events(FmtSpan::CLOSE)
+            .init();
+    }
+
+    let mut config = Config::builder().region(region);
+    if let Some(profile) = profile {
+        config = config.credentials_provider(ProfileCredentialsProvider::new(profile));
+    }
+    let client = Client::from_conf(config.build());
+
+    // ListPolicies returns a page at a time, and sets IsTruncated when there are more.
+    let mut marker = None;
+    let mut count = 0;
+
+    loop {
+        let resp = client
+            .list_policies()
+            .scope(PolicyScopeType::from(scope.as_str()))
+            .only_attached(attached)
+            .set_marker(marker)
+            .send()
+            .await?;
+
+        for policy in resp.policies.unwrap_or_default() {
+            count += 1;
+
+            let arn = policy.arn.unwrap_or_default();
+            println!("{}", &arn);
+
+            if documents {
+                if let Some(version_id) = &policy.default_version_id {
+                    println!("{}", policy_document(&client, &arn, version_id).await?);
+                    println!();
+                }
+            }
+        }
+
+        if !resp.is_truncated {
+            break;
+        }
+
+        marker = resp.marker;
+    }
+
+    println!("Found {} policies", count);
+
+    Ok(())
+}
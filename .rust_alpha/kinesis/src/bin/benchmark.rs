@@ -0,0 +1,691 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0.
+ */
+
+use std::collections::HashMap;
+use std::error::Error;
+use std::fmt;
+use std::sync::Arc;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use kinesis::error::PutRecordsErrorKind;
+use kinesis::model::PutRecordsRequestEntry;
+use kinesis::{Blob, Client, Config, Region, SdkError};
+use kinesis_code_examples::consumer::{consume_shard, list_shard_ids, Start};
+use kinesis_code_examples::latency::{Latencies, Probe, Summary, PROBE_BYTES};
+
+use aws_types::region::{EnvironmentProvider, ProvideRegion};
+
+use common::output::OutputFormat;
+use common::profile::ProfileCredentialsProvider;
+use common::shutdown::Shutdown;
+use serde::Serialize;
+use structopt::StructOpt;
+use tokio::sync::{mpsc, Semaphore};
+use tokio::time::{sleep, sleep_until, Instant};
+use tracing_subscriber::fmt::format::FmtSpan;
+use tracing_subscriber::fmt::SubscriberBuilder;
+
+/// How many records can wait in the channel before the shard tasks wait for the benchmark to catch up.
+const CHANNEL_CAPACITY: usize = 1000;
+
+/// How far before the run starts to read from, in seconds, in case this computer's clock is ahead of Kinesis's.
+/// Records from earlier runs are ignored, so reading a little too much is harmless.
+const CLOCK_SKEW: i64 = 60;
+
+/// The most records that PutRecords accepts in one call.
+const MAX_BATCH_RECORDS: usize = 500;
+
+/// The most bytes that PutRecords accepts in one call, counting the data and partition keys.
+const MAX_BATCH_BYTES: usize = 5 * 1024 * 1024;
+
+/// The most bytes in one record, counting the data and partition key.
+const MAX_RECORD_BYTES: usize = 1024 * 1024;
+
+/// The most bytes in the partition keys that the benchmark uses, which are record numbers.
+const MAX_KEY_BYTES: usize = 20;
+
+/// How many records a shard accepts a second.
+const SHARD_RECORDS_PER_SECOND: usize = 1000;
+
+/// How many bytes a shard accepts a second, counting the data and partition keys.
+const SHARD_BYTES_PER_SECOND: usize = 1024 * 1024;
+
+/// How many times to resend a record that Kinesis didn't put.
+const MAX_RETRIES: u32 = 5;
+
+/// How long to wait before the first retry. The delay doubles after each retry.
+const INITIAL_DELAY: Duration = Duration::from_millis(100);
+
+/// How many times a second the producer puts the records it owes.
+const TICKS_PER_SECOND: u32 = 10;
+
+/// The most PutRecords calls in flight at once, so that one slow call doesn't hold back the rate.
+/// Once they're all in flight, the producer waits, and falls behind the rate it's aiming for.
+const MAX_IN_FLIGHT: usize = 16;
+
+#[derive(Debug, StructOpt)]
+struct Opt {
+    /// The region
+    #[structopt(short, long)]
+    region: Option<String>,
+
+    /// The name of the stream
+    #[structopt(short, long)]
+    name: String,
+
+    /// The record rates to try, in records per second, separated by commas
+    #[structopt(long, use_delimiter = true, default_value = "100,250,500,1000")]
+    rates: Vec<usize>,
+
+    /// The record sizes to try, in bytes, separated by commas
+    #[structopt(short, long, use_delimiter = true, default_value = "100,1000,10000")]
+    sizes: Vec<usize>,
+
+    /// How long to put records at each rate, in seconds
+    #[structopt(short = "t", long, default_value = "10")]
+    step_time: u64,
+
+    /// How long to pause between the rates, in seconds, so the shards recover from throttling
+    #[structopt(short, long, default_value = "5")]
+    pause: u64,
+
+    /// How long to wait for records that haven't been read, in seconds, once they've all been put
+    #[structopt(long, default_value = "30")]
+    timeout: u64,
+
+    /// The format of the output, text or json
+    #[structopt(long, default_value = "text")]
+    output: OutputFormat,
+
+    /// The AWS profile to use from the shared credentials file
+    #[structopt(long)]
+    profile: Option<String>,
+
+    /// Whether to display additional information
+    #[structopt(short, long)]
+    verbose: bool,
+}
+
+/// What the producer measured while it put records.
+#[derive(Debug, Default)]
+struct Puts {
+    records: usize,
+    bytes: usize,
+    /// The latency of each PutRecords call, including the calls that were throttled.
+    latencies: Latencies,
+    /// How many records Kinesis didn't put because a shard's throughput was exceeded.
+    throttled_records: usize,
+    /// How many PutRecords calls were throttled as a whole.
+    throttled_calls: usize,
+    retried_records: usize,
+    /// How many records still weren't put after the last retry.
+    failed_records: usize,
+}
+
+impl Puts {
+    fn merge(&mut self, other: Puts) {
+        self.records += other.records;
+        self.bytes += other.bytes;
+        self.latencies.extend(other.latencies);
+        self.throttled_records += other.throttled_records;
+        self.throttled_calls += other.throttled_calls;
+        self.retried_records += other.retried_records;
+        self.failed_records += other.failed_records;
+    }
+}
+
+/// One record size at one rate.
+#[derive(Debug)]
+struct Step {
+    step: u16,
+    size: usize,
+    rate: usize,
+    puts: Puts,
+    seconds: f64,
+}
+
+/// The measurements of one step.
+#[derive(Debug, Serialize)]
+struct StepReport {
+    record_bytes: usize,
+    target_rate: usize,
+    /// How many records a second the stream's shards accept at this size.
+    shard_limit: usize,
+    achieved_rate: f64,
+    megabytes_per_second: f64,
+    records_put: usize,
+    put_latency: Summary,
+    throttled_records: usize,
+    throttled_calls: usize,
+    retried_records: usize,
+    failed_records: usize,
+    records_read: usize,
+    end_to_end_latency: Summary,
+}
+
+impl StepReport {
+    fn throttled(&self) -> bool {
+        self.throttled_records > 0 || self.throttled_calls > 0
+    }
+}
+
+/// The measurements of every step.
+#[derive(Debug, Serialize)]
+struct Benchmark {
+    stream: String,
+    shards: usize,
+    run: u64,
+    steps: Vec<StepReport>,
+}
+
+impl fmt::Display for Benchmark {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(
+            f,
+            "Benchmark of stream {} ({} shards), run {}",
+            self.stream, self.shards, self.run
+        )?;
+        writeln!(f)?;
+        writeln!(
+            f,
+            "{:>7} {:>6} {:>8} {:>9} {:>6} {:>6}  {:<23}  {:<23}",
+            "Bytes",
+            "Rate",
+            "Achieved",
+            "Throttled",
+            "Failed",
+            "Read",
+            "Put latency (ms)",
+            "End-to-end latency (ms)"
+        )?;
+        writeln!(
+            f,
+            "{:>47}  {:>5} {:>5} {:>5} {:>5}  {:>5} {:>5} {:>5} {:>5}",
+            "", "p50", "p90", "p99", "max", "p50", "p90", "p99", "max"
+        )?;
+
+        for step in &self.steps {
+            writeln!(
+                f,
+                "{:>7} {:>6} {:>8.0} {:>9} {:>6} {:>6}  {}  {}",
+                step.record_bytes,
+                step.target_rate,
+                step.achieved_rate,
+                step.throttled_records,
+                step.failed_records,
+                step.records_read,
+                step.put_latency,
+                step.end_to_end_latency
+            )?;
+        }
+
+        writeln!(f)?;
+
+        let mut sizes: Vec<usize> = self.steps.iter().map(|step| step.record_bytes).collect();
+        sizes.dedup();
+
+        for size in sizes {
+            let steps: Vec<&StepReport> = self
+                .steps
+                .iter()
+                .filter(|step| step.record_bytes == size)
+                .collect();
+
+            let unthrottled = steps
+                .iter()
+                .filter(|step| !step.throttled())
+                .map(|step| step.target_rate)
+                .max();
+
+            write!(
+                f,
+                "{}-byte records: the shards accept up to {} records/second; ",
+                size, steps[0].shard_limit
+            )?;
+
+            match unthrottled {
+                Some(rate) => writeln!(f, "the highest rate without throttling was {}", rate)?,
+                None => writeln!(f, "every rate was throttled")?,
+            }
+        }
+
+        write!(
+            f,
+            "End-to-end latency includes the consumer's polling: up to 1 second when a shard has no records"
+        )
+    }
+}
+
+/// Returns how many bytes a record counts against its shard's throughput.
+fn entry_bytes(entry: &PutRecordsRequestEntry) -> usize {
+    entry
+        .data
+        .as_ref()
+        .map(|data| data.as_ref().len())
+        .unwrap_or_default()
+        + entry
+            .partition_key
+            .as_deref()
+            .map(str::len)
+            .unwrap_or_default()
+}
+
+/// Puts a batch of records, resending the records that Kinesis didn't put with exponential backoff,
+/// and measures each call. Records that still aren't put after the last retry are counted as failed,
+/// rather than returned as an error, since finding the rate where that happens is the point of the benchmark.
+async fn put_batch(
+    client: &Client,
+    stream: &str,
+    mut entries: Vec<PutRecordsRequestEntry>,
+) -> Result<Puts, kinesis::Error> {
+    let mut puts = Puts::default();
+    let mut delay = INITIAL_DELAY;
+
+    for attempt in 0..=MAX_RETRIES {
+        if attempt > 0 {
+            puts.retried_records += entries.len();
+            sleep(delay).await;
+            delay *= 2;
+        }
+
+        let started = Instant::now();
+        let result = client
+            .put_records()
+            .stream_name(stream)
+            .set_records(Some(entries.clone()))
+            .send()
+            .await;
+        puts.latencies.record(started.elapsed());
+
+        let resp = match result {
+            Ok(resp) => resp,
+            Err(SdkError::ServiceError { err, .. })
+                if matches!(
+                    err.kind,
+                    PutRecordsErrorKind::ProvisionedThroughputExceededException(_)
+                ) =>
+            {
+                puts.throttled_calls += 1;
+                continue;
+            }
+            Err(e) => return Err(e.into()),
+        };
+
+        // The results are in the same order as the records, and a failed record has an error code.
+        let mut failed = Vec::new();
+
+        for (entry, result) in entries.into_iter().zip(resp.records.unwrap_or_default()) {
+            match result.error_code.as_deref() {
+                None => {
+                    puts.records += 1;
+                    puts.bytes += entry_bytes(&entry);
+                }
+                Some(code) => {
+                    if code == "ProvisionedThroughputExceededException" {
+                        puts.throttled_records += 1;
+                    }
+                    failed.push(entry);
+                }
+            }
+        }
+
+        entries = failed;
+        if entries.is_empty() {
+            break;
+        }
+    }
+
+    puts.failed_records = entries.len();
+    Ok(puts)
+}
+
+/// Puts the benchmark's records on the stream.
+struct Producer {
+    client: Arc<Client>,
+    stream: Arc<String>,
+    run: u64,
+}
+
+impl Producer {
+    /// Puts records of one size at one rate, for the step time. Ten times a second, the records owed
+    /// by then are created and put in batches, each in its own task, so the calls overlap.
+    async fn run_step(
+        &self,
+        step: u16,
+        size: usize,
+        rate: usize,
+        step_time: Duration,
+        shutdown: &Shutdown,
+    ) -> Result<(Puts, f64), Box<dyn Error>> {
+        let batch_records = MAX_BATCH_RECORDS.min(MAX_BATCH_BYTES / (size + MAX_KEY_BYTES));
+        let semaphore = Arc::new(Semaphore::new(MAX_IN_FLIGHT));
+        let ticks = (step_time.as_secs() as u32 * TICKS_PER_SECOND).max(1);
+        let tick = Duration::from_secs(1) / TICKS_PER_SECOND;
+
+        let start = Instant::now();
+        let mut handles = Vec::new();
+        let mut created = 0;
+
+        for count in 1..=ticks {
+            if shutdown.is_requested() {
+                break;
+            }
+
+            // The records owed by the end of this tick, counted from the start, so that rounding doesn't drift the rate.
+            let owed = rate * count as usize / TICKS_PER_SECOND as usize;
+            let entries: Vec<PutRecordsRequestEntry> = (created..owed)
+                .map(|number| {
+                    PutRecordsRequestEntry::builder()
+                        .data(Blob::new(Probe::now(self.run, step).encode(size)))
+                        .partition_key(number.to_string())
+                        .build()
+                })
+                .collect();
+            created = owed;
+
+            for batch in entries.chunks(batch_records) {
+                let permit = semaphore.clone().acquire_owned().await?;
+                let client = self.client.clone();
+                let stream = self.stream.clone();
+                let batch = batch.to_vec();
+
+                handles.push(tokio::spawn(async move {
+                    let result = put_batch(&client, &stream, batch).await;
+                    drop(permit);
+                    result
+                }));
+            }
+
+            sleep_until(start + tick * count).await;
+        }
+
+        let mut puts = Puts::default();
+        for handle in handles {
+            puts.merge(handle.await??);
+        }
+
+        Ok((puts, start.elapsed().as_secs_f64()))
+    }
+
+    /// Runs a step for each rate, from the lowest, for each size, with a pause between them.
+    /// The rates for a size stop increasing once records fail after every retry,
+    /// since a higher rate would only fail more of them.
+    async fn run(
+        &self,
+        sizes: &[usize],
+        rates: &[usize],
+        step_time: Duration,
+        pause: Duration,
+        shutdown: &Shutdown,
+    ) -> Result<Vec<Step>, Box<dyn Error>> {
+        let mut steps = Vec::new();
+
+        for &size in sizes {
+            for &rate in rates {
+                if shutdown.is_requested() {
+                    return Ok(steps);
+                }
+
+                if !steps.is_empty() {
+                    sleep(pause).await;
+                }
+
+                let step = steps.len() as u16;
+                println!(
+                    "Putting {}-byte records at {} records/second for {} seconds",
+                    size,
+                    rate,
+                    step_time.as_secs()
+                );
+
+                let (puts, seconds) = self.run_step(step, size, rate, step_time, shutdown).await?;
+
+                let failed = puts.failed_records;
+                steps.push(Step {
+                    step,
+                    size,
+                    rate,
+                    puts,
+                    seconds,
+                });
+
+                if failed > 0 {
+                    println!(
+                        "{} records failed after {} retries, so {}-byte records aren't tried at higher rates",
+                        failed, MAX_RETRIES, size
+                    );
+                    break;
+                }
+            }
+        }
+
+        Ok(steps)
+    }
+}
+
+/// Drives an Amazon Kinesis data stream at increasing record rates, for each record size,
+/// while reading every shard of the stream, and reports how the stream kept up:
+/// the latency of the PutRecords calls, how many records were throttled, and how long each record
+/// took from being created to being read.
+/// The rates for a size stop increasing once records fail after every retry.
+/// # Arguments
+///
+/// * `-n NAME` - The name of the stream.
+/// * `[--rates RATES]` - The rates to try, in records per second, separated by commas.
+///   If not supplied, defaults to **100,250,500,1000**.
+/// * `[-s SIZES]` - The record sizes to try, in bytes, separated by commas.
+///   If not supplied, defaults to **100,1000,10000**.
+/// * `[-t STEP-TIME]` - How long to put records at each rate, in seconds.
+///   If not supplied, defaults to **10**.
+/// * `[-p PAUSE]` - How long to pause between the rates, in seconds.
+///   If not supplied, defaults to **5**.
+/// * `[--timeout TIMEOUT]` - How long to wait for records that haven't been read, in seconds,
+///   once they've all been put. If not supplied, defaults to **30**.
+/// * `[-r REGION]` - The region in which the client is created.
+///    If not supplied, uses the value of the **AWS_DEFAULT_REGION** environment variable.
+///    If the environment variable is not set, defaults to **us-west-2**.
+/// * `[--output FORMAT]` - How to display the result, **text** (the default) or **json**.
+/// * `[--profile PROFILE]` - The AWS profile to use from the shared credentials file.
+/// * `[-v]` - Whether to display additional information.
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn Error>> {
+    let Opt {
+        name,
+        output,
+        pause,
+        profile,
+        mut rates,
+        region,
+        sizes,
+        step_time,
+        timeout,
+        verbose,
+    } = Opt::from_args();
+
+    let region = EnvironmentProvider::new()
+        .region()
+        .or_else(|| region.as_ref().map(|region| Region::new(region.clone())))
+        .unwrap_or_else(|| Region::new("us-west-2"));
+
+    rates.sort_unstable();
+    rates.dedup();
+
+    if rates.is_empty() || rates[0] == 0 {
+        return Err("the rates must be at least 1 record a second".into());
+    }
+
+    for &size in &sizes {
+        if size < PROBE_BYTES || size + MAX_KEY_BYTES > MAX_RECORD_BYTES {
+            return Err(format!(
+                "the sizes must be from {} to {} bytes, got {}",
+                PROBE_BYTES,
+                MAX_RECORD_BYTES - MAX_KEY_BYTES,
+                size
+            )
+            .into());
+        }
+    }
+
+    if verbose {
+        println!("Kinesis client version: {}", kinesis::PKG_VERSION);
+        println!("Region:                 {:?}", &region);
+        println!("Stream name:            {}", &name);
+        println!("Rates:                  {:?}", &rates);
+        println!("Sizes:                  {:?}", &sizes);
+        println!("Step time:              {} seconds", step_time);
+        println!();
+
+        SubscriberBuilder::default()
+            .with_env_filter("info")
+            .with_span_events(FmtSpan::CLOSE)
+            .init();
+    }
+
+    let mut config = Config::builder().region(region);
+    if let Some(profile) = profile {
+        config = config.credentials_provider(ProfileCredentialsProvider::new(profile));
+    }
+    let client = Arc::new(Client::from_conf(config.build()));
+
+    let now = SystemTime::now().duration_since(UNIX_EPOCH)?;
+    let run = now.as_millis() as u64;
+
+    // The consumers start reading just before the run, so they see every record it puts,
+    // whenever their shard iterators are ready.
+    let start = Start::AtTimestamp(now.as_secs() as i64 - CLOCK_SKEW);
+    let shutdown = Shutdown::listen();
+    let (sender, mut receiver) = mpsc::channel(CHANNEL_CAPACITY);
+
+    let handles: Vec<_> = list_shard_ids(&client, &name)
+        .await?
+        .into_iter()
+        .map(|shard_id| {
+            tokio::spawn(consume_shard(
+                client.clone(),
+                name.clone(),
+                shard_id,
+                start,
+                sender.clone(),
+                shutdown.clone(),
+            ))
+        })
+        .collect();
+
+    drop(sender);
+
+    let shards = handles.len();
+
+    println!(
+        "Run {}: putting records on stream {}, and reading {} shards",
+        run, &name, shards
+    );
+
+    let producer = Producer {
+        client: client.clone(),
+        stream: Arc::new(name.clone()),
+        run,
+    };
+    let producer = producer.run(
+        &sizes,
+        &rates,
+        Duration::from_secs(step_time),
+        Duration::from_secs(pause),
+        &shutdown,
+    );
+    tokio::pin!(producer);
+
+    let mut steps = None;
+    let mut read: HashMap<u16, Latencies> = HashMap::new();
+    let mut read_count = 0;
+    let mut last_record = Instant::now();
+
+    loop {
+        // Once every record has been put, stop as soon as they've all been read,
+        // or when nothing new has arrived for the timeout.
+        if let Some(steps) = &steps {
+            let put: usize = steps.iter().map(|step: &Step| step.puts.records).sum();
+            if read_count >= put {
+                break;
+            }
+        }
+
+        tokio::select! {
+            result = &mut producer, if steps.is_none() => {
+                steps = Some(result?);
+                last_record = Instant::now();
+            }
+            record = receiver.recv() => match record {
+                Some(record) => {
+                    let probe = match Probe::decode(&record.data) {
+                        Some(probe) if probe.run == run => probe,
+                        _ => continue,
+                    };
+
+                    last_record = Instant::now();
+                    read_count += 1;
+                    read.entry(probe.step).or_default().record(probe.age());
+                }
+                None => break,
+            },
+            _ = sleep_until(last_record + Duration::from_secs(timeout)), if steps.is_some() => break,
+            _ = shutdown.requested() => break,
+        }
+    }
+
+    // The shard tasks only stop by themselves on Ctrl-C, so they're cancelled,
+    // but an error from one of them before then is still reported.
+    drop(receiver);
+
+    for handle in handles {
+        handle.abort();
+        match handle.await {
+            Ok(result) => result?,
+            Err(e) if e.is_cancelled() => {}
+            Err(e) => return Err(e.into()),
+        }
+    }
+
+    let steps = steps.unwrap_or_default();
+
+    let reports = steps
+        .into_iter()
+        .map(|step| {
+            let per_second = |count: usize| {
+                if step.seconds > 0.0 {
+                    count as f64 / step.seconds
+                } else {
+                    0.0
+                }
+            };
+            let end_to_end = read.remove(&step.step).unwrap_or_default();
+
+            StepReport {
+                record_bytes: step.size,
+                target_rate: step.rate,
+                shard_limit: shards
+                    * SHARD_RECORDS_PER_SECOND.min(SHARD_BYTES_PER_SECOND / step.size),
+                achieved_rate: per_second(step.puts.records),
+                megabytes_per_second: per_second(step.puts.bytes) / 1_000_000.0,
+                records_put: step.puts.records,
+                put_latency: step.puts.latencies.summary(),
+                throttled_records: step.puts.throttled_records,
+                throttled_calls: step.puts.throttled_calls,
+                retried_records: step.puts.retried_records,
+                failed_records: step.puts.failed_records,
+                records_read: end_to_end.len(),
+                end_to_end_latency: end_to_end.summary(),
+            }
+        })
+        .collect();
+
+    output.print(&Benchmark {
+        stream: name,
+        shards,
+        run,
+        steps: reports,
+    });
+
+    Ok(())
+}
@@ -0,0 +1,185 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0.
+ */
+
+use std::error::Error;
+use std::fmt;
+use std::sync::Arc;
+
+use kinesis::{Client, Config, Region};
+use kinesis_code_examples::consumer::{consume_shard, list_shard_ids, Start};
+
+use aws_types::region::{EnvironmentProvider, ProvideRegion};
+
+use common::output::OutputFormat;
+use common::profile::ProfileCredentialsProvider;
+use common::shutdown::Shutdown;
+use serde::Serialize;
+use structopt::StructOpt;
+use tokio::sync::mpsc;
+use tracing_subscriber::fmt::format::FmtSpan;
+use tracing_subscriber::fmt::SubscriberBuilder;
+
+/// How many records can wait in the channel before the shard tasks wait for the output to catch up.
+const CHANNEL_CAPACITY: usize = 1000;
+
+#[derive(Debug, StructOpt)]
+struct Opt {
+    /// The region
+    #[structopt(short, long)]
+    region: Option<String>,
+
+    /// The name of the stream
+    #[structopt(short, long)]
+    name: String,
+
+    /// Where in each shard to start reading, oldest or latest
+    #[structopt(short, long, default_value = "latest")]
+    start: Start,
+
+    /// The format of the output, text or json
+    #[structopt(long, default_value = "text")]
+    output: OutputFormat,
+
+    /// The AWS profile to use from the shared credentials file
+    #[structopt(long)]
+    profile: Option<String>,
+
+    /// Whether to display additional information
+    #[structopt(short, long)]
+    verbose: bool,
+}
+
+/// A record, with its data as text.
+#[derive(Debug, Serialize)]
+struct Record {
+    shard_id: String,
+    sequence_number: String,
+    partition_key: String,
+    data: String,
+}
+
+impl fmt::Display for Record {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} {} [{}] {}",
+            self.shard_id, self.sequence_number, self.partition_key, self.data
+        )
+    }
+}
+
+/// Consumes the records on every shard of an Amazon Kinesis data stream at once, and prints them in the order they arrive.
+/// Each shard is read by its own task, with its own shard iterator, and the tasks send the records they read
+/// to a channel, which the main task prints from. An iterator that expires is replaced with one that starts
+/// after the last record read. Runs until you press Ctrl-C, or until every shard is closed.
+/// # Arguments
+///
+/// * `-n NAME` - The name of the stream.
+/// * `[-s START]` - Where in each shard to start reading, **oldest** or **latest**.
+///   If not supplied, defaults to **latest**, which only shows the records added after the consumer starts.
+/// * `[--output FORMAT]` - How to display the records, **text** (the default) or **json**,
+///   which displays each record as a JSON object on its own line.
+/// * `[-r REGION]` - The region in which the client is created.
+///    If not supplied, uses the value of the **AWS_DEFAULT_REGION** environment variable.
+///    If the environment variable is not set, defaults to **us-west-2**.
+/// * `[--profile PROFILE]` - The AWS profile to use from the shared credentials file.
+/// * `[-v]` - Whether to display additional information.
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn Error>> {
+    let Opt {
+        name,
+        output,
+        profile,
+        region,
+        start,
+        verbose,
+    } = Opt::from_args();
+
+    let region = EnvironmentProvider::new()
+        .region()
+        .or_else(|| region.as_ref().map(|region| Region::new(region.clone())))
+        .unwrap_or_else(|| Region::new("us-west-2"));
+
+    if verbose {
+        println!("Kinesis client version: {}", kinesis::PKG_VERSION);
+        println!("Region:                 {:?}", &region);
+        println!("Stream name:            {}", &name);
+        println!("Start:                  {:?}", start);
+
+        SubscriberBuilder::default()
+            .with_env_filter("info")
+            .with_span_events(FmtSpan::CLOSE)
+            .init();
+    }
+
+    let mut config = Config::builder().region(region);
+    if let Some(profile) = profile {
+        config = config.credentials_provider(ProfileCredentialsProvider::new(profile));
+    }
+    let client = Arc::new(Client::from_conf(config.build()));
+
+    let shard_ids = list_shard_ids(&client, &name).await?;
+
+    if !output.is_json() {
+        println!(
+            "Consuming {} shards of stream {}. Press Ctrl-C to stop",
+            shard_ids.len(),
+            &name
+        );
+    }
+
+    let shutdown = Shutdown::listen();
+    let (sender, mut receiver) = mpsc::channel(CHANNEL_CAPACITY);
+
+    let handles: Vec<_> = shard_ids
+        .into_iter()
+        .map(|shard_id| {
+            tokio::spawn(consume_shard(
+                client.clone(),
+                name.clone(),
+                shard_id,
+                start,
+                sender.clone(),
+                shutdown.clone(),
+            ))
+        })
+        .collect();
+
+    // The channel closes when every task has finished and dropped its sender, so this one mustn't be kept.
+    drop(sender);
+
+    let mut count = 0;
+
+    loop {
+        tokio::select! {
+            record = receiver.recv() => match record {
+                Some(record) => {
+                    count += 1;
+                    output.print_line(&Record {
+                        data: String::from_utf8_lossy(&record.data).into_owned(),
+                        shard_id: record.shard_id,
+                        sequence_number: record.sequence_number,
+                        partition_key: record.partition_key,
+                    });
+                }
+                None => break,
+            },
+            _ = shutdown.requested() => break,
+        }
+    }
+
+    // Dropping the receiver makes any task that's waiting to send stop.
+    drop(receiver);
+
+    for handle in handles {
+        handle.await??;
+    }
+
+    if !output.is_json() {
+        println!("Consumed {} records", count);
+    }
+
+    Ok(())
+}
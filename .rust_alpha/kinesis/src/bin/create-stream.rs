@@ -3,34 +3,89 @@
  * SPDX-License-Identifier: Apache-2.0.
  */
 
-use std::process;
+use std::error::Error;
+use std::fmt;
+use std::time::Duration;
 
 use kinesis::{Client, Config, Region};
+use kinesis_code_examples::waiters::{status_name, wait_for_stream_active};
 
 use aws_types::region::{EnvironmentProvider, ProvideRegion};
 
+use common::output::OutputFormat;
+use common::profile::ProfileCredentialsProvider;
+use serde::Serialize;
 use structopt::StructOpt;
 use tracing_subscriber::fmt::format::FmtSpan;
 use tracing_subscriber::fmt::SubscriberBuilder;
 
+/// How long to wait for the new stream to become active.
+const ACTIVE_TIMEOUT: Duration = Duration::from_secs(300);
+
 #[derive(Debug, StructOpt)]
 struct Opt {
     /// The region
     #[structopt(short, long)]
     region: Option<String>,
 
+    /// The name of the stream
     #[structopt(short, long)]
     name: String,
 
+    /// The number of shards in the stream
+    #[structopt(short, long, default_value = "4")]
+    shards: i32,
+
+    /// The format of the output, text or json
+    #[structopt(long, default_value = "text")]
+    output: OutputFormat,
+
+    /// The AWS profile to use from the shared credentials file
+    #[structopt(long)]
+    profile: Option<String>,
+
+    /// Whether to display additional information
     #[structopt(short, long)]
     verbose: bool,
 }
 
+/// The stream that was created.
+#[derive(Debug, Serialize)]
+struct CreatedStream {
+    name: String,
+    shards: i32,
+}
+
+impl fmt::Display for CreatedStream {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "Created stream {} with {} shards",
+            self.name, self.shards
+        )
+    }
+}
+
+/// Creates a Kinesis data stream, and waits for it to become active.
+/// # Arguments
+///
+/// * `-n NAME` - The name of the stream.
+/// * `[-s SHARDS]` - The number of shards in the stream.
+///    If not supplied, defaults to **4**.
+/// * `[-r REGION]` - The region in which the client is created.
+///    If not supplied, uses the value of the **AWS_DEFAULT_REGION** environment variable.
+///    If the environment variable is not set, defaults to **us-west-2**.
+/// * `[--output FORMAT]` - How to display the result, **text** (the default) or **json**.
+/// * `[--profile PROFILE]` - The AWS profile to use from the shared credentials file.
+/// * `[-v]` - Whether to display additional information.
 #[tokio::main]
-async fn main() {
+async fn main() -> Result<(), Box<dyn Error>> {
     let Opt {
         name,
+        profile,
         region,
+        shards,
+        output,
         verbose,
     } = Opt::from_args();
 
@@ -43,6 +98,7 @@ async fn main() {
         println!("Kinesis client version: {}\n", kinesis::PKG_VERSION);
         println!("Region:      {:?}", &region);
         println!("Stream name: {}", name);
+        println!("Shards:      {}", shards);
 
         SubscriberBuilder::default()
             .with_env_filter("info")
@@ -50,22 +106,29 @@ async fn main() {
             .init();
     }
 
-    let config = Config::builder().region(region).build();
+    let mut config = Config::builder().region(region);
+    if let Some(profile) = profile {
+        config = config.credentials_provider(ProfileCredentialsProvider::new(profile));
+    }
 
-    let client = Client::from_conf(config);
+    let client = Client::from_conf(config.build());
 
-    match client
+    client
         .create_stream()
-        .stream_name(name)
-        .shard_count(4)
+        .stream_name(&name)
+        .shard_count(shards)
         .send()
-        .await
-    {
-        Ok(_) => println!("Created stream"),
-        Err(e) => {
-            println!("Got an error creating stream");
-            println!("{}", e);
-            process::exit(1);
+        .await?;
+
+    // The stream can't be written to or read from until it's active.
+    wait_for_stream_active(&client, &name, ACTIVE_TIMEOUT, |status| {
+        if !output.is_json() {
+            println!("Stream status: {}", status_name(status));
         }
-    };
+    })
+    .await?;
+
+    output.print(&CreatedStream { name, shards });
+
+    Ok(())
 }
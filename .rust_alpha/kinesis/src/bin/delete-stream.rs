@@ -3,16 +3,25 @@
  * SPDX-License-Identifier: Apache-2.0.
  */
 
-use std::process;
+use std::error::Error;
+use std::fmt;
+use std::time::Duration;
 
 use kinesis::{Client, Config, Region};
+use kinesis_code_examples::waiters::{status_name, wait_for_stream_deleted};
 
 use aws_types::region::{EnvironmentProvider, ProvideRegion};
 
+use common::output::OutputFormat;
+use common::profile::ProfileCredentialsProvider;
+use serde::Serialize;
 use structopt::StructOpt;
 use tracing_subscriber::fmt::format::FmtSpan;
 use tracing_subscriber::fmt::SubscriberBuilder;
 
+/// How long to wait for the stream to be deleted.
+const DELETED_TIMEOUT: Duration = Duration::from_secs(300);
+
 #[derive(Debug, StructOpt)]
 struct Opt {
     /// The region
@@ -23,16 +32,48 @@ struct Opt {
     #[structopt(short, long)]
     name: String,
 
+    /// The format of the output, text or json
+    #[structopt(long, default_value = "text")]
+    output: OutputFormat,
+
+    /// The AWS profile to use from the shared credentials file
+    #[structopt(long)]
+    profile: Option<String>,
+
     /// Whether to display additional information
     #[structopt(short, long)]
     verbose: bool,
 }
 
+/// The stream that was deleted.
+#[derive(Debug, Serialize)]
+struct DeletedStream {
+    name: String,
+}
+
+impl fmt::Display for DeletedStream {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Deleted stream {}", self.name)
+    }
+}
+
+/// Deletes a Kinesis data stream, and waits until it no longer exists.
+/// # Arguments
+///
+/// * `-n NAME` - The name of the stream.
+/// * `[-r REGION]` - The region in which the client is created.
+///    If not supplied, uses the value of the **AWS_DEFAULT_REGION** environment variable.
+///    If the environment variable is not set, defaults to **us-west-2**.
+/// * `[--output FORMAT]` - How to display the result, **text** (the default) or **json**.
+/// * `[--profile PROFILE]` - The AWS profile to use from the shared credentials file.
+/// * `[-v]` - Whether to display additional information.
 #[tokio::main]
-async fn main() {
+async fn main() -> Result<(), Box<dyn Error>> {
     let Opt {
         name,
+        profile,
         region,
+        output,
         verbose,
     } = Opt::from_args();
 
@@ -52,16 +93,24 @@ async fn main() {
             .init();
     }
 
-    let config = Config::builder().region(region).build();
+    let mut config = Config::builder().region(region);
+    if let Some(profile) = profile {
+        config = config.credentials_provider(ProfileCredentialsProvider::new(profile));
+    }
+
+    let client = Client::from_conf(config.build());
 
-    let client = Client::from_conf(config);
+    client.delete_stream().stream_name(&name).send().await?;
 
-    match client.delete_stream().stream_name(name).send().await {
-        Ok(_) => println!("Deleted stream."),
-        Err(e) => {
-            println!("Got an error deleting the stream:");
-            println!("{}", e);
-            process::exit(1);
+    // Until the stream is gone, a new stream with the same name can't be created.
+    wait_for_stream_deleted(&client, &name, DELETED_TIMEOUT, |status| {
+        if !output.is_json() {
+            println!("Stream status: {}", status_name(status));
         }
-    };
+    })
+    .await?;
+
+    output.print(&DeletedStream { name });
+
+    Ok(())
 }
@@ -2,12 +2,16 @@
  * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
  * SPDX-License-Identifier: Apache-2.0.
  */
-use std::process;
+use std::error::Error;
+use std::fmt;
 
 use kinesis::{Client, Config, Region};
 
 use aws_types::region::{EnvironmentProvider, ProvideRegion};
 
+use common::output::OutputFormat;
+use common::profile::ProfileCredentialsProvider;
+use serde::Serialize;
 use structopt::StructOpt;
 use tracing_subscriber::fmt::format::FmtSpan;
 use tracing_subscriber::fmt::SubscriberBuilder;
@@ -22,15 +26,56 @@ struct Opt {
     #[structopt(short, long)]
     name: String,
 
+    /// The format of the output, text or json
+    #[structopt(long, default_value = "text")]
+    output: OutputFormat,
+
+    /// The AWS profile to use from the shared credentials file
+    #[structopt(long)]
+    profile: Option<String>,
+
     /// Whether to display additional information
     #[structopt(short, long)]
     verbose: bool,
 }
 
+/// A summary of a stream.
+#[derive(Debug, Serialize)]
+struct StreamDescription {
+    name: String,
+    status: String,
+    open_shards: usize,
+    retention_period_hours: i32,
+    encryption: String,
+}
+
+impl fmt::Display for StreamDescription {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "Stream description:")?;
+        writeln!(f, "  Name:              {}:", self.name)?;
+        writeln!(f, "  Status:            {}", self.status)?;
+        writeln!(f, "  Open shards:       {}", self.open_shards)?;
+        writeln!(f, "  Retention (hours): {}", self.retention_period_hours)?;
+        write!(f, "  Encryption:        {}", self.encryption)
+    }
+}
+
+/// Displays information about a Kinesis data stream.
+/// # Arguments
+///
+/// * `-n NAME` - The name of the stream.
+/// * `[-r REGION]` - The region in which the client is created.
+///    If not supplied, uses the value of the **AWS_DEFAULT_REGION** environment variable.
+///    If the environment variable is not set, defaults to **us-west-2**.
+/// * `[--output FORMAT]` - How to display the result, **text** (the default) or **json**.
+/// * `[--profile PROFILE]` - The AWS profile to use from the shared credentials file.
+/// * `[-v]` - Whether to display additional information.
 #[tokio::main]
-async fn main() {
+async fn main() -> Result<(), Box<dyn Error>> {
     let Opt {
         name,
+        output,
+        profile,
         region,
         verbose,
     } = Opt::from_args();
@@ -51,28 +96,32 @@ async fn main() {
             .init();
     }
 
-    let config = Config::builder().region(region).build();
-
-    let client = Client::from_conf(config);
-
-    match client.describe_stream().stream_name(name).send().await {
-        Ok(resp) => {
-            let desc = resp.stream_description.unwrap();
-
-            println!("Stream description:");
-            println!("  Name:              {}:", desc.stream_name.unwrap());
-            println!("  Status:            {:?}", desc.stream_status.unwrap());
-            println!("  Open shards:       {:?}", desc.shards.unwrap().len());
-            println!(
-                "  Retention (hours): {}",
-                desc.retention_period_hours.unwrap()
-            );
-            println!("  Encryption:        {:?}", desc.encryption_type.unwrap());
-        }
-        Err(e) => {
-            println!("Got an error describing stream");
-            println!("{}", e);
-            process::exit(1);
-        }
-    };
+    let mut config = Config::builder().region(region);
+    if let Some(profile) = profile {
+        config = config.credentials_provider(ProfileCredentialsProvider::new(profile));
+    }
+
+    let client = Client::from_conf(config.build());
+
+    let resp = client.describe_stream().stream_name(&name).send().await?;
+
+    let desc = resp
+        .stream_description
+        .ok_or_else(|| format!("no description returned for stream {}", name))?;
+
+    output.print(&StreamDescription {
+        name: desc.stream_name.unwrap_or(name),
+        status: desc
+            .stream_status
+            .map(|status| status.as_str().to_string())
+            .unwrap_or_default(),
+        open_shards: desc.shards.map(|shards| shards.len()).unwrap_or_default(),
+        retention_period_hours: desc.retention_period_hours.unwrap_or_default(),
+        encryption: desc
+            .encryption_type
+            .map(|encryption| encryption.as_str().to_string())
+            .unwrap_or_default(),
+    });
+
+    Ok(())
 }
@@ -0,0 +1,262 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0.
+ */
+
+use std::error::Error;
+use std::fmt;
+use std::time::Duration;
+
+use kinesis::model::ShardIteratorType;
+use kinesis::{Client, Config, Region};
+use kinesis_code_examples::records::{decode, DecodedRecord, SCHEMA_VERSION};
+
+use aws_types::region::{EnvironmentProvider, ProvideRegion};
+
+use common::output::OutputFormat;
+use common::profile::ProfileCredentialsProvider;
+use serde::Serialize;
+use structopt::StructOpt;
+use tokio::time::sleep;
+use tracing_subscriber::fmt::format::FmtSpan;
+use tracing_subscriber::fmt::SubscriberBuilder;
+
+/// How long to wait between GetRecords calls. Each shard supports up to five calls a second.
+const READ_INTERVAL: Duration = Duration::from_millis(250);
+
+#[derive(Debug, StructOpt)]
+struct Opt {
+    /// The region
+    #[structopt(short, long)]
+    region: Option<String>,
+
+    /// The name of the stream
+    #[structopt(short, long)]
+    name: String,
+
+    /// The most records to read from each shard
+    #[structopt(short, long, default_value = "100")]
+    max: usize,
+
+    /// The format of the output, text or json
+    #[structopt(long, default_value = "text")]
+    output: OutputFormat,
+
+    /// The AWS profile to use from the shared credentials file
+    #[structopt(long)]
+    profile: Option<String>,
+
+    /// Whether to display additional information
+    #[structopt(short, long)]
+    verbose: bool,
+}
+
+/// A record that was read, and decoded.
+#[derive(Debug, Serialize)]
+struct JsonRecord {
+    shard_id: String,
+    sequence_number: String,
+    #[serde(flatten)]
+    record: DecodedRecord,
+}
+
+/// The records that were read.
+#[derive(Debug, Serialize)]
+struct JsonRecords {
+    stream: String,
+    records: Vec<JsonRecord>,
+    /// How many records had a newer schema version than this consumer understands, and were skipped.
+    unsupported: usize,
+    /// How many records weren't JSON or gzipped JSON, and were skipped.
+    undecodable: usize,
+}
+
+impl fmt::Display for JsonRecords {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Records on stream {}:", self.stream)?;
+
+        for json in &self.records {
+            let version = match json.record.schema_version {
+                Some(version) => format!("v{}", version),
+                None => "plain".to_string(),
+            };
+
+            write!(
+                f,
+                "\n  {} {} [{}{}] {}",
+                json.shard_id,
+                json.sequence_number,
+                version,
+                if json.record.compressed { ", gzip" } else { "" },
+                json.record.payload
+            )?;
+        }
+
+        if self.unsupported > 0 {
+            write!(
+                f,
+                "\nSkipped {} records with a schema version newer than {}",
+                self.unsupported, SCHEMA_VERSION
+            )?;
+        }
+
+        if self.undecodable > 0 {
+            write!(f, "\nSkipped {} records that aren't JSON", self.undecodable)?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Reads the records in a shard from the beginning, until `max` records are read
+/// or the reader catches up with the end of the shard, and decodes them.
+async fn read_shard(
+    client: &Client,
+    stream: &str,
+    shard_id: &str,
+    max: usize,
+    list: &mut JsonRecords,
+    verbose: bool,
+) -> Result<(), kinesis::Error> {
+    let mut iterator = client
+        .get_shard_iterator()
+        .stream_name(stream)
+        .shard_id(shard_id)
+        .shard_iterator_type(ShardIteratorType::TrimHorizon)
+        .send()
+        .await?
+        .shard_iterator;
+    let mut read = 0;
+
+    while let Some(current) = iterator {
+        if read >= max {
+            break;
+        }
+
+        let resp = client.get_records().shard_iterator(current).send().await?;
+
+        for record in resp.records.unwrap_or_default() {
+            if read >= max {
+                break;
+            }
+            read += 1;
+
+            let sequence_number = record.sequence_number.unwrap_or_default();
+            let data = record
+                .data
+                .map(|data| data.into_inner())
+                .unwrap_or_default();
+
+            // A consumer has to keep going past a bad record, or it would stop the whole shard.
+            match decode(&data) {
+                Ok(decoded) if decoded.schema_version.map_or(false, |v| v > SCHEMA_VERSION) => {
+                    if verbose {
+                        println!(
+                            "Skipping {}, which has schema version {:?}",
+                            &sequence_number, decoded.schema_version
+                        );
+                    }
+                    list.unsupported += 1;
+                }
+                Ok(decoded) => list.records.push(JsonRecord {
+                    shard_id: shard_id.to_string(),
+                    sequence_number,
+                    record: decoded,
+                }),
+                Err(e) => {
+                    eprintln!("Got an error decoding record {}: {}", &sequence_number, e);
+                    list.undecodable += 1;
+                }
+            }
+        }
+
+        // A reader has caught up when it's no milliseconds behind the latest record.
+        if resp.millis_behind_latest == Some(0) {
+            break;
+        }
+
+        iterator = resp.next_shard_iterator;
+        sleep(READ_INTERVAL).await;
+    }
+
+    Ok(())
+}
+
+/// Reads the records on an Amazon Kinesis data stream that `put-json-records`, or any other producer
+/// of JSON, put on it, from the start of each shard.
+/// Decodes each record whether it's compressed with gzip or not, and whether it has a schema version or not,
+/// and skips records with a newer schema version than the consumer understands.
+/// # Arguments
+///
+/// * `-n NAME` - The name of the stream.
+/// * `[-m MAX]` - The most records to read from each shard.
+///   If not supplied, defaults to **100**.
+/// * `[-r REGION]` - The region in which the client is created.
+///    If not supplied, uses the value of the **AWS_DEFAULT_REGION** environment variable.
+///    If the environment variable is not set, defaults to **us-west-2**.
+/// * `[--output FORMAT]` - How to display the result, **text** (the default) or **json**.
+/// * `[--profile PROFILE]` - The AWS profile to use from the shared credentials file.
+/// * `[-v]` - Whether to display additional information.
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn Error>> {
+    let Opt {
+        max,
+        name,
+        output,
+        profile,
+        region,
+        verbose,
+    } = Opt::from_args();
+
+    let region = EnvironmentProvider::new()
+        .region()
+        .or_else(|| region.as_ref().map(|region| Region::new(region.clone())))
+        .unwrap_or_else(|| Region::new("us-west-2"));
+
+    if verbose {
+        println!("Kinesis client version: {}", kinesis::PKG_VERSION);
+        println!("Region:                 {:?}", &region);
+        println!("Stream name:            {}", &name);
+        println!("Max records per shard:  {}", max);
+
+        SubscriberBuilder::default()
+            .with_env_filter("info")
+            .with_span_events(FmtSpan::CLOSE)
+            .init();
+    }
+
+    let mut config = Config::builder().region(region);
+    if let Some(profile) = profile {
+        config = config.credentials_provider(ProfileCredentialsProvider::new(profile));
+    }
+    let client = Client::from_conf(config.build());
+
+    let shards = client
+        .list_shards()
+        .stream_name(&name)
+        .send()
+        .await?
+        .shards
+        .unwrap_or_default();
+
+    let mut list = JsonRecords {
+        stream: name.clone(),
+        records: Vec::new(),
+        unsupported: 0,
+        undecodable: 0,
+    };
+
+    for shard in shards {
+        let shard_id = shard.shard_id.unwrap_or_default();
+
+        if verbose {
+            println!("Reading shard {}", &shard_id);
+        }
+
+        read_shard(&client, &name, &shard_id, max, &mut list, verbose).await?;
+    }
+
+    output.print(&list);
+
+    Ok(())
+}
@@ -0,0 +1,509 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0.
+ */
+
+use std::error::Error;
+use std::fmt;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use cloudwatch::model::{Datapoint, Dimension, Statistic};
+use kinesis::model::MetricsName;
+use kinesis::{Client, Config, Region};
+
+use aws_types::region::{EnvironmentProvider, ProvideRegion};
+
+use common::output::OutputFormat;
+use common::profile::ProfileCredentialsProvider;
+use common::shutdown::Shutdown;
+use serde::Serialize;
+use smithy_types::Instant;
+use structopt::StructOpt;
+use tokio::time::sleep;
+use tracing_subscriber::fmt::format::FmtSpan;
+use tracing_subscriber::fmt::SubscriberBuilder;
+
+/// Kinesis publishes its metrics to CloudWatch once a minute.
+const PERIOD: i32 = 60;
+
+/// How far back to look for the latest datapoint. Metrics can arrive a few minutes late.
+const WINDOW: Duration = Duration::from_secs(300);
+
+#[derive(Debug, StructOpt)]
+struct Opt {
+    /// The region
+    #[structopt(short, long)]
+    region: Option<String>,
+
+    /// The name of the stream
+    #[structopt(short, long)]
+    name: String,
+
+    /// How often to refresh, in seconds
+    #[structopt(short, long, default_value = "60")]
+    interval: u64,
+
+    /// How far behind, in seconds, a consumer can be before it's reported as behind
+    #[structopt(short, long, default_value = "60")]
+    threshold: u64,
+
+    /// Whether to enable the shard-level metrics that show the lag of each shard
+    #[structopt(long)]
+    enable_shard_metrics: bool,
+
+    /// Whether to display one sample and exit
+    #[structopt(long)]
+    once: bool,
+
+    /// Whether to print each sample below the last, rather than redraw the screen
+    #[structopt(long)]
+    no_clear: bool,
+
+    /// The format of the output, text or json
+    #[structopt(long, default_value = "text")]
+    output: OutputFormat,
+
+    /// The AWS profile to use from the shared credentials file
+    #[structopt(long)]
+    profile: Option<String>,
+
+    /// Whether to display additional information
+    #[structopt(short, long)]
+    verbose: bool,
+}
+
+/// The lag of one shard.
+struct ShardLag {
+    shard_id: String,
+    incoming: Option<f64>,
+    iterator_age: Option<f64>,
+}
+
+/// One sample of the stream's metrics.
+struct Sample {
+    incoming: Option<f64>,
+    read: Option<f64>,
+    iterator_age: Option<f64>,
+    retention_hours: i32,
+    shards: Vec<ShardLag>,
+    shard_metrics: bool,
+}
+
+/// Returns the latest value of a Kinesis metric for the stream, or for one shard of it,
+/// or `None` if there's no datapoint in the window, which means nothing happened.
+async fn latest(
+    cloudwatch: &cloudwatch::Client,
+    metric: &str,
+    stream: &str,
+    shard_id: Option<&str>,
+    statistic: Statistic,
+) -> Result<Option<f64>, cloudwatch::Error> {
+    let now = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs() as i64;
+
+    let mut request = cloudwatch
+        .get_metric_statistics()
+        .namespace("AWS/Kinesis")
+        .metric_name(metric)
+        .dimensions(
+            Dimension::builder()
+                .name("StreamName")
+                .value(stream)
+                .build(),
+        )
+        .start_time(Instant::from_epoch_seconds(now - WINDOW.as_secs() as i64))
+        .end_time(Instant::from_epoch_seconds(now))
+        .period(PERIOD)
+        .statistics(statistic.clone());
+
+    if let Some(shard_id) = shard_id {
+        request = request.dimensions(Dimension::builder().name("ShardId").value(shard_id).build());
+    }
+
+    let datapoints = request.send().await?.datapoints.unwrap_or_default();
+
+    // The datapoints aren't sorted, so find the newest.
+    let newest = datapoints.into_iter().max_by_key(|datapoint| {
+        datapoint
+            .timestamp
+            .as_ref()
+            .map(|timestamp| timestamp.epoch_seconds())
+    });
+
+    Ok(newest.and_then(|datapoint| value(&datapoint, &statistic)))
+}
+
+/// Returns the value of the statistic from a datapoint.
+fn value(datapoint: &Datapoint, statistic: &Statistic) -> Option<f64> {
+    match statistic {
+        Statistic::Maximum => datapoint.maximum,
+        Statistic::Sum => datapoint.sum,
+        Statistic::Average => datapoint.average,
+        _ => None,
+    }
+}
+
+/// Returns the IDs of the stream's open shards, the only ones that can still receive records.
+async fn open_shards(client: &Client, stream: &str) -> Result<Vec<String>, kinesis::Error> {
+    let mut shard_ids = Vec::new();
+    let mut next_token: Option<String> = None;
+
+    loop {
+        // ListShards rejects a stream name together with a NextToken.
+        let resp = client
+            .list_shards()
+            .set_stream_name(match next_token {
+                None => Some(stream.to_string()),
+                Some(_) => None,
+            })
+            .set_next_token(next_token)
+            .send()
+            .await?;
+
+        for shard in resp.shards.unwrap_or_default() {
+            let closed = shard
+                .sequence_number_range
+                .map(|range| range.ending_sequence_number.is_some())
+                .unwrap_or_default();
+
+            if !closed {
+                shard_ids.extend(shard.shard_id);
+            }
+        }
+
+        next_token = resp.next_token;
+        if next_token.is_none() {
+            return Ok(shard_ids);
+        }
+    }
+}
+
+/// Gets the stream-level metrics, and the shard-level metrics if they're enabled.
+async fn sample(
+    client: &Client,
+    cloudwatch: &cloudwatch::Client,
+    stream: &str,
+) -> Result<Sample, Box<dyn Error>> {
+    let summary = client
+        .describe_stream_summary()
+        .stream_name(stream)
+        .send()
+        .await?
+        .stream_description_summary
+        .ok_or_else(|| format!("no description returned for stream {}", stream))?;
+
+    // Shard-level metrics cost extra, so they're only published if they've been enabled.
+    let shard_metrics = summary
+        .enhanced_monitoring
+        .unwrap_or_default()
+        .into_iter()
+        .flat_map(|metrics| metrics.shard_level_metrics.unwrap_or_default())
+        .any(|metric| metric == MetricsName::IteratorAgeMilliseconds || metric == MetricsName::All);
+
+    let mut shards = Vec::new();
+    if shard_metrics {
+        for shard_id in open_shards(client, stream).await? {
+            shards.push(ShardLag {
+                incoming: latest(
+                    cloudwatch,
+                    "IncomingRecords",
+                    stream,
+                    Some(&shard_id),
+                    Statistic::Sum,
+                )
+                .await?,
+                iterator_age: latest(
+                    cloudwatch,
+                    "IteratorAgeMilliseconds",
+                    stream,
+                    Some(&shard_id),
+                    Statistic::Maximum,
+                )
+                .await?,
+                shard_id,
+            });
+        }
+    }
+
+    Ok(Sample {
+        incoming: latest(cloudwatch, "IncomingRecords", stream, None, Statistic::Sum).await?,
+        read: latest(
+            cloudwatch,
+            "GetRecords.Records",
+            stream,
+            None,
+            Statistic::Sum,
+        )
+        .await?,
+        iterator_age: latest(
+            cloudwatch,
+            "GetRecords.IteratorAgeMilliseconds",
+            stream,
+            None,
+            Statistic::Maximum,
+        )
+        .await?,
+        retention_hours: summary.retention_period_hours.unwrap_or(24),
+        shards,
+        shard_metrics,
+    })
+}
+
+/// Describes a lag. A consumer that falls behind by more than half of the retention period
+/// is at risk of losing records, which expire before it reads them.
+fn lag_status(
+    iterator_age: Option<f64>,
+    threshold: Duration,
+    retention_hours: i32,
+) -> &'static str {
+    match iterator_age {
+        None => "NO READS",
+        Some(age) if age > f64::from(retention_hours) * 3_600_000.0 / 2.0 => "AT RISK",
+        Some(age) if age > threshold.as_millis() as f64 => "BEHIND",
+        Some(_) => "OK",
+    }
+}
+
+/// Formats a count, or - if there's no datapoint.
+fn count(value: Option<f64>) -> String {
+    value
+        .map(|value| format!("{:.0}", value))
+        .unwrap_or_else(|| "-".to_string())
+}
+
+/// Formats an iterator age in seconds, or - if there's no datapoint.
+fn age(value: Option<f64>) -> String {
+    value
+        .map(|value| format!("{:.1} s", value / 1000.0))
+        .unwrap_or_else(|| "-".to_string())
+}
+
+/// The lag of one shard, as it's displayed.
+#[derive(Debug, Serialize)]
+struct ShardReport {
+    shard_id: String,
+    records_written: Option<f64>,
+    iterator_age_ms: Option<f64>,
+    status: &'static str,
+}
+
+/// A sample, as it's displayed. The shards are only included if shard-level metrics are enabled.
+#[derive(Debug, Serialize)]
+struct Report {
+    stream: String,
+    records_written: Option<f64>,
+    records_read: Option<f64>,
+    iterator_age_ms: Option<f64>,
+    status: &'static str,
+    shards: Option<Vec<ShardReport>>,
+}
+
+impl Report {
+    fn new(stream: &str, sample: Sample, threshold: Duration) -> Self {
+        let retention_hours = sample.retention_hours;
+        let shards = if sample.shard_metrics {
+            Some(
+                sample
+                    .shards
+                    .into_iter()
+                    .map(|shard| ShardReport {
+                        status: lag_status(shard.iterator_age, threshold, retention_hours),
+                        shard_id: shard.shard_id,
+                        records_written: shard.incoming,
+                        iterator_age_ms: shard.iterator_age,
+                    })
+                    .collect(),
+            )
+        } else {
+            None
+        };
+
+        Report {
+            stream: stream.to_string(),
+            records_written: sample.incoming,
+            records_read: sample.read,
+            iterator_age_ms: sample.iterator_age,
+            status: lag_status(sample.iterator_age, threshold, retention_hours),
+            shards,
+        }
+    }
+}
+
+impl fmt::Display for Report {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(
+            f,
+            "  Records written in the last minute: {}",
+            count(self.records_written)
+        )?;
+        writeln!(
+            f,
+            "  Records read in the last minute:    {}",
+            count(self.records_read)
+        )?;
+        writeln!(
+            f,
+            "  Iterator age, oldest consumer:      {} {}",
+            age(self.iterator_age_ms),
+            self.status
+        )?;
+        writeln!(f)?;
+
+        match &self.shards {
+            Some(shards) => {
+                write!(
+                    f,
+                    "  {:<24} {:>10} {:>14}  Status",
+                    "Shard", "Written", "Iterator age"
+                )?;
+                for shard in shards {
+                    write!(
+                        f,
+                        "\n  {:<24} {:>10} {:>14}  {}",
+                        shard.shard_id,
+                        count(shard.records_written),
+                        age(shard.iterator_age_ms),
+                        shard.status
+                    )?;
+                }
+                Ok(())
+            }
+            None => write!(
+                f,
+                "  Shard-level metrics aren't enabled, so the lag of each shard isn't available.\n  \
+                 Run again with --enable-shard-metrics to enable them. They cost extra."
+            ),
+        }
+    }
+}
+
+/// Prints the dashboard for a sample, or in JSON, the sample on one line.
+fn render(report: &Report, opt: &Opt) {
+    if opt.output.is_json() {
+        opt.output.print_line(report);
+        return;
+    }
+
+    if !opt.no_clear {
+        // Clears the terminal and moves the cursor to the top left.
+        print!("\x1b[2J\x1b[H");
+    }
+
+    println!("Lag of the consumers of stream {}", report.stream);
+    if !opt.once {
+        println!(
+            "Refreshes every {} seconds. Press Ctrl-C to stop.",
+            opt.interval
+        );
+    }
+    println!();
+    println!("{}", report);
+    println!();
+}
+
+/// Monitors how far the consumers of a Kinesis data stream are behind the latest record.
+///
+/// The iterator age is how old the last record that a consumer read was when it read it.
+/// A growing iterator age means that the consumers can't keep up with the producers.
+/// The values come from the CloudWatch metrics that Kinesis publishes once a minute,
+/// so they're up to a few minutes old, and the stream-level value is the maximum over all consumers.
+/// # Arguments
+///
+/// * `-n NAME` - The name of the stream.
+/// * `[-i INTERVAL]` - How often to refresh, in seconds. Defaults to 60.
+/// * `[-t THRESHOLD]` - How far behind, in seconds, a consumer can be before it's reported as **BEHIND**.
+///   Defaults to 60.
+/// * `[--enable-shard-metrics]` - Whether to enable the shard-level metrics,
+///   which show the lag of each shard and cost extra.
+/// * `[--once]` - Whether to display one sample and exit.
+/// * `[--no-clear]` - Whether to print each sample below the last, rather than redraw the screen.
+/// * `[--output FORMAT]` - How to display each sample, **text** (the default) or **json**,
+///   which displays each sample as a JSON object on its own line.
+/// * `[-r REGION]` - The region in which the client is created.
+///    If not supplied, uses the value of the **AWS_DEFAULT_REGION** environment variable.
+///    If the environment variable is not set, defaults to **us-west-2**.
+/// * `[--profile PROFILE]` - The AWS profile to use from the shared credentials file.
+/// * `[-v]` - Whether to display additional information.
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn Error>> {
+    let opt = Opt::from_args();
+
+    if opt.interval == 0 {
+        return Err("the interval must be at least one second".into());
+    }
+
+    let region = EnvironmentProvider::new()
+        .region()
+        .or_else(|| {
+            opt.region
+                .as_ref()
+                .map(|region| Region::new(region.clone()))
+        })
+        .unwrap_or_else(|| Region::new("us-west-2"));
+
+    if opt.verbose {
+        println!("Kinesis client version:    {}", kinesis::PKG_VERSION);
+        println!("CloudWatch client version: {}\n", cloudwatch::PKG_VERSION);
+        println!("Region:      {:?}", &region);
+        println!("Stream name: {}", opt.name);
+        println!("Interval:    {}", opt.interval);
+        println!("Threshold:   {}", opt.threshold);
+
+        SubscriberBuilder::default()
+            .with_env_filter("info")
+            .with_span_events(FmtSpan::CLOSE)
+            .init();
+    }
+
+    let mut config = Config::builder().region(region.clone());
+    let mut cloudwatch_config = cloudwatch::Config::builder().region(region);
+    if let Some(profile) = &opt.profile {
+        let provider = ProfileCredentialsProvider::new(profile);
+        config = config.credentials_provider(provider.clone());
+        cloudwatch_config = cloudwatch_config.credentials_provider(provider);
+    }
+
+    let client = Client::from_conf(config.build());
+    let cloudwatch = cloudwatch::Client::from_conf(cloudwatch_config.build());
+
+    if opt.enable_shard_metrics {
+        client
+            .enable_enhanced_monitoring()
+            .stream_name(&opt.name)
+            .shard_level_metrics(MetricsName::IncomingRecords)
+            .shard_level_metrics(MetricsName::IteratorAgeMilliseconds)
+            .send()
+            .await?;
+
+        if !opt.output.is_json() {
+            println!("Enabled shard-level metrics. The first values appear within a few minutes.");
+        }
+    }
+
+    // Ctrl-C is how the monitor normally stops, so it ends the loop rather than failing.
+    let shutdown = Shutdown::listen();
+    let interval = Duration::from_secs(opt.interval);
+    let threshold = Duration::from_secs(opt.threshold);
+
+    loop {
+        let sample = tokio::select! {
+            sample = sample(&client, &cloudwatch, &opt.name) => sample?,
+            _ = shutdown.requested() => break,
+        };
+
+        render(&Report::new(&opt.name, sample, threshold), &opt);
+
+        if opt.once {
+            break;
+        }
+
+        tokio::select! {
+            _ = sleep(interval) => {}
+            _ = shutdown.requested() => break,
+        }
+    }
+
+    Ok(())
+}
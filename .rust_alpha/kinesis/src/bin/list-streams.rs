@@ -3,12 +3,15 @@
  * SPDX-License-Identifier: Apache-2.0.
  */
 
-use std::process;
+use std::fmt;
 
-use kinesis::{Client, Config, Region};
+use kinesis::{Client, Config, Error, Region};
 
 use aws_types::region::{EnvironmentProvider, ProvideRegion};
 
+use common::output::OutputFormat;
+use common::profile::ProfileCredentialsProvider;
+use serde::Serialize;
 use structopt::StructOpt;
 use tracing_subscriber::fmt::format::FmtSpan;
 use tracing_subscriber::fmt::SubscriberBuilder;
@@ -19,14 +22,54 @@ struct Opt {
     #[structopt(short, long)]
     region: Option<String>,
 
+    /// The format of the output, text or json
+    #[structopt(long, default_value = "text")]
+    output: OutputFormat,
+
+    /// The AWS profile to use from the shared credentials file
+    #[structopt(long)]
+    profile: Option<String>,
+
     /// Whether to display additional information
     #[structopt(short, long)]
     verbose: bool,
 }
 
+/// The names of your streams.
+#[derive(Debug, Serialize)]
+struct StreamList {
+    streams: Vec<String>,
+}
+
+impl fmt::Display for StreamList {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "Stream names:")?;
+
+        for stream in &self.streams {
+            writeln!(f, "  {}", stream)?;
+        }
+
+        write!(f, "Found {} stream(s)", self.streams.len())
+    }
+}
+
+/// Lists your Kinesis data streams.
+/// # Arguments
+///
+/// * `[-r REGION]` - The region in which the client is created.
+///    If not supplied, uses the value of the **AWS_DEFAULT_REGION** environment variable.
+///    If the environment variable is not set, defaults to **us-west-2**.
+/// * `[--output FORMAT]` - How to display the result, **text** (the default) or **json**.
+/// * `[--profile PROFILE]` - The AWS profile to use from the shared credentials file.
+/// * `[-v]` - Whether to display additional information.
 #[tokio::main]
-async fn main() {
-    let Opt { region, verbose } = Opt::from_args();
+async fn main() -> Result<(), Error> {
+    let Opt {
+        output,
+        profile,
+        region,
+        verbose,
+    } = Opt::from_args();
 
     let region = EnvironmentProvider::new()
         .region()
@@ -43,25 +86,18 @@ async fn main() {
             .init();
     }
 
-    let config = Config::builder().region(region).build();
+    let mut config = Config::builder().region(region);
+    if let Some(profile) = profile {
+        config = config.credentials_provider(ProfileCredentialsProvider::new(profile));
+    }
 
-    let client = Client::from_conf(config);
+    let client = Client::from_conf(config.build());
 
-    match client.list_streams().send().await {
-        Ok(resp) => {
-            println!("Stream names:");
+    let resp = client.list_streams().send().await?;
 
-            let streams = resp.stream_names.unwrap_or_default();
-            for stream in &streams {
-                println!("  {}", stream);
-            }
+    let streams = resp.stream_names.unwrap_or_default();
 
-            println!("Found {} stream(s)", streams.len());
-        }
-        Err(e) => {
-            println!("Got an error listing stream names:");
-            println!("{}", e);
-            process::exit(1);
-        }
-    };
+    output.print(&StreamList { streams });
+
+    Ok(())
 }
@@ -0,0 +1,239 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0.
+ */
+
+use std::error::Error;
+use std::fmt;
+use std::fs::File;
+use std::io::{self, BufRead, BufReader};
+
+use kinesis::{Blob, Client, Config, Region};
+use kinesis_code_examples::records::{encode, SCHEMA_VERSION};
+
+use aws_types::region::{EnvironmentProvider, ProvideRegion};
+
+use common::output::OutputFormat;
+use common::profile::ProfileCredentialsProvider;
+use serde::Serialize;
+use serde_json::Value;
+use structopt::StructOpt;
+use tracing_subscriber::fmt::format::FmtSpan;
+use tracing_subscriber::fmt::SubscriberBuilder;
+
+#[derive(Debug, StructOpt)]
+struct Opt {
+    /// The region
+    #[structopt(short, long)]
+    region: Option<String>,
+
+    /// The name of the stream
+    #[structopt(short, long)]
+    name: String,
+
+    /// The file of newline-delimited JSON to put on the stream. Defaults to stdin
+    #[structopt(short, long)]
+    file: Option<String>,
+
+    /// The field of each JSON object to use as the partition key. Defaults to the line number
+    #[structopt(short, long)]
+    key_field: Option<String>,
+
+    /// Whether to compress each record with gzip
+    #[structopt(short, long)]
+    gzip: bool,
+
+    /// The schema version to tag each record with
+    #[structopt(short, long, default_value = "2")]
+    schema_version: u32,
+
+    /// The format of the output, text or json
+    #[structopt(long, default_value = "text")]
+    output: OutputFormat,
+
+    /// The AWS profile to use from the shared credentials file
+    #[structopt(long)]
+    profile: Option<String>,
+
+    /// Whether to display additional information
+    #[structopt(short, long)]
+    verbose: bool,
+}
+
+/// How many records were put, and how much compression saved.
+#[derive(Debug, Serialize)]
+struct PutJsonRecords {
+    stream: String,
+    records: usize,
+    schema_version: u32,
+    compressed: bool,
+    /// The size of the payloads, as JSON.
+    json_bytes: usize,
+    /// The size of the records that were put, after adding the envelope and compressing.
+    record_bytes: usize,
+}
+
+impl fmt::Display for PutJsonRecords {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "Put {} records with schema version {} on stream {}",
+            self.records, self.schema_version, self.stream
+        )?;
+
+        if self.compressed && self.record_bytes > 0 {
+            write!(
+                f,
+                "\nCompressed {} bytes of JSON into {} bytes, {:.1} times smaller",
+                self.json_bytes,
+                self.record_bytes,
+                self.json_bytes as f64 / self.record_bytes as f64
+            )?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Returns the partition key of a record: the value of the key field, if one is set,
+/// or else the line number.
+fn partition_key(
+    payload: &Value,
+    key_field: &Option<String>,
+    line: usize,
+) -> Result<String, String> {
+    let field = match key_field {
+        Some(field) => field,
+        None => return Ok(line.to_string()),
+    };
+
+    match payload.get(field) {
+        Some(Value::String(s)) if !s.is_empty() => Ok(s.clone()),
+        Some(Value::Number(n)) => Ok(n.to_string()),
+        _ => Err(format!(
+            "line {} has no string or number field {}",
+            line, field
+        )),
+    }
+}
+
+/// Puts JSON payloads on an Amazon Kinesis data stream, one record per line of the input.
+/// Each payload is wrapped in an envelope that records its schema version, and can be compressed with gzip.
+/// Use `get-json-records` to read the records back.
+/// # Arguments
+///
+/// * `-n NAME` - The name of the stream.
+/// * `[-f FILE]` - The file of newline-delimited JSON to put on the stream.
+///   If not supplied, reads the payloads from stdin.
+/// * `[-k KEY-FIELD]` - The field of each JSON object to use as the partition key.
+///   If not supplied, uses the line number.
+/// * `[-g]` - Whether to compress each record with gzip.
+/// * `[-s SCHEMA-VERSION]` - The schema version to tag each record with.
+///   If not supplied, defaults to **2**.
+/// * `[-r REGION]` - The region in which the client is created.
+///    If not supplied, uses the value of the **AWS_DEFAULT_REGION** environment variable.
+///    If the environment variable is not set, defaults to **us-west-2**.
+/// * `[--output FORMAT]` - How to display the result, **text** (the default) or **json**.
+/// * `[--profile PROFILE]` - The AWS profile to use from the shared credentials file.
+/// * `[-v]` - Whether to display additional information.
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn Error>> {
+    let Opt {
+        file,
+        gzip,
+        key_field,
+        name,
+        output,
+        profile,
+        region,
+        schema_version,
+        verbose,
+    } = Opt::from_args();
+
+    let region = EnvironmentProvider::new()
+        .region()
+        .or_else(|| region.as_ref().map(|region| Region::new(region.clone())))
+        .unwrap_or_else(|| Region::new("us-west-2"));
+
+    if verbose {
+        println!("Kinesis client version: {}", kinesis::PKG_VERSION);
+        println!("Region:                 {:?}", &region);
+        println!("Stream name:            {}", &name);
+        println!("File:                   {:?}", &file);
+        println!("Key field:              {:?}", &key_field);
+        println!("Compress:               {}", gzip);
+        println!(
+            "Schema version:         {} (latest is {})",
+            schema_version, SCHEMA_VERSION
+        );
+
+        SubscriberBuilder::default()
+            .with_env_filter("info")
+            .with_span_events(FmtSpan::CLOSE)
+            .init();
+    }
+
+    let mut config = Config::builder().region(region);
+    if let Some(profile) = profile {
+        config = config.credentials_provider(ProfileCredentialsProvider::new(profile));
+    }
+    let client = Client::from_conf(config.build());
+
+    let reader: Box<dyn BufRead> = match &file {
+        Some(file) => Box::new(BufReader::new(
+            File::open(file).map_err(|e| format!("couldn't open {}: {}", file, e))?,
+        )),
+        None => Box::new(BufReader::new(io::stdin())),
+    };
+
+    let mut records = 0;
+    let mut json_bytes = 0;
+    let mut record_bytes = 0;
+
+    // Each line is numbered from 1, so that the number matches what an editor shows.
+    for (index, line) in reader.lines().enumerate() {
+        let line = line?;
+        let number = index + 1;
+
+        if line.trim().is_empty() {
+            continue;
+        }
+
+        let payload: Value = serde_json::from_str(&line)
+            .map_err(|e| format!("line {} is not JSON: {}", number, e))?;
+        let key = partition_key(&payload, &key_field, number)?;
+        let data = encode(&payload, schema_version, gzip)?;
+
+        json_bytes += line.len();
+        record_bytes += data.len();
+
+        let resp = client
+            .put_record()
+            .stream_name(&name)
+            .partition_key(key)
+            .data(Blob::new(data))
+            .send()
+            .await?;
+        records += 1;
+
+        if verbose {
+            println!(
+                "Put line {} in {} ({})",
+                number,
+                resp.shard_id.as_deref().unwrap_or_default(),
+                resp.sequence_number.as_deref().unwrap_or_default()
+            );
+        }
+    }
+
+    output.print(&PutJsonRecords {
+        stream: name,
+        records,
+        schema_version,
+        compressed: gzip,
+        json_bytes,
+        record_bytes,
+    });
+
+    Ok(())
+}
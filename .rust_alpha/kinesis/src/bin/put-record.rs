@@ -3,12 +3,15 @@
  * SPDX-License-Identifier: Apache-2.0.
  */
 
-use std::process;
+use std::fmt;
 
-use kinesis::{Client, Config, Region};
+use kinesis::{Client, Config, Error, Region};
 
 use aws_types::region::{EnvironmentProvider, ProvideRegion};
 
+use common::output::OutputFormat;
+use common::profile::ProfileCredentialsProvider;
+use serde::Serialize;
 use structopt::StructOpt;
 use tracing_subscriber::fmt::format::FmtSpan;
 use tracing_subscriber::fmt::SubscriberBuilder;
@@ -19,26 +22,72 @@ struct Opt {
     #[structopt(short, long)]
     region: Option<String>,
 
+    /// The data to add to the stream
     #[structopt(short, long)]
     data: String,
 
+    /// The partition key
     #[structopt(short, long)]
     key: String,
 
+    /// The name of the stream
     #[structopt(short, long)]
     name: String,
 
+    /// The format of the output, text or json
+    #[structopt(long, default_value = "text")]
+    output: OutputFormat,
+
+    /// The AWS profile to use from the shared credentials file
+    #[structopt(long)]
+    profile: Option<String>,
+
+    /// Whether to display additional information
     #[structopt(short, long)]
     verbose: bool,
 }
 
+/// Where the record was stored.
+#[derive(Debug, Serialize)]
+struct PutRecord {
+    stream: String,
+    shard_id: Option<String>,
+    sequence_number: Option<String>,
+}
+
+impl fmt::Display for PutRecord {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "Put data into stream {} (shard {}, sequence number {})",
+            self.stream,
+            self.shard_id.as_deref().unwrap_or("unknown"),
+            self.sequence_number.as_deref().unwrap_or("unknown")
+        )
+    }
+}
+
+/// Adds a record to a Kinesis data stream.
+/// # Arguments
+///
+/// * `-d DATA` - The data to add.
+/// * `-k KEY` - The partition key of the record.
+/// * `-n NAME` - The name of the stream.
+/// * `[-r REGION]` - The region in which the client is created.
+///    If not supplied, uses the value of the **AWS_DEFAULT_REGION** environment variable.
+///    If the environment variable is not set, defaults to **us-west-2**.
+/// * `[--output FORMAT]` - How to display the result, **text** (the default) or **json**.
+/// * `[--profile PROFILE]` - The AWS profile to use from the shared credentials file.
+/// * `[-v]` - Whether to display additional information.
 #[tokio::main]
-async fn main() {
+async fn main() -> Result<(), Error> {
     let Opt {
         data,
         key,
         name,
+        profile,
         region,
+        output,
         verbose,
     } = Opt::from_args();
 
@@ -61,25 +110,28 @@ async fn main() {
             .init();
     }
 
-    let config = Config::builder().region(region).build();
+    let mut config = Config::builder().region(region);
+    if let Some(profile) = profile {
+        config = config.credentials_provider(ProfileCredentialsProvider::new(profile));
+    }
 
-    let client = Client::from_conf(config);
+    let client = Client::from_conf(config.build());
 
     let blob = kinesis::Blob::new(data);
 
-    match client
+    let resp = client
         .put_record()
         .data(blob)
         .partition_key(key)
-        .stream_name(name)
+        .stream_name(&name)
         .send()
-        .await
-    {
-        Ok(_) => println!("Put data into stream."),
-        Err(e) => {
-            println!("Got an error putting record:");
-            println!("{}", e);
-            process::exit(1);
-        }
-    };
+        .await?;
+
+    output.print(&PutRecord {
+        stream: name,
+        shard_id: resp.shard_id,
+        sequence_number: resp.sequence_number,
+    });
+
+    Ok(())
 }
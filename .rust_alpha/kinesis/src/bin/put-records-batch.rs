@@ -0,0 +1,328 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0.
+ */
+
+use std::error::Error;
+use std::fmt;
+use std::fs::File;
+use std::io::{self, BufRead, BufReader};
+use std::time::{Duration, Instant};
+
+use kinesis::model::PutRecordsRequestEntry;
+use kinesis::{Blob, Client, Config, Region};
+
+use aws_types::region::{EnvironmentProvider, ProvideRegion};
+
+use common::output::OutputFormat;
+use common::profile::ProfileCredentialsProvider;
+use serde::Serialize;
+use serde_json::Value;
+use structopt::StructOpt;
+use tokio::time::sleep;
+use tracing_subscriber::fmt::format::FmtSpan;
+use tracing_subscriber::fmt::SubscriberBuilder;
+
+/// The most records that PutRecords accepts in one call.
+const MAX_BATCH_RECORDS: usize = 500;
+
+/// The most bytes that PutRecords accepts in one call, counting the data and partition keys.
+const MAX_BATCH_BYTES: usize = 5 * 1024 * 1024;
+
+/// The most bytes in one record, counting the data and partition key.
+const MAX_RECORD_BYTES: usize = 1024 * 1024;
+
+/// How many times to resend the records that Kinesis didn't put.
+const MAX_RETRIES: u32 = 5;
+
+/// How long to wait before the first retry. The delay doubles after each retry.
+const INITIAL_DELAY: Duration = Duration::from_millis(100);
+
+#[derive(Debug, StructOpt)]
+struct Opt {
+    /// The region
+    #[structopt(short, long)]
+    region: Option<String>,
+
+    /// The name of the stream
+    #[structopt(short, long)]
+    name: String,
+
+    /// The file of newline-delimited JSON to put on the stream. Defaults to stdin
+    #[structopt(short, long)]
+    file: Option<String>,
+
+    /// The field of each JSON object to use as the partition key. Defaults to the line number
+    #[structopt(short, long)]
+    key_field: Option<String>,
+
+    /// The format of the output, text or json
+    #[structopt(long, default_value = "text")]
+    output: OutputFormat,
+
+    /// The AWS profile to use from the shared credentials file
+    #[structopt(long)]
+    profile: Option<String>,
+
+    /// Whether to display additional information
+    #[structopt(short, long)]
+    verbose: bool,
+}
+
+/// How many records were put, and how fast.
+#[derive(Debug, Serialize)]
+struct PutRecords {
+    stream: String,
+    records: usize,
+    bytes: usize,
+    batches: usize,
+    /// How many records were resent after Kinesis reported that it hadn't put them.
+    retried_records: usize,
+    seconds: f64,
+    records_per_second: f64,
+    megabytes_per_second: f64,
+}
+
+impl fmt::Display for PutRecords {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(
+            f,
+            "Put {} records ({} bytes) on stream {} in {} batches",
+            self.records, self.bytes, self.stream, self.batches
+        )?;
+        writeln!(f, "Resent {} failed records", self.retried_records)?;
+        write!(
+            f,
+            "Took {:.1} seconds: {:.0} records/second, {:.2} MB/second",
+            self.seconds, self.records_per_second, self.megabytes_per_second
+        )
+    }
+}
+
+/// A batch of records waiting to be put.
+#[derive(Default)]
+struct Batch {
+    entries: Vec<PutRecordsRequestEntry>,
+    bytes: usize,
+}
+
+/// Returns the partition key of a record: the value of the key field, if one is set,
+/// or else the line number. Kinesis hashes partition keys with MD5 to choose a shard,
+/// so consecutive line numbers spread the records evenly across the shards.
+fn partition_key(
+    record: &Value,
+    key_field: &Option<String>,
+    line: usize,
+) -> Result<String, String> {
+    let field = match key_field {
+        Some(field) => field,
+        None => return Ok(line.to_string()),
+    };
+
+    match record.get(field) {
+        Some(Value::String(s)) if !s.is_empty() => Ok(s.clone()),
+        Some(Value::Number(n)) => Ok(n.to_string()),
+        _ => Err(format!(
+            "line {} has no string or number field {}",
+            line, field
+        )),
+    }
+}
+
+/// Puts a batch of records, resending only the records that Kinesis reports as failed,
+/// such as when a shard's throughput is exceeded, with exponential backoff between the attempts.
+/// Returns how many records were resent.
+async fn put_batch(
+    client: &Client,
+    stream: &str,
+    mut entries: Vec<PutRecordsRequestEntry>,
+) -> Result<usize, Box<dyn Error>> {
+    let mut retried = 0;
+    let mut delay = INITIAL_DELAY;
+
+    for attempt in 0..=MAX_RETRIES {
+        if attempt > 0 {
+            retried += entries.len();
+            sleep(delay).await;
+            delay *= 2;
+        }
+
+        let resp = client
+            .put_records()
+            .stream_name(stream)
+            .set_records(Some(entries.clone()))
+            .send()
+            .await?;
+
+        // The results are in the same order as the records, and a failed record has an error code.
+        let mut failed = Vec::new();
+        let mut last_error = None;
+
+        for (entry, result) in entries.into_iter().zip(resp.records.unwrap_or_default()) {
+            if let Some(code) = result.error_code {
+                last_error = Some(format!(
+                    "{}: {}",
+                    code,
+                    result.error_message.unwrap_or_default()
+                ));
+                failed.push(entry);
+            }
+        }
+
+        if failed.is_empty() {
+            return Ok(retried);
+        }
+
+        if attempt == MAX_RETRIES {
+            return Err(format!(
+                "{} records still failed after {} retries, the last with {}",
+                failed.len(),
+                MAX_RETRIES,
+                last_error.unwrap_or_default()
+            )
+            .into());
+        }
+
+        entries = failed;
+    }
+
+    unreachable!("the last attempt returns")
+}
+
+/// Puts the lines of a file of newline-delimited JSON on an Amazon Kinesis data stream, one record per line,
+/// with PutRecords, in batches of up to 500 records or 5 MB.
+/// Kinesis can put some of the records in a batch and not others, so only the records that failed are resent.
+/// Displays how many records were put, and how fast.
+/// # Arguments
+///
+/// * `-n NAME` - The name of the stream.
+/// * `[-f FILE]` - The file to put on the stream, with one JSON value on each line.
+///   If not supplied, reads the records from stdin.
+/// * `[-k KEY-FIELD]` - The field of each JSON object to use as the partition key.
+///   If not supplied, uses the line number, which spreads the records evenly across the shards.
+/// * `[-r REGION]` - The region in which the client is created.
+///    If not supplied, uses the value of the **AWS_DEFAULT_REGION** environment variable.
+///    If the environment variable is not set, defaults to **us-west-2**.
+/// * `[--output FORMAT]` - How to display the result, **text** (the default) or **json**.
+/// * `[--profile PROFILE]` - The AWS profile to use from the shared credentials file.
+/// * `[-v]` - Whether to display additional information.
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn Error>> {
+    let Opt {
+        file,
+        key_field,
+        name,
+        output,
+        profile,
+        region,
+        verbose,
+    } = Opt::from_args();
+
+    let region = EnvironmentProvider::new()
+        .region()
+        .or_else(|| region.as_ref().map(|region| Region::new(region.clone())))
+        .unwrap_or_else(|| Region::new("us-west-2"));
+
+    if verbose {
+        println!("Kinesis client version: {}", kinesis::PKG_VERSION);
+        println!("Region:                 {:?}", &region);
+        println!("Stream name:            {}", &name);
+        println!("File:                   {:?}", &file);
+        println!("Key field:              {:?}", &key_field);
+
+        SubscriberBuilder::default()
+            .with_env_filter("info")
+            .with_span_events(FmtSpan::CLOSE)
+            .init();
+    }
+
+    let mut config = Config::builder().region(region);
+    if let Some(profile) = profile {
+        config = config.credentials_provider(ProfileCredentialsProvider::new(profile));
+    }
+    let client = Client::from_conf(config.build());
+
+    let reader: Box<dyn BufRead> = match &file {
+        Some(file) => Box::new(BufReader::new(
+            File::open(file).map_err(|e| format!("couldn't open {}: {}", file, e))?,
+        )),
+        None => Box::new(BufReader::new(io::stdin())),
+    };
+
+    let start = Instant::now();
+    let mut records = 0;
+    let mut bytes = 0;
+    let mut batches = 0;
+    let mut retried_records = 0;
+    let mut batch = Batch::default();
+
+    // Each line is numbered from 1, so that the number matches what an editor shows.
+    for (index, line) in reader.lines().enumerate() {
+        let line = line?;
+        let number = index + 1;
+
+        if line.trim().is_empty() {
+            continue;
+        }
+
+        let record: Value = serde_json::from_str(&line)
+            .map_err(|e| format!("line {} is not JSON: {}", number, e))?;
+        let key = partition_key(&record, &key_field, number)?;
+        let size = line.len() + key.len();
+
+        if size > MAX_RECORD_BYTES {
+            return Err(format!(
+                "line {} is {} bytes, more than a record can hold",
+                number, size
+            )
+            .into());
+        }
+
+        if batch.entries.len() == MAX_BATCH_RECORDS || batch.bytes + size > MAX_BATCH_BYTES {
+            let full = std::mem::take(&mut batch);
+            retried_records += put_batch(&client, &name, full.entries).await?;
+            batches += 1;
+
+            if verbose {
+                println!("Put {} records", records);
+            }
+        }
+
+        batch.entries.push(
+            PutRecordsRequestEntry::builder()
+                .data(Blob::new(line))
+                .partition_key(key)
+                .build(),
+        );
+        batch.bytes += size;
+        records += 1;
+        bytes += size;
+    }
+
+    if !batch.entries.is_empty() {
+        retried_records += put_batch(&client, &name, batch.entries).await?;
+        batches += 1;
+    }
+
+    let seconds = start.elapsed().as_secs_f64();
+    let per_second = |count: usize| {
+        if seconds > 0.0 {
+            count as f64 / seconds
+        } else {
+            0.0
+        }
+    };
+
+    output.print(&PutRecords {
+        stream: name,
+        records,
+        bytes,
+        batches,
+        retried_records,
+        seconds,
+        records_per_second: per_second(records),
+        megabytes_per_second: per_second(bytes) / 1_000_000.0,
+    });
+
+    Ok(())
+}
@@ -0,0 +1,323 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0.
+ */
+
+use std::error::Error;
+use std::fmt;
+use std::time::Duration;
+
+use kinesis::model::ShardIteratorType;
+use kinesis::{Blob, Client, Config, Region};
+use kinesis_code_examples::waiters::{status_name, wait_for_stream_active};
+
+use aws_types::region::{EnvironmentProvider, ProvideRegion};
+
+use common::output::OutputFormat;
+use common::profile::ProfileCredentialsProvider;
+use common::shutdown::Shutdown;
+use serde::Serialize;
+use structopt::StructOpt;
+use tokio::time::sleep;
+use tracing_subscriber::fmt::format::FmtSpan;
+use tracing_subscriber::fmt::SubscriberBuilder;
+
+/// How long to wait for the new stream to become active.
+const ACTIVE_TIMEOUT: Duration = Duration::from_secs(300);
+
+/// How many times to call GetRecords before giving up on reading all of the records.
+/// A new shard iterator can return no records for the first few calls, even when the shard has records.
+const MAX_READS: usize = 10;
+
+#[derive(Debug, StructOpt)]
+struct Opt {
+    /// The region
+    #[structopt(short, long)]
+    region: Option<String>,
+
+    /// The name of the stream to create
+    #[structopt(short, long, default_value = "rust-scenario-stream")]
+    name: String,
+
+    /// How many records to put on the stream
+    #[structopt(short, long, default_value = "10")]
+    count: usize,
+
+    /// Whether to keep the stream
+    #[structopt(short, long)]
+    keep: bool,
+
+    /// The format of the output, text or json
+    #[structopt(long, default_value = "text")]
+    output: OutputFormat,
+
+    /// The AWS profile to use from the shared credentials file
+    #[structopt(long)]
+    profile: Option<String>,
+
+    /// Whether to display additional information
+    #[structopt(short, long)]
+    verbose: bool,
+}
+
+/// The resources that the scenario has created, so that they can be deleted even if a later step fails.
+#[derive(Default)]
+struct Resources {
+    stream: bool,
+}
+
+/// A record that the scenario read back, with its data as text.
+#[derive(Debug, Serialize)]
+struct Record {
+    sequence_number: String,
+    data: String,
+}
+
+/// What the scenario found.
+#[derive(Debug, Serialize)]
+struct Summary {
+    stream: String,
+    shards: Vec<String>,
+    records: Vec<Record>,
+    kept: bool,
+}
+
+impl fmt::Display for Summary {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "Read {} records from the {} shards of {}",
+            self.records.len(),
+            self.shards.len(),
+            self.stream
+        )?;
+
+        if self.kept {
+            write!(f, "; kept the stream")
+        } else {
+            write!(f, "; deleted the stream")
+        }
+    }
+}
+
+/// Displays a step of the scenario, unless the output is JSON, which is only the summary.
+fn step(opt: &Opt, message: impl fmt::Display) {
+    if !opt.output.is_json() {
+        println!("{}", message);
+    }
+}
+
+/// Reads the records in the shard from the beginning, until `count` records are read,
+/// or GetRecords has been called `MAX_READS` times.
+async fn read_records(
+    client: &Client,
+    opt: &Opt,
+    shard_id: &str,
+) -> Result<Vec<Record>, kinesis::Error> {
+    let mut iterator = client
+        .get_shard_iterator()
+        .stream_name(&opt.name)
+        .shard_id(shard_id)
+        .shard_iterator_type(ShardIteratorType::TrimHorizon)
+        .send()
+        .await?
+        .shard_iterator;
+
+    let mut records = Vec::new();
+
+    for _ in 0..MAX_READS {
+        if records.len() >= opt.count {
+            break;
+        }
+
+        let current = match iterator {
+            Some(current) => current,
+            None => break,
+        };
+
+        let resp = client.get_records().shard_iterator(current).send().await?;
+
+        for record in resp.records.unwrap_or_default() {
+            let data = record
+                .data
+                .map(|data| data.into_inner())
+                .unwrap_or_default();
+            let record = Record {
+                sequence_number: record.sequence_number.unwrap_or_default(),
+                data: String::from_utf8_lossy(&data).into_owned(),
+            };
+            step(
+                opt,
+                format!("  {}: {}", record.sequence_number, record.data),
+            );
+            records.push(record);
+        }
+
+        iterator = resp.next_shard_iterator;
+
+        // Each shard supports up to five GetRecords calls a second.
+        sleep(Duration::from_secs(1)).await;
+    }
+
+    Ok(records)
+}
+
+/// Runs the steps of the scenario, stopping at the first error,
+/// and returns the IDs of the shards and the records read back.
+async fn run(
+    client: &Client,
+    opt: &Opt,
+    resources: &mut Resources,
+) -> Result<(Vec<String>, Vec<Record>), Box<dyn Error>> {
+    step(
+        opt,
+        format!("1. Creating stream {} with one shard", &opt.name),
+    );
+    client
+        .create_stream()
+        .stream_name(&opt.name)
+        .shard_count(1)
+        .send()
+        .await?;
+    resources.stream = true;
+
+    wait_for_stream_active(client, &opt.name, ACTIVE_TIMEOUT, |status| {
+        step(opt, format!("  Stream status: {}", status_name(status)));
+    })
+    .await?;
+
+    step(
+        opt,
+        format!("2. Putting {} records on the stream", opt.count),
+    );
+    for i in 0..opt.count {
+        let resp = client
+            .put_record()
+            .stream_name(&opt.name)
+            .partition_key(format!("key-{}", i))
+            .data(Blob::new(format!("Record {}", i)))
+            .send()
+            .await?;
+
+        if opt.verbose {
+            step(
+                opt,
+                format!(
+                    "  Put record {} in {}",
+                    resp.sequence_number.as_deref().unwrap_or_default(),
+                    resp.shard_id.as_deref().unwrap_or_default()
+                ),
+            );
+        }
+    }
+
+    step(opt, "3. Listing the shards of the stream");
+    let shards: Vec<String> = client
+        .list_shards()
+        .stream_name(&opt.name)
+        .send()
+        .await?
+        .shards
+        .unwrap_or_default()
+        .into_iter()
+        .filter_map(|shard| shard.shard_id)
+        .collect();
+    for shard_id in &shards {
+        step(opt, format!("  {}", shard_id));
+    }
+
+    let shard_id = shards.first().ok_or("the stream has no shards")?;
+
+    step(opt, format!("4. Reading the records from {}", shard_id));
+    let records = read_records(client, opt, shard_id).await?;
+
+    if records.len() < opt.count {
+        return Err(format!("read {} of the {} records put", records.len(), opt.count).into());
+    }
+
+    Ok((shards, records))
+}
+
+/// Deletes the stream, and with it, the records.
+async fn tear_down(client: &Client, opt: &Opt, resources: &Resources) {
+    if resources.stream {
+        if let Err(e) = client.delete_stream().stream_name(&opt.name).send().await {
+            eprintln!("Got an error deleting stream {}: {}", &opt.name, e);
+        }
+    }
+}
+
+/// Gets started with Amazon Kinesis Data Streams by running a complete workflow in one program.
+/// The scenario creates a stream with one shard, waits for it to become active,
+/// puts records on it, lists its shards, and reads the records back from the start of the shard.
+/// Finally, it deletes the stream, even if a step fails or you press Ctrl-C.
+/// # Arguments
+///
+/// * `[-n NAME]` - The name of the stream to create.
+///   If not supplied, defaults to **rust-scenario-stream**.
+/// * `[-c COUNT]` - How many records to put on the stream.
+///   If not supplied, defaults to **10**.
+/// * `[-k]` - Whether to keep the stream.
+/// * `[--output FORMAT]` - How to display the result, **text** (the default) or **json**,
+///   which only displays a summary once the scenario is finished.
+/// * `[-r REGION]` - The region in which the stream is created.
+///   If the **AWS_DEFAULT_REGION** environment variable is set, it's used instead.
+///   If neither is set, defaults to **us-west-2**.
+/// * `[--profile PROFILE]` - The AWS profile to use from the shared credentials file.
+/// * `[-v]` - Whether to display additional information.
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn Error>> {
+    let opt = Opt::from_args();
+
+    let region = EnvironmentProvider::new()
+        .region()
+        .or_else(|| {
+            opt.region
+                .as_ref()
+                .map(|region| Region::new(region.clone()))
+        })
+        .unwrap_or_else(|| Region::new("us-west-2"));
+
+    if opt.verbose {
+        println!("Kinesis client version: {}", kinesis::PKG_VERSION);
+        println!("Region:                 {:?}", &region);
+        println!("Stream:                 {}", &opt.name);
+        println!("Records:                {}", opt.count);
+        println!();
+
+        SubscriberBuilder::default()
+            .with_env_filter("info")
+            .with_span_events(FmtSpan::CLOSE)
+            .init();
+    }
+
+    let mut config = Config::builder().region(region);
+    if let Some(profile) = &opt.profile {
+        config = config.credentials_provider(ProfileCredentialsProvider::new(profile));
+    }
+    let client = Client::from_conf(config.build());
+
+    let mut resources = Resources::default();
+
+    // Ctrl-C stops the scenario wherever it is, and still cleans up what it created.
+    let shutdown = Shutdown::listen();
+    let result = shutdown.run(run(&client, &opt, &mut resources)).await;
+
+    if opt.keep {
+        step(&opt, format!("Keeping stream {}", &opt.name));
+    } else {
+        step(&opt, "5. Deleting the stream");
+        tear_down(&client, &opt, &resources).await;
+    }
+
+    let (shards, records) = result?;
+
+    opt.output.print(&Summary {
+        stream: opt.name.clone(),
+        shards,
+        records,
+        kept: opt.keep,
+    });
+
+    Ok(())
+}
@@ -0,0 +1,104 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0.
+ */
+
+use std::error::Error;
+
+use kinesis::{Client, Config, Region};
+use kinesis_code_examples::shards::{list_shards, ShardMap};
+
+use aws_types::region::{EnvironmentProvider, ProvideRegion};
+
+use common::output::OutputFormat;
+use common::profile::ProfileCredentialsProvider;
+use structopt::StructOpt;
+use tracing_subscriber::fmt::format::FmtSpan;
+use tracing_subscriber::fmt::SubscriberBuilder;
+
+#[derive(Debug, StructOpt)]
+struct Opt {
+    /// The region
+    #[structopt(short, long)]
+    region: Option<String>,
+
+    /// The name of the stream
+    #[structopt(short, long)]
+    name: String,
+
+    /// How many characters wide to draw the hash key space
+    #[structopt(short, long, default_value = "64")]
+    width: usize,
+
+    /// The format of the output, text or json
+    #[structopt(long, default_value = "text")]
+    output: OutputFormat,
+
+    /// The AWS profile to use from the shared credentials file
+    #[structopt(long)]
+    profile: Option<String>,
+
+    /// Whether to display additional information
+    #[structopt(short, long)]
+    verbose: bool,
+}
+
+/// Displays how a Kinesis data stream's shards were split and merged: a tree with each shard
+/// under the shard it came from, and a timeline of the part of the hash key space that each shard owns,
+/// oldest generation first. Closed shards are listed until their records expire,
+/// so the history goes back as far as the stream's retention period.
+/// # Arguments
+///
+/// * `-n NAME` - The name of the stream.
+/// * `[-w WIDTH]` - How many characters wide to draw the hash key space. Defaults to **64**.
+/// * `[-r REGION]` - The region in which the client is created.
+///    If not supplied, uses the value of the **AWS_DEFAULT_REGION** environment variable.
+///    If the environment variable is not set, defaults to **us-west-2**.
+/// * `[--output FORMAT]` - How to display the result, **text** (the default) or **json**.
+/// * `[--profile PROFILE]` - The AWS profile to use from the shared credentials file.
+/// * `[-v]` - Whether to display additional information.
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn Error>> {
+    let Opt {
+        name,
+        output,
+        profile,
+        region,
+        verbose,
+        width,
+    } = Opt::from_args();
+
+    let region = EnvironmentProvider::new()
+        .region()
+        .or_else(|| region.as_ref().map(|region| Region::new(region.clone())))
+        .unwrap_or_else(|| Region::new("us-west-2"));
+
+    if width == 0 {
+        return Err("the width must be at least 1".into());
+    }
+
+    if verbose {
+        println!("Kinesis client version: {}\n", kinesis::PKG_VERSION);
+        println!("Region:      {:?}", &region);
+        println!("Stream name: {}", name);
+        println!("Width:       {}", width);
+
+        SubscriberBuilder::default()
+            .with_env_filter("info")
+            .with_span_events(FmtSpan::CLOSE)
+            .init();
+    }
+
+    let mut config = Config::builder().region(region);
+    if let Some(profile) = profile {
+        config = config.credentials_provider(ProfileCredentialsProvider::new(profile));
+    }
+
+    let client = Client::from_conf(config.build());
+
+    let shards = list_shards(&client, &name).await?;
+
+    output.print(&ShardMap::new(shards, width));
+
+    Ok(())
+}
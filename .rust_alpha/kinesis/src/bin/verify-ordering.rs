@@ -0,0 +1,406 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0.
+ */
+
+use std::collections::HashMap;
+use std::error::Error;
+use std::sync::Arc;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use kinesis::error::PutRecordErrorKind;
+use kinesis::model::PutRecordsRequestEntry;
+use kinesis::{Blob, Client, Config, Region, SdkError};
+use kinesis_code_examples::consumer::{consume_shard, list_shard_ids, Start};
+use kinesis_code_examples::ordering::{Numbered, Observation, Verifier};
+
+use aws_types::region::{EnvironmentProvider, ProvideRegion};
+
+use common::output::OutputFormat;
+use common::profile::ProfileCredentialsProvider;
+use common::shutdown::Shutdown;
+use structopt::StructOpt;
+use tokio::sync::mpsc;
+use tokio::time::{sleep, sleep_until, Instant};
+use tracing_subscriber::fmt::format::FmtSpan;
+use tracing_subscriber::fmt::SubscriberBuilder;
+
+/// How many records can wait in the channel before the shard tasks wait for the verifier to catch up.
+const CHANNEL_CAPACITY: usize = 1000;
+
+/// How far before the run starts to read from, in seconds, in case this computer's clock is ahead of Kinesis's.
+/// Records from earlier runs are ignored, so reading a little too much is harmless.
+const CLOCK_SKEW: i64 = 60;
+
+/// The most records that PutRecords accepts in one call.
+const MAX_BATCH_RECORDS: usize = 500;
+
+/// How many times to resend a record that Kinesis didn't put.
+const MAX_RETRIES: u32 = 5;
+
+/// How long to wait before the first retry. The delay doubles after each retry.
+const INITIAL_DELAY: Duration = Duration::from_millis(100);
+
+/// How long to keep reading after every record has arrived, to catch late duplicates.
+const SETTLE_TIME: Duration = Duration::from_secs(3);
+
+#[derive(Debug, StructOpt)]
+struct Opt {
+    /// The region
+    #[structopt(short, long)]
+    region: Option<String>,
+
+    /// The name of the stream
+    #[structopt(short, long)]
+    name: String,
+
+    /// How many partition keys to put records for
+    #[structopt(short, long, default_value = "4")]
+    keys: usize,
+
+    /// How many records to put for each partition key
+    #[structopt(short, long, default_value = "100")]
+    count: u64,
+
+    /// Whether to put the records with PutRecords, which can reorder the records it resends, rather than PutRecord
+    #[structopt(short, long)]
+    batch: bool,
+
+    /// Put every Nth record twice, as a producer that retries after a timeout would
+    #[structopt(long)]
+    duplicate_every: Option<u64>,
+
+    /// How long to wait for missing records, in seconds, once they've all been put
+    #[structopt(short, long, default_value = "60")]
+    timeout: u64,
+
+    /// The format of the output, text or json
+    #[structopt(long, default_value = "text")]
+    output: OutputFormat,
+
+    /// The AWS profile to use from the shared credentials file
+    #[structopt(long)]
+    profile: Option<String>,
+
+    /// Whether to display additional information
+    #[structopt(short, long)]
+    verbose: bool,
+}
+
+/// Returns the records to put, in the order to put them: the records for all of the keys interleaved,
+/// with every Nth record repeated right after itself if `duplicate_every` is set.
+fn records(run: &str, keys: &[String], count: u64, duplicate_every: Option<u64>) -> Vec<Numbered> {
+    let mut records = Vec::new();
+
+    for sequence in 0..count {
+        for key in keys {
+            let copies = match duplicate_every {
+                Some(every) if (sequence + 1) % every == 0 => 2,
+                _ => 1,
+            };
+
+            for _ in 0..copies {
+                records.push(Numbered {
+                    run: run.to_string(),
+                    key: key.clone(),
+                    sequence,
+                });
+            }
+        }
+    }
+
+    records
+}
+
+/// Puts the records one at a time with PutRecord. Each record for a key passes the sequence number
+/// of the key's previous record as its sequence number for ordering, so Kinesis keeps them in order
+/// even if a retried call lands after a later one.
+async fn put_one_by_one(
+    client: &Client,
+    stream: &str,
+    records: &[Numbered],
+) -> Result<(), Box<dyn Error>> {
+    let mut previous = HashMap::new();
+
+    for record in records {
+        let mut delay = INITIAL_DELAY;
+
+        let resp = loop {
+            match client
+                .put_record()
+                .stream_name(stream)
+                .partition_key(&record.key)
+                .data(Blob::new(serde_json::to_vec(record)?))
+                .set_sequence_number_for_ordering(previous.get(&record.key).cloned())
+                .send()
+                .await
+            {
+                Ok(resp) => break resp,
+                Err(SdkError::ServiceError { err, .. })
+                    if matches!(
+                        err.kind,
+                        PutRecordErrorKind::ProvisionedThroughputExceededException(_)
+                    ) =>
+                {
+                    sleep(delay).await;
+                    delay *= 2;
+                }
+                Err(e) => return Err(e.into()),
+            }
+        };
+
+        previous.insert(record.key.clone(), resp.sequence_number.unwrap_or_default());
+    }
+
+    Ok(())
+}
+
+/// Puts the records in batches with PutRecords, resending only the records that Kinesis reports as failed.
+/// The records after a failed record were already put, so a resent record arrives after them, out of order.
+async fn put_in_batches(
+    client: &Client,
+    stream: &str,
+    records: &[Numbered],
+) -> Result<(), Box<dyn Error>> {
+    for chunk in records.chunks(MAX_BATCH_RECORDS) {
+        let mut entries = chunk
+            .iter()
+            .map(|record| {
+                Ok(PutRecordsRequestEntry::builder()
+                    .partition_key(&record.key)
+                    .data(Blob::new(serde_json::to_vec(record)?))
+                    .build())
+            })
+            .collect::<Result<Vec<_>, serde_json::Error>>()?;
+        let mut delay = INITIAL_DELAY;
+
+        for attempt in 0..=MAX_RETRIES {
+            if attempt > 0 {
+                sleep(delay).await;
+                delay *= 2;
+            }
+
+            let resp = client
+                .put_records()
+                .stream_name(stream)
+                .set_records(Some(entries.clone()))
+                .send()
+                .await?;
+
+            // The results are in the same order as the records, and a failed record has an error code.
+            entries = entries
+                .into_iter()
+                .zip(resp.records.unwrap_or_default())
+                .filter(|(_, result)| result.error_code.is_some())
+                .map(|(entry, _)| entry)
+                .collect();
+
+            if entries.is_empty() {
+                break;
+            }
+        }
+
+        if !entries.is_empty() {
+            return Err(format!(
+                "{} records still failed after {} retries",
+                entries.len(),
+                MAX_RETRIES
+            )
+            .into());
+        }
+    }
+
+    Ok(())
+}
+
+/// Checks the ordering guarantee of an Amazon Kinesis data stream.
+/// Puts numbered records for several partition keys while reading every shard of the stream,
+/// and reports, for each key, the records that arrived twice, arrived after a later record, or never arrived.
+/// Kinesis keeps the records for each key in order, because they all go to the same shard,
+/// but a producer that retries can put a record twice, so a correct consumer tolerates duplicates.
+/// With **--batch**, the records Kinesis fails are resent after the rest, which shows up as records out of order.
+/// Records are tagged with a run ID, so records from earlier runs on the same stream are ignored.
+/// Exits with an error if any records are out of order or missing.
+/// # Arguments
+///
+/// * `-n NAME` - The name of the stream.
+/// * `[-k KEYS]` - How many partition keys to put records for.
+///   If not supplied, defaults to **4**.
+/// * `[-c COUNT]` - How many records to put for each partition key.
+///   If not supplied, defaults to **100**.
+/// * `[-b]` - Put the records with PutRecords rather than PutRecord.
+/// * `[--duplicate-every N]` - Put every Nth record twice.
+/// * `[-t TIMEOUT]` - How long to wait for missing records, in seconds, once they've all been put.
+///   If not supplied, defaults to **60**.
+/// * `[-r REGION]` - The region in which the client is created.
+///    If not supplied, uses the value of the **AWS_DEFAULT_REGION** environment variable.
+///    If the environment variable is not set, defaults to **us-west-2**.
+/// * `[--output FORMAT]` - How to display the result, **text** (the default) or **json**.
+/// * `[--profile PROFILE]` - The AWS profile to use from the shared credentials file.
+/// * `[-v]` - Whether to display additional information, including each record that isn't in order.
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn Error>> {
+    let Opt {
+        batch,
+        count,
+        duplicate_every,
+        keys,
+        name,
+        output,
+        profile,
+        region,
+        timeout,
+        verbose,
+    } = Opt::from_args();
+
+    let region = EnvironmentProvider::new()
+        .region()
+        .or_else(|| region.as_ref().map(|region| Region::new(region.clone())))
+        .unwrap_or_else(|| Region::new("us-west-2"));
+
+    if keys == 0 || count == 0 {
+        return Err("the number of keys and the count must be at least 1".into());
+    }
+
+    if duplicate_every == Some(0) {
+        return Err("--duplicate-every must be at least 1".into());
+    }
+
+    if verbose {
+        println!("Kinesis client version: {}", kinesis::PKG_VERSION);
+        println!("Region:                 {:?}", &region);
+        println!("Stream name:            {}", &name);
+        println!("Keys:                   {}", keys);
+        println!("Count:                  {}", count);
+        println!("Batch:                  {}", batch);
+        println!("Duplicate every:        {:?}", duplicate_every);
+        println!("Timeout:                {}", timeout);
+
+        SubscriberBuilder::default()
+            .with_env_filter("info")
+            .with_span_events(FmtSpan::CLOSE)
+            .init();
+    }
+
+    let mut config = Config::builder().region(region);
+    if let Some(profile) = profile {
+        config = config.credentials_provider(ProfileCredentialsProvider::new(profile));
+    }
+    let client = Arc::new(Client::from_conf(config.build()));
+
+    let now = SystemTime::now().duration_since(UNIX_EPOCH)?;
+    let run = now.as_millis().to_string();
+    let key_names: Vec<String> = (0..keys).map(|key| format!("key-{}", key)).collect();
+    let to_put = records(&run, &key_names, count, duplicate_every);
+    let mut verifier = Verifier::new(key_names, count);
+
+    // The consumers start reading just before the run, so they see every record it puts,
+    // whenever their shard iterators are ready.
+    let start = Start::AtTimestamp(now.as_secs() as i64 - CLOCK_SKEW);
+    let shutdown = Shutdown::listen();
+    let (sender, mut receiver) = mpsc::channel(CHANNEL_CAPACITY);
+
+    let handles: Vec<_> = list_shard_ids(&client, &name)
+        .await?
+        .into_iter()
+        .map(|shard_id| {
+            tokio::spawn(consume_shard(
+                client.clone(),
+                name.clone(),
+                shard_id,
+                start,
+                sender.clone(),
+                shutdown.clone(),
+            ))
+        })
+        .collect();
+
+    drop(sender);
+
+    println!(
+        "Run {}: putting {} records for {} keys on stream {}, and reading {} shards",
+        &run,
+        to_put.len(),
+        keys,
+        &name,
+        handles.len()
+    );
+
+    let producer = async {
+        if batch {
+            put_in_batches(&client, &name, &to_put).await
+        } else {
+            put_one_by_one(&client, &name, &to_put).await
+        }
+    };
+    tokio::pin!(producer);
+
+    let mut produced = false;
+    let mut last_record = Instant::now();
+
+    loop {
+        // Once every record has been put, stop when nothing new has arrived for a while:
+        // a short while if every record has arrived, or the timeout if some are still missing.
+        let wait = if verifier.is_complete() {
+            SETTLE_TIME
+        } else {
+            Duration::from_secs(timeout)
+        };
+
+        tokio::select! {
+            result = &mut producer, if !produced => {
+                result?;
+                produced = true;
+                last_record = Instant::now();
+                println!("Put {} records", to_put.len());
+            }
+            record = receiver.recv() => match record {
+                Some(record) => {
+                    let numbered: Numbered = match serde_json::from_slice(&record.data) {
+                        Ok(numbered) => numbered,
+                        Err(_) => continue,
+                    };
+
+                    if numbered.run != run {
+                        continue;
+                    }
+
+                    last_record = Instant::now();
+
+                    let observation = verifier.observe(&record.partition_key, numbered.sequence, &record.shard_id);
+                    if verbose && observation != Observation::InOrder {
+                        println!(
+                            "{} record {} from {}: {:?}",
+                            &record.partition_key, numbered.sequence, &record.shard_id, observation
+                        );
+                    }
+                }
+                None => break,
+            },
+            _ = sleep_until(last_record + wait), if produced => break,
+            _ = shutdown.requested() => break,
+        }
+    }
+
+    // The shard tasks only stop by themselves on Ctrl-C, so they're cancelled,
+    // but an error from one of them before then is still reported.
+    drop(receiver);
+
+    for handle in handles {
+        handle.abort();
+        match handle.await {
+            Ok(result) => result?,
+            Err(e) if e.is_cancelled() => {}
+            Err(e) => return Err(e.into()),
+        }
+    }
+
+    let report = verifier.report(&run);
+    output.print(&report);
+
+    if !report.ordered || !report.complete {
+        return Err("the records weren't all read in order".into());
+    }
+
+    Ok(())
+}
@@ -0,0 +1,217 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0.
+ */
+
+//! Reads every shard of a stream at once, with a task per shard.
+//!
+//! Each task gets its own shard iterator, calls GetRecords until the shard is closed or the user
+//! presses Ctrl-C, and sends the records it reads to a channel. The caller receives from the
+//! channel, which closes once every task has stopped:
+//!
+//! ```ignore
+//! let (sender, mut receiver) = mpsc::channel(CHANNEL_CAPACITY);
+//! for shard_id in list_shard_ids(&client, stream).await? {
+//!     tokio::spawn(consume_shard(client.clone(), stream.into(), shard_id, start, sender.clone(), shutdown.clone()));
+//! }
+//! drop(sender);
+//! while let Some(record) = receiver.recv().await { ... }
+//! ```
+//!
+//! An iterator expires five minutes after it's returned, such as when the receiver is slow.
+//! It's replaced with one that starts after the last record read, so that no record is read
+//! twice or missed. Throttled calls are retried with exponential backoff.
+
+use std::str::FromStr;
+use std::sync::Arc;
+use std::time::Duration;
+
+use kinesis::error::GetRecordsErrorKind;
+use kinesis::model::ShardIteratorType;
+use kinesis::{Client, SdkError};
+
+use common::shutdown::Shutdown;
+use smithy_types::Instant;
+use tokio::sync::mpsc;
+use tokio::time::sleep;
+
+/// How long to wait after a GetRecords call that returned no records.
+/// Each shard supports up to five GetRecords calls a second, shared by all of its consumers.
+const IDLE_INTERVAL: Duration = Duration::from_secs(1);
+
+/// How long to wait after the shard's read throughput was exceeded. The delay doubles each time in a row.
+const THROTTLE_DELAY: Duration = Duration::from_millis(500);
+
+/// The longest delay after the shard's read throughput was exceeded.
+const MAX_THROTTLE_DELAY: Duration = Duration::from_secs(10);
+
+/// Where in each shard to start reading.
+#[derive(Clone, Copy, Debug)]
+pub enum Start {
+    /// The oldest record in the shard.
+    Oldest,
+    /// Only the records added after the consumer starts.
+    Latest,
+    /// The records added at or after the time, in seconds since the Unix epoch.
+    AtTimestamp(i64),
+}
+
+impl FromStr for Start {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "oldest" => Ok(Start::Oldest),
+            "latest" => Ok(Start::Latest),
+            _ => Err(format!("the start must be oldest or latest, got {}", s)),
+        }
+    }
+}
+
+/// A record, and the shard it was read from.
+#[derive(Debug)]
+pub struct Consumed {
+    pub shard_id: String,
+    pub sequence_number: String,
+    pub partition_key: String,
+    pub data: Vec<u8>,
+}
+
+/// Lists the IDs of the stream's shards.
+/// ListShards returns up to 1,000 shards a page. Later pages are requested with the token alone.
+pub async fn list_shard_ids(client: &Client, stream: &str) -> Result<Vec<String>, kinesis::Error> {
+    let mut shard_ids = Vec::new();
+    let mut token: Option<String> = None;
+
+    loop {
+        let request = match token {
+            Some(token) => client.list_shards().next_token(token),
+            None => client.list_shards().stream_name(stream),
+        };
+        let resp = request.send().await?;
+
+        shard_ids.extend(
+            resp.shards
+                .unwrap_or_default()
+                .into_iter()
+                .filter_map(|shard| shard.shard_id),
+        );
+
+        token = resp.next_token;
+        if token.is_none() {
+            return Ok(shard_ids);
+        }
+    }
+}
+
+/// Gets an iterator for the shard. After an iterator expires, the new one starts after the
+/// last record read, so that no record is read twice or missed.
+async fn shard_iterator(
+    client: &Client,
+    stream: &str,
+    shard_id: &str,
+    start: Start,
+    last_sequence_number: &Option<String>,
+) -> Result<Option<String>, kinesis::Error> {
+    let request = client
+        .get_shard_iterator()
+        .stream_name(stream)
+        .shard_id(shard_id);
+
+    let request = match (last_sequence_number, start) {
+        (Some(sequence_number), _) => request
+            .shard_iterator_type(ShardIteratorType::AfterSequenceNumber)
+            .starting_sequence_number(sequence_number),
+        (None, Start::Oldest) => request.shard_iterator_type(ShardIteratorType::TrimHorizon),
+        (None, Start::Latest) => request.shard_iterator_type(ShardIteratorType::Latest),
+        (None, Start::AtTimestamp(seconds)) => request
+            .shard_iterator_type(ShardIteratorType::AtTimestamp)
+            .timestamp(Instant::from_epoch_seconds(seconds)),
+    };
+
+    Ok(request.send().await?.shard_iterator)
+}
+
+/// Reads a shard until it's closed, which happens when the stream is resharded, or until Ctrl-C,
+/// and sends each record to the channel.
+/// The records from the GetRecords call in progress when Ctrl-C is pressed are still sent.
+pub async fn consume_shard(
+    client: Arc<Client>,
+    stream: String,
+    shard_id: String,
+    start: Start,
+    records: mpsc::Sender<Consumed>,
+    shutdown: Shutdown,
+) -> Result<(), kinesis::Error> {
+    let mut last_sequence_number = None;
+    let mut iterator =
+        shard_iterator(&client, &stream, &shard_id, start, &last_sequence_number).await?;
+    let mut throttle_delay = THROTTLE_DELAY;
+
+    while let Some(current) = iterator.take() {
+        if shutdown.is_requested() {
+            break;
+        }
+
+        let resp = match client.get_records().shard_iterator(&current).send().await {
+            Ok(resp) => resp,
+            // An iterator expires five minutes after it's returned, such as when the receiver was paused.
+            Err(SdkError::ServiceError { err, .. })
+                if matches!(err.kind, GetRecordsErrorKind::ExpiredIteratorException(_)) =>
+            {
+                iterator =
+                    shard_iterator(&client, &stream, &shard_id, start, &last_sequence_number)
+                        .await?;
+                continue;
+            }
+            Err(SdkError::ServiceError { err, .. })
+                if matches!(
+                    err.kind,
+                    GetRecordsErrorKind::ProvisionedThroughputExceededException(_)
+                ) =>
+            {
+                sleep(throttle_delay).await;
+                throttle_delay = (throttle_delay * 2).min(MAX_THROTTLE_DELAY);
+                // A throttled call doesn't use up the iterator, so it's used again.
+                iterator = Some(current);
+                continue;
+            }
+            Err(e) => return Err(e.into()),
+        };
+        throttle_delay = THROTTLE_DELAY;
+
+        let batch = resp.records.unwrap_or_default();
+        let idle = batch.is_empty();
+
+        for record in batch {
+            last_sequence_number = record.sequence_number.clone();
+
+            let consumed = Consumed {
+                shard_id: shard_id.clone(),
+                sequence_number: record.sequence_number.unwrap_or_default(),
+                partition_key: record.partition_key.unwrap_or_default(),
+                data: record
+                    .data
+                    .map(|data| data.into_inner())
+                    .unwrap_or_default(),
+            };
+
+            // The receiver is only dropped when the consumer is stopping.
+            if records.send(consumed).await.is_err() {
+                return Ok(());
+            }
+        }
+
+        // A closed shard has no next iterator, once every record in it has been read.
+        iterator = resp.next_shard_iterator;
+
+        if idle {
+            tokio::select! {
+                _ = sleep(IDLE_INTERVAL) => {}
+                _ = shutdown.requested() => break,
+            }
+        }
+    }
+
+    Ok(())
+}
@@ -0,0 +1,167 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0.
+ */
+
+//! Measures how long records take to get through a stream, for `benchmark`.
+//!
+//! Each record that the benchmark puts starts with a probe: the run and step that put it,
+//! and when it was created, in microseconds since the Unix epoch. The rest of the record is
+//! padding, to make it the size being measured. The consumer decodes the probe from each record
+//! it reads, and the time since the record was created is its end-to-end latency:
+//!
+//! ```ignore
+//! let data = Probe::now(run, step).encode(size);
+//! // ... put the record, and read it back ...
+//! if let Some(probe) = Probe::decode(&record.data) {
+//!     latencies.record(probe.age());
+//! }
+//! ```
+//!
+//! The producer and the consumer run on the same computer, so they share a clock.
+
+use std::convert::TryInto;
+use std::fmt;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use serde::Serialize;
+
+/// The bytes that every probe starts with, so that other records on the stream are ignored.
+const MAGIC: &[u8; 4] = b"KBM1";
+
+/// How many bytes a probe takes: the magic bytes, the run, the step, and the time.
+pub const PROBE_BYTES: usize = 4 + 8 + 2 + 8;
+
+/// The start of a record that the benchmark puts.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Probe {
+    /// Which run of the benchmark put the record.
+    pub run: u64,
+    /// Which step of the run put the record.
+    pub step: u16,
+    /// When the record was created, in microseconds since the Unix epoch.
+    pub created_at: u64,
+}
+
+fn micros_since_epoch(time: SystemTime) -> u64 {
+    time.duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_micros() as u64
+}
+
+impl Probe {
+    /// Returns a probe for a record created now.
+    pub fn now(run: u64, step: u16) -> Self {
+        Probe {
+            run,
+            step,
+            created_at: micros_since_epoch(SystemTime::now()),
+        }
+    }
+
+    /// Writes the probe, padded to `size` bytes. A size smaller than a probe isn't padded.
+    pub fn encode(&self, size: usize) -> Vec<u8> {
+        let mut data = Vec::with_capacity(size.max(PROBE_BYTES));
+        data.extend_from_slice(MAGIC);
+        data.extend_from_slice(&self.run.to_be_bytes());
+        data.extend_from_slice(&self.step.to_be_bytes());
+        data.extend_from_slice(&self.created_at.to_be_bytes());
+        data.resize(size.max(PROBE_BYTES), b'.');
+        data
+    }
+
+    /// Reads the probe at the start of a record, or returns `None` if the record doesn't start with one.
+    pub fn decode(data: &[u8]) -> Option<Self> {
+        if data.len() < PROBE_BYTES || !data.starts_with(MAGIC) {
+            return None;
+        }
+
+        Some(Probe {
+            run: u64::from_be_bytes(data[4..12].try_into().ok()?),
+            step: u16::from_be_bytes(data[12..14].try_into().ok()?),
+            created_at: u64::from_be_bytes(data[14..22].try_into().ok()?),
+        })
+    }
+
+    /// Returns how long ago the record was created.
+    pub fn age(&self) -> Duration {
+        let now = micros_since_epoch(SystemTime::now());
+        Duration::from_micros(now.saturating_sub(self.created_at))
+    }
+}
+
+/// A collection of latencies.
+#[derive(Clone, Debug, Default)]
+pub struct Latencies {
+    samples: Vec<Duration>,
+}
+
+impl Latencies {
+    pub fn record(&mut self, latency: Duration) {
+        self.samples.push(latency);
+    }
+
+    /// Adds the latencies from another collection, such as one from another task.
+    pub fn extend(&mut self, other: Latencies) {
+        self.samples.extend(other.samples);
+    }
+
+    pub fn len(&self) -> usize {
+        self.samples.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.samples.is_empty()
+    }
+
+    /// Returns the median, 90th and 99th percentiles, and the maximum.
+    pub fn summary(&self) -> Summary {
+        let mut samples = self.samples.clone();
+        samples.sort();
+
+        // The nearest-rank percentile: the smallest sample that at least p percent of the samples are at or below.
+        let percentile = |p: usize| -> f64 {
+            if samples.is_empty() {
+                return 0.0;
+            }
+            let rank = (p * samples.len() + 99) / 100;
+            millis(samples[rank.max(1) - 1])
+        };
+
+        Summary {
+            count: samples.len(),
+            p50_ms: percentile(50),
+            p90_ms: percentile(90),
+            p99_ms: percentile(99),
+            max_ms: samples.last().copied().map(millis).unwrap_or_default(),
+        }
+    }
+}
+
+fn millis(duration: Duration) -> f64 {
+    duration.as_secs_f64() * 1000.0
+}
+
+/// The percentiles of a collection of latencies, in milliseconds.
+#[derive(Clone, Debug, Default, PartialEq, Serialize)]
+pub struct Summary {
+    pub count: usize,
+    pub p50_ms: f64,
+    pub p90_ms: f64,
+    pub p99_ms: f64,
+    pub max_ms: f64,
+}
+
+impl fmt::Display for Summary {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.count == 0 {
+            return write!(f, "{:>23}", "-");
+        }
+
+        write!(
+            f,
+            "{:>5.0} {:>5.0} {:>5.0} {:>5.0}",
+            self.p50_ms, self.p90_ms, self.p99_ms, self.max_ms
+        )
+    }
+}
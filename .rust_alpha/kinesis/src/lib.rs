@@ -0,0 +1,13 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0.
+ */
+
+//! Helpers shared by the Kinesis code examples.
+
+pub mod consumer;
+pub mod latency;
+pub mod ordering;
+pub mod records;
+pub mod shards;
+pub mod waiters;
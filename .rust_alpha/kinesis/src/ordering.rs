@@ -0,0 +1,257 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0.
+ */
+
+//! Checks that the records for each partition key are read in the order they were put.
+//!
+//! Kinesis only promises order within a shard, and every record with the same partition key
+//! goes to the same shard, so the records for one key come back in the order Kinesis accepted them.
+//! The producer numbers the records for each key from 0, and the consumer passes each number
+//! it reads to a `Verifier`, which notices numbers that repeat, go backwards, or never arrive:
+//!
+//! ```ignore
+//! let mut verifier = Verifier::new(keys, count);
+//! verifier.observe(&record.partition_key, sequence, &record.shard_id);
+//! let report = verifier.report(run);
+//! ```
+//!
+//! Repeats are expected: a producer that retries after a timeout can put a record twice,
+//! so consumers must be idempotent. Records that go backwards aren't expected from PutRecord
+//! with a sequence number for ordering, but are possible when PutRecords resends failed records,
+//! since the records after them were already put.
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt;
+
+use serde::{Deserialize, Serialize};
+
+/// The body of each record that the producer puts.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct Numbered {
+    /// Which run put the record, so that records left on the stream by earlier runs are ignored.
+    pub run: String,
+    pub key: String,
+    /// The record's position among the records for its key, from 0.
+    pub sequence: u64,
+}
+
+/// How a record compares with the records read before it for the same key.
+#[derive(Debug, PartialEq)]
+pub enum Observation {
+    /// The record is the next one, or comes after a gap that hasn't been filled yet.
+    InOrder,
+    /// The record skipped ahead. Holds the numbers that were skipped.
+    Gap(u64, u64),
+    /// The record was read before.
+    Duplicate,
+    /// The record arrived after a record that was put after it.
+    OutOfOrder,
+    /// The record isn't for one of the keys, or its number is out of range.
+    Unexpected,
+}
+
+/// What was read for one partition key.
+#[derive(Debug, Default)]
+struct KeyState {
+    seen: BTreeSet<u64>,
+    highest: Option<u64>,
+    received: u64,
+    duplicates: u64,
+    out_of_order: u64,
+    shards: BTreeSet<String>,
+}
+
+/// Tracks the records read for each partition key.
+#[derive(Debug)]
+pub struct Verifier {
+    expected: u64,
+    keys: BTreeMap<String, KeyState>,
+    unexpected: u64,
+}
+
+impl Verifier {
+    /// Creates a verifier that expects `expected` records, numbered from 0, for each of the keys.
+    pub fn new<I>(keys: I, expected: u64) -> Self
+    where
+        I: IntoIterator<Item = String>,
+    {
+        Verifier {
+            expected,
+            keys: keys
+                .into_iter()
+                .map(|key| (key, KeyState::default()))
+                .collect(),
+            unexpected: 0,
+        }
+    }
+
+    /// Records that the numbered record for the key was read from the shard.
+    pub fn observe(&mut self, key: &str, sequence: u64, shard_id: &str) -> Observation {
+        let expected = self.expected;
+        let state = match self.keys.get_mut(key) {
+            Some(state) if sequence < expected => state,
+            _ => {
+                self.unexpected += 1;
+                return Observation::Unexpected;
+            }
+        };
+
+        state.received += 1;
+        state.shards.insert(shard_id.to_string());
+
+        if !state.seen.insert(sequence) {
+            state.duplicates += 1;
+            return Observation::Duplicate;
+        }
+
+        match state.highest {
+            Some(highest) if sequence < highest => {
+                state.out_of_order += 1;
+                Observation::OutOfOrder
+            }
+            highest => {
+                state.highest = Some(sequence);
+                let next = highest.map(|highest| highest + 1).unwrap_or(0);
+                if sequence > next {
+                    Observation::Gap(next, sequence - 1)
+                } else {
+                    Observation::InOrder
+                }
+            }
+        }
+    }
+
+    /// Returns whether every record for every key has been read.
+    pub fn is_complete(&self) -> bool {
+        self.keys
+            .values()
+            .all(|state| state.seen.len() as u64 == self.expected)
+    }
+
+    /// Summarizes what was read for each key.
+    pub fn report(&self, run: &str) -> OrderingReport {
+        let keys: Vec<KeyReport> = self
+            .keys
+            .iter()
+            .map(|(key, state)| KeyReport {
+                key: key.clone(),
+                received: state.received,
+                duplicates: state.duplicates,
+                out_of_order: state.out_of_order,
+                missing: (0..self.expected)
+                    .filter(|sequence| !state.seen.contains(sequence))
+                    .collect(),
+                shards: state.shards.iter().cloned().collect(),
+            })
+            .collect();
+
+        OrderingReport {
+            run: run.to_string(),
+            expected: self.expected * self.keys.len() as u64,
+            received: keys.iter().map(|key| key.received).sum(),
+            duplicates: keys.iter().map(|key| key.duplicates).sum(),
+            out_of_order: keys.iter().map(|key| key.out_of_order).sum(),
+            missing: keys.iter().map(|key| key.missing.len() as u64).sum(),
+            unexpected: self.unexpected,
+            ordered: keys.iter().all(|key| key.out_of_order == 0),
+            complete: keys.iter().all(|key| key.missing.is_empty()),
+            keys,
+        }
+    }
+}
+
+/// What was read for one partition key.
+#[derive(Debug, Serialize)]
+pub struct KeyReport {
+    pub key: String,
+    pub received: u64,
+    pub duplicates: u64,
+    pub out_of_order: u64,
+    /// The numbers of the records that were never read.
+    pub missing: Vec<u64>,
+    /// The shards the key's records were read from. More than one means the stream was resharded.
+    pub shards: Vec<String>,
+}
+
+/// Whether the records were all read, in order.
+#[derive(Debug, Serialize)]
+pub struct OrderingReport {
+    pub run: String,
+    pub expected: u64,
+    pub received: u64,
+    pub duplicates: u64,
+    pub out_of_order: u64,
+    pub missing: u64,
+    /// Records from this run that weren't for one of the keys, or had a number out of range.
+    pub unexpected: u64,
+    /// Whether the records for every key were read in order. Duplicates don't count against it.
+    pub ordered: bool,
+    /// Whether every record for every key was read.
+    pub complete: bool,
+    pub keys: Vec<KeyReport>,
+}
+
+impl fmt::Display for OrderingReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "Run {}", self.run)?;
+
+        for key in &self.keys {
+            writeln!(
+                f,
+                "  {}: {} received, {} duplicates, {} out of order, {} missing, shards {}",
+                key.key,
+                key.received,
+                key.duplicates,
+                key.out_of_order,
+                key.missing.len(),
+                key.shards.join(", ")
+            )?;
+
+            if !key.missing.is_empty() {
+                writeln!(f, "    Missing: {}", ranges(&key.missing))?;
+            }
+        }
+
+        writeln!(
+            f,
+            "Received {} of {} records: {} duplicates, {} out of order, {} missing, {} unexpected",
+            self.received,
+            self.expected,
+            self.duplicates,
+            self.out_of_order,
+            self.missing,
+            self.unexpected
+        )?;
+        write!(
+            f,
+            "Ordering per key: {}. Completeness: {}",
+            if self.ordered { "PASS" } else { "FAIL" },
+            if self.complete { "PASS" } else { "FAIL" }
+        )
+    }
+}
+
+/// Formats sorted numbers as ranges, such as 3, 7-9, 12.
+fn ranges(numbers: &[u64]) -> String {
+    let mut ranges: Vec<(u64, u64)> = Vec::new();
+
+    for &number in numbers {
+        match ranges.last_mut() {
+            Some((_, end)) if *end + 1 == number => *end = number,
+            _ => ranges.push((number, number)),
+        }
+    }
+
+    ranges
+        .iter()
+        .map(|(start, end)| {
+            if start == end {
+                start.to_string()
+            } else {
+                format!("{}-{}", start, end)
+            }
+        })
+        .collect::<Vec<_>>()
+        .join(", ")
+}
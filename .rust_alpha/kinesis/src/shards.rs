@@ -0,0 +1,371 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0.
+ */
+
+//! Maps how a stream's shards were split and merged.
+//!
+//! Each shard owns a range of the 128-bit hash key space. Splitting a shard closes it and opens
+//! two children that share its range; merging two adjacent shards closes both and opens one child,
+//! whose __ParentShardId__ and __AdjacentParentShardId__ name them. ListShards returns the closed
+//! shards too, until their records expire, so the history can be rebuilt from one listing:
+//!
+//! ```ignore
+//! let map = ShardMap::new(list_shards(&client, &stream).await?, 64);
+//! println!("{}", map);
+//! ```
+//!
+//! The map is displayed as a tree, with each shard under the shard it came from,
+//! and as a timeline of the hash key ranges, a row per shard, oldest generation first,
+//! drawn with `#` while the shard is open and `=` once it's closed:
+//!
+//! ```text
+//! shardId-000000000000 closed
+//! ├── shardId-000000000001 closed, split from shardId-000000000000
+//! │   └── shardId-000000000003 open, merged from shardId-000000000001 and shardId-000000000002
+//! └── shardId-000000000002 closed, split from shardId-000000000000
+//!     └── shardId-000000000003 (see above)
+//!
+//! 0 |================================| shardId-000000000000
+//! 1 |================................| shardId-000000000001
+//! 1 |................================| shardId-000000000002
+//! 2 |################################| shardId-000000000003
+//! ```
+
+use std::collections::{BTreeMap, HashMap};
+use std::fmt;
+
+use kinesis::Client;
+use serde::{Serialize, Serializer};
+
+/// A shard, where it came from, and the hash keys it owns.
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct ShardInfo {
+    pub id: String,
+    /// The shard that was split, or the first of the two shards that were merged, to make this one.
+    pub parent: Option<String>,
+    /// The second of the two shards that were merged to make this one.
+    pub adjacent_parent: Option<String>,
+    #[serde(serialize_with = "as_string")]
+    pub starting_hash_key: u128,
+    #[serde(serialize_with = "as_string")]
+    pub ending_hash_key: u128,
+    /// Whether the shard was closed by a split or merge, so no more records are added to it.
+    pub closed: bool,
+}
+
+/// Hash keys are written as strings, as Kinesis returns them, since they don't fit in a JSON number.
+fn as_string<S: Serializer>(key: &u128, serializer: S) -> Result<S::Ok, S::Error> {
+    serializer.serialize_str(&key.to_string())
+}
+
+/// Lists every shard of the stream, closed or open, reading every page of ListShards.
+pub async fn list_shards(client: &Client, stream: &str) -> Result<Vec<ShardInfo>, String> {
+    let mut shards = Vec::new();
+    let mut token: Option<String> = None;
+
+    loop {
+        // Later pages are requested with the token alone.
+        let request = match token {
+            Some(token) => client.list_shards().next_token(token),
+            None => client.list_shards().stream_name(stream),
+        };
+        let resp = request
+            .send()
+            .await
+            .map_err(|e| kinesis::Error::from(e).to_string())?;
+
+        for shard in resp.shards.unwrap_or_default() {
+            let id = shard.shard_id.unwrap_or_default();
+            let range = shard
+                .hash_key_range
+                .ok_or_else(|| format!("shard {} has no hash key range", id))?;
+            let parse = |key: Option<String>| {
+                key.unwrap_or_default()
+                    .parse::<u128>()
+                    .map_err(|e| format!("shard {} has a bad hash key: {}", id, e))
+            };
+
+            shards.push(ShardInfo {
+                starting_hash_key: parse(range.starting_hash_key)?,
+                ending_hash_key: parse(range.ending_hash_key)?,
+                closed: shard
+                    .sequence_number_range
+                    .map(|range| range.ending_sequence_number.is_some())
+                    .unwrap_or_default(),
+                parent: shard.parent_shard_id,
+                adjacent_parent: shard.adjacent_parent_shard_id,
+                id,
+            });
+        }
+
+        token = resp.next_token;
+        if token.is_none() {
+            return Ok(shards);
+        }
+    }
+}
+
+/// How a shard was made.
+#[derive(Clone, Debug, PartialEq, Serialize)]
+#[serde(tag = "kind", rename_all = "lowercase")]
+pub enum Origin {
+    /// The shard was there when the stream was created, or its parents have expired.
+    Original,
+    /// The shard is one of the children of a split.
+    Split { parent: String },
+    /// The shard is the child of two merged shards.
+    Merge {
+        parent: String,
+        adjacent_parent: String,
+    },
+}
+
+/// A shard, with how it was made, and how many splits and merges it's from the oldest listed shard.
+#[derive(Debug, Serialize)]
+pub struct MappedShard {
+    #[serde(flatten)]
+    pub shard: ShardInfo,
+    pub origin: Origin,
+    pub generation: usize,
+}
+
+/// The shards of a stream, and how they're related.
+#[derive(Debug, Serialize)]
+pub struct ShardMap {
+    /// The shards, oldest generation first, then by hash key.
+    pub shards: Vec<MappedShard>,
+    pub splits: usize,
+    pub merges: usize,
+    /// How many characters wide the timeline's bars are.
+    #[serde(skip)]
+    pub width: usize,
+}
+
+impl ShardMap {
+    /// Works out how the shards are related. A parent that's no longer listed,
+    /// because its records have expired, is treated as if it didn't exist.
+    pub fn new(shards: Vec<ShardInfo>, width: usize) -> ShardMap {
+        let by_id: HashMap<&str, &ShardInfo> = shards
+            .iter()
+            .map(|shard| (shard.id.as_str(), shard))
+            .collect();
+        let listed = |id: &Option<String>| {
+            id.as_deref()
+                .filter(|id| by_id.contains_key(id))
+                .map(str::to_string)
+        };
+
+        let mut generations: HashMap<String, usize> = HashMap::new();
+        for shard in &shards {
+            generation(&shard.id, &by_id, &mut generations);
+        }
+
+        let mut mapped: Vec<MappedShard> = shards
+            .iter()
+            .map(|shard| {
+                let origin = match (listed(&shard.parent), listed(&shard.adjacent_parent)) {
+                    (Some(parent), Some(adjacent_parent)) => Origin::Merge {
+                        parent,
+                        adjacent_parent,
+                    },
+                    (Some(parent), None) => Origin::Split { parent },
+                    _ => Origin::Original,
+                };
+
+                MappedShard {
+                    shard: shard.clone(),
+                    origin,
+                    generation: generations[&shard.id],
+                }
+            })
+            .collect();
+
+        mapped.sort_by(|a, b| {
+            (a.generation, a.shard.starting_hash_key)
+                .cmp(&(b.generation, b.shard.starting_hash_key))
+        });
+
+        // A split makes two children of one parent; count it once.
+        let mut split_parents: Vec<&str> = mapped
+            .iter()
+            .filter_map(|shard| match &shard.origin {
+                Origin::Split { parent } => Some(parent.as_str()),
+                _ => None,
+            })
+            .collect();
+        split_parents.sort_unstable();
+        split_parents.dedup();
+        let splits = split_parents.len();
+
+        let merges = mapped
+            .iter()
+            .filter(|shard| matches!(shard.origin, Origin::Merge { .. }))
+            .count();
+
+        ShardMap {
+            shards: mapped,
+            splits,
+            merges,
+            width,
+        }
+    }
+
+    /// Returns the children of each shard, by the ID of the parent, in hash key order.
+    /// A merged shard is a child of both of its parents.
+    fn children(&self) -> BTreeMap<&str, Vec<&MappedShard>> {
+        let mut children: BTreeMap<&str, Vec<&MappedShard>> = BTreeMap::new();
+
+        for shard in &self.shards {
+            match &shard.origin {
+                Origin::Original => {}
+                Origin::Split { parent } => children.entry(parent).or_default().push(shard),
+                Origin::Merge {
+                    parent,
+                    adjacent_parent,
+                } => {
+                    children.entry(parent).or_default().push(shard);
+                    children.entry(adjacent_parent).or_default().push(shard);
+                }
+            }
+        }
+
+        children
+    }
+
+    /// Writes the shard and its descendants as a tree. A merged shard is written in full
+    /// under its first parent, and refers back to it under the second.
+    fn write_tree(
+        &self,
+        f: &mut fmt::Formatter<'_>,
+        shard: &MappedShard,
+        prefix: &str,
+        children: &BTreeMap<&str, Vec<&MappedShard>>,
+        parent: Option<&str>,
+    ) -> fmt::Result {
+        if let (Origin::Merge { parent: first, .. }, Some(parent)) = (&shard.origin, parent) {
+            if first != parent {
+                return writeln!(f, "{} (see above)", shard.shard.id);
+            }
+        }
+
+        write!(f, "{} {}", shard.shard.id, status(&shard.shard))?;
+        match &shard.origin {
+            Origin::Original => writeln!(f)?,
+            Origin::Split { parent } => writeln!(f, ", split from {}", parent)?,
+            Origin::Merge {
+                parent,
+                adjacent_parent,
+            } => writeln!(f, ", merged from {} and {}", parent, adjacent_parent)?,
+        }
+
+        let empty = Vec::new();
+        let kids = children.get(shard.shard.id.as_str()).unwrap_or(&empty);
+        for (i, child) in kids.iter().enumerate() {
+            let last = i + 1 == kids.len();
+            write!(f, "{}{}", prefix, if last { "└── " } else { "├── " })?;
+
+            let prefix = format!("{}{}", prefix, if last { "    " } else { "│   " });
+            self.write_tree(f, child, &prefix, children, Some(&shard.shard.id))?;
+        }
+
+        Ok(())
+    }
+
+    /// Draws the part of the hash key space that the shard owns, with `#` if the shard is open,
+    /// and `=` if it's closed.
+    fn bar(&self, shard: &ShardInfo) -> String {
+        let column =
+            |key: u128| (key as f64 / u128::MAX as f64 * self.width as f64).round() as usize;
+
+        let start = column(shard.starting_hash_key).min(self.width.saturating_sub(1));
+        // Every shard gets at least one character, however small its range.
+        let end = column(shard.ending_hash_key).clamp(start + 1, self.width);
+        let fill = if shard.closed { '=' } else { '#' };
+
+        let mut bar = String::with_capacity(self.width);
+        for i in 0..self.width {
+            bar.push(if i >= start && i < end { fill } else { '.' });
+        }
+
+        bar
+    }
+}
+
+/// Returns how many splits and merges the shard is from a listed shard without parents,
+/// following the longer line when it was merged.
+fn generation(
+    id: &str,
+    by_id: &HashMap<&str, &ShardInfo>,
+    generations: &mut HashMap<String, usize>,
+) -> usize {
+    if let Some(generation) = generations.get(id) {
+        return *generation;
+    }
+
+    let shard = by_id[id];
+    let generation = [&shard.parent, &shard.adjacent_parent]
+        .iter()
+        .filter_map(|parent| parent.as_deref())
+        .filter(|parent| by_id.contains_key(parent))
+        .map(|parent| generation(parent, by_id, generations) + 1)
+        .max()
+        .unwrap_or(0);
+
+    generations.insert(id.to_string(), generation);
+    generation
+}
+
+fn status(shard: &ShardInfo) -> &'static str {
+    if shard.closed {
+        "closed"
+    } else {
+        "open"
+    }
+}
+
+impl fmt::Display for ShardMap {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let children = self.children();
+
+        for root in self
+            .shards
+            .iter()
+            .filter(|shard| shard.origin == Origin::Original)
+        {
+            self.write_tree(f, root, "", &children, None)?;
+        }
+
+        writeln!(f)?;
+
+        let digits = self
+            .shards
+            .last()
+            .map(|shard| shard.generation.to_string().len())
+            .unwrap_or(1);
+        for shard in &self.shards {
+            writeln!(
+                f,
+                "{:>digits$} |{}| {}",
+                shard.generation,
+                self.bar(&shard.shard),
+                shard.shard.id,
+                digits = digits
+            )?;
+        }
+
+        let open = self
+            .shards
+            .iter()
+            .filter(|shard| !shard.shard.closed)
+            .count();
+        write!(
+            f,
+            "\n{} open shards, {} closed; {} splits, {} merges",
+            open,
+            self.shards.len() - open,
+            self.splits,
+            self.merges
+        )
+    }
+}
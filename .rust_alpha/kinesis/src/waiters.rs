@@ -0,0 +1,178 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0.
+ */
+
+//! Waits for a Kinesis data stream to reach a state.
+//!
+//! `CreateStream` and `DeleteStream` return while the stream is still __CREATING__ or __DELETING__.
+//! The waiters poll `DescribeStreamSummary`, and call `on_status` each time the status changes,
+//! so that an example can show the transitions:
+//!
+//! ```ignore
+//! wait_for_stream_active(&client, &name, Duration::from_secs(300), |status| {
+//!     println!("Stream status: {}", status_name(status));
+//! })
+//! .await?;
+//! ```
+
+use std::error::Error;
+use std::fmt;
+use std::time::{Duration, Instant};
+
+use kinesis::error::DescribeStreamSummaryErrorKind;
+use kinesis::model::StreamStatus;
+use kinesis::{Client, SdkError};
+use tokio::time::sleep;
+
+/// How long to wait before the first retry. The delay doubles after each retry.
+const INITIAL_DELAY: Duration = Duration::from_secs(1);
+
+/// The longest delay between two retries.
+const MAX_DELAY: Duration = Duration::from_secs(10);
+
+/// Why waiting for a stream failed.
+#[derive(Debug)]
+pub enum WaitError {
+    /// DescribeStreamSummary failed.
+    Sdk(kinesis::Error),
+    /// The stream didn't reach the state before the timeout. Holds the last status seen.
+    Timeout(String, Option<StreamStatus>),
+}
+
+impl fmt::Display for WaitError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            WaitError::Sdk(e) => write!(f, "{}", e),
+            WaitError::Timeout(stream, status) => write!(
+                f,
+                "stream {} did not change in time, last status: {}",
+                stream,
+                status_name(status.as_ref())
+            ),
+        }
+    }
+}
+
+impl Error for WaitError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            WaitError::Sdk(e) => Some(e),
+            WaitError::Timeout(..) => None,
+        }
+    }
+}
+
+impl From<kinesis::Error> for WaitError {
+    fn from(e: kinesis::Error) -> Self {
+        WaitError::Sdk(e)
+    }
+}
+
+/// Returns the name of a status, such as __ACTIVE__, or __NOT FOUND__ if the stream doesn't exist.
+pub fn status_name(status: Option<&StreamStatus>) -> &str {
+    status.map(|status| status.as_str()).unwrap_or("NOT FOUND")
+}
+
+/// Returns the stream's status, or `None` if the stream doesn't exist.
+pub async fn stream_status(
+    client: &Client,
+    name: &str,
+) -> Result<Option<StreamStatus>, kinesis::Error> {
+    match client
+        .describe_stream_summary()
+        .stream_name(name)
+        .send()
+        .await
+    {
+        Ok(resp) => Ok(resp
+            .stream_description_summary
+            .and_then(|summary| summary.stream_status)),
+        Err(SdkError::ServiceError { err, .. })
+            if matches!(
+                err.kind,
+                DescribeStreamSummaryErrorKind::ResourceNotFoundError(_)
+            ) =>
+        {
+            Ok(None)
+        }
+        Err(e) => Err(e.into()),
+    }
+}
+
+/// Polls the stream's status until `done` returns true, backing off exponentially between calls.
+async fn wait_for<D, F>(
+    client: &Client,
+    name: &str,
+    timeout: Duration,
+    done: D,
+    mut on_status: F,
+) -> Result<(), WaitError>
+where
+    D: Fn(Option<&StreamStatus>) -> bool,
+    F: FnMut(Option<&StreamStatus>),
+{
+    let deadline = Instant::now() + timeout;
+    let mut delay = INITIAL_DELAY;
+    let mut last = None;
+    let mut first = true;
+
+    loop {
+        let status = stream_status(client, name).await?;
+
+        if first || status != last {
+            on_status(status.as_ref());
+            first = false;
+        }
+
+        if done(status.as_ref()) {
+            return Ok(());
+        }
+
+        last = status;
+
+        let now = Instant::now();
+        if now >= deadline {
+            return Err(WaitError::Timeout(name.to_string(), last));
+        }
+
+        // Don't sleep past the deadline; check one last time when it's reached.
+        sleep(delay.min(deadline - now)).await;
+        delay = (delay * 2).min(MAX_DELAY);
+    }
+}
+
+/// Waits until the stream is __ACTIVE__, and can be read and written.
+/// Calls `on_status` with the first status it sees, and again each time the status changes.
+pub async fn wait_for_stream_active<F>(
+    client: &Client,
+    name: &str,
+    timeout: Duration,
+    on_status: F,
+) -> Result<(), WaitError>
+where
+    F: FnMut(Option<&StreamStatus>),
+{
+    wait_for(
+        client,
+        name,
+        timeout,
+        |status| status == Some(&StreamStatus::Active),
+        on_status,
+    )
+    .await
+}
+
+/// Waits until the stream no longer exists.
+/// Calls `on_status` with the first status it sees, and again each time the status changes.
+pub async fn wait_for_stream_deleted<F>(
+    client: &Client,
+    name: &str,
+    timeout: Duration,
+    on_status: F,
+) -> Result<(), WaitError>
+where
+    F: FnMut(Option<&StreamStatus>),
+{
+    wait_for(client, name, timeout, |status| status.is_none(), on_status).await
+}
@@ -2,12 +2,11 @@
  * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
  * SPDX-License-Identifier: Apache-2.0.
  */
-use std::process;
-
-use kms::{Client, Config, Region};
+use kms::{Client, Config, Error, Region};
 
 use aws_types::region::ProvideRegion;
 
+use common::profile::ProfileCredentialsProvider;
 use structopt::StructOpt;
 use tracing_subscriber::fmt::format::FmtSpan;
 use tracing_subscriber::fmt::SubscriberBuilder;
@@ -18,21 +17,40 @@ struct Opt {
     #[structopt(short, long)]
     default_region: Option<String>,
 
+    /// A description of the key
+    #[structopt(long)]
+    description: Option<String>,
+
+    /// An alias for the key, such as alias/my-key
+    #[structopt(short, long)]
+    alias: Option<String>,
+
+    /// The AWS profile to use from the shared credentials file
+    #[structopt(long)]
+    profile: Option<String>,
+
     /// Activate verbose mode    
     #[structopt(short, long)]
     verbose: bool,
 }
-/// Creates an AWS KMS key.
+/// Creates an AWS KMS key, for encrypting and decrypting, and optionally gives it an alias.
 /// # Arguments
 ///
+/// * `[--description DESCRIPTION]` - A description of the key.
+/// * `[-a ALIAS]` - An alias for the key, which the other examples accept instead of the key ID.
+///   It must start with **alias/**, and can't start with **alias/aws/**.
 /// * `[-d DEFAULT-REGION]` - The region in which the client is created.
 ///    If not supplied, uses the value of the **AWS_DEFAULT_REGION** environment variable.
 ///    If the environment variable is not set, defaults to **us-west-2**.
+/// * `[--profile PROFILE]` - The AWS profile to use from the shared credentials file.
 /// * `[-v]` - Whether to display additional information.
 #[tokio::main]
-async fn main() {
+async fn main() -> Result<(), Error> {
     let Opt {
+        alias,
         default_region,
+        description,
+        profile,
         verbose,
     } = Opt::from_args();
 
@@ -45,6 +63,8 @@ async fn main() {
     if verbose {
         println!("KMS client version: {}\n", kms::PKG_VERSION);
         println!("Region:      {:?}", &region);
+        println!("Description: {:?}", &description);
+        println!("Alias:       {:?}", &alias);
 
         SubscriberBuilder::default()
             .with_env_filter("info")
@@ -52,22 +72,34 @@ async fn main() {
             .init();
     }
 
-    let conf = Config::builder().region(region).build();
-    let client = Client::from_conf(conf);
-
-    match client.create_key().send().await {
-        Ok(resp) => {
-            let id = resp
-                .key_metadata
-                .unwrap()
-                .key_id
-                .unwrap_or_else(|| String::from("No ID!"));
-            println!("Key: {}", id);
-        }
-        Err(e) => {
-            println!("Got error creating key:");
-            println!("{}", e);
-            process::exit(1);
-        }
-    };
+    let mut config = Config::builder().region(region);
+    if let Some(profile) = profile {
+        config = config.credentials_provider(ProfileCredentialsProvider::new(profile));
+    }
+    let client = Client::from_conf(config.build());
+
+    let resp = client
+        .create_key()
+        .set_description(description)
+        .send()
+        .await?;
+
+    let id = resp
+        .key_metadata
+        .and_then(|metadata| metadata.key_id)
+        .unwrap_or_else(|| String::from("No ID!"));
+    println!("Key: {}", id);
+
+    if let Some(alias) = alias {
+        client
+            .create_alias()
+            .alias_name(&alias)
+            .target_key_id(&id)
+            .send()
+            .await?;
+
+        println!("Alias: {}", alias);
+    }
+
+    Ok(())
 }
@@ -3,13 +3,15 @@
  * SPDX-License-Identifier: Apache-2.0.
  */
 
-use std::fs;
-use std::process;
+use std::error::Error;
 
 use kms::{Blob, Client, Config, Region};
+use kms_code_examples::envelope::{open, Sealed};
+use kms_code_examples::files::{decode_base64, read_input, write_output};
 
 use aws_types::region::ProvideRegion;
 
+use common::profile::ProfileCredentialsProvider;
 use structopt::StructOpt;
 use tracing_subscriber::fmt::format::FmtSpan;
 use tracing_subscriber::fmt::SubscriberBuilder;
@@ -20,34 +22,55 @@ struct Opt {
     #[structopt(short, long)]
     default_region: Option<String>,
 
-    /// Specifies the encryption key
+    /// Specifies the encryption key. Defaults to the key the text was encrypted with
     #[structopt(short, long)]
-    key: String,
+    key: Option<String>,
 
-    /// The name of the input file with encrypted text to decrypt
+    /// The name of the input file with encrypted text to decrypt. Defaults to stdin
     #[structopt(short, long)]
-    input: String,
+    input: Option<String>,
+
+    /// The name of the file to store the decrypted text in. Defaults to stdout
+    #[structopt(short, long)]
+    out: Option<String>,
+
+    /// Whether the input is a file that generate-data-key encrypted with a data key
+    #[structopt(short, long)]
+    envelope: bool,
+
+    /// The AWS profile to use from the shared credentials file
+    #[structopt(long)]
+    profile: Option<String>,
 
     /// Specifies whether to display additonal runtime informmation
     #[structopt(short, long)]
     verbose: bool,
 }
 
-/// Decrypts a string encrypted by AWS KMS.
+/// Decrypts the base64 text that **encrypt** wrote, or, with `-e`, a file that
+/// **generate-data-key** encrypted with a data key, and writes the plaintext to a file or stdout.
 /// # Arguments
 ///
-/// * `-k KEY` - The encryption key.
-/// * `-i INPUT` - The encrypted string.
+/// * `[-k KEY]` - The encryption key. If supplied, the text must have been encrypted with it.
+///   If not supplied, AWS KMS uses the key that the text was encrypted with.
+/// * `[-i INPUT]` - The file to decrypt. If not supplied, decrypts what's read from stdin.
+/// * `[-o OUT]` - The name of the file to store the plaintext in. If not supplied, writes it to stdout.
+/// * `[-e]` - Whether the input is a file that **generate-data-key** encrypted.
+///   The data key at the start of the file is decrypted with AWS KMS, and then decrypts the rest of the file.
 /// * `[-d DEFAULT-REGION]` - The region in which the client is created.
 ///    If not supplied, uses the value of the **AWS_DEFAULT_REGION** environment variable.
 ///    If the environment variable is not set, defaults to **us-west-2**.
-/// * `[-v]` - Whether to display additional information.
+/// * `[--profile PROFILE]` - The AWS profile to use from the shared credentials file.
+/// * `[-v]` - Whether to display additional information, to stderr.
 #[tokio::main]
-async fn main() {
+async fn main() -> Result<(), Box<dyn Error>> {
     let Opt {
         key,
         input,
+        out,
+        envelope,
         default_region,
+        profile,
         verbose,
     } = Opt::from_args();
 
@@ -57,52 +80,65 @@ async fn main() {
         .or_else(|| aws_types::region::default_provider().region())
         .unwrap_or_else(|| Region::new("us-west-2"));
 
+    // The plaintext can go to stdout, so the additional information goes to stderr.
     if verbose {
-        println!("KMS client version: {}\n", kms::PKG_VERSION);
-        println!("Region: {:?}", &region);
-        println!("Key:    {}", key);
-        println!("Input:  {}", input);
+        eprintln!("KMS client version: {}\n", kms::PKG_VERSION);
+        eprintln!("Region:   {:?}", &region);
+        eprintln!("Key:      {:?}", key);
+        eprintln!("Input:    {:?}", input);
+        eprintln!("Out:      {:?}", out);
+        eprintln!("Envelope: {}", envelope);
 
         SubscriberBuilder::default()
             .with_env_filter("info")
             .with_span_events(FmtSpan::CLOSE)
+            .with_writer(std::io::stderr)
             .init();
     }
 
-    let conf = Config::builder().region(region).build();
-    let client = Client::from_conf(conf);
+    let data = read_input(input.as_deref())?;
+
+    // An envelope-encrypted file holds the encrypted data key, which is what AWS KMS decrypts.
+    // Otherwise the input is base64, as encrypt writes it.
+    let sealed = if envelope {
+        Some(Sealed::from_bytes(&data)?)
+    } else {
+        None
+    };
 
-    // Open input text file and get contents as a string
-    // input is a base-64 encoded string, so decode it:
-    let data = fs::read_to_string(input)
-        .map(|input| {
-            base64::decode(input).expect("Input file does not contain valid base 64 characters.")
-        })
-        .map(Blob::new);
+    let ciphertext = match &sealed {
+        Some(sealed) => sealed.encrypted_key.clone(),
+        None => decode_base64(&data)?,
+    };
 
-    let resp = match client
+    let mut config = Config::builder().region(region);
+    if let Some(profile) = profile {
+        config = config.credentials_provider(ProfileCredentialsProvider::new(profile));
+    }
+    let client = Client::from_conf(config.build());
+
+    let resp = client
         .decrypt()
-        .key_id(key)
-        .ciphertext_blob(data.unwrap())
+        .set_key_id(key)
+        .ciphertext_blob(Blob::new(ciphertext))
         .send()
-        .await
-    {
-        Ok(output) => output,
-        Err(e) => {
-            eprintln!("Encryption failure: {}", e);
-            process::exit(1);
-        }
-    };
+        .await?;
 
-    let inner = resp.plaintext.unwrap();
-    let bytes = inner.as_ref();
+    let inner = resp.plaintext.ok_or("no plaintext returned")?;
 
-    let s = match String::from_utf8(bytes.to_vec()) {
-        Ok(v) => v,
-        Err(e) => panic!("Invalid UTF-8 sequence: {}", e),
+    if verbose {
+        eprintln!(
+            "Decrypted with key {}",
+            resp.key_id.as_deref().unwrap_or_default()
+        );
+    }
+
+    let plaintext = match &sealed {
+        Some(sealed) => open(inner.as_ref(), sealed)?,
+        None => inner.as_ref().to_vec(),
     };
 
-    println!();
-    println!("Decoded string:");
-    println!("{}", s);
+    write_output(out.as_deref(), &plaintext)?;
+
+    Ok(())
 }
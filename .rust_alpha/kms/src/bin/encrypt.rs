@@ -3,18 +3,21 @@
  * SPDX-License-Identifier: Apache-2.0.
  */
 
-use std::fs::File;
-use std::io::Write;
-use std::process;
+use std::error::Error;
 
 use kms::{Blob, Client, Config, Region};
+use kms_code_examples::files::{read_input, write_output};
 
 use aws_types::region::ProvideRegion;
 
+use common::profile::ProfileCredentialsProvider;
 use structopt::StructOpt;
 use tracing_subscriber::fmt::format::FmtSpan;
 use tracing_subscriber::fmt::SubscriberBuilder;
 
+/// The most bytes that AWS KMS encrypts itself. Larger data needs a data key.
+const MAX_PLAINTEXT_BYTES: usize = 4096;
+
 #[derive(Debug, StructOpt)]
 struct Opt {
     /// The region. Overrides environment variable AWS_DEFAULT_REGION.
@@ -26,34 +29,49 @@ struct Opt {
     key: String,
 
     /// Specifies the text to encrypt
+    #[structopt(short, long, conflicts_with = "input")]
+    text: Option<String>,
+
+    /// Specifies the name of the file to encrypt. Defaults to stdin
     #[structopt(short, long)]
-    text: String,
+    input: Option<String>,
 
-    /// Specifies the name of the file to store the encrypted text in
+    /// Specifies the name of the file to store the encrypted text in. Defaults to stdout
     #[structopt(short, long)]
-    out: String,
+    out: Option<String>,
+
+    /// The AWS profile to use from the shared credentials file
+    #[structopt(long)]
+    profile: Option<String>,
 
     /// Whether to display additional runtime information
     #[structopt(short, long)]
     verbose: bool,
 }
 
-/// Encrypts a string using an AWS KMS key.
+/// Encrypts a string, a file, or stdin using an AWS KMS key,
+/// and writes the ciphertext as base64 to a file or stdout.
 /// # Arguments
 ///
-/// * `-k KEY` - The KMS key.
-/// * `-o OUT` - The name of the file to store the encryped key in.
-/// * `-t TEXT` - The string to encrypt.
+/// * `-k KEY` - The KMS key, as a key ID, key ARN, alias name, or alias ARN.
+/// * `[-t TEXT]` - The string to encrypt.
+/// * `[-i INPUT]` - The file to encrypt, of up to 4 KB.
+///   If neither `-t` nor `-i` is supplied, encrypts what's read from stdin.
+/// * `[-o OUT]` - The name of the file to store the encrypted text in, as base64.
+///   If not supplied, writes it to stdout.
 /// * `[-d DEFAULT-REGION]` - The region in which the client is created.
 ///    If not supplied, uses the value of the **AWS_DEFAULT_REGION** environment variable.
 ///    If the environment variable is not set, defaults to **us-west-2**.
-/// * `[-v]` - Whether to display additional information.
+/// * `[--profile PROFILE]` - The AWS profile to use from the shared credentials file.
+/// * `[-v]` - Whether to display additional information, to stderr.
 #[tokio::main]
-async fn main() {
+async fn main() -> Result<(), Box<dyn Error>> {
     let Opt {
         key,
+        input,
         out,
         default_region,
+        profile,
         text,
         verbose,
     } = Opt::from_args();
@@ -64,43 +82,61 @@ async fn main() {
         .or_else(|| aws_types::region::default_provider().region())
         .unwrap_or_else(|| Region::new("us-west-2"));
 
+    // The ciphertext can go to stdout, so the additional information goes to stderr.
     if verbose {
-        println!("KMS client version: {}\n", kms::PKG_VERSION);
-        println!("Region: {:?}", &region);
-        println!("Key:    {}", key);
-        println!("Text:   {}", text);
-        println!("Out:    {}", out);
+        eprintln!("KMS client version: {}\n", kms::PKG_VERSION);
+        eprintln!("Region: {:?}", &region);
+        eprintln!("Key:    {}", key);
+        eprintln!("Text:   {:?}", text);
+        eprintln!("Input:  {:?}", input);
+        eprintln!("Out:    {:?}", out);
 
         SubscriberBuilder::default()
             .with_env_filter("info")
             .with_span_events(FmtSpan::CLOSE)
+            .with_writer(std::io::stderr)
             .init();
     }
 
-    let conf = Config::builder().region(region).build();
-    let client = Client::from_conf(conf);
+    let plaintext = match text {
+        Some(text) => text.into_bytes(),
+        None => read_input(input.as_deref())?,
+    };
 
-    let blob = Blob::new(text.as_bytes());
+    if plaintext.len() > MAX_PLAINTEXT_BYTES {
+        return Err(format!(
+            "AWS KMS encrypts up to {} bytes, and the input is {}; use generate-data-key to encrypt it with a data key",
+            MAX_PLAINTEXT_BYTES,
+            plaintext.len()
+        )
+        .into());
+    }
 
-    let resp = match client.encrypt().key_id(key).plaintext(blob).send().await {
-        Ok(output) => output,
-        Err(e) => {
-            eprintln!("Encryption failure: {}", e);
-            process::exit(1);
-        }
-    };
+    let mut config = Config::builder().region(region);
+    if let Some(profile) = profile {
+        config = config.credentials_provider(ProfileCredentialsProvider::new(profile));
+    }
+    let client = Client::from_conf(config.build());
+
+    let resp = client
+        .encrypt()
+        .key_id(key)
+        .plaintext(Blob::new(plaintext))
+        .send()
+        .await?;
 
     // Did we get an encrypted blob?
-    let blob = resp.ciphertext_blob.expect("Could not get encrypted text");
-    let bytes = blob.as_ref();
+    let blob = resp.ciphertext_blob.ok_or("no encrypted text returned")?;
 
-    let s = base64::encode(&bytes);
+    let s = base64::encode(blob.as_ref());
 
-    let mut ofile = File::create(&out).expect("unable to create file");
-    ofile.write_all(s.as_bytes()).expect("unable to write");
+    write_output(out.as_deref(), format!("{}\n", s).as_bytes())?;
 
     if verbose {
-        println!("Wrote the following to {}", &out);
-        println!("{}", s);
+        if let Some(out) = &out {
+            eprintln!("Wrote {} characters of base64 to {}", s.len(), out);
+        }
     }
+
+    Ok(())
 }
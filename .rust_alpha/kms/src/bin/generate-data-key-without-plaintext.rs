@@ -2,7 +2,7 @@
  * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
  * SPDX-License-Identifier: Apache-2.0.
  */
-use std::process;
+use std::error::Error;
 
 use kms::model::DataKeySpec;
 
@@ -10,6 +10,7 @@ use kms::{Client, Config, Region};
 
 use aws_types::region::ProvideRegion;
 
+use common::profile::ProfileCredentialsProvider;
 use structopt::StructOpt;
 use tracing_subscriber::fmt::format::FmtSpan;
 use tracing_subscriber::fmt::SubscriberBuilder;
@@ -24,6 +25,10 @@ struct Opt {
     #[structopt(short, long)]
     key: String,
 
+    /// The AWS profile to use from the shared credentials file
+    #[structopt(long)]
+    profile: Option<String>,
+
     /// Specifies whether to display additonal runtime information
     #[structopt(short, long)]
     verbose: bool,
@@ -36,12 +41,14 @@ struct Opt {
 /// * `[-d DEFAULT-REGION]` - The region in which the client is created.
 ///    If not supplied, uses the value of the **AWS_DEFAULT_REGION** environment variable.
 ///    If the environment variable is not set, defaults to **us-west-2**.
+/// * `[--profile PROFILE]` - The AWS profile to use from the shared credentials file.
 /// * `[-v]` - Whether to display additional information.
 #[tokio::main]
-async fn main() {
+async fn main() -> Result<(), Box<dyn Error>> {
     let Opt {
         key,
         default_region,
+        profile,
         verbose,
     } = Opt::from_args();
 
@@ -62,29 +69,27 @@ async fn main() {
             .init();
     }
 
-    let conf = Config::builder().region(region).build();
-    let client = Client::from_conf(conf);
+    let mut config = Config::builder().region(region);
+    if let Some(profile) = profile {
+        config = config.credentials_provider(ProfileCredentialsProvider::new(profile));
+    }
+    let client = Client::from_conf(config.build());
 
-    let resp = match client
+    let resp = client
         .generate_data_key_without_plaintext()
         .key_id(key)
         .key_spec(DataKeySpec::Aes256)
         .send()
-        .await
-    {
-        Ok(output) => output,
-        Err(e) => {
-            eprintln!("Encryption failure: {}", e);
-            process::exit(1);
-        }
-    };
+        .await?;
 
     // Did we get an encrypted blob?
-    let blob = resp.ciphertext_blob.expect("Could not get encrypted text");
+    let blob = resp.ciphertext_blob.ok_or("no encrypted text returned")?;
     let bytes = blob.as_ref();
 
     let s = base64::encode(&bytes);
 
     println!("\nData key:");
     println!("{}", s);
+
+    Ok(())
 }
@@ -2,13 +2,16 @@
  * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
  * SPDX-License-Identifier: Apache-2.0.
  */
-use std::process;
+use std::error::Error;
 
 use kms::model::DataKeySpec;
 use kms::{Client, Config, Region};
+use kms_code_examples::envelope::seal;
+use kms_code_examples::files::{read_input, write_output};
 
 use aws_types::region::ProvideRegion;
 
+use common::profile::ProfileCredentialsProvider;
 use structopt::StructOpt;
 use tracing_subscriber::fmt::format::FmtSpan;
 use tracing_subscriber::fmt::SubscriberBuilder;
@@ -23,24 +26,45 @@ struct Opt {
     #[structopt(short, long)]
     key: String,
 
+    /// The name of the file to encrypt with the data key. Defaults to stdin
+    #[structopt(short, long)]
+    input: Option<String>,
+
+    /// The name of the file to store the encrypted file in
+    #[structopt(short, long)]
+    out: String,
+
+    /// The AWS profile to use from the shared credentials file
+    #[structopt(long)]
+    profile: Option<String>,
+
     /// Specifies whether additonal runtime informmation is displayed
     #[structopt(short, long)]
     verbose: bool,
 }
 
-/// Creates an AWS KMS data key.
+/// Creates an AWS KMS data key, and uses it to encrypt a file of any size on the client,
+/// which is called envelope encryption. The file is encrypted with the plaintext copy of the data key,
+/// which is then thrown away, and the encrypted copy is stored at the start of the encrypted file.
+/// **decrypt -e** decrypts the file again.
 /// # Arguments
 ///
-/// * `[-k KEY]` - The name of the key.
+/// * `-k KEY` - The KMS key that encrypts the data key, as a key ID, key ARN, alias name, or alias ARN.
+/// * `[-i INPUT]` - The file to encrypt. If not supplied, encrypts what's read from stdin.
+/// * `-o OUT` - The name of the file to store the encrypted file in.
 /// * `[-d DEFAULT-REGION]` - The region in which the client is created.
 ///    If not supplied, uses the value of the **AWS_DEFAULT_REGION** environment variable.
 ///    If the environment variable is not set, defaults to **us-west-2**.
+/// * `[--profile PROFILE]` - The AWS profile to use from the shared credentials file.
 /// * `[-v]` - Whether to display additional information.
 #[tokio::main]
-async fn main() {
+async fn main() -> Result<(), Box<dyn Error>> {
     let Opt {
         key,
+        input,
+        out,
         default_region,
+        profile,
         verbose,
     } = Opt::from_args();
 
@@ -54,6 +78,8 @@ async fn main() {
         println!("KMS client version: {}\n", kms::PKG_VERSION);
         println!("Region: {:?}", &region);
         println!("Key:    {}", key);
+        println!("Input:  {:?}", input);
+        println!("Out:    {}", out);
 
         SubscriberBuilder::default()
             .with_env_filter("info")
@@ -61,29 +87,47 @@ async fn main() {
             .init();
     }
 
-    let conf = Config::builder().region(region).build();
-    let client = Client::from_conf(conf);
+    let plaintext = read_input(input.as_deref())?;
+
+    let mut config = Config::builder().region(region);
+    if let Some(profile) = profile {
+        config = config.credentials_provider(ProfileCredentialsProvider::new(profile));
+    }
+    let client = Client::from_conf(config.build());
 
-    let resp = match client
+    let resp = client
         .generate_data_key()
         .key_id(key)
         .key_spec(DataKeySpec::Aes256)
         .send()
-        .await
-    {
-        Ok(output) => output,
-        Err(e) => {
-            eprintln!("Encryption failure: {}", e);
-            process::exit(1);
-        }
-    };
-
-    // Did we get an encrypted blob?
-    let blob = resp.ciphertext_blob.expect("Could not get encrypted text");
-    let bytes = blob.as_ref();
-
-    let s = base64::encode(&bytes);
-
-    println!("\nData key:");
-    println!("{}", s);
+        .await?;
+
+    // The data key comes back twice: in plaintext, to encrypt with now,
+    // and encrypted under the KMS key, to keep with the encrypted file.
+    let encrypted_key = resp.ciphertext_blob.ok_or("no encrypted key returned")?;
+    let mut plaintext_key = resp
+        .plaintext
+        .ok_or("no plaintext key returned")?
+        .into_inner();
+
+    let sealed = seal(&plaintext_key, encrypted_key.as_ref(), &plaintext);
+
+    // The plaintext key isn't needed once the file is encrypted, so it's overwritten
+    // rather than left in memory.
+    plaintext_key.iter_mut().for_each(|byte| *byte = 0);
+
+    let sealed = sealed?;
+    write_output(Some(&out), &sealed.to_bytes())?;
+
+    println!(
+        "Encrypted {} bytes to {} with a data key from key {}",
+        plaintext.len(),
+        out,
+        resp.key_id.as_deref().unwrap_or_default()
+    );
+    println!();
+    println!("Encrypted data key, stored at the start of the file:");
+    println!("{}", base64::encode(encrypted_key.as_ref()));
+
+    Ok(())
 }
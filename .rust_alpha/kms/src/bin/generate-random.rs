@@ -3,12 +3,13 @@
  * SPDX-License-Identifier: Apache-2.0.
  */
 
-use std::process;
+use std::error::Error;
 
 use kms::{Client, Config, Region};
 
 use aws_types::region::ProvideRegion;
 
+use common::profile::ProfileCredentialsProvider;
 use structopt::StructOpt;
 use tracing_subscriber::fmt::format::FmtSpan;
 use tracing_subscriber::fmt::SubscriberBuilder;
@@ -23,6 +24,10 @@ struct Opt {
     #[structopt(short, long)]
     length: i32,
 
+    /// The AWS profile to use from the shared credentials file
+    #[structopt(long)]
+    profile: Option<String>,
+
     /// Specifies whether additonal runtime informmation is displayed
     #[structopt(short, long)]
     verbose: bool,
@@ -35,12 +40,14 @@ struct Opt {
 /// * `[-d DEFAULT-REGION]` - The region in which the client is created.
 ///    If not supplied, uses the value of the **AWS_DEFAULT_REGION** environment variable.
 ///    If the environment variable is not set, defaults to **us-west-2**.
+/// * `[--profile PROFILE]` - The AWS profile to use from the shared credentials file.
 /// * `[-v]` - Whether to display additional information.
 #[tokio::main]
-async fn main() {
+async fn main() -> Result<(), Box<dyn Error>> {
     let Opt {
         length,
         default_region,
+        profile,
         verbose,
     } = Opt::from_args();
 
@@ -52,12 +59,11 @@ async fn main() {
 
     // Trap out-of-range-values:
     match length {
-        1...1024 => {
+        1..=1024 => {
             println!("Generating a {} byte random string", length);
         }
         _ => {
-            println!("Length {} is not within range 1-1024", length);
-            process::exit(1);
+            return Err(format!("Length {} is not within range 1-1024", length).into());
         }
     }
 
@@ -72,29 +78,26 @@ async fn main() {
             .init();
     }
 
-    let conf = Config::builder().region(region).build();
-    let client = Client::from_conf(conf);
+    let mut config = Config::builder().region(region);
+    if let Some(profile) = profile {
+        config = config.credentials_provider(ProfileCredentialsProvider::new(profile));
+    }
+    let client = Client::from_conf(config.build());
 
-    let resp = match client
+    let resp = client
         .generate_random()
         .number_of_bytes(length)
         .send()
-        .await
-    {
-        Ok(output) => output,
-        Err(e) => {
-            println!("Got an error calling GenerateRandom:");
-            println!("{}", e);
-            process::exit(1);
-        }
-    };
+        .await?;
 
     // Did we get an encrypted blob?
-    let blob = resp.plaintext.expect("Could not get encrypted text");
+    let blob = resp.plaintext.ok_or("no random data returned")?;
     let bytes = blob.as_ref();
 
     let s = base64::encode(&bytes);
 
     println!("Data key:");
     println!("{}", s);
+
+    Ok(())
 }
@@ -3,25 +3,43 @@
  * SPDX-License-Identifier: Apache-2.0.
  */
 
+use std::error::Error;
+
 use aws_hyper::StandardClient;
 use kms::operation::GenerateRandom;
 use kms::Region;
+
+use common::profile::ProfileCredentialsProvider;
+use structopt::StructOpt;
 use tracing_subscriber::fmt::format::FmtSpan;
 use tracing_subscriber::fmt::SubscriberBuilder;
 
+#[derive(Debug, StructOpt)]
+struct Opt {
+    /// The AWS profile to use from the shared credentials file
+    #[structopt(long)]
+    profile: Option<String>,
+}
+
 /// Creates a random byte string that is cryptographically secure in __us-east-1__.
+/// # Arguments
+///
+/// * `[--profile PROFILE]` - The AWS profile to use from the shared credentials file.
 #[tokio::main]
-async fn main() {
+async fn main() -> Result<(), Box<dyn Error>> {
+    let Opt { profile } = Opt::from_args();
+
     SubscriberBuilder::default()
         .with_env_filter("info")
         .with_span_events(FmtSpan::CLOSE)
         .init();
-    let config = kms::Config::builder()
-        // region can also be loaded from AWS_DEFAULT_REGION, just remove this line.
-        .region(Region::new("us-east-1"))
-        // creds loaded from environment variables, or they can be hard coded.
-        // Other credential providers not currently supported
-        .build();
+    // region can also be loaded from AWS_DEFAULT_REGION, just remove this line.
+    let mut config = kms::Config::builder().region(Region::new("us-east-1"));
+    // creds loaded from environment variables, or from a profile in the shared credentials file.
+    if let Some(profile) = profile {
+        config = config.credentials_provider(ProfileCredentialsProvider::new(profile));
+    }
+    let config = config.build();
     // NB: This example uses the "low level internal API" for demonstration purposes
     // This is sometimes necessary to get precise control over behavior, but in most cases
     // using `kms::Client` is recommended.
@@ -30,13 +48,12 @@ async fn main() {
         .call(
             GenerateRandom::builder()
                 .number_of_bytes(64)
-                .build()
-                .expect("valid operation")
-                .make_operation(&config)
-                .expect("valid operation"),
+                .build()?
+                .make_operation(&config)?,
         )
-        .await
-        .expect("failed to generate random data");
+        .await?;
     println!("{:?}", data);
-    assert_eq!(data.plaintext.expect("should have data").as_ref().len(), 64);
+    assert_eq!(data.plaintext.map(|blob| blob.as_ref().len()), Some(64));
+
+    Ok(())
 }
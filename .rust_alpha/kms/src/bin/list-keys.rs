@@ -0,0 +1,155 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0.
+ */
+
+use std::collections::HashMap;
+
+use kms::{Client, Config, Error, Region};
+
+use aws_types::region::ProvideRegion;
+
+use common::profile::ProfileCredentialsProvider;
+use structopt::StructOpt;
+use tracing_subscriber::fmt::format::FmtSpan;
+use tracing_subscriber::fmt::SubscriberBuilder;
+
+#[derive(Debug, StructOpt)]
+struct Opt {
+    /// The region. Overrides environment variable AWS_DEFAULT_REGION.
+    #[structopt(short, long)]
+    default_region: Option<String>,
+
+    /// Whether to display each key's state, who manages it, and its description
+    #[structopt(short, long)]
+    long: bool,
+
+    /// The AWS profile to use from the shared credentials file
+    #[structopt(long)]
+    profile: Option<String>,
+
+    /// Whether to display additional information
+    #[structopt(short, long)]
+    verbose: bool,
+}
+
+/// Lists the AWS KMS keys in the Region, with their aliases.
+/// # Arguments
+///
+/// * `[-l]` - Whether to display each key's state, whether it's managed by you or by AWS,
+///   and its description. This describes each key in turn.
+/// * `[-d DEFAULT-REGION]` - The region in which the client is created.
+///    If not supplied, uses the value of the **AWS_DEFAULT_REGION** environment variable.
+///    If the environment variable is not set, defaults to **us-west-2**.
+/// * `[--profile PROFILE]` - The AWS profile to use from the shared credentials file.
+/// * `[-v]` - Whether to display additional information.
+#[tokio::main]
+async fn main() -> Result<(), Error> {
+    let Opt {
+        default_region,
+        long,
+        profile,
+        verbose,
+    } = Opt::from_args();
+
+    let region = default_region
+        .as_ref()
+        .map(|region| Region::new(region.clone()))
+        .or_else(|| aws_types::region::default_provider().region())
+        .unwrap_or_else(|| Region::new("us-west-2"));
+
+    if verbose {
+        println!("KMS client version: {}\n", kms::PKG_VERSION);
+        println!("Region: {:?}", &region);
+        println!("Long:   {}", long);
+
+        SubscriberBuilder::default()
+            .with_env_filter("info")
+            .with_span_events(FmtSpan::CLOSE)
+            .init();
+    }
+
+    let mut config = Config::builder().region(region);
+    if let Some(profile) = profile {
+        config = config.credentials_provider(ProfileCredentialsProvider::new(profile));
+    }
+    let client = Client::from_conf(config.build());
+
+    // ListKeys and ListAliases return a marker, and set truncated, while there are more to list.
+    let mut keys = Vec::new();
+    let mut marker = None;
+
+    loop {
+        let resp = client.list_keys().set_marker(marker).send().await?;
+
+        keys.extend(resp.keys.unwrap_or_default());
+
+        marker = resp.next_marker;
+        if !resp.truncated || marker.is_none() {
+            break;
+        }
+    }
+
+    let mut aliases: HashMap<String, Vec<String>> = HashMap::new();
+    let mut marker = None;
+
+    loop {
+        let resp = client.list_aliases().set_marker(marker).send().await?;
+
+        for alias in resp.aliases.unwrap_or_default() {
+            if let (Some(key_id), Some(name)) = (alias.target_key_id, alias.alias_name) {
+                aliases.entry(key_id).or_default().push(name);
+            }
+        }
+
+        marker = resp.next_marker;
+        if !resp.truncated || marker.is_none() {
+            break;
+        }
+    }
+
+    for key in &keys {
+        let key_id = key.key_id.as_deref().unwrap_or_default();
+        let names = aliases
+            .get(key_id)
+            .map(|names| names.join(", "))
+            .unwrap_or_default();
+
+        println!("{}  {}", key_id, names);
+
+        if long {
+            let metadata = client
+                .describe_key()
+                .key_id(key_id)
+                .send()
+                .await?
+                .key_metadata;
+
+            if let Some(metadata) = metadata {
+                println!(
+                    "    {} key, {}, {}",
+                    metadata
+                        .key_manager
+                        .as_ref()
+                        .map(|manager| manager.as_str())
+                        .unwrap_or_default(),
+                    metadata
+                        .key_state
+                        .as_ref()
+                        .map(|state| state.as_str())
+                        .unwrap_or_default(),
+                    metadata
+                        .description
+                        .as_deref()
+                        .filter(|description| !description.is_empty())
+                        .unwrap_or("no description")
+                );
+            }
+        }
+    }
+
+    println!();
+    println!("Found {} keys", keys.len());
+
+    Ok(())
+}
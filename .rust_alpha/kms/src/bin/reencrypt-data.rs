@@ -3,15 +3,16 @@
  * SPDX-License-Identifier: Apache-2.0.
  */
 
+use std::error::Error;
 use std::fs;
 use std::fs::File;
 use std::io::Write;
-use std::process;
 
 use kms::{Blob, Client, Config, Region};
 
 use aws_types::region::ProvideRegion;
 
+use common::profile::ProfileCredentialsProvider;
 use structopt::StructOpt;
 use tracing_subscriber::fmt::format::FmtSpan;
 use tracing_subscriber::fmt::SubscriberBuilder;
@@ -34,6 +35,10 @@ struct Opt {
     /// The name of the output file containing the reencrypted text
     #[structopt(short, long)]
     output_file: String,
+    /// The AWS profile to use from the shared credentials file
+    #[structopt(long)]
+    profile: Option<String>,
+
     /// Whether to display additonal runtime information
     #[structopt(short, long)]
     verbose: bool,
@@ -49,15 +54,17 @@ struct Opt {
 /// * `[-d DEFAULT-REGION]` - The region in which the client is created.
 ///    If not supplied, uses the value of the **AWS_DEFAULT_REGION** environment variable.
 ///    If the environment variable is not set, defaults to **us-west-2**.
+/// * `[--profile PROFILE]` - The AWS profile to use from the shared credentials file.
 /// * `[-v]` - Whether to display additional information.
 #[tokio::main]
-async fn main() {
+async fn main() -> Result<(), Box<dyn Error>> {
     let Opt {
         first_key,
         new_key,
         input_file,
         output_file,
         default_region,
+        profile,
         verbose,
     } = Opt::from_args();
 
@@ -81,40 +88,34 @@ async fn main() {
             .init();
     }
 
-    let conf = Config::builder().region(region).build();
-    let client = Client::from_conf(conf);
+    let mut config = Config::builder().region(region);
+    if let Some(profile) = profile {
+        config = config.credentials_provider(ProfileCredentialsProvider::new(profile));
+    }
+    let client = Client::from_conf(config.build());
 
     // Get blob from input file
     // Open input text file and get contents as a string
     // input is a base-64 encoded string, so decode it:
-    let data = fs::read_to_string(input_file)
-        .map(|input_file| base64::decode(input_file).expect("invalid base 64"))
-        .map(Blob::new);
+    let data = Blob::new(base64::decode(fs::read_to_string(input_file)?)?);
 
-    let resp = match client
+    let resp = client
         .re_encrypt()
-        .ciphertext_blob(data.unwrap())
+        .ciphertext_blob(data)
         .source_key_id(first_key)
         .destination_key_id(new_key)
         .send()
-        .await
-    {
-        Ok(output) => output,
-        Err(e) => {
-            eprintln!("Encryption failure: {}", e);
-            process::exit(1);
-        }
-    };
+        .await?;
 
     // Did we get an encrypted blob?
-    let blob = resp.ciphertext_blob.expect("Could not get encrypted text");
+    let blob = resp.ciphertext_blob.ok_or("no encrypted text returned")?;
     let bytes = blob.as_ref();
 
     let s = base64::encode(&bytes);
     let o = &output_file;
 
-    let mut ofile = File::create(o).expect("unable to create file");
-    ofile.write_all(s.as_bytes()).expect("unable to write");
+    let mut ofile = File::create(o)?;
+    ofile.write_all(s.as_bytes())?;
 
     if verbose {
         println!("Wrote the following to {}:", output_file);
@@ -122,4 +123,6 @@ async fn main() {
     } else {
         println!("Wrote base64-encoded output to {}", output_file);
     }
+
+    Ok(())
 }
@@ -0,0 +1,169 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0.
+ */
+
+//! Envelope encryption of local files with a data key from AWS KMS.
+//!
+//! AWS KMS encrypts at most 4 KB itself, so a larger file is encrypted on the client with a data key:
+//! GenerateDataKey returns the key twice, as plaintext and encrypted under a KMS key. The file is
+//! encrypted with the plaintext key, using AES-256 in GCM mode, which also detects any change to the
+//! ciphertext, and the plaintext key is then dropped. Only the encrypted key is kept, at the start of
+//! the encrypted file, so that whoever can decrypt with the KMS key can decrypt the file:
+//!
+//! ```ignore
+//! let sealed = seal(&plaintext_key, &encrypted_key, &data)?;
+//! fs::write(&out, sealed.to_bytes())?;
+//! // Later:
+//! let sealed = Sealed::from_bytes(&fs::read(&out)?)?;
+//! let plaintext_key = /* Decrypt sealed.encrypted_key with AWS KMS */;
+//! let data = open(&plaintext_key, &sealed)?;
+//! ```
+//!
+//! An encrypted file holds, in order: the bytes __KMSE__, a version byte, the length of the
+//! encrypted key as two big-endian bytes, the encrypted key, the IV, and the ciphertext with
+//! the GCM tag appended.
+
+use std::convert::TryInto;
+use std::error::Error;
+use std::fmt;
+
+use aes_gcm::aead::{Aead, NewAead};
+use aes_gcm::{Aes256Gcm, Key, Nonce};
+use rand::RngCore;
+
+/// The bytes that every encrypted file starts with.
+const MAGIC: &[u8; 4] = b"KMSE";
+/// The version of the format that `Sealed::to_bytes` writes.
+const VERSION: u8 = 1;
+
+/// The length of the IV, in bytes.
+pub const IV_LEN: usize = 12;
+/// The length of a data key, in bytes.
+pub const KEY_LEN: usize = 32;
+
+/// Why a file couldn't be encrypted or decrypted.
+#[derive(Debug)]
+pub enum EnvelopeError {
+    /// The file wasn't written by `Sealed::to_bytes`, or was cut short.
+    Malformed(String),
+    /// AES-GCM failed; when decrypting, the data key is wrong or the file was changed.
+    Crypto,
+}
+
+impl fmt::Display for EnvelopeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            EnvelopeError::Malformed(reason) => write!(f, "not an encrypted file: {}", reason),
+            EnvelopeError::Crypto => write!(
+                f,
+                "couldn't decrypt the file: the data key is wrong, or the file was changed"
+            ),
+        }
+    }
+}
+
+impl Error for EnvelopeError {}
+
+/// An encrypted file: the encrypted data key, the IV, and the ciphertext with the GCM tag appended.
+#[derive(Debug, PartialEq)]
+pub struct Sealed {
+    /// The data key, encrypted under the KMS key.
+    pub encrypted_key: Vec<u8>,
+    pub iv: [u8; IV_LEN],
+    pub ciphertext: Vec<u8>,
+}
+
+impl Sealed {
+    /// Writes the encrypted file.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = Vec::with_capacity(
+            MAGIC.len() + 3 + self.encrypted_key.len() + IV_LEN + self.ciphertext.len(),
+        );
+        bytes.extend_from_slice(MAGIC);
+        bytes.push(VERSION);
+        bytes.extend_from_slice(&(self.encrypted_key.len() as u16).to_be_bytes());
+        bytes.extend_from_slice(&self.encrypted_key);
+        bytes.extend_from_slice(&self.iv);
+        bytes.extend_from_slice(&self.ciphertext);
+        bytes
+    }
+
+    /// Reads an encrypted file.
+    pub fn from_bytes(bytes: &[u8]) -> Result<Sealed, EnvelopeError> {
+        let malformed = |reason: &str| EnvelopeError::Malformed(reason.to_string());
+
+        let rest = bytes
+            .strip_prefix(MAGIC)
+            .ok_or_else(|| malformed("it doesn't start with KMSE"))?;
+
+        let (version, rest) = rest
+            .split_first()
+            .ok_or_else(|| malformed("it has no version"))?;
+        if *version != VERSION {
+            return Err(EnvelopeError::Malformed(format!(
+                "version {} isn't supported",
+                version
+            )));
+        }
+
+        if rest.len() < 2 {
+            return Err(malformed("it has no key length"));
+        }
+        let (length, rest) = rest.split_at(2);
+        let length = u16::from_be_bytes([length[0], length[1]]) as usize;
+
+        if rest.len() < length + IV_LEN {
+            return Err(malformed("it's shorter than its key and IV"));
+        }
+        let (encrypted_key, rest) = rest.split_at(length);
+        let (iv, ciphertext) = rest.split_at(IV_LEN);
+
+        Ok(Sealed {
+            encrypted_key: encrypted_key.to_vec(),
+            iv: iv.try_into().expect("the IV was split at its length"),
+            ciphertext: ciphertext.to_vec(),
+        })
+    }
+}
+
+/// Returns the AES-GCM cipher for a data key, which must be 256 bits.
+fn cipher(data_key: &[u8]) -> Result<Aes256Gcm, EnvelopeError> {
+    if data_key.len() != KEY_LEN {
+        return Err(EnvelopeError::Malformed(format!(
+            "the data key is {} bytes, not {}",
+            data_key.len(),
+            KEY_LEN
+        )));
+    }
+
+    Ok(Aes256Gcm::new(Key::from_slice(data_key)))
+}
+
+/// Encrypts the plaintext with a 256-bit data key and a random IV,
+/// and keeps the encrypted copy of the data key with it.
+pub fn seal(
+    data_key: &[u8],
+    encrypted_key: &[u8],
+    plaintext: &[u8],
+) -> Result<Sealed, EnvelopeError> {
+    let mut iv = [0u8; IV_LEN];
+    rand::thread_rng().fill_bytes(&mut iv);
+
+    let ciphertext = cipher(data_key)?
+        .encrypt(Nonce::from_slice(&iv), plaintext)
+        .map_err(|_| EnvelopeError::Crypto)?;
+
+    Ok(Sealed {
+        encrypted_key: encrypted_key.to_vec(),
+        iv,
+        ciphertext,
+    })
+}
+
+/// Decrypts what `seal` encrypted, checking the GCM tag.
+pub fn open(data_key: &[u8], sealed: &Sealed) -> Result<Vec<u8>, EnvelopeError> {
+    cipher(data_key)?
+        .decrypt(Nonce::from_slice(&sealed.iv), sealed.ciphertext.as_ref())
+        .map_err(|_| EnvelopeError::Crypto)
+}
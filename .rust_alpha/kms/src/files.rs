@@ -0,0 +1,50 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0.
+ */
+
+//! Reads the input of an example from a file or stdin, and writes its output to a file or stdout,
+//! so the examples can be chained in a pipeline:
+//!
+//! ```sh
+//! echo "Hello" | cargo run --bin encrypt -- -k KEY | cargo run --bin decrypt
+//! ```
+
+use std::fs;
+use std::io::{self, Read, Write};
+
+/// Reads the whole file, or stdin if no file is named.
+pub fn read_input(file: Option<&str>) -> Result<Vec<u8>, String> {
+    match file {
+        Some(file) => fs::read(file).map_err(|e| format!("couldn't read {}: {}", file, e)),
+        None => {
+            let mut data = Vec::new();
+            io::stdin()
+                .read_to_end(&mut data)
+                .map_err(|e| format!("couldn't read stdin: {}", e))?;
+            Ok(data)
+        }
+    }
+}
+
+/// Writes the data to the file, or to stdout if no file is named.
+pub fn write_output(file: Option<&str>, data: &[u8]) -> Result<(), String> {
+    match file {
+        Some(file) => fs::write(file, data).map_err(|e| format!("couldn't write {}: {}", file, e)),
+        None => io::stdout()
+            .write_all(data)
+            .and_then(|_| io::stdout().flush())
+            .map_err(|e| format!("couldn't write stdout: {}", e)),
+    }
+}
+
+/// Decodes base64 text, ignoring the line breaks and spaces that copying it can add.
+pub fn decode_base64(text: &[u8]) -> Result<Vec<u8>, String> {
+    let text: Vec<u8> = text
+        .iter()
+        .copied()
+        .filter(|byte| !byte.is_ascii_whitespace())
+        .collect();
+
+    base64::decode(&text).map_err(|e| format!("the input isn't base64: {}", e))
+}
@@ -4,14 +4,19 @@
  */
 
 // types from the Rust standard library
-use std::{process, str};
+use std::error::Error;
+use std::fs;
+use std::io::{self, Read};
+use std::str;
 
 // For command-line arguments.
+use common::profile::ProfileCredentialsProvider;
 use structopt::StructOpt;
 
 // types from the AWS SDK for Rust
 use aws_types::region::ProvideRegion;
-use lambda::{error::InvokeErrorKind, Client, Config, Region, SdkError};
+use lambda::model::LogType;
+use lambda::{error::InvokeErrorKind, Blob, Client, Config, Region, SdkError};
 
 // types from other third-party crates
 use tracing_subscriber::fmt::format::FmtSpan;
@@ -27,24 +32,49 @@ struct Opt {
     #[structopt(short, long)]
     arn: String,
 
+    /// The file containing the JSON payload to send to the function, or - to read it from stdin
+    #[structopt(short, long)]
+    payload: Option<String>,
+
+    /// The AWS profile to use from the shared credentials file
+    #[structopt(long)]
+    profile: Option<String>,
+
     /// Whether to display additional runtime information
     #[structopt(short, long)]
     verbose: bool,
 }
 
-/// Invokes a Lambda function by its ARN.
+/// Reads the payload from a file, or from stdin if the file name is `-`.
+fn read_payload(file: &str) -> io::Result<Vec<u8>> {
+    if file == "-" {
+        let mut payload = Vec::new();
+        io::stdin().read_to_end(&mut payload)?;
+        Ok(payload)
+    } else {
+        fs::read(file)
+    }
+}
+
+/// Invokes a Lambda function by its ARN, and displays its response and the end of its log.
 /// # Arguments
 ///
 /// * `-a ARN` - The ARN of the Lambda function.
+/// * `[-p PAYLOAD]` - The file containing the JSON payload to send to the function.
+///    If **-**, the payload is read from stdin.
+///    If not supplied, the function is invoked without a payload.
 /// * `[-d DEFAULT-REGION]` - The region in which the client is created.
 ///    If not supplied, uses the value of the **AWS_DEFAULT_REGION** environment variable.
 ///    If the environment variable is not set, defaults to **us-west-2**.
+/// * `[--profile PROFILE]` - The AWS profile to use from the shared credentials file.
 /// * `[-v]` - Whether to display additional information.
 #[tokio::main]
-async fn main() {
+async fn main() -> Result<(), Box<dyn Error>> {
     let Opt {
         arn,
         default_region,
+        payload,
+        profile,
         verbose,
     } = Opt::from_args();
 
@@ -58,6 +88,7 @@ async fn main() {
         println!("Lambda client version: {}", lambda::PKG_VERSION);
         println!("Region:                {:?}", &region);
         println!("Lambda function ARN:   {}", arn);
+        println!("Payload:               {:?}", payload);
 
         SubscriberBuilder::default()
             .with_env_filter("info")
@@ -68,16 +99,24 @@ async fn main() {
     // The AWS SDK for Rust service clients can be instantiated in a few
     // different ways. The way we're instantiating it here is to first build
     // a Config struct, then pass the Config to the Client.
-    let config = Config::builder().region(region).build();
-    let client = Client::from_conf(config);
+    let mut config = Config::builder().region(region);
+    if let Some(profile) = profile {
+        config = config.credentials_provider(ProfileCredentialsProvider::new(profile));
+    }
+    let client = Client::from_conf(config.build());
+
+    // Read the payload before calling the service, so that a missing file
+    // is reported without invoking the function.
+    let payload = payload.as_deref().map(read_payload).transpose()?;
 
     // we call the `invoke()` method on the client. The term 'invoke' is a bit
     // overloaded in the industry, but here it refers to a method named
     // 'Invoke' as part of the AWS Lambda API.
     // <https://docs.aws.amazon.com/lambda/latest/dg/API_Invoke.html>
     //
-    // The Invoke API accepts several arguments, but we're only going to
-    // include the required arguments in this example. The function name can
+    // The Invoke API accepts several arguments. Besides the required
+    // function name, we're sending the payload, if there is one, and asking
+    // for the tail of the execution log. The function name can
     // be the human friendly name with or without a version alias. It can be a
     // full ARN, or a partial ARN that includes the AWS account ID. following
     // are examples of each that will point to the same AWS resource.
@@ -89,7 +128,14 @@ async fn main() {
     //
     // We are going to use the full ARN to prevent any ambiguity in which
     // function will be invoked.
-    match client.invoke().function_name(arn).send().await {
+    match client
+        .invoke()
+        .function_name(arn)
+        .set_payload(payload.map(Blob::new))
+        .log_type(LogType::Tail)
+        .send()
+        .await
+    {
         // If the API call returns without an error, the Lambda Invoke API
         // returns a response object containing the following:
         //
@@ -109,15 +155,29 @@ async fn main() {
         //
         // Payload: The response from the function, or an error object.
         //
-        // For our example, we are just going to interact with the payload
-        // portion of the response
+        // For our example, we are going to display the payload, whether
+        // the function failed, and the log.
         Ok(resp) => {
             // if the payload is not None, then we're safe to try and decode it
             // from the utf encoding we get back from the service into
             // something a bit more human friendly.
             if let Some(blob) = resp.payload {
-                let s = str::from_utf8(blob.as_ref()).expect("invalid utf-8");
-                println!("Response: {:?}", s);
+                let s = str::from_utf8(blob.as_ref())?;
+                println!("Response: {}", s);
+            }
+
+            // An error thrown by the function code isn't an error of the
+            // Invoke API, so the call still succeeds. The payload then holds
+            // the error message and stack trace.
+            if let Some(function_error) = resp.function_error {
+                println!("Function error: {}", function_error);
+            }
+
+            // The log is only returned because we asked for LogType::Tail.
+            if let Some(log_result) = resp.log_result {
+                let log = base64::decode(log_result)?;
+                println!("Log tail:");
+                println!("{}", String::from_utf8_lossy(&log));
             }
         }
 
@@ -130,21 +190,20 @@ async fn main() {
         // SDK wouldn't know how to 'retry', such as when the resource doesn't
         // exist.
         //
-        // For our example, we will simply print that the function doesn't
-        // exist and return a non-zero exit code to indicate the failure.
+        // For our example, we will return an error saying that the function
+        // doesn't exist. Returning an error from main prints it and exits
+        // with a non-zero exit code to indicate the failure.
         Err(SdkError::ServiceError { err, .. })
             if matches!(err.kind, InvokeErrorKind::ResourceNotFoundError(_)) =>
         {
-            println!("This lambda function does not exist: {}", err);
-            process::exit(1);
+            return Err(format!("This lambda function does not exist: {}", err).into());
         }
         // For any other kind of error, we will want to know more information
         // about it so that we can better understand how to handle it in the
-        // future. We are going to print the error message and again return a
-        // non-zero status code to indicate the failure.
-        Err(err) => {
-            println!("Got an error invoking the function: {}", err);
-            process::exit(1);
-        }
+        // future. We are going to return the error as is, which again exits
+        // with a non-zero status code to indicate the failure.
+        Err(err) => return Err(err.into()),
     };
+
+    Ok(())
 }
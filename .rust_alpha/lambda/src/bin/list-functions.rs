@@ -3,12 +3,11 @@
  * SPDX-License-Identifier: Apache-2.0.
  */
 
-use std::process;
-
 // For command-line arguments.
+use common::profile::ProfileCredentialsProvider;
 use structopt::StructOpt;
 
-use lambda::{Client, Config, Region};
+use lambda::{Client, Config, Error, Region};
 
 use aws_types::region::ProvideRegion;
 
@@ -21,22 +20,28 @@ struct Opt {
     #[structopt(short, long)]
     default_region: Option<String>,
 
+    /// The AWS profile to use from the shared credentials file
+    #[structopt(long)]
+    profile: Option<String>,
+
     /// Whether to display additional runtime information
     #[structopt(short, long)]
     verbose: bool,
 }
 
-/// Lists the ARNs of your Lambda functions.
+/// Lists the ARNs of all of your Lambda functions.
 /// # Arguments
 ///
 /// * `[-d DEFAULT-REGION]` - The region in which the client is created.
 ///    If not supplied, uses the value of the **AWS_DEFAULT_REGION** environment variable.
 ///    If the environment variable is not set, defaults to **us-west-2**.
+/// * `[--profile PROFILE]` - The AWS profile to use from the shared credentials file.
 /// * `[-v]` - Whether to display additional information.
 #[tokio::main]
-async fn main() {
+async fn main() -> Result<(), Error> {
     let Opt {
         default_region,
+        profile,
         verbose,
     } = Opt::from_args();
 
@@ -56,30 +61,37 @@ async fn main() {
             .init();
     }
 
-    let config = Config::builder().region(region).build();
-    let client = Client::from_conf(config);
+    let mut config = Config::builder().region(region);
+    if let Some(profile) = profile {
+        config = config.credentials_provider(ProfileCredentialsProvider::new(profile));
+    }
+    let client = Client::from_conf(config.build());
 
-    match client.list_functions().send().await {
-        Ok(resp) => {
-            println!("Function ARNs:");
+    println!("Function ARNs:");
 
-            let functions = resp.functions.unwrap_or_default();
+    // ListFunctions returns at most 50 functions per call,
+    // so keep calling it with the returned marker until there isn't one.
+    let mut marker = None;
+    let mut count = 0;
 
-            for function in &functions {
-                match &function.function_arn {
-                    None => {}
-                    Some(f) => {
-                        println!("{}", f);
-                    }
-                }
-            }
+    loop {
+        let resp = client.list_functions().set_marker(marker).send().await?;
+
+        for function in resp.functions.unwrap_or_default() {
+            count += 1;
 
-            println!("Found {} functions", functions.len());
+            if let Some(f) = function.function_arn {
+                println!("{}", f);
+            }
         }
-        Err(e) => {
-            println!("Got an error listing functions:");
-            println!("{}", e);
-            process::exit(1);
+
+        marker = resp.next_marker;
+        if marker.is_none() {
+            break;
         }
-    };
+    }
+
+    println!("Found {} functions", count);
+
+    Ok(())
 }
@@ -0,0 +1,119 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0.
+ */
+
+use std::error::Error;
+use std::fs;
+
+use common::profile::ProfileCredentialsProvider;
+use structopt::StructOpt;
+
+use aws_types::region::ProvideRegion;
+use lambda::{Blob, Client, Config, Region};
+
+use tracing_subscriber::fmt::format::FmtSpan;
+use tracing_subscriber::fmt::SubscriberBuilder;
+
+#[derive(Debug, StructOpt)]
+struct Opt {
+    /// The region. Overrides environment variable AWS_DEFAULT_REGION.
+    #[structopt(short, long)]
+    default_region: Option<String>,
+
+    /// The name or ARN of the Lambda function
+    #[structopt(short, long)]
+    function: String,
+
+    /// The ZIP file containing the new code
+    #[structopt(short, long)]
+    zip_file: String,
+
+    /// Whether to publish a new version of the function
+    #[structopt(short, long)]
+    publish: bool,
+
+    /// The AWS profile to use from the shared credentials file
+    #[structopt(long)]
+    profile: Option<String>,
+
+    /// Whether to display additional runtime information
+    #[structopt(short, long)]
+    verbose: bool,
+}
+
+/// Replaces the code of a Lambda function with the contents of a local ZIP file.
+/// # Arguments
+///
+/// * `-f FUNCTION` - The name or ARN of the Lambda function.
+/// * `-z ZIP-FILE` - The ZIP file containing the new code.
+///    The file must be smaller than 50 MB. Use Amazon S3 for larger deployment packages.
+/// * `[-p]` - Whether to publish a new version of the function.
+/// * `[-d DEFAULT-REGION]` - The region in which the client is created.
+///    If not supplied, uses the value of the **AWS_DEFAULT_REGION** environment variable.
+///    If the environment variable is not set, defaults to **us-west-2**.
+/// * `[--profile PROFILE]` - The AWS profile to use from the shared credentials file.
+/// * `[-v]` - Whether to display additional information.
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn Error>> {
+    let Opt {
+        default_region,
+        function,
+        profile,
+        publish,
+        verbose,
+        zip_file,
+    } = Opt::from_args();
+
+    let region = default_region
+        .as_ref()
+        .map(|region| Region::new(region.clone()))
+        .or_else(|| aws_types::region::default_provider().region())
+        .unwrap_or_else(|| Region::new("us-west-2"));
+
+    if verbose {
+        println!("Lambda client version: {}", lambda::PKG_VERSION);
+        println!("Region:                {:?}", &region);
+        println!("Function:              {}", &function);
+        println!("ZIP file:              {}", &zip_file);
+        println!("Publish:               {}", publish);
+
+        SubscriberBuilder::default()
+            .with_env_filter("info")
+            .with_span_events(FmtSpan::CLOSE)
+            .init();
+    }
+
+    let mut config = Config::builder().region(region);
+    if let Some(profile) = profile {
+        config = config.credentials_provider(ProfileCredentialsProvider::new(profile));
+    }
+    let client = Client::from_conf(config.build());
+
+    let code = fs::read(&zip_file)?;
+
+    let resp = client
+        .update_function_code()
+        .function_name(&function)
+        .zip_file(Blob::new(code))
+        .publish(publish)
+        .send()
+        .await?;
+
+    println!(
+        "Updated {}",
+        resp.function_arn.as_deref().unwrap_or(&function)
+    );
+    println!("Version:       {}", resp.version.unwrap_or_default());
+    println!("Code size:     {} bytes", resp.code_size);
+    println!("Code SHA-256:  {}", resp.code_sha256.unwrap_or_default());
+    println!(
+        "Update status: {}",
+        resp.last_update_status
+            .as_ref()
+            .map(|status| status.as_str())
+            .unwrap_or_default()
+    );
+
+    Ok(())
+}
@@ -0,0 +1,447 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0.
+ */
+
+use std::error::Error;
+use std::fmt;
+use std::fs;
+use std::path::Path;
+use std::sync::Arc;
+
+use polly::model::{Engine, LanguageCode, OutputFormat, Voice};
+use polly::{Client, Config, Region};
+
+use aws_types::region::{EnvironmentProvider, ProvideRegion};
+
+use common::output;
+use common::profile::ProfileCredentialsProvider;
+use serde::Serialize;
+use structopt::StructOpt;
+use tokio::sync::Semaphore;
+use tracing_subscriber::fmt::format::FmtSpan;
+use tracing_subscriber::fmt::SubscriberBuilder;
+
+/// The text to synthesize if no file is supplied.
+const SAMPLE_TEXT: &str = "Hello! This is how I sound. \
+    On Tuesday, 3 March 2020, the temperature in Seattle was 12 degrees, with a 40% chance of rain.";
+
+#[derive(Debug, StructOpt)]
+struct Opt {
+    /// The region
+    #[structopt(short, long)]
+    region: Option<String>,
+
+    /// The file containing the text to synthesize. Defaults to a short sample
+    #[structopt(short, long)]
+    filename: Option<String>,
+
+    /// The language of the voices to compare
+    #[structopt(short, long, default_value = "en-US")]
+    language_code: String,
+
+    /// A voice to compare. Repeat to compare several. Defaults to every voice for the language
+    #[structopt(long)]
+    voice: Vec<String>,
+
+    /// An engine to compare, standard or neural. Repeat to compare both. Defaults to both
+    #[structopt(short, long)]
+    engine: Vec<String>,
+
+    /// The directory to write the audio files and the index page to
+    #[structopt(short, long, default_value = "voices")]
+    directory: String,
+
+    /// How many voices to synthesize at once
+    #[structopt(short, long, default_value = "4")]
+    concurrency: usize,
+
+    /// The format of the output, text or json
+    #[structopt(long, default_value = "text")]
+    output: output::OutputFormat,
+
+    /// The AWS profile to use from the shared credentials file
+    #[structopt(long)]
+    profile: Option<String>,
+
+    /// Whether to show additional output
+    #[structopt(short, long)]
+    verbose: bool,
+}
+
+/// An audio file of one voice with one engine.
+#[derive(Debug, Serialize)]
+struct Sample {
+    voice: String,
+    gender: String,
+    engine: String,
+    /// The path of the audio file, relative to the directory.
+    file: String,
+    bytes: usize,
+}
+
+/// A voice and engine that couldn't be synthesized.
+#[derive(Debug, Serialize)]
+struct Failure {
+    voice: String,
+    engine: String,
+    error: String,
+}
+
+/// The samples that were written, and the index page that plays them.
+#[derive(Debug, Serialize)]
+struct Comparison {
+    directory: String,
+    index: String,
+    language_code: String,
+    samples: Vec<Sample>,
+    failures: Vec<Failure>,
+}
+
+impl fmt::Display for Comparison {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "Wrote {} {} samples to {}. Open {} to listen to them",
+            self.samples.len(),
+            self.language_code,
+            self.directory,
+            self.index
+        )?;
+
+        for sample in &self.samples {
+            write!(
+                f,
+                "\n  {:<12}  {:<8}  {}",
+                sample.voice, sample.engine, sample.file
+            )?;
+        }
+
+        for failure in &self.failures {
+            write!(
+                f,
+                "\nCouldn't synthesize {} with the {} engine: {}",
+                failure.voice, failure.engine, failure.error
+            )?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Escapes the characters that mean something in HTML.
+fn escape_html(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+/// Writes a page with an audio player for each sample, so the voices can be played one after another.
+fn index_page(language_code: &str, text: &str, samples: &[Sample]) -> String {
+    let rows: String = samples
+        .iter()
+        .map(|sample| {
+            format!(
+                "      <tr><td>{}</td><td>{}</td><td>{}</td>\
+                 <td><audio controls preload=\"none\" src=\"{}\"></audio></td></tr>\n",
+                escape_html(&sample.voice),
+                escape_html(&sample.gender),
+                escape_html(&sample.engine),
+                escape_html(&sample.file)
+            )
+        })
+        .collect();
+
+    format!(
+        "<!DOCTYPE html>
+<html>
+  <head>
+    <meta charset=\"utf-8\">
+    <title>Amazon Polly {language} voices</title>
+  </head>
+  <body>
+    <h1>Amazon Polly {language} voices</h1>
+    <blockquote>{text}</blockquote>
+    <table>
+      <tr><th>Voice</th><th>Gender</th><th>Engine</th><th>Sample</th></tr>
+{rows}    </table>
+  </body>
+</html>
+",
+        language = escape_html(language_code),
+        text = escape_html(text),
+        rows = rows
+    )
+}
+
+/// Gets the voices for the language, a page at a time.
+async fn describe_voices(
+    client: &Client,
+    language_code: &LanguageCode,
+) -> Result<Vec<Voice>, polly::Error> {
+    let mut voices = Vec::new();
+    let mut next_token = None;
+
+    loop {
+        let resp = client
+            .describe_voices()
+            .language_code(language_code.clone())
+            .set_next_token(next_token)
+            .send()
+            .await?;
+
+        voices.extend(resp.voices.unwrap_or_default());
+
+        next_token = resp.next_token;
+        if next_token.is_none() {
+            return Ok(voices);
+        }
+    }
+}
+
+/// Synthesizes the text with a voice and an engine, and writes the MP3 file to `VOICE/ENGINE.mp3` in the directory.
+/// Returns the path of the file, relative to the directory, and its size.
+async fn synthesize(
+    client: &Client,
+    directory: &Path,
+    text: &str,
+    voice: &Voice,
+    engine: &Engine,
+) -> Result<(String, usize), Box<dyn Error + Send + Sync>> {
+    let voice_id = voice.id.clone().ok_or("the voice has no ID")?;
+
+    let resp = client
+        .synthesize_speech()
+        .output_format(OutputFormat::Mp3)
+        .engine(engine.clone())
+        .text(text)
+        .voice_id(voice_id.clone())
+        .send()
+        .await?;
+
+    let audio = resp.audio_stream.collect().await?.into_bytes();
+
+    let file = format!("{}/{}.mp3", voice_id.as_str(), engine.as_str());
+    let path = directory.join(&file);
+    if let Some(parent) = path.parent() {
+        tokio::fs::create_dir_all(parent).await?;
+    }
+    tokio::fs::write(&path, &audio).await?;
+
+    Ok((file, audio.len()))
+}
+
+/// Synthesizes the same text with several Amazon Polly voices and engines at once, for comparison.
+/// The example gets the voices for a language with DescribeVoices, synthesizes the text with each voice and each of
+/// the engines it supports, and writes the MP3 files to a directory for each voice, with an __index.html__ page
+/// that has a player for each file.
+/// A voice that fails, such as a neural voice in a region without the neural engine, is reported, and the rest go on.
+/// # Arguments
+///
+/// * `[-f FILENAME]` - The name of the file containing the text to synthesize.
+///    If not supplied, synthesizes a short sample with a date, a number, and a percentage.
+/// * `[-l LANGUAGE-CODE]` - The language of the voices to compare.
+///    If not supplied, defaults to **en-US**.
+/// * `[--voice VOICE]` - A voice to compare, such as **Joanna**. Repeat to compare several.
+///    If not supplied, compares every voice for the language.
+/// * `[-e ENGINE]` - An engine to compare, **standard** or **neural**. Repeat to compare both.
+///    If not supplied, compares both.
+/// * `[-d DIRECTORY]` - The directory to write the files to.
+///    If not supplied, defaults to **voices**.
+/// * `[-c CONCURRENCY]` - How many voices to synthesize at once.
+///    If not supplied, defaults to **4**.
+/// * `[-r REGION]` - The region in which the client is created.
+///    If not supplied, uses the value of the **AWS_DEFAULT_REGION** environment variable.
+///    If the environment variable is not set, defaults to **us-west-2**.
+/// * `[--output FORMAT]` - How to display the result, **text** (the default) or **json**.
+/// * `[--profile PROFILE]` - The AWS profile to use from the shared credentials file.
+/// * `[-v]` - Whether to display additional information.
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn Error>> {
+    let Opt {
+        concurrency,
+        directory,
+        engine,
+        filename,
+        language_code,
+        output,
+        profile,
+        region,
+        verbose,
+        voice,
+    } = Opt::from_args();
+
+    if concurrency == 0 {
+        return Err("the concurrency must be at least 1".into());
+    }
+
+    let language = LanguageCode::from(language_code.as_str());
+    if let LanguageCode::Unknown(code) = &language {
+        return Err(format!("unknown language code {}", code).into());
+    }
+
+    let engines = if engine.is_empty() {
+        vec![Engine::Standard, Engine::Neural]
+    } else {
+        engine
+            .iter()
+            .map(|engine| match Engine::from(engine.as_str()) {
+                Engine::Unknown(engine) => Err(format!(
+                    "the engine must be standard or neural, got {}",
+                    engine
+                )),
+                engine => Ok(engine),
+            })
+            .collect::<Result<Vec<_>, _>>()?
+    };
+
+    let text = match &filename {
+        Some(filename) => fs::read_to_string(filename)?,
+        None => SAMPLE_TEXT.to_string(),
+    };
+
+    let region = EnvironmentProvider::new()
+        .region()
+        .or_else(|| region.as_ref().map(|region| Region::new(region.clone())))
+        .unwrap_or_else(|| Region::new("us-west-2"));
+
+    if verbose {
+        println!("polly client version: {}\n", polly::PKG_VERSION);
+        println!("Region:        {:?}", &region);
+        println!("Filename:      {:?}", &filename);
+        println!("Language code: {}", &language_code);
+        println!("Voices:        {:?}", &voice);
+        println!("Engines:       {:?}", &engines);
+        println!("Directory:     {}", &directory);
+        println!("Concurrency:   {}", concurrency);
+
+        SubscriberBuilder::default()
+            .with_env_filter("info")
+            .with_span_events(FmtSpan::CLOSE)
+            .init();
+    }
+
+    let mut config = Config::builder().region(region);
+    if let Some(profile) = profile {
+        config = config.credentials_provider(ProfileCredentialsProvider::new(profile));
+    }
+
+    let client = Arc::new(Client::from_conf(config.build()));
+
+    let mut voices = describe_voices(&client, &language).await?;
+
+    if !voice.is_empty() {
+        for name in &voice {
+            if !voices
+                .iter()
+                .any(|v| v.id.as_ref().map(|id| id.as_str()) == Some(name.as_str()))
+            {
+                return Err(format!("there's no {} voice {}", &language_code, name).into());
+            }
+        }
+
+        voices.retain(|v| {
+            v.id.as_ref()
+                .map_or(false, |id| voice.iter().any(|name| name == id.as_str()))
+        });
+    }
+
+    voices.sort_by(|a, b| a.name.cmp(&b.name));
+
+    // Each voice is synthesized with each selected engine that it supports.
+    let jobs: Vec<(Arc<Voice>, Engine)> = voices
+        .into_iter()
+        .map(Arc::new)
+        .flat_map(|voice| {
+            engines
+                .iter()
+                .filter(|engine| {
+                    voice
+                        .supported_engines
+                        .as_ref()
+                        .map_or(false, |supported| supported.contains(engine))
+                })
+                .map(|engine| (voice.clone(), engine.clone()))
+                .collect::<Vec<_>>()
+        })
+        .collect();
+
+    if jobs.is_empty() {
+        return Err(format!(
+            "no {} voice supports the engines {:?}",
+            &language_code, &engines
+        )
+        .into());
+    }
+
+    fs::create_dir_all(&directory)?;
+
+    let text = Arc::new(text);
+    let path = Arc::new(Path::new(&directory).to_path_buf());
+
+    // The semaphore limits how many requests run at once, so that they stay under the request quota.
+    let permits = Arc::new(Semaphore::new(concurrency));
+
+    let handles: Vec<_> = jobs
+        .into_iter()
+        .map(|(voice, engine)| {
+            let client = client.clone();
+            let text = text.clone();
+            let path = path.clone();
+            let permits = permits.clone();
+
+            tokio::spawn(async move {
+                let _permit = permits.acquire().await;
+                let result = synthesize(&client, &path, &text, &voice, &engine).await;
+                (voice, engine, result)
+            })
+        })
+        .collect();
+
+    let mut samples = Vec::new();
+    let mut failures = Vec::new();
+
+    for handle in handles {
+        let (voice, engine, result) = handle.await?;
+        let name = voice.name.clone().unwrap_or_default();
+
+        match result {
+            Ok((file, bytes)) => {
+                if verbose {
+                    println!("Synthesized {} with the {} engine", &name, engine.as_str());
+                }
+
+                samples.push(Sample {
+                    voice: name,
+                    gender: voice
+                        .gender
+                        .as_ref()
+                        .map(|gender| gender.as_str().to_string())
+                        .unwrap_or_default(),
+                    engine: engine.as_str().to_string(),
+                    file,
+                    bytes,
+                });
+            }
+            Err(e) => failures.push(Failure {
+                voice: name,
+                engine: engine.as_str().to_string(),
+                error: e.to_string(),
+            }),
+        }
+    }
+
+    let index = Path::new(&directory).join("index.html");
+    fs::write(&index, index_page(&language_code, &text, &samples))?;
+
+    output.print(&Comparison {
+        directory,
+        index: index.display().to_string(),
+        language_code,
+        samples,
+        failures,
+    });
+
+    Ok(())
+}
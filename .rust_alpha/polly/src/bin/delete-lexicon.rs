@@ -0,0 +1,116 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0.
+ */
+
+use std::error::Error;
+use std::fmt;
+
+use polly::error::DeleteLexiconErrorKind;
+use polly::{Client, Config, Region, SdkError};
+
+use aws_types::region::{EnvironmentProvider, ProvideRegion};
+
+use common::output::OutputFormat;
+use common::profile::ProfileCredentialsProvider;
+use serde::Serialize;
+use structopt::StructOpt;
+use tracing_subscriber::fmt::format::FmtSpan;
+use tracing_subscriber::fmt::SubscriberBuilder;
+
+#[derive(Debug, StructOpt)]
+struct Opt {
+    /// The region
+    #[structopt(short, long)]
+    region: Option<String>,
+
+    /// The name of the lexicon
+    #[structopt(short, long)]
+    name: String,
+
+    /// The format of the output, text or json
+    #[structopt(long, default_value = "text")]
+    output: OutputFormat,
+
+    /// The AWS profile to use from the shared credentials file
+    #[structopt(long)]
+    profile: Option<String>,
+
+    /// Whether to show additional output
+    #[structopt(short, long)]
+    verbose: bool,
+}
+
+/// The lexicon that was deleted.
+#[derive(Debug, Serialize)]
+struct DeletedLexicon {
+    name: String,
+}
+
+impl fmt::Display for DeletedLexicon {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Deleted lexicon {}", self.name)
+    }
+}
+
+/// Deletes a pronunciation lexicon from the region.
+/// Speech that's synthesized with the lexicon afterwards fails, so remove it from any requests that name it first.
+/// # Arguments
+///
+/// * `-n NAME` - The name of the lexicon.
+/// * `[-r REGION]` - The region in which the client is created.
+///    If not supplied, uses the value of the **AWS_DEFAULT_REGION** environment variable.
+///    If the environment variable is not set, defaults to **us-west-2**.
+/// * `[--output FORMAT]` - How to display the result, **text** (the default) or **json**.
+/// * `[--profile PROFILE]` - The AWS profile to use from the shared credentials file.
+/// * `[-v]` - Whether to display additional information.
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn Error>> {
+    let Opt {
+        name,
+        output,
+        profile,
+        region,
+        verbose,
+    } = Opt::from_args();
+
+    let region = EnvironmentProvider::new()
+        .region()
+        .or_else(|| region.as_ref().map(|region| Region::new(region.clone())))
+        .unwrap_or_else(|| Region::new("us-west-2"));
+
+    if verbose {
+        println!("polly client version: {}\n", polly::PKG_VERSION);
+        println!("Region:       {:?}", &region);
+        println!("Lexicon name: {}", &name);
+
+        SubscriberBuilder::default()
+            .with_env_filter("info")
+            .with_span_events(FmtSpan::CLOSE)
+            .init();
+    }
+
+    let mut config = Config::builder().region(region);
+    if let Some(profile) = profile {
+        config = config.credentials_provider(ProfileCredentialsProvider::new(profile));
+    }
+
+    let client = Client::from_conf(config.build());
+
+    match client.delete_lexicon().name(&name).send().await {
+        Ok(_) => {}
+        Err(SdkError::ServiceError { err, .. })
+            if matches!(
+                err.kind,
+                DeleteLexiconErrorKind::LexiconNotFoundException(_)
+            ) =>
+        {
+            return Err(format!("there's no lexicon named {} in the region", &name).into());
+        }
+        Err(e) => return Err(e.into()),
+    }
+
+    output.print(&DeletedLexicon { name });
+
+    Ok(())
+}
@@ -3,12 +3,17 @@
  * SPDX-License-Identifier: Apache-2.0.
  */
 
-use std::process;
+use std::error::Error;
+use std::fmt;
 
+use polly::model::{Engine, LanguageCode};
 use polly::{Client, Config, Region};
 
 use aws_types::region::{EnvironmentProvider, ProvideRegion};
 
+use common::output::OutputFormat;
+use common::profile::ProfileCredentialsProvider;
+use serde::Serialize;
 use structopt::StructOpt;
 use tracing_subscriber::fmt::format::FmtSpan;
 use tracing_subscriber::fmt::SubscriberBuilder;
@@ -19,21 +24,102 @@ struct Opt {
     #[structopt(short, long)]
     region: Option<String>,
 
+    /// Only list the voices for this language, such as en-US
+    #[structopt(short, long)]
+    language_code: Option<String>,
+
+    /// Only list the voices that support this engine, standard or neural
+    #[structopt(short, long)]
+    engine: Option<String>,
+
+    /// The format of the output, text or json
+    #[structopt(long, default_value = "text")]
+    output: OutputFormat,
+
+    /// The AWS profile to use from the shared credentials file
+    #[structopt(long)]
+    profile: Option<String>,
+
     /// Display additional information
     #[structopt(short, long)]
     verbose: bool,
 }
 
-/// Displays a list of the voices in the region.
+/// A voice, its language, and the engines it supports.
+#[derive(Debug, Serialize)]
+struct VoiceInfo {
+    /// The ID of the voice, which the other examples take as the voice.
+    id: String,
+    name: Option<String>,
+    gender: Option<String>,
+    language_code: Option<String>,
+    language: Option<String>,
+    engines: Vec<String>,
+}
+
+/// The voices in the region.
+#[derive(Debug, Serialize)]
+struct VoiceList {
+    voices: Vec<VoiceInfo>,
+}
+
+impl fmt::Display for VoiceList {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(
+            f,
+            "{:<12}  {:<8}  {:<8}  {:<24}  Engines",
+            "Voice", "Gender", "Code", "Language"
+        )?;
+
+        for voice in &self.voices {
+            writeln!(
+                f,
+                "{:<12}  {:<8}  {:<8}  {:<24}  {}",
+                voice.id,
+                voice.gender.as_deref().unwrap_or("-"),
+                voice.language_code.as_deref().unwrap_or("-"),
+                voice.language.as_deref().unwrap_or("-"),
+                voice.engines.join(", ")
+            )?;
+        }
+
+        writeln!(f, "\nFound {} voices", self.voices.len())
+    }
+}
+
+/// Displays a table of the voices in the region, with their gender, language, and the engines they support,
+/// so you can find a voice for the other examples.
+/// Amazon Polly filters the voices by language and engine, and returns them a page at a time.
 /// # Arguments
 ///
-/// * `[-d DEFAULT-REGION]` - The region in which the client is created.
+/// * `[-l LANGUAGE-CODE]` - Only lists the voices for this language, such as **en-US**.
+/// * `[-e ENGINE]` - Only lists the voices that support this engine, **standard** or **neural**.
+/// * `[-r REGION]` - The region in which the client is created.
 ///    If not supplied, uses the value of the **AWS_DEFAULT_REGION** environment variable.
 ///    If the environment variable is not set, defaults to **us-west-2**.
+/// * `[--output FORMAT]` - How to display the result, **text** (the default) or **json**.
+/// * `[--profile PROFILE]` - The AWS profile to use from the shared credentials file.
 /// * `[-v]` - Whether to display additional information.
 #[tokio::main]
-async fn main() {
-    let Opt { region, verbose } = Opt::from_args();
+async fn main() -> Result<(), Box<dyn Error>> {
+    let Opt {
+        engine,
+        language_code,
+        output,
+        profile,
+        region,
+        verbose,
+    } = Opt::from_args();
+
+    let engine = engine.map(|engine| Engine::from(engine.as_str()));
+    if let Some(Engine::Unknown(engine)) = &engine {
+        return Err(format!("the engine must be standard or neural, got {}", engine).into());
+    }
+
+    let language_code = language_code.map(|code| LanguageCode::from(code.as_str()));
+    if let Some(LanguageCode::Unknown(code)) = &language_code {
+        return Err(format!("unknown language code {}", code).into());
+    }
 
     let region = EnvironmentProvider::new()
         .region()
@@ -42,7 +128,9 @@ async fn main() {
 
     if verbose {
         println!("polly client version: {}\n", polly::PKG_VERSION);
-        println!("Region: {:?}", &region);
+        println!("Region:        {:?}", &region);
+        println!("Language code: {:?}", &language_code);
+        println!("Engine:        {:?}", &engine);
 
         SubscriberBuilder::default()
             .with_env_filter("info")
@@ -50,30 +138,52 @@ async fn main() {
             .init();
     }
 
-    let config = Config::builder().region(region).build();
-    let client = Client::from_conf(config);
-
-    match client.describe_voices().send().await {
-        Ok(resp) => {
-            println!("Voices:");
-            let voices = resp.voices.unwrap_or_default();
-            for voice in &voices {
-                println!(
-                    "  Name:     {}",
-                    voice.name.as_deref().unwrap_or("No name!")
-                );
-                println!(
-                    "  Language:     {}",
-                    voice.language_name.as_deref().unwrap_or("No language!")
-                );
+    let mut config = Config::builder().region(region);
+    if let Some(profile) = profile {
+        config = config.credentials_provider(ProfileCredentialsProvider::new(profile));
+    }
+    let client = Client::from_conf(config.build());
+
+    let mut voices = Vec::new();
+    let mut next_token = None;
+
+    loop {
+        let resp = client
+            .describe_voices()
+            .set_language_code(language_code.clone())
+            .set_engine(engine.clone())
+            .set_next_token(next_token)
+            .send()
+            .await?;
+
+        voices.extend(resp.voices.unwrap_or_default().into_iter().map(|voice| {
+            VoiceInfo {
+                id: voice
+                    .id
+                    .map(|id| id.as_str().to_string())
+                    .unwrap_or_default(),
+                name: voice.name,
+                gender: voice.gender.map(|gender| gender.as_str().to_string()),
+                language_code: voice.language_code.map(|code| code.as_str().to_string()),
+                language: voice.language_name,
+                engines: voice
+                    .supported_engines
+                    .unwrap_or_default()
+                    .iter()
+                    .map(|engine| engine.as_str().to_string())
+                    .collect(),
             }
+        }));
 
-            println!("\nFound {} voices\n", voices.len());
+        next_token = resp.next_token;
+        if next_token.is_none() {
+            break;
         }
-        Err(e) => {
-            println!("Got an error describing voices:");
-            println!("{}", e);
-            process::exit(1);
-        }
-    };
+    }
+
+    voices.sort_by(|a, b| (&a.language_code, &a.id).cmp(&(&b.language_code, &b.id)));
+
+    output.print(&VoiceList { voices });
+
+    Ok(())
 }
@@ -0,0 +1,150 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0.
+ */
+
+use std::error::Error;
+use std::fmt;
+use std::fs;
+use std::path::PathBuf;
+
+use polly::{Client, Config, Region};
+use polly_code_examples::lexicons::{file_name, list_names};
+
+use aws_types::region::ProvideRegion;
+
+use common::output::OutputFormat;
+use common::profile::ProfileCredentialsProvider;
+use serde::Serialize;
+use structopt::StructOpt;
+use tracing_subscriber::fmt::format::FmtSpan;
+use tracing_subscriber::fmt::SubscriberBuilder;
+
+#[derive(Debug, StructOpt)]
+struct Opt {
+    /// The region to export the lexicons from. Overrides environment variable AWS_DEFAULT_REGION.
+    #[structopt(short, long)]
+    default_region: Option<String>,
+
+    /// The directory to save the lexicons in
+    #[structopt(short, long, default_value = "lexicons")]
+    output_dir: PathBuf,
+
+    /// The format of the output, text or json
+    #[structopt(long, default_value = "text")]
+    output: OutputFormat,
+
+    /// The AWS profile to use from the shared credentials file
+    #[structopt(long)]
+    profile: Option<String>,
+
+    /// Whether to display additional information
+    #[structopt(short, long)]
+    verbose: bool,
+}
+
+/// A lexicon and the file it was saved in.
+#[derive(Debug, Serialize)]
+struct ExportedLexicon {
+    name: String,
+    path: String,
+}
+
+/// The lexicons that were exported.
+#[derive(Debug, Serialize)]
+struct Exported {
+    directory: String,
+    lexicons: Vec<ExportedLexicon>,
+}
+
+impl fmt::Display for Exported {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for lexicon in &self.lexicons {
+            writeln!(f, "Exported {} to {}", lexicon.name, lexicon.path)?;
+        }
+
+        write!(
+            f,
+            "\nExported {} lexicons to {}",
+            self.lexicons.len(),
+            self.directory
+        )
+    }
+}
+
+/// Downloads every pronunciation lexicon in an AWS Region and saves each one in a directory
+/// as a Pronunciation Lexicon Specification (PLS) file, named after the lexicon, such as **W3C.pls**.
+/// Use the **import-lexicons** example to upload them to another region.
+/// # Arguments
+///
+/// * `[-o OUTPUT-DIR]` - The directory to save the lexicons in. Defaults to **lexicons**.
+/// * `[-d DEFAULT-REGION]` - The region to export the lexicons from.
+///    If not supplied, uses the value of the **AWS_DEFAULT_REGION** environment variable.
+///    If the environment variable is not set, defaults to **us-west-2**.
+/// * `[--output FORMAT]` - How to display the result, **text** (the default) or **json**.
+/// * `[--profile PROFILE]` - The AWS profile to use from the shared credentials file.
+/// * `[-v]` - Whether to display additional information.
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn Error>> {
+    let Opt {
+        default_region,
+        output_dir,
+        output,
+        profile,
+        verbose,
+    } = Opt::from_args();
+
+    let region = default_region
+        .as_ref()
+        .map(|region| Region::new(region.clone()))
+        .or_else(|| aws_types::region::default_provider().region())
+        .unwrap_or_else(|| Region::new("us-west-2"));
+
+    if verbose {
+        println!("polly client version: {}\n", polly::PKG_VERSION);
+        println!("Region:           {:?}", &region);
+        println!("Output directory: {}", output_dir.display());
+
+        SubscriberBuilder::default()
+            .with_env_filter("info")
+            .with_span_events(FmtSpan::CLOSE)
+            .init();
+    }
+
+    let mut config = Config::builder().region(region);
+    if let Some(profile) = profile {
+        config = config.credentials_provider(ProfileCredentialsProvider::new(profile));
+    }
+
+    let client = Client::from_conf(config.build());
+
+    fs::create_dir_all(&output_dir)?;
+
+    let names = list_names(&client).await?;
+    let mut lexicons = Vec::with_capacity(names.len());
+
+    for name in names {
+        let resp = client.get_lexicon().name(name).send().await?;
+
+        // The content is saved as Amazon Polly stored it, so importing it gives the same lexicon.
+        let content = resp
+            .lexicon
+            .and_then(|lexicon| lexicon.content)
+            .ok_or_else(|| format!("no content returned for lexicon {}", name))?;
+
+        let path = output_dir.join(file_name(&name));
+        fs::write(&path, content)?;
+
+        lexicons.push(ExportedLexicon {
+            name,
+            path: path.display().to_string(),
+        });
+    }
+
+    output.print(&Exported {
+        directory: output_dir.display().to_string(),
+        lexicons,
+    });
+
+    Ok(())
+}
@@ -0,0 +1,150 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0.
+ */
+
+use std::error::Error;
+use std::fmt;
+
+use polly::error::GetLexiconErrorKind;
+use polly::{Client, Config, Region, SdkError};
+use polly_code_examples::lexicons::pretty_print;
+
+use aws_types::region::{EnvironmentProvider, ProvideRegion};
+
+use common::output::OutputFormat;
+use common::profile::ProfileCredentialsProvider;
+use serde::Serialize;
+use structopt::StructOpt;
+use tracing_subscriber::fmt::format::FmtSpan;
+use tracing_subscriber::fmt::SubscriberBuilder;
+
+#[derive(Debug, StructOpt)]
+struct Opt {
+    /// The region
+    #[structopt(short, long)]
+    region: Option<String>,
+
+    /// The name of the lexicon
+    #[structopt(short, long)]
+    name: String,
+
+    /// The format of the output, text or json
+    #[structopt(long, default_value = "text")]
+    output: OutputFormat,
+
+    /// The AWS profile to use from the shared credentials file
+    #[structopt(long)]
+    profile: Option<String>,
+
+    /// Whether to show additional output
+    #[structopt(short, long)]
+    verbose: bool,
+}
+
+/// A lexicon and its content.
+#[derive(Debug, Serialize)]
+struct LexiconContent {
+    name: String,
+    language: String,
+    alphabet: String,
+    lexemes: i32,
+    /// The PLS XML of the lexicon, as it was stored.
+    content: String,
+}
+
+impl fmt::Display for LexiconContent {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(
+            f,
+            "Lexicon {} ({}, {} alphabet) has {} lexemes:\n",
+            self.name, self.language, self.alphabet, self.lexemes
+        )?;
+
+        // Amazon Polly only stores well-formed lexicons, but they're shown as they are if they can't be indented.
+        match pretty_print(&self.content) {
+            Ok(pretty) => write!(f, "{}", pretty),
+            Err(_) => writeln!(f, "{}", self.content),
+        }
+    }
+}
+
+/// Displays a pronunciation lexicon that's stored in the region, with its Pronunciation Lexicon Specification (PLS) XML indented.
+/// The JSON output holds the XML as it was stored.
+/// # Arguments
+///
+/// * `-n NAME` - The name of the lexicon.
+/// * `[-r REGION]` - The region in which the client is created.
+///    If not supplied, uses the value of the **AWS_DEFAULT_REGION** environment variable.
+///    If the environment variable is not set, defaults to **us-west-2**.
+/// * `[--output FORMAT]` - How to display the result, **text** (the default) or **json**.
+/// * `[--profile PROFILE]` - The AWS profile to use from the shared credentials file.
+/// * `[-v]` - Whether to display additional information.
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn Error>> {
+    let Opt {
+        name,
+        output,
+        profile,
+        region,
+        verbose,
+    } = Opt::from_args();
+
+    let region = EnvironmentProvider::new()
+        .region()
+        .or_else(|| region.as_ref().map(|region| Region::new(region.clone())))
+        .unwrap_or_else(|| Region::new("us-west-2"));
+
+    if verbose {
+        println!("polly client version: {}\n", polly::PKG_VERSION);
+        println!("Region:       {:?}", &region);
+        println!("Lexicon name: {}", &name);
+
+        SubscriberBuilder::default()
+            .with_env_filter("info")
+            .with_span_events(FmtSpan::CLOSE)
+            .init();
+    }
+
+    let mut config = Config::builder().region(region);
+    if let Some(profile) = profile {
+        config = config.credentials_provider(ProfileCredentialsProvider::new(profile));
+    }
+
+    let client = Client::from_conf(config.build());
+
+    let resp = match client.get_lexicon().name(&name).send().await {
+        Ok(resp) => resp,
+        Err(SdkError::ServiceError { err, .. })
+            if matches!(err.kind, GetLexiconErrorKind::LexiconNotFoundException(_)) =>
+        {
+            return Err(format!("there's no lexicon named {} in the region", &name).into());
+        }
+        Err(e) => return Err(e.into()),
+    };
+
+    let attributes = resp.lexicon_attributes;
+
+    output.print(&LexiconContent {
+        language: attributes
+            .as_ref()
+            .and_then(|attributes| attributes.language_code.as_ref())
+            .map(|code| code.as_str().to_string())
+            .unwrap_or_default(),
+        alphabet: attributes
+            .as_ref()
+            .and_then(|attributes| attributes.alphabet.clone())
+            .unwrap_or_default(),
+        lexemes: attributes
+            .as_ref()
+            .map(|attributes| attributes.lexemes_count)
+            .unwrap_or_default(),
+        content: resp
+            .lexicon
+            .and_then(|lexicon| lexicon.content)
+            .unwrap_or_default(),
+        name,
+    });
+
+    Ok(())
+}
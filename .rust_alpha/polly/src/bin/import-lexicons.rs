@@ -0,0 +1,245 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0.
+ */
+
+use std::collections::HashSet;
+use std::error::Error;
+use std::fmt;
+use std::fs;
+use std::path::PathBuf;
+
+use polly::{Client, Config, Region};
+use polly_code_examples::lexicons::{check_name, list_names, name_from_file, validate};
+
+use aws_types::region::ProvideRegion;
+
+use common::output::OutputFormat;
+use common::profile::ProfileCredentialsProvider;
+use serde::Serialize;
+use structopt::StructOpt;
+use tracing_subscriber::fmt::format::FmtSpan;
+use tracing_subscriber::fmt::SubscriberBuilder;
+
+#[derive(Debug, StructOpt)]
+struct Opt {
+    /// The region to import the lexicons into. Overrides environment variable AWS_DEFAULT_REGION.
+    #[structopt(short, long)]
+    default_region: Option<String>,
+
+    /// The directory holding the PLS files
+    #[structopt(short, long, default_value = "lexicons")]
+    input_dir: PathBuf,
+
+    /// Text to put before the name of each lexicon, so that it doesn't clash with an existing lexicon
+    #[structopt(long, default_value = "")]
+    prefix: String,
+
+    /// Whether to replace a lexicon that already has the name, rather than skip it
+    #[structopt(long)]
+    overwrite: bool,
+
+    /// The format of the output, text or json
+    #[structopt(long, default_value = "text")]
+    output: OutputFormat,
+
+    /// The AWS profile to use from the shared credentials file
+    #[structopt(long)]
+    profile: Option<String>,
+
+    /// Whether to display additional information
+    #[structopt(short, long)]
+    verbose: bool,
+}
+
+/// What happened to a file.
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "lowercase")]
+enum Outcome {
+    Imported,
+    Replaced,
+    /// The lexicon already exists.
+    Skipped,
+    /// The file or its lexicon name isn't valid.
+    Failed,
+}
+
+/// A file and the lexicon it was imported as.
+#[derive(Debug, Serialize)]
+struct ImportedFile {
+    file: String,
+    name: String,
+    outcome: Outcome,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    reason: Option<String>,
+}
+
+/// What happened to each file in the directory.
+#[derive(Debug, Serialize)]
+struct Imported {
+    files: Vec<ImportedFile>,
+}
+
+impl Imported {
+    fn count(&self, outcome: fn(&Outcome) -> bool) -> usize {
+        self.files
+            .iter()
+            .filter(|file| outcome(&file.outcome))
+            .count()
+    }
+}
+
+impl fmt::Display for Imported {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for file in &self.files {
+            let reason = file.reason.as_deref().unwrap_or_default();
+
+            match file.outcome {
+                Outcome::Imported => writeln!(f, "Imported {} from {}", file.name, file.file)?,
+                Outcome::Replaced => writeln!(f, "Replaced {} with {}", file.name, file.file)?,
+                Outcome::Skipped => writeln!(f, "Skipped {}: {}", file.file, reason)?,
+                // The reason already names the file.
+                Outcome::Failed => writeln!(f, "Skipped {}", reason)?,
+            }
+        }
+
+        write!(
+            f,
+            "\nImported {} lexicons; skipped {} that already exist",
+            self.count(|outcome| matches!(outcome, Outcome::Imported | Outcome::Replaced)),
+            self.count(|outcome| matches!(outcome, Outcome::Skipped))
+        )
+    }
+}
+
+/// Uploads every Pronunciation Lexicon Specification (PLS) file in a directory, such as one that
+/// the **export-lexicons** example wrote, as a lexicon named after the file.
+/// A lexicon whose name is already taken in the region is skipped, unless **--overwrite** is set;
+/// use **--prefix** to import the lexicons under new names instead.
+/// Each file is checked before it's uploaded, and one that isn't valid is reported and skipped.
+/// # Arguments
+///
+/// * `[-i INPUT-DIR]` - The directory holding the PLS files. Defaults to **lexicons**.
+/// * `[--prefix PREFIX]` - Text to put before the name of each lexicon, such as **eu**.
+///   A lexicon name can have at most 20 letters and digits, including the prefix.
+/// * `[--overwrite]` - Whether to replace a lexicon that already has the name.
+/// * `[-d DEFAULT-REGION]` - The region to import the lexicons into.
+///    If not supplied, uses the value of the **AWS_DEFAULT_REGION** environment variable.
+///    If the environment variable is not set, defaults to **us-west-2**.
+/// * `[--output FORMAT]` - How to display the result, **text** (the default) or **json**.
+/// * `[--profile PROFILE]` - The AWS profile to use from the shared credentials file.
+/// * `[-v]` - Whether to display additional information.
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn Error>> {
+    let Opt {
+        default_region,
+        input_dir,
+        prefix,
+        overwrite,
+        output,
+        profile,
+        verbose,
+    } = Opt::from_args();
+
+    let region = default_region
+        .as_ref()
+        .map(|region| Region::new(region.clone()))
+        .or_else(|| aws_types::region::default_provider().region())
+        .unwrap_or_else(|| Region::new("us-west-2"));
+
+    if verbose {
+        println!("polly client version: {}\n", polly::PKG_VERSION);
+        println!("Region:          {:?}", &region);
+        println!("Input directory: {}", input_dir.display());
+        println!("Prefix:          {}", &prefix);
+        println!("Overwrite:       {}", overwrite);
+
+        SubscriberBuilder::default()
+            .with_env_filter("info")
+            .with_span_events(FmtSpan::CLOSE)
+            .init();
+    }
+
+    // The files are imported in name order, so that the output is the same each time.
+    let mut files: Vec<(String, PathBuf)> = fs::read_dir(&input_dir)?
+        .map(|entry| entry.map(|entry| entry.path()))
+        .collect::<Result<Vec<_>, _>>()?
+        .into_iter()
+        .filter_map(|path| name_from_file(&path).map(|name| (format!("{}{}", prefix, name), path)))
+        .collect();
+    files.sort();
+
+    if files.is_empty() {
+        return Err(format!("{} holds no .pls files", input_dir.display()).into());
+    }
+
+    let mut config = Config::builder().region(region);
+    if let Some(profile) = profile {
+        config = config.credentials_provider(ProfileCredentialsProvider::new(profile));
+    }
+
+    let client = Client::from_conf(config.build());
+
+    let existing: HashSet<String> = list_names(&client).await?.into_iter().collect();
+
+    let mut imported = Imported { files: Vec::new() };
+
+    for (name, path) in files {
+        let file = path.display().to_string();
+        let mut result = |outcome, reason| {
+            imported.files.push(ImportedFile {
+                file: file.clone(),
+                name: name.clone(),
+                outcome,
+                reason,
+            })
+        };
+
+        if let Err(e) = check_name(&name) {
+            result(
+                Outcome::Failed,
+                Some(format!("{}: the lexicon name {}", file, e)),
+            );
+            continue;
+        }
+
+        if existing.contains(&name) && !overwrite {
+            result(
+                Outcome::Skipped,
+                Some(format!(
+                    "lexicon {} already exists; use --prefix or --overwrite",
+                    name
+                )),
+            );
+            continue;
+        }
+
+        let content = fs::read_to_string(&path)?;
+        if let Err(e) = validate(&file, &content) {
+            result(Outcome::Failed, Some(e));
+            continue;
+        }
+
+        client
+            .put_lexicon()
+            .name(&name)
+            .content(content)
+            .send()
+            .await?;
+
+        if existing.contains(&name) {
+            result(Outcome::Replaced, None);
+        } else {
+            result(Outcome::Imported, None);
+        }
+    }
+
+    output.print(&imported);
+
+    let failed = imported.count(|outcome| matches!(outcome, Outcome::Failed));
+    if failed > 0 {
+        return Err(format!("{} files couldn't be imported", failed).into());
+    }
+
+    Ok(())
+}
@@ -3,12 +3,17 @@
  * SPDX-License-Identifier: Apache-2.0.
  */
 
-use std::process;
+use std::fmt;
 
-use polly::{Client, Config, Region};
+use polly::model::LexiconAttributes;
+use polly::{Client, Config, Error, Region};
 
 use aws_types::region::{EnvironmentProvider, ProvideRegion};
 
+use common::output::OutputFormat;
+use common::profile::ProfileCredentialsProvider;
+use serde::Serialize;
+use smithy_types::instant::Format;
 use structopt::StructOpt;
 use tracing_subscriber::fmt::format::FmtSpan;
 use tracing_subscriber::fmt::SubscriberBuilder;
@@ -19,21 +24,71 @@ struct Opt {
     #[structopt(short, long)]
     region: Option<String>,
 
+    /// The format of the output, text or json
+    #[structopt(long, default_value = "text")]
+    output: OutputFormat,
+
+    /// The AWS profile to use from the shared credentials file
+    #[structopt(long)]
+    profile: Option<String>,
+
     /// Activate verbose mode
     #[structopt(short, long)]
     verbose: bool,
 }
 
-/// Displays a list of the lexicons in the region.
+/// A lexicon, its language, and how many entries it has.
+#[derive(Debug, Serialize)]
+struct LexiconInfo {
+    name: String,
+    language: String,
+    alphabet: String,
+    lexemes: i32,
+    size: i32,
+    last_modified: String,
+}
+
+/// The lexicons in the region.
+#[derive(Debug, Serialize)]
+struct LexiconList {
+    lexicons: Vec<LexiconInfo>,
+}
+
+impl fmt::Display for LexiconList {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "Lexicons:")?;
+
+        for lexicon in &self.lexicons {
+            writeln!(f, "  Name:          {}", lexicon.name)?;
+            writeln!(f, "  Language:      {}", lexicon.language)?;
+            writeln!(f, "  Alphabet:      {}", lexicon.alphabet)?;
+            writeln!(f, "  Lexemes:       {}", lexicon.lexemes)?;
+            writeln!(f, "  Size:          {} bytes", lexicon.size)?;
+            writeln!(f, "  Last modified: {}\n", lexicon.last_modified)?;
+        }
+
+        writeln!(f, "\nFound {} lexicons.", self.lexicons.len())
+    }
+}
+
+/// Displays a list of the lexicons in the region, with their language, alphabet, and how many lexemes they have.
+/// Use the __get-lexicon__ example to display a lexicon's content.
 /// # Arguments
 ///
 /// * `[-d DEFAULT-REGION]` - The region in which the client is created.
 ///    If not supplied, uses the value of the **AWS_DEFAULT_REGION** environment variable.
 ///    If the environment variable is not set, defaults to **us-west-2**.
+/// * `[--output FORMAT]` - How to display the result, **text** (the default) or **json**.
+/// * `[--profile PROFILE]` - The AWS profile to use from the shared credentials file.
 /// * `[-v]` - Whether to display additional information.
 #[tokio::main]
-async fn main() {
-    let Opt { region, verbose } = Opt::from_args();
+async fn main() -> Result<(), Error> {
+    let Opt {
+        output,
+        profile,
+        region,
+        verbose,
+    } = Opt::from_args();
 
     let region = EnvironmentProvider::new()
         .region()
@@ -50,38 +105,57 @@ async fn main() {
             .init();
     }
 
-    let config = Config::builder().region(region).build();
+    let mut config = Config::builder().region(region);
+    if let Some(profile) = profile {
+        config = config.credentials_provider(ProfileCredentialsProvider::new(profile));
+    }
 
-    let client = Client::from_conf(config);
+    let client = Client::from_conf(config.build());
 
-    match client.list_lexicons().send().await {
-        Ok(resp) => {
-            println!("Lexicons:");
-            let lexicons = resp.lexicons.unwrap_or_default();
+    // ListLexicons returns the lexicons a page at a time.
+    let mut lexicons = Vec::new();
+    let mut next_token = None;
 
-            for lexicon in &lexicons {
-                println!(
-                    "  Name:     {}",
-                    lexicon.name.as_deref().unwrap_or_default()
-                );
-                println!(
-                    "  Language: {:?}\n",
-                    lexicon
+    loop {
+        let resp = client
+            .list_lexicons()
+            .set_next_token(next_token)
+            .send()
+            .await?;
+
+        lexicons.extend(
+            resp.lexicons
+                .unwrap_or_default()
+                .into_iter()
+                .map(|lexicon| {
+                    let attributes = lexicon
                         .attributes
-                        .as_ref()
-                        .map(|attrib| attrib
+                        .unwrap_or_else(|| LexiconAttributes::builder().build());
+
+                    LexiconInfo {
+                        name: lexicon.name.unwrap_or_default(),
+                        language: attributes
                             .language_code
-                            .as_ref()
-                            .expect("languages must have language codes"))
-                        .expect("languages must have attributes")
-                );
-            }
-            println!("\nFound {} lexicons.\n", lexicons.len());
-        }
-        Err(e) => {
-            println!("Got an error listing lexicons:");
-            println!("{}", e);
-            process::exit(1);
+                            .map(|code| code.as_str().to_string())
+                            .unwrap_or_default(),
+                        alphabet: attributes.alphabet.unwrap_or_default(),
+                        lexemes: attributes.lexemes_count,
+                        size: attributes.size,
+                        last_modified: attributes
+                            .last_modified
+                            .map(|time| time.fmt(Format::DateTime))
+                            .unwrap_or_default(),
+                    }
+                }),
+        );
+
+        next_token = resp.next_token;
+        if next_token.is_none() {
+            break;
         }
-    };
+    }
+
+    output.print(&LexiconList { lexicons });
+
+    Ok(())
 }
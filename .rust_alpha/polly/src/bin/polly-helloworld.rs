@@ -4,7 +4,65 @@
  */
 
 use polly::model::{Engine, Voice};
+use polly::{Client, Config, Region};
 use std::error::Error;
+use std::fmt;
+
+use aws_types::region::ProvideRegion;
+
+use common::output::OutputFormat;
+use common::profile::ProfileCredentialsProvider;
+use serde::Serialize;
+use structopt::StructOpt;
+use tracing_subscriber::fmt::format::FmtSpan;
+use tracing_subscriber::fmt::SubscriberBuilder;
+
+#[derive(Debug, StructOpt)]
+struct Opt {
+    /// The region. Overrides environment variable AWS_DEFAULT_REGION.
+    #[structopt(short, long)]
+    default_region: Option<String>,
+
+    /// The format of the output, text or json
+    #[structopt(long, default_value = "text")]
+    output: OutputFormat,
+
+    /// The AWS profile to use from the shared credentials file
+    #[structopt(long)]
+    profile: Option<String>,
+
+    /// Whether to display additional information
+    #[structopt(short, long)]
+    verbose: bool,
+}
+
+/// A voice and its language.
+#[derive(Debug, Serialize)]
+struct VoiceInfo {
+    name: String,
+    language: String,
+}
+
+/// The voices in the region, and the IDs of those supporting a neural engine.
+#[derive(Debug, Serialize)]
+struct Voices {
+    voices: Vec<VoiceInfo>,
+    neural_voices: Vec<String>,
+}
+
+impl fmt::Display for Voices {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for voice in &self.voices {
+            writeln!(f, "I can speak as: {} in {:?}", voice.name, voice.language)?;
+        }
+
+        write!(
+            f,
+            "Voices supporting a neural engine: {:?}",
+            self.neural_voices
+        )
+    }
+}
 
 /// Displays a list of the voices and their language, and those supporting a neural engine, in the region.
 /// # Arguments
@@ -12,10 +70,40 @@ use std::error::Error;
 /// * `[-d DEFAULT-REGION]` - The region in which the client is created.
 ///    If not supplied, uses the value of the **AWS_DEFAULT_REGION** environment variable.
 ///    If the environment variable is not set, defaults to **us-west-2**.
+/// * `[--output FORMAT]` - How to display the result, **text** (the default) or **json**.
+/// * `[--profile PROFILE]` - The AWS profile to use from the shared credentials file.
 /// * `[-v]` - Whether to display additional information.
 #[tokio::main]
 async fn main() -> Result<(), Box<dyn Error + Send + Sync + 'static>> {
-    let client = polly::Client::from_env();
+    let Opt {
+        default_region,
+        output,
+        profile,
+        verbose,
+    } = Opt::from_args();
+
+    let region = default_region
+        .as_ref()
+        .map(|region| Region::new(region.clone()))
+        .or_else(|| aws_types::region::default_provider().region())
+        .unwrap_or_else(|| Region::new("us-west-2"));
+
+    if verbose {
+        println!("Polly client version: {}", polly::PKG_VERSION);
+        println!("Region:               {:?}", &region);
+        println!();
+
+        SubscriberBuilder::default()
+            .with_env_filter("info")
+            .with_span_events(FmtSpan::CLOSE)
+            .init();
+    }
+
+    let mut config = Config::builder().region(region);
+    if let Some(profile) = profile {
+        config = config.credentials_provider(ProfileCredentialsProvider::new(profile));
+    }
+    let client = Client::from_conf(config.build());
     let mut tok = None;
     let mut voices: Vec<Voice> = vec![];
     // Below is an an example of how pagination can be implemented manually.
@@ -25,14 +113,7 @@ async fn main() -> Result<(), Box<dyn Error + Send + Sync + 'static>> {
             req = req.next_token(tok);
         }
         let resp = req.send().await?;
-        for voice in resp.voices.unwrap_or_default() {
-            println!(
-                "I can speak as: {} in {:?}",
-                voice.name.as_ref().unwrap(),
-                voice.language_name.as_ref().unwrap()
-            );
-            voices.push(voice);
-        }
+        voices.extend(resp.voices.unwrap_or_default());
         tok = match resp.next_token {
             Some(next) => Some(next),
             None => break,
@@ -47,9 +128,19 @@ async fn main() -> Result<(), Box<dyn Error + Send + Sync + 'static>> {
                 .unwrap_or_default()
                 .contains(&Engine::Neural)
         })
-        .map(|voice| voice.id.as_ref().unwrap())
-        .collect::<Vec<_>>();
+        .filter_map(|voice| voice.id.as_ref().map(|id| id.as_str().to_string()))
+        .collect();
+
+    output.print(&Voices {
+        voices: voices
+            .iter()
+            .map(|voice| VoiceInfo {
+                name: voice.name.clone().unwrap_or_default(),
+                language: voice.language_name.clone().unwrap_or_default(),
+            })
+            .collect(),
+        neural_voices,
+    });
 
-    println!("Voices supporting a neural engine: {:?}", neural_voices);
     Ok(())
 }
@@ -0,0 +1,275 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0.
+ */
+
+use std::convert::Infallible;
+use std::error::Error;
+use std::net::SocketAddr;
+use std::sync::Arc;
+
+use polly::error::SynthesizeSpeechErrorKind;
+use polly::model::{Engine, OutputFormat, TextType, VoiceId};
+use polly::{Client, Config, Region, SdkError};
+
+use aws_types::region::{EnvironmentProvider, ProvideRegion};
+
+use common::profile::ProfileCredentialsProvider;
+use common::shutdown::Shutdown;
+use hyper::body::HttpBody;
+use hyper::header::CONTENT_TYPE;
+use hyper::service::{make_service_fn, service_fn};
+use hyper::{Body, Method, Request, Response, Server, StatusCode};
+use serde::Deserialize;
+use structopt::StructOpt;
+use tracing_subscriber::fmt::format::FmtSpan;
+use tracing_subscriber::fmt::SubscriberBuilder;
+
+/// The largest request body the server accepts. Amazon Polly accepts up to 6,000 characters
+/// of text, including SSML tags, in a request, and a character can take up to four bytes.
+const MAX_BODY_BYTES: usize = 6_000 * 4 + 1024;
+
+#[derive(Debug, StructOpt)]
+struct Opt {
+    /// The region
+    #[structopt(short, long)]
+    region: Option<String>,
+
+    /// The address and port to listen on
+    #[structopt(short, long, default_value = "127.0.0.1:3000")]
+    address: SocketAddr,
+
+    /// The AWS profile to use from the shared credentials file
+    #[structopt(long)]
+    profile: Option<String>,
+
+    /// Whether to show additional output
+    #[structopt(short, long)]
+    verbose: bool,
+}
+
+/// The body of a request to synthesize speech.
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct SynthesizeRequest {
+    text: String,
+    /// The voice, such as Joanna. Defaults to Joanna.
+    voice: Option<String>,
+    /// The audio format, mp3, ogg_vorbis, or pcm. Defaults to mp3.
+    format: Option<String>,
+    /// The engine, standard or neural. Defaults to standard.
+    engine: Option<String>,
+    /// Whether the text is SSML.
+    #[serde(default)]
+    ssml: bool,
+}
+
+/// Returns a response with a status and a JSON error message.
+fn error(status: StatusCode, message: impl std::fmt::Display) -> Response<Body> {
+    let body = serde_json::json!({ "error": message.to_string() });
+
+    Response::builder()
+        .status(status)
+        .header(CONTENT_TYPE, "application/json")
+        .body(Body::from(body.to_string()))
+        .expect("the response is valid")
+}
+
+/// Checks the request, and returns the options for the SynthesizeSpeech call.
+fn parse(
+    body: &[u8],
+) -> Result<(SynthesizeRequest, VoiceId, OutputFormat, Engine), Response<Body>> {
+    let request: SynthesizeRequest = serde_json::from_slice(body)
+        .map_err(|e| error(StatusCode::BAD_REQUEST, format!("invalid request: {}", e)))?;
+
+    if request.text.trim().is_empty() {
+        return Err(error(StatusCode::BAD_REQUEST, "the text is empty"));
+    }
+
+    let voice = VoiceId::from(request.voice.as_deref().unwrap_or("Joanna"));
+    if let VoiceId::Unknown(voice) = &voice {
+        return Err(error(
+            StatusCode::BAD_REQUEST,
+            format!("unknown voice {}", voice),
+        ));
+    }
+
+    let format = OutputFormat::from(request.format.as_deref().unwrap_or("mp3"));
+    if matches!(format, OutputFormat::Unknown(_) | OutputFormat::Json) {
+        return Err(error(
+            StatusCode::BAD_REQUEST,
+            "the format must be mp3, ogg_vorbis, or pcm",
+        ));
+    }
+
+    let engine = Engine::from(request.engine.as_deref().unwrap_or("standard"));
+    if let Engine::Unknown(engine) = &engine {
+        return Err(error(
+            StatusCode::BAD_REQUEST,
+            format!("unknown engine {}", engine),
+        ));
+    }
+
+    Ok((request, voice, format, engine))
+}
+
+/// Handles POST /synthesize: synthesizes the text in the request, and streams the audio back
+/// as Amazon Polly returns it, rather than waiting for all of it.
+async fn synthesize(client: &Client, req: Request<Body>) -> Response<Body> {
+    // A request with a Content-Length header that's too large is turned away before it's read.
+    if req.body().size_hint().lower() > MAX_BODY_BYTES as u64 {
+        return error(
+            StatusCode::PAYLOAD_TOO_LARGE,
+            format!("the request is larger than {} bytes", MAX_BODY_BYTES),
+        );
+    }
+
+    let body = match hyper::body::to_bytes(req.into_body()).await {
+        Ok(body) if body.len() > MAX_BODY_BYTES => {
+            return error(
+                StatusCode::PAYLOAD_TOO_LARGE,
+                format!("the request is larger than {} bytes", MAX_BODY_BYTES),
+            )
+        }
+        Ok(body) => body,
+        Err(e) => return error(StatusCode::BAD_REQUEST, e),
+    };
+
+    let (request, voice, format, engine) = match parse(&body) {
+        Ok(parsed) => parsed,
+        Err(response) => return response,
+    };
+
+    let text_type = if request.ssml {
+        TextType::Ssml
+    } else {
+        TextType::Text
+    };
+
+    let resp = match client
+        .synthesize_speech()
+        .engine(engine)
+        .output_format(format)
+        .text(request.text)
+        .text_type(text_type)
+        .voice_id(voice)
+        .send()
+        .await
+    {
+        Ok(resp) => resp,
+        // Errors in the request are the client's to fix. Anything else is the server's.
+        Err(SdkError::ServiceError { err, .. })
+            if matches!(
+                err.kind,
+                SynthesizeSpeechErrorKind::InvalidSsmlException(_)
+                    | SynthesizeSpeechErrorKind::TextLengthExceededException(_)
+                    | SynthesizeSpeechErrorKind::EngineNotSupportedException(_)
+                    | SynthesizeSpeechErrorKind::LexiconNotFoundException(_)
+            ) =>
+        {
+            return error(StatusCode::BAD_REQUEST, err)
+        }
+        Err(e) => return error(StatusCode::BAD_GATEWAY, e),
+    };
+
+    let content_type = resp
+        .content_type
+        .unwrap_or_else(|| "application/octet-stream".to_string());
+
+    Response::builder()
+        .header(CONTENT_TYPE, content_type)
+        .body(Body::wrap_stream(resp.audio_stream))
+        .expect("the response is valid")
+}
+
+/// Routes a request to its handler.
+async fn route(
+    client: Arc<Client>,
+    req: Request<Body>,
+    verbose: bool,
+) -> Result<Response<Body>, Infallible> {
+    if verbose {
+        println!("{} {}", req.method(), req.uri());
+    }
+
+    let response = match (req.method(), req.uri().path()) {
+        (&Method::POST, "/synthesize") => synthesize(&client, req).await,
+        (_, "/synthesize") => error(StatusCode::METHOD_NOT_ALLOWED, "use POST"),
+        _ => error(StatusCode::NOT_FOUND, "not found"),
+    };
+
+    if verbose {
+        println!("  {}", response.status());
+    }
+
+    Ok(response)
+}
+
+/// Runs an HTTP server that synthesizes speech with Amazon Polly.
+/// POST a JSON object to /synthesize, such as **{"text": "Hello", "voice": "Matthew", "format": "ogg_vorbis"}**,
+/// and the server streams the audio back as Amazon Polly returns it.
+/// Every request shares one client, and so its connection pool and credentials.
+/// Runs until you press Ctrl-C, and finishes the requests in progress before it stops.
+/// # Arguments
+///
+/// * `[-a ADDRESS]` - The address and port to listen on.
+///   If not supplied, defaults to **127.0.0.1:3000**.
+/// * `[-r REGION]` - The region in which the client is created.
+///   If the **AWS_DEFAULT_REGION** environment variable is set, it's used instead.
+///   If neither is set, defaults to **us-west-2**.
+/// * `[--profile PROFILE]` - The AWS profile to use from the shared credentials file.
+/// * `[-v]` - Whether to display additional information, including each request.
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn Error>> {
+    let Opt {
+        address,
+        profile,
+        region,
+        verbose,
+    } = Opt::from_args();
+
+    let region = EnvironmentProvider::new()
+        .region()
+        .or_else(|| region.as_ref().map(|region| Region::new(region.clone())))
+        .unwrap_or_else(|| Region::new("us-west-2"));
+
+    if verbose {
+        println!("polly client version: {}\n", polly::PKG_VERSION);
+        println!("Region:  {:?}", &region);
+        println!("Address: {}", &address);
+
+        SubscriberBuilder::default()
+            .with_env_filter("info")
+            .with_span_events(FmtSpan::CLOSE)
+            .init();
+    }
+
+    let mut config = Config::builder().region(region);
+    if let Some(profile) = profile {
+        config = config.credentials_provider(ProfileCredentialsProvider::new(profile));
+    }
+    let client = Arc::new(Client::from_conf(config.build()));
+
+    // Each connection gets a handle to the same client.
+    let make_service = make_service_fn(move |_| {
+        let client = client.clone();
+
+        async move { Ok::<_, Infallible>(service_fn(move |req| route(client.clone(), req, verbose))) }
+    });
+
+    let shutdown = Shutdown::listen();
+    let server = Server::try_bind(&address)?.serve(make_service);
+
+    println!(
+        "Listening on http://{}/synthesize. Press Ctrl-C to stop",
+        server.local_addr()
+    );
+
+    server
+        .with_graceful_shutdown(async move { shutdown.requested().await })
+        .await?;
+
+    println!("Stopped");
+
+    Ok(())
+}
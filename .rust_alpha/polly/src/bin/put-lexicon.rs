@@ -3,12 +3,18 @@
  * SPDX-License-Identifier: Apache-2.0.
  */
 
-use std::process;
+use std::error::Error;
+use std::fmt;
+use std::fs;
 
 use polly::{Client, Config, Region};
+use polly_code_examples::lexicons::{to_pls, validate, Lexeme};
 
 use aws_types::region::{EnvironmentProvider, ProvideRegion};
 
+use common::output::OutputFormat;
+use common::profile::ProfileCredentialsProvider;
+use serde::Serialize;
 use structopt::StructOpt;
 use tracing_subscriber::fmt::format::FmtSpan;
 use tracing_subscriber::fmt::SubscriberBuilder;
@@ -24,33 +30,76 @@ struct Opt {
     name: String,
 
     /// The word to replace
-    #[structopt(short, long)]
-    from: String,
+    #[structopt(short, long, required_unless = "file", conflicts_with = "file")]
+    from: Option<String>,
 
     /// The replacement
-    #[structopt(short, long)]
-    to: String,
+    #[structopt(short, long, required_unless = "file", conflicts_with = "file")]
+    to: Option<String>,
+
+    /// A PLS file to upload as the lexicon, instead of a single replacement
+    #[structopt(long)]
+    file: Option<String>,
+
+    /// The format of the output, text or json
+    #[structopt(long, default_value = "text")]
+    output: OutputFormat,
+
+    /// The AWS profile to use from the shared credentials file
+    #[structopt(long)]
+    profile: Option<String>,
 
     /// Whether to show additional output
     #[structopt(short, long)]
     verbose: bool,
 }
 
-/// Stores a pronunciation lexicon in an AWS Region.
+/// The lexicon that was stored.
+#[derive(Debug, Serialize)]
+struct AddedLexicon {
+    name: String,
+    from: Option<String>,
+    to: Option<String>,
+    file: Option<String>,
+}
+
+impl fmt::Display for AddedLexicon {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match (&self.file, &self.from, &self.to) {
+            (Some(file), _, _) => write!(f, "Added lexicon {} from {}", self.name, file),
+            (None, Some(from), Some(to)) => write!(
+                f,
+                "Added lexicon {} replacing {} with {}",
+                self.name, from, to
+            ),
+            _ => write!(f, "Added lexicon {}", self.name),
+        }
+    }
+}
+
+/// Stores a pronunciation lexicon in an AWS Region, either a Pronunciation Lexicon Specification (PLS) file,
+/// or a lexicon with a single replacement that the example writes.
+/// Either way, the XML is checked before it's uploaded, and replaces any lexicon with the same name.
 /// # Arguments
 ///
-/// * `-f FROM` - The original text to customize.
 /// * `-n NAME` - The name of the lexicon.
+/// * `-f FROM` - The original text to customize.
 /// * `-t TO` - The customized version of the original text.
+/// * `--file FILE` - A PLS file to upload, instead of **-f** and **-t**.
 /// * `[-d DEFAULT-REGION]` - The region in which the client is created.
 ///    If not supplied, uses the value of the **AWS_DEFAULT_REGION** environment variable.
 ///    If the environment variable is not set, defaults to **us-west-2**.
+/// * `[--output FORMAT]` - How to display the result, **text** (the default) or **json**.
+/// * `[--profile PROFILE]` - The AWS profile to use from the shared credentials file.
 /// * `[-v]` - Whether to display additional information.
 #[tokio::main]
-async fn main() {
+async fn main() -> Result<(), Box<dyn Error>> {
     let Opt {
+        file,
         from,
         name,
+        output,
+        profile,
         region,
         to,
         verbose,
@@ -65,8 +114,9 @@ async fn main() {
         println!("polly client version: {}\n", polly::PKG_VERSION);
         println!("Region:           {:?}", &region);
         println!("Lexicon name:     {}", name);
-        println!("Text to replace:  {}", from);
-        println!("Replacement text: {}", to);
+        println!("Text to replace:  {:?}", from);
+        println!("Replacement text: {:?}", to);
+        println!("File:             {:?}", file);
 
         SubscriberBuilder::default()
             .with_env_filter("info")
@@ -74,29 +124,35 @@ async fn main() {
             .init();
     }
 
-    let config = Config::builder().region(region).build();
+    let mut config = Config::builder().region(region);
+    if let Some(profile) = profile {
+        config = config.credentials_provider(ProfileCredentialsProvider::new(profile));
+    }
+
+    let client = Client::from_conf(config.build());
 
-    let client = Client::from_conf(config);
+    // The text is escaped, so a word such as AT&T doesn't break the XML.
+    let content = match (&file, &from, &to) {
+        (Some(file), _, _) => fs::read_to_string(file)?,
+        (None, Some(from), Some(to)) => to_pls("en-US", &[Lexeme::alias(from, to)]),
+        _ => unreachable!("structopt requires a file, or the text to replace and its replacement"),
+    };
 
-    let content = format!("<?xml version=\"1.0\" encoding=\"UTF-8\"?>
-    <lexicon version=\"1.0\" xmlns=\"http://www.w3.org/2005/01/pronunciation-lexicon\" xmlns:xsi=\"http://www.w3.org/2001/XMLSchema-instance\"
-    xsi:schemaLocation=\"http://www.w3.org/2005/01/pronunciation-lexicon http://www.w3.org/TR/2007/CR-pronunciation-lexicon-20071212/pls.xsd\"
-    alphabet=\"ipa\" xml:lang=\"en-US\">
-    <lexeme><grapheme>{}</grapheme><alias>{}</alias></lexeme>
-    </lexicon>", from, to);
+    validate(file.as_deref().unwrap_or("lexicon"), &content)?;
 
-    match client
+    client
         .put_lexicon()
-        .name(name)
+        .name(&name)
         .content(content)
         .send()
-        .await
-    {
-        Ok(_) => println!("Added lexicon"),
-        Err(e) => {
-            println!("Got an error adding lexicon:");
-            println!("{}", e);
-            process::exit(1);
-        }
-    };
+        .await?;
+
+    output.print(&AddedLexicon {
+        name,
+        from,
+        to,
+        file,
+    });
+
+    Ok(())
 }
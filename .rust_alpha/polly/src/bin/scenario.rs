@@ -0,0 +1,216 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0.
+ */
+
+use std::error::Error;
+
+use polly::model::{Engine, LanguageCode, OutputFormat, VoiceId};
+use polly::{Client, Config, Region};
+
+use aws_types::region::{EnvironmentProvider, ProvideRegion};
+
+use bytes::Buf;
+
+use common::profile::ProfileCredentialsProvider;
+use common::shutdown::Shutdown;
+use structopt::StructOpt;
+use tokio::io::AsyncWriteExt;
+use tracing_subscriber::fmt::format::FmtSpan;
+use tracing_subscriber::fmt::SubscriberBuilder;
+
+/// The text that the scenario synthesizes. The lexicon expands the abbreviation.
+const TEXT: &str = "The W3C publishes the standards that web browsers follow.";
+
+/// The lexicon, which tells Amazon Polly to say W3C as World Wide Web Consortium.
+const LEXICON: &str = r#"<?xml version="1.0" encoding="UTF-8"?>
+<lexicon version="1.0" xmlns="http://www.w3.org/2005/01/pronunciation-lexicon"
+    alphabet="ipa" xml:lang="en-US">
+  <lexeme><grapheme>W3C</grapheme><alias>World Wide Web Consortium</alias></lexeme>
+</lexicon>"#;
+
+#[derive(Debug, StructOpt)]
+struct Opt {
+    /// The region
+    #[structopt(short, long)]
+    region: Option<String>,
+
+    /// The name of the lexicon to create. Up to 20 letters and digits
+    #[structopt(short, long, default_value = "rustScenario")]
+    name: String,
+
+    /// The file in which to save the speech
+    #[structopt(short, long, default_value = "scenario.mp3")]
+    filename: String,
+
+    /// Whether to keep the lexicon
+    #[structopt(short, long)]
+    keep: bool,
+
+    /// The AWS profile to use from the shared credentials file
+    #[structopt(long)]
+    profile: Option<String>,
+
+    /// Whether to show additional output
+    #[structopt(short, long)]
+    verbose: bool,
+}
+
+/// The resources that the scenario has created, so that they can be deleted even if a later step fails.
+#[derive(Default)]
+struct Resources {
+    lexicon: bool,
+}
+
+/// Lists the US English voices of the standard engine, and returns the one to use.
+/// Prefers Joanna, which the other examples use.
+async fn choose_voice(client: &Client) -> Result<VoiceId, Box<dyn Error>> {
+    let voices = client
+        .describe_voices()
+        .language_code(LanguageCode::EnUs)
+        .engine(Engine::Standard)
+        .send()
+        .await?
+        .voices
+        .unwrap_or_default();
+
+    for voice in &voices {
+        println!(
+            "  {} ({:?})",
+            voice.name.as_deref().unwrap_or_default(),
+            voice.gender
+        );
+    }
+
+    let ids: Vec<VoiceId> = voices.into_iter().filter_map(|voice| voice.id).collect();
+
+    if ids.contains(&VoiceId::Joanna) {
+        return Ok(VoiceId::Joanna);
+    }
+
+    Ok(ids
+        .into_iter()
+        .next()
+        .ok_or("no US English voices were found")?)
+}
+
+/// Runs the steps of the scenario, stopping at the first error.
+async fn run(client: &Client, opt: &Opt, resources: &mut Resources) -> Result<(), Box<dyn Error>> {
+    println!("1. Listing the US English voices");
+    let voice = choose_voice(client).await?;
+    println!("  Using {}", voice.as_str());
+
+    println!("2. Storing lexicon {}", &opt.name);
+    client
+        .put_lexicon()
+        .name(&opt.name)
+        .content(LEXICON)
+        .send()
+        .await?;
+    resources.lexicon = true;
+
+    println!("3. Getting lexicon {}", &opt.name);
+    let attributes = client
+        .get_lexicon()
+        .name(&opt.name)
+        .send()
+        .await?
+        .lexicon_attributes
+        .ok_or("Amazon Polly did not return the lexicon attributes")?;
+    println!(
+        "  Language: {:?}, lexemes: {}",
+        attributes.language_code, attributes.lexemes_count
+    );
+
+    println!("4. Synthesizing: {}", TEXT);
+    let resp = client
+        .synthesize_speech()
+        .output_format(OutputFormat::Mp3)
+        .text(TEXT)
+        .voice_id(voice)
+        .lexicon_names(&opt.name)
+        .send()
+        .await?;
+
+    let mut blob = resp.audio_stream.collect().await?;
+    let bytes = blob.remaining();
+
+    let mut file = tokio::fs::File::create(&opt.filename).await?;
+    file.write_all_buf(&mut blob).await?;
+    println!("  Saved {} bytes of speech in {}", bytes, &opt.filename);
+
+    Ok(())
+}
+
+/// Deletes the lexicon. The speech file is kept, so that you can listen to it.
+async fn tear_down(client: &Client, opt: &Opt, resources: &Resources) {
+    if resources.lexicon {
+        if let Err(e) = client.delete_lexicon().name(&opt.name).send().await {
+            eprintln!("Got an error deleting lexicon {}: {}", &opt.name, e);
+        }
+    }
+}
+
+/// Gets started with Amazon Polly by running a complete workflow in one program.
+/// The scenario lists the US English voices, stores a pronunciation lexicon and reads it back,
+/// and synthesizes some text with the lexicon into an MP3 file.
+/// Finally, it deletes the lexicon, even if a step fails or you press Ctrl-C.
+/// # Arguments
+///
+/// * `[-n NAME]` - The name of the lexicon to create.
+///   If not supplied, defaults to **rustScenario**.
+/// * `[-f FILENAME]` - The file in which to save the speech.
+///   If not supplied, defaults to **scenario.mp3**.
+/// * `[-k]` - Whether to keep the lexicon.
+/// * `[-r REGION]` - The region in which the client is created.
+///   If the **AWS_DEFAULT_REGION** environment variable is set, it's used instead.
+///   If neither is set, defaults to **us-west-2**.
+/// * `[--profile PROFILE]` - The AWS profile to use from the shared credentials file.
+/// * `[-v]` - Whether to display additional information.
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn Error>> {
+    let opt = Opt::from_args();
+
+    let region = EnvironmentProvider::new()
+        .region()
+        .or_else(|| {
+            opt.region
+                .as_ref()
+                .map(|region| Region::new(region.clone()))
+        })
+        .unwrap_or_else(|| Region::new("us-west-2"));
+
+    if opt.verbose {
+        println!("polly client version: {}\n", polly::PKG_VERSION);
+        println!("Region:   {:?}", &region);
+        println!("Lexicon:  {}", &opt.name);
+        println!("Filename: {}", &opt.filename);
+        println!();
+
+        SubscriberBuilder::default()
+            .with_env_filter("info")
+            .with_span_events(FmtSpan::CLOSE)
+            .init();
+    }
+
+    let mut config = Config::builder().region(region);
+    if let Some(profile) = &opt.profile {
+        config = config.credentials_provider(ProfileCredentialsProvider::new(profile));
+    }
+    let client = Client::from_conf(config.build());
+
+    let mut resources = Resources::default();
+
+    // Ctrl-C stops the scenario wherever it is, and still cleans up what it created.
+    let shutdown = Shutdown::listen();
+    let result = shutdown.run(run(&client, &opt, &mut resources)).await;
+
+    if opt.keep {
+        println!("Keeping lexicon {}", &opt.name);
+    } else {
+        println!("5. Deleting the lexicon");
+        tear_down(&client, &opt, &resources).await;
+    }
+
+    result
+}
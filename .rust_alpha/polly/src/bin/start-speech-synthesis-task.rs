@@ -0,0 +1,266 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0.
+ */
+use std::error::Error;
+use std::fmt;
+use std::fs;
+use std::path::Path;
+use std::time::Duration;
+
+use polly::model::{OutputFormat, TaskStatus, VoiceId};
+use polly::{Client, Config, Region};
+use polly_code_examples::usage::{Estimate, SPEECH_SYNTHESIS_TASK};
+
+use aws_types::region::ProvideRegion;
+
+use common::output;
+use common::profile::ProfileCredentialsProvider;
+use serde::Serialize;
+use structopt::StructOpt;
+use tokio::io::AsyncWriteExt;
+use tokio::time::sleep;
+use tracing_subscriber::fmt::format::FmtSpan;
+use tracing_subscriber::fmt::SubscriberBuilder;
+
+#[derive(Debug, StructOpt)]
+struct Opt {
+    /// The region. Overrides environment variable AWS_DEFAULT_REGION.
+    #[structopt(short, long)]
+    default_region: Option<String>,
+
+    /// The bucket in which Amazon Polly saves the audio file
+    #[structopt(short, long)]
+    bucket: String,
+
+    /// The file containing the text to synthesize
+    #[structopt(short, long)]
+    filename: String,
+
+    /// The prefix of the audio file in the bucket
+    #[structopt(short, long, default_value = "")]
+    prefix: String,
+
+    /// The voice that speaks the text
+    #[structopt(long, default_value = "Joanna")]
+    voice: String,
+
+    /// Whether to download the audio file when the task completes
+    #[structopt(short = "D", long)]
+    download: bool,
+
+    /// Whether to display how many characters are billed and what they cost, rather than synthesize them
+    #[structopt(long)]
+    estimate: bool,
+
+    /// The format of the output, text or json
+    #[structopt(long, default_value = "text")]
+    output: output::OutputFormat,
+
+    /// The AWS profile to use from the shared credentials file
+    #[structopt(long)]
+    profile: Option<String>,
+
+    /// Whether to show additional output
+    #[structopt(short, long)]
+    verbose: bool,
+}
+
+/// The speech synthesis task that completed.
+#[derive(Debug, Serialize)]
+struct CompletedTask {
+    task_id: String,
+    output_uri: String,
+    characters: i32,
+    downloaded: Option<String>,
+}
+
+impl fmt::Display for CompletedTask {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "Task ID:    {}", self.task_id)?;
+        writeln!(f, "Output URI: {}", self.output_uri)?;
+        write!(f, "Characters: {}", self.characters)?;
+
+        if let Some(file) = &self.downloaded {
+            write!(f, "\nSaved the speech in {}", file)?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Downloads the object that Amazon Polly created into a local file.
+async fn download(
+    region: Region,
+    profile: Option<&str>,
+    bucket: &str,
+    key: &str,
+    file: &str,
+) -> Result<(), Box<dyn Error>> {
+    let mut config = s3::Config::builder().region(region);
+    if let Some(profile) = profile {
+        config = config.credentials_provider(ProfileCredentialsProvider::new(profile));
+    }
+    let client = s3::Client::from_conf(config.build());
+
+    let resp = client.get_object().bucket(bucket).key(key).send().await?;
+    let data = resp.body.collect().await?.into_bytes();
+
+    let mut out = tokio::fs::File::create(file).await?;
+    out.write_all(&data).await?;
+
+    Ok(())
+}
+
+/// Synthesizes long UTF-8 input, plain text or SSML, into an MP3 file in an Amazon S3 bucket.
+/// Unlike `SynthesizeSpeech`, a speech synthesis task runs asynchronously and accepts up to 100,000 characters,
+/// so the example polls the task until it completes.
+/// # Arguments
+///
+/// * `-b BUCKET` - The bucket in which Amazon Polly saves the audio file.
+/// * `-f FILENAME` - The name of the file containing the text to synthesize.
+/// * `[-p PREFIX]` - The prefix of the audio file in the bucket.
+///    Amazon Polly names the file with the task ID and an __mp3__ extension.
+/// * `[--voice VOICE]` - The voice that speaks the text.
+///    If not supplied, defaults to **Joanna**.
+/// * `[-D]` - Whether to download the audio file when the task completes.
+///    The file is saved with the same basename as the text file, but with an __mp3__ extension.
+/// * `[--estimate]` - Whether to display the number of billed characters and an estimate of their cost,
+///    rather than start the task. Also warns if the input is too long for a single task.
+/// * `[-d DEFAULT-REGION]` - The region in which the client is created.
+///    If not supplied, uses the value of the **AWS_DEFAULT_REGION** environment variable.
+///    If the environment variable is not set, defaults to **us-west-2**.
+/// * `[--output FORMAT]` - How to display the result, **text** (the default) or **json**.
+/// * `[--profile PROFILE]` - The AWS profile to use from the shared credentials file.
+/// * `[-v]` - Whether to display additional information.
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn Error>> {
+    let Opt {
+        bucket,
+        default_region,
+        download: should_download,
+        estimate,
+        filename,
+        output,
+        prefix,
+        profile,
+        voice,
+        verbose,
+    } = Opt::from_args();
+
+    let region = default_region
+        .as_ref()
+        .map(|region| Region::new(region.clone()))
+        .or_else(|| aws_types::region::default_provider().region())
+        .unwrap_or_else(|| Region::new("us-west-2"));
+
+    if verbose {
+        println!("polly client version: {}\n", polly::PKG_VERSION);
+        println!("Region:   {:?}", &region);
+        println!("Bucket:   {}", &bucket);
+        println!("Filename: {}", &filename);
+        println!("Prefix:   {}", &prefix);
+        println!("Voice:    {}", &voice);
+        println!("Download: {}", should_download);
+
+        SubscriberBuilder::default()
+            .with_env_filter("info")
+            .with_span_events(FmtSpan::CLOSE)
+            .init();
+    }
+
+    let mut config = Config::builder().region(region.clone());
+    if let Some(profile) = &profile {
+        config = config.credentials_provider(ProfileCredentialsProvider::new(profile));
+    }
+
+    let client = Client::from_conf(config.build());
+
+    let content = fs::read_to_string(&filename)?;
+
+    if estimate {
+        output.print(&Estimate::new(
+            &filename,
+            &content,
+            false,
+            &SPEECH_SYNTHESIS_TASK,
+        )?);
+        return Ok(());
+    }
+
+    let resp = client
+        .start_speech_synthesis_task()
+        .output_format(OutputFormat::Mp3)
+        .output_s3_bucket_name(&bucket)
+        .output_s3_key_prefix(&prefix)
+        .text(content)
+        .voice_id(VoiceId::from(voice.as_str()))
+        .send()
+        .await?;
+
+    let task_id = resp
+        .synthesis_task
+        .and_then(|task| task.task_id)
+        .ok_or("Amazon Polly did not return a task ID")?;
+
+    if verbose {
+        println!("Started task {}", &task_id);
+    }
+
+    // Poll the task until it either completes or fails.
+    let task = loop {
+        let resp = client
+            .get_speech_synthesis_task()
+            .task_id(&task_id)
+            .send()
+            .await?;
+
+        let task = resp
+            .synthesis_task
+            .ok_or("Amazon Polly did not return the task")?;
+
+        match task.task_status {
+            Some(TaskStatus::Completed) => break task,
+            Some(TaskStatus::Failed) => {
+                return Err(format!(
+                    "Task {} failed: {}",
+                    task_id,
+                    task.task_status_reason.unwrap_or_default()
+                )
+                .into())
+            }
+            _ => {
+                if verbose {
+                    println!("Task status: {:?}", task.task_status);
+                }
+
+                sleep(Duration::from_secs(5)).await;
+            }
+        }
+    };
+
+    let downloaded = if should_download {
+        // Amazon Polly names the object with the prefix, the task ID, and the format's extension.
+        let key = format!("{}{}.mp3", prefix, task_id);
+        let stem = Path::new(&filename)
+            .file_stem()
+            .and_then(|stem| stem.to_str())
+            .unwrap_or("speech");
+        let file = format!("{}.mp3", stem);
+
+        download(region, profile.as_deref(), &bucket, &key, &file).await?;
+
+        Some(file)
+    } else {
+        None
+    };
+
+    output.print(&CompletedTask {
+        task_id,
+        output_uri: task.output_uri.unwrap_or_default(),
+        characters: task.request_characters,
+        downloaded,
+    });
+
+    Ok(())
+}
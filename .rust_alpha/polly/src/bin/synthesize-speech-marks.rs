@@ -0,0 +1,375 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0.
+ */
+
+use std::error::Error;
+use std::fmt;
+use std::fs;
+use std::path::Path;
+use std::str::FromStr;
+
+use polly::model::{OutputFormat, SpeechMarkType, VoiceId};
+use polly::{Client, Config, Region};
+
+use aws_types::region::{EnvironmentProvider, ProvideRegion};
+
+use bytes::Buf;
+
+use common::output;
+use common::profile::ProfileCredentialsProvider;
+use serde::{Deserialize, Serialize};
+use structopt::StructOpt;
+use tokio::io::AsyncWriteExt;
+use tracing_subscriber::fmt::format::FmtSpan;
+use tracing_subscriber::fmt::SubscriberBuilder;
+
+/// How long the last cue stays on screen after its last word starts, if there's no viseme to end it.
+const LAST_WORD_MILLIS: u64 = 1000;
+
+#[derive(Debug, StructOpt)]
+struct Opt {
+    /// The region
+    #[structopt(short, long)]
+    region: Option<String>,
+
+    /// The file containing the text to synthesize
+    #[structopt(short, long)]
+    filename: String,
+
+    /// The format of the subtitles, srt or vtt
+    #[structopt(short, long, default_value = "vtt")]
+    subtitles: SubtitleFormat,
+
+    /// The most words in each subtitle
+    #[structopt(short, long, default_value = "8")]
+    max_words: usize,
+
+    /// The voice to use
+    #[structopt(long, default_value = "Joanna")]
+    voice: String,
+
+    /// The format of the output, text or json
+    #[structopt(long, default_value = "text")]
+    output: output::OutputFormat,
+
+    /// The AWS profile to use from the shared credentials file
+    #[structopt(long)]
+    profile: Option<String>,
+
+    /// Whether to show additional output
+    #[structopt(short, long)]
+    verbose: bool,
+}
+
+/// The format of the subtitle file.
+#[derive(Clone, Copy, Debug)]
+enum SubtitleFormat {
+    /// SubRip, which most video players read.
+    Srt,
+    /// WebVTT, which web browsers read. Each word is timed, so players can highlight it, karaoke style.
+    Vtt,
+}
+
+impl SubtitleFormat {
+    fn extension(self) -> &'static str {
+        match self {
+            SubtitleFormat::Srt => "srt",
+            SubtitleFormat::Vtt => "vtt",
+        }
+    }
+
+    /// Formats a time in milliseconds as HH:MM:SS,mmm for SubRip or HH:MM:SS.mmm for WebVTT.
+    fn timestamp(self, millis: u64) -> String {
+        let separator = match self {
+            SubtitleFormat::Srt => ',',
+            SubtitleFormat::Vtt => '.',
+        };
+
+        format!(
+            "{:02}:{:02}:{:02}{}{:03}",
+            millis / 3_600_000,
+            millis / 60_000 % 60,
+            millis / 1000 % 60,
+            separator,
+            millis % 1000
+        )
+    }
+}
+
+impl FromStr for SubtitleFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "srt" => Ok(SubtitleFormat::Srt),
+            "vtt" => Ok(SubtitleFormat::Vtt),
+            _ => Err(format!("the subtitle format must be srt or vtt, got {}", s)),
+        }
+    }
+}
+
+/// A speech mark, one line of the newline-delimited JSON that Amazon Polly returns.
+/// The time is in milliseconds from the start of the audio.
+#[derive(Debug, Deserialize)]
+struct SpeechMark {
+    time: u64,
+    #[serde(rename = "type")]
+    kind: String,
+    value: String,
+}
+
+/// A subtitle: the words that are shown together, and when each one is spoken.
+#[derive(Debug)]
+struct Cue {
+    words: Vec<(u64, String)>,
+    end: u64,
+}
+
+/// The files that were written.
+#[derive(Debug, Serialize)]
+struct SubtitledSpeech {
+    input: String,
+    audio: String,
+    subtitles: String,
+    cues: usize,
+    words: usize,
+    visemes: usize,
+    /// How long the speech is, in milliseconds, from the last viseme.
+    duration_millis: u64,
+}
+
+impl fmt::Display for SubtitledSpeech {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "Wrote speech synthesized from {} to {}, and {} subtitles with {} words to {}",
+            self.input, self.audio, self.cues, self.words, self.subtitles
+        )?;
+        write!(
+            f,
+            "\nThe speech lasts {:.1} seconds, with {} visemes",
+            self.duration_millis as f64 / 1000.0,
+            self.visemes
+        )
+    }
+}
+
+/// Groups the words into cues. A cue ends at the start of a sentence, or when it has `max_words` words,
+/// and lasts until the next cue starts. The last cue lasts until the speech ends.
+fn build_cues(marks: &[SpeechMark], max_words: usize, speech_end: u64) -> Vec<Cue> {
+    let mut cues: Vec<Cue> = Vec::new();
+    let mut words = Vec::new();
+
+    for mark in marks {
+        match mark.kind.as_str() {
+            "sentence" if !words.is_empty() => cues.push(Cue {
+                words: std::mem::take(&mut words),
+                end: mark.time,
+            }),
+            "word" => {
+                if words.len() == max_words {
+                    cues.push(Cue {
+                        words: std::mem::take(&mut words),
+                        end: mark.time,
+                    });
+                }
+                words.push((mark.time, mark.value.clone()));
+            }
+            _ => {}
+        }
+    }
+
+    if !words.is_empty() {
+        cues.push(Cue {
+            words,
+            end: speech_end,
+        });
+    }
+
+    cues
+}
+
+/// Writes the cues as a subtitle file.
+/// WebVTT cues have a timestamp tag before each word after the first, so that players can highlight each word as it's spoken.
+fn write_subtitles(cues: &[Cue], format: SubtitleFormat) -> String {
+    let mut out = String::new();
+
+    if let SubtitleFormat::Vtt = format {
+        out.push_str("WEBVTT\n\n");
+    }
+
+    for (index, cue) in cues.iter().enumerate() {
+        if let SubtitleFormat::Srt = format {
+            out.push_str(&format!("{}\n", index + 1));
+        }
+
+        out.push_str(&format!(
+            "{} --> {}\n",
+            format.timestamp(cue.words[0].0),
+            format.timestamp(cue.end)
+        ));
+
+        let text: Vec<String> = cue
+            .words
+            .iter()
+            .enumerate()
+            .map(|(position, (time, word))| match format {
+                SubtitleFormat::Vtt if position > 0 => {
+                    format!("<{}>{}", format.timestamp(*time), word)
+                }
+                _ => word.clone(),
+            })
+            .collect();
+
+        out.push_str(&text.join(" "));
+        out.push_str("\n\n");
+    }
+
+    out
+}
+
+/// Synthesizes a text file into an MP3 file, and writes subtitles that are timed to the speech.
+/// Amazon Polly returns speech marks instead of audio when the output format is JSON:
+/// one JSON object per line, with the time of each sentence, word, and viseme.
+/// The example makes one request for the marks and one for the audio, with the same voice and text,
+/// so that the times match.
+/// # Arguments
+///
+/// * `-f FILENAME` - The name of the file containing the text to synthesize.
+///    The audio is saved in a file with the same basename and an __mp3__ extension,
+///    and the subtitles in a file with an __srt__ or __vtt__ extension.
+/// * `[-s SUBTITLES]` - The format of the subtitles, **srt** or **vtt**.
+///    If not supplied, defaults to **vtt**.
+/// * `[-m MAX-WORDS]` - The most words in each subtitle.
+///    If not supplied, defaults to **8**.
+/// * `[--voice VOICE]` - The voice to use.
+///    If not supplied, defaults to **Joanna**.
+/// * `[-r REGION]` - The region in which the client is created.
+///    If not supplied, uses the value of the **AWS_DEFAULT_REGION** environment variable.
+///    If the environment variable is not set, defaults to **us-west-2**.
+/// * `[--output FORMAT]` - How to display the result, **text** (the default) or **json**.
+/// * `[--profile PROFILE]` - The AWS profile to use from the shared credentials file.
+/// * `[-v]` - Whether to display additional information.
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn Error>> {
+    let Opt {
+        filename,
+        max_words,
+        output,
+        profile,
+        region,
+        subtitles,
+        verbose,
+        voice,
+    } = Opt::from_args();
+
+    if max_words == 0 {
+        return Err("each subtitle needs at least one word".into());
+    }
+
+    let voice_id = VoiceId::from(voice.as_str());
+    if let VoiceId::Unknown(voice) = &voice_id {
+        return Err(format!("unknown voice {}", voice).into());
+    }
+
+    let region = EnvironmentProvider::new()
+        .region()
+        .or_else(|| region.as_ref().map(|region| Region::new(region.clone())))
+        .unwrap_or_else(|| Region::new("us-west-2"));
+
+    if verbose {
+        println!("polly client version: {}\n", polly::PKG_VERSION);
+        println!("Region:    {:?}", &region);
+        println!("Filename:  {}", &filename);
+        println!("Subtitles: {:?}", subtitles);
+        println!("Max words: {}", max_words);
+        println!("Voice:     {}", &voice);
+
+        SubscriberBuilder::default()
+            .with_env_filter("info")
+            .with_span_events(FmtSpan::CLOSE)
+            .init();
+    }
+
+    let mut config = Config::builder().region(region);
+    if let Some(profile) = profile {
+        config = config.credentials_provider(ProfileCredentialsProvider::new(profile));
+    }
+
+    let client = Client::from_conf(config.build());
+
+    let content = fs::read_to_string(&filename)?;
+
+    let resp = client
+        .synthesize_speech()
+        .output_format(OutputFormat::Json)
+        .speech_mark_types(SpeechMarkType::Sentence)
+        .speech_mark_types(SpeechMarkType::Word)
+        .speech_mark_types(SpeechMarkType::Viseme)
+        .text(&content)
+        .voice_id(voice_id.clone())
+        .send()
+        .await?;
+
+    let data = resp.audio_stream.collect().await?.into_bytes();
+    let marks = String::from_utf8_lossy(&data)
+        .lines()
+        .filter(|line| !line.trim().is_empty())
+        .map(serde_json::from_str)
+        .collect::<Result<Vec<SpeechMark>, _>>()?;
+
+    let words = marks.iter().filter(|mark| mark.kind == "word").count();
+    let visemes = marks.iter().filter(|mark| mark.kind == "viseme").count();
+
+    // The last viseme is the silence at the end of the speech.
+    let duration_millis = marks
+        .iter()
+        .filter(|mark| mark.kind == "viseme")
+        .map(|mark| mark.time)
+        .max()
+        .or_else(|| marks.iter().map(|mark| mark.time + LAST_WORD_MILLIS).max())
+        .unwrap_or_default();
+
+    if verbose {
+        println!(
+            "Got {} speech marks: {} words and {} visemes",
+            marks.len(),
+            words,
+            visemes
+        );
+    }
+
+    let cues = build_cues(&marks, max_words, duration_millis);
+
+    let resp = client
+        .synthesize_speech()
+        .output_format(OutputFormat::Mp3)
+        .text(content)
+        .voice_id(voice_id)
+        .send()
+        .await?;
+
+    let mut blob = resp.audio_stream.collect().await?;
+
+    let stem = Path::new(&filename).with_extension("");
+    let audio = format!("{}.mp3", stem.display());
+    let subtitle_file = format!("{}.{}", stem.display(), subtitles.extension());
+
+    let mut file = tokio::fs::File::create(&audio).await?;
+    file.write_all_buf(&mut blob).await?;
+
+    fs::write(&subtitle_file, write_subtitles(&cues, subtitles))?;
+
+    output.print(&SubtitledSpeech {
+        input: filename,
+        audio,
+        subtitles: subtitle_file,
+        cues: cues.len(),
+        words,
+        visemes,
+        duration_millis,
+    });
+
+    Ok(())
+}
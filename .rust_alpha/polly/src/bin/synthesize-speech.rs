@@ -2,14 +2,23 @@
  * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
  * SPDX-License-Identifier: Apache-2.0.
  */
+use std::error::Error;
+use std::fmt;
 use std::fs;
-use std::process;
+use std::path::Path;
 
-use polly::model::{OutputFormat, VoiceId};
-use polly::{Client, Config, Region};
+use polly::error::SynthesizeSpeechErrorKind;
+use polly::model::{OutputFormat, TextType, VoiceId};
+use polly::{Client, Config, Region, SdkError};
+use polly_code_examples::usage::{Estimate, SYNTHESIZE_SPEECH};
 
 use aws_types::region::{EnvironmentProvider, ProvideRegion};
 
+use bytes::Buf;
+
+use common::output;
+use common::profile::ProfileCredentialsProvider;
+use serde::Serialize;
 use structopt::StructOpt;
 use tokio::io::AsyncWriteExt;
 use tracing_subscriber::fmt::format::FmtSpan;
@@ -25,25 +34,112 @@ struct Opt {
     #[structopt(short, long)]
     filename: String,
 
+    /// Whether the file contains SSML. Files with an .ssml or .xml extension are always treated as SSML
+    #[structopt(long)]
+    ssml: bool,
+
+    /// The name of a lexicon to apply. Repeat to apply up to five lexicons, in order of precedence
+    #[structopt(long)]
+    lexicon: Vec<String>,
+
+    /// Whether to display how many characters are billed and what they cost, rather than synthesize them
+    #[structopt(long)]
+    estimate: bool,
+
+    /// The format of the output, text or json
+    #[structopt(long, default_value = "text")]
+    output: output::OutputFormat,
+
+    /// The AWS profile to use from the shared credentials file
+    #[structopt(long)]
+    profile: Option<String>,
+
     /// Whether to show additional output
     #[structopt(short, long)]
     verbose: bool,
 }
 
+/// The text file that was synthesized and the audio file that was written.
+#[derive(Debug, Serialize)]
+struct SynthesizedSpeech {
+    input: String,
+    output: String,
+    bytes: usize,
+}
+
+impl fmt::Display for SynthesizedSpeech {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "Wrote {} bytes of speech synthesized from {} to {}",
+            self.bytes, self.input, self.output
+        )
+    }
+}
+
+/// The most lexicons that a request can apply.
+const MAX_LEXICONS: usize = 5;
+
+/// Returns whether the file should be treated as SSML because of its extension.
+fn has_ssml_extension(filename: &str) -> bool {
+    Path::new(filename)
+        .extension()
+        .and_then(|ext| ext.to_str())
+        .map(|ext| ext.eq_ignore_ascii_case("ssml") || ext.eq_ignore_ascii_case("xml"))
+        .unwrap_or_default()
+}
+
+/// Checks that the SSML is well-formed XML with a <speak> root element,
+/// so that mistakes are reported with their location before the request is sent.
+fn validate_ssml(filename: &str, content: &str) -> Result<(), String> {
+    let doc = roxmltree::Document::parse(content).map_err(|e| {
+        let pos = e.pos();
+        format!("{}:{}:{}: invalid SSML: {}", filename, pos.row, pos.col, e)
+    })?;
+
+    let root = doc.root_element();
+    if root.tag_name().name() != "speak" {
+        let pos = doc.text_pos_at(root.range().start);
+        return Err(format!(
+            "{}:{}:{}: the root element must be <speak>, not <{}>",
+            filename,
+            pos.row,
+            pos.col,
+            root.tag_name().name()
+        ));
+    }
+
+    Ok(())
+}
+
 /// Synthesizes UTF-8 input, plain text or SSML, to a stream of bytes in a file.
 /// # Arguments
 ///
 /// * `-f FILENAME` - The name of the file containing the text to synthesize.
 ///    The output is saved in MP3 format in a file with the same basename, but with an __mp3__ extension.
+/// * `[--ssml]` - Whether the file contains SSML rather than plain text.
+///    Files with an __ssml__ or __xml__ extension are always treated as SSML.
+/// * `[--lexicon LEXICON]` - The name of a pronunciation lexicon, stored with the __put-lexicon__ example, to apply.
+///    Repeat to apply up to five lexicons. If a word is in more than one, the first lexicon wins.
+/// * `[--estimate]` - Whether to display the number of billed characters, which excludes SSML tags,
+///    and an estimate of their cost, rather than synthesize them.
+///    Also warns if the input is too long for a single request.
 /// * `[-d DEFAULT-REGION]` - The region in which the client is created.
 ///    If not supplied, uses the value of the **AWS_DEFAULT_REGION** environment variable.
 ///    If the environment variable is not set, defaults to **us-west-2**.
+/// * `[--output FORMAT]` - How to display the result, **text** (the default) or **json**.
+/// * `[--profile PROFILE]` - The AWS profile to use from the shared credentials file.
 /// * `[-v]` - Whether to display additional information.
 #[tokio::main]
-async fn main() {
+async fn main() -> Result<(), Box<dyn Error>> {
     let Opt {
+        estimate,
         filename,
+        lexicon,
+        output,
+        profile,
         region,
+        ssml,
         verbose,
     } = Opt::from_args();
 
@@ -52,10 +148,21 @@ async fn main() {
         .or_else(|| region.as_ref().map(|region| Region::new(region.clone())))
         .unwrap_or_else(|| Region::new("us-west-2"));
 
+    if lexicon.len() > MAX_LEXICONS {
+        return Err(format!(
+            "a request can apply up to {} lexicons, got {}",
+            MAX_LEXICONS,
+            lexicon.len()
+        )
+        .into());
+    }
+
     if verbose {
         println!("polly client version: {}\n", polly::PKG_VERSION);
         println!("Region:   {:?}", &region);
         println!("Filename: {}", filename);
+        println!("SSML:     {}", ssml);
+        println!("Lexicons: {:?}", &lexicon);
 
         SubscriberBuilder::default()
             .with_env_filter("info")
@@ -63,43 +170,85 @@ async fn main() {
             .init();
     }
 
-    let config = Config::builder().region(region).build();
+    let mut config = Config::builder().region(region);
+    if let Some(profile) = profile {
+        config = config.credentials_provider(ProfileCredentialsProvider::new(profile));
+    }
 
-    let client = Client::from_conf(config);
+    let client = Client::from_conf(config.build());
 
-    let content = fs::read_to_string(&filename);
+    let content = fs::read_to_string(&filename)?;
+
+    let is_ssml = ssml || has_ssml_extension(&filename);
+    if is_ssml {
+        validate_ssml(&filename, &content)?;
+    }
+
+    if estimate {
+        output.print(&Estimate::new(
+            &filename,
+            &content,
+            is_ssml,
+            &SYNTHESIZE_SPEECH,
+        )?);
+        return Ok(());
+    }
+
+    let text_type = if is_ssml {
+        TextType::Ssml
+    } else {
+        TextType::Text
+    };
+
+    let lexicon_names = if lexicon.is_empty() {
+        None
+    } else {
+        Some(lexicon)
+    };
 
     let resp = match client
         .synthesize_speech()
         .output_format(OutputFormat::Mp3)
-        .text(content.unwrap())
+        .set_lexicon_names(lexicon_names)
+        .text(content)
+        .text_type(text_type)
         .voice_id(VoiceId::Joanna)
         .send()
         .await
     {
-        Ok(output) => output,
-        Err(e) => {
-            println!("Got an error synthesizing speech:");
-            println!("{}", e);
-            process::exit(1);
+        Ok(resp) => resp,
+        Err(SdkError::ServiceError { err, .. })
+            if matches!(
+                err.kind,
+                SynthesizeSpeechErrorKind::LexiconNotFoundException(_)
+            ) =>
+        {
+            return Err(format!(
+                "a lexicon wasn't found in the region, or isn't for the voice's language: {}",
+                err
+            )
+            .into());
         }
+        Err(e) => return Err(e.into()),
     };
 
     // Get MP3 data from response and save it
-    let mut blob = resp
-        .audio_stream
-        .collect()
-        .await
-        .expect("failed to read data");
+    let mut blob = resp.audio_stream.collect().await?;
 
     let parts: Vec<&str> = filename.split('.').collect();
     let out_file = format!("{}{}", String::from(parts[0]), ".mp3");
 
-    let mut file = tokio::fs::File::create(out_file)
-        .await
-        .expect("failed to create file");
+    let mut file = tokio::fs::File::create(&out_file).await?;
 
-    file.write_all_buf(&mut blob)
-        .await
-        .expect("failed to write to file");
+    let bytes = blob.remaining();
+
+    file.write_all_buf(&mut blob).await?;
+
+    output.print(&SynthesizedSpeech {
+        input: filename,
+        output: out_file,
+        bytes,
+    });
+
+    Ok(())
 }
@@ -0,0 +1,304 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0.
+ */
+
+//! Builds, checks, and formats pronunciation lexicons.
+//!
+//! A lexicon is a Pronunciation Lexicon Specification (PLS) XML document. `to_pls` writes one
+//! from a list of lexemes, escaping the text, so that a grapheme such as `AT&T` is stored as written,
+//! and `validate` checks one that was written by hand before it's uploaded:
+//!
+//! ```ignore
+//! let content = to_pls("en-US", &[Lexeme::alias("W3C", "World Wide Web Consortium")]);
+//! validate("inline", &content)?;
+//! ```
+//!
+//! Amazon Polly returns a lexicon as the XML it was stored with,
+//! which is often a single line. `pretty_print` indents each element on its own line,
+//! keeping an element that only holds text, such as a `<grapheme>`, on one line:
+//!
+//! ```ignore
+//! let lexicon = resp.lexicon.ok_or("no lexicon returned")?;
+//! println!("{}", pretty_print(&lexicon.content.unwrap_or_default())?);
+//! ```
+//!
+//! The XML is checked first, so that a lexicon that isn't well-formed is reported rather than mangled.
+//! Tags, attributes, comments, and text are copied as they are; only the whitespace between them changes.
+//!
+//! Lexicons belong to a region. To copy them to another region, `list_names` reads every page
+//! of ListLexicons, and each lexicon is saved as `NAME.pls`, which `name_from_file` reads back:
+//!
+//! ```ignore
+//! for name in list_names(&client).await? {
+//!     let resp = client.get_lexicon().name(&name).send().await?;
+//!     fs::write(dir.join(file_name(&name)), resp.lexicon.and_then(|l| l.content).unwrap_or_default())?;
+//! }
+//! ```
+
+use std::path::Path;
+
+use polly::Client;
+
+/// How far each level is indented.
+const INDENT: &str = "  ";
+
+/// The extension of a lexicon file.
+pub const PLS_EXTENSION: &str = "pls";
+
+/// The longest name that a lexicon can have.
+pub const MAX_NAME_LEN: usize = 20;
+
+/// The namespace of the elements of a PLS document.
+pub const PLS_NAMESPACE: &str = "http://www.w3.org/2005/01/pronunciation-lexicon";
+
+/// An entry in a lexicon: a word, and what to say instead of it.
+#[derive(Debug)]
+pub struct Lexeme {
+    /// The word as it's written in the text.
+    pub grapheme: String,
+    /// What to say instead of the grapheme.
+    pub alias: String,
+}
+
+impl Lexeme {
+    /// Says the grapheme as the alias, such as "World Wide Web Consortium" for "W3C".
+    pub fn alias(grapheme: impl Into<String>, alias: impl Into<String>) -> Self {
+        Lexeme {
+            grapheme: grapheme.into(),
+            alias: alias.into(),
+        }
+    }
+}
+
+/// Escapes the characters that mean something in XML text and attribute values.
+pub fn escape_xml(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&apos;"),
+            c => escaped.push(c),
+        }
+    }
+
+    escaped
+}
+
+/// Writes a PLS document for the language, such as __en-US__, with the lexemes.
+/// Aliases are plain words, so the alphabet isn't used, but Amazon Polly requires one, so the document declares IPA.
+pub fn to_pls(language_code: &str, lexemes: &[Lexeme]) -> String {
+    let mut pls = format!(
+        "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n\
+         <lexicon version=\"1.0\" xmlns=\"{}\" alphabet=\"ipa\" xml:lang=\"{}\">\n",
+        PLS_NAMESPACE,
+        escape_xml(language_code)
+    );
+
+    for lexeme in lexemes {
+        pls.push_str(&format!(
+            "{}<lexeme>\n{}{}<grapheme>{}</grapheme>\n{}{}<alias>{}</alias>\n{}</lexeme>\n",
+            INDENT,
+            INDENT,
+            INDENT,
+            escape_xml(&lexeme.grapheme),
+            INDENT,
+            INDENT,
+            escape_xml(&lexeme.alias),
+            INDENT
+        ));
+    }
+
+    pls.push_str("</lexicon>\n");
+    pls
+}
+
+/// Checks that the content is well-formed XML with a PLS `<lexicon>` root element,
+/// so that mistakes are reported with their location before the lexicon is uploaded.
+/// `source` names where the content came from, such as the file name, in the error.
+pub fn validate(source: &str, content: &str) -> Result<(), String> {
+    let doc = roxmltree::Document::parse(content).map_err(|e| {
+        let pos = e.pos();
+        format!("{}:{}:{}: invalid lexicon: {}", source, pos.row, pos.col, e)
+    })?;
+
+    let root = doc.root_element();
+    if root.tag_name().name() != "lexicon" || root.tag_name().namespace() != Some(PLS_NAMESPACE) {
+        let pos = doc.text_pos_at(root.range().start);
+        return Err(format!(
+            "{}:{}:{}: the root element must be <lexicon xmlns=\"{}\">",
+            source, pos.row, pos.col, PLS_NAMESPACE
+        ));
+    }
+
+    Ok(())
+}
+
+/// A piece of the XML document.
+#[derive(Debug, PartialEq)]
+enum Token<'a> {
+    /// A start tag, such as `<lexeme>`.
+    Open(&'a str),
+    /// An end tag, such as `</lexeme>`.
+    Close(&'a str),
+    /// A tag that's complete in itself: an empty element, a declaration, a comment, or CDATA.
+    Other(&'a str),
+    /// The text between two tags, without its leading and trailing whitespace.
+    Text(&'a str),
+}
+
+/// Returns the length of the tag that starts the string.
+/// A `>` inside a quoted attribute value doesn't end the tag.
+fn tag_len(xml: &str) -> usize {
+    for (open, close) in &[("<!--", "-->"), ("<![CDATA[", "]]>"), ("<?", "?>")] {
+        if xml.starts_with(open) {
+            return xml.find(close).map_or(xml.len(), |end| end + close.len());
+        }
+    }
+
+    let mut quote = None;
+
+    for (i, c) in xml.char_indices() {
+        match (quote, c) {
+            (None, '"') | (None, '\'') => quote = Some(c),
+            (Some(q), c) if q == c => quote = None,
+            (None, '>') => return i + 1,
+            _ => {}
+        }
+    }
+
+    xml.len()
+}
+
+/// Splits the XML into tags and text.
+fn tokenize(mut xml: &str) -> Vec<Token<'_>> {
+    let mut tokens = Vec::new();
+
+    while !xml.is_empty() {
+        if xml.starts_with('<') {
+            let (tag, rest) = xml.split_at(tag_len(xml));
+
+            tokens.push(if tag.starts_with("</") {
+                Token::Close(tag)
+            } else if tag.ends_with("/>") || tag.starts_with("<!") || tag.starts_with("<?") {
+                Token::Other(tag)
+            } else {
+                Token::Open(tag)
+            });
+
+            xml = rest;
+        } else {
+            let (text, rest) = xml.split_at(xml.find('<').unwrap_or_else(|| xml.len()));
+
+            if !text.trim().is_empty() {
+                tokens.push(Token::Text(text.trim()));
+            }
+
+            xml = rest;
+        }
+    }
+
+    tokens
+}
+
+/// Indents the lexicon's XML, one element to a line.
+pub fn pretty_print(xml: &str) -> Result<String, roxmltree::Error> {
+    roxmltree::Document::parse(xml)?;
+
+    let tokens = tokenize(xml);
+    let mut out = String::new();
+    let mut depth = 0;
+    let mut i = 0;
+
+    while i < tokens.len() {
+        let indent = INDENT.repeat(depth);
+
+        match (&tokens[i], tokens.get(i + 1), tokens.get(i + 2)) {
+            // An element that only holds text, or nothing, goes on one line.
+            (Token::Open(open), Some(Token::Text(text)), Some(Token::Close(close))) => {
+                out.push_str(&format!("{}{}{}{}\n", indent, open, text, close));
+                i += 3;
+            }
+            (Token::Open(open), Some(Token::Close(close)), _) => {
+                out.push_str(&format!("{}{}{}\n", indent, open, close));
+                i += 2;
+            }
+            (Token::Open(open), _, _) => {
+                out.push_str(&format!("{}{}\n", indent, open));
+                depth += 1;
+                i += 1;
+            }
+            (Token::Close(close), _, _) => {
+                depth = depth.saturating_sub(1);
+                out.push_str(&format!("{}{}\n", INDENT.repeat(depth), close));
+                i += 1;
+            }
+            (Token::Other(tag), _, _) | (Token::Text(tag), _, _) => {
+                out.push_str(&format!("{}{}\n", indent, tag));
+                i += 1;
+            }
+        }
+    }
+
+    Ok(out)
+}
+
+/// Checks that a lexicon name is one to twenty letters and digits, as Amazon Polly requires.
+pub fn check_name(name: &str) -> Result<(), String> {
+    if name.is_empty() || name.len() > MAX_NAME_LEN {
+        return Err(format!(
+            "{} must be from 1 through {} characters long",
+            name, MAX_NAME_LEN
+        ));
+    }
+
+    if !name.chars().all(|c| c.is_ascii_alphanumeric()) {
+        return Err(format!("{} can only hold letters and digits", name));
+    }
+
+    Ok(())
+}
+
+/// Returns the name of the file that a lexicon is saved in, such as __W3C.pls__.
+pub fn file_name(name: &str) -> String {
+    format!("{}.{}", name, PLS_EXTENSION)
+}
+
+/// Returns the name of the lexicon saved in a file, or `None` if the file isn't a `.pls` file.
+pub fn name_from_file(path: &Path) -> Option<String> {
+    if path.extension()? != PLS_EXTENSION {
+        return None;
+    }
+
+    Some(path.file_stem()?.to_string_lossy().into_owned())
+}
+
+/// Lists the names of the lexicons in the client's region, reading every page of ListLexicons.
+pub async fn list_names(client: &Client) -> Result<Vec<String>, polly::Error> {
+    let mut names = Vec::new();
+    let mut next_token = None;
+
+    loop {
+        let resp = client
+            .list_lexicons()
+            .set_next_token(next_token)
+            .send()
+            .await?;
+
+        names.extend(
+            resp.lexicons
+                .unwrap_or_default()
+                .into_iter()
+                .filter_map(|lexicon| lexicon.name),
+        );
+
+        next_token = resp.next_token;
+        if next_token.is_none() {
+            return Ok(names);
+        }
+    }
+}
@@ -0,0 +1,137 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0.
+ */
+
+//! Counts the characters that Amazon Polly bills for, and estimates what synthesizing them costs.
+//!
+//! Amazon Polly bills for every character of plain text, including spaces and punctuation.
+//! For SSML, only the text is billed, not the tags, but the tags still count towards
+//! the total size that each operation accepts:
+//!
+//! ```ignore
+//! let estimate = Estimate::new(&filename, &content, ssml, &SYNTHESIZE_SPEECH)?;
+//! output.print(&estimate);
+//! ```
+
+use std::fmt;
+
+use serde::Serialize;
+
+/// The price of synthesizing one million characters with the standard engine, in US dollars.
+/// See [Amazon Polly pricing](https://aws.amazon.com/polly/pricing/) for the price in your region.
+pub const STANDARD_PRICE_PER_MILLION: f64 = 4.0;
+
+/// How many characters an operation accepts in one request.
+#[derive(Debug)]
+pub struct Limits {
+    /// The name of the operation.
+    pub operation: &'static str,
+    /// The most billed characters.
+    pub billed: usize,
+    /// The most characters, including SSML tags.
+    pub total: usize,
+}
+
+/// The limits of `SynthesizeSpeech`, which returns the speech in its response.
+pub const SYNTHESIZE_SPEECH: Limits = Limits {
+    operation: "SynthesizeSpeech",
+    billed: 3_000,
+    total: 6_000,
+};
+
+/// The limits of `StartSpeechSynthesisTask`, which saves the speech in an Amazon S3 bucket.
+pub const SPEECH_SYNTHESIS_TASK: Limits = Limits {
+    operation: "StartSpeechSynthesisTask",
+    billed: 100_000,
+    total: 200_000,
+};
+
+/// The characters in some input, and what synthesizing them costs.
+#[derive(Debug, Serialize)]
+pub struct Estimate {
+    pub input: String,
+    pub ssml: bool,
+    pub total_characters: usize,
+    pub billed_characters: usize,
+    pub ssml_characters: usize,
+    pub estimated_cost: f64,
+    pub warnings: Vec<String>,
+}
+
+impl Estimate {
+    /// Counts the characters in `content`, and checks them against the operation's limits.
+    /// Fails if `ssml` is set and the content isn't well-formed XML.
+    pub fn new(
+        input: &str,
+        content: &str,
+        ssml: bool,
+        limits: &Limits,
+    ) -> Result<Estimate, roxmltree::Error> {
+        let total = content.chars().count();
+        let billed = if ssml {
+            billed_ssml_characters(content)?
+        } else {
+            total
+        };
+
+        let mut warnings = Vec::new();
+        if billed > limits.billed {
+            warnings.push(format!(
+                "{} accepts at most {} billed characters, and this input has {}",
+                limits.operation, limits.billed, billed
+            ));
+        }
+        if total > limits.total {
+            warnings.push(format!(
+                "{} accepts at most {} characters including SSML tags, and this input has {}",
+                limits.operation, limits.total, total
+            ));
+        }
+
+        Ok(Estimate {
+            input: input.to_string(),
+            ssml,
+            total_characters: total,
+            billed_characters: billed,
+            ssml_characters: total - billed,
+            estimated_cost: billed as f64 * STANDARD_PRICE_PER_MILLION / 1_000_000.0,
+            warnings,
+        })
+    }
+}
+
+impl fmt::Display for Estimate {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "Input:              {}", self.input)?;
+        writeln!(f, "Total characters:   {}", self.total_characters)?;
+        if self.ssml {
+            writeln!(f, "SSML characters:    {}", self.ssml_characters)?;
+        }
+        writeln!(f, "Billed characters:  {}", self.billed_characters)?;
+        write!(
+            f,
+            "Estimated cost:     ${:.6} (standard engine)",
+            self.estimated_cost
+        )?;
+
+        for warning in &self.warnings {
+            write!(f, "\nWarning: {}", warning)?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Returns the number of characters of text in an SSML document, which are the ones Amazon Polly bills for.
+/// Entities such as `&amp;` count as the one character they stand for.
+fn billed_ssml_characters(content: &str) -> Result<usize, roxmltree::Error> {
+    let doc = roxmltree::Document::parse(content)?;
+
+    Ok(doc
+        .descendants()
+        .filter(|node| node.is_text())
+        .filter_map(|node| node.text())
+        .map(|text| text.chars().count())
+        .sum())
+}
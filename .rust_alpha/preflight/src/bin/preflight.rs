@@ -0,0 +1,496 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0.
+ */
+
+use std::env;
+use std::error::Error;
+use std::fmt;
+use std::time::Duration;
+
+use aws_types::region::{ProvideRegion, Region};
+use sts::SdkError;
+
+use common::output::OutputFormat;
+use common::profile::ProfileCredentialsProvider;
+use serde::Serialize;
+use structopt::StructOpt;
+use tokio::time::timeout;
+use tracing_subscriber::fmt::format::FmtSpan;
+use tracing_subscriber::fmt::SubscriberBuilder;
+
+/// The services that have a check, by the name of their directory.
+const SERVICES: &[&str] = &[
+    "cloudwatch",
+    "cloudwatchlogs",
+    "dynamodb",
+    "ec2",
+    "ecr",
+    "ecs",
+    "iam",
+    "kinesis",
+    "kms",
+    "lambda",
+    "polly",
+    "rds",
+    "s3",
+    "secretsmanager",
+    "sns",
+    "sqs",
+    "ssm",
+];
+
+/// The error codes that mean the credentials are fine, but aren't allowed to make the call.
+const DENIED_CODES: &[&str] = &[
+    "AccessDenied",
+    "AccessDeniedException",
+    "AuthorizationError",
+    "UnauthorizedOperation",
+];
+
+/// The error codes that mean the credentials themselves are wrong, so every other call fails too.
+const CREDENTIALS_CODES: &[&str] = &[
+    "AuthFailure",
+    "ExpiredToken",
+    "ExpiredTokenException",
+    "InvalidAccessKeyId",
+    "InvalidClientTokenId",
+    "InvalidSignatureException",
+    "SignatureDoesNotMatch",
+    "UnrecognizedClientException",
+];
+
+/// How long to wait for each call. A call that takes longer usually means the region or the network is wrong.
+const CHECK_TIMEOUT: Duration = Duration::from_secs(15);
+
+#[derive(Debug, StructOpt)]
+struct Opt {
+    /// The region. Overrides environment variable AWS_DEFAULT_REGION.
+    #[structopt(short, long)]
+    default_region: Option<String>,
+
+    /// A service to check, such as s3. Repeat to check several. Defaults to every service
+    #[structopt(short, long)]
+    service: Vec<String>,
+
+    /// The format of the output, text or json
+    #[structopt(long, default_value = "text")]
+    output: OutputFormat,
+
+    /// The AWS profile to use from the shared credentials file
+    #[structopt(long)]
+    profile: Option<String>,
+
+    /// Whether to display additional information
+    #[structopt(short, long)]
+    verbose: bool,
+}
+
+/// The outcome of one check.
+#[derive(Clone, Copy, Debug, PartialEq, Serialize)]
+#[serde(rename_all = "lowercase")]
+enum Status {
+    Pass,
+    /// The credentials aren't allowed to make the call.
+    Denied,
+    Fail,
+    /// The check wasn't run, because the credentials don't work.
+    Skipped,
+}
+
+impl fmt::Display for Status {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let status = match self {
+            Status::Pass => "PASS",
+            Status::Denied => "DENIED",
+            Status::Fail => "FAIL",
+            Status::Skipped => "SKIPPED",
+        };
+        write!(f, "{}", status)
+    }
+}
+
+/// One row of the matrix: a call that an example makes, and whether it worked.
+#[derive(Debug, Serialize)]
+struct Check {
+    service: &'static str,
+    action: &'static str,
+    status: Status,
+    detail: String,
+}
+
+/// Where the credentials and region came from, who the credentials belong to, and the checks.
+#[derive(Debug, Serialize)]
+struct Report {
+    region: String,
+    region_source: &'static str,
+    credentials_source: String,
+    account: Option<String>,
+    arn: Option<String>,
+    checks: Vec<Check>,
+    passed: usize,
+    failed: usize,
+}
+
+impl fmt::Display for Report {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(
+            f,
+            "Region:      {} (from {})",
+            self.region, self.region_source
+        )?;
+        writeln!(f, "Credentials: {}", self.credentials_source)?;
+        writeln!(f, "Account:     {}", self.account.as_deref().unwrap_or("-"))?;
+        writeln!(f, "Identity:    {}", self.arn.as_deref().unwrap_or("-"))?;
+        writeln!(f)?;
+        writeln!(f, "{:<16}{:<34}{:<9}Detail", "Service", "Action", "Result")?;
+
+        for check in &self.checks {
+            writeln!(
+                f,
+                "{:<16}{:<34}{:<9}{}",
+                check.service,
+                check.action,
+                check.status.to_string(),
+                check.detail
+            )?;
+        }
+
+        writeln!(f)?;
+        write!(f, "{} of {} checks passed", self.passed, self.checks.len())
+    }
+}
+
+/// Classifies a service error by its code.
+fn classify(code: Option<&str>, message: String) -> (Status, String) {
+    match code {
+        Some(code) if DENIED_CODES.contains(&code) => (Status::Denied, message),
+        Some(code) if CREDENTIALS_CODES.contains(&code) => (
+            Status::Fail,
+            format!("the credentials were rejected: {}", message),
+        ),
+        _ => (Status::Fail, message),
+    }
+}
+
+/// A check that passed.
+fn check_passed(service: &'static str, action: &'static str) -> Check {
+    Check {
+        service,
+        action,
+        status: Status::Pass,
+        detail: String::new(),
+    }
+}
+
+/// A check that failed, for a reason other than the call being denied.
+fn check_failed(service: &'static str, action: &'static str, detail: String) -> Check {
+    Check {
+        service,
+        action,
+        status: Status::Fail,
+        detail,
+    }
+}
+
+/// Sends the request, with a timeout, and turns the result into a check.
+/// Every service has its own error type, each with a `code` method, so this is a macro rather than a function.
+macro_rules! check {
+    ($service:expr, $action:expr, $request:expr) => {{
+        let (status, detail) = match timeout(CHECK_TIMEOUT, $request.send()).await {
+            Ok(Ok(_)) => (Status::Pass, String::new()),
+            Ok(Err(SdkError::ServiceError { err, .. })) => classify(err.code(), err.to_string()),
+            Ok(Err(e)) => (Status::Fail, e.to_string()),
+            Err(_) => (
+                Status::Fail,
+                format!(
+                    "no response in {} seconds; check the region and your network",
+                    CHECK_TIMEOUT.as_secs()
+                ),
+            ),
+        };
+
+        Check {
+            service: $service,
+            action: $action,
+            status,
+            detail,
+        }
+    }};
+}
+
+/// Creates a client for the service, with the region and, if one is named, the profile's credentials.
+macro_rules! client {
+    ($service:ident, $region:expr, $profile:expr) => {{
+        let mut config = $service::Config::builder().region($region.clone());
+        if let Some(profile) = $profile {
+            config = config.credentials_provider(ProfileCredentialsProvider::new(profile));
+        }
+        $service::Client::from_conf(config.build())
+    }};
+}
+
+/// Checks that the code examples can run: that there are credentials, where the region comes from,
+/// and that the credentials are allowed to make a basic read-only call to each service the examples use,
+/// such as **s3:ListAllMyBuckets** and **dynamodb:ListTables**. Displays a pass/fail matrix.
+/// **DENIED** means the credentials work, but their policies don't allow the call, so the examples for
+/// that service fail the same way. None of the calls create or change anything.
+/// Exits with an error if any check doesn't pass.
+/// # Arguments
+///
+/// * `[-s SERVICE]` - A service to check, such as **s3**. Repeat to check several.
+///   If not supplied, checks every service.
+/// * `[-d DEFAULT-REGION]` - The region in which the clients are created.
+///   If not supplied, uses the value of the **AWS_DEFAULT_REGION** environment variable.
+///   If the environment variable is not set, defaults to **us-west-2**.
+/// * `[--output FORMAT]` - How to display the result, **text** (the default) or **json**.
+/// * `[--profile PROFILE]` - The AWS profile to use from the shared credentials file.
+/// * `[-v]` - Whether to display additional information.
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn Error>> {
+    let Opt {
+        default_region,
+        output,
+        profile,
+        service,
+        verbose,
+    } = Opt::from_args();
+
+    for name in &service {
+        if !SERVICES.contains(&name.as_str()) {
+            return Err(format!(
+                "there's no check for {}; use one of {}",
+                name,
+                SERVICES.join(", ")
+            )
+            .into());
+        }
+    }
+
+    // Most examples take the region from -d first, and then from the environment.
+    // The DynamoDB, Kinesis, and Polly examples prefer AWS_DEFAULT_REGION to their -r option.
+    let (region, region_source) = match default_region {
+        Some(region) => (Region::new(region), "the -d option"),
+        None => match aws_types::region::default_provider().region() {
+            Some(region) => (region, "AWS_DEFAULT_REGION or AWS_REGION"),
+            None => (Region::new("us-west-2"), "the default"),
+        },
+    };
+
+    if verbose {
+        println!("STS client version: {}", sts::PKG_VERSION);
+        println!("Region:             {:?}", &region);
+        println!("Services:           {:?}", &service);
+        println!();
+
+        SubscriberBuilder::default()
+            .with_env_filter("info")
+            .with_span_events(FmtSpan::CLOSE)
+            .init();
+    }
+
+    // Without a profile, the SDK only reads credentials from the environment.
+    let credentials = match &profile {
+        Some(profile) => ProfileCredentialsProvider::new(profile)
+            .load()
+            .map(|_| format!("profile {}", profile))
+            .map_err(|e| e.to_string()),
+        None if env::var_os("AWS_ACCESS_KEY_ID").is_some()
+            && env::var_os("AWS_SECRET_ACCESS_KEY").is_some() =>
+        {
+            Ok("the AWS_ACCESS_KEY_ID and AWS_SECRET_ACCESS_KEY environment variables".to_string())
+        }
+        None => Err(
+            "no profile was named, and AWS_ACCESS_KEY_ID and AWS_SECRET_ACCESS_KEY aren't set"
+                .to_string(),
+        ),
+    };
+
+    let mut report = Report {
+        region: region.as_ref().to_string(),
+        region_source,
+        credentials_source: credentials
+            .clone()
+            .unwrap_or_else(|e| format!("none: {}", e)),
+        account: None,
+        arn: None,
+        checks: Vec::new(),
+        passed: 0,
+        failed: 0,
+    };
+
+    // GetCallerIdentity needs no permissions, so it only fails if the credentials, region, or network are wrong.
+    let identity = if credentials.is_ok() {
+        let client = client!(sts, region, &profile);
+        match timeout(CHECK_TIMEOUT, client.get_caller_identity().send()).await {
+            Ok(Ok(resp)) => {
+                report.account = resp.account;
+                report.arn = resp.arn;
+                check_passed("sts", "sts:GetCallerIdentity")
+            }
+            Ok(Err(SdkError::ServiceError { err, .. })) => {
+                let (status, detail) = classify(err.code(), err.to_string());
+                Check {
+                    service: "sts",
+                    action: "sts:GetCallerIdentity",
+                    status,
+                    detail,
+                }
+            }
+            Ok(Err(e)) => check_failed("sts", "sts:GetCallerIdentity", e.to_string()),
+            Err(_) => check_failed(
+                "sts",
+                "sts:GetCallerIdentity",
+                format!(
+                    "no response in {} seconds; check the region and your network",
+                    CHECK_TIMEOUT.as_secs()
+                ),
+            ),
+        }
+    } else {
+        check_failed(
+            "sts",
+            "sts:GetCallerIdentity",
+            "there are no credentials".to_string(),
+        )
+    };
+
+    let identity_ok = identity.status == Status::Pass;
+    report.checks.push(identity);
+
+    let wanted = |name: &str| service.is_empty() || service.iter().any(|s| s == name);
+
+    for &name in SERVICES.iter().filter(|name| wanted(name)) {
+        if !identity_ok {
+            report.checks.push(Check {
+                service: name,
+                action: "-",
+                status: Status::Skipped,
+                detail: "the credentials don't work, so nothing else will".to_string(),
+            });
+            continue;
+        }
+
+        let check = match name {
+            "cloudwatch" => check!(
+                "cloudwatch",
+                "cloudwatch:ListMetrics",
+                client!(cloudwatch, region, &profile).list_metrics()
+            ),
+            "cloudwatchlogs" => check!(
+                "cloudwatchlogs",
+                "logs:DescribeLogGroups",
+                client!(cloudwatchlogs, region, &profile)
+                    .describe_log_groups()
+                    .limit(1)
+            ),
+            "dynamodb" => check!(
+                "dynamodb",
+                "dynamodb:ListTables",
+                client!(dynamodb, region, &profile).list_tables().limit(1)
+            ),
+            "ec2" => check!(
+                "ec2",
+                "ec2:DescribeInstances",
+                client!(ec2, region, &profile)
+                    .describe_instances()
+                    .max_results(5)
+            ),
+            "ecr" => check!(
+                "ecr",
+                "ecr:DescribeRepositories",
+                client!(ecr, region, &profile)
+                    .describe_repositories()
+                    .max_results(1)
+            ),
+            "ecs" => check!(
+                "ecs",
+                "ecs:ListClusters",
+                client!(ecs, region, &profile)
+                    .list_clusters()
+                    .max_results(1)
+            ),
+            "iam" => check!(
+                "iam",
+                "iam:ListRoles",
+                client!(iam, region, &profile).list_roles().max_items(1)
+            ),
+            "kinesis" => check!(
+                "kinesis",
+                "kinesis:ListStreams",
+                client!(kinesis, region, &profile).list_streams().limit(1)
+            ),
+            "kms" => check!(
+                "kms",
+                "kms:ListKeys",
+                client!(kms, region, &profile).list_keys().limit(1)
+            ),
+            "lambda" => check!(
+                "lambda",
+                "lambda:ListFunctions",
+                client!(lambda, region, &profile)
+                    .list_functions()
+                    .max_items(1)
+            ),
+            "polly" => check!(
+                "polly",
+                "polly:DescribeVoices",
+                client!(polly, region, &profile).describe_voices()
+            ),
+            "rds" => check!(
+                "rds",
+                "rds:DescribeDBInstances",
+                client!(rds, region, &profile)
+                    .describe_db_instances()
+                    .max_records(20)
+            ),
+            "s3" => check!(
+                "s3",
+                "s3:ListAllMyBuckets",
+                client!(s3, region, &profile).list_buckets()
+            ),
+            "secretsmanager" => check!(
+                "secretsmanager",
+                "secretsmanager:ListSecrets",
+                client!(secretsmanager, region, &profile)
+                    .list_secrets()
+                    .max_results(1)
+            ),
+            "sns" => check!(
+                "sns",
+                "sns:ListTopics",
+                client!(sns, region, &profile).list_topics()
+            ),
+            "sqs" => check!(
+                "sqs",
+                "sqs:ListQueues",
+                client!(sqs, region, &profile).list_queues()
+            ),
+            "ssm" => check!(
+                "ssm",
+                "ssm:DescribeParameters",
+                client!(ssm, region, &profile)
+                    .describe_parameters()
+                    .max_results(1)
+            ),
+            _ => unreachable!("every service in SERVICES has a check"),
+        };
+
+        report.checks.push(check);
+    }
+
+    report.passed = report
+        .checks
+        .iter()
+        .filter(|check| check.status == Status::Pass)
+        .count();
+    report.failed = report.checks.len() - report.passed;
+
+    output.print(&report);
+
+    if report.failed > 0 {
+        return Err(format!("{} checks didn't pass", report.failed).into());
+    }
+
+    Ok(())
+}
@@ -8,6 +8,7 @@ use aws_types::region::ProvideRegion;
 use qldb::model::PermissionsMode;
 use qldb::{Client, Config, Error, Region};
 
+use common::profile::ProfileCredentialsProvider;
 use structopt::StructOpt;
 use tracing_subscriber::fmt::format::FmtSpan;
 use tracing_subscriber::fmt::SubscriberBuilder;
@@ -22,6 +23,10 @@ struct Opt {
     #[structopt(short, long)]
     ledger: String,
 
+    /// The AWS profile to use from the shared credentials file
+    #[structopt(long)]
+    profile: Option<String>,
+
     /// Whether to display additional runtime information
     #[structopt(short, long)]
     verbose: bool,
@@ -34,12 +39,14 @@ struct Opt {
 /// * `[-d DEFAULT-REGION]` - The region in which the client is created.
 ///    If not supplied, uses the value of the **AWS_DEFAULT_REGION** environment variable.
 ///    If the environment variable is not set, defaults to **us-west-2**.
+/// * `[--profile PROFILE]` - The AWS profile to use from the shared credentials file.
 /// * `[-v]` - Whether to display additional information.
 #[tokio::main]
 async fn main() -> Result<(), Error> {
     let Opt {
         default_region,
         ledger,
+        profile,
         verbose,
     } = Opt::from_args();
 
@@ -59,8 +66,11 @@ async fn main() -> Result<(), Error> {
             .init();
     }
 
-    let conf = Config::builder().region(region).build();
-    let client = Client::from_conf(conf);
+    let mut config = Config::builder().region(region);
+    if let Some(profile) = profile {
+        config = config.credentials_provider(ProfileCredentialsProvider::new(profile));
+    }
+    let client = Client::from_conf(config.build());
 
     let result = client
         .create_ledger()
@@ -69,7 +79,7 @@ async fn main() -> Result<(), Error> {
         .send()
         .await?;
 
-    println!("ARN: {}", result.arn.unwrap());
+    println!("ARN: {}", result.arn.as_deref().unwrap_or_default());
 
     Ok(())
 }
@@ -8,6 +8,7 @@ use aws_types::region::ProvideRegion;
 //use qldbsession::model::StartSessionRequest;
 use qldb::{Client, Config, Error, Region};
 
+use common::profile::ProfileCredentialsProvider;
 use structopt::StructOpt;
 use tracing_subscriber::fmt::format::FmtSpan;
 use tracing_subscriber::fmt::SubscriberBuilder;
@@ -18,6 +19,10 @@ struct Opt {
     #[structopt(short, long)]
     default_region: Option<String>,
 
+    /// The AWS profile to use from the shared credentials file
+    #[structopt(long)]
+    profile: Option<String>,
+
     /// Whether to display additional runtime information
     #[structopt(short, long)]
     verbose: bool,
@@ -29,11 +34,13 @@ struct Opt {
 /// * `[-d DEFAULT-REGION]` - The region in which the client is created.
 ///    If not supplied, uses the value of the **AWS_DEFAULT_REGION** environment variable.
 ///    If the environment variable is not set, defaults to **us-west-2**.
+/// * `[--profile PROFILE]` - The AWS profile to use from the shared credentials file.
 /// * `[-v]` - Whether to display additional information.
 #[tokio::main]
 async fn main() -> Result<(), Error> {
     let Opt {
         default_region,
+        profile,
         verbose,
     } = Opt::from_args();
 
@@ -53,8 +60,11 @@ async fn main() -> Result<(), Error> {
             .init();
     }
 
-    let conf = Config::builder().region(region).build();
-    let client = Client::from_conf(conf);
+    let mut config = Config::builder().region(region);
+    if let Some(profile) = profile {
+        config = config.credentials_provider(ProfileCredentialsProvider::new(profile));
+    }
+    let client = Client::from_conf(config.build());
 
     let result = client.list_ledgers().send().await?;
 
@@ -0,0 +1,211 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0.
+ */
+
+use std::error::Error;
+use std::time::Duration;
+
+use rds::{Client, Config, Region};
+use rds_code_examples::waiters::{status_name, wait_for_db_instance, AVAILABLE};
+
+use aws_types::region::ProvideRegion;
+
+use common::profile::ProfileCredentialsProvider;
+use rand::distributions::Alphanumeric;
+use rand::Rng;
+use structopt::StructOpt;
+use tracing_subscriber::fmt::format::FmtSpan;
+use tracing_subscriber::fmt::SubscriberBuilder;
+
+/// How long to wait for the new DB instance to be available. Most take 5 to 15 minutes.
+const AVAILABLE_TIMEOUT: Duration = Duration::from_secs(40 * 60);
+
+/// The length of a generated master password.
+const PASSWORD_LENGTH: usize = 20;
+
+#[derive(Debug, StructOpt)]
+struct Opt {
+    /// The region. Overrides environment variable AWS_DEFAULT_REGION.
+    #[structopt(short, long)]
+    default_region: Option<String>,
+
+    /// The identifier of the DB instance, such as database-1
+    #[structopt(short, long)]
+    instance: String,
+
+    /// The database engine, such as mysql or postgres
+    #[structopt(short, long, default_value = "mysql")]
+    engine: String,
+
+    /// The version of the database engine. Defaults to the engine's default version
+    #[structopt(long)]
+    engine_version: Option<String>,
+
+    /// The DB instance class, such as db.t3.micro
+    #[structopt(short, long, default_value = "db.t3.micro")]
+    class: String,
+
+    /// The storage to allocate, in GiB
+    #[structopt(short, long, default_value = "20")]
+    storage: i32,
+
+    /// The storage type, gp2, gp3, io1, or standard
+    #[structopt(long)]
+    storage_type: Option<String>,
+
+    /// The name of the master user
+    #[structopt(short, long, default_value = "admin")]
+    username: String,
+
+    /// The password of the master user. Defaults to a random password, which is displayed once
+    #[structopt(short, long)]
+    password: Option<String>,
+
+    /// The name of a database to create in the DB instance
+    #[structopt(long)]
+    db_name: Option<String>,
+
+    /// Whether to return as soon as the DB instance is being created, rather than waiting until it's available
+    #[structopt(long)]
+    no_wait: bool,
+
+    /// The AWS profile to use from the shared credentials file
+    #[structopt(long)]
+    profile: Option<String>,
+
+    /// Whether to display additional runtime information
+    #[structopt(short, long)]
+    verbose: bool,
+}
+
+/// Creates an Amazon RDS DB instance, and displays its status until it's available.
+/// The DB instance is created in the default VPC, and isn't publicly accessible.
+/// # Arguments
+///
+/// * `-i INSTANCE` - The identifier of the DB instance.
+/// * `[-e ENGINE]` - The database engine, such as **postgres**.
+///   If not supplied, defaults to **mysql**.
+/// * `[--engine-version ENGINE-VERSION]` - The version of the database engine.
+///   If not supplied, uses the engine's default version.
+/// * `[-c CLASS]` - The DB instance class.
+///   If not supplied, defaults to **db.t3.micro**.
+/// * `[-s STORAGE]` - The storage to allocate, in GiB.
+///   If not supplied, defaults to **20**.
+/// * `[--storage-type STORAGE-TYPE]` - The storage type, **gp2**, **gp3**, **io1**, or **standard**.
+///   If not supplied, Amazon RDS chooses.
+/// * `[-u USERNAME]` - The name of the master user.
+///   If not supplied, defaults to **admin**.
+/// * `[-p PASSWORD]` - The password of the master user.
+///   If not supplied, a random password is generated and displayed once.
+/// * `[--db-name DB-NAME]` - The name of a database to create in the DB instance.
+/// * `[--no-wait]` - Return as soon as the DB instance is being created.
+/// * `[-d DEFAULT-REGION]` - The region in which the client is created.
+///   If not supplied, uses the value of the **AWS_DEFAULT_REGION** environment variable.
+///   If the environment variable is not set, defaults to **us-west-2**.
+/// * `[--profile PROFILE]` - The AWS profile to use from the shared credentials file.
+/// * `[-v]` - Whether to display additional information.
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn Error>> {
+    let Opt {
+        class,
+        db_name,
+        default_region,
+        engine,
+        engine_version,
+        instance,
+        no_wait,
+        password,
+        profile,
+        storage,
+        storage_type,
+        username,
+        verbose,
+    } = Opt::from_args();
+
+    let region = default_region
+        .as_ref()
+        .map(|region| Region::new(region.clone()))
+        .or_else(|| aws_types::region::default_provider().region())
+        .unwrap_or_else(|| Region::new("us-west-2"));
+
+    if storage <= 0 {
+        return Err("the storage must be at least 1 GiB".into());
+    }
+
+    if verbose {
+        println!("RDS client version: {}", rds::PKG_VERSION);
+        println!("Region:             {:?}", &region);
+        println!("DB instance:        {}", &instance);
+        println!("Engine:             {}", &engine);
+        println!("Engine version:     {:?}", &engine_version);
+        println!("Class:              {}", &class);
+        println!("Storage:            {} GiB", storage);
+        println!("Storage type:       {:?}", &storage_type);
+        println!("Master username:    {}", &username);
+        println!("Database name:      {:?}", &db_name);
+        println!();
+
+        SubscriberBuilder::default()
+            .with_env_filter("info")
+            .with_span_events(FmtSpan::CLOSE)
+            .init();
+    }
+
+    // Letters and digits meet every engine's password rules.
+    let generated = password.is_none();
+    let password = password.unwrap_or_else(|| {
+        rand::thread_rng()
+            .sample_iter(&Alphanumeric)
+            .take(PASSWORD_LENGTH)
+            .map(char::from)
+            .collect()
+    });
+
+    let mut config = Config::builder().region(region);
+    if let Some(profile) = profile {
+        config = config.credentials_provider(ProfileCredentialsProvider::new(profile));
+    }
+    let client = Client::from_conf(config.build());
+
+    client
+        .create_db_instance()
+        .db_instance_identifier(&instance)
+        .engine(&engine)
+        .set_engine_version(engine_version)
+        .db_instance_class(&class)
+        .allocated_storage(storage)
+        .set_storage_type(storage_type)
+        .master_username(&username)
+        .master_user_password(&password)
+        .set_db_name(db_name)
+        .publicly_accessible(false)
+        .send()
+        .await?;
+
+    println!("Creating DB instance {}", &instance);
+
+    if generated {
+        println!(
+            "The password of master user {} is {}. Save it now; it isn't displayed again",
+            &username, &password
+        );
+    }
+
+    if no_wait {
+        return Ok(());
+    }
+
+    wait_for_db_instance(
+        &client,
+        &instance,
+        Some(AVAILABLE),
+        AVAILABLE_TIMEOUT,
+        |status| println!("DB instance status: {}", status_name(status)),
+    )
+    .await?;
+
+    println!("DB instance {} is available", &instance);
+
+    Ok(())
+}
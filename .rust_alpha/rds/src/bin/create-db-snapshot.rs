@@ -0,0 +1,123 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0.
+ */
+
+use std::error::Error;
+use std::time::Duration;
+
+use rds::{Client, Config, Region};
+use rds_code_examples::waiters::{status_name, wait_for_db_snapshot};
+
+use aws_types::region::ProvideRegion;
+
+use common::profile::ProfileCredentialsProvider;
+use structopt::StructOpt;
+use tracing_subscriber::fmt::format::FmtSpan;
+use tracing_subscriber::fmt::SubscriberBuilder;
+
+/// How long to wait for the DB snapshot to be available. It depends on how much data the DB instance has.
+const AVAILABLE_TIMEOUT: Duration = Duration::from_secs(60 * 60);
+
+#[derive(Debug, StructOpt)]
+struct Opt {
+    /// The region. Overrides environment variable AWS_DEFAULT_REGION.
+    #[structopt(short, long)]
+    default_region: Option<String>,
+
+    /// The identifier of the DB instance to snapshot
+    #[structopt(short, long)]
+    instance: String,
+
+    /// The identifier of the DB snapshot, such as database-1-before-upgrade
+    #[structopt(short, long)]
+    snapshot: String,
+
+    /// Whether to return as soon as the DB snapshot is being created, rather than waiting until it's available
+    #[structopt(long)]
+    no_wait: bool,
+
+    /// The AWS profile to use from the shared credentials file
+    #[structopt(long)]
+    profile: Option<String>,
+
+    /// Whether to display additional runtime information
+    #[structopt(short, long)]
+    verbose: bool,
+}
+
+/// Creates a manual snapshot of an Amazon RDS DB instance, and displays its progress until it's available.
+/// Unlike automated backups, a manual snapshot is kept until you delete it, even after the DB instance is deleted.
+/// # Arguments
+///
+/// * `-i INSTANCE` - The identifier of the DB instance to snapshot.
+///   It must be **available**.
+/// * `-s SNAPSHOT` - The identifier of the DB snapshot.
+/// * `[--no-wait]` - Return as soon as the DB snapshot is being created.
+/// * `[-d DEFAULT-REGION]` - The region in which the client is created.
+///   If not supplied, uses the value of the **AWS_DEFAULT_REGION** environment variable.
+///   If the environment variable is not set, defaults to **us-west-2**.
+/// * `[--profile PROFILE]` - The AWS profile to use from the shared credentials file.
+/// * `[-v]` - Whether to display additional information.
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn Error>> {
+    let Opt {
+        default_region,
+        instance,
+        no_wait,
+        profile,
+        snapshot,
+        verbose,
+    } = Opt::from_args();
+
+    let region = default_region
+        .as_ref()
+        .map(|region| Region::new(region.clone()))
+        .or_else(|| aws_types::region::default_provider().region())
+        .unwrap_or_else(|| Region::new("us-west-2"));
+
+    if verbose {
+        println!("RDS client version: {}", rds::PKG_VERSION);
+        println!("Region:             {:?}", &region);
+        println!("DB instance:        {}", &instance);
+        println!("DB snapshot:        {}", &snapshot);
+        println!();
+
+        SubscriberBuilder::default()
+            .with_env_filter("info")
+            .with_span_events(FmtSpan::CLOSE)
+            .init();
+    }
+
+    let mut config = Config::builder().region(region);
+    if let Some(profile) = profile {
+        config = config.credentials_provider(ProfileCredentialsProvider::new(profile));
+    }
+    let client = Client::from_conf(config.build());
+
+    client
+        .create_db_snapshot()
+        .db_instance_identifier(&instance)
+        .db_snapshot_identifier(&snapshot)
+        .send()
+        .await?;
+
+    println!("Creating DB snapshot {} of {}", &snapshot, &instance);
+
+    if no_wait {
+        return Ok(());
+    }
+
+    wait_for_db_snapshot(&client, &snapshot, AVAILABLE_TIMEOUT, |status, progress| {
+        println!(
+            "DB snapshot status: {} ({}% done)",
+            status_name(status),
+            progress
+        )
+    })
+    .await?;
+
+    println!("DB snapshot {} is available", &snapshot);
+
+    Ok(())
+}
@@ -0,0 +1,138 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0.
+ */
+
+use std::error::Error;
+use std::time::Duration;
+
+use rds::{Client, Config, Region};
+use rds_code_examples::waiters::{status_name, wait_for_db_instance};
+
+use aws_types::region::ProvideRegion;
+
+use common::profile::ProfileCredentialsProvider;
+use structopt::StructOpt;
+use tracing_subscriber::fmt::format::FmtSpan;
+use tracing_subscriber::fmt::SubscriberBuilder;
+
+/// How long to wait for the DB instance to be deleted, including taking its final snapshot.
+const DELETED_TIMEOUT: Duration = Duration::from_secs(60 * 60);
+
+#[derive(Debug, StructOpt)]
+struct Opt {
+    /// The region. Overrides environment variable AWS_DEFAULT_REGION.
+    #[structopt(short, long)]
+    default_region: Option<String>,
+
+    /// The identifier of the DB instance to delete
+    #[structopt(short, long)]
+    instance: String,
+
+    /// The identifier of a DB snapshot to take before the DB instance is deleted
+    #[structopt(
+        short,
+        long,
+        required_unless = "skip-final-snapshot",
+        conflicts_with = "skip-final-snapshot"
+    )]
+    final_snapshot: Option<String>,
+
+    /// Whether to delete the DB instance without taking a final snapshot, losing its data
+    #[structopt(long)]
+    skip_final_snapshot: bool,
+
+    /// Whether to return as soon as the DB instance is being deleted, rather than waiting until it's gone
+    #[structopt(long)]
+    no_wait: bool,
+
+    /// The AWS profile to use from the shared credentials file
+    #[structopt(long)]
+    profile: Option<String>,
+
+    /// Whether to display additional runtime information
+    #[structopt(short, long)]
+    verbose: bool,
+}
+
+/// Deletes an Amazon RDS DB instance, and displays its status until it's gone.
+/// You must choose between a final snapshot and none, so the data isn't lost by accident.
+/// The DB instance's automated backups are deleted with it; a final snapshot is kept until you delete it.
+/// # Arguments
+///
+/// * `-i INSTANCE` - The identifier of the DB instance to delete.
+/// * `[-f FINAL-SNAPSHOT]` - The identifier of a DB snapshot to take before the DB instance is deleted.
+/// * `[--skip-final-snapshot]` - Delete the DB instance without a final snapshot.
+///   You must supply either **-f** or **--skip-final-snapshot**.
+/// * `[--no-wait]` - Return as soon as the DB instance is being deleted.
+/// * `[-d DEFAULT-REGION]` - The region in which the client is created.
+///   If not supplied, uses the value of the **AWS_DEFAULT_REGION** environment variable.
+///   If the environment variable is not set, defaults to **us-west-2**.
+/// * `[--profile PROFILE]` - The AWS profile to use from the shared credentials file.
+/// * `[-v]` - Whether to display additional information.
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn Error>> {
+    let Opt {
+        default_region,
+        final_snapshot,
+        instance,
+        no_wait,
+        profile,
+        skip_final_snapshot,
+        verbose,
+    } = Opt::from_args();
+
+    let region = default_region
+        .as_ref()
+        .map(|region| Region::new(region.clone()))
+        .or_else(|| aws_types::region::default_provider().region())
+        .unwrap_or_else(|| Region::new("us-west-2"));
+
+    if verbose {
+        println!("RDS client version: {}", rds::PKG_VERSION);
+        println!("Region:             {:?}", &region);
+        println!("DB instance:        {}", &instance);
+        println!("Final snapshot:     {:?}", &final_snapshot);
+        println!();
+
+        SubscriberBuilder::default()
+            .with_env_filter("info")
+            .with_span_events(FmtSpan::CLOSE)
+            .init();
+    }
+
+    let mut config = Config::builder().region(region);
+    if let Some(profile) = profile {
+        config = config.credentials_provider(ProfileCredentialsProvider::new(profile));
+    }
+    let client = Client::from_conf(config.build());
+
+    client
+        .delete_db_instance()
+        .db_instance_identifier(&instance)
+        .skip_final_snapshot(skip_final_snapshot)
+        .set_final_db_snapshot_identifier(final_snapshot.clone())
+        .send()
+        .await?;
+
+    match &final_snapshot {
+        Some(final_snapshot) => println!(
+            "Deleting DB instance {}, after taking DB snapshot {}",
+            &instance, final_snapshot
+        ),
+        None => println!("Deleting DB instance {}", &instance),
+    }
+
+    if no_wait {
+        return Ok(());
+    }
+
+    wait_for_db_instance(&client, &instance, None, DELETED_TIMEOUT, |status| {
+        println!("DB instance status: {}", status_name(status))
+    })
+    .await?;
+
+    println!("Deleted DB instance {}", &instance);
+
+    Ok(())
+}
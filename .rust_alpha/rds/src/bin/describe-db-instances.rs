@@ -0,0 +1,147 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0.
+ */
+
+use std::error::Error;
+
+use rds::{Client, Config, Region};
+
+use aws_types::region::ProvideRegion;
+
+use common::profile::ProfileCredentialsProvider;
+use structopt::StructOpt;
+use tracing_subscriber::fmt::format::FmtSpan;
+use tracing_subscriber::fmt::SubscriberBuilder;
+
+#[derive(Debug, StructOpt)]
+struct Opt {
+    /// The region. Overrides environment variable AWS_DEFAULT_REGION.
+    #[structopt(short, long)]
+    default_region: Option<String>,
+
+    /// The identifier of the DB instance to describe
+    #[structopt(short, long)]
+    instance: Option<String>,
+
+    /// The AWS profile to use from the shared credentials file
+    #[structopt(long)]
+    profile: Option<String>,
+
+    /// Whether to display additional runtime information
+    #[structopt(short, long)]
+    verbose: bool,
+}
+
+/// Lists your Amazon RDS DB instances, with their engine, class, storage, status, and endpoint,
+/// following every page of results.
+/// # Arguments
+///
+/// * `[-i INSTANCE]` - The identifier of the DB instance to describe.
+///   If not supplied, describes all of your DB instances.
+/// * `[-d DEFAULT-REGION]` - The region in which the client is created.
+///   If not supplied, uses the value of the **AWS_DEFAULT_REGION** environment variable.
+///   If the environment variable is not set, defaults to **us-west-2**.
+/// * `[--profile PROFILE]` - The AWS profile to use from the shared credentials file.
+/// * `[-v]` - Whether to display additional information.
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn Error>> {
+    let Opt {
+        default_region,
+        instance,
+        profile,
+        verbose,
+    } = Opt::from_args();
+
+    let region = default_region
+        .as_ref()
+        .map(|region| Region::new(region.clone()))
+        .or_else(|| aws_types::region::default_provider().region())
+        .unwrap_or_else(|| Region::new("us-west-2"));
+
+    if verbose {
+        println!("RDS client version: {}", rds::PKG_VERSION);
+        println!("Region:             {:?}", &region);
+        println!("DB instance:        {:?}", &instance);
+        println!();
+
+        SubscriberBuilder::default()
+            .with_env_filter("info")
+            .with_span_events(FmtSpan::CLOSE)
+            .init();
+    }
+
+    let mut config = Config::builder().region(region);
+    if let Some(profile) = profile {
+        config = config.credentials_provider(ProfileCredentialsProvider::new(profile));
+    }
+    let client = Client::from_conf(config.build());
+
+    let mut count = 0;
+    let mut marker = None;
+
+    println!("DB instances:");
+
+    loop {
+        let resp = client
+            .describe_db_instances()
+            .set_db_instance_identifier(instance.clone())
+            .set_marker(marker)
+            .send()
+            .await?;
+
+        for db_instance in resp.db_instances.unwrap_or_default() {
+            println!(
+                "  {}",
+                db_instance
+                    .db_instance_identifier
+                    .as_deref()
+                    .unwrap_or_default()
+            );
+            println!(
+                "  Engine:   {} {}",
+                db_instance.engine.as_deref().unwrap_or_default(),
+                db_instance.engine_version.as_deref().unwrap_or_default()
+            );
+            println!(
+                "  Class:    {}",
+                db_instance.db_instance_class.as_deref().unwrap_or_default()
+            );
+            println!(
+                "  Storage:  {} GiB {}",
+                db_instance.allocated_storage,
+                db_instance.storage_type.as_deref().unwrap_or_default()
+            );
+            println!(
+                "  Status:   {}",
+                db_instance
+                    .db_instance_status
+                    .as_deref()
+                    .unwrap_or_default()
+            );
+            println!("  Multi-AZ: {}", db_instance.multi_az);
+
+            // The endpoint isn't available until the DB instance is created.
+            match &db_instance.endpoint {
+                Some(endpoint) => println!(
+                    "  Endpoint: {}:{}",
+                    endpoint.address.as_deref().unwrap_or_default(),
+                    endpoint.port
+                ),
+                None => println!("  Endpoint: not available yet"),
+            }
+            println!();
+
+            count += 1;
+        }
+
+        marker = resp.marker;
+        if marker.is_none() {
+            break;
+        }
+    }
+
+    println!("Found {} DB instances", count);
+
+    Ok(())
+}
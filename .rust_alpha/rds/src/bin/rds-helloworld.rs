@@ -7,6 +7,7 @@ use rds::{Client, Config, Region};
 
 use aws_types::region::ProvideRegion;
 
+use common::profile::ProfileCredentialsProvider;
 use structopt::StructOpt;
 use tracing_subscriber::fmt::format::FmtSpan;
 use tracing_subscriber::fmt::SubscriberBuilder;
@@ -17,6 +18,10 @@ struct Opt {
     #[structopt(short, long)]
     default_region: Option<String>,
 
+    /// The AWS profile to use from the shared credentials file
+    #[structopt(long)]
+    profile: Option<String>,
+
     /// Whether to display additional runtime information
     #[structopt(short, long)]
     verbose: bool,
@@ -28,11 +33,13 @@ struct Opt {
 /// * `[-d DEFAULT-REGION]` - The region in which the client is created.
 ///    If not supplied, uses the value of the **AWS_DEFAULT_REGION** environment variable.
 ///    If the environment variable is not set, defaults to **us-west-2**.
+/// * `[--profile PROFILE]` - The AWS profile to use from the shared credentials file.
 /// * `[-v]` - Whether to display additional information.
 #[tokio::main]
 async fn main() -> Result<(), rds::Error> {
     let Opt {
         default_region,
+        profile,
         verbose,
     } = Opt::from_args();
 
@@ -52,38 +59,43 @@ async fn main() -> Result<(), rds::Error> {
             .init();
     }
 
-    let conf = Config::builder().region(region).build();
-    let client = Client::from_conf(conf);
+    let mut config = Config::builder().region(region);
+    if let Some(profile) = profile {
+        config = config.credentials_provider(ProfileCredentialsProvider::new(profile));
+    }
+    let client = Client::from_conf(config.build());
 
     let result = client.describe_db_instances().send().await?;
 
     for db_instance in result.db_instances.unwrap_or_default() {
         println!(
-            "DB instance identifier: {:?}",
+            "DB instance identifier: {}",
             db_instance
                 .db_instance_identifier
-                .expect("instance should have identifiers")
+                .as_deref()
+                .unwrap_or_default()
         );
         println!(
-            "DB instance class:      {:?}",
-            db_instance
-                .db_instance_class
-                .expect("instance should have class")
+            "DB instance class:      {}",
+            db_instance.db_instance_class.as_deref().unwrap_or_default()
         );
         println!(
-            "DB instance engine:     {:?}",
-            db_instance.engine.expect("instance should have engine")
+            "DB instance engine:     {}",
+            db_instance.engine.as_deref().unwrap_or_default()
         );
         println!(
-            "DB instance status:     {:?}",
+            "DB instance status:     {}",
             db_instance
                 .db_instance_status
-                .expect("instance should have status")
-        );
-        println!(
-            "DB instance endpoint:   {:?}",
-            db_instance.endpoint.expect("instance should have endpoint")
+                .as_deref()
+                .unwrap_or_default()
         );
+
+        // The endpoint isn't available until the instance is created.
+        match db_instance.endpoint {
+            Some(endpoint) => println!("DB instance endpoint:   {:?}", endpoint),
+            None => println!("DB instance endpoint:   not available yet"),
+        }
     }
 
     Ok(())
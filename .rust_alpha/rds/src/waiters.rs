@@ -0,0 +1,238 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0.
+ */
+
+//! Waits for an Amazon RDS DB instance or DB snapshot to reach a status.
+//!
+//! `CreateDBInstance`, `CreateDBSnapshot`, and `DeleteDBInstance` return as soon as the work starts,
+//! and the work takes minutes. The waiters poll `DescribeDBInstances` or `DescribeDBSnapshots`,
+//! and call `on_status` each time the status changes, so that an example can show the transitions:
+//!
+//! ```ignore
+//! wait_for_db_instance(&client, &id, Some(AVAILABLE), Duration::from_secs(1800), |status| {
+//!     println!("DB instance status: {}", status_name(status));
+//! })
+//! .await?;
+//! ```
+
+use std::error::Error;
+use std::fmt;
+use std::time::{Duration, Instant};
+
+use rds::{Client, SdkError};
+use tokio::time::sleep;
+
+/// The status of a DB instance or DB snapshot that's ready to use.
+pub const AVAILABLE: &str = "available";
+
+/// The error code that DescribeDBInstances returns for a DB instance it doesn't know.
+const DB_INSTANCE_NOT_FOUND: &str = "DBInstanceNotFound";
+
+/// The error code that DescribeDBSnapshots returns for a DB snapshot it doesn't know.
+const DB_SNAPSHOT_NOT_FOUND: &str = "DBSnapshotNotFound";
+
+/// The statuses that a DB instance doesn't leave without help, so there's no point in waiting.
+const DB_INSTANCE_FAILED_STATUSES: &[&str] = &[
+    "failed",
+    "inaccessible-encryption-credentials",
+    "incompatible-network",
+    "incompatible-option-group",
+    "incompatible-parameters",
+    "incompatible-restore",
+    "restore-error",
+    "storage-full",
+];
+
+/// How long to wait before the first retry. The delay doubles after each retry.
+const INITIAL_DELAY: Duration = Duration::from_secs(5);
+
+/// The longest delay between two retries.
+const MAX_DELAY: Duration = Duration::from_secs(30);
+
+/// Why waiting for a DB instance or DB snapshot failed.
+#[derive(Debug)]
+pub enum WaitError {
+    /// DescribeDBInstances or DescribeDBSnapshots failed.
+    Sdk(rds::Error),
+    /// The DB instance or DB snapshot has a status it can't leave for the one it was waited for,
+    /// such as __failed__, or __deleting__ while waiting for __available__.
+    Unreachable(String, String),
+    /// The DB instance or DB snapshot didn't reach the status before the timeout. Holds the last status seen.
+    Timeout(String, Option<String>),
+}
+
+impl fmt::Display for WaitError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            WaitError::Sdk(e) => write!(f, "{}", e),
+            WaitError::Unreachable(id, status) => {
+                write!(f, "{} is {}, so it can't reach the status", id, status)
+            }
+            WaitError::Timeout(id, status) => write!(
+                f,
+                "{} did not change in time, last status: {}",
+                id,
+                status_name(status.as_deref())
+            ),
+        }
+    }
+}
+
+impl Error for WaitError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            WaitError::Sdk(e) => Some(e),
+            WaitError::Unreachable(..) | WaitError::Timeout(..) => None,
+        }
+    }
+}
+
+impl From<rds::Error> for WaitError {
+    fn from(e: rds::Error) -> Self {
+        WaitError::Sdk(e)
+    }
+}
+
+/// Returns the status, such as __available__, or __not found__ if the DB instance or DB snapshot doesn't exist.
+pub fn status_name(status: Option<&str>) -> &str {
+    status.unwrap_or("not found")
+}
+
+/// Returns the DB instance's status, or `None` if Amazon RDS doesn't know the DB instance.
+pub async fn db_instance_status(
+    client: &Client,
+    db_instance_id: &str,
+) -> Result<Option<String>, rds::Error> {
+    match client
+        .describe_db_instances()
+        .db_instance_identifier(db_instance_id)
+        .send()
+        .await
+    {
+        Ok(resp) => Ok(resp
+            .db_instances
+            .unwrap_or_default()
+            .into_iter()
+            .find_map(|db_instance| db_instance.db_instance_status)),
+        Err(SdkError::ServiceError { err, .. }) if err.code() == Some(DB_INSTANCE_NOT_FOUND) => {
+            Ok(None)
+        }
+        Err(e) => Err(e.into()),
+    }
+}
+
+/// Polls the DB instance's status until it's `target`, or, if `target` is `None`, until the DB instance is gone.
+/// Backs off exponentially between calls, and calls `on_status` with the first status it sees,
+/// and again each time the status changes.
+/// A DB instance that isn't found yet is waited for while waiting for a status,
+/// since a new DB instance can take a moment to appear.
+pub async fn wait_for_db_instance<F>(
+    client: &Client,
+    db_instance_id: &str,
+    target: Option<&str>,
+    timeout: Duration,
+    mut on_status: F,
+) -> Result<(), WaitError>
+where
+    F: FnMut(Option<&str>),
+{
+    let deadline = Instant::now() + timeout;
+    let mut delay = INITIAL_DELAY;
+    let mut last = None;
+    let mut first = true;
+
+    loop {
+        let status = db_instance_status(client, db_instance_id).await?;
+
+        if first || status != last {
+            on_status(status.as_deref());
+            first = false;
+        }
+
+        if status.as_deref() == target {
+            return Ok(());
+        }
+
+        if let (Some(status), Some(_)) = (&status, target) {
+            if status == "deleting" || DB_INSTANCE_FAILED_STATUSES.contains(&status.as_str()) {
+                return Err(WaitError::Unreachable(
+                    db_instance_id.to_string(),
+                    status.clone(),
+                ));
+            }
+        }
+
+        last = status;
+
+        let now = Instant::now();
+        if now >= deadline {
+            return Err(WaitError::Timeout(db_instance_id.to_string(), last));
+        }
+
+        // Don't sleep past the deadline; check one last time when it's reached.
+        sleep(delay.min(deadline - now)).await;
+        delay = (delay * 2).min(MAX_DELAY);
+    }
+}
+
+/// Polls the DB snapshot's status until it's __available__, backing off exponentially between calls.
+/// Calls `on_status` with the status and the percent done each time either changes.
+pub async fn wait_for_db_snapshot<F>(
+    client: &Client,
+    db_snapshot_id: &str,
+    timeout: Duration,
+    mut on_status: F,
+) -> Result<(), WaitError>
+where
+    F: FnMut(Option<&str>, i32),
+{
+    let deadline = Instant::now() + timeout;
+    let mut delay = INITIAL_DELAY;
+    let mut last = None;
+
+    loop {
+        let db_snapshot = match client
+            .describe_db_snapshots()
+            .db_snapshot_identifier(db_snapshot_id)
+            .send()
+            .await
+        {
+            Ok(resp) => resp.db_snapshots.unwrap_or_default().into_iter().next(),
+            Err(SdkError::ServiceError { err, .. })
+                if err.code() == Some(DB_SNAPSHOT_NOT_FOUND) =>
+            {
+                None
+            }
+            Err(e) => return Err(WaitError::Sdk(e.into())),
+        };
+
+        let status = db_snapshot.as_ref().and_then(|s| s.status.clone());
+        let progress = db_snapshot.map(|s| s.percent_progress).unwrap_or_default();
+
+        let current = Some((status.clone(), progress));
+        if current != last {
+            on_status(status.as_deref(), progress);
+            last = current;
+        }
+
+        match status.as_deref() {
+            Some(AVAILABLE) => return Ok(()),
+            Some(status @ "failed") | Some(status @ "deleting") => {
+                return Err(WaitError::Unreachable(
+                    db_snapshot_id.to_string(),
+                    status.to_string(),
+                ))
+            }
+            _ => {}
+        }
+
+        let now = Instant::now();
+        if now >= deadline {
+            return Err(WaitError::Timeout(db_snapshot_id.to_string(), status));
+        }
+
+        sleep(delay.min(deadline - now)).await;
+        delay = (delay * 2).min(MAX_DELAY);
+    }
+}
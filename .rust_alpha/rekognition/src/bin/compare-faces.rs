@@ -0,0 +1,191 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0.
+ */
+
+use std::error::Error;
+use std::fmt;
+
+use rekognition::{Client, Config, Region};
+use rekognition_code_examples::image::{describe_box, parse_percent, ImageSource};
+
+use aws_types::region::ProvideRegion;
+
+use common::output::OutputFormat;
+use common::profile::ProfileCredentialsProvider;
+use serde::Serialize;
+use structopt::StructOpt;
+use tracing_subscriber::fmt::format::FmtSpan;
+use tracing_subscriber::fmt::SubscriberBuilder;
+
+#[derive(Debug, StructOpt)]
+struct Opt {
+    /// The default region
+    #[structopt(short, long)]
+    default_region: Option<String>,
+
+    /// The image with the face to look for, as a file name or an S3 URI
+    #[structopt(short, long)]
+    source: ImageSource,
+
+    /// The image to look for the face in, as a file name or an S3 URI
+    #[structopt(short, long)]
+    target: ImageSource,
+
+    /// The lowest similarity, from 0 through 100, of a face that matches
+    #[structopt(long, default_value = "80", parse(try_from_str = parse_percent))]
+    similarity_threshold: f32,
+
+    /// The format of the output, text or json
+    #[structopt(long, default_value = "text")]
+    output: OutputFormat,
+
+    /// The AWS profile to use from the shared credentials file
+    #[structopt(long)]
+    profile: Option<String>,
+
+    /// Whether to display additional information
+    #[structopt(short, long)]
+    verbose: bool,
+}
+
+/// A face in the target image that matches the source face.
+#[derive(Debug, Serialize)]
+struct Match {
+    similarity: f32,
+    bounding_box: String,
+}
+
+/// Where the source face is, and which faces in the target image match it.
+#[derive(Debug, Serialize)]
+struct Comparison {
+    source: String,
+    target: String,
+    source_face: String,
+    matches: Vec<Match>,
+    /// Where the faces in the target image that don't match are.
+    unmatched: Vec<String>,
+}
+
+impl fmt::Display for Comparison {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "Face in {}: {}", self.source, self.source_face)?;
+        writeln!(f, "Faces in {}:", self.target)?;
+
+        for face in &self.matches {
+            writeln!(
+                f,
+                "  Match, {:.1}% similar: {}",
+                face.similarity, face.bounding_box
+            )?;
+        }
+        for bounding_box in &self.unmatched {
+            writeln!(f, "  No match: {}", bounding_box)?;
+        }
+
+        write!(
+            f,
+            "\n{} of {} faces match.",
+            self.matches.len(),
+            self.matches.len() + self.unmatched.len()
+        )
+    }
+}
+
+/// Compares the largest face in one image with each face in another image with Amazon Rekognition,
+/// and displays which faces match, how similar they are, and where they are.
+/// # Arguments
+///
+/// * `-s SOURCE` - The image with the face to look for. If it has more than one face, the largest is used.
+/// * `-t TARGET` - The image to look for the face in.
+///   Each image is a JPEG or PNG file of up to 5 MB, or an S3 URI, such as **s3://bucket/photo.jpg**, of up to 15 MB.
+/// * `[--similarity-threshold SIMILARITY]` - The lowest similarity, from 0 through 100, of a face that matches.
+///   Defaults to **80**.
+/// * `[-d DEFAULT-REGION]` - The region in which the client is created.
+///   If not supplied, uses the value of the **AWS_DEFAULT_REGION** environment variable.
+///   If the environment variable is not set, defaults to **us-west-2**.
+/// * `[--output FORMAT]` - How to display the result, **text** (the default) or **json**.
+/// * `[--profile PROFILE]` - The AWS profile to use from the shared credentials file.
+/// * `[-v]` - Whether to display additional information.
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn Error>> {
+    let Opt {
+        default_region,
+        source,
+        target,
+        similarity_threshold,
+        output,
+        profile,
+        verbose,
+    } = Opt::from_args();
+
+    let region = default_region
+        .as_ref()
+        .map(|region| Region::new(region.clone()))
+        .or_else(|| aws_types::region::default_provider().region())
+        .unwrap_or_else(|| Region::new("us-west-2"));
+
+    if verbose {
+        println!("Rekognition client version: {}", rekognition::PKG_VERSION);
+        println!("Region:                     {:?}", &region);
+        println!("Source:                     {}", &source);
+        println!("Target:                     {}", &target);
+        println!("Similarity threshold:       {}", similarity_threshold);
+        println!();
+
+        SubscriberBuilder::default()
+            .with_env_filter("info")
+            .with_span_events(FmtSpan::CLOSE)
+            .init();
+    }
+
+    let mut config = Config::builder().region(region);
+    if let Some(profile) = profile {
+        config = config.credentials_provider(ProfileCredentialsProvider::new(profile));
+    }
+    let client = Client::from_conf(config.build());
+
+    let resp = client
+        .compare_faces()
+        .source_image(source.to_image()?)
+        .target_image(target.to_image()?)
+        .similarity_threshold(similarity_threshold)
+        .send()
+        .await?;
+
+    let matches = resp
+        .face_matches
+        .unwrap_or_default()
+        .into_iter()
+        .map(|face_match| Match {
+            similarity: face_match.similarity.unwrap_or_default(),
+            bounding_box: describe_box(
+                face_match
+                    .face
+                    .as_ref()
+                    .and_then(|face| face.bounding_box.as_ref()),
+            ),
+        })
+        .collect();
+
+    let unmatched = resp
+        .unmatched_faces
+        .unwrap_or_default()
+        .iter()
+        .map(|face| describe_box(face.bounding_box.as_ref()))
+        .collect();
+
+    output.print(&Comparison {
+        source: source.to_string(),
+        target: target.to_string(),
+        source_face: describe_box(
+            resp.source_image_face
+                .as_ref()
+                .and_then(|face| face.bounding_box.as_ref()),
+        ),
+        matches,
+        unmatched,
+    });
+
+    Ok(())
+}
@@ -0,0 +1,204 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0.
+ */
+
+use std::error::Error;
+use std::fmt;
+
+use rekognition::model::Attribute;
+use rekognition::{Client, Config, Region};
+use rekognition_code_examples::image::{describe_box, parse_percent, ImageSource};
+
+use aws_types::region::ProvideRegion;
+
+use common::output::OutputFormat;
+use common::profile::ProfileCredentialsProvider;
+use serde::Serialize;
+use structopt::StructOpt;
+use tracing_subscriber::fmt::format::FmtSpan;
+use tracing_subscriber::fmt::SubscriberBuilder;
+
+#[derive(Debug, StructOpt)]
+struct Opt {
+    /// The default region
+    #[structopt(short, long)]
+    default_region: Option<String>,
+
+    /// The image, as a file name or an S3 URI such as s3://bucket/photo.jpg
+    #[structopt(short, long)]
+    image: ImageSource,
+
+    /// The lowest confidence, from 0 through 100, of the faces to display
+    #[structopt(short, long, default_value = "90", parse(try_from_str = parse_percent))]
+    min_confidence: f32,
+
+    /// Whether to return every facial attribute, such as emotions, rather than the default set
+    #[structopt(short, long)]
+    all_attributes: bool,
+
+    /// The format of the output, text or json
+    #[structopt(long, default_value = "text")]
+    output: OutputFormat,
+
+    /// The AWS profile to use from the shared credentials file
+    #[structopt(long)]
+    profile: Option<String>,
+
+    /// Whether to display additional information
+    #[structopt(short, long)]
+    verbose: bool,
+}
+
+/// An emotion that a face appears to show.
+#[derive(Debug, Serialize)]
+struct Emotion {
+    emotion: String,
+    confidence: f32,
+}
+
+/// A face found in the image.
+#[derive(Debug, Serialize)]
+struct Face {
+    confidence: f32,
+    bounding_box: String,
+    /// The emotions, most confident first. Only returned with every attribute.
+    emotions: Vec<Emotion>,
+}
+
+/// The faces in the image.
+#[derive(Debug, Serialize)]
+struct Faces {
+    image: String,
+    faces: Vec<Face>,
+}
+
+impl fmt::Display for Faces {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "Faces in {}:", self.image)?;
+
+        for (i, face) in self.faces.iter().enumerate() {
+            writeln!(
+                f,
+                "  Face {}: {:.1}% confident, {}",
+                i + 1,
+                face.confidence,
+                face.bounding_box
+            )?;
+
+            for emotion in &face.emotions {
+                writeln!(f, "    {:5.1}%  {}", emotion.confidence, emotion.emotion)?;
+            }
+        }
+
+        write!(f, "\nFound {} faces.", self.faces.len())
+    }
+}
+
+/// Detects the faces in an image with Amazon Rekognition, and displays where each one is,
+/// and how confident Amazon Rekognition is that it's a face. With **-a**, it also displays
+/// the emotions that each face appears to show.
+/// # Arguments
+///
+/// * `-i IMAGE` - The image, as a JPEG or PNG file of up to 5 MB,
+///   or as an S3 URI, such as **s3://bucket/photo.jpg**, of up to 15 MB.
+/// * `[-m MIN-CONFIDENCE]` - The lowest confidence, from 0 through 100, of the faces to display.
+///   Defaults to **90**.
+/// * `[-a]` - Whether to return every facial attribute, rather than the default set.
+/// * `[-d DEFAULT-REGION]` - The region in which the client is created.
+///   If not supplied, uses the value of the **AWS_DEFAULT_REGION** environment variable.
+///   If the environment variable is not set, defaults to **us-west-2**.
+/// * `[--output FORMAT]` - How to display the result, **text** (the default) or **json**.
+/// * `[--profile PROFILE]` - The AWS profile to use from the shared credentials file.
+/// * `[-v]` - Whether to display additional information.
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn Error>> {
+    let Opt {
+        default_region,
+        image,
+        min_confidence,
+        all_attributes,
+        output,
+        profile,
+        verbose,
+    } = Opt::from_args();
+
+    let region = default_region
+        .as_ref()
+        .map(|region| Region::new(region.clone()))
+        .or_else(|| aws_types::region::default_provider().region())
+        .unwrap_or_else(|| Region::new("us-west-2"));
+
+    if verbose {
+        println!("Rekognition client version: {}", rekognition::PKG_VERSION);
+        println!("Region:                     {:?}", &region);
+        println!("Image:                      {}", &image);
+        println!("Minimum confidence:         {}", min_confidence);
+        println!("All attributes:             {}", all_attributes);
+        println!();
+
+        SubscriberBuilder::default()
+            .with_env_filter("info")
+            .with_span_events(FmtSpan::CLOSE)
+            .init();
+    }
+
+    let mut config = Config::builder().region(region);
+    if let Some(profile) = profile {
+        config = config.credentials_provider(ProfileCredentialsProvider::new(profile));
+    }
+    let client = Client::from_conf(config.build());
+
+    let attributes = if all_attributes {
+        Attribute::All
+    } else {
+        Attribute::Default
+    };
+
+    let resp = client
+        .detect_faces()
+        .image(image.to_image()?)
+        .attributes(attributes)
+        .send()
+        .await?;
+
+    // DetectFaces has no confidence threshold, so the faces are filtered here.
+    let faces = resp
+        .face_details
+        .unwrap_or_default()
+        .into_iter()
+        .filter(|face| face.confidence.unwrap_or_default() >= min_confidence)
+        .map(|face| {
+            let mut emotions: Vec<Emotion> = face
+                .emotions
+                .unwrap_or_default()
+                .into_iter()
+                .map(|emotion| Emotion {
+                    emotion: emotion
+                        .r#type
+                        .map(|kind| kind.as_str().to_string())
+                        .unwrap_or_default(),
+                    confidence: emotion.confidence.unwrap_or_default(),
+                })
+                .collect();
+            emotions.sort_by(|a, b| {
+                b.confidence
+                    .partial_cmp(&a.confidence)
+                    .unwrap_or(std::cmp::Ordering::Equal)
+            });
+
+            Face {
+                confidence: face.confidence.unwrap_or_default(),
+                bounding_box: describe_box(face.bounding_box.as_ref()),
+                emotions,
+            }
+        })
+        .collect();
+
+    output.print(&Faces {
+        image: image.to_string(),
+        faces,
+    });
+
+    Ok(())
+}
@@ -0,0 +1,180 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0.
+ */
+
+use std::error::Error;
+use std::fmt;
+
+use rekognition::{Client, Config, Region};
+use rekognition_code_examples::image::{describe_box, parse_percent, ImageSource};
+
+use aws_types::region::ProvideRegion;
+
+use common::output::OutputFormat;
+use common::profile::ProfileCredentialsProvider;
+use serde::Serialize;
+use structopt::StructOpt;
+use tracing_subscriber::fmt::format::FmtSpan;
+use tracing_subscriber::fmt::SubscriberBuilder;
+
+#[derive(Debug, StructOpt)]
+struct Opt {
+    /// The default region
+    #[structopt(short, long)]
+    default_region: Option<String>,
+
+    /// The image, as a file name or an S3 URI such as s3://bucket/photo.jpg
+    #[structopt(short, long)]
+    image: ImageSource,
+
+    /// The lowest confidence, from 0 through 100, of the labels to return
+    #[structopt(short, long, default_value = "55", parse(try_from_str = parse_percent))]
+    min_confidence: f32,
+
+    /// The most labels to return
+    #[structopt(long)]
+    max_labels: Option<i32>,
+
+    /// The format of the output, text or json
+    #[structopt(long, default_value = "text")]
+    output: OutputFormat,
+
+    /// The AWS profile to use from the shared credentials file
+    #[structopt(long)]
+    profile: Option<String>,
+
+    /// Whether to display additional information
+    #[structopt(short, long)]
+    verbose: bool,
+}
+
+/// An object, scene, or concept in the image.
+#[derive(Debug, Serialize)]
+struct Label {
+    name: String,
+    confidence: f32,
+    /// The more general labels that this one is a kind of, such as Vehicle for Car.
+    parents: Vec<String>,
+    /// Where each instance of a common object, such as each car, is in the image.
+    instances: Vec<String>,
+}
+
+/// The labels in the image, most confident first.
+#[derive(Debug, Serialize)]
+struct Labels {
+    image: String,
+    labels: Vec<Label>,
+}
+
+impl fmt::Display for Labels {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "Labels in {}:", self.image)?;
+
+        for label in &self.labels {
+            write!(f, "  {:5.1}%  {}", label.confidence, label.name)?;
+            if !label.parents.is_empty() {
+                write!(f, " ({})", label.parents.join(", "))?;
+            }
+            writeln!(f)?;
+
+            for instance in &label.instances {
+                writeln!(f, "            {}", instance)?;
+            }
+        }
+
+        write!(f, "\nFound {} labels.", self.labels.len())
+    }
+}
+
+/// Detects the objects, scenes, and concepts in an image with Amazon Rekognition,
+/// and displays each label with its confidence and the more general labels it belongs to.
+/// For common objects, such as people and cars, it also displays where each one is in the image.
+/// # Arguments
+///
+/// * `-i IMAGE` - The image, as a JPEG or PNG file of up to 5 MB,
+///   or as an S3 URI, such as **s3://bucket/photo.jpg**, of up to 15 MB.
+/// * `[-m MIN-CONFIDENCE]` - The lowest confidence, from 0 through 100, of the labels to return.
+///   Defaults to **55**.
+/// * `[--max-labels MAX-LABELS]` - The most labels to return.
+/// * `[-d DEFAULT-REGION]` - The region in which the client is created.
+///   If not supplied, uses the value of the **AWS_DEFAULT_REGION** environment variable.
+///   If the environment variable is not set, defaults to **us-west-2**.
+/// * `[--output FORMAT]` - How to display the result, **text** (the default) or **json**.
+/// * `[--profile PROFILE]` - The AWS profile to use from the shared credentials file.
+/// * `[-v]` - Whether to display additional information.
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn Error>> {
+    let Opt {
+        default_region,
+        image,
+        min_confidence,
+        max_labels,
+        output,
+        profile,
+        verbose,
+    } = Opt::from_args();
+
+    let region = default_region
+        .as_ref()
+        .map(|region| Region::new(region.clone()))
+        .or_else(|| aws_types::region::default_provider().region())
+        .unwrap_or_else(|| Region::new("us-west-2"));
+
+    if verbose {
+        println!("Rekognition client version: {}", rekognition::PKG_VERSION);
+        println!("Region:                     {:?}", &region);
+        println!("Image:                      {}", &image);
+        println!("Minimum confidence:         {}", min_confidence);
+        println!("Maximum labels:             {:?}", max_labels);
+        println!();
+
+        SubscriberBuilder::default()
+            .with_env_filter("info")
+            .with_span_events(FmtSpan::CLOSE)
+            .init();
+    }
+
+    let mut config = Config::builder().region(region);
+    if let Some(profile) = profile {
+        config = config.credentials_provider(ProfileCredentialsProvider::new(profile));
+    }
+    let client = Client::from_conf(config.build());
+
+    let resp = client
+        .detect_labels()
+        .image(image.to_image()?)
+        .min_confidence(min_confidence)
+        .set_max_labels(max_labels)
+        .send()
+        .await?;
+
+    let labels = resp
+        .labels
+        .unwrap_or_default()
+        .into_iter()
+        .map(|label| Label {
+            name: label.name.unwrap_or_default(),
+            confidence: label.confidence.unwrap_or_default(),
+            parents: label
+                .parents
+                .unwrap_or_default()
+                .into_iter()
+                .filter_map(|parent| parent.name)
+                .collect(),
+            instances: label
+                .instances
+                .unwrap_or_default()
+                .iter()
+                .map(|instance| describe_box(instance.bounding_box.as_ref()))
+                .collect(),
+        })
+        .collect();
+
+    output.print(&Labels {
+        image: image.to_string(),
+        labels,
+    });
+
+    Ok(())
+}
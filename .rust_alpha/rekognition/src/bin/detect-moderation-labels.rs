@@ -0,0 +1,159 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0.
+ */
+
+use std::error::Error;
+use std::fmt;
+
+use rekognition::{Client, Config, Region};
+use rekognition_code_examples::image::{parse_percent, ImageSource};
+
+use aws_types::region::ProvideRegion;
+
+use common::output::OutputFormat;
+use common::profile::ProfileCredentialsProvider;
+use serde::Serialize;
+use structopt::StructOpt;
+use tracing_subscriber::fmt::format::FmtSpan;
+use tracing_subscriber::fmt::SubscriberBuilder;
+
+#[derive(Debug, StructOpt)]
+struct Opt {
+    /// The default region
+    #[structopt(short, long)]
+    default_region: Option<String>,
+
+    /// The image, as a file name or an S3 URI such as s3://bucket/photo.jpg
+    #[structopt(short, long)]
+    image: ImageSource,
+
+    /// The lowest confidence, from 0 through 100, of the labels to return
+    #[structopt(short, long, default_value = "50", parse(try_from_str = parse_percent))]
+    min_confidence: f32,
+
+    /// The format of the output, text or json
+    #[structopt(long, default_value = "text")]
+    output: OutputFormat,
+
+    /// The AWS profile to use from the shared credentials file
+    #[structopt(long)]
+    profile: Option<String>,
+
+    /// Whether to display additional information
+    #[structopt(short, long)]
+    verbose: bool,
+}
+
+/// A kind of unsafe content found in the image.
+#[derive(Debug, Serialize)]
+struct ModerationLabel {
+    name: String,
+    confidence: f32,
+    /// The top-level category, such as Violence, or none if this is one.
+    parent: Option<String>,
+}
+
+/// The unsafe content found in the image, and whether there was any.
+#[derive(Debug, Serialize)]
+struct Moderation {
+    image: String,
+    flagged: bool,
+    labels: Vec<ModerationLabel>,
+}
+
+impl fmt::Display for Moderation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if !self.flagged {
+            return write!(f, "No unsafe content found in {}", self.image);
+        }
+
+        writeln!(f, "Unsafe content in {}:", self.image)?;
+
+        for label in &self.labels {
+            // A second-level label is indented under its top-level category.
+            let indent = if label.parent.is_some() { "    " } else { "" };
+            writeln!(f, "  {:5.1}%  {}{}", label.confidence, indent, label.name)?;
+        }
+
+        write!(f, "\nFound {} moderation labels.", self.labels.len())
+    }
+}
+
+/// Detects unsafe content in an image with Amazon Rekognition, such as nudity, violence,
+/// or drugs, and displays each moderation label with its confidence, under its top-level category.
+/// # Arguments
+///
+/// * `-i IMAGE` - The image, as a JPEG or PNG file of up to 5 MB,
+///   or as an S3 URI, such as **s3://bucket/photo.jpg**, of up to 15 MB.
+/// * `[-m MIN-CONFIDENCE]` - The lowest confidence, from 0 through 100, of the labels to return.
+///   Defaults to **50**.
+/// * `[-d DEFAULT-REGION]` - The region in which the client is created.
+///   If not supplied, uses the value of the **AWS_DEFAULT_REGION** environment variable.
+///   If the environment variable is not set, defaults to **us-west-2**.
+/// * `[--output FORMAT]` - How to display the result, **text** (the default) or **json**.
+/// * `[--profile PROFILE]` - The AWS profile to use from the shared credentials file.
+/// * `[-v]` - Whether to display additional information.
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn Error>> {
+    let Opt {
+        default_region,
+        image,
+        min_confidence,
+        output,
+        profile,
+        verbose,
+    } = Opt::from_args();
+
+    let region = default_region
+        .as_ref()
+        .map(|region| Region::new(region.clone()))
+        .or_else(|| aws_types::region::default_provider().region())
+        .unwrap_or_else(|| Region::new("us-west-2"));
+
+    if verbose {
+        println!("Rekognition client version: {}", rekognition::PKG_VERSION);
+        println!("Region:                     {:?}", &region);
+        println!("Image:                      {}", &image);
+        println!("Minimum confidence:         {}", min_confidence);
+        println!();
+
+        SubscriberBuilder::default()
+            .with_env_filter("info")
+            .with_span_events(FmtSpan::CLOSE)
+            .init();
+    }
+
+    let mut config = Config::builder().region(region);
+    if let Some(profile) = profile {
+        config = config.credentials_provider(ProfileCredentialsProvider::new(profile));
+    }
+    let client = Client::from_conf(config.build());
+
+    let resp = client
+        .detect_moderation_labels()
+        .image(image.to_image()?)
+        .min_confidence(min_confidence)
+        .send()
+        .await?;
+
+    let labels: Vec<ModerationLabel> = resp
+        .moderation_labels
+        .unwrap_or_default()
+        .into_iter()
+        .map(|label| ModerationLabel {
+            name: label.name.unwrap_or_default(),
+            confidence: label.confidence.unwrap_or_default(),
+            // A top-level category has an empty parent name.
+            parent: label.parent_name.filter(|parent| !parent.is_empty()),
+        })
+        .collect();
+
+    output.print(&Moderation {
+        image: image.to_string(),
+        flagged: !labels.is_empty(),
+        labels,
+    });
+
+    Ok(())
+}
@@ -0,0 +1,125 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0.
+ */
+
+//! Reads the image to analyze from a local file or an Amazon S3 object, and describes where
+//! in the image something was found.
+//!
+//! An image is named by a path, or by an S3 URI, and Amazon Rekognition is sent either
+//! the file's bytes or the object's location, so that it reads the object itself:
+//!
+//! ```ignore
+//! let source: ImageSource = "s3://photos/beach.jpg".parse()?;
+//! let image = source.to_image()?;
+//! client.detect_labels().image(image).send().await?;
+//! ```
+//!
+//! Amazon Rekognition accepts JPEG and PNG images, up to 5 MB when they're sent as bytes,
+//! and up to 15 MB in Amazon S3. A file that's too large is rejected before it's sent.
+
+use std::fmt;
+use std::fs;
+use std::io;
+use std::str::FromStr;
+
+use rekognition::model::{BoundingBox, Image, S3Object};
+use rekognition::Blob;
+
+/// The largest image, in bytes, that can be sent in a request.
+pub const MAX_BYTES: u64 = 5 * 1024 * 1024;
+
+/// Where an image is.
+#[derive(Clone, Debug, PartialEq)]
+pub enum ImageSource {
+    /// A local file.
+    File(String),
+    /// An object in Amazon S3.
+    S3 { bucket: String, key: String },
+}
+
+impl FromStr for ImageSource {
+    type Err = String;
+
+    /// Reads `s3://BUCKET/KEY` as an S3 object, and anything else as a file name.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let path = match s.strip_prefix("s3://") {
+            Some(path) => path,
+            None => return Ok(ImageSource::File(s.to_string())),
+        };
+
+        match path.split_once('/') {
+            Some((bucket, key)) if !bucket.is_empty() && !key.is_empty() => Ok(ImageSource::S3 {
+                bucket: bucket.to_string(),
+                key: key.to_string(),
+            }),
+            _ => Err(format!(
+                "{} isn't an S3 URI such as s3://bucket/photo.jpg",
+                s
+            )),
+        }
+    }
+}
+
+impl fmt::Display for ImageSource {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ImageSource::File(path) => write!(f, "{}", path),
+            ImageSource::S3 { bucket, key } => write!(f, "s3://{}/{}", bucket, key),
+        }
+    }
+}
+
+impl ImageSource {
+    /// Returns the image to send to Amazon Rekognition: the file's bytes, or the S3 object's location.
+    pub fn to_image(&self) -> io::Result<Image> {
+        match self {
+            ImageSource::File(path) => {
+                let size = fs::metadata(path)?.len();
+                if size > MAX_BYTES {
+                    return Err(io::Error::new(
+                        io::ErrorKind::InvalidInput,
+                        format!(
+                            "{} is {} bytes; upload it to Amazon S3 to analyze an image over {} bytes",
+                            path, size, MAX_BYTES
+                        ),
+                    ));
+                }
+
+                Ok(Image::builder().bytes(Blob::new(fs::read(path)?)).build())
+            }
+            ImageSource::S3 { bucket, key } => Ok(Image::builder()
+                .s3_object(S3Object::builder().bucket(bucket).name(key).build())
+                .build()),
+        }
+    }
+}
+
+/// Parses a confidence or similarity, which is a percentage from 0 through 100.
+pub fn parse_percent(s: &str) -> Result<f32, String> {
+    let percent: f32 = s.parse().map_err(|_| format!("{} isn't a number", s))?;
+
+    if !(0.0..=100.0).contains(&percent) {
+        return Err(format!("{} must be from 0 through 100", s));
+    }
+
+    Ok(percent)
+}
+
+/// Describes where a box is in the image, as percentages of the image's width and height,
+/// such as `20% x 35% at (12%, 40%)`, which is a box 20% of the image wide and 35% high,
+/// whose top-left corner is 12% of the way across and 40% of the way down.
+pub fn describe_box(bounding_box: Option<&BoundingBox>) -> String {
+    let percent = |ratio: Option<f32>| (ratio.unwrap_or_default() * 100.0).round();
+
+    match bounding_box {
+        Some(b) => format!(
+            "{}% x {}% at ({}%, {}%)",
+            percent(b.width),
+            percent(b.height),
+            percent(b.left),
+            percent(b.top)
+        ),
+        None => "unknown".to_string(),
+    }
+}
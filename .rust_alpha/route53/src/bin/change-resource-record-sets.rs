@@ -0,0 +1,223 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0.
+ */
+
+use std::error::Error;
+use std::net::Ipv4Addr;
+use std::time::Duration;
+
+use route53::model::{
+    Change, ChangeAction, ChangeBatch, ResourceRecord, ResourceRecordSet, RrType,
+};
+use route53::{Client, Config, Region};
+use route53_code_examples::waiters::wait_for_change_insync;
+use route53_code_examples::zones::{absolute, bare_id, find_zone, qualify};
+
+use aws_types::region::ProvideRegion;
+
+use common::profile::ProfileCredentialsProvider;
+use structopt::StructOpt;
+use tracing_subscriber::fmt::format::FmtSpan;
+use tracing_subscriber::fmt::SubscriberBuilder;
+
+/// How long to wait for the change to reach every Route 53 name server.
+const INSYNC_TIMEOUT: Duration = Duration::from_secs(5 * 60);
+
+#[derive(Debug, StructOpt)]
+struct Opt {
+    /// The region. Overrides environment variable AWS_DEFAULT_REGION.
+    #[structopt(short, long)]
+    default_region: Option<String>,
+
+    /// The ID or domain name of the hosted zone
+    #[structopt(short, long)]
+    zone: String,
+
+    /// The name of the record, such as www or www.example.com
+    #[structopt(short, long)]
+    name: String,
+
+    /// The type of the record, A or CNAME
+    #[structopt(short = "t", long = "type", default_value = "A")]
+    record_type: String,
+
+    /// The value of the record: an IPv4 address for A, which can be repeated, or a domain name for CNAME
+    #[structopt(long, required = true)]
+    value: Vec<String>,
+
+    /// How many seconds resolvers can cache the record for
+    #[structopt(long, default_value = "300")]
+    ttl: i64,
+
+    /// A comment about the change
+    #[structopt(long)]
+    comment: Option<String>,
+
+    /// Whether to return as soon as Route 53 accepts the change, rather than waiting until it's in sync
+    #[structopt(long)]
+    no_wait: bool,
+
+    /// The AWS profile to use from the shared credentials file
+    #[structopt(long)]
+    profile: Option<String>,
+
+    /// Whether to display additional information
+    #[structopt(short, long)]
+    verbose: bool,
+}
+
+/// Checks the values for the record type, and returns the type.
+fn check_values(record_type: &str, values: &[String]) -> Result<RrType, String> {
+    match record_type.to_ascii_uppercase().as_str() {
+        "A" => {
+            for value in values {
+                value
+                    .parse::<Ipv4Addr>()
+                    .map_err(|_| format!("{} is not an IPv4 address", value))?;
+            }
+            Ok(RrType::A)
+        }
+        "CNAME" if values.len() == 1 => Ok(RrType::Cname),
+        "CNAME" => Err("a CNAME record has exactly one value".to_string()),
+        _ => Err(format!(
+            "the record type must be A or CNAME, got {}",
+            record_type
+        )),
+    }
+}
+
+/// Creates a record in a Route 53 hosted zone, or replaces the record with that name and type
+/// if there is one (an UPSERT), and waits until the change is in sync on all of the Route 53 name servers.
+/// # Arguments
+///
+/// * `-z ZONE` - The ID of the hosted zone, or its domain name, such as **example.com**.
+/// * `-n NAME` - The name of the record, such as **www** or **www.example.com**.
+///   A name that isn't in the zone's domain has the domain added.
+/// * `[-t TYPE]` - The type of the record, **A** (the default) or **CNAME**.
+/// * `--value VALUE` - The value of the record: an IPv4 address for **A**, or a domain name for **CNAME**.
+///   Repeat to give an **A** record several addresses.
+/// * `[--ttl TTL]` - How many seconds resolvers can cache the record for.
+///   If not supplied, defaults to **300**.
+/// * `[--comment COMMENT]` - A comment about the change.
+/// * `[--no-wait]` - Return as soon as Route 53 accepts the change.
+/// * `[-d DEFAULT-REGION]` - The region in which the client is created.
+///   If not supplied, uses the value of the **AWS_DEFAULT_REGION** environment variable.
+///   If the environment variable is not set, defaults to **us-west-2**.
+/// * `[--profile PROFILE]` - The AWS profile to use from the shared credentials file.
+/// * `[-v]` - Whether to display additional information.
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn Error>> {
+    let Opt {
+        comment,
+        default_region,
+        name,
+        no_wait,
+        profile,
+        record_type,
+        ttl,
+        value,
+        verbose,
+        zone,
+    } = Opt::from_args();
+
+    let region = default_region
+        .as_ref()
+        .map(|region| Region::new(region.clone()))
+        .or_else(|| aws_types::region::default_provider().region())
+        .unwrap_or_else(|| Region::new("us-west-2"));
+
+    let record_type = check_values(&record_type, &value)?;
+
+    if ttl < 0 {
+        return Err(format!("the TTL must be 0 or more seconds, got {}", ttl).into());
+    }
+
+    // A CNAME's value is a domain name, which Route 53 stores with a trailing dot.
+    let value: Vec<String> = match record_type {
+        RrType::Cname => value.iter().map(|value| absolute(value)).collect(),
+        _ => value,
+    };
+
+    if verbose {
+        println!("Route53 client version: {}", route53::PKG_VERSION);
+        println!("Region:                 {:?}", &region);
+        println!("Hosted zone:            {}", &zone);
+        println!("Name:                   {}", &name);
+        println!("Type:                   {}", record_type.as_str());
+        println!("Values:                 {:?}", &value);
+        println!("TTL:                    {}", ttl);
+        println!("Comment:                {:?}", &comment);
+        println!();
+
+        SubscriberBuilder::default()
+            .with_env_filter("info")
+            .with_span_events(FmtSpan::CLOSE)
+            .init();
+    }
+
+    let mut config = Config::builder().region(region);
+    if let Some(profile) = profile {
+        config = config.credentials_provider(ProfileCredentialsProvider::new(profile));
+    }
+    let client = Client::from_conf(config.build());
+
+    let zone = find_zone(&client, &zone).await?;
+    let name = qualify(&name, &zone.name);
+
+    let record = ResourceRecordSet::builder()
+        .name(&name)
+        .r#type(record_type.clone())
+        .ttl(ttl)
+        .set_resource_records(Some(
+            value
+                .iter()
+                .map(|value| ResourceRecord::builder().value(value).build())
+                .collect(),
+        ))
+        .build();
+
+    let resp = client
+        .change_resource_record_sets()
+        .hosted_zone_id(&zone.id)
+        .change_batch(
+            ChangeBatch::builder()
+                .set_comment(comment)
+                .changes(
+                    Change::builder()
+                        .action(ChangeAction::Upsert)
+                        .resource_record_set(record)
+                        .build(),
+                )
+                .build(),
+        )
+        .send()
+        .await?;
+
+    let change_id = resp
+        .change_info
+        .and_then(|info| info.id)
+        .ok_or("Route 53 did not return the ID of the change")?;
+
+    println!(
+        "Upserted {} {} -> {} in {} (change {})",
+        record_type.as_str(),
+        &name,
+        value.join(", "),
+        &zone.name,
+        bare_id(&change_id)
+    );
+
+    if no_wait {
+        return Ok(());
+    }
+
+    wait_for_change_insync(&client, &change_id, INSYNC_TIMEOUT, |status| {
+        println!("Change status: {}", status.as_str())
+    })
+    .await?;
+
+    println!("The change is live on all of the Route 53 name servers");
+
+    Ok(())
+}
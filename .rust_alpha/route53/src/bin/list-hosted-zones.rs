@@ -0,0 +1,117 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0.
+ */
+
+use std::error::Error;
+
+use route53::{Client, Config, Region};
+use route53_code_examples::zones::bare_id;
+
+use aws_types::region::ProvideRegion;
+
+use common::profile::ProfileCredentialsProvider;
+use structopt::StructOpt;
+use tracing_subscriber::fmt::format::FmtSpan;
+use tracing_subscriber::fmt::SubscriberBuilder;
+
+#[derive(Debug, StructOpt)]
+struct Opt {
+    /// The region. Overrides environment variable AWS_DEFAULT_REGION.
+    #[structopt(short, long)]
+    default_region: Option<String>,
+
+    /// The AWS profile to use from the shared credentials file
+    #[structopt(long)]
+    profile: Option<String>,
+
+    /// Whether to display additional information
+    #[structopt(short, long)]
+    verbose: bool,
+}
+
+/// Lists your Route 53 hosted zones, following every page of results:
+/// the ID, domain name, and number of records of each, and whether it's public or private.
+/// Hosted zones are global, so the region only decides which endpoint the client uses.
+/// # Arguments
+///
+/// * `[-d DEFAULT-REGION]` - The region in which the client is created.
+///   If not supplied, uses the value of the **AWS_DEFAULT_REGION** environment variable.
+///   If the environment variable is not set, defaults to **us-west-2**.
+/// * `[--profile PROFILE]` - The AWS profile to use from the shared credentials file.
+/// * `[-v]` - Whether to display additional information.
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn Error>> {
+    let Opt {
+        default_region,
+        profile,
+        verbose,
+    } = Opt::from_args();
+
+    let region = default_region
+        .as_ref()
+        .map(|region| Region::new(region.clone()))
+        .or_else(|| aws_types::region::default_provider().region())
+        .unwrap_or_else(|| Region::new("us-west-2"));
+
+    if verbose {
+        println!("Route53 client version: {}", route53::PKG_VERSION);
+        println!("Region:                 {:?}", &region);
+        println!();
+
+        SubscriberBuilder::default()
+            .with_env_filter("info")
+            .with_span_events(FmtSpan::CLOSE)
+            .init();
+    }
+
+    let mut config = Config::builder().region(region);
+    if let Some(profile) = profile {
+        config = config.credentials_provider(ProfileCredentialsProvider::new(profile));
+    }
+    let client = Client::from_conf(config.build());
+
+    let mut count = 0;
+    let mut marker = None;
+
+    loop {
+        let resp = client.list_hosted_zones().set_marker(marker).send().await?;
+
+        for zone in resp.hosted_zones.unwrap_or_default() {
+            let private = zone
+                .config
+                .as_ref()
+                .map(|config| config.private_zone)
+                .unwrap_or_default();
+
+            println!(
+                "{:<24} {:<40} {:>6} records  {}",
+                bare_id(zone.id.as_deref().unwrap_or_default()),
+                zone.name.as_deref().unwrap_or_default(),
+                zone.resource_record_set_count.unwrap_or_default(),
+                if private { "private" } else { "public" }
+            );
+
+            if let Some(comment) = zone
+                .config
+                .as_ref()
+                .and_then(|config| config.comment.as_deref())
+                .filter(|comment| !comment.is_empty())
+            {
+                println!("  {}", comment);
+            }
+
+            count += 1;
+        }
+
+        if !resp.is_truncated {
+            break;
+        }
+        marker = resp.next_marker;
+    }
+
+    println!();
+    println!("Found {} hosted zones", count);
+
+    Ok(())
+}
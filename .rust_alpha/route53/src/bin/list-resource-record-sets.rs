@@ -0,0 +1,188 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0.
+ */
+
+use std::error::Error;
+
+use route53::model::{ResourceRecordSet, RrType};
+use route53::{Client, Config, Region};
+use route53_code_examples::zones::{find_zone, qualify};
+
+use aws_types::region::ProvideRegion;
+
+use common::profile::ProfileCredentialsProvider;
+use structopt::StructOpt;
+use tracing_subscriber::fmt::format::FmtSpan;
+use tracing_subscriber::fmt::SubscriberBuilder;
+
+#[derive(Debug, StructOpt)]
+struct Opt {
+    /// The region. Overrides environment variable AWS_DEFAULT_REGION.
+    #[structopt(short, long)]
+    default_region: Option<String>,
+
+    /// The ID or domain name of the hosted zone
+    #[structopt(short, long)]
+    zone: String,
+
+    /// Only list the records with this name, such as www
+    #[structopt(short, long)]
+    name: Option<String>,
+
+    /// Only list the records of this type, such as A or CNAME. Needs --name
+    #[structopt(short = "t", long = "type", requires = "name")]
+    record_type: Option<String>,
+
+    /// The AWS profile to use from the shared credentials file
+    #[structopt(long)]
+    profile: Option<String>,
+
+    /// Whether to display additional information
+    #[structopt(short, long)]
+    verbose: bool,
+}
+
+/// Returns the record's values, or the target of an alias record.
+fn values(record: &ResourceRecordSet) -> Vec<String> {
+    if let Some(alias) = &record.alias_target {
+        return vec![format!(
+            "ALIAS {}",
+            alias.dns_name.as_deref().unwrap_or_default()
+        )];
+    }
+
+    record
+        .resource_records
+        .as_deref()
+        .unwrap_or_default()
+        .iter()
+        .map(|value| value.value.clone().unwrap_or_default())
+        .collect()
+}
+
+/// Lists the records in a Route 53 hosted zone, following every page of results.
+/// # Arguments
+///
+/// * `-z ZONE` - The ID of the hosted zone, or its domain name, such as **example.com**.
+/// * `[-n NAME]` - Only list the records with this name, such as **www** or **www.example.com**.
+/// * `[-t TYPE]` - Only list the records of this type, such as **A** or **CNAME**. Needs `-n`.
+/// * `[-d DEFAULT-REGION]` - The region in which the client is created.
+///   If not supplied, uses the value of the **AWS_DEFAULT_REGION** environment variable.
+///   If the environment variable is not set, defaults to **us-west-2**.
+/// * `[--profile PROFILE]` - The AWS profile to use from the shared credentials file.
+/// * `[-v]` - Whether to display additional information.
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn Error>> {
+    let Opt {
+        default_region,
+        name,
+        profile,
+        record_type,
+        verbose,
+        zone,
+    } = Opt::from_args();
+
+    let region = default_region
+        .as_ref()
+        .map(|region| Region::new(region.clone()))
+        .or_else(|| aws_types::region::default_provider().region())
+        .unwrap_or_else(|| Region::new("us-west-2"));
+
+    let record_type =
+        record_type.map(|record_type| RrType::from(record_type.to_ascii_uppercase().as_str()));
+
+    if verbose {
+        println!("Route53 client version: {}", route53::PKG_VERSION);
+        println!("Region:                 {:?}", &region);
+        println!("Hosted zone:            {}", &zone);
+        println!("Name:                   {:?}", &name);
+        println!("Type:                   {:?}", &record_type);
+        println!();
+
+        SubscriberBuilder::default()
+            .with_env_filter("info")
+            .with_span_events(FmtSpan::CLOSE)
+            .init();
+    }
+
+    let mut config = Config::builder().region(region);
+    if let Some(profile) = profile {
+        config = config.credentials_provider(ProfileCredentialsProvider::new(profile));
+    }
+    let client = Client::from_conf(config.build());
+
+    let zone = find_zone(&client, &zone).await?;
+    let name = name.map(|name| qualify(&name, &zone.name));
+
+    println!("Records in {} ({}):", &zone.name, &zone.id);
+
+    // The records are sorted by name and then type, and listing starts at this name and type.
+    let mut start_name = name.clone();
+    let mut start_type = record_type.clone();
+    let mut start_identifier = None;
+    let mut count = 0;
+
+    'pages: loop {
+        let resp = client
+            .list_resource_record_sets()
+            .hosted_zone_id(&zone.id)
+            .set_start_record_name(start_name)
+            .set_start_record_type(start_type)
+            .set_start_record_identifier(start_identifier)
+            .send()
+            .await?;
+
+        for record in resp.resource_record_sets.unwrap_or_default() {
+            let record_name = record.name.as_deref().unwrap_or_default();
+
+            // Once past the name or type that was asked for, there are no more matches.
+            if let Some(name) = &name {
+                if !record_name.eq_ignore_ascii_case(name)
+                    || (record_type.is_some() && record.r#type != record_type)
+                {
+                    break 'pages;
+                }
+            }
+
+            let ttl = record.ttl.map(|ttl| ttl.to_string()).unwrap_or_default();
+
+            for (i, value) in values(&record).iter().enumerate() {
+                if i == 0 {
+                    println!(
+                        "  {:<40} {:<6} {:>6}  {}",
+                        record_name,
+                        record
+                            .r#type
+                            .as_ref()
+                            .map(|t| t.as_str())
+                            .unwrap_or_default(),
+                        ttl,
+                        value
+                    );
+                } else {
+                    println!("  {:<40} {:<6} {:>6}  {}", "", "", "", value);
+                }
+            }
+
+            if let Some(set_identifier) = &record.set_identifier {
+                println!("    Set identifier: {}", set_identifier);
+            }
+
+            count += 1;
+        }
+
+        if !resp.is_truncated {
+            break;
+        }
+
+        start_name = resp.next_record_name;
+        start_type = resp.next_record_type;
+        start_identifier = resp.next_record_identifier;
+    }
+
+    println!();
+    println!("Found {} records", count);
+
+    Ok(())
+}
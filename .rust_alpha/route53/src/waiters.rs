@@ -0,0 +1,124 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0.
+ */
+
+//! Waits for a change to the records in a hosted zone to reach all of the Route 53 name servers.
+//!
+//! `ChangeResourceRecordSets` returns while the change is still __PENDING__. Once it's __INSYNC__,
+//! every Route 53 name server answers with the new records, usually within 60 seconds.
+//! The waiter polls `GetChange`, and calls `on_status` each time the status changes:
+//!
+//! ```ignore
+//! wait_for_change_insync(&client, &change_id, Duration::from_secs(300), |status| {
+//!     println!("Change status: {}", status.as_str());
+//! })
+//! .await?;
+//! ```
+
+use std::error::Error;
+use std::fmt;
+use std::time::{Duration, Instant};
+
+use route53::model::ChangeStatus;
+use route53::Client;
+use tokio::time::sleep;
+
+use crate::zones::bare_id;
+
+/// How long to wait before the first retry. The delay doubles after each retry.
+const INITIAL_DELAY: Duration = Duration::from_secs(2);
+
+/// The longest delay between two retries.
+const MAX_DELAY: Duration = Duration::from_secs(15);
+
+/// Why waiting for a change failed.
+#[derive(Debug)]
+pub enum WaitError {
+    /// GetChange failed.
+    Sdk(route53::Error),
+    /// The change wasn't in sync before the timeout. Holds the last status seen.
+    Timeout(String, Option<ChangeStatus>),
+}
+
+impl fmt::Display for WaitError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            WaitError::Sdk(e) => write!(f, "{}", e),
+            WaitError::Timeout(id, status) => write!(
+                f,
+                "change {} was not in sync in time, last status: {}",
+                id,
+                status
+                    .as_ref()
+                    .map(|status| status.as_str())
+                    .unwrap_or("unknown")
+            ),
+        }
+    }
+}
+
+impl Error for WaitError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            WaitError::Sdk(e) => Some(e),
+            WaitError::Timeout(..) => None,
+        }
+    }
+}
+
+impl From<route53::Error> for WaitError {
+    fn from(e: route53::Error) -> Self {
+        WaitError::Sdk(e)
+    }
+}
+
+/// Polls the change until it's __INSYNC__, backing off exponentially between calls.
+/// Calls `on_status` with the first status it sees, and again each time the status changes.
+/// The ID can have the __/change/__ prefix that `ChangeResourceRecordSets` returns.
+pub async fn wait_for_change_insync<F>(
+    client: &Client,
+    change_id: &str,
+    timeout: Duration,
+    mut on_status: F,
+) -> Result<(), WaitError>
+where
+    F: FnMut(&ChangeStatus),
+{
+    let id = bare_id(change_id);
+    let deadline = Instant::now() + timeout;
+    let mut delay = INITIAL_DELAY;
+    let mut last = None;
+
+    loop {
+        let status = client
+            .get_change()
+            .id(id)
+            .send()
+            .await
+            .map_err(route53::Error::from)?
+            .change_info
+            .and_then(|info| info.status);
+
+        if let Some(status) = &status {
+            if last.as_ref() != Some(status) {
+                on_status(status);
+            }
+
+            if *status == ChangeStatus::Insync {
+                return Ok(());
+            }
+        }
+
+        last = status;
+
+        let now = Instant::now();
+        if now >= deadline {
+            return Err(WaitError::Timeout(id.to_string(), last));
+        }
+
+        // Don't sleep past the deadline; check one last time when it's reached.
+        sleep(delay.min(deadline - now)).await;
+        delay = (delay * 2).min(MAX_DELAY);
+    }
+}
@@ -0,0 +1,164 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0.
+ */
+
+//! Finds a hosted zone by its ID or its domain name, and qualifies record names with the zone's domain.
+//!
+//! Route 53 returns IDs with a prefix, such as __/hostedzone/Z1D633PJN98FT9__, but most operations
+//! want the bare ID. The examples accept either form, or the zone's domain name:
+//!
+//! ```ignore
+//! let zone = find_zone(&client, "example.com").await?;
+//! let name = qualify("www", &zone.name); // www.example.com.
+//! client.list_resource_record_sets().hosted_zone_id(&zone.id) /* ... */;
+//! ```
+
+use std::error::Error;
+use std::fmt;
+
+use route53::Client;
+
+/// A hosted zone's bare ID and its domain name, which ends with a dot.
+#[derive(Clone, Debug)]
+pub struct Zone {
+    pub id: String,
+    pub name: String,
+    pub private: bool,
+}
+
+/// Why a hosted zone couldn't be found.
+#[derive(Debug)]
+pub enum ZoneError {
+    /// Route 53 returned an error.
+    Sdk(route53::Error),
+    /// No hosted zone has this domain name.
+    NotFound(String),
+    /// Several hosted zones have this domain name, such as a public and a private zone. Holds their IDs.
+    Ambiguous(String, Vec<String>),
+}
+
+impl fmt::Display for ZoneError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ZoneError::Sdk(e) => write!(f, "{}", e),
+            ZoneError::NotFound(name) => write!(f, "no hosted zone is named {}", name),
+            ZoneError::Ambiguous(name, ids) => write!(
+                f,
+                "{} hosted zones are named {}; use one of their IDs instead: {}",
+                ids.len(),
+                name,
+                ids.join(", ")
+            ),
+        }
+    }
+}
+
+impl Error for ZoneError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            ZoneError::Sdk(e) => Some(e),
+            ZoneError::NotFound(_) | ZoneError::Ambiguous(..) => None,
+        }
+    }
+}
+
+impl From<route53::Error> for ZoneError {
+    fn from(e: route53::Error) -> Self {
+        ZoneError::Sdk(e)
+    }
+}
+
+/// Removes the __/hostedzone/__ or __/change/__ prefix from an ID that Route 53 returned.
+pub fn bare_id(id: &str) -> &str {
+    id.rsplit('/').next().unwrap_or(id)
+}
+
+/// Adds the trailing dot that Route 53 uses at the end of domain names, if it's missing.
+pub fn absolute(name: &str) -> String {
+    if name.ends_with('.') {
+        name.to_string()
+    } else {
+        format!("{}.", name)
+    }
+}
+
+/// Returns the record name in the zone, such as __www.example.com.__ for __www__ or __www.example.com__.
+/// A name that's already in the zone, or is the zone's own name, is kept as it is.
+pub fn qualify(name: &str, zone_name: &str) -> String {
+    let name = absolute(name);
+    let zone_name = absolute(zone_name);
+
+    if name.eq_ignore_ascii_case(&zone_name)
+        || name
+            .to_ascii_lowercase()
+            .ends_with(&format!(".{}", zone_name.to_ascii_lowercase()))
+    {
+        name
+    } else {
+        format!("{}{}", name, zone_name)
+    }
+}
+
+/// Returns the hosted zone with this ID, with or without the __/hostedzone/__ prefix,
+/// or with this domain name, such as __example.com__.
+pub async fn find_zone(client: &Client, zone: &str) -> Result<Zone, ZoneError> {
+    // Domain names have at least one dot, which IDs never do.
+    if !zone.trim_end_matches('.').contains('.') {
+        let hosted_zone = client
+            .get_hosted_zone()
+            .id(bare_id(zone))
+            .send()
+            .await
+            .map_err(route53::Error::from)?
+            .hosted_zone
+            .ok_or_else(|| ZoneError::NotFound(zone.to_string()))?;
+
+        return Ok(Zone {
+            id: bare_id(hosted_zone.id.as_deref().unwrap_or(zone)).to_string(),
+            name: hosted_zone.name.unwrap_or_default(),
+            private: hosted_zone
+                .config
+                .map(|config| config.private_zone)
+                .unwrap_or_default(),
+        });
+    }
+
+    let name = absolute(zone);
+
+    // The zones are sorted by name, starting with this one, so only the first page can hold it.
+    let zones: Vec<Zone> = client
+        .list_hosted_zones_by_name()
+        .dns_name(&name)
+        .send()
+        .await
+        .map_err(route53::Error::from)?
+        .hosted_zones
+        .unwrap_or_default()
+        .into_iter()
+        .filter(|hosted_zone| {
+            hosted_zone
+                .name
+                .as_deref()
+                .map(|zone_name| zone_name.eq_ignore_ascii_case(&name))
+                .unwrap_or_default()
+        })
+        .map(|hosted_zone| Zone {
+            id: bare_id(hosted_zone.id.as_deref().unwrap_or_default()).to_string(),
+            name: hosted_zone.name.unwrap_or_default(),
+            private: hosted_zone
+                .config
+                .map(|config| config.private_zone)
+                .unwrap_or_default(),
+        })
+        .collect();
+
+    match zones.len() {
+        0 => Err(ZoneError::NotFound(name)),
+        1 => Ok(zones.into_iter().next().expect("there's one zone")),
+        _ => Err(ZoneError::Ambiguous(
+            name,
+            zones.into_iter().map(|zone| zone.id).collect(),
+        )),
+    }
+}
@@ -0,0 +1,468 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0.
+ */
+
+use std::collections::BTreeMap;
+use std::error::Error;
+use std::fmt;
+use std::sync::Arc;
+
+use s3::{Client, Config, Endpoint, Region};
+
+use aws_types::region::ProvideRegion;
+
+use common::endpoint;
+use common::output::OutputFormat;
+use common::profile::ProfileCredentialsProvider;
+use serde::Serialize;
+use structopt::StructOpt;
+use tokio::sync::Semaphore;
+use tracing_subscriber::fmt::format::FmtSpan;
+use tracing_subscriber::fmt::SubscriberBuilder;
+
+/// The storage class of an object that ListObjectsV2 doesn't report one for.
+const STANDARD: &str = "STANDARD";
+
+#[derive(Debug, StructOpt)]
+struct Opt {
+    /// The default region
+    #[structopt(short, long)]
+    default_region: Option<String>,
+
+    /// The name of the bucket
+    #[structopt(short, long)]
+    bucket: String,
+
+    /// The prefix of the objects to analyze. Defaults to the whole bucket
+    #[structopt(short, long, default_value = "")]
+    prefix: String,
+
+    /// How many levels of the key, after the prefix, to group the objects by
+    #[structopt(long, default_value = "1")]
+    depth: usize,
+
+    /// The delimiter between the levels of a key
+    #[structopt(long, default_value = "/")]
+    delimiter: String,
+
+    /// How many prefixes to list at once
+    #[structopt(short, long, default_value = "8")]
+    concurrency: usize,
+
+    /// The format of the output, text or json
+    #[structopt(long, default_value = "text")]
+    output: OutputFormat,
+
+    /// The AWS profile to use from the shared credentials file
+    #[structopt(long)]
+    profile: Option<String>,
+
+    /// Whether to display additional information
+    #[structopt(short, long)]
+    verbose: bool,
+}
+
+/// The number and size of a set of objects.
+#[derive(Debug, Default, Clone, Serialize)]
+struct Usage {
+    objects: u64,
+    bytes: u64,
+}
+
+impl Usage {
+    fn add(&mut self, other: &Usage) {
+        self.objects += other.objects;
+        self.bytes += other.bytes;
+    }
+}
+
+/// The usage of the objects under one group prefix, in total and by storage class.
+#[derive(Debug, Default, Clone, Serialize)]
+struct PrefixUsage {
+    prefix: String,
+    #[serde(flatten)]
+    total: Usage,
+    storage_classes: BTreeMap<String, Usage>,
+}
+
+impl PrefixUsage {
+    fn add(&mut self, storage_class: &str, usage: &Usage) {
+        self.total.add(usage);
+        self.storage_classes
+            .entry(storage_class.to_string())
+            .or_default()
+            .add(usage);
+    }
+}
+
+/// What the analysis found.
+#[derive(Debug, Serialize)]
+struct Analysis {
+    bucket: String,
+    prefix: String,
+    #[serde(flatten)]
+    total: Usage,
+    /// The usage of the whole prefix, by storage class.
+    storage_classes: BTreeMap<String, Usage>,
+    /// The usage of each group, the largest first.
+    prefixes: Vec<PrefixUsage>,
+}
+
+/// Formats a number of bytes in the largest binary unit that keeps it at least 1, such as 1.5 GiB.
+fn format_bytes(bytes: u64) -> String {
+    const UNITS: [&str; 6] = ["B", "KiB", "MiB", "GiB", "TiB", "PiB"];
+
+    let mut size = bytes as f64;
+    let mut unit = 0;
+    while size >= 1024.0 && unit < UNITS.len() - 1 {
+        size /= 1024.0;
+        unit += 1;
+    }
+
+    if unit == 0 {
+        format!("{} B", bytes)
+    } else {
+        format!("{:.1} {}", size, UNITS[unit])
+    }
+}
+
+impl fmt::Display for Analysis {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(
+            f,
+            "Bucket {} under prefix {:?}: {} objects, {}",
+            self.bucket,
+            self.prefix,
+            self.total.objects,
+            format_bytes(self.total.bytes)
+        )?;
+
+        writeln!(f, "\nBy storage class:")?;
+        for (storage_class, usage) in &self.storage_classes {
+            writeln!(
+                f,
+                "  {:<20} {:>12} objects {:>12}",
+                storage_class,
+                usage.objects,
+                format_bytes(usage.bytes)
+            )?;
+        }
+
+        write!(f, "\nBy prefix:")?;
+        for prefix in &self.prefixes {
+            let share = if self.total.bytes == 0 {
+                0.0
+            } else {
+                prefix.total.bytes as f64 * 100.0 / self.total.bytes as f64
+            };
+
+            let classes: Vec<String> = prefix
+                .storage_classes
+                .iter()
+                .map(|(storage_class, usage)| {
+                    format!("{} {}", storage_class, format_bytes(usage.bytes))
+                })
+                .collect();
+
+            write!(
+                f,
+                "\n  {:>12} {:>5.1}% {:>12} objects  {}  ({})",
+                format_bytes(prefix.total.bytes),
+                share,
+                prefix.total.objects,
+                // The objects at the top of the bucket have no prefix.
+                if prefix.prefix.is_empty() {
+                    "(top level)"
+                } else {
+                    &prefix.prefix
+                },
+                classes.join(", ")
+            )?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Returns the group that a key is counted in: the prefix and the first `depth` levels of the rest of the key,
+/// each with its delimiter. A key with fewer levels is counted in its own directory,
+/// so `logs/a.txt` is in `logs/`, even when the depth is 2.
+fn group_of(key: &str, prefix: &str, delimiter: &str, depth: usize) -> String {
+    let rest = &key[prefix.len().min(key.len())..];
+    let mut end = 0;
+
+    for _ in 0..depth {
+        match rest[end..].find(delimiter) {
+            Some(i) => end += i + delimiter.len(),
+            None => break,
+        }
+    }
+
+    format!("{}{}", prefix, &rest[..end])
+}
+
+/// Lists the first level under the prefix, and returns the common prefixes to hand to the workers,
+/// and the usage of the objects directly under the prefix, which no worker will see.
+async fn list_top_level(
+    client: &Client,
+    bucket: &str,
+    prefix: &str,
+    delimiter: &str,
+) -> Result<(Vec<String>, PrefixUsage), s3::Error> {
+    let mut prefixes = Vec::new();
+    let mut direct = PrefixUsage {
+        prefix: prefix.to_string(),
+        ..Default::default()
+    };
+    let mut token = None;
+
+    loop {
+        let resp = client
+            .list_objects_v2()
+            .bucket(bucket)
+            .prefix(prefix)
+            .delimiter(delimiter)
+            .set_continuation_token(token)
+            .send()
+            .await?;
+
+        for common_prefix in resp.common_prefixes.unwrap_or_default() {
+            prefixes.extend(common_prefix.prefix);
+        }
+
+        for object in resp.contents.unwrap_or_default() {
+            let storage_class = object
+                .storage_class
+                .as_ref()
+                .map(|storage_class| storage_class.as_str())
+                .unwrap_or(STANDARD);
+            direct.add(
+                storage_class,
+                &Usage {
+                    objects: 1,
+                    bytes: object.size.max(0) as u64,
+                },
+            );
+        }
+
+        token = resp.next_continuation_token;
+        if token.is_none() {
+            return Ok((prefixes, direct));
+        }
+    }
+}
+
+/// Lists every object under a common prefix, a page at a time, and adds it to its group.
+async fn analyze_prefix(
+    client: &Client,
+    bucket: &str,
+    prefix: &str,
+    base: &str,
+    delimiter: &str,
+    depth: usize,
+) -> Result<BTreeMap<String, PrefixUsage>, s3::Error> {
+    let mut groups: BTreeMap<String, PrefixUsage> = BTreeMap::new();
+    let mut token = None;
+
+    loop {
+        let resp = client
+            .list_objects_v2()
+            .bucket(bucket)
+            .prefix(prefix)
+            .set_continuation_token(token)
+            .send()
+            .await?;
+
+        for object in resp.contents.unwrap_or_default() {
+            let key = object.key.unwrap_or_default();
+            let group = group_of(&key, base, delimiter, depth);
+            let storage_class = object
+                .storage_class
+                .as_ref()
+                .map(|storage_class| storage_class.as_str())
+                .unwrap_or(STANDARD);
+
+            groups
+                .entry(group.clone())
+                .or_insert_with(|| PrefixUsage {
+                    prefix: group,
+                    ..Default::default()
+                })
+                .add(
+                    storage_class,
+                    &Usage {
+                        objects: 1,
+                        bytes: object.size.max(0) as u64,
+                    },
+                );
+        }
+
+        token = resp.next_continuation_token;
+        if token.is_none() {
+            return Ok(groups);
+        }
+    }
+}
+
+/// Analyzes how much storage the objects in an Amazon S3 bucket use, by prefix and by storage class.
+/// The first level under the prefix is listed with a delimiter, and then a worker lists everything under
+/// each of the common prefixes it finds, several at a time, so a bucket with many top-level prefixes
+/// is listed in parallel. Only the current versions of objects are counted, not older versions
+/// or incomplete multipart uploads.
+/// # Arguments
+///
+/// * `-b BUCKET` - The name of the bucket.
+/// * `[-p PREFIX]` - The prefix of the objects to analyze.
+///   If not supplied, analyzes the whole bucket.
+/// * `[--depth DEPTH]` - How many levels of the key, after the prefix, to group the objects by.
+///   If not supplied, defaults to **1**.
+/// * `[--delimiter DELIMITER]` - The delimiter between the levels of a key.
+///   If not supplied, defaults to **/**.
+/// * `[-c CONCURRENCY]` - How many prefixes to list at once.
+///   If not supplied, defaults to **8**.
+/// * `[-d DEFAULT-REGION]` - The region containing the bucket.
+///   If not supplied, uses the value of the **AWS_DEFAULT_REGION** environment variable.
+///   If the environment variable is not set, defaults to **us-west-2**.
+/// * `[--output FORMAT]` - How to display the result, **text** (the default) or **json**.
+/// * `[--profile PROFILE]` - The AWS profile to use from the shared credentials file.
+/// * `[-v]` - Whether to display additional information.
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn Error>> {
+    let Opt {
+        bucket,
+        concurrency,
+        default_region,
+        delimiter,
+        depth,
+        output,
+        prefix,
+        profile,
+        verbose,
+    } = Opt::from_args();
+
+    let region = default_region
+        .as_ref()
+        .map(|region| Region::new(region.clone()))
+        .or_else(|| aws_types::region::default_provider().region())
+        .unwrap_or_else(|| Region::new("us-west-2"));
+
+    if concurrency == 0 {
+        return Err("the concurrency must be at least 1".into());
+    }
+
+    if depth == 0 {
+        return Err("the depth must be at least 1".into());
+    }
+
+    if delimiter.is_empty() {
+        return Err("the delimiter can't be empty".into());
+    }
+
+    if verbose {
+        println!("S3 client version: {}", s3::PKG_VERSION);
+        println!("Region:            {:?}", &region);
+        println!("Bucket:            {}", &bucket);
+        println!("Prefix:            {}", &prefix);
+        println!("Depth:             {}", depth);
+        println!("Delimiter:         {}", &delimiter);
+        println!("Concurrency:       {}", concurrency);
+
+        SubscriberBuilder::default()
+            .with_env_filter("info")
+            .with_span_events(FmtSpan::CLOSE)
+            .init();
+    }
+
+    let mut config = Config::builder().region(&region);
+    if let Some(profile) = profile {
+        config = config.credentials_provider(ProfileCredentialsProvider::new(profile));
+    }
+
+    // Sends the requests to LocalStack, or another local endpoint, if AWS_ENDPOINT_URL is set.
+    if let Some(uri) = endpoint::from_env()? {
+        config = config.endpoint_resolver(Endpoint::immutable(uri));
+    }
+
+    let client = Arc::new(Client::from_conf(config.build()));
+
+    let (top_level, direct) = list_top_level(&client, &bucket, &prefix, &delimiter).await?;
+
+    if verbose {
+        println!(
+            "Found {} prefixes and {} objects directly under {:?}",
+            top_level.len(),
+            direct.total.objects,
+            &prefix
+        );
+    }
+
+    // The semaphore limits how many prefixes are listed at once.
+    let permits = Arc::new(Semaphore::new(concurrency));
+
+    let handles: Vec<_> = top_level
+        .into_iter()
+        .map(|top| {
+            let client = client.clone();
+            let bucket = bucket.clone();
+            let base = prefix.clone();
+            let delimiter = delimiter.clone();
+            let permits = permits.clone();
+
+            tokio::spawn(async move {
+                let _permit = permits.acquire().await;
+                let groups = analyze_prefix(&client, &bucket, &top, &base, &delimiter, depth).await;
+                (top, groups)
+            })
+        })
+        .collect();
+
+    let mut groups: BTreeMap<String, PrefixUsage> = BTreeMap::new();
+    if direct.total.objects > 0 {
+        groups.insert(direct.prefix.clone(), direct);
+    }
+
+    for handle in handles {
+        let (top, result) = handle.await?;
+        let result = result.map_err(|e| format!("couldn't list {}: {}", &top, e))?;
+
+        if verbose {
+            println!("Listed {}", &top);
+        }
+
+        // The workers' prefixes don't overlap, so neither do their groups.
+        groups.extend(result);
+    }
+
+    let mut total = Usage::default();
+    let mut storage_classes: BTreeMap<String, Usage> = BTreeMap::new();
+
+    for group in groups.values() {
+        total.add(&group.total);
+
+        for (storage_class, usage) in &group.storage_classes {
+            storage_classes
+                .entry(storage_class.clone())
+                .or_default()
+                .add(usage);
+        }
+    }
+
+    let mut prefixes: Vec<PrefixUsage> = groups.into_iter().map(|(_, group)| group).collect();
+    prefixes.sort_by(|a, b| {
+        b.total
+            .bytes
+            .cmp(&a.total.bytes)
+            .then_with(|| a.prefix.cmp(&b.prefix))
+    });
+
+    output.print(&Analysis {
+        bucket,
+        prefix,
+        total,
+        storage_classes,
+        prefixes,
+    });
+
+    Ok(())
+}
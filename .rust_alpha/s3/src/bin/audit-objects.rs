@@ -0,0 +1,352 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0.
+ */
+
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::error::Error;
+use std::fmt;
+use std::fs;
+use std::sync::Arc;
+
+use s3::{Client, Config, Endpoint, Region};
+
+use aws_types::region::ProvideRegion;
+
+use common::endpoint;
+use common::output::OutputFormat;
+use common::profile::ProfileCredentialsProvider;
+use serde::Serialize;
+use structopt::StructOpt;
+use tokio::sync::Semaphore;
+use tokio_stream::StreamExt;
+use tracing_subscriber::fmt::format::FmtSpan;
+use tracing_subscriber::fmt::SubscriberBuilder;
+
+#[derive(Debug, StructOpt)]
+struct Opt {
+    /// The default region
+    #[structopt(short, long)]
+    default_region: Option<String>,
+
+    /// The name of the bucket
+    #[structopt(short, long)]
+    bucket: String,
+
+    /// The prefix of the objects to audit. Each path in the manifest is added to it to get the key
+    #[structopt(short, long, default_value = "")]
+    prefix: String,
+
+    /// The manifest, in the format that md5sum writes: an MD5 digest and a path on each line
+    #[structopt(short, long)]
+    manifest: String,
+
+    /// Whether to download every object, rather than trusting ETags that are MD5 digests
+    #[structopt(long)]
+    download: bool,
+
+    /// How many objects to download at once
+    #[structopt(short, long, default_value = "8")]
+    concurrency: usize,
+
+    /// The format of the output, text or json
+    #[structopt(long, default_value = "text")]
+    output: OutputFormat,
+
+    /// The AWS profile to use from the shared credentials file
+    #[structopt(long)]
+    profile: Option<String>,
+
+    /// Whether to display additional information
+    #[structopt(short, long)]
+    verbose: bool,
+}
+
+/// What the audit found.
+#[derive(Debug, Serialize)]
+struct Audit {
+    bucket: String,
+    prefix: String,
+    /// How many objects matched the manifest.
+    verified: usize,
+    /// How many of those were verified by downloading them, rather than by their ETag.
+    downloaded: usize,
+    /// The keys in the manifest that aren't in the bucket.
+    missing: Vec<String>,
+    /// The keys whose content doesn't match the manifest.
+    corrupted: Vec<String>,
+    /// The keys under the prefix that aren't in the manifest.
+    extra: Vec<String>,
+}
+
+impl Audit {
+    /// How many objects are missing, corrupted, or extra.
+    fn problems(&self) -> usize {
+        self.missing.len() + self.corrupted.len() + self.extra.len()
+    }
+}
+
+impl fmt::Display for Audit {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "Verified {} objects in bucket {} under prefix {:?}, downloading {} of them",
+            self.verified, self.bucket, self.prefix, self.downloaded
+        )?;
+
+        for (label, keys) in &[
+            ("Missing", &self.missing),
+            ("Corrupted", &self.corrupted),
+            ("Extra", &self.extra),
+        ] {
+            if !keys.is_empty() {
+                write!(f, "\n{} ({}):", label, keys.len())?;
+
+                for key in keys.iter() {
+                    write!(f, "\n  {}", key)?;
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Reads a manifest in the format that md5sum writes, and returns the MD5 digest of each key.
+/// The path on each line can start with ./ and, for binary mode, be marked with *.
+fn read_manifest(file: &str, prefix: &str) -> Result<BTreeMap<String, String>, Box<dyn Error>> {
+    let mut digests = BTreeMap::new();
+
+    for (index, line) in fs::read_to_string(file)?.lines().enumerate() {
+        if line.trim().is_empty() {
+            continue;
+        }
+
+        let (digest, path) = line
+            .split_once(' ')
+            .filter(|(digest, _)| digest.len() == 32)
+            .ok_or_else(|| format!("line {} of the manifest is not DIGEST  PATH", index + 1))?;
+        let path = path.trim_start_matches(|c| c == ' ' || c == '*');
+        let path = path.strip_prefix("./").unwrap_or(path);
+
+        digests.insert(format!("{}{}", prefix, path), digest.to_ascii_lowercase());
+    }
+
+    Ok(digests)
+}
+
+/// Lists the objects under the prefix, a page at a time, and returns the ETag of each key.
+async fn list_etags(
+    client: &Client,
+    bucket: &str,
+    prefix: &str,
+) -> Result<HashMap<String, String>, s3::Error> {
+    let mut etags = HashMap::new();
+    let mut token = None;
+
+    loop {
+        let resp = client
+            .list_objects_v2()
+            .bucket(bucket)
+            .prefix(prefix)
+            .set_continuation_token(token)
+            .send()
+            .await?;
+
+        for object in resp.contents.unwrap_or_default() {
+            if let Some(key) = object.key {
+                // ETags are quoted.
+                let etag = object.e_tag.unwrap_or_default();
+                etags.insert(key, etag.trim_matches('"').to_ascii_lowercase());
+            }
+        }
+
+        token = resp.next_continuation_token;
+        if token.is_none() {
+            return Ok(etags);
+        }
+    }
+}
+
+/// Downloads an object, and returns the MD5 digest of its content.
+/// The content is hashed as it arrives, so large objects aren't held in memory.
+async fn download_digest(
+    client: &Client,
+    bucket: &str,
+    key: &str,
+) -> Result<String, Box<dyn Error + Send + Sync>> {
+    let mut body = client
+        .get_object()
+        .bucket(bucket)
+        .key(key)
+        .send()
+        .await?
+        .body;
+    let mut context = md5::Context::new();
+
+    while let Some(chunk) = body.next().await {
+        context.consume(&chunk?);
+    }
+
+    Ok(format!("{:x}", context.compute()))
+}
+
+/// Whether an ETag is the MD5 digest of the object's content.
+/// It isn't for multipart uploads, whose ETags end in -PARTS. It isn't for objects encrypted with
+/// AWS KMS either, but those can't be told apart, so a mismatch is checked by downloading the object.
+fn is_md5(etag: &str) -> bool {
+    etag.len() == 32 && etag.bytes().all(|b| b.is_ascii_hexdigit())
+}
+
+/// Audits the objects under a prefix in an Amazon S3 bucket against a manifest of MD5 digests,
+/// and reports the objects that are missing, corrupted, or not in the manifest.
+/// An object whose ETag matches the manifest's digest is verified without downloading it.
+/// Every other object is downloaded, several at a time, and its digest computed.
+/// Returns an error if the audit finds any problems, so that scripts can check the exit code.
+/// # Arguments
+///
+/// * `-b BUCKET` - The name of the bucket.
+/// * `-m MANIFEST` - The manifest, such as the output of **md5sum**.
+/// * `[-p PREFIX]` - The prefix of the objects to audit.
+///   If not supplied, audits the whole bucket.
+/// * `[--download]` - Whether to download every object, rather than trusting ETags.
+/// * `[-c CONCURRENCY]` - How many objects to download at once.
+///   If not supplied, defaults to **8**.
+/// * `[-d DEFAULT-REGION]` - The region containing the bucket.
+///   If not supplied, uses the value of the **AWS_DEFAULT_REGION** environment variable.
+///   If the environment variable is not set, defaults to **us-west-2**.
+/// * `[--output FORMAT]` - How to display the result, **text** (the default) or **json**.
+/// * `[--profile PROFILE]` - The AWS profile to use from the shared credentials file.
+/// * `[-v]` - Whether to display additional information.
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn Error>> {
+    let Opt {
+        bucket,
+        concurrency,
+        default_region,
+        download,
+        manifest,
+        output,
+        prefix,
+        profile,
+        verbose,
+    } = Opt::from_args();
+
+    let region = default_region
+        .as_ref()
+        .map(|region| Region::new(region.clone()))
+        .or_else(|| aws_types::region::default_provider().region())
+        .unwrap_or_else(|| Region::new("us-west-2"));
+
+    if concurrency == 0 {
+        return Err("the concurrency must be at least 1".into());
+    }
+
+    let digests = read_manifest(&manifest, &prefix)?;
+
+    if verbose {
+        println!("S3 client version: {}", s3::PKG_VERSION);
+        println!("Region:            {:?}", &region);
+        println!("Bucket:            {}", &bucket);
+        println!("Prefix:            {}", &prefix);
+        println!(
+            "Manifest:          {} ({} objects)",
+            &manifest,
+            digests.len()
+        );
+        println!("Download all:      {}", download);
+        println!("Concurrency:       {}", concurrency);
+
+        SubscriberBuilder::default()
+            .with_env_filter("info")
+            .with_span_events(FmtSpan::CLOSE)
+            .init();
+    }
+
+    let mut config = Config::builder().region(&region);
+    if let Some(profile) = profile {
+        config = config.credentials_provider(ProfileCredentialsProvider::new(profile));
+    }
+
+    // Sends the requests to LocalStack, or another local endpoint, if AWS_ENDPOINT_URL is set.
+    if let Some(uri) = endpoint::from_env()? {
+        config = config.endpoint_resolver(Endpoint::immutable(uri));
+    }
+
+    let client = Arc::new(Client::from_conf(config.build()));
+
+    let etags = list_etags(&client, &bucket, &prefix).await?;
+
+    let mut verified = 0;
+    let mut missing = Vec::new();
+    let mut to_download = Vec::new();
+
+    for (key, digest) in &digests {
+        match etags.get(key) {
+            None => missing.push(key.clone()),
+            Some(etag) if !download && is_md5(etag) && etag == digest => verified += 1,
+            Some(_) => to_download.push((key.clone(), digest.clone())),
+        }
+    }
+
+    let extra: Vec<String> = etags
+        .keys()
+        .filter(|key| !digests.contains_key(*key))
+        .cloned()
+        .collect::<BTreeSet<_>>()
+        .into_iter()
+        .collect();
+
+    // The semaphore limits how many downloads run at once.
+    let permits = Arc::new(Semaphore::new(concurrency));
+    let downloaded = to_download.len();
+
+    let handles: Vec<_> = to_download
+        .into_iter()
+        .map(|(key, digest)| {
+            let client = client.clone();
+            let bucket = bucket.clone();
+            let permits = permits.clone();
+
+            tokio::spawn(async move {
+                let _permit = permits.acquire().await;
+                let actual = download_digest(&client, &bucket, &key).await;
+                (key, digest, actual)
+            })
+        })
+        .collect();
+
+    let mut corrupted = Vec::new();
+
+    for handle in handles {
+        let (key, digest, actual) = handle.await?;
+        let actual = actual.map_err(|e| format!("couldn't download {}: {}", &key, e))?;
+
+        if verbose {
+            println!("Downloaded {}: {}", &key, &actual);
+        }
+
+        if actual == digest {
+            verified += 1;
+        } else {
+            corrupted.push(key);
+        }
+    }
+
+    let audit = Audit {
+        bucket,
+        prefix,
+        verified,
+        downloaded,
+        missing,
+        corrupted,
+        extra,
+    };
+
+    output.print(&audit);
+
+    match audit.problems() {
+        0 => Ok(()),
+        problems => Err(format!("the audit found {} problems", problems).into()),
+    }
+}
@@ -0,0 +1,197 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0.
+ */
+
+use std::error::Error;
+use std::fmt;
+
+use s3::{Client, Config, Endpoint, Region};
+use s3_code_examples::buckets::ensure_bucket;
+use s3_code_examples::copy::copy_source;
+
+use aws_types::region::ProvideRegion;
+
+use common::endpoint;
+use common::output::OutputFormat;
+use common::profile::ProfileCredentialsProvider;
+use serde::Serialize;
+use structopt::StructOpt;
+use tracing_subscriber::fmt::format::FmtSpan;
+use tracing_subscriber::fmt::SubscriberBuilder;
+
+#[derive(Debug, StructOpt)]
+struct Opt {
+    /// The default region
+    #[structopt(short, long)]
+    default_region: Option<String>,
+
+    /// The name of the bucket that contains the object
+    #[structopt(short, long)]
+    source_bucket: String,
+
+    /// The object to copy
+    #[structopt(short = "k", long)]
+    source_key: String,
+
+    /// The name of the bucket to copy the object to. Defaults to the source bucket
+    #[structopt(short, long)]
+    bucket: Option<String>,
+
+    /// The name of the copy. Defaults to the name of the source object
+    #[structopt(long)]
+    key: Option<String>,
+
+    /// Whether to delete the source object after it's copied
+    #[structopt(long = "move")]
+    move_object: bool,
+
+    /// The format of the output, text or json
+    #[structopt(long, default_value = "text")]
+    output: OutputFormat,
+
+    /// The AWS profile to use from the shared credentials file
+    #[structopt(long)]
+    profile: Option<String>,
+
+    /// Whether to display additional information
+    #[structopt(short, long)]
+    verbose: bool,
+}
+
+/// The object that was copied.
+#[derive(Debug, Serialize)]
+struct CopiedObject {
+    source_bucket: String,
+    source_key: String,
+    bucket: String,
+    key: String,
+    e_tag: Option<String>,
+    moved: bool,
+}
+
+impl fmt::Display for CopiedObject {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} {} from bucket {} to {} in bucket {}",
+            if self.moved { "Moved" } else { "Copied" },
+            self.source_key,
+            self.source_bucket,
+            self.key,
+            self.bucket
+        )?;
+
+        if let Some(e_tag) = &self.e_tag {
+            write!(f, " (ETag {})", e_tag)?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Copies an object within a bucket, or to another bucket, and optionally deletes the original.
+/// Amazon S3 has no rename or move operation, so a move is a copy followed by a delete.
+/// The source object is deleted only if the copy succeeds.
+/// # Arguments
+///
+/// * `-s SOURCE-BUCKET` - The name of the bucket that contains the object.
+/// * `-k SOURCE-KEY` - The name of the object to copy.
+/// * `[-b BUCKET]` - The name of the bucket to copy the object to.
+///   If not supplied, the object is copied within the source bucket.
+/// * `[--key KEY]` - The name of the copy.
+///   If not supplied, uses the name of the source object.
+/// * `[--move]` - Whether to delete the source object after it's copied.
+/// * `[-d DEFAULT-REGION]` - The region containing the buckets.
+///   If not supplied, uses the value of the **AWS_DEFAULT_REGION** environment variable.
+///   If the environment variable is not set, defaults to **us-west-2**.
+/// * `[--output FORMAT]` - How to display the result, **text** (the default) or **json**.
+/// * `[--profile PROFILE]` - The AWS profile to use from the shared credentials file.
+/// * `[-v]` - Whether to display additional information.
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn Error>> {
+    let Opt {
+        bucket,
+        default_region,
+        key,
+        move_object,
+        output,
+        profile,
+        source_bucket,
+        source_key,
+        verbose,
+    } = Opt::from_args();
+
+    let region = default_region
+        .as_ref()
+        .map(|region| Region::new(region.clone()))
+        .or_else(|| aws_types::region::default_provider().region())
+        .unwrap_or_else(|| Region::new("us-west-2"));
+
+    let bucket = bucket.unwrap_or_else(|| source_bucket.clone());
+    let key = key.unwrap_or_else(|| source_key.clone());
+
+    if bucket == source_bucket && key == source_key {
+        return Err("the copy must have a different bucket or key than the source object".into());
+    }
+
+    if verbose {
+        println!("S3 client version: {}", s3::PKG_VERSION);
+        println!("Region:            {:?}", &region);
+        println!("Source bucket:     {}", &source_bucket);
+        println!("Source key:        {}", &source_key);
+        println!("Bucket:            {}", &bucket);
+        println!("Key:               {}", &key);
+        println!("Move:              {}", move_object);
+
+        SubscriberBuilder::default()
+            .with_env_filter("info")
+            .with_span_events(FmtSpan::CLOSE)
+            .init();
+    }
+
+    let mut config = Config::builder().region(&region);
+    if let Some(profile) = profile {
+        config = config.credentials_provider(ProfileCredentialsProvider::new(profile));
+    }
+
+    // Sends the requests to LocalStack, or another local endpoint, if AWS_ENDPOINT_URL is set.
+    if let Some(uri) = endpoint::from_env()? {
+        config = config.endpoint_resolver(Endpoint::immutable(uri));
+    }
+
+    let client = Client::from_conf(config.build());
+
+    ensure_bucket(&client, &bucket, &region, false).await?;
+
+    // The copy happens within Amazon S3, so the object isn't downloaded and uploaded again.
+    let resp = client
+        .copy_object()
+        .copy_source(copy_source(&source_bucket, &source_key))
+        .bucket(&bucket)
+        .key(&key)
+        .send()
+        .await?;
+
+    let e_tag = resp.copy_object_result.and_then(|result| result.e_tag);
+
+    if move_object {
+        client
+            .delete_object()
+            .bucket(&source_bucket)
+            .key(&source_key)
+            .send()
+            .await?;
+    }
+
+    output.print(&CopiedObject {
+        source_bucket,
+        source_key,
+        bucket,
+        key,
+        e_tag,
+        moved: move_object,
+    });
+
+    Ok(())
+}
@@ -3,14 +3,18 @@
  * SPDX-License-Identifier: Apache-2.0.
  */
 
-use std::process;
+use std::error::Error;
+use std::fmt;
 
-use s3::{Client, Config, Region};
-
-use s3::model::{BucketLocationConstraint, CreateBucketConfiguration};
+use s3::{Client, Config, Endpoint, Region};
+use s3_code_examples::buckets::ensure_bucket;
 
 use aws_types::region::ProvideRegion;
 
+use common::endpoint;
+use common::output::OutputFormat;
+use common::profile::ProfileCredentialsProvider;
+use serde::Serialize;
 use structopt::StructOpt;
 use tracing_subscriber::fmt::format::FmtSpan;
 use tracing_subscriber::fmt::SubscriberBuilder;
@@ -25,24 +29,59 @@ struct Opt {
     #[structopt(short, long)]
     name: String,
 
+    /// The format of the output, text or json
+    #[structopt(long, default_value = "text")]
+    output: OutputFormat,
+
+    /// The AWS profile to use from the shared credentials file
+    #[structopt(long)]
+    profile: Option<String>,
+
     /// Whether to display additional information
     #[structopt(short, long)]
     verbose: bool,
 }
 
-/// Creates an Amazon S3 bucket
+/// The result of creating a bucket.
+#[derive(Debug, Serialize)]
+struct CreatedBucket {
+    bucket: String,
+    region: String,
+    created: bool,
+}
+
+impl fmt::Display for CreatedBucket {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.created {
+            write!(f, "Created bucket {} in {}", self.bucket, self.region)
+        } else {
+            write!(
+                f,
+                "You already own bucket {} in {}",
+                self.bucket, self.region
+            )
+        }
+    }
+}
+
+/// Creates an Amazon S3 bucket, unless you already own it.
+/// Reports a clear error if the name is taken by another account, or you own the bucket in another region.
 /// # Arguments
 ///
 /// * `-n NAME` - The name of the bucket.
 /// * `[-d DEFAULT-REGION]` - The region containing the bucket.
 ///   If not supplied, uses the value of the **AWS_DEFAULT_REGION** environment variable.
 ///   If the environment variable is not set, defaults to **us-west-2**.
+/// * `[--output FORMAT]` - How to display the result, **text** (the default) or **json**.
+/// * `[--profile PROFILE]` - The AWS profile to use from the shared credentials file.
 /// * `[-v]` - Whether to display additional information.
 #[tokio::main]
-async fn main() {
+async fn main() -> Result<(), Box<dyn Error>> {
     let Opt {
         default_region,
         name,
+        output,
+        profile,
         verbose,
     } = Opt::from_args();
 
@@ -52,8 +91,6 @@ async fn main() {
         .or_else(|| aws_types::region::default_provider().region())
         .unwrap_or_else(|| Region::new("us-west-2"));
 
-    let r: &str = &region.as_ref();
-
     if verbose {
         println!("S3 client version: {}", s3::PKG_VERSION);
         println!("Region:            {:?}", &region);
@@ -64,30 +101,25 @@ async fn main() {
             .init();
     }
 
-    let config = Config::builder().region(&region).build();
+    let mut config = Config::builder().region(&region);
+    if let Some(profile) = profile {
+        config = config.credentials_provider(ProfileCredentialsProvider::new(profile));
+    }
 
-    let client = Client::from_conf(config);
+    // Sends the requests to LocalStack, or another local endpoint, if AWS_ENDPOINT_URL is set.
+    if let Some(uri) = endpoint::from_env()? {
+        config = config.endpoint_resolver(Endpoint::immutable(uri));
+    }
 
-    let constraint = BucketLocationConstraint::from(r);
-    let cfg = CreateBucketConfiguration::builder()
-        .location_constraint(constraint)
-        .build();
+    let client = Client::from_conf(config.build());
 
-    match client
-        .create_bucket()
-        .create_bucket_configuration(cfg)
-        .bucket(&name)
-        .send()
-        .await
-    {
-        Ok(_) => {
-            println!("Created bucket {}", name);
-        }
+    let created = ensure_bucket(&client, &name, &region, true).await?;
 
-        Err(e) => {
-            println!("Got an error creating bucket:");
-            println!("{}", e);
-            process::exit(1);
-        }
-    };
+    output.print(&CreatedBucket {
+        bucket: name,
+        region: region.as_ref().to_string(),
+        created,
+    });
+
+    Ok(())
 }
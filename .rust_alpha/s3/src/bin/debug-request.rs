@@ -0,0 +1,180 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0.
+ */
+
+use std::error::Error;
+use std::fmt;
+
+use s3::{Client, Config, Region, SdkError};
+use s3_code_examples::debug::{LoggingConnector, TARGET};
+
+use aws_types::region::ProvideRegion;
+
+use common::output::OutputFormat;
+use common::profile::ProfileCredentialsProvider;
+use serde::Serialize;
+use structopt::StructOpt;
+use tracing_subscriber::fmt::SubscriberBuilder;
+
+#[derive(Debug, StructOpt)]
+struct Opt {
+    /// The default region
+    #[structopt(short, long)]
+    default_region: Option<String>,
+
+    /// The name of the bucket to list the objects in. Defaults to listing your buckets
+    #[structopt(short, long)]
+    bucket: Option<String>,
+
+    /// Whether to change a signed header after signing, to cause a SignatureDoesNotMatch error
+    #[structopt(long)]
+    tamper: bool,
+
+    /// Which log messages to display, as a tracing filter
+    #[structopt(long, default_value = "info,debug_request=trace")]
+    filter: String,
+
+    /// The format of the output, text or json
+    #[structopt(long, default_value = "text")]
+    output: OutputFormat,
+
+    /// The AWS profile to use from the shared credentials file
+    #[structopt(long)]
+    profile: Option<String>,
+
+    /// Whether to display additional information
+    #[structopt(short, long)]
+    verbose: bool,
+}
+
+/// What the request listed.
+#[derive(Debug, Serialize)]
+struct Listed {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    bucket: Option<String>,
+    count: i64,
+}
+
+impl fmt::Display for Listed {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.bucket {
+            Some(bucket) => write!(f, "Listed {} objects in {}", self.count, bucket),
+            None => write!(f, "Listed {} buckets", self.count),
+        }
+    }
+}
+
+/// Sends a request to Amazon S3 and logs it after it's signed, with the canonical request
+/// and string to sign that the signature is computed from, and then logs the raw response.
+/// Use this to find out why a request fails with **SignatureDoesNotMatch**.
+/// # Arguments
+///
+/// * `[-b BUCKET]` - The bucket to list the objects in.
+///   If not supplied, lists your buckets.
+/// * `[--tamper]` - Whether to change the **x-amz-date** header after the request is signed,
+///   as a proxy might, so that the request fails with **SignatureDoesNotMatch**.
+/// * `[--filter FILTER]` - Which log messages to display. Defaults to **info,debug_request=trace**.
+///   Add **aws_sigv4=trace** to also see what the signer logs.
+/// * `[--output FORMAT]` - How to display the result, **text** (the default) or **json**.
+///   With **json**, the log messages are written to stderr, so that stdout is only the result.
+/// * `[-d DEFAULT-REGION]` - The region in which the client is created.
+///   If not supplied, uses the value of the **AWS_DEFAULT_REGION** environment variable.
+///   If the environment variable is not set, defaults to **us-west-2**.
+/// * `[--profile PROFILE]` - The AWS profile to use from the shared credentials file.
+/// * `[-v]` - Whether to display additional information.
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn Error>> {
+    let Opt {
+        default_region,
+        bucket,
+        tamper,
+        filter,
+        output,
+        profile,
+        verbose,
+    } = Opt::from_args();
+
+    let region = default_region
+        .as_ref()
+        .map(|region| Region::new(region.clone()))
+        .or_else(|| aws_types::region::default_provider().region())
+        .unwrap_or_else(|| Region::new("us-west-2"));
+
+    if verbose {
+        println!("S3 client version: {}", s3::PKG_VERSION);
+        println!("Region:            {:?}", &region);
+        println!("Bucket:            {:?}", bucket);
+        println!("Tamper:            {}", tamper);
+        println!("Filter:            {}", filter);
+        println!();
+    }
+
+    // The log messages are the point of this example, so they're always displayed.
+    if output.is_json() {
+        SubscriberBuilder::default()
+            .with_env_filter(filter)
+            .with_writer(std::io::stderr)
+            .init();
+    } else {
+        SubscriberBuilder::default().with_env_filter(filter).init();
+    }
+
+    let mut config = Config::builder().region(&region);
+    if let Some(profile) = profile {
+        config = config.credentials_provider(ProfileCredentialsProvider::new(profile));
+    }
+
+    // The connector sends the requests once they're signed, so wrapping it sees them as AWS does.
+    let conn = LoggingConnector::new(aws_hyper::conn::Standard::https()).tamper(tamper);
+    let client = Client::from_conf_conn(config.build(), conn);
+
+    let count = match &bucket {
+        Some(bucket) => {
+            let resp = client
+                .list_objects_v2()
+                .bucket(bucket)
+                .max_keys(10)
+                .send()
+                .await;
+
+            if let Err(SdkError::ServiceError { err, .. }) = &resp {
+                explain(err.code());
+            }
+
+            i64::from(resp?.key_count)
+        }
+        None => {
+            let resp = client.list_buckets().send().await;
+
+            if let Err(SdkError::ServiceError { err, .. }) = &resp {
+                explain(err.code());
+            }
+
+            resp?.buckets.unwrap_or_default().len() as i64
+        }
+    };
+
+    output.print(&Listed { bucket, count });
+
+    Ok(())
+}
+
+/// Explains how to find the cause of a SignatureDoesNotMatch error.
+/// The explanation goes to stderr, with the error that follows it.
+fn explain(code: Option<&str>) {
+    if code != Some("SignatureDoesNotMatch") {
+        return;
+    }
+
+    eprintln!();
+    eprintln!("The request failed with SignatureDoesNotMatch.");
+    eprintln!(
+        "Compare the CanonicalRequest and StringToSign in the response logged by {}",
+        TARGET
+    );
+    eprintln!("with the canonical request and string to sign logged before the request was sent.");
+    eprintln!("A line that differs is the part of the request that changed after signing.");
+    eprintln!("If they're the same, the secret access key or the clock is wrong.");
+    eprintln!();
+}
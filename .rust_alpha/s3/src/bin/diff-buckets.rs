@@ -0,0 +1,139 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0.
+ */
+
+use std::error::Error;
+use std::fs;
+
+use s3::{Client, Config, Endpoint, Region};
+use s3_code_examples::inventory::{diff, list, Location};
+
+use aws_types::region::ProvideRegion;
+
+use common::endpoint;
+use common::output::OutputFormat;
+use common::profile::ProfileCredentialsProvider;
+use structopt::StructOpt;
+use tracing_subscriber::fmt::format::FmtSpan;
+use tracing_subscriber::fmt::SubscriberBuilder;
+
+#[derive(Debug, StructOpt)]
+struct Opt {
+    /// The default region
+    #[structopt(short, long)]
+    default_region: Option<String>,
+
+    /// The source, as BUCKET or BUCKET/PREFIX
+    #[structopt(short, long)]
+    source: Location,
+
+    /// The target, as BUCKET or BUCKET/PREFIX
+    #[structopt(short, long)]
+    target: Location,
+
+    /// The file to write the plan that makes the target match the source to, as JSON
+    #[structopt(long)]
+    plan: Option<String>,
+
+    /// Whether the plan deletes the objects that only the target has
+    #[structopt(long)]
+    delete: bool,
+
+    /// The format of the output, text or json
+    #[structopt(long, default_value = "text")]
+    output: OutputFormat,
+
+    /// The AWS profile to use from the shared credentials file
+    #[structopt(long)]
+    profile: Option<String>,
+
+    /// Whether to display additional information
+    #[structopt(short, long)]
+    verbose: bool,
+}
+
+/// Lists the objects in two Amazon S3 buckets, or under two prefixes, at the same time,
+/// and reports the keys that only one side has, and the keys whose sizes or ETags differ.
+/// Keys are compared relative to each side's prefix.
+/// Optionally writes a plan, as JSON, of the copies and deletions that make the target match the source.
+/// # Arguments
+///
+/// * `-s SOURCE` - The source bucket, with an optional prefix, such as **photos/2021/**.
+/// * `-t TARGET` - The target bucket, with an optional prefix.
+/// * `[--plan FILE]` - The file to write the plan to.
+/// * `[--delete]` - Whether the plan deletes the objects that only the target has.
+/// * `[-d DEFAULT-REGION]` - The region containing both buckets.
+///   If not supplied, uses the value of the **AWS_DEFAULT_REGION** environment variable.
+///   If the environment variable is not set, defaults to **us-west-2**.
+/// * `[--output FORMAT]` - How to display the result, **text** (the default) or **json**.
+/// * `[--profile PROFILE]` - The AWS profile to use from the shared credentials file.
+/// * `[-v]` - Whether to display additional information.
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn Error>> {
+    let Opt {
+        default_region,
+        source,
+        target,
+        plan,
+        delete,
+        output,
+        profile,
+        verbose,
+    } = Opt::from_args();
+
+    let region = default_region
+        .as_ref()
+        .map(|region| Region::new(region.clone()))
+        .or_else(|| aws_types::region::default_provider().region())
+        .unwrap_or_else(|| Region::new("us-west-2"));
+
+    if verbose {
+        println!("S3 client version: {}", s3::PKG_VERSION);
+        println!("Region:            {:?}", &region);
+        println!("Source:            {}", &source);
+        println!("Target:            {}", &target);
+        println!("Plan:              {:?}", &plan);
+        println!("Delete:            {}", delete);
+
+        SubscriberBuilder::default()
+            .with_env_filter("info")
+            .with_span_events(FmtSpan::CLOSE)
+            .init();
+    }
+
+    let mut config = Config::builder().region(&region);
+    if let Some(profile) = profile {
+        config = config.credentials_provider(ProfileCredentialsProvider::new(profile));
+    }
+
+    // Sends the requests to LocalStack, or another local endpoint, if AWS_ENDPOINT_URL is set.
+    if let Some(uri) = endpoint::from_env()? {
+        config = config.endpoint_resolver(Endpoint::immutable(uri));
+    }
+
+    let client = Client::from_conf(config.build());
+
+    // Both sides are listed at once, since a large bucket takes a request per 1,000 objects.
+    let (source_listing, target_listing) =
+        tokio::try_join!(list(&client, &source), list(&client, &target))?;
+
+    let diff = diff(&source_listing, &target_listing);
+
+    if let Some(file) = &plan {
+        let plan = diff.plan(&source, &target, delete);
+        fs::write(file, serde_json::to_string_pretty(&plan)?)?;
+
+        if !output.is_json() {
+            println!(
+                "Wrote a plan of {} actions to {}\n",
+                plan.actions.len(),
+                file
+            );
+        }
+    }
+
+    output.print(&diff);
+
+    Ok(())
+}
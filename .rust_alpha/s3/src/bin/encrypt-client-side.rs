@@ -0,0 +1,270 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0.
+ */
+
+use std::error::Error;
+use std::fmt;
+use std::fs;
+use std::path::Path;
+
+use kms::model::DataKeySpec;
+use kms::Blob;
+use s3::{ByteStream, Endpoint, Region};
+use s3_code_examples::buckets::ensure_bucket;
+use s3_code_examples::envelope::{encryption_context, metadata, open, seal, Envelope};
+
+use aws_types::region::ProvideRegion;
+
+use common::endpoint;
+use common::output::OutputFormat;
+use common::profile::ProfileCredentialsProvider;
+use serde::Serialize;
+use structopt::StructOpt;
+use tracing_subscriber::fmt::format::FmtSpan;
+use tracing_subscriber::fmt::SubscriberBuilder;
+
+#[derive(Debug, StructOpt)]
+struct Opt {
+    /// The default region
+    #[structopt(short, long)]
+    default_region: Option<String>,
+
+    /// The name of the bucket
+    #[structopt(short, long)]
+    bucket: String,
+
+    /// The file to encrypt and upload
+    #[structopt(short, long)]
+    file: String,
+
+    /// The name of the object. Defaults to the name of the file
+    #[structopt(short, long)]
+    key: Option<String>,
+
+    /// The ID, ARN, or alias of the AWS KMS key that encrypts the data key
+    #[structopt(long)]
+    kms_key_id: String,
+
+    /// The file to save the downloaded and decrypted object in. Defaults to FILE.decrypted
+    #[structopt(short, long)]
+    out: Option<String>,
+
+    /// The format of the output, text or json
+    #[structopt(long, default_value = "text")]
+    output: OutputFormat,
+
+    /// The AWS profile to use from the shared credentials file
+    #[structopt(long)]
+    profile: Option<String>,
+
+    /// Whether to display additional information
+    #[structopt(short, long)]
+    verbose: bool,
+}
+
+/// The object that was encrypted, uploaded, downloaded, and decrypted.
+#[derive(Debug, Serialize)]
+struct RoundTrip {
+    bucket: String,
+    key: String,
+    kms_key_id: String,
+    bytes: usize,
+    encrypted_bytes: usize,
+    out: String,
+    matches: bool,
+}
+
+impl fmt::Display for RoundTrip {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(
+            f,
+            "Uploaded {} to bucket {}, encrypted with a data key from KMS key {}",
+            self.key, self.bucket, self.kms_key_id
+        )?;
+        writeln!(f, "  Plaintext:  {} bytes", self.bytes)?;
+        writeln!(f, "  Ciphertext: {} bytes", self.encrypted_bytes)?;
+        write!(
+            f,
+            "Downloaded and decrypted it to {}, which {} the original",
+            self.out,
+            if self.matches {
+                "matches"
+            } else {
+                "DOES NOT match"
+            }
+        )
+    }
+}
+
+/// Encrypts a file on the client with envelope encryption, uploads it to an Amazon S3 bucket,
+/// and then downloads and decrypts it again.
+/// AWS KMS generates a data key for the object, which encrypts the file with AES-256-GCM.
+/// The data key, encrypted under the KMS key, and the IV are stored in the object's metadata,
+/// so Amazon S3 only ever sees ciphertext.
+/// The metadata follows the V2 format of the Amazon S3 encryption clients in the other AWS SDKs.
+/// # Arguments
+///
+/// * `-b BUCKET` - The name of the bucket.
+/// * `-f FILE` - The file to encrypt and upload.
+/// * `[-k KEY]` - The name of the object.
+///   If not supplied, uses the name of the file.
+/// * `--kms-key-id KMS-KEY-ID` - The AWS KMS key that encrypts the data key.
+/// * `[-o OUT]` - The file to save the decrypted object in.
+///   If not supplied, uses the name of the file with **.decrypted** appended.
+/// * `[-d DEFAULT-REGION]` - The region containing the bucket and the KMS key.
+///   If not supplied, uses the value of the **AWS_DEFAULT_REGION** environment variable.
+///   If the environment variable is not set, defaults to **us-west-2**.
+/// * `[--output FORMAT]` - How to display the result, **text** (the default) or **json**.
+/// * `[--profile PROFILE]` - The AWS profile to use from the shared credentials file.
+/// * `[-v]` - Whether to display additional information.
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn Error>> {
+    let Opt {
+        bucket,
+        default_region,
+        file,
+        key,
+        kms_key_id,
+        out,
+        output,
+        profile,
+        verbose,
+    } = Opt::from_args();
+
+    let region = default_region
+        .as_ref()
+        .map(|region| Region::new(region.clone()))
+        .or_else(|| aws_types::region::default_provider().region())
+        .unwrap_or_else(|| Region::new("us-west-2"));
+
+    let path = Path::new(&file);
+
+    let key = key.unwrap_or_else(|| {
+        path.file_name()
+            .map(|name| name.to_string_lossy().into_owned())
+            .unwrap_or_else(|| file.clone())
+    });
+
+    let out = out.unwrap_or_else(|| format!("{}.decrypted", &file));
+
+    if verbose {
+        println!("S3 client version:  {}", s3::PKG_VERSION);
+        println!("KMS client version: {}", kms::PKG_VERSION);
+        println!("Region:             {:?}", &region);
+        println!("Bucket:             {}", &bucket);
+        println!("File:               {}", &file);
+        println!("Key:                {}", &key);
+        println!("KMS key:            {}", &kms_key_id);
+        println!("Decrypted file:     {}", &out);
+
+        SubscriberBuilder::default()
+            .with_env_filter("info")
+            .with_span_events(FmtSpan::CLOSE)
+            .init();
+    }
+
+    let mut s3_config = s3::Config::builder().region(&region);
+    let mut kms_config = kms::Config::builder().region(&region);
+    if let Some(profile) = profile {
+        s3_config = s3_config.credentials_provider(ProfileCredentialsProvider::new(&profile));
+        kms_config = kms_config.credentials_provider(ProfileCredentialsProvider::new(&profile));
+    }
+
+    // Sends the requests to LocalStack, or another local endpoint, if AWS_ENDPOINT_URL is set.
+    if let Some(uri) = endpoint::from_env()? {
+        s3_config = s3_config.endpoint_resolver(Endpoint::immutable(uri.clone()));
+        kms_config = kms_config.endpoint_resolver(kms::Endpoint::immutable(uri));
+    }
+
+    let s3_client = s3::Client::from_conf(s3_config.build());
+    let kms_client = kms::Client::from_conf(kms_config.build());
+
+    ensure_bucket(&s3_client, &bucket, &region, false).await?;
+
+    let plaintext = fs::read(path).map_err(|e| format!("couldn't read {}: {}", &file, e))?;
+
+    // The encryption context isn't secret, but KMS won't decrypt the data key without it,
+    // which ties the key to the algorithm it encrypts with.
+    let data_key = kms_client
+        .generate_data_key()
+        .key_id(&kms_key_id)
+        .key_spec(DataKeySpec::Aes256)
+        .set_encryption_context(Some(encryption_context()))
+        .send()
+        .await?;
+
+    let encrypted_key = data_key
+        .ciphertext_blob
+        .ok_or("KMS did not return the encrypted data key")?;
+    let sealed = {
+        let plaintext_key = data_key
+            .plaintext
+            .ok_or("KMS did not return the data key")?;
+        seal(plaintext_key.as_ref(), &plaintext)?
+    };
+
+    let encrypted_bytes = sealed.ciphertext.len();
+
+    s3_client
+        .put_object()
+        .bucket(&bucket)
+        .key(&key)
+        .body(ByteStream::from(sealed.ciphertext))
+        .content_type("application/octet-stream")
+        .set_metadata(Some(metadata(
+            encrypted_key.as_ref(),
+            &sealed.iv,
+            plaintext.len(),
+        )))
+        .send()
+        .await?;
+
+    if verbose {
+        println!("Uploaded {} encrypted bytes", encrypted_bytes);
+    }
+
+    // Anyone who can read the object gets only the ciphertext;
+    // decrypting it also takes permission to decrypt with the KMS key.
+    let resp = s3_client
+        .get_object()
+        .bucket(&bucket)
+        .key(&key)
+        .send()
+        .await?;
+
+    let envelope = Envelope::from_metadata(&resp.metadata.unwrap_or_default())?;
+    let ciphertext = resp.body.collect().await?.into_bytes();
+
+    let plaintext_key = kms_client
+        .decrypt()
+        .key_id(&kms_key_id)
+        .ciphertext_blob(Blob::new(envelope.encrypted_key))
+        .set_encryption_context(Some(envelope.encryption_context))
+        .send()
+        .await?
+        .plaintext
+        .ok_or("KMS did not return the data key")?;
+
+    let decrypted = open(plaintext_key.as_ref(), &envelope.iv, &ciphertext)?;
+
+    fs::write(&out, &decrypted).map_err(|e| format!("couldn't write {}: {}", &out, e))?;
+
+    let matches = decrypted == plaintext;
+
+    output.print(&RoundTrip {
+        bucket,
+        key,
+        kms_key_id,
+        bytes: plaintext.len(),
+        encrypted_bytes,
+        out,
+        matches,
+    });
+
+    if !matches {
+        return Err("the decrypted object doesn't match the file".into());
+    }
+
+    Ok(())
+}
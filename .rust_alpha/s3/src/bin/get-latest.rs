@@ -0,0 +1,276 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0.
+ */
+
+use std::error::Error;
+use std::fmt;
+use std::path::Path;
+use std::time::SystemTime;
+
+use s3::{Client, Config, Endpoint, Region};
+
+use aws_types::region::ProvideRegion;
+
+use common::endpoint;
+use common::output::OutputFormat;
+use common::profile::ProfileCredentialsProvider;
+use serde::Serialize;
+use smithy_types::instant::Format;
+use structopt::StructOpt;
+use tokio::fs::File;
+use tokio::io::AsyncWriteExt;
+use tokio_stream::StreamExt;
+use tracing_subscriber::fmt::format::FmtSpan;
+use tracing_subscriber::fmt::SubscriberBuilder;
+
+#[derive(Debug, StructOpt)]
+struct Opt {
+    /// The default region
+    #[structopt(short, long)]
+    default_region: Option<String>,
+
+    /// The name of the bucket
+    #[structopt(short, long)]
+    bucket: String,
+
+    /// Only consider the objects whose keys start with this prefix, such as logs/2021/
+    #[structopt(short, long)]
+    prefix: Option<String>,
+
+    /// The file to save the object in. Defaults to the last part of the key
+    #[structopt(short, long)]
+    file: Option<String>,
+
+    /// Whether to only display the latest object, without downloading it
+    #[structopt(long)]
+    dry_run: bool,
+
+    /// The format of the output, text or json
+    #[structopt(long, default_value = "text")]
+    output: OutputFormat,
+
+    /// The AWS profile to use from the shared credentials file
+    #[structopt(long)]
+    profile: Option<String>,
+
+    /// Whether to display additional information
+    #[structopt(short, long)]
+    verbose: bool,
+}
+
+/// The most recently modified object, as it was listed.
+#[derive(Debug)]
+struct Latest {
+    key: String,
+    e_tag: Option<String>,
+    size: i64,
+    modified: SystemTime,
+    last_modified: String,
+}
+
+/// The latest object, and where it was downloaded to.
+#[derive(Debug, Serialize)]
+struct LatestObject {
+    bucket: String,
+    key: String,
+    size: i64,
+    last_modified: String,
+    /// How many objects were compared.
+    objects: usize,
+    file: Option<String>,
+}
+
+impl fmt::Display for LatestObject {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "The latest of {} objects is {}, {} bytes, modified {}",
+            self.objects, self.key, self.size, self.last_modified
+        )?;
+
+        if let Some(file) = &self.file {
+            write!(f, "\nDownloaded it from bucket {} to {}", self.bucket, file)?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Finds the most recently modified object under a prefix in an Amazon S3 bucket, and downloads it,
+/// as when fetching the newest log file or backup.
+/// Amazon S3 lists keys in alphabetical order, not by date, so every page of the listing is read,
+/// keeping the newest object seen so far, rather than only the first page.
+/// Objects modified in the same second are ordered by key, so the same object is picked each time.
+/// Keys that end with / and are empty, which the console creates as folders, are skipped.
+/// The download only succeeds if the object hasn't been replaced since it was listed.
+/// # Arguments
+///
+/// * `-b BUCKET` - The name of the bucket.
+/// * `[-p PREFIX]` - Only consider the objects whose keys start with this prefix.
+///   If not supplied, considers every object in the bucket.
+/// * `[-f FILE]` - The file to save the object in.
+///   If not supplied, uses the last part of the key.
+/// * `[--dry-run]` - Whether to only display the latest object, without downloading it.
+/// * `[-d DEFAULT-REGION]` - The region containing the bucket.
+///   If not supplied, uses the value of the **AWS_DEFAULT_REGION** environment variable.
+///   If the environment variable is not set, defaults to **us-west-2**.
+/// * `[--output FORMAT]` - How to display the result, **text** (the default) or **json**.
+/// * `[--profile PROFILE]` - The AWS profile to use from the shared credentials file.
+/// * `[-v]` - Whether to display additional information.
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn Error>> {
+    let Opt {
+        bucket,
+        default_region,
+        dry_run,
+        file,
+        output,
+        prefix,
+        profile,
+        verbose,
+    } = Opt::from_args();
+
+    let region = default_region
+        .as_ref()
+        .map(|region| Region::new(region.clone()))
+        .or_else(|| aws_types::region::default_provider().region())
+        .unwrap_or_else(|| Region::new("us-west-2"));
+
+    if verbose {
+        println!("S3 client version: {}", s3::PKG_VERSION);
+        println!("Region:            {:?}", &region);
+        println!("Bucket:            {}", &bucket);
+        println!("Prefix:            {:?}", &prefix);
+        println!("File:              {:?}", &file);
+        println!("Dry run:           {}", dry_run);
+
+        SubscriberBuilder::default()
+            .with_env_filter("info")
+            .with_span_events(FmtSpan::CLOSE)
+            .init();
+    }
+
+    let mut config = Config::builder().region(&region);
+    if let Some(profile) = profile {
+        config = config.credentials_provider(ProfileCredentialsProvider::new(profile));
+    }
+
+    // Sends the requests to LocalStack, or another local endpoint, if AWS_ENDPOINT_URL is set.
+    if let Some(uri) = endpoint::from_env()? {
+        config = config.endpoint_resolver(Endpoint::immutable(uri));
+    }
+
+    let client = Client::from_conf(config.build());
+
+    let mut latest: Option<Latest> = None;
+    let mut objects = 0;
+    let mut token = None;
+    let mut pages = 0;
+
+    loop {
+        let resp = client
+            .list_objects_v2()
+            .bucket(&bucket)
+            .set_prefix(prefix.clone())
+            .set_continuation_token(token)
+            .send()
+            .await?;
+        pages += 1;
+
+        for object in resp.contents.unwrap_or_default() {
+            let key = object.key.unwrap_or_default();
+            if key.ends_with('/') && object.size == 0 {
+                continue;
+            }
+
+            let last_modified = match object.last_modified {
+                Some(last_modified) => last_modified,
+                None => continue,
+            };
+            let modified = match last_modified.to_system_time() {
+                Some(modified) => modified,
+                None => continue,
+            };
+
+            objects += 1;
+
+            let newer = match &latest {
+                Some(latest) => (modified, &key) > (latest.modified, &latest.key),
+                None => true,
+            };
+
+            if newer {
+                latest = Some(Latest {
+                    key,
+                    e_tag: object.e_tag,
+                    size: object.size,
+                    modified,
+                    last_modified: last_modified.fmt(Format::DateTime),
+                });
+            }
+        }
+
+        token = resp.next_continuation_token;
+        if token.is_none() {
+            break;
+        }
+    }
+
+    if verbose {
+        println!("Read {} pages", pages);
+    }
+
+    let latest = latest.ok_or_else(|| match &prefix {
+        Some(prefix) => format!("bucket {} has no objects under {}", &bucket, prefix),
+        None => format!("bucket {} has no objects", &bucket),
+    })?;
+
+    let file = if dry_run {
+        None
+    } else {
+        let file = file.unwrap_or_else(|| {
+            Path::new(&latest.key)
+                .file_name()
+                .map(|name| name.to_string_lossy().into_owned())
+                .unwrap_or_else(|| latest.key.clone())
+        });
+
+        // If the object was replaced after it was listed, there's a newer one; fail rather than download it.
+        let resp = client
+            .get_object()
+            .bucket(&bucket)
+            .key(&latest.key)
+            .set_if_match(latest.e_tag.clone())
+            .send()
+            .await
+            .map_err(|e| {
+                format!(
+                    "couldn't download {}, which might have changed since it was listed: {}",
+                    &latest.key, e
+                )
+            })?;
+
+        let mut out = File::create(&file).await?;
+        let mut body = resp.body;
+
+        while let Some(chunk) = body.next().await {
+            out.write_all(&chunk?).await?;
+        }
+
+        out.flush().await?;
+
+        Some(file)
+    };
+
+    output.print(&LatestObject {
+        bucket,
+        key: latest.key,
+        size: latest.size,
+        last_modified: latest.last_modified,
+        objects,
+        file,
+    });
+
+    Ok(())
+}
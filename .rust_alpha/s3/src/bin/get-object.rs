@@ -0,0 +1,207 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0.
+ */
+
+use std::error::Error;
+use std::fmt;
+use std::path::Path;
+
+use s3::{Client, Config, Endpoint, Region};
+
+use aws_types::region::ProvideRegion;
+
+use common::endpoint;
+use common::output::OutputFormat;
+use common::profile::ProfileCredentialsProvider;
+use indicatif::{ProgressBar, ProgressStyle};
+use serde::Serialize;
+use structopt::StructOpt;
+use tokio::fs::File;
+use tokio::io::AsyncWriteExt;
+use tokio_stream::StreamExt;
+use tracing_subscriber::fmt::format::FmtSpan;
+use tracing_subscriber::fmt::SubscriberBuilder;
+
+#[derive(Debug, StructOpt)]
+struct Opt {
+    /// The default region
+    #[structopt(short, long)]
+    default_region: Option<String>,
+
+    /// The name of the bucket
+    #[structopt(short, long)]
+    bucket: String,
+
+    /// The object to download
+    #[structopt(short, long)]
+    key: String,
+
+    /// The file to save the object in. Defaults to the last part of the key
+    #[structopt(short, long)]
+    file: Option<String>,
+
+    /// The byte range to download, such as 0-1023
+    #[structopt(long)]
+    range: Option<String>,
+
+    /// The format of the output, text or json
+    #[structopt(long, default_value = "text")]
+    output: OutputFormat,
+
+    /// The AWS profile to use from the shared credentials file
+    #[structopt(long)]
+    profile: Option<String>,
+
+    /// Whether to display additional information
+    #[structopt(short, long)]
+    verbose: bool,
+}
+
+/// The object that was downloaded.
+#[derive(Debug, Serialize)]
+struct DownloadedObject {
+    bucket: String,
+    key: String,
+    file: String,
+    range: Option<String>,
+    bytes: u64,
+}
+
+impl fmt::Display for DownloadedObject {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "Downloaded {} bytes of {} from bucket {} to {}",
+            self.bytes, self.key, self.bucket, self.file
+        )?;
+
+        if let Some(range) = &self.range {
+            write!(f, " ({})", range)?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Turns a range such as 0-1023 into the HTTP form, bytes=0-1023.
+fn http_range(range: &str) -> String {
+    if range.starts_with("bytes=") {
+        range.to_string()
+    } else {
+        format!("bytes={}", range)
+    }
+}
+
+/// Downloads an object from an Amazon S3 bucket to a local file.
+/// The object body is written to the file as it arrives, so large objects aren't held in memory.
+/// # Arguments
+///
+/// * `-b BUCKET` - The name of the bucket.
+/// * `-k KEY` - The name of the object.
+/// * `[-f FILE]` - The file to save the object in.
+///   If not supplied, uses the last part of the key.
+/// * `[--range RANGE]` - The byte range to download, such as **0-1023**.
+///   If not supplied, downloads the whole object.
+/// * `[-d DEFAULT-REGION]` - The region containing the bucket.
+///   If not supplied, uses the value of the **AWS_DEFAULT_REGION** environment variable.
+///   If the environment variable is not set, defaults to **us-west-2**.
+/// * `[--output FORMAT]` - How to display the result, **text** (the default) or **json**.
+/// * `[--profile PROFILE]` - The AWS profile to use from the shared credentials file.
+/// * `[-v]` - Whether to display additional information.
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn Error>> {
+    let Opt {
+        bucket,
+        default_region,
+        file,
+        key,
+        output,
+        profile,
+        range,
+        verbose,
+    } = Opt::from_args();
+
+    let region = default_region
+        .as_ref()
+        .map(|region| Region::new(region.clone()))
+        .or_else(|| aws_types::region::default_provider().region())
+        .unwrap_or_else(|| Region::new("us-west-2"));
+
+    let file = file.unwrap_or_else(|| {
+        Path::new(&key)
+            .file_name()
+            .map(|name| name.to_string_lossy().into_owned())
+            .unwrap_or_else(|| key.clone())
+    });
+
+    let range = range.as_deref().map(http_range);
+
+    if verbose {
+        println!("S3 client version: {}", s3::PKG_VERSION);
+        println!("Region:            {:?}", &region);
+        println!("Bucket:            {}", &bucket);
+        println!("Key:               {}", &key);
+        println!("File:              {}", &file);
+        println!("Range:             {:?}", &range);
+
+        SubscriberBuilder::default()
+            .with_env_filter("info")
+            .with_span_events(FmtSpan::CLOSE)
+            .init();
+    }
+
+    let mut config = Config::builder().region(&region);
+    if let Some(profile) = profile {
+        config = config.credentials_provider(ProfileCredentialsProvider::new(profile));
+    }
+
+    // Sends the requests to LocalStack, or another local endpoint, if AWS_ENDPOINT_URL is set.
+    if let Some(uri) = endpoint::from_env()? {
+        config = config.endpoint_resolver(Endpoint::immutable(uri));
+    }
+
+    let client = Client::from_conf(config.build());
+
+    let resp = client
+        .get_object()
+        .bucket(&bucket)
+        .key(&key)
+        .set_range(range.clone())
+        .send()
+        .await?;
+
+    // For a ranged GET, the content length is the length of the range.
+    let progress = ProgressBar::new(resp.content_length as u64);
+    progress.set_style(
+        ProgressStyle::default_bar()
+            .template("{bar:40} {bytes}/{total_bytes} ({bytes_per_sec}, {eta})"),
+    );
+
+    let mut out = File::create(&file).await?;
+
+    let mut body = resp.body;
+    let mut bytes: u64 = 0;
+
+    while let Some(chunk) = body.next().await {
+        let chunk = chunk?;
+        out.write_all(&chunk).await?;
+
+        bytes += chunk.len() as u64;
+        progress.set_position(bytes);
+    }
+
+    out.flush().await?;
+
+    progress.finish_and_clear();
+
+    output.print(&DownloadedObject {
+        bucket,
+        key,
+        file,
+        range,
+        bytes,
+    });
+
+    Ok(())
+}
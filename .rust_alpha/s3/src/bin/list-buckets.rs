@@ -3,12 +3,15 @@
  * SPDX-License-Identifier: Apache-2.0.
  */
 
-use std::process;
+use std::fmt;
 
-use s3::{Client, Config, Region};
+use s3::{Client, Config, Error, Region};
 
 use aws_types::region::ProvideRegion;
 
+use common::output::OutputFormat;
+use common::profile::ProfileCredentialsProvider;
+use serde::Serialize;
 use structopt::StructOpt;
 use tracing_subscriber::fmt::format::FmtSpan;
 use tracing_subscriber::fmt::SubscriberBuilder;
@@ -19,23 +22,52 @@ struct Opt {
     #[structopt(short, long)]
     default_region: Option<String>,
 
+    /// The format of the output, text or json
+    #[structopt(long, default_value = "text")]
+    output: OutputFormat,
+
+    /// The AWS profile to use from the shared credentials file
+    #[structopt(long)]
+    profile: Option<String>,
+
     /// Whether to display additional information
     #[structopt(short, long)]
     verbose: bool,
 }
 
+/// The names of your buckets.
+#[derive(Debug, Serialize)]
+struct BucketList {
+    buckets: Vec<String>,
+}
+
+impl fmt::Display for BucketList {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "\nBuckets:\n")?;
+
+        for bucket in &self.buckets {
+            writeln!(f, "{}", bucket)?;
+        }
+
+        write!(f, "\nFound {} buckets globally", self.buckets.len())
+    }
+}
+
 /// Lists your Amazon S3 buckets
 /// # Arguments
 ///
 /// * `[-d DEFAULT-REGION]` - The region containing the buckets.
 ///   If not supplied, uses the value of the **AWS_DEFAULT_REGION** environment variable.
 ///   If the environment variable is not set, defaults to **us-west-2**.
-/// * `[-g]` - Whether to display buckets in all regions.
+/// * `[--output FORMAT]` - How to display the result, **text** (the default) or **json**.
+/// * `[--profile PROFILE]` - The AWS profile to use from the shared credentials file.
 /// * `[-v]` - Whether to display additional information.
 #[tokio::main]
-async fn main() {
+async fn main() -> Result<(), Error> {
     let Opt {
         default_region,
+        output,
+        profile,
         verbose,
     } = Opt::from_args();
 
@@ -55,34 +87,23 @@ async fn main() {
             .init();
     }
 
-    let config = Config::builder().region(&region).build();
-
-    let client = Client::from_conf(config);
+    let mut config = Config::builder().region(&region);
+    if let Some(profile) = profile {
+        config = config.credentials_provider(ProfileCredentialsProvider::new(profile));
+    }
 
-    let mut num_buckets = 0;
+    let client = Client::from_conf(config.build());
 
-    match client.list_buckets().send().await {
-        Ok(resp) => {
-            println!("\nBuckets:\n");
+    let resp = client.list_buckets().send().await?;
 
-            let buckets = resp.buckets.unwrap_or_default();
+    let buckets = resp
+        .buckets
+        .unwrap_or_default()
+        .into_iter()
+        .filter_map(|bucket| bucket.name)
+        .collect();
 
-            for bucket in &buckets {
-                match &bucket.name {
-                    None => {}
-                    Some(b) => {
-                        println!("{}", b);
-                        num_buckets += 1;
-                    }
-                }
-            }
+    output.print(&BucketList { buckets });
 
-            println!("\nFound {} buckets globally", num_buckets);
-        }
-        Err(e) => {
-            println!("Got an error listing buckets:");
-            println!("{}", e);
-            process::exit(1);
-        }
-    };
+    Ok(())
 }
@@ -0,0 +1,211 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0.
+ */
+
+use std::error::Error;
+use std::fmt;
+
+use s3::{Client, Config, Endpoint, Region};
+use s3_code_examples::copy::copy_source_version;
+use s3_code_examples::versions::{list_versions, Version};
+
+use aws_types::region::ProvideRegion;
+
+use common::endpoint;
+use common::output::OutputFormat;
+use common::profile::ProfileCredentialsProvider;
+use serde::Serialize;
+use structopt::StructOpt;
+use tracing_subscriber::fmt::format::FmtSpan;
+use tracing_subscriber::fmt::SubscriberBuilder;
+
+#[derive(Debug, StructOpt)]
+struct Opt {
+    /// The default region
+    #[structopt(short, long)]
+    default_region: Option<String>,
+
+    /// The name of the bucket
+    #[structopt(short, long)]
+    bucket: String,
+
+    /// The name of the object
+    #[structopt(short, long)]
+    key: String,
+
+    /// The ID of a version to restore, by copying it over the current version
+    #[structopt(long)]
+    restore: Option<String>,
+
+    /// The format of the output, text or json
+    #[structopt(long, default_value = "text")]
+    output: OutputFormat,
+
+    /// The AWS profile to use from the shared credentials file
+    #[structopt(long)]
+    profile: Option<String>,
+
+    /// Whether to display additional information
+    #[structopt(short, long)]
+    verbose: bool,
+}
+
+/// The versions of an object, newest first, and the version that was restored, if any.
+#[derive(Debug, Serialize)]
+struct ObjectVersions {
+    bucket: String,
+    key: String,
+    versions: Vec<Version>,
+    /// The ID of the version that was copied over the current version.
+    restored: Option<String>,
+    /// The ID of the new version that the copy created.
+    new_version_id: Option<String>,
+}
+
+impl fmt::Display for ObjectVersions {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} versions of {} in bucket {}",
+            self.versions.len(),
+            self.key,
+            self.bucket
+        )?;
+
+        for version in &self.versions {
+            write!(
+                f,
+                "\n{}  {:<32}  {:>12}{}",
+                version.last_modified,
+                version.version_id,
+                if version.delete_marker {
+                    "DELETE MARKER".to_string()
+                } else {
+                    version.size.to_string()
+                },
+                if version.is_latest { "  (latest)" } else { "" }
+            )?;
+        }
+
+        if let (Some(restored), Some(new_version_id)) = (&self.restored, &self.new_version_id) {
+            write!(
+                f,
+                "\nRestored version {} as new version {}",
+                restored, new_version_id
+            )?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Lists the versions of a key. ListObjectVersions lists every key that starts with the prefix,
+/// so the other keys are skipped.
+async fn key_versions(client: &Client, bucket: &str, key: &str) -> Result<Vec<Version>, s3::Error> {
+    let mut versions = list_versions(client, bucket, key).await?;
+    versions.retain(|version| version.key == key);
+    Ok(versions)
+}
+
+/// Lists the versions and delete markers of an object in an Amazon S3 bucket that has versioning enabled,
+/// and can restore an earlier version by copying it over the current one.
+/// The copy becomes the latest version, so the versions in between are kept, and the restore can be undone.
+/// Restoring also undeletes an object whose latest version is a delete marker.
+/// # Arguments
+///
+/// * `-b BUCKET` - The name of the bucket.
+/// * `-k KEY` - The name of the object.
+/// * `[--restore VERSION-ID]` - The ID of the version to restore.
+/// * `[-d DEFAULT-REGION]` - The region containing the bucket.
+///   If not supplied, uses the value of the **AWS_DEFAULT_REGION** environment variable.
+///   If the environment variable is not set, defaults to **us-west-2**.
+/// * `[--output FORMAT]` - How to display the result, **text** (the default) or **json**.
+/// * `[--profile PROFILE]` - The AWS profile to use from the shared credentials file.
+/// * `[-v]` - Whether to display additional information.
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn Error>> {
+    let Opt {
+        bucket,
+        default_region,
+        key,
+        output,
+        profile,
+        restore,
+        verbose,
+    } = Opt::from_args();
+
+    let region = default_region
+        .as_ref()
+        .map(|region| Region::new(region.clone()))
+        .or_else(|| aws_types::region::default_provider().region())
+        .unwrap_or_else(|| Region::new("us-west-2"));
+
+    if verbose {
+        println!("S3 client version: {}", s3::PKG_VERSION);
+        println!("Region:            {:?}", &region);
+        println!("Bucket:            {}", &bucket);
+        println!("Key:               {}", &key);
+        println!("Restore:           {:?}", &restore);
+
+        SubscriberBuilder::default()
+            .with_env_filter("info")
+            .with_span_events(FmtSpan::CLOSE)
+            .init();
+    }
+
+    let mut config = Config::builder().region(&region);
+    if let Some(profile) = profile {
+        config = config.credentials_provider(ProfileCredentialsProvider::new(profile));
+    }
+
+    // Sends the requests to LocalStack, or another local endpoint, if AWS_ENDPOINT_URL is set.
+    if let Some(uri) = endpoint::from_env()? {
+        config = config.endpoint_resolver(Endpoint::immutable(uri));
+    }
+
+    let client = Client::from_conf(config.build());
+
+    let mut versions = key_versions(&client, &bucket, &key).await?;
+    let mut new_version_id = None;
+
+    if let Some(version_id) = &restore {
+        match versions
+            .iter()
+            .find(|version| &version.version_id == version_id)
+        {
+            None => {
+                return Err(format!("{} has no version {}", &key, version_id).into());
+            }
+            Some(version) if version.delete_marker => {
+                return Err(format!("version {} is a delete marker", version_id).into());
+            }
+            Some(version) if version.is_latest => {
+                return Err(format!("version {} is already the latest", version_id).into());
+            }
+            Some(_) => {}
+        }
+
+        let resp = client
+            .copy_object()
+            .bucket(&bucket)
+            .key(&key)
+            .copy_source(copy_source_version(&bucket, &key, version_id))
+            .send()
+            .await?;
+        new_version_id = resp.version_id;
+
+        // Lists the versions again, so that the new one is shown.
+        versions = key_versions(&client, &bucket, &key).await?;
+    }
+
+    output.print(&ObjectVersions {
+        bucket,
+        key,
+        versions,
+        restored: restore,
+        new_version_id,
+    });
+
+    Ok(())
+}
@@ -3,12 +3,18 @@
  * SPDX-License-Identifier: Apache-2.0.
  */
 
-use std::process;
+use std::error::Error;
+use std::fmt;
 
-use s3::{Client, Config, Region};
+use s3::{Client, Config, Endpoint, Region};
 
 use aws_types::region::ProvideRegion;
 
+use common::endpoint;
+use common::output::OutputFormat;
+use common::profile::ProfileCredentialsProvider;
+use serde::Serialize;
+use smithy_types::instant::Format;
 use structopt::StructOpt;
 use tracing_subscriber::fmt::format::FmtSpan;
 use tracing_subscriber::fmt::SubscriberBuilder;
@@ -23,24 +29,131 @@ struct Opt {
     #[structopt(short, long)]
     bucket: String,
 
+    /// Only list the objects whose keys start with this prefix
+    #[structopt(short, long)]
+    prefix: Option<String>,
+
+    /// Group the keys that contain this delimiter after the prefix, such as /
+    #[structopt(long)]
+    delimiter: Option<String>,
+
+    /// The maximum number of keys that each request returns
+    #[structopt(short, long)]
+    max_keys: Option<i32>,
+
+    /// The format of the output, text or json
+    #[structopt(long, default_value = "text")]
+    output: OutputFormat,
+
+    /// The AWS profile to use from the shared credentials file
+    #[structopt(long)]
+    profile: Option<String>,
+
     /// Whether to display additional information
     #[structopt(short, long)]
     verbose: bool,
 }
 
-/// Lists the objects in an Amazon S3 bucket.
+/// An object in a bucket.
+#[derive(Debug, Serialize)]
+struct Object {
+    key: String,
+    size: i64,
+    storage_class: String,
+    last_modified: String,
+}
+
+/// The objects in a bucket, and the common prefixes that group the rest of the keys.
+#[derive(Debug, Serialize)]
+struct ObjectList {
+    bucket: String,
+    prefixes: Vec<String>,
+    objects: Vec<Object>,
+}
+
+impl fmt::Display for ObjectList {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let size_width = self
+            .objects
+            .iter()
+            .map(|object| object.size.to_string().len())
+            .max()
+            .unwrap_or_default();
+        let class_width = self
+            .objects
+            .iter()
+            .map(|object| object.storage_class.len())
+            .max()
+            .unwrap_or_default();
+        let date_width = self
+            .objects
+            .iter()
+            .map(|object| object.last_modified.len())
+            .max()
+            .unwrap_or_default();
+
+        write!(
+            f,
+            "{} objects and {} prefixes in {}",
+            self.objects.len(),
+            self.prefixes.len(),
+            self.bucket
+        )?;
+
+        // Like the AWS CLI, show the prefixes as directories before the objects.
+        for prefix in &self.prefixes {
+            write!(
+                f,
+                "\n{:>width$}  {}",
+                "PRE",
+                prefix,
+                width = date_width + size_width + class_width + 4
+            )?;
+        }
+
+        for object in &self.objects {
+            write!(
+                f,
+                "\n{:<date_width$}  {:>size_width$}  {:<class_width$}  {}",
+                object.last_modified,
+                object.size,
+                object.storage_class,
+                object.key,
+                date_width = date_width,
+                size_width = size_width,
+                class_width = class_width
+            )?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Lists the objects in an Amazon S3 bucket, following continuation tokens until every page is read.
 /// # Arguments
 ///
-/// * `-n NAME` - The name of the bucket.
+/// * `-b BUCKET` - The name of the bucket.
+/// * `[-p PREFIX]` - Only list the objects whose keys start with this prefix.
+/// * `[--delimiter DELIMITER]` - Group the keys that contain the delimiter after the prefix
+///   into common prefixes, instead of listing them.
+/// * `[-m MAX-KEYS]` - The maximum number of keys that each request returns.
+///   If not supplied, Amazon S3 returns up to 1000 keys per request.
 /// * `[-d DEFAULT-REGION]` - The region containing the bucket.
 ///   If not supplied, uses the value of the **AWS_DEFAULT_REGION** environment variable.
 ///   If the environment variable is not set, defaults to **us-west-2**.
+/// * `[--output FORMAT]` - How to display the result, **text** (the default) or **json**.
+/// * `[--profile PROFILE]` - The AWS profile to use from the shared credentials file.
 /// * `[-v]` - Whether to display additional information.
 #[tokio::main]
-async fn main() {
+async fn main() -> Result<(), Box<dyn Error>> {
     let Opt {
         default_region,
         bucket,
+        delimiter,
+        max_keys,
+        output,
+        prefix,
+        profile,
         verbose,
     } = Opt::from_args();
 
@@ -53,6 +166,10 @@ async fn main() {
     if verbose {
         println!("S3 client version: {}", s3::PKG_VERSION);
         println!("Region:            {:?}", &region);
+        println!("Bucket:            {}", &bucket);
+        println!("Prefix:            {:?}", &prefix);
+        println!("Delimiter:         {:?}", &delimiter);
+        println!("Max keys:          {:?}", &max_keys);
 
         SubscriberBuilder::default()
             .with_env_filter("info")
@@ -60,21 +177,73 @@ async fn main() {
             .init();
     }
 
-    let config = Config::builder().region(&region).build();
+    let mut config = Config::builder().region(&region);
+    if let Some(profile) = profile {
+        config = config.credentials_provider(ProfileCredentialsProvider::new(profile));
+    }
+
+    // Sends the requests to LocalStack, or another local endpoint, if AWS_ENDPOINT_URL is set.
+    if let Some(uri) = endpoint::from_env()? {
+        config = config.endpoint_resolver(Endpoint::immutable(uri));
+    }
+
+    let client = Client::from_conf(config.build());
+
+    let mut prefixes = Vec::new();
+    let mut objects = Vec::new();
+    let mut token = None;
+    let mut pages = 0;
 
-    let client = Client::from_conf(config);
+    loop {
+        let resp = client
+            .list_objects_v2()
+            .bucket(&bucket)
+            .set_prefix(prefix.clone())
+            .set_delimiter(delimiter.clone())
+            .set_max_keys(max_keys)
+            .set_continuation_token(token)
+            .send()
+            .await?;
+        pages += 1;
 
-    match client.list_objects().bucket(&bucket).send().await {
-        Ok(resp) => {
-            println!("Objects:");
-            for object in resp.contents.unwrap_or_default() {
-                println!(" {}", object.key.expect("objects have keys"));
-            }
+        prefixes.extend(
+            resp.common_prefixes
+                .unwrap_or_default()
+                .into_iter()
+                .filter_map(|common_prefix| common_prefix.prefix),
+        );
+
+        for object in resp.contents.unwrap_or_default() {
+            objects.push(Object {
+                key: object.key.unwrap_or_default(),
+                size: object.size,
+                storage_class: object
+                    .storage_class
+                    .as_ref()
+                    .map(|class| class.as_str().to_string())
+                    .unwrap_or_default(),
+                last_modified: object
+                    .last_modified
+                    .map(|time| time.fmt(Format::DateTime))
+                    .unwrap_or_default(),
+            });
         }
-        Err(e) => {
-            println!("Got an error retrieving objects for bucket:");
-            println!("{}", e);
-            process::exit(1);
+
+        token = resp.next_continuation_token;
+        if token.is_none() {
+            break;
         }
     }
+
+    if verbose {
+        println!("Read {} pages", pages);
+    }
+
+    output.print(&ObjectList {
+        bucket,
+        prefixes,
+        objects,
+    });
+
+    Ok(())
 }
@@ -0,0 +1,228 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0.
+ */
+
+use std::error::Error;
+use std::fmt;
+
+use s3::model::{
+    ServerSideEncryption, ServerSideEncryptionByDefault, ServerSideEncryptionConfiguration,
+    ServerSideEncryptionRule,
+};
+use s3::{Client, Config, Endpoint, Region};
+use s3_code_examples::buckets::ensure_bucket;
+
+use aws_types::region::ProvideRegion;
+
+use common::endpoint;
+use common::output::OutputFormat;
+use common::profile::ProfileCredentialsProvider;
+use serde::Serialize;
+use structopt::StructOpt;
+use tracing_subscriber::fmt::format::FmtSpan;
+use tracing_subscriber::fmt::SubscriberBuilder;
+
+#[derive(Debug, StructOpt)]
+struct Opt {
+    /// The default region
+    #[structopt(short, long)]
+    default_region: Option<String>,
+
+    /// The name of the bucket
+    #[structopt(short, long)]
+    bucket: String,
+
+    /// The default encryption, aes256 or aws:kms
+    #[structopt(long, default_value = "aes256", parse(try_from_str = parse_sse))]
+    sse: ServerSideEncryption,
+
+    /// The ID or ARN of the AWS KMS key to encrypt objects with. Defaults to the AWS managed key for Amazon S3
+    #[structopt(long)]
+    kms_key_id: Option<String>,
+
+    /// Whether to use an S3 Bucket Key, which cuts the number of requests to AWS KMS
+    #[structopt(long)]
+    bucket_key: bool,
+
+    /// The format of the output, text or json
+    #[structopt(long, default_value = "text")]
+    output: OutputFormat,
+
+    /// The AWS profile to use from the shared credentials file
+    #[structopt(long)]
+    profile: Option<String>,
+
+    /// Whether to display additional information
+    #[structopt(short, long)]
+    verbose: bool,
+}
+
+/// A default encryption rule of a bucket.
+#[derive(Debug, Serialize)]
+struct EncryptionRule {
+    sse_algorithm: String,
+    kms_key_id: Option<String>,
+    bucket_key_enabled: bool,
+}
+
+/// The default encryption of the bucket, as GetBucketEncryption returns it.
+#[derive(Debug, Serialize)]
+struct BucketEncryption {
+    bucket: String,
+    rules: Vec<EncryptionRule>,
+}
+
+impl fmt::Display for BucketEncryption {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Default encryption of bucket {}:", self.bucket)?;
+
+        for rule in &self.rules {
+            write!(f, "\n  {}", rule.sse_algorithm)?;
+
+            if let Some(kms_key_id) = &rule.kms_key_id {
+                write!(f, " with key {}", kms_key_id)?;
+            }
+
+            if rule.bucket_key_enabled {
+                write!(f, ", using an S3 Bucket Key")?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Parses the server-side encryption. Amazon S3 calls SSE-S3 AES256 and SSE-KMS aws:kms.
+fn parse_sse(s: &str) -> Result<ServerSideEncryption, String> {
+    match s.to_ascii_lowercase().as_str() {
+        "aes256" => Ok(ServerSideEncryption::Aes256),
+        "aws:kms" => Ok(ServerSideEncryption::AwsKms),
+        _ => Err(format!(
+            "the encryption must be aes256 or aws:kms, got {}",
+            s
+        )),
+    }
+}
+
+/// Sets the default encryption of an Amazon S3 bucket, which encrypts each new object that's uploaded
+/// without its own encryption settings, then reads it back to check it.
+/// Objects that are already in the bucket keep the encryption they have.
+/// # Arguments
+///
+/// * `-b BUCKET` - The name of the bucket.
+/// * `[--sse ENCRYPTION]` - The default encryption, **aes256** for SSE-S3 or **aws:kms** for SSE-KMS.
+///   If not supplied, defaults to **aes256**.
+/// * `[--kms-key-id KMS-KEY-ID]` - The AWS KMS key to encrypt objects with, for **aws:kms**.
+///   If not supplied, uses the AWS managed key for Amazon S3.
+/// * `[--bucket-key]` - Whether to use an S3 Bucket Key for **aws:kms**, which cuts the cost of AWS KMS requests.
+/// * `[-d DEFAULT-REGION]` - The region containing the bucket.
+///   If not supplied, uses the value of the **AWS_DEFAULT_REGION** environment variable.
+///   If the environment variable is not set, defaults to **us-west-2**.
+/// * `[--output FORMAT]` - How to display the result, **text** (the default) or **json**.
+/// * `[--profile PROFILE]` - The AWS profile to use from the shared credentials file.
+/// * `[-v]` - Whether to display additional information.
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn Error>> {
+    let Opt {
+        bucket,
+        bucket_key,
+        default_region,
+        kms_key_id,
+        output,
+        profile,
+        sse,
+        verbose,
+    } = Opt::from_args();
+
+    if sse != ServerSideEncryption::AwsKms && (kms_key_id.is_some() || bucket_key) {
+        return Err("--kms-key-id and --bucket-key need --sse aws:kms".into());
+    }
+
+    let region = default_region
+        .as_ref()
+        .map(|region| Region::new(region.clone()))
+        .or_else(|| aws_types::region::default_provider().region())
+        .unwrap_or_else(|| Region::new("us-west-2"));
+
+    if verbose {
+        println!("S3 client version: {}", s3::PKG_VERSION);
+        println!("Region:            {:?}", &region);
+        println!("Bucket:            {}", &bucket);
+        println!("Encryption:        {}", sse.as_str());
+        println!("KMS key:           {:?}", &kms_key_id);
+        println!("Bucket key:        {}", bucket_key);
+
+        SubscriberBuilder::default()
+            .with_env_filter("info")
+            .with_span_events(FmtSpan::CLOSE)
+            .init();
+    }
+
+    let mut config = Config::builder().region(&region);
+    if let Some(profile) = profile {
+        config = config.credentials_provider(ProfileCredentialsProvider::new(profile));
+    }
+
+    // Sends the requests to LocalStack, or another local endpoint, if AWS_ENDPOINT_URL is set.
+    if let Some(uri) = endpoint::from_env()? {
+        config = config.endpoint_resolver(Endpoint::immutable(uri));
+    }
+
+    let client = Client::from_conf(config.build());
+
+    ensure_bucket(&client, &bucket, &region, false).await?;
+
+    let rule = ServerSideEncryptionRule::builder()
+        .apply_server_side_encryption_by_default(
+            ServerSideEncryptionByDefault::builder()
+                .sse_algorithm(sse)
+                .set_kms_master_key_id(kms_key_id)
+                .build(),
+        )
+        .bucket_key_enabled(bucket_key)
+        .build();
+
+    client
+        .put_bucket_encryption()
+        .bucket(&bucket)
+        .server_side_encryption_configuration(
+            ServerSideEncryptionConfiguration::builder()
+                .rules(rule)
+                .build(),
+        )
+        .send()
+        .await?;
+
+    // Reads the configuration back, as Amazon S3 stores it.
+    let rules = client
+        .get_bucket_encryption()
+        .bucket(&bucket)
+        .send()
+        .await?
+        .server_side_encryption_configuration
+        .and_then(|configuration| configuration.rules)
+        .unwrap_or_default();
+
+    let rules = rules
+        .into_iter()
+        .filter_map(|rule| {
+            let bucket_key_enabled = rule.bucket_key_enabled;
+
+            rule.apply_server_side_encryption_by_default
+                .map(|default| EncryptionRule {
+                    sse_algorithm: default
+                        .sse_algorithm
+                        .as_ref()
+                        .map(|sse| sse.as_str().to_string())
+                        .unwrap_or_default(),
+                    kms_key_id: default.kms_master_key_id,
+                    bucket_key_enabled,
+                })
+        })
+        .collect();
+
+    output.print(&BucketEncryption { bucket, rules });
+
+    Ok(())
+}
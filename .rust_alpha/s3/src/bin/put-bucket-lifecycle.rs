@@ -0,0 +1,289 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0.
+ */
+
+use std::error::Error;
+use std::fmt;
+
+use s3::model::{
+    AbortIncompleteMultipartUpload, BucketLifecycleConfiguration, ExpirationStatus,
+    LifecycleExpiration, LifecycleRule, LifecycleRuleFilter, Transition, TransitionStorageClass,
+};
+use s3::{Client, Config, Endpoint, Region};
+use s3_code_examples::buckets::ensure_bucket;
+use s3_code_examples::lifecycle::{get_rules, RuleSummary};
+
+use aws_types::region::ProvideRegion;
+
+use common::endpoint;
+use common::output::OutputFormat;
+use common::profile::ProfileCredentialsProvider;
+use serde::Serialize;
+use structopt::StructOpt;
+use tracing_subscriber::fmt::format::FmtSpan;
+use tracing_subscriber::fmt::SubscriberBuilder;
+
+/// How many days objects must be stored before they can move to S3 Standard-IA,
+/// and how long they must stay there before they can move to S3 Glacier.
+const MIN_IA_DAYS: i32 = 30;
+
+#[derive(Debug, StructOpt)]
+struct Opt {
+    /// The default region
+    #[structopt(short, long)]
+    default_region: Option<String>,
+
+    /// The name of the bucket
+    #[structopt(short, long)]
+    bucket: String,
+
+    /// The ID of the rule. A rule with the same ID is replaced
+    #[structopt(short, long, default_value = "rust-example")]
+    id: String,
+
+    /// The prefix of the objects the rule applies to. Defaults to all objects
+    #[structopt(short, long, default_value = "")]
+    prefix: String,
+
+    /// How many days after they're created to move objects to S3 Standard-IA
+    #[structopt(long)]
+    ia_days: Option<i32>,
+
+    /// How many days after they're created to move objects to S3 Glacier
+    #[structopt(long)]
+    glacier_days: Option<i32>,
+
+    /// How many days after they're created to delete objects
+    #[structopt(long)]
+    expire_days: Option<i32>,
+
+    /// How many days after they're started to stop incomplete multipart uploads
+    #[structopt(long)]
+    abort_days: Option<i32>,
+
+    /// Whether to add the rule disabled
+    #[structopt(long)]
+    disabled: bool,
+
+    /// The format of the output, text or json
+    #[structopt(long, default_value = "text")]
+    output: OutputFormat,
+
+    /// The AWS profile to use from the shared credentials file
+    #[structopt(long)]
+    profile: Option<String>,
+
+    /// Whether to display additional information
+    #[structopt(short, long)]
+    verbose: bool,
+}
+
+/// The rule that was put, and how many other rules the bucket has.
+#[derive(Debug, Serialize)]
+struct PutLifecycleRule {
+    bucket: String,
+    rule: RuleSummary,
+    /// Whether a rule with the same ID was replaced.
+    replaced: bool,
+    other_rules: usize,
+}
+
+impl fmt::Display for PutLifecycleRule {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} lifecycle rule on bucket {}, which has {} other rules:\n{}",
+            if self.replaced { "Replaced" } else { "Added" },
+            self.bucket,
+            self.other_rules,
+            self.rule
+        )
+    }
+}
+
+/// Checks that the days make sense together, as Amazon S3 does, so that mistakes are reported clearly.
+fn validate(opt: &Opt) -> Result<(), String> {
+    let days = [
+        ("--ia-days", opt.ia_days),
+        ("--glacier-days", opt.glacier_days),
+        ("--expire-days", opt.expire_days),
+        ("--abort-days", opt.abort_days),
+    ];
+
+    if days.iter().all(|(_, days)| days.is_none()) {
+        return Err(
+            "the rule needs at least one of --ia-days, --glacier-days, --expire-days, or --abort-days"
+                .to_string(),
+        );
+    }
+
+    for (flag, days) in &days {
+        if let Some(days) = days {
+            if *days < 1 {
+                return Err(format!("{} must be at least 1", flag));
+            }
+        }
+    }
+
+    if let Some(ia) = opt.ia_days {
+        if ia < MIN_IA_DAYS {
+            return Err(format!("--ia-days must be at least {}", MIN_IA_DAYS));
+        }
+
+        if let Some(glacier) = opt.glacier_days {
+            if glacier < ia + MIN_IA_DAYS {
+                return Err(format!(
+                    "--glacier-days must be at least {} days after --ia-days",
+                    MIN_IA_DAYS
+                ));
+            }
+        }
+    }
+
+    if let Some(expire) = opt.expire_days {
+        let last_transition = opt.glacier_days.or(opt.ia_days).unwrap_or_default();
+
+        if expire <= last_transition {
+            return Err("--expire-days must be after the transitions".to_string());
+        }
+    }
+
+    Ok(())
+}
+
+/// Builds the rule from the options.
+fn build_rule(opt: &Opt) -> LifecycleRule {
+    let status = if opt.disabled {
+        ExpirationStatus::Disabled
+    } else {
+        ExpirationStatus::Enabled
+    };
+
+    let mut rule = LifecycleRule::builder()
+        .id(&opt.id)
+        .filter(LifecycleRuleFilter::Prefix(opt.prefix.clone()))
+        .status(status);
+
+    for (days, storage_class) in &[
+        (opt.ia_days, TransitionStorageClass::StandardIa),
+        (opt.glacier_days, TransitionStorageClass::Glacier),
+    ] {
+        if let Some(days) = days {
+            rule = rule.transitions(
+                Transition::builder()
+                    .days(*days)
+                    .storage_class(storage_class.clone())
+                    .build(),
+            );
+        }
+    }
+
+    if let Some(days) = opt.expire_days {
+        rule = rule.expiration(LifecycleExpiration::builder().days(days).build());
+    }
+
+    if let Some(days) = opt.abort_days {
+        rule = rule.abort_incomplete_multipart_upload(
+            AbortIncompleteMultipartUpload::builder()
+                .days_after_initiation(days)
+                .build(),
+        );
+    }
+
+    rule.build()
+}
+
+/// Adds a lifecycle rule to an Amazon S3 bucket, or replaces the rule with the same ID,
+/// and keeps the bucket's other rules.
+/// The rule can move objects to S3 Standard-IA and S3 Glacier, delete them, and stop incomplete multipart uploads,
+/// whose parts are otherwise stored, and billed, until they're deleted.
+/// # Arguments
+///
+/// * `-b BUCKET` - The name of the bucket.
+/// * `[-i ID]` - The ID of the rule. A rule with the same ID is replaced.
+///   If not supplied, defaults to **rust-example**.
+/// * `[-p PREFIX]` - The prefix of the objects the rule applies to.
+///   If not supplied, the rule applies to all objects.
+/// * `[--ia-days DAYS]` - How many days after they're created to move objects to S3 Standard-IA. At least 30.
+/// * `[--glacier-days DAYS]` - How many days after they're created to move objects to S3 Glacier.
+/// * `[--expire-days DAYS]` - How many days after they're created to delete objects.
+/// * `[--abort-days DAYS]` - How many days after they're started to stop incomplete multipart uploads.
+/// * `[--disabled]` - Whether to add the rule disabled.
+/// * `[-d DEFAULT-REGION]` - The region containing the bucket.
+///   If not supplied, uses the value of the **AWS_DEFAULT_REGION** environment variable.
+///   If the environment variable is not set, defaults to **us-west-2**.
+/// * `[--output FORMAT]` - How to display the result, **text** (the default) or **json**.
+/// * `[--profile PROFILE]` - The AWS profile to use from the shared credentials file.
+/// * `[-v]` - Whether to display additional information.
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn Error>> {
+    let opt = Opt::from_args();
+
+    validate(&opt)?;
+
+    let region = opt
+        .default_region
+        .as_ref()
+        .map(|region| Region::new(region.clone()))
+        .or_else(|| aws_types::region::default_provider().region())
+        .unwrap_or_else(|| Region::new("us-west-2"));
+
+    if opt.verbose {
+        println!("S3 client version: {}", s3::PKG_VERSION);
+        println!("Region:            {:?}", &region);
+        println!("Bucket:            {}", &opt.bucket);
+        println!("Rule ID:           {}", &opt.id);
+        println!("Prefix:            {:?}", &opt.prefix);
+
+        SubscriberBuilder::default()
+            .with_env_filter("info")
+            .with_span_events(FmtSpan::CLOSE)
+            .init();
+    }
+
+    let mut config = Config::builder().region(&region);
+    if let Some(profile) = &opt.profile {
+        config = config.credentials_provider(ProfileCredentialsProvider::new(profile));
+    }
+
+    // Sends the requests to LocalStack, or another local endpoint, if AWS_ENDPOINT_URL is set.
+    if let Some(uri) = endpoint::from_env()? {
+        config = config.endpoint_resolver(Endpoint::immutable(uri));
+    }
+
+    let client = Client::from_conf(config.build());
+
+    ensure_bucket(&client, &opt.bucket, &region, false).await?;
+
+    // PutBucketLifecycleConfiguration replaces all of the rules, so the others are put back.
+    let mut rules = get_rules(&client, &opt.bucket).await?;
+    let before = rules.len();
+    rules.retain(|rule| rule.id.as_deref() != Some(opt.id.as_str()));
+    let replaced = rules.len() < before;
+    let other_rules = rules.len();
+
+    let rule = build_rule(&opt);
+    let summary = RuleSummary::from(&rule);
+    rules.push(rule);
+
+    client
+        .put_bucket_lifecycle_configuration()
+        .bucket(&opt.bucket)
+        .lifecycle_configuration(
+            BucketLifecycleConfiguration::builder()
+                .set_rules(Some(rules))
+                .build(),
+        )
+        .send()
+        .await?;
+
+    opt.output.print(&PutLifecycleRule {
+        bucket: opt.bucket.clone(),
+        rule: summary,
+        replaced,
+        other_rules,
+    });
+
+    Ok(())
+}
@@ -0,0 +1,174 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0.
+ */
+
+use std::error::Error;
+use std::fmt;
+
+use s3::model::{BucketVersioningStatus, VersioningConfiguration};
+use s3::{Client, Config, Endpoint, Region};
+use s3_code_examples::buckets::ensure_bucket;
+
+use aws_types::region::ProvideRegion;
+
+use common::endpoint;
+use common::output::OutputFormat;
+use common::profile::ProfileCredentialsProvider;
+use serde::Serialize;
+use structopt::StructOpt;
+use tracing_subscriber::fmt::format::FmtSpan;
+use tracing_subscriber::fmt::SubscriberBuilder;
+
+#[derive(Debug, StructOpt)]
+struct Opt {
+    /// The default region
+    #[structopt(short, long)]
+    default_region: Option<String>,
+
+    /// The name of the bucket
+    #[structopt(short, long)]
+    bucket: String,
+
+    /// Whether to suspend versioning, rather than enable it
+    #[structopt(short, long)]
+    suspend: bool,
+
+    /// The format of the output, text or json
+    #[structopt(long, default_value = "text")]
+    output: OutputFormat,
+
+    /// The AWS profile to use from the shared credentials file
+    #[structopt(long)]
+    profile: Option<String>,
+
+    /// Whether to display additional information
+    #[structopt(short, long)]
+    verbose: bool,
+}
+
+/// The versioning status of the bucket, before and after.
+#[derive(Debug, Serialize)]
+struct BucketVersioning {
+    bucket: String,
+    /// The status before, which is Unversioned if versioning was never enabled.
+    previous: String,
+    status: String,
+}
+
+impl fmt::Display for BucketVersioning {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.previous == self.status {
+            write!(
+                f,
+                "Versioning of bucket {} was already {}",
+                self.bucket, self.status
+            )
+        } else {
+            write!(
+                f,
+                "Changed versioning of bucket {} from {} to {}",
+                self.bucket, self.previous, self.status
+            )
+        }
+    }
+}
+
+/// Returns the versioning status of the bucket.
+/// A bucket that never had versioning enabled has no status, and can't go back to that.
+async fn versioning_status(client: &Client, bucket: &str) -> Result<String, s3::Error> {
+    let status = client
+        .get_bucket_versioning()
+        .bucket(bucket)
+        .send()
+        .await?
+        .status;
+
+    Ok(status
+        .as_ref()
+        .map(|status| status.as_str().to_string())
+        .unwrap_or_else(|| "Unversioned".to_string()))
+}
+
+/// Enables or suspends versioning of an Amazon S3 bucket.
+/// While versioning is enabled, overwriting or deleting an object keeps the earlier version,
+/// which you can list and restore with `list-object-versions`.
+/// Suspending versioning keeps the versions that already exist.
+/// # Arguments
+///
+/// * `-b BUCKET` - The name of the bucket.
+/// * `[-s]` - Whether to suspend versioning, rather than enable it.
+/// * `[-d DEFAULT-REGION]` - The region containing the bucket.
+///   If not supplied, uses the value of the **AWS_DEFAULT_REGION** environment variable.
+///   If the environment variable is not set, defaults to **us-west-2**.
+/// * `[--output FORMAT]` - How to display the result, **text** (the default) or **json**.
+/// * `[--profile PROFILE]` - The AWS profile to use from the shared credentials file.
+/// * `[-v]` - Whether to display additional information.
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn Error>> {
+    let Opt {
+        bucket,
+        default_region,
+        output,
+        profile,
+        suspend,
+        verbose,
+    } = Opt::from_args();
+
+    let region = default_region
+        .as_ref()
+        .map(|region| Region::new(region.clone()))
+        .or_else(|| aws_types::region::default_provider().region())
+        .unwrap_or_else(|| Region::new("us-west-2"));
+
+    if verbose {
+        println!("S3 client version: {}", s3::PKG_VERSION);
+        println!("Region:            {:?}", &region);
+        println!("Bucket:            {}", &bucket);
+        println!("Suspend:           {}", suspend);
+
+        SubscriberBuilder::default()
+            .with_env_filter("info")
+            .with_span_events(FmtSpan::CLOSE)
+            .init();
+    }
+
+    let mut config = Config::builder().region(&region);
+    if let Some(profile) = profile {
+        config = config.credentials_provider(ProfileCredentialsProvider::new(profile));
+    }
+
+    // Sends the requests to LocalStack, or another local endpoint, if AWS_ENDPOINT_URL is set.
+    if let Some(uri) = endpoint::from_env()? {
+        config = config.endpoint_resolver(Endpoint::immutable(uri));
+    }
+
+    let client = Client::from_conf(config.build());
+
+    ensure_bucket(&client, &bucket, &region, false).await?;
+
+    let previous = versioning_status(&client, &bucket).await?;
+
+    let status = if suspend {
+        BucketVersioningStatus::Suspended
+    } else {
+        BucketVersioningStatus::Enabled
+    };
+
+    client
+        .put_bucket_versioning()
+        .bucket(&bucket)
+        .versioning_configuration(VersioningConfiguration::builder().status(status).build())
+        .send()
+        .await?;
+
+    let status = versioning_status(&client, &bucket).await?;
+
+    output.print(&BucketVersioning {
+        bucket,
+        previous,
+        status,
+    });
+
+    Ok(())
+}
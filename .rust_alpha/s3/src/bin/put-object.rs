@@ -0,0 +1,312 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0.
+ */
+
+use std::collections::HashMap;
+use std::error::Error;
+use std::fmt;
+use std::path::Path;
+
+use s3::model::{ServerSideEncryption, StorageClass};
+use s3::{ByteStream, Client, Config, Endpoint, Region};
+use s3_code_examples::buckets::ensure_bucket;
+
+use aws_types::region::ProvideRegion;
+
+use common::endpoint;
+use common::output::OutputFormat;
+use common::profile::ProfileCredentialsProvider;
+use serde::Serialize;
+use structopt::StructOpt;
+use tracing_subscriber::fmt::format::FmtSpan;
+use tracing_subscriber::fmt::SubscriberBuilder;
+
+#[derive(Debug, StructOpt)]
+struct Opt {
+    /// The default region
+    #[structopt(short, long)]
+    default_region: Option<String>,
+
+    /// The name of the bucket
+    #[structopt(short, long)]
+    bucket: String,
+
+    /// The file to upload
+    #[structopt(short, long)]
+    file: String,
+
+    /// The name of the object. Defaults to the name of the file
+    #[structopt(short, long)]
+    key: Option<String>,
+
+    /// The content type. Defaults to a type guessed from the file extension
+    #[structopt(long)]
+    content_type: Option<String>,
+
+    /// Metadata to attach to the object, as KEY=VALUE. Can be repeated
+    #[structopt(long, parse(try_from_str = parse_metadata))]
+    metadata: Vec<(String, String)>,
+
+    /// The storage class, such as STANDARD_IA
+    #[structopt(long)]
+    storage_class: Option<String>,
+
+    /// The server-side encryption, aes256 or aws:kms. Defaults to the bucket's default encryption
+    #[structopt(long, parse(try_from_str = parse_sse))]
+    sse: Option<ServerSideEncryption>,
+
+    /// The ID or ARN of the AWS KMS key to encrypt the object with. Defaults to the AWS managed key for Amazon S3
+    #[structopt(long, requires = "sse")]
+    kms_key_id: Option<String>,
+
+    /// Whether to create the bucket if it doesn't exist
+    #[structopt(long)]
+    create_bucket: bool,
+
+    /// The format of the output, text or json
+    #[structopt(long, default_value = "text")]
+    output: OutputFormat,
+
+    /// The AWS profile to use from the shared credentials file
+    #[structopt(long)]
+    profile: Option<String>,
+
+    /// Whether to display additional information
+    #[structopt(short, long)]
+    verbose: bool,
+}
+
+/// The object that was uploaded.
+#[derive(Debug, Serialize)]
+struct UploadedObject {
+    bucket: String,
+    key: String,
+    content_type: String,
+    storage_class: Option<String>,
+    metadata: HashMap<String, String>,
+    server_side_encryption: Option<String>,
+    kms_key_id: Option<String>,
+    e_tag: Option<String>,
+    version_id: Option<String>,
+}
+
+impl fmt::Display for UploadedObject {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "Uploaded {} to bucket {} as {}",
+            self.key, self.bucket, self.content_type
+        )?;
+
+        if let Some(storage_class) = &self.storage_class {
+            write!(f, "\n  Storage class: {}", storage_class)?;
+        }
+
+        for (key, value) in &self.metadata {
+            write!(f, "\n  Metadata:      {}={}", key, value)?;
+        }
+
+        if let Some(sse) = &self.server_side_encryption {
+            write!(f, "\n  Encryption:    {}", sse)?;
+        }
+
+        if let Some(kms_key_id) = &self.kms_key_id {
+            write!(f, "\n  KMS key:       {}", kms_key_id)?;
+        }
+
+        if let Some(e_tag) = &self.e_tag {
+            write!(f, "\n  ETag:          {}", e_tag)?;
+        }
+
+        if let Some(version_id) = &self.version_id {
+            write!(f, "\n  Version:       {}", version_id)?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Splits a KEY=VALUE metadata argument.
+fn parse_metadata(s: &str) -> Result<(String, String), String> {
+    match s.split_once('=') {
+        Some((key, value)) if !key.is_empty() => Ok((key.to_string(), value.to_string())),
+        _ => Err(format!("metadata must be KEY=VALUE, got {}", s)),
+    }
+}
+
+/// Parses the server-side encryption. Amazon S3 calls SSE-S3 AES256 and SSE-KMS aws:kms.
+fn parse_sse(s: &str) -> Result<ServerSideEncryption, String> {
+    match s.to_ascii_lowercase().as_str() {
+        "aes256" => Ok(ServerSideEncryption::Aes256),
+        "aws:kms" => Ok(ServerSideEncryption::AwsKms),
+        _ => Err(format!(
+            "the encryption must be aes256 or aws:kms, got {}",
+            s
+        )),
+    }
+}
+
+/// Guesses the content type of a file from its extension.
+/// Files with an unknown extension are uploaded as application/octet-stream.
+fn guess_content_type(path: &Path) -> &'static str {
+    let ext = path
+        .extension()
+        .and_then(|ext| ext.to_str())
+        .map(|ext| ext.to_ascii_lowercase())
+        .unwrap_or_default();
+
+    match ext.as_str() {
+        "txt" => "text/plain",
+        "htm" | "html" => "text/html",
+        "css" => "text/css",
+        "csv" => "text/csv",
+        "js" => "application/javascript",
+        "json" => "application/json",
+        "xml" => "application/xml",
+        "pdf" => "application/pdf",
+        "zip" => "application/zip",
+        "gz" => "application/gzip",
+        "gif" => "image/gif",
+        "jpg" | "jpeg" => "image/jpeg",
+        "png" => "image/png",
+        "svg" => "image/svg+xml",
+        "mp3" => "audio/mpeg",
+        "ogg" => "audio/ogg",
+        "wav" => "audio/wav",
+        "mp4" => "video/mp4",
+        _ => "application/octet-stream",
+    }
+}
+
+/// Uploads a local file to an Amazon S3 bucket.
+/// # Arguments
+///
+/// * `-b BUCKET` - The name of the bucket.
+/// * `-f FILE` - The file to upload.
+/// * `[-k KEY]` - The name of the object.
+///   If not supplied, uses the name of the file.
+/// * `[--content-type CONTENT-TYPE]` - The content type of the object.
+///   If not supplied, the content type is guessed from the file extension.
+/// * `[--metadata KEY=VALUE]...` - Metadata to attach to the object.
+/// * `[--storage-class STORAGE-CLASS]` - The storage class of the object, such as **STANDARD_IA**.
+/// * `[--sse ENCRYPTION]` - The server-side encryption, **aes256** for SSE-S3 or **aws:kms** for SSE-KMS.
+///   If not supplied, the object is encrypted with the bucket's default encryption, if it has one.
+/// * `[--kms-key-id KMS-KEY-ID]` - The AWS KMS key to encrypt the object with, for **aws:kms**.
+///   If not supplied, uses the AWS managed key for Amazon S3.
+/// * `[--create-bucket]` - Whether to create the bucket in the region if it doesn't exist.
+/// * `[-d DEFAULT-REGION]` - The region containing the bucket.
+///   If not supplied, uses the value of the **AWS_DEFAULT_REGION** environment variable.
+///   If the environment variable is not set, defaults to **us-west-2**.
+/// * `[--output FORMAT]` - How to display the result, **text** (the default) or **json**.
+/// * `[--profile PROFILE]` - The AWS profile to use from the shared credentials file.
+/// * `[-v]` - Whether to display additional information.
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn Error>> {
+    let Opt {
+        bucket,
+        content_type,
+        create_bucket,
+        default_region,
+        file,
+        key,
+        kms_key_id,
+        metadata,
+        output,
+        profile,
+        sse,
+        storage_class,
+        verbose,
+    } = Opt::from_args();
+
+    if kms_key_id.is_some() && sse != Some(ServerSideEncryption::AwsKms) {
+        return Err("--kms-key-id needs --sse aws:kms".into());
+    }
+
+    let region = default_region
+        .as_ref()
+        .map(|region| Region::new(region.clone()))
+        .or_else(|| aws_types::region::default_provider().region())
+        .unwrap_or_else(|| Region::new("us-west-2"));
+
+    let path = Path::new(&file);
+
+    let key = key.unwrap_or_else(|| {
+        path.file_name()
+            .map(|name| name.to_string_lossy().into_owned())
+            .unwrap_or_else(|| file.clone())
+    });
+
+    let content_type = content_type.unwrap_or_else(|| guess_content_type(path).to_string());
+    let metadata: HashMap<String, String> = metadata.into_iter().collect();
+
+    if verbose {
+        println!("S3 client version: {}", s3::PKG_VERSION);
+        println!("Region:            {:?}", &region);
+        println!("Bucket:            {}", &bucket);
+        println!("File:              {}", &file);
+        println!("Key:               {}", &key);
+        println!("Content type:      {}", &content_type);
+        println!("Storage class:     {:?}", &storage_class);
+        println!("Metadata:          {:?}", &metadata);
+        println!("Encryption:        {:?}", &sse);
+        println!("KMS key:           {:?}", &kms_key_id);
+
+        SubscriberBuilder::default()
+            .with_env_filter("info")
+            .with_span_events(FmtSpan::CLOSE)
+            .init();
+    }
+
+    let body = ByteStream::from_path(path).await?;
+
+    let mut config = Config::builder().region(&region);
+    if let Some(profile) = profile {
+        config = config.credentials_provider(ProfileCredentialsProvider::new(profile));
+    }
+
+    // Sends the requests to LocalStack, or another local endpoint, if AWS_ENDPOINT_URL is set.
+    if let Some(uri) = endpoint::from_env()? {
+        config = config.endpoint_resolver(Endpoint::immutable(uri));
+    }
+
+    let client = Client::from_conf(config.build());
+
+    if ensure_bucket(&client, &bucket, &region, create_bucket).await? && verbose {
+        println!("Created bucket {}", &bucket);
+    }
+
+    let resp = client
+        .put_object()
+        .bucket(&bucket)
+        .key(&key)
+        .body(body)
+        .content_type(&content_type)
+        .set_metadata(if metadata.is_empty() {
+            None
+        } else {
+            Some(metadata.clone())
+        })
+        .set_storage_class(storage_class.as_deref().map(StorageClass::from))
+        .set_server_side_encryption(sse)
+        .set_ssekms_key_id(kms_key_id)
+        .send()
+        .await?;
+
+    output.print(&UploadedObject {
+        bucket,
+        key,
+        content_type,
+        storage_class,
+        metadata,
+        server_side_encryption: resp
+            .server_side_encryption
+            .as_ref()
+            .map(|sse| sse.as_str().to_string()),
+        kms_key_id: resp.ssekms_key_id,
+        e_tag: resp.e_tag,
+        version_id: resp.version_id,
+    });
+
+    Ok(())
+}
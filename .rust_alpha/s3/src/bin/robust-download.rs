@@ -0,0 +1,219 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0.
+ */
+
+use std::error::Error;
+use std::fmt;
+use std::path::Path;
+
+use s3::{Client, Config, Endpoint, Region};
+use s3_code_examples::download::{DownloadReport, Downloader, Mode, Retry};
+
+use aws_types::region::ProvideRegion;
+
+use common::endpoint;
+use common::output::OutputFormat;
+use common::profile::ProfileCredentialsProvider;
+use serde::Serialize;
+use structopt::StructOpt;
+use tracing_subscriber::fmt::format::FmtSpan;
+use tracing_subscriber::fmt::SubscriberBuilder;
+
+#[derive(Debug, StructOpt)]
+struct Opt {
+    /// The default region
+    #[structopt(short, long)]
+    default_region: Option<String>,
+
+    /// The name of the bucket
+    #[structopt(short, long)]
+    bucket: String,
+
+    /// The object to download
+    #[structopt(short, long)]
+    key: String,
+
+    /// The file to save the object in. Defaults to the last part of the key
+    #[structopt(short, long)]
+    file: Option<String>,
+
+    /// The size of each chunk, in MiB
+    #[structopt(long, default_value = "8")]
+    chunk_size: u64,
+
+    /// How many chunks to download at once
+    #[structopt(short, long, default_value = "4")]
+    concurrency: usize,
+
+    /// How many times to try each chunk
+    #[structopt(short, long, default_value = "5")]
+    attempts: u32,
+
+    /// The percentage of requests, from 0 through 100, to fail on purpose, to show the retries
+    #[structopt(long, default_value = "0")]
+    simulate_failures: f64,
+
+    /// The format of the output, text or json
+    #[structopt(long, default_value = "text")]
+    output: OutputFormat,
+
+    /// The AWS profile to use from the shared credentials file
+    #[structopt(long)]
+    profile: Option<String>,
+
+    /// Whether to display additional information
+    #[structopt(short, long)]
+    verbose: bool,
+}
+
+/// The object that was downloaded, and how.
+#[derive(Debug, Serialize)]
+struct Downloaded {
+    bucket: String,
+    key: String,
+    file: String,
+    #[serde(flatten)]
+    report: DownloadReport,
+}
+
+impl fmt::Display for Downloaded {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let report = &self.report;
+
+        writeln!(
+            f,
+            "Downloaded {} bytes of {} from bucket {} to {}",
+            report.bytes, self.key, self.bucket, self.file
+        )?;
+
+        match report.mode {
+            Mode::Ranged => writeln!(f, "Mode:    {} ranged chunks", report.chunks.len())?,
+            Mode::SingleStream => writeln!(f, "Mode:    a single stream")?,
+        }
+        write!(f, "Retries: {}", report.retries())?;
+
+        for chunk in report
+            .chunks
+            .iter()
+            .filter(|chunk| !chunk.errors.is_empty())
+        {
+            write!(
+                f,
+                "\n  Chunk {} (bytes {}-{}) took {} attempts:",
+                chunk.index, chunk.start, chunk.end, chunk.attempts
+            )?;
+            for error in &chunk.errors {
+                write!(f, "\n    {}", error)?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Downloads an object from an Amazon S3 bucket in ranged chunks, several at a time,
+/// retrying each chunk on its own when it fails, so that one failed request doesn't restart the download.
+/// Each chunk is checked for length, and the file is checked against the object's size.
+/// If the server ignores ranges, the object is downloaded in a single stream instead.
+/// # Arguments
+///
+/// * `-b BUCKET` - The name of the bucket.
+/// * `-k KEY` - The name of the object.
+/// * `[-f FILE]` - The file to save the object in.
+///   If not supplied, uses the last part of the key.
+/// * `[--chunk-size MIB]` - The size of each chunk, in MiB. Defaults to **8**.
+/// * `[-c CONCURRENCY]` - How many chunks to download at once. Defaults to **4**.
+/// * `[-a ATTEMPTS]` - How many times to try each chunk. Defaults to **5**.
+/// * `[--simulate-failures PERCENT]` - The percentage of requests to fail on purpose, to show the retries.
+/// * `[-d DEFAULT-REGION]` - The region containing the bucket.
+///   If not supplied, uses the value of the **AWS_DEFAULT_REGION** environment variable.
+///   If the environment variable is not set, defaults to **us-west-2**.
+/// * `[--output FORMAT]` - How to display the result, **text** (the default) or **json**.
+/// * `[--profile PROFILE]` - The AWS profile to use from the shared credentials file.
+/// * `[-v]` - Whether to display additional information.
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn Error>> {
+    let Opt {
+        attempts,
+        bucket,
+        chunk_size,
+        concurrency,
+        default_region,
+        file,
+        key,
+        output,
+        profile,
+        simulate_failures,
+        verbose,
+    } = Opt::from_args();
+
+    let region = default_region
+        .as_ref()
+        .map(|region| Region::new(region.clone()))
+        .or_else(|| aws_types::region::default_provider().region())
+        .unwrap_or_else(|| Region::new("us-west-2"));
+
+    if !(0.0..=100.0).contains(&simulate_failures) {
+        return Err("--simulate-failures must be from 0 through 100".into());
+    }
+    if chunk_size == 0 || attempts == 0 {
+        return Err("the chunk size and the number of attempts must be at least 1".into());
+    }
+
+    let file = file.unwrap_or_else(|| {
+        Path::new(&key)
+            .file_name()
+            .map(|name| name.to_string_lossy().into_owned())
+            .unwrap_or_else(|| key.clone())
+    });
+
+    if verbose {
+        println!("S3 client version: {}", s3::PKG_VERSION);
+        println!("Region:            {:?}", &region);
+        println!("Bucket:            {}", &bucket);
+        println!("Key:               {}", &key);
+        println!("File:              {}", &file);
+        println!("Chunk size:        {} MiB", chunk_size);
+        println!("Concurrency:       {}", concurrency);
+        println!("Attempts:          {}", attempts);
+        println!("Failure rate:      {}%", simulate_failures);
+
+        SubscriberBuilder::default()
+            .with_env_filter("info")
+            .with_span_events(FmtSpan::CLOSE)
+            .init();
+    }
+
+    let mut config = Config::builder().region(&region);
+    if let Some(profile) = profile {
+        config = config.credentials_provider(ProfileCredentialsProvider::new(profile));
+    }
+
+    // Sends the requests to LocalStack, or another local endpoint, if AWS_ENDPOINT_URL is set.
+    if let Some(uri) = endpoint::from_env()? {
+        config = config.endpoint_resolver(Endpoint::immutable(uri));
+    }
+
+    let client = Client::from_conf(config.build());
+
+    let report = Downloader::new(client, &bucket, &key)
+        .chunk_size(chunk_size * 1024 * 1024)
+        .concurrency(concurrency)
+        .retry(Retry {
+            attempts,
+            ..Retry::default()
+        })
+        .simulate_failures(simulate_failures / 100.0)
+        .download(Path::new(&file))
+        .await?;
+
+    output.print(&Downloaded {
+        bucket,
+        key,
+        file,
+        report,
+    });
+
+    Ok(())
+}
@@ -4,12 +4,17 @@
  */
 
 use s3::{ByteStream, Client, Config, Region};
+use s3_code_examples::buckets::ensure_bucket;
 
 use aws_types::region::ProvideRegion;
 
+use common::output::OutputFormat;
+use common::profile::ProfileCredentialsProvider;
+use serde::Serialize;
 use structopt::StructOpt;
 
 use std::error::Error;
+use std::fmt;
 use std::path::Path;
 use tracing_subscriber::fmt::format::FmtSpan;
 use tracing_subscriber::fmt::SubscriberBuilder;
@@ -28,11 +33,38 @@ struct Opt {
     #[structopt(short, long)]
     key: String,
 
+    /// The format of the output, text or json
+    #[structopt(long, default_value = "text")]
+    output: OutputFormat,
+
+    /// The AWS profile to use from the shared credentials file
+    #[structopt(long)]
+    profile: Option<String>,
+
     /// Whether to display additional runtime information
     #[structopt(short, long)]
     verbose: bool,
 }
 
+/// The buckets that were found, and the object that was uploaded and read back.
+#[derive(Debug, Serialize)]
+struct HelloWorld {
+    buckets: Vec<String>,
+    version_id: Option<String>,
+    data: String,
+}
+
+impl fmt::Display for HelloWorld {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for bucket in &self.buckets {
+            writeln!(f, "bucket: {:?}", bucket)?;
+        }
+
+        writeln!(f, "Upload success. Version: {:?}", self.version_id)?;
+        write!(f, "data: {:?}", self.data)
+    }
+}
+
 /// Lists your buckets and uploads a file to a bucket.
 /// # Arguments
 ///
@@ -41,6 +73,8 @@ struct Opt {
 /// * `[-d DEFAULT-REGION]` - The region in which the client is created.
 ///    If not supplied, uses the value of the **AWS_DEFAULT_REGION** environment variable.
 ///    If the environment variable is not set, defaults to **us-west-2**.
+/// * `[--output FORMAT]` - How to display the result, **text** (the default) or **json**.
+/// * `[--profile PROFILE]` - The AWS profile to use from the shared credentials file.
 /// * `[-v]` - Whether to display additional information.
 #[tokio::main]
 async fn main() -> Result<(), Box<dyn Error>> {
@@ -48,6 +82,8 @@ async fn main() -> Result<(), Box<dyn Error>> {
         bucket,
         default_region,
         key,
+        output,
+        profile,
         verbose,
     } = Opt::from_args();
 
@@ -69,14 +105,22 @@ async fn main() -> Result<(), Box<dyn Error>> {
             .init();
     }
 
-    let conf = Config::builder().region(region).build();
-    let client = Client::from_conf(conf);
+    let mut config = Config::builder().region(&region);
+    if let Some(profile) = profile {
+        config = config.credentials_provider(ProfileCredentialsProvider::new(profile));
+    }
+    let client = Client::from_conf(config.build());
 
     let resp = client.list_buckets().send().await?;
 
-    for bucket in resp.buckets.unwrap_or_default() {
-        println!("bucket: {:?}", bucket.name.expect("buckets have names"))
-    }
+    let buckets = resp
+        .buckets
+        .unwrap_or_default()
+        .into_iter()
+        .filter_map(|bucket| bucket.name)
+        .collect();
+
+    ensure_bucket(&client, &bucket, &region, false).await?;
 
     let body = ByteStream::from_path(Path::new("Cargo.toml")).await?;
 
@@ -88,10 +132,16 @@ async fn main() -> Result<(), Box<dyn Error>> {
         .send()
         .await?;
 
-    println!("Upload success. Version: {:?}", resp.version_id);
+    let version_id = resp.version_id;
 
     let resp = client.get_object().bucket(bucket).key(key).send().await?;
     let data = resp.body.collect().await?;
-    println!("data: {:?}", data.into_bytes());
+
+    output.print(&HelloWorld {
+        buckets,
+        version_id,
+        data: String::from_utf8_lossy(&data.into_bytes()).into_owned(),
+    });
+
     Ok(())
 }
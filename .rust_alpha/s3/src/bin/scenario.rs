@@ -0,0 +1,288 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0.
+ */
+
+use std::error::Error;
+use std::fmt;
+
+use s3::{ByteStream, Client, Config, Endpoint, Region};
+use s3_code_examples::buckets::ensure_bucket;
+use s3_code_examples::copy::copy_source;
+
+use aws_types::region::ProvideRegion;
+
+use common::endpoint;
+use common::output::OutputFormat;
+use common::profile::ProfileCredentialsProvider;
+use common::shutdown::Shutdown;
+use serde::Serialize;
+use structopt::StructOpt;
+use tracing_subscriber::fmt::format::FmtSpan;
+use tracing_subscriber::fmt::SubscriberBuilder;
+
+/// The object that the scenario uploads.
+const KEY: &str = "scenario/hello.txt";
+
+/// The copy of the object.
+const COPY_KEY: &str = "scenario/hello-copy.txt";
+
+/// The content of the object.
+const BODY: &str = "Hello from the AWS SDK for Rust!";
+
+#[derive(Debug, StructOpt)]
+struct Opt {
+    /// The default region
+    #[structopt(short, long)]
+    default_region: Option<String>,
+
+    /// The name of the bucket to create. Bucket names are shared by all AWS accounts
+    #[structopt(short, long)]
+    bucket: String,
+
+    /// Whether to keep the bucket and its objects
+    #[structopt(short, long)]
+    keep: bool,
+
+    /// The format of the output, text or json
+    #[structopt(long, default_value = "text")]
+    output: OutputFormat,
+
+    /// The AWS profile to use from the shared credentials file
+    #[structopt(long)]
+    profile: Option<String>,
+
+    /// Whether to display additional information
+    #[structopt(short, long)]
+    verbose: bool,
+}
+
+/// The resources that the scenario has created, so that they can be deleted even if a later step fails.
+#[derive(Default)]
+struct Resources {
+    bucket: bool,
+    keys: Vec<String>,
+}
+
+/// An object in the bucket.
+#[derive(Debug, Serialize)]
+struct Object {
+    key: String,
+    size: i64,
+}
+
+/// What the scenario found.
+#[derive(Debug, Serialize)]
+struct Summary {
+    bucket: String,
+    objects: Vec<Object>,
+    content: String,
+    kept: bool,
+}
+
+impl fmt::Display for Summary {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "Listed {} objects in {} and downloaded {}",
+            self.objects.len(),
+            self.bucket,
+            COPY_KEY
+        )?;
+
+        if self.kept {
+            write!(f, "; kept the bucket and its objects")
+        } else {
+            write!(f, "; deleted what the scenario created")
+        }
+    }
+}
+
+/// Displays a step of the scenario, unless the output is JSON, which is only the summary.
+fn step(opt: &Opt, message: impl fmt::Display) {
+    if !opt.output.is_json() {
+        println!("{}", message);
+    }
+}
+
+/// Lists the keys and sizes of the objects in the bucket.
+async fn list_objects(client: &Client, bucket: &str) -> Result<Vec<Object>, s3::Error> {
+    let resp = client.list_objects_v2().bucket(bucket).send().await?;
+
+    Ok(resp
+        .contents
+        .unwrap_or_default()
+        .into_iter()
+        .map(|object| Object {
+            key: object.key.unwrap_or_default(),
+            size: object.size,
+        })
+        .collect())
+}
+
+/// Downloads an object, and returns its content as text.
+async fn get_object(client: &Client, bucket: &str, key: &str) -> Result<String, Box<dyn Error>> {
+    let resp = client.get_object().bucket(bucket).key(key).send().await?;
+    let data = resp.body.collect().await?.into_bytes();
+
+    Ok(String::from_utf8(data.to_vec())?)
+}
+
+/// Runs the steps of the scenario, stopping at the first error,
+/// and returns the objects in the bucket and the content of the copy.
+async fn run(
+    client: &Client,
+    opt: &Opt,
+    region: &Region,
+    resources: &mut Resources,
+) -> Result<(Vec<Object>, String), Box<dyn Error>> {
+    step(
+        opt,
+        format!("1. Creating bucket {} in {}", &opt.bucket, region.as_ref()),
+    );
+    // If you already own the bucket, it's used, and only the objects that the scenario uploads are deleted.
+    if ensure_bucket(client, &opt.bucket, region, true).await? {
+        resources.bucket = true;
+    } else {
+        step(opt, "  You already own the bucket, so it won't be deleted");
+    }
+
+    step(opt, format!("2. Uploading {}", KEY));
+    client
+        .put_object()
+        .bucket(&opt.bucket)
+        .key(KEY)
+        .content_type("text/plain")
+        .body(ByteStream::from(BODY.as_bytes().to_vec()))
+        .send()
+        .await?;
+    resources.keys.push(KEY.to_string());
+
+    step(opt, format!("3. Copying {} to {}", KEY, COPY_KEY));
+    client
+        .copy_object()
+        .copy_source(copy_source(&opt.bucket, KEY))
+        .bucket(&opt.bucket)
+        .key(COPY_KEY)
+        .send()
+        .await?;
+    resources.keys.push(COPY_KEY.to_string());
+
+    step(opt, "4. Listing the objects in the bucket");
+    let objects = list_objects(client, &opt.bucket).await?;
+    for object in &objects {
+        step(opt, format!("  {} ({} bytes)", object.key, object.size));
+    }
+
+    step(opt, format!("5. Downloading {}", COPY_KEY));
+    let body = get_object(client, &opt.bucket, COPY_KEY).await?;
+    step(opt, format!("  Content: {}", body));
+
+    if body != BODY {
+        return Err(format!("expected the copy to contain {:?}, got {:?}", BODY, body).into());
+    }
+
+    Ok((objects, body))
+}
+
+/// Deletes the objects and then the bucket, which must be empty to be deleted.
+/// Keeps going after an error, so that as much as possible is cleaned up.
+async fn tear_down(client: &Client, opt: &Opt, resources: &Resources) {
+    for key in &resources.keys {
+        if let Err(e) = client
+            .delete_object()
+            .bucket(&opt.bucket)
+            .key(key)
+            .send()
+            .await
+        {
+            eprintln!("Got an error deleting {}: {}", key, e);
+        }
+    }
+
+    if resources.bucket {
+        if let Err(e) = client.delete_bucket().bucket(&opt.bucket).send().await {
+            eprintln!("Got an error deleting bucket {}: {}", &opt.bucket, e);
+        }
+    }
+}
+
+/// Gets started with Amazon S3 by running a complete workflow in one program.
+/// The scenario creates a bucket, uploads an object, copies it,
+/// lists the objects in the bucket, and downloads the copy.
+/// Finally, it deletes the objects and the bucket, even if a step fails or you press Ctrl-C.
+/// # Arguments
+///
+/// * `-b BUCKET` - The name of the bucket to create.
+/// * `[-k]` - Whether to keep the bucket and its objects.
+/// * `[--output FORMAT]` - How to display the result, **text** (the default) or **json**,
+///   which only displays a summary once the scenario is finished.
+/// * `[-d DEFAULT-REGION]` - The region in which the bucket is created.
+///   If not supplied, uses the value of the **AWS_DEFAULT_REGION** environment variable.
+///   If the environment variable is not set, defaults to **us-west-2**.
+/// * `[--profile PROFILE]` - The AWS profile to use from the shared credentials file.
+/// * `[-v]` - Whether to display additional information.
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn Error>> {
+    let opt = Opt::from_args();
+
+    let region = opt
+        .default_region
+        .as_ref()
+        .map(|region| Region::new(region.clone()))
+        .or_else(|| aws_types::region::default_provider().region())
+        .unwrap_or_else(|| Region::new("us-west-2"));
+
+    if opt.verbose {
+        println!("S3 client version: {}", s3::PKG_VERSION);
+        println!("Region:            {:?}", &region);
+        println!("Bucket:            {}", &opt.bucket);
+        println!();
+
+        SubscriberBuilder::default()
+            .with_env_filter("info")
+            .with_span_events(FmtSpan::CLOSE)
+            .init();
+    }
+
+    let mut config = Config::builder().region(&region);
+    if let Some(profile) = &opt.profile {
+        config = config.credentials_provider(ProfileCredentialsProvider::new(profile));
+    }
+
+    // Sends the requests to LocalStack, or another local endpoint, if AWS_ENDPOINT_URL is set.
+    if let Some(uri) = endpoint::from_env()? {
+        config = config.endpoint_resolver(Endpoint::immutable(uri));
+    }
+
+    let client = Client::from_conf(config.build());
+
+    let mut resources = Resources::default();
+
+    // Ctrl-C stops the scenario wherever it is, and still cleans up what it created.
+    let shutdown = Shutdown::listen();
+    let result = shutdown
+        .run(run(&client, &opt, &region, &mut resources))
+        .await;
+
+    if opt.keep {
+        step(
+            &opt,
+            format!("Keeping bucket {} and its objects", &opt.bucket),
+        );
+    } else {
+        step(&opt, "6. Deleting the objects and the bucket");
+        tear_down(&client, &opt, &resources).await;
+    }
+
+    let (objects, content) = result?;
+
+    opt.output.print(&Summary {
+        bucket: opt.bucket.clone(),
+        objects,
+        content,
+        kept: opt.keep,
+    });
+
+    Ok(())
+}
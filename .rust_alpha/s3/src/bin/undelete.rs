@@ -0,0 +1,259 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0.
+ */
+
+use std::error::Error;
+use std::fmt;
+
+use s3::{Client, Config, Endpoint, Region};
+use s3_code_examples::buckets::ensure_bucket;
+use s3_code_examples::versions::{list_versions, Version};
+
+use aws_types::region::ProvideRegion;
+
+use common::endpoint;
+use common::output::OutputFormat;
+use common::profile::ProfileCredentialsProvider;
+use serde::Serialize;
+use structopt::StructOpt;
+use tracing_subscriber::fmt::format::FmtSpan;
+use tracing_subscriber::fmt::SubscriberBuilder;
+
+#[derive(Debug, StructOpt)]
+struct Opt {
+    /// The default region
+    #[structopt(short, long)]
+    default_region: Option<String>,
+
+    /// The name of the bucket
+    #[structopt(short, long)]
+    bucket: String,
+
+    /// The name of the object to undelete
+    #[structopt(short, long, conflicts_with = "prefix", required_unless = "prefix")]
+    key: Option<String>,
+
+    /// The prefix of the objects to undelete
+    #[structopt(short, long)]
+    prefix: Option<String>,
+
+    /// Whether to only display what would be undeleted
+    #[structopt(long)]
+    dry_run: bool,
+
+    /// The format of the output, text or json
+    #[structopt(long, default_value = "text")]
+    output: OutputFormat,
+
+    /// The AWS profile to use from the shared credentials file
+    #[structopt(long)]
+    profile: Option<String>,
+
+    /// Whether to display additional information
+    #[structopt(short, long)]
+    verbose: bool,
+}
+
+/// An object that was undeleted.
+#[derive(Debug, Serialize)]
+struct Undeleted {
+    key: String,
+    /// The IDs of the delete markers that were removed, newest first.
+    /// There's more than one if the object was deleted again after it was already deleted.
+    delete_markers: Vec<String>,
+    /// The ID of the version that is the latest again.
+    restored_version: String,
+}
+
+/// The objects that were undeleted.
+#[derive(Debug, Serialize)]
+struct Undelete {
+    bucket: String,
+    dry_run: bool,
+    undeleted: Vec<Undeleted>,
+    /// The keys that only have delete markers, so there's nothing to restore.
+    unrecoverable: Vec<String>,
+}
+
+impl fmt::Display for Undelete {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} {} objects in bucket {}",
+            if self.dry_run {
+                "Would undelete"
+            } else {
+                "Undeleted"
+            },
+            self.undeleted.len(),
+            self.bucket
+        )?;
+
+        for undeleted in &self.undeleted {
+            write!(
+                f,
+                "\n  {}, restoring version {}",
+                undeleted.key, undeleted.restored_version
+            )?;
+        }
+
+        if !self.unrecoverable.is_empty() {
+            write!(
+                f,
+                "\n{} objects only have delete markers, so there's nothing to restore:",
+                self.unrecoverable.len()
+            )?;
+
+            for key in &self.unrecoverable {
+                write!(f, "\n  {}", key)?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Finds the deleted objects among the versions: the keys whose latest version is a delete marker.
+/// Returns the objects that can be undeleted, and the keys that can't, because every version is a delete marker.
+/// The versions of each key must be together, newest first, as `list_versions` returns them.
+fn find_deleted(versions: &[Version]) -> (Vec<Undeleted>, Vec<String>) {
+    let mut undeleted = Vec::new();
+    let mut unrecoverable = Vec::new();
+
+    for (index, marker) in versions.iter().enumerate() {
+        if !(marker.is_latest && marker.delete_marker) {
+            continue;
+        }
+
+        // The newest version under the delete markers is the one that becomes the latest again,
+        // once every delete marker above it is removed.
+        let delete_markers: Vec<String> = versions[index..]
+            .iter()
+            .take_while(|version| version.key == marker.key && version.delete_marker)
+            .map(|version| version.version_id.clone())
+            .collect();
+        let restored = versions[index + delete_markers.len()..]
+            .iter()
+            .take_while(|version| version.key == marker.key)
+            .find(|version| !version.delete_marker);
+
+        match restored {
+            Some(version) => undeleted.push(Undeleted {
+                key: marker.key.clone(),
+                delete_markers,
+                restored_version: version.version_id.clone(),
+            }),
+            None => unrecoverable.push(marker.key.clone()),
+        }
+    }
+
+    (undeleted, unrecoverable)
+}
+
+/// Undeletes objects in an Amazon S3 bucket that has versioning enabled.
+/// Deleting an object in a versioned bucket adds a delete marker, which hides the object's versions.
+/// The example finds the objects whose latest version is a delete marker, and deletes the marker,
+/// and any other delete markers right under it from deleting the object again,
+/// which makes the newest version under them the latest again.
+/// Deleting a delete marker needs its version ID. Deleting a specific version can't be undone.
+/// # Arguments
+///
+/// * `-b BUCKET` - The name of the bucket.
+/// * `-k KEY` - The name of the object to undelete.
+/// * `-p PREFIX` - The prefix of the objects to undelete, instead of a single key.
+/// * `[--dry-run]` - Whether to only display what would be undeleted.
+/// * `[-d DEFAULT-REGION]` - The region containing the bucket.
+///   If not supplied, uses the value of the **AWS_DEFAULT_REGION** environment variable.
+///   If the environment variable is not set, defaults to **us-west-2**.
+/// * `[--output FORMAT]` - How to display the result, **text** (the default) or **json**.
+/// * `[--profile PROFILE]` - The AWS profile to use from the shared credentials file.
+/// * `[-v]` - Whether to display additional information.
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn Error>> {
+    let Opt {
+        bucket,
+        default_region,
+        dry_run,
+        key,
+        output,
+        prefix,
+        profile,
+        verbose,
+    } = Opt::from_args();
+
+    let region = default_region
+        .as_ref()
+        .map(|region| Region::new(region.clone()))
+        .or_else(|| aws_types::region::default_provider().region())
+        .unwrap_or_else(|| Region::new("us-west-2"));
+
+    if verbose {
+        println!("S3 client version: {}", s3::PKG_VERSION);
+        println!("Region:            {:?}", &region);
+        println!("Bucket:            {}", &bucket);
+        println!("Key:               {:?}", &key);
+        println!("Prefix:            {:?}", &prefix);
+        println!("Dry run:           {}", dry_run);
+
+        SubscriberBuilder::default()
+            .with_env_filter("info")
+            .with_span_events(FmtSpan::CLOSE)
+            .init();
+    }
+
+    let mut config = Config::builder().region(&region);
+    if let Some(profile) = profile {
+        config = config.credentials_provider(ProfileCredentialsProvider::new(profile));
+    }
+
+    // Sends the requests to LocalStack, or another local endpoint, if AWS_ENDPOINT_URL is set.
+    if let Some(uri) = endpoint::from_env()? {
+        config = config.endpoint_resolver(Endpoint::immutable(uri));
+    }
+
+    let client = Client::from_conf(config.build());
+
+    ensure_bucket(&client, &bucket, &region, false).await?;
+
+    // A key is listed as a prefix, which can match other keys too, so those are skipped.
+    let mut versions = match (&key, &prefix) {
+        (Some(key), _) => list_versions(&client, &bucket, key).await?,
+        (None, Some(prefix)) => list_versions(&client, &bucket, prefix).await?,
+        (None, None) => unreachable!("structopt requires a key or a prefix"),
+    };
+    if let Some(key) = &key {
+        versions.retain(|version| &version.key == key);
+    }
+
+    let (undeleted, unrecoverable) = find_deleted(&versions);
+
+    if !dry_run {
+        for object in &undeleted {
+            // Newest first, so that if one fails, the object is still deleted rather than partly restored.
+            for delete_marker in &object.delete_markers {
+                client
+                    .delete_object()
+                    .bucket(&bucket)
+                    .key(&object.key)
+                    .version_id(delete_marker)
+                    .send()
+                    .await
+                    .map_err(|e| format!("couldn't undelete {}: {}", &object.key, e))?;
+
+                if verbose {
+                    println!("Deleted delete marker {} of {}", delete_marker, &object.key);
+                }
+            }
+        }
+    }
+
+    output.print(&Undelete {
+        bucket,
+        dry_run,
+        undeleted,
+        unrecoverable,
+    });
+
+    Ok(())
+}
@@ -0,0 +1,251 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0.
+ */
+
+use std::collections::HashMap;
+use std::error::Error;
+use std::fmt;
+
+use s3::model::MetadataDirective;
+use s3::{Client, Config, Endpoint, Region};
+use s3_code_examples::buckets::ensure_bucket;
+use s3_code_examples::copy::copy_source;
+
+use aws_types::region::ProvideRegion;
+
+use common::endpoint;
+use common::output::OutputFormat;
+use common::profile::ProfileCredentialsProvider;
+use serde::Serialize;
+use structopt::StructOpt;
+use tracing_subscriber::fmt::format::FmtSpan;
+use tracing_subscriber::fmt::SubscriberBuilder;
+
+/// The largest object that CopyObject can copy, 5 GiB.
+/// Larger objects must be copied with a multipart upload.
+const MAX_COPY_SIZE: i64 = 5 * 1024 * 1024 * 1024;
+
+#[derive(Debug, StructOpt)]
+struct Opt {
+    /// The default region
+    #[structopt(short, long)]
+    default_region: Option<String>,
+
+    /// The name of the bucket
+    #[structopt(short, long)]
+    bucket: String,
+
+    /// The name of the object
+    #[structopt(short, long)]
+    key: String,
+
+    /// The new content type
+    #[structopt(long)]
+    content_type: Option<String>,
+
+    /// Metadata to add or change, as KEY=VALUE. Can be repeated
+    #[structopt(long, parse(try_from_str = parse_metadata))]
+    metadata: Vec<(String, String)>,
+
+    /// Metadata to remove. Can be repeated
+    #[structopt(long)]
+    remove: Vec<String>,
+
+    /// Whether to replace all of the user metadata, rather than keep what isn't changed
+    #[structopt(long)]
+    replace_all: bool,
+
+    /// The format of the output, text or json
+    #[structopt(long, default_value = "text")]
+    output: OutputFormat,
+
+    /// The AWS profile to use from the shared credentials file
+    #[structopt(long)]
+    profile: Option<String>,
+
+    /// Whether to display additional information
+    #[structopt(short, long)]
+    verbose: bool,
+}
+
+/// The object whose metadata was updated.
+#[derive(Debug, Serialize)]
+struct UpdatedObject {
+    bucket: String,
+    key: String,
+    content_type: Option<String>,
+    metadata: HashMap<String, String>,
+    e_tag: Option<String>,
+}
+
+impl fmt::Display for UpdatedObject {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "Updated the metadata of {} in bucket {}",
+            self.key, self.bucket
+        )?;
+
+        if let Some(content_type) = &self.content_type {
+            write!(f, "\n  Content type: {}", content_type)?;
+        }
+
+        for (key, value) in &self.metadata {
+            write!(f, "\n  Metadata:     {}={}", key, value)?;
+        }
+
+        if let Some(e_tag) = &self.e_tag {
+            write!(f, "\n  ETag:         {}", e_tag)?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Splits a KEY=VALUE metadata argument.
+fn parse_metadata(s: &str) -> Result<(String, String), String> {
+    match s.split_once('=') {
+        Some((key, value)) if !key.is_empty() => Ok((key.to_string(), value.to_string())),
+        _ => Err(format!("metadata must be KEY=VALUE, got {}", s)),
+    }
+}
+
+/// Changes the user metadata or content type of an existing object.
+///
+/// Amazon S3 metadata can't be edited in place. Instead, the object is copied onto itself
+/// with the **REPLACE** metadata directive, which replaces *all* of its metadata with what's in the request.
+/// So this example first reads the current metadata, and sends back everything that isn't being changed,
+/// including the content type, cache control, storage class, and encryption settings,
+/// which the copy would otherwise reset to their defaults.
+/// # Arguments
+///
+/// * `-b BUCKET` - The name of the bucket.
+/// * `-k KEY` - The name of the object.
+/// * `[--content-type CONTENT-TYPE]` - The new content type of the object.
+/// * `[--metadata KEY=VALUE]...` - User metadata to add or change.
+/// * `[--remove KEY]...` - User metadata to remove.
+/// * `[--replace-all]` - Whether to replace all of the user metadata with the **--metadata** values,
+///   rather than keep the metadata that isn't changed.
+/// * `[-d DEFAULT-REGION]` - The region containing the bucket.
+///   If not supplied, uses the value of the **AWS_DEFAULT_REGION** environment variable.
+///   If the environment variable is not set, defaults to **us-west-2**.
+/// * `[--output FORMAT]` - How to display the result, **text** (the default) or **json**.
+/// * `[--profile PROFILE]` - The AWS profile to use from the shared credentials file.
+/// * `[-v]` - Whether to display additional information.
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn Error>> {
+    let Opt {
+        bucket,
+        content_type,
+        default_region,
+        key,
+        metadata,
+        output,
+        profile,
+        remove,
+        replace_all,
+        verbose,
+    } = Opt::from_args();
+
+    let region = default_region
+        .as_ref()
+        .map(|region| Region::new(region.clone()))
+        .or_else(|| aws_types::region::default_provider().region())
+        .unwrap_or_else(|| Region::new("us-west-2"));
+
+    if content_type.is_none() && metadata.is_empty() && remove.is_empty() && !replace_all {
+        return Err("nothing to update: supply --content-type, --metadata, or --remove".into());
+    }
+
+    if verbose {
+        println!("S3 client version: {}", s3::PKG_VERSION);
+        println!("Region:            {:?}", &region);
+        println!("Bucket:            {}", &bucket);
+        println!("Key:               {}", &key);
+        println!("Content type:      {:?}", &content_type);
+        println!("Metadata:          {:?}", &metadata);
+        println!("Remove:            {:?}", &remove);
+        println!("Replace all:       {}", replace_all);
+
+        SubscriberBuilder::default()
+            .with_env_filter("info")
+            .with_span_events(FmtSpan::CLOSE)
+            .init();
+    }
+
+    let mut config = Config::builder().region(&region);
+    if let Some(profile) = profile {
+        config = config.credentials_provider(ProfileCredentialsProvider::new(profile));
+    }
+
+    // Sends the requests to LocalStack, or another local endpoint, if AWS_ENDPOINT_URL is set.
+    if let Some(uri) = endpoint::from_env()? {
+        config = config.endpoint_resolver(Endpoint::immutable(uri));
+    }
+
+    let client = Client::from_conf(config.build());
+
+    ensure_bucket(&client, &bucket, &region, false).await?;
+
+    let current = client
+        .head_object()
+        .bucket(&bucket)
+        .key(&key)
+        .send()
+        .await?;
+
+    if current.content_length > MAX_COPY_SIZE {
+        return Err(format!(
+            "{} is larger than 5 GiB, so its metadata can't be changed with a single copy",
+            key
+        )
+        .into());
+    }
+
+    let mut new_metadata = if replace_all {
+        HashMap::new()
+    } else {
+        current.metadata.unwrap_or_default()
+    };
+    for name in &remove {
+        new_metadata.remove(name);
+    }
+    new_metadata.extend(metadata);
+
+    let new_content_type = content_type.or(current.content_type);
+
+    // Copying only if the ETag still matches means that if someone else changes the object
+    // between the HeadObject call and the copy, the copy fails rather than overwriting their change.
+    let mut copy = client
+        .copy_object()
+        .copy_source(copy_source(&bucket, &key))
+        .bucket(&bucket)
+        .key(&key)
+        .metadata_directive(MetadataDirective::Replace)
+        .set_metadata(Some(new_metadata.clone()))
+        .set_content_type(new_content_type.clone())
+        .set_cache_control(current.cache_control)
+        .set_content_disposition(current.content_disposition)
+        .set_content_encoding(current.content_encoding)
+        .set_content_language(current.content_language)
+        .set_storage_class(current.storage_class)
+        .set_server_side_encryption(current.server_side_encryption)
+        .set_ssekms_key_id(current.ssekms_key_id);
+
+    if let Some(e_tag) = &current.e_tag {
+        copy = copy.copy_source_if_match(e_tag);
+    }
+
+    let resp = copy.send().await?;
+
+    output.print(&UpdatedObject {
+        bucket,
+        key,
+        content_type: new_content_type,
+        metadata: new_metadata,
+        e_tag: resp.copy_object_result.and_then(|result| result.e_tag),
+    });
+
+    Ok(())
+}
@@ -0,0 +1,179 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0.
+ */
+
+//! Checks that a bucket exists, and that it's in the client's region, before an example writes to it.
+//!
+//! Writing to a bucket in another region fails with a redirect, and writing to a bucket that
+//! another account owns fails with __Access Denied__, and neither error says what's wrong.
+//! Examples that write call `ensure_bucket` first, which calls `HeadBucket` and reports the problem,
+//! and can create the bucket if it doesn't exist:
+//!
+//! ```ignore
+//! let created = ensure_bucket(&client, &bucket, &region, create_bucket).await?;
+//! ```
+
+use std::error::Error;
+use std::fmt;
+
+use s3::error::CreateBucketErrorKind;
+use s3::model::{BucketLocationConstraint, CreateBucketConfiguration};
+use s3::{Client, Region, SdkError};
+
+/// The header in which Amazon S3 says which region a bucket is in.
+const BUCKET_REGION_HEADER: &str = "x-amz-bucket-region";
+
+/// What `HeadBucket` found out about a bucket.
+#[derive(Debug, PartialEq, Eq)]
+pub enum BucketStatus {
+    /// The bucket is in the client's region, and you can access it. Usually that means you own it.
+    Owned,
+    /// The bucket doesn't exist.
+    NotFound,
+    /// The bucket exists, but you can't access it. Usually that means another account owns it.
+    Forbidden,
+    /// The bucket is in another region. Holds the region, if Amazon S3 said which.
+    WrongRegion(Option<String>),
+}
+
+/// Why a bucket can't be used.
+#[derive(Debug)]
+pub enum BucketError {
+    /// HeadBucket or CreateBucket failed.
+    Sdk(s3::Error),
+    /// The bucket doesn't exist, and wasn't created.
+    NotFound(String),
+    /// The bucket exists, but you can't access it.
+    Forbidden(String),
+    /// The bucket is in another region than the client.
+    WrongRegion {
+        bucket: String,
+        region: Option<String>,
+        expected: String,
+    },
+}
+
+impl fmt::Display for BucketError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            BucketError::Sdk(e) => write!(f, "{}", e),
+            BucketError::NotFound(bucket) => write!(f, "bucket {} does not exist", bucket),
+            BucketError::Forbidden(bucket) => write!(
+                f,
+                "bucket {} exists, but you don't have access to it. \
+                 Bucket names are shared by all AWS accounts, so it probably belongs to another account",
+                bucket
+            ),
+            BucketError::WrongRegion {
+                bucket,
+                region: Some(region),
+                expected,
+            } => write!(
+                f,
+                "bucket {} is in {}, not {}. Run the example with -d {}",
+                bucket, region, expected, region
+            ),
+            BucketError::WrongRegion {
+                bucket,
+                region: None,
+                expected,
+            } => write!(
+                f,
+                "bucket {} is not in {}. Run the example in the bucket's region",
+                bucket, expected
+            ),
+        }
+    }
+}
+
+impl Error for BucketError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            BucketError::Sdk(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+impl From<s3::Error> for BucketError {
+    fn from(e: s3::Error) -> Self {
+        BucketError::Sdk(e)
+    }
+}
+
+/// Calls `HeadBucket`, and works out from the status code whether the bucket can be used.
+/// `HeadBucket` responses have no body, so the status code is all there is to go on.
+pub async fn bucket_status(client: &Client, bucket: &str) -> Result<BucketStatus, s3::Error> {
+    match client.head_bucket().bucket(bucket).send().await {
+        Ok(_) => Ok(BucketStatus::Owned),
+        Err(SdkError::ServiceError { err, raw }) => match raw.status().as_u16() {
+            301 => Ok(BucketStatus::WrongRegion(
+                raw.headers()
+                    .get(BUCKET_REGION_HEADER)
+                    .and_then(|region| region.to_str().ok())
+                    .map(str::to_string),
+            )),
+            403 => Ok(BucketStatus::Forbidden),
+            404 => Ok(BucketStatus::NotFound),
+            _ => Err(SdkError::ServiceError { err, raw }.into()),
+        },
+        Err(e) => Err(e.into()),
+    }
+}
+
+/// Creates a bucket in the region.
+/// Succeeds if you already own a bucket with the name, so it's safe to call more than once.
+pub async fn create_bucket(
+    client: &Client,
+    bucket: &str,
+    region: &Region,
+) -> Result<(), BucketError> {
+    let mut req = client.create_bucket().bucket(bucket);
+
+    // us-east-1 is the default location, and Amazon S3 rejects it as a location constraint.
+    if region.as_ref() != "us-east-1" {
+        req = req.create_bucket_configuration(
+            CreateBucketConfiguration::builder()
+                .location_constraint(BucketLocationConstraint::from(region.as_ref()))
+                .build(),
+        );
+    }
+
+    match req.send().await {
+        Ok(_) => Ok(()),
+        Err(SdkError::ServiceError { err, raw }) => match &err.kind {
+            CreateBucketErrorKind::BucketAlreadyOwnedByYou(_) => Ok(()),
+            CreateBucketErrorKind::BucketAlreadyExists(_) => {
+                Err(BucketError::Forbidden(bucket.to_string()))
+            }
+            _ => Err(BucketError::Sdk(SdkError::ServiceError { err, raw }.into())),
+        },
+        Err(e) => Err(BucketError::Sdk(e.into())),
+    }
+}
+
+/// Checks that the bucket exists in the client's region and that you can access it.
+/// If the bucket doesn't exist and `create` is set, creates it.
+/// Returns whether the bucket was created.
+pub async fn ensure_bucket(
+    client: &Client,
+    bucket: &str,
+    region: &Region,
+    create: bool,
+) -> Result<bool, BucketError> {
+    match bucket_status(client, bucket).await? {
+        BucketStatus::Owned => Ok(false),
+        BucketStatus::NotFound if create => {
+            create_bucket(client, bucket, region).await?;
+            Ok(true)
+        }
+        BucketStatus::NotFound => Err(BucketError::NotFound(bucket.to_string())),
+        BucketStatus::Forbidden => Err(BucketError::Forbidden(bucket.to_string())),
+        BucketStatus::WrongRegion(actual) => Err(BucketError::WrongRegion {
+            bucket: bucket.to_string(),
+            region: actual,
+            expected: region.as_ref().to_string(),
+        }),
+    }
+}
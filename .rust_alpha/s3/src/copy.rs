@@ -0,0 +1,39 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0.
+ */
+
+//! Builds the copy source of CopyObject, BUCKET/KEY, with the key URL-encoded as Amazon S3 requires.
+//!
+//! Without the encoding, keys that contain spaces, plus signs, or non-ASCII characters can't be copied:
+//!
+//! ```ignore
+//! client
+//!     .copy_object()
+//!     .copy_source(copy_source(&source_bucket, &source_key))
+//!     .bucket(&bucket)
+//!     .key(&key)
+//!     .send()
+//!     .await?;
+//! ```
+
+use percent_encoding::{utf8_percent_encode, AsciiSet, NON_ALPHANUMERIC};
+
+/// The characters that are encoded in the key of a copy source.
+/// Everything except the unreserved characters and the slashes that separate the parts of the key.
+pub const COPY_SOURCE: &AsciiSet = &NON_ALPHANUMERIC
+    .remove(b'-')
+    .remove(b'.')
+    .remove(b'_')
+    .remove(b'~')
+    .remove(b'/');
+
+/// Returns the copy source of the object's current version.
+pub fn copy_source(bucket: &str, key: &str) -> String {
+    format!("{}/{}", bucket, utf8_percent_encode(key, COPY_SOURCE))
+}
+
+/// Returns the copy source of a version of the object.
+pub fn copy_source_version(bucket: &str, key: &str, version_id: &str) -> String {
+    format!("{}?versionId={}", copy_source(bucket, key), version_id)
+}
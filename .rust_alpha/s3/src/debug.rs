@@ -0,0 +1,309 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0.
+ */
+
+//! Logging the requests that the SDK sends, after they're signed, and the responses it gets back.
+//!
+//! A **SignatureDoesNotMatch** error means that the signature AWS computed from the request it
+//! received isn't the one in the request's **Authorization** header. AWS Signature Version 4 signs
+//! a _canonical request_, built from the method, path, query string, some of the headers, and a
+//! hash of the body, so the error almost always comes from one of those changing after signing,
+//! such as a proxy rewriting a header, or from the wrong secret key or clock.
+//!
+//! `LoggingConnector` wraps the connector that sends the requests. It logs each signed request,
+//! the canonical request and string to sign rebuilt from it, and the raw response,
+//! at the trace level with the target `debug_request`:
+//!
+//! ```ignore
+//! let conn = LoggingConnector::new(aws_hyper::conn::Standard::https());
+//! let client = Client::from_conf_conn(config, conn);
+//! ```
+//!
+//! For a signature error, Amazon S3 returns the canonical request and string to sign that it
+//! computed, to compare with the ones logged here.
+
+use std::fmt::Write;
+use std::future::Future;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+
+use http::header::{HeaderValue, AUTHORIZATION};
+use http::{Request, Response};
+use sha2::{Digest, Sha256};
+use smithy_http::body::SdkBody;
+use tower::{BoxError, Service};
+use tracing::trace;
+
+/// The target of the log messages, to turn them on with a filter such as `debug_request=trace`.
+pub const TARGET: &str = "debug_request";
+
+/// How much of a body is logged, in bytes.
+const MAX_BODY_LOG: usize = 4096;
+
+/// The headers whose values are never logged.
+const REDACTED: &[&str] = &["x-amz-security-token"];
+
+/// A connector that logs the signed requests that it sends, and the responses, before passing them on.
+#[derive(Clone, Debug)]
+pub struct LoggingConnector<S> {
+    inner: S,
+    tamper: bool,
+}
+
+impl<S> LoggingConnector<S> {
+    /// Logs the requests that `inner` sends.
+    pub fn new(inner: S) -> Self {
+        LoggingConnector {
+            inner,
+            tamper: false,
+        }
+    }
+
+    /// Changes the **x-amz-date** header of each request by a second after it's signed,
+    /// as a proxy might change a header, so that AWS rejects the request with **SignatureDoesNotMatch**.
+    pub fn tamper(mut self, tamper: bool) -> Self {
+        self.tamper = tamper;
+        self
+    }
+}
+
+impl<S> Service<Request<SdkBody>> for LoggingConnector<S>
+where
+    S: Service<Request<SdkBody>, Response = Response<SdkBody>> + Send + Clone + 'static,
+    S::Error: Into<BoxError> + Send + Sync + 'static,
+    S::Future: Send + 'static,
+{
+    type Response = Response<SdkBody>;
+    type Error = BoxError;
+    type Future = Pin<Box<dyn Future<Output = Result<Self::Response, Self::Error>> + Send>>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx).map_err(Into::into)
+    }
+
+    fn call(&mut self, mut req: Request<SdkBody>) -> Self::Future {
+        if self.tamper {
+            tamper_date(&mut req);
+        }
+
+        log_request(&req);
+
+        let sent = self.inner.call(req);
+
+        Box::pin(async move {
+            let resp = sent.await.map_err(Into::into)?;
+
+            // The body is read here so that it can be logged, and then handed back to the SDK.
+            let (parts, body) = resp.into_parts();
+            let body = hyper::body::to_bytes(body).await?;
+
+            trace!(
+                target: TARGET,
+                "Response:\n{:?} {}\n{}\n{}",
+                parts.version,
+                parts.status,
+                headers(&parts.headers),
+                body_text(&body)
+            );
+
+            Ok(Response::from_parts(parts, SdkBody::from(body)))
+        })
+    }
+}
+
+/// Logs the request, as it's sent, and the canonical request and string to sign rebuilt from it.
+fn log_request(req: &Request<SdkBody>) {
+    let body = req.body().bytes();
+
+    trace!(
+        target: TARGET,
+        "Signed request:\n{} {}\n{}\n{}",
+        req.method(),
+        req.uri(),
+        headers(req.headers()),
+        body.map(body_text)
+            .unwrap_or_else(|| "<streaming body>".to_string())
+    );
+
+    match canonical_request(req) {
+        Some((canonical, string_to_sign)) => {
+            trace!(target: TARGET, "Canonical request:\n{}", canonical);
+            trace!(target: TARGET, "String to sign:\n{}", string_to_sign);
+        }
+        None => trace!(
+            target: TARGET,
+            "The request has no AWS Signature Version 4 Authorization header"
+        ),
+    }
+}
+
+/// Formats the headers one per line, leaving out the values of the ones that are secret.
+fn headers(headers: &http::HeaderMap) -> String {
+    headers
+        .iter()
+        .map(|(name, value)| {
+            if REDACTED.contains(&name.as_str()) {
+                format!("{}: <redacted>", name)
+            } else {
+                format!("{}: {}", name, value.to_str().unwrap_or("<binary>"))
+            }
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// Formats a body as text, up to `MAX_BODY_LOG` bytes of it.
+fn body_text(body: &[u8]) -> String {
+    if body.len() > MAX_BODY_LOG {
+        format!(
+            "{}... ({} more bytes)",
+            String::from_utf8_lossy(&body[..MAX_BODY_LOG]),
+            body.len() - MAX_BODY_LOG
+        )
+    } else {
+        String::from_utf8_lossy(body).into_owned()
+    }
+}
+
+/// Rebuilds the canonical request and the string to sign from a signed request,
+/// or returns `None` if it isn't signed with Signature Version 4.
+///
+/// The signed headers and credential scope come from the **Authorization** header, such as
+/// `AWS4-HMAC-SHA256 Credential=AKIDEXAMPLE/20210801/us-west-2/s3/aws4_request,
+/// SignedHeaders=host;x-amz-content-sha256;x-amz-date, Signature=...`.
+fn canonical_request(req: &Request<SdkBody>) -> Option<(String, String)> {
+    let authorization = req.headers().get(AUTHORIZATION)?.to_str().ok()?;
+    let fields = authorization.strip_prefix("AWS4-HMAC-SHA256 ")?;
+
+    let field = |name: &str| {
+        fields
+            .split(',')
+            .map(str::trim)
+            .find_map(|field| field.strip_prefix(name))
+    };
+
+    let signed_headers = field("SignedHeaders=")?;
+    // The scope is the credential without the access key ID.
+    let scope = field("Credential=")?.splitn(2, '/').nth(1)?;
+
+    let mut canonical = String::new();
+    let _ = writeln!(canonical, "{}", req.method());
+    // Amazon S3 signs the path as it's sent; other services encode it a second time.
+    let _ = writeln!(canonical, "{}", req.uri().path());
+
+    let mut query: Vec<(&str, &str)> = req
+        .uri()
+        .query()
+        .unwrap_or_default()
+        .split('&')
+        .filter(|pair| !pair.is_empty())
+        .map(|pair| {
+            let mut parts = pair.splitn(2, '=');
+            (
+                parts.next().unwrap_or_default(),
+                parts.next().unwrap_or_default(),
+            )
+        })
+        .collect();
+    query.sort_unstable();
+    let query: Vec<String> = query
+        .iter()
+        .map(|(name, value)| format!("{}={}", name, value))
+        .collect();
+    let _ = writeln!(canonical, "{}", query.join("&"));
+
+    for name in signed_headers.split(';') {
+        let values: Vec<String> = req
+            .headers()
+            .get_all(name)
+            .iter()
+            .map(|value| {
+                // Sequential spaces count as one.
+                String::from_utf8_lossy(value.as_bytes())
+                    .split_whitespace()
+                    .collect::<Vec<_>>()
+                    .join(" ")
+            })
+            .collect();
+
+        // The connector adds the host header when it sends the request, after signing.
+        let value = if values.is_empty() && name == "host" {
+            req.uri()
+                .authority()
+                .map(|a| a.to_string())
+                .unwrap_or_default()
+        } else {
+            values.join(",")
+        };
+
+        let _ = writeln!(canonical, "{}:{}", name, value);
+    }
+
+    let _ = writeln!(canonical);
+    let _ = writeln!(canonical, "{}", signed_headers);
+
+    let payload_hash = match req.headers().get("x-amz-content-sha256") {
+        Some(hash) => hash.to_str().unwrap_or_default().to_string(),
+        None => req
+            .body()
+            .bytes()
+            .map(|body| hex(&Sha256::digest(body)))
+            .unwrap_or_else(|| "UNSIGNED-PAYLOAD".to_string()),
+    };
+    canonical.push_str(&payload_hash);
+
+    let date = req
+        .headers()
+        .get("x-amz-date")
+        .and_then(|date| date.to_str().ok())
+        .unwrap_or_default();
+
+    let string_to_sign = format!(
+        "AWS4-HMAC-SHA256\n{}\n{}\n{}",
+        date,
+        scope,
+        hex(&Sha256::digest(canonical.as_bytes()))
+    );
+
+    Some((canonical, string_to_sign))
+}
+
+/// Formats bytes as lowercase hex.
+fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|byte| format!("{:02x}", byte)).collect()
+}
+
+/// Moves the **x-amz-date** header, such as `20210801T120000Z`, by one second,
+/// back if it's the last second of a minute so that only the seconds change.
+fn tamper_date(req: &mut Request<SdkBody>) {
+    let date = match req
+        .headers()
+        .get("x-amz-date")
+        .and_then(|date| date.to_str().ok())
+    {
+        Some(date) if date.len() == 16 => date.to_string(),
+        _ => return,
+    };
+
+    let seconds: u8 = match date[13..15].parse() {
+        Ok(seconds) => seconds,
+        Err(_) => return,
+    };
+    let seconds = if seconds < 59 {
+        seconds + 1
+    } else {
+        seconds - 1
+    };
+    let tampered = format!("{}{:02}Z", &date[..13], seconds);
+
+    if let Ok(value) = HeaderValue::from_str(&tampered) {
+        trace!(
+            target: TARGET,
+            "Changed x-amz-date from {} to {} after signing",
+            date,
+            tampered
+        );
+        req.headers_mut().insert("x-amz-date", value);
+    }
+}
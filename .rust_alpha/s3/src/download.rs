@@ -0,0 +1,589 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0.
+ */
+
+//! Downloads an object in ranged chunks that are retried one at a time, so that a failed
+//! request costs one chunk rather than the whole download.
+//!
+//! The object's size and ETag are read with HeadObject first. Each chunk is then requested with
+//! a `Range` header and `If-Match` on the ETag, so that a chunk of a newer version of the object,
+//! uploaded during the download, is never mixed in. Each chunk is checked for length before it's
+//! written at its offset in the file, and the bytes that all the chunks wrote are added up and
+//! checked against the object's size:
+//!
+//! ```ignore
+//! let downloader = Downloader::new(client, bucket, key);
+//! let report = downloader.download(Path::new("photo.jpg")).await?;
+//! println!("{} bytes in {} chunks, {} retries", report.bytes, report.chunks.len(), report.retries());
+//! ```
+//!
+//! Some S3-compatible servers ignore the `Range` header and return the whole object.
+//! A one-byte ranged request is sent first, and if the response has no `Content-Range`,
+//! the object is downloaded in a single stream instead, which is retried as a whole.
+
+use std::error::Error;
+use std::fmt;
+use std::io::{self, SeekFrom};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::Duration;
+
+use s3::error::GetObjectError;
+use s3::{Client, SdkError};
+use serde::Serialize;
+use tokio::fs::{File, OpenOptions};
+use tokio::io::{AsyncSeekExt, AsyncWriteExt};
+use tokio::sync::Semaphore;
+use tokio::time::sleep;
+use tokio_stream::StreamExt;
+
+/// The default size of a chunk. Each chunk is held in memory until it's written.
+pub const DEFAULT_CHUNK_SIZE: u64 = 8 * 1024 * 1024;
+
+/// A part of the object, from `start` through `end`, inclusive, as in a `Range` header.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Chunk {
+    pub index: usize,
+    pub start: u64,
+    pub end: u64,
+}
+
+impl Chunk {
+    /// How many bytes the chunk has.
+    pub fn size(&self) -> u64 {
+        self.end - self.start + 1
+    }
+
+    /// The value of the `Range` header that requests the chunk, such as `bytes=0-8388607`.
+    pub fn range(&self) -> String {
+        format!("bytes={}-{}", self.start, self.end)
+    }
+}
+
+/// Splits an object of `size` bytes into chunks of `chunk_size` bytes. The last chunk can be shorter.
+pub fn plan_chunks(size: u64, chunk_size: u64) -> Vec<Chunk> {
+    let chunk_size = chunk_size.max(1);
+
+    (0..size)
+        .step_by(chunk_size as usize)
+        .enumerate()
+        .map(|(index, start)| Chunk {
+            index,
+            start,
+            end: (start + chunk_size).min(size) - 1,
+        })
+        .collect()
+}
+
+/// How many times to try each request, and how long to wait between tries.
+#[derive(Clone, Copy, Debug)]
+pub struct Retry {
+    /// How many times to try, including the first.
+    pub attempts: u32,
+    /// How long to wait before the first retry. The delay doubles after each retry.
+    pub initial_delay: Duration,
+    /// The longest delay between two tries.
+    pub max_delay: Duration,
+}
+
+impl Default for Retry {
+    fn default() -> Self {
+        Retry {
+            attempts: 5,
+            initial_delay: Duration::from_millis(200),
+            max_delay: Duration::from_secs(10),
+        }
+    }
+}
+
+impl Retry {
+    /// How long to wait after the attempt, counting from 1, failed.
+    fn delay(&self, attempt: u32) -> Duration {
+        let factor = 2u32.saturating_pow(attempt.saturating_sub(1));
+        self.initial_delay
+            .checked_mul(factor)
+            .unwrap_or(self.max_delay)
+            .min(self.max_delay)
+    }
+}
+
+/// Why a download failed.
+#[derive(Debug)]
+pub enum DownloadError {
+    /// HeadObject failed, or a request failed in a way that retrying can't fix, such as access being denied.
+    Sdk(s3::Error),
+    /// The object was replaced during the download, so its chunks can't be put together.
+    Changed(String),
+    /// A chunk failed every attempt. Holds its index, the number of attempts, and the last error.
+    Chunk(usize, u32, String),
+    /// The single-stream download failed every attempt. Holds the number of attempts and the last error.
+    Stream(u32, String),
+    /// The file doesn't have as many bytes as the object.
+    SizeMismatch { expected: u64, actual: u64 },
+    /// The file couldn't be written.
+    Io(io::Error),
+}
+
+impl fmt::Display for DownloadError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DownloadError::Sdk(e) => write!(f, "{}", e),
+            DownloadError::Changed(key) => write!(
+                f,
+                "{} was changed during the download; download it again",
+                key
+            ),
+            DownloadError::Chunk(index, attempts, e) => write!(
+                f,
+                "chunk {} failed {} times, last with: {}",
+                index, attempts, e
+            ),
+            DownloadError::Stream(attempts, e) => {
+                write!(
+                    f,
+                    "the download failed {} times, last with: {}",
+                    attempts, e
+                )
+            }
+            DownloadError::SizeMismatch { expected, actual } => write!(
+                f,
+                "the file has {} bytes, but the object has {}",
+                actual, expected
+            ),
+            DownloadError::Io(e) => write!(f, "{}", e),
+        }
+    }
+}
+
+impl Error for DownloadError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            DownloadError::Sdk(e) => Some(e),
+            DownloadError::Io(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+impl From<s3::Error> for DownloadError {
+    fn from(e: s3::Error) -> Self {
+        DownloadError::Sdk(e)
+    }
+}
+
+impl From<io::Error> for DownloadError {
+    fn from(e: io::Error) -> Self {
+        DownloadError::Io(e)
+    }
+}
+
+/// How a failed attempt is handled.
+enum Attempt {
+    /// Try again, such as after a server error or a dropped connection.
+    Retry(String),
+    /// Give up on the whole download.
+    Stop(DownloadError),
+}
+
+/// Decides whether a failed GetObject is worth retrying. Server errors, throttling, and failures
+/// to send the request or read the response are; errors in the request itself aren't.
+fn classify(e: SdkError<GetObjectError>, key: &str) -> Attempt {
+    let status = match &e {
+        SdkError::ServiceError { raw, .. } => Some(raw.status().as_u16()),
+        _ => None,
+    };
+
+    match status {
+        Some(412) => Attempt::Stop(DownloadError::Changed(key.to_string())),
+        Some(429) | Some(500..=599) => Attempt::Retry(e.to_string()),
+        Some(_) => Attempt::Stop(DownloadError::Sdk(e.into())),
+        None if matches!(e, SdkError::ConstructionFailure(_)) => {
+            Attempt::Stop(DownloadError::Sdk(e.into()))
+        }
+        None => Attempt::Retry(e.to_string()),
+    }
+}
+
+/// How the object was downloaded.
+#[derive(Clone, Copy, Debug, PartialEq, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum Mode {
+    /// In ranged chunks, each retried on its own.
+    Ranged,
+    /// In one request, because the server ignores ranges, or the object fits in one chunk.
+    SingleStream,
+}
+
+/// How one chunk, or the single stream, was downloaded.
+#[derive(Debug, Serialize)]
+pub struct ChunkReport {
+    pub index: usize,
+    pub start: u64,
+    pub end: u64,
+    /// How many bytes were written to the file.
+    pub bytes: u64,
+    pub attempts: u32,
+    /// Why each failed attempt failed.
+    pub errors: Vec<String>,
+}
+
+/// What the download did.
+#[derive(Debug, Serialize)]
+pub struct DownloadReport {
+    pub mode: Mode,
+    pub bytes: u64,
+    pub chunks: Vec<ChunkReport>,
+}
+
+impl DownloadReport {
+    /// How many attempts failed and were retried.
+    pub fn retries(&self) -> usize {
+        self.chunks.iter().map(|chunk| chunk.errors.len()).sum()
+    }
+}
+
+/// What every chunk's task needs.
+struct Shared {
+    client: Client,
+    bucket: String,
+    key: String,
+    etag: Option<String>,
+    path: PathBuf,
+    retry: Retry,
+    failure_rate: f64,
+}
+
+impl Shared {
+    /// Fails the attempt on purpose, as often as `failure_rate` says, to show the retries at work.
+    fn simulate_failure(&self) -> Result<(), String> {
+        if self.failure_rate > 0.0 && rand::random::<f64>() < self.failure_rate {
+            Err("simulated failure".to_string())
+        } else {
+            Ok(())
+        }
+    }
+
+    /// Downloads the chunk, retrying it until it succeeds or runs out of attempts, and writes it to the file.
+    async fn download_chunk(&self, chunk: Chunk) -> Result<ChunkReport, DownloadError> {
+        let mut errors = Vec::new();
+
+        for attempt in 1..=self.retry.attempts {
+            match self.try_chunk(chunk).await {
+                Ok(bytes) => {
+                    return Ok(ChunkReport {
+                        index: chunk.index,
+                        start: chunk.start,
+                        end: chunk.end,
+                        bytes,
+                        attempts: attempt,
+                        errors,
+                    })
+                }
+                Err(Attempt::Stop(e)) => return Err(e),
+                Err(Attempt::Retry(e)) => {
+                    errors.push(e);
+                    if attempt < self.retry.attempts {
+                        sleep(self.retry.delay(attempt)).await;
+                    }
+                }
+            }
+        }
+
+        Err(DownloadError::Chunk(
+            chunk.index,
+            self.retry.attempts,
+            errors.pop().unwrap_or_default(),
+        ))
+    }
+
+    /// Returns how many bytes were written.
+    async fn try_chunk(&self, chunk: Chunk) -> Result<u64, Attempt> {
+        self.simulate_failure().map_err(Attempt::Retry)?;
+
+        let resp = self
+            .client
+            .get_object()
+            .bucket(&self.bucket)
+            .key(&self.key)
+            .range(chunk.range())
+            .set_if_match(self.etag.clone())
+            .send()
+            .await
+            .map_err(|e| classify(e, &self.key))?;
+
+        // The connection can drop part way through the body.
+        let data = resp
+            .body
+            .collect()
+            .await
+            .map_err(|e| Attempt::Retry(e.to_string()))?
+            .into_bytes();
+
+        if data.len() as u64 != chunk.size() {
+            return Err(Attempt::Retry(format!(
+                "got {} bytes of a {}-byte chunk",
+                data.len(),
+                chunk.size()
+            )));
+        }
+
+        // Each chunk opens the file on its own, so that chunks are written as they arrive, in any order.
+        let write = async {
+            let mut file = OpenOptions::new().write(true).open(&self.path).await?;
+            file.seek(SeekFrom::Start(chunk.start)).await?;
+            file.write_all(&data).await?;
+            file.flush().await
+        };
+        write
+            .await
+            .map_err(|e| Attempt::Stop(DownloadError::Io(e)))?;
+
+        Ok(data.len() as u64)
+    }
+
+    /// Downloads the whole object in one request, retrying it from the start until it succeeds
+    /// or runs out of attempts.
+    async fn download_stream(&self, size: u64) -> Result<ChunkReport, DownloadError> {
+        let mut errors = Vec::new();
+
+        for attempt in 1..=self.retry.attempts {
+            match self.try_stream(size).await {
+                Ok(bytes) => {
+                    return Ok(ChunkReport {
+                        index: 0,
+                        start: 0,
+                        end: size.saturating_sub(1),
+                        bytes,
+                        attempts: attempt,
+                        errors,
+                    })
+                }
+                Err(Attempt::Stop(e)) => return Err(e),
+                Err(Attempt::Retry(e)) => {
+                    errors.push(e);
+                    if attempt < self.retry.attempts {
+                        sleep(self.retry.delay(attempt)).await;
+                    }
+                }
+            }
+        }
+
+        Err(DownloadError::Stream(
+            self.retry.attempts,
+            errors.pop().unwrap_or_default(),
+        ))
+    }
+
+    /// Returns how many bytes were written.
+    async fn try_stream(&self, size: u64) -> Result<u64, Attempt> {
+        self.simulate_failure().map_err(Attempt::Retry)?;
+
+        let resp = self
+            .client
+            .get_object()
+            .bucket(&self.bucket)
+            .key(&self.key)
+            .set_if_match(self.etag.clone())
+            .send()
+            .await
+            .map_err(|e| classify(e, &self.key))?;
+
+        let mut file = File::create(&self.path)
+            .await
+            .map_err(|e| Attempt::Stop(DownloadError::Io(e)))?;
+
+        let mut body = resp.body;
+        let mut bytes = 0;
+        while let Some(data) = body.next().await {
+            let data = data.map_err(|e| Attempt::Retry(e.to_string()))?;
+            file.write_all(&data)
+                .await
+                .map_err(|e| Attempt::Stop(DownloadError::Io(e)))?;
+            bytes += data.len() as u64;
+        }
+        file.flush()
+            .await
+            .map_err(|e| Attempt::Stop(DownloadError::Io(e)))?;
+
+        if bytes != size {
+            return Err(Attempt::Retry(format!(
+                "got {} bytes of a {}-byte object",
+                bytes, size
+            )));
+        }
+
+        Ok(bytes)
+    }
+
+    /// Asks for the first byte, to find out whether the server honors ranges.
+    /// A server that does answers with a `Content-Range` header.
+    async fn supports_ranges(&self) -> Result<bool, DownloadError> {
+        let resp = self
+            .client
+            .get_object()
+            .bucket(&self.bucket)
+            .key(&self.key)
+            .range("bytes=0-0")
+            .send()
+            .await;
+
+        match resp {
+            Ok(resp) => Ok(resp.content_range.is_some() && resp.content_length == 1),
+            Err(e) => match classify(e, &self.key) {
+                Attempt::Stop(e) => Err(e),
+                // If even this request fails, the single stream, which is retried, is the safer choice.
+                Attempt::Retry(_) => Ok(false),
+            },
+        }
+    }
+}
+
+/// Downloads an object in chunks, retrying each chunk on its own.
+pub struct Downloader {
+    client: Client,
+    bucket: String,
+    key: String,
+    chunk_size: u64,
+    concurrency: usize,
+    retry: Retry,
+    failure_rate: f64,
+}
+
+impl Downloader {
+    pub fn new(client: Client, bucket: impl Into<String>, key: impl Into<String>) -> Self {
+        Downloader {
+            client,
+            bucket: bucket.into(),
+            key: key.into(),
+            chunk_size: DEFAULT_CHUNK_SIZE,
+            concurrency: 4,
+            retry: Retry::default(),
+            failure_rate: 0.0,
+        }
+    }
+
+    /// Sets the size of each chunk, in bytes.
+    pub fn chunk_size(mut self, chunk_size: u64) -> Self {
+        self.chunk_size = chunk_size.max(1);
+        self
+    }
+
+    /// Sets how many chunks are downloaded at once.
+    pub fn concurrency(mut self, concurrency: usize) -> Self {
+        self.concurrency = concurrency.max(1);
+        self
+    }
+
+    pub fn retry(mut self, retry: Retry) -> Self {
+        self.retry = retry;
+        self
+    }
+
+    /// Sets the fraction of requests, from 0 through 1, that fail on purpose before they're sent.
+    pub fn simulate_failures(mut self, failure_rate: f64) -> Self {
+        self.failure_rate = failure_rate;
+        self
+    }
+
+    /// Downloads the object to the file, and checks that as many bytes were written as the object has.
+    /// If a chunk fails for good, the chunks still running are stopped, and the file is left incomplete.
+    pub async fn download(self, path: &Path) -> Result<DownloadReport, DownloadError> {
+        let head = self
+            .client
+            .head_object()
+            .bucket(&self.bucket)
+            .key(&self.key)
+            .send()
+            .await
+            .map_err(s3::Error::from)?;
+
+        let size = head.content_length.max(0) as u64;
+        let concurrency = self.concurrency;
+        let chunk_size = self.chunk_size;
+
+        let shared = Arc::new(Shared {
+            client: self.client,
+            bucket: self.bucket,
+            key: self.key,
+            etag: head.e_tag,
+            path: path.to_path_buf(),
+            retry: self.retry,
+            failure_rate: self.failure_rate,
+        });
+
+        let ranged = size > chunk_size && shared.supports_ranges().await?;
+
+        let chunks = if ranged {
+            // The file is created at its full size, so that each chunk can be written at its offset.
+            File::create(path).await?.set_len(size).await?;
+
+            let permits = Arc::new(Semaphore::new(concurrency));
+            let handles: Vec<_> = plan_chunks(size, chunk_size)
+                .into_iter()
+                .map(|chunk| {
+                    let shared = shared.clone();
+                    let permits = permits.clone();
+
+                    tokio::spawn(async move {
+                        let _permit = permits.acquire().await;
+                        shared.download_chunk(chunk).await
+                    })
+                })
+                .collect();
+
+            let mut chunks = Vec::with_capacity(handles.len());
+            let mut handles = handles.into_iter();
+            let mut failed = None;
+
+            for handle in handles.by_ref() {
+                let result = match handle.await {
+                    Ok(result) => result,
+                    Err(e) => Err(DownloadError::Io(io::Error::new(io::ErrorKind::Other, e))),
+                };
+
+                match result {
+                    Ok(chunk) => chunks.push(chunk),
+                    Err(e) => {
+                        failed = Some(e);
+                        break;
+                    }
+                }
+            }
+
+            if let Some(e) = failed {
+                // Stops the chunks that are still running, so that nothing more is written to the file.
+                for handle in handles {
+                    handle.abort();
+                }
+                return Err(e);
+            }
+
+            chunks
+        } else {
+            vec![shared.download_stream(size).await?]
+        };
+
+        let report = DownloadReport {
+            mode: if ranged {
+                Mode::Ranged
+            } else {
+                Mode::SingleStream
+            },
+            bytes: chunks.iter().map(|chunk| chunk.bytes).sum(),
+            chunks,
+        };
+
+        // The file was created at its full size in ranged mode, so its length proves nothing.
+        // The bytes that were actually written are counted instead.
+        let actual = report.bytes;
+        if actual != size {
+            return Err(DownloadError::SizeMismatch {
+                expected: size,
+                actual,
+            });
+        }
+
+        Ok(report)
+    }
+}
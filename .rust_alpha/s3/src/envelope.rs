@@ -0,0 +1,223 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0.
+ */
+
+//! Envelope encryption of objects on the client, before they're uploaded to Amazon S3.
+//!
+//! Each object is encrypted with its own data key, which AWS KMS generates. The object is encrypted
+//! with AES-256 in GCM mode, which also detects any change to the ciphertext. Only the copy of the
+//! data key that's encrypted under the KMS key is kept, in the object's metadata, along with the IV,
+//! so that whoever can decrypt with the KMS key can decrypt the object, and Amazon S3 never sees the
+//! plaintext or the data key.
+//!
+//! The metadata uses the names and values of the V2 format of the Amazon S3 encryption clients in
+//! the other AWS SDKs, with the `kms+context` key wrapping algorithm:
+//!
+//! ```ignore
+//! let sealed = seal(&data_key, &plaintext)?;
+//! let metadata = metadata(&encrypted_data_key, &sealed.iv, plaintext.len());
+//! // Later, with the object's metadata and body:
+//! let envelope = Envelope::from_metadata(&metadata)?;
+//! let plaintext = open(&data_key, &envelope.iv, &body)?;
+//! ```
+
+use std::collections::HashMap;
+use std::error::Error;
+use std::fmt;
+
+use aes_gcm::aead::{Aead, NewAead};
+use aes_gcm::{Aes256Gcm, Key, Nonce};
+use rand::RngCore;
+
+/// The metadata that holds the data key, encrypted under the KMS key, in base64.
+pub const KEY_METADATA: &str = "x-amz-key-v2";
+/// The metadata that holds the IV, in base64.
+pub const IV_METADATA: &str = "x-amz-iv";
+/// The metadata that names the content encryption algorithm.
+pub const CEK_ALG_METADATA: &str = "x-amz-cek-alg";
+/// The metadata that names how the data key is encrypted.
+pub const WRAP_ALG_METADATA: &str = "x-amz-wrap-alg";
+/// The metadata that holds the encryption context of the data key, as JSON.
+pub const MATDESC_METADATA: &str = "x-amz-matdesc";
+/// The metadata that holds the length of the GCM tag, in bits.
+pub const TAG_LEN_METADATA: &str = "x-amz-tag-len";
+/// The metadata that holds the length of the plaintext.
+pub const CONTENT_LENGTH_METADATA: &str = "x-amz-unencrypted-content-length";
+
+/// The content encryption algorithm, as the Amazon S3 encryption clients name it.
+pub const CEK_ALG: &str = "AES/GCM/NoPadding";
+/// The key wrapping algorithm: a KMS data key, with the content encryption algorithm in its encryption context.
+pub const WRAP_ALG: &str = "kms+context";
+/// The encryption context key that binds the data key to the content encryption algorithm.
+pub const CEK_ALG_CONTEXT: &str = "aws:x-amz-cek-alg";
+
+/// The length of the GCM tag that's appended to the ciphertext, in bytes.
+pub const TAG_LEN: usize = 16;
+/// The length of the IV, in bytes.
+pub const IV_LEN: usize = 12;
+/// The length of a data key, in bytes.
+pub const KEY_LEN: usize = 32;
+
+/// Why an object couldn't be encrypted or decrypted.
+#[derive(Debug)]
+pub enum EnvelopeError {
+    /// The object doesn't have this metadata, so it wasn't encrypted on the client.
+    MissingMetadata(&'static str),
+    /// The object was encrypted with an algorithm that this module doesn't support.
+    Unsupported(String),
+    /// The metadata isn't valid.
+    Malformed(String),
+    /// AES-GCM failed; when decrypting, the data key is wrong or the ciphertext was changed.
+    Crypto,
+}
+
+impl fmt::Display for EnvelopeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            EnvelopeError::MissingMetadata(name) => write!(
+                f,
+                "the object has no {} metadata, so it isn't encrypted on the client",
+                name
+            ),
+            EnvelopeError::Unsupported(alg) => write!(f, "unsupported algorithm {}", alg),
+            EnvelopeError::Malformed(reason) => write!(f, "malformed metadata: {}", reason),
+            EnvelopeError::Crypto => write!(
+                f,
+                "couldn't decrypt the object: the data key is wrong, or the object was changed"
+            ),
+        }
+    }
+}
+
+impl Error for EnvelopeError {}
+
+/// An encrypted object: the IV, and the ciphertext with the GCM tag appended.
+#[derive(Debug)]
+pub struct Sealed {
+    pub iv: [u8; IV_LEN],
+    pub ciphertext: Vec<u8>,
+}
+
+/// What the metadata of an encrypted object says about how to decrypt it.
+#[derive(Debug)]
+pub struct Envelope {
+    /// The data key, encrypted under the KMS key.
+    pub encrypted_key: Vec<u8>,
+    pub iv: Vec<u8>,
+    /// The encryption context to decrypt the data key with.
+    pub encryption_context: HashMap<String, String>,
+}
+
+impl Envelope {
+    /// Reads the envelope from an object's metadata, checking that the algorithms are the ones `seal` uses.
+    pub fn from_metadata(metadata: &HashMap<String, String>) -> Result<Envelope, EnvelopeError> {
+        let get = |name: &'static str| {
+            metadata
+                .get(name)
+                .map(String::as_str)
+                .ok_or(EnvelopeError::MissingMetadata(name))
+        };
+
+        let encrypted_key = get(KEY_METADATA)?;
+        let cek_alg = get(CEK_ALG_METADATA)?;
+        if cek_alg != CEK_ALG {
+            return Err(EnvelopeError::Unsupported(cek_alg.to_string()));
+        }
+
+        let wrap_alg = get(WRAP_ALG_METADATA)?;
+        if wrap_alg != WRAP_ALG {
+            return Err(EnvelopeError::Unsupported(wrap_alg.to_string()));
+        }
+
+        let encrypted_key = base64::decode(encrypted_key)
+            .map_err(|e| EnvelopeError::Malformed(format!("{}: {}", KEY_METADATA, e)))?;
+        let iv = base64::decode(get(IV_METADATA)?)
+            .map_err(|e| EnvelopeError::Malformed(format!("{}: {}", IV_METADATA, e)))?;
+        if iv.len() != IV_LEN {
+            return Err(EnvelopeError::Malformed(format!(
+                "the IV is {} bytes, not {}",
+                iv.len(),
+                IV_LEN
+            )));
+        }
+
+        let encryption_context = match metadata.get(MATDESC_METADATA) {
+            Some(matdesc) => serde_json::from_str(matdesc)
+                .map_err(|e| EnvelopeError::Malformed(format!("{}: {}", MATDESC_METADATA, e)))?,
+            None => encryption_context(),
+        };
+
+        Ok(Envelope {
+            encrypted_key,
+            iv,
+            encryption_context,
+        })
+    }
+}
+
+/// The encryption context to generate a data key with, which KMS requires again to decrypt it.
+pub fn encryption_context() -> HashMap<String, String> {
+    let mut context = HashMap::new();
+    context.insert(CEK_ALG_CONTEXT.to_string(), CEK_ALG.to_string());
+    context
+}
+
+/// Returns the AES-GCM cipher for a data key, which must be 256 bits.
+fn cipher(data_key: &[u8]) -> Result<Aes256Gcm, EnvelopeError> {
+    if data_key.len() != KEY_LEN {
+        return Err(EnvelopeError::Malformed(format!(
+            "the data key is {} bytes, not {}",
+            data_key.len(),
+            KEY_LEN
+        )));
+    }
+
+    Ok(Aes256Gcm::new(Key::from_slice(data_key)))
+}
+
+/// Encrypts the plaintext with a 256-bit data key, and a random IV.
+pub fn seal(data_key: &[u8], plaintext: &[u8]) -> Result<Sealed, EnvelopeError> {
+    let mut iv = [0u8; IV_LEN];
+    rand::thread_rng().fill_bytes(&mut iv);
+
+    let ciphertext = cipher(data_key)?
+        .encrypt(Nonce::from_slice(&iv), plaintext)
+        .map_err(|_| EnvelopeError::Crypto)?;
+
+    Ok(Sealed { iv, ciphertext })
+}
+
+/// Decrypts what `seal` encrypted, checking the GCM tag.
+pub fn open(data_key: &[u8], iv: &[u8], ciphertext: &[u8]) -> Result<Vec<u8>, EnvelopeError> {
+    if iv.len() != IV_LEN {
+        return Err(EnvelopeError::Malformed(format!(
+            "the IV is {} bytes, not {}",
+            iv.len(),
+            IV_LEN
+        )));
+    }
+
+    cipher(data_key)?
+        .decrypt(Nonce::from_slice(iv), ciphertext)
+        .map_err(|_| EnvelopeError::Crypto)
+}
+
+/// Returns the metadata to upload an encrypted object with.
+pub fn metadata(encrypted_key: &[u8], iv: &[u8], plaintext_len: usize) -> HashMap<String, String> {
+    let matdesc = serde_json::to_string(&encryption_context())
+        .expect("a map of strings is always valid JSON");
+
+    let mut metadata = HashMap::new();
+    metadata.insert(KEY_METADATA.to_string(), base64::encode(encrypted_key));
+    metadata.insert(IV_METADATA.to_string(), base64::encode(iv));
+    metadata.insert(CEK_ALG_METADATA.to_string(), CEK_ALG.to_string());
+    metadata.insert(WRAP_ALG_METADATA.to_string(), WRAP_ALG.to_string());
+    metadata.insert(MATDESC_METADATA.to_string(), matdesc);
+    metadata.insert(TAG_LEN_METADATA.to_string(), (TAG_LEN * 8).to_string());
+    metadata.insert(
+        CONTENT_LENGTH_METADATA.to_string(),
+        plaintext_len.to_string(),
+    );
+    metadata
+}
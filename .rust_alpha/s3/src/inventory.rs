@@ -0,0 +1,328 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0.
+ */
+
+//! Compares the objects under two locations, each a bucket and an optional prefix.
+//!
+//! Keys are compared relative to their location's prefix, so `photos/2021/` in one bucket
+//! can be compared with `backup/photos/2021/` in another. `list` reads every page of
+//! ListObjectsV2, and `diff` reports the keys that only one side has, and the keys whose
+//! size or ETag differ:
+//!
+//! ```ignore
+//! let (source, target) = tokio::try_join!(list(&client, &a), list(&client, &b))?;
+//! let diff = diff(&source, &target);
+//! let plan = diff.plan(&a, &b, delete);
+//! ```
+//!
+//! The ETag of an object uploaded in one part is the MD5 digest of its content, but the ETag of
+//! a multipart upload, such as `"9b2cf535f27731c974343645a3985328-2"`, depends on the part size too,
+//! so two copies of the same content can have different ETags. Those differences are reported
+//! separately, since they don't prove that the content differs.
+
+use std::collections::BTreeMap;
+use std::fmt;
+use std::str::FromStr;
+
+use s3::Client;
+use serde::Serialize;
+
+/// A bucket, and the prefix of the keys to compare in it.
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct Location {
+    pub bucket: String,
+    pub prefix: String,
+}
+
+impl FromStr for Location {
+    type Err = String;
+
+    /// Reads `BUCKET`, `BUCKET/PREFIX`, or `s3://BUCKET/PREFIX`.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.strip_prefix("s3://").unwrap_or(s);
+        let (bucket, prefix) = s.split_once('/').unwrap_or((s, ""));
+
+        if bucket.is_empty() {
+            return Err(format!("{} has no bucket name", s));
+        }
+
+        Ok(Location {
+            bucket: bucket.to_string(),
+            prefix: prefix.to_string(),
+        })
+    }
+}
+
+impl fmt::Display for Location {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "s3://{}/{}", self.bucket, self.prefix)
+    }
+}
+
+/// The size and ETag of an object.
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct Entry {
+    pub size: i64,
+    pub etag: String,
+}
+
+/// The objects under a location, by key relative to its prefix.
+pub type Listing = BTreeMap<String, Entry>;
+
+/// Returns whether an ETag is that of a multipart upload, which ends with the number of parts.
+pub fn is_multipart(etag: &str) -> bool {
+    etag.contains('-')
+}
+
+/// Lists every object under the location, following the continuation tokens until every page is read.
+pub async fn list(client: &Client, location: &Location) -> Result<Listing, s3::Error> {
+    let mut listing = Listing::new();
+    let mut token = None;
+
+    loop {
+        let resp = client
+            .list_objects_v2()
+            .bucket(&location.bucket)
+            .prefix(&location.prefix)
+            .set_continuation_token(token)
+            .send()
+            .await?;
+
+        for object in resp.contents.unwrap_or_default() {
+            if let Some(key) = object.key {
+                let relative = key[location.prefix.len()..].to_string();
+                // ETags are quoted.
+                let etag = object.e_tag.unwrap_or_default();
+
+                listing.insert(
+                    relative,
+                    Entry {
+                        size: object.size,
+                        etag: etag.trim_matches('"').to_ascii_lowercase(),
+                    },
+                );
+            }
+        }
+
+        token = resp.next_continuation_token;
+        if token.is_none() {
+            return Ok(listing);
+        }
+    }
+}
+
+/// A key whose objects differ, with both sides.
+#[derive(Debug, PartialEq, Serialize)]
+pub struct Mismatch {
+    pub key: String,
+    pub source: Entry,
+    pub target: Entry,
+}
+
+/// How the objects under two locations differ, by relative key.
+#[derive(Debug, Default, PartialEq, Serialize)]
+pub struct Diff {
+    /// The keys that only the source has.
+    pub only_in_source: Vec<String>,
+    /// The keys that only the target has.
+    pub only_in_target: Vec<String>,
+    /// The keys whose objects are different sizes.
+    pub size_mismatches: Vec<Mismatch>,
+    /// The keys whose objects are the same size, but whose single-part ETags differ,
+    /// so their content differs.
+    pub etag_mismatches: Vec<Mismatch>,
+    /// The keys whose objects are the same size, but whose ETags differ where at least one is
+    /// a multipart ETag, so their content might be the same.
+    pub multipart_etag_mismatches: Vec<Mismatch>,
+    /// How many keys both sides have with the same size and ETag.
+    pub identical: usize,
+}
+
+/// Compares two listings.
+pub fn diff(source: &Listing, target: &Listing) -> Diff {
+    let mut diff = Diff::default();
+
+    for (key, source_entry) in source {
+        let target_entry = match target.get(key) {
+            Some(entry) => entry,
+            None => {
+                diff.only_in_source.push(key.clone());
+                continue;
+            }
+        };
+
+        let mismatch = || Mismatch {
+            key: key.clone(),
+            source: source_entry.clone(),
+            target: target_entry.clone(),
+        };
+
+        if source_entry.size != target_entry.size {
+            diff.size_mismatches.push(mismatch());
+        } else if source_entry.etag == target_entry.etag {
+            diff.identical += 1;
+        } else if is_multipart(&source_entry.etag) || is_multipart(&target_entry.etag) {
+            diff.multipart_etag_mismatches.push(mismatch());
+        } else {
+            diff.etag_mismatches.push(mismatch());
+        }
+    }
+
+    diff.only_in_target = target
+        .keys()
+        .filter(|key| !source.contains_key(*key))
+        .cloned()
+        .collect();
+
+    diff
+}
+
+impl Diff {
+    /// Whether both sides have the same keys, sizes, and ETags.
+    pub fn is_empty(&self) -> bool {
+        self.only_in_source.is_empty()
+            && self.only_in_target.is_empty()
+            && self.size_mismatches.is_empty()
+            && self.etag_mismatches.is_empty()
+            && self.multipart_etag_mismatches.is_empty()
+    }
+
+    /// Returns the plan that makes the target match the source: copy each object that
+    /// the target is missing or has a different version of, and, if `delete` is set,
+    /// delete each object that only the target has. Objects that differ only in a multipart
+    /// ETag are copied too, since their content can't be compared without downloading them.
+    pub fn plan(&self, source: &Location, target: &Location, delete: bool) -> SyncPlan {
+        let mut actions: Vec<Action> = self
+            .only_in_source
+            .iter()
+            .map(|key| Action::Copy {
+                key: key.clone(),
+                reason: Reason::Missing,
+            })
+            .collect();
+
+        let differs = [
+            (&self.size_mismatches, Reason::Size),
+            (&self.etag_mismatches, Reason::Etag),
+            (&self.multipart_etag_mismatches, Reason::MultipartEtag),
+        ];
+        for (mismatches, reason) in differs.iter() {
+            actions.extend(mismatches.iter().map(|mismatch| Action::Copy {
+                key: mismatch.key.clone(),
+                reason: *reason,
+            }));
+        }
+
+        if delete {
+            actions.extend(
+                self.only_in_target
+                    .iter()
+                    .map(|key| Action::Delete { key: key.clone() }),
+            );
+        }
+
+        SyncPlan {
+            source: source.clone(),
+            target: target.clone(),
+            actions,
+        }
+    }
+}
+
+impl fmt::Display for Diff {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let sections = [
+            ("Only in the source", &self.only_in_source),
+            ("Only in the target", &self.only_in_target),
+        ];
+        for (title, keys) in sections.iter() {
+            if !keys.is_empty() {
+                writeln!(f, "{} ({}):", title, keys.len())?;
+                for key in keys.iter() {
+                    writeln!(f, "  {}", key)?;
+                }
+                writeln!(f)?;
+            }
+        }
+
+        let sections = [
+            ("Different sizes", &self.size_mismatches),
+            ("Different content (ETag)", &self.etag_mismatches),
+            (
+                "Different multipart ETags, content might match",
+                &self.multipart_etag_mismatches,
+            ),
+        ];
+        for (title, mismatches) in sections.iter() {
+            if !mismatches.is_empty() {
+                writeln!(f, "{} ({}):", title, mismatches.len())?;
+                for mismatch in mismatches.iter() {
+                    writeln!(
+                        f,
+                        "  {}  {} bytes {}  vs  {} bytes {}",
+                        mismatch.key,
+                        mismatch.source.size,
+                        mismatch.source.etag,
+                        mismatch.target.size,
+                        mismatch.target.etag
+                    )?;
+                }
+                writeln!(f)?;
+            }
+        }
+
+        if self.is_empty() {
+            write!(
+                f,
+                "The locations match: {} identical objects",
+                self.identical
+            )
+        } else {
+            write!(f, "{} identical objects", self.identical)
+        }
+    }
+}
+
+/// Why an object is copied.
+#[derive(Clone, Copy, Debug, PartialEq, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum Reason {
+    /// The target doesn't have the object.
+    Missing,
+    /// The target's object is a different size.
+    Size,
+    /// The target's object has different content.
+    Etag,
+    /// The target's object has a different multipart ETag, so its content might differ.
+    MultipartEtag,
+}
+
+/// A step in making the target match the source.
+#[derive(Debug, PartialEq, Serialize)]
+#[serde(tag = "action", rename_all = "lowercase")]
+pub enum Action {
+    /// Copy the object with the key, relative to the prefixes, from the source to the target.
+    Copy { key: String, reason: Reason },
+    /// Delete the object with the key, relative to the target's prefix, from the target.
+    Delete { key: String },
+}
+
+/// The actions that make the target match the source, written as JSON, such as:
+///
+/// ```json
+/// {
+///   "source": { "bucket": "photos", "prefix": "2021/" },
+///   "target": { "bucket": "photos-backup", "prefix": "2021/" },
+///   "actions": [
+///     { "action": "copy", "key": "beach.jpg", "reason": "missing" },
+///     { "action": "delete", "key": "old.jpg" }
+///   ]
+/// }
+/// ```
+#[derive(Debug, PartialEq, Serialize)]
+pub struct SyncPlan {
+    pub source: Location,
+    pub target: Location,
+    pub actions: Vec<Action>,
+}
@@ -0,0 +1,15 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0.
+ */
+
+//! Helpers shared by the Amazon S3 code examples.
+
+pub mod buckets;
+pub mod copy;
+pub mod debug;
+pub mod download;
+pub mod envelope;
+pub mod inventory;
+pub mod lifecycle;
+pub mod versions;
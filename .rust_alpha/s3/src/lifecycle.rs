@@ -0,0 +1,158 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0.
+ */
+
+//! Reads the lifecycle rules of a bucket, and describes them.
+//!
+//! A bucket's lifecycle configuration is replaced as a whole, so an example that changes one rule
+//! reads the others first, and puts them back with it:
+//!
+//! ```ignore
+//! let mut rules = get_rules(&client, &bucket).await?;
+//! rules.retain(|rule| rule.id.as_deref() != Some(&id));
+//! ```
+//!
+//! `RuleSummary` turns a rule into something that can be displayed as text or JSON.
+
+use std::fmt;
+
+use s3::model::{LifecycleRule, LifecycleRuleFilter};
+use s3::{Client, SdkError};
+use serde::Serialize;
+
+/// The error code of GetBucketLifecycleConfiguration for a bucket without any rules.
+/// It isn't modeled, so it's matched by its code.
+const NO_SUCH_LIFECYCLE_CONFIGURATION: &str = "NoSuchLifecycleConfiguration";
+
+/// Returns the lifecycle rules of a bucket, which are empty if it has none.
+pub async fn get_rules(client: &Client, bucket: &str) -> Result<Vec<LifecycleRule>, s3::Error> {
+    match client
+        .get_bucket_lifecycle_configuration()
+        .bucket(bucket)
+        .send()
+        .await
+    {
+        Ok(resp) => Ok(resp.rules.unwrap_or_default()),
+        Err(SdkError::ServiceError { err, .. })
+            if err.code() == Some(NO_SUCH_LIFECYCLE_CONFIGURATION) =>
+        {
+            Ok(Vec::new())
+        }
+        Err(e) => Err(e.into()),
+    }
+}
+
+/// When objects move to another storage class.
+#[derive(Debug, Serialize)]
+pub struct TransitionSummary {
+    pub days: i32,
+    pub storage_class: String,
+}
+
+/// A lifecycle rule, described.
+#[derive(Debug, Serialize)]
+pub struct RuleSummary {
+    pub id: String,
+    pub enabled: bool,
+    /// Which objects the rule applies to.
+    pub filter: String,
+    pub transitions: Vec<TransitionSummary>,
+    /// How many days after they're created objects are deleted.
+    pub expiration_days: Option<i32>,
+    /// How many days after they're started incomplete multipart uploads are stopped, and their parts deleted.
+    pub abort_incomplete_multipart_days: Option<i32>,
+}
+
+/// Describes which objects a rule applies to.
+fn describe_filter(rule: &LifecycleRule) -> String {
+    // Older rules have a prefix rather than a filter.
+    let filter = match (&rule.filter, &rule.prefix) {
+        (Some(filter), _) => filter,
+        (None, Some(prefix)) => return format!("prefix {:?}", prefix),
+        (None, None) => return "all objects".to_string(),
+    };
+
+    match filter {
+        LifecycleRuleFilter::Prefix(prefix) if prefix.is_empty() => "all objects".to_string(),
+        LifecycleRuleFilter::Prefix(prefix) => format!("prefix {:?}", prefix),
+        LifecycleRuleFilter::Tag(tag) => format!(
+            "tag {}={}",
+            tag.key.as_deref().unwrap_or_default(),
+            tag.value.as_deref().unwrap_or_default()
+        ),
+        LifecycleRuleFilter::And(and) => format!(
+            "prefix {:?} and {} tags",
+            and.prefix.as_deref().unwrap_or_default(),
+            and.tags.as_ref().map_or(0, Vec::len)
+        ),
+    }
+}
+
+impl From<&LifecycleRule> for RuleSummary {
+    fn from(rule: &LifecycleRule) -> Self {
+        // The SDK returns 0 for the days that aren't set.
+        let days = |days: i32| Some(days).filter(|days| *days > 0);
+
+        RuleSummary {
+            id: rule.id.clone().unwrap_or_default(),
+            enabled: rule.status.as_ref().map(|status| status.as_str()) == Some("Enabled"),
+            filter: describe_filter(rule),
+            transitions: rule
+                .transitions
+                .iter()
+                .flatten()
+                .map(|transition| TransitionSummary {
+                    days: transition.days,
+                    storage_class: transition
+                        .storage_class
+                        .as_ref()
+                        .map(|class| class.as_str().to_string())
+                        .unwrap_or_default(),
+                })
+                .collect(),
+            expiration_days: rule
+                .expiration
+                .as_ref()
+                .and_then(|expiration| days(expiration.days)),
+            abort_incomplete_multipart_days: rule
+                .abort_incomplete_multipart_upload
+                .as_ref()
+                .and_then(|abort| days(abort.days_after_initiation)),
+        }
+    }
+}
+
+impl fmt::Display for RuleSummary {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} ({}), for {}",
+            self.id,
+            if self.enabled { "enabled" } else { "disabled" },
+            self.filter
+        )?;
+
+        for transition in &self.transitions {
+            write!(
+                f,
+                "\n  After {} days, moves objects to {}",
+                transition.days, transition.storage_class
+            )?;
+        }
+
+        if let Some(days) = self.expiration_days {
+            write!(f, "\n  After {} days, deletes objects", days)?;
+        }
+
+        if let Some(days) = self.abort_incomplete_multipart_days {
+            write!(
+                f,
+                "\n  After {} days, stops incomplete multipart uploads",
+                days
+            )?;
+        }
+
+        Ok(())
+    }
+}
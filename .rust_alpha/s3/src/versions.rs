@@ -0,0 +1,111 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0.
+ */
+
+//! Lists the versions and delete markers of the objects in a bucket that has versioning enabled.
+//!
+//! ListObjectVersions returns the versions and the delete markers in separate lists, a page at a time.
+//! `list_versions` reads every page, and merges the lists, so that each key's versions are together,
+//! newest first:
+//!
+//! ```ignore
+//! let versions = list_versions(&client, &bucket, &prefix).await?;
+//! let latest = versions.iter().filter(|version| version.is_latest);
+//! ```
+
+use s3::Client;
+use serde::Serialize;
+use smithy_types::instant::Format;
+
+/// A version of an object, or a delete marker, which hides the object's earlier versions.
+#[derive(Debug, Serialize)]
+pub struct Version {
+    pub key: String,
+    pub version_id: String,
+    pub delete_marker: bool,
+    pub is_latest: bool,
+    pub size: i64,
+    pub last_modified: String,
+    #[serde(skip)]
+    pub epoch_seconds: i64,
+}
+
+/// Lists the versions and delete markers of every key that starts with the prefix,
+/// following the markers until every page is read.
+/// The versions are sorted by key, and each key's versions are newest first.
+pub async fn list_versions(
+    client: &Client,
+    bucket: &str,
+    prefix: &str,
+) -> Result<Vec<Version>, s3::Error> {
+    let mut versions = Vec::new();
+    let mut key_marker = None;
+    let mut version_id_marker = None;
+
+    loop {
+        let resp = client
+            .list_object_versions()
+            .bucket(bucket)
+            .prefix(prefix)
+            .set_key_marker(key_marker)
+            .set_version_id_marker(version_id_marker)
+            .send()
+            .await?;
+
+        for version in resp.versions.unwrap_or_default() {
+            versions.push(Version {
+                key: version.key.unwrap_or_default(),
+                version_id: version.version_id.unwrap_or_default(),
+                delete_marker: false,
+                is_latest: version.is_latest,
+                size: version.size,
+                epoch_seconds: version
+                    .last_modified
+                    .as_ref()
+                    .map(|time| time.epoch_seconds())
+                    .unwrap_or_default(),
+                last_modified: version
+                    .last_modified
+                    .map(|time| time.fmt(Format::DateTime))
+                    .unwrap_or_default(),
+            });
+        }
+
+        for marker in resp.delete_markers.unwrap_or_default() {
+            versions.push(Version {
+                key: marker.key.unwrap_or_default(),
+                version_id: marker.version_id.unwrap_or_default(),
+                delete_marker: true,
+                is_latest: marker.is_latest,
+                size: 0,
+                epoch_seconds: marker
+                    .last_modified
+                    .as_ref()
+                    .map(|time| time.epoch_seconds())
+                    .unwrap_or_default(),
+                last_modified: marker
+                    .last_modified
+                    .map(|time| time.fmt(Format::DateTime))
+                    .unwrap_or_default(),
+            });
+        }
+
+        if !resp.is_truncated {
+            break;
+        }
+
+        key_marker = resp.next_key_marker;
+        version_id_marker = resp.next_version_id_marker;
+    }
+
+    // Timestamps only have a resolution of a second, so the latest version is put first explicitly.
+    versions.sort_by(|a, b| {
+        a.key
+            .cmp(&b.key)
+            .then(b.is_latest.cmp(&a.is_latest))
+            .then(b.epoch_seconds.cmp(&a.epoch_seconds))
+    });
+
+    Ok(versions)
+}
@@ -0,0 +1,196 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0.
+ */
+
+//! Runs the Amazon S3 examples against LocalStack,
+//! and checks that objects can be uploaded, listed, downloaded, copied, and moved.
+//!
+//! Start LocalStack with the docker-compose.yml file in the parent directory, and then run:
+//!
+//! ```text
+//! cargo test -p s3-code-examples --features integration
+//! ```
+//!
+//! To use another endpoint, set `INTEGRATION_ENDPOINT_URL`.
+
+#![cfg(feature = "integration")]
+
+use std::env;
+use std::fs;
+use std::process::Command;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use serde_json::Value;
+
+/// The endpoint of LocalStack, which the tests use unless INTEGRATION_ENDPOINT_URL is set.
+const DEFAULT_ENDPOINT: &str = "http://localhost:4566";
+
+/// Returns a command that runs an example against the local endpoint, with dummy credentials,
+/// so that the tests can't reach a real AWS account.
+fn example(path: &str) -> Command {
+    let endpoint =
+        env::var("INTEGRATION_ENDPOINT_URL").unwrap_or_else(|_| DEFAULT_ENDPOINT.to_string());
+
+    let mut command = Command::new(path);
+    command
+        .env("AWS_ENDPOINT_URL", endpoint)
+        .env("AWS_DEFAULT_REGION", "us-west-2")
+        .env("AWS_ACCESS_KEY_ID", "test")
+        .env("AWS_SECRET_ACCESS_KEY", "test")
+        .env_remove("AWS_SESSION_TOKEN");
+
+    command
+}
+
+/// Runs an example with `--output json`, checks that it succeeds, and returns what it printed.
+fn run(path: &str, args: &[&str]) -> Value {
+    let output = example(path)
+        .args(args)
+        .args(&["--output", "json"])
+        .output()
+        .unwrap_or_else(|e| panic!("couldn't run {}: {}", path, e));
+
+    assert!(
+        output.status.success(),
+        "{} {:?} failed: {}",
+        path,
+        args,
+        String::from_utf8_lossy(&output.stderr)
+    );
+
+    serde_json::from_slice(&output.stdout).unwrap_or_else(|e| {
+        panic!(
+            "{} {:?} didn't print JSON ({}): {}",
+            path,
+            args,
+            e,
+            String::from_utf8_lossy(&output.stdout)
+        )
+    })
+}
+
+/// Returns a name that no other test run uses.
+fn unique(prefix: &str) -> String {
+    let nanos = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|since| since.as_nanos())
+        .unwrap_or_default();
+
+    format!("{}-{}", prefix, nanos)
+}
+
+/// Returns the keys of the objects in the bucket.
+fn keys(bucket: &str) -> Vec<String> {
+    run(env!("CARGO_BIN_EXE_list-objects"), &["-b", bucket])["objects"]
+        .as_array()
+        .cloned()
+        .unwrap_or_default()
+        .into_iter()
+        .filter_map(|object| object["key"].as_str().map(str::to_string))
+        .collect()
+}
+
+#[test]
+fn put_list_get_copy_move() {
+    let bucket = unique("integration");
+    let dir = env::temp_dir().join(unique("s3-integration"));
+    fs::create_dir_all(&dir).expect("couldn't create the temporary directory");
+
+    let upload = dir.join("upload.txt");
+    let download = dir.join("download.txt");
+    fs::write(&upload, "Hello from the integration tests").expect("couldn't write the upload");
+
+    let created = run(env!("CARGO_BIN_EXE_create-bucket"), &["-n", &bucket]);
+    assert_eq!(created["bucket"], bucket.as_str());
+
+    // A key with a space checks that copy-object encodes the copy source.
+    let uploaded = run(
+        env!("CARGO_BIN_EXE_put-object"),
+        &[
+            "-b",
+            &bucket,
+            "-f",
+            upload.to_str().unwrap_or_default(),
+            "-k",
+            "docs/hello world.txt",
+        ],
+    );
+    assert_eq!(uploaded["content_type"], "text/plain");
+    assert_eq!(keys(&bucket), vec!["docs/hello world.txt"]);
+
+    run(
+        env!("CARGO_BIN_EXE_get-object"),
+        &[
+            "-b",
+            &bucket,
+            "-k",
+            "docs/hello world.txt",
+            "-f",
+            download.to_str().unwrap_or_default(),
+        ],
+    );
+    assert_eq!(
+        fs::read(&download).expect("couldn't read the download"),
+        fs::read(&upload).expect("couldn't read the upload")
+    );
+
+    run(
+        env!("CARGO_BIN_EXE_copy-object"),
+        &[
+            "-s",
+            &bucket,
+            "-k",
+            "docs/hello world.txt",
+            "--key",
+            "copy.txt",
+        ],
+    );
+    assert_eq!(keys(&bucket), vec!["copy.txt", "docs/hello world.txt"]);
+
+    let moved = run(
+        env!("CARGO_BIN_EXE_copy-object"),
+        &[
+            "-s",
+            &bucket,
+            "-k",
+            "copy.txt",
+            "--key",
+            "moved.txt",
+            "--move",
+        ],
+    );
+    assert_eq!(moved["moved"], true);
+    assert_eq!(keys(&bucket), vec!["docs/hello world.txt", "moved.txt"]);
+
+    let _ = fs::remove_dir_all(&dir);
+}
+
+#[test]
+fn create_bucket_is_idempotent_and_put_object_checks_the_bucket() {
+    let bucket = unique("ensure");
+    let dir = env::temp_dir().join(unique("s3-ensure"));
+    fs::create_dir_all(&dir).expect("couldn't create the temporary directory");
+
+    let upload = dir.join("upload.txt");
+    fs::write(&upload, "Hello from the integration tests").expect("couldn't write the upload");
+    let file = upload.to_str().unwrap_or_default();
+
+    // Without --create-bucket, uploading to a bucket that doesn't exist fails before the upload.
+    let output = example(env!("CARGO_BIN_EXE_put-object"))
+        .args(&["-b", &bucket, "-f", file])
+        .output()
+        .expect("couldn't run put-object");
+    assert!(!output.status.success());
+
+    run(
+        env!("CARGO_BIN_EXE_put-object"),
+        &["-b", &bucket, "-f", file, "--create-bucket"],
+    );
+    assert_eq!(keys(&bucket), vec!["upload.txt"]);
+
+    let created = run(env!("CARGO_BIN_EXE_create-bucket"), &["-n", &bucket]);
+    assert_eq!(created["created"], false);
+
+    let _ = fs::remove_dir_all(&dir);
+}
@@ -7,6 +7,7 @@ use aws_types::region::ProvideRegion;
 
 use sagemaker::{Client, Config, Region};
 
+use common::profile::ProfileCredentialsProvider;
 use structopt::StructOpt;
 
 #[derive(Debug, StructOpt)]
@@ -15,6 +16,10 @@ struct Opt {
     #[structopt(short, long)]
     default_region: Option<String>,
 
+    /// The AWS profile to use from the shared credentials file
+    #[structopt(long)]
+    profile: Option<String>,
+
     /// Whether to display additional runtime information
     #[structopt(short, long)]
     verbose: bool,
@@ -26,6 +31,7 @@ struct Opt {
 /// * `[-d DEFAULT-REGION]` - The region in which the client is created.
 ///    If not supplied, uses the value of the **AWS_DEFAULT_REGION** environment variable.
 ///    If the environment variable is not set, defaults to **us-west-2**.
+/// * `[--profile PROFILE]` - The AWS profile to use from the shared credentials file.
 /// * `[-v]` - Whether to display additional information.#[tokio::main]
 #[tokio::main]
 async fn main() -> Result<(), sagemaker::Error> {
@@ -33,6 +39,7 @@ async fn main() -> Result<(), sagemaker::Error> {
 
     let Opt {
         default_region,
+        profile,
         verbose,
     } = Opt::from_args();
 
@@ -48,24 +55,38 @@ async fn main() -> Result<(), sagemaker::Error> {
         println!();
     }
 
-    let conf = Config::builder().region(region).build();
-    let client = Client::from_conf(conf);
+    let mut config = Config::builder().region(region);
+    if let Some(profile) = profile {
+        config = config.credentials_provider(ProfileCredentialsProvider::new(profile));
+    }
+    let client = Client::from_conf(config.build());
     let job_details = client.list_training_jobs().send().await?;
 
     println!("Job Name\tCreation DateTime\tDuration\tStatus");
     for j in job_details.training_job_summaries.unwrap_or_default() {
         let name = j.training_job_name.as_deref().unwrap_or_default();
-        let creation_time = j.creation_time.unwrap().to_chrono();
-        let training_end_time = j.training_end_time.unwrap().to_chrono();
+        let creation_time = match &j.creation_time {
+            Some(time) => time.to_chrono(),
+            None => continue,
+        };
+
+        // Jobs that are still running don't have an end time.
+        let duration = match &j.training_end_time {
+            Some(time) => (time.to_chrono() - creation_time).num_seconds().to_string(),
+            None => String::from("-"),
+        };
 
-        let status = j.training_job_status.unwrap();
-        let duration = training_end_time - creation_time;
+        let status = j
+            .training_job_status
+            .as_ref()
+            .map(|status| status.as_str())
+            .unwrap_or_default();
 
         println!(
-            "{}\t{}\t{}\t{:#?}",
+            "{}\t{}\t{}\t{}",
             name,
             creation_time.format("%Y-%m-%d@%H:%M:%S"),
-            duration.num_seconds(),
+            duration,
             status
         );
     }
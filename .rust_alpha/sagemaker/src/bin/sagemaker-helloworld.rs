@@ -7,6 +7,7 @@ use aws_types::region::ProvideRegion;
 
 use sagemaker::{Client, Config, Region};
 
+use common::profile::ProfileCredentialsProvider;
 use structopt::StructOpt;
 
 #[derive(Debug, StructOpt)]
@@ -15,6 +16,10 @@ struct Opt {
     #[structopt(short, long)]
     default_region: Option<String>,
 
+    /// The AWS profile to use from the shared credentials file
+    #[structopt(long)]
+    profile: Option<String>,
+
     /// Whether to display additional runtime information
     #[structopt(short, long)]
     verbose: bool,
@@ -26,6 +31,7 @@ struct Opt {
 /// * `[-d DEFAULT-REGION]` - The region in which the client is created.
 ///    If not supplied, uses the value of the **AWS_DEFAULT_REGION** environment variable.
 ///    If the environment variable is not set, defaults to **us-west-2**.
+/// * `[--profile PROFILE]` - The AWS profile to use from the shared credentials file.
 /// * `[-v]` - Whether to display additional information.
 #[tokio::main]
 async fn main() -> Result<(), sagemaker::Error> {
@@ -33,6 +39,7 @@ async fn main() -> Result<(), sagemaker::Error> {
 
     let Opt {
         default_region,
+        profile,
         verbose,
     } = Opt::from_args();
 
@@ -47,17 +54,28 @@ async fn main() -> Result<(), sagemaker::Error> {
         println!("Region:                   {:?}", &region);
     }
 
-    let conf = Config::builder().region(region).build();
-    let client = Client::from_conf(conf);
+    let mut config = Config::builder().region(region);
+    if let Some(profile) = profile {
+        config = config.credentials_provider(ProfileCredentialsProvider::new(profile));
+    }
+    let client = Client::from_conf(config.build());
     let notebooks = client.list_notebook_instances().send().await?;
 
     for n in notebooks.notebook_instances.unwrap_or_default() {
-        let n_instance_type = n.instance_type.unwrap();
-        let n_status = n.notebook_instance_status.unwrap();
+        let n_instance_type = n
+            .instance_type
+            .as_ref()
+            .map(|instance_type| instance_type.as_str())
+            .unwrap_or_default();
+        let n_status = n
+            .notebook_instance_status
+            .as_ref()
+            .map(|status| status.as_str())
+            .unwrap_or_default();
         let n_name = n.notebook_instance_name.as_deref().unwrap_or_default();
 
         println!(
-            "Notebook Name : {}, Notebook Status : {:#?}, Notebook Instance Type : {:#?}",
+            "Notebook Name : {}, Notebook Status : {}, Notebook Instance Type : {}",
             n_name, n_status, n_instance_type
         );
     }
@@ -0,0 +1,384 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0.
+ */
+
+use std::error::Error;
+use std::time::{Duration, Instant};
+
+use aws_types::region::{ProvideRegion, Region};
+
+use cloudwatch::model::{
+    ComparisonOperator, HistoryItemType, MetricDatum, StandardUnit, StateValue, Statistic,
+};
+
+use common::profile::ProfileCredentialsProvider;
+use common::shutdown::Shutdown;
+use structopt::StructOpt;
+use tokio::time::sleep;
+use tracing_subscriber::fmt::format::FmtSpan;
+use tracing_subscriber::fmt::SubscriberBuilder;
+
+/// The period, in seconds, over which the alarm evaluates the metric.
+/// Periods shorter than a minute require a high-resolution metric.
+const PERIOD: i32 = 10;
+
+/// How often the scenario publishes a value and checks the alarm's state.
+const PUBLISH_INTERVAL: Duration = Duration::from_secs(5);
+
+/// How long to wait for the alarm to reach a state before giving up.
+const TRANSITION_TIMEOUT: Duration = Duration::from_secs(600);
+
+#[derive(Debug, StructOpt)]
+struct Opt {
+    /// The region. Overrides environment variable AWS_DEFAULT_REGION.
+    #[structopt(short, long)]
+    default_region: Option<String>,
+
+    /// The email address that the alarm notifies
+    #[structopt(short, long)]
+    email: String,
+
+    /// The name of the alarm and of the topic that it notifies
+    #[structopt(short, long, default_value = "cloudwatch-alarm")]
+    name: String,
+
+    /// The namespace of the custom metric
+    #[structopt(long, default_value = "RustExamples")]
+    namespace: String,
+
+    /// The name of the custom metric
+    #[structopt(short, long, default_value = "PendingJobs")]
+    metric: String,
+
+    /// The value above which the alarm goes off
+    #[structopt(short, long, default_value = "10")]
+    threshold: f64,
+
+    /// Whether to keep the alarm and topic
+    #[structopt(short, long)]
+    keep: bool,
+
+    /// The AWS profile to use from the shared credentials file
+    #[structopt(long)]
+    profile: Option<String>,
+
+    /// Whether to display additional runtime information
+    #[structopt(short, long)]
+    verbose: bool,
+}
+
+/// The AWS clients used by the scenario.
+struct Clients {
+    cloudwatch: cloudwatch::Client,
+    sns: sns::Client,
+}
+
+/// The resources that the scenario has created, so that they can be deleted even if a later step fails.
+#[derive(Default)]
+struct Resources {
+    topic_arn: Option<String>,
+    alarm: bool,
+}
+
+/// Publishes one value of the custom metric.
+/// A storage resolution of one second makes it a high-resolution metric.
+async fn put_value(
+    client: &cloudwatch::Client,
+    opt: &Opt,
+    value: f64,
+) -> Result<(), cloudwatch::Error> {
+    client
+        .put_metric_data()
+        .namespace(&opt.namespace)
+        .metric_data(
+            MetricDatum::builder()
+                .metric_name(&opt.metric)
+                .value(value)
+                .unit(StandardUnit::Count)
+                .storage_resolution(1)
+                .build(),
+        )
+        .send()
+        .await?;
+
+    Ok(())
+}
+
+/// Returns the alarm's current state and the reason that CloudWatch gives for it.
+async fn alarm_state(
+    client: &cloudwatch::Client,
+    name: &str,
+) -> Result<(Option<StateValue>, String), cloudwatch::Error> {
+    let alarm = client
+        .describe_alarms()
+        .alarm_names(name)
+        .send()
+        .await?
+        .metric_alarms
+        .unwrap_or_default()
+        .into_iter()
+        .next();
+
+    Ok(match alarm {
+        Some(alarm) => (alarm.state_value, alarm.state_reason.unwrap_or_default()),
+        None => (None, String::new()),
+    })
+}
+
+/// Keeps publishing the value until the alarm reaches the target state,
+/// and displays each state that the alarm passes through on the way.
+async fn drive_to(
+    client: &cloudwatch::Client,
+    opt: &Opt,
+    value: f64,
+    target: StateValue,
+) -> Result<(), Box<dyn Error>> {
+    let start = Instant::now();
+    let mut last = None;
+
+    loop {
+        put_value(client, opt, value).await?;
+
+        let (state, reason) = alarm_state(client, &opt.name).await?;
+
+        if state != last {
+            println!(
+                "  {:>3}s {}: {}",
+                start.elapsed().as_secs(),
+                state
+                    .as_ref()
+                    .map(|state| state.as_str())
+                    .unwrap_or("UNKNOWN"),
+                reason
+            );
+            last = state;
+        }
+
+        if last.as_ref() == Some(&target) {
+            return Ok(());
+        }
+
+        if start.elapsed() > TRANSITION_TIMEOUT {
+            return Err(format!(
+                "the alarm did not reach {} within {} seconds",
+                target.as_str(),
+                TRANSITION_TIMEOUT.as_secs()
+            )
+            .into());
+        }
+
+        sleep(PUBLISH_INTERVAL).await;
+    }
+}
+
+/// Displays the alarm's state changes, oldest first.
+async fn show_history(client: &cloudwatch::Client, name: &str) -> Result<(), cloudwatch::Error> {
+    let mut items = client
+        .describe_alarm_history()
+        .alarm_name(name)
+        .history_item_type(HistoryItemType::StateUpdate)
+        .send()
+        .await?
+        .alarm_history_items
+        .unwrap_or_default();
+
+    // CloudWatch returns the newest change first.
+    items.reverse();
+
+    let first = items
+        .first()
+        .and_then(|item| item.timestamp.as_ref())
+        .map(|timestamp| timestamp.epoch_seconds())
+        .unwrap_or_default();
+
+    for item in items {
+        let offset = item
+            .timestamp
+            .map(|timestamp| timestamp.epoch_seconds() - first)
+            .unwrap_or_default();
+
+        println!(
+            "  +{:>3}s {}",
+            offset,
+            item.history_summary.unwrap_or_default()
+        );
+    }
+
+    Ok(())
+}
+
+/// Runs the steps of the scenario, stopping at the first error.
+async fn run(
+    clients: &Clients,
+    opt: &Opt,
+    resources: &mut Resources,
+) -> Result<(), Box<dyn Error>> {
+    println!("1. Creating topic {}", &opt.name);
+    let topic_arn = clients
+        .sns
+        .create_topic()
+        .name(&opt.name)
+        .send()
+        .await?
+        .topic_arn
+        .ok_or("Amazon SNS did not return a topic ARN")?;
+    resources.topic_arn = Some(topic_arn.clone());
+
+    println!("2. Subscribing {} to the topic", &opt.email);
+    clients
+        .sns
+        .subscribe()
+        .topic_arn(&topic_arn)
+        .protocol("email")
+        .endpoint(&opt.email)
+        .send()
+        .await?;
+    println!("  Confirm the subscription in the email that Amazon SNS sends, to receive the notifications");
+
+    // The alarm notifies the topic both when it goes off and when it recovers.
+    println!(
+        "3. Creating alarm {} on {}/{} > {}",
+        &opt.name, &opt.namespace, &opt.metric, opt.threshold
+    );
+    clients
+        .cloudwatch
+        .put_metric_alarm()
+        .alarm_name(&opt.name)
+        .alarm_description("Created by the AWS SDK for Rust cloudwatch-alarm scenario")
+        .namespace(&opt.namespace)
+        .metric_name(&opt.metric)
+        .statistic(Statistic::Maximum)
+        .period(PERIOD)
+        .evaluation_periods(1)
+        .threshold(opt.threshold)
+        .comparison_operator(ComparisonOperator::GreaterThanThreshold)
+        .alarm_actions(&topic_arn)
+        .ok_actions(&topic_arn)
+        .send()
+        .await?;
+    resources.alarm = true;
+
+    let low = opt.threshold / 2.0;
+    let high = opt.threshold * 2.0;
+
+    println!("4. Publishing {} until the alarm is OK", low);
+    drive_to(&clients.cloudwatch, opt, low, StateValue::Ok).await?;
+
+    println!("5. Publishing {} until the alarm goes off", high);
+    drive_to(&clients.cloudwatch, opt, high, StateValue::Alarm).await?;
+
+    println!("6. Publishing {} until the alarm recovers", low);
+    drive_to(&clients.cloudwatch, opt, low, StateValue::Ok).await?;
+
+    println!("7. Alarm history");
+    show_history(&clients.cloudwatch, &opt.name).await?;
+
+    Ok(())
+}
+
+/// Deletes the alarm and the topic. Deleting the topic also deletes its subscription.
+/// Keeps going after an error, so that as much as possible is cleaned up.
+async fn tear_down(clients: &Clients, opt: &Opt, resources: &Resources) {
+    if resources.alarm {
+        if let Err(e) = clients
+            .cloudwatch
+            .delete_alarms()
+            .alarm_names(&opt.name)
+            .send()
+            .await
+        {
+            eprintln!("Got an error deleting alarm {}: {}", &opt.name, e);
+        }
+    }
+
+    if let Some(topic_arn) = &resources.topic_arn {
+        if let Err(e) = clients.sns.delete_topic().topic_arn(topic_arn).send().await {
+            eprintln!("Got an error deleting topic {}: {}", topic_arn, e);
+        }
+    }
+}
+
+/// Monitors a custom metric with an Amazon CloudWatch alarm that sends email through Amazon SNS.
+/// The scenario creates an Amazon SNS topic with an email subscription,
+/// and an alarm on a custom metric that notifies the topic.
+/// It then publishes values of the metric below, above, and again below the threshold,
+/// and displays each state that the alarm goes through.
+/// Finally, it deletes the alarm and the topic.
+/// # Arguments
+///
+/// * `-e EMAIL` - The email address that the alarm notifies.
+/// * `[-n NAME]` - The name of the alarm and of the topic.
+///   If not supplied, defaults to **cloudwatch-alarm**.
+/// * `[--namespace NAMESPACE]` - The namespace of the custom metric.
+///   If not supplied, defaults to **RustExamples**.
+/// * `[-m METRIC]` - The name of the custom metric.
+///   If not supplied, defaults to **PendingJobs**.
+/// * `[-t THRESHOLD]` - The value above which the alarm goes off.
+///   If not supplied, defaults to **10**.
+/// * `[-k]` - Whether to keep the alarm and topic.
+/// * `[-d DEFAULT-REGION]` - The region in which the clients are created.
+///    If not supplied, uses the value of the **AWS_DEFAULT_REGION** environment variable.
+///    If the environment variable is not set, defaults to **us-west-2**.
+/// * `[--profile PROFILE]` - The AWS profile to use from the shared credentials file.
+/// * `[-v]` - Whether to display additional information.
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn Error>> {
+    let opt = Opt::from_args();
+
+    if opt.threshold <= 0.0 {
+        return Err("the threshold must be greater than zero".into());
+    }
+
+    let region = opt
+        .default_region
+        .as_ref()
+        .map(|region| Region::new(region.clone()))
+        .or_else(|| aws_types::region::default_provider().region())
+        .unwrap_or_else(|| Region::new("us-west-2"));
+
+    if opt.verbose {
+        println!("CloudWatch client version: {}", cloudwatch::PKG_VERSION);
+        println!("SNS client version:        {}", sns::PKG_VERSION);
+        println!("Region:                    {:?}", &region);
+        println!("Email:                     {}", &opt.email);
+        println!("Name:                      {}", &opt.name);
+        println!("Namespace:                 {}", &opt.namespace);
+        println!("Metric:                    {}", &opt.metric);
+        println!("Threshold:                 {}", opt.threshold);
+        println!();
+
+        SubscriberBuilder::default()
+            .with_env_filter("info")
+            .with_span_events(FmtSpan::CLOSE)
+            .init();
+    }
+
+    let mut cloudwatch_config = cloudwatch::Config::builder().region(region.clone());
+    let mut sns_config = sns::Config::builder().region(region);
+    if let Some(profile) = &opt.profile {
+        cloudwatch_config =
+            cloudwatch_config.credentials_provider(ProfileCredentialsProvider::new(profile));
+        sns_config = sns_config.credentials_provider(ProfileCredentialsProvider::new(profile));
+    }
+
+    let clients = Clients {
+        cloudwatch: cloudwatch::Client::from_conf(cloudwatch_config.build()),
+        sns: sns::Client::from_conf(sns_config.build()),
+    };
+
+    let mut resources = Resources::default();
+
+    // Ctrl-C stops the scenario wherever it is, and still cleans up what it created.
+    let shutdown = Shutdown::listen();
+    let result = shutdown.run(run(&clients, &opt, &mut resources)).await;
+
+    if opt.keep {
+        println!("Keeping alarm and topic {}", &opt.name);
+    } else {
+        println!("8. Deleting the alarm and topic");
+        tear_down(&clients, &opt, &resources).await;
+    }
+
+    result
+}
@@ -0,0 +1,579 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0.
+ */
+
+use std::error::Error;
+use std::time::Duration;
+
+use aws_types::region::{ProvideRegion, Region};
+
+use s3::model::{BucketLocationConstraint, CreateBucketConfiguration};
+use s3::{ByteStream, SdkError};
+
+use common::profile::ProfileCredentialsProvider;
+use common::shutdown::Shutdown;
+use serde_json::json;
+use structopt::StructOpt;
+use tokio::time::sleep;
+use tracing_subscriber::fmt::format::FmtSpan;
+use tracing_subscriber::fmt::SubscriberBuilder;
+
+/// The object that the scenario puts in the bucket, and then reads from the other account.
+const SAMPLE_KEY: &str = "cross-account.txt";
+
+/// The name of the inline policy that the scenario puts on the role.
+const POLICY_NAME: &str = "read-bucket";
+
+/// How many times to retry a request that should be allowed, before deciding that it's denied.
+/// Changes to roles and policies can take several seconds to take effect.
+const MAX_ATTEMPTS: u32 = 6;
+
+#[derive(Debug, StructOpt)]
+struct Opt {
+    /// The region. Overrides environment variable AWS_DEFAULT_REGION.
+    #[structopt(short, long)]
+    default_region: Option<String>,
+
+    /// The name of the bucket to create in the owner's account
+    #[structopt(short, long)]
+    bucket: String,
+
+    /// The name of the role to create in the owner's account
+    #[structopt(short, long, default_value = "cross-account-reader")]
+    name: String,
+
+    /// The profile in the shared credentials file for the account that owns the bucket
+    #[structopt(long)]
+    owner_profile: String,
+
+    /// Whether to keep the bucket, its policy, and the role
+    #[structopt(short, long)]
+    keep: bool,
+
+    /// The AWS profile to use from the shared credentials file for the account that reads the bucket
+    #[structopt(long)]
+    profile: Option<String>,
+
+    /// Whether to display additional runtime information
+    #[structopt(short, long)]
+    verbose: bool,
+}
+
+/// The AWS clients used by the scenario.
+/// The owner clients use the account that owns the bucket, and the reader clients use the other account.
+struct Clients {
+    owner_iam: iam::Client,
+    owner_s3: s3::Client,
+    owner_sts: sts::Client,
+    reader_s3: s3::Client,
+    reader_sts: sts::Client,
+}
+
+/// The resources that the scenario has created, so that they can be deleted even if a later step fails.
+#[derive(Default)]
+struct Resources {
+    bucket: bool,
+    object: bool,
+    bucket_policy: bool,
+    role: bool,
+    role_policy: bool,
+}
+
+/// What happened when the scenario tried to read the bucket.
+enum Access {
+    /// The keys in the bucket, and the contents of the sample object.
+    Allowed(Vec<String>, String),
+    /// The operation that Amazon S3 denied.
+    Denied(&'static str),
+}
+
+/// Returns the ID of the account that the client's credentials belong to.
+async fn account_id(client: &sts::Client) -> Result<String, Box<dyn Error>> {
+    let account = client
+        .get_caller_identity()
+        .send()
+        .await?
+        .account
+        .ok_or("AWS STS did not return the account ID")?;
+
+    Ok(account)
+}
+
+/// Returns a policy that allows listing and reading the objects in the bucket.
+/// The same statements work as a role's permission policy, or, with a principal, as a bucket policy.
+fn read_statements(bucket: &str, principal: Option<&str>) -> String {
+    let mut list = json!({
+        "Effect": "Allow",
+        "Action": "s3:ListBucket",
+        "Resource": format!("arn:aws:s3:::{}", bucket)
+    });
+    let mut get = json!({
+        "Effect": "Allow",
+        "Action": "s3:GetObject",
+        "Resource": format!("arn:aws:s3:::{}/*", bucket)
+    });
+
+    if let Some(principal) = principal {
+        list["Principal"] = json!({ "AWS": principal });
+        get["Principal"] = json!({ "AWS": principal });
+    }
+
+    json!({
+        "Version": "2012-10-17",
+        "Statement": [list, get]
+    })
+    .to_string()
+}
+
+/// Lists the bucket and reads the sample object.
+/// Returns which operation was denied, rather than an error, if Amazon S3 denies access.
+async fn read_bucket(client: &s3::Client, bucket: &str) -> Result<Access, Box<dyn Error>> {
+    let keys = match client.list_objects_v2().bucket(bucket).send().await {
+        Ok(resp) => resp
+            .contents
+            .unwrap_or_default()
+            .into_iter()
+            .filter_map(|object| object.key)
+            .collect(),
+        Err(SdkError::ServiceError { err, .. }) if err.code() == Some("AccessDenied") => {
+            return Ok(Access::Denied("ListObjectsV2"))
+        }
+        Err(e) => return Err(e.into()),
+    };
+
+    let data = match client
+        .get_object()
+        .bucket(bucket)
+        .key(SAMPLE_KEY)
+        .send()
+        .await
+    {
+        Ok(resp) => resp.body.collect().await?.into_bytes(),
+        Err(SdkError::ServiceError { err, .. }) if err.code() == Some("AccessDenied") => {
+            return Ok(Access::Denied("GetObject"))
+        }
+        Err(e) => return Err(e.into()),
+    };
+
+    Ok(Access::Allowed(
+        keys,
+        String::from_utf8_lossy(&data).into_owned(),
+    ))
+}
+
+/// Tries to read the bucket, expecting to be denied, and explains why.
+async fn expect_denied(
+    client: &s3::Client,
+    bucket: &str,
+    reason: &str,
+) -> Result<(), Box<dyn Error>> {
+    match read_bucket(client, bucket).await? {
+        Access::Denied(operation) => println!("  {} was denied, because {}", operation, reason),
+        Access::Allowed(keys, _) => println!(
+            "  Unexpectedly allowed to list {} objects. Another policy must grant access",
+            keys.len()
+        ),
+    }
+
+    Ok(())
+}
+
+/// Tries to read the bucket, expecting to be allowed, and displays what it read.
+/// Retries with an increasing delay while the policy change propagates,
+/// and returns an error that names the missing policy if access is still denied.
+async fn expect_allowed(
+    client: &s3::Client,
+    bucket: &str,
+    missing: &str,
+) -> Result<(), Box<dyn Error>> {
+    let mut delay = Duration::from_secs(2);
+
+    for attempt in 1..=MAX_ATTEMPTS {
+        match read_bucket(client, bucket).await? {
+            Access::Allowed(keys, contents) => {
+                println!("  Objects: {}", keys.join(", "));
+                println!("  {}: {}", SAMPLE_KEY, contents.trim_end());
+                return Ok(());
+            }
+            Access::Denied(operation) if attempt < MAX_ATTEMPTS => {
+                println!("  {} is still denied, retrying", operation);
+                sleep(delay).await;
+                delay *= 2;
+            }
+            Access::Denied(operation) => {
+                return Err(format!(
+                    "{} on bucket {} is still denied: {}",
+                    operation, bucket, missing
+                )
+                .into())
+            }
+        }
+    }
+
+    Err(format!("bucket {} is still denied: {}", bucket, missing).into())
+}
+
+/// Assumes the role with the reader's credentials, and returns an Amazon S3 client that uses the role.
+/// Retries with an increasing delay while the new role propagates.
+async fn assume_role(
+    client: &sts::Client,
+    region: Region,
+    role_arn: &str,
+    reader_account: &str,
+) -> Result<s3::Client, Box<dyn Error>> {
+    let mut delay = Duration::from_secs(2);
+
+    for attempt in 1..=MAX_ATTEMPTS {
+        match client
+            .assume_role()
+            .role_arn(role_arn)
+            .role_session_name("cross-account")
+            .send()
+            .await
+        {
+            Ok(resp) => {
+                let credentials = resp
+                    .credentials
+                    .ok_or("AssumeRole did not return credentials")?;
+
+                // Temporary credentials are only valid together with their session token.
+                let config = s3::Config::builder()
+                    .region(region)
+                    .credentials_provider(s3::Credentials::from_keys(
+                        credentials
+                            .access_key_id
+                            .ok_or("the credentials have no access key ID")?,
+                        credentials
+                            .secret_access_key
+                            .ok_or("the credentials have no secret access key")?,
+                        credentials.session_token,
+                    ))
+                    .build();
+
+                return Ok(s3::Client::from_conf(config));
+            }
+            Err(sts::SdkError::ServiceError { err, .. })
+                if err.code() == Some("AccessDenied") && attempt < MAX_ATTEMPTS =>
+            {
+                println!("  The role can't be assumed yet, retrying");
+                sleep(delay).await;
+                delay *= 2;
+            }
+            Err(sts::SdkError::ServiceError { err, .. }) if err.code() == Some("AccessDenied") => {
+                return Err(format!(
+                    "could not assume role {}: its trust policy must trust account {}, \
+                     and your identity in that account must be allowed to call sts:AssumeRole on it",
+                    role_arn, reader_account
+                )
+                .into())
+            }
+            Err(e) => return Err(e.into()),
+        }
+    }
+
+    Err(format!("could not assume role {}", role_arn).into())
+}
+
+/// Runs the steps of the scenario, stopping at the first error.
+async fn run(
+    clients: &Clients,
+    region: Region,
+    opt: &Opt,
+    resources: &mut Resources,
+) -> Result<(), Box<dyn Error>> {
+    println!("1. Identifying the accounts");
+    let owner_account = account_id(&clients.owner_sts).await?;
+    let reader_account = account_id(&clients.reader_sts).await?;
+    println!("  Owner:  {}", &owner_account);
+    println!("  Reader: {}", &reader_account);
+
+    if owner_account == reader_account {
+        return Err(
+            "the owner profile and the reader profile must be in different accounts".into(),
+        );
+    }
+
+    let reader_principal = format!("arn:aws:iam::{}:root", &reader_account);
+
+    println!("2. Creating bucket {} in the owner's account", &opt.bucket);
+    let mut request = clients.owner_s3.create_bucket().bucket(&opt.bucket);
+
+    // us-east-1 is the default location, and rejects an explicit location constraint.
+    if region.as_ref() != "us-east-1" {
+        request = request.create_bucket_configuration(
+            CreateBucketConfiguration::builder()
+                .location_constraint(BucketLocationConstraint::from(region.as_ref()))
+                .build(),
+        );
+    }
+
+    request.send().await?;
+    resources.bucket = true;
+
+    clients
+        .owner_s3
+        .put_object()
+        .bucket(&opt.bucket)
+        .key(SAMPLE_KEY)
+        .body(ByteStream::from(
+            format!("Written by account {}", &owner_account).into_bytes(),
+        ))
+        .send()
+        .await?;
+    resources.object = true;
+
+    println!("3. Reading the bucket directly from the reader's account");
+    expect_denied(
+        &clients.reader_s3,
+        &opt.bucket,
+        "the bucket policy doesn't grant the reader's account access",
+    )
+    .await?;
+
+    println!(
+        "4. Creating role {} in the owner's account, which account {} can assume",
+        &opt.name, &reader_account
+    );
+    let trust_policy = json!({
+        "Version": "2012-10-17",
+        "Statement": [{
+            "Effect": "Allow",
+            "Principal": { "AWS": &reader_principal },
+            "Action": "sts:AssumeRole"
+        }]
+    });
+
+    let role_arn = clients
+        .owner_iam
+        .create_role()
+        .role_name(&opt.name)
+        .assume_role_policy_document(trust_policy.to_string())
+        .send()
+        .await?
+        .role
+        .and_then(|role| role.arn)
+        .ok_or("IAM did not return the role ARN")?;
+    resources.role = true;
+    println!("  {}", &role_arn);
+
+    println!("5. Reading the bucket as the role, before the role has a permission policy");
+    let role_s3 = assume_role(
+        &clients.reader_sts,
+        region.clone(),
+        &role_arn,
+        &reader_account,
+    )
+    .await?;
+    expect_denied(
+        &role_s3,
+        &opt.bucket,
+        "the role's permission policy doesn't allow it",
+    )
+    .await?;
+
+    println!("6. Allowing the role to read the bucket, with a role policy");
+    clients
+        .owner_iam
+        .put_role_policy()
+        .role_name(&opt.name)
+        .policy_name(POLICY_NAME)
+        .policy_document(read_statements(&opt.bucket, None))
+        .send()
+        .await?;
+    resources.role_policy = true;
+
+    // The role and the bucket are in the same account, so the role policy alone is enough.
+    expect_allowed(
+        &role_s3,
+        &opt.bucket,
+        "the role's permission policy must allow s3:ListBucket and s3:GetObject",
+    )
+    .await?;
+
+    println!("7. Allowing the reader's account to read the bucket, with a bucket policy");
+    clients
+        .owner_s3
+        .put_bucket_policy()
+        .bucket(&opt.bucket)
+        .policy(read_statements(&opt.bucket, Some(&reader_principal)))
+        .send()
+        .await?;
+    resources.bucket_policy = true;
+
+    // Across accounts, both sides have to allow access:
+    // the bucket policy trusts the reader's account, and the reader's own IAM policies
+    // must still allow the reader's identity to read the bucket.
+    expect_allowed(
+        &clients.reader_s3,
+        &opt.bucket,
+        "the bucket policy must grant the reader's account access, \
+         and the reader's IAM policies must allow s3:ListBucket and s3:GetObject on the bucket",
+    )
+    .await?;
+
+    Ok(())
+}
+
+/// Deletes the bucket policy, the object, the bucket, the role policy, and the role.
+/// Keeps going after an error, so that as much as possible is cleaned up.
+async fn tear_down(clients: &Clients, opt: &Opt, resources: &Resources) {
+    if resources.bucket_policy {
+        if let Err(e) = clients
+            .owner_s3
+            .delete_bucket_policy()
+            .bucket(&opt.bucket)
+            .send()
+            .await
+        {
+            eprintln!("Got an error deleting the policy of {}: {}", &opt.bucket, e);
+        }
+    }
+
+    if resources.object {
+        if let Err(e) = clients
+            .owner_s3
+            .delete_object()
+            .bucket(&opt.bucket)
+            .key(SAMPLE_KEY)
+            .send()
+            .await
+        {
+            eprintln!("Got an error deleting {}: {}", SAMPLE_KEY, e);
+        }
+    }
+
+    if resources.bucket {
+        if let Err(e) = clients
+            .owner_s3
+            .delete_bucket()
+            .bucket(&opt.bucket)
+            .send()
+            .await
+        {
+            eprintln!("Got an error deleting bucket {}: {}", &opt.bucket, e);
+        }
+    }
+
+    if resources.role_policy {
+        if let Err(e) = clients
+            .owner_iam
+            .delete_role_policy()
+            .role_name(&opt.name)
+            .policy_name(POLICY_NAME)
+            .send()
+            .await
+        {
+            eprintln!("Got an error deleting the policy of {}: {}", &opt.name, e);
+        }
+    }
+
+    if resources.role {
+        if let Err(e) = clients
+            .owner_iam
+            .delete_role()
+            .role_name(&opt.name)
+            .send()
+            .await
+        {
+            eprintln!("Got an error deleting role {}: {}", &opt.name, e);
+        }
+    }
+}
+
+/// Reads an Amazon S3 bucket that belongs to another AWS account, in the two ways that S3 allows.
+/// The scenario uses two profiles: one for the account that owns the bucket, and one for the account that reads it.
+/// It creates a bucket and a role in the owner's account, and shows that the reader is denied
+/// until either the role's permission policy or the bucket policy grants access.
+/// Finally, it deletes the bucket and the role.
+/// # Arguments
+///
+/// * `-b BUCKET` - The name of the bucket to create in the owner's account.
+/// * `--owner-profile OWNER-PROFILE` - The profile for the account that owns the bucket.
+/// * `[-n NAME]` - The name of the role to create in the owner's account.
+///   If not supplied, defaults to **cross-account-reader**.
+/// * `[-k]` - Whether to keep the bucket, its policy, and the role.
+/// * `[-d DEFAULT-REGION]` - The region in which the clients are created.
+///    If not supplied, uses the value of the **AWS_DEFAULT_REGION** environment variable.
+///    If the environment variable is not set, defaults to **us-west-2**.
+/// * `[--profile PROFILE]` - The AWS profile to use from the shared credentials file for the reader's account.
+/// * `[-v]` - Whether to display additional information.
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn Error>> {
+    let opt = Opt::from_args();
+
+    let region = opt
+        .default_region
+        .as_ref()
+        .map(|region| Region::new(region.clone()))
+        .or_else(|| aws_types::region::default_provider().region())
+        .unwrap_or_else(|| Region::new("us-west-2"));
+
+    if opt.verbose {
+        println!("IAM client version: {}", iam::PKG_VERSION);
+        println!("S3 client version:  {}", s3::PKG_VERSION);
+        println!("STS client version: {}", sts::PKG_VERSION);
+        println!("Region:             {:?}", &region);
+        println!("Bucket:             {}", &opt.bucket);
+        println!("Role:               {}", &opt.name);
+        println!("Owner profile:      {}", &opt.owner_profile);
+        println!("Reader profile:     {:?}", &opt.profile);
+        println!();
+
+        SubscriberBuilder::default()
+            .with_env_filter("info")
+            .with_span_events(FmtSpan::CLOSE)
+            .init();
+    }
+
+    let owner = ProfileCredentialsProvider::new(opt.owner_profile.clone());
+
+    let mut reader_s3_config = s3::Config::builder().region(region.clone());
+    let mut reader_sts_config = sts::Config::builder().region(region.clone());
+    if let Some(profile) = &opt.profile {
+        reader_s3_config =
+            reader_s3_config.credentials_provider(ProfileCredentialsProvider::new(profile));
+        reader_sts_config =
+            reader_sts_config.credentials_provider(ProfileCredentialsProvider::new(profile));
+    }
+
+    let clients = Clients {
+        owner_iam: iam::Client::from_conf(
+            iam::Config::builder()
+                .region(region.clone())
+                .credentials_provider(owner.clone())
+                .build(),
+        ),
+        owner_s3: s3::Client::from_conf(
+            s3::Config::builder()
+                .region(region.clone())
+                .credentials_provider(owner.clone())
+                .build(),
+        ),
+        owner_sts: sts::Client::from_conf(
+            sts::Config::builder()
+                .region(region.clone())
+                .credentials_provider(owner)
+                .build(),
+        ),
+        reader_s3: s3::Client::from_conf(reader_s3_config.build()),
+        reader_sts: sts::Client::from_conf(reader_sts_config.build()),
+    };
+
+    let mut resources = Resources::default();
+
+    // Ctrl-C stops the scenario wherever it is, and still cleans up what it created.
+    let shutdown = Shutdown::listen();
+    let result = shutdown
+        .run(run(&clients, region, &opt, &mut resources))
+        .await;
+
+    if opt.keep {
+        println!("Keeping bucket {} and role {}", &opt.bucket, &opt.name);
+    } else {
+        println!("8. Deleting the bucket and the role");
+        tear_down(&clients, &opt, &resources).await;
+    }
+
+    result
+}
@@ -0,0 +1,661 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0.
+ */
+
+use std::collections::HashMap;
+use std::error::Error;
+use std::time::Duration;
+
+use aws_types::region::{ProvideRegion, Region};
+
+use dynamodb::error::BatchWriteItemError;
+use dynamodb::model::{AttributeValue, PutRequest, ScalarAttributeType, WriteRequest};
+use dynamodb::SdkError;
+use s3::ByteStream;
+
+use common::profile::ProfileCredentialsProvider;
+use common::shutdown::Shutdown;
+use serde_json::Value;
+use structopt::StructOpt;
+use tokio::time::sleep;
+use tracing_subscriber::fmt::format::FmtSpan;
+use tracing_subscriber::fmt::SubscriberBuilder;
+
+/// The most items that BatchWriteItem accepts in one call.
+const BATCH_SIZE: usize = 25;
+
+/// How many times to resend items that DynamoDB didn't process.
+const MAX_RETRIES: u32 = 5;
+
+#[derive(Debug, StructOpt)]
+struct Opt {
+    /// The region. Overrides environment variable AWS_DEFAULT_REGION.
+    #[structopt(short, long)]
+    default_region: Option<String>,
+
+    /// The bucket that receives the CSV files
+    #[structopt(short, long)]
+    bucket: String,
+
+    /// The prefix to watch for new CSV files
+    #[structopt(short, long, default_value = "incoming/")]
+    prefix: String,
+
+    /// The prefix under which rows that can't be loaded are written
+    #[structopt(short, long, default_value = "errors/")]
+    errors_prefix: String,
+
+    /// The URL of the queue that receives the bucket's event notifications
+    #[structopt(short, long)]
+    queue_url: String,
+
+    /// The table to load the rows into
+    #[structopt(short, long)]
+    table: String,
+
+    /// Whether to stop once the queue is empty, instead of waiting for more files
+    #[structopt(short, long)]
+    once: bool,
+
+    /// The AWS profile to use from the shared credentials file
+    #[structopt(long)]
+    profile: Option<String>,
+
+    /// Whether to display additional runtime information
+    #[structopt(short, long)]
+    verbose: bool,
+}
+
+/// The AWS clients used by the scenario.
+struct Clients {
+    dynamodb: dynamodb::Client,
+    s3: s3::Client,
+    sqs: sqs::Client,
+}
+
+/// A key attribute of the table.
+struct KeyAttribute {
+    name: String,
+    /// Whether the attribute is a number, rather than a string.
+    number: bool,
+}
+
+/// A row that couldn't be loaded, and why.
+struct ErrorRow {
+    fields: Vec<String>,
+    reason: String,
+}
+
+/// The result of loading one CSV file.
+#[derive(Default)]
+struct LoadSummary {
+    loaded: usize,
+    errors: Vec<ErrorRow>,
+}
+
+/// Decodes an object key from an S3 event notification.
+/// Keys are URL-encoded, with spaces sent as +.
+fn decode_key(key: &str) -> String {
+    let bytes = key.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+
+    while i < bytes.len() {
+        match bytes[i] {
+            b'+' => decoded.push(b' '),
+            b'%' if i + 2 < bytes.len() => {
+                let hex = std::str::from_utf8(&bytes[i + 1..i + 3]).unwrap_or("");
+                match u8::from_str_radix(hex, 16) {
+                    Ok(b) => {
+                        decoded.push(b);
+                        i += 2;
+                    }
+                    Err(_) => decoded.push(b'%'),
+                }
+            }
+            b => decoded.push(b),
+        }
+        i += 1;
+    }
+
+    String::from_utf8_lossy(&decoded).into_owned()
+}
+
+/// Returns the bucket and key of each object named in an S3 event notification.
+/// The test event that S3 sends when notifications are first configured has no records.
+fn objects_in_event(body: &str) -> Result<Vec<(String, String)>, serde_json::Error> {
+    let event: Value = serde_json::from_str(body)?;
+
+    Ok(event["Records"]
+        .as_array()
+        .map(|records| {
+            records
+                .iter()
+                .filter(|record| {
+                    record["eventName"]
+                        .as_str()
+                        .map_or(false, |name| name.starts_with("ObjectCreated:"))
+                })
+                .filter_map(|record| {
+                    let bucket = record["s3"]["bucket"]["name"].as_str()?;
+                    let key = record["s3"]["object"]["key"].as_str()?;
+                    Some((bucket.to_string(), decode_key(key)))
+                })
+                .collect()
+        })
+        .unwrap_or_default())
+}
+
+/// Converts a CSV field into an attribute value.
+/// Fields that look like numbers are stored as numbers; everything else is stored as a string.
+fn field_to_attribute(field: &str) -> AttributeValue {
+    if !field.is_empty() && field.parse::<f64>().map_or(false, |n| n.is_finite()) {
+        AttributeValue::N(field.to_string())
+    } else {
+        AttributeValue::S(field.to_string())
+    }
+}
+
+/// Returns the key attributes of the table, which must be strings or numbers.
+async fn key_attributes(
+    client: &dynamodb::Client,
+    table: &str,
+) -> Result<Vec<KeyAttribute>, Box<dyn Error>> {
+    let description = client
+        .describe_table()
+        .table_name(table)
+        .send()
+        .await?
+        .table
+        .ok_or("DynamoDB did not return the table description")?;
+
+    let definitions = description.attribute_definitions.unwrap_or_default();
+    let mut keys = Vec::new();
+
+    for element in description.key_schema.unwrap_or_default() {
+        let name = element.attribute_name.unwrap_or_default();
+        let attribute_type = definitions
+            .iter()
+            .find(|definition| definition.attribute_name.as_deref() == Some(name.as_str()))
+            .and_then(|definition| definition.attribute_type.clone());
+
+        let number = match attribute_type {
+            Some(ScalarAttributeType::S) => false,
+            Some(ScalarAttributeType::N) => true,
+            _ => {
+                return Err(format!(
+                    "key attribute {} of table {} must be a string or a number",
+                    name, table
+                )
+                .into())
+            }
+        };
+
+        keys.push(KeyAttribute { name, number });
+    }
+
+    Ok(keys)
+}
+
+/// Converts a row into an item. Key fields get the key attribute's type, and empty fields are left out.
+/// A row whose key is empty, or isn't a number when it should be, is an error,
+/// since DynamoDB would reject the whole batch it was in.
+fn row_to_item(
+    headers: &[String],
+    fields: &[String],
+    keys: &[KeyAttribute],
+) -> Result<HashMap<String, AttributeValue>, String> {
+    let mut item: HashMap<String, AttributeValue> = headers
+        .iter()
+        .zip(fields)
+        .filter(|(_, field)| !field.is_empty())
+        .map(|(name, field)| (name.clone(), field_to_attribute(field)))
+        .collect();
+
+    for key in keys {
+        let field = headers
+            .iter()
+            .position(|name| *name == key.name)
+            .map_or("", |i| fields[i].as_str());
+
+        if field.is_empty() {
+            return Err(format!("key attribute {} is missing or empty", key.name));
+        }
+
+        let value = if key.number {
+            match field_to_attribute(field) {
+                number @ AttributeValue::N(_) => number,
+                _ => return Err(format!("key attribute {} must be a number", key.name)),
+            }
+        } else {
+            AttributeValue::S(field.to_string())
+        };
+
+        item.insert(key.name.clone(), value);
+    }
+
+    Ok(item)
+}
+
+/// Returns the fields of an item, in the order of the headers.
+fn item_fields(headers: &[String], item: &HashMap<String, AttributeValue>) -> Vec<String> {
+    headers
+        .iter()
+        .map(|name| match item.get(name) {
+            Some(AttributeValue::S(s)) | Some(AttributeValue::N(s)) => s.clone(),
+            _ => String::new(),
+        })
+        .collect()
+}
+
+/// Writes a batch of items, resending any that DynamoDB didn't process.
+/// Returns the items that still weren't written after the last retry.
+async fn write_batch(
+    client: &dynamodb::Client,
+    table: &str,
+    items: Vec<HashMap<String, AttributeValue>>,
+) -> Result<Vec<HashMap<String, AttributeValue>>, SdkError<BatchWriteItemError>> {
+    let mut requests: Vec<WriteRequest> = items
+        .into_iter()
+        .map(|item| {
+            WriteRequest::builder()
+                .put_request(PutRequest::builder().set_item(Some(item)).build())
+                .build()
+        })
+        .collect();
+
+    for attempt in 0..=MAX_RETRIES {
+        if attempt > 0 {
+            sleep(Duration::from_millis(100 * 2u64.pow(attempt))).await;
+        }
+
+        let mut request_items = HashMap::new();
+        request_items.insert(table.to_string(), requests);
+
+        let resp = client
+            .batch_write_item()
+            .set_request_items(Some(request_items))
+            .send()
+            .await?;
+
+        requests = resp
+            .unprocessed_items
+            .and_then(|mut unprocessed| unprocessed.remove(table))
+            .unwrap_or_default();
+
+        if requests.is_empty() {
+            return Ok(Vec::new());
+        }
+    }
+
+    Ok(requests
+        .into_iter()
+        .filter_map(|request| request.put_request.and_then(|put| put.item))
+        .collect())
+}
+
+/// Parses a CSV file and loads its rows into the table.
+/// The first row holds the attribute names.
+/// Rows with the wrong number of fields, a bad key, or that DynamoDB rejects, are returned as errors.
+async fn load_csv(
+    client: &dynamodb::Client,
+    table: &str,
+    keys: &[KeyAttribute],
+    data: &[u8],
+) -> Result<(Vec<String>, LoadSummary), Box<dyn Error>> {
+    let mut reader = csv::ReaderBuilder::new().flexible(true).from_reader(data);
+    let headers: Vec<String> = reader.headers()?.iter().map(String::from).collect();
+
+    let mut summary = LoadSummary::default();
+    let mut batch: Vec<HashMap<String, AttributeValue>> = Vec::with_capacity(BATCH_SIZE);
+
+    for record in reader.records() {
+        let record = match record {
+            Ok(record) => record,
+            Err(e) => {
+                summary.errors.push(ErrorRow {
+                    fields: Vec::new(),
+                    reason: e.to_string(),
+                });
+                continue;
+            }
+        };
+
+        let fields: Vec<String> = record.iter().map(String::from).collect();
+
+        if fields.len() != headers.len() {
+            summary.errors.push(ErrorRow {
+                reason: format!("expected {} fields, got {}", headers.len(), fields.len()),
+                fields,
+            });
+            continue;
+        }
+
+        let item = match row_to_item(&headers, &fields, keys) {
+            Ok(item) => item,
+            Err(reason) => {
+                summary.errors.push(ErrorRow { fields, reason });
+                continue;
+            }
+        };
+
+        // DynamoDB rejects a batch that puts the same key twice, so the batch is written first,
+        // and the later row replaces the earlier one, as it would if the rows were written one at a time.
+        if batch.iter().any(|pending| {
+            keys.iter()
+                .all(|key| pending.get(&key.name) == item.get(&key.name))
+        }) {
+            flush(client, table, &headers, &mut batch, &mut summary).await?;
+        }
+
+        batch.push(item);
+
+        if batch.len() == BATCH_SIZE {
+            flush(client, table, &headers, &mut batch, &mut summary).await?;
+        }
+    }
+
+    flush(client, table, &headers, &mut batch, &mut summary).await?;
+
+    Ok((headers, summary))
+}
+
+/// Writes the pending batch and records the outcome in the summary.
+async fn flush(
+    client: &dynamodb::Client,
+    table: &str,
+    headers: &[String],
+    batch: &mut Vec<HashMap<String, AttributeValue>>,
+    summary: &mut LoadSummary,
+) -> Result<(), dynamodb::Error> {
+    if batch.is_empty() {
+        return Ok(());
+    }
+
+    let items = batch.split_off(0);
+    let count = items.len();
+
+    let unprocessed = match write_batch(client, table, items.clone()).await {
+        Ok(unprocessed) => unprocessed,
+        // One item that DynamoDB rejects fails the whole batch, so each item is written on its own
+        // to find out which.
+        Err(SdkError::ServiceError { err, .. }) if err.code() == Some("ValidationException") => {
+            return put_each(client, table, headers, items, summary).await;
+        }
+        Err(e) => return Err(e.into()),
+    };
+
+    summary.loaded += count - unprocessed.len();
+
+    for item in unprocessed {
+        summary.errors.push(ErrorRow {
+            fields: item_fields(headers, &item),
+            reason: String::from("not processed after retries"),
+        });
+    }
+
+    Ok(())
+}
+
+/// Writes each item with PutItem, and records the items that DynamoDB rejects in the summary.
+async fn put_each(
+    client: &dynamodb::Client,
+    table: &str,
+    headers: &[String],
+    items: Vec<HashMap<String, AttributeValue>>,
+    summary: &mut LoadSummary,
+) -> Result<(), dynamodb::Error> {
+    for item in items {
+        let result = client
+            .put_item()
+            .table_name(table)
+            .set_item(Some(item.clone()))
+            .send()
+            .await;
+
+        match result {
+            Ok(_) => summary.loaded += 1,
+            Err(SdkError::ServiceError { err, .. })
+                if err.code() == Some("ValidationException") =>
+            {
+                summary.errors.push(ErrorRow {
+                    fields: item_fields(headers, &item),
+                    reason: err.to_string(),
+                });
+            }
+            Err(e) => return Err(e.into()),
+        }
+    }
+
+    Ok(())
+}
+
+/// Writes the rows that couldn't be loaded to the errors prefix, as CSV with an extra error column.
+async fn write_errors(
+    client: &s3::Client,
+    bucket: &str,
+    key: &str,
+    headers: &[String],
+    errors: &[ErrorRow],
+) -> Result<(), Box<dyn Error>> {
+    let mut writer = csv::WriterBuilder::new().flexible(true).from_writer(vec![]);
+
+    writer.write_record(headers.iter().map(String::as_str).chain(Some("error")))?;
+
+    for row in errors {
+        writer.write_record(
+            row.fields
+                .iter()
+                .map(String::as_str)
+                .chain(Some(row.reason.as_str())),
+        )?;
+    }
+
+    let data = writer.into_inner()?;
+
+    client
+        .put_object()
+        .bucket(bucket)
+        .key(key)
+        .content_type("text/csv")
+        .body(ByteStream::from(data))
+        .send()
+        .await?;
+
+    Ok(())
+}
+
+/// Downloads one CSV file and loads it into the table.
+async fn ingest(
+    clients: &Clients,
+    opt: &Opt,
+    keys: &[KeyAttribute],
+    bucket: &str,
+    key: &str,
+) -> Result<(), Box<dyn Error>> {
+    println!("Loading s3://{}/{}", bucket, key);
+
+    let resp = clients
+        .s3
+        .get_object()
+        .bucket(bucket)
+        .key(key)
+        .send()
+        .await?;
+    let data = resp.body.collect().await?.into_bytes();
+
+    let (headers, summary) = load_csv(&clients.dynamodb, &opt.table, keys, &data).await?;
+
+    println!("  Loaded {} rows into {}", summary.loaded, &opt.table);
+
+    if !summary.errors.is_empty() {
+        let name = key.strip_prefix(opt.prefix.as_str()).unwrap_or(key);
+        let errors_key = format!("{}{}", &opt.errors_prefix, name);
+
+        write_errors(&clients.s3, bucket, &errors_key, &headers, &summary.errors).await?;
+
+        println!(
+            "  Wrote {} rows that couldn't be loaded to s3://{}/{}",
+            summary.errors.len(),
+            bucket,
+            errors_key
+        );
+    }
+
+    Ok(())
+}
+
+/// Receives event notifications from the queue and loads each new CSV file.
+/// A message is deleted only after all of its files are loaded,
+/// so a file that fails is retried when the message becomes visible again.
+/// Ctrl-C stops the loop after the messages already received are processed, so no file is left half loaded.
+async fn run(
+    clients: &Clients,
+    opt: &Opt,
+    keys: &[KeyAttribute],
+    shutdown: &Shutdown,
+) -> Result<(), Box<dyn Error>> {
+    while !shutdown.is_requested() {
+        // Stop waiting for messages right away on Ctrl-C, rather than at the end of the long poll.
+        let resp = tokio::select! {
+            resp = clients
+                .sqs
+                .receive_message()
+                .queue_url(&opt.queue_url)
+                .max_number_of_messages(10)
+                .wait_time_seconds(20)
+                .send() => resp?,
+            _ = shutdown.requested() => break,
+        };
+
+        let messages = resp.messages.unwrap_or_default();
+
+        if messages.is_empty() {
+            if opt.once {
+                return Ok(());
+            }
+            continue;
+        }
+
+        for message in messages {
+            let body = message.body.unwrap_or_default();
+
+            let objects = match objects_in_event(&body) {
+                Ok(objects) => objects,
+                Err(e) => {
+                    eprintln!("Skipping a message that isn't an S3 event: {}", e);
+                    continue;
+                }
+            };
+
+            let mut ok = true;
+
+            for (bucket, key) in objects {
+                if bucket != opt.bucket
+                    || !key.starts_with(&opt.prefix)
+                    || !key.to_ascii_lowercase().ends_with(".csv")
+                {
+                    continue;
+                }
+
+                if let Err(e) = ingest(clients, opt, keys, &bucket, &key).await {
+                    eprintln!("Got an error loading s3://{}/{}: {}", bucket, key, e);
+                    ok = false;
+                }
+            }
+
+            if ok {
+                if let Some(receipt_handle) = message.receipt_handle {
+                    clients
+                        .sqs
+                        .delete_message()
+                        .queue_url(&opt.queue_url)
+                        .receipt_handle(receipt_handle)
+                        .send()
+                        .await?;
+                }
+            }
+        }
+    }
+
+    println!("Stopped");
+
+    Ok(())
+}
+
+/// Loads CSV files into an Amazon DynamoDB table as they are uploaded to an Amazon S3 bucket.
+/// The bucket must send ObjectCreated event notifications to an Amazon SQS queue.
+/// Rows that can't be loaded are written, with the reason, to a CSV file under the errors prefix.
+/// # Arguments
+///
+/// * `-b BUCKET` - The bucket that receives the CSV files.
+/// * `-q QUEUE-URL` - The URL of the queue that receives the bucket's event notifications.
+/// * `-t TABLE` - The table to load the rows into.
+/// * `[-p PREFIX]` - The prefix to watch for new CSV files.
+///   If not supplied, defaults to **incoming/**.
+/// * `[-e ERRORS-PREFIX]` - The prefix under which rows that can't be loaded are written.
+///   If not supplied, defaults to **errors/**.
+/// * `[-o]` - Whether to stop once the queue is empty.
+/// * `[-d DEFAULT-REGION]` - The region in which the clients are created.
+///    If not supplied, uses the value of the **AWS_DEFAULT_REGION** environment variable.
+///    If the environment variable is not set, defaults to **us-west-2**.
+/// * `[--profile PROFILE]` - The AWS profile to use from the shared credentials file.
+/// * `[-v]` - Whether to display additional information.
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn Error>> {
+    let opt = Opt::from_args();
+
+    let region = opt
+        .default_region
+        .as_ref()
+        .map(|region| Region::new(region.clone()))
+        .or_else(|| aws_types::region::default_provider().region())
+        .unwrap_or_else(|| Region::new("us-west-2"));
+
+    if opt.verbose {
+        println!("DynamoDB client version: {}", dynamodb::PKG_VERSION);
+        println!("S3 client version:       {}", s3::PKG_VERSION);
+        println!("SQS client version:      {}", sqs::PKG_VERSION);
+        println!("Region:                  {:?}", &region);
+        println!("Bucket:                  {}", &opt.bucket);
+        println!("Prefix:                  {}", &opt.prefix);
+        println!("Errors prefix:           {}", &opt.errors_prefix);
+        println!("Queue URL:               {}", &opt.queue_url);
+        println!("Table:                   {}", &opt.table);
+        println!();
+
+        SubscriberBuilder::default()
+            .with_env_filter("info")
+            .with_span_events(FmtSpan::CLOSE)
+            .init();
+    }
+
+    let mut dynamodb_config = dynamodb::Config::builder().region(region.clone());
+    let mut s3_config = s3::Config::builder().region(region.clone());
+    let mut sqs_config = sqs::Config::builder().region(region);
+    if let Some(profile) = &opt.profile {
+        dynamodb_config =
+            dynamodb_config.credentials_provider(ProfileCredentialsProvider::new(profile));
+        s3_config = s3_config.credentials_provider(ProfileCredentialsProvider::new(profile));
+        sqs_config = sqs_config.credentials_provider(ProfileCredentialsProvider::new(profile));
+    }
+
+    let clients = Clients {
+        dynamodb: dynamodb::Client::from_conf(dynamodb_config.build()),
+        s3: s3::Client::from_conf(s3_config.build()),
+        sqs: sqs::Client::from_conf(sqs_config.build()),
+    };
+
+    // The key is checked in each row, so that one bad row can't make DynamoDB reject the rows batched with it.
+    let keys = key_attributes(&clients.dynamodb, &opt.table).await?;
+
+    let shutdown = Shutdown::listen();
+
+    println!(
+        "Waiting for CSV files under s3://{}/{}. Press Ctrl-C to stop",
+        &opt.bucket, &opt.prefix
+    );
+
+    run(&clients, &opt, &keys, &shutdown).await
+}
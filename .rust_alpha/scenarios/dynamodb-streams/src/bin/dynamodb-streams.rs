@@ -0,0 +1,508 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0.
+ */
+
+use std::collections::HashSet;
+use std::error::Error;
+use std::io::{Cursor, Write};
+use std::time::{Duration, Instant};
+
+use aws_types::region::{ProvideRegion, Region};
+
+use cloudwatchlogs::error::FilterLogEventsErrorKind;
+use cloudwatchlogs::SdkError;
+use dynamodb::model::{
+    AttributeDefinition, AttributeValue, BillingMode, KeySchemaElement, KeyType,
+    ScalarAttributeType, StreamSpecification, StreamViewType,
+};
+use dynamodb_code_examples::waiters::wait_for_table_active;
+use lambda::model::{EventSourcePosition, FunctionCode, Runtime};
+use lambda::Blob;
+
+use common::profile::ProfileCredentialsProvider;
+use common::shutdown::Shutdown;
+use serde_json::json;
+use structopt::StructOpt;
+use tokio::time::sleep;
+use tracing_subscriber::fmt::format::FmtSpan;
+use tracing_subscriber::fmt::SubscriberBuilder;
+use zip::write::FileOptions;
+use zip::ZipWriter;
+
+/// The source of the function that logs the changes to the table.
+const PRINT_CHANGES_SOURCE: &str = include_str!("functions/print_changes.py");
+
+/// The managed policy that lets a function read a DynamoDB stream and write its logs.
+const DYNAMODB_EXECUTION_POLICY: &str =
+    "arn:aws:iam::aws:policy/service-role/AWSLambdaDynamoDBExecutionRole";
+
+/// The prefix of the log lines in which the function reports a change.
+const CHANGE_PREFIX: &str = "CHANGE ";
+
+/// How long to wait for the new table to become active.
+const TABLE_TIMEOUT: Duration = Duration::from_secs(120);
+
+/// How long to wait for all of the changes to show up in the function's log.
+const TAIL_TIMEOUT: Duration = Duration::from_secs(300);
+
+#[derive(Debug, StructOpt)]
+struct Opt {
+    /// The region. Overrides environment variable AWS_DEFAULT_REGION.
+    #[structopt(short, long)]
+    default_region: Option<String>,
+
+    /// The name of the table, and the prefix of the names of the function and role
+    #[structopt(short, long, default_value = "dynamodb-streams")]
+    name: String,
+
+    /// Whether to keep the table, function, and role
+    #[structopt(short, long)]
+    keep: bool,
+
+    /// The AWS profile to use from the shared credentials file
+    #[structopt(long)]
+    profile: Option<String>,
+
+    /// Whether to display additional runtime information
+    #[structopt(short, long)]
+    verbose: bool,
+}
+
+/// The AWS clients used by the scenario.
+struct Clients {
+    cloudwatchlogs: cloudwatchlogs::Client,
+    dynamodb: dynamodb::Client,
+    iam: iam::Client,
+    lambda: lambda::Client,
+}
+
+/// The resources that the scenario has created, so that they can be deleted even if a later step fails.
+#[derive(Default)]
+struct Resources {
+    table: bool,
+    role: Option<String>,
+    function: Option<String>,
+    mapping_uuid: Option<String>,
+}
+
+/// Returns a deployment package containing a single Python file named index.py.
+fn zip_source(source: &str) -> Result<Vec<u8>, Box<dyn Error>> {
+    let mut zip = ZipWriter::new(Cursor::new(Vec::new()));
+    zip.start_file("index.py", FileOptions::default())?;
+    zip.write_all(source.as_bytes())?;
+
+    Ok(zip.finish()?.into_inner())
+}
+
+/// Returns the name of the log group that AWS Lambda writes the function's log to.
+fn log_group(function: &str) -> String {
+    format!("/aws/lambda/{}", function)
+}
+
+/// Creates a table with a stream that records both the old and the new version of each changed item,
+/// and returns the stream's ARN.
+async fn create_table(client: &dynamodb::Client, table: &str) -> Result<String, Box<dyn Error>> {
+    let stream_arn = client
+        .create_table()
+        .table_name(table)
+        .key_schema(
+            KeySchemaElement::builder()
+                .attribute_name("id")
+                .key_type(KeyType::Hash)
+                .build(),
+        )
+        .attribute_definitions(
+            AttributeDefinition::builder()
+                .attribute_name("id")
+                .attribute_type(ScalarAttributeType::S)
+                .build(),
+        )
+        .billing_mode(BillingMode::PayPerRequest)
+        .stream_specification(
+            StreamSpecification::builder()
+                .stream_enabled(true)
+                .stream_view_type(StreamViewType::NewAndOldImages)
+                .build(),
+        )
+        .send()
+        .await?
+        .table_description
+        .and_then(|table| table.latest_stream_arn)
+        .ok_or("DynamoDB did not return the stream ARN")?;
+
+    Ok(stream_arn)
+}
+
+/// Creates a role that AWS Lambda can assume to read the stream, and returns its ARN.
+async fn create_role(client: &iam::Client, name: &str) -> Result<String, Box<dyn Error>> {
+    let trust_policy = json!({
+        "Version": "2012-10-17",
+        "Statement": [{
+            "Effect": "Allow",
+            "Principal": { "Service": "lambda.amazonaws.com" },
+            "Action": "sts:AssumeRole"
+        }]
+    });
+
+    let arn = client
+        .create_role()
+        .role_name(name)
+        .assume_role_policy_document(trust_policy.to_string())
+        .send()
+        .await?
+        .role
+        .and_then(|role| role.arn)
+        .ok_or("IAM did not return the role ARN")?;
+
+    client
+        .attach_role_policy()
+        .role_name(name)
+        .policy_arn(DYNAMODB_EXECUTION_POLICY)
+        .send()
+        .await?;
+
+    Ok(arn)
+}
+
+/// Waits until the event source mapping is enabled, which is when AWS Lambda starts reading the stream.
+async fn wait_for_mapping(client: &lambda::Client, uuid: &str) -> Result<(), lambda::Error> {
+    loop {
+        let state = client
+            .get_event_source_mapping()
+            .uuid(uuid)
+            .send()
+            .await?
+            .state
+            .unwrap_or_default();
+
+        if state == "Enabled" {
+            return Ok(());
+        }
+
+        println!("  Mapping state: {}", state);
+        sleep(Duration::from_secs(5)).await;
+    }
+}
+
+/// Makes each kind of change to the table: inserts, a modification, and a removal.
+/// Returns the number of changes, which is the number of stream records to expect.
+async fn write_items(client: &dynamodb::Client, table: &str) -> Result<usize, dynamodb::Error> {
+    let mut changes = 0;
+
+    for id in &["item-1", "item-2", "item-3"] {
+        client
+            .put_item()
+            .table_name(table)
+            .item("id", AttributeValue::S(id.to_string()))
+            .item("status", AttributeValue::S("new".to_string()))
+            .send()
+            .await?;
+        println!("  Put {}", id);
+        changes += 1;
+    }
+
+    client
+        .update_item()
+        .table_name(table)
+        .key("id", AttributeValue::S("item-1".to_string()))
+        .update_expression("SET #status = :status")
+        .expression_attribute_names("#status", "status")
+        .expression_attribute_values(":status", AttributeValue::S("shipped".to_string()))
+        .send()
+        .await?;
+    println!("  Updated item-1");
+    changes += 1;
+
+    client
+        .delete_item()
+        .table_name(table)
+        .key("id", AttributeValue::S("item-2".to_string()))
+        .send()
+        .await?;
+    println!("  Deleted item-2");
+    changes += 1;
+
+    Ok(changes)
+}
+
+/// Displays the changes that the function logs, as they arrive,
+/// until it has seen the expected number of them.
+async fn tail_changes(
+    client: &cloudwatchlogs::Client,
+    log_group: &str,
+    expected: usize,
+) -> Result<(), Box<dyn Error>> {
+    let start = Instant::now();
+    let mut seen = HashSet::new();
+
+    while seen.len() < expected {
+        if start.elapsed() > TAIL_TIMEOUT {
+            return Err(format!(
+                "only {} of {} changes arrived within {} seconds",
+                seen.len(),
+                expected,
+                TAIL_TIMEOUT.as_secs()
+            )
+            .into());
+        }
+
+        sleep(Duration::from_secs(5)).await;
+
+        let mut token = None;
+
+        loop {
+            let resp = match client
+                .filter_log_events()
+                .log_group_name(log_group)
+                .filter_pattern("CHANGE")
+                .set_next_token(token)
+                .send()
+                .await
+            {
+                Ok(resp) => resp,
+                // The log group doesn't exist until the function first runs.
+                Err(SdkError::ServiceError { err, .. })
+                    if matches!(err.kind, FilterLogEventsErrorKind::ResourceNotFoundError(_)) =>
+                {
+                    break
+                }
+                Err(e) => return Err(e.into()),
+            };
+
+            for event in resp.events.unwrap_or_default() {
+                let id = event.event_id.unwrap_or_default();
+                let message = event.message.unwrap_or_default();
+
+                if let Some(change) = message.trim().strip_prefix(CHANGE_PREFIX) {
+                    if seen.insert(id) {
+                        println!("  {:>3}s {}", start.elapsed().as_secs(), change);
+                    }
+                }
+            }
+
+            token = resp.next_token;
+            if token.is_none() {
+                break;
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Runs the steps of the scenario, stopping at the first error.
+async fn run(
+    clients: &Clients,
+    opt: &Opt,
+    resources: &mut Resources,
+) -> Result<(), Box<dyn Error>> {
+    println!("1. Creating table {} with a stream", &opt.name);
+    let stream_arn = create_table(&clients.dynamodb, &opt.name).await?;
+    resources.table = true;
+    wait_for_table_active(&clients.dynamodb, &opt.name, TABLE_TIMEOUT).await?;
+    println!("  {}", &stream_arn);
+
+    println!("2. Creating role");
+    let role = format!("{}-lambda", &opt.name);
+    let role_arn = create_role(&clients.iam, &role).await?;
+    resources.role = Some(role);
+
+    // New roles take a few seconds before AWS Lambda can assume them.
+    sleep(Duration::from_secs(10)).await;
+
+    let function = format!("{}-print-changes", &opt.name);
+    println!("3. Deploying function {}", &function);
+    clients
+        .lambda
+        .create_function()
+        .function_name(&function)
+        .runtime(Runtime::Python38)
+        .handler("index.handler")
+        .role(&role_arn)
+        .code(
+            FunctionCode::builder()
+                .zip_file(Blob::new(zip_source(PRINT_CHANGES_SOURCE)?))
+                .build(),
+        )
+        .send()
+        .await?;
+    resources.function = Some(function.clone());
+
+    // Starting at the trim horizon means the function also gets changes
+    // made before the mapping was enabled.
+    println!("4. Connecting the stream to the function");
+    let uuid = clients
+        .lambda
+        .create_event_source_mapping()
+        .event_source_arn(&stream_arn)
+        .function_name(&function)
+        .starting_position(EventSourcePosition::TrimHorizon)
+        .batch_size(10)
+        .send()
+        .await?
+        .uuid
+        .ok_or("AWS Lambda did not return the mapping UUID")?;
+    resources.mapping_uuid = Some(uuid.clone());
+    wait_for_mapping(&clients.lambda, &uuid).await?;
+
+    println!("5. Changing items in the table");
+    let expected = write_items(&clients.dynamodb, &opt.name).await?;
+
+    println!(
+        "6. Waiting for the function to log the {} changes",
+        expected
+    );
+    tail_changes(&clients.cloudwatchlogs, &log_group(&function), expected).await?;
+
+    Ok(())
+}
+
+/// Deletes the mapping, function, log group, role, and table.
+/// Keeps going after an error, so that as much as possible is cleaned up.
+async fn tear_down(clients: &Clients, opt: &Opt, resources: &Resources) {
+    if let Some(uuid) = &resources.mapping_uuid {
+        if let Err(e) = clients
+            .lambda
+            .delete_event_source_mapping()
+            .uuid(uuid)
+            .send()
+            .await
+        {
+            eprintln!("Got an error deleting event source mapping {}: {}", uuid, e);
+        }
+    }
+
+    if let Some(function) = &resources.function {
+        if let Err(e) = clients
+            .lambda
+            .delete_function()
+            .function_name(function)
+            .send()
+            .await
+        {
+            eprintln!("Got an error deleting function {}: {}", function, e);
+        }
+
+        // AWS Lambda doesn't delete the log group with the function.
+        // It might not exist if the function never ran, so don't report an error.
+        let _ = clients
+            .cloudwatchlogs
+            .delete_log_group()
+            .log_group_name(log_group(function))
+            .send()
+            .await;
+    }
+
+    // IAM won't delete a role that still has policies.
+    if let Some(role) = &resources.role {
+        if let Err(e) = clients
+            .iam
+            .detach_role_policy()
+            .role_name(role)
+            .policy_arn(DYNAMODB_EXECUTION_POLICY)
+            .send()
+            .await
+        {
+            eprintln!("Got an error detaching the policy from {}: {}", role, e);
+        }
+
+        if let Err(e) = clients.iam.delete_role().role_name(role).send().await {
+            eprintln!("Got an error deleting role {}: {}", role, e);
+        }
+    }
+
+    if resources.table {
+        if let Err(e) = clients
+            .dynamodb
+            .delete_table()
+            .table_name(&opt.name)
+            .send()
+            .await
+        {
+            eprintln!("Got an error deleting table {}: {}", &opt.name, e);
+        }
+    }
+}
+
+/// Processes the changes to an Amazon DynamoDB table with an AWS Lambda function.
+/// The scenario creates a table with a stream, deploys a function that logs each change,
+/// and connects the stream to the function with an event source mapping.
+/// It then inserts, modifies, and removes items,
+/// and tails the function's log in Amazon CloudWatch Logs to show the changes arriving.
+/// Finally, it deletes everything it created.
+/// # Arguments
+///
+/// * `[-n NAME]` - The name of the table, and the prefix of the names of the function and role.
+///   If not supplied, defaults to **dynamodb-streams**.
+/// * `[-k]` - Whether to keep the table, function, and role.
+/// * `[-d DEFAULT-REGION]` - The region in which the clients are created.
+///    If not supplied, uses the value of the **AWS_DEFAULT_REGION** environment variable.
+///    If the environment variable is not set, defaults to **us-west-2**.
+/// * `[--profile PROFILE]` - The AWS profile to use from the shared credentials file.
+/// * `[-v]` - Whether to display additional information.
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn Error>> {
+    let opt = Opt::from_args();
+
+    let region = opt
+        .default_region
+        .as_ref()
+        .map(|region| Region::new(region.clone()))
+        .or_else(|| aws_types::region::default_provider().region())
+        .unwrap_or_else(|| Region::new("us-west-2"));
+
+    if opt.verbose {
+        println!(
+            "CloudWatch Logs client version: {}",
+            cloudwatchlogs::PKG_VERSION
+        );
+        println!("DynamoDB client version:        {}", dynamodb::PKG_VERSION);
+        println!("IAM client version:             {}", iam::PKG_VERSION);
+        println!("Lambda client version:          {}", lambda::PKG_VERSION);
+        println!("Region:                         {:?}", &region);
+        println!("Name:                           {}", &opt.name);
+        println!();
+
+        SubscriberBuilder::default()
+            .with_env_filter("info")
+            .with_span_events(FmtSpan::CLOSE)
+            .init();
+    }
+
+    let mut cloudwatchlogs_config = cloudwatchlogs::Config::builder().region(region.clone());
+    let mut dynamodb_config = dynamodb::Config::builder().region(region.clone());
+    let mut iam_config = iam::Config::builder().region(region.clone());
+    let mut lambda_config = lambda::Config::builder().region(region);
+    if let Some(profile) = &opt.profile {
+        cloudwatchlogs_config =
+            cloudwatchlogs_config.credentials_provider(ProfileCredentialsProvider::new(profile));
+        dynamodb_config =
+            dynamodb_config.credentials_provider(ProfileCredentialsProvider::new(profile));
+        iam_config = iam_config.credentials_provider(ProfileCredentialsProvider::new(profile));
+        lambda_config =
+            lambda_config.credentials_provider(ProfileCredentialsProvider::new(profile));
+    }
+
+    let clients = Clients {
+        cloudwatchlogs: cloudwatchlogs::Client::from_conf(cloudwatchlogs_config.build()),
+        dynamodb: dynamodb::Client::from_conf(dynamodb_config.build()),
+        iam: iam::Client::from_conf(iam_config.build()),
+        lambda: lambda::Client::from_conf(lambda_config.build()),
+    };
+
+    let mut resources = Resources::default();
+
+    // Ctrl-C stops the scenario wherever it is, and still cleans up what it created.
+    let shutdown = Shutdown::listen();
+    let result = shutdown.run(run(&clients, &opt, &mut resources)).await;
+
+    if opt.keep {
+        println!("Keeping the resources named {}", &opt.name);
+    } else {
+        println!("7. Deleting the mapping, function, role, and table");
+        tear_down(&clients, &opt, &resources).await;
+    }
+
+    result
+}
@@ -0,0 +1,18 @@
+# Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+# SPDX-License-Identifier: Apache-2.0
+
+import json
+
+
+def handler(event, context):
+    """Logs each change to the table as a single line that starts with CHANGE."""
+    for record in event["Records"]:
+        change = {
+            "event": record["eventName"],
+            "keys": record["dynamodb"]["Keys"],
+            "old": record["dynamodb"].get("OldImage"),
+            "new": record["dynamodb"].get("NewImage"),
+        }
+        print("CHANGE " + json.dumps(change))
+
+    return {"processed": len(event["Records"])}
@@ -0,0 +1,744 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0.
+ */
+
+use std::error::Error;
+use std::fs;
+use std::io;
+use std::thread;
+use std::time::{Duration, Instant};
+
+use aws_types::region::{ProvideRegion, Region};
+
+use ec2::model::{
+    Filter, IamInstanceProfileSpecification, InstanceStateName, InstanceType, IpPermission,
+    IpRange, ResourceType, SummaryStatus, Tag, TagSpecification,
+};
+use ssm::error::GetCommandInvocationErrorKind;
+use ssm::model::{CommandInvocationStatus, InstanceInformationStringFilter, PingStatus};
+use ssm::SdkError;
+
+use common::profile::ProfileCredentialsProvider;
+use common::shutdown::Shutdown;
+use serde_json::json;
+use structopt::StructOpt;
+use tokio::sync::oneshot;
+use tokio::time::sleep;
+use tracing_subscriber::fmt::format::FmtSpan;
+use tracing_subscriber::fmt::SubscriberBuilder;
+
+/// The public parameter that holds the ID of the latest Amazon Linux 2 AMI in the region.
+const AMAZON_LINUX_PARAMETER: &str =
+    "/aws/service/ami-amazon-linux-latest/amzn2-ami-hvm-x86_64-gp2";
+
+/// The managed policy that lets AWS Systems Manager manage an instance.
+const SSM_MANAGED_INSTANCE_POLICY: &str = "arn:aws:iam::aws:policy/AmazonSSMManagedInstanceCore";
+
+/// How long to wait for the instance to pass its status checks, or to terminate.
+const INSTANCE_TIMEOUT: Duration = Duration::from_secs(600);
+
+/// How long to wait for the instance to register with AWS Systems Manager, and for the command to run.
+const COMMAND_TIMEOUT: Duration = Duration::from_secs(300);
+
+#[derive(Debug, StructOpt)]
+struct Opt {
+    /// The region. Overrides environment variable AWS_DEFAULT_REGION.
+    #[structopt(short, long)]
+    default_region: Option<String>,
+
+    /// The name of the key pair, security group, and instance
+    #[structopt(short, long, default_value = "ec2-lifecycle")]
+    name: String,
+
+    /// The instance type
+    #[structopt(short, long, default_value = "t3.micro")]
+    instance_type: String,
+
+    /// The IPv4 address range, in CIDR notation, that can connect to the instance with SSH, such as 203.0.113.25/32
+    #[structopt(short, long)]
+    ssh_cidr: String,
+
+    /// A shell command to run on the instance with AWS Systems Manager
+    #[structopt(short, long)]
+    command: Option<String>,
+
+    /// Whether to keep the instance and everything it uses
+    #[structopt(short, long)]
+    keep: bool,
+
+    /// The AWS profile to use from the shared credentials file
+    #[structopt(long)]
+    profile: Option<String>,
+
+    /// Whether to display additional runtime information
+    #[structopt(short, long)]
+    verbose: bool,
+}
+
+/// The AWS clients used by the scenario.
+struct Clients {
+    ec2: ec2::Client,
+    iam: iam::Client,
+    ssm: ssm::Client,
+}
+
+/// The resources that the scenario has created, so that they can be deleted even if a later step fails.
+#[derive(Default)]
+struct Resources {
+    key_pair: Option<String>,
+    key_file: Option<String>,
+    security_group_id: Option<String>,
+    role: Option<String>,
+    role_policy: bool,
+    instance_profile: Option<String>,
+    instance_profile_role: bool,
+    instance_id: Option<String>,
+}
+
+/// Returns the ID of the latest Amazon Linux 2 AMI.
+async fn latest_amazon_linux(client: &ssm::Client) -> Result<String, Box<dyn Error>> {
+    let ami = client
+        .get_parameter()
+        .name(AMAZON_LINUX_PARAMETER)
+        .send()
+        .await?
+        .parameter
+        .and_then(|parameter| parameter.value)
+        .ok_or("AWS Systems Manager did not return the AMI ID")?;
+
+    Ok(ami)
+}
+
+/// Saves the private key so that only the current user can read it, as SSH requires.
+fn save_key(file: &str, key_material: &str) -> io::Result<()> {
+    fs::write(file, key_material)?;
+
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        fs::set_permissions(file, fs::Permissions::from_mode(0o400))?;
+    }
+
+    Ok(())
+}
+
+/// Returns the ID of the default VPC.
+async fn default_vpc(client: &ec2::Client) -> Result<String, Box<dyn Error>> {
+    let vpc_id = client
+        .describe_vpcs()
+        .filters(Filter::builder().name("isDefault").values("true").build())
+        .send()
+        .await?
+        .vpcs
+        .unwrap_or_default()
+        .into_iter()
+        .find_map(|vpc| vpc.vpc_id)
+        .ok_or("the region has no default VPC")?;
+
+    Ok(vpc_id)
+}
+
+/// Creates a role and an instance profile that let AWS Systems Manager run commands on the instance,
+/// and returns the name of the instance profile.
+async fn create_instance_profile(
+    client: &iam::Client,
+    name: &str,
+    resources: &mut Resources,
+) -> Result<String, Box<dyn Error>> {
+    let trust_policy = json!({
+        "Version": "2012-10-17",
+        "Statement": [{
+            "Effect": "Allow",
+            "Principal": { "Service": "ec2.amazonaws.com" },
+            "Action": "sts:AssumeRole"
+        }]
+    });
+
+    client
+        .create_role()
+        .role_name(name)
+        .assume_role_policy_document(trust_policy.to_string())
+        .send()
+        .await?;
+    resources.role = Some(name.to_string());
+
+    client
+        .attach_role_policy()
+        .role_name(name)
+        .policy_arn(SSM_MANAGED_INSTANCE_POLICY)
+        .send()
+        .await?;
+    resources.role_policy = true;
+
+    client
+        .create_instance_profile()
+        .instance_profile_name(name)
+        .send()
+        .await?;
+    resources.instance_profile = Some(name.to_string());
+
+    client
+        .add_role_to_instance_profile()
+        .instance_profile_name(name)
+        .role_name(name)
+        .send()
+        .await?;
+    resources.instance_profile_role = true;
+
+    Ok(name.to_string())
+}
+
+/// Waits until the instance is running and has passed both its system and instance status checks,
+/// displaying each status as it changes.
+async fn wait_for_status_checks(
+    client: &ec2::Client,
+    instance_id: &str,
+) -> Result<(), Box<dyn Error>> {
+    let start = Instant::now();
+    let mut last = String::new();
+
+    loop {
+        let status = client
+            .describe_instance_status()
+            .instance_ids(instance_id)
+            .include_all_instances(true)
+            .send()
+            .await?
+            .instance_statuses
+            .unwrap_or_default()
+            .into_iter()
+            .next();
+
+        if let Some(status) = status {
+            let state = status.instance_state.and_then(|state| state.name);
+            let system = status.system_status.and_then(|summary| summary.status);
+            let instance = status.instance_status.and_then(|summary| summary.status);
+
+            let line = format!(
+                "state: {}, system status: {}, instance status: {}",
+                state.as_ref().map(|state| state.as_str()).unwrap_or("-"),
+                system.as_ref().map(|status| status.as_str()).unwrap_or("-"),
+                instance
+                    .as_ref()
+                    .map(|status| status.as_str())
+                    .unwrap_or("-")
+            );
+            if line != last {
+                println!("  {:>3}s {}", start.elapsed().as_secs(), line);
+                last = line;
+            }
+
+            if state == Some(InstanceStateName::Running)
+                && system == Some(SummaryStatus::Ok)
+                && instance == Some(SummaryStatus::Ok)
+            {
+                return Ok(());
+            }
+        }
+
+        if start.elapsed() > INSTANCE_TIMEOUT {
+            return Err("the instance did not pass its status checks in time".into());
+        }
+
+        sleep(Duration::from_secs(10)).await;
+    }
+}
+
+/// Displays how to connect to the instance with SSH.
+async fn show_connection(
+    client: &ec2::Client,
+    instance_id: &str,
+    key_file: &str,
+) -> Result<(), Box<dyn Error>> {
+    let instance = client
+        .describe_instances()
+        .instance_ids(instance_id)
+        .send()
+        .await?
+        .reservations
+        .unwrap_or_default()
+        .into_iter()
+        .flat_map(|reservation| reservation.instances.unwrap_or_default())
+        .next()
+        .ok_or("Amazon EC2 did not return the instance")?;
+
+    let host = instance
+        .public_dns_name
+        .filter(|name| !name.is_empty())
+        .or(instance.public_ip_address)
+        .ok_or("the instance has no public address")?;
+
+    println!("  Public address: {}", &host);
+    println!("  Connect with:   ssh -i {} ec2-user@{}", key_file, &host);
+
+    Ok(())
+}
+
+/// Runs the shell command on the instance with AWS Systems Manager, and displays its output.
+async fn run_command(
+    client: &ssm::Client,
+    instance_id: &str,
+    command: &str,
+) -> Result<(), Box<dyn Error>> {
+    let start = Instant::now();
+
+    // The SSM Agent on the instance has to register before it can receive commands.
+    loop {
+        let online = client
+            .describe_instance_information()
+            .filters(
+                InstanceInformationStringFilter::builder()
+                    .key("InstanceIds")
+                    .values(instance_id)
+                    .build(),
+            )
+            .send()
+            .await?
+            .instance_information_list
+            .unwrap_or_default()
+            .iter()
+            .any(|info| info.ping_status == Some(PingStatus::Online));
+
+        if online {
+            break;
+        }
+
+        if start.elapsed() > COMMAND_TIMEOUT {
+            return Err("the instance did not register with AWS Systems Manager in time".into());
+        }
+
+        sleep(Duration::from_secs(10)).await;
+    }
+
+    let command_id = client
+        .send_command()
+        .instance_ids(instance_id)
+        .document_name("AWS-RunShellScript")
+        .parameters("commands", vec![command.to_string()])
+        .send()
+        .await?
+        .command
+        .and_then(|command| command.command_id)
+        .ok_or("AWS Systems Manager did not return the command ID")?;
+
+    loop {
+        // The invocation isn't available until a moment after the command is sent.
+        sleep(Duration::from_secs(2)).await;
+
+        let invocation = match client
+            .get_command_invocation()
+            .command_id(&command_id)
+            .instance_id(instance_id)
+            .send()
+            .await
+        {
+            Ok(invocation) => invocation,
+            Err(SdkError::ServiceError { err, .. })
+                if matches!(
+                    err.kind,
+                    GetCommandInvocationErrorKind::InvocationDoesNotExist(_)
+                ) && start.elapsed() < COMMAND_TIMEOUT =>
+            {
+                continue;
+            }
+            Err(e) => return Err(e.into()),
+        };
+
+        match invocation.status {
+            Some(CommandInvocationStatus::Pending)
+            | Some(CommandInvocationStatus::InProgress)
+            | Some(CommandInvocationStatus::Delayed) => {}
+            status => {
+                println!(
+                    "  Status: {}",
+                    status.as_ref().map(|status| status.as_str()).unwrap_or("-")
+                );
+                for line in invocation
+                    .standard_output_content
+                    .unwrap_or_default()
+                    .lines()
+                {
+                    println!("  | {}", line);
+                }
+                for line in invocation
+                    .standard_error_content
+                    .unwrap_or_default()
+                    .lines()
+                {
+                    println!("  ! {}", line);
+                }
+
+                return Ok(());
+            }
+        }
+
+        if start.elapsed() > COMMAND_TIMEOUT {
+            return Err("the command did not finish in time".into());
+        }
+    }
+}
+
+/// Waits for the user to press Enter.
+async fn pause() -> Result<(), Box<dyn Error>> {
+    let (sender, receiver) = oneshot::channel();
+
+    // A thread of its own, rather than spawn_blocking, because the runtime waits for blocking tasks
+    // before it exits, and the read never finishes if the user presses Ctrl-C instead of Enter.
+    thread::spawn(move || {
+        let mut line = String::new();
+        let _ = sender.send(io::stdin().read_line(&mut line).map(|_| ()));
+    });
+
+    receiver.await??;
+
+    Ok(())
+}
+
+/// Runs the steps of the scenario, stopping at the first error.
+async fn run(
+    clients: &Clients,
+    opt: &Opt,
+    resources: &mut Resources,
+) -> Result<(), Box<dyn Error>> {
+    println!("1. Finding the latest Amazon Linux 2 AMI");
+    let ami = latest_amazon_linux(&clients.ssm).await?;
+    println!("  {}", &ami);
+
+    println!("2. Creating key pair {}", &opt.name);
+    let key_material = clients
+        .ec2
+        .create_key_pair()
+        .key_name(&opt.name)
+        .send()
+        .await?
+        .key_material
+        .ok_or("Amazon EC2 did not return the private key")?;
+    resources.key_pair = Some(opt.name.clone());
+
+    // Amazon EC2 doesn't keep the private key, so this is the only chance to save it.
+    let key_file = format!("{}.pem", &opt.name);
+    save_key(&key_file, &key_material)?;
+    resources.key_file = Some(key_file.clone());
+    println!("  Saved the private key in {}", &key_file);
+
+    println!(
+        "3. Creating security group {} that allows SSH from {}",
+        &opt.name, &opt.ssh_cidr
+    );
+    let vpc_id = default_vpc(&clients.ec2).await?;
+    let group_id = clients
+        .ec2
+        .create_security_group()
+        .group_name(&opt.name)
+        .description("Allows SSH to the instance that the ec2-lifecycle scenario launches")
+        .vpc_id(vpc_id)
+        .send()
+        .await?
+        .group_id
+        .ok_or("Amazon EC2 did not return the security group ID")?;
+    resources.security_group_id = Some(group_id.clone());
+
+    clients
+        .ec2
+        .authorize_security_group_ingress()
+        .group_id(&group_id)
+        .ip_permissions(
+            IpPermission::builder()
+                .ip_protocol("tcp")
+                .from_port(22)
+                .to_port(22)
+                .ip_ranges(
+                    IpRange::builder()
+                        .cidr_ip(&opt.ssh_cidr)
+                        .description("SSH")
+                        .build(),
+                )
+                .build(),
+        )
+        .send()
+        .await?;
+
+    let instance_profile = match &opt.command {
+        Some(_) => {
+            println!("4. Creating a role that lets AWS Systems Manager run commands");
+            let instance_profile =
+                create_instance_profile(&clients.iam, &opt.name, resources).await?;
+
+            // New instance profiles take a few seconds before Amazon EC2 can use them.
+            sleep(Duration::from_secs(10)).await;
+
+            Some(
+                IamInstanceProfileSpecification::builder()
+                    .name(instance_profile)
+                    .build(),
+            )
+        }
+        None => None,
+    };
+
+    println!("5. Launching a {} instance", &opt.instance_type);
+    let instance_id = clients
+        .ec2
+        .run_instances()
+        .image_id(ami)
+        .instance_type(InstanceType::from(opt.instance_type.as_str()))
+        .key_name(&opt.name)
+        .security_group_ids(&group_id)
+        .set_iam_instance_profile(instance_profile)
+        .min_count(1)
+        .max_count(1)
+        .tag_specifications(
+            TagSpecification::builder()
+                .resource_type(ResourceType::Instance)
+                .tags(Tag::builder().key("Name").value(&opt.name).build())
+                .build(),
+        )
+        .send()
+        .await?
+        .instances
+        .unwrap_or_default()
+        .into_iter()
+        .find_map(|instance| instance.instance_id)
+        .ok_or("Amazon EC2 did not return the instance ID")?;
+    resources.instance_id = Some(instance_id.clone());
+    println!("  {}", &instance_id);
+
+    println!("6. Waiting for the status checks to pass");
+    wait_for_status_checks(&clients.ec2, &instance_id).await?;
+
+    println!("7. Connecting to the instance");
+    show_connection(&clients.ec2, &instance_id, &key_file).await?;
+
+    if let Some(command) = &opt.command {
+        println!("8. Running {} with AWS Systems Manager", command);
+        run_command(&clients.ssm, &instance_id, command).await?;
+    }
+
+    if !opt.keep {
+        println!();
+        println!("Press Enter to terminate the instance and clean up");
+        pause().await?;
+    }
+
+    Ok(())
+}
+
+/// Waits until the instance is terminated, which is when its security group can be deleted.
+async fn wait_for_termination(
+    client: &ec2::Client,
+    instance_id: &str,
+) -> Result<(), Box<dyn Error>> {
+    let start = Instant::now();
+
+    loop {
+        let state = client
+            .describe_instances()
+            .instance_ids(instance_id)
+            .send()
+            .await?
+            .reservations
+            .unwrap_or_default()
+            .into_iter()
+            .flat_map(|reservation| reservation.instances.unwrap_or_default())
+            .find_map(|instance| instance.state.and_then(|state| state.name));
+
+        if state == Some(InstanceStateName::Terminated) {
+            return Ok(());
+        }
+
+        if start.elapsed() > INSTANCE_TIMEOUT {
+            return Err("the instance did not terminate in time".into());
+        }
+
+        sleep(Duration::from_secs(10)).await;
+    }
+}
+
+/// Terminates the instance, and deletes the security group, key pair, instance profile, and role.
+/// Keeps going after an error, so that as much as possible is cleaned up.
+async fn tear_down(clients: &Clients, resources: &Resources) {
+    if let Some(instance_id) = &resources.instance_id {
+        if let Err(e) = clients
+            .ec2
+            .terminate_instances()
+            .instance_ids(instance_id)
+            .send()
+            .await
+        {
+            eprintln!("Got an error terminating instance {}: {}", instance_id, e);
+        }
+
+        // Amazon EC2 won't delete a security group that a running instance still uses.
+        if let Err(e) = wait_for_termination(&clients.ec2, instance_id).await {
+            eprintln!(
+                "Got an error waiting for {} to terminate: {}",
+                instance_id, e
+            );
+        }
+    }
+
+    if let Some(group_id) = &resources.security_group_id {
+        if let Err(e) = clients
+            .ec2
+            .delete_security_group()
+            .group_id(group_id)
+            .send()
+            .await
+        {
+            eprintln!("Got an error deleting security group {}: {}", group_id, e);
+        }
+    }
+
+    if let Some(key_pair) = &resources.key_pair {
+        if let Err(e) = clients
+            .ec2
+            .delete_key_pair()
+            .key_name(key_pair)
+            .send()
+            .await
+        {
+            eprintln!("Got an error deleting key pair {}: {}", key_pair, e);
+        }
+    }
+
+    if let Some(key_file) = &resources.key_file {
+        if let Err(e) = fs::remove_file(key_file) {
+            eprintln!("Got an error deleting {}: {}", key_file, e);
+        }
+    }
+
+    // IAM won't delete a role that's still in an instance profile or still has policies.
+    if let (Some(instance_profile), Some(role)) = (&resources.instance_profile, &resources.role) {
+        if resources.instance_profile_role {
+            if let Err(e) = clients
+                .iam
+                .remove_role_from_instance_profile()
+                .instance_profile_name(instance_profile)
+                .role_name(role)
+                .send()
+                .await
+            {
+                eprintln!(
+                    "Got an error removing {} from {}: {}",
+                    role, instance_profile, e
+                );
+            }
+        }
+    }
+
+    if let Some(instance_profile) = &resources.instance_profile {
+        if let Err(e) = clients
+            .iam
+            .delete_instance_profile()
+            .instance_profile_name(instance_profile)
+            .send()
+            .await
+        {
+            eprintln!(
+                "Got an error deleting instance profile {}: {}",
+                instance_profile, e
+            );
+        }
+    }
+
+    if let Some(role) = &resources.role {
+        if resources.role_policy {
+            if let Err(e) = clients
+                .iam
+                .detach_role_policy()
+                .role_name(role)
+                .policy_arn(SSM_MANAGED_INSTANCE_POLICY)
+                .send()
+                .await
+            {
+                eprintln!("Got an error detaching the policy from {}: {}", role, e);
+            }
+        }
+
+        if let Err(e) = clients.iam.delete_role().role_name(role).send().await {
+            eprintln!("Got an error deleting role {}: {}", role, e);
+        }
+    }
+}
+
+/// Launches an Amazon EC2 instance from scratch, and cleans up after it.
+/// The scenario finds the latest Amazon Linux 2 AMI, creates a key pair and a security group that allows SSH,
+/// launches an instance, waits for it to pass its status checks, and displays how to connect to it with SSH.
+/// It can also run a shell command on the instance with AWS Systems Manager.
+/// When you press Enter, or if you press Ctrl-C at any point,
+/// it terminates the instance and deletes everything it created.
+/// # Arguments
+///
+/// * `-s SSH-CIDR` - The IPv4 address range that can connect to the instance with SSH, such as **203.0.113.25/32**.
+/// * `[-n NAME]` - The name of the key pair, security group, and instance.
+///   If not supplied, defaults to **ec2-lifecycle**.
+/// * `[-i INSTANCE-TYPE]` - The instance type.
+///   If not supplied, defaults to **t3.micro**.
+/// * `[-c COMMAND]` - A shell command to run on the instance with AWS Systems Manager.
+/// * `[-k]` - Whether to keep the instance and everything it uses.
+/// * `[-d DEFAULT-REGION]` - The region in which the clients are created.
+///    If not supplied, uses the value of the **AWS_DEFAULT_REGION** environment variable.
+///    If the environment variable is not set, defaults to **us-west-2**.
+/// * `[--profile PROFILE]` - The AWS profile to use from the shared credentials file.
+/// * `[-v]` - Whether to display additional information.
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn Error>> {
+    let opt = Opt::from_args();
+
+    let region = opt
+        .default_region
+        .as_ref()
+        .map(|region| Region::new(region.clone()))
+        .or_else(|| aws_types::region::default_provider().region())
+        .unwrap_or_else(|| Region::new("us-west-2"));
+
+    if opt.verbose {
+        println!("EC2 client version: {}", ec2::PKG_VERSION);
+        println!("IAM client version: {}", iam::PKG_VERSION);
+        println!("SSM client version: {}", ssm::PKG_VERSION);
+        println!("Region:             {:?}", &region);
+        println!("Name:               {}", &opt.name);
+        println!("Instance type:      {}", &opt.instance_type);
+        println!("SSH CIDR:           {}", &opt.ssh_cidr);
+        println!("Command:            {:?}", &opt.command);
+        println!();
+
+        SubscriberBuilder::default()
+            .with_env_filter("info")
+            .with_span_events(FmtSpan::CLOSE)
+            .init();
+    }
+
+    let mut ec2_config = ec2::Config::builder().region(region.clone());
+    let mut iam_config = iam::Config::builder().region(region.clone());
+    let mut ssm_config = ssm::Config::builder().region(region);
+    if let Some(profile) = &opt.profile {
+        ec2_config = ec2_config.credentials_provider(ProfileCredentialsProvider::new(profile));
+        iam_config = iam_config.credentials_provider(ProfileCredentialsProvider::new(profile));
+        ssm_config = ssm_config.credentials_provider(ProfileCredentialsProvider::new(profile));
+    }
+
+    let clients = Clients {
+        ec2: ec2::Client::from_conf(ec2_config.build()),
+        iam: iam::Client::from_conf(iam_config.build()),
+        ssm: ssm::Client::from_conf(ssm_config.build()),
+    };
+
+    let mut resources = Resources::default();
+
+    // Ctrl-C stops the scenario wherever it is, and still cleans up what it created.
+    let shutdown = Shutdown::listen();
+    let result = shutdown.run(run(&clients, &opt, &mut resources)).await;
+
+    if opt.keep {
+        println!("Keeping the instance and the resources named {}", &opt.name);
+    } else {
+        println!(
+            "9. Terminating the instance, and deleting the security group, key pair, and role"
+        );
+        tear_down(&clients, &resources).await;
+    }
+
+    result
+}
@@ -0,0 +1,331 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0.
+ */
+
+use std::error::Error;
+use std::time::Duration;
+
+use aws_types::region::{ProvideRegion, Region};
+
+use sts::Credentials;
+
+use common::profile::ProfileCredentialsProvider;
+use common::shutdown::Shutdown;
+use serde_json::json;
+use structopt::StructOpt;
+use tokio::time::sleep;
+use tracing_subscriber::fmt::format::FmtSpan;
+use tracing_subscriber::fmt::SubscriberBuilder;
+
+/// The name of the inline policy that the scenario puts on the group.
+const POLICY_NAME: &str = "bucket-read-only";
+
+/// How many times to try the new access key before giving up.
+/// New access keys can take several seconds to become usable.
+const MAX_ATTEMPTS: u32 = 8;
+
+#[derive(Debug, StructOpt)]
+struct Opt {
+    /// The region. Overrides environment variable AWS_DEFAULT_REGION.
+    #[structopt(short, long)]
+    default_region: Option<String>,
+
+    /// The name of the user to create
+    #[structopt(short, long)]
+    user_name: String,
+
+    /// The name of the group to create and add the user to
+    #[structopt(short, long)]
+    group_name: String,
+
+    /// The bucket that the group can read
+    #[structopt(short, long)]
+    bucket: String,
+
+    /// Whether to keep the user, group, policy, and access key
+    #[structopt(short, long)]
+    keep: bool,
+
+    /// The AWS profile to use from the shared credentials file
+    #[structopt(long)]
+    profile: Option<String>,
+
+    /// Whether to display additional runtime information
+    #[structopt(short, long)]
+    verbose: bool,
+}
+
+/// The resources that the scenario has created, so that they can be deleted even if a later step fails.
+#[derive(Default)]
+struct Resources {
+    user: bool,
+    group: bool,
+    membership: bool,
+    policy: bool,
+    access_key_id: Option<String>,
+}
+
+/// Returns a policy that allows listing and reading the objects in one bucket, and nothing else.
+fn bucket_read_only_policy(bucket: &str) -> String {
+    json!({
+        "Version": "2012-10-17",
+        "Statement": [
+            {
+                "Effect": "Allow",
+                "Action": "s3:ListBucket",
+                "Resource": format!("arn:aws:s3:::{}", bucket)
+            },
+            {
+                "Effect": "Allow",
+                "Action": "s3:GetObject",
+                "Resource": format!("arn:aws:s3:::{}/*", bucket)
+            }
+        ]
+    })
+    .to_string()
+}
+
+/// Calls GetCallerIdentity with the new access key, and returns the ARN it authenticates as.
+/// Retries with an increasing delay while the key propagates.
+async fn verify_access_key(
+    region: Region,
+    access_key_id: &str,
+    secret_access_key: &str,
+) -> Result<String, Box<dyn Error>> {
+    let config = sts::Config::builder()
+        .region(region)
+        .credentials_provider(Credentials::from_keys(
+            access_key_id,
+            secret_access_key,
+            None,
+        ))
+        .build();
+    let client = sts::Client::from_conf(config);
+
+    let mut delay = Duration::from_secs(1);
+
+    for attempt in 1..=MAX_ATTEMPTS {
+        match client.get_caller_identity().send().await {
+            Ok(resp) => return Ok(resp.arn.unwrap_or_default()),
+            Err(e) if attempt < MAX_ATTEMPTS => {
+                println!("  The access key isn't usable yet ({}), retrying", e);
+                sleep(delay).await;
+                delay *= 2;
+            }
+            Err(e) => return Err(e.into()),
+        }
+    }
+
+    Err("the access key never became usable".into())
+}
+
+/// Runs the steps of the scenario, stopping at the first error.
+async fn run(
+    client: &iam::Client,
+    region: Region,
+    opt: &Opt,
+    resources: &mut Resources,
+) -> Result<(), Box<dyn Error>> {
+    println!("1. Creating user {}", &opt.user_name);
+    let user_arn = client
+        .create_user()
+        .user_name(&opt.user_name)
+        .send()
+        .await?
+        .user
+        .and_then(|user| user.arn)
+        .unwrap_or_default();
+    resources.user = true;
+    println!("  {}", user_arn);
+
+    println!("2. Creating group {}", &opt.group_name);
+    client
+        .create_group()
+        .group_name(&opt.group_name)
+        .send()
+        .await?;
+    resources.group = true;
+
+    println!("3. Adding {} to {}", &opt.user_name, &opt.group_name);
+    client
+        .add_user_to_group()
+        .group_name(&opt.group_name)
+        .user_name(&opt.user_name)
+        .send()
+        .await?;
+    resources.membership = true;
+
+    // Granting permissions to the group rather than the user
+    // means the next user who needs the same access only has to join the group.
+    println!(
+        "4. Allowing {} to read bucket {}",
+        &opt.group_name, &opt.bucket
+    );
+    client
+        .put_group_policy()
+        .group_name(&opt.group_name)
+        .policy_name(POLICY_NAME)
+        .policy_document(bucket_read_only_policy(&opt.bucket))
+        .send()
+        .await?;
+    resources.policy = true;
+
+    println!("5. Creating an access key for {}", &opt.user_name);
+    let key = client
+        .create_access_key()
+        .user_name(&opt.user_name)
+        .send()
+        .await?
+        .access_key
+        .ok_or("IAM did not return the access key")?;
+    let access_key_id = key
+        .access_key_id
+        .ok_or("the access key has no access key ID")?;
+    let secret_access_key = key
+        .secret_access_key
+        .ok_or("the access key has no secret access key")?;
+    resources.access_key_id = Some(access_key_id.clone());
+    println!("  {}", &access_key_id);
+
+    println!("6. Verifying the access key with AWS STS");
+    let caller_arn = verify_access_key(region, &access_key_id, &secret_access_key).await?;
+    println!("  The access key authenticates as {}", caller_arn);
+
+    if caller_arn != user_arn {
+        return Err(format!("expected to authenticate as {}", user_arn).into());
+    }
+
+    Ok(())
+}
+
+/// Deletes what the scenario created, in the reverse order.
+/// IAM won't delete a user or group while anything still refers to it.
+/// Keeps going after an error, so that as much as possible is cleaned up.
+async fn tear_down(client: &iam::Client, opt: &Opt, resources: &Resources) {
+    if let Some(access_key_id) = &resources.access_key_id {
+        if let Err(e) = client
+            .delete_access_key()
+            .user_name(&opt.user_name)
+            .access_key_id(access_key_id)
+            .send()
+            .await
+        {
+            eprintln!("Got an error deleting access key {}: {}", access_key_id, e);
+        }
+    }
+
+    if resources.policy {
+        if let Err(e) = client
+            .delete_group_policy()
+            .group_name(&opt.group_name)
+            .policy_name(POLICY_NAME)
+            .send()
+            .await
+        {
+            eprintln!("Got an error deleting policy {}: {}", POLICY_NAME, e);
+        }
+    }
+
+    if resources.membership {
+        if let Err(e) = client
+            .remove_user_from_group()
+            .group_name(&opt.group_name)
+            .user_name(&opt.user_name)
+            .send()
+            .await
+        {
+            eprintln!(
+                "Got an error removing {} from {}: {}",
+                &opt.user_name, &opt.group_name, e
+            );
+        }
+    }
+
+    if resources.group {
+        if let Err(e) = client
+            .delete_group()
+            .group_name(&opt.group_name)
+            .send()
+            .await
+        {
+            eprintln!("Got an error deleting group {}: {}", &opt.group_name, e);
+        }
+    }
+
+    if resources.user {
+        if let Err(e) = client.delete_user().user_name(&opt.user_name).send().await {
+            eprintln!("Got an error deleting user {}: {}", &opt.user_name, e);
+        }
+    }
+}
+
+/// Onboards an IAM user with least-privilege access.
+/// The scenario creates a user and a group, adds the user to the group,
+/// gives the group read-only access to one Amazon S3 bucket, and creates an access key for the user.
+/// It then verifies the access key by calling AWS STS GetCallerIdentity with it.
+/// Finally, it deletes everything it created.
+/// # Arguments
+///
+/// * `-u USER-NAME` - The name of the user to create.
+/// * `-g GROUP-NAME` - The name of the group to create.
+/// * `-b BUCKET` - The bucket that the group can read.
+/// * `[-k]` - Whether to keep the user, group, policy, and access key.
+/// * `[-d DEFAULT-REGION]` - The region in which the clients are created.
+///    If not supplied, uses the value of the **AWS_DEFAULT_REGION** environment variable.
+///    If the environment variable is not set, defaults to **us-west-2**.
+/// * `[--profile PROFILE]` - The AWS profile to use from the shared credentials file.
+/// * `[-v]` - Whether to display additional information.
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn Error>> {
+    let opt = Opt::from_args();
+
+    let region = opt
+        .default_region
+        .as_ref()
+        .map(|region| Region::new(region.clone()))
+        .or_else(|| aws_types::region::default_provider().region())
+        .unwrap_or_else(|| Region::new("us-west-2"));
+
+    if opt.verbose {
+        println!("IAM client version: {}", iam::PKG_VERSION);
+        println!("STS client version: {}", sts::PKG_VERSION);
+        println!("Region:             {:?}", &region);
+        println!("User name:          {}", &opt.user_name);
+        println!("Group name:         {}", &opt.group_name);
+        println!("Bucket:             {}", &opt.bucket);
+        println!();
+
+        SubscriberBuilder::default()
+            .with_env_filter("info")
+            .with_span_events(FmtSpan::CLOSE)
+            .init();
+    }
+
+    let mut iam_config = iam::Config::builder().region(region.clone());
+    if let Some(profile) = &opt.profile {
+        iam_config = iam_config.credentials_provider(ProfileCredentialsProvider::new(profile));
+    }
+
+    let client = iam::Client::from_conf(iam_config.build());
+
+    let mut resources = Resources::default();
+
+    // Ctrl-C stops the scenario wherever it is, and still cleans up what it created.
+    let shutdown = Shutdown::listen();
+    let result = shutdown
+        .run(run(&client, region, &opt, &mut resources))
+        .await;
+
+    if opt.keep {
+        println!(
+            "Keeping user {} and group {}",
+            &opt.user_name, &opt.group_name
+        );
+    } else {
+        println!("7. Deleting the access key, policy, group, and user");
+        tear_down(&client, &opt, &resources).await;
+    }
+
+    result
+}
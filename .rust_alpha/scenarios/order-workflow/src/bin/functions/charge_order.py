@@ -0,0 +1,16 @@
+# Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+# SPDX-License-Identifier: Apache-2.0
+
+
+class PaymentDeclined(Exception):
+    pass
+
+
+def handler(event, context):
+    """Charges for the order, declining large orders."""
+    total = event["quantity"] * event["price"]
+    if total > 1000:
+        raise PaymentDeclined("Orders over 1000 need approval")
+
+    event["charged"] = total
+    return event
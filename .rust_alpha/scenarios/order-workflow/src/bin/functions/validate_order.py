@@ -0,0 +1,12 @@
+# Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+# SPDX-License-Identifier: Apache-2.0
+
+def handler(event, context):
+    """Rejects orders without any items or without a price."""
+    if event.get("quantity", 0) <= 0:
+        raise ValueError("The order must contain at least one item")
+    if event.get("price", 0) <= 0:
+        raise ValueError("The order must have a price")
+
+    event["validated"] = True
+    return event
@@ -0,0 +1,486 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0.
+ */
+
+use std::error::Error;
+use std::io::{Cursor, Write};
+use std::time::Duration;
+
+use aws_types::region::{ProvideRegion, Region};
+
+use lambda::model::{FunctionCode, Runtime};
+use lambda::Blob;
+use sfn::model::ExecutionStatus;
+
+use common::profile::ProfileCredentialsProvider;
+use common::shutdown::Shutdown;
+use serde_json::json;
+use structopt::StructOpt;
+use tokio::time::sleep;
+use tracing_subscriber::fmt::format::FmtSpan;
+use tracing_subscriber::fmt::SubscriberBuilder;
+use zip::write::FileOptions;
+use zip::ZipWriter;
+
+/// The source of the function that validates an order.
+const VALIDATE_ORDER_SOURCE: &str = include_str!("functions/validate_order.py");
+
+/// The source of the function that charges for an order.
+const CHARGE_ORDER_SOURCE: &str = include_str!("functions/charge_order.py");
+
+/// The state machine definition, with placeholders for the function ARNs.
+const STATE_MACHINE_DEFINITION: &str = include_str!("state-machine.json");
+
+/// The managed policy that lets a function write its logs to Amazon CloudWatch Logs.
+const LAMBDA_BASIC_EXECUTION_POLICY: &str =
+    "arn:aws:iam::aws:policy/service-role/AWSLambdaBasicExecutionRole";
+
+/// The name of the inline policy that lets the state machine invoke the functions.
+const INVOKE_POLICY_NAME: &str = "invoke-order-functions";
+
+#[derive(Debug, StructOpt)]
+struct Opt {
+    /// The region. Overrides environment variable AWS_DEFAULT_REGION.
+    #[structopt(short, long)]
+    default_region: Option<String>,
+
+    /// The prefix of the names of the functions, roles, and state machine
+    #[structopt(short, long, default_value = "order-workflow")]
+    name: String,
+
+    /// The number of items in the order. Use 0 to see the order fail validation
+    #[structopt(short, long, default_value = "3")]
+    quantity: u32,
+
+    /// The price of each item. Use a total over 1000 to see the payment declined
+    #[structopt(short, long, default_value = "25")]
+    price: u32,
+
+    /// Whether to keep the functions, roles, and state machine
+    #[structopt(short, long)]
+    keep: bool,
+
+    /// The AWS profile to use from the shared credentials file
+    #[structopt(long)]
+    profile: Option<String>,
+
+    /// Whether to display additional runtime information
+    #[structopt(short, long)]
+    verbose: bool,
+}
+
+/// The AWS clients used by the scenario.
+struct Clients {
+    iam: iam::Client,
+    lambda: lambda::Client,
+    sfn: sfn::Client,
+}
+
+/// The resources that the scenario has created, so that they can be deleted even if a later step fails.
+#[derive(Default)]
+struct Resources {
+    lambda_role: Option<String>,
+    state_machine_role: Option<String>,
+    functions: Vec<String>,
+    state_machine_arn: Option<String>,
+}
+
+/// Returns a deployment package containing a single Python file named index.py.
+fn zip_source(source: &str) -> Result<Vec<u8>, Box<dyn Error>> {
+    let mut zip = ZipWriter::new(Cursor::new(Vec::new()));
+    zip.start_file("index.py", FileOptions::default())?;
+    zip.write_all(source.as_bytes())?;
+
+    Ok(zip.finish()?.into_inner())
+}
+
+/// Creates a role that the service can assume, and returns its ARN.
+async fn create_role(
+    client: &iam::Client,
+    name: &str,
+    service: &str,
+) -> Result<String, Box<dyn Error>> {
+    let trust_policy = json!({
+        "Version": "2012-10-17",
+        "Statement": [{
+            "Effect": "Allow",
+            "Principal": { "Service": service },
+            "Action": "sts:AssumeRole"
+        }]
+    });
+
+    let arn = client
+        .create_role()
+        .role_name(name)
+        .assume_role_policy_document(trust_policy.to_string())
+        .send()
+        .await?
+        .role
+        .and_then(|role| role.arn)
+        .ok_or("IAM did not return the role ARN")?;
+
+    Ok(arn)
+}
+
+/// Creates a Python function from its source, and returns its ARN.
+async fn create_function(
+    client: &lambda::Client,
+    name: &str,
+    role_arn: &str,
+    source: &str,
+) -> Result<String, Box<dyn Error>> {
+    let arn = client
+        .create_function()
+        .function_name(name)
+        .runtime(Runtime::Python38)
+        .handler("index.handler")
+        .role(role_arn)
+        .code(
+            FunctionCode::builder()
+                .zip_file(Blob::new(zip_source(source)?))
+                .build(),
+        )
+        .send()
+        .await?
+        .function_arn
+        .ok_or("AWS Lambda did not return the function ARN")?;
+
+    Ok(arn)
+}
+
+/// Waits for an execution to finish, and returns its status.
+async fn wait_for_execution(
+    client: &sfn::Client,
+    execution_arn: &str,
+) -> Result<ExecutionStatus, sfn::Error> {
+    loop {
+        let resp = client
+            .describe_execution()
+            .execution_arn(execution_arn)
+            .send()
+            .await?;
+
+        match resp.status {
+            Some(ExecutionStatus::Running) | None => sleep(Duration::from_secs(1)).await,
+            Some(status) => return Ok(status),
+        }
+    }
+}
+
+/// Displays the execution history as a timeline,
+/// with each event's time since the execution started.
+async fn show_timeline(client: &sfn::Client, execution_arn: &str) -> Result<(), sfn::Error> {
+    let mut events = Vec::new();
+    let mut token = None;
+
+    loop {
+        let resp = client
+            .get_execution_history()
+            .execution_arn(execution_arn)
+            .set_next_token(token)
+            .send()
+            .await?;
+
+        events.extend(resp.events.unwrap_or_default());
+
+        token = resp.next_token;
+        if token.is_none() {
+            break;
+        }
+    }
+
+    let times = events
+        .iter()
+        .map(|event| {
+            event
+                .timestamp
+                .as_ref()
+                .and_then(|timestamp| timestamp.to_system_time())
+        })
+        .collect::<Vec<_>>();
+    let start = times.iter().flatten().min().copied();
+
+    for (event, time) in events.iter().zip(&times) {
+        let offset = match (start, time) {
+            (Some(start), Some(time)) => time.duration_since(start).unwrap_or_default().as_millis(),
+            _ => 0,
+        };
+
+        // One mark per 100 milliseconds, so that slow steps stand out.
+        let bar = "#".repeat((1 + offset / 100).min(60) as usize);
+
+        println!(
+            "  {:>6} ms  {:<3} {:<32} {}",
+            offset,
+            event.id,
+            event
+                .r#type
+                .as_ref()
+                .map(|event_type| event_type.as_str())
+                .unwrap_or_default(),
+            bar
+        );
+    }
+
+    Ok(())
+}
+
+/// Runs the steps of the scenario, stopping at the first error.
+async fn run(
+    clients: &Clients,
+    opt: &Opt,
+    resources: &mut Resources,
+) -> Result<(), Box<dyn Error>> {
+    println!("1. Creating roles");
+    let lambda_role = format!("{}-lambda", &opt.name);
+    let lambda_role_arn = create_role(&clients.iam, &lambda_role, "lambda.amazonaws.com").await?;
+    resources.lambda_role = Some(lambda_role.clone());
+
+    clients
+        .iam
+        .attach_role_policy()
+        .role_name(&lambda_role)
+        .policy_arn(LAMBDA_BASIC_EXECUTION_POLICY)
+        .send()
+        .await?;
+
+    let state_machine_role = format!("{}-states", &opt.name);
+    let state_machine_role_arn =
+        create_role(&clients.iam, &state_machine_role, "states.amazonaws.com").await?;
+    resources.state_machine_role = Some(state_machine_role.clone());
+
+    // New roles take a few seconds before AWS Lambda and Step Functions can assume them.
+    sleep(Duration::from_secs(10)).await;
+
+    println!("2. Deploying functions");
+    let validate_name = format!("{}-validate", &opt.name);
+    let validate_arn = create_function(
+        &clients.lambda,
+        &validate_name,
+        &lambda_role_arn,
+        VALIDATE_ORDER_SOURCE,
+    )
+    .await?;
+    resources.functions.push(validate_name);
+    println!("  {}", &validate_arn);
+
+    let charge_name = format!("{}-charge", &opt.name);
+    let charge_arn = create_function(
+        &clients.lambda,
+        &charge_name,
+        &lambda_role_arn,
+        CHARGE_ORDER_SOURCE,
+    )
+    .await?;
+    resources.functions.push(charge_name);
+    println!("  {}", &charge_arn);
+
+    let invoke_policy = json!({
+        "Version": "2012-10-17",
+        "Statement": [{
+            "Effect": "Allow",
+            "Action": "lambda:InvokeFunction",
+            "Resource": [&validate_arn, &charge_arn]
+        }]
+    });
+
+    clients
+        .iam
+        .put_role_policy()
+        .role_name(&state_machine_role)
+        .policy_name(INVOKE_POLICY_NAME)
+        .policy_document(invoke_policy.to_string())
+        .send()
+        .await?;
+
+    println!("3. Creating state machine {}", &opt.name);
+    let definition = STATE_MACHINE_DEFINITION
+        .replace("${ValidateOrderArn}", &validate_arn)
+        .replace("${ChargeOrderArn}", &charge_arn);
+
+    // The role's new inline policy also takes a few seconds to take effect.
+    sleep(Duration::from_secs(10)).await;
+
+    let state_machine_arn = clients
+        .sfn
+        .create_state_machine()
+        .name(&opt.name)
+        .definition(definition)
+        .role_arn(&state_machine_role_arn)
+        .send()
+        .await?
+        .state_machine_arn
+        .ok_or("Step Functions did not return the state machine ARN")?;
+    resources.state_machine_arn = Some(state_machine_arn.clone());
+
+    let order = json!({
+        "orderId": "order-1",
+        "quantity": opt.quantity,
+        "price": opt.price,
+    });
+
+    println!("4. Processing order {}", order);
+    let execution_arn = clients
+        .sfn
+        .start_execution()
+        .state_machine_arn(&state_machine_arn)
+        .input(order.to_string())
+        .send()
+        .await?
+        .execution_arn
+        .ok_or("Step Functions did not return the execution ARN")?;
+
+    let status = wait_for_execution(&clients.sfn, &execution_arn).await?;
+    println!("  The execution finished with status {}", status.as_str());
+
+    println!("5. Execution history");
+    show_timeline(&clients.sfn, &execution_arn).await?;
+
+    Ok(())
+}
+
+/// Deletes the state machine, functions, and roles.
+/// Keeps going after an error, so that as much as possible is cleaned up.
+async fn tear_down(clients: &Clients, resources: &Resources) {
+    if let Some(arn) = &resources.state_machine_arn {
+        if let Err(e) = clients
+            .sfn
+            .delete_state_machine()
+            .state_machine_arn(arn)
+            .send()
+            .await
+        {
+            eprintln!("Got an error deleting state machine {}: {}", arn, e);
+        }
+    }
+
+    for name in &resources.functions {
+        if let Err(e) = clients
+            .lambda
+            .delete_function()
+            .function_name(name)
+            .send()
+            .await
+        {
+            eprintln!("Got an error deleting function {}: {}", name, e);
+        }
+    }
+
+    // IAM won't delete a role that still has policies.
+    if let Some(role) = &resources.lambda_role {
+        if let Err(e) = clients
+            .iam
+            .detach_role_policy()
+            .role_name(role)
+            .policy_arn(LAMBDA_BASIC_EXECUTION_POLICY)
+            .send()
+            .await
+        {
+            eprintln!("Got an error detaching the policy from {}: {}", role, e);
+        }
+    }
+
+    if let Some(role) = &resources.state_machine_role {
+        if let Err(e) = clients
+            .iam
+            .delete_role_policy()
+            .role_name(role)
+            .policy_name(INVOKE_POLICY_NAME)
+            .send()
+            .await
+        {
+            eprintln!("Got an error deleting the policy from {}: {}", role, e);
+        }
+    }
+
+    for role in resources
+        .lambda_role
+        .iter()
+        .chain(&resources.state_machine_role)
+    {
+        if let Err(e) = clients.iam.delete_role().role_name(role).send().await {
+            eprintln!("Got an error deleting role {}: {}", role, e);
+        }
+    }
+}
+
+/// Processes an order with an AWS Step Functions state machine that chains two AWS Lambda functions.
+/// The scenario creates the IAM roles that the functions and state machine need,
+/// deploys a function that validates an order and a function that charges for it,
+/// and creates a state machine that runs them in turn, retrying service errors
+/// and sending invalid or declined orders to failure states.
+/// It then processes an order, and displays the execution history as a timeline.
+/// Finally, it deletes everything it created.
+/// # Arguments
+///
+/// * `[-n NAME]` - The prefix of the names of the resources.
+///   If not supplied, defaults to **order-workflow**.
+/// * `[-q QUANTITY]` - The number of items in the order.
+///   If not supplied, defaults to **3**.
+/// * `[-p PRICE]` - The price of each item.
+///   If not supplied, defaults to **25**.
+/// * `[-k]` - Whether to keep the functions, roles, and state machine.
+/// * `[-d DEFAULT-REGION]` - The region in which the clients are created.
+///    If not supplied, uses the value of the **AWS_DEFAULT_REGION** environment variable.
+///    If the environment variable is not set, defaults to **us-west-2**.
+/// * `[--profile PROFILE]` - The AWS profile to use from the shared credentials file.
+/// * `[-v]` - Whether to display additional information.
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn Error>> {
+    let opt = Opt::from_args();
+
+    let region = opt
+        .default_region
+        .as_ref()
+        .map(|region| Region::new(region.clone()))
+        .or_else(|| aws_types::region::default_provider().region())
+        .unwrap_or_else(|| Region::new("us-west-2"));
+
+    if opt.verbose {
+        println!("IAM client version:            {}", iam::PKG_VERSION);
+        println!("Lambda client version:         {}", lambda::PKG_VERSION);
+        println!("Step Functions client version: {}", sfn::PKG_VERSION);
+        println!("Region:                        {:?}", &region);
+        println!("Name:                          {}", &opt.name);
+        println!("Quantity:                      {}", opt.quantity);
+        println!("Price:                         {}", opt.price);
+        println!();
+
+        SubscriberBuilder::default()
+            .with_env_filter("info")
+            .with_span_events(FmtSpan::CLOSE)
+            .init();
+    }
+
+    let mut iam_config = iam::Config::builder().region(region.clone());
+    let mut lambda_config = lambda::Config::builder().region(region.clone());
+    let mut sfn_config = sfn::Config::builder().region(region);
+    if let Some(profile) = &opt.profile {
+        iam_config = iam_config.credentials_provider(ProfileCredentialsProvider::new(profile));
+        lambda_config =
+            lambda_config.credentials_provider(ProfileCredentialsProvider::new(profile));
+        sfn_config = sfn_config.credentials_provider(ProfileCredentialsProvider::new(profile));
+    }
+
+    let clients = Clients {
+        iam: iam::Client::from_conf(iam_config.build()),
+        lambda: lambda::Client::from_conf(lambda_config.build()),
+        sfn: sfn::Client::from_conf(sfn_config.build()),
+    };
+
+    let mut resources = Resources::default();
+
+    // Ctrl-C stops the scenario wherever it is, and still cleans up what it created.
+    let shutdown = Shutdown::listen();
+    let result = shutdown.run(run(&clients, &opt, &mut resources)).await;
+
+    if opt.keep {
+        println!("Keeping the resources named {}", &opt.name);
+    } else {
+        println!("6. Deleting the state machine, functions, and roles");
+        tear_down(&clients, &resources).await;
+    }
+
+    result
+}
@@ -0,0 +1,497 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0.
+ */
+
+use std::error::Error;
+use std::fs;
+use std::path::Path;
+use std::time::Duration;
+
+use aws_types::region::{ProvideRegion, Region};
+
+use dynamodb::error::{DescribeTableError, DescribeTableErrorKind};
+use dynamodb::model::{
+    AttributeDefinition, AttributeValue, BillingMode, KeySchemaElement, KeyType,
+    ScalarAttributeType, TableStatus,
+};
+use dynamodb::SdkError;
+use rekognition::model::{Image, S3Object};
+use s3::error::{HeadObjectError, HeadObjectErrorKind};
+use s3::ByteStream;
+
+use common::profile::ProfileCredentialsProvider;
+use common::shutdown::Shutdown;
+use structopt::StructOpt;
+use tokio::time::sleep;
+use tracing_subscriber::fmt::format::FmtSpan;
+use tracing_subscriber::fmt::SubscriberBuilder;
+
+/// The photo extensions that Amazon Rekognition can analyze.
+const PHOTO_EXTENSIONS: &[&str] = &["jpg", "jpeg", "png"];
+
+#[derive(Debug, StructOpt)]
+struct Opt {
+    /// The region. Overrides environment variable AWS_DEFAULT_REGION.
+    #[structopt(short, long)]
+    default_region: Option<String>,
+
+    /// The bucket that holds the photos
+    #[structopt(short, long)]
+    bucket: String,
+
+    /// The table that holds the labels. Created if it doesn't exist
+    #[structopt(short, long, default_value = "photo-labels")]
+    table: String,
+
+    /// The folder of photos to upload and analyze
+    #[structopt(short, long)]
+    folder: Option<String>,
+
+    /// The label to find photos for, such as Dog
+    #[structopt(short, long)]
+    label: Option<String>,
+
+    /// The minimum confidence, from 0 to 100, of the labels to store
+    #[structopt(short, long, default_value = "80")]
+    min_confidence: f32,
+
+    /// The AWS profile to use from the shared credentials file
+    #[structopt(long)]
+    profile: Option<String>,
+
+    /// Whether to display additional runtime information
+    #[structopt(short, long)]
+    verbose: bool,
+}
+
+/// The AWS clients used by the scenario.
+struct Clients {
+    dynamodb: dynamodb::Client,
+    rekognition: rekognition::Client,
+    s3: s3::Client,
+}
+
+/// The resources that this run has added, so that they can be deleted if you press Ctrl-C.
+/// A photo that was already in the bucket isn't included, so that a photo from an earlier run is never deleted.
+#[derive(Default)]
+struct Resources {
+    table: bool,
+    photos: Vec<String>,
+    /// The label and photo of each item.
+    items: Vec<(String, String)>,
+}
+
+/// Returns whether the file is a photo that Amazon Rekognition can analyze.
+fn is_photo(path: &Path) -> bool {
+    path.extension()
+        .and_then(|ext| ext.to_str())
+        .map(|ext| PHOTO_EXTENSIONS.contains(&ext.to_ascii_lowercase().as_str()))
+        .unwrap_or_default()
+}
+
+/// Labels are stored in lowercase, so that finding photos isn't case-sensitive.
+fn normalize(label: &str) -> String {
+    label.to_lowercase()
+}
+
+/// Creates the table unless it already exists, and waits for it to become active.
+/// The table's partition key is the label and its sort key is the photo,
+/// so that all of the photos with a label can be found with a single query.
+async fn ensure_table(
+    client: &dynamodb::Client,
+    table: &str,
+    resources: &mut Resources,
+) -> Result<(), dynamodb::Error> {
+    // DescribeTable finds the table however many tables the account has, unlike a page of ListTables.
+    let exists = match client.describe_table().table_name(table).send().await {
+        Ok(_) => true,
+        Err(SdkError::ServiceError {
+            err:
+                DescribeTableError {
+                    kind: DescribeTableErrorKind::ResourceNotFoundException(_),
+                    ..
+                },
+            ..
+        }) => false,
+        Err(e) => return Err(e.into()),
+    };
+
+    if !exists {
+        client
+            .create_table()
+            .table_name(table)
+            .key_schema(
+                KeySchemaElement::builder()
+                    .attribute_name("label")
+                    .key_type(KeyType::Hash)
+                    .build(),
+            )
+            .key_schema(
+                KeySchemaElement::builder()
+                    .attribute_name("photo")
+                    .key_type(KeyType::Range)
+                    .build(),
+            )
+            .attribute_definitions(
+                AttributeDefinition::builder()
+                    .attribute_name("label")
+                    .attribute_type(ScalarAttributeType::S)
+                    .build(),
+            )
+            .attribute_definitions(
+                AttributeDefinition::builder()
+                    .attribute_name("photo")
+                    .attribute_type(ScalarAttributeType::S)
+                    .build(),
+            )
+            .billing_mode(BillingMode::PayPerRequest)
+            .send()
+            .await?;
+        resources.table = true;
+
+        println!("  Created table {}", table);
+    }
+
+    loop {
+        let status = client
+            .describe_table()
+            .table_name(table)
+            .send()
+            .await?
+            .table
+            .and_then(|table| table.table_status);
+
+        if status == Some(TableStatus::Active) {
+            return Ok(());
+        }
+
+        println!("  Table status: {:?}", status);
+        sleep(Duration::from_secs(5)).await;
+    }
+}
+
+/// Uploads a photo, detects its labels, and stores them in the table.
+/// Returns the names of the labels.
+async fn analyze_photo(
+    clients: &Clients,
+    opt: &Opt,
+    path: &Path,
+    key: &str,
+    resources: &mut Resources,
+) -> Result<Vec<String>, Box<dyn Error>> {
+    let new = match clients
+        .s3
+        .head_object()
+        .bucket(&opt.bucket)
+        .key(key)
+        .send()
+        .await
+    {
+        Ok(_) => false,
+        Err(SdkError::ServiceError {
+            err:
+                HeadObjectError {
+                    kind: HeadObjectErrorKind::NotFound(_),
+                    ..
+                },
+            ..
+        }) => true,
+        Err(e) => return Err(e.into()),
+    };
+
+    clients
+        .s3
+        .put_object()
+        .bucket(&opt.bucket)
+        .key(key)
+        .body(ByteStream::from_path(path).await?)
+        .send()
+        .await?;
+    if new {
+        resources.photos.push(key.to_string());
+    }
+
+    // Amazon Rekognition reads the photo directly from the bucket.
+    let image = Image::builder()
+        .s3_object(S3Object::builder().bucket(&opt.bucket).name(key).build())
+        .build();
+
+    let labels = clients
+        .rekognition
+        .detect_labels()
+        .image(image)
+        .min_confidence(opt.min_confidence)
+        .send()
+        .await?
+        .labels
+        .unwrap_or_default();
+
+    let mut names = Vec::new();
+
+    for label in labels {
+        let name = match label.name {
+            Some(name) => name,
+            None => continue,
+        };
+
+        clients
+            .dynamodb
+            .put_item()
+            .table_name(&opt.table)
+            .item("label", AttributeValue::S(normalize(&name)))
+            .item("photo", AttributeValue::S(key.to_string()))
+            .item("name", AttributeValue::S(name.clone()))
+            .item(
+                "confidence",
+                AttributeValue::N(label.confidence.unwrap_or_default().to_string()),
+            )
+            .send()
+            .await?;
+        if new {
+            resources.items.push((normalize(&name), key.to_string()));
+        }
+
+        names.push(name);
+    }
+
+    Ok(names)
+}
+
+/// Uploads and analyzes every photo in the folder.
+async fn analyze_folder(
+    clients: &Clients,
+    opt: &Opt,
+    folder: &str,
+    resources: &mut Resources,
+) -> Result<(), Box<dyn Error>> {
+    let mut count = 0;
+
+    for entry in fs::read_dir(folder)? {
+        let path = entry?.path();
+
+        if !path.is_file() || !is_photo(&path) {
+            continue;
+        }
+
+        let key = path
+            .file_name()
+            .and_then(|name| name.to_str())
+            .ok_or("photo file names must be valid UTF-8")?
+            .to_string();
+
+        let labels = analyze_photo(clients, opt, &path, &key, resources).await?;
+        println!("  {}: {}", key, labels.join(", "));
+
+        count += 1;
+    }
+
+    println!("  Analyzed {} photos", count);
+
+    Ok(())
+}
+
+/// Displays the photos that contain the label, with the most confident match first.
+async fn find_photos(
+    client: &dynamodb::Client,
+    table: &str,
+    label: &str,
+) -> Result<(), dynamodb::Error> {
+    let mut photos = Vec::new();
+    let mut start_key = None;
+
+    loop {
+        let resp = client
+            .query()
+            .table_name(table)
+            .key_condition_expression("#label = :label")
+            .expression_attribute_names("#label", "label")
+            .expression_attribute_values(":label", AttributeValue::S(normalize(label)))
+            .set_exclusive_start_key(start_key)
+            .send()
+            .await?;
+
+        for mut item in resp.items.unwrap_or_default() {
+            let photo = match item.remove("photo") {
+                Some(AttributeValue::S(photo)) => photo,
+                _ => continue,
+            };
+            let confidence = match item.remove("confidence") {
+                Some(AttributeValue::N(n)) => n.parse::<f32>().unwrap_or_default(),
+                _ => 0.0,
+            };
+
+            photos.push((photo, confidence));
+        }
+
+        start_key = resp.last_evaluated_key;
+        if start_key.is_none() {
+            break;
+        }
+    }
+
+    photos.sort_by(|a, b| b.1.partial_cmp(&a.1).unwrap_or(std::cmp::Ordering::Equal));
+
+    if photos.is_empty() {
+        println!("  No photos contain {}", label);
+    }
+
+    for (photo, confidence) in &photos {
+        println!("  {} ({:.1}%)", photo, confidence);
+    }
+
+    Ok(())
+}
+
+/// Runs the steps of the scenario, stopping at the first error.
+async fn run(
+    clients: &Clients,
+    opt: &Opt,
+    resources: &mut Resources,
+) -> Result<(), Box<dyn Error>> {
+    println!("1. Preparing table {}", &opt.table);
+    ensure_table(&clients.dynamodb, &opt.table, resources).await?;
+
+    if let Some(folder) = &opt.folder {
+        println!("2. Analyzing the photos in {}", folder);
+        analyze_folder(clients, opt, folder, resources).await?;
+    }
+
+    if let Some(label) = &opt.label {
+        println!("3. Finding photos that contain {}", label);
+        find_photos(&clients.dynamodb, &opt.table, label).await?;
+    }
+
+    Ok(())
+}
+
+/// Deletes the photos and labels that this run added, and the table if this run created it.
+/// Keeps going after an error, so that as much as possible is cleaned up.
+async fn tear_down(clients: &Clients, opt: &Opt, resources: &Resources) {
+    if resources.table {
+        // Deleting the table deletes the labels in it.
+        if let Err(e) = clients
+            .dynamodb
+            .delete_table()
+            .table_name(&opt.table)
+            .send()
+            .await
+        {
+            eprintln!("Got an error deleting table {}: {}", &opt.table, e);
+        }
+    } else {
+        for (label, photo) in &resources.items {
+            if let Err(e) = clients
+                .dynamodb
+                .delete_item()
+                .table_name(&opt.table)
+                .key("label", AttributeValue::S(label.clone()))
+                .key("photo", AttributeValue::S(photo.clone()))
+                .send()
+                .await
+            {
+                eprintln!("Got an error deleting label {} of {}: {}", label, photo, e);
+            }
+        }
+    }
+
+    for photo in &resources.photos {
+        if let Err(e) = clients
+            .s3
+            .delete_object()
+            .bucket(&opt.bucket)
+            .key(photo)
+            .send()
+            .await
+        {
+            eprintln!("Got an error deleting {}: {}", photo, e);
+        }
+    }
+}
+
+/// Analyzes photos and finds them by what they contain.
+/// The scenario uploads the photos in a local folder to an Amazon S3 bucket,
+/// detects the labels in each photo with Amazon Rekognition,
+/// and stores the labels in an Amazon DynamoDB table.
+/// It can then find all of the photos that contain a label, such as Dog.
+/// The photos and labels are kept, so that you can find photos in later runs,
+/// but if you press Ctrl-C, it deletes the ones that this run added.
+/// # Arguments
+///
+/// * `-b BUCKET` - The bucket that holds the photos.
+/// * `[-f FOLDER]` - The folder of photos to upload and analyze.
+/// * `[-l LABEL]` - The label to find photos for.
+///   At least one of FOLDER and LABEL must be supplied.
+/// * `[-t TABLE]` - The table that holds the labels.
+///   If not supplied, defaults to **photo-labels**.
+/// * `[-m MIN-CONFIDENCE]` - The minimum confidence of the labels to store.
+///   If not supplied, defaults to **80**.
+/// * `[-d DEFAULT-REGION]` - The region in which the clients are created.
+///    If not supplied, uses the value of the **AWS_DEFAULT_REGION** environment variable.
+///    If the environment variable is not set, defaults to **us-west-2**.
+/// * `[--profile PROFILE]` - The AWS profile to use from the shared credentials file.
+/// * `[-v]` - Whether to display additional information.
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn Error>> {
+    let opt = Opt::from_args();
+
+    if opt.folder.is_none() && opt.label.is_none() {
+        return Err("supply a folder of photos to analyze, a label to find, or both".into());
+    }
+
+    let region = opt
+        .default_region
+        .as_ref()
+        .map(|region| Region::new(region.clone()))
+        .or_else(|| aws_types::region::default_provider().region())
+        .unwrap_or_else(|| Region::new("us-west-2"));
+
+    if opt.verbose {
+        println!("DynamoDB client version:    {}", dynamodb::PKG_VERSION);
+        println!("Rekognition client version: {}", rekognition::PKG_VERSION);
+        println!("S3 client version:          {}", s3::PKG_VERSION);
+        println!("Region:                     {:?}", &region);
+        println!("Bucket:                     {}", &opt.bucket);
+        println!("Table:                      {}", &opt.table);
+        println!("Folder:                     {:?}", &opt.folder);
+        println!("Label:                      {:?}", &opt.label);
+        println!("Minimum confidence:         {}", opt.min_confidence);
+        println!();
+
+        SubscriberBuilder::default()
+            .with_env_filter("info")
+            .with_span_events(FmtSpan::CLOSE)
+            .init();
+    }
+
+    let mut dynamodb_config = dynamodb::Config::builder().region(region.clone());
+    let mut rekognition_config = rekognition::Config::builder().region(region.clone());
+    let mut s3_config = s3::Config::builder().region(region);
+    if let Some(profile) = &opt.profile {
+        dynamodb_config =
+            dynamodb_config.credentials_provider(ProfileCredentialsProvider::new(profile));
+        rekognition_config =
+            rekognition_config.credentials_provider(ProfileCredentialsProvider::new(profile));
+        s3_config = s3_config.credentials_provider(ProfileCredentialsProvider::new(profile));
+    }
+
+    let clients = Clients {
+        dynamodb: dynamodb::Client::from_conf(dynamodb_config.build()),
+        rekognition: rekognition::Client::from_conf(rekognition_config.build()),
+        s3: s3::Client::from_conf(s3_config.build()),
+    };
+
+    let mut resources = Resources::default();
+
+    // Ctrl-C stops the scenario wherever it is, and cleans up what this run added,
+    // so that a folder isn't left half analyzed.
+    let shutdown = Shutdown::listen();
+    let result = shutdown.run(run(&clients, &opt, &mut resources)).await;
+
+    if shutdown.is_requested() {
+        println!("Deleting the photos and labels that this run added");
+        tear_down(&clients, &opt, &resources).await;
+    }
+
+    result
+}
@@ -0,0 +1,688 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0.
+ */
+
+use std::error::Error;
+use std::fs;
+use std::time::SystemTime;
+
+use aws_types::region::{ProvideRegion, Region};
+
+use polly::model::{OutputFormat, TextType, VoiceId};
+use s3::error::{HeadObjectError, HeadObjectErrorKind};
+use s3::model::{
+    BucketLocationConstraint, CreateBucketConfiguration, IndexDocument,
+    PublicAccessBlockConfiguration, WebsiteConfiguration,
+};
+use s3::{ByteStream, SdkError};
+
+use common::profile::ProfileCredentialsProvider;
+use common::shutdown::Shutdown;
+use serde_json::json;
+use structopt::StructOpt;
+use tracing_subscriber::fmt::format::FmtSpan;
+use tracing_subscriber::fmt::SubscriberBuilder;
+
+/// The most characters that the scenario sends to Amazon Polly in one request.
+/// Amazon Polly accepts up to 3000 billed characters of text per request.
+const MAX_CHUNK: usize = 2500;
+
+/// The prefix of the episodes' keys in the bucket.
+const EPISODE_PREFIX: &str = "episodes/";
+
+/// The regions whose S3 website endpoints use a dash, rather than a dot, before the region.
+const LEGACY_WEBSITE_REGIONS: &[&str] = &[
+    "us-east-1",
+    "us-west-1",
+    "us-west-2",
+    "ap-southeast-1",
+    "ap-southeast-2",
+    "ap-northeast-1",
+    "eu-west-1",
+    "sa-east-1",
+];
+
+#[derive(Debug, StructOpt)]
+struct Opt {
+    /// The region. Overrides environment variable AWS_DEFAULT_REGION.
+    #[structopt(short, long)]
+    default_region: Option<String>,
+
+    /// The bucket that holds the podcast. Created if it doesn't exist
+    #[structopt(short, long)]
+    bucket: String,
+
+    /// The title of the podcast
+    #[structopt(short, long, default_value = "AWS SDK for Rust podcast")]
+    title: String,
+
+    /// The voice that reads the articles
+    #[structopt(long, default_value = "Joanna")]
+    voice: String,
+
+    /// The AWS profile to use from the shared credentials file
+    #[structopt(long)]
+    profile: Option<String>,
+
+    /// Whether to display additional runtime information
+    #[structopt(short, long)]
+    verbose: bool,
+
+    /// The text files or URLs of the articles to add as episodes
+    #[structopt(required = true)]
+    articles: Vec<String>,
+}
+
+/// The AWS clients used by the scenario.
+struct Clients {
+    polly: polly::Client,
+    s3: s3::Client,
+}
+
+/// The resources that this run has added, so that they can be deleted if you press Ctrl-C.
+/// An episode that replaced one from an earlier run isn't included, so that it's never deleted.
+#[derive(Default)]
+struct Resources {
+    bucket: bool,
+    episodes: Vec<String>,
+}
+
+/// An article to turn into an episode.
+struct Article {
+    slug: String,
+    title: String,
+    text: String,
+}
+
+/// An episode in the bucket, as listed in the feed and on the index page.
+struct Episode {
+    key: String,
+    title: String,
+    bytes: i64,
+    published: SystemTime,
+}
+
+/// Returns the S3 website endpoint for a bucket.
+fn website_endpoint(bucket: &str, region: &str) -> String {
+    let separator = if LEGACY_WEBSITE_REGIONS.contains(&region) {
+        '-'
+    } else {
+        '.'
+    };
+
+    format!("{}.s3-website{}{}.amazonaws.com", bucket, separator, region)
+}
+
+/// Returns a name for the episode's key, made from the last part of the file name or URL,
+/// without its extension.
+fn slug(source: &str) -> String {
+    let name = source
+        .trim_end_matches('/')
+        .rsplit('/')
+        .next()
+        .unwrap_or_default();
+    let stem = match name.rfind('.') {
+        Some(dot) if dot > 0 => &name[..dot],
+        _ => name,
+    };
+
+    let slug = stem
+        .to_ascii_lowercase()
+        .split(|c: char| !c.is_ascii_alphanumeric())
+        .filter(|part| !part.is_empty())
+        .collect::<Vec<_>>()
+        .join("-");
+
+    if slug.is_empty() {
+        "episode".to_string()
+    } else {
+        slug
+    }
+}
+
+/// Reads an article from a file, or downloads it if the source is an HTTP or HTTPS URL.
+/// The first line of the article is its title.
+async fn read_article(source: &str) -> Result<Article, Box<dyn Error>> {
+    let text = if source.starts_with("http://") || source.starts_with("https://") {
+        let resp = reqwest::get(source).await?.error_for_status()?;
+
+        // Reading an HTML page aloud would include its markup, menus, and scripts.
+        let is_html = resp
+            .headers()
+            .get(reqwest::header::CONTENT_TYPE)
+            .and_then(|value| value.to_str().ok())
+            .map(|value| value.starts_with("text/html"))
+            .unwrap_or_default();
+        if is_html {
+            return Err(format!(
+                "{} is an HTML page. Save the text of the article in a file, and use the file instead",
+                source
+            )
+            .into());
+        }
+
+        resp.text().await?
+    } else {
+        fs::read_to_string(source)?
+    };
+
+    let slug = slug(source);
+    let title = text
+        .lines()
+        .map(str::trim)
+        .find(|line| !line.is_empty())
+        .map(str::to_string)
+        .unwrap_or_else(|| slug.clone());
+
+    if text.trim().is_empty() {
+        return Err(format!("{} has no text", source).into());
+    }
+
+    Ok(Article { slug, title, text })
+}
+
+/// Splits the text into chunks that Amazon Polly can synthesize in one request.
+/// Chunks end at the end of a sentence, unless a single sentence is too long, in which case it's split between words.
+fn chunk_text(text: &str, max: usize) -> Vec<String> {
+    let mut chunks = Vec::new();
+    let mut chunk = String::new();
+    let mut sentence = String::new();
+
+    for word in text.split_whitespace() {
+        if !sentence.is_empty() && sentence.len() + word.len() + 1 > max {
+            // The sentence alone doesn't fit in a request.
+            if !chunk.is_empty() {
+                chunks.push(std::mem::take(&mut chunk));
+            }
+            chunks.push(std::mem::take(&mut sentence));
+        }
+
+        if !sentence.is_empty() {
+            sentence.push(' ');
+        }
+        sentence.push_str(word);
+
+        if word.ends_with(|c| c == '.' || c == '!' || c == '?') {
+            if !chunk.is_empty() && chunk.len() + sentence.len() + 1 > max {
+                chunks.push(std::mem::take(&mut chunk));
+            }
+            if !chunk.is_empty() {
+                chunk.push(' ');
+            }
+            chunk.push_str(&sentence);
+            sentence.clear();
+        }
+    }
+
+    if !sentence.is_empty() {
+        if !chunk.is_empty() && chunk.len() + sentence.len() + 1 > max {
+            chunks.push(std::mem::take(&mut chunk));
+        }
+        if !chunk.is_empty() {
+            chunk.push(' ');
+        }
+        chunk.push_str(&sentence);
+    }
+
+    if !chunk.is_empty() {
+        chunks.push(chunk);
+    }
+
+    chunks
+}
+
+/// Synthesizes each chunk to MP3, and returns the audio of all of the chunks in order.
+/// MP3 is a sequence of independent frames, so the audio of each chunk can simply be appended.
+async fn synthesize(
+    client: &polly::Client,
+    chunks: &[String],
+    voice: &str,
+) -> Result<Vec<u8>, Box<dyn Error>> {
+    let mut audio = Vec::new();
+
+    for chunk in chunks {
+        let resp = client
+            .synthesize_speech()
+            .output_format(OutputFormat::Mp3)
+            .text(chunk)
+            .text_type(TextType::Text)
+            .voice_id(VoiceId::from(voice))
+            .send()
+            .await?;
+
+        audio.extend_from_slice(&resp.audio_stream.collect().await?.into_bytes());
+    }
+
+    Ok(audio)
+}
+
+/// Creates the bucket unless it already exists, and returns whether it was created.
+async fn ensure_bucket(client: &s3::Client, bucket: &str, region: &str) -> Result<bool, s3::Error> {
+    if client.head_bucket().bucket(bucket).send().await.is_ok() {
+        println!("  Bucket {} already exists", bucket);
+        return Ok(false);
+    }
+
+    let mut request = client.create_bucket().bucket(bucket);
+
+    // us-east-1 is the default location, and rejects an explicit location constraint.
+    if region != "us-east-1" {
+        request = request.create_bucket_configuration(
+            CreateBucketConfiguration::builder()
+                .location_constraint(BucketLocationConstraint::from(region))
+                .build(),
+        );
+    }
+
+    request.send().await?;
+    println!("  Created bucket {}", bucket);
+
+    Ok(true)
+}
+
+/// Returns whether the bucket has an object with the key.
+async fn object_exists(client: &s3::Client, bucket: &str, key: &str) -> Result<bool, s3::Error> {
+    match client.head_object().bucket(bucket).key(key).send().await {
+        Ok(_) => Ok(true),
+        Err(SdkError::ServiceError {
+            err:
+                HeadObjectError {
+                    kind: HeadObjectErrorKind::NotFound(_),
+                    ..
+                },
+            ..
+        }) => Ok(false),
+        Err(e) => Err(e.into()),
+    }
+}
+
+/// Configures the bucket for website hosting, and allows anyone to read its objects,
+/// so that podcast players can download the feed and the episodes.
+async fn configure_website(client: &s3::Client, bucket: &str) -> Result<(), s3::Error> {
+    // A public bucket policy is rejected while the bucket blocks public access.
+    client
+        .put_public_access_block()
+        .bucket(bucket)
+        .public_access_block_configuration(
+            PublicAccessBlockConfiguration::builder()
+                .block_public_acls(true)
+                .ignore_public_acls(true)
+                .block_public_policy(false)
+                .restrict_public_buckets(false)
+                .build(),
+        )
+        .send()
+        .await?;
+
+    let policy = json!({
+        "Version": "2012-10-17",
+        "Statement": [{
+            "Sid": "PublicReadGetObject",
+            "Effect": "Allow",
+            "Principal": "*",
+            "Action": "s3:GetObject",
+            "Resource": format!("arn:aws:s3:::{}/*", bucket)
+        }]
+    });
+
+    client
+        .put_bucket_policy()
+        .bucket(bucket)
+        .policy(policy.to_string())
+        .send()
+        .await?;
+
+    client
+        .put_bucket_website()
+        .bucket(bucket)
+        .website_configuration(
+            WebsiteConfiguration::builder()
+                .index_document(IndexDocument::builder().suffix("index.html").build())
+                .build(),
+        )
+        .send()
+        .await?;
+
+    Ok(())
+}
+
+/// Returns every episode in the bucket, newest first,
+/// including the episodes that earlier runs of the scenario added.
+async fn list_episodes(client: &s3::Client, bucket: &str) -> Result<Vec<Episode>, Box<dyn Error>> {
+    let mut episodes = Vec::new();
+    let mut token = None;
+
+    loop {
+        let resp = client
+            .list_objects_v2()
+            .bucket(bucket)
+            .prefix(EPISODE_PREFIX)
+            .set_continuation_token(token)
+            .send()
+            .await?;
+
+        for object in resp.contents.unwrap_or_default() {
+            let key = match object.key {
+                Some(key) => key,
+                None => continue,
+            };
+
+            // The title is kept in the object's metadata, because the key can't hold every character.
+            let title = client
+                .head_object()
+                .bucket(bucket)
+                .key(&key)
+                .send()
+                .await?
+                .metadata
+                .and_then(|mut metadata| metadata.remove("title"))
+                .unwrap_or_else(|| key.clone());
+
+            episodes.push(Episode {
+                title,
+                bytes: object.size,
+                published: object
+                    .last_modified
+                    .and_then(|modified| modified.to_system_time())
+                    .unwrap_or(SystemTime::UNIX_EPOCH),
+                key,
+            });
+        }
+
+        token = resp.next_continuation_token;
+        if token.is_none() {
+            break;
+        }
+    }
+
+    episodes.sort_by(|a, b| b.published.cmp(&a.published));
+
+    Ok(episodes)
+}
+
+/// Escapes the characters that have a special meaning in XML and HTML.
+fn escape_xml(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+        .replace('\'', "&apos;")
+}
+
+/// Returns the RSS 2.0 feed of the episodes, which podcast players subscribe to.
+/// Podcast players require absolute URLs for the episodes' audio.
+fn rss_feed(title: &str, site_url: &str, episodes: &[Episode]) -> String {
+    let mut feed = String::new();
+
+    feed.push_str("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+    feed.push_str("<rss version=\"2.0\">\n<channel>\n");
+    feed.push_str(&format!("  <title>{}</title>\n", escape_xml(title)));
+    feed.push_str(&format!("  <link>{}/</link>\n", site_url));
+    feed.push_str(&format!(
+        "  <description>{}, read by Amazon Polly</description>\n",
+        escape_xml(title)
+    ));
+
+    for episode in episodes {
+        let url = format!("{}/{}", site_url, episode.key);
+
+        feed.push_str("  <item>\n");
+        feed.push_str(&format!(
+            "    <title>{}</title>\n",
+            escape_xml(&episode.title)
+        ));
+        feed.push_str(&format!(
+            "    <enclosure url=\"{}\" length=\"{}\" type=\"audio/mpeg\"/>\n",
+            escape_xml(&url),
+            episode.bytes
+        ));
+        feed.push_str(&format!("    <guid>{}</guid>\n", escape_xml(&url)));
+        feed.push_str(&format!(
+            "    <pubDate>{}</pubDate>\n",
+            httpdate::fmt_http_date(episode.published)
+        ));
+        feed.push_str("  </item>\n");
+    }
+
+    feed.push_str("</channel>\n</rss>\n");
+
+    feed
+}
+
+/// Returns the index page of the website, with a player for each episode and a link to the feed.
+fn index_page(title: &str, episodes: &[Episode]) -> String {
+    let mut page = String::new();
+
+    page.push_str("<!DOCTYPE html>\n<html>\n<head>\n");
+    page.push_str("  <meta charset=\"utf-8\">\n");
+    page.push_str(&format!("  <title>{}</title>\n", escape_xml(title)));
+    page.push_str(
+        "  <link rel=\"alternate\" type=\"application/rss+xml\" title=\"RSS\" href=\"feed.xml\">\n",
+    );
+    page.push_str("</head>\n<body>\n");
+    page.push_str(&format!("  <h1>{}</h1>\n", escape_xml(title)));
+    page.push_str("  <p><a href=\"feed.xml\">Subscribe with RSS</a></p>\n");
+
+    for episode in episodes {
+        page.push_str(&format!("  <h2>{}</h2>\n", escape_xml(&episode.title)));
+        page.push_str(&format!(
+            "  <audio controls preload=\"none\" src=\"{}\"></audio>\n",
+            escape_xml(&episode.key)
+        ));
+    }
+
+    page.push_str("</body>\n</html>\n");
+
+    page
+}
+
+/// Runs the steps of the scenario, stopping at the first error.
+async fn run(
+    clients: &Clients,
+    region: &str,
+    opt: &Opt,
+    resources: &mut Resources,
+) -> Result<(), Box<dyn Error>> {
+    println!("1. Reading {} articles", opt.articles.len());
+    let mut articles = Vec::new();
+
+    // Read every article first, so that a bad file or URL is reported before anything is synthesized.
+    for source in &opt.articles {
+        let article = read_article(source).await?;
+        println!("  {}: {}", &article.slug, &article.title);
+        articles.push(article);
+    }
+
+    println!("2. Creating the website in bucket {}", &opt.bucket);
+    resources.bucket = ensure_bucket(&clients.s3, &opt.bucket, region).await?;
+    configure_website(&clients.s3, &opt.bucket).await?;
+
+    println!("3. Synthesizing and uploading the episodes");
+    for article in &articles {
+        let chunks = chunk_text(&article.text, MAX_CHUNK);
+        let audio = synthesize(&clients.polly, &chunks, &opt.voice).await?;
+        let key = format!("{}{}.mp3", EPISODE_PREFIX, &article.slug);
+        let new = !object_exists(&clients.s3, &opt.bucket, &key).await?;
+
+        println!(
+            "  {}: {} characters in {} requests, {} bytes of audio",
+            &key,
+            article.text.len(),
+            chunks.len(),
+            audio.len()
+        );
+
+        clients
+            .s3
+            .put_object()
+            .bucket(&opt.bucket)
+            .key(&key)
+            .content_type("audio/mpeg")
+            .metadata("title", &article.title)
+            .body(ByteStream::from(audio))
+            .send()
+            .await?;
+        if new {
+            resources.episodes.push(key);
+        }
+    }
+
+    println!("4. Publishing the feed and the index page");
+    let site_url = format!("http://{}", website_endpoint(&opt.bucket, region));
+    let episodes = list_episodes(&clients.s3, &opt.bucket).await?;
+
+    clients
+        .s3
+        .put_object()
+        .bucket(&opt.bucket)
+        .key("feed.xml")
+        .content_type("application/rss+xml")
+        .body(ByteStream::from(
+            rss_feed(&opt.title, &site_url, &episodes).into_bytes(),
+        ))
+        .send()
+        .await?;
+
+    // The feed now lists the new episodes, so the podcast is kept even if the scenario stops now.
+    *resources = Resources::default();
+
+    clients
+        .s3
+        .put_object()
+        .bucket(&opt.bucket)
+        .key("index.html")
+        .content_type("text/html")
+        .body(ByteStream::from(
+            index_page(&opt.title, &episodes).into_bytes(),
+        ))
+        .send()
+        .await?;
+
+    println!("  {} episodes in the podcast", episodes.len());
+    println!();
+    println!("Listen at:    {}/", &site_url);
+    println!("Subscribe to: {}/feed.xml", &site_url);
+
+    Ok(())
+}
+
+/// Deletes the episodes that this run added, or if this run created the bucket,
+/// everything in the bucket and then the bucket.
+/// Keeps going after an error, so that as much as possible is cleaned up.
+async fn tear_down(client: &s3::Client, opt: &Opt, resources: &Resources) {
+    let mut keys = resources.episodes.clone();
+
+    if resources.bucket {
+        match client.list_objects_v2().bucket(&opt.bucket).send().await {
+            Ok(resp) => {
+                keys = resp
+                    .contents
+                    .unwrap_or_default()
+                    .into_iter()
+                    .filter_map(|object| object.key)
+                    .collect();
+            }
+            Err(e) => eprintln!("Got an error listing bucket {}: {}", &opt.bucket, e),
+        }
+    }
+
+    for key in &keys {
+        if let Err(e) = client
+            .delete_object()
+            .bucket(&opt.bucket)
+            .key(key)
+            .send()
+            .await
+        {
+            eprintln!("Got an error deleting {}: {}", key, e);
+        }
+    }
+
+    if resources.bucket {
+        if let Err(e) = client.delete_bucket().bucket(&opt.bucket).send().await {
+            eprintln!("Got an error deleting bucket {}: {}", &opt.bucket, e);
+        }
+    }
+}
+
+/// Turns text articles into a podcast.
+/// The scenario reads each article from a file or URL, synthesizes it to MP3 with Amazon Polly,
+/// splitting long articles into several requests, and uploads the audio to an Amazon S3 bucket.
+/// It then publishes an RSS feed and an index page for every episode in the bucket,
+/// and serves them from the bucket as a static website.
+/// Running the scenario again with more articles adds them to the podcast.
+/// If you press Ctrl-C before the feed is published, it deletes the episodes that this run added,
+/// and the bucket if this run created it.
+/// # Arguments
+///
+/// * `-b BUCKET` - The bucket that holds the podcast.
+///    If the bucket doesn't exist, it's created.
+/// * `ARTICLE...` - The text files, or HTTP or HTTPS URLs of plain text, to add as episodes.
+///    The first line of each article is the title of its episode.
+/// * `[-t TITLE]` - The title of the podcast.
+///    If not supplied, defaults to **AWS SDK for Rust podcast**.
+/// * `[--voice VOICE]` - The Amazon Polly voice that reads the articles.
+///    If not supplied, defaults to **Joanna**.
+/// * `[-d DEFAULT-REGION]` - The region in which the clients are created.
+///    If not supplied, uses the value of the **AWS_DEFAULT_REGION** environment variable.
+///    If the environment variable is not set, defaults to **us-west-2**.
+/// * `[--profile PROFILE]` - The AWS profile to use from the shared credentials file.
+/// * `[-v]` - Whether to display additional information.
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn Error>> {
+    let opt = Opt::from_args();
+
+    let region = opt
+        .default_region
+        .as_ref()
+        .map(|region| Region::new(region.clone()))
+        .or_else(|| aws_types::region::default_provider().region())
+        .unwrap_or_else(|| Region::new("us-west-2"));
+
+    if opt.verbose {
+        println!("Polly client version: {}", polly::PKG_VERSION);
+        println!("S3 client version:    {}", s3::PKG_VERSION);
+        println!("Region:               {:?}", &region);
+        println!("Bucket:               {}", &opt.bucket);
+        println!("Title:                {}", &opt.title);
+        println!("Voice:                {}", &opt.voice);
+        println!("Articles:             {:?}", &opt.articles);
+        println!();
+
+        SubscriberBuilder::default()
+            .with_env_filter("info")
+            .with_span_events(FmtSpan::CLOSE)
+            .init();
+    }
+
+    let region_name = region.as_ref().to_string();
+
+    let mut polly_config = polly::Config::builder().region(region.clone());
+    let mut s3_config = s3::Config::builder().region(region);
+    if let Some(profile) = &opt.profile {
+        polly_config = polly_config.credentials_provider(ProfileCredentialsProvider::new(profile));
+        s3_config = s3_config.credentials_provider(ProfileCredentialsProvider::new(profile));
+    }
+
+    let clients = Clients {
+        polly: polly::Client::from_conf(polly_config.build()),
+        s3: s3::Client::from_conf(s3_config.build()),
+    };
+
+    let mut resources = Resources::default();
+
+    // Ctrl-C stops the scenario wherever it is, and cleans up what this run added,
+    // so that the bucket doesn't hold episodes that the feed doesn't list.
+    let shutdown = Shutdown::listen();
+    let result = shutdown
+        .run(run(&clients, &region_name, &opt, &mut resources))
+        .await;
+
+    if shutdown.is_requested() {
+        println!("Deleting what this run added");
+        tear_down(&clients.s3, &opt, &resources).await;
+    }
+
+    result
+}
@@ -0,0 +1,504 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0.
+ */
+
+use std::error::Error;
+use std::time::{Duration, Instant};
+
+use aws_types::region::{ProvideRegion, Region};
+
+use s3::model::{
+    BucketVersioningStatus, DeleteMarkerReplication, DeleteMarkerReplicationStatus, Destination,
+    ReplicationConfiguration, ReplicationRule, ReplicationRuleFilter, ReplicationRuleStatus,
+    ReplicationStatus, VersioningConfiguration,
+};
+use s3::{ByteStream, SdkError};
+use s3_code_examples::buckets::create_bucket;
+use s3_code_examples::versions::list_versions;
+
+use common::profile::ProfileCredentialsProvider;
+use common::shutdown::Shutdown;
+use serde_json::json;
+use structopt::StructOpt;
+use tokio::time::sleep;
+use tracing_subscriber::fmt::format::FmtSpan;
+use tracing_subscriber::fmt::SubscriberBuilder;
+
+/// The object that the scenario puts in the source bucket, and waits for in the destination bucket.
+const SAMPLE_KEY: &str = "replication-test.txt";
+
+/// The name of the inline policy that the scenario puts on the role.
+const POLICY_NAME: &str = "replicate-bucket";
+
+/// How many times to retry PutBucketReplication while the new role propagates.
+const MAX_ATTEMPTS: u32 = 6;
+
+/// How often to check whether the sample object has replicated.
+const POLL_INTERVAL: Duration = Duration::from_secs(5);
+
+#[derive(Debug, StructOpt)]
+struct Opt {
+    /// The region of the source bucket. Overrides environment variable AWS_DEFAULT_REGION.
+    #[structopt(short, long)]
+    default_region: Option<String>,
+
+    /// The name of the source bucket to create
+    #[structopt(short, long)]
+    source: String,
+
+    /// The name of the destination bucket to create
+    #[structopt(short = "t", long)]
+    destination: String,
+
+    /// The region of the destination bucket, which must differ from the source bucket's
+    #[structopt(short = "r", long)]
+    destination_region: String,
+
+    /// The name of the role that Amazon S3 assumes to replicate the objects
+    #[structopt(short, long, default_value = "s3-replication")]
+    name: String,
+
+    /// How many seconds to wait for the sample object to replicate
+    #[structopt(long, default_value = "600")]
+    timeout: u64,
+
+    /// Whether to keep the buckets, their objects, and the role
+    #[structopt(short, long)]
+    keep: bool,
+
+    /// The AWS profile to use from the shared credentials file
+    #[structopt(long)]
+    profile: Option<String>,
+
+    /// Whether to display additional runtime information
+    #[structopt(short, long)]
+    verbose: bool,
+}
+
+/// The AWS clients used by the scenario. Each Amazon S3 client is in the region of its bucket.
+struct Clients {
+    iam: iam::Client,
+    source: s3::Client,
+    destination: s3::Client,
+}
+
+/// The resources that the scenario has created, so that they can be deleted even if a later step fails.
+#[derive(Default)]
+struct Resources {
+    source_bucket: bool,
+    destination_bucket: bool,
+    role: bool,
+    role_policy: bool,
+}
+
+/// Returns the permission policy that replication needs: reading the source bucket's configuration
+/// and object versions, and replicating them into the destination bucket.
+fn replication_policy(source: &str, destination: &str) -> String {
+    json!({
+        "Version": "2012-10-17",
+        "Statement": [
+            {
+                "Effect": "Allow",
+                "Action": ["s3:GetReplicationConfiguration", "s3:ListBucket"],
+                "Resource": format!("arn:aws:s3:::{}", source)
+            },
+            {
+                "Effect": "Allow",
+                "Action": [
+                    "s3:GetObjectVersionForReplication",
+                    "s3:GetObjectVersionAcl",
+                    "s3:GetObjectVersionTagging"
+                ],
+                "Resource": format!("arn:aws:s3:::{}/*", source)
+            },
+            {
+                "Effect": "Allow",
+                "Action": ["s3:ReplicateObject", "s3:ReplicateDelete", "s3:ReplicateTags"],
+                "Resource": format!("arn:aws:s3:::{}/*", destination)
+            }
+        ]
+    })
+    .to_string()
+}
+
+/// Enables versioning of a bucket. Both buckets need it for replication.
+async fn enable_versioning(client: &s3::Client, bucket: &str) -> Result<(), s3::Error> {
+    client
+        .put_bucket_versioning()
+        .bucket(bucket)
+        .versioning_configuration(
+            VersioningConfiguration::builder()
+                .status(BucketVersioningStatus::Enabled)
+                .build(),
+        )
+        .send()
+        .await?;
+
+    Ok(())
+}
+
+/// Puts the replication configuration on the source bucket.
+/// Amazon S3 checks that it can assume the role, so this retries while the new role propagates.
+async fn put_replication(
+    client: &s3::Client,
+    opt: &Opt,
+    role_arn: &str,
+) -> Result<(), Box<dyn Error>> {
+    // A rule with a filter must say whether to replicate delete markers.
+    let rule = ReplicationRule::builder()
+        .id("replicate-all")
+        .priority(1)
+        .filter(ReplicationRuleFilter::Prefix(String::new()))
+        .status(ReplicationRuleStatus::Enabled)
+        .delete_marker_replication(
+            DeleteMarkerReplication::builder()
+                .status(DeleteMarkerReplicationStatus::Disabled)
+                .build(),
+        )
+        .destination(
+            Destination::builder()
+                .bucket(format!("arn:aws:s3:::{}", &opt.destination))
+                .build(),
+        )
+        .build();
+
+    let configuration = ReplicationConfiguration::builder()
+        .role(role_arn)
+        .rules(rule)
+        .build();
+
+    let mut delay = Duration::from_secs(2);
+
+    for attempt in 1..=MAX_ATTEMPTS {
+        match client
+            .put_bucket_replication()
+            .bucket(&opt.source)
+            .replication_configuration(configuration.clone())
+            .send()
+            .await
+        {
+            Ok(_) => return Ok(()),
+            Err(SdkError::ServiceError { err, .. })
+                if err.code() == Some("InvalidRequest") && attempt < MAX_ATTEMPTS =>
+            {
+                println!("  The role can't be used yet, retrying");
+                sleep(delay).await;
+                delay *= 2;
+            }
+            Err(e) => return Err(e.into()),
+        }
+    }
+
+    Err(format!(
+        "could not put the replication configuration on {}",
+        &opt.source
+    )
+    .into())
+}
+
+/// Waits until the source object's replication status is COMPLETED, and the object is in the destination bucket.
+async fn wait_for_replica(clients: &Clients, opt: &Opt) -> Result<(), Box<dyn Error>> {
+    let start = Instant::now();
+    let timeout = Duration::from_secs(opt.timeout);
+
+    loop {
+        let status = clients
+            .source
+            .head_object()
+            .bucket(&opt.source)
+            .key(SAMPLE_KEY)
+            .send()
+            .await?
+            .replication_status;
+
+        match status {
+            Some(ReplicationStatus::Complete) => break,
+            Some(ReplicationStatus::Failed) => {
+                return Err(format!(
+                    "{} failed to replicate. Check the role's permission policy",
+                    SAMPLE_KEY
+                )
+                .into())
+            }
+            status if start.elapsed() > timeout => {
+                return Err(format!(
+                    "{} has not replicated after {} seconds, its status is {:?}",
+                    SAMPLE_KEY, opt.timeout, status
+                )
+                .into())
+            }
+            status => {
+                println!(
+                    "  Status: {} after {} seconds",
+                    status.as_ref().map_or("none", |status| status.as_str()),
+                    start.elapsed().as_secs()
+                );
+                sleep(POLL_INTERVAL).await;
+            }
+        }
+    }
+
+    // The replica is marked as one, so that it isn't replicated again.
+    let replica = clients
+        .destination
+        .head_object()
+        .bucket(&opt.destination)
+        .key(SAMPLE_KEY)
+        .send()
+        .await?;
+
+    println!(
+        "  Replicated in {} seconds. The copy in {} has status {:?}",
+        start.elapsed().as_secs(),
+        &opt.destination,
+        replica.replication_status
+    );
+
+    Ok(())
+}
+
+/// Runs the steps of the scenario, stopping at the first error.
+async fn run(
+    clients: &Clients,
+    source_region: &Region,
+    destination_region: &Region,
+    opt: &Opt,
+    resources: &mut Resources,
+) -> Result<(), Box<dyn Error>> {
+    println!(
+        "1. Creating source bucket {} in {}",
+        &opt.source,
+        source_region.as_ref()
+    );
+    create_bucket(&clients.source, &opt.source, source_region).await?;
+    resources.source_bucket = true;
+
+    println!(
+        "2. Creating destination bucket {} in {}",
+        &opt.destination,
+        destination_region.as_ref()
+    );
+    create_bucket(&clients.destination, &opt.destination, destination_region).await?;
+    resources.destination_bucket = true;
+
+    println!("3. Enabling versioning of both buckets");
+    enable_versioning(&clients.source, &opt.source).await?;
+    enable_versioning(&clients.destination, &opt.destination).await?;
+
+    println!("4. Creating role {}, which Amazon S3 can assume", &opt.name);
+    let trust_policy = json!({
+        "Version": "2012-10-17",
+        "Statement": [{
+            "Effect": "Allow",
+            "Principal": { "Service": "s3.amazonaws.com" },
+            "Action": "sts:AssumeRole"
+        }]
+    });
+
+    let role_arn = clients
+        .iam
+        .create_role()
+        .role_name(&opt.name)
+        .assume_role_policy_document(trust_policy.to_string())
+        .send()
+        .await?
+        .role
+        .and_then(|role| role.arn)
+        .ok_or("IAM did not return the role ARN")?;
+    resources.role = true;
+    println!("  {}", &role_arn);
+
+    clients
+        .iam
+        .put_role_policy()
+        .role_name(&opt.name)
+        .policy_name(POLICY_NAME)
+        .policy_document(replication_policy(&opt.source, &opt.destination))
+        .send()
+        .await?;
+    resources.role_policy = true;
+
+    println!(
+        "5. Putting the replication configuration on {}",
+        &opt.source
+    );
+    put_replication(&clients.source, opt, &role_arn).await?;
+
+    println!("6. Putting {} in {}", SAMPLE_KEY, &opt.source);
+    clients
+        .source
+        .put_object()
+        .bucket(&opt.source)
+        .key(SAMPLE_KEY)
+        .body(ByteStream::from(
+            format!("Replicated from {}", source_region.as_ref()).into_bytes(),
+        ))
+        .send()
+        .await?;
+
+    println!(
+        "7. Waiting for {} to replicate to {}. This usually takes less than 15 minutes",
+        SAMPLE_KEY, &opt.destination
+    );
+    wait_for_replica(clients, opt).await?;
+
+    Ok(())
+}
+
+/// Deletes every version of every object in a versioned bucket, then the bucket.
+async fn delete_bucket(client: &s3::Client, bucket: &str) -> Result<(), Box<dyn Error>> {
+    for version in list_versions(client, bucket, "").await? {
+        client
+            .delete_object()
+            .bucket(bucket)
+            .key(&version.key)
+            .version_id(&version.version_id)
+            .send()
+            .await?;
+    }
+
+    client.delete_bucket().bucket(bucket).send().await?;
+
+    Ok(())
+}
+
+/// Deletes the buckets, the role policy, and the role.
+/// Keeps going after an error, so that as much as possible is cleaned up.
+async fn tear_down(clients: &Clients, opt: &Opt, resources: &Resources) {
+    // The replication configuration goes with the source bucket.
+    if resources.source_bucket {
+        if let Err(e) = delete_bucket(&clients.source, &opt.source).await {
+            eprintln!("Got an error deleting bucket {}: {}", &opt.source, e);
+        }
+    }
+
+    if resources.destination_bucket {
+        if let Err(e) = delete_bucket(&clients.destination, &opt.destination).await {
+            eprintln!("Got an error deleting bucket {}: {}", &opt.destination, e);
+        }
+    }
+
+    if resources.role_policy {
+        if let Err(e) = clients
+            .iam
+            .delete_role_policy()
+            .role_name(&opt.name)
+            .policy_name(POLICY_NAME)
+            .send()
+            .await
+        {
+            eprintln!("Got an error deleting the policy of {}: {}", &opt.name, e);
+        }
+    }
+
+    if resources.role {
+        if let Err(e) = clients.iam.delete_role().role_name(&opt.name).send().await {
+            eprintln!("Got an error deleting role {}: {}", &opt.name, e);
+        }
+    }
+}
+
+/// Sets up cross-region replication between two Amazon S3 buckets, and checks that it works.
+/// The scenario creates a source bucket and a destination bucket in another region, enables versioning of both,
+/// creates the IAM role that Amazon S3 assumes to replicate objects, and puts the replication configuration
+/// on the source bucket. Then it puts an object in the source bucket, and waits for it to replicate.
+/// Finally, it deletes every version of every object, the buckets, and the role.
+/// # Arguments
+///
+/// * `-s SOURCE` - The name of the source bucket to create.
+/// * `-t DESTINATION` - The name of the destination bucket to create.
+/// * `-r DESTINATION-REGION` - The region of the destination bucket.
+/// * `[-n NAME]` - The name of the role to create.
+///   If not supplied, defaults to **s3-replication**.
+/// * `[--timeout SECONDS]` - How long to wait for the object to replicate.
+///   If not supplied, defaults to **600**.
+/// * `[-k]` - Whether to keep the buckets, their objects, and the role.
+/// * `[-d DEFAULT-REGION]` - The region of the source bucket.
+///    If not supplied, uses the value of the **AWS_DEFAULT_REGION** environment variable.
+///    If the environment variable is not set, defaults to **us-west-2**.
+/// * `[--profile PROFILE]` - The AWS profile to use from the shared credentials file.
+/// * `[-v]` - Whether to display additional information.
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn Error>> {
+    let opt = Opt::from_args();
+
+    let source_region = opt
+        .default_region
+        .as_ref()
+        .map(|region| Region::new(region.clone()))
+        .or_else(|| aws_types::region::default_provider().region())
+        .unwrap_or_else(|| Region::new("us-west-2"));
+    let destination_region = Region::new(opt.destination_region.clone());
+
+    if source_region.as_ref() == destination_region.as_ref() {
+        return Err(format!(
+            "the destination region must differ from the source region, {}",
+            source_region.as_ref()
+        )
+        .into());
+    }
+
+    if opt.verbose {
+        println!("IAM client version: {}", iam::PKG_VERSION);
+        println!("S3 client version:  {}", s3::PKG_VERSION);
+        println!(
+            "Source:             {} in {:?}",
+            &opt.source, &source_region
+        );
+        println!(
+            "Destination:        {} in {:?}",
+            &opt.destination, &destination_region
+        );
+        println!("Role:               {}", &opt.name);
+        println!();
+
+        SubscriberBuilder::default()
+            .with_env_filter("info")
+            .with_span_events(FmtSpan::CLOSE)
+            .init();
+    }
+
+    let mut iam_config = iam::Config::builder().region(source_region.clone());
+    let mut source_config = s3::Config::builder().region(source_region.clone());
+    let mut destination_config = s3::Config::builder().region(destination_region.clone());
+    if let Some(profile) = &opt.profile {
+        iam_config = iam_config.credentials_provider(ProfileCredentialsProvider::new(profile));
+        source_config =
+            source_config.credentials_provider(ProfileCredentialsProvider::new(profile));
+        destination_config =
+            destination_config.credentials_provider(ProfileCredentialsProvider::new(profile));
+    }
+
+    let clients = Clients {
+        iam: iam::Client::from_conf(iam_config.build()),
+        source: s3::Client::from_conf(source_config.build()),
+        destination: s3::Client::from_conf(destination_config.build()),
+    };
+
+    let mut resources = Resources::default();
+
+    // Ctrl-C stops the scenario wherever it is, and still cleans up what it created.
+    let shutdown = Shutdown::listen();
+    let result = shutdown
+        .run(run(
+            &clients,
+            &source_region,
+            &destination_region,
+            &opt,
+            &mut resources,
+        ))
+        .await;
+
+    if opt.keep {
+        println!(
+            "Keeping buckets {} and {}, and role {}",
+            &opt.source, &opt.destination, &opt.name
+        );
+    } else {
+        println!("8. Deleting the buckets and the role");
+        tear_down(&clients, &opt, &resources).await;
+    }
+
+    result
+}
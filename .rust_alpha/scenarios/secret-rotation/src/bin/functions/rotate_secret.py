@@ -0,0 +1,96 @@
+# Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+# SPDX-License-Identifier: Apache-2.0
+
+"""Rotates a secret whose value is a JSON object with a password field.
+
+AWS Secrets Manager calls the function once for each of the four steps of a rotation.
+A function that rotates the credentials of a real database or service would also
+change the password there in setSecret, and sign in with it in testSecret.
+"""
+
+import json
+
+import boto3
+
+service = boto3.client("secretsmanager")
+
+
+def handler(event, context):
+    arn = event["SecretId"]
+    token = event["ClientRequestToken"]
+    step = event["Step"]
+
+    metadata = service.describe_secret(SecretId=arn)
+    if not metadata.get("RotationEnabled"):
+        raise ValueError("Rotation is not enabled for secret %s" % arn)
+
+    stages = metadata["VersionIdsToStages"]
+    if token not in stages:
+        raise ValueError("Version %s of secret %s has no stage" % (token, arn))
+    if "AWSCURRENT" in stages[token]:
+        print("Version %s is already current" % token)
+        return
+    if "AWSPENDING" not in stages[token]:
+        raise ValueError("Version %s of secret %s is not pending" % (token, arn))
+
+    if step == "createSecret":
+        create_secret(arn, token)
+    elif step == "setSecret":
+        print("setSecret: nothing to update for this secret")
+    elif step == "testSecret":
+        test_secret(arn, token)
+    elif step == "finishSecret":
+        finish_secret(arn, token, stages)
+    else:
+        raise ValueError("Unknown step %s" % step)
+
+
+def create_secret(arn, token):
+    """Stores a copy of the current value with a new random password as the pending version."""
+    current = json.loads(
+        service.get_secret_value(SecretId=arn, VersionStage="AWSCURRENT")["SecretString"]
+    )
+
+    try:
+        service.get_secret_value(SecretId=arn, VersionId=token, VersionStage="AWSPENDING")
+        print("createSecret: the pending version already exists")
+        return
+    except service.exceptions.ResourceNotFoundException:
+        pass
+
+    current["password"] = service.get_random_password(ExcludeCharacters="/@\"'\\")[
+        "RandomPassword"
+    ]
+    service.put_secret_value(
+        SecretId=arn,
+        ClientRequestToken=token,
+        SecretString=json.dumps(current),
+        VersionStages=["AWSPENDING"],
+    )
+    print("createSecret: stored the pending version %s" % token)
+
+
+def test_secret(arn, token):
+    """Checks that the pending version has a password."""
+    pending = json.loads(
+        service.get_secret_value(SecretId=arn, VersionId=token, VersionStage="AWSPENDING")[
+            "SecretString"
+        ]
+    )
+    if not pending.get("password"):
+        raise ValueError("The pending version has no password")
+    print("testSecret: the pending version has a password")
+
+
+def finish_secret(arn, token, stages):
+    """Makes the pending version current. Secrets Manager then labels the old version AWSPREVIOUS."""
+    current_version = next(
+        version for version, labels in stages.items() if "AWSCURRENT" in labels
+    )
+    service.update_secret_version_stage(
+        SecretId=arn,
+        VersionStage="AWSCURRENT",
+        MoveToVersionId=token,
+        RemoveFromVersionId=current_version,
+    )
+    print("finishSecret: version %s is now current" % token)
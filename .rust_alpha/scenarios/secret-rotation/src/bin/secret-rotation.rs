@@ -0,0 +1,507 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0.
+ */
+
+use std::collections::HashMap;
+use std::error::Error;
+use std::io::{Cursor, Write};
+use std::time::{Duration, Instant};
+
+use aws_types::region::{ProvideRegion, Region};
+
+use lambda::model::{FunctionCode, Runtime, State};
+use lambda::Blob;
+use secretsmanager::model::RotationRulesType;
+
+use common::profile::ProfileCredentialsProvider;
+use common::shutdown::Shutdown;
+use serde_json::{json, Value};
+use structopt::StructOpt;
+use tokio::time::sleep;
+use tracing_subscriber::fmt::format::FmtSpan;
+use tracing_subscriber::fmt::SubscriberBuilder;
+use zip::write::FileOptions;
+use zip::ZipWriter;
+
+/// The source of the function that rotates the secret.
+const ROTATE_SECRET_SOURCE: &str = include_str!("functions/rotate_secret.py");
+
+/// The managed policy that lets a function write its logs to Amazon CloudWatch Logs.
+const LAMBDA_BASIC_EXECUTION_POLICY: &str =
+    "arn:aws:iam::aws:policy/service-role/AWSLambdaBasicExecutionRole";
+
+/// The name of the inline policy that lets the function rotate the secret.
+const ROTATE_POLICY_NAME: &str = "rotate-secret";
+
+/// The password that the secret starts with.
+const INITIAL_PASSWORD: &str = "initial-password";
+
+/// How long to wait for the rotation to finish.
+const ROTATION_TIMEOUT: Duration = Duration::from_secs(300);
+
+#[derive(Debug, StructOpt)]
+struct Opt {
+    /// The region. Overrides environment variable AWS_DEFAULT_REGION.
+    #[structopt(short, long)]
+    default_region: Option<String>,
+
+    /// The name of the secret, and the prefix of the names of the function and role
+    #[structopt(short, long, default_value = "secret-rotation")]
+    name: String,
+
+    /// Whether to keep the secret, function, and role
+    #[structopt(short, long)]
+    keep: bool,
+
+    /// The AWS profile to use from the shared credentials file
+    #[structopt(long)]
+    profile: Option<String>,
+
+    /// Whether to display additional runtime information
+    #[structopt(short, long)]
+    verbose: bool,
+}
+
+/// The AWS clients used by the scenario.
+struct Clients {
+    iam: iam::Client,
+    lambda: lambda::Client,
+    secretsmanager: secretsmanager::Client,
+}
+
+/// The resources that the scenario has created, so that they can be deleted even if a later step fails.
+#[derive(Default)]
+struct Resources {
+    secret_arn: Option<String>,
+    role: Option<String>,
+    role_policy: bool,
+    function: Option<String>,
+}
+
+/// Returns a deployment package containing a single Python file named index.py.
+fn zip_source(source: &str) -> Result<Vec<u8>, Box<dyn Error>> {
+    let mut zip = ZipWriter::new(Cursor::new(Vec::new()));
+    zip.start_file("index.py", FileOptions::default())?;
+    zip.write_all(source.as_bytes())?;
+
+    Ok(zip.finish()?.into_inner())
+}
+
+/// Creates the role that the function runs as, and returns its ARN.
+/// The role can only read and update the one secret.
+async fn create_role(
+    client: &iam::Client,
+    name: &str,
+    secret_arn: &str,
+    resources: &mut Resources,
+) -> Result<String, Box<dyn Error>> {
+    let trust_policy = json!({
+        "Version": "2012-10-17",
+        "Statement": [{
+            "Effect": "Allow",
+            "Principal": { "Service": "lambda.amazonaws.com" },
+            "Action": "sts:AssumeRole"
+        }]
+    });
+
+    let arn = client
+        .create_role()
+        .role_name(name)
+        .assume_role_policy_document(trust_policy.to_string())
+        .send()
+        .await?
+        .role
+        .and_then(|role| role.arn)
+        .ok_or("IAM did not return the role ARN")?;
+    resources.role = Some(name.to_string());
+
+    client
+        .attach_role_policy()
+        .role_name(name)
+        .policy_arn(LAMBDA_BASIC_EXECUTION_POLICY)
+        .send()
+        .await?;
+
+    let rotate_policy = json!({
+        "Version": "2012-10-17",
+        "Statement": [
+            {
+                "Effect": "Allow",
+                "Action": [
+                    "secretsmanager:DescribeSecret",
+                    "secretsmanager:GetSecretValue",
+                    "secretsmanager:PutSecretValue",
+                    "secretsmanager:UpdateSecretVersionStage"
+                ],
+                "Resource": secret_arn
+            },
+            {
+                "Effect": "Allow",
+                "Action": "secretsmanager:GetRandomPassword",
+                "Resource": "*"
+            }
+        ]
+    });
+
+    client
+        .put_role_policy()
+        .role_name(name)
+        .policy_name(ROTATE_POLICY_NAME)
+        .policy_document(rotate_policy.to_string())
+        .send()
+        .await?;
+    resources.role_policy = true;
+
+    Ok(arn)
+}
+
+/// Waits until a new function is active, so that AWS Secrets Manager can invoke it.
+async fn wait_for_function(client: &lambda::Client, name: &str) -> Result<(), lambda::Error> {
+    loop {
+        let state = client
+            .get_function_configuration()
+            .function_name(name)
+            .send()
+            .await?
+            .state;
+
+        if state == Some(State::Active) {
+            return Ok(());
+        }
+
+        sleep(Duration::from_secs(2)).await;
+    }
+}
+
+/// Returns the stages of each version of the secret, such as __AWSCURRENT__.
+async fn version_stages(
+    client: &secretsmanager::Client,
+    secret_arn: &str,
+) -> Result<HashMap<String, Vec<String>>, secretsmanager::Error> {
+    Ok(client
+        .describe_secret()
+        .secret_id(secret_arn)
+        .send()
+        .await?
+        .version_ids_to_stages
+        .unwrap_or_default())
+}
+
+/// Displays the stages of each version of the secret.
+fn show_stages(stages: &HashMap<String, Vec<String>>) {
+    let mut versions = stages.iter().collect::<Vec<_>>();
+    versions.sort();
+
+    for (version, labels) in versions {
+        println!("  {} {}", version, labels.join(", "));
+    }
+}
+
+/// Waits until the new version is current, displaying the stages each time they change.
+async fn wait_for_rotation(
+    client: &secretsmanager::Client,
+    secret_arn: &str,
+    new_version: &str,
+) -> Result<HashMap<String, Vec<String>>, Box<dyn Error>> {
+    let start = Instant::now();
+    let mut last = HashMap::new();
+
+    loop {
+        let stages = version_stages(client, secret_arn).await?;
+
+        if stages != last {
+            println!("  After {} seconds:", start.elapsed().as_secs());
+            show_stages(&stages);
+        }
+
+        let is_current = stages
+            .get(new_version)
+            .map(|labels| labels.iter().any(|label| label == "AWSCURRENT"))
+            .unwrap_or_default();
+
+        if is_current {
+            return Ok(stages);
+        }
+
+        if start.elapsed() > ROTATION_TIMEOUT {
+            return Err(format!(
+                "version {} did not become current within {} seconds",
+                new_version,
+                ROTATION_TIMEOUT.as_secs()
+            )
+            .into());
+        }
+
+        last = stages;
+        sleep(Duration::from_secs(5)).await;
+    }
+}
+
+/// Returns the password in the version of the secret with the stage.
+async fn password(
+    client: &secretsmanager::Client,
+    secret_arn: &str,
+    stage: &str,
+) -> Result<String, Box<dyn Error>> {
+    let secret_string = client
+        .get_secret_value()
+        .secret_id(secret_arn)
+        .version_stage(stage)
+        .send()
+        .await?
+        .secret_string
+        .ok_or("the secret has no string value")?;
+
+    let value: Value = serde_json::from_str(&secret_string)?;
+
+    Ok(value["password"]
+        .as_str()
+        .ok_or("the secret has no password")?
+        .to_string())
+}
+
+/// Runs the steps of the scenario, stopping at the first error.
+async fn run(
+    clients: &Clients,
+    opt: &Opt,
+    resources: &mut Resources,
+) -> Result<(), Box<dyn Error>> {
+    println!("1. Creating secret {}", &opt.name);
+    let initial = json!({ "username": "app", "password": INITIAL_PASSWORD });
+    let secret = clients
+        .secretsmanager
+        .create_secret()
+        .name(&opt.name)
+        .secret_string(initial.to_string())
+        .send()
+        .await?;
+    let secret_arn = secret.arn.ok_or("Secrets Manager did not return the ARN")?;
+    let initial_version = secret.version_id.unwrap_or_default();
+    resources.secret_arn = Some(secret_arn.clone());
+    println!("  Version {} is current", &initial_version);
+
+    println!("2. Creating role");
+    let role = format!("{}-lambda", &opt.name);
+    let role_arn = create_role(&clients.iam, &role, &secret_arn, resources).await?;
+
+    // New roles and policies take a few seconds before AWS Lambda can use them.
+    sleep(Duration::from_secs(10)).await;
+
+    let function = format!("{}-rotate", &opt.name);
+    println!("3. Deploying rotation function {}", &function);
+    let function_arn = clients
+        .lambda
+        .create_function()
+        .function_name(&function)
+        .runtime(Runtime::Python38)
+        .handler("index.handler")
+        .role(&role_arn)
+        .code(
+            FunctionCode::builder()
+                .zip_file(Blob::new(zip_source(ROTATE_SECRET_SOURCE)?))
+                .build(),
+        )
+        .send()
+        .await?
+        .function_arn
+        .ok_or("AWS Lambda did not return the function ARN")?;
+    resources.function = Some(function.clone());
+
+    // Only Secrets Manager, and only for this secret, can invoke the function.
+    clients
+        .lambda
+        .add_permission()
+        .function_name(&function)
+        .statement_id("secretsmanager-rotation")
+        .action("lambda:InvokeFunction")
+        .principal("secretsmanager.amazonaws.com")
+        .source_arn(&secret_arn)
+        .send()
+        .await?;
+
+    wait_for_function(&clients.lambda, &function).await?;
+
+    // Turning on rotation also starts the first rotation right away.
+    println!("4. Turning on rotation every 30 days");
+    let new_version = clients
+        .secretsmanager
+        .rotate_secret()
+        .secret_id(&secret_arn)
+        .rotation_lambda_arn(&function_arn)
+        .rotation_rules(
+            RotationRulesType::builder()
+                .automatically_after_days(30)
+                .build(),
+        )
+        .send()
+        .await?
+        .version_id
+        .ok_or("Secrets Manager did not return the new version ID")?;
+    println!("  Rotating to version {}", &new_version);
+
+    println!("5. Waiting for the rotation to finish");
+    let stages = wait_for_rotation(&clients.secretsmanager, &secret_arn, &new_version).await?;
+
+    println!("6. Verifying the version stages");
+    let is_previous = stages
+        .get(&initial_version)
+        .map(|labels| labels.iter().any(|label| label == "AWSPREVIOUS"))
+        .unwrap_or_default();
+    if !is_previous {
+        return Err(format!("version {} is not AWSPREVIOUS", &initial_version).into());
+    }
+    println!("  The original version is now AWSPREVIOUS");
+
+    let current = password(&clients.secretsmanager, &secret_arn, "AWSCURRENT").await?;
+    let previous = password(&clients.secretsmanager, &secret_arn, "AWSPREVIOUS").await?;
+    if current == previous || previous != INITIAL_PASSWORD {
+        return Err("the rotation did not change the password".into());
+    }
+    println!(
+        "  The current password has {} characters, and the previous password is still readable",
+        current.len()
+    );
+
+    Ok(())
+}
+
+/// Deletes the secret, function, and role.
+/// Keeps going after an error, so that as much as possible is cleaned up.
+async fn tear_down(clients: &Clients, resources: &Resources) {
+    // Without a recovery window, the secret is deleted right away,
+    // so that the scenario can run again with the same name.
+    if let Some(arn) = &resources.secret_arn {
+        if let Err(e) = clients
+            .secretsmanager
+            .delete_secret()
+            .secret_id(arn)
+            .force_delete_without_recovery(true)
+            .send()
+            .await
+        {
+            eprintln!("Got an error deleting secret {}: {}", arn, e);
+        }
+    }
+
+    if let Some(function) = &resources.function {
+        if let Err(e) = clients
+            .lambda
+            .delete_function()
+            .function_name(function)
+            .send()
+            .await
+        {
+            eprintln!("Got an error deleting function {}: {}", function, e);
+        }
+    }
+
+    // IAM won't delete a role that still has policies.
+    if let Some(role) = &resources.role {
+        if resources.role_policy {
+            if let Err(e) = clients
+                .iam
+                .delete_role_policy()
+                .role_name(role)
+                .policy_name(ROTATE_POLICY_NAME)
+                .send()
+                .await
+            {
+                eprintln!("Got an error deleting the policy from {}: {}", role, e);
+            }
+        }
+
+        if let Err(e) = clients
+            .iam
+            .detach_role_policy()
+            .role_name(role)
+            .policy_arn(LAMBDA_BASIC_EXECUTION_POLICY)
+            .send()
+            .await
+        {
+            eprintln!("Got an error detaching the policy from {}: {}", role, e);
+        }
+
+        if let Err(e) = clients.iam.delete_role().role_name(role).send().await {
+            eprintln!("Got an error deleting role {}: {}", role, e);
+        }
+    }
+}
+
+/// Rotates an AWS Secrets Manager secret with an AWS Lambda function.
+/// The scenario creates a secret with a username and password,
+/// deploys a rotation function from the bundled template, and turns on rotation,
+/// which rotates the secret right away.
+/// It waits for the new version to become current,
+/// and verifies that the original version is still readable as the previous version.
+/// Finally, it deletes everything it created.
+/// # Arguments
+///
+/// * `[-n NAME]` - The name of the secret, and the prefix of the names of the function and role.
+///   If not supplied, defaults to **secret-rotation**.
+/// * `[-k]` - Whether to keep the secret, function, and role.
+/// * `[-d DEFAULT-REGION]` - The region in which the clients are created.
+///    If not supplied, uses the value of the **AWS_DEFAULT_REGION** environment variable.
+///    If the environment variable is not set, defaults to **us-west-2**.
+/// * `[--profile PROFILE]` - The AWS profile to use from the shared credentials file.
+/// * `[-v]` - Whether to display additional information.
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn Error>> {
+    let opt = Opt::from_args();
+
+    let region = opt
+        .default_region
+        .as_ref()
+        .map(|region| Region::new(region.clone()))
+        .or_else(|| aws_types::region::default_provider().region())
+        .unwrap_or_else(|| Region::new("us-west-2"));
+
+    if opt.verbose {
+        println!("IAM client version:             {}", iam::PKG_VERSION);
+        println!("Lambda client version:          {}", lambda::PKG_VERSION);
+        println!(
+            "Secrets Manager client version: {}",
+            secretsmanager::PKG_VERSION
+        );
+        println!("Region:                         {:?}", &region);
+        println!("Name:                           {}", &opt.name);
+        println!();
+
+        SubscriberBuilder::default()
+            .with_env_filter("info")
+            .with_span_events(FmtSpan::CLOSE)
+            .init();
+    }
+
+    let mut iam_config = iam::Config::builder().region(region.clone());
+    let mut lambda_config = lambda::Config::builder().region(region.clone());
+    let mut secretsmanager_config = secretsmanager::Config::builder().region(region);
+    if let Some(profile) = &opt.profile {
+        iam_config = iam_config.credentials_provider(ProfileCredentialsProvider::new(profile));
+        lambda_config =
+            lambda_config.credentials_provider(ProfileCredentialsProvider::new(profile));
+        secretsmanager_config =
+            secretsmanager_config.credentials_provider(ProfileCredentialsProvider::new(profile));
+    }
+
+    let clients = Clients {
+        iam: iam::Client::from_conf(iam_config.build()),
+        lambda: lambda::Client::from_conf(lambda_config.build()),
+        secretsmanager: secretsmanager::Client::from_conf(secretsmanager_config.build()),
+    };
+
+    let mut resources = Resources::default();
+
+    // Ctrl-C stops the scenario wherever it is, and still cleans up what it created.
+    let shutdown = Shutdown::listen();
+    let result = shutdown.run(run(&clients, &opt, &mut resources)).await;
+
+    if opt.keep {
+        println!("Keeping the resources named {}", &opt.name);
+    } else {
+        println!("7. Deleting the secret, function, and role");
+        tear_down(&clients, &resources).await;
+    }
+
+    result
+}
@@ -0,0 +1,396 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0.
+ */
+
+use std::collections::BTreeSet;
+use std::error::Error;
+use std::time::Duration;
+
+use aws_types::region::{ProvideRegion, Region};
+
+use sns::model::MessageAttributeValue;
+use sqs::model::QueueAttributeName;
+
+use common::profile::ProfileCredentialsProvider;
+use common::shutdown::Shutdown;
+use serde_json::json;
+use structopt::StructOpt;
+use tokio::time::sleep;
+use tracing_subscriber::fmt::format::FmtSpan;
+use tracing_subscriber::fmt::SubscriberBuilder;
+
+#[derive(Debug, StructOpt)]
+struct Opt {
+    /// The region. Overrides environment variable AWS_DEFAULT_REGION.
+    #[structopt(short, long)]
+    default_region: Option<String>,
+
+    /// The prefix of the names of the topic and queues
+    #[structopt(short, long, default_value = "fanout")]
+    name: String,
+
+    /// Whether to keep the topic, subscriptions, and queues
+    #[structopt(short, long)]
+    keep: bool,
+
+    /// The AWS profile to use from the shared credentials file
+    #[structopt(long)]
+    profile: Option<String>,
+
+    /// Whether to display additional runtime information
+    #[structopt(short, long)]
+    verbose: bool,
+}
+
+/// A queue and the filter policy of its subscription to the topic.
+struct Subscriber {
+    /// The suffix of the queue name.
+    name: &'static str,
+    /// The message attribute the filter policy matches on.
+    attribute: &'static str,
+    /// The attribute values the filter policy accepts.
+    values: &'static [&'static str],
+}
+
+/// The queues that the scenario creates. Each one receives a different subset of the messages.
+const SUBSCRIBERS: &[Subscriber] = &[
+    Subscriber {
+        name: "orders",
+        attribute: "event",
+        values: &["order_placed", "order_cancelled"],
+    },
+    Subscriber {
+        name: "shipping",
+        attribute: "event",
+        values: &["order_placed", "order_shipped"],
+    },
+    Subscriber {
+        name: "priority",
+        attribute: "priority",
+        values: &["high"],
+    },
+];
+
+/// A message that the scenario publishes, as (body, event, priority).
+const MESSAGES: &[(&str, &str, &str)] = &[
+    ("Order 1 placed", "order_placed", "normal"),
+    ("Order 2 placed", "order_placed", "high"),
+    ("Order 1 shipped", "order_shipped", "normal"),
+    ("Order 3 cancelled", "order_cancelled", "normal"),
+    ("Order 2 shipped", "order_shipped", "high"),
+    ("Inventory low", "inventory_low", "high"),
+];
+
+/// The AWS clients used by the scenario.
+struct Clients {
+    sns: sns::Client,
+    sqs: sqs::Client,
+}
+
+/// The resources that the scenario has created, so that they can be deleted even if a later step fails.
+#[derive(Default)]
+struct Resources {
+    topic_arn: Option<String>,
+    queue_urls: Vec<String>,
+    subscription_arns: Vec<String>,
+}
+
+impl Subscriber {
+    /// Returns whether the filter policy accepts a message with the given attributes.
+    fn accepts(&self, event: &str, priority: &str) -> bool {
+        let value = match self.attribute {
+            "event" => event,
+            _ => priority,
+        };
+
+        self.values.contains(&value)
+    }
+
+    /// Returns the filter policy as a JSON document.
+    fn filter_policy(&self) -> String {
+        json!({ self.attribute: self.values }).to_string()
+    }
+}
+
+/// Returns a string message attribute.
+fn attribute(value: &str) -> MessageAttributeValue {
+    MessageAttributeValue::builder()
+        .data_type("String")
+        .string_value(value)
+        .build()
+}
+
+/// Creates a queue that the topic is allowed to send messages to,
+/// and returns the queue URL and ARN.
+async fn create_queue(
+    client: &sqs::Client,
+    name: &str,
+    topic_arn: &str,
+) -> Result<(String, String), Box<dyn Error>> {
+    let queue_url = client
+        .create_queue()
+        .queue_name(name)
+        .send()
+        .await?
+        .queue_url
+        .ok_or("Amazon SQS did not return a queue URL")?;
+
+    let queue_arn = client
+        .get_queue_attributes()
+        .queue_url(&queue_url)
+        .attribute_names(QueueAttributeName::QueueArn)
+        .send()
+        .await?
+        .attributes
+        .and_then(|mut attributes| attributes.remove(&QueueAttributeName::QueueArn))
+        .ok_or("Amazon SQS did not return a queue ARN")?;
+
+    // Without this policy, Amazon SNS can't deliver messages to the queue.
+    let policy = json!({
+        "Version": "2012-10-17",
+        "Statement": [{
+            "Effect": "Allow",
+            "Principal": { "Service": "sns.amazonaws.com" },
+            "Action": "sqs:SendMessage",
+            "Resource": &queue_arn,
+            "Condition": { "ArnEquals": { "aws:SourceArn": topic_arn } }
+        }]
+    });
+
+    client
+        .set_queue_attributes()
+        .queue_url(&queue_url)
+        .attributes(QueueAttributeName::Policy, policy.to_string())
+        .send()
+        .await?;
+
+    Ok((queue_url, queue_arn))
+}
+
+/// Receives every message in the queue, deletes them, and returns their bodies.
+/// Stops after a long poll returns no messages.
+async fn drain_queue(
+    client: &sqs::Client,
+    queue_url: &str,
+) -> Result<BTreeSet<String>, sqs::Error> {
+    let mut bodies = BTreeSet::new();
+
+    loop {
+        let messages = client
+            .receive_message()
+            .queue_url(queue_url)
+            .max_number_of_messages(10)
+            .wait_time_seconds(5)
+            .send()
+            .await?
+            .messages
+            .unwrap_or_default();
+
+        if messages.is_empty() {
+            return Ok(bodies);
+        }
+
+        for message in messages {
+            if let Some(handle) = message.receipt_handle {
+                client
+                    .delete_message()
+                    .queue_url(queue_url)
+                    .receipt_handle(handle)
+                    .send()
+                    .await?;
+            }
+
+            bodies.extend(message.body);
+        }
+    }
+}
+
+/// Runs the steps of the scenario, stopping at the first error.
+/// Returns whether every queue received exactly the messages its filter policy accepts.
+async fn run(
+    clients: &Clients,
+    opt: &Opt,
+    resources: &mut Resources,
+) -> Result<bool, Box<dyn Error>> {
+    println!("1. Creating topic {}", &opt.name);
+    let topic_arn = clients
+        .sns
+        .create_topic()
+        .name(&opt.name)
+        .send()
+        .await?
+        .topic_arn
+        .ok_or("Amazon SNS did not return a topic ARN")?;
+    resources.topic_arn = Some(topic_arn.clone());
+
+    println!("2. Creating and subscribing {} queues", SUBSCRIBERS.len());
+    for subscriber in SUBSCRIBERS {
+        let queue_name = format!("{}-{}", &opt.name, subscriber.name);
+        let (queue_url, queue_arn) = create_queue(&clients.sqs, &queue_name, &topic_arn).await?;
+        resources.queue_urls.push(queue_url);
+
+        // Raw delivery puts just the message in the queue, instead of the Amazon SNS JSON envelope.
+        let subscription_arn = clients
+            .sns
+            .subscribe()
+            .topic_arn(&topic_arn)
+            .protocol("sqs")
+            .endpoint(queue_arn)
+            .attributes("FilterPolicy", subscriber.filter_policy())
+            .attributes("RawMessageDelivery", "true")
+            .return_subscription_arn(true)
+            .send()
+            .await?
+            .subscription_arn
+            .ok_or("Amazon SNS did not return a subscription ARN")?;
+        resources.subscription_arns.push(subscription_arn);
+
+        println!("  {} accepts {}", queue_name, subscriber.filter_policy());
+    }
+
+    // Filter policies can take a little while to take effect.
+    sleep(Duration::from_secs(10)).await;
+
+    println!("3. Publishing {} messages", MESSAGES.len());
+    for (body, event, priority) in MESSAGES {
+        clients
+            .sns
+            .publish()
+            .topic_arn(&topic_arn)
+            .message(*body)
+            .message_attributes("event", attribute(event))
+            .message_attributes("priority", attribute(priority))
+            .send()
+            .await?;
+
+        println!("  {} (event={}, priority={})", body, event, priority);
+    }
+
+    println!("4. Receiving the messages in each queue");
+    let mut all_match = true;
+
+    for (subscriber, queue_url) in SUBSCRIBERS.iter().zip(&resources.queue_urls) {
+        let received = drain_queue(&clients.sqs, queue_url).await?;
+        let expected = MESSAGES
+            .iter()
+            .filter(|(_, event, priority)| subscriber.accepts(event, priority))
+            .map(|(body, _, _)| body.to_string())
+            .collect::<BTreeSet<_>>();
+
+        println!("  {}-{}:", &opt.name, subscriber.name);
+        for body in &received {
+            println!("    {}", body);
+        }
+
+        if received == expected {
+            println!("    Received the {} expected messages", expected.len());
+        } else {
+            all_match = false;
+            println!("    Expected {:?}", expected);
+        }
+    }
+
+    Ok(all_match)
+}
+
+/// Deletes the subscriptions, topic, and queues.
+/// Keeps going after an error, so that as much as possible is cleaned up.
+async fn tear_down(clients: &Clients, resources: &Resources) {
+    for subscription_arn in &resources.subscription_arns {
+        if let Err(e) = clients
+            .sns
+            .unsubscribe()
+            .subscription_arn(subscription_arn)
+            .send()
+            .await
+        {
+            eprintln!("Got an error unsubscribing {}: {}", subscription_arn, e);
+        }
+    }
+
+    if let Some(topic_arn) = &resources.topic_arn {
+        if let Err(e) = clients.sns.delete_topic().topic_arn(topic_arn).send().await {
+            eprintln!("Got an error deleting topic {}: {}", topic_arn, e);
+        }
+    }
+
+    for queue_url in &resources.queue_urls {
+        if let Err(e) = clients.sqs.delete_queue().queue_url(queue_url).send().await {
+            eprintln!("Got an error deleting queue {}: {}", queue_url, e);
+        }
+    }
+}
+
+/// Demonstrates publish/subscribe messaging with Amazon SNS and Amazon SQS.
+/// The scenario creates an Amazon SNS topic and three Amazon SQS queues subscribed to it,
+/// each with a different filter policy, publishes a mix of messages to the topic,
+/// and shows which messages each queue received.
+/// Finally, it deletes the subscriptions, the topic, and the queues.
+/// # Arguments
+///
+/// * `[-n NAME]` - The prefix of the names of the topic and queues.
+///   If not supplied, defaults to **fanout**.
+/// * `[-k]` - Whether to keep the topic, subscriptions, and queues.
+/// * `[-d DEFAULT-REGION]` - The region in which the clients are created.
+///    If not supplied, uses the value of the **AWS_DEFAULT_REGION** environment variable.
+///    If the environment variable is not set, defaults to **us-west-2**.
+/// * `[--profile PROFILE]` - The AWS profile to use from the shared credentials file.
+/// * `[-v]` - Whether to display additional information.
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn Error>> {
+    let opt = Opt::from_args();
+
+    let region = opt
+        .default_region
+        .as_ref()
+        .map(|region| Region::new(region.clone()))
+        .or_else(|| aws_types::region::default_provider().region())
+        .unwrap_or_else(|| Region::new("us-west-2"));
+
+    if opt.verbose {
+        println!("SNS client version: {}", sns::PKG_VERSION);
+        println!("SQS client version: {}", sqs::PKG_VERSION);
+        println!("Region:             {:?}", &region);
+        println!("Name:               {}", &opt.name);
+        println!();
+
+        SubscriberBuilder::default()
+            .with_env_filter("info")
+            .with_span_events(FmtSpan::CLOSE)
+            .init();
+    }
+
+    let mut sns_config = sns::Config::builder().region(region.clone());
+    let mut sqs_config = sqs::Config::builder().region(region);
+    if let Some(profile) = &opt.profile {
+        sns_config = sns_config.credentials_provider(ProfileCredentialsProvider::new(profile));
+        sqs_config = sqs_config.credentials_provider(ProfileCredentialsProvider::new(profile));
+    }
+
+    let clients = Clients {
+        sns: sns::Client::from_conf(sns_config.build()),
+        sqs: sqs::Client::from_conf(sqs_config.build()),
+    };
+
+    let mut resources = Resources::default();
+
+    // Ctrl-C stops the scenario wherever it is, and still cleans up what it created.
+    let shutdown = Shutdown::listen();
+    let result = shutdown.run(run(&clients, &opt, &mut resources)).await;
+
+    if opt.keep {
+        println!("Keeping the topic and queues named {}", &opt.name);
+    } else {
+        println!("5. Deleting the subscriptions, topic, and queues");
+        tear_down(&clients, &resources).await;
+    }
+
+    if !result? {
+        return Err("some queues did not receive the expected messages".into());
+    }
+
+    println!("Every queue received the messages its filter policy accepts");
+
+    Ok(())
+}
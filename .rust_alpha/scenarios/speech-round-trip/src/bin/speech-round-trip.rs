@@ -0,0 +1,529 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0.
+ */
+
+use std::error::Error;
+use std::fs;
+use std::time::Duration;
+
+use aws_types::region::{ProvideRegion, Region};
+
+use polly::model::{OutputFormat, VoiceId};
+use s3::ByteStream;
+use transcribe::model::{LanguageCode, Media, MediaFormat, TranscriptionJobStatus};
+
+use common::profile::ProfileCredentialsProvider;
+use common::shutdown::Shutdown;
+use serde_json::Value;
+use structopt::StructOpt;
+use tokio::time::sleep;
+use tracing_subscriber::fmt::format::FmtSpan;
+use tracing_subscriber::fmt::SubscriberBuilder;
+
+/// The text that's spoken if none is supplied. It has no numbers or abbreviations,
+/// which Amazon Transcribe can write differently, such as 3 for three, without being wrong.
+const SAMPLE_TEXT: &str = "The quick brown fox jumps over the lazy dog. \
+    Amazon Polly turns text into lifelike speech, and Amazon Transcribe turns speech back into text. \
+    If both of them do their jobs well, the words that come out should match the words that went in.";
+
+/// The longest text that SynthesizeSpeech accepts, in characters, not counting SSML tags.
+const MAX_TEXT_LENGTH: usize = 3000;
+
+#[derive(Debug, StructOpt)]
+struct Opt {
+    /// The region. Overrides environment variable AWS_DEFAULT_REGION.
+    #[structopt(short, long)]
+    default_region: Option<String>,
+
+    /// The bucket used to hold the speech and the transcript
+    #[structopt(short, long)]
+    bucket: String,
+
+    /// The text to speak. Defaults to a sample text
+    #[structopt(short, long, conflicts_with = "file")]
+    text: Option<String>,
+
+    /// A file holding the text to speak
+    #[structopt(short, long)]
+    file: Option<String>,
+
+    /// The Amazon Polly voice that speaks the text
+    #[structopt(long, default_value = "Joanna")]
+    voice: String,
+
+    /// The language of the text, such as en-US, which the voice must speak
+    #[structopt(short, long, default_value = "en-US")]
+    language: String,
+
+    /// The highest word error rate that passes, from 0 to 1
+    #[structopt(short, long, default_value = "0.1")]
+    max_wer: f64,
+
+    /// Whether to keep the speech, transcript, and transcription job
+    #[structopt(short, long)]
+    keep: bool,
+
+    /// The AWS profile to use from the shared credentials file
+    #[structopt(long)]
+    profile: Option<String>,
+
+    /// Whether to display additional runtime information
+    #[structopt(short, long)]
+    verbose: bool,
+}
+
+/// The AWS clients used by the scenario.
+struct Clients {
+    polly: polly::Client,
+    s3: s3::Client,
+    transcribe: transcribe::Client,
+}
+
+/// How one word of the original text lines up with the transcript.
+#[derive(Debug, PartialEq)]
+enum Edit {
+    /// The transcript has the word.
+    Same(String),
+    /// The transcript has another word in its place.
+    Substituted(String, String),
+    /// The transcript is missing the word.
+    Deleted(String),
+    /// The transcript has a word that isn't in the text.
+    Inserted(String),
+}
+
+/// Splits text into lowercase words, without punctuation,
+/// so that only differences in the words themselves count as errors.
+/// Apostrophes and hyphens inside words are kept, so don't and dont are different words.
+fn words(text: &str) -> Vec<String> {
+    text.split_whitespace()
+        .map(|word| {
+            word.trim_matches(|c: char| !c.is_alphanumeric())
+                .chars()
+                .filter(|c| c.is_alphanumeric() || *c == '\'' || *c == '-')
+                .collect::<String>()
+                .to_lowercase()
+        })
+        .filter(|word| !word.is_empty())
+        .collect()
+}
+
+/// Lines up the transcript with the original text, word by word, with the fewest edits
+/// (the Levenshtein distance over words).
+fn align(reference: &[String], hypothesis: &[String]) -> Vec<Edit> {
+    let (n, m) = (reference.len(), hypothesis.len());
+
+    // distance[i][j] is the number of edits that turn the first i reference words into the first j transcript words.
+    let mut distance = vec![vec![0usize; m + 1]; n + 1];
+    for (i, row) in distance.iter_mut().enumerate() {
+        row[0] = i;
+    }
+    for j in 0..=m {
+        distance[0][j] = j;
+    }
+
+    for i in 1..=n {
+        for j in 1..=m {
+            let substitution = distance[i - 1][j - 1]
+                + if reference[i - 1] == hypothesis[j - 1] {
+                    0
+                } else {
+                    1
+                };
+            distance[i][j] = substitution
+                .min(distance[i - 1][j] + 1)
+                .min(distance[i][j - 1] + 1);
+        }
+    }
+
+    // Walks back from the end, preferring matches and substitutions, to recover the edits.
+    let mut edits = Vec::new();
+    let (mut i, mut j) = (n, m);
+
+    while i > 0 || j > 0 {
+        if i > 0 && j > 0 {
+            let same = reference[i - 1] == hypothesis[j - 1];
+            let cost = if same { 0 } else { 1 };
+
+            if distance[i][j] == distance[i - 1][j - 1] + cost {
+                edits.push(if same {
+                    Edit::Same(reference[i - 1].clone())
+                } else {
+                    Edit::Substituted(reference[i - 1].clone(), hypothesis[j - 1].clone())
+                });
+                i -= 1;
+                j -= 1;
+                continue;
+            }
+        }
+
+        if i > 0 && distance[i][j] == distance[i - 1][j] + 1 {
+            edits.push(Edit::Deleted(reference[i - 1].clone()));
+            i -= 1;
+        } else {
+            edits.push(Edit::Inserted(hypothesis[j - 1].clone()));
+            j -= 1;
+        }
+    }
+
+    edits.reverse();
+    edits
+}
+
+/// Returns the word error rate: the substitutions, deletions, and insertions,
+/// divided by the number of words in the original text.
+/// It can be more than 1, if the transcript has many more words than the text.
+fn word_error_rate(edits: &[Edit]) -> f64 {
+    let errors = edits
+        .iter()
+        .filter(|edit| !matches!(edit, Edit::Same(_)))
+        .count();
+    let reference_words = edits
+        .iter()
+        .filter(|edit| !matches!(edit, Edit::Inserted(_)))
+        .count();
+
+    if reference_words == 0 {
+        return if errors == 0 { 0.0 } else { 1.0 };
+    }
+
+    errors as f64 / reference_words as f64
+}
+
+/// Speaks the text, and uploads the MP3 audio to the bucket. Returns its S3 URI.
+async fn synthesize(
+    clients: &Clients,
+    text: &str,
+    voice: &str,
+    bucket: &str,
+    key: &str,
+) -> Result<String, Box<dyn Error>> {
+    let resp = clients
+        .polly
+        .synthesize_speech()
+        .output_format(OutputFormat::Mp3)
+        .text(text)
+        .voice_id(VoiceId::from(voice))
+        .send()
+        .await?;
+
+    let audio = resp.audio_stream.collect().await?.into_bytes();
+    println!("  Got {} bytes of speech", audio.len());
+
+    clients
+        .s3
+        .put_object()
+        .bucket(bucket)
+        .key(key)
+        .body(ByteStream::from(audio))
+        .content_type("audio/mpeg")
+        .send()
+        .await?;
+
+    Ok(format!("s3://{}/{}", bucket, key))
+}
+
+/// Starts a transcription job and waits for it to finish.
+/// The transcript is written to the bucket as JOB-NAME.json.
+async fn transcribe_audio(
+    client: &transcribe::Client,
+    job_name: &str,
+    media_uri: &str,
+    language: &str,
+    bucket: &str,
+) -> Result<(), Box<dyn Error>> {
+    client
+        .start_transcription_job()
+        .transcription_job_name(job_name)
+        .media(Media::builder().media_file_uri(media_uri).build())
+        .media_format(MediaFormat::Mp3)
+        .language_code(LanguageCode::from(language))
+        .output_bucket_name(bucket)
+        .send()
+        .await?;
+
+    loop {
+        let resp = client
+            .get_transcription_job()
+            .transcription_job_name(job_name)
+            .send()
+            .await?;
+
+        let job = resp
+            .transcription_job
+            .ok_or("no transcription job returned")?;
+
+        match job.transcription_job_status {
+            Some(TranscriptionJobStatus::Completed) => return Ok(()),
+            Some(TranscriptionJobStatus::Failed) => {
+                return Err(format!(
+                    "transcription failed: {}",
+                    job.failure_reason.as_deref().unwrap_or("unknown reason")
+                )
+                .into())
+            }
+            status => {
+                println!("  Transcription job status: {:?}", status);
+                sleep(Duration::from_secs(5)).await;
+            }
+        }
+    }
+}
+
+/// Reads the transcript that Amazon Transcribe wrote to the bucket.
+async fn read_transcript(
+    client: &s3::Client,
+    bucket: &str,
+    key: &str,
+) -> Result<String, Box<dyn Error>> {
+    let resp = client.get_object().bucket(bucket).key(key).send().await?;
+    let data = resp.body.collect().await?;
+    let json: Value = serde_json::from_slice(&data.into_bytes())?;
+
+    // The transcript is in results.transcripts[0].transcript.
+    json["results"]["transcripts"][0]["transcript"]
+        .as_str()
+        .map(String::from)
+        .ok_or_else(|| "the transcript file did not contain a transcript".into())
+}
+
+/// Deletes the transcription job and the objects the scenario created in the bucket.
+async fn clean_up(clients: &Clients, bucket: &str, job_name: &str, keys: &[&str]) {
+    if let Err(e) = clients
+        .transcribe
+        .delete_transcription_job()
+        .transcription_job_name(job_name)
+        .send()
+        .await
+    {
+        eprintln!(
+            "Got an error deleting transcription job {}: {}",
+            job_name, e
+        );
+    }
+
+    for key in keys {
+        if let Err(e) = clients
+            .s3
+            .delete_object()
+            .bucket(bucket)
+            .key(*key)
+            .send()
+            .await
+        {
+            eprintln!("Got an error deleting {}: {}", key, e);
+        }
+    }
+}
+
+/// Runs the steps of the scenario, stopping at the first error. Returns the word error rate.
+async fn run(
+    clients: &Clients,
+    opt: &Opt,
+    text: &str,
+    audio_key: &str,
+    job_name: &str,
+) -> Result<f64, Box<dyn Error>> {
+    println!("1. Speaking the text as {}", &opt.voice);
+    let media_uri = synthesize(clients, text, &opt.voice, &opt.bucket, audio_key).await?;
+
+    println!("2. Transcribing {}", &media_uri);
+    transcribe_audio(
+        &clients.transcribe,
+        job_name,
+        &media_uri,
+        &opt.language,
+        &opt.bucket,
+    )
+    .await?;
+
+    let transcript =
+        read_transcript(&clients.s3, &opt.bucket, &format!("{}.json", job_name)).await?;
+
+    println!("3. Comparing the transcript with the text");
+    println!("  Text:       {}", text);
+    println!("  Transcript: {}", transcript);
+
+    let edits = align(&words(text), &words(&transcript));
+    let mut counts = (0, 0, 0);
+
+    for edit in &edits {
+        match edit {
+            Edit::Same(_) => {}
+            Edit::Substituted(expected, got) => {
+                println!("  Substituted: {} -> {}", expected, got);
+                counts.0 += 1;
+            }
+            Edit::Deleted(expected) => {
+                println!("  Deleted:     {}", expected);
+                counts.1 += 1;
+            }
+            Edit::Inserted(got) => {
+                println!("  Inserted:    {}", got);
+                counts.2 += 1;
+            }
+        }
+    }
+
+    let wer = word_error_rate(&edits);
+
+    println!(
+        "  {} substitutions, {} deletions, and {} insertions in {} words",
+        counts.0,
+        counts.1,
+        counts.2,
+        words(text).len()
+    );
+    println!("  Word error rate: {:.1}%", wer * 100.0);
+
+    Ok(wer)
+}
+
+/// Checks that speech survives a round trip through two services.
+/// The scenario speaks a text with Amazon Polly, uploads the speech to an Amazon S3 bucket,
+/// transcribes it with Amazon Transcribe, and compares the transcript with the text, word by word.
+/// It reports the word error rate (WER), which is the fewest substituted, deleted, and inserted words
+/// that turn the text into the transcript, divided by the number of words in the text.
+/// Case and punctuation don't count.
+/// If the WER is higher than the maximum, the scenario fails, so it can be run as a test.
+/// # Arguments
+///
+/// * `-b BUCKET` - The bucket used to hold the speech and the transcript.
+/// * `[-t TEXT]` - The text to speak.
+/// * `[-f FILE]` - A file holding the text to speak.
+///   If neither `-t` nor `-f` is supplied, speaks a sample text.
+/// * `[--voice VOICE]` - The Amazon Polly voice that speaks the text.
+///   If not supplied, defaults to **Joanna**.
+/// * `[-l LANGUAGE]` - The language of the text, which the voice must speak.
+///   If not supplied, defaults to **en-US**.
+/// * `[-m MAX-WER]` - The highest word error rate that passes, from 0 to 1.
+///   If not supplied, defaults to **0.1** (10%).
+/// * `[-k]` - Whether to keep the speech, transcript, and transcription job.
+/// * `[-d DEFAULT-REGION]` - The region in which the clients are created.
+///    If not supplied, uses the value of the **AWS_DEFAULT_REGION** environment variable.
+///    If the environment variable is not set, defaults to **us-west-2**.
+/// * `[--profile PROFILE]` - The AWS profile to use from the shared credentials file.
+/// * `[-v]` - Whether to display additional information.
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn Error>> {
+    let opt = Opt::from_args();
+
+    let region = opt
+        .default_region
+        .as_ref()
+        .map(|region| Region::new(region.clone()))
+        .or_else(|| aws_types::region::default_provider().region())
+        .unwrap_or_else(|| Region::new("us-west-2"));
+
+    let text = match (&opt.text, &opt.file) {
+        (Some(text), _) => text.clone(),
+        (None, Some(file)) => {
+            fs::read_to_string(file).map_err(|e| format!("couldn't read {}: {}", file, e))?
+        }
+        (None, None) => SAMPLE_TEXT.to_string(),
+    };
+    let text = text.trim().to_string();
+
+    if words(&text).is_empty() {
+        return Err("the text has no words".into());
+    }
+
+    if text.chars().count() > MAX_TEXT_LENGTH {
+        return Err(format!(
+            "the text is longer than the {} characters that Amazon Polly speaks at once",
+            MAX_TEXT_LENGTH
+        )
+        .into());
+    }
+
+    if !(0.0..=1.0).contains(&opt.max_wer) {
+        return Err(format!(
+            "the maximum word error rate must be from 0 to 1, got {}",
+            opt.max_wer
+        )
+        .into());
+    }
+
+    if opt.verbose {
+        println!("Polly client version:      {}", polly::PKG_VERSION);
+        println!("S3 client version:         {}", s3::PKG_VERSION);
+        println!("Transcribe client version: {}", transcribe::PKG_VERSION);
+        println!("Region:                    {:?}", &region);
+        println!("Bucket:                    {}", &opt.bucket);
+        println!("Voice:                     {}", &opt.voice);
+        println!("Language:                  {}", &opt.language);
+        println!("Maximum WER:               {}", opt.max_wer);
+        println!();
+
+        SubscriberBuilder::default()
+            .with_env_filter("info")
+            .with_span_events(FmtSpan::CLOSE)
+            .init();
+    }
+
+    let mut polly_config = polly::Config::builder().region(region.clone());
+    let mut s3_config = s3::Config::builder().region(region.clone());
+    let mut transcribe_config = transcribe::Config::builder().region(region);
+    if let Some(profile) = &opt.profile {
+        polly_config = polly_config.credentials_provider(ProfileCredentialsProvider::new(profile));
+        s3_config = s3_config.credentials_provider(ProfileCredentialsProvider::new(profile));
+        transcribe_config =
+            transcribe_config.credentials_provider(ProfileCredentialsProvider::new(profile));
+    }
+
+    let clients = Clients {
+        polly: polly::Client::from_conf(polly_config.build()),
+        s3: s3::Client::from_conf(s3_config.build()),
+        transcribe: transcribe::Client::from_conf(transcribe_config.build()),
+    };
+
+    // Transcription job names must be unique in the account and region.
+    let job_name = format!(
+        "speech-round-trip-{}",
+        std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)?
+            .as_secs()
+    );
+    let audio_key = format!("{}.mp3", job_name);
+    let transcript_key = format!("{}.json", job_name);
+
+    // Ctrl-C stops the scenario wherever it is, and still cleans up what it created.
+    let shutdown = Shutdown::listen();
+    let result = shutdown
+        .run(run(&clients, &opt, &text, &audio_key, &job_name))
+        .await;
+
+    if opt.keep {
+        println!(
+            "Keeping s3://{}/{}, s3://{}/{}, and transcription job {}",
+            &opt.bucket, &audio_key, &opt.bucket, &transcript_key, &job_name
+        );
+    } else {
+        println!("4. Cleaning up");
+        clean_up(
+            &clients,
+            &opt.bucket,
+            &job_name,
+            &[&audio_key, &transcript_key],
+        )
+        .await;
+    }
+
+    let wer = result?;
+    if wer > opt.max_wer {
+        return Err(format!(
+            "the word error rate is {:.1}%, more than the maximum of {:.1}%",
+            wer * 100.0,
+            opt.max_wer * 100.0
+        )
+        .into());
+    }
+
+    println!(
+        "PASS: the word error rate is at most {:.1}%",
+        opt.max_wer * 100.0
+    );
+
+    Ok(())
+}
@@ -0,0 +1,873 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0.
+ */
+
+use std::collections::HashMap;
+use std::error::Error;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use aws_types::region::{ProvideRegion, Region};
+
+use cloudfront::model::{
+    Aliases, CloudFrontOriginAccessIdentityConfig, CustomErrorResponse, CustomErrorResponses,
+    DefaultCacheBehavior, DistributionConfig, InvalidationBatch, MinimumProtocolVersion, Origin,
+    Origins, Paths, S3OriginConfig, SslSupportMethod, ViewerCertificate, ViewerProtocolPolicy,
+};
+use route53::model::{
+    AliasTarget, Change, ChangeAction, ChangeBatch, ResourceRecord, ResourceRecordSet, RrType,
+};
+use s3::model::{
+    BucketLocationConstraint, CreateBucketConfiguration, ErrorDocument, IndexDocument,
+    PublicAccessBlockConfiguration, WebsiteConfiguration,
+};
+use s3::ByteStream;
+
+use common::profile::ProfileCredentialsProvider;
+use common::shutdown::Shutdown;
+use serde_json::json;
+use structopt::StructOpt;
+use tracing_subscriber::fmt::format::FmtSpan;
+use tracing_subscriber::fmt::SubscriberBuilder;
+
+/// The ID of the CloudFront managed cache policy CachingOptimized.
+const CACHING_OPTIMIZED_POLICY_ID: &str = "658327ea-f89d-4fab-a63d-7e88639e58f6";
+
+/// The hosted zone ID that Route 53 uses for all alias records that point to CloudFront.
+const CLOUDFRONT_HOSTED_ZONE_ID: &str = "Z2FDTNDATAQYW2";
+
+/// The regions whose S3 website endpoints use a dash, rather than a dot, before the region.
+const LEGACY_WEBSITE_REGIONS: &[&str] = &[
+    "us-east-1",
+    "us-west-1",
+    "us-west-2",
+    "ap-southeast-1",
+    "ap-southeast-2",
+    "ap-northeast-1",
+    "eu-west-1",
+    "sa-east-1",
+];
+
+#[derive(Debug, StructOpt)]
+struct Opt {
+    /// The region. Overrides environment variable AWS_DEFAULT_REGION.
+    #[structopt(short, long)]
+    default_region: Option<String>,
+
+    /// The bucket that holds the site. Created if it doesn't exist
+    #[structopt(short, long)]
+    bucket: String,
+
+    /// The directory containing the site
+    #[structopt(short, long, default_value = "site")]
+    site_dir: String,
+
+    /// Whether to serve the site through a CloudFront distribution instead of S3 website hosting
+    #[structopt(short, long)]
+    cloudfront: bool,
+
+    /// The domain name to point at the site, such as www.example.com
+    #[structopt(long)]
+    domain: Option<String>,
+
+    /// The ID of the Route 53 hosted zone for the domain
+    #[structopt(long)]
+    hosted_zone_id: Option<String>,
+
+    /// The ARN of an ACM certificate in us-east-1 for the domain. Required with --cloudfront and --domain
+    #[structopt(long)]
+    certificate_arn: Option<String>,
+
+    /// The AWS profile to use from the shared credentials file
+    #[structopt(long)]
+    profile: Option<String>,
+
+    /// Whether to display additional runtime information
+    #[structopt(short, long)]
+    verbose: bool,
+}
+
+/// The AWS clients used by the scenario.
+struct Clients {
+    cloudfront: cloudfront::Client,
+    route53: route53::Client,
+    s3: s3::Client,
+}
+
+/// The resources that this run has created, so that they can be deleted if you press Ctrl-C.
+#[derive(Default)]
+struct Resources {
+    bucket: bool,
+    identity: Option<String>,
+    distribution: Option<String>,
+}
+
+/// A CloudFront distribution that serves the site.
+struct Distribution {
+    id: String,
+    domain_name: String,
+}
+
+/// What changed in the bucket during an upload.
+#[derive(Default)]
+struct SyncSummary {
+    uploaded: Vec<String>,
+    unchanged: usize,
+    deleted: Vec<String>,
+}
+
+/// Returns the content type of a file, based on its extension.
+fn guess_content_type(path: &Path) -> &'static str {
+    let ext = path
+        .extension()
+        .and_then(|ext| ext.to_str())
+        .map(|ext| ext.to_ascii_lowercase())
+        .unwrap_or_default();
+
+    match ext.as_str() {
+        "htm" | "html" => "text/html",
+        "css" => "text/css",
+        "js" => "application/javascript",
+        "json" => "application/json",
+        "txt" => "text/plain",
+        "xml" => "application/xml",
+        "gif" => "image/gif",
+        "ico" => "image/x-icon",
+        "jpg" | "jpeg" => "image/jpeg",
+        "png" => "image/png",
+        "svg" => "image/svg+xml",
+        "webp" => "image/webp",
+        "woff" => "font/woff",
+        "woff2" => "font/woff2",
+        _ => "application/octet-stream",
+    }
+}
+
+/// Returns every file under the directory, keyed by its object key in the bucket.
+fn site_files(dir: &Path, prefix: &str) -> io::Result<Vec<(String, PathBuf)>> {
+    let mut files = Vec::new();
+
+    for entry in fs::read_dir(dir)? {
+        let path = entry?.path();
+        let name = path.file_name().unwrap_or_default().to_string_lossy();
+        let key = format!("{}{}", prefix, name);
+
+        if path.is_dir() {
+            files.extend(site_files(&path, &format!("{}/", key))?);
+        } else {
+            files.push((key, path));
+        }
+    }
+
+    Ok(files)
+}
+
+/// Returns the S3 website endpoint for a bucket.
+fn website_endpoint(bucket: &str, region: &str) -> String {
+    let separator = if LEGACY_WEBSITE_REGIONS.contains(&region) {
+        '-'
+    } else {
+        '.'
+    };
+
+    format!("{}.s3-website{}{}.amazonaws.com", bucket, separator, region)
+}
+
+/// Returns a value that is unique for each request, for CloudFront's caller references.
+fn caller_reference() -> String {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|since| since.as_millis().to_string())
+        .unwrap_or_default()
+}
+
+/// Creates the bucket unless it already exists, and returns whether it was created.
+async fn ensure_bucket(client: &s3::Client, bucket: &str, region: &str) -> Result<bool, s3::Error> {
+    if client.head_bucket().bucket(bucket).send().await.is_ok() {
+        println!("  Bucket {} already exists", bucket);
+        return Ok(false);
+    }
+
+    let mut request = client.create_bucket().bucket(bucket);
+
+    // us-east-1 is the default location, and rejects an explicit location constraint.
+    if region != "us-east-1" {
+        request = request.create_bucket_configuration(
+            CreateBucketConfiguration::builder()
+                .location_constraint(BucketLocationConstraint::from(region))
+                .build(),
+        );
+    }
+
+    request.send().await?;
+    println!("  Created bucket {}", bucket);
+
+    Ok(true)
+}
+
+/// Makes the bucket match the site directory.
+/// Only uploads files whose content differs from the object in the bucket,
+/// and deletes objects that are no longer part of the site.
+async fn sync_site(
+    client: &s3::Client,
+    bucket: &str,
+    site_dir: &str,
+) -> Result<SyncSummary, Box<dyn Error>> {
+    // For objects uploaded in a single part, the ETag is the MD5 digest of the content.
+    let mut existing = HashMap::new();
+    let mut token = None;
+
+    loop {
+        let resp = client
+            .list_objects_v2()
+            .bucket(bucket)
+            .set_continuation_token(token)
+            .send()
+            .await?;
+
+        for object in resp.contents.unwrap_or_default() {
+            if let Some(key) = object.key {
+                let etag = object.e_tag.unwrap_or_default();
+                existing.insert(key, etag.trim_matches('"').to_string());
+            }
+        }
+
+        token = resp.next_continuation_token;
+        if token.is_none() {
+            break;
+        }
+    }
+
+    let mut summary = SyncSummary::default();
+
+    for (key, path) in site_files(Path::new(site_dir), "")? {
+        let data = fs::read(&path)?;
+        let digest = format!("{:x}", md5::compute(&data));
+
+        if existing.remove(&key).as_deref() == Some(digest.as_str()) {
+            summary.unchanged += 1;
+            continue;
+        }
+
+        client
+            .put_object()
+            .bucket(bucket)
+            .key(&key)
+            .content_type(guess_content_type(&path))
+            .body(ByteStream::from(data))
+            .send()
+            .await?;
+
+        println!("  Uploaded {}", key);
+        summary.uploaded.push(key);
+    }
+
+    // Anything left over is no longer part of the site.
+    for (key, _) in existing {
+        client
+            .delete_object()
+            .bucket(bucket)
+            .key(&key)
+            .send()
+            .await?;
+
+        println!("  Deleted {}", key);
+        summary.deleted.push(key);
+    }
+
+    Ok(summary)
+}
+
+/// Configures the bucket for website hosting, and allows anyone to read its objects.
+async fn configure_website(client: &s3::Client, bucket: &str) -> Result<(), s3::Error> {
+    // A public bucket policy is rejected while the bucket blocks public access.
+    client
+        .put_public_access_block()
+        .bucket(bucket)
+        .public_access_block_configuration(
+            PublicAccessBlockConfiguration::builder()
+                .block_public_acls(true)
+                .ignore_public_acls(true)
+                .block_public_policy(false)
+                .restrict_public_buckets(false)
+                .build(),
+        )
+        .send()
+        .await?;
+
+    let policy = json!({
+        "Version": "2012-10-17",
+        "Statement": [{
+            "Sid": "PublicReadGetObject",
+            "Effect": "Allow",
+            "Principal": "*",
+            "Action": "s3:GetObject",
+            "Resource": format!("arn:aws:s3:::{}/*", bucket)
+        }]
+    });
+
+    client
+        .put_bucket_policy()
+        .bucket(bucket)
+        .policy(policy.to_string())
+        .send()
+        .await?;
+
+    client
+        .put_bucket_website()
+        .bucket(bucket)
+        .website_configuration(
+            WebsiteConfiguration::builder()
+                .index_document(IndexDocument::builder().suffix("index.html").build())
+                .error_document(ErrorDocument::builder().key("error.html").build())
+                .build(),
+        )
+        .send()
+        .await?;
+
+    Ok(())
+}
+
+/// Returns the ID and canonical user ID of the origin access identity for the bucket,
+/// creating the identity if it doesn't exist. The boolean is true if the identity already existed.
+async fn ensure_origin_access_identity(
+    client: &cloudfront::Client,
+    comment: &str,
+) -> Result<(String, String, bool), Box<dyn Error>> {
+    let existing = client
+        .list_cloud_front_origin_access_identities()
+        .send()
+        .await?
+        .cloud_front_origin_access_identity_list
+        .and_then(|list| list.items)
+        .unwrap_or_default()
+        .into_iter()
+        .find(|identity| identity.comment.as_deref() == Some(comment));
+
+    if let Some(identity) = existing {
+        println!("  Using origin access identity {:?}", identity.id);
+
+        return Ok((
+            identity.id.ok_or("the origin access identity has no ID")?,
+            identity
+                .s3_canonical_user_id
+                .ok_or("the origin access identity has no canonical user ID")?,
+            true,
+        ));
+    }
+
+    let identity = client
+        .create_cloud_front_origin_access_identity()
+        .cloud_front_origin_access_identity_config(
+            CloudFrontOriginAccessIdentityConfig::builder()
+                .caller_reference(caller_reference())
+                .comment(comment)
+                .build(),
+        )
+        .send()
+        .await?
+        .cloud_front_origin_access_identity
+        .ok_or("CloudFront did not return the origin access identity")?;
+
+    println!("  Created origin access identity {:?}", identity.id);
+
+    Ok((
+        identity.id.ok_or("the origin access identity has no ID")?,
+        identity
+            .s3_canonical_user_id
+            .ok_or("the origin access identity has no canonical user ID")?,
+        false,
+    ))
+}
+
+/// Allows only the origin access identity to read the bucket's objects.
+async fn grant_origin_access_identity(
+    client: &s3::Client,
+    bucket: &str,
+    canonical_user_id: &str,
+) -> Result<(), s3::Error> {
+    let policy = json!({
+        "Version": "2012-10-17",
+        "Statement": [{
+            "Sid": "CloudFrontReadGetObject",
+            "Effect": "Allow",
+            "Principal": { "CanonicalUser": canonical_user_id },
+            "Action": "s3:GetObject",
+            "Resource": format!("arn:aws:s3:::{}/*", bucket)
+        }]
+    });
+
+    client
+        .put_bucket_policy()
+        .bucket(bucket)
+        .policy(policy.to_string())
+        .send()
+        .await?;
+
+    Ok(())
+}
+
+/// Returns the distribution that serves the bucket, creating it if it doesn't exist.
+/// The boolean is true if the distribution already existed.
+async fn ensure_distribution(
+    client: &cloudfront::Client,
+    opt: &Opt,
+    comment: &str,
+    identity_id: &str,
+) -> Result<(Distribution, bool), Box<dyn Error>> {
+    let existing = client
+        .list_distributions()
+        .send()
+        .await?
+        .distribution_list
+        .and_then(|list| list.items)
+        .unwrap_or_default()
+        .into_iter()
+        .find(|distribution| distribution.comment.as_deref() == Some(comment));
+
+    if let Some(distribution) = existing {
+        println!("  Using distribution {:?}", distribution.id);
+
+        let distribution = Distribution {
+            id: distribution.id.ok_or("the distribution has no ID")?,
+            domain_name: distribution
+                .domain_name
+                .ok_or("the distribution has no domain name")?,
+        };
+
+        return Ok((distribution, true));
+    }
+
+    let origin_id = format!("S3-{}", &opt.bucket);
+
+    let origin = Origin::builder()
+        .id(&origin_id)
+        .domain_name(format!("{}.s3.amazonaws.com", &opt.bucket))
+        .s3_origin_config(
+            S3OriginConfig::builder()
+                .origin_access_identity(format!(
+                    "origin-access-identity/cloudfront/{}",
+                    identity_id
+                ))
+                .build(),
+        )
+        .build();
+
+    let cache_behavior = DefaultCacheBehavior::builder()
+        .target_origin_id(&origin_id)
+        .viewer_protocol_policy(ViewerProtocolPolicy::RedirectToHttps)
+        .cache_policy_id(CACHING_OPTIMIZED_POLICY_ID)
+        .build();
+
+    // A private bucket returns 403 for missing objects, so show the error page for those too.
+    let error_response = CustomErrorResponse::builder()
+        .error_code(403)
+        .response_code("404")
+        .response_page_path("/error.html")
+        .build();
+
+    let mut config = DistributionConfig::builder()
+        .caller_reference(caller_reference())
+        .comment(comment)
+        .enabled(true)
+        .default_root_object("index.html")
+        .origins(Origins::builder().quantity(1).items(origin).build())
+        .default_cache_behavior(cache_behavior)
+        .custom_error_responses(
+            CustomErrorResponses::builder()
+                .quantity(1)
+                .items(error_response)
+                .build(),
+        );
+
+    if let (Some(domain), Some(certificate_arn)) = (&opt.domain, &opt.certificate_arn) {
+        config = config
+            .aliases(Aliases::builder().quantity(1).items(domain).build())
+            .viewer_certificate(
+                ViewerCertificate::builder()
+                    .acm_certificate_arn(certificate_arn)
+                    .ssl_support_method(SslSupportMethod::SniOnly)
+                    .minimum_protocol_version(MinimumProtocolVersion::from("TLSv1.2_2021"))
+                    .build(),
+            );
+    }
+
+    let distribution = client
+        .create_distribution()
+        .distribution_config(config.build())
+        .send()
+        .await?
+        .distribution
+        .ok_or("CloudFront did not return the distribution")?;
+
+    println!("  Created distribution {:?}", distribution.id);
+
+    let distribution = Distribution {
+        id: distribution.id.ok_or("the distribution has no ID")?,
+        domain_name: distribution
+            .domain_name
+            .ok_or("the distribution has no domain name")?,
+    };
+
+    Ok((distribution, false))
+}
+
+/// Removes the changed files from the distribution's edge caches.
+async fn invalidate(
+    client: &cloudfront::Client,
+    distribution_id: &str,
+    keys: &[String],
+) -> Result<(), cloudfront::Error> {
+    let paths = keys
+        .iter()
+        .map(|key| format!("/{}", key))
+        .collect::<Vec<_>>();
+
+    client
+        .create_invalidation()
+        .distribution_id(distribution_id)
+        .invalidation_batch(
+            InvalidationBatch::builder()
+                .caller_reference(caller_reference())
+                .paths(
+                    Paths::builder()
+                        .quantity(paths.len() as i32)
+                        .set_items(Some(paths))
+                        .build(),
+                )
+                .build(),
+        )
+        .send()
+        .await?;
+
+    Ok(())
+}
+
+/// Points the domain at the site.
+/// A CloudFront distribution gets an alias record; an S3 website endpoint gets a CNAME record.
+async fn upsert_record(
+    client: &route53::Client,
+    hosted_zone_id: &str,
+    domain: &str,
+    target: &str,
+    cloudfront: bool,
+) -> Result<(), route53::Error> {
+    let record = if cloudfront {
+        ResourceRecordSet::builder()
+            .name(domain)
+            .r#type(RrType::A)
+            .alias_target(
+                AliasTarget::builder()
+                    .hosted_zone_id(CLOUDFRONT_HOSTED_ZONE_ID)
+                    .dns_name(target)
+                    .evaluate_target_health(false)
+                    .build(),
+            )
+            .build()
+    } else {
+        ResourceRecordSet::builder()
+            .name(domain)
+            .r#type(RrType::Cname)
+            .ttl(300)
+            .resource_records(ResourceRecord::builder().value(target).build())
+            .build()
+    };
+
+    // UPSERT creates the record, or replaces it if it exists, so redeploying is safe.
+    client
+        .change_resource_record_sets()
+        .hosted_zone_id(hosted_zone_id)
+        .change_batch(
+            ChangeBatch::builder()
+                .changes(
+                    Change::builder()
+                        .action(ChangeAction::Upsert)
+                        .resource_record_set(record)
+                        .build(),
+                )
+                .build(),
+        )
+        .send()
+        .await?;
+
+    Ok(())
+}
+
+/// Checks the combination of options before anything is created.
+fn check_options(opt: &Opt) -> Result<(), String> {
+    if opt.domain.is_some() != opt.hosted_zone_id.is_some() {
+        return Err("--domain and --hosted-zone-id must be used together".into());
+    }
+
+    if let Some(domain) = &opt.domain {
+        if opt.cloudfront && opt.certificate_arn.is_none() {
+            return Err("--certificate-arn is required to use --domain with --cloudfront".into());
+        }
+
+        // S3 website hosting finds the bucket from the Host header of the request.
+        if !opt.cloudfront && domain != &opt.bucket {
+            return Err(format!(
+                "to use --domain without --cloudfront, the bucket must be named {}",
+                domain
+            ));
+        }
+    }
+
+    if !Path::new(&opt.site_dir).join("index.html").is_file() {
+        return Err(format!(
+            "{} does not contain an index.html file",
+            &opt.site_dir
+        ));
+    }
+
+    Ok(())
+}
+
+/// Runs the steps of the scenario, stopping at the first error.
+async fn run(
+    clients: &Clients,
+    opt: &Opt,
+    region_name: &str,
+    resources: &mut Resources,
+) -> Result<(), Box<dyn Error>> {
+    println!("1. Preparing bucket {}", &opt.bucket);
+    resources.bucket = ensure_bucket(&clients.s3, &opt.bucket, region_name).await?;
+
+    println!("2. Uploading {}", &opt.site_dir);
+    let summary = sync_site(&clients.s3, &opt.bucket, &opt.site_dir).await?;
+    println!(
+        "  {} uploaded, {} unchanged, {} deleted",
+        summary.uploaded.len(),
+        summary.unchanged,
+        summary.deleted.len()
+    );
+
+    let target = if opt.cloudfront {
+        println!("3. Serving the bucket through CloudFront");
+        let comment = format!("static-site:{}", &opt.bucket);
+
+        let (identity_id, canonical_user_id, existed) =
+            ensure_origin_access_identity(&clients.cloudfront, &comment).await?;
+        if !existed {
+            resources.identity = Some(identity_id.clone());
+        }
+        grant_origin_access_identity(&clients.s3, &opt.bucket, &canonical_user_id).await?;
+
+        let (distribution, existed) =
+            ensure_distribution(&clients.cloudfront, opt, &comment, &identity_id).await?;
+        if !existed {
+            resources.distribution = Some(distribution.id.clone());
+        }
+
+        let changed = summary
+            .uploaded
+            .iter()
+            .chain(&summary.deleted)
+            .cloned()
+            .collect::<Vec<_>>();
+
+        if existed && !changed.is_empty() {
+            invalidate(&clients.cloudfront, &distribution.id, &changed).await?;
+            println!("  Invalidated {} paths", changed.len());
+        }
+
+        println!("  A new distribution can take several minutes to deploy");
+
+        distribution.domain_name
+    } else {
+        println!("3. Configuring website hosting");
+        configure_website(&clients.s3, &opt.bucket).await?;
+
+        website_endpoint(&opt.bucket, region_name)
+    };
+
+    let host = match (&opt.domain, &opt.hosted_zone_id) {
+        (Some(domain), Some(hosted_zone_id)) => {
+            println!("4. Pointing {} at {}", domain, &target);
+            upsert_record(
+                &clients.route53,
+                hosted_zone_id,
+                domain,
+                &target,
+                opt.cloudfront,
+            )
+            .await?;
+
+            domain.clone()
+        }
+        _ => target,
+    };
+
+    // CloudFront redirects HTTP to HTTPS; S3 website endpoints only support HTTP.
+    let scheme = if opt.cloudfront { "https" } else { "http" };
+    println!("The site is available at {}://{}/", scheme, host);
+
+    Ok(())
+}
+
+/// Deletes what this run created: the origin access identity, and the bucket with everything in it.
+/// A new distribution can't be deleted until it's disabled and deployed, which takes several minutes,
+/// so it's reported instead, and the origin access identity that it uses is kept.
+/// Keeps going after an error, so that as much as possible is cleaned up.
+async fn tear_down(clients: &Clients, opt: &Opt, resources: &Resources) {
+    if let Some(id) = &resources.distribution {
+        println!(
+            "  Distribution {} was created. Disable it, wait for it to deploy, and then delete it",
+            id
+        );
+    } else if let Some(id) = &resources.identity {
+        // CloudFront only deletes the identity with the ETag of its current version.
+        let deleted = match clients
+            .cloudfront
+            .get_cloud_front_origin_access_identity()
+            .id(id)
+            .send()
+            .await
+        {
+            Ok(resp) => clients
+                .cloudfront
+                .delete_cloud_front_origin_access_identity()
+                .id(id)
+                .set_if_match(resp.e_tag)
+                .send()
+                .await
+                .map(|_| ())
+                .map_err(cloudfront::Error::from),
+            Err(e) => Err(e.into()),
+        };
+
+        if let Err(e) = deleted {
+            eprintln!("Got an error deleting origin access identity {}: {}", id, e);
+        }
+    }
+
+    if resources.bucket {
+        let keys = match clients
+            .s3
+            .list_objects_v2()
+            .bucket(&opt.bucket)
+            .send()
+            .await
+        {
+            Ok(resp) => resp
+                .contents
+                .unwrap_or_default()
+                .into_iter()
+                .filter_map(|object| object.key)
+                .collect(),
+            Err(e) => {
+                eprintln!("Got an error listing bucket {}: {}", &opt.bucket, e);
+                Vec::new()
+            }
+        };
+
+        for key in &keys {
+            if let Err(e) = clients
+                .s3
+                .delete_object()
+                .bucket(&opt.bucket)
+                .key(key)
+                .send()
+                .await
+            {
+                eprintln!("Got an error deleting {}: {}", key, e);
+            }
+        }
+
+        if let Err(e) = clients.s3.delete_bucket().bucket(&opt.bucket).send().await {
+            eprintln!("Got an error deleting bucket {}: {}", &opt.bucket, e);
+        }
+    }
+}
+
+/// Deploys a static website.
+/// The scenario creates an Amazon S3 bucket if needed and uploads the files in the site directory.
+/// It then either configures the bucket for website hosting,
+/// or serves the private bucket through an Amazon CloudFront distribution with an origin access identity.
+/// Optionally, it points a domain at the site with an Amazon Route 53 record.
+/// Every step reuses what already exists, so running the scenario again redeploys the site:
+/// only changed files are uploaded, deleted files are removed,
+/// and changed files are invalidated in the CloudFront cache.
+/// If you press Ctrl-C, it deletes the bucket and the origin access identity if this run created them.
+/// # Arguments
+///
+/// * `-b BUCKET` - The bucket that holds the site.
+/// * `[-s SITE-DIR]` - The directory containing the site.
+///   If not supplied, defaults to **site**.
+/// * `[-c]` - Whether to serve the site through CloudFront instead of S3 website hosting.
+/// * `[--domain DOMAIN]` - The domain name to point at the site.
+/// * `[--hosted-zone-id HOSTED-ZONE-ID]` - The Route 53 hosted zone for the domain.
+/// * `[--certificate-arn CERTIFICATE-ARN]` - The ACM certificate for the domain, when using CloudFront.
+/// * `[-d DEFAULT-REGION]` - The region in which the clients are created.
+///    If not supplied, uses the value of the **AWS_DEFAULT_REGION** environment variable.
+///    If the environment variable is not set, defaults to **us-west-2**.
+/// * `[--profile PROFILE]` - The AWS profile to use from the shared credentials file.
+/// * `[-v]` - Whether to display additional information.
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn Error>> {
+    let opt = Opt::from_args();
+
+    check_options(&opt)?;
+
+    let region = opt
+        .default_region
+        .as_ref()
+        .map(|region| Region::new(region.clone()))
+        .or_else(|| aws_types::region::default_provider().region())
+        .unwrap_or_else(|| Region::new("us-west-2"));
+
+    let region_name = region.as_ref().to_string();
+
+    if opt.verbose {
+        println!("CloudFront client version: {}", cloudfront::PKG_VERSION);
+        println!("Route 53 client version:   {}", route53::PKG_VERSION);
+        println!("S3 client version:         {}", s3::PKG_VERSION);
+        println!("Region:                    {:?}", &region);
+        println!("Bucket:                    {}", &opt.bucket);
+        println!("Site directory:            {}", &opt.site_dir);
+        println!("CloudFront:                {}", opt.cloudfront);
+        println!("Domain:                    {:?}", &opt.domain);
+        println!();
+
+        SubscriberBuilder::default()
+            .with_env_filter("info")
+            .with_span_events(FmtSpan::CLOSE)
+            .init();
+    }
+
+    let mut cloudfront_config = cloudfront::Config::builder().region(region.clone());
+    let mut route53_config = route53::Config::builder().region(region.clone());
+    let mut s3_config = s3::Config::builder().region(region);
+    if let Some(profile) = &opt.profile {
+        cloudfront_config =
+            cloudfront_config.credentials_provider(ProfileCredentialsProvider::new(profile));
+        route53_config =
+            route53_config.credentials_provider(ProfileCredentialsProvider::new(profile));
+        s3_config = s3_config.credentials_provider(ProfileCredentialsProvider::new(profile));
+    }
+
+    let clients = Clients {
+        cloudfront: cloudfront::Client::from_conf(cloudfront_config.build()),
+        route53: route53::Client::from_conf(route53_config.build()),
+        s3: s3::Client::from_conf(s3_config.build()),
+    };
+
+    let mut resources = Resources::default();
+
+    // Ctrl-C stops the scenario wherever it is, and deletes what this run created,
+    // so that a half-deployed site isn't left behind.
+    let shutdown = Shutdown::listen();
+    let result = shutdown
+        .run(run(&clients, &opt, &region_name, &mut resources))
+        .await;
+
+    if shutdown.is_requested() {
+        println!("Deleting what this run created");
+        tear_down(&clients, &opt, &resources).await;
+    }
+
+    result
+}
@@ -0,0 +1,293 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0.
+ */
+
+use std::error::Error;
+use std::sync::Arc;
+use std::time::Duration;
+
+use aws_types::region::{ProvideRegion, Region};
+
+use kinesis_code_examples::consumer::{consume_shard, list_shard_ids, Consumed, Start};
+use s3::ByteStream;
+
+use chrono::Utc;
+use common::profile::ProfileCredentialsProvider;
+use common::shutdown::Shutdown;
+use structopt::StructOpt;
+use tokio::sync::mpsc;
+use tokio::time::{sleep_until, Instant};
+use tracing_subscriber::fmt::format::FmtSpan;
+use tracing_subscriber::fmt::SubscriberBuilder;
+
+/// How many records can wait in the channel before the shard tasks wait for the archiver to catch up.
+const CHANNEL_CAPACITY: usize = 1000;
+
+#[derive(Debug, StructOpt)]
+struct Opt {
+    /// The region. Overrides environment variable AWS_DEFAULT_REGION.
+    #[structopt(short, long)]
+    default_region: Option<String>,
+
+    /// The name of the Kinesis stream to archive
+    #[structopt(short, long)]
+    name: String,
+
+    /// The bucket to write the archive to
+    #[structopt(short, long)]
+    bucket: String,
+
+    /// The prefix of the archived objects. Defaults to the name of the stream and a slash
+    #[structopt(short, long)]
+    prefix: Option<String>,
+
+    /// The most seconds to buffer records before writing them to an object
+    #[structopt(long, default_value = "60")]
+    seconds: u64,
+
+    /// The most megabytes to buffer before writing them to an object
+    #[structopt(long, default_value = "5")]
+    megabytes: usize,
+
+    /// Where in each shard to start reading, oldest or latest
+    #[structopt(short, long, default_value = "latest")]
+    start: Start,
+
+    /// The AWS profile to use from the shared credentials file
+    #[structopt(long)]
+    profile: Option<String>,
+
+    /// Whether to display additional runtime information
+    #[structopt(short, long)]
+    verbose: bool,
+}
+
+/// The records that are waiting to be written, one per line.
+struct Buffer {
+    data: Vec<u8>,
+    records: usize,
+    /// When the first record in the buffer arrived, or `None` if the buffer is empty.
+    opened: Option<Instant>,
+}
+
+impl Buffer {
+    fn new() -> Self {
+        Buffer {
+            data: Vec::new(),
+            records: 0,
+            opened: None,
+        }
+    }
+
+    /// Adds a record, ending it with a newline if it doesn't already end with one,
+    /// so that the records can be told apart in the object.
+    fn push(&mut self, record: Consumed) {
+        self.data.extend_from_slice(&record.data);
+        if !record.data.ends_with(b"\n") {
+            self.data.push(b'\n');
+        }
+
+        self.records += 1;
+        self.opened.get_or_insert_with(Instant::now);
+    }
+}
+
+/// The objects and records that the archiver has written.
+#[derive(Default)]
+struct Totals {
+    objects: usize,
+    records: usize,
+    bytes: usize,
+}
+
+/// Writes the buffered records to a new object, and empties the buffer.
+/// Like Firehose, the key starts with the prefix and the UTC hour, so the objects for a time range are easy to find,
+/// and ends with the time it was written and a counter, so that no two objects have the same key.
+async fn flush(
+    client: &s3::Client,
+    opt: &Opt,
+    prefix: &str,
+    buffer: &mut Buffer,
+    totals: &mut Totals,
+) -> Result<(), Box<dyn Error>> {
+    if buffer.records == 0 {
+        return Ok(());
+    }
+
+    let now = Utc::now();
+    let key = format!(
+        "{}{}/{}-{}-{:06}",
+        prefix,
+        now.format("%Y/%m/%d/%H"),
+        &opt.name,
+        now.format("%Y-%m-%d-%H-%M-%S"),
+        totals.objects
+    );
+
+    let Buffer { data, records, .. } = std::mem::replace(buffer, Buffer::new());
+    let bytes = data.len();
+
+    client
+        .put_object()
+        .bucket(&opt.bucket)
+        .key(&key)
+        .content_type("application/x-ndjson")
+        .body(ByteStream::from(data))
+        .send()
+        .await?;
+
+    println!(
+        "  Wrote {} records ({} bytes) to s3://{}/{}",
+        records, bytes, &opt.bucket, &key
+    );
+
+    totals.objects += 1;
+    totals.records += records;
+    totals.bytes += bytes;
+
+    Ok(())
+}
+
+/// Archives an Amazon Kinesis data stream to an Amazon S3 bucket, the way Amazon Kinesis Data Firehose does, but by hand.
+/// A task per shard reads the records, and the archiver buffers them, one per line,
+/// until the buffer is a number of seconds old or a number of megabytes in size, and then writes it to a new object.
+/// When you press Ctrl-C, the shard tasks stop after the GetRecords call in progress,
+/// and the archiver writes everything they read before it stops, so no record that was read is lost.
+/// The archiver doesn't record where it got to, so when it starts again it reads from the oldest or the latest record.
+/// # Arguments
+///
+/// * `-n NAME` - The name of the stream to archive.
+/// * `-b BUCKET` - The bucket to write the archive to.
+/// * `[-p PREFIX]` - The prefix of the archived objects.
+///   If not supplied, defaults to the name of the stream and a slash.
+/// * `[--seconds SECONDS]` - The most seconds to buffer records before writing them.
+///   If not supplied, defaults to **60**.
+/// * `[--megabytes MEGABYTES]` - The most megabytes to buffer before writing them.
+///   If not supplied, defaults to **5**.
+/// * `[-s START]` - Where in each shard to start reading, **oldest** or **latest**.
+///   If not supplied, defaults to **latest**.
+/// * `[-d DEFAULT-REGION]` - The region in which the clients are created.
+///    If not supplied, uses the value of the **AWS_DEFAULT_REGION** environment variable.
+///    If the environment variable is not set, defaults to **us-west-2**.
+/// * `[--profile PROFILE]` - The AWS profile to use from the shared credentials file.
+/// * `[-v]` - Whether to display additional information.
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn Error>> {
+    let opt = Opt::from_args();
+
+    if opt.seconds == 0 || opt.megabytes == 0 {
+        return Err("the seconds and the megabytes must be more than 0".into());
+    }
+
+    let region = opt
+        .default_region
+        .as_ref()
+        .map(|region| Region::new(region.clone()))
+        .or_else(|| aws_types::region::default_provider().region())
+        .unwrap_or_else(|| Region::new("us-west-2"));
+
+    let prefix = opt
+        .prefix
+        .clone()
+        .unwrap_or_else(|| format!("{}/", &opt.name));
+    let max_age = Duration::from_secs(opt.seconds);
+    let max_bytes = opt.megabytes * 1024 * 1024;
+
+    if opt.verbose {
+        println!("Kinesis client version: {}", kinesis::PKG_VERSION);
+        println!("S3 client version:      {}", s3::PKG_VERSION);
+        println!("Region:                 {:?}", &region);
+        println!("Stream:                 {}", &opt.name);
+        println!("Bucket:                 {}", &opt.bucket);
+        println!("Prefix:                 {}", &prefix);
+        println!("Seconds:                {}", opt.seconds);
+        println!("Megabytes:              {}", opt.megabytes);
+        println!("Start:                  {:?}", opt.start);
+        println!();
+
+        SubscriberBuilder::default()
+            .with_env_filter("info")
+            .with_span_events(FmtSpan::CLOSE)
+            .init();
+    }
+
+    let mut kinesis_config = kinesis::Config::builder().region(region.clone());
+    let mut s3_config = s3::Config::builder().region(region);
+    if let Some(profile) = &opt.profile {
+        kinesis_config =
+            kinesis_config.credentials_provider(ProfileCredentialsProvider::new(profile));
+        s3_config = s3_config.credentials_provider(ProfileCredentialsProvider::new(profile));
+    }
+
+    let kinesis = Arc::new(kinesis::Client::from_conf(kinesis_config.build()));
+    let s3 = s3::Client::from_conf(s3_config.build());
+
+    let shard_ids = list_shard_ids(&kinesis, &opt.name).await?;
+
+    println!(
+        "Archiving {} shards of stream {} to s3://{}/{}. Press Ctrl-C to stop",
+        shard_ids.len(),
+        &opt.name,
+        &opt.bucket,
+        &prefix
+    );
+
+    let shutdown = Shutdown::listen();
+    let (sender, mut receiver) = mpsc::channel(CHANNEL_CAPACITY);
+
+    let handles: Vec<_> = shard_ids
+        .into_iter()
+        .map(|shard_id| {
+            tokio::spawn(consume_shard(
+                kinesis.clone(),
+                opt.name.clone(),
+                shard_id,
+                opt.start,
+                sender.clone(),
+                shutdown.clone(),
+            ))
+        })
+        .collect();
+
+    // The channel closes when every task has finished and dropped its sender, so this one mustn't be kept.
+    drop(sender);
+
+    let mut buffer = Buffer::new();
+    let mut totals = Totals::default();
+
+    // Ctrl-C doesn't stop this loop. It stops the shard tasks, and the loop ends once they've
+    // sent everything they read and the channel closes, so the last buffer holds every record.
+    loop {
+        let deadline = buffer.opened.map(|opened| opened + max_age);
+
+        tokio::select! {
+            record = receiver.recv() => match record {
+                Some(record) => {
+                    buffer.push(record);
+
+                    if buffer.data.len() >= max_bytes {
+                        flush(&s3, &opt, &prefix, &mut buffer, &mut totals).await?;
+                    }
+                }
+                None => break,
+            },
+            _ = sleep_until(deadline.unwrap_or_else(Instant::now)), if deadline.is_some() => {
+                flush(&s3, &opt, &prefix, &mut buffer, &mut totals).await?;
+            }
+        }
+    }
+
+    flush(&s3, &opt, &prefix, &mut buffer, &mut totals).await?;
+
+    for handle in handles {
+        handle.await??;
+    }
+
+    println!(
+        "Archived {} records ({} bytes) in {} objects",
+        totals.records, totals.bytes, totals.objects
+    );
+
+    Ok(())
+}
@@ -0,0 +1,456 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0.
+ */
+
+use std::error::Error;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+use aws_types::region::{ProvideRegion, Region};
+
+use firehose::model::{
+    BufferingHints, DeliveryStreamStatus, DeliveryStreamType, ExtendedS3DestinationConfiguration,
+    KinesisStreamSourceConfiguration,
+};
+use kinesis::model::{PutRecordsRequestEntry, StreamStatus};
+use kinesis::Blob;
+
+use common::profile::ProfileCredentialsProvider;
+use common::shutdown::Shutdown;
+use rand::Rng;
+use serde_json::json;
+use structopt::StructOpt;
+use tokio::time::sleep;
+use tracing_subscriber::fmt::format::FmtSpan;
+use tracing_subscriber::fmt::SubscriberBuilder;
+
+/// How long to wait for Firehose to deliver the buffered records after the producer stops.
+/// Firehose flushes its buffer at least every buffering interval, which is 60 seconds here.
+const DELIVERY_TIMEOUT: Duration = Duration::from_secs(300);
+
+/// How many lines of a delivered object to display.
+const SAMPLE_LINES: usize = 5;
+
+#[derive(Debug, StructOpt)]
+struct Opt {
+    /// The region. Overrides environment variable AWS_DEFAULT_REGION.
+    #[structopt(short, long)]
+    default_region: Option<String>,
+
+    /// The bucket that Firehose delivers the records to
+    #[structopt(short, long)]
+    bucket: String,
+
+    /// The IAM role that Firehose assumes to read the stream and write to the bucket
+    #[structopt(short, long)]
+    role_arn: String,
+
+    /// The name used for the Kinesis stream, the delivery stream, and the start of the S3 prefix
+    #[structopt(short, long, default_value = "streaming-pipeline")]
+    name: String,
+
+    /// How many seconds to run the producer
+    #[structopt(short, long, default_value = "30")]
+    seconds: u64,
+
+    /// Whether to keep the streams and delivered objects
+    #[structopt(short, long)]
+    keep: bool,
+
+    /// The AWS profile to use from the shared credentials file
+    #[structopt(long)]
+    profile: Option<String>,
+
+    /// Whether to display additional runtime information
+    #[structopt(short, long)]
+    verbose: bool,
+}
+
+/// The AWS clients used by the scenario.
+struct Clients {
+    firehose: firehose::Client,
+    kinesis: kinesis::Client,
+    s3: s3::Client,
+}
+
+/// Creates a Kinesis data stream, waits for it to become active, and returns its ARN.
+async fn create_stream(client: &kinesis::Client, name: &str) -> Result<String, Box<dyn Error>> {
+    client
+        .create_stream()
+        .stream_name(name)
+        .shard_count(1)
+        .send()
+        .await?;
+
+    loop {
+        let resp = client.describe_stream().stream_name(name).send().await?;
+        let desc = resp
+            .stream_description
+            .ok_or("no stream description returned")?;
+
+        if desc.stream_status == Some(StreamStatus::Active) {
+            return Ok(desc.stream_arn.ok_or("the stream has no ARN")?);
+        }
+
+        println!("  Stream status: {:?}", desc.stream_status);
+        sleep(Duration::from_secs(5)).await;
+    }
+}
+
+/// Creates a Firehose delivery stream that reads from the Kinesis stream and writes to the bucket,
+/// and waits for it to become active.
+async fn create_delivery_stream(
+    client: &firehose::Client,
+    name: &str,
+    stream_arn: &str,
+    role_arn: &str,
+    bucket: &str,
+    prefix: &str,
+) -> Result<(), Box<dyn Error>> {
+    let source = KinesisStreamSourceConfiguration::builder()
+        .kinesis_stream_arn(stream_arn)
+        .role_arn(role_arn)
+        .build();
+
+    // Flush to S3 every minute or every megabyte, whichever comes first.
+    let destination = ExtendedS3DestinationConfiguration::builder()
+        .bucket_arn(format!("arn:aws:s3:::{}", bucket))
+        .role_arn(role_arn)
+        .prefix(prefix)
+        .error_output_prefix(format!("{}errors/", prefix))
+        .buffering_hints(
+            BufferingHints::builder()
+                .interval_in_seconds(60)
+                .size_in_m_bs(1)
+                .build(),
+        )
+        .build();
+
+    client
+        .create_delivery_stream()
+        .delivery_stream_name(name)
+        .delivery_stream_type(DeliveryStreamType::KinesisStreamAsSource)
+        .kinesis_stream_source_configuration(source)
+        .extended_s3_destination_configuration(destination)
+        .send()
+        .await?;
+
+    loop {
+        let resp = client
+            .describe_delivery_stream()
+            .delivery_stream_name(name)
+            .send()
+            .await?;
+        let status = resp
+            .delivery_stream_description
+            .and_then(|desc| desc.delivery_stream_status);
+
+        match status {
+            Some(DeliveryStreamStatus::Active) => return Ok(()),
+            Some(DeliveryStreamStatus::CreatingFailed) => {
+                return Err("the delivery stream could not be created".into())
+            }
+            status => {
+                println!("  Delivery stream status: {:?}", status);
+                sleep(Duration::from_secs(10)).await;
+            }
+        }
+    }
+}
+
+/// Puts synthetic sensor readings on the stream for the given time.
+/// Returns the number of records that were accepted.
+async fn produce(
+    client: &kinesis::Client,
+    name: &str,
+    duration: Duration,
+) -> Result<usize, Box<dyn Error>> {
+    let start = Instant::now();
+    let mut sent = 0;
+
+    while start.elapsed() < duration {
+        let entries = (0..10)
+            .map(|_| {
+                let mut rng = rand::thread_rng();
+                let sensor = format!("sensor-{}", rng.gen_range(1..=5));
+                let reading = json!({
+                    "sensor": sensor,
+                    "temperature": rng.gen_range(15.0..30.0),
+                    "timestamp": SystemTime::now()
+                        .duration_since(UNIX_EPOCH)
+                        .map(|since| since.as_millis() as u64)
+                        .unwrap_or_default(),
+                });
+
+                // Firehose concatenates the records, so end each one with a newline.
+                PutRecordsRequestEntry::builder()
+                    .data(Blob::new(format!("{}\n", reading)))
+                    .partition_key(sensor)
+                    .build()
+            })
+            .collect::<Vec<_>>();
+
+        let count = entries.len();
+
+        let resp = client
+            .put_records()
+            .stream_name(name)
+            .set_records(Some(entries))
+            .send()
+            .await?;
+
+        sent += count - resp.failed_record_count.unwrap_or_default() as usize;
+
+        sleep(Duration::from_millis(500)).await;
+    }
+
+    Ok(sent)
+}
+
+/// Waits until Firehose has delivered at least one object under the prefix,
+/// and returns the keys of the delivered objects.
+async fn wait_for_delivery(
+    client: &s3::Client,
+    bucket: &str,
+    prefix: &str,
+) -> Result<Vec<String>, Box<dyn Error>> {
+    let start = Instant::now();
+
+    loop {
+        let keys = list_keys(client, bucket, prefix).await?;
+
+        if !keys.is_empty() {
+            return Ok(keys);
+        }
+
+        if start.elapsed() > DELIVERY_TIMEOUT {
+            return Err(format!(
+                "no objects were delivered to s3://{}/{} within {} seconds",
+                bucket,
+                prefix,
+                DELIVERY_TIMEOUT.as_secs()
+            )
+            .into());
+        }
+
+        println!("  Waiting for Firehose to deliver the records");
+        sleep(Duration::from_secs(15)).await;
+    }
+}
+
+/// Lists the keys of all of the objects under the prefix.
+async fn list_keys(
+    client: &s3::Client,
+    bucket: &str,
+    prefix: &str,
+) -> Result<Vec<String>, s3::Error> {
+    let mut keys = Vec::new();
+    let mut token = None;
+
+    loop {
+        let resp = client
+            .list_objects_v2()
+            .bucket(bucket)
+            .prefix(prefix)
+            .set_continuation_token(token)
+            .send()
+            .await?;
+
+        keys.extend(
+            resp.contents
+                .unwrap_or_default()
+                .into_iter()
+                .filter_map(|object| object.key),
+        );
+
+        token = resp.next_continuation_token;
+        if token.is_none() {
+            return Ok(keys);
+        }
+    }
+}
+
+/// Displays the first few records in a delivered object.
+async fn sample(client: &s3::Client, bucket: &str, key: &str) -> Result<(), Box<dyn Error>> {
+    let resp = client.get_object().bucket(bucket).key(key).send().await?;
+    let data = resp.body.collect().await?.into_bytes();
+    let text = String::from_utf8_lossy(&data);
+
+    println!("  Records in {}: {}", key, text.lines().count());
+
+    for line in text.lines().take(SAMPLE_LINES) {
+        println!("    {}", line);
+    }
+
+    Ok(())
+}
+
+/// Deletes the delivery stream, the Kinesis stream, and the delivered objects.
+/// Keeps going after an error, so that as much as possible is cleaned up.
+async fn tear_down(clients: &Clients, opt: &Opt, prefix: &str) {
+    if let Err(e) = clients
+        .firehose
+        .delete_delivery_stream()
+        .delivery_stream_name(&opt.name)
+        .send()
+        .await
+    {
+        eprintln!("Got an error deleting delivery stream {}: {}", &opt.name, e);
+    }
+
+    if let Err(e) = clients
+        .kinesis
+        .delete_stream()
+        .stream_name(&opt.name)
+        .send()
+        .await
+    {
+        eprintln!("Got an error deleting stream {}: {}", &opt.name, e);
+    }
+
+    let keys = match list_keys(&clients.s3, &opt.bucket, prefix).await {
+        Ok(keys) => keys,
+        Err(e) => {
+            eprintln!("Got an error listing the delivered objects: {}", e);
+            return;
+        }
+    };
+
+    for key in keys {
+        if let Err(e) = clients
+            .s3
+            .delete_object()
+            .bucket(&opt.bucket)
+            .key(&key)
+            .send()
+            .await
+        {
+            eprintln!("Got an error deleting {}: {}", key, e);
+        }
+    }
+}
+
+/// Runs the steps of the scenario, stopping at the first error.
+async fn run(clients: &Clients, opt: &Opt, prefix: &str) -> Result<(), Box<dyn Error>> {
+    println!("1. Creating Kinesis data stream {}", &opt.name);
+    let stream_arn = create_stream(&clients.kinesis, &opt.name).await?;
+
+    println!("2. Creating Firehose delivery stream {}", &opt.name);
+    create_delivery_stream(
+        &clients.firehose,
+        &opt.name,
+        &stream_arn,
+        &opt.role_arn,
+        &opt.bucket,
+        prefix,
+    )
+    .await?;
+
+    println!("3. Producing records for {} seconds", opt.seconds);
+    let sent = produce(
+        &clients.kinesis,
+        &opt.name,
+        Duration::from_secs(opt.seconds),
+    )
+    .await?;
+    println!("  Put {} records on the stream", sent);
+
+    println!("4. Verifying delivery to s3://{}/{}", &opt.bucket, prefix);
+    let keys = wait_for_delivery(&clients.s3, &opt.bucket, prefix).await?;
+    println!("  Found {} delivered objects", keys.len());
+
+    sample(&clients.s3, &opt.bucket, &keys[0]).await?;
+
+    Ok(())
+}
+
+/// Builds a streaming analytics pipeline, sends data through it, and verifies the data arrives.
+/// The scenario creates an Amazon Kinesis data stream and an Amazon Kinesis Data Firehose delivery stream
+/// that reads from it and writes to an Amazon S3 bucket,
+/// runs a producer that puts synthetic sensor readings on the stream,
+/// and then lists and samples the objects that Firehose delivers.
+/// Finally, it deletes the streams and the delivered objects.
+/// # Arguments
+///
+/// * `-b BUCKET` - The bucket that Firehose delivers the records to.
+/// * `-r ROLE-ARN` - The IAM role that Firehose assumes to read the stream and write to the bucket.
+/// * `[-n NAME]` - The name of the streams, and the start of the S3 prefix.
+///   Each run delivers under its own prefix, **NAME/TIMESTAMP/**, so only that run's objects are deleted.
+///   If not supplied, defaults to **streaming-pipeline**.
+/// * `[-s SECONDS]` - How long to run the producer.
+///   If not supplied, defaults to **30**.
+/// * `[-k]` - Whether to keep the streams and delivered objects.
+/// * `[-d DEFAULT-REGION]` - The region in which the clients are created.
+///    If not supplied, uses the value of the **AWS_DEFAULT_REGION** environment variable.
+///    If the environment variable is not set, defaults to **us-west-2**.
+/// * `[--profile PROFILE]` - The AWS profile to use from the shared credentials file.
+/// * `[-v]` - Whether to display additional information.
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn Error>> {
+    let opt = Opt::from_args();
+
+    let region = opt
+        .default_region
+        .as_ref()
+        .map(|region| Region::new(region.clone()))
+        .or_else(|| aws_types::region::default_provider().region())
+        .unwrap_or_else(|| Region::new("us-west-2"));
+
+    // A prefix of its own keeps this run's objects apart from those of earlier runs that were kept,
+    // so that tearing down deletes only the objects that this run delivered.
+    let prefix = format!(
+        "{}/{}/",
+        &opt.name,
+        SystemTime::now().duration_since(UNIX_EPOCH)?.as_secs()
+    );
+
+    if opt.verbose {
+        println!("Firehose client version: {}", firehose::PKG_VERSION);
+        println!("Kinesis client version:  {}", kinesis::PKG_VERSION);
+        println!("S3 client version:       {}", s3::PKG_VERSION);
+        println!("Region:                  {:?}", &region);
+        println!("Bucket:                  {}", &opt.bucket);
+        println!("Role ARN:                {}", &opt.role_arn);
+        println!("Name:                    {}", &opt.name);
+        println!("Seconds:                 {}", opt.seconds);
+        println!();
+
+        SubscriberBuilder::default()
+            .with_env_filter("info")
+            .with_span_events(FmtSpan::CLOSE)
+            .init();
+    }
+
+    let mut firehose_config = firehose::Config::builder().region(region.clone());
+    let mut kinesis_config = kinesis::Config::builder().region(region.clone());
+    let mut s3_config = s3::Config::builder().region(region);
+    if let Some(profile) = &opt.profile {
+        firehose_config =
+            firehose_config.credentials_provider(ProfileCredentialsProvider::new(profile));
+        kinesis_config =
+            kinesis_config.credentials_provider(ProfileCredentialsProvider::new(profile));
+        s3_config = s3_config.credentials_provider(ProfileCredentialsProvider::new(profile));
+    }
+
+    let clients = Clients {
+        firehose: firehose::Client::from_conf(firehose_config.build()),
+        kinesis: kinesis::Client::from_conf(kinesis_config.build()),
+        s3: s3::Client::from_conf(s3_config.build()),
+    };
+
+    // Ctrl-C stops the scenario wherever it is, and still cleans up what it created.
+    let shutdown = Shutdown::listen();
+    let result = shutdown.run(run(&clients, &opt, &prefix)).await;
+
+    if opt.keep {
+        println!(
+            "Keeping the streams named {} and the objects in s3://{}/{}",
+            &opt.name, &opt.bucket, &prefix
+        );
+    } else {
+        println!("5. Tearing down the pipeline");
+        tear_down(&clients, &opt, &prefix).await;
+    }
+
+    result
+}
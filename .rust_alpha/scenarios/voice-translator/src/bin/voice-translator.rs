@@ -0,0 +1,394 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0.
+ */
+
+use std::error::Error;
+use std::path::Path;
+use std::time::Duration;
+
+use aws_types::region::{ProvideRegion, Region};
+
+use polly::model::{OutputFormat, VoiceId};
+use s3::ByteStream;
+use transcribe::model::{LanguageCode, Media, MediaFormat, TranscriptionJobStatus};
+
+use common::profile::ProfileCredentialsProvider;
+use common::shutdown::Shutdown;
+use serde_json::Value;
+use structopt::StructOpt;
+use tokio::io::AsyncWriteExt;
+use tokio::time::sleep;
+use tracing_subscriber::fmt::format::FmtSpan;
+use tracing_subscriber::fmt::SubscriberBuilder;
+
+#[derive(Debug, StructOpt)]
+struct Opt {
+    /// The region. Overrides environment variable AWS_DEFAULT_REGION.
+    #[structopt(short, long)]
+    default_region: Option<String>,
+
+    /// The bucket used to hold the audio file and the transcript
+    #[structopt(short, long)]
+    bucket: String,
+
+    /// The audio file to translate
+    #[structopt(short, long)]
+    audio_file: String,
+
+    /// The language spoken in the audio file, such as en-US
+    #[structopt(short, long, default_value = "en-US")]
+    language: String,
+
+    /// The language to translate into, such as es
+    #[structopt(short, long)]
+    target_language: String,
+
+    /// The Amazon Polly voice that speaks the translation, such as Lucia
+    #[structopt(long)]
+    voice: String,
+
+    /// Whether to keep the uploaded audio file, transcript, and transcription job
+    #[structopt(short, long)]
+    keep: bool,
+
+    /// The AWS profile to use from the shared credentials file
+    #[structopt(long)]
+    profile: Option<String>,
+
+    /// Whether to display additional runtime information
+    #[structopt(short, long)]
+    verbose: bool,
+}
+
+/// The AWS clients used by the scenario.
+struct Clients {
+    polly: polly::Client,
+    s3: s3::Client,
+    transcribe: transcribe::Client,
+    translate: translate::Client,
+}
+
+/// Uploads the audio file to the bucket and returns its S3 URI.
+async fn upload_audio(
+    client: &s3::Client,
+    bucket: &str,
+    key: &str,
+    audio_file: &str,
+) -> Result<String, Box<dyn Error>> {
+    let body = ByteStream::from_path(Path::new(audio_file)).await?;
+
+    client
+        .put_object()
+        .bucket(bucket)
+        .key(key)
+        .body(body)
+        .send()
+        .await?;
+
+    Ok(format!("s3://{}/{}", bucket, key))
+}
+
+/// Starts a transcription job and waits for it to finish.
+/// The transcript is written to the bucket as JOB-NAME.json.
+async fn transcribe_audio(
+    client: &transcribe::Client,
+    job_name: &str,
+    media_uri: &str,
+    media_format: &str,
+    language: &str,
+    bucket: &str,
+) -> Result<(), Box<dyn Error>> {
+    client
+        .start_transcription_job()
+        .transcription_job_name(job_name)
+        .media(Media::builder().media_file_uri(media_uri).build())
+        .media_format(MediaFormat::from(media_format))
+        .language_code(LanguageCode::from(language))
+        .output_bucket_name(bucket)
+        .send()
+        .await?;
+
+    loop {
+        let resp = client
+            .get_transcription_job()
+            .transcription_job_name(job_name)
+            .send()
+            .await?;
+
+        let job = resp
+            .transcription_job
+            .ok_or("no transcription job returned")?;
+
+        match job.transcription_job_status {
+            Some(TranscriptionJobStatus::Completed) => return Ok(()),
+            Some(TranscriptionJobStatus::Failed) => {
+                return Err(format!(
+                    "transcription failed: {}",
+                    job.failure_reason.as_deref().unwrap_or("unknown reason")
+                )
+                .into())
+            }
+            status => {
+                println!("  Transcription job status: {:?}", status);
+                sleep(Duration::from_secs(5)).await;
+            }
+        }
+    }
+}
+
+/// Reads the transcript that Amazon Transcribe wrote to the bucket.
+async fn read_transcript(
+    client: &s3::Client,
+    bucket: &str,
+    key: &str,
+) -> Result<String, Box<dyn Error>> {
+    let resp = client.get_object().bucket(bucket).key(key).send().await?;
+    let data = resp.body.collect().await?;
+    let json: Value = serde_json::from_slice(&data.into_bytes())?;
+
+    // The transcript is in results.transcripts[0].transcript.
+    json["results"]["transcripts"][0]["transcript"]
+        .as_str()
+        .map(String::from)
+        .ok_or_else(|| "the transcript file did not contain a transcript".into())
+}
+
+/// Translates the text into the target language.
+async fn translate_text(
+    client: &translate::Client,
+    text: &str,
+    source_language: &str,
+    target_language: &str,
+) -> Result<String, Box<dyn Error>> {
+    let resp = client
+        .translate_text()
+        .text(text)
+        .source_language_code(source_language)
+        .target_language_code(target_language)
+        .send()
+        .await?;
+
+    Ok(resp.translated_text.unwrap_or_default())
+}
+
+/// Synthesizes the text and saves the MP3 audio in a file.
+async fn synthesize(
+    client: &polly::Client,
+    text: &str,
+    voice: &str,
+    out_file: &str,
+) -> Result<(), Box<dyn Error>> {
+    let resp = client
+        .synthesize_speech()
+        .output_format(OutputFormat::Mp3)
+        .text(text)
+        .voice_id(VoiceId::from(voice))
+        .send()
+        .await?;
+
+    let mut blob = resp.audio_stream.collect().await?;
+    let mut file = tokio::fs::File::create(out_file).await?;
+    file.write_all_buf(&mut blob).await?;
+
+    Ok(())
+}
+
+/// Deletes the transcription job and the objects the scenario created in the bucket.
+async fn clean_up(clients: &Clients, bucket: &str, job_name: &str, keys: &[&str]) {
+    if let Err(e) = clients
+        .transcribe
+        .delete_transcription_job()
+        .transcription_job_name(job_name)
+        .send()
+        .await
+    {
+        eprintln!(
+            "Got an error deleting transcription job {}: {}",
+            job_name, e
+        );
+    }
+
+    for key in keys {
+        if let Err(e) = clients
+            .s3
+            .delete_object()
+            .bucket(bucket)
+            .key(*key)
+            .send()
+            .await
+        {
+            eprintln!("Got an error deleting {}: {}", key, e);
+        }
+    }
+}
+
+/// Runs the steps of the scenario, stopping at the first error.
+async fn run(
+    clients: &Clients,
+    opt: &Opt,
+    audio_key: &str,
+    job_name: &str,
+    out_file: &str,
+) -> Result<(), Box<dyn Error>> {
+    let media_format = Path::new(&opt.audio_file)
+        .extension()
+        .and_then(|ext| ext.to_str())
+        .map(|ext| ext.to_ascii_lowercase())
+        .ok_or("the audio file must have an extension such as .mp3 or .wav")?;
+
+    println!("1. Uploading {} to bucket {}", &opt.audio_file, &opt.bucket);
+    let media_uri = upload_audio(&clients.s3, &opt.bucket, audio_key, &opt.audio_file).await?;
+
+    println!("2. Transcribing {}", &media_uri);
+    transcribe_audio(
+        &clients.transcribe,
+        job_name,
+        &media_uri,
+        &media_format,
+        &opt.language,
+        &opt.bucket,
+    )
+    .await?;
+
+    let transcript =
+        read_transcript(&clients.s3, &opt.bucket, &format!("{}.json", job_name)).await?;
+    println!("  Transcript: {}", transcript);
+
+    // Amazon Translate wants the language, such as en, not the locale, such as en-US.
+    let source_language = opt.language.split('-').next().unwrap_or("auto");
+
+    println!(
+        "3. Translating the transcript into {}",
+        &opt.target_language
+    );
+    let translation = translate_text(
+        &clients.translate,
+        &transcript,
+        source_language,
+        &opt.target_language,
+    )
+    .await?;
+    println!("  Translation: {}", translation);
+
+    println!("4. Speaking the translation as {}", &opt.voice);
+    synthesize(&clients.polly, &translation, &opt.voice, out_file).await?;
+
+    println!("  Saved the translated speech in {}", out_file);
+
+    Ok(())
+}
+
+/// Translates spoken audio into another language.
+/// The scenario uploads an audio file to an Amazon S3 bucket, transcribes it with Amazon Transcribe,
+/// translates the transcript with Amazon Translate,
+/// and speaks the translation with Amazon Polly, saving the result in an MP3 file.
+/// # Arguments
+///
+/// * `-b BUCKET` - The bucket used to hold the audio file and transcript.
+/// * `-a AUDIO-FILE` - The audio file to translate, such as **hello.mp3**.
+///   The translated speech is saved as **hello-TARGET-LANGUAGE.mp3**.
+/// * `-t TARGET-LANGUAGE` - The language to translate into, such as **es**.
+/// * `--voice VOICE` - The Amazon Polly voice that speaks the translation, such as **Lucia**.
+/// * `[-l LANGUAGE]` - The language spoken in the audio file.
+///   If not supplied, defaults to **en-US**.
+/// * `[-k]` - Whether to keep the uploaded file, transcript, and transcription job.
+/// * `[-d DEFAULT-REGION]` - The region in which the clients are created.
+///    If not supplied, uses the value of the **AWS_DEFAULT_REGION** environment variable.
+///    If the environment variable is not set, defaults to **us-west-2**.
+/// * `[--profile PROFILE]` - The AWS profile to use from the shared credentials file.
+/// * `[-v]` - Whether to display additional information.
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn Error>> {
+    let opt = Opt::from_args();
+
+    let region = opt
+        .default_region
+        .as_ref()
+        .map(|region| Region::new(region.clone()))
+        .or_else(|| aws_types::region::default_provider().region())
+        .unwrap_or_else(|| Region::new("us-west-2"));
+
+    if opt.verbose {
+        println!("Polly client version:      {}", polly::PKG_VERSION);
+        println!("S3 client version:         {}", s3::PKG_VERSION);
+        println!("Transcribe client version: {}", transcribe::PKG_VERSION);
+        println!("Translate client version:  {}", translate::PKG_VERSION);
+        println!("Region:                    {:?}", &region);
+        println!("Bucket:                    {}", &opt.bucket);
+        println!("Audio file:                {}", &opt.audio_file);
+        println!("Language:                  {}", &opt.language);
+        println!("Target language:           {}", &opt.target_language);
+        println!("Voice:                     {}", &opt.voice);
+        println!();
+
+        SubscriberBuilder::default()
+            .with_env_filter("info")
+            .with_span_events(FmtSpan::CLOSE)
+            .init();
+    }
+
+    let mut polly_config = polly::Config::builder().region(region.clone());
+    let mut s3_config = s3::Config::builder().region(region.clone());
+    let mut transcribe_config = transcribe::Config::builder().region(region.clone());
+    let mut translate_config = translate::Config::builder().region(region);
+    if let Some(profile) = &opt.profile {
+        polly_config = polly_config.credentials_provider(ProfileCredentialsProvider::new(profile));
+        s3_config = s3_config.credentials_provider(ProfileCredentialsProvider::new(profile));
+        transcribe_config =
+            transcribe_config.credentials_provider(ProfileCredentialsProvider::new(profile));
+        translate_config =
+            translate_config.credentials_provider(ProfileCredentialsProvider::new(profile));
+    }
+
+    let clients = Clients {
+        polly: polly::Client::from_conf(polly_config.build()),
+        s3: s3::Client::from_conf(s3_config.build()),
+        transcribe: transcribe::Client::from_conf(transcribe_config.build()),
+        translate: translate::Client::from_conf(translate_config.build()),
+    };
+
+    let stem = Path::new(&opt.audio_file)
+        .file_stem()
+        .map(|stem| stem.to_string_lossy().into_owned())
+        .unwrap_or_else(|| String::from("audio"));
+    let audio_key = Path::new(&opt.audio_file)
+        .file_name()
+        .map(|name| name.to_string_lossy().into_owned())
+        .unwrap_or_else(|| opt.audio_file.clone());
+
+    // Transcription job names must be unique in the account and region.
+    let job_name = format!(
+        "voice-translator-{}-{}",
+        stem,
+        std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)?
+            .as_secs()
+    );
+    let transcript_key = format!("{}.json", job_name);
+    let out_file = format!("{}-{}.mp3", stem, &opt.target_language);
+
+    // Ctrl-C stops the scenario wherever it is, and still cleans up what it created.
+    let shutdown = Shutdown::listen();
+    let result = shutdown
+        .run(run(&clients, &opt, &audio_key, &job_name, &out_file))
+        .await;
+
+    if opt.keep {
+        println!(
+            "Keeping s3://{}/{}, s3://{}/{}, and transcription job {}",
+            &opt.bucket, &audio_key, &opt.bucket, &transcript_key, &job_name
+        );
+    } else {
+        println!("5. Cleaning up");
+        clean_up(
+            &clients,
+            &opt.bucket,
+            &job_name,
+            &[&audio_key, &transcript_key],
+        )
+        .await;
+    }
+
+    result
+}
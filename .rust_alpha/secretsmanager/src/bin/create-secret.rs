@@ -3,10 +3,14 @@
  * SPDX-License-Identifier: Apache-2.0.
  */
 
-use secretsmanager::{Client, Config, Region};
+use std::error::Error;
+use std::fs;
+
+use secretsmanager::{Blob, Client, Config, Region};
 
 use aws_types::region::{EnvironmentProvider, ProvideRegion};
 
+use common::profile::ProfileCredentialsProvider;
 use structopt::StructOpt;
 
 use tracing_subscriber::fmt::format::FmtSpan;
@@ -23,27 +27,40 @@ struct Opt {
     name: String,
 
     /// The value of the secret
+    #[structopt(short, long, required_unless = "file", conflicts_with = "file")]
+    secret_value: Option<String>,
+
+    /// The file with the binary value of the secret
     #[structopt(short, long)]
-    secret_value: String,
+    file: Option<String>,
+
+    /// The AWS profile to use from the shared credentials file
+    #[structopt(long)]
+    profile: Option<String>,
 
     /// Whether to display additonal runtime information
     #[structopt(short, long)]
     verbose: bool,
 }
 
-/// Creates a secret.
+/// Creates a secret, with either a string value or the binary contents of a file.
 /// # Arguments
 ///
 /// * `-n NAME` - The name of the secret.
 /// * `-s SECRET_VALUE` - The secret value.
-/// * `[-d DEFAULT-REGION]` - The region in which the client is created.
+///   You must supply either this or a file.
+/// * `-f FILE` - The file with the binary secret value, such as a certificate or a keystore.
+/// * `[-r REGION]` - The region in which the client is created.
 ///    If not supplied, uses the value of the **AWS_DEFAULT_REGION** environment variable.
 ///    If the environment variable is not set, defaults to **us-west-2**.
+/// * `[--profile PROFILE]` - The AWS profile to use from the shared credentials file.
 /// * `[-v]` - Whether to display additional information.
 #[tokio::main]
-async fn main() {
+async fn main() -> Result<(), Box<dyn Error>> {
     let Opt {
+        file,
         name,
+        profile,
         region,
         secret_value,
         verbose,
@@ -61,7 +78,7 @@ async fn main() {
         );
         println!("Region:       {:?}", &region);
         println!("Secret name:  {}", name);
-        println!("Secret value: {}", secret_value);
+        println!("Secret file:  {:?}", file);
 
         SubscriberBuilder::default()
             .with_env_filter("info")
@@ -69,18 +86,28 @@ async fn main() {
             .init();
     }
 
-    let config = Config::builder().region(region).build();
+    let mut config = Config::builder().region(region);
+    if let Some(profile) = profile {
+        config = config.credentials_provider(ProfileCredentialsProvider::new(profile));
+    }
 
-    let client = Client::from_conf(config);
+    let client = Client::from_conf(config.build());
 
-    match client
+    // A secret holds either a string or binary data, so set only one of them.
+    let secret_binary = match &file {
+        Some(file) => Some(Blob::new(fs::read(file)?)),
+        None => None,
+    };
+
+    let resp = client
         .create_secret()
         .name(name)
-        .secret_string(secret_value)
+        .set_secret_string(secret_value)
+        .set_secret_binary(secret_binary)
         .send()
-        .await
-    {
-        Ok(_) => println!("Created secret"),
-        Err(e) => panic!("Failed to create secret: {}", e),
-    };
+        .await?;
+
+    println!("Created secret {}", resp.arn.as_deref().unwrap_or_default());
+
+    Ok(())
 }
@@ -2,12 +2,14 @@
  * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
  * SPDX-License-Identifier: Apache-2.0.
  */
-use std::process;
+use std::error::Error;
+use std::fs;
 
 use secretsmanager::{Client, Config, Region};
 
 use aws_types::region::{EnvironmentProvider, ProvideRegion};
 
+use common::profile::ProfileCredentialsProvider;
 use structopt::StructOpt;
 
 use tracing_subscriber::fmt::format::FmtSpan;
@@ -23,24 +25,37 @@ struct Opt {
     #[structopt(short, long)]
     name: String,
 
+    /// The file to save a binary secret in
+    #[structopt(short, long)]
+    file: Option<String>,
+
+    /// The AWS profile to use from the shared credentials file
+    #[structopt(long)]
+    profile: Option<String>,
+
     /// Whether to display additonal runtime information
     #[structopt(short, long)]
     verbose: bool,
 }
 
 /// Retrieves the value of a secret.
+/// Displays a string secret. Saves a binary secret in a file, or displays it base64-encoded.
 /// # Arguments
 ///
-/// * `-n NAME` - The name of the secret.
-/// * `-s SECRET_VALUE` - The secret value.
-/// * `[-d DEFAULT-REGION]` - The region in which the client is created.
+/// * `-n NAME` - The name or ARN of the secret.
+/// * `[-f FILE]` - The file in which to save the value of a binary secret.
+///   If not supplied, displays the value base64-encoded.
+/// * `[-r REGION]` - The region in which the client is created.
 ///    If not supplied, uses the value of the **AWS_DEFAULT_REGION** environment variable.
 ///    If the environment variable is not set, defaults to **us-west-2**.
+/// * `[--profile PROFILE]` - The AWS profile to use from the shared credentials file.
 /// * `[-v]` - Whether to display additional information.
 #[tokio::main]
-async fn main() {
+async fn main() -> Result<(), Box<dyn Error>> {
     let Opt {
+        file,
         name,
+        profile,
         region,
         verbose,
     } = Opt::from_args();
@@ -64,20 +79,30 @@ async fn main() {
             .init();
     }
 
-    let config = Config::builder().region(region).build();
-    let client = Client::from_conf(config);
-
-    match client.get_secret_value().secret_id(name).send().await {
-        Ok(resp) => {
-            println!(
-                "Value: {}",
-                resp.secret_string.as_deref().unwrap_or("No value!")
-            );
-        }
-        Err(e) => {
-            println!("Got an error listing secrets:");
-            println!("{}", e);
-            process::exit(1);
-        }
-    };
+    let mut config = Config::builder().region(region);
+    if let Some(profile) = profile {
+        config = config.credentials_provider(ProfileCredentialsProvider::new(profile));
+    }
+    let client = Client::from_conf(config.build());
+
+    let resp = client.get_secret_value().secret_id(name).send().await?;
+
+    // A secret created from a string has SecretString; one created from binary data has SecretBinary.
+    match (resp.secret_string, resp.secret_binary) {
+        (Some(value), _) => println!("Value: {}", value),
+        (None, Some(binary)) => match file {
+            Some(file) => {
+                fs::write(&file, binary.as_ref())?;
+                println!(
+                    "Saved {} bytes of binary value to {}",
+                    binary.as_ref().len(),
+                    file
+                );
+            }
+            None => println!("Binary value (base64): {}", base64::encode(binary.as_ref())),
+        },
+        (None, None) => println!("No value!"),
+    }
+
+    Ok(())
 }
@@ -2,12 +2,11 @@
  * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
  * SPDX-License-Identifier: Apache-2.0.
  */
-use std::process;
-
-use secretsmanager::{Client, Config, Region};
+use secretsmanager::{Client, Config, Error, Region};
 
 use aws_types::region::{EnvironmentProvider, ProvideRegion};
 
+use common::profile::ProfileCredentialsProvider;
 use structopt::StructOpt;
 
 use tracing_subscriber::fmt::format::FmtSpan;
@@ -19,21 +18,30 @@ struct Opt {
     #[structopt(short, long)]
     region: Option<String>,
 
+    /// The AWS profile to use from the shared credentials file
+    #[structopt(long)]
+    profile: Option<String>,
+
     /// Whether to display additonal runtime information
     #[structopt(short, long)]
     verbose: bool,
 }
 
-/// Lists the names of your secrets.
+/// Lists the names of your secrets, and whether rotation is enabled for each.
 /// # Arguments
 ///
-/// * `[-d DEFAULT-REGION]` - The region in which the client is created.
+/// * `[-r REGION]` - The region in which the client is created.
 ///    If not supplied, uses the value of the **AWS_DEFAULT_REGION** environment variable.
 ///    If the environment variable is not set, defaults to **us-west-2**.
+/// * `[--profile PROFILE]` - The AWS profile to use from the shared credentials file.
 /// * `[-v]` - Whether to display additional information.
 #[tokio::main]
-async fn main() {
-    let Opt { region, verbose } = Opt::from_args();
+async fn main() -> Result<(), Error> {
+    let Opt {
+        profile,
+        region,
+        verbose,
+    } = Opt::from_args();
 
     let region = EnvironmentProvider::new()
         .region()
@@ -53,24 +61,46 @@ async fn main() {
             .init();
     }
 
-    let config = Config::builder().region(region).build();
-    let client = Client::from_conf(config);
+    let mut config = Config::builder().region(region);
+    if let Some(profile) = profile {
+        config = config.credentials_provider(ProfileCredentialsProvider::new(profile));
+    }
+    let client = Client::from_conf(config.build());
+
+    println!("Secret names:");
 
-    match client.list_secrets().send().await {
-        Ok(resp) => {
-            println!("Secret names:");
+    // ListSecrets returns at most 100 secrets at a time, so follow NextToken to get them all.
+    let mut secrets = 0;
+    let mut next_token = None;
+    loop {
+        let resp = client
+            .list_secrets()
+            .set_next_token(next_token)
+            .send()
+            .await?;
 
-            let secrets = resp.secret_list.unwrap_or_default();
-            for secret in &secrets {
-                println!("  {}", secret.name.as_deref().unwrap_or("No name!"));
-            }
+        for secret in resp.secret_list.unwrap_or_default() {
+            secrets += 1;
 
-            println!("Found {} secrets", secrets.len());
+            let rotation = if secret.rotation_enabled.unwrap_or_default() {
+                "rotation enabled"
+            } else {
+                "rotation disabled"
+            };
+            println!(
+                "  {} ({})",
+                secret.name.as_deref().unwrap_or("No name!"),
+                rotation
+            );
         }
-        Err(e) => {
-            println!("Got an error listing secrets:");
-            println!("{}", e);
-            process::exit(1);
+
+        next_token = resp.next_token;
+        if next_token.is_none() {
+            break;
         }
-    };
+    }
+
+    println!("Found {} secrets", secrets);
+
+    Ok(())
 }
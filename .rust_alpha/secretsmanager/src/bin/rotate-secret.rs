@@ -0,0 +1,122 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0.
+ */
+
+use secretsmanager::model::RotationRulesType;
+use secretsmanager::{Client, Config, Error, Region};
+
+use aws_types::region::{EnvironmentProvider, ProvideRegion};
+
+use common::profile::ProfileCredentialsProvider;
+use structopt::StructOpt;
+
+use tracing_subscriber::fmt::format::FmtSpan;
+use tracing_subscriber::fmt::SubscriberBuilder;
+
+#[derive(Debug, StructOpt)]
+struct Opt {
+    /// The region
+    #[structopt(short, long)]
+    region: Option<String>,
+
+    /// The name of the secret
+    #[structopt(short, long)]
+    name: String,
+
+    /// The ARN of the Lambda function that rotates the secret
+    #[structopt(short, long)]
+    lambda_arn: Option<String>,
+
+    /// The number of days between rotations
+    #[structopt(short, long)]
+    days: Option<i64>,
+
+    /// The AWS profile to use from the shared credentials file
+    #[structopt(long)]
+    profile: Option<String>,
+
+    /// Whether to display additonal runtime information
+    #[structopt(short, long)]
+    verbose: bool,
+}
+
+/// Rotates a secret, and optionally sets up automatic rotation.
+/// Secrets Manager calls the rotation Lambda function, which creates the new version of the secret.
+/// # Arguments
+///
+/// * `-n NAME` - The name or ARN of the secret.
+/// * `[-l LAMBDA-ARN]` - The ARN of the Lambda function that rotates the secret.
+///   You must supply this the first time you rotate a secret.
+///   After that, Secrets Manager uses the same function.
+/// * `[-d DAYS]` - The number of days between automatic rotations.
+///   If not supplied, the secret's rotation schedule doesn't change.
+/// * `[-r REGION]` - The region in which the client is created.
+///    If not supplied, uses the value of the **AWS_DEFAULT_REGION** environment variable.
+///    If the environment variable is not set, defaults to **us-west-2**.
+/// * `[--profile PROFILE]` - The AWS profile to use from the shared credentials file.
+/// * `[-v]` - Whether to display additional information.
+#[tokio::main]
+async fn main() -> Result<(), Error> {
+    let Opt {
+        days,
+        lambda_arn,
+        name,
+        profile,
+        region,
+        verbose,
+    } = Opt::from_args();
+
+    let region = EnvironmentProvider::new()
+        .region()
+        .or_else(|| region.as_ref().map(|region| Region::new(region.clone())))
+        .unwrap_or_else(|| Region::new("us-west-2"));
+
+    if verbose {
+        println!(
+            "SecretsManager client version: {}\n",
+            secretsmanager::PKG_VERSION
+        );
+        println!("Region:      {:?}", &region);
+        println!("Secret name: {}", name);
+        println!("Lambda ARN:  {:?}", lambda_arn);
+        println!("Days:        {:?}", days);
+
+        SubscriberBuilder::default()
+            .with_env_filter("info")
+            .with_span_events(FmtSpan::CLOSE)
+            .init();
+    }
+
+    let mut config = Config::builder().region(region);
+    if let Some(profile) = profile {
+        config = config.credentials_provider(ProfileCredentialsProvider::new(profile));
+    }
+    let client = Client::from_conf(config.build());
+
+    let rotation_rules = days.map(|days| {
+        RotationRulesType::builder()
+            .automatically_after_days(days)
+            .build()
+    });
+
+    let resp = client
+        .rotate_secret()
+        .secret_id(name)
+        .set_rotation_lambda_arn(lambda_arn)
+        .set_rotation_rules(rotation_rules)
+        .send()
+        .await?;
+
+    // The rotation runs in the background; the new version becomes AWSCURRENT when it finishes.
+    println!(
+        "Started rotating secret {}",
+        resp.name.as_deref().unwrap_or_default()
+    );
+    println!(
+        "New version: {}",
+        resp.version_id.as_deref().unwrap_or_default()
+    );
+
+    Ok(())
+}
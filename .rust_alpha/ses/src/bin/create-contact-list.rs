@@ -7,6 +7,7 @@ use aws_types::region::ProvideRegion;
 
 use ses::{Client, Config, Error, Region};
 
+use common::profile::ProfileCredentialsProvider;
 use structopt::StructOpt;
 
 #[derive(Debug, StructOpt)]
@@ -19,6 +20,10 @@ struct Opt {
     #[structopt(short, long)]
     default_region: Option<String>,
 
+    /// The AWS profile to use from the shared credentials file
+    #[structopt(long)]
+    profile: Option<String>,
+
     /// Whether to display additional runtime information
     #[structopt(short, long)]
     verbose: bool,
@@ -31,12 +36,14 @@ struct Opt {
 /// * `[-d DEFAULT-REGION]` - The region in which the client is created.
 ///    If not supplied, uses the value of the **AWS_DEFAULT_REGION** environment variable.
 ///    If the environment variable is not set, defaults to **us-west-2**.
+/// * `[--profile PROFILE]` - The AWS profile to use from the shared credentials file.
 /// * `[-v]` - Whether to display additional information.
 #[tokio::main]
 async fn main() -> Result<(), Error> {
     let Opt {
         contact_list,
         default_region,
+        profile,
         verbose,
     } = Opt::from_args();
 
@@ -53,19 +60,19 @@ async fn main() -> Result<(), Error> {
         println!();
     }
 
-    let conf = Config::builder().region(region).build();
-    let client = Client::from_conf(conf);
+    let mut config = Config::builder().region(region);
+    if let Some(profile) = profile {
+        config = config.credentials_provider(ProfileCredentialsProvider::new(profile));
+    }
+    let client = Client::from_conf(config.build());
 
-    let resp = client
+    client
         .create_contact_list()
         .contact_list_name(contact_list)
         .send()
-        .await;
+        .await?;
 
-    match resp {
-        Ok(_) => println!("Created contact list."),
-        Err(e) => eprintln!("Got an error attempting to create contact list: {}", e),
-    };
+    println!("Created contact list.");
 
     Ok(())
 }
@@ -7,6 +7,7 @@ use aws_types::region::ProvideRegion;
 
 use ses::{Client, Config, Error, Region};
 
+use common::profile::ProfileCredentialsProvider;
 use structopt::StructOpt;
 
 #[derive(Debug, StructOpt)]
@@ -23,6 +24,10 @@ struct Opt {
     #[structopt(short, long)]
     email_address: String,
 
+    /// The AWS profile to use from the shared credentials file
+    #[structopt(long)]
+    profile: Option<String>,
+
     /// Whether to display additional runtime information
     #[structopt(short, long)]
     verbose: bool,
@@ -36,6 +41,7 @@ struct Opt {
 /// * `[-d DEFAULT-REGION]` - The region in which the client is created.
 ///    If not supplied, uses the value of the **AWS_DEFAULT_REGION** environment variable.
 ///    If the environment variable is not set, defaults to **us-west-2**.
+/// * `[--profile PROFILE]` - The AWS profile to use from the shared credentials file.
 /// * `[-v]` - Whether to display additional information.
 #[tokio::main]
 async fn main() -> Result<(), Error> {
@@ -43,6 +49,7 @@ async fn main() -> Result<(), Error> {
         contact_list,
         default_region,
         email_address,
+        profile,
         verbose,
     } = Opt::from_args();
 
@@ -60,19 +67,20 @@ async fn main() -> Result<(), Error> {
         println!();
     }
 
-    let conf = Config::builder().region(region).build();
-    let client = Client::from_conf(conf);
+    let mut config = Config::builder().region(region);
+    if let Some(profile) = profile {
+        config = config.credentials_provider(ProfileCredentialsProvider::new(profile));
+    }
+    let client = Client::from_conf(config.build());
 
-    let new_contact = client
+    client
         .create_contact()
         .contact_list_name(contact_list)
         .email_address(email_address)
         .send()
-        .await;
-    match new_contact {
-        Ok(_) => println!("Created contact"),
-        Err(e) => eprintln!("Got error attempting to create contact: {}", e),
-    };
+        .await?;
+
+    println!("Created contact");
 
     Ok(())
 }
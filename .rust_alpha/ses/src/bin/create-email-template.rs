@@ -0,0 +1,132 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0.
+ */
+
+use aws_types::region::ProvideRegion;
+
+use ses::model::EmailTemplateContent;
+use ses::{Client, Config, Region};
+
+use common::profile::ProfileCredentialsProvider;
+use std::error::Error;
+use std::fs;
+use structopt::StructOpt;
+use tracing_subscriber::fmt::format::FmtSpan;
+use tracing_subscriber::fmt::SubscriberBuilder;
+
+#[derive(Debug, StructOpt)]
+struct Opt {
+    /// The name of the template.
+    #[structopt(short, long)]
+    template_name: String,
+
+    /// The subject of the email. Can contain {{placeholders}}.
+    #[structopt(short, long)]
+    subject: String,
+
+    /// The text body of the email. Can contain {{placeholders}}.
+    #[structopt(long)]
+    text: Option<String>,
+
+    /// A file holding the HTML body of the email. Can contain {{placeholders}}.
+    #[structopt(long)]
+    html_file: Option<String>,
+
+    /// The AWS Region.
+    #[structopt(short, long)]
+    default_region: Option<String>,
+
+    /// The AWS profile to use from the shared credentials file
+    #[structopt(long)]
+    profile: Option<String>,
+
+    /// Whether to display additional runtime information
+    #[structopt(short, long)]
+    verbose: bool,
+}
+
+/// Creates an email template, which **send-email** can fill in and send.
+/// The subject and bodies can contain placeholders, such as **{{name}}**, which are replaced
+/// with the values in the template data when the email is sent.
+/// # Arguments
+///
+/// * `-t TEMPLATE-NAME` - The name of the template.
+/// * `-s SUBJECT` - The subject of the email.
+/// * `[--text TEXT]` - The text body of the email.
+/// * `[--html-file HTML-FILE]` - A file holding the HTML body of the email.
+///   At least one of `--text` and `--html-file` must be supplied.
+/// * `[-d DEFAULT-REGION]` - The region in which the client is created.
+///    If not supplied, uses the value of the **AWS_DEFAULT_REGION** environment variable.
+///    If the environment variable is not set, defaults to **us-west-2**.
+/// * `[--profile PROFILE]` - The AWS profile to use from the shared credentials file.
+/// * `[-v]` - Whether to display additional information.
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn Error>> {
+    let Opt {
+        template_name,
+        subject,
+        text,
+        html_file,
+        default_region,
+        profile,
+        verbose,
+    } = Opt::from_args();
+
+    let region = default_region
+        .as_ref()
+        .map(|region| Region::new(region.clone()))
+        .or_else(|| aws_types::region::default_provider().region())
+        .unwrap_or_else(|| Region::new("us-west-2"));
+
+    if text.is_none() && html_file.is_none() {
+        return Err(
+            "supply a text body with --text, an HTML body with --html-file, or both".into(),
+        );
+    }
+
+    let html = match &html_file {
+        Some(file) => {
+            Some(fs::read_to_string(file).map_err(|e| format!("couldn't read {}: {}", file, e))?)
+        }
+        None => None,
+    };
+
+    if verbose {
+        println!("SES client version: {}", ses::PKG_VERSION);
+        println!("Region:             {:?}", &region);
+        println!("Template name:      {}", &template_name);
+        println!("Subject:            {}", &subject);
+        println!("Text:               {:?}", &text);
+        println!("HTML file:          {:?}", &html_file);
+        println!();
+
+        SubscriberBuilder::default()
+            .with_env_filter("info")
+            .with_span_events(FmtSpan::CLOSE)
+            .init();
+    }
+
+    let mut config = Config::builder().region(region);
+    if let Some(profile) = profile {
+        config = config.credentials_provider(ProfileCredentialsProvider::new(profile));
+    }
+    let client = Client::from_conf(config.build());
+
+    let content = EmailTemplateContent::builder()
+        .subject(subject)
+        .set_text(text)
+        .set_html(html)
+        .build();
+
+    client
+        .create_email_template()
+        .template_name(&template_name)
+        .template_content(content)
+        .send()
+        .await?;
+
+    println!("Created template {}", &template_name);
+
+    Ok(())
+}
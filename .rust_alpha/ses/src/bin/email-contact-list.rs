@@ -8,6 +8,7 @@ use aws_types::region::ProvideRegion;
 use ses::model::{Body, Content, Destination, EmailContent, Message};
 use ses::{Client, Config, Error, Region};
 
+use common::profile::ProfileCredentialsProvider;
 use structopt::StructOpt;
 
 #[derive(Debug, StructOpt)]
@@ -31,6 +32,10 @@ struct Opt {
     /// The subject of the email.
     #[structopt(short, long)]
     subject: String,
+    /// The AWS profile to use from the shared credentials file
+    #[structopt(long)]
+    profile: Option<String>,
+
     /// Whether to display additional runtime information
     #[structopt(short, long)]
     verbose: bool,
@@ -46,6 +51,7 @@ struct Opt {
 /// * `[-d DEFAULT-REGION]` - The region in which the client is created.
 ///    If not supplied, uses the value of the **AWS_DEFAULT_REGION** environment variable.
 ///    If the environment variable is not set, defaults to **us-west-2**.
+/// * `[--profile PROFILE]` - The AWS profile to use from the shared credentials file.
 /// * `[-v]` - Whether to display additional information.
 #[tokio::main]
 async fn main() -> Result<(), Error> {
@@ -54,6 +60,7 @@ async fn main() -> Result<(), Error> {
         default_region,
         from_address,
         message,
+        profile,
         subject,
         verbose,
     } = Opt::from_args();
@@ -74,17 +81,20 @@ async fn main() -> Result<(), Error> {
         println!();
     }
 
-    let conf = Config::builder().region(region).build();
-    let client = Client::from_conf(conf);
+    let mut config = Config::builder().region(region);
+    if let Some(profile) = profile {
+        config = config.credentials_provider(ProfileCredentialsProvider::new(profile));
+    }
+    let client = Client::from_conf(config.build());
 
     // Get list of email addresses from contact list.
     let resp = client
         .list_contacts()
         .contact_list_name(contact_list)
         .send()
-        .await;
+        .await?;
 
-    let contacts = resp.unwrap().contacts.unwrap_or_default();
+    let contacts = resp.contacts.unwrap_or_default();
 
     let cs: String = contacts
         .into_iter()
@@ -103,19 +113,13 @@ async fn main() -> Result<(), Error> {
 
     let email_content = EmailContent::builder().simple(msg).build();
 
-    match client
+    client
         .send_email()
         .from_email_address(from_address)
         .destination(dest)
         .content(email_content)
         .send()
-        .await
-    {
-        Ok(_) => {}
-        Err(e) => {
-            println!("Got an error sending email: {}", e);
-        }
-    }
+        .await?;
 
     Ok(())
 }
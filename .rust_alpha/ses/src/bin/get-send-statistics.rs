@@ -0,0 +1,95 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0.
+ */
+
+use aws_types::region::ProvideRegion;
+
+use ses::{Client, Config, Error, Region};
+
+use common::profile::ProfileCredentialsProvider;
+use structopt::StructOpt;
+use tracing_subscriber::fmt::format::FmtSpan;
+use tracing_subscriber::fmt::SubscriberBuilder;
+
+#[derive(Debug, StructOpt)]
+struct Opt {
+    /// The AWS Region.
+    #[structopt(short, long)]
+    default_region: Option<String>,
+
+    /// The AWS profile to use from the shared credentials file
+    #[structopt(long)]
+    profile: Option<String>,
+
+    /// Whether to display additional runtime information
+    #[structopt(short, long)]
+    verbose: bool,
+}
+
+/// Displays how much email your account has sent in the last 24 hours, against its sending quota,
+/// and whether the account can send email at all.
+/// An account in the sandbox can only send email to verified identities.
+/// # Arguments
+///
+/// * `[-d DEFAULT-REGION]` - The region in which the client is created.
+///    If not supplied, uses the value of the **AWS_DEFAULT_REGION** environment variable.
+///    If the environment variable is not set, defaults to **us-west-2**.
+/// * `[--profile PROFILE]` - The AWS profile to use from the shared credentials file.
+/// * `[-v]` - Whether to display additional information.
+#[tokio::main]
+async fn main() -> Result<(), Error> {
+    let Opt {
+        default_region,
+        profile,
+        verbose,
+    } = Opt::from_args();
+
+    let region = default_region
+        .as_ref()
+        .map(|region| Region::new(region.clone()))
+        .or_else(|| aws_types::region::default_provider().region())
+        .unwrap_or_else(|| Region::new("us-west-2"));
+
+    if verbose {
+        println!("SES client version: {}", ses::PKG_VERSION);
+        println!("Region:             {:?}", &region);
+        println!();
+
+        SubscriberBuilder::default()
+            .with_env_filter("info")
+            .with_span_events(FmtSpan::CLOSE)
+            .init();
+    }
+
+    let mut config = Config::builder().region(region);
+    if let Some(profile) = profile {
+        config = config.credentials_provider(ProfileCredentialsProvider::new(profile));
+    }
+    let client = Client::from_conf(config.build());
+
+    // SES v2 reports the sending statistics with the rest of the account's details.
+    let resp = client.get_account().send().await?;
+
+    println!("Sending enabled:       {}", resp.sending_enabled);
+    println!("Production access:     {}", resp.production_access_enabled);
+    println!(
+        "Enforcement status:    {}",
+        resp.enforcement_status.as_deref().unwrap_or("unknown")
+    );
+
+    if let Some(quota) = resp.send_quota {
+        println!("Sent in last 24 hours: {}", quota.sent_last24_hours);
+        println!("Maximum in 24 hours:   {}", quota.max24_hour_send);
+        println!("Maximum per second:    {}", quota.max_send_rate);
+
+        if quota.max24_hour_send > 0.0 {
+            println!(
+                "Quota used:            {:.1}%",
+                quota.sent_last24_hours / quota.max24_hour_send * 100.0
+            );
+        }
+    }
+
+    Ok(())
+}
@@ -0,0 +1,164 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0.
+ */
+
+use aws_types::region::ProvideRegion;
+
+use ses::model::{Body, Content, Destination, EmailContent, Message, Template};
+use ses::{Client, Config, Region};
+
+use common::profile::ProfileCredentialsProvider;
+use std::error::Error;
+use structopt::StructOpt;
+use tracing_subscriber::fmt::format::FmtSpan;
+use tracing_subscriber::fmt::SubscriberBuilder;
+
+#[derive(Debug, StructOpt)]
+struct Opt {
+    /// The email address of the sender, which must be verified.
+    #[structopt(short, long)]
+    from_address: String,
+
+    /// The email address of a recipient. Repeat to send to several.
+    #[structopt(short, long, required = true)]
+    to_address: Vec<String>,
+
+    /// The subject of the email, for a simple email.
+    #[structopt(short, long, required_unless = "template-name")]
+    subject: Option<String>,
+
+    /// The message of the email, for a simple email.
+    #[structopt(short, long, required_unless = "template-name")]
+    message: Option<String>,
+
+    /// The name of the template to fill in, for a templated email.
+    #[structopt(long, conflicts_with_all = &["subject", "message"])]
+    template_name: Option<String>,
+
+    /// The values of the template's placeholders, as a JSON object.
+    #[structopt(long, default_value = "{}")]
+    template_data: String,
+
+    /// The AWS Region.
+    #[structopt(short, long)]
+    default_region: Option<String>,
+
+    /// The AWS profile to use from the shared credentials file
+    #[structopt(long)]
+    profile: Option<String>,
+
+    /// Whether to display additional runtime information
+    #[structopt(short, long)]
+    verbose: bool,
+}
+
+/// Sends an email, either a simple email with a subject and a message,
+/// or a templated email, which fills in a template created by **create-email-template**.
+/// While your account is in the sandbox, the recipients must also be verified.
+/// # Arguments
+///
+/// * `-f FROM-ADDRESS` - The email address of the sender.
+/// * `-t TO-ADDRESS...` - The email addresses of the recipients.
+/// * `[-s SUBJECT]` - The subject of a simple email.
+/// * `[-m MESSAGE]` - The message of a simple email.
+/// * `[--template-name TEMPLATE-NAME]` - The name of the template of a templated email.
+///   Either `-s` and `-m`, or `--template-name`, must be supplied.
+/// * `[--template-data JSON]` - The values of the template's placeholders, such as **{"name":"Mary"}**.
+///   If not supplied, defaults to **{}**.
+/// * `[-d DEFAULT-REGION]` - The region in which the client is created.
+///    If not supplied, uses the value of the **AWS_DEFAULT_REGION** environment variable.
+///    If the environment variable is not set, defaults to **us-west-2**.
+/// * `[--profile PROFILE]` - The AWS profile to use from the shared credentials file.
+/// * `[-v]` - Whether to display additional information.
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn Error>> {
+    let Opt {
+        from_address,
+        to_address,
+        subject,
+        message,
+        template_name,
+        template_data,
+        default_region,
+        profile,
+        verbose,
+    } = Opt::from_args();
+
+    let region = default_region
+        .as_ref()
+        .map(|region| Region::new(region.clone()))
+        .or_else(|| aws_types::region::default_provider().region())
+        .unwrap_or_else(|| Region::new("us-west-2"));
+
+    if verbose {
+        println!("SES client version: {}", ses::PKG_VERSION);
+        println!("Region:             {:?}", &region);
+        println!("From address:       {}", &from_address);
+        println!("To addresses:       {}", to_address.join(", "));
+        println!("Subject:            {:?}", &subject);
+        println!("Message:            {:?}", &message);
+        println!("Template name:      {:?}", &template_name);
+        println!("Template data:      {}", &template_data);
+        println!();
+
+        SubscriberBuilder::default()
+            .with_env_filter("info")
+            .with_span_events(FmtSpan::CLOSE)
+            .init();
+    }
+
+    let mut config = Config::builder().region(region);
+    if let Some(profile) = profile {
+        config = config.credentials_provider(ProfileCredentialsProvider::new(profile));
+    }
+    let client = Client::from_conf(config.build());
+
+    let content = match template_name {
+        Some(template_name) => {
+            let template = Template::builder()
+                .template_name(template_name)
+                .template_data(template_data)
+                .build();
+
+            EmailContent::builder().template(template).build()
+        }
+        None => {
+            let subject = Content::builder()
+                .data(subject.unwrap_or_default())
+                .charset("UTF-8")
+                .build();
+            let body = Body::builder()
+                .text(
+                    Content::builder()
+                        .data(message.unwrap_or_default())
+                        .charset("UTF-8")
+                        .build(),
+                )
+                .build();
+
+            EmailContent::builder()
+                .simple(Message::builder().subject(subject).body(body).build())
+                .build()
+        }
+    };
+
+    let resp = client
+        .send_email()
+        .from_email_address(from_address)
+        .destination(
+            Destination::builder()
+                .set_to_addresses(Some(to_address))
+                .build(),
+        )
+        .content(content)
+        .send()
+        .await?;
+
+    println!(
+        "Sent message {}",
+        resp.message_id.as_deref().unwrap_or_default()
+    );
+
+    Ok(())
+}
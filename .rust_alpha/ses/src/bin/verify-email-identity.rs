@@ -0,0 +1,147 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0.
+ */
+
+use aws_types::region::ProvideRegion;
+
+use ses::error::CreateEmailIdentityErrorKind;
+use ses::{Client, Config, Error, Region, SdkError};
+
+use common::profile::ProfileCredentialsProvider;
+use structopt::StructOpt;
+use tracing_subscriber::fmt::format::FmtSpan;
+use tracing_subscriber::fmt::SubscriberBuilder;
+
+#[derive(Debug, StructOpt)]
+struct Opt {
+    /// The email address or domain to verify.
+    #[structopt(short, long)]
+    identity: String,
+
+    /// The AWS Region.
+    #[structopt(short, long)]
+    default_region: Option<String>,
+
+    /// The AWS profile to use from the shared credentials file
+    #[structopt(long)]
+    profile: Option<String>,
+
+    /// Whether to display additional runtime information
+    #[structopt(short, long)]
+    verbose: bool,
+}
+
+/// Starts verifying an email address or a domain, so that Amazon SES can send email from it.
+/// For an email address, Amazon SES sends a message with a link that the owner must follow.
+/// For a domain, the example displays the DKIM tokens to publish as CNAME records in the domain's DNS.
+/// If the identity already exists, displays whether it's verified yet, so the example
+/// can be run again to check.
+/// # Arguments
+///
+/// * `-i IDENTITY` - The email address, such as **sender@example.com**, or the domain, such as **example.com**.
+/// * `[-d DEFAULT-REGION]` - The region in which the client is created.
+///    If not supplied, uses the value of the **AWS_DEFAULT_REGION** environment variable.
+///    If the environment variable is not set, defaults to **us-west-2**.
+/// * `[--profile PROFILE]` - The AWS profile to use from the shared credentials file.
+/// * `[-v]` - Whether to display additional information.
+#[tokio::main]
+async fn main() -> Result<(), Error> {
+    let Opt {
+        identity,
+        default_region,
+        profile,
+        verbose,
+    } = Opt::from_args();
+
+    let region = default_region
+        .as_ref()
+        .map(|region| Region::new(region.clone()))
+        .or_else(|| aws_types::region::default_provider().region())
+        .unwrap_or_else(|| Region::new("us-west-2"));
+
+    if verbose {
+        println!("SES client version: {}", ses::PKG_VERSION);
+        println!("Region:             {:?}", &region);
+        println!("Identity:           {}", &identity);
+        println!();
+
+        SubscriberBuilder::default()
+            .with_env_filter("info")
+            .with_span_events(FmtSpan::CLOSE)
+            .init();
+    }
+
+    let mut config = Config::builder().region(region);
+    if let Some(profile) = profile {
+        config = config.credentials_provider(ProfileCredentialsProvider::new(profile));
+    }
+    let client = Client::from_conf(config.build());
+
+    let (identity_type, verified, dkim) = match client
+        .create_email_identity()
+        .email_identity(&identity)
+        .send()
+        .await
+    {
+        Ok(resp) => {
+            println!("Started verifying {}", &identity);
+            (
+                resp.identity_type,
+                resp.verified_for_sending_status,
+                resp.dkim_attributes,
+            )
+        }
+        Err(SdkError::ServiceError { err, .. })
+            if matches!(
+                err.kind,
+                CreateEmailIdentityErrorKind::AlreadyExistsError(_)
+            ) =>
+        {
+            let resp = client
+                .get_email_identity()
+                .email_identity(&identity)
+                .send()
+                .await?;
+            (
+                resp.identity_type,
+                resp.verified_for_sending_status,
+                resp.dkim_attributes,
+            )
+        }
+        Err(e) => return Err(e.into()),
+    };
+
+    println!(
+        "Identity type:        {}",
+        identity_type
+            .as_ref()
+            .map(|identity_type| identity_type.as_str())
+            .unwrap_or("unknown")
+    );
+    println!("Verified for sending: {}", verified);
+
+    if verified {
+        return Ok(());
+    }
+
+    if identity.contains('@') {
+        println!(
+            "Follow the link in the message that Amazon SES sent to {} to verify it.",
+            &identity
+        );
+    } else if let Some(dkim) = dkim {
+        println!(
+            "Add these CNAME records to the DNS for {} to verify it:",
+            &identity
+        );
+        for token in dkim.tokens.unwrap_or_default() {
+            println!(
+                "  {}._domainkey.{} CNAME {}.dkim.amazonses.com",
+                token, &identity, token
+            );
+        }
+    }
+
+    Ok(())
+}
@@ -0,0 +1,138 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0.
+ */
+
+use std::error::Error;
+use std::fs;
+
+use sfn::model::StateMachineType;
+use sfn::{Client, Config, Region};
+use sfn_code_examples::json::check_definition;
+
+use aws_types::region::ProvideRegion;
+
+use common::profile::ProfileCredentialsProvider;
+use structopt::StructOpt;
+use tracing_subscriber::fmt::format::FmtSpan;
+use tracing_subscriber::fmt::SubscriberBuilder;
+
+#[derive(Debug, StructOpt)]
+struct Opt {
+    /// The region. Overrides environment variable AWS_DEFAULT_REGION.
+    #[structopt(short, long)]
+    default_region: Option<String>,
+
+    /// The name of the state machine
+    #[structopt(short, long)]
+    name: String,
+
+    /// The file holding the definition, in the Amazon States Language
+    #[structopt(short, long)]
+    file: String,
+
+    /// The ARN of the role that the state machine runs as
+    #[structopt(short, long)]
+    role_arn: String,
+
+    /// The type of state machine, STANDARD or EXPRESS
+    #[structopt(short = "t", long = "type", default_value = "STANDARD")]
+    machine_type: String,
+
+    /// The AWS profile to use from the shared credentials file
+    #[structopt(long)]
+    profile: Option<String>,
+
+    /// Whether to display additional information
+    #[structopt(short, long)]
+    verbose: bool,
+}
+
+/// Creates a state machine from a definition in the Amazon States Language (ASL).
+/// The definition is checked before it's sent, so that a file that isn't JSON,
+/// or has no **StartAt** state, gives a clear error.
+/// # Arguments
+///
+/// * `-n NAME` - The name of the state machine.
+/// * `-f FILE` - The file holding the definition.
+/// * `-r ROLE-ARN` - The ARN of the role that the state machine runs as,
+///   which Step Functions must be allowed to assume.
+/// * `[-t TYPE]` - The type of state machine, **STANDARD** or **EXPRESS**.
+///   If not supplied, defaults to **STANDARD**.
+/// * `[-d DEFAULT-REGION]` - The region in which the client is created.
+///    If not supplied, uses the value of the **AWS_DEFAULT_REGION** environment variable.
+///    If the environment variable is not set, defaults to **us-west-2**.
+/// * `[--profile PROFILE]` - The AWS profile to use from the shared credentials file.
+/// * `[-v]` - Whether to display additional information.
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn Error>> {
+    let Opt {
+        default_region,
+        file,
+        machine_type,
+        name,
+        profile,
+        role_arn,
+        verbose,
+    } = Opt::from_args();
+
+    let region = default_region
+        .as_ref()
+        .map(|region| Region::new(region.clone()))
+        .or_else(|| aws_types::region::default_provider().region())
+        .unwrap_or_else(|| Region::new("us-west-2"));
+
+    let state_machine_type = match machine_type.to_ascii_uppercase().as_str() {
+        "STANDARD" => StateMachineType::Standard,
+        "EXPRESS" => StateMachineType::Express,
+        _ => {
+            return Err(
+                format!("the type must be STANDARD or EXPRESS, got {}", machine_type).into(),
+            );
+        }
+    };
+
+    let definition =
+        fs::read_to_string(&file).map_err(|e| format!("couldn't read {}: {}", file, e))?;
+    check_definition(&definition)?;
+
+    if verbose {
+        println!("Step Functions client version: {}", sfn::PKG_VERSION);
+        println!("Region:                        {:?}", &region);
+        println!("Name:                          {}", &name);
+        println!("Definition file:               {}", &file);
+        println!("Role ARN:                      {}", &role_arn);
+        println!(
+            "Type:                          {}",
+            state_machine_type.as_str()
+        );
+        println!();
+
+        SubscriberBuilder::default()
+            .with_env_filter("info")
+            .with_span_events(FmtSpan::CLOSE)
+            .init();
+    }
+
+    let mut config = Config::builder().region(region);
+    if let Some(profile) = profile {
+        config = config.credentials_provider(ProfileCredentialsProvider::new(profile));
+    }
+    let client = Client::from_conf(config.build());
+
+    let resp = client
+        .create_state_machine()
+        .name(&name)
+        .definition(definition)
+        .role_arn(role_arn)
+        .r#type(state_machine_type)
+        .send()
+        .await?;
+
+    println!(
+        "Created state machine {}",
+        resp.state_machine_arn.as_deref().unwrap_or_default()
+    );
+
+    Ok(())
+}
@@ -0,0 +1,195 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0.
+ */
+
+use std::error::Error;
+use std::time::Duration;
+
+use sfn::model::ExecutionStatus;
+use sfn::{Client, Config, Region};
+use sfn_code_examples::json::pretty;
+use sfn_code_examples::waiters::wait_for_execution;
+
+use aws_types::region::ProvideRegion;
+
+use common::profile::ProfileCredentialsProvider;
+use smithy_types::instant::Format;
+use structopt::StructOpt;
+use tracing_subscriber::fmt::format::FmtSpan;
+use tracing_subscriber::fmt::SubscriberBuilder;
+
+#[derive(Debug, StructOpt)]
+struct Opt {
+    /// The region. Overrides environment variable AWS_DEFAULT_REGION.
+    #[structopt(short, long)]
+    default_region: Option<String>,
+
+    /// The ARN of the execution
+    #[structopt(short, long)]
+    execution_arn: String,
+
+    /// Whether to describe the execution as it is, rather than waiting for it to finish
+    #[structopt(long)]
+    no_wait: bool,
+
+    /// How many seconds to wait for the execution to finish
+    #[structopt(short, long, default_value = "300")]
+    timeout: u64,
+
+    /// The AWS profile to use from the shared credentials file
+    #[structopt(long)]
+    profile: Option<String>,
+
+    /// Whether to display additional information
+    #[structopt(short, long)]
+    verbose: bool,
+}
+
+/// Returns the error and cause of a failed, timed-out, or aborted execution,
+/// from the last event in its history.
+async fn failure(
+    client: &Client,
+    execution_arn: &str,
+) -> Result<Option<(String, String)>, sfn::Error> {
+    let resp = client
+        .get_execution_history()
+        .execution_arn(execution_arn)
+        .reverse_order(true)
+        .max_results(1)
+        .send()
+        .await?;
+
+    let event = match resp.events.unwrap_or_default().into_iter().next() {
+        Some(event) => event,
+        None => return Ok(None),
+    };
+
+    let failure = if let Some(details) = event.execution_failed_event_details {
+        (details.error, details.cause)
+    } else if let Some(details) = event.execution_timed_out_event_details {
+        (details.error, details.cause)
+    } else if let Some(details) = event.execution_aborted_event_details {
+        (details.error, details.cause)
+    } else {
+        return Ok(None);
+    };
+
+    Ok(Some((
+        failure.0.unwrap_or_default(),
+        failure.1.unwrap_or_default(),
+    )))
+}
+
+/// Describes an execution of a state machine: its status, when it started and stopped,
+/// and its input and output, formatted as indented JSON.
+/// Unless `--no-wait` is supplied, first waits for the execution to finish,
+/// displaying each change of status.
+/// For an execution that didn't succeed, also displays its error and cause.
+/// # Arguments
+///
+/// * `-e EXECUTION-ARN` - The ARN of the execution, which **start-execution** displays.
+/// * `[--no-wait]` - Whether to describe the execution as it is, rather than waiting for it to finish.
+/// * `[-t TIMEOUT]` - How many seconds to wait for the execution to finish.
+///   If not supplied, defaults to **300**.
+/// * `[-d DEFAULT-REGION]` - The region in which the client is created.
+///    If not supplied, uses the value of the **AWS_DEFAULT_REGION** environment variable.
+///    If the environment variable is not set, defaults to **us-west-2**.
+/// * `[--profile PROFILE]` - The AWS profile to use from the shared credentials file.
+/// * `[-v]` - Whether to display additional information.
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn Error>> {
+    let Opt {
+        default_region,
+        execution_arn,
+        no_wait,
+        profile,
+        timeout,
+        verbose,
+    } = Opt::from_args();
+
+    let region = default_region
+        .as_ref()
+        .map(|region| Region::new(region.clone()))
+        .or_else(|| aws_types::region::default_provider().region())
+        .unwrap_or_else(|| Region::new("us-west-2"));
+
+    if verbose {
+        println!("Step Functions client version: {}", sfn::PKG_VERSION);
+        println!("Region:                        {:?}", &region);
+        println!("Execution ARN:                 {}", &execution_arn);
+        println!("Wait:                          {}", !no_wait);
+        println!("Timeout:                       {}s", timeout);
+        println!();
+
+        SubscriberBuilder::default()
+            .with_env_filter("info")
+            .with_span_events(FmtSpan::CLOSE)
+            .init();
+    }
+
+    let mut config = Config::builder().region(region);
+    if let Some(profile) = profile {
+        config = config.credentials_provider(ProfileCredentialsProvider::new(profile));
+    }
+    let client = Client::from_conf(config.build());
+
+    let execution = if no_wait {
+        client
+            .describe_execution()
+            .execution_arn(&execution_arn)
+            .send()
+            .await?
+    } else {
+        wait_for_execution(
+            &client,
+            &execution_arn,
+            Duration::from_secs(timeout),
+            |status| println!("Execution status: {}", status.as_str()),
+        )
+        .await?
+    };
+
+    println!("Name:    {}", execution.name.as_deref().unwrap_or_default());
+    println!(
+        "Status:  {}",
+        execution
+            .status
+            .as_ref()
+            .map(|status| status.as_str())
+            .unwrap_or("unknown")
+    );
+    println!(
+        "Started: {}",
+        execution
+            .start_date
+            .as_ref()
+            .map(|date| date.fmt(Format::DateTime))
+            .unwrap_or_default()
+    );
+    if let Some(stop_date) = &execution.stop_date {
+        println!("Stopped: {}", stop_date.fmt(Format::DateTime));
+    }
+
+    if let Some(input) = &execution.input {
+        println!("Input:");
+        println!("{}", pretty(input, 2));
+    }
+
+    if let Some(output) = &execution.output {
+        println!("Output:");
+        println!("{}", pretty(output, 2));
+    }
+
+    match execution.status {
+        Some(ExecutionStatus::Running) | Some(ExecutionStatus::Succeeded) | None => {}
+        Some(_) => {
+            if let Some((error, cause)) = failure(&client, &execution_arn).await? {
+                println!("Error:   {}", error);
+                println!("Cause:   {}", cause);
+            }
+        }
+    }
+
+    Ok(())
+}
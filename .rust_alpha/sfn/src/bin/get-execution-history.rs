@@ -0,0 +1,213 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0.
+ */
+
+use std::error::Error;
+use std::time::SystemTime;
+
+use sfn::model::HistoryEvent;
+use sfn::{Client, Config, Region};
+use sfn_code_examples::json::pretty;
+
+use aws_types::region::ProvideRegion;
+
+use common::profile::ProfileCredentialsProvider;
+use structopt::StructOpt;
+use tracing_subscriber::fmt::format::FmtSpan;
+use tracing_subscriber::fmt::SubscriberBuilder;
+
+#[derive(Debug, StructOpt)]
+struct Opt {
+    /// The region. Overrides environment variable AWS_DEFAULT_REGION.
+    #[structopt(short, long)]
+    default_region: Option<String>,
+
+    /// The ARN of the execution
+    #[structopt(short, long)]
+    execution_arn: String,
+
+    /// Whether to display the input and output of each state, as well as its name
+    #[structopt(long)]
+    data: bool,
+
+    /// The AWS profile to use from the shared credentials file
+    #[structopt(long)]
+    profile: Option<String>,
+
+    /// Whether to display additional information
+    #[structopt(short, long)]
+    verbose: bool,
+}
+
+/// Returns the lines that describe an event's details, such as the state it entered,
+/// or the error that a task failed with. The input and output are only included with `data`.
+fn details(event: &HistoryEvent, data: bool) -> Vec<String> {
+    let mut lines = Vec::new();
+    let mut json = |label: &str, text: &Option<String>| {
+        if let (true, Some(text)) = (data, text) {
+            lines.push(format!("{}:", label));
+            lines.push(pretty(text, 2));
+        }
+    };
+
+    if let Some(details) = &event.execution_started_event_details {
+        json("Input", &details.input);
+    }
+    if let Some(details) = &event.execution_succeeded_event_details {
+        json("Output", &details.output);
+    }
+    if let Some(details) = &event.state_entered_event_details {
+        json("Input", &details.input);
+    }
+    if let Some(details) = &event.state_exited_event_details {
+        json("Output", &details.output);
+    }
+
+    let mut failure = |error: &Option<String>, cause: &Option<String>| {
+        lines.push(format!("Error: {}", error.as_deref().unwrap_or_default()));
+        if let Some(cause) = cause {
+            lines.push(format!("Cause: {}", cause));
+        }
+    };
+
+    if let Some(details) = &event.execution_failed_event_details {
+        failure(&details.error, &details.cause);
+    }
+    if let Some(details) = &event.execution_timed_out_event_details {
+        failure(&details.error, &details.cause);
+    }
+    if let Some(details) = &event.execution_aborted_event_details {
+        failure(&details.error, &details.cause);
+    }
+    if let Some(details) = &event.task_failed_event_details {
+        failure(&details.error, &details.cause);
+    }
+    if let Some(details) = &event.lambda_function_failed_event_details {
+        failure(&details.error, &details.cause);
+    }
+
+    lines
+}
+
+/// Returns the name of the state that an event entered or exited, if it's that kind of event.
+fn state_name(event: &HistoryEvent) -> Option<&str> {
+    event
+        .state_entered_event_details
+        .as_ref()
+        .and_then(|details| details.name.as_deref())
+        .or_else(|| {
+            event
+                .state_exited_event_details
+                .as_ref()
+                .and_then(|details| details.name.as_deref())
+        })
+}
+
+/// Displays the history of an execution of a state machine, one event after another,
+/// with each event's time since the execution started, its type, and the state it entered or exited.
+/// Failures show their error and cause, and, with `--data`, the input and output of each state
+/// are displayed as indented JSON.
+/// # Arguments
+///
+/// * `-e EXECUTION-ARN` - The ARN of the execution, which **start-execution** displays.
+/// * `[--data]` - Whether to display the input and output of each state.
+/// * `[-d DEFAULT-REGION]` - The region in which the client is created.
+///    If not supplied, uses the value of the **AWS_DEFAULT_REGION** environment variable.
+///    If the environment variable is not set, defaults to **us-west-2**.
+/// * `[--profile PROFILE]` - The AWS profile to use from the shared credentials file.
+/// * `[-v]` - Whether to display additional information.
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn Error>> {
+    let Opt {
+        data,
+        default_region,
+        execution_arn,
+        profile,
+        verbose,
+    } = Opt::from_args();
+
+    let region = default_region
+        .as_ref()
+        .map(|region| Region::new(region.clone()))
+        .or_else(|| aws_types::region::default_provider().region())
+        .unwrap_or_else(|| Region::new("us-west-2"));
+
+    if verbose {
+        println!("Step Functions client version: {}", sfn::PKG_VERSION);
+        println!("Region:                        {:?}", &region);
+        println!("Execution ARN:                 {}", &execution_arn);
+        println!();
+
+        SubscriberBuilder::default()
+            .with_env_filter("info")
+            .with_span_events(FmtSpan::CLOSE)
+            .init();
+    }
+
+    let mut config = Config::builder().region(region);
+    if let Some(profile) = profile {
+        config = config.credentials_provider(ProfileCredentialsProvider::new(profile));
+    }
+    let client = Client::from_conf(config.build());
+
+    let mut events = Vec::new();
+    let mut token = None;
+
+    loop {
+        let resp = client
+            .get_execution_history()
+            .execution_arn(&execution_arn)
+            .set_next_token(token)
+            .send()
+            .await?;
+
+        events.extend(resp.events.unwrap_or_default());
+
+        token = resp.next_token;
+        if token.is_none() {
+            break;
+        }
+    }
+
+    // The first event is when the execution started.
+    let start: Option<SystemTime> = events
+        .first()
+        .and_then(|event| event.timestamp.as_ref())
+        .and_then(|timestamp| timestamp.to_system_time());
+
+    for event in &events {
+        let offset = match (
+            start,
+            event
+                .timestamp
+                .as_ref()
+                .and_then(|timestamp| timestamp.to_system_time()),
+        ) {
+            (Some(start), Some(time)) => time.duration_since(start).unwrap_or_default().as_millis(),
+            _ => 0,
+        };
+
+        println!(
+            "{:>4} {:>8} ms  {:<28} {}",
+            event.id,
+            offset,
+            event
+                .r#type
+                .as_ref()
+                .map(|event_type| event_type.as_str())
+                .unwrap_or_default(),
+            state_name(event).unwrap_or_default()
+        );
+
+        for line in details(event, data) {
+            for line in line.lines() {
+                println!("                  {}", line);
+            }
+        }
+    }
+
+    println!("{} events", events.len());
+
+    Ok(())
+}
@@ -0,0 +1,127 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0.
+ */
+
+use std::error::Error;
+use std::fs;
+
+use sfn::{Client, Config, Region};
+use sfn_code_examples::json::parse;
+
+use aws_types::region::ProvideRegion;
+
+use common::profile::ProfileCredentialsProvider;
+use structopt::StructOpt;
+use tracing_subscriber::fmt::format::FmtSpan;
+use tracing_subscriber::fmt::SubscriberBuilder;
+
+#[derive(Debug, StructOpt)]
+struct Opt {
+    /// The region. Overrides environment variable AWS_DEFAULT_REGION.
+    #[structopt(short, long)]
+    default_region: Option<String>,
+
+    /// The ARN of the state machine
+    #[structopt(short, long)]
+    state_machine_arn: String,
+
+    /// The input of the execution, as JSON
+    #[structopt(short, long, conflicts_with = "input-file")]
+    input: Option<String>,
+
+    /// A file holding the input of the execution, as JSON
+    #[structopt(long)]
+    input_file: Option<String>,
+
+    /// The name of the execution. Defaults to a unique ID
+    #[structopt(short, long)]
+    name: Option<String>,
+
+    /// The AWS profile to use from the shared credentials file
+    #[structopt(long)]
+    profile: Option<String>,
+
+    /// Whether to display additional information
+    #[structopt(short, long)]
+    verbose: bool,
+}
+
+/// Starts an execution of a state machine, with JSON input, and displays the execution's ARN,
+/// which **describe-execution** and **get-execution-history** take.
+/// The input is checked before it's sent.
+/// # Arguments
+///
+/// * `-s STATE-MACHINE-ARN` - The ARN of the state machine.
+/// * `[-i INPUT]` - The input of the execution, such as **{"orderId":"order-1"}**.
+/// * `[--input-file INPUT-FILE]` - A file holding the input of the execution.
+///   If neither `-i` nor `--input-file` is supplied, the input is **{}**.
+/// * `[-n NAME]` - The name of the execution, which must be unique for the state machine.
+///   If not supplied, Step Functions uses a unique ID.
+/// * `[-d DEFAULT-REGION]` - The region in which the client is created.
+///    If not supplied, uses the value of the **AWS_DEFAULT_REGION** environment variable.
+///    If the environment variable is not set, defaults to **us-west-2**.
+/// * `[--profile PROFILE]` - The AWS profile to use from the shared credentials file.
+/// * `[-v]` - Whether to display additional information.
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn Error>> {
+    let Opt {
+        default_region,
+        input,
+        input_file,
+        name,
+        profile,
+        state_machine_arn,
+        verbose,
+    } = Opt::from_args();
+
+    let region = default_region
+        .as_ref()
+        .map(|region| Region::new(region.clone()))
+        .or_else(|| aws_types::region::default_provider().region())
+        .unwrap_or_else(|| Region::new("us-west-2"));
+
+    let input = match (input, &input_file) {
+        (Some(input), _) => input,
+        (None, Some(file)) => {
+            fs::read_to_string(file).map_err(|e| format!("couldn't read {}: {}", file, e))?
+        }
+        (None, None) => "{}".to_string(),
+    };
+    parse("input", &input)?;
+
+    if verbose {
+        println!("Step Functions client version: {}", sfn::PKG_VERSION);
+        println!("Region:                        {:?}", &region);
+        println!("State machine ARN:             {}", &state_machine_arn);
+        println!("Name:                          {:?}", &name);
+        println!("Input:                         {}", &input);
+        println!();
+
+        SubscriberBuilder::default()
+            .with_env_filter("info")
+            .with_span_events(FmtSpan::CLOSE)
+            .init();
+    }
+
+    let mut config = Config::builder().region(region);
+    if let Some(profile) = profile {
+        config = config.credentials_provider(ProfileCredentialsProvider::new(profile));
+    }
+    let client = Client::from_conf(config.build());
+
+    let resp = client
+        .start_execution()
+        .state_machine_arn(state_machine_arn)
+        .input(input)
+        .set_name(name)
+        .send()
+        .await?;
+
+    println!(
+        "Started execution {}",
+        resp.execution_arn.as_deref().unwrap_or_default()
+    );
+
+    Ok(())
+}
@@ -0,0 +1,62 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0.
+ */
+
+//! Checks the JSON that the examples send to Step Functions, and formats the JSON they get back.
+//!
+//! Step Functions takes state machine definitions and execution input as strings, and only reports
+//! a problem with them once the request reaches the service, so the examples parse them first,
+//! to give a clearer error for a typo in a file:
+//!
+//! ```ignore
+//! let definition = fs::read_to_string(&file)?;
+//! check_definition(&definition)?;
+//! ```
+
+use serde_json::Value;
+
+/// Parses the text as JSON, and returns an error that says where it went wrong, if it isn't.
+pub fn parse(what: &str, text: &str) -> Result<Value, String> {
+    serde_json::from_str(text).map_err(|e| format!("the {} isn't valid JSON: {}", what, e))
+}
+
+/// Checks that a definition in the Amazon States Language (ASL) is a JSON object
+/// with the fields that every state machine needs: **StartAt**, and the **States** it names.
+pub fn check_definition(definition: &str) -> Result<(), String> {
+    let definition = parse("definition", definition)?;
+
+    let start_at = definition["StartAt"]
+        .as_str()
+        .ok_or("the definition has no StartAt state")?;
+
+    let states = definition["States"]
+        .as_object()
+        .ok_or("the definition has no States object")?;
+
+    if !states.contains_key(start_at) {
+        return Err(format!(
+            "the StartAt state, {}, isn't one of the States",
+            start_at
+        ));
+    }
+
+    Ok(())
+}
+
+/// Formats JSON from Step Functions over several indented lines, so a large input or output can be read.
+/// Text that isn't JSON is returned as it is.
+pub fn pretty(text: &str, indent: usize) -> String {
+    let pretty = serde_json::from_str::<Value>(text)
+        .ok()
+        .and_then(|value| serde_json::to_string_pretty(&value).ok())
+        .unwrap_or_else(|| text.to_string());
+
+    let padding = " ".repeat(indent);
+
+    pretty
+        .lines()
+        .map(|line| format!("{}{}", padding, line))
+        .collect::<Vec<_>>()
+        .join("\n")
+}
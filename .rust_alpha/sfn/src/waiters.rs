@@ -0,0 +1,124 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0.
+ */
+
+//! Waits for a state machine execution to finish.
+//!
+//! A standard workflow can run for up to a year, so the waiter polls `DescribeExecution`
+//! until the execution leaves __RUNNING__ or the timeout passes, and calls `on_status`
+//! each time the status changes:
+//!
+//! ```ignore
+//! let execution = wait_for_execution(&client, &arn, Duration::from_secs(300), |status| {
+//!     println!("Execution status: {}", status.as_str());
+//! })
+//! .await?;
+//! ```
+
+use std::error::Error;
+use std::fmt;
+use std::time::{Duration, Instant};
+
+use sfn::model::ExecutionStatus;
+use sfn::output::DescribeExecutionOutput;
+use sfn::Client;
+use tokio::time::sleep;
+
+/// How long to wait before the first retry. The delay doubles after each retry.
+const INITIAL_DELAY: Duration = Duration::from_secs(1);
+
+/// The longest delay between two retries.
+const MAX_DELAY: Duration = Duration::from_secs(10);
+
+/// Why waiting for an execution failed.
+#[derive(Debug)]
+pub enum WaitError {
+    /// DescribeExecution failed.
+    Sdk(sfn::Error),
+    /// The execution was still running at the timeout. Holds its ARN.
+    Timeout(String),
+}
+
+impl fmt::Display for WaitError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            WaitError::Sdk(e) => write!(f, "{}", e),
+            WaitError::Timeout(arn) => write!(f, "execution {} was still running", arn),
+        }
+    }
+}
+
+impl Error for WaitError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            WaitError::Sdk(e) => Some(e),
+            WaitError::Timeout(_) => None,
+        }
+    }
+}
+
+impl From<sfn::Error> for WaitError {
+    fn from(e: sfn::Error) -> Self {
+        WaitError::Sdk(e)
+    }
+}
+
+/// Returns whether an execution with this status has finished,
+/// because it succeeded, failed, timed out, or was aborted.
+pub fn is_terminal(status: &ExecutionStatus) -> bool {
+    matches!(
+        status,
+        ExecutionStatus::Succeeded
+            | ExecutionStatus::Failed
+            | ExecutionStatus::TimedOut
+            | ExecutionStatus::Aborted
+    )
+}
+
+/// Polls the execution until it has finished, backing off exponentially between calls,
+/// and returns its description. Calls `on_status` with the first status it sees,
+/// and again each time the status changes.
+pub async fn wait_for_execution<F>(
+    client: &Client,
+    execution_arn: &str,
+    timeout: Duration,
+    mut on_status: F,
+) -> Result<DescribeExecutionOutput, WaitError>
+where
+    F: FnMut(&ExecutionStatus),
+{
+    let deadline = Instant::now() + timeout;
+    let mut delay = INITIAL_DELAY;
+    let mut last = None;
+
+    loop {
+        let execution = client
+            .describe_execution()
+            .execution_arn(execution_arn)
+            .send()
+            .await
+            .map_err(sfn::Error::from)?;
+
+        if let Some(status) = &execution.status {
+            if last.as_ref() != Some(status) {
+                on_status(status);
+            }
+
+            if is_terminal(status) {
+                return Ok(execution);
+            }
+        }
+
+        last = execution.status;
+
+        let now = Instant::now();
+        if now >= deadline {
+            return Err(WaitError::Timeout(execution_arn.to_string()));
+        }
+
+        // Don't sleep past the deadline; check one last time when it's reached.
+        sleep(delay.min(deadline - now)).await;
+        delay = (delay * 2).min(MAX_DELAY);
+    }
+}
@@ -4,8 +4,9 @@
  */
 
 use aws_types::region::ProvideRegion;
+use common::profile::ProfileCredentialsProvider;
 use sns::{Client, Config, Region};
-use std::process::exit;
+use std::error::Error;
 use structopt::StructOpt;
 use tracing_subscriber::fmt::format::FmtSpan;
 use tracing_subscriber::fmt::SubscriberBuilder;
@@ -16,6 +17,10 @@ struct Opt {
     #[structopt(short, long)]
     default_region: Option<String>,
 
+    /// The AWS profile to use from the shared credentials file
+    #[structopt(long)]
+    profile: Option<String>,
+
     /// Whether to display addtional information.
     #[structopt(short, long)]
     verbose: bool,
@@ -27,11 +32,13 @@ struct Opt {
 /// * `[-d DEFAULT-REGION]` - The AWS Region containing the Amazon SNS topic.
 ///   If not supplied, uses the value of the **AWS_DEFAULT_REGION** environment variable.
 ///   If the environment variable is not set, defaults to **us-west-2**.
+/// * `[--profile PROFILE]` - The AWS profile to use from the shared credentials file.
 /// * `[-v]` - Whether to display additional information.
 #[tokio::main]
-async fn main() -> Result<(), sns::Error> {
+async fn main() -> Result<(), Box<dyn Error>> {
     let Opt {
         default_region,
+        profile,
         verbose,
     } = Opt::from_args();
 
@@ -51,8 +58,11 @@ async fn main() -> Result<(), sns::Error> {
             .init();
     }
 
-    let config = Config::builder().region(region).build();
-    let client = Client::from_conf(config);
+    let mut config = Config::builder().region(region);
+    if let Some(profile) = profile {
+        config = config.credentials_provider(ProfileCredentialsProvider::new(profile));
+    }
+    let client = Client::from_conf(config.build());
 
     let topic_output = client.list_topics().send().await?;
     //let mut topics = topics.topics.unwrap_or_default();
@@ -61,8 +71,7 @@ async fn main() -> Result<(), sns::Error> {
 
     match topic_output.topics {
         None => {
-            println!("Did not find any topics in this region.");
-            exit(1);
+            return Err("Did not find any topics in this region.".into());
         }
         Some(topics) => {
             for (_, topic) in topics.iter().enumerate() {
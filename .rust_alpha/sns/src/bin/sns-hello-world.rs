@@ -4,10 +4,11 @@
  */
 
 use sns::{Client, Config, Region};
-use std::process::exit;
+use std::error::Error;
 
 use aws_types::region::ProvideRegion;
 
+use common::profile::ProfileCredentialsProvider;
 use structopt::StructOpt;
 
 #[derive(Debug, StructOpt)]
@@ -20,6 +21,10 @@ struct Opt {
     #[structopt(short, long)]
     email_address: String,
 
+    /// The AWS profile to use from the shared credentials file
+    #[structopt(long)]
+    profile: Option<String>,
+
     /// Whether to display additional runtime information
     #[structopt(short, long)]
     verbose: bool,
@@ -34,14 +39,16 @@ struct Opt {
 /// * `[-d DEFAULT-REGION]` - The region in which the client is created.
 ///    If not supplied, uses the value of the **AWS_DEFAULT_REGION** environment variable.
 ///    If the environment variable is not set, defaults to **us-west-2**.
+/// * `[--profile PROFILE]` - The AWS profile to use from the shared credentials file.
 /// * `[-v]` - Whether to display additional information.
 #[tokio::main]
-async fn main() -> Result<(), sns::Error> {
+async fn main() -> Result<(), Box<dyn Error>> {
     tracing_subscriber::fmt::init();
 
     let Opt {
         default_region,
         email_address,
+        profile,
         verbose,
     } = Opt::from_args();
 
@@ -57,18 +64,18 @@ async fn main() -> Result<(), sns::Error> {
         println!("Email address:      {}", &email_address);
     }
 
-    let conf = Config::builder().region(region).build();
-    let client = Client::from_conf(conf);
+    let mut config = Config::builder().region(region);
+    if let Some(profile) = profile {
+        config = config.credentials_provider(ProfileCredentialsProvider::new(profile));
+    }
+    let client = Client::from_conf(config.build());
 
     let topics = client.list_topics().send().await?;
     let mut topics = topics.topics.unwrap_or_default();
-    let topic_arn = match topics.pop() {
-        Some(topic) => topic.topic_arn.expect("topics have ARNs"),
-        None => {
-            eprintln!("No topics in this account. Please create a topic to proceed");
-            exit(1);
-        }
-    };
+    let topic_arn = topics
+        .pop()
+        .and_then(|topic| topic.topic_arn)
+        .ok_or("No topics in this account. Please create a topic to proceed")?;
 
     println!("Receiving on topic with ARN: `{}`", topic_arn);
 
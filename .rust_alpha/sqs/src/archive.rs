@@ -0,0 +1,146 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0.
+ */
+
+//! The archive format that `archive-queue` writes and `replay-queue` reads.
+//!
+//! An archive is a file of newline-delimited JSON, with one message on each line,
+//! so that it can be appended to, read a line at a time, and inspected with tools such as __jq__.
+//! Binary message attributes are stored as base64:
+//!
+//! ```ignore
+//! let line = serde_json::to_string(&ArchivedMessage::from(message))?;
+//! let entry = serde_json::from_str::<ArchivedMessage>(&line)?.to_entry("0", fifo)?;
+//! ```
+
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
+use sqs::model::{Message, MessageAttributeValue, SendMessageBatchRequestEntry};
+use sqs::Blob;
+
+/// The most messages that SendMessageBatch and DeleteMessageBatch accept in one call.
+pub const MAX_BATCH_MESSAGES: usize = 10;
+
+/// The most bytes that SendMessageBatch accepts in one call, counting the bodies and the message attributes.
+pub const MAX_BATCH_BYTES: usize = 262_144;
+
+/// The system attribute that holds the message group of a message from a FIFO queue.
+const MESSAGE_GROUP_ID: &str = "MessageGroupId";
+
+/// The system attribute that holds the deduplication ID of a message from a FIFO queue.
+const MESSAGE_DEDUPLICATION_ID: &str = "MessageDeduplicationId";
+
+/// A message attribute, as it's stored in an archive.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ArchivedAttribute {
+    /// The type of the attribute, such as **String**, **Number**, or **Binary**.
+    pub data_type: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub string_value: Option<String>,
+    /// The value of a binary attribute, as base64.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub binary_value: Option<String>,
+}
+
+/// A message, as it's stored in an archive.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ArchivedMessage {
+    pub message_id: String,
+    pub body: String,
+    /// The system attributes, such as **SentTimestamp** and, for FIFO queues, **MessageGroupId**.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub attributes: BTreeMap<String, String>,
+    /// The attributes that the sender added to the message.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub message_attributes: BTreeMap<String, ArchivedAttribute>,
+}
+
+impl From<Message> for ArchivedMessage {
+    fn from(message: Message) -> Self {
+        ArchivedMessage {
+            message_id: message.message_id.unwrap_or_default(),
+            body: message.body.unwrap_or_default(),
+            attributes: message
+                .attributes
+                .unwrap_or_default()
+                .into_iter()
+                .map(|(name, value)| (name.as_str().to_string(), value))
+                .collect(),
+            message_attributes: message
+                .message_attributes
+                .unwrap_or_default()
+                .into_iter()
+                .map(|(name, value)| {
+                    (
+                        name,
+                        ArchivedAttribute {
+                            data_type: value.data_type.unwrap_or_default(),
+                            string_value: value.string_value,
+                            binary_value: value
+                                .binary_value
+                                .map(|blob| base64::encode(blob.as_ref())),
+                        },
+                    )
+                })
+                .collect(),
+        }
+    }
+}
+
+impl ArchivedMessage {
+    /// How many bytes the message counts for towards `MAX_BATCH_BYTES`.
+    pub fn size(&self) -> usize {
+        self.body.len()
+            + self
+                .message_attributes
+                .iter()
+                .map(|(name, value)| {
+                    name.len()
+                        + value.data_type.len()
+                        + value.string_value.as_ref().map_or(0, String::len)
+                        // Base64 is a third bigger than the bytes it encodes.
+                        + value.binary_value.as_ref().map_or(0, |b| b.len() / 4 * 3)
+                })
+                .sum::<usize>()
+    }
+
+    /// Builds the entry that sends the message again, with its body and message attributes.
+    /// For a FIFO queue, the message keeps its message group and deduplication ID.
+    pub fn to_entry(
+        &self,
+        id: &str,
+        fifo: bool,
+    ) -> Result<SendMessageBatchRequestEntry, base64::DecodeError> {
+        let mut entry = SendMessageBatchRequestEntry::builder()
+            .id(id)
+            .message_body(&self.body);
+
+        for (name, value) in &self.message_attributes {
+            let binary_value = match &value.binary_value {
+                Some(encoded) => Some(Blob::new(base64::decode(encoded)?)),
+                None => None,
+            };
+
+            entry = entry.message_attributes(
+                name,
+                MessageAttributeValue::builder()
+                    .data_type(&value.data_type)
+                    .set_string_value(value.string_value.clone())
+                    .set_binary_value(binary_value)
+                    .build(),
+            );
+        }
+
+        if fifo {
+            entry = entry
+                .set_message_group_id(self.attributes.get(MESSAGE_GROUP_ID).cloned())
+                .set_message_deduplication_id(
+                    self.attributes.get(MESSAGE_DEDUPLICATION_ID).cloned(),
+                );
+        }
+
+        Ok(entry.build())
+    }
+}
@@ -0,0 +1,178 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0.
+ */
+
+//! Encodes typed values as message attributes, and decodes them again.
+//!
+//! Amazon SQS carries every message attribute as a data type and a string or binary value,
+//! so a number is sent as its digits and checked only when it's parsed on the other side.
+//! A struct implements `MessageAttributes` once, with these helpers, and the sender and receiver share it:
+//!
+//! ```ignore
+//! impl MessageAttributes for Job {
+//!     fn to_attributes(&self) -> HashMap<String, MessageAttributeValue> {
+//!         let mut attributes = HashMap::new();
+//!         attributes.insert("Priority".to_string(), number(self.priority));
+//!         attributes
+//!     }
+//!
+//!     fn from_attributes(attributes: &HashMap<String, MessageAttributeValue>) -> Result<Self, AttributeError> {
+//!         Ok(Job { priority: required(get_number(attributes, "Priority")?, "Priority")? })
+//!     }
+//! }
+//! ```
+
+use std::collections::HashMap;
+use std::error::Error;
+use std::fmt;
+use std::str::FromStr;
+
+use sqs::model::MessageAttributeValue;
+use sqs::Blob;
+
+/// The most message attributes that a message can have.
+pub const MAX_MESSAGE_ATTRIBUTES: usize = 10;
+
+const STRING: &str = "String";
+const NUMBER: &str = "Number";
+const BINARY: &str = "Binary";
+
+/// Why a message attribute couldn't be decoded.
+#[derive(Debug, PartialEq)]
+pub enum AttributeError {
+    /// The message doesn't have the attribute.
+    Missing(String),
+    /// The attribute has another data type. Holds the name, the expected type, and the attribute's type.
+    WrongType(String, &'static str, String),
+    /// The attribute's value can't be parsed as the type it's decoded to. Holds the name and the value.
+    Invalid(String, String),
+}
+
+impl fmt::Display for AttributeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            AttributeError::Missing(name) => write!(f, "the message has no {} attribute", name),
+            AttributeError::WrongType(name, expected, found) => write!(
+                f,
+                "the {} attribute is a {}, not a {}",
+                name, found, expected
+            ),
+            AttributeError::Invalid(name, value) => {
+                write!(f, "the {} attribute has an invalid value: {}", name, value)
+            }
+        }
+    }
+}
+
+impl Error for AttributeError {}
+
+/// A struct that's sent as the message attributes of a message.
+pub trait MessageAttributes: Sized {
+    /// Encodes the struct as message attributes.
+    fn to_attributes(&self) -> HashMap<String, MessageAttributeValue>;
+
+    /// Decodes the struct from the message attributes of a received message.
+    fn from_attributes(
+        attributes: &HashMap<String, MessageAttributeValue>,
+    ) -> Result<Self, AttributeError>;
+}
+
+/// Returns a **String** attribute.
+pub fn string(value: impl Into<String>) -> MessageAttributeValue {
+    MessageAttributeValue::builder()
+        .data_type(STRING)
+        .string_value(value)
+        .build()
+}
+
+/// Returns a **Number** attribute, which can hold any integer or decimal number that `value` displays as.
+pub fn number<N: fmt::Display>(value: N) -> MessageAttributeValue {
+    MessageAttributeValue::builder()
+        .data_type(NUMBER)
+        .string_value(value.to_string())
+        .build()
+}
+
+/// Returns a **Binary** attribute.
+pub fn binary(value: impl Into<Vec<u8>>) -> MessageAttributeValue {
+    MessageAttributeValue::builder()
+        .data_type(BINARY)
+        .binary_value(Blob::new(value))
+        .build()
+}
+
+/// Returns the attribute if it has the expected data type, or a custom type based on it,
+/// such as **Number.float** for **Number**.
+fn typed<'a>(
+    attributes: &'a HashMap<String, MessageAttributeValue>,
+    name: &str,
+    expected: &'static str,
+) -> Result<Option<&'a MessageAttributeValue>, AttributeError> {
+    let value = match attributes.get(name) {
+        Some(value) => value,
+        None => return Ok(None),
+    };
+
+    let data_type = value.data_type.as_deref().unwrap_or_default();
+    let base_type = data_type.split('.').next().unwrap_or_default();
+
+    if base_type != expected {
+        return Err(AttributeError::WrongType(
+            name.to_string(),
+            expected,
+            data_type.to_string(),
+        ));
+    }
+
+    Ok(Some(value))
+}
+
+/// Returns the value of a **String** attribute, or `None` if the message doesn't have it.
+pub fn get_string(
+    attributes: &HashMap<String, MessageAttributeValue>,
+    name: &str,
+) -> Result<Option<String>, AttributeError> {
+    Ok(
+        typed(attributes, name, STRING)?
+            .map(|value| value.string_value.clone().unwrap_or_default()),
+    )
+}
+
+/// Parses the value of a **Number** attribute, or returns `None` if the message doesn't have it.
+/// Fails if the number doesn't fit the type, such as a decimal for an integer type.
+pub fn get_number<N: FromStr>(
+    attributes: &HashMap<String, MessageAttributeValue>,
+    name: &str,
+) -> Result<Option<N>, AttributeError> {
+    match typed(attributes, name, NUMBER)? {
+        Some(value) => {
+            let digits = value.string_value.as_deref().unwrap_or_default();
+
+            digits
+                .parse()
+                .map(Some)
+                .map_err(|_| AttributeError::Invalid(name.to_string(), digits.to_string()))
+        }
+        None => Ok(None),
+    }
+}
+
+/// Returns the bytes of a **Binary** attribute, or `None` if the message doesn't have it.
+pub fn get_binary(
+    attributes: &HashMap<String, MessageAttributeValue>,
+    name: &str,
+) -> Result<Option<Vec<u8>>, AttributeError> {
+    Ok(typed(attributes, name, BINARY)?.map(|value| {
+        value
+            .binary_value
+            .as_ref()
+            .map(|blob| blob.as_ref().to_vec())
+            .unwrap_or_default()
+    }))
+}
+
+/// Turns a missing attribute into an error, for the attributes that a struct can't do without.
+pub fn required<T>(value: Option<T>, name: &str) -> Result<T, AttributeError> {
+    value.ok_or_else(|| AttributeError::Missing(name.to_string()))
+}
@@ -0,0 +1,34 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0.
+ */
+
+//! Exponential backoff with full jitter, for retrying a message later.
+//!
+//! The ceiling doubles with each attempt, up to a maximum, and the delay is a random number of seconds
+//! from zero to the ceiling, so that messages that failed together don't all come back together:
+//!
+//! ```ignore
+//! let delay = full_jitter(&mut rng, backoff_ceiling(attempt, 2, 300));
+//! client.change_message_visibility().visibility_timeout(delay as i32) // ...
+//! ```
+
+use rand::Rng;
+
+/// The longest visibility timeout that Amazon SQS accepts, 12 hours.
+pub const MAX_VISIBILITY_TIMEOUT: u32 = 43_200;
+
+/// Returns the longest delay, in seconds, before the next attempt:
+/// `base` after the first attempt, twice that after the second, and so on, but never more than `max`.
+pub fn backoff_ceiling(attempt: u32, base: u32, max: u32) -> u32 {
+    let factor = 1u32
+        .checked_shl(attempt.saturating_sub(1))
+        .unwrap_or(u32::MAX);
+
+    base.saturating_mul(factor).min(max)
+}
+
+/// Picks a delay from zero to the ceiling, inclusive.
+pub fn full_jitter<R: Rng>(rng: &mut R, ceiling: u32) -> u32 {
+    rng.gen_range(0..=ceiling)
+}
@@ -0,0 +1,259 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0.
+ */
+
+use std::error::Error;
+use std::fmt;
+use std::fs::OpenOptions;
+use std::io::{BufWriter, Write};
+
+use sqs::model::{DeleteMessageBatchRequestEntry, QueueAttributeName};
+use sqs::{Client, Config, Region};
+use sqs_code_examples::archive::{ArchivedMessage, MAX_BATCH_MESSAGES};
+
+use aws_types::region::ProvideRegion;
+
+use common::output::OutputFormat;
+use common::profile::ProfileCredentialsProvider;
+use common::shutdown::Shutdown;
+use serde::Serialize;
+use structopt::StructOpt;
+use tracing_subscriber::fmt::format::FmtSpan;
+use tracing_subscriber::fmt::SubscriberBuilder;
+
+#[derive(Debug, StructOpt)]
+struct Opt {
+    /// The region. Overrides environment variable AWS_DEFAULT_REGION.
+    #[structopt(short, long)]
+    default_region: Option<String>,
+
+    /// The URL of the queue to drain
+    #[structopt(short, long)]
+    queue: String,
+
+    /// The file in which to archive the messages, as newline-delimited JSON
+    #[structopt(short, long)]
+    file: String,
+
+    /// Whether to add the messages to the end of an existing file, rather than refusing to overwrite it
+    #[structopt(short, long)]
+    append: bool,
+
+    /// The most messages to archive. Defaults to all of them
+    #[structopt(short, long)]
+    max: Option<usize>,
+
+    /// How many seconds to wait for messages before deciding that the queue is empty, up to 20
+    #[structopt(short, long, default_value = "2")]
+    wait: i32,
+
+    /// The format of the output, text or json
+    #[structopt(long, default_value = "text")]
+    output: OutputFormat,
+
+    /// The AWS profile to use from the shared credentials file
+    #[structopt(long)]
+    profile: Option<String>,
+
+    /// Whether to display additional information
+    #[structopt(short, long)]
+    verbose: bool,
+}
+
+/// The messages that were archived.
+#[derive(Debug, Serialize)]
+struct Archived {
+    queue: String,
+    file: String,
+    messages: usize,
+    /// How many archived messages couldn't be deleted, and so will be received again.
+    delete_failures: usize,
+    interrupted: bool,
+}
+
+impl fmt::Display for Archived {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "Archived {} messages from {} in {}",
+            self.messages, self.queue, self.file
+        )?;
+
+        if self.delete_failures > 0 {
+            write!(
+                f,
+                "\n{} of them couldn't be deleted from the queue, so they will be delivered again",
+                self.delete_failures
+            )?;
+        }
+
+        if self.interrupted {
+            write!(f, "\nStopped by Ctrl-C before the queue was empty")?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Drains an Amazon SQS queue into a file of newline-delimited JSON, one message on each line.
+/// Each batch of messages is written to the file, and flushed to disk, before it's deleted from the queue,
+/// so a message is never lost, although a failure between the two steps can archive a message twice.
+/// Use `replay-queue` to send the archived messages to a queue again.
+/// # Arguments
+///
+/// * `-q QUEUE` - The URL of the queue to drain.
+/// * `-f FILE` - The file in which to archive the messages.
+/// * `[-a]` - Whether to add the messages to the end of an existing file.
+///   If not supplied, and the file exists, the example fails rather than overwriting it.
+/// * `[-m MAX]` - The most messages to archive.
+///   If not supplied, archives messages until the queue is empty.
+/// * `[-w WAIT]` - How many seconds to wait for messages before deciding that the queue is empty.
+///   If not supplied, defaults to **2**.
+/// * `[--output FORMAT]` - How to display the result, **text** (the default) or **json**.
+/// * `[-d DEFAULT-REGION]` - The region containing the queue.
+///   If not supplied, uses the value of the **AWS_DEFAULT_REGION** environment variable.
+///   If the environment variable is not set, defaults to **us-west-2**.
+/// * `[--profile PROFILE]` - The AWS profile to use from the shared credentials file.
+/// * `[-v]` - Whether to display additional information.
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn Error>> {
+    let Opt {
+        append,
+        default_region,
+        file,
+        max,
+        output,
+        profile,
+        queue,
+        verbose,
+        wait,
+    } = Opt::from_args();
+
+    let region = default_region
+        .as_ref()
+        .map(|region| Region::new(region.clone()))
+        .or_else(|| aws_types::region::default_provider().region())
+        .unwrap_or_else(|| Region::new("us-west-2"));
+
+    if !(0..=20).contains(&wait) {
+        return Err(format!("the wait must be from 0 to 20 seconds, got {}", wait).into());
+    }
+
+    if verbose {
+        println!("SQS client version: {}", sqs::PKG_VERSION);
+        println!("Region:             {:?}", &region);
+        println!("Queue:              {}", &queue);
+        println!("File:               {}", &file);
+        println!("Append:             {}", append);
+        println!("Max messages:       {:?}", max);
+        println!("Wait (seconds):     {}", wait);
+
+        SubscriberBuilder::default()
+            .with_env_filter("info")
+            .with_span_events(FmtSpan::CLOSE)
+            .init();
+    }
+
+    let mut config = Config::builder().region(region);
+    if let Some(profile) = profile {
+        config = config.credentials_provider(ProfileCredentialsProvider::new(profile));
+    }
+    let client = Client::from_conf(config.build());
+
+    let mut writer = BufWriter::new(
+        OpenOptions::new()
+            .write(true)
+            .append(append)
+            .create(append)
+            .create_new(!append)
+            .open(&file)
+            .map_err(|e| format!("couldn't open {}: {}", &file, e))?,
+    );
+
+    let mut archived = 0;
+    let mut delete_failures = 0;
+
+    // Ctrl-C stops the example between batches, so a batch is never left half archived.
+    let shutdown = Shutdown::listen();
+
+    while !shutdown.is_requested() {
+        let count = match max {
+            Some(max) if archived >= max => break,
+            Some(max) => (max - archived).min(MAX_BATCH_MESSAGES),
+            None => MAX_BATCH_MESSAGES,
+        };
+
+        let messages = client
+            .receive_message()
+            .queue_url(&queue)
+            .max_number_of_messages(count as i32)
+            .wait_time_seconds(wait)
+            .attribute_names(QueueAttributeName::All)
+            .message_attribute_names("All")
+            .send()
+            .await?
+            .messages
+            .unwrap_or_default();
+
+        if messages.is_empty() {
+            break;
+        }
+
+        let mut entries = Vec::with_capacity(messages.len());
+
+        for (i, message) in messages.into_iter().enumerate() {
+            let receipt_handle = message
+                .receipt_handle
+                .clone()
+                .ok_or("Amazon SQS returned a message without a receipt handle")?;
+
+            serde_json::to_writer(&mut writer, &ArchivedMessage::from(message))?;
+            writeln!(writer)?;
+
+            entries.push(
+                DeleteMessageBatchRequestEntry::builder()
+                    .id(i.to_string())
+                    .receipt_handle(receipt_handle)
+                    .build(),
+            );
+        }
+
+        // The messages must be on disk before they're deleted from the queue.
+        writer.flush()?;
+        writer.get_ref().sync_data()?;
+
+        let batch = entries.len();
+        let resp = client
+            .delete_message_batch()
+            .queue_url(&queue)
+            .set_entries(Some(entries))
+            .send()
+            .await?;
+
+        for failure in resp.failed.unwrap_or_default() {
+            eprintln!(
+                "Got an error deleting message {}: {}",
+                failure.id.as_deref().unwrap_or_default(),
+                failure.message.as_deref().unwrap_or_default()
+            );
+            delete_failures += 1;
+        }
+
+        archived += batch;
+
+        if verbose {
+            println!("Archived {} messages", archived);
+        }
+    }
+
+    output.print(&Archived {
+        queue,
+        file,
+        messages: archived,
+        delete_failures,
+        interrupted: shutdown.is_requested(),
+    });
+
+    Ok(())
+}
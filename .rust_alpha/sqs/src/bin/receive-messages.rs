@@ -0,0 +1,211 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0.
+ */
+
+use std::error::Error;
+use std::fmt;
+
+use sqs::{Client, Config, Region};
+use sqs_code_examples::attributes::MessageAttributes;
+use sqs_code_examples::jobs::{hex, Job};
+
+use aws_types::region::ProvideRegion;
+
+use common::output::OutputFormat;
+use common::profile::ProfileCredentialsProvider;
+use serde::Serialize;
+use structopt::StructOpt;
+use tracing_subscriber::fmt::format::FmtSpan;
+use tracing_subscriber::fmt::SubscriberBuilder;
+
+#[derive(Debug, StructOpt)]
+struct Opt {
+    /// The region. Overrides environment variable AWS_DEFAULT_REGION.
+    #[structopt(short, long)]
+    default_region: Option<String>,
+
+    /// The URL of the queue
+    #[structopt(short, long)]
+    queue: String,
+
+    /// The most messages to receive, up to 10
+    #[structopt(short, long, default_value = "10")]
+    max: i32,
+
+    /// How many seconds to wait for messages, up to 20
+    #[structopt(short, long, default_value = "20")]
+    wait: i32,
+
+    /// Whether to delete the jobs that were received
+    #[structopt(long)]
+    delete: bool,
+
+    /// The format of the output, text or json
+    #[structopt(long, default_value = "text")]
+    output: OutputFormat,
+
+    /// The AWS profile to use from the shared credentials file
+    #[structopt(long)]
+    profile: Option<String>,
+
+    /// Whether to display additional information
+    #[structopt(short, long)]
+    verbose: bool,
+}
+
+/// A message that was received, with its job, or why its attributes couldn't be decoded.
+#[derive(Debug, Serialize)]
+struct Received {
+    message_id: String,
+    body: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    job: Option<Job>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    error: Option<String>,
+}
+
+/// The messages that were received.
+#[derive(Debug, Serialize)]
+struct Messages {
+    messages: Vec<Received>,
+    deleted: usize,
+}
+
+impl fmt::Display for Messages {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Received {} messages", self.messages.len())?;
+
+        for received in &self.messages {
+            write!(f, "\n  {}: {}", received.message_id, received.body)?;
+
+            match (&received.job, &received.error) {
+                (Some(job), _) => write!(
+                    f,
+                    "\n    {} job, priority {}, submitted at {}, trace ID {}",
+                    job.kind,
+                    job.priority,
+                    job.submitted_at,
+                    job.trace_id.as_deref().map(hex).unwrap_or_default()
+                )?,
+                (None, Some(error)) => write!(f, "\n    Not a job: {}", error)?,
+                (None, None) => {}
+            }
+        }
+
+        if self.deleted > 0 {
+            write!(f, "\nDeleted {} jobs", self.deleted)?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Receives jobs that **send-message** sent, and decodes their typed message attributes.
+/// A message whose attributes aren't a job, such as one with a missing attribute or a priority
+/// that isn't a number, is reported and left on the queue, rather than failing the example.
+/// # Arguments
+///
+/// * `-q QUEUE` - The URL of the queue.
+/// * `[-m MAX]` - The most messages to receive, up to 10.
+///   If not supplied, defaults to **10**.
+/// * `[-w WAIT]` - How many seconds to wait for messages, up to 20.
+///   If not supplied, defaults to **20**.
+/// * `[--delete]` - Whether to delete the jobs that were received.
+///   If not supplied, they're received again after the queue's visibility timeout.
+/// * `[--output FORMAT]` - How to display the result, **text** (the default) or **json**.
+/// * `[-d DEFAULT-REGION]` - The region containing the queue.
+///   If not supplied, uses the value of the **AWS_DEFAULT_REGION** environment variable.
+///   If the environment variable is not set, defaults to **us-west-2**.
+/// * `[--profile PROFILE]` - The AWS profile to use from the shared credentials file.
+/// * `[-v]` - Whether to display additional information.
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn Error>> {
+    let Opt {
+        default_region,
+        delete,
+        max,
+        output,
+        profile,
+        queue,
+        verbose,
+        wait,
+    } = Opt::from_args();
+
+    let region = default_region
+        .as_ref()
+        .map(|region| Region::new(region.clone()))
+        .or_else(|| aws_types::region::default_provider().region())
+        .unwrap_or_else(|| Region::new("us-west-2"));
+
+    if !(1..=10).contains(&max) {
+        return Err(format!("the max must be from 1 to 10, got {}", max).into());
+    }
+
+    if !(0..=20).contains(&wait) {
+        return Err(format!("the wait must be from 0 to 20 seconds, got {}", wait).into());
+    }
+
+    if verbose {
+        println!("SQS client version: {}", sqs::PKG_VERSION);
+        println!("Region:             {:?}", &region);
+        println!("Queue:              {}", &queue);
+        println!("Max:                {}", max);
+        println!("Wait:               {}", wait);
+        println!("Delete:             {}", delete);
+
+        SubscriberBuilder::default()
+            .with_env_filter("info")
+            .with_span_events(FmtSpan::CLOSE)
+            .init();
+    }
+
+    let mut config = Config::builder().region(region);
+    if let Some(profile) = profile {
+        config = config.credentials_provider(ProfileCredentialsProvider::new(profile));
+    }
+    let client = Client::from_conf(config.build());
+
+    let resp = client
+        .receive_message()
+        .queue_url(&queue)
+        .max_number_of_messages(max)
+        .wait_time_seconds(wait)
+        .message_attribute_names("All")
+        .send()
+        .await?;
+
+    let mut messages = Vec::new();
+    let mut deleted = 0;
+
+    for message in resp.messages.unwrap_or_default() {
+        let attributes = message.message_attributes.unwrap_or_default();
+        let (job, error) = match Job::from_attributes(&attributes) {
+            Ok(job) => (Some(job), None),
+            Err(e) => (None, Some(e.to_string())),
+        };
+
+        if delete && job.is_some() {
+            if let Some(receipt_handle) = &message.receipt_handle {
+                client
+                    .delete_message()
+                    .queue_url(&queue)
+                    .receipt_handle(receipt_handle)
+                    .send()
+                    .await?;
+                deleted += 1;
+            }
+        }
+
+        messages.push(Received {
+            message_id: message.message_id.unwrap_or_default(),
+            body: message.body.unwrap_or_default(),
+            job,
+            error,
+        });
+    }
+
+    output.print(&Messages { messages, deleted });
+
+    Ok(())
+}
@@ -0,0 +1,262 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0.
+ */
+
+use std::error::Error;
+use std::fmt;
+use std::fs::File;
+use std::io::{BufRead, BufReader};
+
+use sqs::{Client, Config, Region};
+use sqs_code_examples::archive::{ArchivedMessage, MAX_BATCH_BYTES, MAX_BATCH_MESSAGES};
+
+use aws_types::region::ProvideRegion;
+
+use common::output::OutputFormat;
+use common::profile::ProfileCredentialsProvider;
+use common::shutdown::Shutdown;
+use serde::Serialize;
+use structopt::StructOpt;
+use tracing_subscriber::fmt::format::FmtSpan;
+use tracing_subscriber::fmt::SubscriberBuilder;
+
+#[derive(Debug, StructOpt)]
+struct Opt {
+    /// The region. Overrides environment variable AWS_DEFAULT_REGION.
+    #[structopt(short, long)]
+    default_region: Option<String>,
+
+    /// The URL of the queue to send the messages to
+    #[structopt(short, long)]
+    queue: String,
+
+    /// The archive that archive-queue created
+    #[structopt(short, long)]
+    file: String,
+
+    /// How many lines at the start of the archive to skip, to resume an interrupted replay
+    #[structopt(short, long, default_value = "0")]
+    skip: usize,
+
+    /// The format of the output, text or json
+    #[structopt(long, default_value = "text")]
+    output: OutputFormat,
+
+    /// The AWS profile to use from the shared credentials file
+    #[structopt(long)]
+    profile: Option<String>,
+
+    /// Whether to display additional information
+    #[structopt(short, long)]
+    verbose: bool,
+}
+
+/// The messages that were sent again.
+#[derive(Debug, Serialize)]
+struct Replayed {
+    queue: String,
+    file: String,
+    messages: usize,
+    /// The lines of the archive whose messages couldn't be sent.
+    failed_lines: Vec<usize>,
+    /// If the replay was interrupted, the value of `--skip` that resumes it.
+    resume_skip: Option<usize>,
+}
+
+impl fmt::Display for Replayed {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "Sent {} messages from {} to {}",
+            self.messages, self.file, self.queue
+        )?;
+
+        if !self.failed_lines.is_empty() {
+            write!(
+                f,
+                "\nThe messages on these lines couldn't be sent: {:?}",
+                self.failed_lines
+            )?;
+        }
+
+        if let Some(skip) = self.resume_skip {
+            write!(
+                f,
+                "\nStopped by Ctrl-C. To carry on where it stopped, run the example again with --skip {}",
+                skip
+            )?;
+        }
+
+        Ok(())
+    }
+}
+
+/// A message read from the archive, with its line number, which identifies it in the batch.
+type Line = (usize, ArchivedMessage);
+
+/// Sends a batch of messages, and returns the line numbers of the messages that couldn't be sent.
+async fn send_batch(
+    client: &Client,
+    queue: &str,
+    fifo: bool,
+    batch: &[Line],
+) -> Result<Vec<usize>, Box<dyn Error>> {
+    let mut entries = Vec::with_capacity(batch.len());
+    for (line, message) in batch {
+        entries.push(message.to_entry(&line.to_string(), fifo)?);
+    }
+
+    let resp = client
+        .send_message_batch()
+        .queue_url(queue)
+        .set_entries(Some(entries))
+        .send()
+        .await?;
+
+    let mut failed = Vec::new();
+
+    for failure in resp.failed.unwrap_or_default() {
+        let id = failure.id.unwrap_or_default();
+        eprintln!(
+            "Got an error sending the message on line {}: {}",
+            &id,
+            failure.message.as_deref().unwrap_or_default()
+        );
+        failed.push(id.parse()?);
+    }
+
+    Ok(failed)
+}
+
+/// Sends the messages in an archive that `archive-queue` created to an Amazon SQS queue,
+/// in batches of up to 10 messages, keeping their bodies and message attributes.
+/// The messages get new message IDs, and their system attributes, such as when they were first sent, are lost.
+/// For a FIFO queue, each message keeps its message group and deduplication ID.
+/// # Arguments
+///
+/// * `-q QUEUE` - The URL of the queue to send the messages to.
+/// * `-f FILE` - The archive.
+/// * `[-s SKIP]` - How many lines at the start of the archive to skip.
+///   If not supplied, sends every message.
+/// * `[--output FORMAT]` - How to display the result, **text** (the default) or **json**.
+/// * `[-d DEFAULT-REGION]` - The region containing the queue.
+///   If not supplied, uses the value of the **AWS_DEFAULT_REGION** environment variable.
+///   If the environment variable is not set, defaults to **us-west-2**.
+/// * `[--profile PROFILE]` - The AWS profile to use from the shared credentials file.
+/// * `[-v]` - Whether to display additional information.
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn Error>> {
+    let Opt {
+        default_region,
+        file,
+        output,
+        profile,
+        queue,
+        skip,
+        verbose,
+    } = Opt::from_args();
+
+    let region = default_region
+        .as_ref()
+        .map(|region| Region::new(region.clone()))
+        .or_else(|| aws_types::region::default_provider().region())
+        .unwrap_or_else(|| Region::new("us-west-2"));
+
+    // The names of FIFO queues end in .fifo, and only FIFO queues accept message groups.
+    let fifo = queue.ends_with(".fifo");
+
+    if verbose {
+        println!("SQS client version: {}", sqs::PKG_VERSION);
+        println!("Region:             {:?}", &region);
+        println!("Queue:              {}", &queue);
+        println!("FIFO queue:         {}", fifo);
+        println!("File:               {}", &file);
+        println!("Skip (lines):       {}", skip);
+
+        SubscriberBuilder::default()
+            .with_env_filter("info")
+            .with_span_events(FmtSpan::CLOSE)
+            .init();
+    }
+
+    let mut config = Config::builder().region(region);
+    if let Some(profile) = profile {
+        config = config.credentials_provider(ProfileCredentialsProvider::new(profile));
+    }
+    let client = Client::from_conf(config.build());
+
+    let reader =
+        BufReader::new(File::open(&file).map_err(|e| format!("couldn't open {}: {}", &file, e))?);
+
+    let mut sent = 0;
+    let mut failed_lines = Vec::new();
+    let mut batch: Vec<Line> = Vec::new();
+    let mut batch_bytes = 0;
+    let mut resume_skip = None;
+
+    // Ctrl-C stops the example between batches, so that it can say where to resume.
+    let shutdown = Shutdown::listen();
+
+    // Each line is numbered from 1, so that the number matches what an editor shows.
+    for (index, line) in reader.lines().enumerate().skip(skip) {
+        let line = line?;
+        if line.trim().is_empty() {
+            continue;
+        }
+
+        let message: ArchivedMessage = serde_json::from_str(&line)
+            .map_err(|e| format!("line {} is not an archived message: {}", index + 1, e))?;
+        let size = message.size();
+
+        if size > MAX_BATCH_BYTES {
+            return Err(format!(
+                "the message on line {} is {} bytes, more than Amazon SQS accepts",
+                index + 1,
+                size
+            )
+            .into());
+        }
+
+        if batch.len() == MAX_BATCH_MESSAGES || batch_bytes + size > MAX_BATCH_BYTES {
+            if shutdown.is_requested() {
+                resume_skip = Some(batch[0].0 - 1);
+                batch.clear();
+                break;
+            }
+
+            failed_lines.extend(send_batch(&client, &queue, fifo, &batch).await?);
+            sent += batch.len();
+            batch.clear();
+            batch_bytes = 0;
+
+            if verbose {
+                println!("Sent {} messages", sent);
+            }
+        }
+
+        batch.push((index + 1, message));
+        batch_bytes += size;
+    }
+
+    if !batch.is_empty() {
+        if shutdown.is_requested() {
+            resume_skip = Some(batch[0].0 - 1);
+        } else {
+            failed_lines.extend(send_batch(&client, &queue, fifo, &batch).await?);
+            sent += batch.len();
+        }
+    }
+
+    sent -= failed_lines.len();
+
+    output.print(&Replayed {
+        queue,
+        file,
+        messages: sent,
+        failed_lines,
+        resume_skip,
+    });
+
+    Ok(())
+}
@@ -0,0 +1,383 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0.
+ */
+
+use std::collections::HashMap;
+use std::error::Error;
+use std::fmt;
+
+use sqs::model::{Message, MessageAttributeValue, MessageSystemAttributeName, QueueAttributeName};
+use sqs::{Client, Config, Region};
+use sqs_code_examples::attributes::{
+    get_number, get_string, number, required, string, AttributeError, MessageAttributes,
+    MAX_MESSAGE_ATTRIBUTES,
+};
+use sqs_code_examples::backoff::{backoff_ceiling, full_jitter, MAX_VISIBILITY_TIMEOUT};
+
+use aws_types::region::ProvideRegion;
+
+use common::output::OutputFormat;
+use common::profile::ProfileCredentialsProvider;
+use common::shutdown::Shutdown;
+use rand::Rng;
+use serde::Serialize;
+use structopt::StructOpt;
+use tracing_subscriber::fmt::format::FmtSpan;
+use tracing_subscriber::fmt::SubscriberBuilder;
+
+/// The message attribute that records, on the copy in the dead-letter queue, how many attempts failed.
+const ATTEMPT_COUNT_ATTRIBUTE: &str = "AttemptCount";
+
+/// The message attribute that records, on the copy in the dead-letter queue, why the last attempt failed.
+const LAST_ERROR_ATTRIBUTE: &str = "LastError";
+
+/// The most messages that ReceiveMessage returns in one call.
+const MAX_RECEIVE_MESSAGES: i32 = 10;
+
+/// How long to wait for messages in each ReceiveMessage call. Long polling saves empty receives.
+const WAIT_SECONDS: i32 = 20;
+
+#[derive(Debug, StructOpt)]
+struct Opt {
+    /// The region. Overrides environment variable AWS_DEFAULT_REGION.
+    #[structopt(short, long)]
+    default_region: Option<String>,
+
+    /// The URL of the queue to consume
+    #[structopt(short, long)]
+    queue: String,
+
+    /// The URL of the dead-letter queue for messages that fail every attempt
+    #[structopt(long)]
+    dlq: String,
+
+    /// How many attempts a message gets before it's moved to the dead-letter queue
+    #[structopt(short, long, default_value = "5")]
+    max_attempts: u32,
+
+    /// The longest delay, in seconds, after the first failed attempt. It doubles after each attempt
+    #[structopt(short, long, default_value = "2")]
+    base_delay: u32,
+
+    /// The longest delay, in seconds, after any failed attempt
+    #[structopt(long, default_value = "300")]
+    max_delay: u32,
+
+    /// The chance, from 0 to 1, that processing a message fails, to show the retries
+    #[structopt(short, long, default_value = "0.5")]
+    fail_rate: f64,
+
+    /// The format of the output, text or json
+    #[structopt(long, default_value = "text")]
+    output: OutputFormat,
+
+    /// The AWS profile to use from the shared credentials file
+    #[structopt(long)]
+    profile: Option<String>,
+
+    /// Whether to display additional information
+    #[structopt(short, long)]
+    verbose: bool,
+}
+
+/// What happened to the messages that were received.
+#[derive(Debug, Default, Serialize)]
+struct Consumed {
+    processed: usize,
+    retried: usize,
+    dead_lettered: usize,
+}
+
+impl fmt::Display for Consumed {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "Processed {} messages, scheduled {} retries, and moved {} messages to the dead-letter queue",
+            self.processed, self.retried, self.dead_lettered
+        )
+    }
+}
+
+/// Why a message was moved to the dead-letter queue, recorded as message attributes on the copy there.
+struct DeadLetter {
+    attempt_count: u32,
+    last_error: String,
+}
+
+impl MessageAttributes for DeadLetter {
+    fn to_attributes(&self) -> HashMap<String, MessageAttributeValue> {
+        let mut attributes = HashMap::new();
+
+        attributes.insert(
+            ATTEMPT_COUNT_ATTRIBUTE.to_string(),
+            number(self.attempt_count),
+        );
+        attributes.insert(LAST_ERROR_ATTRIBUTE.to_string(), string(&self.last_error));
+
+        attributes
+    }
+
+    fn from_attributes(
+        attributes: &HashMap<String, MessageAttributeValue>,
+    ) -> Result<Self, AttributeError> {
+        Ok(DeadLetter {
+            attempt_count: required(
+                get_number(attributes, ATTEMPT_COUNT_ATTRIBUTE)?,
+                ATTEMPT_COUNT_ATTRIBUTE,
+            )?,
+            last_error: required(
+                get_string(attributes, LAST_ERROR_ATTRIBUTE)?,
+                LAST_ERROR_ATTRIBUTE,
+            )?,
+        })
+    }
+}
+
+/// Processes a message. This one stands in for real work, and fails at random.
+fn process(message: &Message, fail_rate: f64) -> Result<(), String> {
+    if rand::thread_rng().gen_bool(fail_rate) {
+        return Err(format!(
+            "simulated failure processing {}",
+            message.message_id.as_deref().unwrap_or_default()
+        ));
+    }
+
+    Ok(())
+}
+
+/// Returns which attempt this is: 1 the first time the message is received, 2 the second, and so on.
+fn attempt(message: &Message) -> u32 {
+    message
+        .attributes
+        .as_ref()
+        .and_then(|attributes| attributes.get(&MessageSystemAttributeName::ApproximateReceiveCount))
+        .and_then(|count| count.parse().ok())
+        .unwrap_or(1)
+}
+
+/// Sends a copy of the message to the dead-letter queue, with its message attributes
+/// and, if there's room for them, the attempt count and the last error.
+/// The names of FIFO queues end in .fifo, and a message sent to one needs a message group.
+async fn dead_letter(
+    client: &Client,
+    dlq: &str,
+    message: &Message,
+    attempts: u32,
+    error: &str,
+) -> Result<(), sqs::Error> {
+    let mut attributes = message.message_attributes.clone().unwrap_or_default();
+    let dead_letter = DeadLetter {
+        attempt_count: attempts,
+        last_error: error.to_string(),
+    }
+    .to_attributes();
+
+    if attributes.len() + dead_letter.len() <= MAX_MESSAGE_ATTRIBUTES {
+        attributes.extend(dead_letter);
+    }
+
+    let mut send = client
+        .send_message()
+        .queue_url(dlq)
+        .message_body(message.body.as_deref().unwrap_or_default())
+        .set_message_attributes(Some(attributes));
+
+    if dlq.ends_with(".fifo") {
+        let group = message
+            .attributes
+            .as_ref()
+            .and_then(|attributes| attributes.get(&MessageSystemAttributeName::MessageGroupId))
+            .cloned()
+            .unwrap_or_else(|| "dead-letters".to_string());
+
+        send = send
+            .message_group_id(group)
+            .set_message_deduplication_id(message.message_id.clone());
+    }
+
+    send.send().await?;
+
+    Ok(())
+}
+
+/// Consumes an Amazon SQS queue, retrying each message that fails with exponential backoff and full jitter,
+/// and moving a message that fails every attempt to a dead-letter queue.
+/// A failed message isn't deleted. Instead, its visibility timeout is changed to a random delay,
+/// up to a ceiling that doubles with each attempt, so it's received again after the delay,
+/// and messages that failed together don't come back together.
+/// The attempt count is the message's **ApproximateReceiveCount**, because a received message's attributes
+/// can't be changed; it's recorded as the **AttemptCount** message attribute on the copy in the dead-letter queue,
+/// with the last error as **LastError**. The copy is sent before the message is deleted, so a failure
+/// between the two steps can dead-letter a message twice, but never loses it.
+/// Runs until you press Ctrl-C.
+/// # Arguments
+///
+/// * `-q QUEUE` - The URL of the queue to consume.
+/// * `--dlq DLQ` - The URL of the dead-letter queue.
+/// * `[-m MAX-ATTEMPTS]` - How many attempts a message gets before it's moved to the dead-letter queue.
+///   If not supplied, defaults to **5**.
+/// * `[-b BASE-DELAY]` - The longest delay, in seconds, after the first failed attempt.
+///   If not supplied, defaults to **2**.
+/// * `[--max-delay MAX-DELAY]` - The longest delay, in seconds, after any failed attempt.
+///   If not supplied, defaults to **300**.
+/// * `[-f FAIL-RATE]` - The chance, from 0 to 1, that processing a message fails.
+///   If not supplied, defaults to **0.5**.
+/// * `[--output FORMAT]` - How to display the result, **text** (the default) or **json**.
+/// * `[-d DEFAULT-REGION]` - The region containing the queues.
+///   If not supplied, uses the value of the **AWS_DEFAULT_REGION** environment variable.
+///   If the environment variable is not set, defaults to **us-west-2**.
+/// * `[--profile PROFILE]` - The AWS profile to use from the shared credentials file.
+/// * `[-v]` - Whether to display additional information.
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn Error>> {
+    let Opt {
+        base_delay,
+        default_region,
+        dlq,
+        fail_rate,
+        max_attempts,
+        max_delay,
+        output,
+        profile,
+        queue,
+        verbose,
+    } = Opt::from_args();
+
+    let region = default_region
+        .as_ref()
+        .map(|region| Region::new(region.clone()))
+        .or_else(|| aws_types::region::default_provider().region())
+        .unwrap_or_else(|| Region::new("us-west-2"));
+
+    if max_attempts == 0 {
+        return Err("the max attempts must be at least 1".into());
+    }
+
+    if !(0.0..=1.0).contains(&fail_rate) {
+        return Err(format!("the fail rate must be from 0 to 1, got {}", fail_rate).into());
+    }
+
+    if max_delay > MAX_VISIBILITY_TIMEOUT {
+        return Err(format!(
+            "the max delay can't be more than {} seconds, the longest visibility timeout",
+            MAX_VISIBILITY_TIMEOUT
+        )
+        .into());
+    }
+
+    if verbose {
+        println!("SQS client version: {}", sqs::PKG_VERSION);
+        println!("Region:             {:?}", &region);
+        println!("Queue:              {}", &queue);
+        println!("Dead-letter queue:  {}", &dlq);
+        println!("Max attempts:       {}", max_attempts);
+        println!("Base delay:         {}", base_delay);
+        println!("Max delay:          {}", max_delay);
+        println!("Fail rate:          {}", fail_rate);
+
+        SubscriberBuilder::default()
+            .with_env_filter("info")
+            .with_span_events(FmtSpan::CLOSE)
+            .init();
+    }
+
+    let mut config = Config::builder().region(region);
+    if let Some(profile) = profile {
+        config = config.credentials_provider(ProfileCredentialsProvider::new(profile));
+    }
+    let client = Client::from_conf(config.build());
+
+    let mut consumed = Consumed::default();
+
+    println!("Consuming {}. Press Ctrl-C to stop", &queue);
+
+    // Ctrl-C stops the example between batches, so every message it received is settled one way or another.
+    let shutdown = Shutdown::listen();
+
+    while !shutdown.is_requested() {
+        let messages = match shutdown
+            .run(async {
+                client
+                    .receive_message()
+                    .queue_url(&queue)
+                    .max_number_of_messages(MAX_RECEIVE_MESSAGES)
+                    .wait_time_seconds(WAIT_SECONDS)
+                    .attribute_names(QueueAttributeName::All)
+                    .message_attribute_names("All")
+                    .send()
+                    .await
+                    .map_err(Box::<dyn Error>::from)
+            })
+            .await
+        {
+            Ok(resp) => resp.messages.unwrap_or_default(),
+            Err(_) if shutdown.is_requested() => break,
+            Err(e) => return Err(e),
+        };
+
+        for message in messages {
+            let receipt_handle = message
+                .receipt_handle
+                .as_deref()
+                .ok_or("Amazon SQS returned a message without a receipt handle")?;
+            let message_id = message.message_id.as_deref().unwrap_or_default();
+            let attempt = attempt(&message);
+
+            match process(&message, fail_rate) {
+                Ok(()) => {
+                    client
+                        .delete_message()
+                        .queue_url(&queue)
+                        .receipt_handle(receipt_handle)
+                        .send()
+                        .await?;
+
+                    println!("{} processed on attempt {}", message_id, attempt);
+                    consumed.processed += 1;
+                }
+                Err(error) if attempt >= max_attempts => {
+                    dead_letter(&client, &dlq, &message, attempt, &error).await?;
+
+                    client
+                        .delete_message()
+                        .queue_url(&queue)
+                        .receipt_handle(receipt_handle)
+                        .send()
+                        .await?;
+
+                    println!(
+                        "{} failed attempt {} of {}: {}; moved to the dead-letter queue",
+                        message_id, attempt, max_attempts, error
+                    );
+                    consumed.dead_lettered += 1;
+                }
+                Err(error) => {
+                    let delay = full_jitter(
+                        &mut rand::thread_rng(),
+                        backoff_ceiling(attempt, base_delay, max_delay),
+                    );
+
+                    // The message comes back when its visibility timeout runs out, counting from now.
+                    client
+                        .change_message_visibility()
+                        .queue_url(&queue)
+                        .receipt_handle(receipt_handle)
+                        .visibility_timeout(delay as i32)
+                        .send()
+                        .await?;
+
+                    println!(
+                        "{} failed attempt {} of {}: {}; retrying in {} seconds",
+                        message_id, attempt, max_attempts, error, delay
+                    );
+                    consumed.retried += 1;
+                }
+            }
+        }
+    }
+
+    output.print(&consumed);
+
+    Ok(())
+}
@@ -0,0 +1,172 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0.
+ */
+
+use std::error::Error;
+use std::fmt;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use sqs::{Client, Config, Region};
+use sqs_code_examples::attributes::MessageAttributes;
+use sqs_code_examples::jobs::{hex, Job};
+
+use aws_types::region::ProvideRegion;
+
+use common::output::OutputFormat;
+use common::profile::ProfileCredentialsProvider;
+use rand::Rng;
+use serde::Serialize;
+use structopt::StructOpt;
+use tracing_subscriber::fmt::format::FmtSpan;
+use tracing_subscriber::fmt::SubscriberBuilder;
+
+/// The highest priority a job can have.
+const MAX_PRIORITY: u8 = 9;
+
+#[derive(Debug, StructOpt)]
+struct Opt {
+    /// The region. Overrides environment variable AWS_DEFAULT_REGION.
+    #[structopt(short, long)]
+    default_region: Option<String>,
+
+    /// The URL of the queue
+    #[structopt(short, long)]
+    queue: String,
+
+    /// The body of the message, which is the job's payload
+    #[structopt(short, long)]
+    body: String,
+
+    /// What sort of job it is, such as resize-image
+    #[structopt(short, long)]
+    kind: String,
+
+    /// The priority of the job, from 0, the lowest, to 9
+    #[structopt(short, long, default_value = "5")]
+    priority: u8,
+
+    /// The format of the output, text or json
+    #[structopt(long, default_value = "text")]
+    output: OutputFormat,
+
+    /// The AWS profile to use from the shared credentials file
+    #[structopt(long)]
+    profile: Option<String>,
+
+    /// Whether to display additional information
+    #[structopt(short, long)]
+    verbose: bool,
+}
+
+/// The message that was sent.
+#[derive(Debug, Serialize)]
+struct Sent {
+    message_id: String,
+    job: Job,
+}
+
+impl fmt::Display for Sent {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "Sent message {}, a {} job with priority {} and trace ID {}",
+            self.message_id,
+            self.job.kind,
+            self.job.priority,
+            self.job.trace_id.as_deref().map(hex).unwrap_or_default()
+        )
+    }
+}
+
+/// Sends a job to a queue, described by typed message attributes: the kind of job as a **String**,
+/// its priority and when it was submitted as **Number**s, and a random trace ID as **Binary**.
+/// **receive-messages** decodes the attributes into the same struct.
+/// The names of FIFO queues end in .fifo; a job sent to one is in the message group of its kind.
+/// # Arguments
+///
+/// * `-q QUEUE` - The URL of the queue.
+/// * `-b BODY` - The body of the message, which is the job's payload.
+/// * `-k KIND` - What sort of job it is, such as **resize-image**.
+/// * `[-p PRIORITY]` - The priority of the job, from 0, the lowest, to 9.
+///   If not supplied, defaults to **5**.
+/// * `[--output FORMAT]` - How to display the result, **text** (the default) or **json**.
+/// * `[-d DEFAULT-REGION]` - The region containing the queue.
+///   If not supplied, uses the value of the **AWS_DEFAULT_REGION** environment variable.
+///   If the environment variable is not set, defaults to **us-west-2**.
+/// * `[--profile PROFILE]` - The AWS profile to use from the shared credentials file.
+/// * `[-v]` - Whether to display additional information.
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn Error>> {
+    let Opt {
+        body,
+        default_region,
+        kind,
+        output,
+        priority,
+        profile,
+        queue,
+        verbose,
+    } = Opt::from_args();
+
+    let region = default_region
+        .as_ref()
+        .map(|region| Region::new(region.clone()))
+        .or_else(|| aws_types::region::default_provider().region())
+        .unwrap_or_else(|| Region::new("us-west-2"));
+
+    if priority > MAX_PRIORITY {
+        return Err(format!(
+            "the priority must be from 0 to {}, got {}",
+            MAX_PRIORITY, priority
+        )
+        .into());
+    }
+
+    if verbose {
+        println!("SQS client version: {}", sqs::PKG_VERSION);
+        println!("Region:             {:?}", &region);
+        println!("Queue:              {}", &queue);
+        println!("Kind:               {}", &kind);
+        println!("Priority:           {}", priority);
+
+        SubscriberBuilder::default()
+            .with_env_filter("info")
+            .with_span_events(FmtSpan::CLOSE)
+            .init();
+    }
+
+    let mut config = Config::builder().region(region);
+    if let Some(profile) = profile {
+        config = config.credentials_provider(ProfileCredentialsProvider::new(profile));
+    }
+    let client = Client::from_conf(config.build());
+
+    let job = Job {
+        kind,
+        priority,
+        submitted_at: SystemTime::now().duration_since(UNIX_EPOCH)?.as_secs(),
+        trace_id: Some(rand::thread_rng().gen::<[u8; 16]>().to_vec()),
+    };
+
+    let mut send = client
+        .send_message()
+        .queue_url(&queue)
+        .message_body(body)
+        .set_message_attributes(Some(job.to_attributes()));
+
+    if queue.ends_with(".fifo") {
+        send = send
+            .message_group_id(&job.kind)
+            .set_message_deduplication_id(job.trace_id.as_deref().map(hex));
+    }
+
+    let resp = send.send().await?;
+
+    output.print(&Sent {
+        message_id: resp.message_id.unwrap_or_default(),
+        job,
+    });
+
+    Ok(())
+}
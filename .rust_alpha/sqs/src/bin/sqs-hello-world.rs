@@ -3,22 +3,57 @@
  * SPDX-License-Identifier: Apache-2.0.
  */
 
-use std::process::exit;
+use std::error::Error;
+
+use sqs::{Client, Config, Region};
+
+use aws_types::region::ProvideRegion;
+
+use common::profile::ProfileCredentialsProvider;
+use structopt::StructOpt;
+
+#[derive(Debug, StructOpt)]
+struct Opt {
+    /// The region. Overrides environment variable AWS_DEFAULT_REGION.
+    #[structopt(short, long)]
+    default_region: Option<String>,
+
+    /// The AWS profile to use from the shared credentials file
+    #[structopt(long)]
+    profile: Option<String>,
+}
 
 /// Sends a message to and receives the message from a queue.
+/// # Arguments
+///
+/// * `[-d DEFAULT-REGION]` - The region in which the client is created.
+///    If not supplied, uses the value of the **AWS_DEFAULT_REGION** environment variable.
+///    If the environment variable is not set, defaults to **us-west-2**.
+/// * `[--profile PROFILE]` - The AWS profile to use from the shared credentials file.
 #[tokio::main]
-async fn main() -> Result<(), sqs::Error> {
+async fn main() -> Result<(), Box<dyn Error>> {
     tracing_subscriber::fmt::init();
-    let client = sqs::Client::from_env();
+    let Opt {
+        default_region,
+        profile,
+    } = Opt::from_args();
+
+    let region = default_region
+        .as_ref()
+        .map(|region| Region::new(region.clone()))
+        .or_else(|| aws_types::region::default_provider().region())
+        .unwrap_or_else(|| Region::new("us-west-2"));
+
+    let mut config = Config::builder().region(region);
+    if let Some(profile) = profile {
+        config = config.credentials_provider(ProfileCredentialsProvider::new(profile));
+    }
+    let client = Client::from_conf(config.build());
     let queues = client.list_queues().send().await?;
     let mut queue_urls = queues.queue_urls.unwrap_or_default();
-    let queue_url = match queue_urls.pop() {
-        Some(url) => url,
-        None => {
-            eprintln!("No queues in this account. Please create a queue to proceed");
-            exit(1);
-        }
-    };
+    let queue_url = queue_urls
+        .pop()
+        .ok_or("No queues in this account. Please create a queue to proceed")?;
 
     println!(
         "Sending and receiving messages on with URL: `{}`",
@@ -0,0 +1,80 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0.
+ */
+
+//! The message attributes that `send-message` adds to a job, and that `receive-messages` reads.
+//!
+//! The body of the message is the job's payload; the attributes describe the job,
+//! so a consumer can route or skip it without parsing the body.
+
+use std::collections::HashMap;
+
+use serde::{Serialize, Serializer};
+use sqs::model::MessageAttributeValue;
+
+use crate::attributes::{
+    binary, get_binary, get_number, get_string, number, required, string, AttributeError,
+    MessageAttributes,
+};
+
+const KIND: &str = "JobKind";
+const PRIORITY: &str = "Priority";
+const SUBMITTED_AT: &str = "SubmittedAt";
+const TRACE_ID: &str = "TraceId";
+
+/// Describes a job that's sent as a message.
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct Job {
+    /// What sort of job it is, such as **resize-image**. A **String** attribute.
+    pub kind: String,
+    /// From 0, the lowest, to 9. A **Number** attribute.
+    pub priority: u8,
+    /// When the job was submitted, in seconds since the Unix epoch. A **Number** attribute.
+    pub submitted_at: u64,
+    /// The ID that ties together the logs of the services that handle the job. A **Binary** attribute.
+    /// Serialized as hexadecimal.
+    #[serde(
+        skip_serializing_if = "Option::is_none",
+        serialize_with = "serialize_hex"
+    )]
+    pub trace_id: Option<Vec<u8>>,
+}
+
+/// Formats bytes as lowercase hexadecimal, the way trace IDs are usually written.
+pub fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|byte| format!("{:02x}", byte)).collect()
+}
+
+fn serialize_hex<S: Serializer>(bytes: &Option<Vec<u8>>, serializer: S) -> Result<S::Ok, S::Error> {
+    match bytes {
+        Some(bytes) => serializer.serialize_str(&hex(bytes)),
+        None => serializer.serialize_none(),
+    }
+}
+
+impl MessageAttributes for Job {
+    fn to_attributes(&self) -> HashMap<String, MessageAttributeValue> {
+        let mut attributes = HashMap::new();
+
+        attributes.insert(KIND.to_string(), string(&self.kind));
+        attributes.insert(PRIORITY.to_string(), number(self.priority));
+        attributes.insert(SUBMITTED_AT.to_string(), number(self.submitted_at));
+        if let Some(trace_id) = &self.trace_id {
+            attributes.insert(TRACE_ID.to_string(), binary(trace_id.clone()));
+        }
+
+        attributes
+    }
+
+    fn from_attributes(
+        attributes: &HashMap<String, MessageAttributeValue>,
+    ) -> Result<Self, AttributeError> {
+        Ok(Job {
+            kind: required(get_string(attributes, KIND)?, KIND)?,
+            priority: required(get_number(attributes, PRIORITY)?, PRIORITY)?,
+            submitted_at: required(get_number(attributes, SUBMITTED_AT)?, SUBMITTED_AT)?,
+            trace_id: get_binary(attributes, TRACE_ID)?,
+        })
+    }
+}
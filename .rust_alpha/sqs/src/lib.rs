@@ -0,0 +1,11 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0.
+ */
+
+//! Helpers shared by the Amazon SQS code examples.
+
+pub mod archive;
+pub mod attributes;
+pub mod backoff;
+pub mod jobs;
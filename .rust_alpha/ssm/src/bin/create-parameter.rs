@@ -3,13 +3,12 @@
  * SPDX-License-Identifier: Apache-2.0.
  */
 
-use std::process;
-
 use ssm::model::ParameterType;
-use ssm::{Client, Config, Region};
+use ssm::{Client, Config, Error, Region};
 
 use aws_types::region::{EnvironmentProvider, ProvideRegion};
 
+use common::profile::ProfileCredentialsProvider;
 use structopt::StructOpt;
 
 #[derive(Debug, StructOpt)]
@@ -30,6 +29,10 @@ struct Opt {
     #[structopt(short, long)]
     description: String,
 
+    /// The AWS profile to use from the shared credentials file
+    #[structopt(long)]
+    profile: Option<String>,
+
     /// Whether to display additional information
     #[structopt(short, long)]
     verbose: bool,
@@ -44,13 +47,15 @@ struct Opt {
 /// * `[-d DEFAULT-REGION]` - The region in which the client is created.
 ///    If not supplied, uses the value of the **AWS_DEFAULT_REGION** environment variable.
 ///    If the environment variable is not set, defaults to **us-west-2**.
+/// * `[--profile PROFILE]` - The AWS profile to use from the shared credentials file.
 /// * `[-v]` - Whether to display additional information.
 #[tokio::main]
-async fn main() {
+async fn main() -> Result<(), Error> {
     let Opt {
         name,
         parameter_value,
         description,
+        profile,
         region,
         verbose,
     } = Opt::from_args();
@@ -70,10 +75,13 @@ async fn main() {
         tracing_subscriber::fmt::init();
     }
 
-    let config = Config::builder().region(region).build();
-    let client = Client::from_conf(config);
+    let mut config = Config::builder().region(region);
+    if let Some(profile) = profile {
+        config = config.credentials_provider(ProfileCredentialsProvider::new(profile));
+    }
+    let client = Client::from_conf(config.build());
 
-    match client
+    let resp = client
         .put_parameter()
         .overwrite(true)
         .r#type(ParameterType::String)
@@ -81,14 +89,9 @@ async fn main() {
         .value(parameter_value)
         .description(description)
         .send()
-        .await
-    {
-        Ok(response) => {
-            println!("Success! Parameter now has version: {}", response.version)
-        }
-        Err(error) => {
-            println!("Got an error putting the parameter: {}", error);
-            process::exit(1);
-        }
-    }
+        .await?;
+
+    println!("Success! Parameter now has version: {}", resp.version);
+
+    Ok(())
 }
@@ -3,12 +3,11 @@
  * SPDX-License-Identifier: Apache-2.0.
  */
 
-use std::process;
-
-use ssm::{Client, Config, Region};
+use ssm::{Client, Config, Error, Region};
 
 use aws_types::region::{EnvironmentProvider, ProvideRegion};
 
+use common::profile::ProfileCredentialsProvider;
 use structopt::StructOpt;
 
 #[derive(Debug, StructOpt)]
@@ -17,6 +16,10 @@ struct Opt {
     #[structopt(short, long)]
     region: Option<String>,
 
+    /// The AWS profile to use from the shared credentials file
+    #[structopt(long)]
+    profile: Option<String>,
+
     /// Whether to display additional information
     #[structopt(short, long)]
     verbose: bool,
@@ -28,10 +31,15 @@ struct Opt {
 /// * `[-d DEFAULT-REGION]` - The region in which the client is created.
 ///    If not supplied, uses the value of the **AWS_DEFAULT_REGION** environment variable.
 ///    If the environment variable is not set, defaults to **us-west-2**.
+/// * `[--profile PROFILE]` - The AWS profile to use from the shared credentials file.
 /// * `[-v]` - Whether to display additional information.
 #[tokio::main]
-async fn main() {
-    let Opt { region, verbose } = Opt::from_args();
+async fn main() -> Result<(), Error> {
+    let Opt {
+        profile,
+        region,
+        verbose,
+    } = Opt::from_args();
 
     let region = EnvironmentProvider::new()
         .region()
@@ -45,27 +53,26 @@ async fn main() {
         tracing_subscriber::fmt::init();
     }
 
-    let config = Config::builder().region(region).build();
-    let client = Client::from_conf(config);
+    let mut config = Config::builder().region(region);
+    if let Some(profile) = profile {
+        config = config.credentials_provider(ProfileCredentialsProvider::new(profile));
+    }
+    let client = Client::from_conf(config.build());
 
     println!("Parameter names:");
 
-    match client.describe_parameters().send().await {
-        Ok(response) => {
-            for param in response.parameters.unwrap().iter() {
-                match &param.name {
-                    None => {}
-                    Some(n) => {
-                        println!("  {}", n);
-                    }
-                }
+    let resp = client.describe_parameters().send().await?;
+
+    for param in resp.parameters.unwrap_or_default().iter() {
+        match &param.name {
+            None => {}
+            Some(n) => {
+                println!("  {}", n);
             }
         }
-        Err(error) => {
-            println!("Got an error listing the parameter names: {}", error);
-            process::exit(1);
-        }
     }
 
     println!();
+
+    Ok(())
 }
@@ -0,0 +1,103 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0.
+ */
+
+use ssm::{Client, Config, Error, Region};
+
+use aws_types::region::{EnvironmentProvider, ProvideRegion};
+
+use common::profile::ProfileCredentialsProvider;
+use structopt::StructOpt;
+
+#[derive(Debug, StructOpt)]
+struct Opt {
+    /// The region
+    #[structopt(short, long)]
+    region: Option<String>,
+
+    /// The parameter name
+    #[structopt(short, long)]
+    name: String,
+
+    /// Whether to decrypt the value of a SecureString parameter
+    #[structopt(short, long)]
+    decrypt: bool,
+
+    /// The AWS profile to use from the shared credentials file
+    #[structopt(long)]
+    profile: Option<String>,
+
+    /// Whether to display additional information
+    #[structopt(short, long)]
+    verbose: bool,
+}
+
+/// Displays the value of an AWS Systems Manager parameter.
+/// # Arguments
+///
+/// * `-n NAME` - The name of the parameter.
+/// * `[-d]` - Whether to decrypt the value of a SecureString parameter.
+///   If not supplied, displays the encrypted value.
+///   Decrypting requires the **kms:Decrypt** permission for the parameter's key.
+/// * `[-r REGION]` - The region in which the client is created.
+///    If not supplied, uses the value of the **AWS_DEFAULT_REGION** environment variable.
+///    If the environment variable is not set, defaults to **us-west-2**.
+/// * `[--profile PROFILE]` - The AWS profile to use from the shared credentials file.
+/// * `[-v]` - Whether to display additional information.
+#[tokio::main]
+async fn main() -> Result<(), Error> {
+    let Opt {
+        decrypt,
+        name,
+        profile,
+        region,
+        verbose,
+    } = Opt::from_args();
+
+    let region = EnvironmentProvider::new()
+        .region()
+        .or_else(|| region.as_ref().map(|region| Region::new(region.clone())))
+        .unwrap_or_else(|| Region::new("us-west-2"));
+
+    if verbose {
+        println!("SSM client version:   {}", ssm::PKG_VERSION);
+        println!("Region:               {:?}", &region);
+        println!("Parameter name:       {}", name);
+        println!("Decrypt:              {}", decrypt);
+
+        tracing_subscriber::fmt::init();
+    }
+
+    let mut config = Config::builder().region(region);
+    if let Some(profile) = profile {
+        config = config.credentials_provider(ProfileCredentialsProvider::new(profile));
+    }
+    let client = Client::from_conf(config.build());
+
+    let resp = client
+        .get_parameter()
+        .name(name)
+        .with_decryption(decrypt)
+        .send()
+        .await?;
+
+    if let Some(parameter) = resp.parameter {
+        println!("Name:    {}", parameter.name.as_deref().unwrap_or_default());
+        println!(
+            "Type:    {}",
+            parameter
+                .r#type
+                .as_ref()
+                .map(|t| t.as_str())
+                .unwrap_or_default()
+        );
+        println!("Version: {}", parameter.version);
+        println!(
+            "Value:   {}",
+            parameter.value.as_deref().unwrap_or_default()
+        );
+    }
+
+    Ok(())
+}
@@ -0,0 +1,118 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0.
+ */
+
+use ssm::{Client, Config, Error, Region};
+
+use aws_types::region::{EnvironmentProvider, ProvideRegion};
+
+use common::profile::ProfileCredentialsProvider;
+use structopt::StructOpt;
+
+#[derive(Debug, StructOpt)]
+struct Opt {
+    /// The region
+    #[structopt(short, long)]
+    region: Option<String>,
+
+    /// The path, such as /myapp/dev
+    #[structopt(short, long)]
+    path: String,
+
+    /// Whether to include the parameters below the path's children
+    #[structopt(long)]
+    recursive: bool,
+
+    /// Whether to decrypt the values of SecureString parameters
+    #[structopt(short, long)]
+    decrypt: bool,
+
+    /// The AWS profile to use from the shared credentials file
+    #[structopt(long)]
+    profile: Option<String>,
+
+    /// Whether to display additional information
+    #[structopt(short, long)]
+    verbose: bool,
+}
+
+/// Displays the AWS Systems Manager parameters under a path, such as the configuration of one application.
+/// # Arguments
+///
+/// * `-p PATH` - The path, such as **/myapp/dev**.
+/// * `[--recursive]` - Whether to include all of the parameters under the path.
+///   If not supplied, includes only the parameters one level below the path, such as **/myapp/dev/db-host**.
+/// * `[-d]` - Whether to decrypt the values of SecureString parameters.
+/// * `[-r REGION]` - The region in which the client is created.
+///    If not supplied, uses the value of the **AWS_DEFAULT_REGION** environment variable.
+///    If the environment variable is not set, defaults to **us-west-2**.
+/// * `[--profile PROFILE]` - The AWS profile to use from the shared credentials file.
+/// * `[-v]` - Whether to display additional information.
+#[tokio::main]
+async fn main() -> Result<(), Error> {
+    let Opt {
+        decrypt,
+        path,
+        profile,
+        recursive,
+        region,
+        verbose,
+    } = Opt::from_args();
+
+    let region = EnvironmentProvider::new()
+        .region()
+        .or_else(|| region.as_ref().map(|region| Region::new(region.clone())))
+        .unwrap_or_else(|| Region::new("us-west-2"));
+
+    if verbose {
+        println!("SSM client version:   {}", ssm::PKG_VERSION);
+        println!("Region:               {:?}", &region);
+        println!("Path:                 {}", path);
+        println!("Recursive:            {}", recursive);
+        println!("Decrypt:              {}", decrypt);
+
+        tracing_subscriber::fmt::init();
+    }
+
+    let mut config = Config::builder().region(region);
+    if let Some(profile) = profile {
+        config = config.credentials_provider(ProfileCredentialsProvider::new(profile));
+    }
+    let client = Client::from_conf(config.build());
+
+    println!("Parameters under {}:", path);
+
+    // GetParametersByPath returns at most 10 parameters at a time, and can return fewer,
+    // even none, with a NextToken, so keep going until there's no NextToken.
+    let mut found = 0;
+    let mut next_token = None;
+    loop {
+        let resp = client
+            .get_parameters_by_path()
+            .path(&path)
+            .recursive(recursive)
+            .with_decryption(decrypt)
+            .set_next_token(next_token)
+            .send()
+            .await?;
+
+        for parameter in resp.parameters.unwrap_or_default() {
+            found += 1;
+            println!(
+                "  {} = {}",
+                parameter.name.as_deref().unwrap_or_default(),
+                parameter.value.as_deref().unwrap_or_default()
+            );
+        }
+
+        next_token = resp.next_token;
+        if next_token.is_none() {
+            break;
+        }
+    }
+
+    println!("Found {} parameters", found);
+
+    Ok(())
+}
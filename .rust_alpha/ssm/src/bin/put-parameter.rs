@@ -0,0 +1,131 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0.
+ */
+
+use ssm::model::ParameterType;
+use ssm::{Client, Config, Error, Region};
+
+use aws_types::region::{EnvironmentProvider, ProvideRegion};
+
+use common::profile::ProfileCredentialsProvider;
+use structopt::StructOpt;
+
+#[derive(Debug, StructOpt)]
+struct Opt {
+    /// The region
+    #[structopt(short, long)]
+    region: Option<String>,
+
+    /// The parameter name
+    #[structopt(short, long)]
+    name: String,
+
+    /// The parameter value
+    #[structopt(short, long)]
+    parameter_value: String,
+
+    /// The parameter description
+    #[structopt(short, long)]
+    description: Option<String>,
+
+    /// Whether to encrypt the value, as a SecureString parameter
+    #[structopt(short, long)]
+    secure: bool,
+
+    /// The KMS key that encrypts a SecureString parameter. Defaults to the AWS managed key for Systems Manager
+    #[structopt(short, long)]
+    key_id: Option<String>,
+
+    /// Whether to replace the value of an existing parameter
+    #[structopt(short, long)]
+    overwrite: bool,
+
+    /// The AWS profile to use from the shared credentials file
+    #[structopt(long)]
+    profile: Option<String>,
+
+    /// Whether to display additional information
+    #[structopt(short, long)]
+    verbose: bool,
+}
+
+/// Creates or updates an AWS Systems Manager parameter, as a String or, encrypted, as a SecureString.
+/// # Arguments
+///
+/// * `-n NAME` - The name of the parameter, such as **/myapp/dev/db-password**.
+/// * `-p PARAMETER-VALUE` - The value of the parameter.
+/// * `[-d DESCRIPTION]` - The description of the parameter.
+/// * `[-s]` - Whether to encrypt the value, as a SecureString parameter.
+/// * `[-k KEY-ID]` - The ID, ARN, or alias of the AWS KMS key that encrypts the value.
+///   Implies `-s`. If not supplied, a SecureString is encrypted with the AWS managed key, **alias/aws/ssm**.
+/// * `[-o]` - Whether to replace the value of an existing parameter.
+///   If not supplied, fails if the parameter exists.
+/// * `[-r REGION]` - The region in which the client is created.
+///    If not supplied, uses the value of the **AWS_DEFAULT_REGION** environment variable.
+///    If the environment variable is not set, defaults to **us-west-2**.
+/// * `[--profile PROFILE]` - The AWS profile to use from the shared credentials file.
+/// * `[-v]` - Whether to display additional information.
+#[tokio::main]
+async fn main() -> Result<(), Error> {
+    let Opt {
+        description,
+        key_id,
+        name,
+        overwrite,
+        parameter_value,
+        profile,
+        region,
+        secure,
+        verbose,
+    } = Opt::from_args();
+
+    let region = EnvironmentProvider::new()
+        .region()
+        .or_else(|| region.as_ref().map(|region| Region::new(region.clone())))
+        .unwrap_or_else(|| Region::new("us-west-2"));
+
+    // A KMS key only makes sense for an encrypted parameter.
+    let parameter_type = if secure || key_id.is_some() {
+        ParameterType::SecureString
+    } else {
+        ParameterType::String
+    };
+
+    if verbose {
+        println!("SSM client version:   {}", ssm::PKG_VERSION);
+        println!("Region:               {:?}", &region);
+        println!("Parameter name:       {}", name);
+        println!("Parameter type:       {}", parameter_type.as_str());
+        println!("KMS key:              {:?}", key_id);
+        println!("Overwrite:            {}", overwrite);
+
+        tracing_subscriber::fmt::init();
+    }
+
+    let mut config = Config::builder().region(region);
+    if let Some(profile) = profile {
+        config = config.credentials_provider(ProfileCredentialsProvider::new(profile));
+    }
+    let client = Client::from_conf(config.build());
+
+    let resp = client
+        .put_parameter()
+        .name(&name)
+        .value(parameter_value)
+        .r#type(parameter_type.clone())
+        .set_description(description)
+        .set_key_id(key_id)
+        .overwrite(overwrite)
+        .send()
+        .await?;
+
+    println!(
+        "Put {} parameter {}, version {}",
+        parameter_type.as_str(),
+        name,
+        resp.version
+    );
+
+    Ok(())
+}
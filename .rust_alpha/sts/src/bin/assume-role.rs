@@ -0,0 +1,177 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0.
+ */
+
+use std::error::Error;
+
+use aws_auth::Credentials;
+use aws_types::region::{ProvideRegion, Region};
+
+use common::profile::ProfileCredentialsProvider;
+use structopt::StructOpt;
+use tracing_subscriber::fmt::format::FmtSpan;
+use tracing_subscriber::fmt::SubscriberBuilder;
+
+#[derive(Debug, StructOpt)]
+struct Opt {
+    /// The region. Overrides environment variable AWS_DEFAULT_REGION.
+    #[structopt(short, long)]
+    default_region: Option<String>,
+
+    /// The ARN of the role to assume
+    #[structopt(short, long)]
+    role_arn: String,
+
+    /// The name of the session, which appears in AWS CloudTrail logs
+    #[structopt(short, long, default_value = "rust-assume-role")]
+    session_name: String,
+
+    /// The external ID that the role's trust policy requires
+    #[structopt(long)]
+    external_id: Option<String>,
+
+    /// The serial number or ARN of your MFA device
+    #[structopt(long)]
+    mfa_serial: Option<String>,
+
+    /// The current code from your MFA device
+    #[structopt(long)]
+    token_code: Option<String>,
+
+    /// The AWS profile to use from the shared credentials file when assuming the role
+    #[structopt(long)]
+    profile: Option<String>,
+
+    /// Whether to display additional runtime information
+    #[structopt(short, long)]
+    verbose: bool,
+}
+
+/// Assumes a role, and uses the temporary credentials to list your Amazon S3 buckets.
+/// # Arguments
+///
+/// * `-r ROLE-ARN` - The ARN of the role to assume.
+/// * `[-s SESSION-NAME]` - The name of the session.
+///    If not supplied, defaults to **rust-assume-role**.
+/// * `[--external-id EXTERNAL-ID]` - The external ID that the role's trust policy requires.
+/// * `[--mfa-serial MFA-SERIAL --token-code TOKEN-CODE]` - Your MFA device and its current code,
+///    if the role's trust policy requires MFA.
+/// * `[-d DEFAULT-REGION]` - The region in which the clients are created.
+///    If not supplied, uses the value of the **AWS_DEFAULT_REGION** environment variable.
+///    If the environment variable is not set, defaults to **us-west-2**.
+/// * `[--profile PROFILE]` - The AWS profile to use from the shared credentials file when assuming the role.
+/// * `[-v]` - Whether to display additional information.
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn Error>> {
+    let Opt {
+        default_region,
+        external_id,
+        mfa_serial,
+        profile,
+        role_arn,
+        session_name,
+        token_code,
+        verbose,
+    } = Opt::from_args();
+
+    if mfa_serial.is_some() != token_code.is_some() {
+        return Err("--mfa-serial and --token-code must be used together".into());
+    }
+
+    let region = default_region
+        .as_ref()
+        .map(|region| Region::new(region.clone()))
+        .or_else(|| aws_types::region::default_provider().region())
+        .unwrap_or_else(|| Region::new("us-west-2"));
+
+    if verbose {
+        println!("STS client version: {}", sts::PKG_VERSION);
+        println!("S3 client version:  {}", s3::PKG_VERSION);
+        println!("Region:             {:?}", &region);
+        println!("Role ARN:           {}", &role_arn);
+        println!("Session name:       {}", &session_name);
+        println!("External ID:        {:?}", &external_id);
+        println!("MFA serial:         {:?}", &mfa_serial);
+
+        SubscriberBuilder::default()
+            .with_env_filter("info")
+            .with_span_events(FmtSpan::CLOSE)
+            .init();
+    }
+
+    // The STS client uses your default credentials, or those of the profile, to assume the role.
+    let mut sts_config = sts::Config::builder().region(region.clone());
+    if let Some(profile) = profile {
+        sts_config = sts_config.credentials_provider(ProfileCredentialsProvider::new(profile));
+    }
+    let sts_client = sts::Client::from_conf(sts_config.build());
+
+    let resp = sts_client
+        .assume_role()
+        .role_arn(&role_arn)
+        .role_session_name(&session_name)
+        .set_external_id(external_id)
+        .set_serial_number(mfa_serial)
+        .set_token_code(token_code)
+        .send()
+        .await?;
+
+    if let Some(user) = resp.assumed_role_user {
+        println!(
+            "Assumed role as {}",
+            user.arn.as_deref().unwrap_or_default()
+        );
+    }
+
+    let sts_credentials = resp
+        .credentials
+        .ok_or("AssumeRole did not return credentials")?;
+
+    let expiration = sts_credentials
+        .expiration
+        .and_then(|expiry| expiry.to_system_time());
+
+    // Temporary credentials are only valid together with their session token.
+    let credentials = Credentials::new(
+        sts_credentials
+            .access_key_id
+            .ok_or("the credentials have no access key ID")?,
+        sts_credentials
+            .secret_access_key
+            .ok_or("the credentials have no secret access key")?,
+        sts_credentials.session_token,
+        expiration,
+        "AssumeRole",
+    );
+
+    if let Some(expiration) = expiration {
+        if let Ok(remaining) = expiration.duration_since(std::time::SystemTime::now()) {
+            println!(
+                "The credentials expire in {} minutes",
+                remaining.as_secs() / 60
+            );
+        }
+    }
+
+    // The S3 client uses the role's credentials instead of your default credentials,
+    // so it can only do what the role allows.
+    let s3_config = s3::Config::builder()
+        .region(region)
+        .credentials_provider(credentials)
+        .build();
+    let s3_client = s3::Client::from_conf(s3_config);
+
+    let buckets = s3_client.list_buckets().send().await?;
+    let buckets = buckets.buckets.unwrap_or_default();
+
+    println!("Buckets visible to the role:");
+
+    for bucket in &buckets {
+        println!("  {}", bucket.name.as_deref().unwrap_or_default());
+    }
+
+    println!("Found {} buckets", buckets.len());
+
+    Ok(())
+}
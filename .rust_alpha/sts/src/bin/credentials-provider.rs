@@ -4,16 +4,55 @@
  */
 
 use aws_auth::{CredentialsError, ProvideCredentials};
-use std::sync::{Arc, Mutex};
+use std::error::Error;
+use std::sync::{Arc, Mutex, MutexGuard};
 use std::time::{Duration, SystemTime};
-use sts::Credentials;
+use sts::{Credentials, Region};
+
+use aws_types::region::ProvideRegion;
+
+use common::profile::ProfileCredentialsProvider;
+use structopt::StructOpt;
+
+#[derive(Debug, StructOpt)]
+struct Opt {
+    /// The region. Overrides environment variable AWS_DEFAULT_REGION.
+    #[structopt(short, long)]
+    default_region: Option<String>,
+
+    /// The AWS profile to use from the shared credentials file
+    #[structopt(long)]
+    profile: Option<String>,
+}
 
 /// Implements a basic version of ProvideCredentials with AWS STS
 /// and lists the tables in the region based on those credentials.
+/// The profile, if one is supplied, holds the credentials that call AWS STS.
+/// # Arguments
+///
+/// * `[-d DEFAULT-REGION]` - The region in which the client is created.
+///    If not supplied, uses the value of the **AWS_DEFAULT_REGION** environment variable.
+///    If the environment variable is not set, defaults to **us-west-2**.
+/// * `[--profile PROFILE]` - The AWS profile to use from the shared credentials file.
 #[tokio::main]
-async fn main() -> Result<(), dynamodb::Error> {
+async fn main() -> Result<(), Box<dyn Error>> {
     tracing_subscriber::fmt::init();
-    let client = sts::Client::from_env();
+    let Opt {
+        default_region,
+        profile,
+    } = Opt::from_args();
+
+    let region = default_region
+        .as_ref()
+        .map(|region| Region::new(region.clone()))
+        .or_else(|| aws_types::region::default_provider().region())
+        .unwrap_or_else(|| Region::new("us-west-2"));
+
+    let mut sts_config = sts::Config::builder().region(region.clone());
+    if let Some(profile) = profile {
+        sts_config = sts_config.credentials_provider(ProfileCredentialsProvider::new(profile));
+    }
+    let client = sts::Client::from_conf(sts_config.build());
     let sts_provider = StsCredentialsProvider {
         client,
         credentials: Arc::new(Mutex::new(None)),
@@ -21,6 +60,7 @@ async fn main() -> Result<(), dynamodb::Error> {
     sts_provider.spawn_refresh_loop().await;
 
     let dynamodb_conf = dynamodb::Config::builder()
+        .region(region)
         .credentials_provider(sts_provider)
         .build();
     let client = dynamodb::Client::from_conf(dynamodb_conf);
@@ -39,12 +79,21 @@ struct StsCredentialsProvider {
 
 impl ProvideCredentials for StsCredentialsProvider {
     fn provide_credentials(&self) -> Result<Credentials, CredentialsError> {
-        let inner = self.credentials.lock().unwrap().clone();
+        let inner = self.lock_credentials().clone();
         inner.ok_or(CredentialsError::CredentialsNotLoaded)
     }
 }
 
 impl StsCredentialsProvider {
+    /// Locks the cached credentials.
+    /// The credentials are replaced as a whole, so they're still usable if another thread panicked
+    /// while holding the lock.
+    fn lock_credentials(&self) -> MutexGuard<'_, Option<Credentials>> {
+        self.credentials
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+
     pub async fn spawn_refresh_loop(&self) {
         let _ = self
             .refresh()
@@ -54,7 +103,7 @@ impl StsCredentialsProvider {
         tokio::spawn(async move {
             loop {
                 let needs_refresh = {
-                    let creds = this.credentials.lock().unwrap();
+                    let creds = this.lock_credentials();
                     let expiry = creds.as_ref().and_then(|creds| creds.expiry());
                     if creds.is_none() {
                         true
@@ -74,18 +123,25 @@ impl StsCredentialsProvider {
             }
         });
     }
-    pub async fn refresh(&self) -> Result<(), sts::Error> {
+    pub async fn refresh(&self) -> Result<(), Box<dyn Error + Send + Sync>> {
         let session_token = self.client.get_session_token().send().await?;
         let sts_credentials = session_token
             .credentials
-            .expect("should include credentials");
-        *self.credentials.lock().unwrap() = Some(Credentials::new(
-            sts_credentials.access_key_id.unwrap(),
-            sts_credentials.secret_access_key.unwrap(),
+            .ok_or("GetSessionToken did not return credentials")?;
+        let access_key_id = sts_credentials
+            .access_key_id
+            .ok_or("the credentials have no access key ID")?;
+        let secret_access_key = sts_credentials
+            .secret_access_key
+            .ok_or("the credentials have no secret access key")?;
+        *self.lock_credentials() = Some(Credentials::new(
+            access_key_id,
+            secret_access_key,
             sts_credentials.session_token,
+            // An expiration before 1970 can't be represented, so treat it as no expiration.
             sts_credentials
                 .expiration
-                .map(|expiry| expiry.to_system_time().expect("sts sent a time < 0")),
+                .and_then(|expiry| expiry.to_system_time()),
             "Sts",
         ));
         Ok(())
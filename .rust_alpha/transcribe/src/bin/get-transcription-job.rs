@@ -0,0 +1,201 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0.
+ */
+
+use std::error::Error;
+use std::time::{Duration, Instant};
+
+use transcribe::model::{TranscriptionJob, TranscriptionJobStatus};
+use transcribe::{Client, Config, Region};
+use transcribe_code_examples::transcript::{download, Transcript};
+
+use aws_types::region::ProvideRegion;
+
+use common::profile::ProfileCredentialsProvider;
+use structopt::StructOpt;
+use tokio::time::sleep;
+use tracing_subscriber::fmt::format::FmtSpan;
+use tracing_subscriber::fmt::SubscriberBuilder;
+
+/// How long to wait between requests for the job's status.
+const POLL_INTERVAL: Duration = Duration::from_secs(5);
+
+#[derive(Debug, StructOpt)]
+struct Opt {
+    /// The default region
+    #[structopt(short, long)]
+    default_region: Option<String>,
+
+    /// The name of the transcription job
+    #[structopt(short, long)]
+    job_name: String,
+
+    /// Whether to wait for the job to finish
+    #[structopt(short, long)]
+    wait: bool,
+
+    /// How long to wait, in seconds
+    #[structopt(long, default_value = "600")]
+    timeout: u64,
+
+    /// Whether to download and display the transcript of a finished job
+    #[structopt(short, long)]
+    transcript: bool,
+
+    /// The AWS profile to use from the shared credentials file
+    #[structopt(long)]
+    profile: Option<String>,
+
+    /// Whether to display additional information
+    #[structopt(short, long)]
+    verbose: bool,
+}
+
+/// Gets the transcription job.
+async fn get_job(client: &Client, job_name: &str) -> Result<TranscriptionJob, Box<dyn Error>> {
+    let resp = client
+        .get_transcription_job()
+        .transcription_job_name(job_name)
+        .send()
+        .await?;
+
+    Ok(resp
+        .transcription_job
+        .ok_or("no transcription job returned")?)
+}
+
+/// Gets the transcription job, and, if it's still running, waits for it to finish,
+/// displaying each change in its status.
+async fn wait_for_job(
+    client: &Client,
+    job_name: &str,
+    timeout: Duration,
+) -> Result<TranscriptionJob, Box<dyn Error>> {
+    let deadline = Instant::now() + timeout;
+    let mut last = None;
+
+    loop {
+        let job = get_job(client, job_name).await?;
+        let status = job.transcription_job_status.clone();
+
+        match status {
+            Some(TranscriptionJobStatus::Completed) | Some(TranscriptionJobStatus::Failed) => {
+                return Ok(job)
+            }
+            _ if Instant::now() >= deadline => {
+                return Err(format!(
+                    "job {} didn't finish in {} seconds; its status is {:?}",
+                    job_name,
+                    timeout.as_secs(),
+                    status
+                )
+                .into())
+            }
+            _ => {
+                if status != last {
+                    println!("Status: {:?}", status);
+                    last = status;
+                }
+                sleep(POLL_INTERVAL).await;
+            }
+        }
+    }
+}
+
+/// Displays the status of an Amazon Transcribe job, and, optionally, waits for it to finish
+/// and displays its transcript, a line per sentence, each starting with when it was said,
+/// and the speaker if the job identified speakers.
+/// # Arguments
+///
+/// * `-j JOB-NAME` - The name of the transcription job.
+/// * `[-w]` - Whether to wait for the job to finish.
+/// * `[--timeout SECONDS]` - How long to wait. Defaults to **600**.
+/// * `[-t]` - Whether to download and display the transcript, if the job finished.
+/// * `[-d DEFAULT-REGION]` - The region in which the client is created.
+///   If not supplied, uses the value of the **AWS_DEFAULT_REGION** environment variable.
+///   If the environment variable is not set, defaults to **us-west-2**.
+/// * `[--profile PROFILE]` - The AWS profile to use from the shared credentials file.
+/// * `[-v]` - Whether to display additional information.
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn Error>> {
+    let Opt {
+        default_region,
+        job_name,
+        wait,
+        timeout,
+        transcript,
+        profile,
+        verbose,
+    } = Opt::from_args();
+
+    let region = default_region
+        .as_ref()
+        .map(|region| Region::new(region.clone()))
+        .or_else(|| aws_types::region::default_provider().region())
+        .unwrap_or_else(|| Region::new("us-west-2"));
+
+    if verbose {
+        println!("Transcribe client version: {}", transcribe::PKG_VERSION);
+        println!("Region:                    {:?}", &region);
+        println!("Job name:                  {}", &job_name);
+        println!("Wait:                      {}", wait);
+        println!("Transcript:                {}", transcript);
+        println!();
+
+        SubscriberBuilder::default()
+            .with_env_filter("info")
+            .with_span_events(FmtSpan::CLOSE)
+            .init();
+    }
+
+    let mut transcribe_config = Config::builder().region(region.clone());
+    let mut s3_config = s3::Config::builder().region(region);
+    if let Some(profile) = profile {
+        transcribe_config = transcribe_config
+            .credentials_provider(ProfileCredentialsProvider::new(profile.clone()));
+        s3_config = s3_config.credentials_provider(ProfileCredentialsProvider::new(profile));
+    }
+    let client = Client::from_conf(transcribe_config.build());
+
+    let job = if wait {
+        wait_for_job(&client, &job_name, Duration::from_secs(timeout)).await?
+    } else {
+        get_job(&client, &job_name).await?
+    };
+
+    println!("Job:      {}", job_name);
+    println!("Status:   {:?}", job.transcription_job_status);
+    if let Some(reason) = &job.failure_reason {
+        println!("Failure:  {}", reason);
+    }
+
+    let uri = job
+        .transcript
+        .and_then(|transcript| transcript.transcript_file_uri);
+
+    if let Some(uri) = &uri {
+        println!("URI:      {}", uri);
+    }
+
+    if !transcript {
+        return Ok(());
+    }
+
+    let uri = uri.ok_or("the job has no transcript yet; use -w to wait for it")?;
+    let s3_client = s3::Client::from_conf(s3_config.build());
+    let transcript = Transcript::from_json(&download(&s3_client, &uri).await?)?;
+
+    println!();
+    for line in transcript.lines() {
+        println!("{}", line);
+    }
+
+    if verbose {
+        println!();
+        println!("Full text:");
+        println!("{}", transcript.text);
+    }
+
+    Ok(())
+}
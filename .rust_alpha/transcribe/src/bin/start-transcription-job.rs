@@ -0,0 +1,174 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0.
+ */
+
+use std::error::Error;
+
+use transcribe::model::{LanguageCode, Media, MediaFormat, Settings};
+use transcribe::{Client, Config, Region};
+
+use aws_types::region::ProvideRegion;
+
+use common::profile::ProfileCredentialsProvider;
+use structopt::StructOpt;
+use tracing_subscriber::fmt::format::FmtSpan;
+use tracing_subscriber::fmt::SubscriberBuilder;
+
+/// The media formats that Amazon Transcribe reads, by file extension.
+const FORMATS: &[&str] = &["amr", "flac", "mp3", "mp4", "ogg", "wav", "webm"];
+
+#[derive(Debug, StructOpt)]
+struct Opt {
+    /// The default region
+    #[structopt(short, long)]
+    default_region: Option<String>,
+
+    /// The name of the transcription job
+    #[structopt(short, long)]
+    job_name: String,
+
+    /// The S3 URI of the audio or video file, such as s3://bucket/interview.mp3
+    #[structopt(short = "u", long)]
+    media_uri: String,
+
+    /// The format of the media file, such as mp3. Defaults to the file's extension
+    #[structopt(short = "f", long)]
+    media_format: Option<String>,
+
+    /// The language spoken in the media file, such as en-US
+    #[structopt(short, long, default_value = "en-US")]
+    language: String,
+
+    /// The most speakers to tell apart, from 2 through 10. If not set, speakers aren't identified
+    #[structopt(long)]
+    speakers: Option<i32>,
+
+    /// The bucket to write the transcript to. If not set, Amazon Transcribe keeps it
+    #[structopt(short, long)]
+    output_bucket: Option<String>,
+
+    /// The AWS profile to use from the shared credentials file
+    #[structopt(long)]
+    profile: Option<String>,
+
+    /// Whether to display additional information
+    #[structopt(short, long)]
+    verbose: bool,
+}
+
+/// Returns the media format of a file from its extension, if Amazon Transcribe reads it.
+fn format_of(uri: &str) -> Option<&'static str> {
+    let extension = uri.rsplit_once('.')?.1.to_ascii_lowercase();
+
+    FORMATS.iter().copied().find(|format| *format == extension)
+}
+
+/// Starts an Amazon Transcribe job that transcribes an audio or video file in Amazon S3.
+/// Use `get-transcription-job` to wait for it to finish and display the transcript.
+/// # Arguments
+///
+/// * `-j JOB-NAME` - The name of the transcription job.
+/// * `-u MEDIA-URI` - The S3 URI of the media file, such as **s3://bucket/interview.mp3**.
+/// * `[-f MEDIA-FORMAT]` - The format of the media file, such as **mp3**.
+///   If not supplied, uses the file's extension.
+/// * `[-l LANGUAGE]` - The language spoken in the media file. Defaults to **en-US**.
+/// * `[--speakers SPEAKERS]` - The most speakers to tell apart, from 2 through 10.
+///   If not supplied, the transcript doesn't identify speakers.
+/// * `[-o OUTPUT-BUCKET]` - The bucket to write the transcript to.
+///   If not supplied, Amazon Transcribe keeps the transcript, and its URI expires after a short time.
+/// * `[-d DEFAULT-REGION]` - The region in which the client is created.
+///   If not supplied, uses the value of the **AWS_DEFAULT_REGION** environment variable.
+///   If the environment variable is not set, defaults to **us-west-2**.
+/// * `[--profile PROFILE]` - The AWS profile to use from the shared credentials file.
+/// * `[-v]` - Whether to display additional information.
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn Error>> {
+    let Opt {
+        default_region,
+        job_name,
+        media_uri,
+        media_format,
+        language,
+        speakers,
+        output_bucket,
+        profile,
+        verbose,
+    } = Opt::from_args();
+
+    let region = default_region
+        .as_ref()
+        .map(|region| Region::new(region.clone()))
+        .or_else(|| aws_types::region::default_provider().region())
+        .unwrap_or_else(|| Region::new("us-west-2"));
+
+    if !media_uri.starts_with("s3://") {
+        return Err(format!("{} isn't an S3 URI, such as s3://bucket/key", media_uri).into());
+    }
+
+    let media_format = match media_format {
+        Some(format) => format,
+        None => format_of(&media_uri)
+            .ok_or_else(|| {
+                format!(
+                    "can't tell the format of {}; set -f to one of {}",
+                    media_uri,
+                    FORMATS.join(", ")
+                )
+            })?
+            .to_string(),
+    };
+
+    if verbose {
+        println!("Transcribe client version: {}", transcribe::PKG_VERSION);
+        println!("Region:                    {:?}", &region);
+        println!("Job name:                  {}", &job_name);
+        println!("Media URI:                 {}", &media_uri);
+        println!("Media format:              {}", &media_format);
+        println!("Language:                  {}", &language);
+        println!("Speakers:                  {:?}", &speakers);
+        println!("Output bucket:             {:?}", &output_bucket);
+        println!();
+
+        SubscriberBuilder::default()
+            .with_env_filter("info")
+            .with_span_events(FmtSpan::CLOSE)
+            .init();
+    }
+
+    let mut config = Config::builder().region(region);
+    if let Some(profile) = profile {
+        config = config.credentials_provider(ProfileCredentialsProvider::new(profile));
+    }
+    let client = Client::from_conf(config.build());
+
+    let settings = speakers.map(|speakers| {
+        Settings::builder()
+            .show_speaker_labels(true)
+            .max_speaker_labels(speakers)
+            .build()
+    });
+
+    let resp = client
+        .start_transcription_job()
+        .transcription_job_name(&job_name)
+        .media(Media::builder().media_file_uri(&media_uri).build())
+        .media_format(MediaFormat::from(media_format.as_str()))
+        .language_code(LanguageCode::from(language.as_str()))
+        .set_settings(settings)
+        .set_output_bucket_name(output_bucket)
+        .send()
+        .await?;
+
+    let status = resp
+        .transcription_job
+        .and_then(|job| job.transcription_job_status);
+
+    println!("Started transcription job {}: {:?}", job_name, status);
+    println!(
+        "Run get-transcription-job -j {} -w -t to wait for the transcript",
+        job_name
+    );
+
+    Ok(())
+}
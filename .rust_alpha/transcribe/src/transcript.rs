@@ -0,0 +1,292 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0.
+ */
+
+//! Reads the transcript that a transcription job writes, and formats it as plain text with timestamps.
+//!
+//! The transcript is a JSON file. Besides the whole text, in __results.transcripts__, it has
+//! each word and punctuation mark in __results.items__, with when each word was said, and, if the job
+//! identified speakers, which speaker said it in __results.speaker_labels__:
+//!
+//! ```json
+//! {
+//!   "results": {
+//!     "transcripts": [{ "transcript": "Hello. How are you?" }],
+//!     "items": [
+//!       { "type": "pronunciation", "start_time": "0.04", "end_time": "0.51",
+//!         "alternatives": [{ "confidence": "0.99", "content": "Hello" }] },
+//!       { "type": "punctuation", "alternatives": [{ "confidence": "0.0", "content": "." }] }
+//!     ]
+//!   }
+//! }
+//! ```
+//!
+//! `Transcript::lines` splits the words into a line per sentence, or per turn when the speaker
+//! changes, each starting with when it was said:
+//!
+//! ```ignore
+//! let transcript = Transcript::from_json(&download(&client, &uri).await?)?;
+//! for line in transcript.lines() {
+//!     println!("{}", line);
+//! }
+//! ```
+//!
+//! A job writes its transcript either to a bucket that Amazon Transcribe manages, where the
+//! transcript's URI is presigned and can be downloaded over HTTPS for a short time, or to your own bucket,
+//! from which it's read with `GetObject`.
+
+use std::collections::HashMap;
+use std::error::Error;
+use std::fmt;
+
+use serde::Deserialize;
+
+/// The query parameter of a presigned URL.
+const SIGNATURE_PARAMETER: &str = "X-Amz-Signature=";
+
+/// The punctuation marks that end a sentence.
+const SENTENCE_ENDS: &[&str] = &[".", "?", "!"];
+
+#[derive(Debug, Deserialize)]
+struct File {
+    results: Results,
+}
+
+#[derive(Debug, Deserialize)]
+struct Results {
+    transcripts: Vec<Text>,
+    #[serde(default)]
+    items: Vec<Item>,
+    #[serde(default)]
+    speaker_labels: Option<SpeakerLabels>,
+}
+
+#[derive(Debug, Deserialize)]
+struct Text {
+    transcript: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct Item {
+    #[serde(rename = "type")]
+    kind: String,
+    #[serde(default)]
+    start_time: Option<String>,
+    alternatives: Vec<Alternative>,
+}
+
+#[derive(Debug, Deserialize)]
+struct Alternative {
+    content: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct SpeakerLabels {
+    segments: Vec<Segment>,
+}
+
+#[derive(Debug, Deserialize)]
+struct Segment {
+    items: Vec<SegmentItem>,
+}
+
+#[derive(Debug, Deserialize)]
+struct SegmentItem {
+    start_time: String,
+    speaker_label: String,
+}
+
+/// A word or punctuation mark of a transcript.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Word {
+    pub content: String,
+    /// When the word was said, in seconds from the start. Punctuation has no time.
+    pub start: Option<f64>,
+    /// Who said the word, such as __spk_0__, if the job identified speakers.
+    pub speaker: Option<String>,
+}
+
+impl Word {
+    fn is_punctuation(&self) -> bool {
+        self.start.is_none()
+    }
+}
+
+/// A sentence, or part of one if the speaker changed, and when it started.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Line {
+    pub start: f64,
+    pub speaker: Option<String>,
+    pub text: String,
+}
+
+impl fmt::Display for Line {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "[{}]", timestamp(self.start))?;
+
+        if let Some(speaker) = &self.speaker {
+            write!(f, " {}:", speaker)?;
+        }
+
+        write!(f, " {}", self.text)
+    }
+}
+
+/// Formats seconds as hours, minutes, and seconds to a tenth, such as `00:01:02.5`.
+pub fn timestamp(seconds: f64) -> String {
+    let tenths = (seconds * 10.0).round() as u64;
+
+    format!(
+        "{:02}:{:02}:{:02}.{}",
+        tenths / 36_000,
+        tenths / 600 % 60,
+        tenths / 10 % 60,
+        tenths % 10
+    )
+}
+
+/// Why a transcript couldn't be read.
+#[derive(Debug)]
+pub struct TranscriptError(String);
+
+impl fmt::Display for TranscriptError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "not a transcript: {}", self.0)
+    }
+}
+
+impl Error for TranscriptError {}
+
+/// The transcript of a transcription job.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Transcript {
+    /// The whole text.
+    pub text: String,
+    pub words: Vec<Word>,
+}
+
+impl Transcript {
+    /// Reads the JSON file that a transcription job writes.
+    pub fn from_json(json: &[u8]) -> Result<Transcript, TranscriptError> {
+        let file: File =
+            serde_json::from_slice(json).map_err(|e| TranscriptError(e.to_string()))?;
+        let results = file.results;
+
+        // The speaker labels are matched to the words by when each word starts.
+        let speakers: HashMap<String, String> = results
+            .speaker_labels
+            .map(|labels| labels.segments)
+            .unwrap_or_default()
+            .into_iter()
+            .flat_map(|segment| segment.items)
+            .map(|item| (item.start_time, item.speaker_label))
+            .collect();
+
+        let mut words = Vec::with_capacity(results.items.len());
+        for item in results.items {
+            // The first alternative is the most likely.
+            let content = match item.alternatives.into_iter().next() {
+                Some(alternative) => alternative.content,
+                None => continue,
+            };
+
+            let start = match (item.kind.as_str(), &item.start_time) {
+                ("pronunciation", Some(start)) => Some(
+                    start
+                        .parse()
+                        .map_err(|_| TranscriptError(format!("{} isn't a start time", start)))?,
+                ),
+                _ => None,
+            };
+
+            let speaker = item
+                .start_time
+                .as_ref()
+                .and_then(|start| speakers.get(start).cloned());
+
+            words.push(Word {
+                content,
+                start,
+                speaker,
+            });
+        }
+
+        Ok(Transcript {
+            text: results
+                .transcripts
+                .into_iter()
+                .map(|text| text.transcript)
+                .collect::<Vec<_>>()
+                .join(" "),
+            words,
+        })
+    }
+
+    /// Splits the words into a line per sentence, starting a new line when the speaker changes too.
+    pub fn lines(&self) -> Vec<Line> {
+        let mut lines: Vec<Line> = Vec::new();
+        let mut ended = true;
+
+        for word in &self.words {
+            if word.is_punctuation() {
+                // Punctuation goes on the end of the line, without a space.
+                if let Some(line) = lines.last_mut() {
+                    line.text.push_str(&word.content);
+                }
+                ended = SENTENCE_ENDS.contains(&word.content.as_str());
+                continue;
+            }
+
+            let speaker_changed = lines
+                .last()
+                .map(|line| line.speaker != word.speaker)
+                .unwrap_or(true);
+
+            match lines.last_mut() {
+                Some(line) if !ended && !speaker_changed => {
+                    line.text.push(' ');
+                    line.text.push_str(&word.content);
+                }
+                _ => lines.push(Line {
+                    start: word.start.unwrap_or_default(),
+                    speaker: word.speaker.clone(),
+                    text: word.content.clone(),
+                }),
+            }
+
+            ended = false;
+        }
+
+        lines
+    }
+}
+
+/// Returns the bucket and key of a transcript in your own bucket, from a URI such as
+/// `https://s3.us-west-2.amazonaws.com/BUCKET/KEY`, or `None` if the URI is presigned,
+/// as it is for a transcript in a bucket that Amazon Transcribe manages.
+pub fn bucket_and_key(uri: &str) -> Option<(String, String)> {
+    if uri.contains(SIGNATURE_PARAMETER) {
+        return None;
+    }
+
+    let path = uri.strip_prefix("https://")?.splitn(2, '/').nth(1)?;
+    let (bucket, key) = path.split_once('/')?;
+
+    Some((bucket.to_string(), key.to_string()))
+}
+
+/// Downloads the transcript at the URI that GetTranscriptionJob returns:
+/// with GetObject from your own bucket, or over HTTPS if the URI is presigned.
+pub async fn download(client: &s3::Client, uri: &str) -> Result<Vec<u8>, Box<dyn Error>> {
+    match bucket_and_key(uri) {
+        Some((bucket, key)) => {
+            let resp = client.get_object().bucket(bucket).key(key).send().await?;
+            Ok(resp.body.collect().await?.into_bytes().to_vec())
+        }
+        None => {
+            let resp = reqwest::get(uri).await?.error_for_status()?;
+            Ok(resp.bytes().await?.to_vec())
+        }
+    }
+}
@@ -0,0 +1,136 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0.
+ */
+
+use std::error::Error;
+use std::fs;
+
+use translate::model::{MergeStrategy, TerminologyData, TerminologyDataFormat};
+use translate::{Blob, Client, Config, Region};
+use translate_code_examples::terminology::check_csv;
+
+use aws_types::region::ProvideRegion;
+
+use common::profile::ProfileCredentialsProvider;
+use structopt::StructOpt;
+use tracing_subscriber::fmt::format::FmtSpan;
+use tracing_subscriber::fmt::SubscriberBuilder;
+
+#[derive(Debug, StructOpt)]
+struct Opt {
+    /// The default region
+    #[structopt(short, long)]
+    default_region: Option<String>,
+
+    /// The name of the terminology
+    #[structopt(short, long)]
+    name: String,
+
+    /// The CSV file holding the terminology
+    #[structopt(short, long)]
+    file: String,
+
+    /// The description of the terminology
+    #[structopt(long)]
+    description: Option<String>,
+
+    /// The AWS profile to use from the shared credentials file
+    #[structopt(long)]
+    profile: Option<String>,
+
+    /// Whether to display additional information
+    #[structopt(short, long)]
+    verbose: bool,
+}
+
+/// Imports a custom terminology from a CSV file into Amazon Translate, replacing any terminology
+/// with the same name. The file is checked first, so that a malformed row is reported by its line number.
+/// # Arguments
+///
+/// * `-n NAME` - The name of the terminology.
+/// * `-f FILE` - The CSV file, whose header row lists the source language and then the target languages.
+/// * `[--description DESCRIPTION]` - The description of the terminology.
+/// * `[-d DEFAULT-REGION]` - The region in which the client is created.
+///   If not supplied, uses the value of the **AWS_DEFAULT_REGION** environment variable.
+///   If the environment variable is not set, defaults to **us-west-2**.
+/// * `[--profile PROFILE]` - The AWS profile to use from the shared credentials file.
+/// * `[-v]` - Whether to display additional information.
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn Error>> {
+    let Opt {
+        default_region,
+        name,
+        file,
+        description,
+        profile,
+        verbose,
+    } = Opt::from_args();
+
+    let region = default_region
+        .as_ref()
+        .map(|region| Region::new(region.clone()))
+        .or_else(|| aws_types::region::default_provider().region())
+        .unwrap_or_else(|| Region::new("us-west-2"));
+
+    let csv = fs::read_to_string(&file)?;
+    let summary = check_csv(&csv).map_err(|e| format!("{}: {}", file, e))?;
+
+    if verbose {
+        println!("Translate client version: {}", translate::PKG_VERSION);
+        println!("Region:                   {:?}", &region);
+        println!("Name:                     {}", &name);
+        println!("File:                     {}", &file);
+        println!("Languages:                {:?}", &summary.languages);
+        println!("Terms:                    {}", summary.terms);
+        println!();
+
+        SubscriberBuilder::default()
+            .with_env_filter("info")
+            .with_span_events(FmtSpan::CLOSE)
+            .init();
+    }
+
+    let mut config = Config::builder().region(region);
+    if let Some(profile) = profile {
+        config = config.credentials_provider(ProfileCredentialsProvider::new(profile));
+    }
+    let client = Client::from_conf(config.build());
+
+    let data = TerminologyData::builder()
+        .file(Blob::new(csv.into_bytes()))
+        .format(TerminologyDataFormat::Csv)
+        .build();
+
+    let resp = client
+        .import_terminology()
+        .name(&name)
+        .merge_strategy(MergeStrategy::Overwrite)
+        .set_description(description)
+        .terminology_data(data)
+        .send()
+        .await?;
+
+    let properties = resp
+        .terminology_properties
+        .ok_or("no terminology properties returned")?;
+
+    println!("Imported terminology {}", name);
+    println!(
+        "  Source language:  {}",
+        properties.source_language_code.unwrap_or_default()
+    );
+    println!(
+        "  Target languages: {}",
+        properties
+            .target_language_codes
+            .unwrap_or_default()
+            .join(", ")
+    );
+    println!(
+        "  Terms:            {}",
+        properties.term_count.unwrap_or_default()
+    );
+
+    Ok(())
+}
@@ -0,0 +1,194 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0.
+ */
+
+use std::error::Error;
+use std::time::Duration;
+
+use translate::model::{InputDataConfig, JobStatus, OutputDataConfig};
+use translate::{Client, Config, Region};
+use translate_code_examples::waiters::wait_for_job;
+
+use aws_types::region::ProvideRegion;
+
+use common::profile::ProfileCredentialsProvider;
+use structopt::StructOpt;
+use tracing_subscriber::fmt::format::FmtSpan;
+use tracing_subscriber::fmt::SubscriberBuilder;
+
+#[derive(Debug, StructOpt)]
+struct Opt {
+    /// The default region
+    #[structopt(short, long)]
+    default_region: Option<String>,
+
+    /// The name of the job
+    #[structopt(short = "n", long)]
+    job_name: String,
+
+    /// The S3 URI of the folder holding the documents, such as s3://bucket/input/
+    #[structopt(short, long)]
+    input_uri: String,
+
+    /// The S3 URI of the folder to write the translations to
+    #[structopt(short, long)]
+    output_uri: String,
+
+    /// The ARN of the role that Amazon Translate assumes to read and write the bucket
+    #[structopt(short, long)]
+    role_arn: String,
+
+    /// The language of the documents, such as en, or auto to detect it
+    #[structopt(short, long, default_value = "auto")]
+    source_language: String,
+
+    /// The language to translate into. Repeat to translate into several
+    #[structopt(short, long, required = true)]
+    target_language: Vec<String>,
+
+    /// The type of the documents, such as text/plain or text/html
+    #[structopt(long, default_value = "text/plain")]
+    content_type: String,
+
+    /// The custom terminology to apply
+    #[structopt(long)]
+    terminology: Option<String>,
+
+    /// Whether to wait for the job to finish
+    #[structopt(short, long)]
+    wait: bool,
+
+    /// How long to wait, in seconds
+    #[structopt(long, default_value = "3600")]
+    timeout: u64,
+
+    /// The AWS profile to use from the shared credentials file
+    #[structopt(long)]
+    profile: Option<String>,
+
+    /// Whether to display additional information
+    #[structopt(short, long)]
+    verbose: bool,
+}
+
+/// Starts an Amazon Translate job that translates every document in an S3 folder,
+/// writing the translations to another folder, and, optionally, waits for it to finish,
+/// displaying each change in its status, and then how many documents it translated.
+/// # Arguments
+///
+/// * `-n JOB-NAME` - The name of the job.
+/// * `-i INPUT-URI` - The S3 URI of the folder holding the documents.
+/// * `-o OUTPUT-URI` - The S3 URI of the folder to write the translations to.
+/// * `-r ROLE-ARN` - The ARN of the role that Amazon Translate assumes to read and write the bucket.
+/// * `-t TARGET-LANGUAGE` - The language to translate into. Repeat to translate into several.
+/// * `[-s SOURCE-LANGUAGE]` - The language of the documents.
+///   If not supplied, or **auto**, Amazon Translate detects the language of each document.
+/// * `[--content-type CONTENT-TYPE]` - The type of the documents. Defaults to **text/plain**.
+/// * `[--terminology NAME]` - The custom terminology to apply.
+/// * `[-w]` - Whether to wait for the job to finish.
+/// * `[--timeout SECONDS]` - How long to wait. Defaults to **3600**.
+/// * `[-d DEFAULT-REGION]` - The region in which the client is created.
+///   If not supplied, uses the value of the **AWS_DEFAULT_REGION** environment variable.
+///   If the environment variable is not set, defaults to **us-west-2**.
+/// * `[--profile PROFILE]` - The AWS profile to use from the shared credentials file.
+/// * `[-v]` - Whether to display additional information.
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn Error>> {
+    let Opt {
+        default_region,
+        job_name,
+        input_uri,
+        output_uri,
+        role_arn,
+        source_language,
+        target_language,
+        content_type,
+        terminology,
+        wait,
+        timeout,
+        profile,
+        verbose,
+    } = Opt::from_args();
+
+    let region = default_region
+        .as_ref()
+        .map(|region| Region::new(region.clone()))
+        .or_else(|| aws_types::region::default_provider().region())
+        .unwrap_or_else(|| Region::new("us-west-2"));
+
+    if verbose {
+        println!("Translate client version: {}", translate::PKG_VERSION);
+        println!("Region:                   {:?}", &region);
+        println!("Job name:                 {}", &job_name);
+        println!("Input URI:                {}", &input_uri);
+        println!("Output URI:               {}", &output_uri);
+        println!("Role ARN:                 {}", &role_arn);
+        println!("Source language:          {}", &source_language);
+        println!("Target languages:         {:?}", &target_language);
+        println!("Content type:             {}", &content_type);
+        println!("Terminology:              {:?}", &terminology);
+        println!();
+
+        SubscriberBuilder::default()
+            .with_env_filter("info")
+            .with_span_events(FmtSpan::CLOSE)
+            .init();
+    }
+
+    let mut config = Config::builder().region(region);
+    if let Some(profile) = profile {
+        config = config.credentials_provider(ProfileCredentialsProvider::new(profile));
+    }
+    let client = Client::from_conf(config.build());
+
+    let resp = client
+        .start_text_translation_job()
+        .job_name(&job_name)
+        .input_data_config(
+            InputDataConfig::builder()
+                .s3_uri(&input_uri)
+                .content_type(&content_type)
+                .build(),
+        )
+        .output_data_config(OutputDataConfig::builder().s3_uri(&output_uri).build())
+        .data_access_role_arn(&role_arn)
+        .source_language_code(&source_language)
+        .set_target_language_codes(Some(target_language))
+        .set_terminology_names(terminology.map(|name| vec![name]))
+        .send()
+        .await?;
+
+    let job_id = resp.job_id.ok_or("no job ID returned")?;
+    println!("Started job {} ({})", job_name, job_id);
+
+    if !wait {
+        return Ok(());
+    }
+
+    let job = wait_for_job(&client, &job_id, Duration::from_secs(timeout), |status| {
+        println!("Job status: {}", status.as_str());
+    })
+    .await?;
+
+    if let Some(message) = &job.message {
+        println!("Message: {}", message);
+    }
+
+    if let Some(details) = &job.job_details {
+        println!(
+            "Translated {} of {} documents; {} had errors",
+            details.translated_documents_count.unwrap_or_default(),
+            details.input_documents_count.unwrap_or_default(),
+            details.documents_with_errors_count.unwrap_or_default()
+        );
+    }
+
+    match job.job_status {
+        Some(JobStatus::Completed) | Some(JobStatus::CompletedWithError) => {
+            println!("The translations are under {}", output_uri);
+            Ok(())
+        }
+        status => Err(format!("job {} stopped with status {:?}", job_id, status).into()),
+    }
+}
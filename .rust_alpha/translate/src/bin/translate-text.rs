@@ -0,0 +1,146 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0.
+ */
+
+use std::error::Error;
+
+use translate::{Client, Config, Region};
+
+use aws_types::region::ProvideRegion;
+
+use common::profile::ProfileCredentialsProvider;
+use structopt::StructOpt;
+use tracing_subscriber::fmt::format::FmtSpan;
+use tracing_subscriber::fmt::SubscriberBuilder;
+
+#[derive(Debug, StructOpt)]
+struct Opt {
+    /// The default region
+    #[structopt(short, long)]
+    default_region: Option<String>,
+
+    /// The language to translate into, such as fr
+    #[structopt(short, long)]
+    target_language: String,
+
+    /// The language of the text, such as en, or auto to detect it
+    #[structopt(short, long, default_value = "auto")]
+    source_language: String,
+
+    /// The custom terminology to apply. Repeat to apply several
+    #[structopt(long)]
+    terminology: Vec<String>,
+
+    /// The text to translate
+    #[structopt(required = true)]
+    text: Vec<String>,
+
+    /// The AWS profile to use from the shared credentials file
+    #[structopt(long)]
+    profile: Option<String>,
+
+    /// Whether to display additional information
+    #[structopt(short, long)]
+    verbose: bool,
+}
+
+/// Translates text with Amazon Translate, detecting the language it's in unless told,
+/// and displays the translation, which language the text was in, and which terms came from
+/// a custom terminology.
+/// # Arguments
+///
+/// * `-t TARGET-LANGUAGE` - The language to translate into, such as **fr**.
+/// * `[-s SOURCE-LANGUAGE]` - The language of the text, such as **en**.
+///   If not supplied, or **auto**, Amazon Translate detects it with Amazon Comprehend.
+/// * `[--terminology NAME]` - The custom terminology to apply. Repeat to apply several.
+/// * `TEXT` - The text to translate.
+/// * `[-d DEFAULT-REGION]` - The region in which the client is created.
+///   If not supplied, uses the value of the **AWS_DEFAULT_REGION** environment variable.
+///   If the environment variable is not set, defaults to **us-west-2**.
+/// * `[--profile PROFILE]` - The AWS profile to use from the shared credentials file.
+/// * `[-v]` - Whether to display additional information.
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn Error>> {
+    let Opt {
+        default_region,
+        target_language,
+        source_language,
+        terminology,
+        text,
+        profile,
+        verbose,
+    } = Opt::from_args();
+
+    let region = default_region
+        .as_ref()
+        .map(|region| Region::new(region.clone()))
+        .or_else(|| aws_types::region::default_provider().region())
+        .unwrap_or_else(|| Region::new("us-west-2"));
+
+    let text = text.join(" ");
+
+    if verbose {
+        println!("Translate client version: {}", translate::PKG_VERSION);
+        println!("Region:                   {:?}", &region);
+        println!("Source language:          {}", &source_language);
+        println!("Target language:          {}", &target_language);
+        println!("Terminologies:            {:?}", &terminology);
+        println!("Text:                     {}", &text);
+        println!();
+
+        SubscriberBuilder::default()
+            .with_env_filter("info")
+            .with_span_events(FmtSpan::CLOSE)
+            .init();
+    }
+
+    let mut config = Config::builder().region(region);
+    if let Some(profile) = profile {
+        config = config.credentials_provider(ProfileCredentialsProvider::new(profile));
+    }
+    let client = Client::from_conf(config.build());
+
+    let terminology_names = if terminology.is_empty() {
+        None
+    } else {
+        Some(terminology)
+    };
+
+    let resp = client
+        .translate_text()
+        .text(&text)
+        .source_language_code(&source_language)
+        .target_language_code(&target_language)
+        .set_terminology_names(terminology_names)
+        .send()
+        .await?;
+
+    println!("{}", resp.translated_text.unwrap_or_default());
+
+    let detected = resp.source_language_code.unwrap_or_default();
+    if source_language == "auto" {
+        println!();
+        println!("Detected source language: {}", detected);
+    }
+
+    for applied in resp.applied_terminologies.unwrap_or_default() {
+        let terms = applied.terms.unwrap_or_default();
+        println!();
+        println!(
+            "Terms from {} ({}):",
+            applied.name.unwrap_or_default(),
+            terms.len()
+        );
+
+        for term in terms {
+            println!(
+                "  {} -> {}",
+                term.source_text.unwrap_or_default(),
+                term.target_text.unwrap_or_default()
+            );
+        }
+    }
+
+    Ok(())
+}
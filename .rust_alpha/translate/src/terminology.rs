@@ -0,0 +1,170 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0.
+ */
+
+//! Checks a custom terminology file before it's imported.
+//!
+//! A terminology in CSV has a header row of language codes, the first of which is the source
+//! language, and then a row per term, with its translation into each of the other languages:
+//!
+//! ```text
+//! en,fr,de
+//! Amazon,Amazon,Amazon
+//! sign in,se connecter,anmelden
+//! ```
+//!
+//! ImportTerminology reports a malformed file only as an invalid parameter, so `check_csv`
+//! finds the row that's wrong first:
+//!
+//! ```ignore
+//! let summary = check_csv(&fs::read_to_string(&file)?)?;
+//! println!("{} terms from {}", summary.terms, summary.languages[0]);
+//! ```
+
+use std::collections::HashSet;
+use std::error::Error;
+use std::fmt;
+
+/// The largest terminology file that Amazon Translate imports, in bytes.
+pub const MAX_SIZE: usize = 10 * 1024 * 1024;
+
+/// Why a terminology file can't be imported.
+#[derive(Debug, PartialEq)]
+pub enum TerminologyError {
+    /// The file is larger than `MAX_SIZE`.
+    TooLarge(usize),
+    /// The file has no header row, or a header with fewer than two languages.
+    Header(String),
+    /// A row is wrong. Holds its line number, starting at 1, and why.
+    Row(usize, String),
+}
+
+impl fmt::Display for TerminologyError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TerminologyError::TooLarge(size) => write!(
+                f,
+                "the file is {} bytes; a terminology can be at most {} bytes",
+                size, MAX_SIZE
+            ),
+            TerminologyError::Header(why) => write!(f, "header row: {}", why),
+            TerminologyError::Row(line, why) => write!(f, "line {}: {}", line, why),
+        }
+    }
+}
+
+impl Error for TerminologyError {}
+
+/// What a terminology file holds.
+#[derive(Debug, PartialEq)]
+pub struct Summary {
+    /// The language codes from the header row. The first is the source language.
+    pub languages: Vec<String>,
+    /// How many terms the file has.
+    pub terms: usize,
+}
+
+/// Splits a CSV row into fields. A field in double quotes can hold commas,
+/// and two double quotes in a row stand for one.
+pub fn split_row(row: &str) -> Result<Vec<String>, String> {
+    let mut fields = Vec::new();
+    let mut field = String::new();
+    let mut quoted = false;
+    let mut chars = row.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        match c {
+            '"' if quoted && chars.peek() == Some(&'"') => {
+                field.push('"');
+                chars.next();
+            }
+            '"' if quoted => quoted = false,
+            '"' if field.is_empty() => quoted = true,
+            ',' if !quoted => fields.push(std::mem::take(&mut field)),
+            c => field.push(c),
+        }
+    }
+
+    if quoted {
+        return Err("a quoted field isn't closed".to_string());
+    }
+
+    fields.push(field);
+    Ok(fields)
+}
+
+/// Checks that a CSV terminology has a header of at least two distinct languages,
+/// and that every term has a source term and as many fields as the header.
+pub fn check_csv(csv: &str) -> Result<Summary, TerminologyError> {
+    if csv.len() > MAX_SIZE {
+        return Err(TerminologyError::TooLarge(csv.len()));
+    }
+
+    // A file saved by a spreadsheet can start with a byte order mark.
+    let csv = csv.trim_start_matches('\u{feff}');
+    let mut rows = csv
+        .lines()
+        .enumerate()
+        .filter(|(_, row)| !row.trim().is_empty());
+
+    let (_, header) = rows
+        .next()
+        .ok_or_else(|| TerminologyError::Header("the file is empty".to_string()))?;
+
+    let languages: Vec<String> = split_row(header)
+        .map_err(TerminologyError::Header)?
+        .into_iter()
+        .map(|language| language.trim().to_string())
+        .collect();
+
+    if languages.len() < 2 {
+        return Err(TerminologyError::Header(
+            "it must list the source language and at least one target language, such as en,fr"
+                .to_string(),
+        ));
+    }
+
+    let mut seen = HashSet::new();
+    for language in &languages {
+        if language.is_empty() {
+            return Err(TerminologyError::Header(
+                "a language code is empty".to_string(),
+            ));
+        }
+        if !seen.insert(language) {
+            return Err(TerminologyError::Header(format!(
+                "{} is listed twice",
+                language
+            )));
+        }
+    }
+
+    let mut terms = 0;
+    for (index, row) in rows {
+        let line = index + 1;
+        let fields = split_row(row).map_err(|why| TerminologyError::Row(line, why))?;
+
+        if fields.len() != languages.len() {
+            return Err(TerminologyError::Row(
+                line,
+                format!(
+                    "it has {} fields, but the header lists {} languages",
+                    fields.len(),
+                    languages.len()
+                ),
+            ));
+        }
+
+        if fields[0].trim().is_empty() {
+            return Err(TerminologyError::Row(
+                line,
+                format!("the {} term is empty", languages[0]),
+            ));
+        }
+
+        terms += 1;
+    }
+
+    Ok(Summary { languages, terms })
+}
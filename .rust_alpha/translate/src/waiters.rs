@@ -0,0 +1,118 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0.
+ */
+
+//! Waits for a batch translation job to finish.
+//!
+//! A job translates every document under an S3 prefix, which can take from minutes to hours,
+//! so the waiter polls `DescribeTextTranslationJob` until the job stops or the timeout passes,
+//! and calls `on_status` each time the status changes:
+//!
+//! ```ignore
+//! let job = wait_for_job(&client, &job_id, Duration::from_secs(3600), |status| {
+//!     println!("Job status: {}", status.as_str());
+//! })
+//! .await?;
+//! ```
+
+use std::error::Error;
+use std::fmt;
+use std::time::{Duration, Instant};
+
+use tokio::time::sleep;
+use translate::model::{JobStatus, TextTranslationJobProperties};
+use translate::Client;
+
+/// How long to wait between two calls. A job takes at least a few minutes.
+const POLL_INTERVAL: Duration = Duration::from_secs(30);
+
+/// Why waiting for a job failed.
+#[derive(Debug)]
+pub enum WaitError {
+    /// DescribeTextTranslationJob failed.
+    Sdk(translate::Error),
+    /// The job was still running at the timeout. Holds its ID.
+    Timeout(String),
+}
+
+impl fmt::Display for WaitError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            WaitError::Sdk(e) => write!(f, "{}", e),
+            WaitError::Timeout(id) => write!(f, "job {} was still running", id),
+        }
+    }
+}
+
+impl Error for WaitError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            WaitError::Sdk(e) => Some(e),
+            WaitError::Timeout(_) => None,
+        }
+    }
+}
+
+impl From<translate::Error> for WaitError {
+    fn from(e: translate::Error) -> Self {
+        WaitError::Sdk(e)
+    }
+}
+
+/// Returns whether a job with this status has stopped, whether or not it translated every document.
+pub fn is_terminal(status: &JobStatus) -> bool {
+    matches!(
+        status,
+        JobStatus::Completed
+            | JobStatus::CompletedWithError
+            | JobStatus::Failed
+            | JobStatus::Stopped
+    )
+}
+
+/// Polls the job until it has stopped, and returns its properties.
+/// Calls `on_status` with the first status it sees, and again each time the status changes.
+pub async fn wait_for_job<F>(
+    client: &Client,
+    job_id: &str,
+    timeout: Duration,
+    mut on_status: F,
+) -> Result<TextTranslationJobProperties, WaitError>
+where
+    F: FnMut(&JobStatus),
+{
+    let deadline = Instant::now() + timeout;
+    let mut last = None;
+
+    loop {
+        let properties = client
+            .describe_text_translation_job()
+            .job_id(job_id)
+            .send()
+            .await
+            .map_err(translate::Error::from)?
+            .text_translation_job_properties
+            .unwrap_or_else(|| TextTranslationJobProperties::builder().build());
+
+        if let Some(status) = &properties.job_status {
+            if last.as_ref() != Some(status) {
+                on_status(status);
+            }
+
+            if is_terminal(status) {
+                return Ok(properties);
+            }
+        }
+
+        last = properties.job_status;
+
+        let now = Instant::now();
+        if now >= deadline {
+            return Err(WaitError::Timeout(job_id.to_string()));
+        }
+
+        // Don't sleep past the deadline; check one last time when it's reached.
+        sleep(POLL_INTERVAL.min(deadline - now)).await;
+    }
+}